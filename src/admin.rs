@@ -0,0 +1,448 @@
+use crate::{
+    config::{CoordinatorConfig, CoordinatorSettings, CoordinatorSettingsConfig},
+    coordinator::{BitcoinCoordinator, BitcoinCoordinatorApi},
+    encryption::StoreCipher,
+    errors::BitcoinCoordinatorError,
+    news::news_acks,
+    readiness::readiness_report,
+    speedup::SpeedupStore,
+    storage::{BitcoinCoordinatorStore, BitcoinCoordinatorStoreApi},
+    types::{FundingSummary, News, ReadinessReport, TransactionState},
+};
+use bitcoin::{consensus::encode::deserialize_hex, PublicKey, Transaction, Txid};
+use bitvmx_bitcoin_rpc::bitcoin_client::{BitcoinClient, BitcoinClientApi};
+use bitvmx_transaction_monitor::monitor::{Monitor, MonitorApi};
+use key_manager::create_key_manager_from_config;
+use protocol_builder::types::{output::SpeedupData, Utxo};
+use serde::Serialize;
+use std::{collections::HashMap, fmt, rc::Rc, str::FromStr};
+use storage_backend::storage::Storage;
+
+// Operations of the coordinator-admin binary, kept in the library so they are parsed and run the same way in tests.
+
+pub const DEFAULT_CONFIG_PATH: &str = "config/coordinator_config.yaml";
+
+pub const USAGE: &str = "Usage: coordinator-admin [--config <path>] [--json] <command>
+
+Commands:
+  status                                             Readiness, pending transactions and funding
+  news [--ack-all]                                   Pending news, acknowledged after printing with --ack-all
+  dispatch --tx-hex <hex> --context <context> [--speedup-utxo <txid:vout:amount:pubkey>]
+  add-funding --txid <txid> --vout <vout> --amount <sats> --pubkey <pubkey>
+  cancel --txid <txid>                               Cancels the dispatch of a transaction
+  tick [--count <n>]                                 Ticks the coordinator n times (1 by default)
+
+Commands other than status refuse to run while another process holds the store lock.";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AdminCommand {
+    Help,
+    Status,
+    News {
+        ack_all: bool,
+    },
+    Dispatch {
+        tx: Transaction,
+        context: String,
+        speedup_utxo: Option<Utxo>,
+    },
+    AddFunding(Utxo),
+    Cancel(Txid),
+    Tick(u32),
+}
+
+impl AdminCommand {
+    // Whether the command opens a coordinator, which takes over the store and holds the store lock.
+    // Status only reads the store, see `status`.
+    pub fn is_mutating(&self) -> bool {
+        match self {
+            AdminCommand::Help | AdminCommand::Status => false,
+            AdminCommand::News { .. }
+            | AdminCommand::Dispatch { .. }
+            | AdminCommand::AddFunding(_)
+            | AdminCommand::Cancel(_)
+            | AdminCommand::Tick(_) => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdminArgs {
+    pub config_path: String,
+    pub json: bool,
+    pub command: AdminCommand,
+}
+
+// Parses the arguments after the binary name. Options can be given before or after the command.
+pub fn parse_args<I: IntoIterator<Item = String>>(
+    args: I,
+) -> Result<AdminArgs, BitcoinCoordinatorError> {
+    let mut json = false;
+    let mut ack_all = false;
+    let mut command: Option<String> = None;
+    let mut options: HashMap<String, String> = HashMap::new();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--ack-all" => ack_all = true,
+            "--help" | "-h" => command = Some("help".to_string()),
+            name if name.starts_with("--") => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| invalid_argument(format!("{name} needs a value")))?;
+                options.insert(name.trim_start_matches("--").to_string(), value);
+            }
+            _ if command.is_none() => command = Some(arg),
+            _ => return Err(invalid_argument(format!("unexpected argument {arg}"))),
+        }
+    }
+
+    let config_path = options
+        .remove("config")
+        .unwrap_or_else(|| DEFAULT_CONFIG_PATH.to_string());
+
+    let command = match command.as_deref() {
+        None | Some("help") => AdminCommand::Help,
+        Some("status") => AdminCommand::Status,
+        Some("news") => AdminCommand::News { ack_all },
+        Some("dispatch") => {
+            let tx_hex = required(&mut options, "tx-hex")?;
+            let tx = deserialize_hex::<Transaction>(&tx_hex)
+                .map_err(|e| invalid_argument(format!("--tx-hex is not a transaction: {e}")))?;
+            let speedup_utxo = options
+                .remove("speedup-utxo")
+                .map(|value| parse_utxo(&value))
+                .transpose()?;
+
+            AdminCommand::Dispatch {
+                tx,
+                context: required(&mut options, "context")?,
+                speedup_utxo,
+            }
+        }
+        Some("add-funding") => {
+            let txid = parse_option(&mut options, "txid")?;
+            let vout = parse_option(&mut options, "vout")?;
+            let amount = parse_option(&mut options, "amount")?;
+            let pubkey: PublicKey = parse_option(&mut options, "pubkey")?;
+
+            AdminCommand::AddFunding(Utxo::new(txid, vout, amount, &pubkey))
+        }
+        Some("cancel") => AdminCommand::Cancel(parse_option(&mut options, "txid")?),
+        Some("tick") => {
+            let count = match options.contains_key("count") {
+                true => parse_option(&mut options, "count")?,
+                false => 1,
+            };
+            AdminCommand::Tick(count)
+        }
+        Some(other) => return Err(invalid_argument(format!("unknown command {other}"))),
+    };
+
+    if ack_all && !matches!(command, AdminCommand::News { .. }) {
+        return Err(invalid_argument(
+            "--ack-all is only valid with news".to_string(),
+        ));
+    }
+
+    if let Some(name) = options.keys().next() {
+        return Err(invalid_argument(format!("unknown option --{name}")));
+    }
+
+    Ok(AdminArgs {
+        config_path,
+        json,
+        command,
+    })
+}
+
+// Opens the coordinator of the configuration like the service does, holding the store lock while it is open.
+// Fails with StoreLocked while the service or another admin command has the store open.
+pub fn open_coordinator(
+    config: &CoordinatorConfig,
+) -> Result<BitcoinCoordinator, BitcoinCoordinatorError> {
+    BitcoinCoordinator::open(config)
+}
+
+// Runs status on the storage of the configuration without opening a coordinator: the store is not taken over,
+// the run state is not written and no repair runs, so it can be used while the service runs.
+pub fn status(config: &CoordinatorConfig) -> Result<AdminOutput, BitcoinCoordinatorError> {
+    let settings = config
+        .settings
+        .clone()
+        .unwrap_or_else(|| CoordinatorSettingsConfig::defaults_for(config.rpc.network));
+    settings.validate()?;
+    let settings = CoordinatorSettings::resolve(settings, config.rpc.network);
+
+    let storage = Rc::new(Storage::new(&config.storage).map_err(|e| {
+        BitcoinCoordinatorError::InvalidConfiguration(format!("can not open the storage: {e}"))
+    })?);
+
+    let mut store = BitcoinCoordinatorStore::new(
+        storage.clone(),
+        settings.max_unconfirmed_speedups,
+        settings.retry_attempts_sending_tx,
+        settings.retry_interval_seconds,
+    )?;
+
+    if settings.encrypt_store {
+        let key_manager = create_key_manager_from_config(&config.key_manager, &config.key_storage)
+            .map_err(|e| {
+                BitcoinCoordinatorError::InvalidConfiguration(format!(
+                    "can not open the key manager: {e}"
+                ))
+            })?;
+        store = store.with_encryption(StoreCipher::from_key_manager(&key_manager)?);
+    }
+
+    let monitor = Monitor::new_with_paths(&config.rpc, storage, settings.monitor_settings.clone())?;
+    let client = BitcoinClient::new_from_config(&config.rpc)?;
+
+    read_status(&store, &monitor, &client, settings.min_network_fee_rate)
+}
+
+// Status read from the store, the monitor and the client, nothing is written.
+// The affordable speedups are estimated at the fee rate of the monitor, never below `min_network_fee_rate`.
+pub fn read_status(
+    store: &BitcoinCoordinatorStore,
+    monitor: &dyn MonitorApi,
+    client: &dyn BitcoinClientApi,
+    min_network_fee_rate: u64,
+) -> Result<AdminOutput, BitcoinCoordinatorError> {
+    let current_block_height = monitor.get_monitor_height()?;
+    let (to_dispatch, dispatched): (Vec<_>, Vec<_>) = store
+        .get_pending_tx_entries(current_block_height)?
+        .into_iter()
+        .partition(|entry| entry.state == TransactionState::ToDispatch);
+
+    let network_fee_rate = monitor.get_estimated_fee_rate()?.max(min_network_fee_rate);
+
+    Ok(AdminOutput::Status {
+        readiness: readiness_report(monitor, client, !to_dispatch.is_empty(), None)?,
+        to_dispatch: to_dispatch.len(),
+        dispatched: dispatched.len(),
+        unconfirmed_speedups: store.get_unconfirmed_speedup_entries()?.len(),
+        funding: store.get_funding_summary(network_fee_rate)?,
+    })
+}
+
+// Result of a command, printed as JSON with --json.
+#[derive(Serialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum AdminOutput {
+    Help,
+    Status {
+        readiness: ReadinessReport,
+        to_dispatch: usize,
+        dispatched: usize,
+        unconfirmed_speedups: usize,
+        funding: FundingSummary,
+    },
+    News {
+        news: News,
+        acknowledged: usize,
+    },
+    Dispatched {
+        tx_id: Txid,
+    },
+    FundingAdded {
+        funding: Utxo,
+    },
+    Cancelled {
+        tx_id: Txid,
+    },
+    Ticked {
+        ticks: u32,
+        readiness: ReadinessReport,
+    },
+}
+
+pub fn execute(
+    coordinator: &BitcoinCoordinator,
+    command: &AdminCommand,
+) -> Result<AdminOutput, BitcoinCoordinatorError> {
+    let output = match command {
+        AdminCommand::Help => AdminOutput::Help,
+        AdminCommand::Status => {
+            let overview = coordinator.get_pending_overview()?;
+
+            AdminOutput::Status {
+                readiness: coordinator.readiness()?,
+                to_dispatch: overview.to_dispatch.len(),
+                dispatched: overview.dispatched.len(),
+                unconfirmed_speedups: overview.unconfirmed_speedups.len(),
+                funding: coordinator.get_funding_summary()?,
+            }
+        }
+        AdminCommand::News { ack_all } => {
+            let news = coordinator.get_news()?;
+            let mut acknowledged = 0;
+
+            if *ack_all {
+                acknowledged = coordinator.ack_news_batch(news_acks(&news))?;
+            }
+
+            AdminOutput::News { news, acknowledged }
+        }
+        AdminCommand::Dispatch {
+            tx,
+            context,
+            speedup_utxo,
+        } => {
+            coordinator.dispatch(
+                tx.clone(),
+                speedup_utxo.clone().map(SpeedupData::new),
+                context.clone(),
+                None,
+                None,
+            )?;
+
+            AdminOutput::Dispatched {
+                tx_id: tx.compute_txid(),
+            }
+        }
+        AdminCommand::AddFunding(funding) => {
+            coordinator.add_funding(funding.clone())?;
+            AdminOutput::FundingAdded {
+                funding: funding.clone(),
+            }
+        }
+        AdminCommand::Cancel(tx_id) => {
+            coordinator.cancel_dispatch(*tx_id)?;
+            AdminOutput::Cancelled { tx_id: *tx_id }
+        }
+        AdminCommand::Tick(count) => {
+            for _ in 0..*count {
+                coordinator.tick()?;
+            }
+
+            AdminOutput::Ticked {
+                ticks: *count,
+                readiness: coordinator.readiness()?,
+            }
+        }
+    };
+
+    Ok(output)
+}
+
+impl fmt::Display for AdminOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AdminOutput::Help => write!(f, "{USAGE}"),
+            AdminOutput::Status {
+                readiness,
+                to_dispatch,
+                dispatched,
+                unconfirmed_speedups,
+                funding,
+            } => {
+                writeln!(
+                    f,
+                    "Ready: {} | Indexed height: {} | Tip height: {} | Blocks remaining: {}",
+                    readiness.ready,
+                    readiness.indexed_height,
+                    readiness.tip_height,
+                    readiness.blocks_remaining
+                )?;
+                if let Some(since) = readiness.node_unreachable_since {
+                    writeln!(f, "Node unreachable since: {since}")?;
+                }
+                writeln!(
+                    f,
+                    "To dispatch: {to_dispatch} | Dispatched: {dispatched} | Unconfirmed speedups: {unconfirmed_speedups}"
+                )?;
+                match &funding.funding {
+                    Some(utxo) => write!(
+                        f,
+                        "Funding: {}:{} ({} sats)",
+                        utxo.txid, utxo.vout, utxo.amount
+                    )?,
+                    None => write!(f, "Funding: none")?,
+                }
+                write!(
+                    f,
+                    " | Pool: {} utxos | Spent since funding: {} sats | Affordable speedups: {}",
+                    funding.funding_pool.len(),
+                    funding.spent_since_funding,
+                    funding
+                        .affordable_speedups
+                        .map(|speedups| speedups.to_string())
+                        .unwrap_or_else(|| "unknown".to_string())
+                )
+            }
+            AdminOutput::News { news, acknowledged } => {
+                for news in &news.monitor_news {
+                    writeln!(f, "{news:?}")?;
+                }
+                for news in &news.coordinator_news {
+                    writeln!(f, "{}: {news:?}", news.kind())?;
+                }
+                write!(
+                    f,
+                    "{} news, {acknowledged} acknowledged",
+                    news.monitor_news.len() + news.coordinator_news.len()
+                )
+            }
+            AdminOutput::Dispatched { tx_id } => write!(f, "Dispatched {tx_id}"),
+            AdminOutput::FundingAdded { funding } => write!(
+                f,
+                "Added funding {}:{} ({} sats)",
+                funding.txid, funding.vout, funding.amount
+            ),
+            AdminOutput::Cancelled { tx_id } => write!(f, "Cancelled {tx_id}"),
+            AdminOutput::Ticked { ticks, readiness } => write!(
+                f,
+                "Ticked {ticks} times | Ready: {} | Indexed height: {} | Tip height: {}",
+                readiness.ready, readiness.indexed_height, readiness.tip_height
+            ),
+        }
+    }
+}
+
+fn invalid_argument(message: String) -> BitcoinCoordinatorError {
+    BitcoinCoordinatorError::InvalidArgument(message)
+}
+
+fn required(
+    options: &mut HashMap<String, String>,
+    name: &str,
+) -> Result<String, BitcoinCoordinatorError> {
+    options
+        .remove(name)
+        .ok_or_else(|| invalid_argument(format!("--{name} is required")))
+}
+
+fn parse_option<T: FromStr>(
+    options: &mut HashMap<String, String>,
+    name: &str,
+) -> Result<T, BitcoinCoordinatorError>
+where
+    T::Err: fmt::Display,
+{
+    let value = required(options, name)?;
+    value
+        .parse()
+        .map_err(|e| invalid_argument(format!("--{name} {value}: {e}")))
+}
+
+// A speedup utxo given as txid:vout:amount:pubkey.
+fn parse_utxo(value: &str) -> Result<Utxo, BitcoinCoordinatorError> {
+    let parts: Vec<&str> = value.split(':').collect();
+    let [txid, vout, amount, pubkey] = parts.as_slice() else {
+        return Err(invalid_argument(format!(
+            "--speedup-utxo {value} must be txid:vout:amount:pubkey"
+        )));
+    };
+
+    let parse_error =
+        |e: &dyn fmt::Display| invalid_argument(format!("--speedup-utxo {value}: {e}"));
+    let txid = Txid::from_str(txid).map_err(|e| parse_error(&e))?;
+    let vout = vout.parse::<u32>().map_err(|e| parse_error(&e))?;
+    let amount = amount.parse::<u64>().map_err(|e| parse_error(&e))?;
+    let pubkey = PublicKey::from_str(pubkey).map_err(|e| parse_error(&e))?;
+
+    Ok(Utxo::new(txid, vout, amount, &pubkey))
+}
@@ -0,0 +1,82 @@
+use crate::{errors::BitcoinCoordinatorError, node::NodeApi};
+use bitcoin::Txid;
+use bitcoincore_rpc::{Client, RpcApi};
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+/// Ancestors of an unconfirmed transaction in the node mempool, the transaction included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MempoolAncestry {
+    pub ancestor_count: u64,
+    /// Virtual size of the ancestors in vbytes.
+    pub ancestor_size: u64,
+}
+
+/// Source of the mempool ancestry checked before building a CPFP when `check_mempool_ancestry` is enabled.
+/// By default the node is asked with getmempoolentry.
+pub trait MempoolAncestryProvider {
+    /// Returns the ancestry of the transaction, or None when it is not in the mempool (e.g. it is confirmed).
+    fn get_mempool_ancestry(
+        &self,
+        txid: &Txid,
+    ) -> Result<Option<MempoolAncestry>, BitcoinCoordinatorError>;
+}
+
+impl MempoolAncestryProvider for Client {
+    fn get_mempool_ancestry(
+        &self,
+        txid: &Txid,
+    ) -> Result<Option<MempoolAncestry>, BitcoinCoordinatorError> {
+        match self.get_mempool_entry(txid) {
+            Ok(entry) => Ok(Some(MempoolAncestry {
+                ancestor_count: entry.ancestor_count,
+                ancestor_size: entry.ancestor_size,
+            })),
+            // The node answers with an error for transactions that are not in its mempool.
+            Err(e) if e.to_string().contains("not in mempool") => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+// Mempool ancestry of the transactions asked during a tick.
+// Answers are kept until `reset` is called, so the node is asked at most once per transaction and tick.
+#[derive(Default)]
+pub struct MempoolAncestryCache {
+    provider: Option<Rc<dyn MempoolAncestryProvider>>,
+    ancestry: RefCell<HashMap<Txid, Option<MempoolAncestry>>>,
+}
+
+impl MempoolAncestryCache {
+    pub fn with_provider(mut self, provider: Rc<dyn MempoolAncestryProvider>) -> Self {
+        self.set_provider(provider);
+        self
+    }
+
+    pub fn set_provider(&mut self, provider: Rc<dyn MempoolAncestryProvider>) {
+        self.provider = Some(provider);
+    }
+
+    pub fn reset(&self) {
+        self.ancestry.borrow_mut().clear();
+    }
+
+    // Returns the ancestry from the provider if one was set, otherwise from `node`.
+    pub fn get(
+        &self,
+        txid: &Txid,
+        node: &dyn NodeApi,
+    ) -> Result<Option<MempoolAncestry>, BitcoinCoordinatorError> {
+        if let Some(ancestry) = self.ancestry.borrow().get(txid) {
+            return Ok(*ancestry);
+        }
+
+        let ancestry = match &self.provider {
+            Some(provider) => provider.get_mempool_ancestry(txid)?,
+            None => node.get_mempool_ancestry(txid)?,
+        };
+
+        self.ancestry.borrow_mut().insert(*txid, ancestry);
+
+        Ok(ancestry)
+    }
+}
@@ -0,0 +1,107 @@
+use crate::types::DispatchDeferredReason;
+
+// A transaction to batch, with what it takes from the limits of the speedup chain.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BatchCandidate {
+    pub weight: u64,
+    // Vsize of the transaction and of a CPFP paying only for it, an upper bound of what it adds to the ancestors of the CPFP.
+    pub ancestor_vsize: u64,
+    // The transaction is never batched with other transactions.
+    pub exclusive: bool,
+}
+
+// What the speedup chain can still take when the batches are planned.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BatchLimits {
+    pub max_weight: u64,
+    // Unconfirmed transactions the chain can still take. A batch takes one for each transaction and one for its CPFP.
+    pub unconfirmed_slots: u32,
+    pub ancestor_vsize: u64,
+}
+
+// Batches planned by plan_batches, with the indexes of the candidates in the order they were given.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BatchPlan {
+    pub batches: Vec<Vec<usize>>,
+    // Candidates left for a later tick, with the limit that was reached.
+    pub deferred: Vec<(usize, DispatchDeferredReason)>,
+}
+
+impl BatchPlan {
+    // Unconfirmed slots taken by the batches, one for each transaction and one for each CPFP.
+    pub fn consumed_slots(&self) -> u32 {
+        self.batches
+            .iter()
+            .map(|batch| batch.len() as u32 + 1)
+            .sum()
+    }
+}
+
+// Splits the candidates in batches, each one paid by a single CPFP.
+// `closes_batch` tells if a candidate has to start a new batch instead of joining the open one, given the
+// indexes in the open batch and the index of the candidate, like when the CPFP would pay more than the fee cap.
+// Once a candidate does not fit in the unconfirmed slots or the ancestor size left, it is deferred with every
+// candidate after it, so the transactions are still dispatched in the order they were given.
+pub fn plan_batches<E>(
+    candidates: &[BatchCandidate],
+    limits: &BatchLimits,
+    mut closes_batch: impl FnMut(&[usize], usize) -> Result<bool, E>,
+) -> Result<BatchPlan, E> {
+    let mut plan = BatchPlan::default();
+    let mut current_batch: Vec<usize> = Vec::new();
+    let mut current_weight = 0;
+    let mut available_slots = limits.unconfirmed_slots;
+    let mut available_ancestor_vsize = limits.ancestor_vsize;
+    let mut deferred_reason = None;
+
+    for (index, candidate) in candidates.iter().enumerate() {
+        if let Some(reason) = deferred_reason {
+            plan.deferred.push((index, reason));
+            continue;
+        }
+
+        let joins_current_batch = !candidate.exclusive
+            && !current_batch.is_empty()
+            && current_weight + candidate.weight <= limits.max_weight
+            && !closes_batch(&current_batch, index)?;
+
+        // A transaction starting a batch also takes the slot of the CPFP paying for the batch.
+        let needed_slots = if joins_current_batch { 1 } else { 2 };
+
+        let reason = if needed_slots > available_slots {
+            Some(DispatchDeferredReason::UnconfirmedChainLimit)
+        } else if candidate.ancestor_vsize > available_ancestor_vsize {
+            Some(DispatchDeferredReason::AncestorSizeLimit)
+        } else {
+            None
+        };
+
+        if let Some(reason) = reason {
+            deferred_reason = Some(reason);
+            plan.deferred.push((index, reason));
+            continue;
+        }
+
+        available_slots -= needed_slots;
+        available_ancestor_vsize -= candidate.ancestor_vsize;
+
+        if candidate.exclusive {
+            plan.batches.push(vec![index]);
+            continue;
+        }
+
+        if !joins_current_batch && !current_batch.is_empty() {
+            plan.batches.push(std::mem::take(&mut current_batch));
+            current_weight = 0;
+        }
+
+        current_batch.push(index);
+        current_weight += candidate.weight;
+    }
+
+    if !current_batch.is_empty() {
+        plan.batches.push(current_batch);
+    }
+
+    Ok(plan)
+}
@@ -0,0 +1,66 @@
+use bitcoin_coordinator::{
+    admin::{self, AdminArgs, AdminCommand, USAGE},
+    config::CoordinatorConfig,
+    logging::enable_log_colors_for,
+};
+use bitvmx_settings::settings::load_config_file;
+use std::io::IsTerminal;
+use std::process::ExitCode;
+use tracing_subscriber::EnvFilter;
+
+// Operator tool to inspect and drive a coordinator from its configuration file, see `coordinator-admin --help`.
+fn main() -> ExitCode {
+    let args = match admin::parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    match run(args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {e:#}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: AdminArgs) -> Result<(), anyhow::Error> {
+    if args.command == AdminCommand::Help {
+        println!("{USAGE}");
+        return Ok(());
+    }
+
+    let config = load_config_file::<CoordinatorConfig>(Some(args.config_path.clone()))?;
+
+    // Logs go to stderr, so the output can be parsed when --json is given.
+    // They are plain text unless stderr is a terminal.
+    enable_log_colors_for(&std::io::stderr());
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::new(
+            config
+                .log_level
+                .clone()
+                .unwrap_or_else(|| "warn".to_string()),
+        ))
+        .with_writer(std::io::stderr)
+        .with_ansi(std::io::stderr().is_terminal())
+        .init();
+
+    // Status only reads the store, the other commands open the coordinator, which holds the store lock
+    // so they never run next to the service or another admin command.
+    let output = match args.command {
+        AdminCommand::Status => admin::status(&config)?,
+        _ => admin::execute(&admin::open_coordinator(&config)?, &args.command)?,
+    };
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    } else {
+        println!("{output}");
+    }
+
+    Ok(())
+}
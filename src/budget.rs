@@ -0,0 +1,81 @@
+use crate::types::TickBudgetUsage;
+use bitcoin::Txid;
+use std::cell::{Cell, RefCell};
+
+// Work a single tick can do, set by max_broadcasts_per_tick, max_speedups_per_tick and max_conflict_scan_blocks_per_tick.
+// It is reset at the start of each tick, the transactions left over are dispatched on the next ticks in the same order.
+#[derive(Default)]
+pub struct TickBudget {
+    max_broadcasts: Cell<Option<u32>>,
+    max_speedups: Cell<Option<u32>>,
+    max_conflict_scan_blocks: Cell<u32>,
+    broadcasts: Cell<u32>,
+    speedups: Cell<u32>,
+    conflict_scan_blocks: Cell<u32>,
+    // Transactions left for the next ticks because the budget ran out, in the order they were waiting.
+    deferred_txs: RefCell<Vec<Txid>>,
+}
+
+impl TickBudget {
+    // Starts the budget of a new tick. The limits are taken from the settings on each tick, so updates apply right away.
+    pub fn reset(
+        &self,
+        max_broadcasts: Option<u32>,
+        max_speedups: Option<u32>,
+        max_conflict_scan_blocks: u32,
+    ) {
+        self.max_broadcasts.set(max_broadcasts);
+        self.max_speedups.set(max_speedups);
+        self.max_conflict_scan_blocks.set(max_conflict_scan_blocks);
+        self.broadcasts.set(0);
+        self.speedups.set(0);
+        self.conflict_scan_blocks.set(0);
+        self.deferred_txs.borrow_mut().clear();
+    }
+
+    // Broadcasts left in the tick, None when there is no limit.
+    pub fn remaining_broadcasts(&self) -> Option<u32> {
+        self.max_broadcasts
+            .get()
+            .map(|max| max.saturating_sub(self.broadcasts.get()))
+    }
+
+    pub fn has_speedups_left(&self) -> bool {
+        self.max_speedups
+            .get()
+            .is_none_or(|max| self.speedups.get() < max)
+    }
+
+    // Blocks that can still be read to look for double spends in the tick.
+    pub fn remaining_conflict_scan_blocks(&self) -> u32 {
+        self.max_conflict_scan_blocks
+            .get()
+            .saturating_sub(self.conflict_scan_blocks.get())
+    }
+
+    pub fn record_broadcast(&self) {
+        self.broadcasts.set(self.broadcasts.get() + 1);
+    }
+
+    pub fn record_speedup(&self) {
+        self.speedups.set(self.speedups.get() + 1);
+    }
+
+    pub fn record_conflict_scan_block(&self) {
+        self.conflict_scan_blocks
+            .set(self.conflict_scan_blocks.get() + 1);
+    }
+
+    pub fn defer(&self, tx_ids: impl IntoIterator<Item = Txid>) {
+        self.deferred_txs.borrow_mut().extend(tx_ids);
+    }
+
+    pub fn usage(&self) -> TickBudgetUsage {
+        TickBudgetUsage {
+            broadcasts: self.broadcasts.get(),
+            speedups: self.speedups.get(),
+            conflict_scan_blocks: self.conflict_scan_blocks.get(),
+            deferred_txs: self.deferred_txs.borrow().clone(),
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use crate::types::SpeedupOutcome;
+
+// Share of the recorded speedups that waited longer than expected, None without outcomes.
+pub fn miss_rate(outcomes: &[SpeedupOutcome]) -> Option<f64> {
+    if outcomes.is_empty() {
+        return None;
+    }
+
+    let missed = outcomes.iter().filter(|outcome| outcome.missed).count();
+
+    Some(missed as f64 / outcomes.len() as f64)
+}
+
+// Average blocks the recorded speedups waited until their confirmation, None without outcomes.
+pub fn average_blocks_waited(outcomes: &[SpeedupOutcome]) -> Option<f64> {
+    if outcomes.is_empty() {
+        return None;
+    }
+
+    let blocks: u64 = outcomes
+        .iter()
+        .map(|outcome| outcome.blocks_waited as u64)
+        .sum();
+
+    Some(blocks as f64 / outcomes.len() as f64)
+}
+
+// Returns the factor the bump fee percentage of a stuck speedup is multiplied by.
+// `step` is the configured bump fee percentage. Half of the speedups missing their target keeps it as is, fewer misses
+// make it smaller and more misses make it bigger, from half its increase when no speedup missed to one and a half
+// times its increase when every speedup missed.
+// Without outcomes the miss rate is taken as one half.
+// `shortfall` is the current network fee rate over the one the stuck speedup was priced with. When the network moved
+// faster than the step, the step follows it.
+// The result is kept between `min` and `max`.
+pub fn next_bump_step(
+    step: f64,
+    min: f64,
+    max: f64,
+    miss_rate: Option<f64>,
+    shortfall: f64,
+) -> f64 {
+    let miss_rate = miss_rate.unwrap_or(0.5).clamp(0.0, 1.0);
+    let adjusted = 1.0 + (step - 1.0) * (0.5 + miss_rate);
+
+    adjusted.max(shortfall).clamp(min, max)
+}
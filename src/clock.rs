@@ -0,0 +1,26 @@
+use crate::settings::MAX_RETRY_BACKOFF_SECONDS;
+use chrono::Utc;
+
+// Source of the current time of the store, in milliseconds since the epoch.
+// The retries are scheduled with it, so tests can move the time forward instead of sleeping.
+pub trait Clock {
+    fn now_millis(&self) -> u64;
+}
+
+// Clock of the system, used unless another one is set with BitcoinCoordinatorStore::with_clock.
+#[derive(Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_millis(&self) -> u64 {
+        Utc::now().timestamp_millis() as u64
+    }
+}
+
+// Milliseconds to wait before the next retry, after `retries` retries were already made.
+// The interval doubles with each retry, up to MAX_RETRY_BACKOFF_SECONDS.
+pub fn retry_backoff_millis(interval_seconds: u64, retries: u32) -> u64 {
+    let backoff = interval_seconds.saturating_mul(2u64.saturating_pow(retries));
+
+    backoff.min(MAX_RETRY_BACKOFF_SECONDS.max(interval_seconds)) * 1000
+}
@@ -1,11 +1,26 @@
 use crate::errors::BitcoinCoordinatorError;
 use crate::settings::{
-    DEFAULT_BASE_FEE_MULTIPLIER, DEFAULT_BUMP_FEE_PERCENTAGE, DEFAULT_MAX_FEERATE_SAT_VB,
-    DEFAULT_MAX_RBF_ATTEMPTS, DEFAULT_MAX_TX_WEIGHT, DEFAULT_MAX_UNCONFIRMED_SPEEDUPS,
-    DEFAULT_MIN_BLOCKS_BEFORE_RESEND_SPEEDUP, DEFAULT_MIN_FUNDING_AMOUNT_SATS,
-    DEFAULT_MIN_NETWORK_FEE_RATE, DEFAULT_RBF_FEE_MULTIPLIER, DEFAULT_RETRY_ATTEMPTS_SENDING_TX,
-    DEFAULT_RETRY_INTERVAL_SECONDS, MAX_LIMIT_UNCONFIRMED_PARENTS,
+    DEFAULT_ALLOW_NONSTANDARD_ANCHOR, DEFAULT_AUTO_PRUNE_DEPTH_BLOCKS,
+    DEFAULT_AUTO_TOPUP_AMOUNT_SATS, DEFAULT_AUTO_TOPUP_BELOW_SATS, DEFAULT_BASE_FEE_MULTIPLIER,
+    DEFAULT_BUMP_FEE_PERCENTAGE, DEFAULT_CHECK_MEMPOOL_ANCESTRY, DEFAULT_CONFLICT_DETECTION_BLOCKS,
+    DEFAULT_DUST_THRESHOLD_SATS, DEFAULT_ENCRYPT_STORE, DEFAULT_FEE_OVERPAYMENT_RATIO,
+    DEFAULT_MAX_BROADCASTS_PER_TICK, DEFAULT_MAX_BUMP_FEE_PERCENTAGE,
+    DEFAULT_MAX_CONFLICT_SCAN_BLOCKS_PER_TICK, DEFAULT_MAX_CPFP_FEE_SATS_PER_BATCH,
+    DEFAULT_MAX_FEERATE_SAT_VB, DEFAULT_MAX_PAUSE_BLOCKS, DEFAULT_MAX_RBF_ATTEMPTS,
+    DEFAULT_MAX_REBROADCAST_ATTEMPTS, DEFAULT_MAX_SPEEDUPS_PER_TICK,
+    DEFAULT_MAX_SYNC_STALLED_TICKS, DEFAULT_MAX_TX_WEIGHT, DEFAULT_MAX_UNCONFIRMED_SPEEDUPS,
+    DEFAULT_MIN_BLOCKS_BEFORE_RESEND_SPEEDUP, DEFAULT_MIN_BUMP_FEE_PERCENTAGE,
+    DEFAULT_MIN_FUNDING_AMOUNT_SATS, DEFAULT_MIN_NETWORK_FEE_RATE, DEFAULT_NODE_FAILURE_THRESHOLD,
+    DEFAULT_OWNER_STALE_AFTER_SECONDS, DEFAULT_PAUSE_LOW_PRIORITY_ABOVE_SAT_VB,
+    DEFAULT_PAUSE_NORMAL_PRIORITY_ABOVE_SAT_VB, DEFAULT_QUIET_READY, DEFAULT_RBF_FEE_MULTIPLIER,
+    DEFAULT_REBROADCAST_AFTER_BLOCKS, DEFAULT_RETRY_ATTEMPTS_SENDING_TX,
+    DEFAULT_RETRY_INTERVAL_SECONDS, DEFAULT_TEST_MEMPOOL_ACCEPT, MAINNET_MAX_FEERATE_SAT_VB,
+    MAX_FEE_CONF_TARGET, MAX_LIMIT_UNCONFIRMED_PARENTS, MIN_FEE_CONF_TARGET,
+    SIGNET_MIN_BLOCKS_BEFORE_RESEND_SPEEDUP, TESTNET_MIN_NETWORK_FEE_RATE,
+    TEST_NETWORK_MAX_FEERATE_SAT_VB,
 };
+use crate::types::SettingChange;
+use bitcoin::Network;
 use bitvmx_bitcoin_rpc::rpc_config::RpcConfig;
 use bitvmx_transaction_monitor::config::{MonitorSettings, MonitorSettingsConfig};
 use key_manager::config::KeyManagerConfig;
@@ -17,6 +32,10 @@ use storage_backend::storage_config::StorageConfig;
 pub struct CoordinatorConfig {
     pub storage: StorageConfig,
     pub rpc: RpcConfig,
+    // Where the transactions are broadcast, the fee rate is estimated and the node calls are answered, the node of
+    // `rpc` unless set.
+    #[serde(default)]
+    pub backend: Backend,
     pub key_manager: KeyManagerConfig,
     pub key_storage: StorageConfig,
     pub settings: Option<CoordinatorSettingsConfig>,
@@ -35,9 +54,37 @@ pub struct CoordinatorSettings {
     pub monitor_settings: MonitorSettings,
     pub base_fee_multiplier: f64,
     pub bump_fee_percentage: f64,
+    pub min_bump_fee_percentage: f64,
+    pub max_bump_fee_percentage: f64,
     pub retry_interval_seconds: u64,
     pub retry_attempts_sending_tx: u32,
     pub min_network_fee_rate: u64,
+    pub conflict_detection_blocks: u32,
+    pub max_conflict_scan_blocks_per_tick: u32,
+    pub rebroadcast_after_blocks: u32,
+    pub max_rebroadcast_attempts: u32,
+    pub auto_prune_depth_blocks: Option<u32>,
+    pub max_cpfp_fee_sats_per_batch: Option<u64>,
+    pub max_broadcasts_per_tick: Option<u32>,
+    pub max_speedups_per_tick: Option<u32>,
+    pub auto_topup_below_sats: Option<u64>,
+    pub auto_topup_amount_sats: u64,
+    pub test_mempool_accept: bool,
+    pub allow_nonstandard_anchor: bool,
+    pub check_mempool_ancestry: bool,
+    pub encrypt_store: bool,
+    pub node_failure_threshold: u32,
+    pub max_sync_stalled_ticks: u32,
+    pub owner_stale_after_seconds: u64,
+    pub fee_overpayment_ratio: f64,
+    pub confirmation_milestones: Vec<u32>,
+    pub dust_threshold_sats: u64,
+    pub fee_strategy: FeeStrategy,
+    pub pause_normal_priority_above_sat_vb: Option<u64>,
+    pub pause_low_priority_above_sat_vb: Option<u64>,
+    pub max_pause_blocks: u32,
+    pub quiet_ready: bool,
+    pub mode: CoordinatorMode,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -52,9 +99,96 @@ pub struct CoordinatorSettingsConfig {
     pub monitor_settings: Option<MonitorSettingsConfig>,
     pub base_fee_multiplier: Option<f64>,
     pub bump_fee_percentage: Option<f64>,
+    pub min_bump_fee_percentage: Option<f64>,
+    pub max_bump_fee_percentage: Option<f64>,
     pub retry_interval_seconds: Option<u64>,
     pub retry_attempts_sending_tx: Option<u32>,
     pub min_network_fee_rate: Option<u64>,
+    pub conflict_detection_blocks: Option<u32>,
+    pub max_conflict_scan_blocks_per_tick: Option<u32>,
+    pub rebroadcast_after_blocks: Option<u32>,
+    pub max_rebroadcast_attempts: Option<u32>,
+    pub auto_prune_depth_blocks: Option<u32>,
+    pub max_cpfp_fee_sats_per_batch: Option<u64>,
+    pub max_broadcasts_per_tick: Option<u32>,
+    pub max_speedups_per_tick: Option<u32>,
+    pub auto_topup_below_sats: Option<u64>,
+    pub auto_topup_amount_sats: Option<u64>,
+    pub test_mempool_accept: Option<bool>,
+    pub allow_nonstandard_anchor: Option<bool>,
+    pub check_mempool_ancestry: Option<bool>,
+    pub encrypt_store: Option<bool>,
+    pub node_failure_threshold: Option<u32>,
+    pub max_sync_stalled_ticks: Option<u32>,
+    pub owner_stale_after_seconds: Option<u64>,
+    pub fee_overpayment_ratio: Option<f64>,
+    pub confirmation_milestones: Option<Vec<u32>>,
+    pub dust_threshold_sats: Option<u64>,
+    pub fee_strategy: Option<FeeStrategy>,
+    pub pause_normal_priority_above_sat_vb: Option<u64>,
+    pub pause_low_priority_above_sat_vb: Option<u64>,
+    pub max_pause_blocks: Option<u32>,
+    pub quiet_ready: Option<bool>,
+    pub mode: Option<CoordinatorMode>,
+}
+
+/// Source of the broadcasts, the tip height, the fee estimates, the funding output checks and the blocks read by
+/// the conflict detection. The monitor always indexes the blocks from the node of `rpc`, so a node is needed
+/// with either backend.
+#[derive(Debug, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Backend {
+    /// The bitcoind node of `rpc`.
+    #[default]
+    Rpc,
+    /// An Esplora HTTP API, like `https://blockstream.info/api`.
+    Esplora { url: String },
+}
+
+/// How the network fee rate paid by speedups is obtained.
+/// The result is never below `min_network_fee_rate` and never above `max_feerate_sat_vb`.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeStrategy {
+    /// Fee rate estimated by the node (estimatesmartfee).
+    /// Without conf_target nor mode the node defaults are used.
+    SmartFee {
+        conf_target: Option<u16>,
+        mode: Option<FeeEstimateMode>,
+    },
+    /// Always the same fee rate in sat/vB.
+    Fixed(u64),
+    /// Fee rate returned by the `FeeRateProvider` set with `BitcoinCoordinator::with_fee_rate_provider`.
+    External,
+}
+
+impl Default for FeeStrategy {
+    fn default() -> Self {
+        FeeStrategy::SmartFee {
+            conf_target: None,
+            mode: None,
+        }
+    }
+}
+
+/// Whether the coordinator broadcasts. A coordinator in standby follows its store, for example a replica
+/// kept for disaster recovery, without broadcasting anything until it is promoted with `promote`.
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CoordinatorMode {
+    /// Dispatches and speeds up the transactions.
+    #[default]
+    Active,
+    /// Follows the transactions, the speedups and the news without broadcasting.
+    Standby,
+}
+
+/// Estimate mode passed to estimatesmartfee.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeEstimateMode {
+    Economical,
+    Conservative,
 }
 
 impl Default for CoordinatorSettingsConfig {
@@ -70,14 +204,88 @@ impl Default for CoordinatorSettingsConfig {
             monitor_settings: Some(MonitorSettingsConfig::default()),
             base_fee_multiplier: Some(DEFAULT_BASE_FEE_MULTIPLIER),
             bump_fee_percentage: Some(DEFAULT_BUMP_FEE_PERCENTAGE),
+            min_bump_fee_percentage: Some(DEFAULT_MIN_BUMP_FEE_PERCENTAGE),
+            max_bump_fee_percentage: Some(DEFAULT_MAX_BUMP_FEE_PERCENTAGE),
             retry_interval_seconds: Some(DEFAULT_RETRY_INTERVAL_SECONDS),
             retry_attempts_sending_tx: Some(DEFAULT_RETRY_ATTEMPTS_SENDING_TX),
             min_network_fee_rate: Some(DEFAULT_MIN_NETWORK_FEE_RATE),
+            conflict_detection_blocks: Some(DEFAULT_CONFLICT_DETECTION_BLOCKS),
+            max_conflict_scan_blocks_per_tick: Some(DEFAULT_MAX_CONFLICT_SCAN_BLOCKS_PER_TICK),
+            rebroadcast_after_blocks: Some(DEFAULT_REBROADCAST_AFTER_BLOCKS),
+            max_rebroadcast_attempts: Some(DEFAULT_MAX_REBROADCAST_ATTEMPTS),
+            auto_prune_depth_blocks: DEFAULT_AUTO_PRUNE_DEPTH_BLOCKS,
+            max_cpfp_fee_sats_per_batch: DEFAULT_MAX_CPFP_FEE_SATS_PER_BATCH,
+            max_broadcasts_per_tick: DEFAULT_MAX_BROADCASTS_PER_TICK,
+            max_speedups_per_tick: DEFAULT_MAX_SPEEDUPS_PER_TICK,
+            auto_topup_below_sats: DEFAULT_AUTO_TOPUP_BELOW_SATS,
+            auto_topup_amount_sats: Some(DEFAULT_AUTO_TOPUP_AMOUNT_SATS),
+            test_mempool_accept: Some(DEFAULT_TEST_MEMPOOL_ACCEPT),
+            allow_nonstandard_anchor: Some(DEFAULT_ALLOW_NONSTANDARD_ANCHOR),
+            check_mempool_ancestry: Some(DEFAULT_CHECK_MEMPOOL_ANCESTRY),
+            encrypt_store: Some(DEFAULT_ENCRYPT_STORE),
+            node_failure_threshold: Some(DEFAULT_NODE_FAILURE_THRESHOLD),
+            max_sync_stalled_ticks: Some(DEFAULT_MAX_SYNC_STALLED_TICKS),
+            owner_stale_after_seconds: Some(DEFAULT_OWNER_STALE_AFTER_SECONDS),
+            fee_overpayment_ratio: Some(DEFAULT_FEE_OVERPAYMENT_RATIO),
+            confirmation_milestones: Some(Vec::new()),
+            dust_threshold_sats: Some(DEFAULT_DUST_THRESHOLD_SATS),
+            fee_strategy: Some(FeeStrategy::default()),
+            pause_normal_priority_above_sat_vb: DEFAULT_PAUSE_NORMAL_PRIORITY_ABOVE_SAT_VB,
+            pause_low_priority_above_sat_vb: DEFAULT_PAUSE_LOW_PRIORITY_ABOVE_SAT_VB,
+            max_pause_blocks: Some(DEFAULT_MAX_PAUSE_BLOCKS),
+            quiet_ready: Some(DEFAULT_QUIET_READY),
+            mode: Some(CoordinatorMode::default()),
         }
     }
 }
 
 impl CoordinatorSettingsConfig {
+    /// Default settings of `network`, the network presets over the defaults shared by every network.
+    pub fn defaults_for(network: Network) -> Self {
+        Self {
+            min_blocks_before_resend_speedup: None,
+            max_feerate_sat_vb: None,
+            min_network_fee_rate: None,
+            ..Default::default()
+        }
+        .with_network_defaults(network)
+    }
+
+    /// Fills the settings that depend on the network and are not set with the preset of `network`.
+    /// The settings that are set are always kept.
+    pub fn with_network_defaults(mut self, network: Network) -> Self {
+        let (min_blocks_before_resend_speedup, max_feerate_sat_vb, min_network_fee_rate) =
+            match network {
+                Network::Bitcoin => (
+                    DEFAULT_MIN_BLOCKS_BEFORE_RESEND_SPEEDUP,
+                    MAINNET_MAX_FEERATE_SAT_VB,
+                    DEFAULT_MIN_NETWORK_FEE_RATE,
+                ),
+                Network::Signet => (
+                    SIGNET_MIN_BLOCKS_BEFORE_RESEND_SPEEDUP,
+                    TEST_NETWORK_MAX_FEERATE_SAT_VB,
+                    DEFAULT_MIN_NETWORK_FEE_RATE,
+                ),
+                Network::Testnet | Network::Testnet4 => (
+                    DEFAULT_MIN_BLOCKS_BEFORE_RESEND_SPEEDUP,
+                    TEST_NETWORK_MAX_FEERATE_SAT_VB,
+                    TESTNET_MIN_NETWORK_FEE_RATE,
+                ),
+                _ => (
+                    DEFAULT_MIN_BLOCKS_BEFORE_RESEND_SPEEDUP,
+                    TEST_NETWORK_MAX_FEERATE_SAT_VB,
+                    DEFAULT_MIN_NETWORK_FEE_RATE,
+                ),
+            };
+
+        self.min_blocks_before_resend_speedup
+            .get_or_insert(min_blocks_before_resend_speedup);
+        self.max_feerate_sat_vb.get_or_insert(max_feerate_sat_vb);
+        self.min_network_fee_rate
+            .get_or_insert(min_network_fee_rate);
+        self
+    }
+
     pub fn validate(&self) -> Result<(), BitcoinCoordinatorError> {
         if let Some(max_unconfirmed_speedups) = self.max_unconfirmed_speedups {
             if max_unconfirmed_speedups == 0 {
@@ -212,6 +420,33 @@ impl CoordinatorSettingsConfig {
             }
         }
 
+        for (name, bump_bound) in [
+            ("min_bump_fee_percentage", self.min_bump_fee_percentage),
+            ("max_bump_fee_percentage", self.max_bump_fee_percentage),
+        ] {
+            if let Some(bump_bound) = bump_bound {
+                if !(1.0..=100.0).contains(&bump_bound) {
+                    return Err(BitcoinCoordinatorError::InvalidConfiguration(format!(
+                        "{} ({}) must be between 1.0 and 100.0",
+                        name, bump_bound
+                    )));
+                }
+            }
+        }
+
+        let min_bump_fee_percentage = self
+            .min_bump_fee_percentage
+            .unwrap_or(DEFAULT_MIN_BUMP_FEE_PERCENTAGE);
+        let max_bump_fee_percentage = self
+            .max_bump_fee_percentage
+            .unwrap_or(DEFAULT_MAX_BUMP_FEE_PERCENTAGE);
+        if min_bump_fee_percentage > max_bump_fee_percentage {
+            return Err(BitcoinCoordinatorError::InvalidConfiguration(format!(
+                "min_bump_fee_percentage ({}) must not exceed max_bump_fee_percentage ({})",
+                min_bump_fee_percentage, max_bump_fee_percentage
+            )));
+        }
+
         if let Some(retry_interval_seconds) = self.retry_interval_seconds {
             if retry_interval_seconds == 0 {
                 return Err(BitcoinCoordinatorError::InvalidConfiguration(format!(
@@ -253,6 +488,189 @@ impl CoordinatorSettingsConfig {
             }
         }
 
+        if let Some(conflict_detection_blocks) = self.conflict_detection_blocks {
+            if conflict_detection_blocks == 0 {
+                return Err(BitcoinCoordinatorError::InvalidConfiguration(format!(
+                    "conflict_detection_blocks must be greater than 0, got {}",
+                    conflict_detection_blocks
+                )));
+            }
+        }
+
+        if let Some(max_conflict_scan_blocks_per_tick) = self.max_conflict_scan_blocks_per_tick {
+            if max_conflict_scan_blocks_per_tick == 0 {
+                return Err(BitcoinCoordinatorError::InvalidConfiguration(format!(
+                    "max_conflict_scan_blocks_per_tick must be greater than 0, got {}",
+                    max_conflict_scan_blocks_per_tick
+                )));
+            }
+        }
+
+        if let Some(rebroadcast_after_blocks) = self.rebroadcast_after_blocks {
+            if rebroadcast_after_blocks == 0 {
+                return Err(BitcoinCoordinatorError::InvalidConfiguration(format!(
+                    "rebroadcast_after_blocks must be greater than 0, got {}",
+                    rebroadcast_after_blocks
+                )));
+            }
+        }
+
+        if let Some(max_rebroadcast_attempts) = self.max_rebroadcast_attempts {
+            if max_rebroadcast_attempts == 0 {
+                return Err(BitcoinCoordinatorError::InvalidConfiguration(format!(
+                    "max_rebroadcast_attempts must be greater than 0, got {}",
+                    max_rebroadcast_attempts
+                )));
+            }
+        }
+
+        if let Some(auto_prune_depth_blocks) = self.auto_prune_depth_blocks {
+            if auto_prune_depth_blocks == 0 {
+                return Err(BitcoinCoordinatorError::InvalidConfiguration(format!(
+                    "auto_prune_depth_blocks must be greater than 0, got {}",
+                    auto_prune_depth_blocks
+                )));
+            }
+        }
+
+        if let Some(max_cpfp_fee_sats_per_batch) = self.max_cpfp_fee_sats_per_batch {
+            if max_cpfp_fee_sats_per_batch == 0 {
+                return Err(BitcoinCoordinatorError::InvalidConfiguration(format!(
+                    "max_cpfp_fee_sats_per_batch must be greater than 0, got {}",
+                    max_cpfp_fee_sats_per_batch
+                )));
+            }
+        }
+
+        for (name, limit) in [
+            ("max_broadcasts_per_tick", self.max_broadcasts_per_tick),
+            ("max_speedups_per_tick", self.max_speedups_per_tick),
+        ] {
+            if limit == Some(0) {
+                return Err(BitcoinCoordinatorError::InvalidConfiguration(format!(
+                    "{name} must be greater than 0, got 0"
+                )));
+            }
+        }
+
+        let auto_topup_amount_sats = self
+            .auto_topup_amount_sats
+            .unwrap_or(DEFAULT_AUTO_TOPUP_AMOUNT_SATS);
+
+        if auto_topup_amount_sats == 0 {
+            return Err(BitcoinCoordinatorError::InvalidConfiguration(
+                "auto_topup_amount_sats must be greater than 0".to_string(),
+            ));
+        }
+
+        // A smaller top-up would leave the funding below the threshold and ask again right away.
+        if let Some(auto_topup_below_sats) = self.auto_topup_below_sats {
+            if auto_topup_amount_sats <= auto_topup_below_sats {
+                return Err(BitcoinCoordinatorError::InvalidConfiguration(format!(
+                    "auto_topup_amount_sats ({}) must be greater than auto_topup_below_sats ({})",
+                    auto_topup_amount_sats, auto_topup_below_sats
+                )));
+            }
+        }
+
+        if self.node_failure_threshold == Some(0) {
+            return Err(BitcoinCoordinatorError::InvalidConfiguration(
+                "node_failure_threshold must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.max_sync_stalled_ticks == Some(0) {
+            return Err(BitcoinCoordinatorError::InvalidConfiguration(
+                "max_sync_stalled_ticks must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.owner_stale_after_seconds == Some(0) {
+            return Err(BitcoinCoordinatorError::InvalidConfiguration(
+                "owner_stale_after_seconds must be greater than 0".to_string(),
+            ));
+        }
+
+        if let Some(fee_overpayment_ratio) = self.fee_overpayment_ratio {
+            if fee_overpayment_ratio < 1.0 {
+                return Err(BitcoinCoordinatorError::InvalidConfiguration(format!(
+                    "fee_overpayment_ratio ({}) must be at least 1.0",
+                    fee_overpayment_ratio
+                )));
+            }
+        }
+
+        if let Some(confirmation_milestones) = &self.confirmation_milestones {
+            if confirmation_milestones.contains(&0) {
+                return Err(BitcoinCoordinatorError::InvalidConfiguration(
+                    "confirmation_milestones must be greater than 0".to_string(),
+                ));
+            }
+        }
+
+        if let Some(dust_threshold_sats) = self.dust_threshold_sats {
+            if dust_threshold_sats < DEFAULT_DUST_THRESHOLD_SATS {
+                return Err(BitcoinCoordinatorError::InvalidConfiguration(format!(
+                    "dust_threshold_sats ({}) is below the P2WPKH dust limit of {} sats",
+                    dust_threshold_sats, DEFAULT_DUST_THRESHOLD_SATS
+                )));
+            }
+        }
+
+        match self.fee_strategy {
+            Some(FeeStrategy::SmartFee {
+                conf_target: Some(conf_target),
+                ..
+            }) if !(MIN_FEE_CONF_TARGET..=MAX_FEE_CONF_TARGET).contains(&conf_target) => {
+                return Err(BitcoinCoordinatorError::InvalidConfiguration(format!(
+                    "fee_strategy conf_target must be between {} and {}, got {}",
+                    MIN_FEE_CONF_TARGET, MAX_FEE_CONF_TARGET, conf_target
+                )));
+            }
+            Some(FeeStrategy::Fixed(0)) => {
+                return Err(BitcoinCoordinatorError::InvalidConfiguration(
+                    "fee_strategy fixed fee rate must be greater than 0".to_string(),
+                ));
+            }
+            _ => {}
+        }
+
+        for (name, threshold) in [
+            (
+                "pause_normal_priority_above_sat_vb",
+                self.pause_normal_priority_above_sat_vb,
+            ),
+            (
+                "pause_low_priority_above_sat_vb",
+                self.pause_low_priority_above_sat_vb,
+            ),
+        ] {
+            if threshold == Some(0) {
+                return Err(BitcoinCoordinatorError::InvalidConfiguration(format!(
+                    "{name} must be greater than 0, got 0"
+                )));
+            }
+        }
+
+        // Low urgency dispatches are paused first, a lower Normal threshold would pause them first.
+        if let (Some(normal), Some(low)) = (
+            self.pause_normal_priority_above_sat_vb,
+            self.pause_low_priority_above_sat_vb,
+        ) {
+            if low > normal {
+                return Err(BitcoinCoordinatorError::InvalidConfiguration(format!(
+                    "pause_low_priority_above_sat_vb ({}) cannot exceed pause_normal_priority_above_sat_vb ({})",
+                    low, normal
+                )));
+            }
+        }
+
+        if self.max_pause_blocks == Some(0) {
+            return Err(BitcoinCoordinatorError::InvalidConfiguration(
+                "max_pause_blocks must be greater than 0".to_string(),
+            ));
+        }
+
         // Cross-validation: min_network_fee_rate cannot exceed max_feerate_sat_vb
         if let (Some(min), Some(max)) = (self.min_network_fee_rate, self.max_feerate_sat_vb) {
             if min > max {
@@ -306,6 +724,14 @@ impl From<CoordinatorSettingsConfig> for CoordinatorSettings {
                 .bump_fee_percentage
                 .unwrap_or(DEFAULT_BUMP_FEE_PERCENTAGE),
 
+            min_bump_fee_percentage: settings
+                .min_bump_fee_percentage
+                .unwrap_or(DEFAULT_MIN_BUMP_FEE_PERCENTAGE),
+
+            max_bump_fee_percentage: settings
+                .max_bump_fee_percentage
+                .unwrap_or(DEFAULT_MAX_BUMP_FEE_PERCENTAGE),
+
             retry_interval_seconds: settings
                 .retry_interval_seconds
                 .unwrap_or(DEFAULT_RETRY_INTERVAL_SECONDS),
@@ -317,6 +743,372 @@ impl From<CoordinatorSettingsConfig> for CoordinatorSettings {
             min_network_fee_rate: settings
                 .min_network_fee_rate
                 .unwrap_or(DEFAULT_MIN_NETWORK_FEE_RATE),
+
+            conflict_detection_blocks: settings
+                .conflict_detection_blocks
+                .unwrap_or(DEFAULT_CONFLICT_DETECTION_BLOCKS),
+
+            max_conflict_scan_blocks_per_tick: settings
+                .max_conflict_scan_blocks_per_tick
+                .unwrap_or(DEFAULT_MAX_CONFLICT_SCAN_BLOCKS_PER_TICK),
+
+            rebroadcast_after_blocks: settings
+                .rebroadcast_after_blocks
+                .unwrap_or(DEFAULT_REBROADCAST_AFTER_BLOCKS),
+
+            max_rebroadcast_attempts: settings
+                .max_rebroadcast_attempts
+                .unwrap_or(DEFAULT_MAX_REBROADCAST_ATTEMPTS),
+
+            auto_prune_depth_blocks: settings
+                .auto_prune_depth_blocks
+                .or(DEFAULT_AUTO_PRUNE_DEPTH_BLOCKS),
+
+            max_cpfp_fee_sats_per_batch: settings
+                .max_cpfp_fee_sats_per_batch
+                .or(DEFAULT_MAX_CPFP_FEE_SATS_PER_BATCH),
+
+            max_broadcasts_per_tick: settings
+                .max_broadcasts_per_tick
+                .or(DEFAULT_MAX_BROADCASTS_PER_TICK),
+
+            max_speedups_per_tick: settings
+                .max_speedups_per_tick
+                .or(DEFAULT_MAX_SPEEDUPS_PER_TICK),
+
+            auto_topup_below_sats: settings
+                .auto_topup_below_sats
+                .or(DEFAULT_AUTO_TOPUP_BELOW_SATS),
+
+            auto_topup_amount_sats: settings
+                .auto_topup_amount_sats
+                .unwrap_or(DEFAULT_AUTO_TOPUP_AMOUNT_SATS),
+
+            test_mempool_accept: settings
+                .test_mempool_accept
+                .unwrap_or(DEFAULT_TEST_MEMPOOL_ACCEPT),
+
+            allow_nonstandard_anchor: settings
+                .allow_nonstandard_anchor
+                .unwrap_or(DEFAULT_ALLOW_NONSTANDARD_ANCHOR),
+
+            check_mempool_ancestry: settings
+                .check_mempool_ancestry
+                .unwrap_or(DEFAULT_CHECK_MEMPOOL_ANCESTRY),
+
+            encrypt_store: settings.encrypt_store.unwrap_or(DEFAULT_ENCRYPT_STORE),
+
+            node_failure_threshold: settings
+                .node_failure_threshold
+                .unwrap_or(DEFAULT_NODE_FAILURE_THRESHOLD),
+
+            max_sync_stalled_ticks: settings
+                .max_sync_stalled_ticks
+                .unwrap_or(DEFAULT_MAX_SYNC_STALLED_TICKS),
+
+            owner_stale_after_seconds: settings
+                .owner_stale_after_seconds
+                .unwrap_or(DEFAULT_OWNER_STALE_AFTER_SECONDS),
+
+            fee_overpayment_ratio: settings
+                .fee_overpayment_ratio
+                .unwrap_or(DEFAULT_FEE_OVERPAYMENT_RATIO),
+
+            // Milestones are reported in ascending order, each one once
+            confirmation_milestones: {
+                let mut milestones = settings.confirmation_milestones.unwrap_or_default();
+                milestones.sort_unstable();
+                milestones.dedup();
+                milestones
+            },
+
+            dust_threshold_sats: settings
+                .dust_threshold_sats
+                .unwrap_or(DEFAULT_DUST_THRESHOLD_SATS),
+
+            fee_strategy: settings.fee_strategy.unwrap_or_default(),
+
+            pause_normal_priority_above_sat_vb: settings
+                .pause_normal_priority_above_sat_vb
+                .or(DEFAULT_PAUSE_NORMAL_PRIORITY_ABOVE_SAT_VB),
+
+            pause_low_priority_above_sat_vb: settings
+                .pause_low_priority_above_sat_vb
+                .or(DEFAULT_PAUSE_LOW_PRIORITY_ABOVE_SAT_VB),
+
+            max_pause_blocks: settings
+                .max_pause_blocks
+                .unwrap_or(DEFAULT_MAX_PAUSE_BLOCKS),
+
+            quiet_ready: settings.quiet_ready.unwrap_or(DEFAULT_QUIET_READY),
+
+            mode: settings.mode.unwrap_or_default(),
         }
     }
 }
+
+impl CoordinatorSettings {
+    /// Settings used on `network` when none are configured.
+    pub fn defaults_for(network: Network) -> Self {
+        CoordinatorSettingsConfig::defaults_for(network).into()
+    }
+
+    /// `settings` on `network`, the settings not set take the default of the network.
+    pub fn resolve(settings: CoordinatorSettingsConfig, network: Network) -> Self {
+        settings.with_network_defaults(network).into()
+    }
+
+    // Combinations that are valid but most likely a config written for another network.
+    pub fn network_warnings(&self, network: Network) -> Vec<String> {
+        let mut warnings = Vec::new();
+
+        if network != Network::Bitcoin && self.max_feerate_sat_vb >= MAINNET_MAX_FEERATE_SAT_VB {
+            warnings.push(format!(
+                "max_feerate_sat_vb of {} sat/vb on {network} is a mainnet cap, {} sat/vb is enough on a test network",
+                self.max_feerate_sat_vb, TEST_NETWORK_MAX_FEERATE_SAT_VB
+            ));
+        }
+
+        if network == Network::Bitcoin && self.max_feerate_sat_vb > MAINNET_MAX_FEERATE_SAT_VB {
+            warnings.push(format!(
+                "max_feerate_sat_vb of {} sat/vb on mainnet is above the preset cap of {} sat/vb",
+                self.max_feerate_sat_vb, MAINNET_MAX_FEERATE_SAT_VB
+            ));
+        }
+
+        if network == Network::Signet
+            && self.min_blocks_before_resend_speedup < SIGNET_MIN_BLOCKS_BEFORE_RESEND_SPEEDUP
+        {
+            warnings.push(format!(
+                "min_blocks_before_resend_speedup of {} on signet replaces speedups before the sparse blocks can confirm them",
+                self.min_blocks_before_resend_speedup
+            ));
+        }
+
+        if matches!(network, Network::Testnet | Network::Testnet4)
+            && matches!(self.fee_strategy, FeeStrategy::SmartFee { .. })
+            && self.min_network_fee_rate < TESTNET_MIN_NETWORK_FEE_RATE
+        {
+            warnings.push(format!(
+                "min_network_fee_rate of {} sat/vb on {network} relies on the testnet fee estimates, {} sat/vb is the preset floor",
+                self.min_network_fee_rate, TESTNET_MIN_NETWORK_FEE_RATE
+            ));
+        }
+
+        warnings
+    }
+
+    // Settings that have a different value in `new`, with both values. The monitor settings are not compared.
+    pub fn changes(&self, new: &CoordinatorSettings) -> Vec<SettingChange> {
+        let value = |value: &dyn std::fmt::Debug| format!("{value:?}");
+
+        let settings = [
+            (
+                "max_unconfirmed_speedups",
+                value(&self.max_unconfirmed_speedups),
+                value(&new.max_unconfirmed_speedups),
+            ),
+            (
+                "max_tx_weight",
+                value(&self.max_tx_weight),
+                value(&new.max_tx_weight),
+            ),
+            (
+                "max_rbf_attempts",
+                value(&self.max_rbf_attempts),
+                value(&new.max_rbf_attempts),
+            ),
+            (
+                "min_funding_amount_sats",
+                value(&self.min_funding_amount_sats),
+                value(&new.min_funding_amount_sats),
+            ),
+            (
+                "rbf_fee_multiplier",
+                value(&self.rbf_fee_percentage),
+                value(&new.rbf_fee_percentage),
+            ),
+            (
+                "min_blocks_before_resend_speedup",
+                value(&self.min_blocks_before_resend_speedup),
+                value(&new.min_blocks_before_resend_speedup),
+            ),
+            (
+                "max_feerate_sat_vb",
+                value(&self.max_feerate_sat_vb),
+                value(&new.max_feerate_sat_vb),
+            ),
+            (
+                "base_fee_multiplier",
+                value(&self.base_fee_multiplier),
+                value(&new.base_fee_multiplier),
+            ),
+            (
+                "bump_fee_percentage",
+                value(&self.bump_fee_percentage),
+                value(&new.bump_fee_percentage),
+            ),
+            (
+                "min_bump_fee_percentage",
+                value(&self.min_bump_fee_percentage),
+                value(&new.min_bump_fee_percentage),
+            ),
+            (
+                "max_bump_fee_percentage",
+                value(&self.max_bump_fee_percentage),
+                value(&new.max_bump_fee_percentage),
+            ),
+            (
+                "retry_interval_seconds",
+                value(&self.retry_interval_seconds),
+                value(&new.retry_interval_seconds),
+            ),
+            (
+                "retry_attempts_sending_tx",
+                value(&self.retry_attempts_sending_tx),
+                value(&new.retry_attempts_sending_tx),
+            ),
+            (
+                "min_network_fee_rate",
+                value(&self.min_network_fee_rate),
+                value(&new.min_network_fee_rate),
+            ),
+            (
+                "conflict_detection_blocks",
+                value(&self.conflict_detection_blocks),
+                value(&new.conflict_detection_blocks),
+            ),
+            (
+                "max_conflict_scan_blocks_per_tick",
+                value(&self.max_conflict_scan_blocks_per_tick),
+                value(&new.max_conflict_scan_blocks_per_tick),
+            ),
+            (
+                "rebroadcast_after_blocks",
+                value(&self.rebroadcast_after_blocks),
+                value(&new.rebroadcast_after_blocks),
+            ),
+            (
+                "max_rebroadcast_attempts",
+                value(&self.max_rebroadcast_attempts),
+                value(&new.max_rebroadcast_attempts),
+            ),
+            (
+                "auto_prune_depth_blocks",
+                value(&self.auto_prune_depth_blocks),
+                value(&new.auto_prune_depth_blocks),
+            ),
+            (
+                "max_cpfp_fee_sats_per_batch",
+                value(&self.max_cpfp_fee_sats_per_batch),
+                value(&new.max_cpfp_fee_sats_per_batch),
+            ),
+            (
+                "max_broadcasts_per_tick",
+                value(&self.max_broadcasts_per_tick),
+                value(&new.max_broadcasts_per_tick),
+            ),
+            (
+                "max_speedups_per_tick",
+                value(&self.max_speedups_per_tick),
+                value(&new.max_speedups_per_tick),
+            ),
+            (
+                "auto_topup_below_sats",
+                value(&self.auto_topup_below_sats),
+                value(&new.auto_topup_below_sats),
+            ),
+            (
+                "auto_topup_amount_sats",
+                value(&self.auto_topup_amount_sats),
+                value(&new.auto_topup_amount_sats),
+            ),
+            (
+                "test_mempool_accept",
+                value(&self.test_mempool_accept),
+                value(&new.test_mempool_accept),
+            ),
+            (
+                "allow_nonstandard_anchor",
+                value(&self.allow_nonstandard_anchor),
+                value(&new.allow_nonstandard_anchor),
+            ),
+            (
+                "check_mempool_ancestry",
+                value(&self.check_mempool_ancestry),
+                value(&new.check_mempool_ancestry),
+            ),
+            (
+                "encrypt_store",
+                value(&self.encrypt_store),
+                value(&new.encrypt_store),
+            ),
+            (
+                "node_failure_threshold",
+                value(&self.node_failure_threshold),
+                value(&new.node_failure_threshold),
+            ),
+            (
+                "max_sync_stalled_ticks",
+                value(&self.max_sync_stalled_ticks),
+                value(&new.max_sync_stalled_ticks),
+            ),
+            (
+                "owner_stale_after_seconds",
+                value(&self.owner_stale_after_seconds),
+                value(&new.owner_stale_after_seconds),
+            ),
+            (
+                "fee_overpayment_ratio",
+                value(&self.fee_overpayment_ratio),
+                value(&new.fee_overpayment_ratio),
+            ),
+            (
+                "confirmation_milestones",
+                value(&self.confirmation_milestones),
+                value(&new.confirmation_milestones),
+            ),
+            (
+                "dust_threshold_sats",
+                value(&self.dust_threshold_sats),
+                value(&new.dust_threshold_sats),
+            ),
+            (
+                "fee_strategy",
+                value(&self.fee_strategy),
+                value(&new.fee_strategy),
+            ),
+            (
+                "pause_normal_priority_above_sat_vb",
+                value(&self.pause_normal_priority_above_sat_vb),
+                value(&new.pause_normal_priority_above_sat_vb),
+            ),
+            (
+                "pause_low_priority_above_sat_vb",
+                value(&self.pause_low_priority_above_sat_vb),
+                value(&new.pause_low_priority_above_sat_vb),
+            ),
+            (
+                "max_pause_blocks",
+                value(&self.max_pause_blocks),
+                value(&new.max_pause_blocks),
+            ),
+            (
+                "quiet_ready",
+                value(&self.quiet_ready),
+                value(&new.quiet_ready),
+            ),
+            ("mode", value(&self.mode), value(&new.mode)),
+        ];
+
+        settings
+            .into_iter()
+            .filter(|(_, old_value, new_value)| old_value != new_value)
+            .map(|(name, old_value, new_value)| SettingChange {
+                name: name.to_string(),
+                old_value,
+                new_value,
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,147 @@
+use crate::types::{
+    ConfirmationStats, CoordinatedTransaction, FinalizedTxStats, PackageFeeReport, SpeedupState,
+    SpeedupSummary,
+};
+use bitvmx_bitcoin_rpc::types::BlockHeight;
+
+// Summarizes a transaction when it is finalized.
+// Returns None for transactions saved before their broadcast and confirmation heights were recorded.
+pub fn finalized_tx_stats(tx: &CoordinatedTransaction) -> Option<FinalizedTxStats> {
+    let vsize = tx.tx.vsize() as u64;
+
+    Some(FinalizedTxStats {
+        tx_id: tx.tx_id,
+        first_broadcast_block_height: tx.first_broadcast_block_height?,
+        first_confirmation_block_height: tx.first_confirmation_block_height?,
+        bump_count: tx.bump_count,
+        // Same assumption as the speedup fee, each parent transaction pays 1 sat/vbyte.
+        fee: vsize + tx.bump_fees,
+        vsize,
+        fee_report: tx.fee_report.clone(),
+    })
+}
+
+// Returns the number of speedups spent on a transaction and the sats attributable to it.
+// The fee of a speedup is split evenly between the transactions it pays, and a replacement (RBF) takes the
+// place of the last speedup, so only the speedup that was mined is paid.
+pub fn speedup_costs(speedups: &[SpeedupSummary]) -> (u32, u64) {
+    let count = speedups
+        .iter()
+        .filter(|speedup| speedup.state != SpeedupState::Error)
+        .count() as u32;
+    let fees = paid_speedups(speedups)
+        .iter()
+        .map(|speedup| share(speedup.fee, speedup))
+        .sum();
+
+    (count, fees)
+}
+
+// Computes the fee rate paid by a finalized transaction and its confirmed speedups.
+// Without `parent_fee` the transaction is assumed to pay 1 sat/vbyte, as in the confirmation stats.
+pub fn package_fee_report(
+    tx: &CoordinatedTransaction,
+    parent_fee: Option<u64>,
+    speedups: &[SpeedupSummary],
+    reference_fee_rate: Option<u64>,
+) -> PackageFeeReport {
+    let parent_vsize = tx.tx.vsize() as u64;
+    let confirmed: Vec<&SpeedupSummary> = paid_speedups(speedups)
+        .into_iter()
+        .filter(|speedup| {
+            matches!(
+                speedup.state,
+                SpeedupState::Confirmed | SpeedupState::Finalized
+            )
+        })
+        .collect();
+
+    let speedup_fees: u64 = confirmed
+        .iter()
+        .map(|speedup| share(speedup.fee, speedup))
+        .sum();
+    let vsize = parent_vsize
+        + confirmed
+            .iter()
+            .map(|speedup| share(speedup.vsize, speedup))
+            .sum::<u64>();
+    let fee = parent_fee.unwrap_or(parent_vsize) + speedup_fees;
+
+    PackageFeeReport {
+        parent_fee,
+        speedup_fees,
+        vsize,
+        effective_fee_rate: fee as f64 / vsize.max(1) as f64,
+        reference_fee_rate,
+    }
+}
+
+// Speedups that were sent, a replacement (RBF) taking the place of the last speedup.
+fn paid_speedups(speedups: &[SpeedupSummary]) -> Vec<&SpeedupSummary> {
+    let mut paid = Vec::new();
+
+    for speedup in speedups
+        .iter()
+        .filter(|speedup| speedup.state != SpeedupState::Error)
+    {
+        if speedup.is_rbf {
+            paid.pop();
+        }
+
+        paid.push(speedup);
+    }
+
+    paid
+}
+
+// Part of `amount` attributable to each of the transactions paid by the speedup.
+fn share(amount: u64, speedup: &SpeedupSummary) -> u64 {
+    amount / speedup.paid_txids.len().max(1) as u64
+}
+
+// Aggregates the transactions first confirmed in the last `window_blocks` blocks up to `current_height`.
+pub fn confirmation_stats(
+    stats: &[FinalizedTxStats],
+    current_height: BlockHeight,
+    window_blocks: u32,
+) -> ConfirmationStats {
+    let in_window: Vec<&FinalizedTxStats> = stats
+        .iter()
+        .filter(|tx| {
+            tx.first_confirmation_block_height
+                .saturating_add(window_blocks)
+                > current_height
+        })
+        .collect();
+
+    if in_window.is_empty() {
+        return ConfirmationStats::default();
+    }
+
+    let mut blocks_to_confirm: Vec<u32> = in_window
+        .iter()
+        .map(|tx| {
+            tx.first_confirmation_block_height
+                .saturating_sub(tx.first_broadcast_block_height)
+        })
+        .collect();
+    blocks_to_confirm.sort_unstable();
+
+    let fee: u64 = in_window.iter().map(|tx| tx.fee).sum();
+    let vsize: u64 = in_window.iter().map(|tx| tx.vsize).sum();
+
+    ConfirmationStats {
+        tx_count: in_window.len() as u32,
+        median_blocks_to_confirm: percentile(&blocks_to_confirm, 50),
+        p90_blocks_to_confirm: percentile(&blocks_to_confirm, 90),
+        avg_fee_rate_sat_vb: fee as f64 / vsize.max(1) as f64,
+        bumped_tx_count: in_window.iter().filter(|tx| tx.bump_count > 0).count() as u32,
+    }
+}
+
+// Nearest-rank percentile of sorted values, which can not be empty.
+fn percentile(sorted: &[u32], percent: usize) -> u32 {
+    let rank = (sorted.len() * percent).div_ceil(100);
+
+    sorted[rank.saturating_sub(1)]
+}
@@ -0,0 +1,26 @@
+use crate::errors::BitcoinCoordinatorError;
+use bitcoin::{OutPoint, Transaction, Txid};
+
+// Looks for a confirmed transaction, other than `tx`, spending one of the inputs of `tx`.
+// `get_confirmed_spender` receives an input outpoint and returns the txid of the confirmed transaction spending it,
+// or None when the outpoint is unspent or only spent in the mempool.
+pub fn find_conflicting_tx<F>(
+    tx: &Transaction,
+    mut get_confirmed_spender: F,
+) -> Result<Option<Txid>, BitcoinCoordinatorError>
+where
+    F: FnMut(&OutPoint) -> Result<Option<Txid>, BitcoinCoordinatorError>,
+{
+    let txid = tx.compute_txid();
+
+    for input in tx.input.iter() {
+        if let Some(spender_txid) = get_confirmed_spender(&input.previous_output)? {
+            // The transaction itself could be confirmed before the monitor indexes it.
+            if spender_txid != txid {
+                return Ok(Some(spender_txid));
+            }
+        }
+    }
+
+    Ok(None)
+}
@@ -1,39 +1,173 @@
 use crate::{
-    config::{CoordinatorSettings, CoordinatorSettingsConfig},
-    errors::{BitcoinBroadcastErrorKind, BitcoinCoordinatorError},
-    settings::CPFP_TRANSACTION_CONTEXT,
+    ancestry::{MempoolAncestryCache, MempoolAncestryProvider},
+    batching::{plan_batches, BatchCandidate, BatchLimits},
+    budget::TickBudget,
+    bump::{average_blocks_waited, miss_rate, next_bump_step},
+    config::{
+        Backend, CoordinatorConfig, CoordinatorMode, CoordinatorSettings,
+        CoordinatorSettingsConfig, FeeEstimateMode,
+    },
+    confirmation_stats::{confirmation_stats, package_fee_report, speedup_costs},
+    conflict::find_conflicting_tx,
+    cpfp::{build_cpfp_tx, build_cpfp_tx_without_change, p2wpkh_hash, SpeedupOutputKind},
+    diagnosis::{
+        blocking_reasons, last_dispatch_attempt, last_rbf_block_height, last_speedup_fee_rate,
+    },
+    encryption::StoreCipher,
+    errors::{
+        BitcoinCoordinatorError, BitcoinCoordinatorStoreError, BroadcastFailureAction,
+        BroadcastFailureKind,
+    },
+    esplora::EsploraClient,
+    fee::{FeeRateEstimate, FeeRateEstimator, FeeRateProvider, SmartFeeEstimator},
+    finalized::FinalizedSink,
+    funding::{FundingOutputChecker, FundingOutputState, FundingProvider},
+    lock::StoreLock,
+    locktime::{absolute_lock_height, relative_lock_height, relative_locks},
+    logging::TickSummary,
+    news::{filter_monitor_news, monitor_news_severity, undelivered_news, NewsSubscriber},
+    node::NodeApi,
+    node_health::NodeCircuitBreaker,
+    observer::{CoordinatorObserver, NoopCoordinatorObserver},
+    parent_rbf::{compute_parent_replacement, ParentTxSigner},
+    pegin::record_detected_pegins,
+    prevout::PrevoutCache,
+    rbf::{escalate_replacement, RbfEscalation},
+    readiness::{readiness_report, sync_to_tip},
+    rebroadcast::rebroadcast_missing_tx,
+    review::{ReviewDecision, SpeedupReviewHook},
+    settings::{
+        ANCHOR_SPEND_CONTEXT, CPFP_TRANSACTION_CONTEXT, DEFAULT_FEE_CONF_TARGET,
+        DEFAULT_MAX_FEERATE_SAT_VB, FINALIZED_DELIVERY_PAGE_SIZE, FUNDING_TRANSACTION_CONTEXT,
+        JOURNAL_EXPORT_PAGE_SIZE, MAX_ANCESTOR_SIZE_VBYTES, MAX_LIMIT_UNCONFIRMED_PARENTS,
+        MAX_STORE_WRITE_ATTEMPTS, NEWS_SUBSCRIPTION_CAPACITY,
+    },
     speedup::SpeedupStore,
     storage::{BitcoinCoordinatorStore, BitcoinCoordinatorStoreApi},
     types::{
-        AckNews, CoordinatedSpeedUpTransaction, CoordinatedTransaction, CoordinatorNews, News,
-        SpeedupState, TransactionState,
+        AckNews, BatchCostEstimate, BumpStrategyState, ConfirmationStats, ConflictScanProgress,
+        ContextCancelSummary, CoordinatedSpeedUpTransaction, CoordinatedTransaction,
+        CoordinatorNews, CoordinatorRunState, DetectedPegin, DispatchCostEstimate,
+        DispatchDeferredReason, DispatchOptions, DispatchUrgency, FinalizedDelivery,
+        FinalizedTxEntry, FundingSummary, GroupMember, GroupMemberState, GroupStatus,
+        InternalMonitor, JournalEntry, JournalEvent, News, NewsPage, ParentReplacementIntent,
+        PendingOverview, PruneSummary, ReadinessReport, Severity, ShutdownCheckpoint,
+        ShutdownReport, SpeedupIntent, SpeedupOutcome, SpeedupParent, SpeedupRejection,
+        SpeedupState, SpeedupSummary, TransactionHistory, TransactionState, TxDiagnosis,
+        UtxoSetMember, WatchedFinality, WatchedOutpoint, WatchedUtxoSet,
     },
+    validation::{validate_anchor, validate_context, validate_tx_to_dispatch},
+    write_queue::{PendingStoreWrite, StoreWriteQueue},
 };
-use bitcoin::{Network, Transaction, Txid};
+use bitcoin::{
+    absolute::LockTime, hashes::Hash, transaction::Version, Amount, BlockHash, Network, OutPoint,
+    PublicKey, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Txid, WPubkeyHash, Witness,
+};
+use bitcoincore_rpc::{Auth, Client};
 use bitvmx_bitcoin_rpc::{bitcoin_client::BitcoinClient, rpc_config::RpcConfig};
-use bitvmx_bitcoin_rpc::{bitcoin_client::BitcoinClientApi, types::BlockHeight};
+use bitvmx_bitcoin_rpc::{
+    bitcoin_client::BitcoinClientApi, errors::BitcoinClientError, types::BlockHeight,
+};
 use bitvmx_transaction_monitor::{
     errors::MonitorError,
     monitor::{Monitor, MonitorApi},
-    types::{AckMonitorNews, MonitorNews, MonitorType, TransactionStatus, TypesToMonitor},
+    types::{AckMonitorNews, BlockInfo, MonitorNews, TransactionStatus, TypesToMonitor},
 };
 use console::style;
-use key_manager::key_manager::KeyManager;
+use key_manager::{create_key_manager_from_config, key_manager::KeyManager};
 use protocol_builder::{
     builder::ProtocolBuilder,
     types::{output::SpeedupData, Utxo},
 };
-use std::{rc::Rc, vec};
+use std::{
+    cell::{Cell, Ref, RefCell},
+    collections::{HashMap, HashSet},
+    fs::File,
+    io::BufWriter,
+    path::Path,
+    rc::Rc,
+    sync::mpsc::{self, Receiver, TrySendError},
+    time::Instant,
+    vec,
+};
 use storage_backend::storage::Storage;
 use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+// Batches of transactions and the transactions deferred by the CPFP fee cap, with their estimated fee.
+type BatchedTxs = (
+    Vec<Vec<CoordinatedTransaction>>,
+    Vec<(Txid, u64)>,
+    Vec<(Txid, DispatchDeferredReason)>,
+);
+
+// A step of the tick pipeline run once the monitor is ready.
+type TickStep = fn(&BitcoinCoordinator) -> Result<(), BitcoinCoordinatorError>;
 
 pub struct BitcoinCoordinator {
-    monitor: MonitorType,
+    monitor: Box<dyn MonitorApi>,
     key_manager: Rc<KeyManager>,
     store: BitcoinCoordinatorStore,
-    client: BitcoinClient,
-    _network: Network,
-    settings: CoordinatorSettings,
+    client: Box<dyn BitcoinClientApi>,
+    // Node calls the client does not expose: the conflict detection, the mempool checks and the fee estimates.
+    node: Box<dyn NodeApi>,
+    // Network of the node, the settings not set take its defaults.
+    network: Network,
+    // Changed at runtime with update_settings.
+    settings: RefCell<CoordinatorSettings>,
+    // Whether the dispatched transactions left without a speedup by a previous run were already recovered.
+    recovered: Cell<bool>,
+    // Whether a snapshot was imported and what it watches is not registered in the monitor yet.
+    remonitor: Cell<bool>,
+    // Height of the last automatic prune of the store.
+    last_prune_height: Cell<Option<BlockHeight>>,
+    // Monitor height and block hash of the tick in progress, asked once and forgotten when the tick ends.
+    tick_height: Cell<Option<BlockHeight>>,
+    tick_block_hash: Cell<Option<BlockHash>>,
+    // Block of the last NewBlock news, so each block is reported once.
+    last_new_block: Cell<Option<BlockHash>>,
+    // Transactions that failed during the tick in progress, the other transactions are still processed.
+    tick_failures: Cell<u32>,
+    // Hooks to export metrics, a no-op observer unless one is set with with_observer.
+    observer: Rc<dyn CoordinatorObserver>,
+    // Network fee rate estimated with the configured fee strategy, once per tick.
+    fee_estimator: FeeRateEstimator,
+    // Mempool ancestry of the funding asked to the node, once per tick.
+    mempool_ancestry: MempoolAncestryCache,
+    // Output amounts spent by the transactions finalized in a tick, asked to the client once per tick.
+    prevouts: PrevoutCache,
+    // Asked for more funding when it runs low, the funding is only added manually when it is not set.
+    funding_provider: Option<Rc<dyn FundingProvider>>,
+    // Asked whether the funding is still unspent before a CPFP spends it, the node unless one is set.
+    funding_output_checker: Option<Rc<dyn FundingOutputChecker>>,
+    // Asked for the estimates of the SmartFee fee strategy, the node unless one is set.
+    smart_fee_estimator: Option<Rc<dyn SmartFeeEstimator>>,
+    // Signs the replacements of the transactions dispatched with allow_rbf_of_parent.
+    parent_tx_signer: Option<Rc<dyn ParentTxSigner>>,
+    // Asked whether each signed speedup can be broadcast, speedups are broadcast unreviewed unless one is set.
+    speedup_review_hook: Option<Rc<dyn SpeedupReviewHook>>,
+    // Receives the finalized transactions at the end of each tick, they are only kept in the log unless one is set.
+    finalized_sink: Option<Rc<dyn FinalizedSink>>,
+    // Opens after node_failure_threshold consecutive failures reaching the node, ticks only probe the node while it is open.
+    node_breaker: NodeCircuitBreaker,
+    // Store writes that failed after a broadcast, retried at the start of the next ticks.
+    pending_writes: StoreWriteQueue,
+    // Consumers the news are pushed to at the end of each tick.
+    news_subscribers: RefCell<Vec<NewsSubscriber>>,
+    // Broadcasts and speedups done in the tick in progress, limited by max_broadcasts_per_tick and max_speedups_per_tick.
+    tick_budget: TickBudget,
+    // Work done in the tick in progress, logged in a single line when the tick ends.
+    tick_summary: TickSummary,
+    // Readiness of the monitor on the last tick, logged only when it changes unless quiet_ready is disabled.
+    last_ready: Cell<Option<bool>>,
+    // Run state the previous coordinator left in the store, read when this one was built.
+    previous_run_state: Option<CoordinatorRunState>,
+    // Set by shutdown, the calls that change the work of the coordinator are rejected afterwards.
+    stopped: Cell<bool>,
+    // Id recorded as the owner of the store, the calls that change the store fail once another instance took it over.
+    instance_id: Uuid,
+    // Lock of the storage directory taken by open, released when the coordinator is dropped.
+    store_lock: Option<StoreLock>,
 }
 
 pub trait BitcoinCoordinatorApi {
@@ -41,6 +175,19 @@ pub trait BitcoinCoordinatorApi {
     /// Returns true if the coordinator is ready, false otherwise
     fn is_ready(&self) -> Result<bool, BitcoinCoordinatorError>;
 
+    /// Reports how far the blockchain indexing has progressed
+    /// Returns the monitor indexed height, the node tip height, the blocks remaining, whether there are
+    /// transactions waiting to be dispatched and whether the coordinator is ready.
+    fn readiness(&self) -> Result<ReadinessReport, BitcoinCoordinatorError>;
+
+    /// Ticks the monitor until the blockchain is indexed up to the node tip, without processing transactions
+    /// Calls `progress` with the indexed height and the tip height after each tick.
+    /// Fails with `SyncStalled` when no block is indexed in `max_sync_stalled_ticks` consecutive ticks.
+    fn sync_to_tip(
+        &self,
+        progress: Option<&dyn Fn(BlockHeight, BlockHeight)>,
+    ) -> Result<(), BitcoinCoordinatorError>;
+
     /// Processes pending transactions and updates their status
     /// This method should be called periodically to keep the coordinator state up-to-date
     fn tick(&self) -> Result<(), BitcoinCoordinatorError>;
@@ -52,12 +199,122 @@ pub trait BitcoinCoordinatorApi {
     /// * `data` - The data to monitor
     fn monitor(&self, data: TypesToMonitor) -> Result<(), BitcoinCoordinatorError>;
 
+    /// Registers a type of data to be monitored, with the confirmations after which it is finalized
+    /// Only transactions accept a finality, it must be between 1 and `max_monitoring_confirmations`.
+    /// Once a transaction reaches it, the coordinator stops monitoring it and no more news are reported for it.
+    /// The finality is persisted, and cancelling the transactions removes it.
+    ///
+    /// # Arguments
+    /// * `data` - The data to monitor
+    /// * `finality_confirmations` - Confirmations to finalize the transactions (None means `max_monitoring_confirmations`)
+    fn monitor_with_options(
+        &self,
+        data: TypesToMonitor,
+        finality_confirmations: Option<u32>,
+    ) -> Result<(), BitcoinCoordinatorError>;
+
+    /// Watches an output of a transaction not dispatched by the coordinator until it is spent
+    /// The subscription is persisted, and once a transaction spending the outpoint is mined an `OutpointSpent`
+    /// news is reported with the spending transaction, the input that consumed the outpoint and the block.
+    /// Cancelling a `TypesToMonitor::SpendingUTXOTransaction` for the outpoint removes the subscription.
+    ///
+    /// # Arguments
+    /// * `outpoint` - The output to watch
+    /// * `context` - Additional context information to be returned in the news
+    fn watch_outpoint(
+        &self,
+        outpoint: OutPoint,
+        context: String,
+    ) -> Result<(), BitcoinCoordinatorError>;
+
+    /// Watches an output script, e.g. a protocol address a counterparty deposits into
+    /// The subscription is persisted, and each transaction paying to the script mined in a block processed by the
+    /// coordinator is reported with an `AddressFunded` news holding the transaction, the matched outputs and the context.
+    /// Watching a script again replaces its context.
+    ///
+    /// # Arguments
+    /// * `script_pubkey` - The output script to watch
+    /// * `context` - Additional context information to be returned in the news
+    fn monitor_address(
+        &self,
+        script_pubkey: ScriptBuf,
+        context: String,
+    ) -> Result<(), BitcoinCoordinatorError>;
+
+    /// Stops watching an output script
+    /// Returns false if the script was not watched.
+    fn cancel_monitor_address(
+        &self,
+        script_pubkey: &ScriptBuf,
+    ) -> Result<bool, BitcoinCoordinatorError>;
+
+    /// Watches a set of outputs that must stay unspent, e.g. the collateral posted for a protocol session
+    /// The set is persisted and registered again in the monitor when the coordinator is built. Each member spent by
+    /// a transaction mined in a block is reported with a `CollateralSpent` news holding the label, the outpoint, the
+    /// spending transaction and the members left unspent, and a `CollateralFullySpent` news follows once every
+    /// member is spent. A label can only be watched once until it is cancelled (`UtxoSetAlreadyWatched`).
+    ///
+    /// # Arguments
+    /// * `label` - Name of the set, returned in the news
+    /// * `outpoints` - The members of the set, at least one
+    /// * `context` - Context the outpoints are monitored with
+    fn monitor_utxo_set(
+        &self,
+        label: String,
+        outpoints: Vec<OutPoint>,
+        context: String,
+    ) -> Result<(), BitcoinCoordinatorError>;
+
+    /// Returns the members of a watched UTXO set with the transactions that spent them
+    /// Fails with `UnknownUtxoSet` if no set is watched with the label.
+    fn get_utxo_set_status(&self, label: &str) -> Result<WatchedUtxoSet, BitcoinCoordinatorError>;
+
+    /// Stops watching a UTXO set, its members are no longer monitored
+    /// Returns false if no set was watched with the label.
+    fn cancel_utxo_set(&self, label: &str) -> Result<bool, BitcoinCoordinatorError>;
+
+    /// Monitors transactions as a group, e.g. the transactions of a protocol round
+    /// The members and the context are persisted under the returned group id. A member is finalized once it
+    /// reaches `finality_confirmations` (None means `max_monitoring_confirmations`), and a single `GroupCompleted`
+    /// news is reported once every member is finalized or cancelled.
+    ///
+    /// # Arguments
+    /// * `tx_ids` - The members of the group, at least one
+    /// * `context` - Context the transactions are monitored with
+    /// * `finality_confirmations` - Confirmations to finalize the members
+    fn monitor_group(
+        &self,
+        tx_ids: Vec<Txid>,
+        context: String,
+        finality_confirmations: Option<u32>,
+    ) -> Result<Uuid, BitcoinCoordinatorError>;
+
+    /// Dispatches transactions as a group with `dispatch_batch`, all of them with the same context
+    /// Returns the group id, the members are finalized with their transactions. A single `GroupCompleted` news is
+    /// reported once every member is finalized or cancelled.
+    ///
+    /// # Arguments
+    /// * `txs` - The Bitcoin transactions to dispatch with their speed up information
+    /// * `context` - Context of the transactions, returned in the news
+    /// * `block_height` - Block height to dispatch the transactions (None means now)
+    fn dispatch_group(
+        &self,
+        txs: Vec<(Transaction, Option<SpeedupData>)>,
+        context: String,
+        block_height: Option<BlockHeight>,
+    ) -> Result<Uuid, BitcoinCoordinatorError>;
+
+    /// Returns the state of each member of a group and whether the group is complete
+    /// Fails with `UnknownGroup` if no group has the id.
+    fn get_group_status(&self, group_id: Uuid) -> Result<GroupStatus, BitcoinCoordinatorError>;
+
     /// Dispatches a transaction to the Bitcoin network
     ///
     /// # Arguments
     /// * `tx` - The Bitcoin transaction to dispatch
     /// * `speedup` - Speed up information for the transaction (None means it should not be speed up)
-    /// * `context` - Additional context information for the transaction to be returned in news
+    /// * `context` - Additional context information for the transaction to be returned in news.
+    ///   It must not be empty, longer than `MAX_CONTEXT_LENGTH` or hold control characters (`InvalidContext`).
     /// * `block_height` - Block height to dispatch the transaction (None means now)
     /// * `number_confirmation_trigger` - Just trigger news when the transaction has exactly this number of confirmations (None means all confirmations)
     fn dispatch(
@@ -69,6 +326,39 @@ pub trait BitcoinCoordinatorApi {
         number_confirmation_trigger: Option<u32>,
     ) -> Result<(), BitcoinCoordinatorError>;
 
+    /// Dispatches a transaction to the Bitcoin network with overrides of the global fee policy
+    /// The options are persisted with the transaction and used for all its speedups.
+    ///
+    /// # Arguments
+    /// * `tx` - The Bitcoin transaction to dispatch
+    /// * `speedup` - Speed up information for the transaction (None means it should not be speed up)
+    /// * `context` - Additional context information for the transaction to be returned in news
+    /// * `block_height` - Block height to dispatch the transaction (None means now)
+    /// * `number_confirmation_trigger` - Just trigger news when the transaction has exactly this number of confirmations (None means all confirmations)
+    /// * `options` - Max fee rate, initial bump fee percentage and whether the transaction gets its own speedup
+    fn dispatch_with_options(
+        &self,
+        tx: Transaction,
+        speedup: Option<SpeedupData>,
+        context: String,
+        block_height: Option<BlockHeight>,
+        number_confirmation_trigger: Option<u32>,
+        options: DispatchOptions,
+    ) -> Result<(), BitcoinCoordinatorError>;
+
+    /// Dispatches a batch of transactions to the Bitcoin network
+    /// All the transactions are stored together (either all of them or none) and monitored with a single
+    /// monitor registration per context, so they are picked up together in the same dispatch round.
+    ///
+    /// # Arguments
+    /// * `txs` - The Bitcoin transactions to dispatch with their speed up information and context
+    /// * `block_height` - Block height to dispatch the transactions (None means now)
+    fn dispatch_batch(
+        &self,
+        txs: Vec<(Transaction, Option<SpeedupData>, String)>,
+        block_height: Option<BlockHeight>,
+    ) -> Result<(), BitcoinCoordinatorError>;
+
     /// Cancels the monitor and the dispatch of a type of data
     /// This method removes the monitor and the dispatch from the coordinator's store.
     /// Which means that the data will no longer be monitored.
@@ -77,6 +367,51 @@ pub trait BitcoinCoordinatorApi {
     /// * `data` - The data to cancel
     fn cancel(&self, data: TypesToMonitor) -> Result<(), BitcoinCoordinatorError>;
 
+    /// Changes the block height a pending transaction is dispatched at
+    /// Fails if the transaction was already broadcast.
+    ///
+    /// # Arguments
+    /// * `txid` - The transaction ID to reschedule
+    /// * `new_target` - Block height to dispatch the transaction (None means on the next tick)
+    fn reschedule_dispatch(
+        &self,
+        txid: Txid,
+        new_target: Option<BlockHeight>,
+    ) -> Result<(), BitcoinCoordinatorError>;
+
+    /// Retrieves the transactions waiting for a target block height to be dispatched
+    /// Returns the transaction ID, the target block height and the context of each transaction.
+    fn get_scheduled_dispatches(
+        &self,
+    ) -> Result<Vec<(Txid, BlockHeight, String)>, BitcoinCoordinatorError>;
+
+    /// Cancels the dispatch of a transaction
+    /// The transaction will not be dispatched nor included in future speedups. If it was already broadcast
+    /// it is still monitored, so if it gets confirmed anyway it is reported as a regular news. It stops being
+    /// monitored once it is dropped from the mempool, double spent or past its expiry height, and the speedups
+    /// paying for a dropped transaction are invalidated.
+    /// A DispatchCancelled news is emitted once the transaction is cancelled, cancelling it again does nothing.
+    /// Confirmed transactions can not be cancelled, nor failed or expired ones since they are not dispatched anymore.
+    ///
+    /// # Arguments
+    /// * `txid` - The transaction ID to cancel
+    fn cancel_dispatch(&self, txid: Txid) -> Result<(), BitcoinCoordinatorError>;
+
+    /// Cancels every transaction dispatched with a context, e.g. when the session they belong to is aborted
+    /// Transactions waiting to be dispatched or not confirmed yet are cancelled: they are not dispatched,
+    /// not monitored and not included in future speedups, and a DispatchCancelled news is emitted for each one.
+    /// Confirmed transactions are left untouched.
+    ///
+    /// # Arguments
+    /// * `context` - The context the transactions were dispatched with
+    ///
+    /// # Returns
+    /// The cancelled transactions and the confirmed ones that were skipped
+    fn cancel_by_context(
+        &self,
+        context: &str,
+    ) -> Result<ContextCancelSummary, BitcoinCoordinatorError>;
+
     /// Registers funding information for potential transaction speed-ups
     /// This allows the coordinator to create child pays for parents transactions when needed
     ///
@@ -84,1299 +419,7777 @@ pub trait BitcoinCoordinatorApi {
     /// * `utxo` - Utxo to use for speed-ups
     fn add_funding(&self, utxo: Utxo) -> Result<(), BitcoinCoordinatorError>;
 
+    /// Registers funding for the speedups of a funding group, creating the group the first time
+    /// Transactions dispatched with the group in `DispatchOptions::funding_group` are sped up from this funding,
+    /// in a speedup chain of their own. A group reaching its unconfirmed limit does not stop the other groups.
+    ///
+    /// # Arguments
+    /// * `group_id` - Name of the funding group, for example the protocol session
+    /// * `utxo` - Utxo to use for the speed-ups of the group
+    fn add_funding_group(&self, group_id: &str, utxo: Utxo) -> Result<(), BitcoinCoordinatorError>;
+
+    /// Registers funding like `add_funding`, paying the change of the speedups it funds to another key
+    /// The change of each speedup is spent by the next one with the key it was paid to.
+    ///
+    /// # Arguments
+    /// * `utxo` - Utxo to use for speed-ups
+    /// * `change_pubkey` - Key the change of the speedups is paid to
+    fn add_funding_with_change_key(
+        &self,
+        utxo: Utxo,
+        change_pubkey: PublicKey,
+    ) -> Result<(), BitcoinCoordinatorError>;
+
+    /// Pays the change of the next speedups to a new key, without waiting for a new funding
+    /// The change already paid to the previous key is still spent with that key.
+    ///
+    /// # Arguments
+    /// * `change_pubkey` - Key the change of the next speedups is paid to
+    fn rotate_change_key(&self, change_pubkey: PublicKey) -> Result<(), BitcoinCoordinatorError>;
+
+    /// Removes a funding UTXO that is waiting in the funding pool
+    /// The active funding can not be removed, as it may be paying for unconfirmed speedups.
+    ///
+    /// # Arguments
+    /// * `txid` - The transaction ID of the funding UTXO
+    /// * `vout` - The output index of the funding UTXO
+    fn remove_funding(&self, txid: Txid, vout: u32) -> Result<(), BitcoinCoordinatorError>;
+
+    /// Imports a speedup built and broadcast outside the coordinator, for example a CPFP sent by hand
+    /// to rescue a stuck batch
+    /// The speedup must spend the current funding of the chain of the covered transactions and pay its change
+    /// to a P2WPKH output of `change_pubkey`. It is saved and monitored like a speedup created by the coordinator,
+    /// the covered transactions are not sped up again and the change becomes the funding of the next speedups.
+    ///
+    /// # Arguments
+    /// * `tx` - The speedup transaction, already broadcast
+    /// * `covered_parents` - The dispatched transactions the speedup pays for
+    /// * `change_vout` - The output index of the change
+    /// * `change_pubkey` - Key the change is paid to, used to spend it in the next speedup
+    fn import_external_speedup(
+        &self,
+        tx: Transaction,
+        covered_parents: Vec<Txid>,
+        change_vout: u32,
+        change_pubkey: PublicKey,
+    ) -> Result<(), BitcoinCoordinatorError>;
+
+    /// Retrieves a summary of the speedup funding
+    /// Returns the active funding utxo and the funding pool, the sats spent on speedups from the active funding,
+    /// the number of unconfirmed speedups and an estimate of how many more speedups can be afforded
+    /// at the current network fee rate.
+    fn get_funding_summary(&self) -> Result<FundingSummary, BitcoinCoordinatorError>;
+
+    /// Retrieves what the coordinator is working on
+    /// Returns the transactions waiting to be dispatched with the reason they are held back, the dispatched
+    /// transactions waiting for confirmation and the unconfirmed speedups of the active speedup chain.
+    fn get_pending_overview(&self) -> Result<PendingOverview, BitcoinCoordinatorError>;
+
+    /// Retrieves the speedups (CPFP and RBF) created to pay for a transaction
+    /// Returns every saved speedup that included the transaction, from the oldest to the newest, with its state,
+    /// fee and the other transactions it paid for. Finalized speedups are returned until they are pruned.
+    ///
+    /// # Arguments
+    /// * `txid` - The transaction ID paid by the speedups
+    fn get_speedups_for_tx(
+        &self,
+        txid: Txid,
+    ) -> Result<Vec<SpeedupSummary>, BitcoinCoordinatorError>;
+
+    /// Aggregates the confirmation latency and fees of the transactions finalized recently
+    /// Each finalized transaction is summarized with the heights it was first broadcast and first confirmed at,
+    /// the speedups and replacements spent on it and the fee attributable to it, only the last
+    /// MAX_FINALIZED_TX_STATS summaries are kept. Returns the median and p90 of the blocks to confirm, the
+    /// average fee rate paid including speedups and how many transactions needed to be bumped.
+    ///
+    /// # Arguments
+    /// * `window_blocks` - Number of recent blocks whose first confirmed transactions are aggregated
+    fn get_confirmation_stats(
+        &self,
+        window_blocks: u32,
+    ) -> Result<ConfirmationStats, BitcoinCoordinatorError>;
+
+    /// Estimates what the coordinator would pay to dispatch a set of transactions, without signing,
+    /// broadcasting or saving anything
+    /// Returns the batches the transactions would be dispatched in with the vsize and fee of their CPFPs,
+    /// the total fee and whether the current funding covers it. Transactions heavier than max_tx_weight
+    /// are reported as unbatchable.
+    ///
+    /// # Arguments
+    /// * `txs` - The transactions to dispatch with the speedup data of each one
+    fn estimate_dispatch_cost(
+        &self,
+        txs: Vec<(Transaction, SpeedupData)>,
+    ) -> Result<DispatchCostEstimate, BitcoinCoordinatorError>;
+
+    /// Registers the monitoring of RSK peg-in transactions
+    /// Peg-ins are reported as `RskPeginTransaction` monitor news and, once mined, recorded by the coordinator
+    /// so they can be queried with `get_detected_pegins` after the news is acknowledged.
+    ///
+    /// # Arguments
+    /// * `context` - Context information recorded with every detected peg-in
+    fn monitor_rsk_pegin(&self, context: String) -> Result<(), BitcoinCoordinatorError>;
+
+    /// Retrieves the peg-ins detected since the peg-in monitoring was registered
+    ///
+    /// # Arguments
+    /// * `since_height` - Only the peg-ins mined at this block height or later are returned
+    fn get_detected_pegins(
+        &self,
+        since_height: BlockHeight,
+    ) -> Result<Vec<DetectedPegin>, BitcoinCoordinatorError>;
+
     fn get_transaction(&self, txid: Txid) -> Result<TransactionStatus, BitcoinCoordinatorError>;
 
+    /// Retrieves the coordinator-side history of a dispatched transaction
+    /// Returns its current state, the block height it was broadcast at and the timestamped events
+    /// recorded since it was saved: dispatches, retries, speedups paying for it and state changes.
+    fn get_transaction_history(
+        &self,
+        txid: Txid,
+    ) -> Result<TransactionHistory, BitcoinCoordinatorError>;
+
+    /// Explains why a transaction has not confirmed, without changing anything
+    /// Returns its state and block heights, its last dispatch attempt, the speedups paying for it with their fees,
+    /// the network fee rate against the rate it is paid at, whether funding is available and whether the
+    /// unconfirmed speedup chain is full, with the list of reasons currently holding it back.
+    /// The network fee rate is the one estimated in the last tick.
+    ///
+    /// # Arguments
+    /// * `txid` - The transaction ID of a dispatched transaction
+    fn diagnose(&self, txid: Txid) -> Result<TxDiagnosis, BitcoinCoordinatorError>;
+
     /// Retrieves news about monitored transactions
     /// Returns information about transaction confirmations.
     fn get_news(&self) -> Result<News, BitcoinCoordinatorError>;
 
+    /// Retrieves a bounded page of news, in the same order as get_news
+    /// Monitor news and coordinator news are paged separately: the page has at most `limit` of each, so up to
+    /// `2 * limit` news. Every coordinator news takes a sequence number when it is reported, the page has the
+    /// coordinator news from `since_seq` on in the order they were reported, across every kind, and only those are
+    /// read from the store. The next page starts at `next_seq` of the page, acknowledged news or not.
+    /// Monitor news have no sequence number: the page has the first `limit` unacknowledged monitor news, in the
+    /// order of the monitor, so they advance as they are acknowledged.
+    /// A consumer drains the backlog by acknowledging each page and asking for the next one until `has_more`
+    /// is false.
+    ///
+    /// # Arguments
+    /// * `since_seq` - Sequence number of the first coordinator news, 0 to start with the oldest one
+    /// * `limit` - Maximum number of news to return per news source
+    fn get_news_page(
+        &self,
+        since_seq: u64,
+        limit: usize,
+    ) -> Result<NewsPage, BitcoinCoordinatorError>;
+
+    /// Retrieves the news of a severity or above, like get_news
+    /// The news below `min_severity` are not returned but they are still pending, they are acknowledged with
+    /// ack_news as usual. Coordinator news are in the order they were reported, and the ones below
+    /// `min_severity` are not read from the store. The severity counts of the returned News only count the
+    /// returned news.
+    ///
+    /// # Arguments
+    /// * `min_severity` - The lowest severity returned, `Severity::Info` returns every news
+    fn get_news_filtered(&self, min_severity: Severity) -> Result<News, BitcoinCoordinatorError>;
+
     /// Acknowledges that news has been processed
     /// This prevents the same news from being returned in subsequent calls to get_news()
     ///
     /// # Arguments
     /// * `news` - The news items to acknowledge
     fn ack_news(&self, news: AckNews) -> Result<(), BitcoinCoordinatorError>;
+
+    /// Acknowledges a batch of news in one call
+    /// Each coordinator news list is loaded and written once. Unknown and already acknowledged news are skipped.
+    ///
+    /// # Arguments
+    /// * `news` - The news items to acknowledge
+    ///
+    /// # Returns
+    /// The number of news that were acknowledged
+    fn ack_news_batch(&self, news: Vec<AckNews>) -> Result<usize, BitcoinCoordinatorError>;
+
+    /// Subscribes to the news instead of polling get_news
+    /// At the end of each tick the unacknowledged news not delivered to the subscriber yet are sent through the
+    /// returned channel, as a single News. The delivered news are persisted for the subscriber id, so subscribing
+    /// again with the same id resumes where the previous subscription left off. Delivery is not acknowledgement,
+    /// the news are still acknowledged with ack_news. The tick never waits for a subscriber: when its channel holds
+    /// `NEWS_SUBSCRIPTION_CAPACITY` batches the news are sent on a later tick.
+    ///
+    /// # Arguments
+    /// * `subscriber_id` - Identifies the subscriber across subscriptions and restarts
+    fn subscribe_news(
+        &self,
+        subscriber_id: &str,
+    ) -> Result<Receiver<News>, BitcoinCoordinatorError>;
+
+    /// Removes from the store the data that is no longer needed
+    /// Acknowledged news recorded before the last `older_than_blocks` blocks, finalized transactions
+    /// and finalized speedups that are no longer the funding checkpoint are removed.
+    /// Unacknowledged news and non finalized speedups are never removed.
+    ///
+    /// # Arguments
+    /// * `older_than_blocks` - Number of recent blocks whose acknowledged news are kept
+    ///
+    /// # Returns
+    /// The number of news, transactions and speedups removed
+    fn prune(&self, older_than_blocks: u32) -> Result<PruneSummary, BitcoinCoordinatorError>;
+
+    /// Reads the event journal, an append-only record of the coordinator actions kept for audits
+    /// Every broadcast attempt with its raw transaction, every speedup with its fee inputs, every event of the
+    /// transaction histories and every news emitted is recorded with a timestamp and the monitor height.
+    ///
+    /// # Arguments
+    /// * `from_seq` - Sequence number of the first entry to return
+    /// * `limit` - Maximum number of entries to return
+    fn read_events(
+        &self,
+        from_seq: u64,
+        limit: usize,
+    ) -> Result<Vec<JournalEntry>, BitcoinCoordinatorError>;
+
+    /// Reads the finalized log, an ordered feed of the finalized transactions independent from the news
+    /// Each finalization of a transaction is logged once, with its block and the total fee paid including
+    /// its speedups. A transaction finalized again in another block after a reorg gets a new entry.
+    ///
+    /// # Arguments
+    /// * `since_seq` - Sequence number of the first entry to return
+    /// * `limit` - Maximum number of entries to return
+    fn read_finalized(
+        &self,
+        since_seq: u64,
+        limit: usize,
+    ) -> Result<Vec<FinalizedTxEntry>, BitcoinCoordinatorError>;
+
+    /// Writes every entry of the event journal to a file, as a JSON array ordered by sequence number
+    ///
+    /// # Returns
+    /// The number of entries written
+    fn export_events_json(&self, path: &Path) -> Result<usize, BitcoinCoordinatorError>;
+
+    /// Removes the event journal entries with a sequence number lower than `before_seq`
+    /// The journal is never pruned by `prune` or the automatic prune, only by this call.
+    ///
+    /// # Returns
+    /// The number of entries removed
+    fn prune_events(&self, before_seq: u64) -> Result<u32, BitcoinCoordinatorError>;
+
+    /// Writes the state of the coordinator to a single file, to move it to another host
+    /// The file is versioned and has a checksum. It has the transactions, the speedups, the funding, the news and
+    /// the subscriptions of the store. The state of the monitor is not exported.
+    ///
+    /// # Returns
+    /// The number of records written
+    fn export_snapshot(&self, path: &Path) -> Result<usize, BitcoinCoordinatorError>;
+
+    /// Replaces the state of the coordinator with a snapshot written by `export_snapshot`
+    /// The version and the checksum of the file are checked before anything is written. A store that already
+    /// has records of a coordinator is only replaced when `force` is set. The state of the monitor is not
+    /// imported: the transactions, speedups, watched outpoints and UTXO sets of the snapshot are monitored
+    /// again on the first tick.
+    ///
+    /// # Returns
+    /// The number of records imported
+    fn import_snapshot(&self, path: &Path, force: bool) -> Result<usize, BitcoinCoordinatorError>;
+
+    /// Replaces the coordinator settings while it is running, from the next tick on
+    /// The settings are validated and applied all at once, the changed values are logged and reported
+    /// with a `SettingsUpdated` news. The fee strategy and the store encryption can not be changed, and
+    /// `max_unconfirmed_speedups` can not be lower than the number of speedups currently unconfirmed.
+    /// The monitor settings are kept, the monitor is already running with them.
+    ///
+    /// # Arguments
+    /// * `settings` - The new settings, missing values take the default of the network
+    fn update_settings(
+        &self,
+        settings: CoordinatorSettingsConfig,
+    ) -> Result<(), BitcoinCoordinatorError>;
+
+    /// Returns the settings the coordinator runs with
+    /// The configured settings over the defaults of the network, with the changes of `update_settings`.
+    fn get_effective_settings(&self) -> CoordinatorSettings;
+
+    /// Makes a coordinator in standby active, it broadcasts from the next tick on
+    /// The next tick first reconciles the speedups left by the previous active coordinator and pays the
+    /// dispatched transactions left without a speedup, then dispatches the transactions queued in standby.
+    /// Does nothing when the coordinator is already active.
+    fn promote(&self) -> Result<(), BitcoinCoordinatorError>;
+
+    /// Stops the coordinator cleanly
+    /// Flushes the store writes of broadcast transactions waiting to be retried and the news of the subscribers,
+    /// then persists a checkpoint with the monitor height and the work left. Calls are run one at a time, so
+    /// a shutdown never lands between a broadcast and its save. Afterwards `tick`, `dispatch` and `monitor`
+    /// fail with `CoordinatorStopped`. The next coordinator built on the store reads the checkpoint, and
+    /// recovers the dispatched transactions without speedup only when the previous run did not stop cleanly.
+    /// Returns what was flushed and the checkpoint.
+    fn shutdown(&self) -> Result<ShutdownReport, BitcoinCoordinatorError>;
 }
 
-impl BitcoinCoordinator {
-    pub fn new_with_paths(
-        rpc_config: &RpcConfig,
-        storage: Rc<Storage>,
-        key_manager: Rc<KeyManager>,
-        settings: Option<CoordinatorSettingsConfig>,
-    ) -> Result<Self, BitcoinCoordinatorError> {
-        let monitor_settings = settings.clone().unwrap_or_default().monitor_settings;
-        let monitor = Monitor::new_with_paths(rpc_config, storage.clone(), monitor_settings)?;
+/// Builds a `BitcoinCoordinator` from its parts.
+/// The monitor, store, client, node and key manager are required, the network defaults to regtest
+/// and the settings to `CoordinatorSettingsConfig::defaults_for(network)`. The settings not set take the
+/// default of the network.
+/// The client is set with `with_client`, or with `with_esplora_client` to use an Esplora server.
+#[derive(Default)]
+pub struct BitcoinCoordinatorBuilder {
+    monitor: Option<Box<dyn MonitorApi>>,
+    store: Option<BitcoinCoordinatorStore>,
+    client: Option<Box<dyn BitcoinClientApi>>,
+    node: Option<Box<dyn NodeApi>>,
+    esplora_client: Option<Rc<EsploraClient>>,
+    key_manager: Option<Rc<KeyManager>>,
+    settings: Option<CoordinatorSettingsConfig>,
+    network: Option<Network>,
+}
 
-        let settings_config = settings.unwrap_or_default();
-        settings_config.validate()?;
+impl BitcoinCoordinatorBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-        let coordinator_settings: CoordinatorSettings = CoordinatorSettings::from(settings_config);
+    pub fn with_monitor(mut self, monitor: Box<dyn MonitorApi>) -> Self {
+        self.monitor = Some(monitor);
+        self
+    }
 
-        let store = BitcoinCoordinatorStore::new(
-            storage,
-            coordinator_settings.max_unconfirmed_speedups,
-            coordinator_settings.retry_attempts_sending_tx,
-            coordinator_settings.retry_interval_seconds,
-        )?;
-        let client = BitcoinClient::new_from_config(rpc_config)?;
-        let network = rpc_config.network;
+    // The store is used as is, its retry and unconfirmed speedups limits are not taken from the settings.
+    pub fn with_store(mut self, store: BitcoinCoordinatorStore) -> Self {
+        self.store = Some(store);
+        self
+    }
 
-        Ok(Self {
-            monitor,
-            store,
-            key_manager,
-            client,
-            _network: network,
-            settings: coordinator_settings,
-        })
+    pub fn with_client(mut self, client: Box<dyn BitcoinClientApi>) -> Self {
+        self.client = Some(client);
+        self
     }
 
-    fn process_pending_txs_to_dispatch(&self) -> Result<(), BitcoinCoordinatorError> {
-        // Get pending transactions to be send to the blockchain
-        let pending_txs = self.store.get_txs_to_dispatch()?;
+    // The client, the funding output checks, the SmartFee estimates and, unless one is set with with_node,
+    // the node calls all use the Esplora server.
+    pub fn with_esplora_client(mut self, esplora_client: EsploraClient) -> Self {
+        self.client = Some(Box::new(esplora_client.clone()));
+        self.esplora_client = Some(Rc::new(esplora_client));
+        self
+    }
 
-        if pending_txs.is_empty() {
-            return Ok(());
-        }
+    // Node calls the client does not expose (gettxout, getblock, estimatesmartfee, testmempoolaccept, ...).
+    pub fn with_node(mut self, node: Box<dyn NodeApi>) -> Self {
+        self.node = Some(node);
+        self
+    }
 
-        debug!(
-            "{} Number of transactions to dispatch {}",
-            style("Coordinator").green(),
-            style(pending_txs.len()).yellow()
-        );
+    // Uses the RPC client of the node for the node calls, see with_node.
+    pub fn with_rpc_client(self, rpc_client: Client) -> Self {
+        self.with_node(Box::new(rpc_client))
+    }
 
-        let txs_to_dispatch: Vec<CoordinatedTransaction> = pending_txs
-            .iter()
-            .filter(|tx| self.should_dispatch_tx(tx).unwrap_or(false))
-            .cloned()
-            .collect();
+    pub fn with_key_manager(mut self, key_manager: Rc<KeyManager>) -> Self {
+        self.key_manager = Some(key_manager);
+        self
+    }
 
-        let (txs_to_dispatch_with_speedup, txs_to_dispatch_without_speedup): (Vec<_>, Vec<_>) =
-            txs_to_dispatch
-                .into_iter()
-                .partition(|tx| self.should_speedup(tx));
+    pub fn with_settings(mut self, settings: CoordinatorSettingsConfig) -> Self {
+        self.settings = Some(settings);
+        self
+    }
 
-        if !txs_to_dispatch_without_speedup.is_empty() {
-            info!(
-                "{} Number of transactions to dispatch without speedup {}",
+    pub fn with_network(mut self, network: Network) -> Self {
+        self.network = Some(network);
+        self
+    }
+
+    pub fn build(self) -> Result<BitcoinCoordinator, BitcoinCoordinatorError> {
+        let missing = |field: &str| {
+            BitcoinCoordinatorError::InvalidConfiguration(format!(
+                "{field} is required to build the coordinator"
+            ))
+        };
+
+        let monitor = self.monitor.ok_or_else(|| missing("monitor"))?;
+        let store = self.store.ok_or_else(|| missing("store"))?;
+        let client = self.client.ok_or_else(|| missing("client"))?;
+        let node: Box<dyn NodeApi> = match (self.node, &self.esplora_client) {
+            (Some(node), _) => node,
+            (None, Some(esplora_client)) => Box::new(esplora_client.as_ref().clone()),
+            (None, None) => return Err(missing("node")),
+        };
+        let key_manager = self.key_manager.ok_or_else(|| missing("key_manager"))?;
+
+        let network = self.network.unwrap_or(Network::Regtest);
+        let settings_config = self
+            .settings
+            .unwrap_or_else(|| CoordinatorSettingsConfig::defaults_for(network));
+        settings_config.validate()?;
+
+        let settings = CoordinatorSettings::resolve(settings_config, network);
+        check_node_settings(node.as_ref(), &settings)?;
+        for warning in settings.network_warnings(network) {
+            warn!(
+                "{} {}",
                 style("Coordinator").green(),
-                style(txs_to_dispatch_without_speedup.len()).yellow()
+                style(warning).yellow()
             );
-
-            self.dispatch_txs(txs_to_dispatch_without_speedup)?;
         }
 
-        if !txs_to_dispatch_with_speedup.is_empty() {
-            info!(
-                "{} Number of transactions to dispatch with speedup {}",
+        let fee_estimator =
+            FeeRateEstimator::new(settings.fee_strategy.clone(), settings.min_network_fee_rate);
+
+        // Another coordinator working on the same store would spend the same funding.
+        let instance_id = Uuid::new_v4();
+        if let Some(previous_owner) =
+            store.acquire_ownership(instance_id, settings.owner_stale_after_seconds)?
+        {
+            warn!(
+                "{} Taking over the store from instance {} (pid {}), no heartbeat since {}",
                 style("Coordinator").green(),
-                style(txs_to_dispatch_with_speedup.len()).yellow()
+                style(previous_owner.instance_id).yellow(),
+                style(previous_owner.pid).yellow(),
+                style(previous_owner.heartbeat_at).yellow(),
             );
+        }
 
-            // Check if we can send transactions or we stop the process until CPFP transactions start to be confirmed.
-            if self.store.can_speedup()? {
-                self.speedup_and_dispatch_in_batch(txs_to_dispatch_with_speedup)?;
-            } else {
-                warn!("{} Can not speedup", style("Coordinator").green());
-                let is_funding_available = self.store.is_funding_available()?;
+        // Compound writes are atomic, but a store written by an older version can have list entries
+        // left without their record by a crash. They are dropped before anything reads them.
+        store.repair_dangling_entries()?;
 
-                if !is_funding_available {
-                    self.notify_funding_not_found()?;
-                }
-            }
+        // A clean stop left nothing to recover, otherwise the recovery runs on the first tick.
+        let previous_run_state = store.get_run_state()?;
+        let stopped_cleanly = matches!(
+            &previous_run_state,
+            Some(CoordinatorRunState::Stopped(checkpoint)) if checkpoint.is_clean()
+        );
+        log_previous_run_state(previous_run_state.as_ref());
+        store.set_run_state(CoordinatorRunState::Running)?;
+
+        // The UTXO sets are registered again, the monitor of a new process does not know them.
+        for set in store.get_watched_utxo_sets()? {
+            monitor_utxo_set_members(monitor.as_ref(), &set)?;
         }
 
-        Ok(())
+        Ok(BitcoinCoordinator {
+            monitor,
+            store,
+            key_manager,
+            client,
+            node,
+            network,
+            settings: RefCell::new(settings),
+            recovered: Cell::new(stopped_cleanly),
+            remonitor: Cell::new(false),
+            last_prune_height: Cell::new(None),
+            tick_height: Cell::new(None),
+            tick_block_hash: Cell::new(None),
+            last_new_block: Cell::new(None),
+            tick_failures: Cell::new(0),
+            observer: Rc::new(NoopCoordinatorObserver),
+            fee_estimator,
+            mempool_ancestry: MempoolAncestryCache::default(),
+            prevouts: PrevoutCache::default(),
+            funding_provider: None,
+            funding_output_checker: self
+                .esplora_client
+                .clone()
+                .map(|client| client as Rc<dyn FundingOutputChecker>),
+            smart_fee_estimator: self
+                .esplora_client
+                .map(|client| client as Rc<dyn SmartFeeEstimator>),
+            parent_tx_signer: None,
+            speedup_review_hook: None,
+            finalized_sink: None,
+            node_breaker: NodeCircuitBreaker::default(),
+            pending_writes: StoreWriteQueue::default(),
+            news_subscribers: RefCell::new(Vec::new()),
+            tick_budget: TickBudget::default(),
+            tick_summary: TickSummary::default(),
+            last_ready: Cell::new(None),
+            previous_run_state,
+            stopped: Cell::new(false),
+            instance_id,
+            store_lock: None,
+        })
     }
+}
 
-    fn speedup_and_dispatch_in_batch(
-        &self,
+// Registers in the monitor the members of a UTXO set that are not spent yet.
+fn monitor_utxo_set_members(
+    monitor: &dyn MonitorApi,
+    set: &WatchedUtxoSet,
+) -> Result<(), BitcoinCoordinatorError> {
+    for member in set
+        .outpoints
+        .iter()
+        .filter(|member| member.spending_txid.is_none())
+    {
+        monitor.monitor(TypesToMonitor::SpendingUTXOTransaction(
+            member.outpoint.txid,
+            member.outpoint.vout,
+            set.context.clone(),
+            None,
+        ))?;
+    }
+
+    Ok(())
+}
+
+// The mempool checks need a node that answers them, an Esplora server does not.
+fn check_node_settings(
+    node: &dyn NodeApi,
+    settings: &CoordinatorSettings,
+) -> Result<(), BitcoinCoordinatorError> {
+    if node.supports_mempool_checks() {
+        return Ok(());
+    }
+
+    for (name, enabled) in [
+        ("test_mempool_accept", settings.test_mempool_accept),
+        ("check_mempool_ancestry", settings.check_mempool_ancestry),
+    ] {
+        if enabled {
+            return Err(BitcoinCoordinatorError::InvalidConfiguration(format!(
+                "{name} needs a node that answers the mempool checks, set one with with_node"
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+// Output of a transaction dispatched with allow_rbf_of_parent that pays the fee of its replacements, the last one
+// unless `parent_change_vout` is set. None when it is not an output of the transaction.
+fn parent_change_vout(tx: &Transaction, options: &DispatchOptions) -> Option<usize> {
+    let change_vout = match options.parent_change_vout {
+        Some(vout) => vout as usize,
+        None => tx.output.len().checked_sub(1)?,
+    };
+
+    (change_vout < tx.output.len()).then_some(change_vout)
+}
+
+// Txids of the transactions a speedup is built for.
+fn paid_txids(txs_data: &[(SpeedupData, Transaction, String)]) -> Vec<Txid> {
+    txs_data
+        .iter()
+        .map(|(_, tx, _)| tx.compute_txid())
+        .collect()
+}
+
+// A transaction waiting to be dispatched or confirmed is expired once the block of its expiry height is mined
+// without it, it can not be confirmed by then anymore.
+fn is_expired(tx: &CoordinatedTransaction, current_height: BlockHeight) -> bool {
+    matches!(
+        tx.state,
+        TransactionState::ToDispatch | TransactionState::Dispatched
+    ) && is_past_expiry(tx, current_height)
+}
+
+fn is_past_expiry(tx: &CoordinatedTransaction, current_height: BlockHeight) -> bool {
+    tx.dispatch_options
+        .expires_at_height
+        .is_some_and(|expires_at| current_height >= expires_at)
+}
+
+// Speedup data of a transaction paid by a speedup. Transactions without it are never batched, this only fails
+// when that changed between the check and the use.
+fn required_speedup_data(
+    tx: &CoordinatedTransaction,
+) -> Result<&SpeedupData, BitcoinCoordinatorError> {
+    tx.speedup_data
+        .as_ref()
+        .ok_or(BitcoinCoordinatorError::SpeedupDataMissing(tx.tx_id))
+}
+
+// Amount of the output a speedup spends from the transaction it pays for.
+fn speedup_utxo_amount(speedup_data: &SpeedupData) -> Result<u64, BitcoinCoordinatorError> {
+    match (&speedup_data.utxo, &speedup_data.partial_utxo) {
+        (Some(utxo), _) => Ok(utxo.amount),
+        (None, Some((_, _, amount, _))) => Ok(*amount),
+        (None, None) => Err(BitcoinCoordinatorError::SpeedupDataMissingAmount),
+    }
+}
+
+// Output a speedup spends from the transaction it pays for.
+fn speedup_outpoint(speedup_data: &SpeedupData) -> Option<OutPoint> {
+    match (&speedup_data.utxo, &speedup_data.partial_utxo) {
+        (Some(utxo), _) => Some(OutPoint::new(utxo.txid, utxo.vout)),
+        (None, Some((txid, vout, _, _))) => Some(OutPoint::new(*txid, *vout)),
+        (None, None) => None,
+    }
+}
+
+fn log_previous_run_state(state: Option<&CoordinatorRunState>) {
+    match state {
+        None => {}
+        Some(CoordinatorRunState::Running) => warn!(
+            "{} The previous run did not shut down, reconciling the dispatched transactions",
+            style("Coordinator").green(),
+        ),
+        Some(CoordinatorRunState::Stopped(checkpoint)) if checkpoint.is_clean() => info!(
+            "{} The previous run stopped cleanly at height {}",
+            style("Coordinator").green(),
+            style(checkpoint.monitor_height).blue(),
+        ),
+        Some(CoordinatorRunState::Stopped(checkpoint)) => warn!(
+            "{} The previous run stopped at height {} leaving {} writes, {} speedup intents and {} transactions without speedup, reconciling them",
+            style("Coordinator").green(),
+            style(checkpoint.monitor_height).blue(),
+            style(checkpoint.pending_store_writes).yellow(),
+            style(checkpoint.speedup_intents).yellow(),
+            style(checkpoint.txs_without_speedup).yellow(),
+        ),
+    }
+}
+
+impl BitcoinCoordinator {
+    pub fn builder() -> BitcoinCoordinatorBuilder {
+        BitcoinCoordinatorBuilder::new()
+    }
+
+    pub fn new_with_paths(
+        rpc_config: &RpcConfig,
+        storage: Rc<Storage>,
+        key_manager: Rc<KeyManager>,
+        settings: Option<CoordinatorSettingsConfig>,
+    ) -> Result<Self, BitcoinCoordinatorError> {
+        Self::new_with_backend(rpc_config, &Backend::Rpc, storage, key_manager, settings)
+    }
+
+    // Like new_with_paths, with the transactions broadcast, the fee rate estimated and the node calls answered
+    // through `backend`. The monitor always uses the node of `rpc_config`.
+    pub fn new_with_backend(
+        rpc_config: &RpcConfig,
+        backend: &Backend,
+        storage: Rc<Storage>,
+        key_manager: Rc<KeyManager>,
+        settings: Option<CoordinatorSettingsConfig>,
+    ) -> Result<Self, BitcoinCoordinatorError> {
+        let settings =
+            settings.unwrap_or_else(|| CoordinatorSettingsConfig::defaults_for(rpc_config.network));
+
+        let monitor = Monitor::new_with_paths(
+            rpc_config,
+            storage.clone(),
+            settings.monitor_settings.clone(),
+        )?;
+
+        // The store limits are taken from the settings, the builder validates them again.
+        settings.validate()?;
+        let coordinator_settings =
+            CoordinatorSettings::resolve(settings.clone(), rpc_config.network);
+
+        let mut store = BitcoinCoordinatorStore::new(
+            storage,
+            coordinator_settings.max_unconfirmed_speedups,
+            coordinator_settings.retry_attempts_sending_tx,
+            coordinator_settings.retry_interval_seconds,
+        )?;
+
+        if coordinator_settings.encrypt_store {
+            store = store.with_encryption(StoreCipher::from_key_manager(&key_manager)?);
+        }
+        // With Esplora the node of `rpc_config` only feeds the monitor.
+        let builder = match backend {
+            Backend::Rpc => BitcoinCoordinatorBuilder::new()
+                .with_client(Box::new(BitcoinClient::new_from_config(rpc_config)?))
+                .with_rpc_client(Client::new(
+                    &rpc_config.url,
+                    Auth::UserPass(rpc_config.username.clone(), rpc_config.password.clone()),
+                )?),
+            Backend::Esplora { url } => {
+                BitcoinCoordinatorBuilder::new().with_esplora_client(EsploraClient::new(url))
+            }
+        };
+
+        builder
+            .with_monitor(Box::new(monitor))
+            .with_store(store)
+            .with_key_manager(key_manager)
+            .with_settings(settings)
+            .with_network(rpc_config.network)
+            .build()
+    }
+
+    // Opens the storage and the key manager of the configuration and connects to its node, the way the service runs.
+    // The store lock is taken before the store is, and held until the coordinator is dropped, so the admin
+    // commands that change the store fail with StoreLocked while the service runs.
+    pub fn open(config: &CoordinatorConfig) -> Result<Self, BitcoinCoordinatorError> {
+        let store_lock = StoreLock::acquire(&config.storage.path)?;
+
+        let storage = Rc::new(Storage::new(&config.storage).map_err(|e| {
+            BitcoinCoordinatorError::InvalidConfiguration(format!("can not open the storage: {e}"))
+        })?);
+        let key_manager = Rc::new(
+            create_key_manager_from_config(&config.key_manager, &config.key_storage).map_err(
+                |e| {
+                    BitcoinCoordinatorError::InvalidConfiguration(format!(
+                        "can not open the key manager: {e}"
+                    ))
+                },
+            )?,
+        );
+
+        let mut coordinator = Self::new_with_backend(
+            &config.rpc,
+            &config.backend,
+            storage,
+            key_manager,
+            config.settings.clone(),
+        )?;
+        coordinator.store_lock = Some(store_lock);
+
+        Ok(coordinator)
+    }
+
+    // A coordinator whose store is kept in memory, for short-lived runs (e.g. regtest simulations) where nothing
+    // needs to survive the process. The monitor keeps its own storage, so it is given already built.
+    pub fn new_ephemeral(
+        rpc_config: &RpcConfig,
+        monitor: Box<dyn MonitorApi>,
+        key_manager: Rc<KeyManager>,
+        settings: Option<CoordinatorSettingsConfig>,
+    ) -> Result<Self, BitcoinCoordinatorError> {
+        let settings =
+            settings.unwrap_or_else(|| CoordinatorSettingsConfig::defaults_for(rpc_config.network));
+
+        settings.validate()?;
+        let coordinator_settings =
+            CoordinatorSettings::resolve(settings.clone(), rpc_config.network);
+
+        let store = BitcoinCoordinatorStore::in_memory(
+            coordinator_settings.max_unconfirmed_speedups,
+            coordinator_settings.retry_attempts_sending_tx,
+            coordinator_settings.retry_interval_seconds,
+        )?;
+
+        let rpc_client = Client::new(
+            &rpc_config.url,
+            Auth::UserPass(rpc_config.username.clone(), rpc_config.password.clone()),
+        )?;
+
+        BitcoinCoordinatorBuilder::new()
+            .with_client(Box::new(BitcoinClient::new_from_config(rpc_config)?))
+            .with_monitor(monitor)
+            .with_store(store)
+            .with_rpc_client(rpc_client)
+            .with_key_manager(key_manager)
+            .with_settings(settings)
+            .with_network(rpc_config.network)
+            .build()
+    }
+
+    // Run state the previous coordinator left in the store, None if this is the first one.
+    pub fn previous_run_state(&self) -> Option<&CoordinatorRunState> {
+        self.previous_run_state.as_ref()
+    }
+
+    // Id this coordinator is recorded with as the owner of the store.
+    pub fn instance_id(&self) -> Uuid {
+        self.instance_id
+    }
+
+    pub fn with_observer(mut self, observer: Rc<dyn CoordinatorObserver>) -> Self {
+        self.observer = observer;
+        self
+    }
+
+    // Provider of the fee rate used when the fee strategy is External.
+    pub fn with_fee_rate_provider(mut self, provider: Rc<dyn FeeRateProvider>) -> Self {
+        self.fee_estimator.set_provider(provider);
+        self
+    }
+
+    // Provider of the mempool ancestry checked when check_mempool_ancestry is enabled, instead of the node.
+    pub fn with_mempool_ancestry_provider(
+        mut self,
+        provider: Rc<dyn MempoolAncestryProvider>,
+    ) -> Self {
+        self.mempool_ancestry.set_provider(provider);
+        self
+    }
+
+    // Provider asked for funding when there is none or it drops below auto_topup_below_sats.
+    pub fn with_funding_provider(mut self, provider: Rc<dyn FundingProvider>) -> Self {
+        self.funding_provider = Some(provider);
+        self
+    }
+
+    // Source of the state of the funding outputs checked before a CPFP spends them, instead of the node.
+    pub fn with_funding_output_checker(mut self, checker: Rc<dyn FundingOutputChecker>) -> Self {
+        self.funding_output_checker = Some(checker);
+        self
+    }
+
+    // Source of the estimates of the SmartFee fee strategy, instead of the node.
+    pub fn with_smart_fee_estimator(mut self, estimator: Rc<dyn SmartFeeEstimator>) -> Self {
+        self.smart_fee_estimator = Some(estimator);
+        self
+    }
+
+    // Signer of the replacements of the transactions dispatched with allow_rbf_of_parent.
+    pub fn with_parent_tx_signer(mut self, signer: Rc<dyn ParentTxSigner>) -> Self {
+        self.parent_tx_signer = Some(signer);
+        self
+    }
+
+    // Hook reviewing every signed speedup before it is broadcast.
+    pub fn with_speedup_review_hook(mut self, hook: Rc<dyn SpeedupReviewHook>) -> Self {
+        self.speedup_review_hook = Some(hook);
+        self
+    }
+
+    // Consumer the finalized transactions are pushed to at the end of each tick.
+    pub fn with_finalized_sink(mut self, sink: Rc<dyn FinalizedSink>) -> Self {
+        self.finalized_sink = Some(sink);
+        self
+    }
+
+    fn notify_tick_completed(&self, started_at: Instant) -> Result<(), BitcoinCoordinatorError> {
+        let txs_pending = self.store.get_txs_to_dispatch()?.len();
+        let txs_in_progress = self.store.get_txs_in_progress()?.len();
+        let speedups_unconfirmed = self.store.get_unconfirmed_speedups()?.len();
+
+        self.observer.on_tick_completed(
+            started_at.elapsed(),
+            txs_pending,
+            txs_in_progress,
+            speedups_unconfirmed,
+        );
+
+        let budget = self.tick_budget.usage();
+
+        if !budget.deferred_txs.is_empty() {
+            self.observer
+                .on_tick_budget_exhausted(budget.broadcasts, budget.speedups, txs_pending);
+        }
+
+        self.tick_summary.log(started_at.elapsed());
+
+        Ok(())
+    }
+
+    // The tick pipeline, run once the monitor is ready.
+    fn process_ready_tick(&self) -> Result<(), BitcoinCoordinatorError> {
+        // Every speedup of the tick pays the same network fee rate.
+        self.fee_estimator.reset();
+        self.mempool_ancestry.reset();
+        self.prevouts.reset();
+        self.tick_failures.set(0);
+        self.tick_summary.reset();
+
+        let settings = self.settings();
+        self.tick_budget.reset(
+            settings.max_broadcasts_per_tick,
+            settings.max_speedups_per_tick,
+            settings.max_conflict_scan_blocks_per_tick,
+        );
+        drop(settings);
+
+        // The monitor height is asked once, every step of the tick uses the same block.
+        let block_height = self.monitor.get_monitor_height()?;
+        self.tick_height.set(Some(block_height));
+
+        // The journal entries written during the tick are recorded at the monitor height.
+        self.store.journal().set_block_height(block_height);
+
+        // While a broadcast transaction is not saved the store is behind the node, nothing else is done until it is.
+        self.process_pending_store_writes()?;
+
+        if !self.pending_writes.is_empty() {
+            return Ok(());
+        }
+
+        if self.remonitor.get() {
+            self.monitor_imported_state()?;
+            self.remonitor.set(false);
+        }
+
+        self.process_new_block(block_height)?;
+
+        self.in_funding_groups(Self::reconcile_speedup_intents)?;
+        self.reconcile_parent_replacement_intents()?;
+
+        // A coordinator in standby follows the store without broadcasting, the work waits until it is promoted.
+        let standby = self.is_standby();
+
+        if !standby {
+            self.in_funding_groups(Self::process_failed_speedups)?;
+        }
+
+        if !standby
+            && !self.recovered.get()
+            && self
+                .in_funding_groups(Self::recover_dispatched_txs_without_speedup)?
+                .into_iter()
+                .all(|recovered| recovered)
+        {
+            self.recovered.set(true);
+        }
+
+        // Steps working on the speedups run once for the default chain and once for each funding group.
+        let steps: [TickStep; 13] = [
+            // First, so the speedups orphaned by a reorg are not used as funding by the speedups built in this tick.
+            |coordinator| {
+                coordinator
+                    .in_funding_groups(Self::process_in_progress_speedup_txs)
+                    .map(|_| ())
+            },
+            Self::process_funding_topup,
+            // Before the speedups, so a transaction whose speedup output was spent is not paid by the next CPFP.
+            Self::process_anchor_spends,
+            |coordinator| {
+                coordinator
+                    .in_funding_groups(Self::process_deferred_speedups)
+                    .map(|_| ())
+            },
+            |coordinator| {
+                coordinator
+                    .in_funding_groups(Self::process_pending_txs_to_dispatch)
+                    .map(|_| ())
+            },
+            Self::process_in_progress_txs,
+            Self::process_parent_replacements,
+            Self::process_tx_groups,
+            Self::process_watched_finalities,
+            Self::process_watched_outpoints,
+            Self::process_watched_utxo_sets,
+            Self::process_watched_addresses,
+            Self::process_rsk_pegins,
+        ];
+
+        // When the node becomes unreachable the rest of the tick is skipped, the next ticks only probe it.
+        for step in steps {
+            if self.node_breaker.is_open() {
+                return Ok(());
+            }
+
+            if let Err(error) = step(self) {
+                self.skip_tick_work(error)?;
+            }
+        }
+
+        if self.node_breaker.is_open() {
+            return Ok(());
+        }
+
+        // Pruning is left for the next tick when a speedup was replaced.
+        match self.in_funding_groups(Self::bump_last_speedup) {
+            Ok(replaced) if replaced.contains(&true) => return Ok(()),
+            Ok(_) => {}
+            Err(error) => return self.skip_tick_work(error),
+        }
+
+        self.auto_prune()?;
+
+        Ok(())
+    }
+
+    // Runs a speedup step on the default speedup chain and then on the chain of each funding group.
+    fn in_funding_groups<T>(
+        &self,
+        step: impl Fn(&Self) -> Result<T, BitcoinCoordinatorError>,
+    ) -> Result<Vec<T>, BitcoinCoordinatorError> {
+        let groups = self.store.get_funding_groups()?;
+
+        std::iter::once(None)
+            .chain(groups.iter().map(|group| Some(group.as_str())))
+            .map(|group| self.store.with_funding_group(group, || step(self)))
+            .collect()
+    }
+
+    // Registers in the monitor what an imported snapshot watches, the monitor of this host does not know it.
+    fn monitor_imported_state(&self) -> Result<(), BitcoinCoordinatorError> {
+        let txs = self.store.get_txs_in_progress()?;
+
+        for tx in txs.iter() {
+            self.monitor.monitor(TypesToMonitor::Transactions(
+                vec![tx.tx_id],
+                tx.context.clone(),
+                None,
+            ))?;
+
+            if tx.state == TransactionState::Dispatched {
+                self.monitor_anchor(tx)?;
+            }
+        }
+
+        self.in_funding_groups(Self::monitor_imported_speedups)?;
+
+        for watched in self.store.get_watched_outpoints()? {
+            self.monitor
+                .monitor(TypesToMonitor::SpendingUTXOTransaction(
+                    watched.outpoint.txid,
+                    watched.outpoint.vout,
+                    watched.context,
+                    None,
+                ))?;
+        }
+
+        for set in self.store.get_watched_utxo_sets()? {
+            monitor_utxo_set_members(self.monitor.as_ref(), &set)?;
+        }
+
+        if self.store.get_rsk_pegin_context()?.is_some() {
+            self.monitor.monitor(TypesToMonitor::RskPegin(None))?;
+        }
+
+        info!(
+            "{} Imported snapshot monitored | Transactions({})",
+            style("Coordinator").green(),
+            style(txs.len()).blue(),
+        );
+
+        Ok(())
+    }
+
+    fn monitor_imported_speedups(&self) -> Result<(), BitcoinCoordinatorError> {
+        for speedup in self.store.get_all_pending_speedups()? {
+            if let Some(monitor) = self.store.get_internal_monitor(&speedup.tx_id)? {
+                self.monitor.monitor(TypesToMonitor::Transactions(
+                    vec![speedup.tx_id],
+                    monitor.context().to_string(),
+                    None,
+                ))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Bumps the last speedup when it is not confirmed in time. Returns true when it was replaced (RBF).
+    fn bump_last_speedup(&self) -> Result<bool, BitcoinCoordinatorError> {
+        if self.is_standby() || !self.should_boost_speedup_again()? {
+            return Ok(false);
+        }
+
+        if self.should_rbf_last_speedup()? {
+            self.rbf_last_cpfp()?;
+            return Ok(true);
+        }
+
+        self.boost_cpfp_again()?;
+
+        Ok(false)
+    }
+
+    fn process_pending_txs_to_dispatch(&self) -> Result<(), BitcoinCoordinatorError> {
+        if self.is_standby() {
+            return Ok(());
+        }
+
+        // Get pending transactions to be send to the blockchain. Only the transactions of the funding group are
+        // dispatched, so a CPFP never pays for transactions of different groups.
+        // Expired transactions are left to process_in_progress_txs, which marks them as expired.
+        let funding_group = self.store.funding_group();
+        let current_height = self.current_height()?;
+        let pending_txs: Vec<CoordinatedTransaction> = self
+            .store
+            .get_txs_to_dispatch()?
+            .into_iter()
+            .filter(|tx| tx.dispatch_options.funding_group == funding_group)
+            .filter(|tx| !is_expired(tx, current_height))
+            .collect();
+
+        if pending_txs.is_empty() {
+            return Ok(());
+        }
+
+        debug!(
+            "{} Number of transactions to dispatch {}",
+            style("Coordinator").green(),
+            style(pending_txs.len()).yellow()
+        );
+
+        let ready_txs: Vec<CoordinatedTransaction> = pending_txs
+            .iter()
+            .filter(|tx| self.should_dispatch_tx(tx).unwrap_or(false))
+            .cloned()
+            .collect();
+
+        // The paused transactions do not count against the broadcast budget.
+        let mut txs_to_dispatch = self.pause_on_high_fees(ready_txs)?;
+
+        // The transactions over the broadcast budget keep their place and are dispatched first on the next tick.
+        if let Some(remaining) = self.tick_budget.remaining_broadcasts() {
+            let over_budget =
+                txs_to_dispatch.split_off(txs_to_dispatch.len().min(remaining as usize));
+
+            if !over_budget.is_empty() {
+                debug!(
+                    "{} Broadcast budget of the tick reached, {} transactions left for the next ticks",
+                    style("Coordinator").green(),
+                    style(over_budget.len()).yellow()
+                );
+
+                self.tick_budget
+                    .defer(over_budget.iter().map(|tx| tx.tx_id));
+            }
+        }
+
+        let (txs_to_dispatch_with_speedup, txs_to_dispatch_without_speedup): (Vec<_>, Vec<_>) =
+            txs_to_dispatch
+                .into_iter()
+                .partition(|tx| self.should_speedup(tx));
+
+        if !txs_to_dispatch_without_speedup.is_empty() {
+            debug!(
+                "{} Number of transactions to dispatch without speedup {}",
+                style("Coordinator").green(),
+                style(txs_to_dispatch_without_speedup.len()).yellow()
+            );
+
+            self.dispatch_txs(txs_to_dispatch_without_speedup)?;
+        }
+
+        if !txs_to_dispatch_with_speedup.is_empty() {
+            debug!(
+                "{} Number of transactions to dispatch with speedup {}",
+                style("Coordinator").green(),
+                style(txs_to_dispatch_with_speedup.len()).yellow()
+            );
+
+            // Check if we can send transactions or we stop the process until CPFP transactions start to be confirmed.
+            if self.store.can_speedup()? {
+                self.speedup_and_dispatch_in_batch(txs_to_dispatch_with_speedup)?;
+            } else {
+                warn!("{} Can not speedup", style("Coordinator").green());
+                let is_funding_available = self.store.is_funding_available()?;
+
+                if !is_funding_available {
+                    self.notify_funding_not_found()?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn speedup_and_dispatch_in_batch(
+        &self,
+        txs: Vec<CoordinatedTransaction>,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        // Attempt to dispatch as many transactions as possible in a single CPFP (Child Pays For Parent) transaction,
+        // while ensuring the resulting transaction does not exceed Bitcoin's standardness limits.
+        // We have two policies to dispatch the transactions:
+        // 1. Maximum transaction size: MAX_TX_WEIGHT weight units. Exceeding these limits will result in the transaction
+        // being considered non-standard and rejected by most mempools.
+        // 2. Maximum number of unconfirmed transactions is 25 (MAX_LIMIT_UNCONFIRMED_PARENTS)
+        // If the set of transactions exceeds these limits, will fail the dispatch.
+
+        // A CPFP can spend a speedup utxo only once. Transactions paid by a utxo already in this round
+        // (like a txid listed twice) are left to the next tick.
+        let mut speedup_outpoints = HashSet::new();
+        let txs: Vec<CoordinatedTransaction> = txs
+            .into_iter()
+            .filter(|tx| {
+                let outpoint = tx
+                    .speedup_data
+                    .as_ref()
+                    .and_then(speedup_outpoint)
+                    .unwrap_or(OutPoint::new(tx.tx_id, 0));
+
+                if !speedup_outpoints.insert(outpoint) {
+                    warn!(
+                        "{} Transaction({}) skipped, its speedup utxo is already in the batch",
+                        style("Coordinator").green(),
+                        style(tx.tx_id).yellow()
+                    );
+                    return false;
+                }
+
+                true
+            })
+            .collect();
+
+        // Taproot anchors are signed by the coordinator and partial utxos by the protocol builder, so a CPFP
+        // can not spend both. Transactions paid by a partial utxo are left to the next tick.
+        let has_taproot_anchor = txs.iter().any(|tx| {
+            tx.speedup_data.as_ref().is_some_and(|speedup_data| {
+                SpeedupOutputKind::of_speedup_utxo(&tx.tx, speedup_data)
+                    == SpeedupOutputKind::P2trKeyPath
+            })
+        });
+
+        let txs: Vec<CoordinatedTransaction> = txs
+            .into_iter()
+            .filter(|tx| {
+                let has_partial_utxo = tx
+                    .speedup_data
+                    .as_ref()
+                    .is_some_and(|speedup_data| speedup_data.utxo.is_none());
+
+                if has_taproot_anchor && has_partial_utxo {
+                    warn!(
+                        "{} Transaction({}) skipped, its partial speedup utxo can not be spent with taproot anchors",
+                        style("Coordinator").green(),
+                        style(tx.tx_id).yellow()
+                    );
+                    return false;
+                }
+
+                true
+            })
+            .collect();
+
+        let (txs_in_batch_by_policies, fee_capped_txs, deferred_txs) =
+            self.batch_txs_by_weight_limit(txs)?;
+        self.notify_speedup_fee_cap_exceeded(fee_capped_txs)?;
+        self.notify_dispatch_deferred(deferred_txs)?;
+
+        let mut batches = txs_in_batch_by_policies.into_iter();
+
+        while let Some(txs_batch) = batches.next() {
+            // The batches left are not broadcast, they are batched again on the next tick.
+            if !self.tick_budget.has_speedups_left() {
+                debug!(
+                    "{} Speedup budget of the tick reached, {} batches left for the next ticks",
+                    style("Coordinator").green(),
+                    style(batches.len() + 1).yellow()
+                );
+
+                self.tick_budget.defer(
+                    std::iter::once(txs_batch)
+                        .chain(batches)
+                        .flatten()
+                        .map(|tx| tx.tx_id),
+                );
+                break;
+            }
+
+            // For each batch, attempt to broadcast all transactions individually. After determining which transactions were successfully sent,
+            // construct and broadcast a single CPFP transaction to pay for the entire batch.
+            let txs_sent: Vec<CoordinatedTransaction> = self.dispatch_txs(txs_batch)?;
+
+            // Only create a CPFP (Child Pays For Parent) transaction if there are transactions that were successfully sent in this batch.
+            // If no transactions were sent, skip CPFP creation for this batch.
+            if !txs_sent.is_empty() {
+                debug!(
+                    "{} Sending batch of {} transactions",
+                    style("Coordinator").green(),
+                    txs_sent.len()
+                );
+
+                self.send_cpfp_for_batch(&txs_sent)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn send_cpfp_for_batch(
+        &self,
+        txs: &[CoordinatedTransaction],
+    ) -> Result<(), BitcoinCoordinatorError> {
+        let txs_data: Vec<(SpeedupData, Transaction, String)> = txs
+            .iter()
+            .map(|coordinated_tx| {
+                Ok((
+                    required_speedup_data(coordinated_tx)?.clone(),
+                    coordinated_tx.tx.clone(),
+                    coordinated_tx.context.clone(),
+                ))
+            })
+            .collect::<Result<_, BitcoinCoordinatorError>>()?;
+
+        // The first speedup uses the most aggressive initial bump fee of the transactions in the batch.
+        let bump_fee = txs
+            .iter()
+            .map(|coordinated_tx| {
+                coordinated_tx
+                    .dispatch_options
+                    .initial_bump_fee_percentage
+                    .unwrap_or(self.settings().base_fee_multiplier)
+            })
+            .fold(f64::MIN, f64::max);
+
+        // The funding was checked before the batch was broadcast.
+        let funding = self
+            .store
+            .get_funding()?
+            .ok_or(BitcoinCoordinatorError::FundingDisappeared)?;
+        self.tick_budget.record_speedup();
+        self.create_and_send_cpfp_tx(txs_data, funding, bump_fee, None, None)?;
+
+        Ok(())
+    }
+
+    // A previous run could stop after broadcasting a batch and before creating its CPFP.
+    // Those transactions stay Dispatched without a speedup paying for them, so a CPFP is created for them here.
+    // Transactions already paid by a saved or retrying speedup are skipped, so running it again is harmless.
+    // Returns false when some of them are left to be recovered on a later tick.
+    fn recover_dispatched_txs_without_speedup(&self) -> Result<bool, BitcoinCoordinatorError> {
+        let txs = self.store.get_dispatched_txs_without_speedup()?;
+
+        if txs.is_empty() {
+            return Ok(true);
+        }
+
+        warn!(
+            "{} Recovering {} dispatched transactions without speedup",
+            style("Coordinator").green(),
+            style(txs.len()).yellow()
+        );
+
+        // Transactions left without a CPFP are recovered on a later tick.
+        self.send_cpfp_for_unpaid_txs(txs)
+    }
+
+    // Asks the funding provider for funding when it runs low. The requested funding is monitored and only
+    // registered with add_funding once its transaction is confirmed, a single top-up is pending at a time.
+    fn process_funding_topup(&self) -> Result<(), BitcoinCoordinatorError> {
+        let provider = match &self.funding_provider {
+            Some(provider) if !self.is_standby() => provider.clone(),
+            _ => return Ok(()),
+        };
+
+        if let Some(topup) = self.store.get_pending_funding_topup()? {
+            return self.register_confirmed_topup(topup);
+        }
+
+        if !self.needs_funding_topup()? {
+            return Ok(());
+        }
+
+        let amount = self.settings().auto_topup_amount_sats;
+
+        // The wallet may be empty or unreachable, the top-up is requested again on the next tick.
+        let topup = match provider.request_funding(amount) {
+            Ok(topup) => topup,
+            Err(e) => {
+                warn!(
+                    "{} Funding top-up of {} sats failed: {}",
+                    style("Coordinator").green(),
+                    style(amount).red(),
+                    e
+                );
+                return Ok(());
+            }
+        };
+
+        info!(
+            "{} Funding top-up requested | Txid({}) | Vout({}) | Amount({})",
+            style("Coordinator").green(),
+            style(topup.txid).cyan(),
+            style(topup.vout).cyan(),
+            style(topup.amount).cyan(),
+        );
+
+        self.monitor_internal_tx(topup.txid, InternalMonitor::FundingTopUp)?;
+        self.store.set_pending_funding_topup(Some(topup))?;
+
+        Ok(())
+    }
+
+    // There is no funding, or the active and pool funding together are below auto_topup_below_sats.
+    fn needs_funding_topup(&self) -> Result<bool, BitcoinCoordinatorError> {
+        let funding = match self.store.get_funding()? {
+            Some(funding) => funding,
+            None => return Ok(true),
+        };
+
+        let threshold = match self.settings().auto_topup_below_sats {
+            Some(threshold) => threshold,
+            None => return Ok(false),
+        };
+
+        let pool_amount: u64 = self
+            .store
+            .get_funding_pool()?
+            .iter()
+            .filter(|utxo| utxo.txid != funding.txid || utxo.vout != funding.vout)
+            .map(|utxo| utxo.amount)
+            .sum();
+
+        Ok(funding.amount + pool_amount < threshold)
+    }
+
+    fn register_confirmed_topup(&self, topup: Utxo) -> Result<(), BitcoinCoordinatorError> {
+        let tx_status = match self.monitor.get_tx_status(&topup.txid) {
+            Ok(tx_status) => tx_status,
+            Err(MonitorError::TransactionNotFound(_)) => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+
+        if !tx_status.is_confirmed() {
+            return Ok(());
+        }
+
+        self.monitor.ack_news(AckMonitorNews::Transaction(
+            topup.txid,
+            FUNDING_TRANSACTION_CONTEXT.to_string(),
+        ))?;
+        self.monitor.cancel(TypesToMonitor::Transactions(
+            vec![topup.txid],
+            FUNDING_TRANSACTION_CONTEXT.to_string(),
+            None,
+        ))?;
+        self.store.remove_internal_monitor(&topup.txid)?;
+
+        self.add_funding(topup.clone())?;
+        self.store.set_pending_funding_topup(None)?;
+
+        let news = CoordinatorNews::FundingTopUp(topup.txid, topup.amount);
+        self.update_news(news)?;
+
+        Ok(())
+    }
+
+    // Transactions broadcast when the funding could not pay for their CPFP are deferred.
+    // Their CPFP is sent once the funding can pay for it, for example after add_funding.
+    fn process_deferred_speedups(&self) -> Result<(), BitcoinCoordinatorError> {
+        if self.is_standby() {
+            return Ok(());
+        }
+
+        let deferred = self.store.get_deferred_speedup_txs()?;
+
+        if deferred.is_empty() {
+            return Ok(());
+        }
+
+        let unpaid_txs = self.store.get_dispatched_txs_without_speedup()?;
+
+        // Transactions paid by another speedup, confirmed or cancelled meanwhile do not need a CPFP anymore.
+        let resolved: Vec<Txid> = deferred
+            .iter()
+            .filter(|txid| !unpaid_txs.iter().any(|tx| tx.tx_id == **txid))
+            .cloned()
+            .collect();
+
+        self.store.remove_deferred_speedup_txs(&resolved)?;
+
+        let txs: Vec<CoordinatedTransaction> = unpaid_txs
+            .into_iter()
+            .filter(|tx| deferred.contains(&tx.tx_id))
+            .collect();
+
+        if txs.is_empty() {
+            return Ok(());
+        }
+
+        // Wait for a funding that can pay for the CPFP, instead of reporting insufficient funds on every tick.
+        match self.store.get_funding()? {
+            Some(funding) if funding.amount >= self.settings().min_funding_amount_sats => {}
+            _ => return Ok(()),
+        }
+
+        debug!(
+            "{} Sending deferred CPFP for {} transactions",
+            style("Coordinator").green(),
+            style(txs.len()).yellow()
+        );
+
+        self.send_cpfp_for_unpaid_txs(txs)?;
+
+        Ok(())
+    }
+
+    // Sends a CPFP for each batch of dispatched transactions not paid by any speedup.
+    // Returns false when some of them are left without a CPFP, because there is no funding or the unconfirmed chain is full.
+    fn send_cpfp_for_unpaid_txs(
+        &self,
+        txs: Vec<CoordinatedTransaction>,
+    ) -> Result<bool, BitcoinCoordinatorError> {
+        // Expired transactions are not paid by a CPFP anymore.
+        let current_height = self.current_height()?;
+        let txs: Vec<CoordinatedTransaction> = txs
+            .into_iter()
+            .filter(|tx| !is_expired(tx, current_height))
+            .collect();
+
+        let txs_count = txs.len();
+        // The transactions left out of the batches are already dispatched, they wait for a CPFP on the next ticks.
+        let (txs_batches, fee_capped_txs, _) = self.batch_txs_by_weight_limit(txs)?;
+        self.notify_speedup_fee_cap_exceeded(fee_capped_txs)?;
+        let txs_in_batches: usize = txs_batches.iter().map(|batch| batch.len()).sum();
+
+        for (index, txs_batch) in txs_batches.iter().enumerate() {
+            // Already dispatched, they wait for a CPFP on the next ticks.
+            if !self.tick_budget.has_speedups_left() {
+                self.tick_budget
+                    .defer(txs_batches[index..].iter().flatten().map(|tx| tx.tx_id));
+                return Ok(false);
+            }
+
+            if !self.store.can_speedup()? {
+                warn!("{} Can not speedup", style("Coordinator").green());
+
+                if !self.store.is_funding_available()? {
+                    self.notify_funding_not_found()?;
+                }
+
+                return Ok(false);
+            }
+
+            self.send_cpfp_for_batch(txs_batch)?;
+        }
+
+        Ok(txs_in_batches == txs_count)
+    }
+
+    fn notify_funding_not_found(&self) -> Result<(), BitcoinCoordinatorError> {
+        let news = CoordinatorNews::FundingNotFound;
+        self.update_news(news)?;
+        Ok(())
+    }
+
+    // The speedup was orphaned by a reorg. Its change, and the change of the speedups funded from it,
+    // can not be used as funding until the speedup is mined again.
+    fn notify_speedup_orphaned(
+        &self,
+        speedup: &CoordinatedSpeedUpTransaction,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        let orphaned_speedups = self.store.orphan_speedup(speedup.tx_id)?;
+
+        let parent_txids = speedup.paid_txids();
+
+        warn!(
+            "{} {} Transaction({}) orphaned by a reorg | Parents({:?}) | OrphanedSpeedups({})",
+            style("Coordinator").green(),
+            speedup.get_tx_name(),
+            style(speedup.tx_id).yellow(),
+            parent_txids,
+            style(orphaned_speedups.len()).red(),
+        );
+
+        let news = CoordinatorNews::SpeedupOrphaned(speedup.tx_id, parent_txids);
+        self.update_news(news)?;
+
+        Ok(())
+    }
+
+    // The speedups replaced by a confirmed RBF, and the ones funded from their change, will never be confirmed.
+    // The transactions they paid for that are not paid by the replacement wait for a new CPFP.
+    fn notify_speedup_chain_invalidated(
+        &self,
+        rbf: &CoordinatedSpeedUpTransaction,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        let invalidated = self.store.invalidate_replaced_speedups(rbf.tx_id)?;
+
+        if invalidated.is_empty() {
+            return Ok(());
+        }
+
+        let unpaid_txids = self.defer_invalidated_speedup_txs(&invalidated)?;
+
+        warn!(
+            "{} RBF Transaction({}) confirmed | InvalidatedSpeedups({:?}) | UnpaidTransactions({})",
+            style("Coordinator").green(),
+            style(rbf.tx_id).yellow(),
+            invalidated,
+            style(unpaid_txids.len()).red(),
+        );
+
+        let news = CoordinatorNews::SpeedupChainInvalidated(invalidated);
+        self.update_news(news)?;
+
+        Ok(())
+    }
+
+    // Defers the speedup of the dispatched transactions the invalidated speedups paid for, they wait for a new CPFP.
+    // Returns their txids.
+    fn defer_invalidated_speedup_txs(
+        &self,
+        invalidated: &[Txid],
+    ) -> Result<Vec<Txid>, BitcoinCoordinatorError> {
+        let mut paid_txids = Vec::new();
+        for txid in invalidated.iter() {
+            paid_txids.extend(self.store.get_speedup(txid)?.paid_txids());
+        }
+
+        let unpaid_txids: Vec<Txid> = self
+            .store
+            .get_dispatched_txs_without_speedup()?
+            .into_iter()
+            .map(|tx| tx.tx_id)
+            .filter(|txid| paid_txids.contains(txid))
+            .collect();
+
+        self.store.defer_speedup(&unpaid_txids)?;
+
+        Ok(unpaid_txids)
+    }
+
+    // Prunes the store every `auto_prune_depth_blocks` blocks, when automatic pruning is enabled.
+    fn auto_prune(&self) -> Result<(), BitcoinCoordinatorError> {
+        let depth = match self.settings().auto_prune_depth_blocks {
+            Some(depth) => depth,
+            None => return Ok(()),
+        };
+
+        let current_height = self.current_height()?;
+
+        if let Some(last_prune_height) = self.last_prune_height.get() {
+            if current_height < last_prune_height + depth {
+                return Ok(());
+            }
+        }
+
+        self.prune(depth)?;
+        self.last_prune_height.set(Some(current_height));
+
+        Ok(())
+    }
+
+    // Sends the transaction to the node and records the attempt, with its raw hex, in the journal.
+    // Nothing is sent while the node is unreachable, the error is handled like a connection error.
+    fn send_tx(&self, tx: &Transaction) -> Result<Txid, BitcoinClientError> {
+        if self.node_breaker.is_open() {
+            return Err(BitcoinClientError::ClientError(
+                "Node unreachable, no connection until it answers again".to_string(),
+            ));
+        }
+
+        let result = self.client.send_transaction(tx);
+        self.store
+            .journal()
+            .record(JournalEvent::broadcast_attempt(tx, &result));
+
+        // A rejection is an answer of the node, only connection errors count as failures.
+        match &result {
+            Err(e)
+                if BroadcastFailureKind::from_error_message(&e.to_string())
+                    == BroadcastFailureKind::ConnectionError =>
+            {
+                self.record_node_failure();
+            }
+            _ => {
+                self.node_breaker.record_success();
+            }
+        }
+
+        result
+    }
+
+    // Counts a failure reaching the node, the circuit breaker opens after node_failure_threshold consecutive failures.
+    fn record_node_failure(&self) {
+        let threshold = self.settings().node_failure_threshold;
+
+        if self.node_breaker.record_failure(threshold) {
+            warn!(
+                "{} Node unreachable after {} consecutive failures, dispatch and speedups are suspended",
+                style("Coordinator").green(),
+                style(self.node_breaker.consecutive_failures()).red(),
+            );
+        }
+    }
+
+    // Reports the opening of the circuit breaker with a single NodeUnreachable news.
+    fn report_node_unreachable(&self) -> Result<(), BitcoinCoordinatorError> {
+        if let Some(since) = self.node_breaker.take_unreported() {
+            self.update_news(CoordinatorNews::NodeUnreachable(since))?;
+        }
+
+        Ok(())
+    }
+
+    // Asks the node for its best block while it is unreachable.
+    // Returns true when the node answers, which closes the circuit breaker.
+    fn probe_node(&self) -> Result<bool, BitcoinCoordinatorError> {
+        if let Err(e) = self.client.get_best_block() {
+            debug!(
+                "{} Node still unreachable: {}",
+                style("Coordinator").green(),
+                e
+            );
+            return Ok(false);
+        }
+
+        if let Some(unreachable_ms) = self.node_breaker.record_success() {
+            info!(
+                "{} Node reachable again after {} ms, dispatch and speedups are resumed",
+                style("Coordinator").green(),
+                style(unreachable_ms).blue(),
+            );
+
+            self.update_news(CoordinatorNews::NodeRecovered(unreachable_ms))?;
+        }
+
+        Ok(true)
+    }
+
+    // Ticks the monitor and, once it is ready, runs the tick pipeline.
+    fn run_tick(&self, started_at: Instant) -> Result<(), BitcoinCoordinatorError> {
+        self.monitor.tick()?;
+        // The monitor is considered ready when it has fully indexed the blockchain and is up to date with the latest block.
+        // Note that if there is a significant gap in the indexing process, it may take multiple ticks for the monitor to become ready.
+        let is_ready = self.monitor.is_ready()?;
+
+        let is_ready_str = if is_ready { "Ready" } else { "Not Ready" };
+
+        if self.last_ready.replace(Some(is_ready)) != Some(is_ready) {
+            info!("{} {}", style("Coordinator").green(), is_ready_str);
+        } else if !self.settings().quiet_ready {
+            debug!("{} {}", style("Coordinator").green(), is_ready_str);
+        }
+
+        if !is_ready {
+            return Ok(());
+        }
+
+        let result = self
+            .process_ready_tick()
+            .and_then(|_| self.report_tick_failures());
+
+        // Outside a tick the monitor is asked again for its block.
+        self.tick_height.set(None);
+        self.tick_block_hash.set(None);
+
+        result?;
+        self.notify_tick_completed(started_at)?;
+
+        Ok(())
+    }
+
+    // Settings in use, they can be replaced with update_settings between ticks.
+    fn settings(&self) -> Ref<'_, CoordinatorSettings> {
+        self.settings.borrow()
+    }
+
+    // Height of the monitor, asked only once during a tick.
+    fn current_height(&self) -> Result<BlockHeight, BitcoinCoordinatorError> {
+        match self.tick_height.get() {
+            Some(height) => Ok(height),
+            None => Ok(self.monitor.get_monitor_height()?),
+        }
+    }
+
+    // Hash of the current block of the monitor, asked only once during a tick.
+    // None when the monitor has not indexed any block yet.
+    fn current_block_hash(&self) -> Result<Option<BlockHash>, BitcoinCoordinatorError> {
+        if let Some(hash) = self.tick_block_hash.get() {
+            return Ok(Some(hash));
+        }
+
+        let hash = self.monitor.get_current_block()?.map(|block| block.hash);
+
+        if self.tick_height.get().is_some() {
+            self.tick_block_hash.set(hash);
+        }
+
+        Ok(hash)
+    }
+
+    // Reports the block of the tick with a NewBlock news when the consumer is subscribed to new blocks.
+    fn process_new_block(&self, block_height: BlockHeight) -> Result<(), BitcoinCoordinatorError> {
+        if !self.store.is_subscribed_to_new_blocks()? {
+            return Ok(());
+        }
+
+        let block_hash = match self.current_block_hash()? {
+            Some(block_hash) => block_hash,
+            None => return Ok(()),
+        };
+
+        if self.last_new_block.get() == Some(block_hash) {
+            return Ok(());
+        }
+
+        self.last_new_block.set(Some(block_hash));
+        self.update_news(CoordinatorNews::NewBlock(block_height, block_hash))?;
+
+        Ok(())
+    }
+
+    fn update_news(&self, news: CoordinatorNews) -> Result<(), BitcoinCoordinatorError> {
+        if let Some(current_block_hash) = self.current_block_hash()? {
+            let kind = news.kind();
+            self.store.update_news(news.clone(), current_block_hash)?;
+            self.observer.on_news_emitted(kind);
+            self.tick_summary.record_news();
+            self.store.journal().record(JournalEvent::NewsEmitted(news));
+        }
+
+        Ok(())
+    }
+
+    // This function is designed to expedite a CPFP (Child Pays For Parent) transaction.
+    // It achieves this by creating an additional CPFP transaction to provide further funding to the previous one.
+    // It is ensured that funding is available before invoking this function.
+    fn speedup_cpfp_tx(&self) -> Result<(), BitcoinCoordinatorError> {
+        let funding = self
+            .store
+            .get_funding()?
+            .ok_or(BitcoinCoordinatorError::FundingDisappeared)?;
+
+        // A speedup from the pool funding would not be a descendant of the last speedup, so it can not boost it.
+        if self.store.is_pool_funding(&funding)? {
+            debug!(
+                "{} Active speedup chain can not be boosted with a pool funding",
+                style("Coordinator").green(),
+            );
+
+            return Ok(());
+        }
+
+        let last_speedup = self.store.get_last_speedup()?;
+
+        if let Some((speedup, _)) = last_speedup {
+            let bump_fee_percentage = self.get_bump_fee_percentage_strategy(
+                speedup.bump_fee_percentage_used,
+                Some(speedup.network_fee_rate_used),
+            )?;
+
+            debug!(
+                "{} Boosting CPFP Transaction({})",
+                style("Coordinator").green(),
+                style(speedup.tx_id).yellow()
+            );
+            self.create_and_send_cpfp_tx(vec![], funding, bump_fee_percentage, None, None)?;
+        }
+
+        Ok(())
+    }
+
+    fn inform_dispatch_speedup_error(
+        &self,
+        txs_info: (Vec<Txid>, Vec<String>),
+        speedup_type: String,
+        is_retry_tx: bool,
+        speedup_tx_id: Txid,
+        tx: Transaction,
+        dispatch_error: String,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        if is_retry_tx {
+            warn!(
+                "{} Error Resending {} Transaction({}) | IsRetryTx",
+                style("Coordinator").green(),
+                speedup_type,
+                style(speedup_tx_id).yellow(),
+            );
+        } else {
+            error!(
+                "{} Error Sending  {} Transaction({})",
+                style("Coordinator").green(),
+                speedup_type,
+                style(speedup_tx_id).yellow(),
+            );
+            debug!("Transaction Details: {:?}", style(&tx).magenta());
+        }
+
+        let news = CoordinatorNews::DispatchSpeedUpError(
+            txs_info.0,
+            txs_info.1,
+            speedup_tx_id,
+            dispatch_error,
+        );
+
+        self.update_news(news)?;
+
+        Ok(())
+    }
+
+    // Returns the node error when a replacement (RBF) is rejected for insufficient fee, so the caller can escalate the fee.
+    fn dispatch_speedup(
+        &self,
+        tx: Transaction,
+        speedup_data: CoordinatedSpeedUpTransaction,
+        speedup_fee: u64,
+        retry_txid: Option<Txid>,
+    ) -> Result<Option<String>, BitcoinCoordinatorError> {
+        let speedup_type = speedup_data.get_tx_name();
+
+        debug!(
+            "{} Send {} Transaction({})",
+            style("Coordinator").green(),
+            speedup_type,
+            style(speedup_data.tx_id).yellow(),
+        );
+
+        let txs_info: (Vec<Txid>, Vec<String>) = speedup_data
+            .speedup_tx_data
+            .iter()
+            .map(|parent| (parent.tx_id, parent.context.clone()))
+            .collect();
+
+        // Saved before the broadcast, so a speedup broadcast by a process that stops before saving it is recovered.
+        self.store.save_speedup_intent(SpeedupIntent {
+            speedup: speedup_data.clone(),
+            speedup_fee,
+            retry_txid,
+        })?;
+
+        let dispatch_result = self.send_tx(&tx);
+
+        match dispatch_result {
+            Ok(_) => {
+                let dispatch_block = self.client.get_best_block()?;
+
+                // Update broadcast_block_height with the block where the transaction was dispatched
+                let mut speedup_data_with_block = speedup_data;
+                speedup_data_with_block.broadcast_block_height = dispatch_block;
+
+                self.monitor_internal_tx(speedup_data_with_block.tx_id, InternalMonitor::Speedup)?;
+
+                debug!(
+                    "{} Successfully sent {} Transaction({}) dispatched at block height {}",
+                    style("Coordinator").green(),
+                    speedup_type,
+                    style(speedup_data_with_block.tx_id).yellow(),
+                    style(dispatch_block).blue(),
+                );
+
+                self.notify_speedup_created(&speedup_data_with_block, speedup_fee)?;
+                self.persist_speedup(speedup_data_with_block, retry_txid);
+            }
+            Err(e) => {
+                let error_msg = e.to_string();
+                let error_kind = BroadcastFailureKind::from_error_message(&error_msg);
+
+                // Unless the node already has it, the speedup was not broadcast.
+                if error_kind.action() != BroadcastFailureAction::Dispatched {
+                    self.store.remove_speedup_intent(&speedup_data.tx_id)?;
+                    self.observer
+                        .on_dispatch_error(speedup_data.tx_id, &error_msg);
+                }
+
+                // The funding may have been spent since it was checked, then it is invalidated instead of retried.
+                if error_kind == BroadcastFailureKind::MissingInputs && !speedup_data.is_rbf {
+                    if let FundingOutputState::Spent(spending_txid) =
+                        self.get_funding_output_state(&speedup_data.prev_funding)
+                    {
+                        self.invalidate_funding(
+                            &speedup_data.prev_funding,
+                            spending_txid,
+                            &speedup_data.paid_txids(),
+                            retry_txid,
+                        )?;
+
+                        return Ok(None);
+                    }
+                }
+
+                if error_kind == BroadcastFailureKind::InsufficientReplacementFee
+                    && speedup_data.is_rbf
+                {
+                    warn!(
+                        "{} {} Transaction({}) rejected for insufficient fee: {}",
+                        style("Coordinator").green(),
+                        speedup_type,
+                        style(speedup_data.tx_id).yellow(),
+                        error_msg
+                    );
+
+                    return Ok(Some(error_msg));
+                }
+
+                match error_kind.action() {
+                    BroadcastFailureAction::Dispatched => {
+                        // The speedup transaction is already known by the node (mempool or blockchain),
+                        // so it is handled as sent. A mined one is seen as confirmed on the next tick.
+                        debug!(
+                            "{} {} Transaction({}) already known by node: {}",
+                            style("Coordinator").green(),
+                            speedup_type,
+                            style(speedup_data.tx_id).yellow(),
+                            error_msg
+                        );
+
+                        let dispatch_block = self.current_height()?;
+
+                        let mut speedup_data_with_block = speedup_data;
+                        speedup_data_with_block.broadcast_block_height = dispatch_block;
+
+                        self.monitor_internal_tx(
+                            speedup_data_with_block.tx_id,
+                            InternalMonitor::Speedup,
+                        )?;
+
+                        // Treat as success: persist the speedup so it can be tracked/confirmed/finalized.
+                        self.notify_speedup_created(&speedup_data_with_block, speedup_fee)?;
+                        self.persist_speedup(speedup_data_with_block, retry_txid);
+                    }
+                    action @ (BroadcastFailureAction::Retry | BroadcastFailureAction::Requeue) => {
+                        // Retryable errors (mempool policy / infrastructure).
+                        // If we reach here it's because:
+                        // - this is the first attempt (no `retry_txid`), or
+                        // - the entry came from `get_speedups_for_retry`, which already respected max_retries and intervals.
+                        // While the node is unreachable the NodeUnreachable news is reported instead.
+                        if !(action == BroadcastFailureAction::Requeue
+                            && self.node_breaker.is_open())
+                        {
+                            self.inform_dispatch_speedup_error(
+                                txs_info.clone(),
+                                speedup_type.clone(),
+                                retry_txid.is_some(),
+                                speedup_data.tx_id,
+                                tx.clone(),
+                                error_msg,
+                            )?;
+                        }
+
+                        if retry_txid.is_none() {
+                            // First failure: enqueue for retry with retry_count = 0.
+                            self.store.enqueue_speedup_for_retry(speedup_data)?;
+                        } else if action == BroadcastFailureAction::Retry {
+                            // Increment the retry counter for an already enqueued entry.
+                            // When the node could not be reached the attempt does not count against max_retries.
+                            self.store
+                                .increment_speedup_retry_count(speedup_data.tx_id)?;
+                        }
+                    }
+                    BroadcastFailureAction::Fail => {
+                        // Non-retryable error (policy rejection, malformed transaction, invalid inputs, etc.)
+                        // Don't retry, just report the error
+                        error!(
+                            "{} Fatal error sending {} Transaction({}): {} (not retrying)",
+                            style("Coordinator").green(),
+                            speedup_type,
+                            style(speedup_data.tx_id).yellow(),
+                            error_msg
+                        );
+
+                        self.inform_dispatch_speedup_error(
+                            txs_info.clone(),
+                            speedup_type.clone(),
+                            retry_txid.is_some(),
+                            speedup_data.tx_id,
+                            tx.clone(),
+                            error_msg,
+                        )?;
+
+                        // Remove from retry queue if it was there
+                        if let Some(retry_txid) = retry_txid {
+                            self.store.dequeue_speedup_for_retry(retry_txid)?;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn notify_speedup_created(
+        &self,
+        speedup: &CoordinatedSpeedUpTransaction,
+        speedup_fee: u64,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        self.tick_summary.record_speedup(speedup_fee);
+        self.observer.on_speedup_created(
+            speedup.tx_id,
+            speedup_fee,
+            speedup.network_fee_rate_used,
+            speedup.speedup_tx_data.len(),
+            speedup.is_rbf,
+        );
+
+        let paid_txids = speedup.paid_txids();
+
+        self.update_news(CoordinatorNews::SpeedupCreated(
+            speedup.tx_id,
+            paid_txids,
+            speedup_fee,
+            speedup.network_fee_rate_used,
+            speedup.is_rbf,
+        ))?;
+
+        if let Some(change_sats) = speedup.exhausted_change {
+            self.update_news(CoordinatorNews::FundingExhausted(
+                speedup.tx_id,
+                change_sats,
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    // Saves a broadcast speedup. The node already has it, so a failed write is retried on the next ticks instead of failing the tick.
+    fn persist_speedup(&self, speedup: CoordinatedSpeedUpTransaction, retry_txid: Option<Txid>) {
+        if let Err(e) = self.save_broadcast_speedup(speedup.clone(), retry_txid) {
+            warn!(
+                "{} Failed to save Speedup({}), retrying on the next tick: {}",
+                style("Coordinator").green(),
+                style(speedup.tx_id).yellow(),
+                e
+            );
+
+            self.pending_writes.push(
+                PendingStoreWrite::Speedup {
+                    speedup: Box::new(speedup),
+                    funding_group: self.store.funding_group(),
+                    retry_txid,
+                },
+                1,
+            );
+        }
+    }
+
+    fn save_broadcast_speedup(
+        &self,
+        speedup: CoordinatedSpeedUpTransaction,
+        retry_txid: Option<Txid>,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        let tx_id = speedup.tx_id;
+        let paid_txids = speedup.paid_txids();
+
+        // The record is written last, a retry does not save again a speedup that was saved.
+        match self.store.get_speedup(&tx_id) {
+            Ok(_) => {}
+            Err(BitcoinCoordinatorStoreError::SpeedupNotFound) => {
+                self.store.save_speedup(speedup)?
+            }
+            Err(e) => return Err(e),
+        }
+
+        self.store.remove_deferred_speedup_txs(&paid_txids)?;
+
+        if let Some(retry_txid) = retry_txid {
+            self.store.dequeue_speedup_for_retry(retry_txid)?;
+        }
+
+        // The intent goes last, if the process stops before the speedup is saved it is recovered from it.
+        self.store.remove_speedup_intent(&tx_id)
+    }
+
+    // Saves an external speedup in the speedup chain selected by the store, after checking it spends its funding.
+    fn save_external_speedup(
+        &self,
+        tx: Transaction,
+        parents: Vec<CoordinatedTransaction>,
+        change_vout: u32,
+        change_pubkey: PublicKey,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        let tx_id = tx.compute_txid();
+
+        let funding = self
+            .store
+            .get_funding()?
+            .ok_or(BitcoinCoordinatorStoreError::FundingNotFound)?;
+        let funding_outpoint = OutPoint::new(funding.txid, funding.vout);
+
+        if !tx
+            .input
+            .iter()
+            .any(|input| input.previous_output == funding_outpoint)
+        {
+            return Err(BitcoinCoordinatorError::ExternalSpeedupFundingNotSpent(
+                tx_id,
+                funding_outpoint,
+            ));
+        }
+
+        let change_mismatch = |reason: &str| {
+            BitcoinCoordinatorError::ExternalSpeedupChangeMismatch(
+                tx_id,
+                change_vout,
+                reason.to_string(),
+            )
+        };
+
+        let change_output = tx
+            .output
+            .get(change_vout as usize)
+            .ok_or_else(|| change_mismatch("the output does not exist"))?;
+
+        // The funding is spent as a P2WPKH output, so the change must be one to be spent by the next speedup.
+        let change_script = change_pubkey
+            .wpubkey_hash()
+            .map(|wpubkey_hash| ScriptBuf::new_p2wpkh(&wpubkey_hash))
+            .map_err(|_| change_mismatch("the change key is not compressed"))?;
+
+        if change_output.script_pubkey != change_script {
+            return Err(change_mismatch(
+                "the output is not a P2WPKH output of the change key",
+            ));
+        }
+
+        let change = Utxo::new(
+            tx_id,
+            change_vout,
+            change_output.value.to_sat(),
+            &change_pubkey,
+        );
+
+        // What the funding paid, the fee rate is the one of the package with the covered transactions.
+        let speedup_fee = funding.amount.saturating_sub(change.amount);
+        let parents_vsize: usize = parents.iter().map(|parent| parent.tx.vsize()).sum();
+        let network_fee_rate = speedup_fee / (tx.vsize() + parents_vsize) as u64;
+        let covered_txids: Vec<Txid> = parents.iter().map(|parent| parent.tx_id).collect();
+
+        let speedup_tx_data = parents
+            .into_iter()
+            .map(|parent| {
+                let speedup_data = required_speedup_data(&parent)?.clone();
+                Ok(SpeedupParent::new(speedup_data, &parent.tx, parent.context))
+            })
+            .collect::<Result<_, BitcoinCoordinatorError>>()?;
+
+        let mut speedup = CoordinatedSpeedUpTransaction::new(
+            tx_id,
+            funding,
+            change,
+            false,
+            self.client.get_best_block()?,
+            SpeedupState::Dispatched,
+            self.settings().base_fee_multiplier,
+            speedup_tx_data,
+            network_fee_rate,
+            tx.vsize(),
+        );
+        speedup.is_external = true;
+
+        info!(
+            "{} Import external speedup Transaction({}) | Transactions#({}) | Fee({}) | Change({}:{})",
+            style("Coordinator").green(),
+            style(tx_id).yellow(),
+            style(covered_txids.len()).blue(),
+            style(speedup_fee).blue(),
+            style(tx_id).blue(),
+            style(change_vout).blue(),
+        );
+
+        self.store.journal().record(JournalEvent::SpeedupCreated {
+            tx_id,
+            is_rbf: false,
+            paid_txids: covered_txids.clone(),
+            network_fee_rate,
+            bump_fee_percentage: speedup.bump_fee_percentage_used,
+            vsize: tx.vsize(),
+            parents_vsize,
+            fee: speedup_fee,
+        });
+
+        self.monitor_internal_tx(tx_id, InternalMonitor::Speedup)?;
+
+        self.store.save_speedup(speedup.clone())?;
+        self.store.remove_deferred_speedup_txs(&covered_txids)?;
+        self.notify_speedup_created(&speedup, speedup_fee)?;
+
+        Ok(())
+    }
+
+    // Retries the store writes that failed after a broadcast, in the order they failed.
+    // Stops at the first write that fails again, and fails the tick once it ran out of attempts.
+    fn process_pending_store_writes(&self) -> Result<(), BitcoinCoordinatorError> {
+        while let Some((write, attempts)) = self.pending_writes.pop() {
+            let result = match &write {
+                PendingStoreWrite::TxDispatched {
+                    tx_id,
+                    block_height,
+                    fee_rate,
+                } => match self.store.get_tx(tx_id)?.state {
+                    TransactionState::ToDispatch => {
+                        self.store
+                            .update_tx_to_dispatched(*tx_id, *block_height, *fee_rate)
+                    }
+                    _ => Ok(()),
+                },
+                PendingStoreWrite::Speedup {
+                    speedup,
+                    funding_group,
+                    retry_txid,
+                } => self.store.with_funding_group(funding_group.as_deref(), || {
+                    self.save_broadcast_speedup(*speedup.clone(), *retry_txid)
+                }),
+            };
+
+            let Err(e) = result else {
+                continue;
+            };
+
+            let attempts = attempts + 1;
+
+            // A lost speedup is still recovered from its intent once the store can be written again.
+            if attempts >= MAX_STORE_WRITE_ATTEMPTS {
+                error!(
+                    "{} Store write failed {} times, {} writes are discarded: {}",
+                    style("Coordinator").green(),
+                    style(attempts).red(),
+                    style(self.pending_writes.len() + 1).red(),
+                    e
+                );
+
+                while self.pending_writes.pop().is_some() {}
+
+                return Err(BitcoinCoordinatorError::StoreWriteFailed(
+                    attempts,
+                    e.to_string(),
+                ));
+            }
+
+            warn!(
+                "{} Store write failed {} times, retrying on the next tick: {}",
+                style("Coordinator").green(),
+                style(attempts).yellow(),
+                e
+            );
+
+            self.pending_writes.push_front(write, attempts);
+            break;
+        }
+
+        Ok(())
+    }
+
+    // Retries the store writes waiting for the next tick, as many times as a tick would before giving up.
+    // Returns the number of writes saved, the ones left are reported in the shutdown checkpoint.
+    fn flush_pending_store_writes(&self) -> usize {
+        let mut flushed = 0;
+
+        for _ in 0..MAX_STORE_WRITE_ATTEMPTS {
+            if self.pending_writes.is_empty() {
+                break;
+            }
+
+            let before = self.pending_writes.len();
+
+            if let Err(e) = self.process_pending_store_writes() {
+                // The writes were discarded, a lost speedup is recovered from its intent on the next start.
+                error!(
+                    "{} Store writes not flushed on shutdown: {}",
+                    style("Coordinator").green(),
+                    e
+                );
+                return flushed;
+            }
+
+            flushed += before - self.pending_writes.len();
+        }
+
+        flushed
+    }
+
+    // Fails once the coordinator was shut down.
+    fn check_running(&self) -> Result<(), BitcoinCoordinatorError> {
+        if self.stopped.get() {
+            return Err(BitcoinCoordinatorError::CoordinatorStopped);
+        }
+
+        Ok(())
+    }
+
+    fn is_standby(&self) -> bool {
+        self.settings().mode == CoordinatorMode::Standby
+    }
+
+    // Fails in standby, unless the transactions are only queued to be dispatched once the coordinator is promoted.
+    fn check_active(&self, queue_only: bool) -> Result<(), BitcoinCoordinatorError> {
+        if self.is_standby() && !queue_only {
+            return Err(BitcoinCoordinatorError::CoordinatorInStandby);
+        }
+
+        Ok(())
+    }
+
+    // Fails once another instance took the store over, nothing is changed in a store this coordinator does not own.
+    fn check_ownership(&self) -> Result<(), BitcoinCoordinatorError> {
+        self.store.check_ownership(self.instance_id)?;
+        Ok(())
+    }
+
+    // Resolves the speedups left between their broadcast and their save, asking the node whether they were broadcast.
+    // A speedup the node has is saved as if it had just been sent, the others are discarded.
+    fn reconcile_speedup_intents(&self) -> Result<(), BitcoinCoordinatorError> {
+        for intent in self.store.get_speedup_intents()? {
+            let tx_id = intent.speedup.tx_id;
+
+            match self.store.get_speedup(&tx_id) {
+                Ok(_) => {
+                    self.store.remove_speedup_intent(&tx_id)?;
+                    continue;
+                }
+                Err(BitcoinCoordinatorStoreError::SpeedupNotFound) => {}
+                Err(e) => return Err(e.into()),
+            }
+
+            match self.client.get_transaction(&tx_id) {
+                Ok(Some(_)) => {
+                    info!(
+                        "{} Recovered Speedup({}) broadcast before it was saved",
+                        style("Coordinator").green(),
+                        style(tx_id).yellow(),
+                    );
+
+                    let mut speedup = intent.speedup;
+                    speedup.broadcast_block_height = self.client.get_best_block()?;
+
+                    self.monitor_internal_tx(tx_id, InternalMonitor::Speedup)?;
+
+                    self.notify_speedup_created(&speedup, intent.speedup_fee)?;
+                    self.persist_speedup(speedup, intent.retry_txid);
+                }
+                Ok(None) => {
+                    // The transactions of a new CPFP that was never broadcast are still unpaid.
+                    if !intent.speedup.is_rbf && intent.retry_txid.is_none() {
+                        self.defer_speedup(&intent.speedup.paid_txids())?;
+                    }
+
+                    self.store.remove_speedup_intent(&tx_id)?;
+                }
+                Err(e) => {
+                    warn!(
+                        "{} Could not check whether Speedup({}) was broadcast, checking on the next tick: {}",
+                        style("Coordinator").green(),
+                        style(tx_id).yellow(),
+                        e
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn dispatch_txs(
+        &self,
+        txs: Vec<CoordinatedTransaction>,
+    ) -> Result<Vec<CoordinatedTransaction>, BitcoinCoordinatorError> {
+        let mut txs_sent = Vec::new();
+
+        // The fee rate targeted by the dispatched transactions, used later to top up the speedup chain.
+        let fee_rate_at_dispatch = self.get_network_fee_rate(self.settings().max_feerate_sat_vb)?;
+
+        for tx in txs {
+            // The transactions left are sent once the node answers again.
+            if self.node_breaker.is_open() {
+                break;
+            }
+
+            self.tick_budget.record_broadcast();
+
+            match self.dispatch_tx(&tx, fee_rate_at_dispatch) {
+                Ok(true) => txs_sent.push(tx),
+                Ok(false) => {}
+                Err(e) => self.record_tx_failure(tx.tx_id, e),
+            }
+        }
+
+        Ok(txs_sent)
+    }
+
+    // Sends a transaction and updates its state. Returns true when the transaction is in the mempool, to be paid by a CPFP.
+    fn dispatch_tx(
+        &self,
+        tx: &CoordinatedTransaction,
+        fee_rate_at_dispatch: u64,
+    ) -> Result<bool, BitcoinCoordinatorError> {
+        debug!(
+            "{} Sending Transaction({})",
+            style("Coordinator").green(),
+            style(tx.tx_id).yellow(),
+        );
+
+        let dispatch_result = self.send_tx(&tx.tx);
+
+        match dispatch_result {
+            Ok(_) => {
+                let dispatch_block = self.client.get_best_block()?;
+
+                debug!(
+                    "{} Transaction({}) dispatched at block height {}",
+                    style("Coordinator").green(),
+                    style(tx.tx_id).yellow(),
+                    style(dispatch_block).blue(),
+                );
+
+                // The transaction stays ToDispatch in the store until the write succeeds, a resend is "already known".
+                if let Err(e) = self.store.update_tx_to_dispatched(
+                    tx.tx_id,
+                    dispatch_block,
+                    fee_rate_at_dispatch,
+                ) {
+                    warn!(
+                        "{} Failed to save Transaction({}) as dispatched, retrying on the next tick: {}",
+                        style("Coordinator").green(),
+                        style(tx.tx_id).yellow(),
+                        e
+                    );
+
+                    self.pending_writes.push(
+                        PendingStoreWrite::TxDispatched {
+                            tx_id: tx.tx_id,
+                            block_height: dispatch_block,
+                            fee_rate: fee_rate_at_dispatch,
+                        },
+                        1,
+                    );
+                }
+
+                self.monitor_anchor(tx)?;
+
+                let attempt = tx
+                    .retry_info
+                    .as_ref()
+                    .map_or(0, |retry_info| retry_info.retries_count)
+                    + 1;
+                self.observer.on_transaction_broadcast(tx.tx_id, attempt);
+                self.tick_summary.record_dispatched();
+
+                // Let the consumer correlate the scheduled transaction with its broadcast.
+                if tx.target_block_height.is_some() {
+                    let news = CoordinatorNews::DispatchScheduled(tx.tx_id, dispatch_block);
+                    self.update_news(news)?;
+                }
+
+                Ok(true)
+            }
+            Err(e) => {
+                let error_msg = e.to_string();
+                let error_kind = BroadcastFailureKind::from_error_message(&error_msg);
+
+                // The node already has the transaction, the broadcast did not fail.
+                if error_kind.action() == BroadcastFailureAction::Dispatched {
+                    return self.acknowledge_known_tx(
+                        tx,
+                        fee_rate_at_dispatch,
+                        error_kind,
+                        &error_msg,
+                    );
+                }
+
+                error!(
+                    "{} Error Sending Transaction({}): {}",
+                    style("Coordinator").green(),
+                    style(tx.tx_id).blue(),
+                    error_msg
+                );
+
+                self.observer.on_dispatch_error(tx.tx_id, &error_msg);
+
+                match error_kind.action() {
+                    // Handled above as a successful broadcast.
+                    BroadcastFailureAction::Dispatched => {}
+                    BroadcastFailureAction::Requeue => {
+                        // Infra error, the transaction stays ToDispatch and is sent again on the next tick.
+                        warn!(
+                            "{} Transaction({}) requeued, the node could not be reached",
+                            style("Coordinator").green(),
+                            style(tx.tx_id).yellow(),
+                        );
+                    }
+                    BroadcastFailureAction::Retry => {
+                        self.store.increment_tx_retry_count(tx.tx_id)?;
+                    }
+                    BroadcastFailureAction::Fail => {
+                        self.store
+                            .update_tx_state(tx.tx_id, TransactionState::Failed)?;
+                    }
+                }
+
+                // While the node is unreachable a single NodeUnreachable news is reported, not one per transaction.
+                if error_kind.action() == BroadcastFailureAction::Requeue
+                    && self.node_breaker.is_open()
+                {
+                    return Ok(false);
+                }
+
+                if let Some(news) = error_kind.news(tx.tx_id, tx.context.clone(), error_msg) {
+                    self.update_news(news)?;
+                }
+
+                Ok(false)
+            }
+        }
+    }
+
+    // Handles a transaction the node answered it already has as dispatched at the current height.
+    // Returns true when it is in the mempool, to be paid by the CPFP of the dispatched transactions.
+    fn acknowledge_known_tx(
+        &self,
+        tx: &CoordinatedTransaction,
+        fee_rate_at_dispatch: u64,
+        error_kind: BroadcastFailureKind,
+        error_msg: &str,
+    ) -> Result<bool, BitcoinCoordinatorError> {
+        debug!(
+            "{} Transaction({}) already known by node: {}",
+            style("Coordinator").green(),
+            style(tx.tx_id).yellow(),
+            error_msg
+        );
+
+        let dispatch_height = self.current_height()?;
+
+        self.store
+            .update_tx_to_dispatched(tx.tx_id, dispatch_height, fee_rate_at_dispatch)?;
+        self.monitor_anchor(tx)?;
+        self.tick_summary.record_dispatched();
+
+        match self.monitor.get_tx_status(&tx.tx_id) {
+            Ok(tx_status) if tx_status.is_confirmed() => {
+                // Mined before it was dispatched, so it goes straight to Confirmed.
+                if let Some(block_info) = &tx_status.block_info {
+                    self.save_first_confirmation(tx, block_info.height)?;
+                }
+
+                self.store
+                    .update_tx_state(tx.tx_id, TransactionState::Confirmed)?;
+                self.tick_summary.record_confirmed();
+
+                Ok(false)
+            }
+            // The monitor has not seen the block yet, the transaction is confirmed on a later tick.
+            Ok(_) | Err(MonitorError::TransactionNotFound(_)) => {
+                Ok(error_kind != BroadcastFailureKind::AlreadyInChain)
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    // Splits the transactions in batches, each one paid by a single CPFP.
+    // Also returns the transactions deferred because a CPFP paying only for them would exceed
+    // `max_cpfp_fee_sats_per_batch`, with the estimated fee of that CPFP, and the transactions that do not
+    // fit in the speedup chain, with the limit that was reached.
+    fn batch_txs_by_weight_limit(
+        &self,
+        txs: Vec<CoordinatedTransaction>,
+    ) -> Result<BatchedTxs, BitcoinCoordinatorError> {
+        let mut fee_capped_txs = Vec::new();
+        let mut batchable_txs = Vec::new();
+        let mut candidates = Vec::new();
+
+        // Same fee rate as the CPFP, without reporting a fee rate above the max as news.
+        let max_cpfp_fee = self.settings().max_cpfp_fee_sats_per_batch;
+        let network_fee_rate = match max_cpfp_fee {
+            Some(_) => self
+                .get_estimated_fee_rate()
+                .fee_rate
+                .min(self.settings().max_feerate_sat_vb),
+            None => 0,
+        };
+
+        for tx_data in txs {
+            let weight = tx_data.tx.weight().to_wu();
+
+            if weight > self.settings().max_tx_weight {
+                return Err(BitcoinCoordinatorError::TransactionTooHeavy(
+                    tx_data.tx_id.to_string(),
+                    weight,
+                    self.settings().max_tx_weight,
+                ));
+            }
+
+            if let Some(max_cpfp_fee) = max_cpfp_fee {
+                let solo_fee = self.estimate_batch_cpfp_fee(&[&tx_data], network_fee_rate)?;
+
+                if solo_fee > max_cpfp_fee {
+                    warn!(
+                        "{} Transaction({}) deferred, its CPFP fee exceeds the cap | EstimatedFee({}) | MaxCpfpFee({})",
+                        style("Coordinator").green(),
+                        style(tx_data.tx_id).yellow(),
+                        style(solo_fee).red(),
+                        style(max_cpfp_fee).red(),
+                    );
+                    fee_capped_txs.push((tx_data.tx_id, solo_fee));
+                    continue;
+                }
+            }
+
+            let kind =
+                SpeedupOutputKind::of_speedup_utxo(&tx_data.tx, required_speedup_data(&tx_data)?);
+
+            candidates.push(BatchCandidate {
+                weight,
+                ancestor_vsize: (tx_data.tx.vsize() + self.estimate_speedup_vsize(&[kind])) as u64,
+                exclusive: tx_data.dispatch_options.exclusive_speedup,
+            });
+            batchable_txs.push(tx_data);
+        }
+
+        let (unconfirmed_slots, ancestor_vsize) = self.get_available_ancestry()?;
+        let limits = BatchLimits {
+            max_weight: self.settings().max_tx_weight,
+            unconfirmed_slots,
+            ancestor_vsize,
+        };
+
+        // The batch is closed early when its CPFP would pay more than the cap with the transaction.
+        let plan = plan_batches(&candidates, &limits, |batch, index| match max_cpfp_fee {
+            Some(max_cpfp_fee) => {
+                let batch: Vec<&CoordinatedTransaction> = batch
+                    .iter()
+                    .chain(std::iter::once(&index))
+                    .map(|index| &batchable_txs[*index])
+                    .collect();
+                Ok(self.estimate_batch_cpfp_fee(&batch, network_fee_rate)? > max_cpfp_fee)
+            }
+            None => Ok::<_, BitcoinCoordinatorError>(false),
+        })?;
+
+        let deferred_txs: Vec<(Txid, DispatchDeferredReason)> = plan
+            .deferred
+            .iter()
+            .map(|(index, reason)| (batchable_txs[*index].tx_id, *reason))
+            .collect();
+
+        for (tx_id, reason) in deferred_txs.iter() {
+            warn!(
+                "{} Transaction({}) deferred, the speedup chain has no room for its CPFP | Reason({:?})",
+                style("Coordinator").green(),
+                style(tx_id).yellow(),
+                reason
+            );
+        }
+
+        let mut batchable_txs: Vec<Option<CoordinatedTransaction>> =
+            batchable_txs.into_iter().map(Some).collect();
+
+        let batches = plan
+            .batches
+            .iter()
+            .map(|batch| {
+                batch
+                    .iter()
+                    .filter_map(|index| batchable_txs[*index].take())
+                    .collect()
+            })
+            .collect();
+
+        Ok((batches, fee_capped_txs, deferred_txs))
+    }
+
+    // Fee of a CPFP paying only for the batch at the given fee rate, with the bump fee of its first speedup.
+    // The shortfall of the unconfirmed chain is not included, it is paid once by the next CPFP.
+    fn estimate_batch_cpfp_fee(
+        &self,
+        batch: &[&CoordinatedTransaction],
+        network_fee_rate: u64,
+    ) -> Result<u64, BitcoinCoordinatorError> {
+        let txs_speedup_data: Vec<(SpeedupData, usize)> = batch
+            .iter()
+            .map(|tx| Ok((required_speedup_data(tx)?.clone(), tx.tx.vsize())))
+            .collect::<Result<_, BitcoinCoordinatorError>>()?;
+
+        let anchor_kinds: Vec<SpeedupOutputKind> = batch
+            .iter()
+            .zip(txs_speedup_data.iter())
+            .map(|(tx, (speedup_data, _))| SpeedupOutputKind::of_speedup_utxo(&tx.tx, speedup_data))
+            .collect();
+
+        let bump_fee = batch
+            .iter()
+            .map(|tx| {
+                tx.dispatch_options
+                    .initial_bump_fee_percentage
+                    .unwrap_or(self.settings().base_fee_multiplier)
+            })
+            .fold(f64::MIN, f64::max);
+
+        self.calculate_speedup_fee(
+            &txs_speedup_data,
+            self.estimate_speedup_vsize(&anchor_kinds),
+            bump_fee,
+            network_fee_rate,
+            false,
+            0,
+            0,
+        )
+    }
+
+    fn notify_speedup_fee_cap_exceeded(
+        &self,
+        fee_capped_txs: Vec<(Txid, u64)>,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        let max_cpfp_fee = match self.settings().max_cpfp_fee_sats_per_batch {
+            Some(max_cpfp_fee) => max_cpfp_fee,
+            None => return Ok(()),
+        };
+
+        for (tx_id, estimated_fee) in fee_capped_txs {
+            let news = CoordinatorNews::SpeedupFeeCapExceeded(tx_id, estimated_fee, max_cpfp_fee);
+            self.update_news(news)?;
+        }
+
+        Ok(())
+    }
+
+    // Reports the transactions left waiting to be dispatched, one news for each limit that was reached.
+    fn notify_dispatch_deferred(
+        &self,
+        deferred_txs: Vec<(Txid, DispatchDeferredReason)>,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        for reason in [
+            DispatchDeferredReason::UnconfirmedChainLimit,
+            DispatchDeferredReason::AncestorSizeLimit,
+        ] {
+            let tx_ids: Vec<Txid> = deferred_txs
+                .iter()
+                .filter(|(_, deferred_reason)| *deferred_reason == reason)
+                .map(|(tx_id, _)| *tx_id)
+                .collect();
+
+            if !tx_ids.is_empty() {
+                self.update_news(CoordinatorNews::DispatchDeferred(tx_ids, reason))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Unconfirmed transactions and vbytes that the next CPFPs can still add to the mempool ancestors of the funding.
+    // The local bookkeeping only counts the speedups of this coordinator. When check_mempool_ancestry is enabled,
+    // the node is asked for the real ancestors of the funding, which also count parents created by others.
+    fn get_available_ancestry(&self) -> Result<(u32, u64), BitcoinCoordinatorError> {
+        let available_unconfirmed_txs = self.store.get_available_unconfirmed_txs()?;
+        let local_ancestry = (available_unconfirmed_txs, MAX_ANCESTOR_SIZE_VBYTES);
+
+        if !self.settings().check_mempool_ancestry {
+            return Ok(local_ancestry);
+        }
+
+        let funding = match self.store.get_funding()? {
+            Some(funding) => funding,
+            None => return Ok(local_ancestry),
+        };
+
+        let ancestry = match self.mempool_ancestry.get(&funding.txid, self.node.as_ref()) {
+            Ok(Some(ancestry)) => ancestry,
+            // The funding is confirmed, it has no unconfirmed ancestors.
+            Ok(None) => return Ok(local_ancestry),
+            Err(e) => {
+                warn!(
+                    "{} Mempool ancestry of Funding({}) unavailable, using the local bookkeeping: {}",
+                    style("Coordinator").green(),
+                    style(funding.txid).yellow(),
+                    e
+                );
+                return Ok(local_ancestry);
+            }
+        };
+
+        let node_unconfirmed_txs =
+            (MAX_LIMIT_UNCONFIRMED_PARENTS as u64).saturating_sub(ancestry.ancestor_count) as u32;
+
+        if node_unconfirmed_txs < available_unconfirmed_txs {
+            warn!(
+                "{} Funding({}) has {} unconfirmed ancestors in the mempool, more than the local bookkeeping",
+                style("Coordinator").green(),
+                style(funding.txid).yellow(),
+                style(ancestry.ancestor_count).red(),
+            );
+        }
+
+        Ok((
+            available_unconfirmed_txs.min(node_unconfirmed_txs),
+            MAX_ANCESTOR_SIZE_VBYTES.saturating_sub(ancestry.ancestor_size),
+        ))
+    }
+
+    fn process_failed_speedups(&self) -> Result<(), BitcoinCoordinatorError> {
+        let failed_speedups = self.store.get_speedups_for_retry(
+            self.settings().retry_attempts_sending_tx,
+            self.settings().retry_interval_seconds,
+        )?;
+
+        for speedup in failed_speedups {
+            if self.has_only_cancelled_parents(&speedup)? {
+                // All the transactions paid by this speedup were cancelled, so there is no need to resend it.
+                self.store.dequeue_speedup_for_retry(speedup.tx_id)?;
+                continue;
+            }
+
+            let can_speedup = self.store.can_speedup()?;
+
+            if !can_speedup {
+                return Ok(());
+            }
+
+            let funding = self
+                .store
+                .get_funding()?
+                .ok_or(BitcoinCoordinatorError::FundingDisappeared)?;
+
+            let replace_cpfp_txid = if speedup.is_rbf {
+                Some(speedup.tx_id)
+            } else {
+                None
+            };
+
+            let txs_data: Vec<(SpeedupData, Transaction, String)> = speedup
+                .load_parents(&self.store)?
+                .into_iter()
+                .map(|(speedup_data, tx, _)| (speedup_data, tx, speedup.context.clone()))
+                .collect();
+
+            if let Some(replace_cpfp_txid) = replace_cpfp_txid {
+                self.send_rbf_with_escalation(
+                    txs_data,
+                    funding,
+                    speedup.bump_fee_percentage_used,
+                    replace_cpfp_txid,
+                    Some(speedup.tx_id),
+                )?;
+                continue;
+            }
+
+            self.create_and_send_cpfp_tx(
+                txs_data,
+                funding,
+                speedup.bump_fee_percentage_used,
+                replace_cpfp_txid,
+                Some(speedup.tx_id),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn process_in_progress_speedup_txs(&self) -> Result<(), BitcoinCoordinatorError> {
+        // Speedup chains left behind when rotating the funding are followed until they are finalized.
+        let txs = self.store.get_all_pending_speedups()?;
+
+        for tx in txs
+            .into_iter()
+            .filter(|tx| tx.state != SpeedupState::Finalized)
+        {
+            // Get updated transaction status from monitor
+            let tx_status = self.monitor.get_tx_status(&tx.tx_id);
+
+            match tx_status {
+                Ok(tx_status) => {
+                    debug!(
+                        "{} {} Transaction({}) | Confirmations({})",
+                        style("Coordinator").green(),
+                        tx.get_tx_name(),
+                        style(tx.tx_id).blue(),
+                        style(tx_status.confirmations).blue(),
+                    );
+                    // Handle the case where the transaction is a CPFP (Child Pays For Parent) transaction.
+
+                    // First we acknowledge the transaction to clear any related news.
+                    let ack = AckMonitorNews::Transaction(tx_status.tx_id, tx.context.clone());
+                    self.monitor.ack_news(ack)?;
+
+                    if tx_status.is_finalized(
+                        self.settings()
+                            .monitor_settings
+                            .max_monitoring_confirmations,
+                    ) {
+                        // Once the transaction is finalized, we are not monitoring it anymore.
+                        self.store
+                            .update_speedup_state(tx_status.tx_id, SpeedupState::Finalized)?;
+                        continue;
+                    }
+
+                    if tx_status.is_confirmed() {
+                        if tx.state == SpeedupState::Orphaned {
+                            // The orphaned speedup was mined again, so its change is valid funding again.
+                            self.store.restore_orphaned_speedup(tx_status.tx_id)?;
+                            continue;
+                        }
+
+                        if tx.state != SpeedupState::Confirmed {
+                            if let Some(block_info) = &tx_status.block_info {
+                                self.record_speedup_outcome(&tx, block_info.height)?;
+                            }
+                        }
+
+                        // We want to keep the confirmation on the storage to calculate the maximum speedups
+                        self.store
+                            .update_speedup_state(tx_status.tx_id, SpeedupState::Confirmed)?;
+
+                        if tx.is_rbf && tx.state != SpeedupState::Confirmed {
+                            self.notify_speedup_chain_invalidated(&tx)?;
+                        }
+
+                        continue;
+                    }
+
+                    if tx_status.is_orphan() && tx.state != SpeedupState::Orphaned {
+                        self.notify_speedup_orphaned(&tx)?;
+                    }
+                }
+                Err(MonitorError::TransactionNotFound(_)) => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn process_in_progress_txs(&self) -> Result<(), BitcoinCoordinatorError> {
+        let txs = self.store.get_txs_in_progress()?;
+
+        for tx in txs {
+            if let Err(e) = self.process_in_progress_tx(&tx) {
+                self.record_tx_failure(tx.tx_id, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn process_in_progress_tx(
+        &self,
+        tx: &CoordinatedTransaction,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        // Get updated transaction status from monitor
+        let tx_status = self.monitor.get_tx_status(&tx.tx_id);
+
+        // A transaction confirmed up to its expiry height is followed as usual, even if it is seen later.
+        let current_height = self.current_height()?;
+        let is_confirmed =
+            matches!(&tx_status, Ok(status) if status.confirmations > 0 && !status.is_orphan());
+
+        if is_expired(tx, current_height) && !is_confirmed {
+            return self.expire_tx(tx);
+        }
+
+        // A cancelled transaction is not followed past its expiry height either.
+        if tx.state == TransactionState::Cancelled
+            && is_past_expiry(tx, current_height)
+            && !is_confirmed
+        {
+            return self.stop_following_cancelled_tx(tx);
+        }
+
+        match tx_status {
+            Ok(tx_status) => {
+                debug!(
+                    "{} Transaction({}) | Confirmations({})",
+                    style("Coordinator").green(),
+                    style(tx.tx_id).yellow(),
+                    style(tx_status.confirmations).blue(),
+                );
+
+                // A transaction can be finalized before it is seen as confirmed if the coordinator was stopped.
+                if tx.first_confirmation_block_height.is_none() && !tx_status.is_orphan() {
+                    if let Some(block_info) = &tx_status.block_info {
+                        self.save_first_confirmation(tx, block_info.height)?;
+                    }
+                }
+
+                self.report_confirmation_milestones(tx, &tx_status)?;
+
+                let finality_confirmations = tx.dispatch_options.finality_confirmations.unwrap_or(
+                    self.settings()
+                        .monitor_settings
+                        .max_monitoring_confirmations,
+                );
+
+                if tx_status.is_finalized(finality_confirmations) {
+                    // A low finality can be reached before the transaction was seen as confirmed.
+                    if matches!(
+                        tx.state,
+                        TransactionState::Dispatched
+                            | TransactionState::Cancelled
+                            | TransactionState::Expired
+                    ) {
+                        self.store
+                            .update_tx_state(tx_status.tx_id, TransactionState::Confirmed)?;
+                        self.tick_summary.record_confirmed();
+                    }
+
+                    self.report_package_fees(tx)?;
+
+                    // Once the transaction is finalized, we are not monitoring it anymore.
+                    match &tx_status.block_info {
+                        Some(block_info) => {
+                            self.store.finalize_tx(
+                                tx_status.tx_id,
+                                block_info.height,
+                                block_info.hash,
+                            )?;
+                        }
+                        None => self
+                            .store
+                            .update_tx_state(tx_status.tx_id, TransactionState::Finalized)?,
+                    }
+
+                    self.tick_summary.record_finalized();
+
+                    // The monitor would follow it up to max_monitoring_confirmations.
+                    if tx.dispatch_options.finality_confirmations.is_some() {
+                        self.monitor.cancel(TypesToMonitor::Transactions(
+                            vec![tx.tx_id],
+                            tx.context.clone(),
+                            None,
+                        ))?;
+                    }
+
+                    return Ok(());
+                }
+
+                if tx_status.is_confirmed() {
+                    self.store
+                        .update_tx_state(tx_status.tx_id, TransactionState::Confirmed)?;
+
+                    if tx.state != TransactionState::Confirmed {
+                        self.tick_summary.record_confirmed();
+                    }
+                }
+
+                // The block of a confirmed transaction was orphaned, it has to be mined again.
+                // Speedups paying the transaction are revalidated by process_in_progress_speedup_txs.
+                if tx_status.is_orphan() && tx.state == TransactionState::Confirmed {
+                    self.handle_tx_reorg(tx, &tx_status)?;
+                }
+            }
+            Err(MonitorError::TransactionNotFound(_)) => {
+                // An expired transaction is only followed in case it is confirmed, it is not sent again.
+                if tx.state == TransactionState::Expired {
+                    return Ok(());
+                }
+
+                // A cancelled transaction is only followed while it can be confirmed, it is not sent again.
+                if tx.state == TransactionState::Cancelled {
+                    if self.should_check_conflict(tx)? {
+                        if self.is_tx_dropped(tx)? {
+                            return self.drop_cancelled_tx(tx);
+                        }
+
+                        self.check_tx_conflict(tx)?;
+                    }
+
+                    return Ok(());
+                }
+
+                // In case a transaction is not found, we just wait.
+                // We are going to speed up the CPFP.
+                // If it is missing for too long, one of its inputs could have been double spent.
+                if self.should_check_conflict(tx)? && self.check_tx_conflict(tx)? {
+                    return Ok(());
+                }
+
+                // Transactions without speedup could have been dropped from the mempool, they are sent again.
+                if !self.should_speedup(tx) && !self.is_standby() {
+                    self.rebroadcast_missing_tx(tx)?;
+                }
+            }
+            Err(e) => return Err(e.into()),
+        }
+
+        Ok(())
+    }
+
+    // Reports a ConfirmationMilestone news for each milestone reached since the last one reported. When a reorg drops
+    // the confirmations below a reported milestone it is forgotten, and reported again once it is reached again.
+    fn report_confirmation_milestones(
+        &self,
+        tx: &CoordinatedTransaction,
+        tx_status: &TransactionStatus,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        let settings = self.settings();
+        let milestones = tx
+            .dispatch_options
+            .confirmation_milestones
+            .as_ref()
+            .unwrap_or(&settings.confirmation_milestones);
+
+        let confirmations = if tx_status.is_orphan() {
+            0
+        } else {
+            tx_status.confirmations
+        };
+
+        let mut last = tx
+            .last_confirmation_milestone
+            .filter(|last| *last <= confirmations);
+        if last != tx.last_confirmation_milestone {
+            last = milestones
+                .iter()
+                .copied()
+                .filter(|milestone| *milestone <= confirmations)
+                .max();
+        }
+
+        // The milestones given at dispatch are not sorted
+        let mut reached: Vec<u32> = milestones
+            .iter()
+            .copied()
+            .filter(|milestone| last.is_none_or(|last| *milestone > last))
+            .filter(|milestone| *milestone <= confirmations)
+            .collect();
+        reached.sort_unstable();
+        reached.dedup();
+
+        for milestone in reached.iter() {
+            info!(
+                "{} Transaction({}) | Reached {} confirmations",
+                style("Coordinator").green(),
+                style(tx.tx_id).yellow(),
+                style(milestone).blue(),
+            );
+
+            self.update_news(CoordinatorNews::ConfirmationMilestone(
+                tx.tx_id,
+                *milestone,
+                tx.context.clone(),
+            ))?;
+        }
+
+        let last = reached.last().copied().or(last);
+        if last != tx.last_confirmation_milestone {
+            self.store
+                .save_last_confirmation_milestone(tx.tx_id, last)?;
+        }
+
+        Ok(())
+    }
+
+    // Saves the height a transaction was first confirmed at with the speedups spent on it, for the confirmation stats.
+    fn save_first_confirmation(
+        &self,
+        tx: &CoordinatedTransaction,
+        block_height: BlockHeight,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        let funding_group = tx.dispatch_options.funding_group.as_deref();
+        let speedups = self
+            .store
+            .with_funding_group(funding_group, || self.store.get_speedups_for_tx(&tx.tx_id))?;
+        let (speedup_count, speedup_fees) = speedup_costs(&speedups);
+
+        self.store
+            .save_first_confirmation(tx.tx_id, block_height, speedup_count, speedup_fees)?;
+
+        // The reference of the fee rate paid, the estimate of the tick the confirmation is seen at.
+        self.store
+            .save_fee_rate_at_confirmation(tx.tx_id, self.get_estimated_fee_rate().fee_rate)?;
+
+        Ok(())
+    }
+
+    // Saves the fee rate paid by a transaction being finalized and its confirmed speedups, and reports
+    // FeeOverpayment when it is more than fee_overpayment_ratio times the fee rate estimated at its confirmation.
+    fn report_package_fees(
+        &self,
+        tx: &CoordinatedTransaction,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        // The fee rate at confirmation can have been saved earlier in this tick.
+        let tx = self.store.get_tx(&tx.tx_id)?;
+        let funding_group = tx.dispatch_options.funding_group.as_deref();
+
+        // Speedups are processed after the transactions, a speedup mined since the last tick is still dispatched.
+        let speedups: Vec<SpeedupSummary> = self
+            .store
+            .with_funding_group(funding_group, || self.store.get_speedups_for_tx(&tx.tx_id))?
+            .into_iter()
+            .map(|mut speedup| {
+                if speedup.state == SpeedupState::Dispatched
+                    && self
+                        .monitor
+                        .get_tx_status(&speedup.tx_id)
+                        .is_ok_and(|status| status.is_confirmed())
+                {
+                    speedup.state = SpeedupState::Confirmed;
+                }
+                speedup
+            })
+            .collect();
+
+        let parent_fee = match self.prevouts.tx_fee(&tx.tx, self.client.as_ref()) {
+            Ok(fee) => fee,
+            Err(e) => {
+                warn!(
+                    "{} Could not fetch the amounts spent by Transaction({}) | Error({})",
+                    style("Coordinator").red(),
+                    style(tx.tx_id).yellow(),
+                    style(e).red(),
+                );
+                None
+            }
+        };
+
+        let report = package_fee_report(&tx, parent_fee, &speedups, tx.fee_rate_at_confirmation);
+
+        debug!(
+            "{} Transaction({}) | PackageFeeRate({:.2}) | ReferenceFeeRate({:?})",
+            style("Coordinator").green(),
+            style(tx.tx_id).yellow(),
+            style(report.effective_fee_rate).blue(),
+            style(report.reference_fee_rate).blue(),
+        );
+
+        if let (Some(ratio), Some(reference_fee_rate)) =
+            (report.overpayment_ratio(), report.reference_fee_rate)
+        {
+            if ratio > self.settings().fee_overpayment_ratio {
+                self.update_news(CoordinatorNews::FeeOverpayment(
+                    tx.tx_id,
+                    report.effective_fee_rate,
+                    reference_fee_rate,
+                ))?;
+            }
+        }
+
+        self.store.save_fee_report(tx.tx_id, report)?;
+
+        Ok(())
+    }
+
+    // Transactions dispatched with allow_rbf_of_parent that are not confirmed after min_blocks_before_resend_speedup
+    // blocks are replaced with a higher fee taken from their change output, instead of being paid by a CPFP.
+    fn process_parent_replacements(&self) -> Result<(), BitcoinCoordinatorError> {
+        let signer = match &self.parent_tx_signer {
+            Some(signer) if !self.is_standby() => signer.clone(),
+            _ => return Ok(()),
+        };
+
+        for tx_id in self
+            .store
+            .get_tx_ids_by_state(&TransactionState::Dispatched)?
+        {
+            let tx = self.store.get_tx(&tx_id)?;
+
+            if !tx.dispatch_options.allow_rbf_of_parent {
+                continue;
+            }
+
+            if let Err(e) = self.replace_parent_tx(&tx, signer.as_ref()) {
+                self.record_tx_failure(tx.tx_id, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn replace_parent_tx(
+        &self,
+        tx: &CoordinatedTransaction,
+        signer: &dyn ParentTxSigner,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        let current_height = self.current_height()?;
+
+        let broadcast_block_height = match tx.broadcast_block_height {
+            Some(height) => height,
+            None => return Ok(()),
+        };
+
+        if current_height.saturating_sub(broadcast_block_height)
+            < self.settings().min_blocks_before_resend_speedup
+        {
+            return Ok(());
+        }
+
+        // Only transactions not mined yet are replaced.
+        match self.monitor.get_tx_status(&tx.tx_id) {
+            Ok(_) => return Ok(()),
+            Err(MonitorError::TransactionNotFound(_)) => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        if tx.replaced_txids.len() as u32 >= self.settings().max_rbf_attempts {
+            debug!(
+                "{} Transaction({}) was replaced max_rbf_attempts times, it is not replaced again",
+                style("Coordinator").green(),
+                style(tx.tx_id).yellow(),
+            );
+            return Ok(());
+        }
+
+        let prevouts = signer.get_prevouts(&tx.tx)?;
+        let input_amount: u64 = prevouts.iter().map(|output| output.value.to_sat()).sum();
+        let output_amount: u64 = tx
+            .tx
+            .output
+            .iter()
+            .map(|output| output.value.to_sat())
+            .sum();
+
+        let fee = input_amount.checked_sub(output_amount).ok_or_else(|| {
+            BitcoinCoordinatorError::InvalidTransaction(
+                tx.tx_id,
+                format!("outputs ({output_amount} sats) exceed the prevouts ({input_amount} sats)"),
+            )
+        })?;
+
+        // Checked on dispatch, a transaction saved by an older version may still have it out of range.
+        let change_vout = parent_change_vout(&tx.tx, &tx.dispatch_options).ok_or_else(|| {
+            BitcoinCoordinatorError::InvalidTransaction(
+                tx.tx_id,
+                "parent_change_vout is not an output of the transaction".to_string(),
+            )
+        })?;
+        let change = &tx.tx.output[change_vout];
+
+        let max_feerate_sat_vb = tx
+            .dispatch_options
+            .max_feerate_sat_vb
+            .unwrap_or(self.settings().max_feerate_sat_vb);
+        let fee_rate = self.get_network_fee_rate(max_feerate_sat_vb)?;
+
+        let replacement = match compute_parent_replacement(
+            fee,
+            tx.tx.vsize() as u64,
+            change.value.to_sat(),
+            change.script_pubkey.minimal_non_dust().to_sat(),
+            fee_rate,
+            self.settings().rbf_fee_percentage,
+            max_feerate_sat_vb,
+        ) {
+            Some(replacement) => replacement,
+            None => {
+                warn!(
+                    "{} Transaction({}) can not be replaced, its change output can not pay the fee at {} sat/vB",
+                    style("Coordinator").green(),
+                    style(tx.tx_id).yellow(),
+                    style(fee_rate).red(),
+                );
+                return Ok(());
+            }
+        };
+
+        // The signatures of the original do not commit to the reduced change, every input is signed again.
+        let mut unsigned = tx.tx.clone();
+        unsigned.output[change_vout].value = Amount::from_sat(replacement.change_amount);
+        for input in unsigned.input.iter_mut() {
+            input.script_sig = ScriptBuf::new();
+            input.witness = Witness::new();
+        }
+
+        let signed = signer.sign_replacement(unsigned, &prevouts)?;
+
+        // Saved before the broadcast, so a replacement broadcast by a process that stops before saving it is recovered.
+        let intent = ParentReplacementIntent {
+            replaced_txid: tx.tx_id,
+            replacement: signed,
+            block_height: current_height,
+            fee_rate,
+            extra_fee: replacement.extra_fee,
+        };
+        self.store.save_parent_replacement_intent(intent.clone())?;
+
+        if let Err(e) = self.send_tx(&intent.replacement) {
+            // Unless the node already has it, the replacement was not broadcast and the original is kept.
+            let error_kind = BroadcastFailureKind::from_error_message(&e.to_string());
+
+            if error_kind.action() != BroadcastFailureAction::Dispatched {
+                self.store.remove_parent_replacement_intent(&tx.tx_id)?;
+                return Err(e.into());
+            }
+        }
+
+        self.save_parent_replacement(intent)
+    }
+
+    // Puts a broadcast replacement in the place of the original, in the monitor and in the store.
+    fn save_parent_replacement(
+        &self,
+        intent: ParentReplacementIntent,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        let replaced_txid = intent.replaced_txid;
+        let replacement_txid = intent.replacement.compute_txid();
+        let context = self.store.get_tx(&replaced_txid)?.context;
+
+        // The replacement is monitored with the context of the original, which is not monitored anymore.
+        self.monitor.monitor(TypesToMonitor::Transactions(
+            vec![replacement_txid],
+            context.clone(),
+            None,
+        ))?;
+        self.monitor
+            .ack_news(AckMonitorNews::Transaction(replaced_txid, context.clone()))?;
+        self.monitor.cancel(TypesToMonitor::Transactions(
+            vec![replaced_txid],
+            context,
+            None,
+        ))?;
+
+        self.store.replace_tx(
+            replaced_txid,
+            intent.replacement,
+            intent.block_height,
+            intent.fee_rate,
+            intent.extra_fee,
+        )?;
+
+        info!(
+            "{} Transaction({}) replaced by Transaction({}) | ExtraFee({})",
+            style("Coordinator").green(),
+            style(replaced_txid).yellow(),
+            style(replacement_txid).yellow(),
+            style(intent.extra_fee).blue(),
+        );
+
+        self.update_news(CoordinatorNews::ParentReplaced(
+            replaced_txid,
+            replacement_txid,
+            intent.extra_fee,
+        ))
+    }
+
+    // Resolves the replacements left between their broadcast and their save, asking the node whether they were
+    // broadcast. A replacement the node has is saved as if it had just been sent, the others are discarded.
+    fn reconcile_parent_replacement_intents(&self) -> Result<(), BitcoinCoordinatorError> {
+        for intent in self.store.get_parent_replacement_intents()? {
+            let replaced_txid = intent.replaced_txid;
+
+            // Only a dispatched original can still be replaced.
+            match self.store.get_tx(&replaced_txid) {
+                Ok(tx) if tx.state == TransactionState::Dispatched => {}
+                Ok(_) | Err(BitcoinCoordinatorStoreError::TransactionNotFound(_)) => {
+                    self.store
+                        .remove_parent_replacement_intent(&replaced_txid)?;
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            }
+
+            let replacement_txid = intent.replacement.compute_txid();
+
+            match self.client.get_transaction(&replacement_txid) {
+                Ok(Some(_)) => {
+                    info!(
+                        "{} Recovered Transaction({}) replacing Transaction({}) broadcast before it was saved",
+                        style("Coordinator").green(),
+                        style(replacement_txid).yellow(),
+                        style(replaced_txid).yellow(),
+                    );
+
+                    self.save_parent_replacement(intent)?;
+                }
+                Ok(None) => self
+                    .store
+                    .remove_parent_replacement_intent(&replaced_txid)?,
+                Err(e) => {
+                    warn!(
+                        "{} Could not check whether Transaction({}) was broadcast, checking on the next tick: {}",
+                        style("Coordinator").green(),
+                        style(replacement_txid).yellow(),
+                        e
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // A transaction that can not be processed is skipped, so it does not stop the tick for the other transactions.
+    // It is processed again on the next tick.
+    fn record_tx_failure(&self, tx_id: Txid, error: BitcoinCoordinatorError) {
+        error!(
+            "{} Transaction({}) skipped in this tick: {}",
+            style("Coordinator").green(),
+            style(tx_id).yellow(),
+            error
+        );
+
+        self.tick_failures.set(self.tick_failures.get() + 1);
+    }
+
+    // An error of a state that changed or is invalid skips the rest of the step, the other steps of the tick run
+    // and the work is tried again on the next tick. Other errors stop the tick.
+    fn skip_tick_work(
+        &self,
+        error: BitcoinCoordinatorError,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        let Some(reason) = error.tick_skip_reason() else {
+            return Err(error);
+        };
+
+        error!(
+            "{} Tick work skipped: {}",
+            style("Coordinator").green(),
+            error
+        );
+
+        self.update_news(CoordinatorNews::TickWorkSkipped(reason, error.to_string()))
+    }
+
+    fn report_tick_failures(&self) -> Result<(), BitcoinCoordinatorError> {
+        let failed_count = self.tick_failures.get();
+
+        if failed_count == 0 {
+            return Ok(());
+        }
+
+        warn!(
+            "{} Tick completed with {} failed transactions",
+            style("Coordinator").green(),
+            style(failed_count).red(),
+        );
+
+        self.update_news(CoordinatorNews::TickPartialFailure(failed_count))
+    }
+
+    fn should_check_conflict(
+        &self,
+        tx: &CoordinatedTransaction,
+    ) -> Result<bool, BitcoinCoordinatorError> {
+        if !matches!(
+            tx.state,
+            TransactionState::Dispatched | TransactionState::Cancelled
+        ) {
+            return Ok(false);
+        }
+
+        let broadcast_block_height = match tx.broadcast_block_height {
+            Some(height) => height,
+            None => return Ok(false),
+        };
+
+        let current_block_height = self.current_height()?;
+
+        Ok(current_block_height.saturating_sub(broadcast_block_height)
+            >= self.settings().conflict_detection_blocks)
+    }
+
+    // Returns true when the transaction was double spent and marked as Failed, or dropped when it was cancelled.
+    // The spenders of the inputs spent in the chain are looked for in the blocks since the broadcast. Each block
+    // is read once per transaction: the scan goes on from the block it stopped at on the previous ticks, and
+    // stops when the blocks of the tick budget run out.
+    fn check_tx_conflict(
+        &self,
+        tx: &CoordinatedTransaction,
+    ) -> Result<bool, BitcoinCoordinatorError> {
+        if self.tick_budget.remaining_conflict_scan_blocks() == 0 {
+            return Ok(false);
+        }
+
+        // Mempool spends are ignored, the outpoint is still unspent in the chain.
+        let mut spent_outpoints = HashSet::new();
+        for input in tx.tx.input.iter() {
+            if !self.node.is_unspent(&input.previous_output, false)? {
+                spent_outpoints.insert(input.previous_output);
+            }
+        }
+
+        if spent_outpoints.is_empty() {
+            return Ok(false);
+        }
+
+        let best_block_height = self.node.get_block_count()?;
+        let from_height = self.next_conflict_scan_height(tx, best_block_height)?;
+        let mut progress = None;
+
+        for height in from_height..=best_block_height {
+            if self.tick_budget.remaining_conflict_scan_blocks() == 0 {
+                break;
+            }
+
+            self.tick_budget.record_conflict_scan_block();
+
+            let block_hash = self.node.get_block_hash(height)?;
+            let block_txs = self.node.get_block_transactions(&block_hash)?;
+
+            let conflicting_txid = find_conflicting_tx(&tx.tx, |outpoint| {
+                if !spent_outpoints.contains(outpoint) {
+                    return Ok(None);
+                }
+
+                Ok(block_txs
+                    .iter()
+                    .find(|block_tx| {
+                        block_tx
+                            .input
+                            .iter()
+                            .any(|input| input.previous_output == *outpoint)
+                    })
+                    .map(|block_tx| block_tx.compute_txid()))
+            })?;
+
+            if let Some(conflicting_txid) = conflicting_txid {
+                self.notify_tx_conflicted(tx, conflicting_txid)?;
+                return Ok(true);
+            }
+
+            progress = Some(ConflictScanProgress {
+                block_height: height,
+                block_hash,
+            });
+        }
+
+        if let Some(progress) = progress {
+            self.store.save_conflict_scan_progress(tx.tx_id, progress)?;
+        }
+
+        Ok(false)
+    }
+
+    // Height of the next block to read looking for a double spend of the transaction: the block after the last one
+    // read, or the broadcast block when none was read yet or the last one read is not in the best chain anymore.
+    fn next_conflict_scan_height(
+        &self,
+        tx: &CoordinatedTransaction,
+        best_block_height: BlockHeight,
+    ) -> Result<BlockHeight, BitcoinCoordinatorError> {
+        let broadcast_block_height = tx.broadcast_block_height.unwrap_or_default();
+
+        let progress = match tx.conflict_scan_progress {
+            Some(progress)
+                if progress.block_height >= broadcast_block_height
+                    && progress.block_height <= best_block_height =>
+            {
+                progress
+            }
+            _ => return Ok(broadcast_block_height),
+        };
+
+        if self.node.get_block_hash(progress.block_height)? != progress.block_hash {
+            return Ok(broadcast_block_height);
+        }
+
+        Ok(progress.block_height + 1)
+    }
+
+    fn handle_tx_reorg(
+        &self,
+        tx: &CoordinatedTransaction,
+        tx_status: &TransactionStatus,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        let orphan_block_hash = match &tx_status.block_info {
+            Some(block_info) => block_info.hash,
+            None => return Ok(()),
+        };
+
+        let current_block_hash = match self.current_block_hash()? {
+            Some(block_hash) => block_hash,
+            None => return Ok(()),
+        };
+
+        warn!(
+            "{} Transaction({}) reorged out of Block({})",
+            style("Coordinator").green(),
+            style(tx.tx_id).yellow(),
+            style(orphan_block_hash).red(),
+        );
+
+        let tx = self
+            .store
+            .reorg_tx(tx.tx_id, orphan_block_hash, current_block_hash)?;
+        self.observer.on_news_emitted("TransactionReorged");
+        self.tick_summary.record_news();
+        self.store.journal().record(JournalEvent::NewsEmitted(
+            CoordinatorNews::TransactionReorged(tx.tx_id, orphan_block_hash, tx.context.clone()),
+        ));
+
+        // The transaction is usually back in the mempool, otherwise it is sent again.
+        // If sending fails, the rebroadcast policy sends it again once it is missing for long enough.
+        if self.is_standby() {
+            return Ok(());
+        }
+
+        if let Err(e) = self.send_tx(&tx.tx) {
+            let error_msg = e.to_string();
+
+            if BroadcastFailureKind::from_error_message(&error_msg).action()
+                != BroadcastFailureAction::Dispatched
+            {
+                warn!(
+                    "{} Error sending reorged Transaction({}): {}",
+                    style("Coordinator").green(),
+                    style(tx.tx_id).yellow(),
+                    error_msg
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    fn rebroadcast_missing_tx(
+        &self,
+        tx: &CoordinatedTransaction,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        let current_height = self.current_height()?;
+
+        let news = rebroadcast_missing_tx(
+            self.client.as_ref(),
+            &self.store,
+            tx,
+            current_height,
+            &self.settings(),
+        )?;
+
+        if let Some(news) = news {
+            self.update_news(news)?;
+        }
+
+        Ok(())
+    }
+
+    // The transaction will never be confirmed, so it is marked as failed and it is not speed up anymore.
+    fn notify_tx_conflicted(
+        &self,
+        tx: &CoordinatedTransaction,
+        conflicting_txid: Txid,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        warn!(
+            "{} Transaction({}) double spent by a confirmed transaction | ConflictingTransaction({})",
+            style("Coordinator").green(),
+            style(tx.tx_id).yellow(),
+            style(conflicting_txid).red(),
+        );
+
+        // The cancellation was already reported, the transaction is only dropped.
+        if tx.state == TransactionState::Cancelled {
+            return self.drop_cancelled_tx(tx);
+        }
+
+        self.store
+            .update_tx_state(tx.tx_id, TransactionState::Failed)?;
+
+        self.monitor.cancel(TypesToMonitor::Transactions(
+            vec![tx.tx_id],
+            tx.context.clone(),
+            None,
+        ))?;
+
+        let news =
+            CoordinatorNews::TransactionConflicted(tx.tx_id, conflicting_txid, tx.context.clone());
+        self.update_news(news)?;
+
+        Ok(())
+    }
+
+    // Returns true when an input of the transaction is unspent, also in the mempool, so the transaction was
+    // dropped from the mempool without being mined.
+    fn is_tx_dropped(&self, tx: &CoordinatedTransaction) -> Result<bool, BitcoinCoordinatorError> {
+        for input in tx.tx.input.iter() {
+            if self.node.is_unspent(&input.previous_output, true)? {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    // The cancelled transaction was dropped or double spent, it will never be confirmed. The speedups paying for it
+    // can not be mined either, so they are invalidated and their unconfirmed slots are free again.
+    fn drop_cancelled_tx(
+        &self,
+        tx: &CoordinatedTransaction,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        self.stop_following_cancelled_tx(tx)?;
+
+        self.store
+            .with_funding_group(tx.dispatch_options.funding_group.as_deref(), || {
+                self.invalidate_speedups_paying(tx)
+            })
+    }
+
+    // Stops monitoring a cancelled transaction that was broadcast and removes it from the transactions in progress.
+    fn stop_following_cancelled_tx(
+        &self,
+        tx: &CoordinatedTransaction,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        self.store.untrack_tx(tx.tx_id)?;
+
+        self.monitor.cancel(TypesToMonitor::Transactions(
+            vec![tx.tx_id],
+            tx.context.clone(),
+            None,
+        ))?;
+
+        info!(
+            "{} Cancelled Transaction({}) can not be confirmed anymore, it is not followed",
+            style("Coordinator").green(),
+            style(tx.tx_id).yellow(),
+        );
+
+        Ok(())
+    }
+
+    // The speedups paying for a dropped transaction, and the ones funded from their change, will never be confirmed.
+    // The other transactions they paid for wait for a new CPFP.
+    fn invalidate_speedups_paying(
+        &self,
+        tx: &CoordinatedTransaction,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        let invalidated = self.store.invalidate_speedups_paying(tx.tx_id)?;
+
+        if invalidated.is_empty() {
+            return Ok(());
+        }
+
+        let unpaid_txids = self.defer_invalidated_speedup_txs(&invalidated)?;
+
+        warn!(
+            "{} Cancelled Transaction({}) dropped | InvalidatedSpeedups({:?}) | UnpaidTransactions({})",
+            style("Coordinator").green(),
+            style(tx.tx_id).yellow(),
+            invalidated,
+            style(unpaid_txids.len()).red(),
+        );
+
+        let news = CoordinatorNews::SpeedupChainInvalidated(invalidated);
+        self.update_news(news)?;
+
+        Ok(())
+    }
+
+    // Returns true when the transaction is already waiting to be dispatched or confirmed.
+    fn is_already_dispatched(&self, tx_id: Txid) -> Result<bool, BitcoinCoordinatorError> {
+        match self.store.get_tx(&tx_id) {
+            Ok(tx) => Ok(tx.state == TransactionState::ToDispatch
+                || tx.state == TransactionState::Dispatched),
+            Err(BitcoinCoordinatorStoreError::TransactionNotFound(_)) => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn should_speedup(&self, tx: &CoordinatedTransaction) -> bool {
+        // If the transaction has a CPFP UTXO, we have to speed it up.
+        // Transactions replaced with a higher fee (RBF) are never paid by a CPFP.
+        tx.speedup_data.is_some() && !tx.dispatch_options.allow_rbf_of_parent
+    }
+
+    fn should_dispatch_tx(
+        &self,
+        pending_tx: &CoordinatedTransaction,
+    ) -> Result<bool, BitcoinCoordinatorError> {
+        if !self.dependencies_confirmed(pending_tx)? {
+            return Ok(false);
+        }
+
+        if !self.locktime_matured(pending_tx)? {
+            return Ok(false);
+        }
+
+        let Some(target_block_height) = pending_tx.target_block_height else {
+            return Ok(true);
+        };
+
+        let was_already_broadcasted = pending_tx.broadcast_block_height.is_some();
+
+        if was_already_broadcasted {
+            warn!(
+                "Transaction({}) already broadcasted. This could be a border case or a bug.",
+                pending_tx.tx_id
+            );
+
+            // THIS COULD BE A BORDER CASE OR A BUG.
+            // This code path should not be reached because once a transaction is broadcast,
+            // it should be marked as BroadcastPendingConfirmation.
+            return Ok(false);
+        }
+
+        let current_block_height = self.current_height()?;
+
+        Ok(current_block_height >= target_block_height)
+    }
+
+    // Whether the locktime of the transaction lets the node accept it, so it is never sent early and counted as a
+    // failed attempt. The height a relative lock matures at is only known once the parent is confirmed, it is saved
+    // for the pending overview then, and not while it still moves with every block.
+    fn locktime_matured(
+        &self,
+        pending_tx: &CoordinatedTransaction,
+    ) -> Result<bool, BitcoinCoordinatorError> {
+        let (locktime_height, parents_confirmed) = self.locktime_height(&pending_tx.tx)?;
+
+        if parents_confirmed && locktime_height != pending_tx.locktime_block_height {
+            self.store
+                .save_locktime_height(pending_tx.tx_id, locktime_height)?;
+        }
+
+        let Some(locktime_height) = locktime_height else {
+            return Ok(true);
+        };
+
+        if self.current_height()? < locktime_height {
+            debug!(
+                "{} Transaction({}) waiting for locktime (height {})",
+                style("Coordinator").green(),
+                style(pending_tx.tx_id).yellow(),
+                style(locktime_height).yellow(),
+            );
+
+            return Ok(false);
+        }
+
+        Ok(true)
+    }
+
+    // Block height the transaction can be broadcast at, the highest of its absolute locktime and its relative
+    // locks. A relative lock counts from the confirmation of the parent, known when the monitor follows the parent
+    // or the coordinator dispatched it. While the parent is not confirmed the transaction waits for it, the lock
+    // would mature at the earliest `blocks` above the current height, and false is returned along with the height.
+    fn locktime_height(
+        &self,
+        tx: &Transaction,
+    ) -> Result<(Option<BlockHeight>, bool), BitcoinCoordinatorError> {
+        let mut locktime_height = absolute_lock_height(tx);
+        let mut parents_confirmed = true;
+
+        for (parent_txid, blocks) in relative_locks(tx) {
+            let lock_height = match self.monitor.get_tx_status(&parent_txid) {
+                Ok(status) => match status.block_info {
+                    Some(block_info) if status.confirmations > 0 && !status.is_orphan() => {
+                        relative_lock_height(block_info.height, blocks)
+                    }
+                    _ => {
+                        parents_confirmed = false;
+                        self.current_height()? + blocks
+                    }
+                },
+                Err(MonitorError::TransactionNotFound(_)) => {
+                    if !self.is_already_dispatched(parent_txid)? {
+                        continue;
+                    }
+
+                    parents_confirmed = false;
+                    self.current_height()? + blocks
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            locktime_height = locktime_height.max(Some(lock_height));
+        }
+
+        Ok((locktime_height, parents_confirmed))
+    }
+
+    // Leaves out the transactions that wait for high fees to come down and returns the ones to dispatch now.
+    // A transaction that is not urgent waits while the network fee rate is above the pause threshold of its
+    // urgency, and is dispatched anyway once it waited max_pause_blocks blocks since it was first paused.
+    fn pause_on_high_fees(
+        &self,
         txs: Vec<CoordinatedTransaction>,
+    ) -> Result<Vec<CoordinatedTransaction>, BitcoinCoordinatorError> {
+        let (pause_normal_above, pause_low_above, max_pause_blocks, max_feerate_sat_vb) = {
+            let settings = self.settings();
+            (
+                settings.pause_normal_priority_above_sat_vb,
+                settings.pause_low_priority_above_sat_vb,
+                settings.max_pause_blocks,
+                settings.max_feerate_sat_vb,
+            )
+        };
+
+        let threshold = |tx: &CoordinatedTransaction| match tx.dispatch_options.urgency {
+            DispatchUrgency::Urgent => None,
+            DispatchUrgency::Normal => pause_normal_above,
+            DispatchUrgency::Low => pause_low_above,
+        };
+
+        if txs.iter().all(|tx| threshold(tx).is_none()) {
+            return Ok(txs);
+        }
+
+        let fee_rate = self.get_network_fee_rate(max_feerate_sat_vb)?;
+        let current_height = self.current_height()?;
+        let mut txs_to_dispatch = Vec::new();
+        let mut paused_count: u32 = 0;
+
+        for tx in txs {
+            if threshold(&tx).is_none_or(|threshold| fee_rate <= threshold) {
+                txs_to_dispatch.push(tx);
+                continue;
+            }
+
+            let paused_since = match tx.paused_since_block_height {
+                Some(paused_since) => paused_since,
+                None => {
+                    self.store.save_paused_since(tx.tx_id, current_height)?;
+                    current_height
+                }
+            };
+
+            let max_pause_blocks = tx
+                .dispatch_options
+                .max_pause_blocks
+                .unwrap_or(max_pause_blocks);
+
+            if current_height.saturating_sub(paused_since) >= max_pause_blocks {
+                info!(
+                    "{} Dispatch paused for {} blocks, dispatched at a high fee rate | TxId({}) | FeeRate({})",
+                    style("Coordinator").green(),
+                    style(max_pause_blocks).yellow(),
+                    style(tx.tx_id).yellow(),
+                    style(fee_rate).red(),
+                );
+                txs_to_dispatch.push(tx);
+            } else {
+                paused_count += 1;
+            }
+        }
+
+        if paused_count > 0 {
+            debug!(
+                "{} Dispatches paused by high fees | Paused({}) | FeeRate({})",
+                style("Coordinator").green(),
+                style(paused_count).yellow(),
+                style(fee_rate).red(),
+            );
+
+            // Reported once per block, the news is only updated when the block changes.
+            self.update_news(CoordinatorNews::DispatchPausedHighFees(
+                paused_count,
+                fee_rate,
+            ))?;
+        }
+
+        Ok(txs_to_dispatch)
+    }
+
+    // Whether every dependency of the transaction is confirmed. A dependency that failed, or that was cancelled
+    // before it was broadcast, will never be confirmed, so the transaction is marked as failed too.
+    fn dependencies_confirmed(
+        &self,
+        tx: &CoordinatedTransaction,
+    ) -> Result<bool, BitcoinCoordinatorError> {
+        for dependency in tx.dispatch_options.depends_on.iter() {
+            // A dependency replaced with a higher fee (RBF) is confirmed through its last replacement.
+            let dependency_txid = self
+                .store
+                .get_replacement(dependency)?
+                .unwrap_or(*dependency);
+
+            let dependency_tx = match self.store.get_tx(&dependency_txid) {
+                Ok(dependency_tx) => dependency_tx,
+                Err(BitcoinCoordinatorStoreError::TransactionNotFound(_)) => {
+                    // The dependency was removed from the store, the monitor tells if it was confirmed.
+                    match self.monitor.get_tx_status(&dependency_txid) {
+                        Ok(status) if status.confirmations > 0 && !status.is_orphan() => continue,
+                        Ok(_) => return Ok(false),
+                        Err(MonitorError::TransactionNotFound(_)) => {
+                            self.notify_dependency_failed(tx, *dependency)?;
+                            return Ok(false);
+                        }
+                        Err(e) => return Err(e.into()),
+                    }
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            match dependency_tx.state {
+                TransactionState::Confirmed | TransactionState::Finalized => {}
+                TransactionState::Failed => {
+                    self.notify_dependency_failed(tx, *dependency)?;
+                    return Ok(false);
+                }
+                TransactionState::Cancelled | TransactionState::Expired
+                    if dependency_tx.broadcast_block_height.is_none() =>
+                {
+                    self.notify_dependency_failed(tx, *dependency)?;
+                    return Ok(false);
+                }
+                TransactionState::ToDispatch
+                | TransactionState::Dispatched
+                | TransactionState::Cancelled
+                | TransactionState::Expired => {
+                    debug!(
+                        "{} Transaction({}) waiting for Dependency({}) to be confirmed",
+                        style("Coordinator").green(),
+                        style(tx.tx_id).yellow(),
+                        style(dependency_txid).yellow(),
+                    );
+
+                    return Ok(false);
+                }
+            }
+        }
+
+        Ok(true)
+    }
+
+    // The transaction will never be dispatched, so it is marked as failed like its dependency.
+    fn notify_dependency_failed(
+        &self,
+        tx: &CoordinatedTransaction,
+        dependency_txid: Txid,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        warn!(
+            "{} Transaction({}) will not be dispatched, its dependency failed | Dependency({})",
+            style("Coordinator").green(),
+            style(tx.tx_id).yellow(),
+            style(dependency_txid).red(),
+        );
+
+        self.store
+            .update_tx_state(tx.tx_id, TransactionState::Failed)?;
+
+        self.monitor.cancel(TypesToMonitor::Transactions(
+            vec![tx.tx_id],
+            tx.context.clone(),
+            None,
+        ))?;
+
+        self.update_news(CoordinatorNews::DependencyFailed(tx.tx_id, dependency_txid))?;
+
+        Ok(())
+    }
+
+    // The transaction was not confirmed by its expiry height, so it is not dispatched nor sped up anymore.
+    fn expire_tx(&self, tx: &CoordinatedTransaction) -> Result<(), BitcoinCoordinatorError> {
+        let expires_at = tx.dispatch_options.expires_at_height.unwrap_or_default();
+        let tx = self.store.expire_tx(tx.tx_id)?;
+
+        // If the transaction was never broadcast there is nothing left to monitor.
+        if tx.broadcast_block_height.is_none() {
+            self.monitor.cancel(TypesToMonitor::Transactions(
+                vec![tx.tx_id],
+                tx.context.clone(),
+                None,
+            ))?;
+        }
+
+        self.store
+            .with_funding_group(tx.dispatch_options.funding_group.as_deref(), || {
+                self.store.remove_deferred_speedup_txs(&[tx.tx_id])
+            })?;
+
+        warn!(
+            "{} Transaction({}) expired | ExpiresAt({}) | Broadcast({})",
+            style("Coordinator").green(),
+            style(tx.tx_id).yellow(),
+            style(expires_at).red(),
+            tx.broadcast_block_height.is_some(),
+        );
+
+        self.update_news(CoordinatorNews::TransactionExpired(
+            tx.tx_id, tx.context, expires_at,
+        ))?;
+
+        Ok(())
+    }
+
+    fn create_and_send_cpfp_tx(
+        &self,
+        txs_data: Vec<(SpeedupData, Transaction, String)>,
+        funding: Utxo,
+        bump_fee: f64,
+        replace_cpfp_txid: Option<Txid>,
+        retry_txid: Option<Txid>,
+    ) -> Result<Option<String>, BitcoinCoordinatorError> {
+        // Replacements and retries pay for transactions already paid by a speedup.
+        let is_new_cpfp = replace_cpfp_txid.is_none() && retry_txid.is_none();
+
+        // A broadcast speedup that is not saved yet is missing from the speedup chain, a speedup built
+        // from the chain could spend the same funding. It waits until the pending writes are saved.
+        if !self.pending_writes.is_empty() {
+            if is_new_cpfp {
+                self.defer_speedup(&paid_txids(&txs_data))?;
+            }
+
+            return Ok(None);
+        }
+
+        // Check if the funding amount is below the minimum required for a speedup.
+        // If so, notify via CoordinatorNews and exit early.
+        if funding.amount < self.settings().min_funding_amount_sats {
+            if is_new_cpfp {
+                self.defer_speedup(&paid_txids(&txs_data))?;
+            }
+
+            let news = CoordinatorNews::InsufficientFunds(
+                funding.txid,
+                funding.amount,
+                self.settings().min_funding_amount_sats,
+            );
+            self.update_news(news)?;
+
+            warn!(
+                "{} Insufficient funds for speedup | FundingTx({}) | Amount({}) | MinRequired({})",
+                style("Coordinator").green(),
+                style(funding.txid).yellow(),
+                style(funding.amount).red(),
+                style(self.settings().min_funding_amount_sats).blue(),
+            );
+
+            return Ok(None);
+        }
+
+        // The funding is checked again at build time, a reorg could have orphaned the speedup it comes from.
+        if let Some(orphaned_txid) = self.get_orphaned_funding_source(&funding)? {
+            return self.notify_funding_temporarily_unavailable(
+                orphaned_txid,
+                &paid_txids(&txs_data),
+                is_new_cpfp,
+            );
+        }
+
+        let is_rbf = replace_cpfp_txid.is_some();
+
+        // A replacement spends the funding of the speedup it replaces, so it is only checked for new speedups.
+        if !is_rbf {
+            if let FundingOutputState::Spent(spending_txid) =
+                self.get_funding_output_state(&funding)
+            {
+                // An orphaned speedup waiting to be mined again still spends it, the funding is not lost.
+                if let Some(orphaned_txid) = self.get_orphaned_speedup(spending_txid)? {
+                    return self.notify_funding_temporarily_unavailable(
+                        orphaned_txid,
+                        &paid_txids(&txs_data),
+                        is_new_cpfp,
+                    );
+                }
+
+                self.invalidate_funding(
+                    &funding,
+                    spending_txid,
+                    &paid_txids(&txs_data),
+                    retry_txid,
+                )?;
+
+                // Fall back to the funding that takes its place, if there is one.
+                return match self.store.get_funding()? {
+                    Some(funding) if self.store.can_speedup()? => {
+                        self.create_and_send_cpfp_tx(txs_data, funding, bump_fee, None, None)
+                    }
+                    _ => Ok(None),
+                };
+            }
+        }
+
+        let txs_speedup_data: Vec<(SpeedupData, usize)> = txs_data
+            .iter()
+            .map(|(speedup_data, tx, _)| (speedup_data.clone(), tx.vsize()))
+            .collect();
+
+        let anchor_kinds: Vec<SpeedupOutputKind> = txs_data
+            .iter()
+            .map(|(speedup_data, tx, _)| SpeedupOutputKind::of_speedup_utxo(tx, speedup_data))
+            .collect();
+
+        // The speedup pays up to the most permissive max fee rate of the transactions it pays for.
+        let max_feerate_sat_vb = self
+            .get_dispatch_options(&txs_data)?
+            .iter()
+            .map(|options| {
+                options
+                    .max_feerate_sat_vb
+                    .unwrap_or(self.settings().max_feerate_sat_vb)
+            })
+            .max()
+            .unwrap_or(self.settings().max_feerate_sat_vb);
+
+        let new_network_fee_rate = self.get_network_fee_rate(max_feerate_sat_vb)?;
+
+        // A funding from the pool starts a new chain, so the unconfirmed chain is not an ancestor of this speedup.
+        let (diff_fee_for_unconfirmed_chain, chain_vsize) =
+            if self.store.is_pool_funding(&funding)? {
+                (0, 0)
+            } else {
+                self.get_diff_fee_for_unconfirmed_chain(new_network_fee_rate)?
+            };
+
+        // The funding is signed with its own key, the change may be paid to a rotated key.
+        let change_key = self.store.get_change_key(&funding)?;
+
+        // The speedup is priced with its vsize estimated with the biggest signatures, so it is signed once
+        // with its final fee and output.
+        let estimated_vsize = self.estimate_speedup_vsize(&anchor_kinds);
+        let speedup_fee = self.calculate_speedup_fee(
+            &txs_speedup_data,
+            estimated_vsize,
+            bump_fee,
+            new_network_fee_rate,
+            is_rbf,
+            diff_fee_for_unconfirmed_chain,
+            chain_vsize,
+        )?;
+
+        // Validate that funding can cover the fee
+        if speedup_fee > funding.amount {
+            if is_new_cpfp {
+                self.defer_speedup(&paid_txids(&txs_data))?;
+            }
+
+            let news =
+                CoordinatorNews::InsufficientFunds(funding.txid, funding.amount, speedup_fee);
+            self.update_news(news)?;
+            return Ok(None);
+        }
+
+        // A change below the dust threshold would not be relayed, it is added to the fee instead.
+        // The speedup has no change output, so it ends the funding chain.
+        let input_sats: u64 = txs_speedup_data
+            .iter()
+            .map(|(speedup_data, _)| speedup_utxo_amount(speedup_data))
+            .sum::<Result<u64, _>>()?
+            + funding.amount;
+        let change_sats = input_sats - speedup_fee;
+        let dust_threshold_sats = self.settings().dust_threshold_sats;
+
+        let (speedup_tx, speedup_fee, exhausted_change) = if change_sats < dust_threshold_sats {
+            match self.build_speedup_tx_without_change(&txs_data, &anchor_kinds, &funding)? {
+                Some(speedup_tx) => (speedup_tx, speedup_fee + change_sats, Some(change_sats)),
+                None => {
+                    if is_new_cpfp {
+                        self.defer_speedup(&paid_txids(&txs_data))?;
+                    }
+
+                    // The funding needed to leave a change at the dust threshold.
+                    let required = funding.amount + dust_threshold_sats - change_sats;
+                    let news =
+                        CoordinatorNews::InsufficientFunds(funding.txid, funding.amount, required);
+                    self.update_news(news)?;
+                    return Ok(None);
+                }
+            }
+        } else {
+            let (speedup_tx, speedup_fee) = self.get_speedup_tx(
+                &txs_speedup_data,
+                &anchor_kinds,
+                &funding,
+                &change_key,
+                speedup_fee,
+                estimated_vsize,
+                |vsize| {
+                    self.calculate_speedup_fee(
+                        &txs_speedup_data,
+                        vsize,
+                        bump_fee,
+                        new_network_fee_rate,
+                        is_rbf,
+                        diff_fee_for_unconfirmed_chain,
+                        chain_vsize,
+                    )
+                },
+            )?;
+
+            (speedup_tx, speedup_fee, None)
+        };
+
+        let speedup_tx_id = speedup_tx.compute_txid();
+        let txs_info: Vec<(Txid, String)> = txs_data
+            .iter()
+            .map(|(_, tx, context)| (tx.compute_txid(), context.clone()))
+            .collect();
+
+        if !self.review_speedup(
+            &speedup_tx,
+            &txs_data,
+            speedup_fee,
+            is_rbf,
+            is_new_cpfp,
+            retry_txid,
+        )? {
+            return Ok(None);
+        }
+
+        let speedup_type = if is_rbf { "RBF" } else { "CPFP" };
+        let mut cpfp_to_replace = String::new();
+
+        if let Some(replace_cpfp_txid) = replace_cpfp_txid {
+            cpfp_to_replace = format!("| CPFP_TO_REPLACE({})", replace_cpfp_txid);
+        }
+
+        let previous_txid = speedup_tx.input[0].previous_output.txid;
+
+        debug!(
+            "{} New {} Transaction({}) | Tx2Speedup({:#?}) | Fee({}) | Transactions#({}) | FundingTx({}) | Vout({}) {} | BumpFee({})",
+            style("Coordinator").green(),
+            speedup_type,
+            style(speedup_tx_id).yellow(),
+            style(previous_txid).yellow(),
+            style(speedup_fee).blue(),
+            style(txs_info.len()).blue(),
+            style(funding.txid).blue(),
+            style(funding.vout).blue(),
+            style(cpfp_to_replace).blue(),
+            style(bump_fee).blue(),
+        );
+
+        if let Some(change_sats) = exhausted_change {
+            warn!(
+                "{} Funding exhausted | Speedup({}) | DustChange({}) | DustThreshold({})",
+                style("Coordinator").green(),
+                style(speedup_tx_id).yellow(),
+                style(change_sats).red(),
+                style(dust_threshold_sats).blue(),
+            );
+        }
+
+        // A speedup without change has a zero value OP_RETURN output, its next funding is never used.
+        let change_output = speedup_tx
+            .output
+            .last()
+            .ok_or(BitcoinCoordinatorError::SpeedupHasNoOutputs(speedup_tx_id))?;
+        let new_funding_utxo = Utxo::new(
+            speedup_tx_id,
+            0, // After creating the speedup tx we know that the vout is 0.
+            change_output.value.to_sat(),
+            &change_key,
+        );
+
+        self.store.journal().record(JournalEvent::SpeedupCreated {
+            tx_id: speedup_tx_id,
+            is_rbf,
+            paid_txids: txs_info.iter().map(|(txid, _)| *txid).collect(),
+            network_fee_rate: new_network_fee_rate,
+            bump_fee_percentage: bump_fee,
+            vsize: speedup_tx.vsize(),
+            parents_vsize: txs_speedup_data.iter().map(|(_, vsize)| vsize).sum(),
+            fee: speedup_fee,
+        });
+
+        let mut speedup_data = CoordinatedSpeedUpTransaction::new(
+            speedup_tx_id,
+            funding,
+            new_funding_utxo,
+            is_rbf,
+            0, // Temporary value, will be updated after send_transaction
+            SpeedupState::Dispatched,
+            bump_fee,
+            txs_data
+                .iter()
+                .map(|(speedup_data, tx, context)| {
+                    SpeedupParent::new(speedup_data.clone(), tx, context.clone())
+                })
+                .collect(),
+            new_network_fee_rate,
+            speedup_tx.vsize(),
+        );
+        speedup_data.exhausted_change = exhausted_change;
+        speedup_data.bumped_from = self.bumped_from(is_rbf, txs_data.is_empty(), retry_txid)?;
+
+        self.dispatch_speedup(speedup_tx, speedup_data, speedup_fee, retry_txid)
+    }
+
+    // The bump fee percentage a new speedup grew from, see CoordinatedSpeedUpTransaction::bumped_from.
+    // A replacement grows from the last speedup of the chain, as a boost (a CPFP paying no new transactions) does.
+    fn bumped_from(
+        &self,
+        is_rbf: bool,
+        is_boost: bool,
+        retry_txid: Option<Txid>,
+    ) -> Result<Option<f64>, BitcoinCoordinatorError> {
+        // A retry sends the same speedup again.
+        if let Some(retry_txid) = retry_txid {
+            return Ok(self.store.get_speedup(&retry_txid)?.bumped_from);
+        }
+
+        if !is_rbf && !is_boost {
+            return Ok(None);
+        }
+
+        Ok(self
+            .store
+            .get_last_speedup()?
+            .map(|(cpfp, rbf_tx)| rbf_tx.unwrap_or(cpfp).bump_fee_percentage_used))
+    }
+
+    // Asks the review hook whether the signed speedup can be broadcast, it always can without a hook.
+    // A deferred CPFP for new transactions is built again on the next ticks, a deferred replacement or retry is
+    // left as it is and tried again when it is due, without counting as a failed attempt.
+    fn review_speedup(
+        &self,
+        speedup_tx: &Transaction,
+        txs_data: &[(SpeedupData, Transaction, String)],
+        speedup_fee: u64,
+        is_rbf: bool,
+        is_new_cpfp: bool,
+        retry_txid: Option<Txid>,
+    ) -> Result<bool, BitcoinCoordinatorError> {
+        let Some(hook) = &self.speedup_review_hook else {
+            return Ok(true);
+        };
+
+        let speedup_tx_id = speedup_tx.compute_txid();
+        let txids = paid_txids(txs_data);
+
+        match hook.review(speedup_tx, &txids, speedup_fee, is_rbf) {
+            ReviewDecision::Approve => Ok(true),
+            ReviewDecision::Reject(reason) => {
+                warn!(
+                    "{} Speedup({}) rejected by the review hook: {}",
+                    style("Coordinator").green(),
+                    style(speedup_tx_id).yellow(),
+                    reason
+                );
+
+                if let Some(retry_txid) = retry_txid {
+                    self.store.dequeue_speedup_for_retry(retry_txid)?;
+                }
+
+                self.store.save_speedup_rejection(SpeedupRejection {
+                    tx_id: speedup_tx_id,
+                    paid_txids: txids,
+                    fee: speedup_fee,
+                    is_rbf,
+                    reason: reason.clone(),
+                })?;
+
+                let news = CoordinatorNews::SpeedupRejectedByPolicy(speedup_tx_id, reason);
+                self.update_news(news)?;
+
+                Ok(false)
+            }
+            ReviewDecision::Defer => {
+                info!(
+                    "{} Speedup({}) deferred by the review hook",
+                    style("Coordinator").green(),
+                    style(speedup_tx_id).yellow(),
+                );
+
+                if is_new_cpfp {
+                    self.defer_speedup(&txids)?;
+                }
+
+                Ok(false)
+            }
+        }
+    }
+
+    // An error asking for the funding state is only logged, the broadcast reports a funding that is really spent.
+    fn get_funding_output_state(&self, funding: &Utxo) -> FundingOutputState {
+        let outpoint = OutPoint::new(funding.txid, funding.vout);
+        let result = match &self.funding_output_checker {
+            Some(checker) => checker.get_funding_output_state(&outpoint),
+            None => self
+                .node
+                .is_unspent(&outpoint, true)
+                .map(|unspent| match unspent {
+                    true => FundingOutputState::Unspent,
+                    // Mempool spends are included, the node does not tell which transaction spent the output.
+                    false => FundingOutputState::Spent(None),
+                }),
+        };
+
+        result.unwrap_or_else(|e| {
+            warn!(
+                "{} Could not check FundingTx({}) | Vout({}): {}",
+                style("Coordinator").green(),
+                style(funding.txid).yellow(),
+                style(funding.vout).yellow(),
+                e
+            );
+            FundingOutputState::Unspent
+        })
+    }
+
+    // Returns the speedup the funding comes from when a reorg orphaned it, so its change is not on the active chain.
+    // A speedup still seen as confirmed is checked with the monitor, and marked as orphaned with its descendants.
+    fn get_orphaned_funding_source(
+        &self,
+        funding: &Utxo,
+    ) -> Result<Option<Txid>, BitcoinCoordinatorError> {
+        let source = match self.store.get_speedup(&funding.txid) {
+            Ok(source) => source,
+            Err(BitcoinCoordinatorStoreError::SpeedupNotFound) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        match source.state {
+            SpeedupState::Orphaned => Ok(Some(source.tx_id)),
+            SpeedupState::Confirmed => match self.monitor.get_tx_status(&source.tx_id) {
+                Ok(tx_status) if tx_status.is_orphan() => {
+                    self.notify_speedup_orphaned(&source)?;
+                    Ok(Some(source.tx_id))
+                }
+                Ok(_) | Err(MonitorError::TransactionNotFound(_)) => Ok(None),
+                Err(e) => Err(e.into()),
+            },
+            _ => Ok(None),
+        }
+    }
+
+    // Returns the transaction when it is a speedup orphaned by a reorg.
+    fn get_orphaned_speedup(
+        &self,
+        txid: Option<Txid>,
+    ) -> Result<Option<Txid>, BitcoinCoordinatorError> {
+        let Some(txid) = txid else {
+            return Ok(None);
+        };
+
+        match self.store.get_speedup(&txid) {
+            Ok(speedup) if speedup.state == SpeedupState::Orphaned => Ok(Some(txid)),
+            Ok(_) | Err(BitcoinCoordinatorStoreError::SpeedupNotFound) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    // The speedup is not sent until the orphaned speedup is mined again. New speedups are deferred,
+    // replacements and retries are tried again on the next ticks.
+    fn notify_funding_temporarily_unavailable(
+        &self,
+        orphaned_txid: Txid,
+        paid_txids: &[Txid],
+        is_new_cpfp: bool,
+    ) -> Result<Option<String>, BitcoinCoordinatorError> {
+        warn!(
+            "{} Funding temporarily unavailable, it depends on an orphaned speedup | Speedup({}) | Transactions({})",
+            style("Coordinator").green(),
+            style(orphaned_txid).yellow(),
+            style(paid_txids.len()).red(),
+        );
+
+        if is_new_cpfp {
+            self.defer_speedup(paid_txids)?;
+        }
+
+        let news =
+            CoordinatorNews::FundingTemporarilyUnavailable(orphaned_txid, paid_txids.to_vec());
+        self.update_news(news)?;
+
+        Ok(None)
+    }
+
+    // The funding was spent by a transaction the coordinator did not send. It is never used again, the speedup is
+    // deferred until another funding is available.
+    fn invalidate_funding(
+        &self,
+        funding: &Utxo,
+        spending_txid: Option<Txid>,
+        paid_txids: &[Txid],
+        retry_txid: Option<Txid>,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        let outpoint = OutPoint::new(funding.txid, funding.vout);
+
+        warn!(
+            "{} Funding spent externally | FundingTx({}) | Vout({}) | SpendingTx({:?})",
+            style("Coordinator").green(),
+            style(funding.txid).yellow(),
+            style(funding.vout).yellow(),
+            spending_txid,
+        );
+
+        self.store.invalidate_funding(funding)?;
+
+        if let Some(retry_txid) = retry_txid {
+            self.store.dequeue_speedup_for_retry(retry_txid)?;
+        }
+
+        self.defer_speedup(paid_txids)?;
+        self.update_news(CoordinatorNews::FundingSpentExternally(
+            outpoint,
+            spending_txid,
+        ))?;
+
+        Ok(())
+    }
+
+    // The parents of a CPFP the funding can not pay for were already broadcast, so their CPFP is sent later.
+    fn defer_speedup(&self, txids: &[Txid]) -> Result<(), BitcoinCoordinatorError> {
+        if txids.is_empty() {
+            return Ok(());
+        }
+
+        warn!(
+            "{} Deferring CPFP for {} transactions until funding is available",
+            style("Coordinator").green(),
+            style(txids.len()).yellow(),
+        );
+
+        self.store.defer_speedup(txids)?;
+
+        Ok(())
+    }
+
+    fn get_diff_fee_for_unconfirmed_chain(
+        &self,
+        new_network_fee_rate: u64,
+    ) -> Result<(u64, usize), BitcoinCoordinatorError> {
+        // Each unconfirmed transaction in the chain (speedups and the transactions they pay for) was paid at the
+        // fee rate stored when it was dispatched. We only need to pay the difference up to the new fee rate.
+        let (fee_chain_difference, chain_vsize) = self
+            .store
+            .get_unconfirmed_chain_fee_shortfall(new_network_fee_rate)?;
+
+        Ok((fee_chain_difference, chain_vsize))
+    }
+
+    // Fee rate of the configured fee strategy, never below min_network_fee_rate.
+    // Without an estimate it falls back to the mempool min fee of the node, then to min_network_fee_rate.
+    fn get_estimated_fee_rate(&self) -> FeeRateEstimate {
+        self.fee_estimator.estimate_with_fallback(
+            |conf_target, mode| self.estimate_smart_fee(conf_target, mode),
+            || self.get_mempool_min_fee(),
+        )
+    }
+
+    fn get_mempool_min_fee(&self) -> Result<Option<u64>, BitcoinCoordinatorError> {
+        self.node.get_mempool_min_fee()
+    }
+
+    fn estimate_smart_fee(
+        &self,
+        conf_target: Option<u16>,
+        mode: Option<FeeEstimateMode>,
+    ) -> Result<Option<u64>, BitcoinCoordinatorError> {
+        if let Some(estimator) = &self.smart_fee_estimator {
+            return estimator.estimate_fee_rate(conf_target.unwrap_or(DEFAULT_FEE_CONF_TARGET));
+        }
+
+        // The monitor estimates the fee rate with the node defaults.
+        if conf_target.is_none() && mode.is_none() {
+            return Ok(Some(self.monitor.get_estimated_fee_rate()?));
+        }
+
+        self.node
+            .estimate_smart_fee(conf_target.unwrap_or(DEFAULT_FEE_CONF_TARGET), mode)
+    }
+
+    fn get_network_fee_rate(
+        &self,
+        max_feerate_sat_vb: u64,
+    ) -> Result<u64, BitcoinCoordinatorError> {
+        let estimate = self.get_estimated_fee_rate();
+
+        if estimate.fallback {
+            // Reported once per block, the news is only updated when the block changes.
+            self.update_news(CoordinatorNews::FeeEstimateUnavailable(estimate.fee_rate))?;
+        }
+
+        let mut network_fee_rate = estimate.fee_rate;
+
+        if network_fee_rate > max_feerate_sat_vb {
+            warn!(
+                "{} Estimate feerate sat/vbyte is greater than the max allowed. This could be a bug. | EstimateFeerate({}) | MaxAllowed({})",
+                style("Coordinator").red(),
+                style(network_fee_rate).red(),
+                style(max_feerate_sat_vb).red(),
+            );
+
+            // Inform this with news
+            let news =
+                CoordinatorNews::EstimateFeerateTooHigh(network_fee_rate, max_feerate_sat_vb);
+
+            self.update_news(news)?;
+
+            // Set the estimate feerate to the max allowed
+            network_fee_rate = max_feerate_sat_vb;
+        }
+        Ok(network_fee_rate)
+    }
+
+    // Upper bound of the vsize of a speedup spending the speedup utxos and the funding (a segwit v0 key spend output)
+    // to a single change output, used when nothing is signed.
+    fn estimate_speedup_vsize(&self, anchor_kinds: &[SpeedupOutputKind]) -> usize {
+        let input = |kind: &SpeedupOutputKind| TxIn {
+            previous_output: OutPoint::null(),
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+            witness: kind.dummy_witness(),
+        };
+
+        let speedup_tx = Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: anchor_kinds
+                .iter()
+                .chain(std::iter::once(&SpeedupOutputKind::P2wpkh))
+                .map(input)
+                .collect(),
+            output: vec![TxOut {
+                value: Amount::ZERO,
+                script_pubkey: ScriptBuf::new_p2wpkh(&WPubkeyHash::all_zeros()),
+            }],
+        };
+
+        speedup_tx.vsize()
+    }
+
+    // Builds and signs the speedup paying `speedup_fee`, priced with `estimated_vsize`. The estimate assumes a single
+    // key witness script for the partial utxos, a speedup signed with a longer script is priced again with
+    // `speedup_fee_for` and its actual vsize, and signed a second time.
+    fn get_speedup_tx<F>(
+        &self,
+        txs_data: &[(SpeedupData, usize)],
+        anchor_kinds: &[SpeedupOutputKind],
+        funding: &Utxo,
+        change_key: &PublicKey,
+        speedup_fee: u64,
+        estimated_vsize: usize,
+        speedup_fee_for: F,
+    ) -> Result<(Transaction, u64), BitcoinCoordinatorError>
+    where
+        F: Fn(usize) -> Result<u64, BitcoinCoordinatorError>,
+    {
+        let speedups_data: Vec<SpeedupData> =
+            txs_data.iter().map(|tx_data| tx_data.0.clone()).collect();
+
+        let speedup_tx = self.build_speedup_tx(
+            &speedups_data,
+            anchor_kinds,
+            funding,
+            change_key,
+            speedup_fee,
+        )?;
+
+        let speedup_vsize = speedup_tx.vsize();
+
+        if speedup_vsize <= estimated_vsize {
+            return Ok((speedup_tx, speedup_fee));
+        }
+
+        debug!(
+            "{} Speedup vsize above its estimate | EstimatedVsize({}) | Vsize({})",
+            style("Coordinator").green(),
+            style(estimated_vsize).blue(),
+            style(speedup_vsize).red(),
+        );
+
+        let speedup_fee = speedup_fee_for(speedup_vsize)?;
+        let speedup_tx = self.build_speedup_tx(
+            &speedups_data,
+            anchor_kinds,
+            funding,
+            change_key,
+            speedup_fee,
+        )?;
+
+        Ok((speedup_tx, speedup_fee))
+    }
+
+    // The protocol builder only spends segwit v0 outputs, so speedups paying for a taproot anchor are built
+    // and signed by the coordinator. Both place the speedup utxos first and the funding last.
+    fn build_speedup_tx(
+        &self,
+        speedups_data: &[SpeedupData],
+        anchor_kinds: &[SpeedupOutputKind],
+        funding: &Utxo,
+        change_key: &PublicKey,
+        fee: u64,
+    ) -> Result<Transaction, BitcoinCoordinatorError> {
+        if !anchor_kinds.contains(&SpeedupOutputKind::P2trKeyPath) {
+            let speedup_tx = (ProtocolBuilder {}).speedup_transactions(
+                speedups_data,
+                funding.clone(),
+                change_key,
+                fee,
+                &self.key_manager,
+            )?;
+            self.observer.on_speedup_signed(speedup_tx.input.len());
+
+            return Ok(speedup_tx);
+        }
+
+        let anchors = speedups_data
+            .iter()
+            .zip(anchor_kinds)
+            .map(|(speedup_data, kind)| match &speedup_data.utxo {
+                Some(utxo) => Ok((utxo.clone(), *kind)),
+                None => Err(BitcoinCoordinatorError::SpeedupSigningError(
+                    "a speedup with a taproot anchor can not spend a partial utxo".to_string(),
+                )),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let speedup_tx = build_cpfp_tx(&anchors, funding, change_key, fee, &self.key_manager)?;
+        self.observer.on_speedup_signed(speedup_tx.input.len());
+
+        Ok(speedup_tx)
+    }
+
+    // The protocol builder always adds a change output, so a speedup without change is built by the coordinator.
+    // Returns None when some anchor is a partial utxo, which only the protocol builder can sign.
+    fn build_speedup_tx_without_change(
+        &self,
+        txs_data: &[(SpeedupData, Transaction, String)],
+        anchor_kinds: &[SpeedupOutputKind],
+        funding: &Utxo,
+    ) -> Result<Option<Transaction>, BitcoinCoordinatorError> {
+        let mut anchors = Vec::with_capacity(txs_data.len());
+
+        for ((speedup_data, _, _), kind) in txs_data.iter().zip(anchor_kinds) {
+            match &speedup_data.utxo {
+                Some(utxo) if *kind != SpeedupOutputKind::P2wshScript => {
+                    anchors.push((utxo.clone(), *kind))
+                }
+                _ => return Ok(None),
+            }
+        }
+
+        let speedup_tx = build_cpfp_tx_without_change(&anchors, funding, &self.key_manager)?;
+        self.observer.on_speedup_signed(speedup_tx.input.len());
+
+        Ok(Some(speedup_tx))
+    }
+
+    fn rbf_last_cpfp(&self) -> Result<(), BitcoinCoordinatorError> {
+        // When this function is called, the last speedup was checked to exist.
+        let (speedup, rbf_tx) = self
+            .store
+            .get_last_speedup()?
+            .ok_or(BitcoinCoordinatorError::NoSpeedupToReplace)?;
+
+        // Transactions double spent by a conflicting transaction, or whose speedup output was spent by another
+        // transaction, are not paid anymore.
+        let mut txs_data: Vec<(SpeedupData, Transaction, String)> = Vec::new();
+
+        for parent in speedup.speedup_tx_data.iter() {
+            let tx = self.store.get_tx(&parent.tx_id)?;
+
+            if !matches!(
+                tx.state,
+                TransactionState::Failed | TransactionState::Expired
+            ) && tx.speedup_data.is_some()
+            {
+                txs_data.push((parent.speedup_data.clone(), tx.tx, parent.context.clone()));
+            }
+        }
+
+        if txs_data.is_empty() {
+            return Ok(());
+        }
+
+        // After max_rbf_attempts replacements the CPFP is left to confirm. New transactions are paid from a new
+        // chain once the funding pool is used, and the counter starts again when a replacement confirms.
+        let replacements = self.store.get_speedup_replacements(&speedup)?;
+        let attempts = replacements.len() as u32;
+
+        if attempts >= self.settings().max_rbf_attempts {
+            // Every replacement spends the same funding, its fee is what it takes from it.
+            let fee_spent: u64 = replacements
+                .iter()
+                .map(|replacement| {
+                    replacement
+                        .prev_funding
+                        .amount
+                        .saturating_sub(replacement.next_funding.amount)
+                })
+                .sum();
+
+            warn!(
+                "{} Max RBF attempts reached for CPFP({}) | Attempts({}) | FeeSpent({})",
+                style("Coordinator").green(),
+                style(speedup.tx_id).yellow(),
+                style(attempts).red(),
+                style(fee_spent).blue(),
+            );
+
+            let news = CoordinatorNews::MaxRbfAttemptsReached(speedup.tx_id, attempts, fee_spent);
+            self.update_news(news)?;
+
+            return Ok(());
+        }
+
+        // The new_bump_fee will increase the previous bump fee from the CPFP used by adding the number of RBF operations performed + 1.
+        let mut increase_last_bump_fee = speedup.bump_fee_percentage_used;
+        let mut stuck_network_fee_rate = speedup.network_fee_rate_used;
+
+        if let Some(rbf_tx) = rbf_tx {
+            increase_last_bump_fee = rbf_tx.bump_fee_percentage_used;
+            stuck_network_fee_rate = rbf_tx.network_fee_rate_used;
+        }
+
+        // A replacement grows at least by rbf_fee_percentage, whatever the strategy step is.
+        let new_bump_fee = self
+            .get_bump_fee_percentage_strategy(increase_last_bump_fee, Some(stuck_network_fee_rate))?
+            .max(increase_last_bump_fee * self.settings().rbf_fee_percentage);
+
+        self.send_rbf_with_escalation(
+            txs_data,
+            speedup.prev_funding,
+            new_bump_fee,
+            speedup.tx_id,
+            None,
+        )?;
+
+        Ok(())
+    }
+
+    // Sends a replacement (RBF) for the given CPFP. If the node rejects it for insufficient fee,
+    // the replacement is recomputed with a bigger bump fee in the same tick, up to max_rbf_attempts times.
+    fn send_rbf_with_escalation(
+        &self,
+        txs_data: Vec<(SpeedupData, Transaction, String)>,
+        funding: Utxo,
+        bump_fee: f64,
+        replace_cpfp_txid: Txid,
+        retry_txid: Option<Txid>,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        let escalation = escalate_replacement(
+            self.settings().max_rbf_attempts,
+            bump_fee,
+            |bump_fee| {
+                self.create_and_send_cpfp_tx(
+                    txs_data.clone(),
+                    funding.clone(),
+                    bump_fee,
+                    Some(replace_cpfp_txid),
+                    retry_txid,
+                )
+            },
+            |bump_fee| {
+                // The rejected replacement was priced at the current network fee rate.
+                let new_bump_fee = self
+                    .get_bump_fee_percentage_strategy(bump_fee, None)?
+                    .max(bump_fee * self.settings().rbf_fee_percentage);
+
+                warn!(
+                    "{} Escalating RBF for CPFP({}) | BumpFee({}) | NewBumpFee({})",
+                    style("Coordinator").green(),
+                    style(replace_cpfp_txid).yellow(),
+                    style(bump_fee).blue(),
+                    style(new_bump_fee).blue(),
+                );
+
+                Ok(new_bump_fee)
+            },
+        )?;
+
+        if let RbfEscalation::Failed {
+            attempts,
+            last_error,
+        } = escalation
+        {
+            error!(
+                "{} RBF for CPFP({}) rejected for insufficient fee after {} attempts: {}",
+                style("Coordinator").green(),
+                style(replace_cpfp_txid).yellow(),
+                style(attempts).red(),
+                last_error
+            );
+
+            if let Some(retry_txid) = retry_txid {
+                self.store.dequeue_speedup_for_retry(retry_txid)?;
+            }
+
+            let news =
+                CoordinatorNews::RbfEscalationFailed(replace_cpfp_txid, attempts, last_error);
+            self.update_news(news)?;
+        }
+
+        Ok(())
+    }
+
+    fn boost_cpfp_again(&self) -> Result<(), BitcoinCoordinatorError> {
+        // Check if we can send transactions or we stop the process until CPFP transactions start to be confirmed.
+        if self.store.can_speedup()? {
+            self.speedup_cpfp_tx()?;
+        } else {
+            warn!("{} Can not speedup", style("Coordinator").green());
+
+            let is_funding_available = self.store.is_funding_available()?;
+
+            if !is_funding_available {
+                self.notify_funding_not_found()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn calculate_speedup_fee(
+        &self,
+        tx_to_speedup_info: &[(SpeedupData, usize)],
+        child_vbytes: usize,
+        bump_fee_percentage: f64,
+        network_fee_rate: u64,
+        is_rbf: bool,
+        fee_chain_difference: u64,
+        chain_vsize: usize,
+    ) -> Result<u64, BitcoinCoordinatorError> {
+        // Assumes that each parent transaction pays 1 sat/vbyte.
+        // To calculate the total fee, we need to know the vsize of the child (CPFP) + the vsize of each parent.
+        // Also we have to subtract the parent's transaction vbytes and the total output amounts once.
+
+        let mut parent_amount_outputs: usize = 0;
+        let mut parent_vbytes: usize = 0;
+
+        for (speedup_data, vsize) in tx_to_speedup_info {
+            parent_amount_outputs += speedup_utxo_amount(speedup_data)? as usize;
+            parent_vbytes += vsize;
+        }
+
+        // We substract the vbytes of the parents and the amount of outputs.
+        // Because the child pays for the parents and the parents pay for the outputs
+        let parent_total_sats = parent_vbytes * network_fee_rate as usize;
+        let child_total_sats = child_vbytes * network_fee_rate as usize;
+        let total_sats = parent_total_sats + child_total_sats;
+
+        let mut total_fee = total_sats
+            .saturating_sub(parent_amount_outputs) // amount comming from the parents to discount
+            .saturating_sub(parent_vbytes); // min relay fee of the parents to discount
+
+        if is_rbf && total_fee < child_total_sats * 2 {
+            // Bitcoin Policy (https://github.com/bitcoin/bitcoin/blob/master/doc/policy/mempool-replacements.md?plain=1#L32):
+            // The additional fees (difference between absolute fee paid by the replacement transaction and the
+            // sum paid by the original transactions) pays for the replacement transaction's bandwidth at or
+            // above the rate set by the node's incremental relay feerate. For example, if the incremental relay
+            // feerate is 1 satoshi/vB and the replacement transaction is 500 virtual bytes total, then the
+            // replacement pays a fee at least 500 satoshis higher than the sum of the original transactions.
+
+            // *Rationale*: Try to prevent DoS attacks where an attacker causes the network to repeatedly relay
+            // transactions each paying a tiny additional amount in fees, e.g. just 1 satoshi.
+            total_fee = child_total_sats * 2;
+        }
+
+        let mut total_fee_bumped = (total_fee as f64 * bump_fee_percentage).ceil().round() as u64;
+
+        // The unconfirmed chain was already paid at the fee rate stored for each transaction, so we only add
+        // the exact difference up to the current fee rate. This part is not bumped to avoid overpaying.
+        total_fee_bumped += fee_chain_difference;
+
+        // If a fee bump is being applied, add the virtual size of the transaction chain to the total fee to incentivize the miners to include the chain in the next block.
+        if chain_vsize > 0 && bump_fee_percentage > self.settings().base_fee_multiplier {
+            debug!(
+                "{} Adding to total fee ChainVsize({}) for bump fee {}",
+                style("Coordinator").green(),
+                style(chain_vsize).blue(),
+                style(bump_fee_percentage).blue()
+            );
+            total_fee_bumped += chain_vsize as u64;
+        }
+
+        let mut fee_chain_difference_str = String::new();
+        if fee_chain_difference > 0 {
+            fee_chain_difference_str = "Recomputing fee for chain ".to_string();
+        }
+
+        debug!(
+            "{} {}EstimateNetworkFee({}) | ParentTotalSats({}) | ChildTotalSats({}) | BumpFeePercentage({}) | ParentAmountOutputs({}) | ParentVbytes({}) | TotalFee({}) | FeeChainDifference({}) | ChainVsize({})",
+            style("Coordinator").green(),
+            style(fee_chain_difference_str),
+            style(network_fee_rate).red(),
+            style(parent_total_sats).red(),
+            style(child_total_sats).red(),
+            style(bump_fee_percentage).red(),
+            style(parent_amount_outputs).red(),
+            style(parent_vbytes).red(),
+            style(total_fee_bumped).red(),
+            style(fee_chain_difference).red(),
+            style(chain_vsize).red(),
+        );
+
+        Ok(total_fee_bumped)
+    }
+
+    fn get_bump_fee_percentage_strategy(
+        &self,
+        prev_bump_fee: f64,
+        stuck_network_fee_rate: Option<u64>,
+    ) -> Result<f64, BitcoinCoordinatorError> {
+        if prev_bump_fee <= 0.0 {
+            return Ok(self.settings().base_fee_multiplier);
+        }
+
+        // The previous bump fee is multiplied by a step. The step starts from bump_fee_percentage and is adjusted to
+        // how many of the recent speedups missed their target, and to how much the network fee rate moved since the
+        // stuck speedup was created. It is kept between min_bump_fee_percentage and max_bump_fee_percentage.
+        // For instance, with the default 1.5 and half of the speedups missing, a bump fee of 2 becomes 3.0.
+        let shortfall = match stuck_network_fee_rate {
+            Some(stuck_network_fee_rate) if stuck_network_fee_rate > 0 => {
+                let network_fee_rate =
+                    self.get_network_fee_rate(self.settings().max_feerate_sat_vb)?;
+                network_fee_rate as f64 / stuck_network_fee_rate as f64
+            }
+            _ => 1.0,
+        };
+
+        let strategy = self.bump_strategy_state(shortfall)?;
+        let bumped_feerate = prev_bump_fee * strategy.next_step;
+
+        debug!(
+            "{} Bumping fee from {} to {} | Step({}) | MissRate({:?}) | Shortfall({})",
+            style("Coordinator").green(),
+            style(prev_bump_fee).blue(),
+            style(bumped_feerate).blue(),
+            style(strategy.next_step).blue(),
+            style(strategy.miss_rate).blue(),
+            style(shortfall).blue(),
+        );
+
+        Ok(bumped_feerate)
+    }
+
+    // Summarizes the recorded speedup outcomes and the step they lead to for the given shortfall.
+    fn bump_strategy_state(
+        &self,
+        shortfall: f64,
+    ) -> Result<BumpStrategyState, BitcoinCoordinatorError> {
+        let settings = self.settings();
+        let outcomes = self.store.get_speedup_outcomes()?;
+        let miss_rate = miss_rate(&outcomes);
+
+        Ok(BumpStrategyState {
+            samples: outcomes.len() as u32,
+            miss_rate,
+            average_blocks_waited: average_blocks_waited(&outcomes),
+            next_step: next_bump_step(
+                settings.bump_fee_percentage,
+                settings.min_bump_fee_percentage,
+                settings.max_bump_fee_percentage,
+                miss_rate,
+                shortfall,
+            ),
+            min_step: settings.min_bump_fee_percentage,
+            max_step: settings.max_bump_fee_percentage,
+        })
+    }
+
+    // Records how long a speedup created by the coordinator waited, the first time it is seen confirmed.
+    fn record_speedup_outcome(
+        &self,
+        speedup: &CoordinatedSpeedUpTransaction,
+        confirmation_height: BlockHeight,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        if speedup.is_external {
+            return Ok(());
+        }
+
+        let blocks_waited = confirmation_height.saturating_sub(speedup.broadcast_block_height);
+
+        self.store.record_speedup_outcome(SpeedupOutcome {
+            tx_id: speedup.tx_id,
+            blocks_waited,
+            fee_rate_ratio: speedup.bump_fee_percentage_used,
+            missed: blocks_waited > self.settings().min_blocks_before_resend_speedup,
+            is_rbf: speedup.is_rbf,
+        })?;
+
+        Ok(())
+    }
+
+    fn should_rbf_last_speedup(&self) -> Result<bool, BitcoinCoordinatorError> {
+        let reached_unconfirmed_speedups = self.store.has_reached_max_unconfirmed_speedups()?;
+
+        if reached_unconfirmed_speedups {
+            debug!(
+                "{} Reached max unconfirmed speedups.",
+                style("Coordinator").green()
+            );
+
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    fn should_boost_speedup_again(&self) -> Result<bool, BitcoinCoordinatorError> {
+        let last_speedup = self.store.get_last_speedup()?;
+
+        if let Some((speedup, rbf_tx)) = last_speedup {
+            if self.has_only_cancelled_parents(&speedup)? {
+                debug!(
+                    "{} Last CPFP only pays for cancelled transactions, skip bumping | CPFP({})",
+                    style("Coordinator").green(),
+                    style(speedup.tx_id).blue(),
+                );
+
+                return Ok(false);
+            }
+
+            let current_block_height = self.current_height()?;
+            // This block checks if the last speedup transaction should be replaced-by-fee.
+            // It retrieves the last speedup transaction and the number of times it has already been replaced (replace_speedup_count).
+            // The logic is: if the current block height is greater than the sum of the speedup's broadcast block height and the number of RBFs,
+            // then enough blocks have passed without confirmation, so we should bump the fee again.
+            // This helps ensure that stuck transactions are periodically rebroadcast with higher fees to improve their chances of confirmation.
+            let last_broadcast_block_height = if let Some(rbf_tx) = rbf_tx {
+                rbf_tx.broadcast_block_height
+            } else {
+                speedup.broadcast_block_height
+            };
+
+            if current_block_height.saturating_sub(last_broadcast_block_height)
+                >= self.settings().min_blocks_before_resend_speedup
+            {
+                debug!(
+                    "{} Last CPFP should be bumped | CurrentHeight({}) | BroadcastHeight({}) | MinBlocksBeforeRBF({})",
+                    style("Coordinator").green(),
+                    style(current_block_height).blue(),
+                    style(last_broadcast_block_height).blue(),
+                    style(self.settings().min_blocks_before_resend_speedup).blue(),
+                );
+
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    // Monitor news of a replaced transaction are reported with the txid of its replacement,
+    // an acknowledgement with the replaced txid acknowledges the news of the replacement.
+    fn resolve_monitor_ack(
+        &self,
+        news: AckMonitorNews,
+    ) -> Result<AckMonitorNews, BitcoinCoordinatorError> {
+        if let AckMonitorNews::Transaction(tx_id, context) = &news {
+            if let Some(replacement_txid) = self.store.get_replacement(tx_id)? {
+                return Ok(AckMonitorNews::Transaction(
+                    replacement_txid,
+                    context.clone(),
+                ));
+            }
+        }
+
+        Ok(news)
+    }
+
+    // Dispatch options of the transactions paid by a speedup. Transactions not found in the store use the global settings.
+    fn get_dispatch_options(
+        &self,
+        txs_data: &[(SpeedupData, Transaction, String)],
+    ) -> Result<Vec<DispatchOptions>, BitcoinCoordinatorError> {
+        let mut dispatch_options = Vec::new();
+
+        for (_, tx, _) in txs_data {
+            match self.store.get_tx(&tx.compute_txid()) {
+                Ok(tx) => dispatch_options.push(tx.dispatch_options),
+                Err(BitcoinCoordinatorStoreError::TransactionNotFound(_)) => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Ok(dispatch_options)
+    }
+
+    fn validate_dispatch_options(
+        &self,
+        options: &DispatchOptions,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        if let Some(max_feerate_sat_vb) = options.max_feerate_sat_vb {
+            if max_feerate_sat_vb == 0 || max_feerate_sat_vb > DEFAULT_MAX_FEERATE_SAT_VB {
+                return Err(BitcoinCoordinatorError::InvalidConfiguration(format!(
+                    "max_feerate_sat_vb must be between 1 and {} sat/vb, got {}",
+                    DEFAULT_MAX_FEERATE_SAT_VB, max_feerate_sat_vb
+                )));
+            }
+        }
+
+        if let Some(bump_fee_percentage) = options.initial_bump_fee_percentage {
+            if bump_fee_percentage <= 0.0 || bump_fee_percentage > 100.0 {
+                return Err(BitcoinCoordinatorError::InvalidConfiguration(format!(
+                    "initial_bump_fee_percentage must be greater than 0 and at most 100.0, got {}",
+                    bump_fee_percentage
+                )));
+            }
+        }
+
+        if let Some(group) = &options.funding_group {
+            if !self.store.get_funding_groups()?.contains(group) {
+                return Err(BitcoinCoordinatorError::UnknownFundingGroup(group.clone()));
+            }
+        }
+
+        if let Some(finality_confirmations) = options.finality_confirmations {
+            self.validate_finality_confirmations(finality_confirmations)?;
+        }
+
+        if let Some(expires_at_height) = options.expires_at_height {
+            let current_height = self.current_height()?;
+
+            if expires_at_height <= current_height {
+                return Err(BitcoinCoordinatorError::InvalidConfiguration(format!(
+                    "expires_at_height must be above the current height {}, got {}",
+                    current_height, expires_at_height
+                )));
+            }
+        }
+
+        if options.max_pause_blocks == Some(0) {
+            return Err(BitcoinCoordinatorError::InvalidConfiguration(
+                "max_pause_blocks must be greater than 0".to_string(),
+            ));
+        }
+
+        // Milestones are only seen while the monitor follows the transaction.
+        if let Some(milestones) = &options.confirmation_milestones {
+            let max_confirmations = self
+                .settings()
+                .monitor_settings
+                .max_monitoring_confirmations;
+
+            if let Some(milestone) = milestones
+                .iter()
+                .find(|milestone| **milestone == 0 || **milestone > max_confirmations)
+            {
+                return Err(BitcoinCoordinatorError::InvalidConfiguration(format!(
+                    "confirmation_milestones must be between 1 and {} (max_monitoring_confirmations), got {}",
+                    max_confirmations, milestone
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    // The monitor stops following a transaction after max_monitoring_confirmations, so a transaction can only be
+    // finalized earlier.
+    fn validate_finality_confirmations(
+        &self,
+        finality_confirmations: u32,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        let max_confirmations = self
+            .settings()
+            .monitor_settings
+            .max_monitoring_confirmations;
+
+        if finality_confirmations == 0 || finality_confirmations > max_confirmations {
+            return Err(BitcoinCoordinatorError::InvalidConfiguration(format!(
+                "finality_confirmations must be between 1 and {} (max_monitoring_confirmations), got {}",
+                max_confirmations, finality_confirmations
+            )));
+        }
+
+        Ok(())
+    }
+
+    // Dependencies must be coordinated transactions, and can not depend back on the transaction being dispatched.
+    fn validate_dependencies(
+        &self,
+        tx_id: Txid,
+        options: &DispatchOptions,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        for dependency in options.depends_on.iter() {
+            match self.store.get_tx(dependency) {
+                Ok(_) => {}
+                Err(BitcoinCoordinatorStoreError::TransactionNotFound(_)) => {
+                    return Err(BitcoinCoordinatorError::UnknownDependency(
+                        tx_id,
+                        *dependency,
+                    ));
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        // A transaction dispatched again can be a dependency of its own dependencies.
+        let mut to_visit = options.depends_on.clone();
+        let mut visited = HashSet::new();
+
+        while let Some(dependency) = to_visit.pop() {
+            if dependency == tx_id {
+                return Err(BitcoinCoordinatorError::DependencyCycle(tx_id));
+            }
+
+            if !visited.insert(dependency) {
+                continue;
+            }
+
+            match self.store.get_tx(&dependency) {
+                Ok(dependency_tx) => to_visit.extend(dependency_tx.dispatch_options.depends_on),
+                Err(BitcoinCoordinatorStoreError::TransactionNotFound(_)) => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Ok(())
+    }
+
+    // A transaction replaced with a higher fee must signal RBF and have the output paying the extra fee.
+    fn validate_parent_rbf(
+        &self,
+        tx: &Transaction,
+        options: &DispatchOptions,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        if !options.allow_rbf_of_parent {
+            return Ok(());
+        }
+
+        if self.parent_tx_signer.is_none() {
+            return Err(BitcoinCoordinatorError::InvalidConfiguration(
+                "allow_rbf_of_parent requires a ParentTxSigner, set it with with_parent_tx_signer"
+                    .to_string(),
+            ));
+        }
+
+        let tx_id = tx.compute_txid();
+
+        if !tx.is_explicitly_rbf() {
+            return Err(BitcoinCoordinatorError::InvalidTransaction(
+                tx_id,
+                "allow_rbf_of_parent requires the transaction to signal RBF".to_string(),
+            ));
+        }
+
+        if parent_change_vout(tx, options).is_none() {
+            return Err(BitcoinCoordinatorError::InvalidTransaction(
+                tx_id,
+                format!(
+                    "parent_change_vout {} is not an output of the transaction",
+                    options
+                        .parent_change_vout
+                        .unwrap_or((tx.output.len() as u32).saturating_sub(1))
+                ),
+            ));
+        }
+
+        Ok(())
+    }
+
+    // Checks the transaction before saving it, asking the node if it would accept it when enabled in the settings.
+    // Returns why the speedup anchor is not standard when allow_nonstandard_anchor lets it through.
+    fn validate_tx(
+        &self,
+        tx: &Transaction,
+        speedup_data: Option<&SpeedupData>,
+    ) -> Result<Option<String>, BitcoinCoordinatorError> {
+        // The anchor is checked before the node, which would reject a dust anchor with a less precise reason.
+        let nonstandard_anchor = match speedup_data.map(|data| validate_anchor(tx, data)) {
+            Some(Err(
+                e @ (BitcoinCoordinatorError::AnchorBelowDust(..)
+                | BitcoinCoordinatorError::UnsupportedAnchorScript(..)),
+            )) if self.settings().allow_nonstandard_anchor => {
+                warn!(
+                    "{} Non standard speedup anchor for Transaction({}): {}",
+                    style("Coordinator").green(),
+                    style(tx.compute_txid()).yellow(),
+                    e
+                );
+                Some(e.to_string())
+            }
+            Some(Err(e)) => return Err(e),
+            _ => None,
+        };
+
+        validate_tx_to_dispatch(tx, speedup_data, self.settings().max_tx_weight, |tx| {
+            if !self.settings().test_mempool_accept {
+                return Ok(None);
+            }
+
+            self.node.test_mempool_accept(tx)
+        })?;
+
+        Ok(nonstandard_anchor)
+    }
+
+    // Returns true when every transaction paid by the speedup was cancelled, double spent or expired, so there is no reason to keep paying for it.
+    fn has_only_cancelled_parents(
+        &self,
+        speedup: &CoordinatedSpeedUpTransaction,
+    ) -> Result<bool, BitcoinCoordinatorError> {
+        if speedup.speedup_tx_data.is_empty() {
+            return Ok(false);
+        }
+
+        for parent in speedup.speedup_tx_data.iter() {
+            let tx = self.store.get_tx(&parent.tx_id)?;
+
+            if !matches!(
+                tx.state,
+                TransactionState::Cancelled | TransactionState::Failed | TransactionState::Expired
+            ) {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    // Follows the members of the groups of transactions, a GroupCompleted news is reported once none is pending.
+    // Runs before process_watched_finalities, which stops monitoring the members once they are finalized.
+    fn process_tx_groups(&self) -> Result<(), BitcoinCoordinatorError> {
+        for mut group in self.store.get_tx_groups()? {
+            if group.complete {
+                continue;
+            }
+
+            let mut changed = false;
+
+            for member in group
+                .members
+                .iter_mut()
+                .filter(|member| member.state == GroupMemberState::Pending)
+            {
+                let state =
+                    self.get_group_member_state(member.tx_id, group.finality_confirmations)?;
+
+                if state != GroupMemberState::Pending {
+                    member.state = state;
+                    changed = true;
+                }
+            }
+
+            // Members cancelled since the last tick complete the group too.
+            if group.pending_count() == 0 {
+                group.complete = true;
+
+                info!(
+                    "{} Group({}) completed | Members({})",
+                    style("Coordinator").green(),
+                    style(group.group_id).yellow(),
+                    group.members.len(),
+                );
+            } else if !changed {
+                continue;
+            }
+
+            self.store.save_tx_group(group.clone())?;
+
+            if group.complete {
+                self.update_news(CoordinatorNews::GroupCompleted(
+                    group.group_id,
+                    group.context,
+                ))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // A member dispatched by the coordinator follows the state of its transaction, the other members are
+    // finalized once the monitor reports them with the finality of the group.
+    fn get_group_member_state(
+        &self,
+        tx_id: Txid,
+        finality_confirmations: u32,
+    ) -> Result<GroupMemberState, BitcoinCoordinatorError> {
+        match self.store.get_tx(&tx_id) {
+            Ok(tx) => {
+                return Ok(match tx.state {
+                    TransactionState::Finalized => GroupMemberState::Finalized,
+                    TransactionState::Cancelled => GroupMemberState::Cancelled,
+                    _ => GroupMemberState::Pending,
+                })
+            }
+            Err(BitcoinCoordinatorStoreError::TransactionNotFound(_)) => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        match self.monitor.get_tx_status(&tx_id) {
+            Ok(tx_status) if tx_status.is_finalized(finality_confirmations) => {
+                Ok(GroupMemberState::Finalized)
+            }
+            Ok(_) | Err(MonitorError::TransactionNotFound(_)) => Ok(GroupMemberState::Pending),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    // Saves the members of a group monitored or dispatched together, under a new group id.
+    fn register_group(
+        &self,
+        tx_ids: Vec<Txid>,
+        context: String,
+        finality_confirmations: u32,
+    ) -> Result<Uuid, BitcoinCoordinatorError> {
+        let mut members: Vec<GroupMember> = Vec::new();
+        for tx_id in tx_ids {
+            if members.iter().all(|member| member.tx_id != tx_id) {
+                members.push(GroupMember {
+                    tx_id,
+                    state: GroupMemberState::Pending,
+                });
+            }
+        }
+
+        let group = GroupStatus {
+            group_id: Uuid::new_v4(),
+            context,
+            finality_confirmations,
+            members,
+            complete: false,
+        };
+        self.store.save_tx_group(group.clone())?;
+
+        info!(
+            "{} Group({}) registered | Members({})",
+            style("Coordinator").green(),
+            style(group.group_id).yellow(),
+            group.members.len(),
+        );
+
+        Ok(group.group_id)
+    }
+
+    // Stops monitoring the transactions monitored with a finality once they reach it.
+    fn process_watched_finalities(&self) -> Result<(), BitcoinCoordinatorError> {
+        for watch in self.store.get_watched_finalities()? {
+            let tx_status = match self.monitor.get_tx_status(&watch.tx_id) {
+                Ok(tx_status) => tx_status,
+                Err(MonitorError::TransactionNotFound(_)) => continue,
+                Err(e) => return Err(e.into()),
+            };
+
+            if !tx_status.is_finalized(watch.finality_confirmations) {
+                continue;
+            }
+
+            debug!(
+                "{} Monitored Transaction({}) finalized | Confirmations({})",
+                style("Coordinator").green(),
+                style(watch.tx_id).yellow(),
+                style(tx_status.confirmations).blue(),
+            );
+
+            self.monitor.cancel(TypesToMonitor::Transactions(
+                vec![watch.tx_id],
+                watch.context.clone(),
+                None,
+            ))?;
+            self.store.unwatch_finality(&watch.tx_id)?;
+        }
+
+        Ok(())
+    }
+
+    // Turns the spends of the watched outpoints reported by the monitor into coordinator news.
+    fn process_watched_outpoints(&self) -> Result<(), BitcoinCoordinatorError> {
+        let watched = self.store.get_watched_outpoints()?;
+
+        if watched.is_empty() {
+            return Ok(());
+        }
+
+        for news in self.monitor.get_news()? {
+            let (txid, vout, status, monitor_context) = match news {
+                MonitorNews::SpendingUTXOTransaction(txid, vout, status, context) => {
+                    (txid, vout, status, context)
+                }
+                _ => continue,
+            };
+
+            let outpoint = OutPoint::new(txid, vout);
+
+            let watch = match watched.iter().find(|watch| watch.outpoint == outpoint) {
+                Some(watch) => watch,
+                None => continue,
+            };
+
+            // The spend is reported once the spending transaction is mined.
+            let block_info = match status.block_info {
+                Some(block_info) => block_info,
+                None => continue,
+            };
+
+            let input_index = status
+                .tx
+                .input
+                .iter()
+                .position(|input| input.previous_output == outpoint)
+                .unwrap_or_default() as u32;
+
+            info!(
+                "{} Outpoint({}) spent by Transaction({}) | Input({}) | Block({})",
+                style("Coordinator").green(),
+                style(outpoint).yellow(),
+                style(status.tx_id).yellow(),
+                input_index,
+                block_info.height,
+            );
+
+            let news = CoordinatorNews::OutpointSpent(
+                outpoint,
+                status.tx_id,
+                input_index,
+                block_info,
+                watch.context.clone(),
+            );
+            self.update_news(news)?;
+
+            self.monitor
+                .ack_news(AckMonitorNews::SpendingUTXOTransaction(
+                    txid,
+                    vout,
+                    monitor_context,
+                ))?;
+        }
+
+        Ok(())
+    }
+
+    // Follows the spends of the speedup output of a dispatched transaction. Keyless anchors can be spent by anyone,
+    // and a CPFP spending an output already spent fails with missing inputs.
+    fn monitor_anchor(&self, tx: &CoordinatedTransaction) -> Result<(), BitcoinCoordinatorError> {
+        let Some(anchor) = tx.speedup_data.as_ref().and_then(speedup_outpoint) else {
+            return Ok(());
+        };
+
+        self.monitor
+            .monitor(TypesToMonitor::SpendingUTXOTransaction(
+                anchor.txid,
+                anchor.vout,
+                ANCHOR_SPEND_CONTEXT.to_string(),
+                None,
+            ))?;
+
+        Ok(())
+    }
+
+    // Stops speeding up the transactions whose speedup output was spent by a transaction that is not a speedup
+    // of the coordinator. If that transaction pays for the parent, the parent is confirmed as usual.
+    fn process_anchor_spends(&self) -> Result<(), BitcoinCoordinatorError> {
+        for news in self.monitor.get_news()? {
+            let (txid, vout, status) = match news {
+                MonitorNews::SpendingUTXOTransaction(txid, vout, status, context)
+                    if context == ANCHOR_SPEND_CONTEXT =>
+                {
+                    (txid, vout, status)
+                }
+                _ => continue,
+            };
+
+            let ack = AckMonitorNews::SpendingUTXOTransaction(
+                txid,
+                vout,
+                ANCHOR_SPEND_CONTEXT.to_string(),
+            );
+
+            // The spends of our speedups are expected, and an orphaned spend leaves the output unspent.
+            let is_own_speedup =
+                self.store.get_internal_monitor(&status.tx_id)? == Some(InternalMonitor::Speedup);
+
+            if is_own_speedup || status.is_orphan() {
+                self.monitor.ack_news(ack)?;
+                continue;
+            }
+
+            let tx = match self.store.get_tx(&txid) {
+                Ok(tx) => tx,
+                Err(BitcoinCoordinatorStoreError::TransactionNotFound(_)) => {
+                    self.monitor.ack_news(ack)?;
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            let anchor = OutPoint::new(txid, vout);
+
+            if tx.speedup_data.as_ref().and_then(speedup_outpoint) == Some(anchor) {
+                warn!(
+                    "{} Speedup output({}) of Transaction({}) spent by Transaction({}), it is not sped up anymore",
+                    style("Coordinator").green(),
+                    style(anchor).yellow(),
+                    style(txid).yellow(),
+                    style(status.tx_id).red(),
+                );
+
+                self.store.clear_tx_speedup(txid, status.tx_id)?;
+                self.update_news(CoordinatorNews::AnchorSpentExternally(
+                    txid,
+                    anchor,
+                    status.tx_id,
+                ))?;
+            }
+
+            self.monitor.ack_news(ack)?;
+        }
+
+        Ok(())
+    }
+
+    // Turns the spends of the members of the watched UTXO sets reported by the monitor into collateral news.
+    fn process_watched_utxo_sets(&self) -> Result<(), BitcoinCoordinatorError> {
+        let mut sets = self.store.get_watched_utxo_sets()?;
+
+        if sets.is_empty() {
+            return Ok(());
+        }
+
+        for news in self.monitor.get_news()? {
+            let (txid, vout, status, monitor_context) = match news {
+                MonitorNews::SpendingUTXOTransaction(txid, vout, status, context) => {
+                    (txid, vout, status, context)
+                }
+                _ => continue,
+            };
+
+            let outpoint = OutPoint::new(txid, vout);
+
+            let (set, member_index) = match sets.iter_mut().find_map(|set| {
+                let member_index = set
+                    .outpoints
+                    .iter()
+                    .position(|member| member.outpoint == outpoint)?;
+                (set.context == monitor_context).then_some((set, member_index))
+            }) {
+                Some(found) => found,
+                None => continue,
+            };
+
+            // The spend is reported once the spending transaction is mined.
+            let block_info = match status.block_info {
+                Some(block_info) if !block_info.is_orphan => block_info,
+                _ => continue,
+            };
+
+            let member = &mut set.outpoints[member_index];
+
+            // Later confirmations of the same spend are only acknowledged.
+            if member.spending_txid != Some(status.tx_id) {
+                member.spending_txid = Some(status.tx_id);
+
+                let unspent_count = set.unspent_count();
+
+                info!(
+                    "{} UTXO set {} | Outpoint({}) spent by Transaction({}) | Unspent({}) | Block({})",
+                    style("Coordinator").green(),
+                    style(&set.label).yellow(),
+                    style(outpoint).yellow(),
+                    style(status.tx_id).yellow(),
+                    unspent_count,
+                    block_info.height,
+                );
+
+                self.store.watch_utxo_set(set.clone())?;
+                self.update_news(CoordinatorNews::CollateralSpent(
+                    set.label.clone(),
+                    outpoint,
+                    status.tx_id,
+                    unspent_count,
+                ))?;
+
+                if unspent_count == 0 {
+                    self.update_news(CoordinatorNews::CollateralFullySpent(set.label.clone()))?;
+                }
+            }
+
+            self.monitor
+                .ack_news(AckMonitorNews::SpendingUTXOTransaction(
+                    txid,
+                    vout,
+                    monitor_context,
+                ))?;
+        }
+
+        Ok(())
+    }
+
+    // Reports the transactions of the current block paying to the watched output scripts.
+    // The monitor only tracks transactions by id, so the scripts are matched with the outputs of each block.
+    fn process_watched_addresses(&self) -> Result<(), BitcoinCoordinatorError> {
+        let watched = self.store.get_watched_addresses()?;
+
+        if watched.is_empty() {
+            return Ok(());
+        }
+
+        let block = match self.monitor.get_current_block()? {
+            Some(block) if !block.orphan => block,
+            _ => return Ok(()),
+        };
+
+        let block_info = BlockInfo {
+            height: block.height,
+            hash: block.hash,
+            is_orphan: false,
+        };
+
+        for tx in block.txs.iter() {
+            for watch in watched.iter() {
+                let outputs: Vec<(u32, u64)> = tx
+                    .output
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, output)| output.script_pubkey == watch.script_pubkey)
+                    .map(|(vout, output)| (vout as u32, output.value.to_sat()))
+                    .collect();
+
+                if outputs.is_empty() {
+                    continue;
+                }
+
+                info!(
+                    "{} Address funded by Transaction({}) | Outputs({}) | Block({})",
+                    style("Coordinator").green(),
+                    style(tx.compute_txid()).yellow(),
+                    outputs.len(),
+                    block.height,
+                );
+
+                let news = CoordinatorNews::AddressFunded(
+                    watch.script_pubkey.clone(),
+                    tx.clone(),
+                    outputs,
+                    block_info.clone(),
+                    watch.context.clone(),
+                );
+                self.update_news(news)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Records the peg-ins reported by the monitor, if the peg-in monitoring was registered.
+    fn process_rsk_pegins(&self) -> Result<(), BitcoinCoordinatorError> {
+        let context = match self.store.get_rsk_pegin_context()? {
+            Some(context) => context,
+            None => return Ok(()),
+        };
+
+        for pegin in record_detected_pegins(self.monitor.as_ref(), &self.store, &context)? {
+            info!(
+                "{} RSK peg-in detected | Transaction({}) | Output({}) | Amount({}) | Block({})",
+                style("Coordinator").green(),
+                style(pegin.tx_id).yellow(),
+                pegin.vout,
+                style(pegin.amount).blue(),
+                pegin.block_height,
+            );
+        }
+
+        Ok(())
+    }
+
+    fn get_monitor_news(
+        &self,
+    ) -> Result<impl Iterator<Item = MonitorNews>, BitcoinCoordinatorError> {
+        let list_monitor_news = self.monitor.get_news()?;
+        let mut watched = self.store.get_watched_outpoints()?;
+
+        // The spends of the members of the UTXO sets are reported as collateral news.
+        for set in self.store.get_watched_utxo_sets()? {
+            watched.extend(set.outpoints.iter().map(|member| WatchedOutpoint {
+                outpoint: member.outpoint,
+                context: set.context.clone(),
+            }));
+        }
+
+        let mut internal = HashMap::new();
+        for news in list_monitor_news.iter() {
+            if let MonitorNews::Transaction(txid, _, context_data) = news {
+                if let Some(monitor) = self.get_internal_monitor(txid, context_data)? {
+                    internal.insert(*txid, monitor);
+                }
+            }
+        }
+
+        Ok(filter_monitor_news(list_monitor_news, watched, internal))
+    }
+
+    // Monitors a transaction of the coordinator, registered so its news are not returned by get_news.
+    fn monitor_internal_tx(
+        &self,
+        tx_id: Txid,
+        monitor: InternalMonitor,
     ) -> Result<(), BitcoinCoordinatorError> {
-        // Attempt to dispatch as many transactions as possible in a single CPFP (Child Pays For Parent) transaction,
-        // while ensuring the resulting transaction does not exceed Bitcoin's standardness limits.
-        // We have two policies to dispatch the transactions:
-        // 1. Maximum transaction size: MAX_TX_WEIGHT weight units. Exceeding these limits will result in the transaction
-        // being considered non-standard and rejected by most mempools.
-        // 2. Maximum number of unconfirmed transactions is 25 (MAX_LIMIT_UNCONFIRMED_PARENTS)
-        // If the set of transactions exceeds these limits, will fail the dispatch.
+        self.store.register_internal_monitor(tx_id, monitor)?;
+        self.monitor.monitor(TypesToMonitor::Transactions(
+            vec![tx_id],
+            monitor.context().to_string(),
+            None,
+        ))?;
 
-        let txs_in_batch_by_policies: Vec<Vec<CoordinatedTransaction>> =
-            self.batch_txs_by_weight_limit(txs)?;
+        Ok(())
+    }
 
-        for txs_batch in txs_in_batch_by_policies {
-            // For each batch, attempt to broadcast all transactions individually. After determining which transactions were successfully sent,
-            // construct and broadcast a single CPFP transaction to pay for the entire batch.
-            let txs_sent: Vec<CoordinatedTransaction> = self.dispatch_txs(txs_batch)?;
+    // Internal monitor of a transaction news, matched by txid and exact context.
+    // Stores written before the registry existed have no entry, their speedups and pending top-up are recognized instead.
+    fn get_internal_monitor(
+        &self,
+        txid: &Txid,
+        context_data: &str,
+    ) -> Result<Option<InternalMonitor>, BitcoinCoordinatorError> {
+        if context_data != CPFP_TRANSACTION_CONTEXT && context_data != FUNDING_TRANSACTION_CONTEXT {
+            return Ok(None);
+        }
+
+        if let Some(monitor) = self.store.get_internal_monitor(txid)? {
+            return Ok(Some(monitor));
+        }
+
+        if context_data == CPFP_TRANSACTION_CONTEXT && self.store.get_speedup(txid).is_ok() {
+            return Ok(Some(InternalMonitor::Speedup));
+        }
+
+        let is_topup = context_data == FUNDING_TRANSACTION_CONTEXT
+            && self
+                .store
+                .get_pending_funding_topup()?
+                .is_some_and(|topup| topup.txid == *txid);
+
+        Ok(is_topup.then_some(InternalMonitor::FundingTopUp))
+    }
+
+    // Sends to each subscriber the news not delivered to it yet. A subscriber whose channel is full gets them
+    // on a later tick, and a subscriber that dropped its receiver is removed, its cursor is kept.
+    fn deliver_news(&self) -> Result<(), BitcoinCoordinatorError> {
+        if self.news_subscribers.borrow().is_empty() {
+            return Ok(());
+        }
+
+        let news = self.get_news()?;
+        let mut disconnected = Vec::new();
+
+        for subscriber in self.news_subscribers.borrow().iter() {
+            let delivered = self.store.get_delivered_news(&subscriber.id)?;
+            let (undelivered, fingerprints) = undelivered_news(&news, &delivered);
+
+            // Acknowledged news leave the cursor, get_news does not return them anymore.
+            let mut cursor: Vec<String> = fingerprints
+                .iter()
+                .filter(|fingerprint| delivered.contains(fingerprint))
+                .cloned()
+                .collect();
+
+            if !undelivered.is_empty() {
+                match subscriber.sender.try_send(undelivered) {
+                    Ok(()) => cursor = fingerprints,
+                    Err(TrySendError::Full(_)) => {
+                        warn!(
+                            "{} News subscriber {} is not keeping up, its news are sent on a later tick",
+                            style("Coordinator").green(),
+                            style(&subscriber.id).yellow(),
+                        );
+                    }
+                    Err(TrySendError::Disconnected(_)) => {
+                        disconnected.push(subscriber.id.clone());
+                    }
+                }
+            }
+
+            if cursor != delivered {
+                self.store.set_delivered_news(&subscriber.id, cursor)?;
+            }
+        }
+
+        self.news_subscribers
+            .borrow_mut()
+            .retain(|subscriber| !disconnected.contains(&subscriber.id));
+
+        Ok(())
+    }
+
+    // Hands the finalized sink the entries of the finalized log it failed to take, then the new ones.
+    // A failing sink does not fail the tick, only the entries it failed to take are handed again.
+    fn deliver_finalized(&self) -> Result<(), BitcoinCoordinatorError> {
+        let Some(sink) = &self.finalized_sink else {
+            return Ok(());
+        };
+
+        let delivery = self.store.get_finalized_delivery()?;
+        let mut entries = Vec::new();
+        for seq in delivery.failed.iter() {
+            entries.extend(
+                self.store
+                    .read_finalized(*seq, 1)?
+                    .into_iter()
+                    .filter(|entry| entry.seq == *seq),
+            );
+        }
+        entries.extend(
+            self.store
+                .read_finalized(delivery.next_seq, FINALIZED_DELIVERY_PAGE_SIZE)?,
+        );
 
-            // Only create a CPFP (Child Pays For Parent) transaction if there are transactions that were successfully sent in this batch.
-            // If no transactions were sent, skip CPFP creation for this batch.
-            if !txs_sent.is_empty() {
-                info!(
-                    "{} Sending batch of {} transactions",
+        let mut next_delivery = FinalizedDelivery {
+            next_seq: delivery.next_seq,
+            failed: Vec::new(),
+        };
+
+        for entry in entries {
+            if let Err(e) = sink.deliver(&entry) {
+                warn!(
+                    "{} Finalized Transaction({}) not taken by the sink, handing it again on the next tick: {}",
                     style("Coordinator").green(),
-                    txs_sent.len()
+                    style(entry.tx_id).yellow(),
+                    e
                 );
-
-                let txs_data = txs_sent
-                    .iter()
-                    .map(|coordinated_tx| {
-                        (
-                            coordinated_tx.speedup_data.clone().unwrap(),
-                            coordinated_tx.tx.clone(),
-                            coordinated_tx.context.clone(),
-                        )
-                    })
-                    .collect();
-                // Up to here we have funding and we are sure we have funding.
-                let funding = self.store.get_funding()?.unwrap();
-                self.create_and_send_cpfp_tx(
-                    txs_data,
-                    funding,
-                    self.settings.base_fee_multiplier,
-                    None,
-                    None,
-                )?;
+                next_delivery.failed.push(entry.seq);
             }
+
+            next_delivery.next_seq = next_delivery.next_seq.max(entry.seq + 1);
+        }
+
+        if next_delivery != delivery {
+            self.store.set_finalized_delivery(&next_delivery)?;
         }
 
         Ok(())
     }
+}
 
-    fn notify_funding_not_found(&self) -> Result<(), BitcoinCoordinatorError> {
-        let news = CoordinatorNews::FundingNotFound;
-        self.update_news(news)?;
-        Ok(())
+// A coordinator dropped by a process stopping normally hands the store over right away. A process that dies keeps
+// the store owned until its heartbeat is stale.
+impl Drop for BitcoinCoordinator {
+    fn drop(&mut self) {
+        if let Err(e) = self.store.release_ownership(self.instance_id) {
+            warn!(
+                "{} Store ownership not released: {}",
+                style("Coordinator").green(),
+                e
+            );
+        }
     }
+}
 
-    fn update_news(&self, news: CoordinatorNews) -> Result<(), BitcoinCoordinatorError> {
-        let current_block = self.monitor.get_current_block()?;
+impl BitcoinCoordinatorApi for BitcoinCoordinator {
+    fn tick(&self) -> Result<(), BitcoinCoordinatorError> {
+        self.check_running()?;
+        // The heartbeat keeps other instances from taking the store over while this one is alive.
+        self.store.refresh_ownership(self.instance_id)?;
+        let started_at = Instant::now();
 
-        if let Some(current_block) = current_block {
-            self.store.update_news(news, current_block.hash)?;
+        // While the node is unreachable the tick only probes it, nothing is processed until it answers.
+        if self.node_breaker.is_open() && !self.probe_node()? {
+            return Ok(());
         }
 
-        Ok(())
-    }
+        let result = self.run_tick(started_at);
 
-    // This function is designed to expedite a CPFP (Child Pays For Parent) transaction.
-    // It achieves this by creating an additional CPFP transaction to provide further funding to the previous one.
-    // It is ensured that funding is available before invoking this function.
-    fn speedup_cpfp_tx(&self) -> Result<(), BitcoinCoordinatorError> {
-        let funding = self.store.get_funding()?.unwrap();
+        // A tick stopped by a connection error counts as a failure reaching the node.
+        // Once the circuit breaker opens the error is reported with the NodeUnreachable news instead.
+        if let Err(e) = &result {
+            if e.is_node_unreachable() {
+                self.record_node_failure();
+            }
+        }
 
-        let last_speedup = self.store.get_last_speedup()?;
+        self.report_node_unreachable()?;
 
-        if let Some((speedup, _)) = last_speedup {
-            let bump_fee_percentage =
-                self.get_bump_fee_percentage_strategy(speedup.bump_fee_percentage_used)?;
+        // The news are pushed also when the tick failed, like the NodeUnreachable news.
+        self.deliver_news()?;
+        self.deliver_finalized()?;
 
-            info!(
-                "{} Boosting CPFP Transaction({})",
-                style("Coordinator").green(),
-                style(speedup.tx_id).yellow()
-            );
-            self.create_and_send_cpfp_tx(vec![], funding, bump_fee_percentage, None, None)?;
+        if self.node_breaker.is_open() {
+            return Ok(());
         }
 
-        Ok(())
+        result
     }
 
-    fn inform_dispatch_speedup_error(
+    fn monitor(&self, data: TypesToMonitor) -> Result<(), BitcoinCoordinatorError> {
+        self.monitor_with_options(data, None)
+    }
+
+    fn monitor_with_options(
         &self,
-        txs_info: (Vec<Txid>, Vec<String>),
-        speedup_type: String,
-        is_retry_tx: bool,
-        speedup_tx_id: Txid,
-        tx: Transaction,
-        dispatch_error: String,
+        data: TypesToMonitor,
+        finality_confirmations: Option<u32>,
     ) -> Result<(), BitcoinCoordinatorError> {
-        if is_retry_tx {
-            warn!(
-                "{} Error Resending {} Transaction({}) | IsRetryTx",
-                style("Coordinator").green(),
-                speedup_type,
-                style(speedup_tx_id).yellow(),
-            );
-        } else {
-            error!(
-                "{} Error Sending  {} Transaction({})",
-                style("Coordinator").green(),
-                speedup_type,
-                style(speedup_tx_id).yellow(),
-            );
-            debug!("Transaction Details: {:?}", style(&tx).magenta());
+        self.check_running()?;
+        self.check_ownership()?;
+
+        if let TypesToMonitor::Transactions(txs, _, _) = data.clone() {
+            if txs.is_empty() {
+                return Err(BitcoinCoordinatorError::BitcoinCoordinatorError(
+                    "transactions array is empty".to_string(),
+                ));
+            }
         }
 
-        let news = CoordinatorNews::DispatchSpeedUpError(
-            txs_info.0,
-            txs_info.1,
-            speedup_tx_id,
-            dispatch_error,
-        );
+        if let Some(finality_confirmations) = finality_confirmations {
+            self.validate_finality_confirmations(finality_confirmations)?;
 
-        self.update_news(news)?;
+            if !matches!(data, TypesToMonitor::Transactions(..)) {
+                return Err(BitcoinCoordinatorError::InvalidConfiguration(
+                    "finality_confirmations can only be set for transactions".to_string(),
+                ));
+            }
+        }
+
+        if let TypesToMonitor::Transactions(_, context, _)
+        | TypesToMonitor::SpendingUTXOTransaction(_, _, context, _) = &data
+        {
+            validate_context(context)?;
+        }
+
+        // New blocks are reported by the coordinator from the monitor height, the subscription is persisted.
+        if data == TypesToMonitor::NewBlock {
+            self.store.set_new_block_subscription(true)?;
+            return Ok(());
+        }
+
+        self.monitor.monitor(data.clone())?;
+
+        if let (TypesToMonitor::Transactions(txs, context, _), Some(finality_confirmations)) =
+            (data, finality_confirmations)
+        {
+            for tx_id in txs {
+                self.store.watch_finality(WatchedFinality {
+                    tx_id,
+                    context: context.clone(),
+                    finality_confirmations,
+                })?;
+            }
+        }
 
         Ok(())
     }
 
-    fn dispatch_speedup(
+    fn is_ready(&self) -> Result<bool, BitcoinCoordinatorError> {
+        Ok(self.readiness()?.ready)
+    }
+
+    fn readiness(&self) -> Result<ReadinessReport, BitcoinCoordinatorError> {
+        // The coordinator is currently considered ready when the monitor is ready.
+        let has_pending_txs = self
+            .store
+            .get_txs_in_progress()?
+            .iter()
+            .any(|tx| tx.state == TransactionState::ToDispatch);
+
+        readiness_report(
+            self.monitor.as_ref(),
+            self.client.as_ref(),
+            has_pending_txs,
+            self.node_breaker.unreachable_since(),
+        )
+    }
+
+    fn sync_to_tip(
         &self,
-        tx: Transaction,
-        speedup_data: CoordinatedSpeedUpTransaction,
-        retry_txid: Option<Txid>,
+        progress: Option<&dyn Fn(BlockHeight, BlockHeight)>,
     ) -> Result<(), BitcoinCoordinatorError> {
-        let speedup_type = speedup_data.get_tx_name();
+        self.check_ownership()?;
+
+        let max_stalled_ticks = self.settings().max_sync_stalled_ticks;
+
+        sync_to_tip(
+            self.monitor.as_ref(),
+            self.client.as_ref(),
+            max_stalled_ticks,
+            progress,
+        )?;
 
         info!(
-            "{} Send {} Transaction({})",
+            "{} Synced to the node tip at height {}",
             style("Coordinator").green(),
-            speedup_type,
-            style(speedup_data.tx_id).yellow(),
+            style(self.monitor.get_monitor_height()?).blue(),
         );
 
-        let txs_info: (Vec<Txid>, Vec<String>) = speedup_data
-            .speedup_tx_data
-            .iter()
-            .map(|(_, tx, context)| (tx.compute_txid(), context.clone()))
-            .collect();
+        Ok(())
+    }
 
-        let dispatch_result = self.client.send_transaction(&tx);
+    fn dispatch(
+        &self,
+        tx: Transaction,
+        speedup_data: Option<SpeedupData>,
+        context: String,
+        target_block_height: Option<BlockHeight>,
+        number_confirmation_trigger: Option<u32>,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        self.dispatch_with_options(
+            tx,
+            speedup_data,
+            context,
+            target_block_height,
+            number_confirmation_trigger,
+            DispatchOptions::default(),
+        )
+    }
 
-        match dispatch_result {
-            Ok(_) => {
-                let dispatch_block = self.client.get_best_block()?;
+    fn dispatch_with_options(
+        &self,
+        tx: Transaction,
+        speedup_data: Option<SpeedupData>,
+        context: String,
+        target_block_height: Option<BlockHeight>,
+        number_confirmation_trigger: Option<u32>,
+        options: DispatchOptions,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        self.check_running()?;
+        self.check_ownership()?;
+        self.check_active(options.queue_only)?;
+        validate_context(&context)?;
+        self.validate_dispatch_options(&options)?;
+        let nonstandard_anchor = self.validate_tx(&tx, speedup_data.as_ref())?;
+        self.validate_parent_rbf(&tx, &options)?;
+        self.validate_dependencies(tx.compute_txid(), &options)?;
+
+        // A consumer retrying a dispatch must not reset the state of the transaction.
+        if self.is_already_dispatched(tx.compute_txid())? {
+            if options.allow_duplicate {
+                debug!(
+                    "{} Transaction({}) already dispatched, ignoring duplicate dispatch",
+                    style("Coordinator").green(),
+                    style(tx.compute_txid()).yellow()
+                );
 
-                // Update broadcast_block_height with the block where the transaction was dispatched
-                let mut speedup_data_with_block = speedup_data;
-                speedup_data_with_block.broadcast_block_height = dispatch_block;
+                return Ok(());
+            }
 
-                self.monitor.monitor(TypesToMonitor::Transactions(
-                    vec![speedup_data_with_block.tx_id],
-                    CPFP_TRANSACTION_CONTEXT.to_string(),
-                    None,
-                ))?;
+            return Err(BitcoinCoordinatorError::AlreadyDispatched(
+                tx.compute_txid(),
+            ));
+        }
 
-                info!(
-                    "{} Successfully sent {} Transaction({}) dispatched at block height {}",
-                    style("Coordinator").green(),
-                    speedup_type,
-                    style(speedup_data_with_block.tx_id).yellow(),
-                    style(dispatch_block).blue(),
-                );
+        let to_monitor = TypesToMonitor::Transactions(
+            vec![tx.compute_txid()],
+            context.clone(),
+            number_confirmation_trigger,
+        );
+        self.monitor.monitor(to_monitor)?;
 
-                self.store.save_speedup(speedup_data_with_block)?;
+        // Save the transaction to be dispatched.
+        self.store.save_tx_with_options(
+            tx.clone(),
+            speedup_data,
+            target_block_height,
+            context.clone(),
+            options,
+        )?;
 
-                if let Some(retry_txid) = retry_txid {
-                    self.store.dequeue_speedup_for_retry(retry_txid)?;
-                }
-            }
-            Err(e) => {
-                let error_msg = e.to_string();
-                let error_kind = BitcoinBroadcastErrorKind::from_error_message(&error_msg);
+        if let Some(reason) = nonstandard_anchor {
+            self.update_news(CoordinatorNews::NonStandardAnchor(
+                tx.compute_txid(),
+                context,
+                reason,
+            ))?;
+        }
 
-                match error_kind {
-                    BitcoinBroadcastErrorKind::AlreadyKnown => {
-                        // The speedup transaction is already known by the node (mempool or blockchain),
-                        // So we just acknowledge it, and warn the user.
-                        warn!(
-                            "{} {} Transaction({}) already known by node: {}",
-                            style("Coordinator").green(),
-                            speedup_type,
-                            style(speedup_data.tx_id).yellow(),
-                            error_msg
-                        );
+        info!(
+            "{} Mark Transaction({}) to dispatch",
+            style("Coordinator").green(),
+            style(tx.compute_txid()).yellow()
+        );
 
-                        let dispatch_block = self.client.get_best_block()?;
+        Ok(())
+    }
 
-                        let mut speedup_data_with_block = speedup_data;
-                        speedup_data_with_block.broadcast_block_height = dispatch_block;
+    fn dispatch_batch(
+        &self,
+        txs: Vec<(Transaction, Option<SpeedupData>, String)>,
+        target_block_height: Option<BlockHeight>,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        self.check_running()?;
+        self.check_ownership()?;
+        self.check_active(false)?;
+
+        let mut nonstandard_anchors = Vec::new();
+        for (tx, speedup_data, context) in txs.iter() {
+            validate_context(context)?;
+            if let Some(reason) = self.validate_tx(tx, speedup_data.as_ref())? {
+                nonstandard_anchors.push(CoordinatorNews::NonStandardAnchor(
+                    tx.compute_txid(),
+                    context.clone(),
+                    reason,
+                ));
+            }
 
-                        self.monitor.monitor(TypesToMonitor::Transactions(
-                            vec![speedup_data_with_block.tx_id],
-                            CPFP_TRANSACTION_CONTEXT.to_string(),
-                            None,
-                        ))?;
+            if self.is_already_dispatched(tx.compute_txid())? {
+                return Err(BitcoinCoordinatorError::AlreadyDispatched(
+                    tx.compute_txid(),
+                ));
+            }
+        }
 
-                        // Treat as success: persist the speedup so it can be tracked/confirmed/finalized.
-                        self.store.save_speedup(speedup_data_with_block)?;
+        // Group the txids by context, keeping the batch order, so each context is monitored in a single call.
+        let mut to_monitor: Vec<(String, Vec<Txid>)> = Vec::new();
+        for (tx, _, context) in txs.iter() {
+            let tx_id = tx.compute_txid();
+            match to_monitor.iter_mut().find(|(ctx, _)| ctx == context) {
+                Some((_, tx_ids)) => tx_ids.push(tx_id),
+                None => to_monitor.push((context.clone(), vec![tx_id])),
+            }
+        }
 
-                        if let Some(retry_txid) = retry_txid {
-                            self.store.dequeue_speedup_for_retry(retry_txid)?;
-                        }
-                    }
-                    BitcoinBroadcastErrorKind::MempoolRejection
-                    | BitcoinBroadcastErrorKind::NetworkError => {
-                        // Retryable errors (mempool policy / infrastructure).
-                        // If we reach here it's because:
-                        // - this is the first attempt (no `retry_txid`), or
-                        // - the entry came from `get_speedups_for_retry`, which already respected max_retries and intervals.
-                        self.inform_dispatch_speedup_error(
-                            txs_info.clone(),
-                            speedup_type.clone(),
-                            retry_txid.is_some(),
-                            speedup_data.tx_id,
-                            tx.clone(),
-                            error_msg,
-                        )?;
+        // Save all the transactions to be dispatched. Empty batches and duplicated txids are rejected here.
+        self.store.save_txs(txs, target_block_height)?;
 
-                        if retry_txid.is_some() {
-                            // Increment the retry counter for an already enqueued entry.
-                            self.store
-                                .increment_speedup_retry_count(speedup_data.tx_id)?;
-                        } else {
-                            // First failure: enqueue for retry with retry_count = 0.
-                            self.store.enqueue_speedup_for_retry(speedup_data)?;
-                        }
-                    }
-                    BitcoinBroadcastErrorKind::Other => {
-                        // Non-retryable error (malformed transaction, invalid inputs, etc.)
-                        // Don't retry, just report the error
-                        error!(
-                            "{} Fatal error sending {} Transaction({}): {} (not retrying)",
+        for (index, (context, tx_ids)) in to_monitor.iter().enumerate() {
+            let data = TypesToMonitor::Transactions(tx_ids.clone(), context.clone(), None);
+
+            if let Err(e) = self.monitor.monitor(data) {
+                // Stop monitoring the contexts already registered, the batch is not dispatched at all.
+                for (context, tx_ids) in to_monitor[..index].iter() {
+                    let data = TypesToMonitor::Transactions(tx_ids.clone(), context.clone(), None);
+
+                    if let Err(cancel_error) = self.monitor.cancel(data) {
+                        warn!(
+                            "{} Failed to cancel the monitor of context {} | Error: {}",
                             style("Coordinator").green(),
-                            speedup_type,
-                            style(speedup_data.tx_id).yellow(),
-                            error_msg
+                            style(context).red(),
+                            style(cancel_error).red()
                         );
+                    }
+                }
+
+                // Remove the batch from the store, so it is not dispatched without being monitored.
+                for tx_id in to_monitor.iter().flat_map(|(_, tx_ids)| tx_ids) {
+                    self.store.remove_tx(*tx_id)?;
+                }
+                return Err(e.into());
+            }
+        }
+
+        for tx_id in to_monitor.iter().flat_map(|(_, tx_ids)| tx_ids) {
+            info!(
+                "{} Mark Transaction({}) to dispatch",
+                style("Coordinator").green(),
+                style(tx_id).yellow()
+            );
+        }
 
-                        self.inform_dispatch_speedup_error(
-                            txs_info.clone(),
-                            speedup_type.clone(),
-                            retry_txid.is_some(),
-                            speedup_data.tx_id,
-                            tx.clone(),
-                            error_msg,
-                        )?;
+        for news in nonstandard_anchors {
+            self.update_news(news)?;
+        }
 
-                        // Remove from retry queue if it was there
-                        if let Some(retry_txid) = retry_txid {
-                            self.store.dequeue_speedup_for_retry(retry_txid)?;
-                        }
-                    }
+        Ok(())
+    }
+
+    fn cancel(&self, data: TypesToMonitor) -> Result<(), BitcoinCoordinatorError> {
+        self.check_ownership()?;
+
+        if data == TypesToMonitor::NewBlock {
+            self.store.set_new_block_subscription(false)?;
+            return Ok(());
+        }
+
+        self.monitor.cancel(data.clone())?;
+
+        match data {
+            TypesToMonitor::Transactions(txs, _, _) => {
+                self.store.cancel_group_members(&txs)?;
+
+                for tx in txs {
+                    self.store.remove_tx(tx)?;
+                    self.store.unwatch_finality(&tx)?;
                 }
             }
+            TypesToMonitor::SpendingUTXOTransaction(txid, vout, _, _) => {
+                self.store.unwatch_outpoint(OutPoint::new(txid, vout))?;
+            }
+            _ => {}
         }
 
         Ok(())
     }
 
-    fn dispatch_txs(
+    fn watch_outpoint(
         &self,
-        txs: Vec<CoordinatedTransaction>,
-    ) -> Result<Vec<CoordinatedTransaction>, BitcoinCoordinatorError> {
-        let mut txs_sent = Vec::new();
+        outpoint: OutPoint,
+        context: String,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        self.check_running()?;
+        self.check_ownership()?;
+        validate_context(&context)?;
 
-        for tx in txs {
-            info!(
-                "{} Sending Transaction({})",
-                style("Coordinator").green(),
-                style(tx.tx_id).yellow(),
-            );
+        self.monitor
+            .monitor(TypesToMonitor::SpendingUTXOTransaction(
+                outpoint.txid,
+                outpoint.vout,
+                context.clone(),
+                None,
+            ))?;
 
-            let dispatch_result = self.client.send_transaction(&tx.tx);
+        self.store.watch_outpoint(outpoint, context)?;
 
-            match dispatch_result {
-                Ok(_) => {
-                    let dispatch_block = self.client.get_best_block()?;
+        info!(
+            "{} Watch Outpoint({})",
+            style("Coordinator").green(),
+            style(outpoint).yellow()
+        );
 
-                    info!(
-                        "{} Transaction({}) dispatched at block height {}",
-                        style("Coordinator").green(),
-                        style(tx.tx_id).yellow(),
-                        style(dispatch_block).blue(),
-                    );
+        Ok(())
+    }
 
-                    self.store
-                        .update_tx_to_dispatched(tx.tx_id, dispatch_block)?;
+    fn monitor_address(
+        &self,
+        script_pubkey: ScriptBuf,
+        context: String,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        self.check_running()?;
+        self.check_ownership()?;
+        validate_context(&context)?;
 
-                    txs_sent.push(tx);
-                }
-                Err(e) => {
-                    let error_msg = e.to_string();
+        info!(
+            "{} Watch Address({})",
+            style("Coordinator").green(),
+            style(&script_pubkey).yellow()
+        );
 
-                    error!(
-                        "{} Error Sending Transaction({}): {}",
-                        style("Coordinator").green(),
-                        style(tx.tx_id).blue(),
-                        error_msg
-                    );
+        self.store.watch_address(script_pubkey, context)?;
+
+        Ok(())
+    }
 
-                    let error_kind = BitcoinBroadcastErrorKind::from_error_message(&error_msg);
+    fn cancel_monitor_address(
+        &self,
+        script_pubkey: &ScriptBuf,
+    ) -> Result<bool, BitcoinCoordinatorError> {
+        self.check_ownership()?;
 
-                    let (news, should_push_to_sent) = match error_kind {
-                        BitcoinBroadcastErrorKind::AlreadyKnown => {
-                            let deliver_block_height = self.monitor.get_monitor_height()?;
+        Ok(self.store.unwatch_address(script_pubkey)?)
+    }
 
-                            self.store
-                                .update_tx_to_dispatched(tx.tx_id, deliver_block_height)?;
-
-                            // The transaction is already in mempool or blockchain, so we acknowledge it.
-                            let news = CoordinatorNews::TransactionAlreadyInMempool(
-                                tx.tx_id,
-                                tx.context.clone(),
-                            );
-                            (news, true)
-                        }
-                        BitcoinBroadcastErrorKind::MempoolRejection => {
-                            self.store.increment_tx_retry_count(tx.tx_id)?;
-                            let news = CoordinatorNews::MempoolRejection(
-                                tx.tx_id,
-                                tx.context.clone(),
-                                error_msg,
-                            );
-                            (news, false)
-                        }
-                        BitcoinBroadcastErrorKind::NetworkError => {
-                            // Infra error
-                            self.store.increment_tx_retry_count(tx.tx_id)?;
-                            let news = CoordinatorNews::NetworkError(
-                                tx.tx_id,
-                                tx.context.clone(),
-                                error_msg,
-                            );
-                            (news, false)
-                        }
-                        BitcoinBroadcastErrorKind::Other => {
-                            // Unknown error
-                            self.store
-                                .update_tx_state(tx.tx_id, TransactionState::Failed)?;
-                            let news = CoordinatorNews::DispatchTransactionError(
-                                tx.tx_id,
-                                tx.context.clone(),
-                                error_msg,
-                            );
-                            (news, false)
-                        }
-                    };
+    fn monitor_utxo_set(
+        &self,
+        label: String,
+        outpoints: Vec<OutPoint>,
+        context: String,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        self.check_running()?;
+        self.check_ownership()?;
+        validate_context(&context)?;
+
+        if label.trim().is_empty() {
+            return Err(BitcoinCoordinatorError::InvalidArgument(
+                "the label of a UTXO set can not be empty".to_string(),
+            ));
+        }
 
-                    self.update_news(news)?;
-                    if should_push_to_sent {
-                        txs_sent.push(tx);
-                    }
-                }
+        if outpoints.is_empty() {
+            return Err(BitcoinCoordinatorError::InvalidArgument(format!(
+                "UTXO set {label} has no outpoints"
+            )));
+        }
+
+        if self
+            .store
+            .get_watched_utxo_sets()?
+            .iter()
+            .any(|set| set.label == label)
+        {
+            return Err(BitcoinCoordinatorError::UtxoSetAlreadyWatched(label));
+        }
+
+        let mut members: Vec<UtxoSetMember> = Vec::new();
+        for outpoint in outpoints {
+            if members.iter().all(|member| member.outpoint != outpoint) {
+                members.push(UtxoSetMember {
+                    outpoint,
+                    spending_txid: None,
+                });
             }
         }
 
-        Ok(txs_sent)
-    }
+        let set = WatchedUtxoSet {
+            label,
+            context,
+            outpoints: members,
+        };
 
-    fn batch_txs_by_weight_limit(
-        &self,
-        txs: Vec<CoordinatedTransaction>,
-    ) -> Result<Vec<Vec<CoordinatedTransaction>>, BitcoinCoordinatorError> {
-        // Define the maximum total weight allowed per batch of transactions.
+        monitor_utxo_set_members(self.monitor.as_ref(), &set)?;
+        self.store.watch_utxo_set(set.clone())?;
 
-        let mut batches = Vec::new();
-        let mut current_batch = Vec::new();
-        let mut current_weight = 0;
-        let mut allow_unconfirmed_txs = self.store.get_available_unconfirmed_txs()?;
+        info!(
+            "{} Watch UTXO set {} | Outpoints({})",
+            style("Coordinator").green(),
+            style(&set.label).yellow(),
+            set.outpoints.len(),
+        );
 
-        for tx_data in txs {
-            let weight = tx_data.tx.weight().to_wu();
+        Ok(())
+    }
 
-            if weight > self.settings.max_tx_weight {
-                return Err(BitcoinCoordinatorError::TransactionTooHeavy(
-                    tx_data.tx_id.to_string(),
-                    weight,
-                    self.settings.max_tx_weight,
-                ));
-            }
+    fn get_utxo_set_status(&self, label: &str) -> Result<WatchedUtxoSet, BitcoinCoordinatorError> {
+        self.store
+            .get_watched_utxo_sets()?
+            .into_iter()
+            .find(|set| set.label == label)
+            .ok_or_else(|| BitcoinCoordinatorError::UnknownUtxoSet(label.to_string()))
+    }
 
-            // When adding this transaction, we're extending the mempool ancestry chain: the new CPFP (Child Pays For Parent) transaction becomes,
-            // for example, the 26th ancestor in the mempool's view.
-            // Therefore, we must decrement the available unconfirmed CPFP slots,
-            // since each batch will require a new CPFP transaction and further extend the ancestry chain.
-            if allow_unconfirmed_txs - 1 > 0 {
-                allow_unconfirmed_txs -= 1;
-            } else {
-                batches.push(current_batch);
-                // Up to here we have reached the limit of unconfirmed txs. We can't dispatch more txs.
-                return Ok(batches);
-            }
+    fn cancel_utxo_set(&self, label: &str) -> Result<bool, BitcoinCoordinatorError> {
+        self.check_ownership()?;
 
-            if current_weight + weight > self.settings.max_tx_weight {
-                batches.push(current_batch);
-                current_batch = Vec::new();
-                current_weight = 0;
-            }
+        let set = match self.store.unwatch_utxo_set(label)? {
+            Some(set) => set,
+            None => return Ok(false),
+        };
 
-            current_batch.push(tx_data);
-            current_weight += weight;
+        for member in set.outpoints.iter() {
+            self.monitor
+                .cancel(TypesToMonitor::SpendingUTXOTransaction(
+                    member.outpoint.txid,
+                    member.outpoint.vout,
+                    set.context.clone(),
+                    None,
+                ))?;
         }
 
-        if !current_batch.is_empty() {
-            batches.push(current_batch);
-        }
+        info!(
+            "{} Stop watching UTXO set {}",
+            style("Coordinator").green(),
+            style(label).yellow(),
+        );
 
-        Ok(batches)
+        Ok(true)
     }
 
-    fn process_failed_speedups(&self) -> Result<(), BitcoinCoordinatorError> {
-        let failed_speedups = self.store.get_speedups_for_retry(
-            self.settings.retry_attempts_sending_tx,
-            self.settings.retry_interval_seconds,
+    fn monitor_group(
+        &self,
+        tx_ids: Vec<Txid>,
+        context: String,
+        finality_confirmations: Option<u32>,
+    ) -> Result<Uuid, BitcoinCoordinatorError> {
+        self.monitor_with_options(
+            TypesToMonitor::Transactions(tx_ids.clone(), context.clone(), None),
+            finality_confirmations,
         )?;
 
-        for speedup in failed_speedups {
-            let can_speedup = self.store.can_speedup()?;
+        let finality_confirmations = finality_confirmations.unwrap_or(
+            self.settings()
+                .monitor_settings
+                .max_monitoring_confirmations,
+        );
 
-            if !can_speedup {
-                return Ok(());
-            }
+        self.register_group(tx_ids, context, finality_confirmations)
+    }
 
-            let funding = self.store.get_funding()?.unwrap();
+    fn dispatch_group(
+        &self,
+        txs: Vec<(Transaction, Option<SpeedupData>)>,
+        context: String,
+        block_height: Option<BlockHeight>,
+    ) -> Result<Uuid, BitcoinCoordinatorError> {
+        let tx_ids: Vec<Txid> = txs.iter().map(|(tx, _)| tx.compute_txid()).collect();
+
+        self.dispatch_batch(
+            txs.into_iter()
+                .map(|(tx, speedup_data)| (tx, speedup_data, context.clone()))
+                .collect(),
+            block_height,
+        )?;
 
-            let replace_cpfp_txid = if speedup.is_rbf {
-                Some(speedup.tx_id)
-            } else {
-                None
-            };
+        let finality_confirmations = self
+            .settings()
+            .monitor_settings
+            .max_monitoring_confirmations;
 
-            let txs_data: Vec<(SpeedupData, Transaction, String)> = speedup
-                .speedup_tx_data
-                .iter()
-                .map(|(speedup_data, tx, _)| {
-                    (speedup_data.clone(), tx.clone(), speedup.context.clone())
-                })
-                .collect();
+        self.register_group(tx_ids, context, finality_confirmations)
+    }
 
-            self.create_and_send_cpfp_tx(
-                txs_data,
-                funding,
-                speedup.bump_fee_percentage_used,
-                replace_cpfp_txid,
-                Some(speedup.tx_id),
-            )?;
-        }
+    fn get_group_status(&self, group_id: Uuid) -> Result<GroupStatus, BitcoinCoordinatorError> {
+        self.store
+            .get_tx_groups()?
+            .into_iter()
+            .find(|group| group.group_id == group_id)
+            .ok_or(BitcoinCoordinatorError::UnknownGroup(group_id))
+    }
+
+    fn monitor_rsk_pegin(&self, context: String) -> Result<(), BitcoinCoordinatorError> {
+        self.check_ownership()?;
+
+        self.monitor.monitor(TypesToMonitor::RskPegin(None))?;
+
+        self.store.watch_rsk_pegins(context)?;
+
+        info!("{} Monitor RSK peg-ins", style("Coordinator").green());
 
         Ok(())
     }
 
-    fn process_in_progress_speedup_txs(&self) -> Result<(), BitcoinCoordinatorError> {
-        let txs = self.store.get_pending_speedups()?;
+    fn get_detected_pegins(
+        &self,
+        since_height: BlockHeight,
+    ) -> Result<Vec<DetectedPegin>, BitcoinCoordinatorError> {
+        let pegins = self.store.get_detected_pegins(since_height)?;
+        Ok(pegins)
+    }
 
-        for tx in txs {
-            // Get updated transaction status from monitor
-            let tx_status = self.monitor.get_tx_status(&tx.tx_id);
+    fn get_transaction(&self, txid: Txid) -> Result<TransactionStatus, BitcoinCoordinatorError> {
+        let tx_status = self.monitor.get_tx_status(&txid)?;
+        Ok(tx_status)
+    }
 
-            match tx_status {
-                Ok(tx_status) => {
-                    debug!(
-                        "{} {} Transaction({}) | Confirmations({})",
-                        style("Coordinator").green(),
-                        tx.get_tx_name(),
-                        style(tx.tx_id).blue(),
-                        style(tx_status.confirmations).blue(),
-                    );
-                    // Handle the case where the transaction is a CPFP (Child Pays For Parent) transaction.
+    fn get_transaction_history(
+        &self,
+        txid: Txid,
+    ) -> Result<TransactionHistory, BitcoinCoordinatorError> {
+        let history = self.store.get_tx_history(&txid)?;
+        Ok(history)
+    }
 
-                    // First we acknowledge the transaction to clear any related news.
-                    let ack = AckMonitorNews::Transaction(tx_status.tx_id, tx.context.clone());
-                    self.monitor.ack_news(ack)?;
+    fn diagnose(&self, txid: Txid) -> Result<TxDiagnosis, BitcoinCoordinatorError> {
+        let tx = self.store.get_tx(&txid)?;
+        let history = self.store.get_tx_history(&txid)?;
+        let current_block_height = self.current_height()?;
 
-                    if tx_status
-                        .is_finalized(self.settings.monitor_settings.max_monitoring_confirmations)
-                    {
-                        // Once the transaction is finalized, we are not monitoring it anymore.
-                        self.store
-                            .update_speedup_state(tx_status.tx_id, SpeedupState::Finalized)?;
-                        continue;
-                    }
+        let confirmations = match self.monitor.get_tx_status(&txid) {
+            Ok(tx_status) => Some(tx_status.confirmations),
+            Err(MonitorError::TransactionNotFound(_)) => None,
+            Err(e) => return Err(e.into()),
+        };
 
-                    if tx_status.is_confirmed() {
-                        // We want to keep the confirmation on the storage to calculate the maximum speedups
-                        self.store
-                            .update_speedup_state(tx_status.tx_id, SpeedupState::Confirmed)?;
-                        continue;
-                    }
+        let pending_reason = self
+            .store
+            .get_pending_tx_entries(current_block_height)?
+            .into_iter()
+            .find(|entry| entry.tx_id == txid)
+            .and_then(|entry| entry.pending_reason);
+
+        // Same fee rate as a dispatch, without reporting it as news.
+        let network_fee_rate = self.get_estimated_fee_rate().fee_rate;
+
+        // The speedups of a transaction are in the chain of its funding group.
+        let funding_group = tx.dispatch_options.funding_group.clone();
+        let (speedups, funding_summary, funding_available, has_room) = self
+            .store
+            .with_funding_group(funding_group.as_deref(), || {
+                Ok::<_, BitcoinCoordinatorStoreError>((
+                    self.store.get_speedups_for_tx(&txid)?,
+                    self.store.get_funding_summary(network_fee_rate)?,
+                    self.store.is_funding_available()?,
+                    self.store.has_enough_unconfirmed_txs_for_cpfp()?,
+                ))
+            })?;
+
+        let paying_fee_rate = match last_speedup_fee_rate(&speedups) {
+            Some(fee_rate) => Some(fee_rate),
+            None if tx.broadcast_block_height.is_some() => Some(tx.fee_rate_at_dispatch),
+            None => None,
+        };
 
-                    if tx_status.is_orphan() {
-                        self.store
-                            .update_speedup_state(tx_status.tx_id, SpeedupState::Dispatched)?;
+        let mut diagnosis = TxDiagnosis {
+            tx_id: txid,
+            context: tx.context,
+            state: tx.state,
+            current_block_height,
+            target_block_height: tx.target_block_height,
+            first_broadcast_block_height: tx.first_broadcast_block_height,
+            broadcast_block_height: tx.broadcast_block_height,
+            first_confirmation_block_height: tx.first_confirmation_block_height,
+            confirmations,
+            was_broadcast: tx.first_broadcast_block_height.is_some()
+                || tx.broadcast_block_height.is_some(),
+            last_dispatch_attempt: last_dispatch_attempt(&history.events),
+            retry_count: tx.retry_info.map_or(0, |info| info.retries_count),
+            has_speedup: tx.speedup_data.is_some(),
+            funding_group,
+            last_rbf_block_height: last_rbf_block_height(&speedups),
+            speedup_fees: speedup_costs(&speedups).1,
+            speedups,
+            unconfirmed_speedups: funding_summary.unconfirmed_speedups,
+            max_unconfirmed_speedups: self.store.max_unconfirmed_speedups(),
+            network_fee_rate,
+            paying_fee_rate,
+            funding_available,
+            ancestor_limit_reached: !has_room,
+            node_unreachable_since: self.node_breaker.unreachable_since(),
+            blocking_reasons: Vec::new(),
+        };
+
+        diagnosis.blocking_reasons = blocking_reasons(&diagnosis, pending_reason);
+
+        Ok(diagnosis)
+    }
+
+    fn cancel_dispatch(&self, txid: Txid) -> Result<(), BitcoinCoordinatorError> {
+        self.check_ownership()?;
+
+        let tx = self.store.get_tx(&txid)?;
+
+        match tx.state {
+            TransactionState::Confirmed | TransactionState::Finalized => {
+                return Err(BitcoinCoordinatorError::CannotCancelConfirmed(txid));
+            }
+            TransactionState::Failed | TransactionState::Expired => {
+                return Err(BitcoinCoordinatorError::CannotCancelInactive(
+                    txid,
+                    tx.state.clone(),
+                ));
+            }
+            // Cancelling again does nothing, the cancellation was already reported.
+            TransactionState::Cancelled => return Ok(()),
+            TransactionState::ToDispatch | TransactionState::Dispatched => {}
+        }
+
+        // If the transaction was never broadcast there is nothing left to monitor. The monitor is cancelled
+        // first, so a failure leaves the transaction to dispatch and still monitored.
+        let monitored = TypesToMonitor::Transactions(vec![txid], tx.context.clone(), None);
+        let never_broadcast = tx.broadcast_block_height.is_none();
+
+        if never_broadcast {
+            self.monitor.cancel(monitored.clone())?;
+        }
+
+        let tx = match self.store.cancel_tx(txid) {
+            Ok(tx) => tx,
+            Err(e) => {
+                // The transaction is still to dispatch, it is monitored again.
+                if never_broadcast {
+                    if let Err(monitor_error) = self.monitor.monitor(monitored) {
+                        warn!(
+                            "{} Failed to monitor Transaction({}) again | Error: {}",
+                            style("Coordinator").green(),
+                            style(txid).yellow(),
+                            style(monitor_error).red()
+                        );
                     }
                 }
-                Err(MonitorError::TransactionNotFound(_)) => {}
-                Err(e) => return Err(e.into()),
+                return Err(e.into());
             }
-        }
+        };
+
+        info!(
+            "{} Cancel dispatch of Transaction({})",
+            style("Coordinator").green(),
+            style(txid).yellow()
+        );
+
+        let news = CoordinatorNews::DispatchCancelled(txid, tx.context);
+        self.update_news(news)?;
 
         Ok(())
     }
 
-    fn process_in_progress_txs(&self) -> Result<(), BitcoinCoordinatorError> {
-        let txs = self.store.get_txs_in_progress()?;
+    fn cancel_by_context(
+        &self,
+        context: &str,
+    ) -> Result<ContextCancelSummary, BitcoinCoordinatorError> {
+        self.check_ownership()?;
+
+        let mut summary = ContextCancelSummary::default();
+
+        for tx in self.store.get_txs_by_context(context)? {
+            match tx.state {
+                TransactionState::ToDispatch | TransactionState::Dispatched => {
+                    self.store.cancel_tx(tx.tx_id)?;
+                    // Unlike cancel_dispatch, broadcast transactions are not followed until they confirm.
+                    self.store.untrack_tx(tx.tx_id)?;
+                    summary.cancelled.push(tx.tx_id);
+                }
+                TransactionState::Confirmed | TransactionState::Finalized => {
+                    summary.skipped_confirmed.push(tx.tx_id);
+                }
+                TransactionState::Cancelled
+                | TransactionState::Failed
+                | TransactionState::Expired => {}
+            }
+        }
 
-        for tx in txs {
-            // Get updated transaction status from monitor
-            let tx_status = self.monitor.get_tx_status(&tx.tx_id);
+        if !summary.cancelled.is_empty() {
+            self.monitor.cancel(TypesToMonitor::Transactions(
+                summary.cancelled.clone(),
+                context.to_string(),
+                None,
+            ))?;
+            self.store.remove_deferred_speedup_txs(&summary.cancelled)?;
+
+            for txid in summary.cancelled.iter() {
+                let news = CoordinatorNews::DispatchCancelled(*txid, context.to_string());
+                self.update_news(news)?;
+            }
+        }
 
-            match tx_status {
-                Ok(tx_status) => {
-                    debug!(
-                        "{} Transaction({}) | Confirmations({})",
-                        style("Coordinator").green(),
-                        style(tx.tx_id).yellow(),
-                        style(tx_status.confirmations).blue(),
-                    );
+        info!(
+            "{} Cancel Context({}) | Cancelled({}) | SkippedConfirmed({})",
+            style("Coordinator").green(),
+            style(context).yellow(),
+            style(summary.cancelled.len()).blue(),
+            style(summary.skipped_confirmed.len()).blue()
+        );
 
-                    if tx_status
-                        .is_finalized(self.settings.monitor_settings.max_monitoring_confirmations)
-                    {
-                        // Once the transaction is finalized, we are not monitoring it anymore.
-                        self.store
-                            .update_tx_state(tx_status.tx_id, TransactionState::Finalized)?;
+        Ok(summary)
+    }
+
+    fn reschedule_dispatch(
+        &self,
+        txid: Txid,
+        new_target: Option<BlockHeight>,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        self.check_ownership()?;
 
-                        continue;
-                    }
+        let tx = self.store.get_tx(&txid)?;
 
-                    if tx_status.is_confirmed() {
-                        self.store
-                            .update_tx_state(tx_status.tx_id, TransactionState::Confirmed)?;
-                    }
-                }
-                Err(MonitorError::TransactionNotFound(_)) => {
-                    // In case a transaction is not found, we just wait.
-                    // We are going to speed up the CPFP.
-                }
-                Err(e) => return Err(e.into()),
-            }
+        if tx.state != TransactionState::ToDispatch || tx.broadcast_block_height.is_some() {
+            return Err(BitcoinCoordinatorError::CannotRescheduleDispatched(txid));
         }
 
+        self.store.reschedule_tx(txid, new_target)?;
+
+        info!(
+            "{} Reschedule dispatch of Transaction({}) | Target({:?}) | PreviousTarget({:?})",
+            style("Coordinator").green(),
+            style(txid).yellow(),
+            new_target,
+            tx.target_block_height,
+        );
+
         Ok(())
     }
 
-    fn should_speedup(&self, tx: &CoordinatedTransaction) -> bool {
-        // If the transaction has a CPFP UTXO, we have to speed it up.
-        tx.speedup_data.is_some()
+    fn get_scheduled_dispatches(
+        &self,
+    ) -> Result<Vec<(Txid, BlockHeight, String)>, BitcoinCoordinatorError> {
+        let scheduled_dispatches = self
+            .store
+            .get_scheduled_txs()?
+            .into_iter()
+            .filter_map(|tx| {
+                tx.target_block_height
+                    .map(|target_block_height| (tx.tx_id, target_block_height, tx.context))
+            })
+            .collect();
+
+        Ok(scheduled_dispatches)
     }
 
-    fn should_dispatch_tx(
-        &self,
-        pending_tx: &CoordinatedTransaction,
-    ) -> Result<bool, BitcoinCoordinatorError> {
-        if pending_tx.target_block_height.is_none() {
-            return Ok(true);
-        }
+    fn add_funding(&self, utxo: Utxo) -> Result<(), BitcoinCoordinatorError> {
+        self.check_ownership()?;
 
-        let was_already_broadcasted = pending_tx.broadcast_block_height.is_some();
+        // The funding is spent by the speedups as a P2WPKH output, the key must be compressed.
+        p2wpkh_hash(&utxo.pub_key)?;
 
-        if was_already_broadcasted {
-            warn!(
-                "Transaction({}) already broadcasted. This could be a border case or a bug.",
-                pending_tx.tx_id
-            );
+        info!(
+            "{} Funding added | Txid({}) | Vout({}) | Amount({}) | PublicKey({})",
+            style("Coordinator").green(),
+            style(utxo.txid).cyan(),
+            style(utxo.vout).cyan(),
+            style(utxo.amount).cyan(),
+            style(utxo.pub_key).cyan()
+        );
+        // Each time a speedup transaction is generated, it consumes the previous funding UTXO and leaves any change as the new funding for subsequent speedups.
+        // Therefore, every new funding UTXO should be recorded in the same format as a speedup transaction, ensuring the coordinator always tracks the latest available funding.
+        // If the active funding is paying for unconfirmed speedups, the new funding waits in the funding pool.
+        self.store.add_funding(utxo)?;
 
-            // THIS COULD BE A BORDER CASE OR A BUG.
-            // This code path should not be reached because once a transaction is broadcast,
-            // it should be marked as BroadcastPendingConfirmation.
-            return Ok(false);
+        Ok(())
+    }
+
+    fn add_funding_group(&self, group_id: &str, utxo: Utxo) -> Result<(), BitcoinCoordinatorError> {
+        self.check_ownership()?;
+
+        // The group is part of the store keys of its speedup chain.
+        if group_id.is_empty() || group_id.contains('/') {
+            return Err(BitcoinCoordinatorError::InvalidConfiguration(format!(
+                "funding group must not be empty nor contain '/', got {:?}",
+                group_id
+            )));
         }
 
-        let current_block_height = self.monitor.get_monitor_height()?;
+        p2wpkh_hash(&utxo.pub_key)?;
+
+        info!(
+            "{} Funding group | Group({}) | Txid({}) | Vout({}) | Amount({})",
+            style("Coordinator").green(),
+            style(group_id).cyan(),
+            style(utxo.txid).cyan(),
+            style(utxo.vout).cyan(),
+            style(utxo.amount).cyan()
+        );
+
+        self.store.add_funding_group(group_id)?;
+        self.store
+            .with_funding_group(Some(group_id), || self.store.add_funding(utxo))?;
 
-        Ok(current_block_height >= pending_tx.target_block_height.unwrap())
+        Ok(())
     }
 
-    fn create_and_send_cpfp_tx(
+    fn import_external_speedup(
         &self,
-        txs_data: Vec<(SpeedupData, Transaction, String)>,
-        funding: Utxo,
-        bump_fee: f64,
-        replace_cpfp_txid: Option<Txid>,
-        retry_txid: Option<Txid>,
+        tx: Transaction,
+        covered_parents: Vec<Txid>,
+        change_vout: u32,
+        change_pubkey: PublicKey,
     ) -> Result<(), BitcoinCoordinatorError> {
-        // Check if the funding amount is below the minimum required for a speedup.
-        // If so, notify via CoordinatorNews and exit early.
-        if funding.amount < self.settings.min_funding_amount_sats {
-            let news = CoordinatorNews::InsufficientFunds(
-                funding.txid,
-                funding.amount,
-                self.settings.min_funding_amount_sats,
-            );
-            self.update_news(news)?;
+        self.check_ownership()?;
 
-            warn!(
-                "{} Insufficient funds for speedup | FundingTx({}) | Amount({}) | MinRequired({})",
-                style("Coordinator").green(),
-                style(funding.txid).yellow(),
-                style(funding.amount).red(),
-                style(self.settings.min_funding_amount_sats).blue(),
-            );
+        let tx_id = tx.compute_txid();
 
-            return Ok(());
+        if covered_parents.is_empty() {
+            return Err(BitcoinCoordinatorError::InvalidArgument(format!(
+                "external speedup {} must pay for at least one transaction",
+                tx_id
+            )));
         }
 
-        let is_rbf = replace_cpfp_txid.is_some();
+        match self.store.get_speedup(&tx_id) {
+            Ok(_) => {
+                return Err(BitcoinCoordinatorError::InvalidArgument(format!(
+                    "speedup {} is already known",
+                    tx_id
+                )))
+            }
+            Err(BitcoinCoordinatorStoreError::SpeedupNotFound) => {}
+            Err(e) => return Err(e.into()),
+        }
 
-        let txs_speedup_data = txs_data
-            .iter()
-            .map(|(speedup_data, tx, _)| (speedup_data.clone(), tx.vsize()))
-            .collect();
+        let mut parents = Vec::new();
 
-        let new_network_fee_rate = self.get_network_fee_rate()?;
+        for parent_txid in covered_parents {
+            let parent = self.store.get_tx(&parent_txid)?;
 
-        let (diff_fee_for_unconfirmed_chain, chain_vsize) =
-            self.get_diff_fee_for_unconfirmed_chain(new_network_fee_rate)?;
+            if parent.speedup_data.is_none() {
+                return Err(BitcoinCoordinatorError::InvalidArgument(format!(
+                    "transaction {} has no speedup data",
+                    parent_txid
+                )));
+            }
 
-        let (speedup_tx, speedup_fee) = self.get_speedup_tx(
-            &txs_speedup_data,
-            &funding,
-            bump_fee,
-            is_rbf,
-            new_network_fee_rate,
-            diff_fee_for_unconfirmed_chain,
-            chain_vsize,
-        )?;
-        // Validate that funding can cover the fee
-        if speedup_fee > funding.amount {
-            let news =
-                CoordinatorNews::InsufficientFunds(funding.txid, funding.amount, speedup_fee);
-            self.update_news(news)?;
-            return Ok(());
+            if !tx
+                .input
+                .iter()
+                .any(|input| input.previous_output.txid == parent_txid)
+            {
+                return Err(BitcoinCoordinatorError::ExternalSpeedupParentNotSpent(
+                    tx_id,
+                    parent_txid,
+                ));
+            }
+
+            parents.push(parent);
         }
 
-        let speedup_tx_id = speedup_tx.compute_txid();
-        let txs_info: Vec<(Txid, String)> = txs_data
+        // The speedup extends the speedup chain of the transactions it pays for.
+        let funding_group = parents[0].dispatch_options.funding_group.clone();
+
+        if parents
             .iter()
-            .map(|(_, tx, context)| (tx.compute_txid(), context.clone()))
-            .collect();
+            .any(|parent| parent.dispatch_options.funding_group != funding_group)
+        {
+            return Err(BitcoinCoordinatorError::InvalidArgument(format!(
+                "the transactions paid by external speedup {} belong to different funding groups",
+                tx_id
+            )));
+        }
 
-        let speedup_type = if is_rbf { "RBF" } else { "CPFP" };
-        let mut cpfp_to_replace = String::new();
+        self.store.with_funding_group(funding_group.as_deref(), || {
+            self.save_external_speedup(tx, parents, change_vout, change_pubkey)
+        })
+    }
 
-        if is_rbf {
-            cpfp_to_replace = format!("| CPFP_TO_REPLACE({})", replace_cpfp_txid.unwrap());
-        }
+    fn add_funding_with_change_key(
+        &self,
+        utxo: Utxo,
+        change_pubkey: PublicKey,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        // The change is paid to a P2WPKH output, checked before the funding is added.
+        p2wpkh_hash(&change_pubkey)?;
 
-        let previous_txid = speedup_tx.input[0].previous_output.txid;
+        self.add_funding(utxo.clone())?;
+        self.store.set_change_key(&utxo, change_pubkey)?;
 
-        info!(
-            "{} New {} Transaction({}) | Tx2Speedup({:#?}) | Fee({}) | Transactions#({}) | FundingTx({}) | Vout({}) {} | BumpFee({})",
-            style("Coordinator").green(),
-            speedup_type,
-            style(speedup_tx_id).yellow(),
-            style(previous_txid).yellow(),
-            style(speedup_fee).blue(),
-            style(txs_info.len()).blue(),
-            style(funding.txid).blue(),
-            style(funding.vout).blue(),
-            style(cpfp_to_replace).blue(),
-            style(bump_fee).blue(),
-        );
+        Ok(())
+    }
 
-        let new_funding_utxo = Utxo::new(
-            speedup_tx_id,
-            0, // After creating the speedup tx we know that the vout is 0.
-            speedup_tx.output.last().unwrap().value.to_sat(),
-            &funding.pub_key,
-        );
+    fn rotate_change_key(&self, change_pubkey: PublicKey) -> Result<(), BitcoinCoordinatorError> {
+        self.check_ownership()?;
 
-        let speedup_data = CoordinatedSpeedUpTransaction::new(
-            speedup_tx_id,
-            funding,
-            new_funding_utxo,
-            is_rbf,
-            0, // Temporary value, will be updated after send_transaction
-            SpeedupState::Dispatched,
-            bump_fee,
-            txs_data,
-            new_network_fee_rate,
+        // The change is paid to a P2WPKH output, the key must be compressed.
+        p2wpkh_hash(&change_pubkey)?;
+
+        // The key is set for the funding spent by the next speedup, its change keeps the key along the chain.
+        let funding = self
+            .store
+            .get_funding()?
+            .ok_or(BitcoinCoordinatorStoreError::FundingNotFound)?;
+
+        info!(
+            "{} Change key rotated | FundingTx({}) | Vout({}) | PublicKey({})",
+            style("Coordinator").green(),
+            style(funding.txid).cyan(),
+            style(funding.vout).cyan(),
+            style(change_pubkey).cyan()
         );
 
-        self.dispatch_speedup(speedup_tx, speedup_data, retry_txid)?;
+        self.store.set_change_key(&funding, change_pubkey)?;
 
         Ok(())
     }
 
-    fn get_diff_fee_for_unconfirmed_chain(
-        &self,
-        new_network_fee_rate: u64,
-    ) -> Result<(u64, usize), BitcoinCoordinatorError> {
-        let speedups_unconfirmed = self.store.get_unconfirmed_speedups()?;
+    fn remove_funding(&self, txid: Txid, vout: u32) -> Result<(), BitcoinCoordinatorError> {
+        self.check_ownership()?;
 
-        if speedups_unconfirmed.is_empty() {
-            return Ok((0, 0));
-        }
+        info!(
+            "{} Funding removed | Txid({}) | Vout({})",
+            style("Coordinator").green(),
+            style(txid).cyan(),
+            style(vout).cyan(),
+        );
 
-        let last_fee_rate_used = speedups_unconfirmed.last().unwrap().network_fee_rate_used;
+        self.store.remove_funding(txid, vout)?;
 
-        let mut fee_chain_difference = 0;
-        let mut chain_vsize = 0;
+        Ok(())
+    }
 
-        for speedup in speedups_unconfirmed {
-            let fee_rate_to_pay = new_network_fee_rate.saturating_sub(last_fee_rate_used);
-            let txs_data = speedup
-                .speedup_tx_data
-                .iter()
-                .map(|(speedup_data, tx, _)| (speedup_data.clone(), tx.vsize()))
-                .collect();
+    fn get_funding_summary(&self) -> Result<FundingSummary, BitcoinCoordinatorError> {
+        let network_fee_rate = self.get_network_fee_rate(self.settings().max_feerate_sat_vb)?;
+        let summary = self.store.get_funding_summary(network_fee_rate)?;
 
-            let (tx, fee_to_pay) = self.get_speedup_tx(
-                &txs_data,
-                &speedup.prev_funding,
-                self.settings.base_fee_multiplier, // We should not bump this fee, we are just calculating the difference.
-                speedup.is_rbf,
-                fee_rate_to_pay,
-                0,
-                0,
-            )?;
+        Ok(summary)
+    }
 
-            chain_vsize += tx.vsize();
-            fee_chain_difference += fee_to_pay;
-        }
+    fn get_pending_overview(&self) -> Result<PendingOverview, BitcoinCoordinatorError> {
+        let current_block_height = self.current_height()?;
 
-        Ok((fee_chain_difference, chain_vsize))
+        let (to_dispatch, dispatched) = self
+            .store
+            .get_pending_tx_entries(current_block_height)?
+            .into_iter()
+            .partition(|entry| entry.state == TransactionState::ToDispatch);
+
+        Ok(PendingOverview {
+            to_dispatch,
+            dispatched,
+            unconfirmed_speedups: self.store.get_unconfirmed_speedup_entries()?,
+            node_unreachable_since: self.node_breaker.unreachable_since(),
+            last_tick_budget: self.tick_budget.usage(),
+            bump_strategy: self.bump_strategy_state(1.0)?,
+        })
     }
 
-    fn get_network_fee_rate(&self) -> Result<u64, BitcoinCoordinatorError> {
-        let mut network_fee_rate = match self.monitor.get_estimated_fee_rate() {
-            Ok(rate) => rate,
-            Err(_) => self.settings.min_network_fee_rate,
+    fn get_speedups_for_tx(
+        &self,
+        txid: Txid,
+    ) -> Result<Vec<SpeedupSummary>, BitcoinCoordinatorError> {
+        // The speedups of a transaction are in the chain of its funding group.
+        let funding_group = match self.store.get_tx(&txid) {
+            Ok(tx) => tx.dispatch_options.funding_group,
+            Err(BitcoinCoordinatorStoreError::TransactionNotFound(_)) => None,
+            Err(e) => return Err(e.into()),
         };
 
-        if network_fee_rate > self.settings.max_feerate_sat_vb {
-            warn!(
-                "{} Estimate feerate sat/vbyte is greater than the max allowed. This could be a bug. | EstimateFeerate({}) | MaxAllowed({})",
-                style("Coordinator").red(),
-                style(network_fee_rate).red(),
-                style(self.settings.max_feerate_sat_vb).red(),
-            );
-
-            // Inform this with news
-            let news = CoordinatorNews::EstimateFeerateTooHigh(
-                network_fee_rate,
-                self.settings.max_feerate_sat_vb,
-            );
+        let speedups = self
+            .store
+            .with_funding_group(funding_group.as_deref(), || {
+                self.store.get_speedups_for_tx(&txid)
+            })?;
 
-            self.update_news(news)?;
+        Ok(speedups)
+    }
 
-            // Set the estimate feerate to the max allowed
-            network_fee_rate = self.settings.max_feerate_sat_vb;
-        }
-        Ok(network_fee_rate)
+    fn get_confirmation_stats(
+        &self,
+        window_blocks: u32,
+    ) -> Result<ConfirmationStats, BitcoinCoordinatorError> {
+        let current_height = self.current_height()?;
+
+        Ok(confirmation_stats(
+            &self.store.get_finalized_tx_stats()?,
+            current_height,
+            window_blocks,
+        ))
     }
 
-    fn get_speedup_tx(
+    fn estimate_dispatch_cost(
         &self,
-        txs_data: &Vec<(SpeedupData, usize)>,
-        funding: &Utxo,
-        bump_fee_percentage: f64,
-        is_rbf: bool,
-        network_fee_rate: u64,
-        diff_fee_for_unconfirmed_chain: u64,
-        chain_vsize: usize,
-    ) -> Result<(Transaction, u64), BitcoinCoordinatorError> {
-        let speedups_data: Vec<SpeedupData> =
-            txs_data.iter().map(|tx_data| tx_data.0.clone()).collect();
+        txs: Vec<(Transaction, SpeedupData)>,
+    ) -> Result<DispatchCostEstimate, BitcoinCoordinatorError> {
+        // Same fee rate as a dispatch, without reporting a fee rate above the max as news.
+        let network_fee_rate = self
+            .get_estimated_fee_rate()
+            .fee_rate
+            .min(self.settings().max_feerate_sat_vb);
+
+        let mut unbatchable_txs = Vec::new();
+        let mut txs_to_batch = Vec::new();
+
+        for (tx, speedup_data) in txs {
+            if tx.weight().to_wu() > self.settings().max_tx_weight {
+                unbatchable_txs.push(tx.compute_txid());
+                continue;
+            }
 
-        // TRICK:
-        // - Create the child transaction with a dummy fee to determine the transaction's virtual size (vsize).
-        // - Use the vsize of the child transaction to calculate the total fee required.
-        // - With the total fee calculated, create the final speedup transaction.
-        // - If the child vsize is greater than or equal to the final speedup vsize,
-        //  means the final speedup has sufficient fee.
-        // - Otherwise, we need to increase the fee, we will use the final speedup vsize as the new child vsize.
+            txs_to_batch.push(CoordinatedTransaction::new(
+                tx,
+                Some(speedup_data),
+                TransactionState::ToDispatch,
+                None,
+                String::new(),
+            ));
+        }
 
-        let mut child_vsize = 0;
+        let txids_to_batch: Vec<Txid> = txs_to_batch.iter().map(|tx| tx.tx_id).collect();
+        // Transactions over the CPFP fee cap or without room in the speedup chain are left out of the batches,
+        // they are reported as deferred.
+        let (batches, _, _) = self.batch_txs_by_weight_limit(txs_to_batch)?;
 
-        loop {
-            let dummy_speedup_vsize = (ProtocolBuilder {})
-                .speedup_transactions(
-                    speedups_data.as_slice(),
-                    funding.clone(),
-                    &funding.pub_key,
-                    10000, // Dummy fee
-                    &self.key_manager,
-                )?
-                .vsize();
+        let funding = self.store.get_funding()?;
+        let is_pool_funding = match &funding {
+            Some(funding) => self.store.is_pool_funding(funding)?,
+            None => false,
+        };
 
-            if child_vsize == 0 {
-                child_vsize = dummy_speedup_vsize;
-            }
+        // The first CPFP pays the shortfall of the unconfirmed chain, the next ones extend the chain at the current fee rate.
+        let (mut diff_fee_for_unconfirmed_chain, mut chain_vsize) = if is_pool_funding {
+            (0, 0)
+        } else {
+            self.get_diff_fee_for_unconfirmed_chain(network_fee_rate)?
+        };
 
-            let speedup_fee = self.calculate_speedup_fee(
-                txs_data,
-                child_vsize,
-                bump_fee_percentage,
+        let mut batch_estimates = Vec::new();
+
+        for batch in batches {
+            let txs_speedup_data: Vec<(SpeedupData, usize)> = batch
+                .iter()
+                .map(|tx| Ok((required_speedup_data(tx)?.clone(), tx.tx.vsize())))
+                .collect::<Result<_, BitcoinCoordinatorError>>()?;
+
+            let anchor_kinds: Vec<SpeedupOutputKind> = batch
+                .iter()
+                .zip(txs_speedup_data.iter())
+                .map(|(tx, (speedup_data, _))| {
+                    SpeedupOutputKind::of_speedup_utxo(&tx.tx, speedup_data)
+                })
+                .collect();
+
+            // One input for each transaction in the batch and one for the funding.
+            let cpfp_vsize = self.estimate_speedup_vsize(&anchor_kinds);
+
+            let cpfp_fee = self.calculate_speedup_fee(
+                &txs_speedup_data,
+                cpfp_vsize,
+                self.settings().base_fee_multiplier,
                 network_fee_rate,
-                is_rbf,
+                false,
                 diff_fee_for_unconfirmed_chain,
                 chain_vsize,
             )?;
 
-            let final_speedup_tx = (ProtocolBuilder {}).speedup_transactions(
-                &speedups_data,
-                funding.clone(),
-                &funding.pub_key,
-                speedup_fee,
-                &self.key_manager,
-            )?;
+            diff_fee_for_unconfirmed_chain = 0;
+            chain_vsize += cpfp_vsize
+                + txs_speedup_data
+                    .iter()
+                    .map(|(_, vsize)| vsize)
+                    .sum::<usize>();
+
+            batch_estimates.push(BatchCostEstimate {
+                txids: batch.iter().map(|tx| tx.tx_id).collect(),
+                cpfp_vsize,
+                cpfp_fee,
+            });
+        }
 
-            let final_speedup_vsize = final_speedup_tx.vsize();
+        let deferred_txs = txids_to_batch
+            .into_iter()
+            .filter(|txid| {
+                !batch_estimates
+                    .iter()
+                    .any(|batch| batch.txids.contains(txid))
+            })
+            .collect();
 
-            if child_vsize >= final_speedup_vsize {
-                // If the child vsize is greater than or equal to the final speedup vsize,
-                //  means the final speedup has sufficient fee.
-                return Ok((final_speedup_tx, speedup_fee));
-            } else {
-                // Otherwise, we need to increase the fee, we will use the final speedup vsize as the new child vsize.
-                child_vsize = final_speedup_vsize;
-            }
-        }
+        let total_fee = batch_estimates.iter().map(|batch| batch.cpfp_fee).sum();
+        let funding_amount = funding.map_or(0, |funding| funding.amount);
+        let is_funding_sufficient = funding_amount >= self.settings().min_funding_amount_sats
+            && funding_amount >= total_fee;
+
+        Ok(DispatchCostEstimate {
+            batches: batch_estimates,
+            unbatchable_txs,
+            deferred_txs,
+            network_fee_rate,
+            total_fee,
+            funding_amount,
+            is_funding_sufficient,
+        })
     }
 
-    fn rbf_last_cpfp(&self) -> Result<(), BitcoinCoordinatorError> {
-        // When this function is called, we know that the last speedup exists to be replaced.
-        let (speedup, rbf_tx) = self.store.get_last_speedup()?.unwrap();
+    fn get_news(&self) -> Result<News, BitcoinCoordinatorError> {
+        let monitor_news = self.get_monitor_news()?.collect();
 
-        let mut txs_to_speedup: Vec<CoordinatedTransaction> = Vec::new();
+        let coordinator_news = self.store.get_news()?;
 
-        for (_, tx, _) in speedup.speedup_tx_data.clone() {
-            let tx = self.store.get_tx(&tx.compute_txid())?;
-            txs_to_speedup.push(tx);
-        }
+        Ok(News::new(monitor_news, coordinator_news))
+    }
 
-        // The new_bump_fee will increase the previous bump fee from the CPFP used by adding the number of RBF operations performed + 1.
-        let mut increase_last_bump_fee = speedup.bump_fee_percentage_used;
+    fn get_news_page(
+        &self,
+        since_seq: u64,
+        limit: usize,
+    ) -> Result<NewsPage, BitcoinCoordinatorError> {
+        let mut monitor_news = self.get_monitor_news()?;
+        let page_monitor_news: Vec<MonitorNews> = monitor_news.by_ref().take(limit).collect();
+        let more_monitor_news = monitor_news.next().is_some();
+
+        let (entries, more_coordinator_news) = self.store.get_news_page(since_seq, limit)?;
+        let next_seq = entries.last().map_or(since_seq, |(seq, _)| seq + 1);
+        let coordinator_news = entries.into_iter().map(|(_, news)| news).collect();
+
+        Ok(NewsPage {
+            news: News::new(page_monitor_news, coordinator_news),
+            has_more: more_monitor_news || more_coordinator_news,
+            next_seq,
+        })
+    }
 
-        if let Some(rbf_tx) = rbf_tx {
-            increase_last_bump_fee = rbf_tx.bump_fee_percentage_used;
-        }
+    fn get_news_filtered(&self, min_severity: Severity) -> Result<News, BitcoinCoordinatorError> {
+        let monitor_news = self
+            .get_monitor_news()?
+            .filter(|news| monitor_news_severity(news) >= min_severity)
+            .collect();
 
-        let new_bump_fee = self.get_bump_fee_percentage_strategy(increase_last_bump_fee)?;
+        let coordinator_news = self.store.get_news_filtered(min_severity)?;
 
-        self.create_and_send_cpfp_tx(
-            speedup.speedup_tx_data,
-            speedup.prev_funding,
-            new_bump_fee,
-            Some(speedup.tx_id),
-            None,
-        )?;
+        Ok(News::new(monitor_news, coordinator_news))
+    }
+
+    fn ack_news(&self, news: AckNews) -> Result<(), BitcoinCoordinatorError> {
+        self.check_ownership()?;
 
+        match news {
+            AckNews::Monitor(news) => self.monitor.ack_news(self.resolve_monitor_ack(news)?)?,
+            AckNews::Coordinator(news) => self.store.ack_news(news)?,
+        }
         Ok(())
     }
 
-    fn boost_cpfp_again(&self) -> Result<(), BitcoinCoordinatorError> {
-        // Check if we can send transactions or we stop the process until CPFP transactions start to be confirmed.
-        if self.store.can_speedup()? {
-            self.speedup_cpfp_tx()?;
-        } else {
-            warn!("{} Can not speedup", style("Coordinator").green());
+    fn ack_news_batch(&self, news: Vec<AckNews>) -> Result<usize, BitcoinCoordinatorError> {
+        self.check_ownership()?;
 
-            let is_funding_available = self.store.is_funding_available()?;
+        let mut monitor_acks = Vec::new();
+        let mut coordinator_acks = Vec::new();
 
-            if !is_funding_available {
-                self.notify_funding_not_found()?;
+        for ack in news {
+            match ack {
+                AckNews::Monitor(news) => monitor_acks.push(news),
+                AckNews::Coordinator(news) => coordinator_acks.push(news),
             }
         }
 
-        Ok(())
-    }
-
-    fn calculate_speedup_fee(
-        &self,
-        tx_to_speedup_info: &[(SpeedupData, usize)],
-        child_vbytes: usize,
-        bump_fee_percentage: f64,
-        network_fee_rate: u64,
-        is_rbf: bool,
-        fee_chain_difference: u64,
-        chain_vsize: usize,
-    ) -> Result<u64, BitcoinCoordinatorError> {
-        // Assumes that each parent transaction pays 1 sat/vbyte.
-        // To calculate the total fee, we need to know the vsize of the child (CPFP) + the vsize of each parent.
-        // Also we have to subtract the parent's transaction vbytes and the total output amounts once.
+        let mut acknowledged = self.store.ack_news_batch(coordinator_acks)?;
 
-        let mut parent_amount_outputs: usize = 0;
-        let mut parent_vbytes: usize = 0;
+        for news in monitor_acks {
+            let news = self.resolve_monitor_ack(news)?;
 
-        for (speedup_data, vsize) in tx_to_speedup_info {
-            let amount = if let Some(utxo) = &speedup_data.utxo {
-                utxo.amount as usize
-            } else {
-                speedup_data.partial_utxo.as_ref().unwrap().2 as usize
-            };
-            parent_amount_outputs += amount;
-            parent_vbytes += vsize;
+            // A news the monitor can not acknowledge does not fail the rest of the batch.
+            match self.monitor.ack_news(news.clone()) {
+                Ok(()) => acknowledged += 1,
+                Err(e) => warn!(
+                    "{} Skipping monitor news acknowledgement {:?}: {}",
+                    style("Coordinator").green(),
+                    news,
+                    e
+                ),
+            }
         }
 
-        // We substract the vbytes of the parents and the amount of outputs.
-        // Because the child pays for the parents and the parents pay for the outputs
-        let parent_total_sats = parent_vbytes * network_fee_rate as usize;
-        let child_total_sats = child_vbytes * network_fee_rate as usize;
-        let total_sats = parent_total_sats + child_total_sats;
+        Ok(acknowledged)
+    }
 
-        let mut total_fee = total_sats
-            .saturating_sub(parent_amount_outputs) // amount comming from the parents to discount
-            .saturating_sub(parent_vbytes); // min relay fee of the parents to discount
+    fn subscribe_news(
+        &self,
+        subscriber_id: &str,
+    ) -> Result<Receiver<News>, BitcoinCoordinatorError> {
+        if subscriber_id.is_empty() {
+            return Err(BitcoinCoordinatorError::InvalidArgument(
+                "The news subscriber id can not be empty".to_string(),
+            ));
+        }
 
-        if is_rbf && total_fee < child_total_sats * 2 {
-            // Bitcoin Policy (https://github.com/bitcoin/bitcoin/blob/master/doc/policy/mempool-replacements.md?plain=1#L32):
-            // The additional fees (difference between absolute fee paid by the replacement transaction and the
-            // sum paid by the original transactions) pays for the replacement transaction's bandwidth at or
-            // above the rate set by the node's incremental relay feerate. For example, if the incremental relay
-            // feerate is 1 satoshi/vB and the replacement transaction is 500 virtual bytes total, then the
-            // replacement pays a fee at least 500 satoshis higher than the sum of the original transactions.
+        let (sender, receiver) = mpsc::sync_channel(NEWS_SUBSCRIPTION_CAPACITY);
 
-            // *Rationale*: Try to prevent DoS attacks where an attacker causes the network to repeatedly relay
-            // transactions each paying a tiny additional amount in fees, e.g. just 1 satoshi.
-            total_fee = child_total_sats * 2;
-        }
+        // A new subscription with the same id replaces the previous one.
+        let mut subscribers = self.news_subscribers.borrow_mut();
+        subscribers.retain(|subscriber| subscriber.id != subscriber_id);
+        subscribers.push(NewsSubscriber {
+            id: subscriber_id.to_string(),
+            sender,
+        });
 
-        total_fee += fee_chain_difference as usize;
+        Ok(receiver)
+    }
 
-        // If a fee bump is being applied, add the virtual size of the transaction chain to the total fee to incentivize the miners to include the chain in the next block.
-        if chain_vsize > 0 && bump_fee_percentage > self.settings.base_fee_multiplier {
-            debug!(
-                "{} Adding to total fee ChainVsize({}) for bump fee {}",
-                style("Coordinator").green(),
-                style(chain_vsize).blue(),
-                style(bump_fee_percentage).blue()
-            );
-            total_fee += chain_vsize;
-        }
+    fn prune(&self, older_than_blocks: u32) -> Result<PruneSummary, BitcoinCoordinatorError> {
+        self.check_ownership()?;
 
-        let total_fee_bumped = (total_fee as f64 * bump_fee_percentage).ceil().round() as u64;
+        let current_height = self.current_height()?;
 
-        // TODO IMPORTANT:
-        // To accurately calculate the fee when the estimated fee changes over time, it is essential to retain the estimate_fee
-        // used for each CPFP and recalculate the new value if the estimate_fee differs. Failing to do so may result in overpayment or underpayment.
-        // In this scenario, we need to compute the fee difference between the parent transactions already sent in the previous CPFP chain and the new estimate_fee value.
-        let mut fee_chain_difference_str = String::new();
-        if fee_chain_difference > 0 {
-            fee_chain_difference_str = "Recomputing fee for chain ".to_string();
+        // News recorded in one of the last `older_than_blocks` blocks are kept, even if acknowledged.
+        let mut recent_blocks = HashSet::new();
+
+        for height in current_height.saturating_sub(older_than_blocks) + 1..=current_height {
+            recent_blocks.insert(self.client.get_block_id_by_height(&height)?);
         }
 
-        debug!(
-            "{} {}EstimateNetworkFee({}) | ParentTotalSats({}) | ChildTotalSats({}) | BumpFeePercentage({}) | ParentAmountOutputs({}) | ParentVbytes({}) | TotalFee({}) | FeeChainDifference({}) | ChainVsize({})",
+        let summary = self.store.prune(&recent_blocks)?;
+
+        info!(
+            "{} Store pruned | News({}) | Transactions({}) | Speedups({})",
             style("Coordinator").green(),
-            style(fee_chain_difference_str),
-            style(network_fee_rate).red(),
-            style(parent_total_sats).red(),
-            style(child_total_sats).red(),
-            style(bump_fee_percentage).red(),
-            style(parent_amount_outputs).red(),
-            style(parent_vbytes).red(),
-            style(total_fee_bumped).red(),
-            style(fee_chain_difference).red(),
-            style(chain_vsize).red(),
+            summary.news,
+            summary.transactions,
+            summary.speedups
         );
 
-        Ok(total_fee_bumped)
+        Ok(summary)
     }
 
-    fn get_bump_fee_percentage_strategy(
+    fn read_events(
         &self,
-        prev_bump_fee: f64,
-    ) -> Result<f64, BitcoinCoordinatorError> {
-        if prev_bump_fee <= 0.0 {
-            return Ok(self.settings.base_fee_multiplier);
+        from_seq: u64,
+        limit: usize,
+    ) -> Result<Vec<JournalEntry>, BitcoinCoordinatorError> {
+        let entries = self.store.journal().read_events(from_seq, limit)?;
+        Ok(entries)
+    }
+
+    fn read_finalized(
+        &self,
+        since_seq: u64,
+        limit: usize,
+    ) -> Result<Vec<FinalizedTxEntry>, BitcoinCoordinatorError> {
+        let entries = self.store.read_finalized(since_seq, limit)?;
+        Ok(entries)
+    }
+
+    fn export_events_json(&self, path: &Path) -> Result<usize, BitcoinCoordinatorError> {
+        let mut entries = Vec::new();
+
+        loop {
+            let from_seq = entries
+                .last()
+                .map_or(0, |entry: &JournalEntry| entry.seq + 1);
+            let page = self
+                .store
+                .journal()
+                .read_events(from_seq, JOURNAL_EXPORT_PAGE_SIZE)?;
+
+            if page.is_empty() {
+                break;
+            }
+
+            entries.extend(page);
         }
 
-        // This method increases the previous bump fee by 50%.
-        // The `prev_bump_fee` parameter is the fee used in the last bump.
-        // The new bump factor is calculated by multiplying the previous bump fee by 1.5.
-        // For instance, if the previous bump fee was 1, the new bump factor becomes 1.5.
-        // If the previous bump fee was 2, the new bump factor becomes 3.0.
-        // This approach ensures a proportional increase in the fee rate with each bump attempt.
+        let export_error = |e: String| BitcoinCoordinatorError::JournalExportError(e);
+        let file = File::create(path).map_err(|e| export_error(e.to_string()))?;
+        serde_json::to_writer_pretty(BufWriter::new(file), &entries)
+            .map_err(|e| export_error(e.to_string()))?;
 
         info!(
-            "{} Bumping fee from {} to {}",
+            "{} Event journal exported | Entries({}) | Path({})",
             style("Coordinator").green(),
-            style(prev_bump_fee).blue(),
-            style(prev_bump_fee * self.settings.bump_fee_percentage).blue(),
+            style(entries.len()).blue(),
+            path.display()
         );
-        let bumped_feerate = prev_bump_fee * self.settings.bump_fee_percentage;
-        Ok(bumped_feerate)
+
+        Ok(entries.len())
     }
 
-    fn should_rbf_last_speedup(&self) -> Result<bool, BitcoinCoordinatorError> {
-        let reached_unconfirmed_speedups = self.store.has_reached_max_unconfirmed_speedups()?;
+    fn prune_events(&self, before_seq: u64) -> Result<u32, BitcoinCoordinatorError> {
+        self.check_ownership()?;
 
-        if reached_unconfirmed_speedups {
-            info!(
-                "{} Reached max unconfirmed speedups.",
-                style("Coordinator").green()
-            );
+        let removed = self.store.journal().prune_before(before_seq)?;
 
-            return Ok(true);
-        }
+        info!(
+            "{} Event journal pruned | Entries({}) | BeforeSeq({})",
+            style("Coordinator").green(),
+            style(removed).blue(),
+            style(before_seq).blue()
+        );
 
-        Ok(false)
+        Ok(removed)
     }
 
-    fn should_boost_speedup_again(&self) -> Result<bool, BitcoinCoordinatorError> {
-        let last_speedup = self.store.get_last_speedup()?;
+    fn export_snapshot(&self, path: &Path) -> Result<usize, BitcoinCoordinatorError> {
+        Ok(self.store.export_snapshot(path)?)
+    }
 
-        if let Some((speedup, rbf_tx)) = last_speedup {
-            let current_block_height = self.monitor.get_monitor_height()?;
-            // This block checks if the last speedup transaction should be replaced-by-fee.
-            // It retrieves the last speedup transaction and the number of times it has already been replaced (replace_speedup_count).
-            // The logic is: if the current block height is greater than the sum of the speedup's broadcast block height and the number of RBFs,
-            // then enough blocks have passed without confirmation, so we should bump the fee again.
-            // This helps ensure that stuck transactions are periodically rebroadcast with higher fees to improve their chances of confirmation.
-            let last_broadcast_block_height = if let Some(rbf_tx) = rbf_tx {
-                rbf_tx.broadcast_block_height
-            } else {
-                speedup.broadcast_block_height
-            };
+    fn import_snapshot(&self, path: &Path, force: bool) -> Result<usize, BitcoinCoordinatorError> {
+        self.check_ownership()?;
 
-            if current_block_height.saturating_sub(last_broadcast_block_height)
-                >= self.settings.min_blocks_before_resend_speedup
-            {
-                debug!(
-                    "{} Last CPFP should be bumped | CurrentHeight({}) | BroadcastHeight({}) | MinBlocksBeforeRBF({})",
-                    style("Coordinator").green(),
-                    style(current_block_height).blue(),
-                    style(last_broadcast_block_height).blue(),
-                    style(self.settings.min_blocks_before_resend_speedup).blue(),
-                );
+        let imported = self.store.import_snapshot(path, force)?;
 
-                return Ok(true);
-            }
-        }
+        // The dispatched transactions of the snapshot left without a speedup are recovered too.
+        self.recovered.set(false);
+        self.remonitor.set(true);
 
-        Ok(false)
+        Ok(imported)
     }
-}
 
-impl BitcoinCoordinatorApi for BitcoinCoordinator {
-    fn tick(&self) -> Result<(), BitcoinCoordinatorError> {
-        self.monitor.tick()?;
-        // The monitor is considered ready when it has fully indexed the blockchain and is up to date with the latest block.
-        // Note that if there is a significant gap in the indexing process, it may take multiple ticks for the monitor to become ready.
-        let is_ready = self.monitor.is_ready()?;
+    fn update_settings(
+        &self,
+        settings: CoordinatorSettingsConfig,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        self.check_ownership()?;
 
-        let is_ready_str = if is_ready { "Ready" } else { "Not Ready" };
-        debug!("{} {}", style("Coordinator").green(), is_ready_str);
+        settings.validate()?;
 
-        if !is_ready {
-            return Ok(());
+        let current = self.settings().clone();
+        let new_settings = CoordinatorSettings {
+            // The monitor is already running with its settings.
+            monitor_settings: current.monitor_settings.clone(),
+            ..CoordinatorSettings::resolve(settings, self.network)
+        };
+
+        check_node_settings(self.node.as_ref(), &new_settings)?;
+
+        if new_settings.fee_strategy != current.fee_strategy {
+            return Err(BitcoinCoordinatorError::InvalidConfiguration(
+                "fee_strategy can not be changed while the coordinator is running".to_string(),
+            ));
+        }
+
+        if new_settings.mode != current.mode {
+            return Err(BitcoinCoordinatorError::InvalidConfiguration(
+                "mode can not be changed while the coordinator is running, use promote".to_string(),
+            ));
         }
 
-        self.process_failed_speedups()?;
-        self.process_pending_txs_to_dispatch()?;
-        self.process_in_progress_txs()?;
-        self.process_in_progress_speedup_txs()?;
+        if new_settings.encrypt_store != current.encrypt_store {
+            return Err(BitcoinCoordinatorError::InvalidConfiguration(
+                "encrypt_store can not be changed while the coordinator is running".to_string(),
+            ));
+        }
 
-        if self.should_boost_speedup_again()? {
-            if self.should_rbf_last_speedup()? {
-                self.rbf_last_cpfp()?;
-                return Ok(());
-            }
+        let unconfirmed_speedups = self.store.get_unconfirmed_speedups()?.len() as u32;
 
-            self.boost_cpfp_again()?;
+        if new_settings.max_unconfirmed_speedups < unconfirmed_speedups {
+            return Err(BitcoinCoordinatorError::InvalidConfiguration(format!(
+                "max_unconfirmed_speedups ({}) is lower than the {} speedups currently unconfirmed",
+                new_settings.max_unconfirmed_speedups, unconfirmed_speedups
+            )));
         }
 
-        Ok(())
-    }
+        let changes = current.changes(&new_settings);
 
-    fn monitor(&self, data: TypesToMonitor) -> Result<(), BitcoinCoordinatorError> {
-        if let TypesToMonitor::Transactions(txs, _, _) = data.clone() {
-            if txs.is_empty() {
-                return Err(BitcoinCoordinatorError::BitcoinCoordinatorError(
-                    "transactions array is empty".to_string(),
-                ));
-            }
+        if changes.is_empty() {
+            return Ok(());
+        }
+
+        self.store.set_limits(
+            new_settings.max_unconfirmed_speedups,
+            new_settings.retry_attempts_sending_tx,
+            new_settings.retry_interval_seconds,
+        );
+        self.fee_estimator
+            .set_min_network_fee_rate(new_settings.min_network_fee_rate);
+        *self.settings.borrow_mut() = new_settings;
+
+        for change in changes.iter() {
+            info!(
+                "{} Setting updated | {}({} -> {})",
+                style("Coordinator").green(),
+                change.name,
+                style(&change.old_value).yellow(),
+                style(&change.new_value).blue()
+            );
+        }
+
+        for warning in self.settings().network_warnings(self.network) {
+            warn!(
+                "{} {}",
+                style("Coordinator").green(),
+                style(warning).yellow()
+            );
         }
 
-        self.monitor.monitor(data)?;
+        self.update_news(CoordinatorNews::SettingsUpdated(changes))?;
 
         Ok(())
     }
 
-    fn is_ready(&self) -> Result<bool, BitcoinCoordinatorError> {
-        // The coordinator is currently considered ready when the monitor is ready.
-        Ok(self.monitor.is_ready()?)
+    fn get_effective_settings(&self) -> CoordinatorSettings {
+        self.settings().clone()
     }
 
-    fn dispatch(
-        &self,
-        tx: Transaction,
-        speedup_data: Option<SpeedupData>,
-        context: String,
-        target_block_height: Option<BlockHeight>,
-        number_confirmation_trigger: Option<u32>,
-    ) -> Result<(), BitcoinCoordinatorError> {
-        let to_monitor = TypesToMonitor::Transactions(
-            vec![tx.compute_txid()],
-            context.clone(),
-            number_confirmation_trigger,
-        );
-        self.monitor.monitor(to_monitor)?;
+    fn promote(&self) -> Result<(), BitcoinCoordinatorError> {
+        self.check_running()?;
+        self.check_ownership()?;
 
-        // Save the transaction to be dispatched.
-        self.store
-            .save_tx(tx.clone(), speedup_data, target_block_height, context)?;
+        if !self.is_standby() {
+            return Ok(());
+        }
+
+        self.settings.borrow_mut().mode = CoordinatorMode::Active;
+        // The recovery runs again, for the transactions the previous active coordinator dispatched last.
+        self.recovered.set(false);
 
         info!(
-            "{} Mark Transaction({}) to dispatch",
+            "{} Promoted to active, broadcasting from the next tick",
             style("Coordinator").green(),
-            style(tx.compute_txid()).yellow()
         );
 
         Ok(())
     }
 
-    fn cancel(&self, data: TypesToMonitor) -> Result<(), BitcoinCoordinatorError> {
-        self.monitor.cancel(data.clone())?;
+    fn shutdown(&self) -> Result<ShutdownReport, BitcoinCoordinatorError> {
+        self.check_running()?;
+        self.check_ownership()?;
+        self.stopped.set(true);
 
-        if let TypesToMonitor::Transactions(txs, _, _) = data {
-            for tx in txs {
-                self.store.remove_tx(tx)?;
-            }
+        let queued_store_writes = self.pending_writes.len();
+        let flushed_store_writes = self.flush_pending_store_writes();
+
+        if let Err(e) = self.deliver_news() {
+            warn!(
+                "{} News not delivered to the subscribers on shutdown: {}",
+                style("Coordinator").green(),
+                e
+            );
         }
 
-        Ok(())
-    }
+        let txs_in_progress = self.store.get_txs_in_progress()?;
+        let txs_to_dispatch = txs_in_progress
+            .iter()
+            .filter(|tx| tx.state == TransactionState::ToDispatch)
+            .count();
 
-    fn get_transaction(&self, txid: Txid) -> Result<TransactionStatus, BitcoinCoordinatorError> {
-        let tx_status = self.monitor.get_tx_status(&txid)?;
-        Ok(tx_status)
-    }
+        let in_groups = |count: fn(&Self) -> Result<usize, BitcoinCoordinatorError>| {
+            self.in_funding_groups(count)
+                .map(|counts| counts.into_iter().sum::<usize>())
+        };
+
+        let checkpoint = ShutdownCheckpoint {
+            stopped_at: self.store.now_millis(),
+            monitor_height: self.monitor.get_monitor_height()?,
+            txs_to_dispatch,
+            txs_in_progress: txs_in_progress.len() - txs_to_dispatch,
+            unconfirmed_speedups: in_groups(|coordinator| {
+                Ok(coordinator.store.get_unconfirmed_speedups()?.len())
+            })?,
+            txs_without_speedup: in_groups(|coordinator| {
+                Ok(coordinator
+                    .store
+                    .get_dispatched_txs_without_speedup()?
+                    .len())
+            })?,
+            speedup_intents: in_groups(|coordinator| {
+                Ok(coordinator.store.get_speedup_intents()?.len())
+            })?,
+            // Writes still queued or discarded after failing again.
+            pending_store_writes: queued_store_writes - flushed_store_writes,
+        };
+
+        self.store
+            .set_run_state(CoordinatorRunState::Stopped(checkpoint.clone()))?;
+        self.store.release_ownership(self.instance_id)?;
 
-    fn add_funding(&self, utxo: Utxo) -> Result<(), BitcoinCoordinatorError> {
         info!(
-            "{} Funding added | Txid({}) | Vout({}) | Amount({}) | PublicKey({})",
+            "{} Shut down at height {} | flushed {} writes | {} transactions to dispatch | {} in progress | clean: {}",
             style("Coordinator").green(),
-            style(utxo.txid).cyan(),
-            style(utxo.vout).cyan(),
-            style(utxo.amount).cyan(),
-            style(utxo.pub_key).cyan()
+            style(checkpoint.monitor_height).blue(),
+            style(flushed_store_writes).blue(),
+            style(checkpoint.txs_to_dispatch).yellow(),
+            style(checkpoint.txs_in_progress).yellow(),
+            style(checkpoint.is_clean()).blue(),
         );
-        // Each time a speedup transaction is generated, it consumes the previous funding UTXO and leaves any change as the new funding for subsequent speedups.
-        // Therefore, every new funding UTXO should be recorded in the same format as a speedup transaction, ensuring the coordinator always tracks the latest available funding.
-        self.store.add_funding(utxo)?;
-
-        Ok(())
-    }
-
-    fn get_news(&self) -> Result<News, BitcoinCoordinatorError> {
-        let list_monitor_news = self.monitor.get_news()?;
-
-        let monitor_news = list_monitor_news
-            .into_iter()
-            .filter(|tx| {
-                if let MonitorNews::Transaction(_, _, context_data) = tx {
-                    !context_data.contains(CPFP_TRANSACTION_CONTEXT)
-                } else {
-                    true
-                }
-            })
-            .collect();
-
-        let coordinator_news = self.store.get_news()?;
-
-        Ok(News::new(monitor_news, coordinator_news))
-    }
 
-    fn ack_news(&self, news: AckNews) -> Result<(), BitcoinCoordinatorError> {
-        match news {
-            AckNews::Monitor(news) => self.monitor.ack_news(news)?,
-            AckNews::Coordinator(news) => self.store.ack_news(news)?,
-        }
-        Ok(())
+        Ok(ShutdownReport {
+            flushed_store_writes,
+            checkpoint,
+        })
     }
 }
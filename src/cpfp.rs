@@ -0,0 +1,261 @@
+use crate::errors::BitcoinCoordinatorError;
+use bitcoin::{
+    absolute::LockTime,
+    ecdsa,
+    key::{Secp256k1, UntweakedPublicKey},
+    opcodes::all::OP_CHECKSIG,
+    script::Builder,
+    secp256k1::Message,
+    sighash::{EcdsaSighashType, Prevouts, SighashCache, TapSighashType},
+    taproot,
+    transaction::Version,
+    Amount, OutPoint, PublicKey, Script, ScriptBuf, Sequence, Transaction, TxIn, TxOut,
+    WPubkeyHash, Witness,
+};
+use key_manager::key_manager::KeyManager;
+use protocol_builder::types::{output::SpeedupData, Utxo};
+
+/// How an output paid to a speedup key is spent by a CPFP.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpeedupOutputKind {
+    /// Segwit v0 key spend (P2WPKH), signed with ECDSA and SIGHASH_ALL.
+    P2wpkh,
+    /// Taproot key path spend (P2TR without script tree), signed with schnorr and SIGHASH_DEFAULT.
+    P2trKeyPath,
+    /// Segwit v0 script spend (P2WSH) of a partial utxo, signed by the protocol builder with the script
+    /// of its output type.
+    P2wshScript,
+}
+
+impl SpeedupOutputKind {
+    /// Returns how `script_pubkey` is spent with `public_key`, or None when the key can not spend it.
+    pub fn from_script_pubkey(script_pubkey: &Script, public_key: &PublicKey) -> Option<Self> {
+        if let Ok(wpubkey_hash) = public_key.wpubkey_hash() {
+            if script_pubkey == ScriptBuf::new_p2wpkh(&wpubkey_hash).as_script() {
+                return Some(SpeedupOutputKind::P2wpkh);
+            }
+        }
+
+        if SpeedupOutputKind::P2trKeyPath
+            .script_pubkey(public_key)
+            .is_ok_and(|p2tr| script_pubkey == p2tr.as_script())
+        {
+            return Some(SpeedupOutputKind::P2trKeyPath);
+        }
+
+        None
+    }
+
+    /// Returns how the speedup utxo of `tx` is spent.
+    /// Partial utxos are spent by the protocol builder, which only spends segwit v0 outputs.
+    pub fn of_speedup_utxo(tx: &Transaction, speedup_data: &SpeedupData) -> Self {
+        if let (None, Some((_, vout, _, _))) = (&speedup_data.utxo, &speedup_data.partial_utxo) {
+            return match tx.output.get(*vout as usize) {
+                Some(output) if output.script_pubkey.is_p2wsh() => SpeedupOutputKind::P2wshScript,
+                _ => SpeedupOutputKind::P2wpkh,
+            };
+        }
+
+        speedup_data
+            .utxo
+            .as_ref()
+            .and_then(|utxo| {
+                let output = tx.output.get(utxo.vout as usize)?;
+                Self::from_script_pubkey(&output.script_pubkey, &utxo.pub_key)
+            })
+            .unwrap_or(SpeedupOutputKind::P2wpkh)
+    }
+
+    /// Returns the script of an output of this kind paid to `public_key`.
+    /// A P2WPKH output can only be paid to a compressed key, an uncompressed key is an error.
+    pub fn script_pubkey(
+        &self,
+        public_key: &PublicKey,
+    ) -> Result<ScriptBuf, BitcoinCoordinatorError> {
+        let script_pubkey = match self {
+            SpeedupOutputKind::P2wpkh => ScriptBuf::new_p2wpkh(&p2wpkh_hash(public_key)?),
+            SpeedupOutputKind::P2trKeyPath => {
+                let internal_key = UntweakedPublicKey::from(public_key.inner);
+                ScriptBuf::new_p2tr(&Secp256k1::verification_only(), internal_key, None)
+            }
+            SpeedupOutputKind::P2wshScript => {
+                ScriptBuf::new_p2wsh(&single_key_script(public_key).wscript_hash())
+            }
+        };
+
+        Ok(script_pubkey)
+    }
+
+    /// Witness with the biggest signature, used to estimate the vsize of a CPFP before signing it.
+    pub fn dummy_witness(&self) -> Witness {
+        match self {
+            // DER signature with sighash flag and a compressed public key.
+            SpeedupOutputKind::P2wpkh => Witness::from_slice(&[vec![0u8; 73], vec![0u8; 33]]),
+            // Schnorr signature, SIGHASH_DEFAULT does not add a sighash flag.
+            SpeedupOutputKind::P2trKeyPath => Witness::from_slice(&[vec![0u8; 64]]),
+            // DER signature with sighash flag and the witness script, assumed to be a single key script
+            // (<pubkey> OP_CHECKSIG). The fee of a signed speedup is computed from its actual vsize.
+            SpeedupOutputKind::P2wshScript => {
+                Witness::from_slice(&[vec![0u8; 73], vec![0u8; SINGLE_KEY_SCRIPT_LEN]])
+            }
+        }
+    }
+}
+
+// Hash of a key paid with P2WPKH, which only takes compressed keys.
+pub fn p2wpkh_hash(public_key: &PublicKey) -> Result<WPubkeyHash, BitcoinCoordinatorError> {
+    public_key
+        .wpubkey_hash()
+        .map_err(|_| BitcoinCoordinatorError::UncompressedPublicKey(*public_key))
+}
+
+// A push of a compressed public key followed by OP_CHECKSIG.
+const SINGLE_KEY_SCRIPT_LEN: usize = 35;
+
+fn single_key_script(public_key: &PublicKey) -> ScriptBuf {
+    Builder::new()
+        .push_key(public_key)
+        .push_opcode(OP_CHECKSIG)
+        .into_script()
+}
+
+// Builds and signs a CPFP spending the speedup outputs (anchors) and the funding (a P2WPKH output)
+// to a single change output paid to `change_key`. Each input is signed with the key of its utxo.
+// It is used when some anchor is a taproot output, the protocol builder only spends segwit v0 outputs.
+pub fn build_cpfp_tx(
+    anchors: &[(Utxo, SpeedupOutputKind)],
+    funding: &Utxo,
+    change_key: &PublicKey,
+    fee: u64,
+    key_manager: &KeyManager,
+) -> Result<Transaction, BitcoinCoordinatorError> {
+    let total_amount: u64 =
+        anchors.iter().map(|(utxo, _)| utxo.amount).sum::<u64>() + funding.amount;
+
+    // The caller checks the fee can be paid, an unpayable CPFP is never broadcast.
+    let change = TxOut {
+        value: Amount::from_sat(total_amount.saturating_sub(fee)),
+        script_pubkey: SpeedupOutputKind::P2wpkh.script_pubkey(change_key)?,
+    };
+
+    sign_cpfp_tx(anchors, funding, change, key_manager)
+}
+
+// Builds and signs a CPFP like build_cpfp_tx that pays all its inputs as fee, used when the change would be dust.
+// A transaction needs an output, it has a single empty OP_RETURN output of zero value.
+pub fn build_cpfp_tx_without_change(
+    anchors: &[(Utxo, SpeedupOutputKind)],
+    funding: &Utxo,
+    key_manager: &KeyManager,
+) -> Result<Transaction, BitcoinCoordinatorError> {
+    let output = TxOut {
+        value: Amount::ZERO,
+        script_pubkey: ScriptBuf::new_op_return([]),
+    };
+
+    sign_cpfp_tx(anchors, funding, output, key_manager)
+}
+
+fn sign_cpfp_tx(
+    anchors: &[(Utxo, SpeedupOutputKind)],
+    funding: &Utxo,
+    output: TxOut,
+    key_manager: &KeyManager,
+) -> Result<Transaction, BitcoinCoordinatorError> {
+    let mut inputs: Vec<(Utxo, SpeedupOutputKind)> = anchors.to_vec();
+    inputs.push((funding.clone(), SpeedupOutputKind::P2wpkh));
+
+    let prevouts: Vec<TxOut> = inputs
+        .iter()
+        .map(|(utxo, kind)| {
+            Ok(TxOut {
+                value: Amount::from_sat(utxo.amount),
+                script_pubkey: kind.script_pubkey(&utxo.pub_key)?,
+            })
+        })
+        .collect::<Result<_, BitcoinCoordinatorError>>()?;
+
+    let mut tx = Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: inputs
+            .iter()
+            .map(|(utxo, _)| TxIn {
+                previous_output: OutPoint::new(utxo.txid, utxo.vout),
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                witness: Witness::new(),
+            })
+            .collect(),
+        output: vec![output],
+    };
+
+    let signing_error = |e: String| BitcoinCoordinatorError::SpeedupSigningError(e);
+    let mut witnesses = Vec::with_capacity(inputs.len());
+    let mut sighash_cache = SighashCache::new(&tx);
+
+    for (index, (utxo, kind)) in inputs.iter().enumerate() {
+        let witness = match kind {
+            SpeedupOutputKind::P2wpkh => {
+                let sighash = sighash_cache
+                    .p2wpkh_signature_hash(
+                        index,
+                        &prevouts[index].script_pubkey,
+                        prevouts[index].value,
+                        EcdsaSighashType::All,
+                    )
+                    .map_err(|e| signing_error(e.to_string()))?;
+
+                let signature = key_manager
+                    .sign_ecdsa_message(&Message::from(sighash), &utxo.pub_key)
+                    .map_err(|e| signing_error(e.to_string()))?;
+
+                let signature = ecdsa::Signature {
+                    signature,
+                    sighash_type: EcdsaSighashType::All,
+                };
+
+                Witness::p2wpkh(&signature, &utxo.pub_key.inner)
+            }
+            SpeedupOutputKind::P2trKeyPath => {
+                let sighash = sighash_cache
+                    .taproot_key_spend_signature_hash(
+                        index,
+                        &Prevouts::All(&prevouts),
+                        TapSighashType::Default,
+                    )
+                    .map_err(|e| signing_error(e.to_string()))?;
+
+                // Key path spend of an output without script tree, the key is tweaked with no merkle root.
+                let (signature, _) = key_manager
+                    .sign_schnorr_message_with_tap_tweak(
+                        &Message::from(sighash),
+                        &utxo.pub_key,
+                        None,
+                    )
+                    .map_err(|e| signing_error(e.to_string()))?;
+
+                let signature = taproot::Signature {
+                    signature,
+                    sighash_type: TapSighashType::Default,
+                };
+
+                Witness::p2tr_key_spend(&signature)
+            }
+            // Script spends need the script of the partial utxo output type, only the protocol builder signs them.
+            SpeedupOutputKind::P2wshScript => {
+                return Err(signing_error(
+                    "a P2WSH anchor can not be signed with a speedup key".to_string(),
+                ))
+            }
+        };
+
+        witnesses.push(witness);
+    }
+
+    for (input, witness) in tx.input.iter_mut().zip(witnesses) {
+        input.witness = witness;
+    }
+
+    Ok(tx)
+}
@@ -0,0 +1,110 @@
+use crate::types::{
+    BlockingReason, PendingReason, SpeedupState, SpeedupSummary, TransactionEvent,
+    TransactionHistoryEntry, TransactionState, TxDiagnosis,
+};
+use bitvmx_bitcoin_rpc::types::BlockHeight;
+
+// Lists what holds a transaction back, from the facts gathered by diagnose and the reason it is pending.
+// Only transactions waiting to be dispatched or confirmed can be blocked.
+pub fn blocking_reasons(
+    diagnosis: &TxDiagnosis,
+    pending_reason: Option<PendingReason>,
+) -> Vec<BlockingReason> {
+    let mut reasons = Vec::new();
+
+    if !matches!(
+        diagnosis.state,
+        TransactionState::ToDispatch | TransactionState::Dispatched
+    ) {
+        return reasons;
+    }
+
+    if diagnosis.node_unreachable_since.is_some() {
+        reasons.push(BlockingReason::NodeUnreachable);
+    }
+
+    match pending_reason {
+        Some(PendingReason::TargetHeightNotReached) => {
+            reasons.push(BlockingReason::AwaitingTargetHeight)
+        }
+        Some(PendingReason::WaitingForLocktime(height)) => {
+            reasons.push(BlockingReason::AwaitingLocktime(height))
+        }
+        Some(PendingReason::DependencyNotConfirmed) => {
+            reasons.push(BlockingReason::DependencyNotConfirmed)
+        }
+        Some(PendingReason::RetryBackoff) => reasons.push(BlockingReason::RetryBackoff),
+        Some(PendingReason::RetriesExhausted) => reasons.push(BlockingReason::RetriesExhausted),
+        Some(PendingReason::FundingBlocked) | None => {}
+    }
+
+    // Speedups are blocked by the funding or by the size of the unconfirmed chain.
+    // A deferred speedup with funding available and room in the chain means the funding is too small.
+    let funding_blocked =
+        pending_reason == Some(PendingReason::FundingBlocked) && !diagnosis.ancestor_limit_reached;
+
+    let waiting_for_height = matches!(
+        pending_reason,
+        Some(PendingReason::TargetHeightNotReached | PendingReason::WaitingForLocktime(_))
+    );
+
+    if diagnosis.has_speedup && !waiting_for_height {
+        if !diagnosis.funding_available || funding_blocked {
+            reasons.push(BlockingReason::FundingInsufficient);
+        }
+
+        if diagnosis.ancestor_limit_reached {
+            reasons.push(BlockingReason::AncestorLimitReached);
+        }
+    }
+
+    if diagnosis.state == TransactionState::Dispatched
+        && diagnosis
+            .paying_fee_rate
+            .is_some_and(|fee_rate| fee_rate < diagnosis.network_fee_rate)
+    {
+        reasons.push(BlockingReason::FeeBelowNetworkRate);
+    }
+
+    reasons
+}
+
+// The last event of the history that sent the transaction to the node, or failed trying.
+pub fn last_dispatch_attempt(events: &[TransactionHistoryEntry]) -> Option<TransactionEvent> {
+    events
+        .iter()
+        .rev()
+        .map(|entry| &entry.event)
+        .find(|event| {
+            matches!(
+                event,
+                TransactionEvent::Dispatched { .. }
+                    | TransactionEvent::DispatchRetried { .. }
+                    | TransactionEvent::Rebroadcast { .. }
+                    | TransactionEvent::ReplacementDispatched { .. }
+            )
+        })
+        .cloned()
+}
+
+// Fee rate of the last speedup that can still be confirmed, None if there is none.
+pub fn last_speedup_fee_rate(speedups: &[SpeedupSummary]) -> Option<u64> {
+    speedups
+        .iter()
+        .rev()
+        .find(|speedup| {
+            matches!(
+                speedup.state,
+                SpeedupState::Dispatched | SpeedupState::Confirmed | SpeedupState::Finalized
+            )
+        })
+        .map(|speedup| speedup.network_fee_rate_used)
+}
+
+pub fn last_rbf_block_height(speedups: &[SpeedupSummary]) -> Option<BlockHeight> {
+    speedups
+        .iter()
+        .filter(|speedup| speedup.is_rbf)
+        .map(|speedup| speedup.broadcast_block_height)
+        .max()
+}
@@ -0,0 +1,84 @@
+use crate::{errors::BitcoinCoordinatorError, settings::STORE_ENCRYPTION_KEY_INDEX};
+use bitcoin::{
+    hashes::{sha256, Hash},
+    secp256k1::Message,
+};
+use chacha20poly1305::{aead::Aead, Key, KeyInit, XChaCha20Poly1305, XNonce};
+use key_manager::{errors::KeyManagerError, key_manager::KeyManager, key_type::BitcoinKeyType};
+
+// Format prefix of the encrypted records, followed by the hex of the nonce and the ciphertext.
+// Records without it were written in plaintext and are read as they are.
+const ENCRYPTED_RECORD_PREFIX: &str = "enc1:";
+const NONCE_SIZE: usize = 24;
+
+// Message signed by the key manager to derive the store encryption key.
+const KEY_DERIVATION_MESSAGE: &[u8] = b"bitcoin_coordinator/store_encryption_key";
+
+/// Encrypts the records of the coordinator store with XChaCha20-Poly1305 and a random nonce per record.
+/// The key is only kept in memory, it is never written to the store.
+#[derive(Clone)]
+pub struct StoreCipher {
+    cipher: XChaCha20Poly1305,
+}
+
+impl StoreCipher {
+    pub fn new(key: &[u8; 32]) -> Self {
+        Self {
+            cipher: XChaCha20Poly1305::new(Key::from_slice(key)),
+        }
+    }
+
+    /// Derives the key from the signature of a fixed message, so the same key manager always opens the store.
+    pub fn from_key_manager(key_manager: &KeyManager) -> Result<Self, BitcoinCoordinatorError> {
+        let key_error = |e: KeyManagerError| {
+            BitcoinCoordinatorError::BitcoinCoordinatorError(format!(
+                "Failed to derive the store encryption key: {e}"
+            ))
+        };
+
+        let public_key = key_manager
+            .derive_keypair(BitcoinKeyType::P2wpkh, STORE_ENCRYPTION_KEY_INDEX)
+            .map_err(key_error)?;
+        let digest = sha256::Hash::hash(KEY_DERIVATION_MESSAGE);
+        let signature = key_manager
+            .sign_ecdsa_message(&Message::from_digest(digest.to_byte_array()), &public_key)
+            .map_err(key_error)?;
+
+        let key = sha256::Hash::hash(&signature.serialize_compact());
+
+        Ok(Self::new(&key.to_byte_array()))
+    }
+
+    pub fn is_encrypted(record: &str) -> bool {
+        record.starts_with(ENCRYPTED_RECORD_PREFIX)
+    }
+
+    // Returns None if encryption fails, which only happens for payloads too large for the cipher.
+    pub fn encrypt(&self, plaintext: &[u8]) -> Option<String> {
+        let nonce: [u8; NONCE_SIZE] = rand::random();
+        let ciphertext = self
+            .cipher
+            .encrypt(XNonce::from_slice(&nonce), plaintext)
+            .ok()?;
+
+        Some(format!(
+            "{ENCRYPTED_RECORD_PREFIX}{}{}",
+            hex::encode(nonce),
+            hex::encode(ciphertext)
+        ))
+    }
+
+    // Returns None if the record is malformed, was written with another key or was tampered with.
+    pub fn decrypt(&self, record: &str) -> Option<Vec<u8>> {
+        let bytes = hex::decode(record.strip_prefix(ENCRYPTED_RECORD_PREFIX)?).ok()?;
+
+        if bytes.len() < NONCE_SIZE {
+            return None;
+        }
+
+        let (nonce, ciphertext) = bytes.split_at(NONCE_SIZE);
+        self.cipher
+            .decrypt(XNonce::from_slice(nonce), ciphertext)
+            .ok()
+    }
+}
@@ -1,9 +1,11 @@
-use crate::types::TransactionState;
-use bitcoin::Txid;
+use crate::types::{CoordinatorNews, TickSkipReason, TransactionState};
+use bitcoin::{OutPoint, PublicKey, Txid};
 use bitvmx_bitcoin_rpc::errors::BitcoinClientError;
 use config as settings;
 use protocol_builder::errors::ProtocolBuilderError;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use uuid::Uuid;
 
 #[derive(Error, Debug)]
 pub enum BitVMXError {
@@ -45,6 +47,9 @@ pub enum BitcoinCoordinatorStoreError {
     #[error("Speedup transaction not found")]
     SpeedupNotFound,
 
+    #[error("Speedup {0} is part of the speedup chain and can not be removed")]
+    CannotRemoveActiveSpeedup(Txid),
+
     #[error("Invalid transaction state")]
     InvalidTransactionState,
 
@@ -56,6 +61,53 @@ pub enum BitcoinCoordinatorStoreError {
 
     #[error("Transaction state transition invalid: from {0:?} to {1:?}. Txid: {2}")]
     InvalidStateTransition(TransactionState, TransactionState, Txid),
+
+    #[error("Transaction batch is empty")]
+    EmptyTransactionBatch,
+
+    #[error("Duplicate transaction in batch: {0}")]
+    DuplicateTransactionInBatch(Txid),
+
+    #[error("Transaction already dispatched: {0}")]
+    TransactionAlreadyDispatched(Txid),
+
+    #[error("Failed to decrypt store record {0}, the encryption key is wrong or missing")]
+    DecryptionError(String),
+
+    #[error("Failed to encrypt store record {0}")]
+    EncryptionError(String),
+
+    #[error(
+        "Store record {0} has version {1}, it was written by a newer version of the coordinator"
+    )]
+    UnsupportedRecordVersion(String, u16),
+
+    #[error("Failed to upgrade store record {0}: {1}")]
+    RecordMigrationError(String, String),
+
+    #[error("Failed to write store record {0}")]
+    WriteFailed(String),
+
+    #[error("Store is owned by instance {0} (pid {1}), its last heartbeat was {2} seconds ago")]
+    StoreAlreadyOwned(Uuid, u32, u64),
+
+    #[error("Store ownership was lost, the owner is now {0:?}")]
+    StoreOwnershipLost(Option<Uuid>),
+
+    #[error("Store transaction {0} is not open")]
+    StoreTransactionNotOpen(Uuid),
+
+    #[error("Error reading or writing the store snapshot {0}: {1}")]
+    SnapshotFileError(String, String),
+
+    #[error("Store snapshot has version {0}, only version {1} can be imported")]
+    UnsupportedSnapshotVersion(u32, u32),
+
+    #[error("Store snapshot checksum is {0}, its records hash to {1}")]
+    SnapshotChecksumMismatch(String, String),
+
+    #[error("Store is not empty, it has {0} records the snapshot would replace")]
+    StoreNotEmpty(usize),
 }
 
 #[derive(Error, Debug)]
@@ -86,6 +138,146 @@ pub enum BitcoinCoordinatorError {
 
     #[error("Invalid configuration: {0}")]
     InvalidConfiguration(String),
+
+    #[error("Cannot cancel a confirmed transaction: {0}")]
+    CannotCancelConfirmed(Txid),
+
+    #[error("Cannot cancel transaction {0}, it is {1:?} and will not be dispatched anymore")]
+    CannotCancelInactive(Txid, TransactionState),
+
+    #[error("Cannot reschedule a transaction that was already broadcast: {0}")]
+    CannotRescheduleDispatched(Txid),
+
+    #[error("Transaction already dispatched: {0}")]
+    AlreadyDispatched(Txid),
+
+    #[error("Transaction {0} depends on unknown transaction {1}")]
+    UnknownDependency(Txid, Txid),
+
+    #[error("Transaction {0} depends on itself through its dependencies")]
+    DependencyCycle(Txid),
+
+    #[error("Unknown funding group {0}, it must be added with add_funding_group")]
+    UnknownFundingGroup(String),
+
+    #[error("Invalid transaction {0}: {1}")]
+    InvalidTransaction(Txid, String),
+
+    #[error("Invalid speedup utxo for transaction {0}: {1}")]
+    InvalidSpeedupUtxo(Txid, String),
+
+    #[error("Speedup anchor of {0} sats is below the dust threshold of {1} sats for its script")]
+    AnchorBelowDust(u64, u64),
+
+    #[error("Speedup anchor of transaction {0} can not be spent by the CPFP: {1}")]
+    UnsupportedAnchorScript(Txid, String),
+
+    #[error("Speedup anchor does not match an output of transaction {0}: {1}")]
+    AnchorOutputMismatch(Txid, String),
+
+    #[error("Transaction {0} rejected by mempool: {1}")]
+    TransactionRejectedByMempool(Txid, String),
+
+    #[error("Error signing speedup transaction: {0}")]
+    SpeedupSigningError(String),
+
+    #[error("Error exporting the event journal: {0}")]
+    JournalExportError(String),
+
+    #[error("Sync to the node tip stalled at height {0}, no block indexed in {1} ticks")]
+    SyncStalled(u32, u32),
+
+    #[error(
+        "Store is locked by another process ({1}), remove {0} if no process is using the storage"
+    )]
+    StoreLocked(String, String),
+
+    #[error("Error with the store lock: {0}")]
+    StoreLockError(String),
+
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+
+    #[error("Invalid context {0:?}: {1}")]
+    InvalidContext(String, String),
+
+    #[error("The coordinator is stopped")]
+    CoordinatorStopped,
+
+    #[error("The coordinator is in standby, nothing is dispatched until it is promoted")]
+    CoordinatorInStandby,
+
+    #[error("Store write still failing after {0} attempts: {1}")]
+    StoreWriteFailed(u32, String),
+
+    #[error("External speedup {0} does not spend the current funding {1}")]
+    ExternalSpeedupFundingNotSpent(Txid, OutPoint),
+
+    #[error("External speedup {0} does not pay the declared change to output {1}: {2}")]
+    ExternalSpeedupChangeMismatch(Txid, u32, String),
+
+    #[error("External speedup {0} does not spend an output of transaction {1}")]
+    ExternalSpeedupParentNotSpent(Txid, Txid),
+
+    #[error("A UTXO set is already watched with label {0}")]
+    UtxoSetAlreadyWatched(String),
+
+    #[error("No UTXO set is watched with label {0}")]
+    UnknownUtxoSet(String),
+
+    #[error("No group of transactions has id {0}")]
+    UnknownGroup(Uuid),
+
+    #[error("The funding of the speedup chain was not found when the speedup was built")]
+    FundingDisappeared,
+
+    #[error("Speedup transaction {0} has no outputs")]
+    SpeedupHasNoOutputs(Txid),
+
+    #[error("There is no speedup to replace")]
+    NoSpeedupToReplace,
+
+    #[error("Speedup data has neither a speedup utxo nor a partial utxo amount")]
+    SpeedupDataMissingAmount,
+
+    #[error("Transaction {0} has no speedup data, it can not be paid by a speedup")]
+    SpeedupDataMissing(Txid),
+
+    #[error("Public key {0} is not compressed, it can not be paid with P2WPKH")]
+    UncompressedPublicKey(PublicKey),
+}
+
+impl BitcoinCoordinatorError {
+    /// Why the rest of a tick step is skipped on this error, None when the error stops the tick.
+    pub fn tick_skip_reason(&self) -> Option<TickSkipReason> {
+        match self {
+            BitcoinCoordinatorError::FundingDisappeared => Some(TickSkipReason::FundingDisappeared),
+            BitcoinCoordinatorError::SpeedupHasNoOutputs(_) => {
+                Some(TickSkipReason::SpeedupHasNoOutputs)
+            }
+            BitcoinCoordinatorError::NoSpeedupToReplace => Some(TickSkipReason::NoSpeedupToReplace),
+            BitcoinCoordinatorError::SpeedupDataMissingAmount => {
+                Some(TickSkipReason::SpeedupDataMissingAmount)
+            }
+            BitcoinCoordinatorError::SpeedupDataMissing(_) => {
+                Some(TickSkipReason::SpeedupDataMissing)
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the error comes from a call that could not reach the node (connection refused, timeout, warmup).
+    pub fn is_node_unreachable(&self) -> bool {
+        let error_msg = match self {
+            BitcoinCoordinatorError::BitcoinClientError(e) => e.to_string(),
+            BitcoinCoordinatorError::RpcError(e) => e.to_string(),
+            BitcoinCoordinatorError::MonitorError(e) => e.to_string(),
+            _ => return false,
+        };
+
+        BroadcastFailureKind::from_error_message(&error_msg)
+            == BroadcastFailureKind::ConnectionError
+    }
 }
 
 #[derive(Error, Debug)]
@@ -117,6 +309,10 @@ pub enum BitcoinBroadcastErrorKind {
     AlreadyKnown,
     /// The transaction was rejected by mempool policy (fee too low, mempool full, etc.).
     MempoolRejection,
+    /// A replacement (RBF) was rejected because its fee does not pay enough over the replaced transactions (code -26).
+    InsufficientReplacementFee,
+    /// The transaction breaks a mempool policy rule that does not change by retrying it (conflict, chain limits, etc.).
+    PolicyRejection,
     /// A network/connection/timeout error occurred while talking to the node.
     NetworkError,
     /// Any other unexpected error.
@@ -125,29 +321,202 @@ pub enum BitcoinBroadcastErrorKind {
 
 impl BitcoinBroadcastErrorKind {
     pub fn from_error_message(error_msg: &str) -> Self {
+        BroadcastFailureKind::from_error_message(error_msg).into()
+    }
+}
+
+impl From<BroadcastFailureKind> for BitcoinBroadcastErrorKind {
+    fn from(kind: BroadcastFailureKind) -> Self {
+        match kind {
+            BroadcastFailureKind::AlreadyInMempool | BroadcastFailureKind::AlreadyInChain => {
+                BitcoinBroadcastErrorKind::AlreadyKnown
+            }
+            BroadcastFailureKind::MinRelayFeeNotMet | BroadcastFailureKind::MempoolFull => {
+                BitcoinBroadcastErrorKind::MempoolRejection
+            }
+            BroadcastFailureKind::InsufficientReplacementFee => {
+                BitcoinBroadcastErrorKind::InsufficientReplacementFee
+            }
+            BroadcastFailureKind::MempoolConflict
+            | BroadcastFailureKind::TooLongMempoolChain
+            | BroadcastFailureKind::PolicyRejection => BitcoinBroadcastErrorKind::PolicyRejection,
+            BroadcastFailureKind::ConnectionError => BitcoinBroadcastErrorKind::NetworkError,
+            BroadcastFailureKind::MissingInputs | BroadcastFailureKind::Other => {
+                BitcoinBroadcastErrorKind::Other
+            }
+        }
+    }
+}
+
+// Bitcoin Core RPC error codes returned by sendrawtransaction.
+const RPC_VERIFY_REJECTED: i32 = -26;
+const RPC_VERIFY_ALREADY_IN_CHAIN: i32 = -27;
+const RPC_IN_WARMUP: i32 = -28;
+
+/// Reason why the node did not accept a transaction broadcast, parsed from the RPC error code and message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BroadcastFailureKind {
+    /// The transaction is already in the node mempool (txn-already-in-mempool, txn-already-known).
+    AlreadyInMempool,
+    /// The transaction is already in a block, its outputs are in the utxo set (code -27).
+    AlreadyInChain,
+    /// The transaction spends an output already spent by another mempool transaction (txn-mempool-conflict).
+    MempoolConflict,
+    /// An input of the transaction does not exist or was already spent in the chain (bad-txns-inputs-missingorspent).
+    MissingInputs,
+    /// The fee rate is below the node min relay fee or the mempool min fee.
+    MinRelayFeeNotMet,
+    /// The mempool is full and the transaction does not pay enough to enter it.
+    MempoolFull,
+    /// The transaction exceeds the mempool ancestor or descendant limits (too-long-mempool-chain).
+    TooLongMempoolChain,
+    /// A replacement (RBF) does not pay enough over the replaced transactions.
+    InsufficientReplacementFee,
+    /// Any other mempool policy rejection (code -26), like dust or non-standard outputs.
+    PolicyRejection,
+    /// The node could not be reached or is not ready (connection refused, timeout, warmup).
+    ConnectionError,
+    /// Any other error.
+    Other,
+}
+
+/// What the coordinator does with a transaction whose broadcast failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BroadcastFailureAction {
+    /// The node already has the transaction, so it is handled as dispatched, or as confirmed when it is mined.
+    Dispatched,
+    /// The transaction is sent again on the next tick, without counting a retry attempt.
+    Requeue,
+    /// The transaction is sent again after the retry interval, counting a retry attempt.
+    Retry,
+    /// The transaction is marked as Failed.
+    Fail,
+}
+
+impl BroadcastFailureKind {
+    pub fn from_error_message(error_msg: &str) -> Self {
+        Self::classify(rpc_error_code(error_msg), error_msg)
+    }
+
+    pub fn classify(code: Option<i32>, error_msg: &str) -> Self {
         let msg = error_msg;
 
-        // Already-known / already-confirmed transaction
+        // Already-confirmed transaction
+        if msg.contains("Transaction outputs already in utxo set")
+            || msg.contains("already in block chain")
+            || code == Some(RPC_VERIFY_ALREADY_IN_CHAIN)
+        {
+            return BroadcastFailureKind::AlreadyInChain;
+        }
+
+        // Already-known transaction, "txn-already-known" is also returned for a transaction mined since.
         if msg.contains("already in mempool")
-            || msg.contains("Transaction outputs already in utxo set")
+            || msg.contains("txn-already-in-mempool")
+            || msg.contains("txn-already-known")
+            || msg.contains("already known")
         {
-            return BitcoinBroadcastErrorKind::AlreadyKnown;
+            return BroadcastFailureKind::AlreadyInMempool;
         }
 
-        // Mempool policy / fee issues
-        if msg.contains("mempool full")
-            || msg.contains("insufficient priority")
-            || msg.contains("min relay fee")
+        if msg.contains("txn-mempool-conflict") {
+            return BroadcastFailureKind::MempoolConflict;
+        }
+
+        if msg.contains("bad-txns-inputs-missingorspent")
+            || msg.contains("missing-inputs")
+            || msg.contains("Missing inputs")
+        {
+            return BroadcastFailureKind::MissingInputs;
+        }
+
+        if msg.contains("too-long-mempool-chain") {
+            return BroadcastFailureKind::TooLongMempoolChain;
+        }
+
+        // Replacement (RBF) fee does not satisfy the incremental relay fee
+        if msg.contains("insufficient fee") {
+            return BroadcastFailureKind::InsufficientReplacementFee;
+        }
+
+        if msg.contains("mempool full") {
+            return BroadcastFailureKind::MempoolFull;
+        }
+
+        if msg.contains("min relay fee")
             || msg.contains("mempool min fee not met")
+            || msg.contains("insufficient priority")
         {
-            return BitcoinBroadcastErrorKind::MempoolRejection;
+            return BroadcastFailureKind::MinRelayFeeNotMet;
+        }
+
+        if code == Some(RPC_VERIFY_REJECTED) {
+            return BroadcastFailureKind::PolicyRejection;
         }
 
         // Infrastructure / connectivity issues
-        if msg.contains("network") || msg.contains("connection") || msg.contains("timeout") {
-            return BitcoinBroadcastErrorKind::NetworkError;
+        if code == Some(RPC_IN_WARMUP)
+            || msg.contains("network")
+            || msg.contains("connection")
+            || msg.contains("Connection")
+            || msg.contains("timeout")
+        {
+            return BroadcastFailureKind::ConnectionError;
+        }
+
+        BroadcastFailureKind::Other
+    }
+
+    pub fn action(&self) -> BroadcastFailureAction {
+        match self {
+            BroadcastFailureKind::AlreadyInMempool | BroadcastFailureKind::AlreadyInChain => {
+                BroadcastFailureAction::Dispatched
+            }
+            // The node was not asked about the transaction, so the attempt does not count.
+            BroadcastFailureKind::ConnectionError => BroadcastFailureAction::Requeue,
+            // Fee and mempool size conditions change over time, so the transaction may be accepted later.
+            BroadcastFailureKind::MinRelayFeeNotMet
+            | BroadcastFailureKind::MempoolFull
+            | BroadcastFailureKind::InsufficientReplacementFee => BroadcastFailureAction::Retry,
+            BroadcastFailureKind::MempoolConflict
+            | BroadcastFailureKind::MissingInputs
+            | BroadcastFailureKind::TooLongMempoolChain
+            | BroadcastFailureKind::PolicyRejection
+            | BroadcastFailureKind::Other => BroadcastFailureAction::Fail,
         }
+    }
 
-        BitcoinBroadcastErrorKind::Other
+    /// The news reported when a transaction broadcast fails with this kind.
+    /// None when the node already has the transaction, as the broadcast did not fail.
+    pub fn news(&self, txid: Txid, context: String, error_msg: String) -> Option<CoordinatorNews> {
+        let news = match self.action() {
+            BroadcastFailureAction::Dispatched => return None,
+            BroadcastFailureAction::Requeue => {
+                CoordinatorNews::NetworkError(txid, context, error_msg)
+            }
+            BroadcastFailureAction::Retry => {
+                CoordinatorNews::MempoolRejection(txid, context, error_msg)
+            }
+            BroadcastFailureAction::Fail => {
+                CoordinatorNews::DispatchTransactionError(txid, context, error_msg, *self)
+            }
+        };
+
+        Some(news)
     }
 }
+
+// Parses the RPC error code from an error message like "RpcError { code: -26, message: .. }".
+// The code is written `code: -26` by the RPC client and `"code":-26` in the JSON forwarded by Esplora.
+fn rpc_error_code(error_msg: &str) -> Option<i32> {
+    let start = ["code: ", "\"code\":"]
+        .iter()
+        .find_map(|prefix| Some(error_msg.find(prefix)? + prefix.len()))?;
+    let code: String = error_msg[start..]
+        .chars()
+        .enumerate()
+        .take_while(|(i, c)| c.is_ascii_digit() || (*i == 0 && *c == '-'))
+        .map(|(_, c)| c)
+        .collect();
+
+    code.parse().ok()
+}
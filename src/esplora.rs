@@ -0,0 +1,385 @@
+use crate::{
+    ancestry::MempoolAncestry,
+    config::FeeEstimateMode,
+    errors::BitcoinCoordinatorError,
+    fee::SmartFeeEstimator,
+    funding::{FundingOutputChecker, FundingOutputState},
+    node::NodeApi,
+    settings::{DEFAULT_ESPLORA_TIMEOUT_SECONDS, DEFAULT_FEE_CONF_TARGET},
+};
+use bitcoin::{consensus::encode, Address, Amount, Block, BlockHash, OutPoint, Transaction, Txid};
+use bitvmx_bitcoin_rpc::{
+    bitcoin_client::{BitcoinClientApi, RawTxInfo},
+    errors::BitcoinClientError,
+    types::BlockHeight,
+};
+use serde::Deserialize;
+use std::{collections::HashMap, io::Read, str::FromStr, time::Duration};
+
+// Answer of /tx/:txid/outspend/:vout, mempool spends included.
+#[derive(Debug, Deserialize)]
+struct Outspend {
+    spent: bool,
+    txid: Option<Txid>,
+    // Status of the spending transaction, only when the output is spent.
+    status: Option<TxStatus>,
+}
+
+// Answer of /tx/:txid/status.
+#[derive(Debug, Deserialize)]
+struct TxStatus {
+    confirmed: bool,
+    block_height: Option<BlockHeight>,
+}
+
+/// Client of an Esplora HTTP API, used instead of the node with `Backend::Esplora`.
+/// It broadcasts the transactions, reports the tip height, estimates the fee rate, checks the funding outputs and
+/// answers the node calls of the conflict detection. The wallet and mining calls of `BitcoinClientApi` and the
+/// mempool checks of `NodeApi` (testmempoolaccept and the mempool ancestry) are not supported.
+#[derive(Clone)]
+pub struct EsploraClient {
+    url: String,
+    agent: ureq::Agent,
+}
+
+impl EsploraClient {
+    /// `url` is the base of the API, like `https://blockstream.info/api`.
+    pub fn new(url: &str) -> Self {
+        let agent = ureq::AgentBuilder::new()
+            .timeout(Duration::from_secs(DEFAULT_ESPLORA_TIMEOUT_SECONDS))
+            .build();
+
+        Self {
+            url: url.trim_end_matches('/').to_string(),
+            agent,
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    // Body of a GET request, None when the server answers 404.
+    fn get(&self, path: &str) -> Result<Option<String>, BitcoinClientError> {
+        let url = format!("{}{}", self.url, path);
+
+        match self.agent.get(&url).call() {
+            Ok(response) => response
+                .into_string()
+                .map(Some)
+                .map_err(|e| BitcoinClientError::ClientError(format!("{url}: {e}"))),
+            Err(ureq::Error::Status(404, _)) => Ok(None),
+            Err(ureq::Error::Status(status, response)) => {
+                let body = response.into_string().unwrap_or_default();
+                Err(BitcoinClientError::ClientError(format!(
+                    "{url}: status {status}: {body}"
+                )))
+            }
+            Err(e) => Err(BitcoinClientError::ClientError(e.to_string())),
+        }
+    }
+
+    // Body of a GET request to a binary endpoint, like `/block/:hash/raw`.
+    fn get_bytes(&self, path: &str) -> Result<Vec<u8>, BitcoinClientError> {
+        let url = format!("{}{}", self.url, path);
+
+        let response = match self.agent.get(&url).call() {
+            Ok(response) => response,
+            Err(ureq::Error::Status(status, response)) => {
+                let body = response.into_string().unwrap_or_default();
+                return Err(BitcoinClientError::ClientError(format!(
+                    "{url}: status {status}: {body}"
+                )));
+            }
+            Err(e) => return Err(BitcoinClientError::ClientError(e.to_string())),
+        };
+
+        let mut bytes = Vec::new();
+        response
+            .into_reader()
+            .read_to_end(&mut bytes)
+            .map_err(|e| BitcoinClientError::ClientError(format!("{url}: {e}")))?;
+
+        Ok(bytes)
+    }
+
+    fn get_required(&self, path: &str) -> Result<String, BitcoinClientError> {
+        self.get(path)?.ok_or_else(|| {
+            BitcoinClientError::ClientError(format!("{}{}: not found", self.url, path))
+        })
+    }
+
+    fn get_json<T: for<'de> Deserialize<'de>>(&self, path: &str) -> Result<T, BitcoinClientError> {
+        let body = self.get_required(path)?;
+
+        serde_json::from_str(&body)
+            .map_err(|e| BitcoinClientError::ClientError(format!("{}{}: {e}", self.url, path)))
+    }
+
+    /// Broadcasts the transaction with `POST /tx`.
+    /// A rejection keeps the message of the node, so it is classified like a sendrawtransaction error.
+    pub fn broadcast(&self, tx: &Transaction) -> Result<Txid, BitcoinClientError> {
+        let url = format!("{}/tx", self.url);
+        let send_error = |error| BitcoinClientError::FailedToSendTransaction { error };
+
+        let body = match self
+            .agent
+            .post(&url)
+            .send_string(&encode::serialize_hex(tx))
+        {
+            Ok(response) => response
+                .into_string()
+                .map_err(|e| send_error(format!("{url}: {e}")))?,
+            Err(ureq::Error::Status(_, response)) => {
+                return Err(send_error(response.into_string().unwrap_or_default()))
+            }
+            Err(e) => return Err(send_error(e.to_string())),
+        };
+
+        Txid::from_str(body.trim()).map_err(|e| send_error(format!("{url}: {e}")))
+    }
+
+    pub fn get_tip_height(&self) -> Result<BlockHeight, BitcoinClientError> {
+        let body = self.get_required("/blocks/tip/height")?;
+
+        body.trim()
+            .parse()
+            .map_err(|e| BitcoinClientError::ClientError(format!("tip height {body}: {e}")))
+    }
+
+    /// Fee rates in sat/vB by confirmation target, from `/fee-estimates`.
+    pub fn get_fee_estimates(&self) -> Result<HashMap<u16, f64>, BitcoinClientError> {
+        let estimates: HashMap<String, f64> = self.get_json("/fee-estimates")?;
+
+        Ok(estimates
+            .into_iter()
+            .filter_map(|(target, fee_rate)| Some((target.parse().ok()?, fee_rate)))
+            .collect())
+    }
+
+    /// Fee rate in sat/vB to confirm within `conf_target` blocks, rounded up.
+    pub fn get_fee_rate(&self, conf_target: u16) -> Result<Option<u64>, BitcoinClientError> {
+        Ok(select_fee_rate(&self.get_fee_estimates()?, conf_target))
+    }
+
+    /// Spending transaction of the outpoint, in the chain or in the mempool.
+    pub fn get_outspend(&self, outpoint: &OutPoint) -> Result<Option<Txid>, BitcoinClientError> {
+        let outspend: Outspend =
+            self.get_json(&format!("/tx/{}/outspend/{}", outpoint.txid, outpoint.vout))?;
+
+        match (outspend.spent, outspend.txid) {
+            (false, _) => Ok(None),
+            (true, Some(txid)) => Ok(Some(txid)),
+            (true, None) => Err(BitcoinClientError::ClientError(format!(
+                "outpoint {outpoint} is spent by an unknown transaction"
+            ))),
+        }
+    }
+
+    // Status of the transaction, None when the server does not know it.
+    fn get_tx_status(&self, txid: &Txid) -> Result<Option<TxStatus>, BitcoinClientError> {
+        let Some(body) = self.get(&format!("/tx/{txid}/status"))? else {
+            return Ok(None);
+        };
+
+        serde_json::from_str(&body)
+            .map(Some)
+            .map_err(|e| BitcoinClientError::ClientError(format!("transaction {txid}: {e}")))
+    }
+
+    fn unsupported<T>(&self, call: &str) -> Result<T, BitcoinClientError> {
+        Err(BitcoinClientError::ClientError(format!(
+            "{call} is not supported by the Esplora backend"
+        )))
+    }
+}
+
+/// Fee rate of the estimate for the highest target not above `conf_target`, the smallest target when there is none.
+/// Esplora returns an estimate for a few targets only, a lower target always pays enough for a higher one.
+pub fn select_fee_rate(estimates: &HashMap<u16, f64>, conf_target: u16) -> Option<u64> {
+    let target = estimates
+        .keys()
+        .filter(|target| **target <= conf_target)
+        .max()
+        .or_else(|| estimates.keys().min())?;
+
+    let fee_rate = estimates[target];
+
+    if !fee_rate.is_finite() || fee_rate <= 0.0 {
+        return None;
+    }
+
+    Some(fee_rate.ceil() as u64)
+}
+
+impl BitcoinClientApi for EsploraClient {
+    fn send_transaction(&self, tx: &Transaction) -> Result<Txid, BitcoinClientError> {
+        self.broadcast(tx)
+    }
+
+    fn get_best_block(&self) -> Result<BlockHeight, BitcoinClientError> {
+        self.get_tip_height()
+    }
+
+    fn estimate_smart_fee(&self) -> Result<u64, BitcoinClientError> {
+        self.get_fee_rate(DEFAULT_FEE_CONF_TARGET)?
+            .ok_or_else(|| BitcoinClientError::ClientError("no fee rate estimate".to_string()))
+    }
+
+    fn fund_address(
+        &self,
+        _address: &Address,
+        _amount: Amount,
+    ) -> Result<(Transaction, u32), BitcoinClientError> {
+        self.unsupported("fund_address")
+    }
+
+    fn mine_blocks_to_address(
+        &self,
+        _blocks: u64,
+        _address: &Address,
+    ) -> Result<Vec<BlockHash>, BitcoinClientError> {
+        self.unsupported("mine_blocks_to_address")
+    }
+
+    fn get_transaction(&self, txid: &Txid) -> Result<Option<Transaction>, BitcoinClientError> {
+        let Some(body) = self.get(&format!("/tx/{txid}/hex"))? else {
+            return Ok(None);
+        };
+
+        encode::deserialize_hex(body.trim())
+            .map(Some)
+            .map_err(|e| BitcoinClientError::ClientError(format!("transaction {txid}: {e}")))
+    }
+
+    fn get_raw_transaction_info(&self, txid: &Txid) -> Result<RawTxInfo, BitcoinClientError> {
+        let status: TxStatus = self.get_json(&format!("/tx/{txid}/status"))?;
+
+        let confirmations = match (status.confirmed, status.block_height) {
+            (true, Some(block_height)) => {
+                Some(self.get_tip_height()?.saturating_sub(block_height) + 1)
+            }
+            _ => None,
+        };
+
+        Ok(RawTxInfo { confirmations })
+    }
+
+    fn get_block_id_by_height(
+        &self,
+        height: &BlockHeight,
+    ) -> Result<BlockHash, BitcoinClientError> {
+        let body = self.get_required(&format!("/block-height/{height}"))?;
+
+        BlockHash::from_str(body.trim())
+            .map_err(|e| BitcoinClientError::ClientError(format!("block {height}: {e}")))
+    }
+
+    fn invalidate_block(&self, _hash: &BlockHash) -> Result<(), BitcoinClientError> {
+        self.unsupported("invalidate_block")
+    }
+
+    fn init_wallet(&self, _name: &str) -> Result<Address, BitcoinClientError> {
+        self.unsupported("init_wallet")
+    }
+}
+
+impl FundingOutputChecker for EsploraClient {
+    fn get_funding_output_state(
+        &self,
+        outpoint: &OutPoint,
+    ) -> Result<FundingOutputState, BitcoinCoordinatorError> {
+        Ok(match self.get_outspend(outpoint)? {
+            Some(txid) => FundingOutputState::Spent(Some(txid)),
+            None => FundingOutputState::Unspent,
+        })
+    }
+}
+
+// Esplora has no estimate modes, only the confirmation target is used.
+impl SmartFeeEstimator for EsploraClient {
+    fn estimate_fee_rate(&self, conf_target: u16) -> Result<Option<u64>, BitcoinCoordinatorError> {
+        Ok(self.get_fee_rate(conf_target)?)
+    }
+}
+
+impl NodeApi for EsploraClient {
+    fn is_unspent(
+        &self,
+        outpoint: &OutPoint,
+        include_mempool: bool,
+    ) -> Result<bool, BitcoinCoordinatorError> {
+        let Some(status) = self.get_tx_status(&outpoint.txid)? else {
+            return Ok(false);
+        };
+
+        if !include_mempool && !status.confirmed {
+            return Ok(false);
+        }
+
+        let outspend: Outspend =
+            self.get_json(&format!("/tx/{}/outspend/{}", outpoint.txid, outpoint.vout))?;
+
+        let spent = outspend.spent
+            && (include_mempool || outspend.status.is_none_or(|status| status.confirmed));
+
+        Ok(!spent)
+    }
+
+    fn get_block_count(&self) -> Result<BlockHeight, BitcoinCoordinatorError> {
+        Ok(self.get_tip_height()?)
+    }
+
+    fn get_block_hash(&self, height: BlockHeight) -> Result<BlockHash, BitcoinCoordinatorError> {
+        Ok(self.get_block_id_by_height(&height)?)
+    }
+
+    fn get_block_transactions(
+        &self,
+        block_hash: &BlockHash,
+    ) -> Result<Vec<Transaction>, BitcoinCoordinatorError> {
+        let bytes = self.get_bytes(&format!("/block/{block_hash}/raw"))?;
+
+        let block: Block = encode::deserialize(&bytes)
+            .map_err(|e| BitcoinClientError::ClientError(format!("block {block_hash}: {e}")))?;
+
+        Ok(block.txdata)
+    }
+
+    // Esplora has no mempool min fee, the estimate of the highest target is the lowest fee rate still confirmed.
+    fn get_mempool_min_fee(&self) -> Result<Option<u64>, BitcoinCoordinatorError> {
+        let estimates = self.get_fee_estimates()?;
+
+        Ok(estimates
+            .keys()
+            .max()
+            .and_then(|target| select_fee_rate(&estimates, *target)))
+    }
+
+    // Esplora has no estimate modes, only the confirmation target is used.
+    fn estimate_smart_fee(
+        &self,
+        conf_target: u16,
+        _mode: Option<FeeEstimateMode>,
+    ) -> Result<Option<u64>, BitcoinCoordinatorError> {
+        Ok(self.get_fee_rate(conf_target)?)
+    }
+
+    fn test_mempool_accept(
+        &self,
+        _tx: &Transaction,
+    ) -> Result<Option<String>, BitcoinCoordinatorError> {
+        Ok(self.unsupported("testmempoolaccept")?)
+    }
+
+    fn get_mempool_ancestry(
+        &self,
+        _txid: &Txid,
+    ) -> Result<Option<MempoolAncestry>, BitcoinCoordinatorError> {
+        Ok(self.unsupported("getmempoolentry")?)
+    }
+
+    fn supports_mempool_checks(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,153 @@
+use crate::{
+    config::{FeeEstimateMode, FeeStrategy},
+    errors::BitcoinCoordinatorError,
+};
+use console::style;
+use std::{cell::Cell, rc::Rc};
+use tracing::warn;
+
+/// Source of the fee rate used when the fee strategy is `FeeStrategy::External`.
+pub trait FeeRateProvider {
+    /// Returns the fee rate in sat/vB, or None when there is no estimate.
+    fn get_fee_rate(&self) -> Result<Option<u64>, BitcoinCoordinatorError>;
+}
+
+/// Source of the estimates of the SmartFee fee strategy instead of the node.
+/// Set with `BitcoinCoordinator::with_smart_fee_estimator`.
+pub trait SmartFeeEstimator {
+    /// Returns the fee rate in sat/vB to confirm within `conf_target` blocks, or None when there is no estimate.
+    fn estimate_fee_rate(&self, conf_target: u16) -> Result<Option<u64>, BitcoinCoordinatorError>;
+}
+
+// Fee rate returned by the estimator.
+// `fallback` is true when there was no estimate and the fee rate is the mempool min fee or min_network_fee_rate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FeeRateEstimate {
+    pub fee_rate: u64,
+    pub fallback: bool,
+}
+
+// Estimates the network fee rate with the configured strategy.
+// The estimate is kept until `reset` is called, so the node or the provider is asked at most once per tick.
+pub struct FeeRateEstimator {
+    strategy: FeeStrategy,
+    min_network_fee_rate: Cell<u64>,
+    provider: Option<Rc<dyn FeeRateProvider>>,
+    fee_rate: Cell<Option<FeeRateEstimate>>,
+}
+
+impl FeeRateEstimator {
+    pub fn new(strategy: FeeStrategy, min_network_fee_rate: u64) -> Self {
+        Self {
+            strategy,
+            min_network_fee_rate: Cell::new(min_network_fee_rate),
+            provider: None,
+            fee_rate: Cell::new(None),
+        }
+    }
+
+    pub fn with_provider(mut self, provider: Rc<dyn FeeRateProvider>) -> Self {
+        self.set_provider(provider);
+        self
+    }
+
+    pub fn set_provider(&mut self, provider: Rc<dyn FeeRateProvider>) {
+        self.provider = Some(provider);
+    }
+
+    // Used when the settings are updated at runtime, applied from the next estimate.
+    pub fn set_min_network_fee_rate(&self, min_network_fee_rate: u64) {
+        self.min_network_fee_rate.set(min_network_fee_rate);
+    }
+
+    // Forgets the last estimate, the next call to `estimate` asks for a new one.
+    pub fn reset(&self) {
+        self.fee_rate.set(None);
+    }
+
+    // Returns the fee rate in sat/vB, never below min_network_fee_rate.
+    // `smart_fee` asks the node for an estimate with the conf target and mode of the SmartFee strategy.
+    // Errors, zero and missing estimates fall back to min_network_fee_rate.
+    pub fn estimate<F>(&self, smart_fee: F) -> u64
+    where
+        F: FnOnce(
+            Option<u16>,
+            Option<FeeEstimateMode>,
+        ) -> Result<Option<u64>, BitcoinCoordinatorError>,
+    {
+        self.estimate_with_fallback(smart_fee, || Ok(None)).fee_rate
+    }
+
+    // Same as `estimate`, but errors, zero and missing estimates fall back first to `mempool_min_fee`
+    // (the mempoolminfee of the node, in sat/vB) and then to min_network_fee_rate.
+    // A fresh regtest node has no fee estimates until it has seen enough transactions.
+    pub fn estimate_with_fallback<F, M>(&self, smart_fee: F, mempool_min_fee: M) -> FeeRateEstimate
+    where
+        F: FnOnce(
+            Option<u16>,
+            Option<FeeEstimateMode>,
+        ) -> Result<Option<u64>, BitcoinCoordinatorError>,
+        M: FnOnce() -> Result<Option<u64>, BitcoinCoordinatorError>,
+    {
+        if let Some(estimate) = self.fee_rate.get() {
+            return estimate;
+        }
+
+        let estimate = match &self.strategy {
+            FeeStrategy::SmartFee { conf_target, mode } => smart_fee(*conf_target, *mode),
+            FeeStrategy::Fixed(sat_vb) => Ok(Some(*sat_vb)),
+            FeeStrategy::External => match &self.provider {
+                Some(provider) => provider.get_fee_rate(),
+                None => Err(BitcoinCoordinatorError::InvalidConfiguration(
+                    "fee strategy is External but no fee rate provider was set".to_string(),
+                )),
+            },
+        };
+
+        let estimate = match estimate {
+            Ok(Some(fee_rate)) if fee_rate > 0 => FeeRateEstimate {
+                fee_rate: fee_rate.max(self.min_network_fee_rate.get()),
+                fallback: false,
+            },
+            Ok(_) => self.fallback("no fee rate estimate", mempool_min_fee),
+            Err(e) => self.fallback(&e.to_string(), mempool_min_fee),
+        };
+
+        self.fee_rate.set(Some(estimate));
+
+        estimate
+    }
+
+    fn fallback<M>(&self, reason: &str, mempool_min_fee: M) -> FeeRateEstimate
+    where
+        M: FnOnce() -> Result<Option<u64>, BitcoinCoordinatorError>,
+    {
+        let fee_rate = match mempool_min_fee() {
+            Ok(Some(fee_rate)) if fee_rate > 0 => {
+                warn!(
+                    "{} Fee rate estimation unavailable, using the mempool min fee ({}): {}",
+                    style("Coordinator").green(),
+                    style(fee_rate).yellow(),
+                    reason
+                );
+
+                fee_rate.max(self.min_network_fee_rate.get())
+            }
+            _ => {
+                warn!(
+                    "{} Fee rate estimation unavailable, using the min network fee rate ({}): {}",
+                    style("Coordinator").green(),
+                    style(self.min_network_fee_rate.get()).yellow(),
+                    reason
+                );
+
+                self.min_network_fee_rate.get()
+            }
+        };
+
+        FeeRateEstimate {
+            fee_rate,
+            fallback: true,
+        }
+    }
+}
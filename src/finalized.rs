@@ -0,0 +1,9 @@
+use crate::{errors::BitcoinCoordinatorError, types::FinalizedTxEntry};
+
+/// Receives the entries of the finalized log, the transactions finalized by the coordinator, in sequence order.
+/// Set with `BitcoinCoordinator::with_finalized_sink`. The log can also be read with `read_finalized`, without a sink.
+pub trait FinalizedSink {
+    /// Called at the end of the tick for each entry not taken yet. An error does not fail the tick,
+    /// the entry is handed again on the next ticks until it is taken.
+    fn deliver(&self, entry: &FinalizedTxEntry) -> Result<(), BitcoinCoordinatorError>;
+}
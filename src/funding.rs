@@ -0,0 +1,81 @@
+use crate::errors::BitcoinCoordinatorError;
+use bitcoin::{Address, Amount, CompressedPublicKey, Network, OutPoint, PublicKey, Txid};
+use bitcoincore_rpc::{Client, RpcApi};
+use bitvmx_bitcoin_rpc::bitcoin_client::BitcoinClientApi;
+use protocol_builder::types::Utxo;
+use std::rc::Rc;
+
+/// Source of the funding requested when there is no funding or the remaining funding drops below
+/// `auto_topup_below_sats`. Set with `BitcoinCoordinator::with_funding_provider`.
+pub trait FundingProvider {
+    /// Sends `target_sats` to an output spendable by the coordinator and returns it.
+    /// The output is registered as funding once its transaction is confirmed.
+    fn request_funding(&self, target_sats: u64) -> Result<Utxo, BitcoinCoordinatorError>;
+}
+
+/// Funds a P2WPKH output of `public_key` from the wallet of the node.
+pub struct WalletFundingProvider {
+    client: Rc<dyn BitcoinClientApi>,
+    public_key: PublicKey,
+    network: Network,
+}
+
+impl WalletFundingProvider {
+    pub fn new(client: Rc<dyn BitcoinClientApi>, public_key: PublicKey, network: Network) -> Self {
+        Self {
+            client,
+            public_key,
+            network,
+        }
+    }
+}
+
+impl FundingProvider for WalletFundingProvider {
+    fn request_funding(&self, target_sats: u64) -> Result<Utxo, BitcoinCoordinatorError> {
+        let compressed = CompressedPublicKey::try_from(self.public_key)
+            .map_err(|e| BitcoinCoordinatorError::InvalidConfiguration(e.to_string()))?;
+        let address = Address::p2wpkh(&compressed, self.network);
+
+        let (tx, vout) = self
+            .client
+            .fund_address(&address, Amount::from_sat(target_sats))?;
+
+        Ok(Utxo::new(
+            tx.compute_txid(),
+            vout,
+            target_sats,
+            &self.public_key,
+        ))
+    }
+}
+
+/// State of a funding output, checked before a CPFP spends it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FundingOutputState {
+    Unspent,
+    /// The output was spent, or never existed. Holds the spending transaction when it is known.
+    Spent(Option<Txid>),
+}
+
+/// Source of the state of the funding outputs. Set with `BitcoinCoordinator::with_funding_output_checker`,
+/// by default the node is asked with gettxout.
+pub trait FundingOutputChecker {
+    fn get_funding_output_state(
+        &self,
+        outpoint: &OutPoint,
+    ) -> Result<FundingOutputState, BitcoinCoordinatorError>;
+}
+
+impl FundingOutputChecker for Client {
+    fn get_funding_output_state(
+        &self,
+        outpoint: &OutPoint,
+    ) -> Result<FundingOutputState, BitcoinCoordinatorError> {
+        // Mempool spends are included, a funding spent by an unconfirmed transaction can not be spent either.
+        // gettxout does not tell which transaction spent the output.
+        match self.get_tx_out(&outpoint.txid, outpoint.vout, Some(true))? {
+            Some(_) => Ok(FundingOutputState::Unspent),
+            None => Ok(FundingOutputState::Spent(None)),
+        }
+    }
+}
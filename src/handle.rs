@@ -0,0 +1,559 @@
+use crate::{
+    config::{CoordinatorSettings, CoordinatorSettingsConfig},
+    coordinator::{BitcoinCoordinator, BitcoinCoordinatorApi},
+    errors::BitcoinCoordinatorError,
+    types::{
+        AckNews, ConfirmationStats, ContextCancelSummary, DetectedPegin, DispatchCostEstimate,
+        DispatchOptions, FundingSummary, GroupStatus, JournalEntry, News, NewsPage,
+        PendingOverview, PruneSummary, ReadinessReport, Severity, ShutdownReport, SpeedupSummary,
+        TransactionHistory, TxDiagnosis, WatchedUtxoSet,
+    },
+};
+use bitcoin::{OutPoint, PublicKey, ScriptBuf, Transaction, Txid};
+use bitvmx_bitcoin_rpc::types::BlockHeight;
+use bitvmx_transaction_monitor::types::{TransactionStatus, TypesToMonitor};
+use protocol_builder::types::{output::SpeedupData, Utxo};
+use std::{
+    future::Future,
+    path::PathBuf,
+    pin::Pin,
+    sync::{mpsc, Arc, Condvar, Mutex},
+    task::{Context, Poll, Waker},
+    thread::{self, JoinHandle},
+};
+use uuid::Uuid;
+
+type Job = Box<dyn FnOnce(&BitcoinCoordinator) + Send>;
+
+// Thread-safe access to a BitcoinCoordinator.
+// The coordinator is not Send (it holds Rc handles), so it is built and owned by a dedicated thread.
+// Every call is sent to that thread as a request and executed in order, so ticks, dispatches and news
+// are serialized. Each call returns a CoordinatorResponse that can be awaited or waited for.
+pub struct BitcoinCoordinatorHandle {
+    sender: Option<mpsc::Sender<Job>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl BitcoinCoordinatorHandle {
+    // Spawns the coordinator thread. `build` runs on that thread and creates the coordinator,
+    // for example with BitcoinCoordinator::new_with_paths.
+    pub fn spawn<F>(build: F) -> Result<Self, BitcoinCoordinatorError>
+    where
+        F: FnOnce() -> Result<BitcoinCoordinator, BitcoinCoordinatorError> + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let (ready_sender, ready_receiver) = mpsc::channel();
+
+        let worker = thread::Builder::new()
+            .name("bitcoin-coordinator".to_string())
+            .spawn(move || {
+                let coordinator = match build() {
+                    Ok(coordinator) => {
+                        let _ = ready_sender.send(Ok(()));
+                        coordinator
+                    }
+                    Err(e) => {
+                        let _ = ready_sender.send(Err(e));
+                        return;
+                    }
+                };
+
+                // Runs until every handle sender is dropped.
+                for job in receiver {
+                    job(&coordinator);
+                }
+            })
+            .map_err(|e| {
+                BitcoinCoordinatorError::BitcoinCoordinatorError(format!(
+                    "Failed to spawn the coordinator thread: {e}"
+                ))
+            })?;
+
+        match ready_receiver.recv() {
+            Ok(Ok(())) => Ok(Self {
+                sender: Some(sender),
+                worker: Some(worker),
+            }),
+            Ok(Err(e)) => {
+                let _ = worker.join();
+                Err(e)
+            }
+            Err(_) => {
+                let _ = worker.join();
+                Err(coordinator_stopped())
+            }
+        }
+    }
+
+    // Runs `call` on the coordinator thread and returns its result.
+    fn request<T, F>(&self, call: F) -> CoordinatorResponse<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&BitcoinCoordinator) -> Result<T, BitcoinCoordinatorError> + Send + 'static,
+    {
+        let (responder, response) = CoordinatorResponse::new();
+
+        let job: Job = Box::new(move |coordinator| responder.send(call(coordinator)));
+
+        // If the coordinator thread is gone the job is dropped and the responder reports it.
+        if let Some(sender) = &self.sender {
+            let _ = sender.send(job);
+        }
+
+        response
+    }
+
+    pub fn is_ready(&self) -> CoordinatorResponse<bool> {
+        self.request(|coordinator| coordinator.is_ready())
+    }
+
+    pub fn readiness(&self) -> CoordinatorResponse<ReadinessReport> {
+        self.request(|coordinator| coordinator.readiness())
+    }
+
+    // The progress callback runs on the coordinator thread.
+    pub fn sync_to_tip(
+        &self,
+        progress: Option<Box<dyn Fn(BlockHeight, BlockHeight) + Send>>,
+    ) -> CoordinatorResponse<()> {
+        self.request(move |coordinator| {
+            coordinator.sync_to_tip(
+                progress
+                    .as_ref()
+                    .map(|progress| progress.as_ref() as &dyn Fn(BlockHeight, BlockHeight)),
+            )
+        })
+    }
+
+    pub fn tick(&self) -> CoordinatorResponse<()> {
+        self.request(|coordinator| coordinator.tick())
+    }
+
+    pub fn monitor(&self, data: TypesToMonitor) -> CoordinatorResponse<()> {
+        self.request(move |coordinator| coordinator.monitor(data))
+    }
+
+    pub fn monitor_with_options(
+        &self,
+        data: TypesToMonitor,
+        finality_confirmations: Option<u32>,
+    ) -> CoordinatorResponse<()> {
+        self.request(move |coordinator| {
+            coordinator.monitor_with_options(data, finality_confirmations)
+        })
+    }
+
+    pub fn dispatch(
+        &self,
+        tx: Transaction,
+        speedup: Option<SpeedupData>,
+        context: String,
+        block_height: Option<BlockHeight>,
+        number_confirmation_trigger: Option<u32>,
+    ) -> CoordinatorResponse<()> {
+        self.request(move |coordinator| {
+            coordinator.dispatch(
+                tx,
+                speedup,
+                context,
+                block_height,
+                number_confirmation_trigger,
+            )
+        })
+    }
+
+    pub fn dispatch_with_options(
+        &self,
+        tx: Transaction,
+        speedup: Option<SpeedupData>,
+        context: String,
+        block_height: Option<BlockHeight>,
+        number_confirmation_trigger: Option<u32>,
+        options: DispatchOptions,
+    ) -> CoordinatorResponse<()> {
+        self.request(move |coordinator| {
+            coordinator.dispatch_with_options(
+                tx,
+                speedup,
+                context,
+                block_height,
+                number_confirmation_trigger,
+                options,
+            )
+        })
+    }
+
+    pub fn dispatch_batch(
+        &self,
+        txs: Vec<(Transaction, Option<SpeedupData>, String)>,
+        block_height: Option<BlockHeight>,
+    ) -> CoordinatorResponse<()> {
+        self.request(move |coordinator| coordinator.dispatch_batch(txs, block_height))
+    }
+
+    pub fn watch_outpoint(&self, outpoint: OutPoint, context: String) -> CoordinatorResponse<()> {
+        self.request(move |coordinator| coordinator.watch_outpoint(outpoint, context))
+    }
+
+    pub fn monitor_address(
+        &self,
+        script_pubkey: ScriptBuf,
+        context: String,
+    ) -> CoordinatorResponse<()> {
+        self.request(move |coordinator| coordinator.monitor_address(script_pubkey, context))
+    }
+
+    pub fn cancel_monitor_address(&self, script_pubkey: ScriptBuf) -> CoordinatorResponse<bool> {
+        self.request(move |coordinator| coordinator.cancel_monitor_address(&script_pubkey))
+    }
+
+    pub fn monitor_utxo_set(
+        &self,
+        label: String,
+        outpoints: Vec<OutPoint>,
+        context: String,
+    ) -> CoordinatorResponse<()> {
+        self.request(move |coordinator| coordinator.monitor_utxo_set(label, outpoints, context))
+    }
+
+    pub fn get_utxo_set_status(&self, label: String) -> CoordinatorResponse<WatchedUtxoSet> {
+        self.request(move |coordinator| coordinator.get_utxo_set_status(&label))
+    }
+
+    pub fn cancel_utxo_set(&self, label: String) -> CoordinatorResponse<bool> {
+        self.request(move |coordinator| coordinator.cancel_utxo_set(&label))
+    }
+
+    pub fn monitor_group(
+        &self,
+        tx_ids: Vec<Txid>,
+        context: String,
+        finality_confirmations: Option<u32>,
+    ) -> CoordinatorResponse<Uuid> {
+        self.request(move |coordinator| {
+            coordinator.monitor_group(tx_ids, context, finality_confirmations)
+        })
+    }
+
+    pub fn dispatch_group(
+        &self,
+        txs: Vec<(Transaction, Option<SpeedupData>)>,
+        context: String,
+        block_height: Option<BlockHeight>,
+    ) -> CoordinatorResponse<Uuid> {
+        self.request(move |coordinator| coordinator.dispatch_group(txs, context, block_height))
+    }
+
+    pub fn get_group_status(&self, group_id: Uuid) -> CoordinatorResponse<GroupStatus> {
+        self.request(move |coordinator| coordinator.get_group_status(group_id))
+    }
+
+    pub fn cancel(&self, data: TypesToMonitor) -> CoordinatorResponse<()> {
+        self.request(move |coordinator| coordinator.cancel(data))
+    }
+
+    pub fn cancel_dispatch(&self, txid: Txid) -> CoordinatorResponse<()> {
+        self.request(move |coordinator| coordinator.cancel_dispatch(txid))
+    }
+
+    pub fn cancel_by_context(&self, context: String) -> CoordinatorResponse<ContextCancelSummary> {
+        self.request(move |coordinator| coordinator.cancel_by_context(&context))
+    }
+
+    pub fn reschedule_dispatch(
+        &self,
+        txid: Txid,
+        new_target: Option<BlockHeight>,
+    ) -> CoordinatorResponse<()> {
+        self.request(move |coordinator| coordinator.reschedule_dispatch(txid, new_target))
+    }
+
+    pub fn get_scheduled_dispatches(
+        &self,
+    ) -> CoordinatorResponse<Vec<(Txid, BlockHeight, String)>> {
+        self.request(|coordinator| coordinator.get_scheduled_dispatches())
+    }
+
+    pub fn add_funding(&self, utxo: Utxo) -> CoordinatorResponse<()> {
+        self.request(move |coordinator| coordinator.add_funding(utxo))
+    }
+
+    pub fn add_funding_group(&self, group_id: String, utxo: Utxo) -> CoordinatorResponse<()> {
+        self.request(move |coordinator| coordinator.add_funding_group(&group_id, utxo))
+    }
+
+    pub fn add_funding_with_change_key(
+        &self,
+        utxo: Utxo,
+        change_pubkey: PublicKey,
+    ) -> CoordinatorResponse<()> {
+        self.request(move |coordinator| {
+            coordinator.add_funding_with_change_key(utxo, change_pubkey)
+        })
+    }
+
+    pub fn rotate_change_key(&self, change_pubkey: PublicKey) -> CoordinatorResponse<()> {
+        self.request(move |coordinator| coordinator.rotate_change_key(change_pubkey))
+    }
+
+    pub fn remove_funding(&self, txid: Txid, vout: u32) -> CoordinatorResponse<()> {
+        self.request(move |coordinator| coordinator.remove_funding(txid, vout))
+    }
+
+    pub fn import_external_speedup(
+        &self,
+        tx: Transaction,
+        covered_parents: Vec<Txid>,
+        change_vout: u32,
+        change_pubkey: PublicKey,
+    ) -> CoordinatorResponse<()> {
+        self.request(move |coordinator| {
+            coordinator.import_external_speedup(tx, covered_parents, change_vout, change_pubkey)
+        })
+    }
+
+    pub fn get_funding_summary(&self) -> CoordinatorResponse<FundingSummary> {
+        self.request(|coordinator| coordinator.get_funding_summary())
+    }
+
+    pub fn get_pending_overview(&self) -> CoordinatorResponse<PendingOverview> {
+        self.request(|coordinator| coordinator.get_pending_overview())
+    }
+
+    pub fn get_speedups_for_tx(&self, txid: Txid) -> CoordinatorResponse<Vec<SpeedupSummary>> {
+        self.request(move |coordinator| coordinator.get_speedups_for_tx(txid))
+    }
+
+    pub fn get_confirmation_stats(
+        &self,
+        window_blocks: u32,
+    ) -> CoordinatorResponse<ConfirmationStats> {
+        self.request(move |coordinator| coordinator.get_confirmation_stats(window_blocks))
+    }
+
+    pub fn estimate_dispatch_cost(
+        &self,
+        txs: Vec<(Transaction, SpeedupData)>,
+    ) -> CoordinatorResponse<DispatchCostEstimate> {
+        self.request(move |coordinator| coordinator.estimate_dispatch_cost(txs))
+    }
+
+    pub fn get_transaction(&self, txid: Txid) -> CoordinatorResponse<TransactionStatus> {
+        self.request(move |coordinator| coordinator.get_transaction(txid))
+    }
+
+    pub fn diagnose(&self, txid: Txid) -> CoordinatorResponse<TxDiagnosis> {
+        self.request(move |coordinator| coordinator.diagnose(txid))
+    }
+
+    pub fn monitor_rsk_pegin(&self, context: String) -> CoordinatorResponse<()> {
+        self.request(move |coordinator| coordinator.monitor_rsk_pegin(context))
+    }
+
+    pub fn get_detected_pegins(
+        &self,
+        since_height: BlockHeight,
+    ) -> CoordinatorResponse<Vec<DetectedPegin>> {
+        self.request(move |coordinator| coordinator.get_detected_pegins(since_height))
+    }
+
+    pub fn get_transaction_history(&self, txid: Txid) -> CoordinatorResponse<TransactionHistory> {
+        self.request(move |coordinator| coordinator.get_transaction_history(txid))
+    }
+
+    pub fn get_news(&self) -> CoordinatorResponse<News> {
+        self.request(|coordinator| coordinator.get_news())
+    }
+
+    pub fn get_news_page(&self, since_seq: u64, limit: usize) -> CoordinatorResponse<NewsPage> {
+        self.request(move |coordinator| coordinator.get_news_page(since_seq, limit))
+    }
+
+    pub fn get_news_filtered(&self, min_severity: Severity) -> CoordinatorResponse<News> {
+        self.request(move |coordinator| coordinator.get_news_filtered(min_severity))
+    }
+
+    pub fn ack_news(&self, news: AckNews) -> CoordinatorResponse<()> {
+        self.request(move |coordinator| coordinator.ack_news(news))
+    }
+
+    pub fn ack_news_batch(&self, news: Vec<AckNews>) -> CoordinatorResponse<usize> {
+        self.request(move |coordinator| coordinator.ack_news_batch(news))
+    }
+
+    pub fn subscribe_news(&self, subscriber_id: &str) -> CoordinatorResponse<mpsc::Receiver<News>> {
+        let subscriber_id = subscriber_id.to_string();
+        self.request(move |coordinator| coordinator.subscribe_news(&subscriber_id))
+    }
+
+    pub fn prune(&self, older_than_blocks: u32) -> CoordinatorResponse<PruneSummary> {
+        self.request(move |coordinator| coordinator.prune(older_than_blocks))
+    }
+
+    pub fn read_events(
+        &self,
+        from_seq: u64,
+        limit: usize,
+    ) -> CoordinatorResponse<Vec<JournalEntry>> {
+        self.request(move |coordinator| coordinator.read_events(from_seq, limit))
+    }
+
+    pub fn export_events_json(&self, path: PathBuf) -> CoordinatorResponse<usize> {
+        self.request(move |coordinator| coordinator.export_events_json(&path))
+    }
+
+    pub fn prune_events(&self, before_seq: u64) -> CoordinatorResponse<u32> {
+        self.request(move |coordinator| coordinator.prune_events(before_seq))
+    }
+
+    pub fn export_snapshot(&self, path: PathBuf) -> CoordinatorResponse<usize> {
+        self.request(move |coordinator| coordinator.export_snapshot(&path))
+    }
+
+    pub fn import_snapshot(&self, path: PathBuf, force: bool) -> CoordinatorResponse<usize> {
+        self.request(move |coordinator| coordinator.import_snapshot(&path, force))
+    }
+
+    pub fn update_settings(&self, settings: CoordinatorSettingsConfig) -> CoordinatorResponse<()> {
+        self.request(move |coordinator| coordinator.update_settings(settings))
+    }
+
+    pub fn get_effective_settings(&self) -> CoordinatorResponse<CoordinatorSettings> {
+        self.request(|coordinator| Ok(coordinator.get_effective_settings()))
+    }
+
+    pub fn promote(&self) -> CoordinatorResponse<()> {
+        self.request(|coordinator| coordinator.promote())
+    }
+
+    // Shuts the coordinator down after the pending requests are processed, then stops its thread.
+    // The thread is stopped also when the shutdown fails.
+    pub fn shutdown(mut self) -> Result<ShutdownReport, BitcoinCoordinatorError> {
+        let report = self.request(|coordinator| coordinator.shutdown()).wait();
+        self.stop()?;
+
+        report
+    }
+
+    fn stop(&mut self) -> Result<(), BitcoinCoordinatorError> {
+        // Dropping the sender ends the coordinator thread loop.
+        self.sender.take();
+
+        if let Some(worker) = self.worker.take() {
+            worker.join().map_err(|_| {
+                BitcoinCoordinatorError::BitcoinCoordinatorError(
+                    "The coordinator thread panicked".to_string(),
+                )
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for BitcoinCoordinatorHandle {
+    fn drop(&mut self) {
+        let _ = self.stop();
+    }
+}
+
+fn coordinator_stopped() -> BitcoinCoordinatorError {
+    BitcoinCoordinatorError::CoordinatorStopped
+}
+
+struct ResponseSlot<T> {
+    result: Option<Result<T, BitcoinCoordinatorError>>,
+    waker: Option<Waker>,
+}
+
+type SharedSlot<T> = Arc<(Mutex<ResponseSlot<T>>, Condvar)>;
+
+// The result of a request to the coordinator thread.
+// Async callers can `.await` it, sync callers can block on `wait()`.
+pub struct CoordinatorResponse<T> {
+    slot: SharedSlot<T>,
+}
+
+impl<T> CoordinatorResponse<T> {
+    fn new() -> (Responder<T>, Self) {
+        let slot = Arc::new((
+            Mutex::new(ResponseSlot {
+                result: None,
+                waker: None,
+            }),
+            Condvar::new(),
+        ));
+
+        (
+            Responder {
+                slot: Some(slot.clone()),
+            },
+            Self { slot },
+        )
+    }
+
+    // Blocks the current thread until the coordinator thread answers.
+    pub fn wait(self) -> Result<T, BitcoinCoordinatorError> {
+        let (lock, condvar) = &*self.slot;
+        let mut slot = lock.lock().unwrap();
+
+        loop {
+            if let Some(result) = slot.result.take() {
+                return result;
+            }
+
+            slot = condvar.wait(slot).unwrap();
+        }
+    }
+}
+
+impl<T> Future for CoordinatorResponse<T> {
+    type Output = Result<T, BitcoinCoordinatorError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let (lock, _) = &*self.slot;
+        let mut slot = lock.lock().unwrap();
+
+        if let Some(result) = slot.result.take() {
+            return Poll::Ready(result);
+        }
+
+        slot.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+// Sends the result of a request back to its CoordinatorResponse.
+// If it is dropped without answering (the coordinator thread stopped), the response gets an error.
+struct Responder<T> {
+    slot: Option<SharedSlot<T>>,
+}
+
+impl<T> Responder<T> {
+    fn send(mut self, result: Result<T, BitcoinCoordinatorError>) {
+        if let Some(slot) = self.slot.take() {
+            complete(&slot, result);
+        }
+    }
+}
+
+impl<T> Drop for Responder<T> {
+    fn drop(&mut self) {
+        if let Some(slot) = self.slot.take() {
+            complete(&slot, Err(coordinator_stopped()));
+        }
+    }
+}
+
+fn complete<T>(slot: &SharedSlot<T>, result: Result<T, BitcoinCoordinatorError>) {
+    let (lock, condvar) = &**slot;
+    let mut slot = lock.lock().unwrap();
+
+    slot.result = Some(result);
+
+    if let Some(waker) = slot.waker.take() {
+        waker.wake();
+    }
+
+    condvar.notify_all();
+}
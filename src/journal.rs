@@ -0,0 +1,242 @@
+use crate::{
+    encryption::StoreCipher,
+    errors::BitcoinCoordinatorStoreError,
+    store_backend::StoreBackend,
+    types::{JournalEntry, JournalEvent},
+};
+use bitvmx_bitcoin_rpc::types::BlockHeight;
+use chrono::Utc;
+use console::style;
+use std::{cell::Cell, rc::Rc};
+use tracing::warn;
+use uuid::Uuid;
+
+enum JournalKey {
+    NextSeq,
+    FirstSeq,
+    Entry(u64),
+}
+
+// Append-only log of the actions of the coordinator, kept for audits.
+// Entries are never changed once written, they are only removed by an explicit `prune_before`.
+pub struct EventJournal {
+    store: Rc<dyn StoreBackend>,
+    // Monitor height written in the entries, updated by the coordinator on every tick.
+    block_height: Cell<Option<BlockHeight>>,
+    // Next sequence number, also counting the entries written in store transactions not committed yet.
+    next_seq: Cell<u64>,
+    // Set when the store is encrypted, the entries hold raw transactions and are encrypted with the same key.
+    cipher: Option<StoreCipher>,
+}
+
+impl EventJournal {
+    pub fn new(store: Rc<dyn StoreBackend>) -> Self {
+        Self {
+            store,
+            block_height: Cell::new(None),
+            next_seq: Cell::new(0),
+            cipher: None,
+        }
+    }
+
+    // Encrypts the entries written from now on. Plaintext entries already in the store are still readable.
+    pub(crate) fn set_cipher(&mut self, cipher: StoreCipher) {
+        self.cipher = Some(cipher);
+    }
+
+    fn get_key(&self, key: JournalKey) -> String {
+        let prefix = "bitcoin_coordinator/journal";
+        match key {
+            JournalKey::NextSeq => format!("{prefix}/next_seq"),
+            JournalKey::FirstSeq => format!("{prefix}/first_seq"),
+            JournalKey::Entry(seq) => format!("{prefix}/entry/{seq:020}"),
+        }
+    }
+
+    pub fn set_block_height(&self, block_height: BlockHeight) {
+        self.block_height.set(Some(block_height));
+    }
+
+    pub fn append(&self, event: JournalEvent) -> Result<u64, BitcoinCoordinatorStoreError> {
+        let transaction_id = self.store.begin_transaction();
+
+        match self.append_events(vec![event], Some(transaction_id)) {
+            Ok(seq) => {
+                self.store.commit_transaction(transaction_id)?;
+                Ok(seq)
+            }
+            Err(e) => {
+                self.store.rollback_transaction(transaction_id)?;
+                Err(e)
+            }
+        }
+    }
+
+    // Appends an entry for an action already done. A failure is only logged, the action must not be
+    // undone or retried because it could not be recorded.
+    pub(crate) fn record(&self, event: JournalEvent) {
+        if let Err(e) = self.append(event) {
+            warn!(
+                "{} Error writing the event journal: {}",
+                style("Coordinator").green(),
+                e
+            );
+        }
+    }
+
+    // Writes the entries inside the store transaction if one is given, and returns the sequence number of the last one.
+    // A rolled back transaction leaves a gap in the sequence numbers, they are never reused.
+    pub(crate) fn append_events(
+        &self,
+        events: Vec<JournalEvent>,
+        transaction_id: Option<Uuid>,
+    ) -> Result<u64, BitcoinCoordinatorStoreError> {
+        // Another journal on the same storage can write entries too, the stored counter is never behind.
+        let mut seq = self.next_seq.get().max(self.stored_next_seq()?);
+        let timestamp = Utc::now().timestamp_millis() as u64;
+        let block_height = self.block_height.get();
+
+        for event in events {
+            let entry = JournalEntry {
+                seq,
+                timestamp,
+                block_height,
+                event,
+            };
+
+            self.set_entry(seq, &entry, transaction_id)?;
+            seq += 1;
+        }
+
+        self.store
+            .set(self.get_key(JournalKey::NextSeq), seq, transaction_id)?;
+        self.next_seq.set(seq);
+
+        Ok(seq.saturating_sub(1))
+    }
+
+    // Returns at most `limit` entries with a sequence number equal or greater than `from_seq`, in order.
+    pub fn read_events(
+        &self,
+        from_seq: u64,
+        limit: usize,
+    ) -> Result<Vec<JournalEntry>, BitcoinCoordinatorStoreError> {
+        let next_seq = self.stored_next_seq()?;
+        let mut seq = from_seq.max(self.first_seq()?);
+        let mut entries = Vec::new();
+
+        while seq < next_seq && entries.len() < limit {
+            if let Some(entry) = self.get_entry(seq)? {
+                entries.push(entry);
+            }
+
+            seq += 1;
+        }
+
+        Ok(entries)
+    }
+
+    // Removes the entries with a sequence number lower than `seq` and returns how many were removed.
+    // The sequence numbers of the removed entries are not reused.
+    pub fn prune_before(&self, seq: u64) -> Result<u32, BitcoinCoordinatorStoreError> {
+        let first_seq = self.first_seq()?;
+        let last_seq = seq.min(self.stored_next_seq()?);
+
+        if last_seq <= first_seq {
+            return Ok(0);
+        }
+
+        let transaction_id = self.store.begin_transaction();
+        let mut removed = 0;
+
+        let result = (|| {
+            for seq in first_seq..last_seq {
+                let key = self.get_key(JournalKey::Entry(seq));
+
+                if self.store.has_key(&key)? {
+                    self.store.remove(&key, Some(transaction_id))?;
+                    removed += 1;
+                }
+            }
+
+            self.store.set(
+                self.get_key(JournalKey::FirstSeq),
+                last_seq,
+                Some(transaction_id),
+            )?;
+
+            Ok::<(), BitcoinCoordinatorStoreError>(())
+        })();
+
+        match result {
+            Ok(()) => {
+                self.store.commit_transaction(transaction_id)?;
+                Ok(removed)
+            }
+            Err(e) => {
+                self.store.rollback_transaction(transaction_id)?;
+                Err(e)
+            }
+        }
+    }
+
+    fn set_entry(
+        &self,
+        seq: u64,
+        entry: &JournalEntry,
+        transaction_id: Option<Uuid>,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        let key = self.get_key(JournalKey::Entry(seq));
+
+        let Some(cipher) = &self.cipher else {
+            self.store.set(key, entry, transaction_id)?;
+            return Ok(());
+        };
+
+        let plaintext = serde_json::to_vec(entry)
+            .map_err(|e| BitcoinCoordinatorStoreError::SerializationError(e.to_string()))?;
+        let record = cipher
+            .encrypt(&plaintext)
+            .ok_or_else(|| BitcoinCoordinatorStoreError::EncryptionError(key.clone()))?;
+
+        self.store.set(key, record, transaction_id)?;
+        Ok(())
+    }
+
+    // Reads an entry, decrypting it if it was written encrypted.
+    fn get_entry(&self, seq: u64) -> Result<Option<JournalEntry>, BitcoinCoordinatorStoreError> {
+        let key = self.get_key(JournalKey::Entry(seq));
+
+        let entry = match self.store.get::<&str, serde_json::Value>(key.as_str())? {
+            Some(serde_json::Value::String(record)) if StoreCipher::is_encrypted(&record) => {
+                let plaintext = self
+                    .cipher
+                    .as_ref()
+                    .and_then(|cipher| cipher.decrypt(&record))
+                    .ok_or_else(|| BitcoinCoordinatorStoreError::DecryptionError(key.clone()))?;
+
+                serde_json::from_slice(&plaintext)
+            }
+            Some(record) => serde_json::from_value(record),
+            None => return Ok(None),
+        };
+
+        entry
+            .map(Some)
+            .map_err(|e| BitcoinCoordinatorStoreError::SerializationError(e.to_string()))
+    }
+
+    fn stored_next_seq(&self) -> Result<u64, BitcoinCoordinatorStoreError> {
+        Ok(self
+            .store
+            .get::<String, u64>(self.get_key(JournalKey::NextSeq))?
+            .unwrap_or_default())
+    }
+
+    fn first_seq(&self) -> Result<u64, BitcoinCoordinatorStoreError> {
+        Ok(self
+            .store
+            .get::<String, u64>(self.get_key(JournalKey::FirstSeq))?
+            .unwrap_or_default())
+    }
+}
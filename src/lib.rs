@@ -1,11 +1,51 @@
+pub mod admin;
+pub mod ancestry;
+pub mod batching;
+pub mod budget;
+pub mod bump;
+pub mod clock;
 pub mod config;
+pub mod confirmation_stats;
+pub mod conflict;
 pub mod coordinator;
+pub mod cpfp;
+pub mod diagnosis;
+pub mod encryption;
 pub mod errors;
+pub mod esplora;
+pub mod fee;
+pub mod finalized;
+pub mod funding;
+pub mod handle;
+pub mod journal;
+pub mod lock;
+pub mod locktime;
+pub mod logging;
+pub mod news;
+pub mod node;
+pub mod node_health;
+pub mod observer;
+pub mod parent_rbf;
+pub mod pegin;
+pub mod prevout;
+pub mod rbf;
+pub mod readiness;
+pub mod rebroadcast;
+pub mod record;
+pub mod review;
+pub mod runner;
 pub mod settings;
+pub mod snapshot;
 pub mod speedup;
 pub mod storage;
+pub mod store_backend;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub mod types;
+pub mod validation;
+pub mod write_queue;
 pub use bitvmx_transaction_monitor::types::AckMonitorNews;
+pub use bitvmx_transaction_monitor::types::BlockInfo;
 pub use bitvmx_transaction_monitor::types::MonitorNews;
 pub use bitvmx_transaction_monitor::types::TransactionStatus;
 pub use bitvmx_transaction_monitor::types::TypesToMonitor;
@@ -0,0 +1,66 @@
+use crate::errors::BitcoinCoordinatorError;
+use std::{
+    fs::{self, OpenOptions},
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+};
+
+// Lock file next to the storage, held by the process that changes the store (the service or the admin CLI).
+// It is removed when the lock is dropped. A process killed while holding it leaves the file behind,
+// it must be removed by hand once no process is using the storage.
+pub struct StoreLock {
+    path: PathBuf,
+}
+
+impl StoreLock {
+    pub fn lock_path(storage_path: &str) -> PathBuf {
+        PathBuf::from(format!("{}.lock", storage_path.trim_end_matches('/')))
+    }
+
+    // Fails with StoreLocked when another process holds the lock.
+    pub fn acquire(storage_path: &str) -> Result<Self, BitcoinCoordinatorError> {
+        let path = Self::lock_path(storage_path);
+
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent).map_err(|e| lock_error(&path, e))?;
+        }
+
+        let mut file = match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                let holder = fs::read_to_string(&path).unwrap_or_default();
+                return Err(BitcoinCoordinatorError::StoreLocked(
+                    path.display().to_string(),
+                    holder.trim().to_string(),
+                ));
+            }
+            Err(e) => return Err(lock_error(&path, e)),
+        };
+
+        // The holder is written for the operator, it is not checked when the lock is acquired.
+        write!(file, "pid {}", std::process::id()).map_err(|e| lock_error(&path, e))?;
+
+        Ok(Self { path })
+    }
+
+    pub fn is_locked(storage_path: &str) -> bool {
+        Self::lock_path(storage_path).exists()
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for StoreLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn lock_error(path: &Path, error: std::io::Error) -> BitcoinCoordinatorError {
+    BitcoinCoordinatorError::StoreLockError(format!("{}: {error}", path.display()))
+}
@@ -0,0 +1,42 @@
+use bitcoin::{absolute, relative, Transaction, Txid};
+use bitvmx_bitcoin_rpc::types::BlockHeight;
+
+// Block height the absolute locktime of the transaction lets it be broadcast at, the node accepts it in the
+// mempool once the next block is higher than the locktime. None when the transaction is final: the locktime
+// is zero, disabled by the sequence of every input, or a timestamp (time locks are not handled).
+pub fn absolute_lock_height(tx: &Transaction) -> Option<BlockHeight> {
+    if !tx.is_lock_time_enabled() {
+        return None;
+    }
+
+    match tx.lock_time {
+        absolute::LockTime::Blocks(height) if height.to_consensus_u32() > 0 => {
+            Some(height.to_consensus_u32())
+        }
+        _ => None,
+    }
+}
+
+// Parents the inputs of the transaction are relatively locked to (BIP68), with the number of blocks
+// the parent needs to be buried under. Time based relative locks are not handled.
+pub fn relative_locks(tx: &Transaction) -> Vec<(Txid, u32)> {
+    if tx.version.0 < 2 {
+        return Vec::new();
+    }
+
+    tx.input
+        .iter()
+        .filter_map(|input| match input.sequence.to_relative_lock_time() {
+            Some(relative::LockTime::Blocks(blocks)) if blocks.value() > 0 => {
+                Some((input.previous_output.txid, u32::from(blocks.value())))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+// Block height a relative lock lets the transaction be broadcast at, when its parent is confirmed at
+// `parent_height`: the transaction is valid in the block `blocks` above the parent.
+pub fn relative_lock_height(parent_height: BlockHeight, blocks: u32) -> BlockHeight {
+    parent_height + blocks - 1
+}
@@ -0,0 +1,88 @@
+use std::cell::Cell;
+use std::io::IsTerminal;
+use std::time::Duration;
+use tracing::{debug, info};
+
+// Work done in the tick in progress, logged as a single summary line at the end of the tick.
+// It is reset at the start of each tick, like the tick budget.
+#[derive(Default)]
+pub struct TickSummary {
+    dispatched: Cell<u32>,
+    confirmed: Cell<u32>,
+    finalized: Cell<u32>,
+    speedups: Cell<u32>,
+    speedup_fees: Cell<u64>,
+    news: Cell<u32>,
+}
+
+impl TickSummary {
+    pub fn reset(&self) {
+        self.dispatched.set(0);
+        self.confirmed.set(0);
+        self.finalized.set(0);
+        self.speedups.set(0);
+        self.speedup_fees.set(0);
+        self.news.set(0);
+    }
+
+    pub fn record_dispatched(&self) {
+        self.dispatched.set(self.dispatched.get() + 1);
+    }
+
+    pub fn record_confirmed(&self) {
+        self.confirmed.set(self.confirmed.get() + 1);
+    }
+
+    pub fn record_finalized(&self) {
+        self.finalized.set(self.finalized.get() + 1);
+    }
+
+    pub fn record_speedup(&self, fee: u64) {
+        self.speedups.set(self.speedups.get() + 1);
+        self.speedup_fees.set(self.speedup_fees.get() + fee);
+    }
+
+    pub fn record_news(&self) {
+        self.news.set(self.news.get() + 1);
+    }
+
+    fn is_idle(&self) -> bool {
+        self.dispatched.get() == 0
+            && self.confirmed.get() == 0
+            && self.finalized.get() == 0
+            && self.speedups.get() == 0
+            && self.news.get() == 0
+    }
+
+    // Logs the summary with tracing fields, so log collectors can parse it.
+    // Ticks that did nothing are only logged at debug level.
+    pub fn log(&self, duration: Duration) {
+        macro_rules! log_summary {
+            ($level:ident) => {
+                $level!(
+                    dispatched = self.dispatched.get(),
+                    confirmed = self.confirmed.get(),
+                    finalized = self.finalized.get(),
+                    speedups = self.speedups.get(),
+                    speedup_fees_sats = self.speedup_fees.get(),
+                    news = self.news.get(),
+                    duration_ms = duration.as_millis() as u64,
+                    "Coordinator tick completed"
+                )
+            };
+        }
+
+        if self.is_idle() {
+            log_summary!(debug);
+        } else {
+            log_summary!(info);
+        }
+    }
+}
+
+/// Enables the colors of the coordinator log lines only when the output they are written to is a terminal.
+/// The colors follow stdout unless this is called, call it with the writer of the tracing subscriber
+/// when the logs go elsewhere, e.g. `enable_log_colors_for(&std::io::stderr())`.
+pub fn enable_log_colors_for(output: &impl IsTerminal) {
+    console::set_colors_enabled(output.is_terminal());
+}
@@ -0,0 +1,114 @@
+use crate::{
+    settings::ANCHOR_SPEND_CONTEXT,
+    types::{AckNews, InternalMonitor, News, Severity, WatchedOutpoint},
+};
+use bitcoin::{
+    hashes::{sha256, Hash},
+    OutPoint, Txid,
+};
+use bitvmx_transaction_monitor::types::{AckMonitorNews, MonitorNews};
+use serde::Serialize;
+use std::{collections::HashMap, sync::mpsc::SyncSender};
+
+// A consumer subscribed with subscribe_news. The news delivered to it are persisted with its id.
+pub struct NewsSubscriber {
+    pub id: String,
+    pub sender: SyncSender<News>,
+}
+
+// Identifies a news by its content, to know whether it was already delivered.
+// A news reported again with other data, like a transaction with more confirmations, is a new news.
+pub fn news_fingerprint<T: Serialize>(news: &T) -> String {
+    let json = serde_json::to_vec(news).unwrap_or_default();
+    sha256::Hash::hash(&json).to_string()
+}
+
+// Returns the news whose fingerprint is not in `delivered`, and the fingerprints of all the news.
+pub fn undelivered_news(news: &News, delivered: &[String]) -> (News, Vec<String>) {
+    let mut monitor_news = Vec::new();
+    let mut coordinator_news = Vec::new();
+    let mut fingerprints = Vec::new();
+
+    for news in news.monitor_news.iter() {
+        let fingerprint = news_fingerprint(news);
+
+        if !delivered.contains(&fingerprint) {
+            monitor_news.push(news.clone());
+        }
+
+        fingerprints.push(fingerprint);
+    }
+
+    for news in news.coordinator_news.iter() {
+        let fingerprint = news_fingerprint(news);
+
+        if !delivered.contains(&fingerprint) {
+            coordinator_news.push(news.clone());
+        }
+
+        fingerprints.push(fingerprint);
+    }
+
+    (News::new(monitor_news, coordinator_news), fingerprints)
+}
+
+// Monitor news without the ones related to the coordinator's own CPFP and funding top-up transactions,
+// nor the spends of the watched outpoints, which are reported as coordinator news.
+// A transaction news is internal only when its txid is in `internal` and its context is the one of that monitor.
+// Peg-in news are always returned, they are acknowledged by the caller.
+pub fn filter_monitor_news(
+    news: Vec<MonitorNews>,
+    watched: Vec<WatchedOutpoint>,
+    internal: HashMap<Txid, InternalMonitor>,
+) -> impl Iterator<Item = MonitorNews> {
+    news.into_iter().filter(move |news| match news {
+        MonitorNews::Transaction(txid, _, context_data) => internal
+            .get(txid)
+            .is_none_or(|monitor| monitor.context() != context_data),
+        MonitorNews::SpendingUTXOTransaction(txid, vout, _, context_data) => {
+            context_data != ANCHOR_SPEND_CONTEXT
+                && !watched
+                    .iter()
+                    .any(|watch| watch.outpoint == OutPoint::new(*txid, *vout))
+        }
+        _ => true,
+    })
+}
+
+// Acknowledgement of a monitor news, as given to ack_news.
+pub fn monitor_news_ack(news: &MonitorNews) -> AckMonitorNews {
+    match news {
+        MonitorNews::Transaction(txid, _, context_data) => {
+            AckMonitorNews::Transaction(*txid, context_data.clone())
+        }
+        MonitorNews::RskPeginTransaction(txid, _) => AckMonitorNews::RskPeginTransaction(*txid),
+        MonitorNews::SpendingUTXOTransaction(txid, vout, _, context_data) => {
+            AckMonitorNews::SpendingUTXOTransaction(*txid, *vout, context_data.clone())
+        }
+        MonitorNews::NewBlock(..) => AckMonitorNews::NewBlock,
+    }
+}
+
+// How urgently a monitor news needs attention. The monitor only reports what it was asked to follow,
+// the problems of the dispatched transactions are reported by the coordinator news.
+pub fn monitor_news_severity(news: &MonitorNews) -> Severity {
+    match news {
+        MonitorNews::Transaction(..) => Severity::Info,
+        MonitorNews::RskPeginTransaction(..) => Severity::Info,
+        MonitorNews::SpendingUTXOTransaction(..) => Severity::Info,
+        MonitorNews::NewBlock(..) => Severity::Info,
+    }
+}
+
+// Acknowledgements of every news in `news`, monitor news first.
+pub fn news_acks(news: &News) -> Vec<AckNews> {
+    news.monitor_news
+        .iter()
+        .map(|news| AckNews::Monitor(monitor_news_ack(news)))
+        .chain(
+            news.coordinator_news
+                .iter()
+                .map(|news| AckNews::Coordinator(news.ack())),
+        )
+        .collect()
+}
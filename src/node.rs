@@ -0,0 +1,133 @@
+use crate::{
+    ancestry::{MempoolAncestry, MempoolAncestryProvider},
+    config::FeeEstimateMode,
+    errors::BitcoinCoordinatorError,
+};
+use bitcoin::{BlockHash, OutPoint, Transaction, Txid};
+use bitcoincore_rpc::{json::EstimateMode, Client, RpcApi};
+use bitvmx_bitcoin_rpc::types::BlockHeight;
+
+/// Node calls the coordinator needs besides `BitcoinClientApi`: the conflict detection, the optional mempool
+/// checks and the fee estimates of the node.
+/// Implemented by the `bitcoincore_rpc::Client` of the node and by `EsploraClient`.
+/// Set with `BitcoinCoordinatorBuilder::with_node`, or `with_rpc_client` for the RPC client. Without one, the
+/// Esplora client of `with_esplora_client` is the node.
+pub trait NodeApi {
+    /// Whether the output exists and is unspent. Mempool spends count as spent only when `include_mempool` is set.
+    fn is_unspent(
+        &self,
+        outpoint: &OutPoint,
+        include_mempool: bool,
+    ) -> Result<bool, BitcoinCoordinatorError>;
+
+    /// Height of the tip of the best chain.
+    fn get_block_count(&self) -> Result<BlockHeight, BitcoinCoordinatorError>;
+
+    /// Hash of the block at `height` in the best chain.
+    fn get_block_hash(&self, height: BlockHeight) -> Result<BlockHash, BitcoinCoordinatorError>;
+
+    /// Transactions of the block, in the order they were mined.
+    fn get_block_transactions(
+        &self,
+        block_hash: &BlockHash,
+    ) -> Result<Vec<Transaction>, BitcoinCoordinatorError>;
+
+    /// Minimum fee rate in sat/vB accepted in the mempool, or None when it is not known.
+    fn get_mempool_min_fee(&self) -> Result<Option<u64>, BitcoinCoordinatorError>;
+
+    /// Fee rate in sat/vB to confirm within `conf_target` blocks, or None when there is no estimate.
+    fn estimate_smart_fee(
+        &self,
+        conf_target: u16,
+        mode: Option<FeeEstimateMode>,
+    ) -> Result<Option<u64>, BitcoinCoordinatorError>;
+
+    /// Reason the mempool rejects the transaction, or None when it would be accepted.
+    fn test_mempool_accept(
+        &self,
+        tx: &Transaction,
+    ) -> Result<Option<String>, BitcoinCoordinatorError>;
+
+    /// Ancestry of the transaction in the mempool, or None when it is not in the mempool.
+    fn get_mempool_ancestry(
+        &self,
+        txid: &Txid,
+    ) -> Result<Option<MempoolAncestry>, BitcoinCoordinatorError>;
+
+    /// Whether `test_mempool_accept` and `get_mempool_ancestry` are answered. When they are not, the
+    /// `test_mempool_accept` and `check_mempool_ancestry` settings are rejected.
+    fn supports_mempool_checks(&self) -> bool {
+        true
+    }
+}
+
+impl NodeApi for Client {
+    fn is_unspent(
+        &self,
+        outpoint: &OutPoint,
+        include_mempool: bool,
+    ) -> Result<bool, BitcoinCoordinatorError> {
+        let tx_out = self.get_tx_out(&outpoint.txid, outpoint.vout, Some(include_mempool))?;
+
+        Ok(tx_out.is_some())
+    }
+
+    fn get_block_count(&self) -> Result<BlockHeight, BitcoinCoordinatorError> {
+        Ok(RpcApi::get_block_count(self)? as BlockHeight)
+    }
+
+    fn get_block_hash(&self, height: BlockHeight) -> Result<BlockHash, BitcoinCoordinatorError> {
+        Ok(RpcApi::get_block_hash(self, height as u64)?)
+    }
+
+    fn get_block_transactions(
+        &self,
+        block_hash: &BlockHash,
+    ) -> Result<Vec<Transaction>, BitcoinCoordinatorError> {
+        Ok(self.get_block(block_hash)?.txdata)
+    }
+
+    fn get_mempool_min_fee(&self) -> Result<Option<u64>, BitcoinCoordinatorError> {
+        let info = self.get_mempool_info()?;
+
+        // The node returns BTC/kvB, rounded up to sat/vB like the smart fee estimate.
+        Ok(Some(info.mempool_min_fee.to_sat().div_ceil(1000)))
+    }
+
+    fn estimate_smart_fee(
+        &self,
+        conf_target: u16,
+        mode: Option<FeeEstimateMode>,
+    ) -> Result<Option<u64>, BitcoinCoordinatorError> {
+        let mode = mode.map(|mode| match mode {
+            FeeEstimateMode::Economical => EstimateMode::Economical,
+            FeeEstimateMode::Conservative => EstimateMode::Conservative,
+        });
+
+        let result = RpcApi::estimate_smart_fee(self, conf_target, mode)?;
+
+        // The node returns BTC/kvB, rounded up to sat/vB so the fee rate is never underpaid.
+        Ok(result
+            .fee_rate
+            .map(|fee_rate| fee_rate.to_sat().div_ceil(1000)))
+    }
+
+    fn test_mempool_accept(
+        &self,
+        tx: &Transaction,
+    ) -> Result<Option<String>, BitcoinCoordinatorError> {
+        let result = RpcApi::test_mempool_accept(self, &[tx])?;
+
+        Ok(result
+            .into_iter()
+            .find(|result| !result.allowed)
+            .map(|result| result.reject_reason.unwrap_or_default()))
+    }
+
+    fn get_mempool_ancestry(
+        &self,
+        txid: &Txid,
+    ) -> Result<Option<MempoolAncestry>, BitcoinCoordinatorError> {
+        MempoolAncestryProvider::get_mempool_ancestry(self, txid)
+    }
+}
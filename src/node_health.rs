@@ -0,0 +1,64 @@
+use chrono::Utc;
+use std::cell::Cell;
+
+// Circuit breaker around the calls to the node.
+// It opens after `node_failure_threshold` consecutive failures reaching the node, and closes when a call succeeds.
+// While it is open the coordinator only probes the node on tick, instead of sending requests that would fail.
+#[derive(Default)]
+pub struct NodeCircuitBreaker {
+    consecutive_failures: Cell<u32>,
+    // Timestamp in milliseconds since the breaker is open.
+    unreachable_since: Cell<Option<u64>>,
+    // Whether the opening of the breaker was already reported with a NodeUnreachable news.
+    reported: Cell<bool>,
+}
+
+impl NodeCircuitBreaker {
+    pub fn is_open(&self) -> bool {
+        self.unreachable_since.get().is_some()
+    }
+
+    pub fn unreachable_since(&self) -> Option<u64> {
+        self.unreachable_since.get()
+    }
+
+    pub fn consecutive_failures(&self) -> u32 {
+        self.consecutive_failures.get()
+    }
+
+    // Counts a failure reaching the node. Returns true when this failure opens the breaker.
+    pub fn record_failure(&self, threshold: u32) -> bool {
+        let failures = self.consecutive_failures.get() + 1;
+        self.consecutive_failures.set(failures);
+
+        if self.is_open() || failures < threshold {
+            return false;
+        }
+
+        self.unreachable_since
+            .set(Some(Utc::now().timestamp_millis() as u64));
+        self.reported.set(false);
+
+        true
+    }
+
+    // Counts a call that reached the node. Returns the milliseconds the node was unreachable when it closes the breaker.
+    pub fn record_success(&self) -> Option<u64> {
+        self.consecutive_failures.set(0);
+
+        let since = self.unreachable_since.take()?;
+
+        Some((Utc::now().timestamp_millis() as u64).saturating_sub(since))
+    }
+
+    // Returns the timestamp since the node is unreachable the first time it is asked after the breaker opens.
+    pub fn take_unreported(&self) -> Option<u64> {
+        let since = self.unreachable_since.get()?;
+
+        if self.reported.replace(true) {
+            return None;
+        }
+
+        Some(since)
+    }
+}
@@ -0,0 +1,60 @@
+use bitcoin::Txid;
+use std::time::Duration;
+
+/// Hooks called by the coordinator while it processes transactions, to export metrics.
+/// Every method has a no-op default, so an observer only implements the events it needs.
+pub trait CoordinatorObserver {
+    /// Called at the end of every tick processed while the coordinator is ready.
+    /// - duration: Time spent in the tick, including the monitor tick
+    /// - txs_pending: Transactions waiting to be dispatched
+    /// - txs_in_progress: Transactions dispatched and not finalized yet
+    /// - speedups_unconfirmed: Speedups (CPFP/RBF) not confirmed yet
+    fn on_tick_completed(
+        &self,
+        _duration: Duration,
+        _txs_pending: usize,
+        _txs_in_progress: usize,
+        _speedups_unconfirmed: usize,
+    ) {
+    }
+
+    /// Called when a transaction is accepted by the node.
+    /// - attempt: 1 for the first broadcast, increased on every retry
+    fn on_transaction_broadcast(&self, _txid: Txid, _attempt: u32) {}
+
+    /// Called when a speedup transaction is accepted by the node.
+    /// - fee: Fee paid by the speedup in sats
+    /// - fee_rate: Network fee rate (sat/vB) targeted by the speedup
+    /// - num_parents: Number of transactions paid by the speedup
+    /// - is_rbf: Whether the speedup replaces a previous CPFP
+    fn on_speedup_created(
+        &self,
+        _txid: Txid,
+        _fee: u64,
+        _fee_rate: u64,
+        _num_parents: usize,
+        _is_rbf: bool,
+    ) {
+    }
+
+    /// Called every time a speedup transaction is signed with the key manager, before it is sent.
+    /// - inputs: Inputs signed, the speedup utxos and the funding
+    fn on_speedup_signed(&self, _inputs: usize) {}
+
+    /// Called at the end of a tick that left transactions for the next ticks because a tick limit was reached.
+    /// - broadcasts: Transactions sent during the tick, up to max_broadcasts_per_tick
+    /// - speedups: CPFPs sent during the tick, up to max_speedups_per_tick
+    /// - txs_remaining: Transactions left to dispatch on the next ticks
+    fn on_tick_budget_exhausted(&self, _broadcasts: u32, _speedups: u32, _txs_remaining: usize) {}
+
+    /// Called when a coordinator news is stored, with the name of the news variant.
+    fn on_news_emitted(&self, _kind: &str) {}
+
+    /// Called when the node rejects a transaction or a speedup transaction.
+    fn on_dispatch_error(&self, _txid: Txid, _error: &str) {}
+}
+
+/// Observer used when none is set, it ignores every event.
+pub struct NoopCoordinatorObserver;
+
+impl CoordinatorObserver for NoopCoordinatorObserver {}
@@ -0,0 +1,62 @@
+use crate::{errors::BitcoinCoordinatorError, settings::INCREMENTAL_RELAY_FEE_RATE};
+use bitcoin::{Transaction, TxOut};
+
+/// Signs the replacements of the transactions dispatched with `allow_rbf_of_parent`.
+/// Set with `BitcoinCoordinator::with_parent_tx_signer`.
+pub trait ParentTxSigner {
+    /// Returns the outputs spent by the inputs of the transaction, in input order.
+    /// The fee paid by the transaction is computed from them.
+    fn get_prevouts(&self, tx: &Transaction) -> Result<Vec<TxOut>, BitcoinCoordinatorError>;
+
+    /// Signs every input of `replacement`, a copy of a dispatched transaction with its change output reduced
+    /// and its witnesses removed, and returns it ready to be broadcast.
+    fn sign_replacement(
+        &self,
+        replacement: Transaction,
+        prevouts: &[TxOut],
+    ) -> Result<Transaction, BitcoinCoordinatorError>;
+}
+
+/// Fee of the replacement of a transaction and the amount left in its change output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParentReplacement {
+    pub fee: u64,
+    /// Fee paid over the replaced transaction, taken from the change output.
+    pub extra_fee: u64,
+    pub change_amount: u64,
+}
+
+/// Computes the replacement of a transaction of `vsize` vbytes paying `fee` sats.
+/// The replacement pays the highest of `fee_rate` (sat/vB), the fee of the transaction times `fee_multiplier`
+/// and the fee of the transaction plus the incremental relay fee (BIP-125).
+/// Returns None when the change left would be dust, or the replacement would pay more than `max_fee_rate`.
+pub fn compute_parent_replacement(
+    fee: u64,
+    vsize: u64,
+    change_amount: u64,
+    dust_limit: u64,
+    fee_rate: u64,
+    fee_multiplier: f64,
+    max_fee_rate: u64,
+) -> Option<ParentReplacement> {
+    let new_fee = (vsize * fee_rate)
+        .max((fee as f64 * fee_multiplier).ceil() as u64)
+        .max(fee + vsize * INCREMENTAL_RELAY_FEE_RATE);
+
+    if new_fee > vsize * max_fee_rate {
+        return None;
+    }
+
+    let extra_fee = new_fee - fee;
+    let change_amount = change_amount.checked_sub(extra_fee)?;
+
+    if change_amount < dust_limit {
+        return None;
+    }
+
+    Some(ParentReplacement {
+        fee: new_fee,
+        extra_fee,
+        change_amount,
+    })
+}
@@ -0,0 +1,58 @@
+use crate::{
+    errors::BitcoinCoordinatorError,
+    storage::{BitcoinCoordinatorStore, BitcoinCoordinatorStoreApi},
+    types::DetectedPegin,
+};
+use bitcoin::Transaction;
+use bitvmx_transaction_monitor::{monitor::MonitorApi, types::MonitorNews};
+
+// Output locking the pegged-in amount: the first output that is not the OP_RETURN carrying the RSK data.
+pub fn pegin_output(tx: &Transaction) -> Option<(u32, u64)> {
+    tx.output
+        .iter()
+        .enumerate()
+        .find(|(_, output)| !output.script_pubkey.is_op_return())
+        .map(|(vout, output)| (vout as u32, output.value.to_sat()))
+}
+
+// Records the mined peg-ins reported by the monitor and returns the ones not recorded before.
+// The monitor news are not acknowledged, they are still returned by get_news until the caller acknowledges them.
+pub fn record_detected_pegins<M: MonitorApi + ?Sized>(
+    monitor: &M,
+    store: &BitcoinCoordinatorStore,
+    context: &str,
+) -> Result<Vec<DetectedPegin>, BitcoinCoordinatorError> {
+    let mut detected = Vec::new();
+
+    for news in monitor.get_news()? {
+        let (tx_id, status) = match news {
+            MonitorNews::RskPeginTransaction(tx_id, status) => (tx_id, status),
+            _ => continue,
+        };
+
+        // The peg-in is recorded once it is mined.
+        let block_info = match status.block_info {
+            Some(block_info) => block_info,
+            None => continue,
+        };
+
+        let (vout, amount) = match pegin_output(&status.tx) {
+            Some(output) => output,
+            None => continue,
+        };
+
+        let pegin = DetectedPegin {
+            tx_id,
+            vout,
+            amount,
+            block_height: block_info.height,
+            context: context.to_string(),
+        };
+
+        if store.save_detected_pegin(pegin.clone())? {
+            detected.push(pegin);
+        }
+    }
+
+    Ok(detected)
+}
@@ -0,0 +1,66 @@
+use bitcoin::{Transaction, Txid};
+use bitvmx_bitcoin_rpc::{bitcoin_client::BitcoinClientApi, errors::BitcoinClientError};
+use std::{cell::RefCell, collections::HashMap};
+
+// Output amounts of the transactions spent by the finalized transactions of a tick.
+// Answers are kept until `reset` is called, so the client is asked at most once per transaction and tick.
+#[derive(Default)]
+pub struct PrevoutCache {
+    amounts: RefCell<HashMap<Txid, Option<Vec<u64>>>>,
+}
+
+impl PrevoutCache {
+    pub fn reset(&self) {
+        self.amounts.borrow_mut().clear();
+    }
+
+    // Returns the fee paid by `tx`, its input amounts minus its output amounts.
+    // None if a spent transaction is unknown to the client or does not have the spent output.
+    pub fn tx_fee(
+        &self,
+        tx: &Transaction,
+        client: &dyn BitcoinClientApi,
+    ) -> Result<Option<u64>, BitcoinClientError> {
+        let mut input_amount = 0;
+
+        for input in &tx.input {
+            let prevout = input.previous_output;
+
+            match self.output_amount(&prevout.txid, prevout.vout as usize, client)? {
+                Some(amount) => input_amount += amount,
+                None => return Ok(None),
+            }
+        }
+
+        let output_amount: u64 = tx.output.iter().map(|output| output.value.to_sat()).sum();
+
+        Ok(input_amount.checked_sub(output_amount))
+    }
+
+    fn output_amount(
+        &self,
+        txid: &Txid,
+        vout: usize,
+        client: &dyn BitcoinClientApi,
+    ) -> Result<Option<u64>, BitcoinClientError> {
+        if let Some(amounts) = self.amounts.borrow().get(txid) {
+            return Ok(amounts
+                .as_ref()
+                .and_then(|amounts| amounts.get(vout).copied()));
+        }
+
+        let amounts = client.get_transaction(txid)?.map(|tx| {
+            tx.output
+                .iter()
+                .map(|output| output.value.to_sat())
+                .collect::<Vec<u64>>()
+        });
+        let amount = amounts
+            .as_ref()
+            .and_then(|amounts| amounts.get(vout).copied());
+
+        self.amounts.borrow_mut().insert(*txid, amounts);
+
+        Ok(amount)
+    }
+}
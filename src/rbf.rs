@@ -0,0 +1,47 @@
+use crate::errors::BitcoinCoordinatorError;
+
+// Outcome of sending a replacement (RBF) escalating the fee bump each time the node rejects it for insufficient fee.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RbfEscalation {
+    // The replacement was accepted. `bump_fee` is the bump fee percentage used in the accepted attempt.
+    Sent { attempts: u32, bump_fee: f64 },
+
+    // Every attempt was rejected for insufficient fee. `last_error` is the last error returned by the node.
+    Failed { attempts: u32, last_error: String },
+}
+
+// Sends a replacement up to `max_attempts` times.
+// `send_replacement` receives the bump fee percentage to use and returns the node error message when the replacement
+// is rejected for insufficient fee, or None when it was handled (sent or reported by other means).
+// After each rejection the bump fee percentage is increased with `next_bump_fee`.
+pub fn escalate_replacement<S, B>(
+    max_attempts: u32,
+    initial_bump_fee: f64,
+    mut send_replacement: S,
+    next_bump_fee: B,
+) -> Result<RbfEscalation, BitcoinCoordinatorError>
+where
+    S: FnMut(f64) -> Result<Option<String>, BitcoinCoordinatorError>,
+    B: Fn(f64) -> Result<f64, BitcoinCoordinatorError>,
+{
+    let mut bump_fee = initial_bump_fee;
+    let mut attempts = 0;
+
+    loop {
+        attempts += 1;
+
+        let last_error = match send_replacement(bump_fee)? {
+            Some(error) => error,
+            None => return Ok(RbfEscalation::Sent { attempts, bump_fee }),
+        };
+
+        if attempts >= max_attempts {
+            return Ok(RbfEscalation::Failed {
+                attempts,
+                last_error,
+            });
+        }
+
+        bump_fee = next_bump_fee(bump_fee)?;
+    }
+}
@@ -0,0 +1,80 @@
+use crate::{errors::BitcoinCoordinatorError, types::ReadinessReport};
+use bitvmx_bitcoin_rpc::{bitcoin_client::BitcoinClientApi, types::BlockHeight};
+use bitvmx_transaction_monitor::monitor::MonitorApi;
+
+// Builds the readiness report from the monitor indexed height and the node tip height.
+// The coordinator is ready when the monitor is, the remaining blocks explain why it is not.
+// While the node is unreachable it is not asked for its tip and the coordinator is not ready.
+pub fn readiness_report<M, C>(
+    monitor: &M,
+    client: &C,
+    has_pending_txs: bool,
+    node_unreachable_since: Option<u64>,
+) -> Result<ReadinessReport, BitcoinCoordinatorError>
+where
+    M: MonitorApi + ?Sized,
+    C: BitcoinClientApi + ?Sized,
+{
+    let indexed_height = monitor.get_monitor_height()?;
+
+    let (tip_height, ready) = match node_unreachable_since {
+        Some(_) => (indexed_height, false),
+        None => (client.get_best_block()?, monitor.is_ready()?),
+    };
+
+    Ok(ReadinessReport {
+        ready,
+        indexed_height,
+        tip_height,
+        blocks_remaining: tip_height.saturating_sub(indexed_height),
+        has_pending_txs,
+        node_unreachable_since,
+    })
+}
+
+// Ticks the monitor until it is ready, reporting (indexed height, tip height) to `progress` after each tick.
+// Only the blockchain is indexed, no transaction is dispatched nor sped up while catching up.
+// Fails with SyncStalled when the indexed height does not advance in `max_stalled_ticks` consecutive ticks.
+pub fn sync_to_tip<M, C>(
+    monitor: &M,
+    client: &C,
+    max_stalled_ticks: u32,
+    progress: Option<&dyn Fn(BlockHeight, BlockHeight)>,
+) -> Result<(), BitcoinCoordinatorError>
+where
+    M: MonitorApi + ?Sized,
+    C: BitcoinClientApi + ?Sized,
+{
+    let mut last_height = monitor.get_monitor_height()?;
+    let mut stalled_ticks = 0;
+
+    loop {
+        monitor.tick()?;
+
+        let indexed_height = monitor.get_monitor_height()?;
+        let tip_height = client.get_best_block()?;
+
+        if let Some(progress) = progress {
+            progress(indexed_height, tip_height);
+        }
+
+        if monitor.is_ready()? {
+            return Ok(());
+        }
+
+        if indexed_height > last_height {
+            last_height = indexed_height;
+            stalled_ticks = 0;
+            continue;
+        }
+
+        stalled_ticks += 1;
+
+        if stalled_ticks >= max_stalled_ticks {
+            return Err(BitcoinCoordinatorError::SyncStalled(
+                indexed_height,
+                stalled_ticks,
+            ));
+        }
+    }
+}
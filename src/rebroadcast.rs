@@ -0,0 +1,111 @@
+use crate::{
+    config::CoordinatorSettings,
+    errors::{BitcoinCoordinatorError, BroadcastFailureAction, BroadcastFailureKind},
+    storage::{BitcoinCoordinatorStore, BitcoinCoordinatorStoreApi},
+    types::{CoordinatedTransaction, CoordinatorNews, JournalEvent, TransactionState},
+};
+use bitvmx_bitcoin_rpc::{bitcoin_client::BitcoinClientApi, types::BlockHeight};
+use console::style;
+use tracing::{info, warn};
+
+/// What to do with a dispatched transaction without speedup that the monitor can not find.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RebroadcastAction {
+    /// The transaction was sent (or sent again) recently, wait for the monitor to find it.
+    Wait,
+    /// Send the transaction again, with the attempt number starting at 1.
+    Rebroadcast(u32),
+    /// The transaction was already sent again max_rebroadcast_attempts times.
+    GiveUp(u32),
+}
+
+// Decides whether a missing transaction is sent again at `current_height`.
+// Transactions with speedup are not rebroadcast, their CPFP is bumped instead.
+pub fn next_rebroadcast_action(
+    tx: &CoordinatedTransaction,
+    current_height: BlockHeight,
+    rebroadcast_after_blocks: u32,
+    max_rebroadcast_attempts: u32,
+) -> RebroadcastAction {
+    if tx.state != TransactionState::Dispatched || tx.speedup_data.is_some() {
+        return RebroadcastAction::Wait;
+    }
+
+    let last_sent_height = match tx
+        .last_rebroadcast_block_height
+        .or(tx.broadcast_block_height)
+    {
+        Some(height) => height,
+        None => return RebroadcastAction::Wait,
+    };
+
+    if current_height.saturating_sub(last_sent_height) < rebroadcast_after_blocks {
+        return RebroadcastAction::Wait;
+    }
+
+    if tx.rebroadcast_count >= max_rebroadcast_attempts {
+        return RebroadcastAction::GiveUp(tx.rebroadcast_count);
+    }
+
+    RebroadcastAction::Rebroadcast(tx.rebroadcast_count + 1)
+}
+
+// Sends a missing transaction again when the rebroadcast policy says so, and returns the news to report.
+// Connection errors are not counted as an attempt, the transaction is sent again on the next tick.
+pub fn rebroadcast_missing_tx<C: BitcoinClientApi + ?Sized>(
+    client: &C,
+    store: &BitcoinCoordinatorStore,
+    tx: &CoordinatedTransaction,
+    current_height: BlockHeight,
+    settings: &CoordinatorSettings,
+) -> Result<Option<CoordinatorNews>, BitcoinCoordinatorError> {
+    let action = next_rebroadcast_action(
+        tx,
+        current_height,
+        settings.rebroadcast_after_blocks,
+        settings.max_rebroadcast_attempts,
+    );
+
+    match action {
+        RebroadcastAction::Wait => Ok(None),
+        RebroadcastAction::GiveUp(attempts) => Ok(Some(
+            CoordinatorNews::MaxRebroadcastAttemptsReached(tx.tx_id, attempts),
+        )),
+        RebroadcastAction::Rebroadcast(_) => {
+            let result = client.send_transaction(&tx.tx);
+            store
+                .journal()
+                .record(JournalEvent::broadcast_attempt(&tx.tx, &result));
+
+            if let Err(e) = result {
+                let error_msg = e.to_string();
+                let error_kind = BroadcastFailureKind::from_error_message(&error_msg);
+
+                warn!(
+                    "{} Error rebroadcasting Transaction({}): {}",
+                    style("Coordinator").green(),
+                    style(tx.tx_id).yellow(),
+                    error_msg
+                );
+
+                if error_kind.action() == BroadcastFailureAction::Requeue {
+                    return Ok(None);
+                }
+            }
+
+            let attempt = store.record_tx_rebroadcast(tx.tx_id, current_height)?;
+
+            info!(
+                "{} Transaction({}) rebroadcast at block height {} | Attempt({})",
+                style("Coordinator").green(),
+                style(tx.tx_id).yellow(),
+                style(current_height).blue(),
+                style(attempt).blue(),
+            );
+
+            Ok(Some(CoordinatorNews::TransactionRebroadcast(
+                tx.tx_id, attempt,
+            )))
+        }
+    }
+}
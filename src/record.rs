@@ -0,0 +1,887 @@
+use crate::{
+    errors::{BitcoinCoordinatorStoreError, BroadcastFailureKind},
+    types::{
+        CoordinatorNews, DispatchDeferredReason, SettingChange, Severity, SpeedupParent,
+        TickSkipReason,
+    },
+};
+use bitcoin::{BlockHash, OutPoint, ScriptBuf, Transaction, Txid};
+use bitvmx_bitcoin_rpc::types::BlockHeight;
+use bitvmx_transaction_monitor::types::BlockInfo;
+use protocol_builder::types::output::SpeedupData;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+// Version of the format of the records written to the store.
+// Records written before the records were versioned have no envelope, they are version 0.
+pub const STORE_RECORD_VERSION: u16 = 2;
+
+/// Envelope of every record written by the coordinator store, with the version of the format of its payload.
+/// Older records are upgraded when they are read, and written again with the current version.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct StoredRecord<T> {
+    pub version: u16,
+    pub payload: T,
+}
+
+impl<T> StoredRecord<T> {
+    pub fn new(payload: T) -> Self {
+        Self {
+            version: STORE_RECORD_VERSION,
+            payload,
+        }
+    }
+}
+
+// Upgrades the payload of the record stored at a key from a version to the next one.
+type Migration = fn(&str, Value) -> Result<Value, String>;
+
+// Migrations indexed by the version they upgrade from. A new version needs its migration to compile.
+const MIGRATIONS: [Migration; STORE_RECORD_VERSION as usize] =
+    [migrate_unversioned, migrate_speedup_parents];
+
+/// Returns the payload of a stored record upgraded to the current version, and whether it was upgraded.
+/// A record written by a newer version of the coordinator can not be read.
+pub fn upgrade_record(
+    key: &str,
+    record: Value,
+) -> Result<(Value, bool), BitcoinCoordinatorStoreError> {
+    let (version, mut payload) = open_envelope(record);
+
+    if version > STORE_RECORD_VERSION {
+        return Err(BitcoinCoordinatorStoreError::UnsupportedRecordVersion(
+            key.to_string(),
+            version,
+        ));
+    }
+
+    for migration in MIGRATIONS[version as usize..].iter() {
+        payload = migration(key, payload)
+            .map_err(|e| BitcoinCoordinatorStoreError::RecordMigrationError(key.to_string(), e))?;
+    }
+
+    Ok((payload, version < STORE_RECORD_VERSION))
+}
+
+// Splits a record in its version and payload. Records without envelope are version 0.
+fn open_envelope(record: Value) -> (u16, Value) {
+    let Value::Object(mut fields) = record else {
+        return (0, record);
+    };
+
+    let version = fields.get("version").and_then(Value::as_u64);
+
+    match version {
+        Some(version) if fields.len() == 2 && fields.contains_key("payload") => {
+            let payload = fields.remove("payload").unwrap_or_default();
+            (u16::try_from(version).unwrap_or(u16::MAX), payload)
+        }
+        _ => (0, Value::Object(fields)),
+    }
+}
+
+// Version 0 records are the payload as it was written. Only the news changed, they were stored as tuples
+// ending with the block hash they were reported at and their ack flag.
+// Fields added to the other records since then are read with their default value.
+fn migrate_unversioned(key: &str, payload: Value) -> Result<Value, String> {
+    let Some(name) = key.strip_prefix("bitcoin_coordinator/news/") else {
+        return Ok(payload);
+    };
+
+    if let Some((_, fields)) = LEGACY_NEWS_RECORDS.iter().find(|(news, _)| *news == name) {
+        return legacy_news_record(fields, payload);
+    }
+
+    let Some((_, fields)) = LEGACY_NEWS_LISTS.iter().find(|(news, _)| *news == name) else {
+        return Err(format!("Unknown news {name}"));
+    };
+
+    let Value::Array(news_list) = payload else {
+        return Err("News list is not an array".to_string());
+    };
+
+    news_list
+        .into_iter()
+        .map(|news| legacy_news_record(fields, news))
+        .collect::<Result<Vec<_>, _>>()
+        .map(Value::Array)
+}
+
+// Version 1 speedups kept a copy of every transaction they paid for, already stored in its coordinated transaction
+// record. They keep a reference to it from version 2. Speedups are stored alone, in the retry list and in the intents.
+fn migrate_speedup_parents(key: &str, mut payload: Value) -> Result<Value, String> {
+    if !key.starts_with("bitcoin_coordinator/speedup/") {
+        return Ok(payload);
+    }
+
+    match &mut payload {
+        Value::Array(records) => {
+            for record in records.iter_mut() {
+                match record.get_mut("speedup") {
+                    Some(speedup) => slim_speedup_parents(speedup)?,
+                    None => slim_speedup_parents(record)?,
+                }
+            }
+        }
+        record => slim_speedup_parents(record)?,
+    }
+
+    Ok(payload)
+}
+
+// Replaces the (speedup data, transaction, context) tuples of a speedup with references to the transactions.
+fn slim_speedup_parents(speedup: &mut Value) -> Result<(), String> {
+    let Some(Value::Array(parents)) = speedup.get_mut("speedup_tx_data") else {
+        return Ok(());
+    };
+
+    for parent in parents.iter_mut() {
+        let Value::Array(fields) = parent else {
+            continue;
+        };
+
+        let (speedup_data, tx, context): (SpeedupData, Transaction, String) =
+            serde_json::from_value(Value::Array(std::mem::take(fields)))
+                .map_err(|e| format!("Speedup parent is not a tuple: {e}"))?;
+
+        *parent = serde_json::to_value(SpeedupParent::new(speedup_data, &tx, context))
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+// Names the fields of a news stored as a tuple, and moves its block hash and ack flag to the record.
+fn legacy_news_record(fields: &[&str], news: Value) -> Result<Value, String> {
+    let Value::Array(mut values) = news else {
+        return Err("News is not a tuple".to_string());
+    };
+
+    let status = values.pop();
+    let Some(Value::Array(mut status)) = status.filter(|_| values.len() == fields.len()) else {
+        return Err(format!("News is not a tuple of {} fields", fields.len()));
+    };
+
+    let acknowledged = status.pop().unwrap_or_default();
+    let block_hash = status.pop().unwrap_or_default();
+
+    let news = match fields.is_empty() {
+        true => Value::Null,
+        false => Value::Object(
+            fields
+                .iter()
+                .map(|field| field.to_string())
+                .zip(values)
+                .collect::<Map<_, _>>(),
+        ),
+    };
+
+    let mut record = Map::new();
+    record.insert("news".to_string(), news);
+    record.insert("block_hash".to_string(), block_hash);
+    record.insert("acknowledged".to_string(), acknowledged);
+
+    Ok(Value::Object(record))
+}
+
+// Fields of the news kept in lists before the records were versioned, by news key.
+const LEGACY_NEWS_LISTS: &[(&str, &[&str])] = &[
+    ("insufficient_funds", &["tx_id", "available", "required"]),
+    (
+        "speedup_fee_cap_exceeded",
+        &["tx_id", "estimated_fee", "cap"],
+    ),
+    ("funding_topup", &["tx_id", "amount"]),
+    (
+        "parent_replaced",
+        &["tx_id", "replacement_txid", "extra_fee"],
+    ),
+    (
+        "dispatch_transaction_error",
+        &["tx_id", "context", "error", "kind"],
+    ),
+    (
+        "dispatch_speed_up_error",
+        &["tx_ids", "contexts", "speedup_txid", "error"],
+    ),
+    (
+        "estimate_feerate_too_high",
+        &["estimated_fee_rate", "max_fee_rate"],
+    ),
+    ("transaction_already_in_mempool", &["tx_id", "context"]),
+    ("mempool_rejection", &["tx_id", "context", "error"]),
+    ("network_error", &["tx_id", "context", "error"]),
+    ("dispatch_cancelled", &["tx_id", "context"]),
+    ("rbf_escalation_failed", &["tx_id", "attempts", "error"]),
+    ("max_rbf_attempts_reached", &["tx_id", "attempts", "fee"]),
+    ("transaction_rebroadcast", &["tx_id", "attempt"]),
+    ("max_rebroadcast_attempts_reached", &["tx_id", "attempts"]),
+    ("speedup_orphaned", &["tx_id", "paid_txids"]),
+    ("speedup_chain_invalidated", &["tx_ids"]),
+    (
+        "speedup_created",
+        &["tx_id", "paid_txids", "fee", "fee_rate", "is_rbf"],
+    ),
+    (
+        "transaction_conflicted",
+        &["tx_id", "conflicting_txid", "context"],
+    ),
+    (
+        "transaction_reorged",
+        &["tx_id", "orphan_block_hash", "context"],
+    ),
+    ("dispatch_scheduled", &["tx_id", "block_height"]),
+    ("dependency_failed", &["tx_id", "dependency_txid"]),
+    (
+        "outpoint_spent",
+        &[
+            "outpoint",
+            "spending_txid",
+            "input_index",
+            "block_info",
+            "context",
+        ],
+    ),
+];
+
+// Fields of the news kept as a single record before the records were versioned, by news key.
+const LEGACY_NEWS_RECORDS: &[(&str, &[&str])] = &[
+    ("funding_not_found", &[]),
+    ("fee_estimate_unavailable", &["fee_rate"]),
+    ("tick_partial_failure", &["failed_count"]),
+    ("node_unreachable", &["since"]),
+    ("node_recovered", &["unreachable_ms"]),
+    ("settings_updated", &["changes"]),
+    ("new_block", &["height"]),
+];
+
+/// A news stored by the coordinator, with the block it was reported at and whether it was acknowledged.
+/// The severity is the one the news had when it was reported, so a later classification does not change it.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct NewsRecord<T> {
+    pub news: T,
+    pub block_hash: BlockHash,
+    pub acknowledged: bool,
+    // None for the news stored before the severities, they are classified when read.
+    #[serde(default)]
+    pub severity: Option<Severity>,
+    // Position of the news in the news log, set when the news is logged. None for the news stored before
+    // the log, they are numbered when the log is built.
+    #[serde(default)]
+    pub seq: Option<u64>,
+}
+
+impl<T> NewsRecord<T> {
+    pub fn with_severity(news: T, block_hash: BlockHash, severity: Severity) -> Self {
+        Self {
+            news,
+            block_hash,
+            acknowledged: false,
+            severity: Some(severity),
+            seq: None,
+        }
+    }
+}
+
+impl<T: Clone + Into<CoordinatorNews>> NewsRecord<T> {
+    pub fn new(news: T, block_hash: BlockHash) -> Self {
+        let coordinator_news: CoordinatorNews = news.clone().into();
+        Self::with_severity(news, block_hash, coordinator_news.severity())
+    }
+}
+
+// Stored news, one struct per CoordinatorNews variant so their fields can evolve.
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct DispatchTransactionErrorNews {
+    pub tx_id: Txid,
+    pub context: String,
+    pub error: String,
+    pub kind: BroadcastFailureKind,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct DispatchSpeedUpErrorNews {
+    pub tx_ids: Vec<Txid>,
+    pub contexts: Vec<String>,
+    pub speedup_txid: Txid,
+    pub error: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct InsufficientFundsNews {
+    pub tx_id: Txid,
+    pub available: u64,
+    pub required: u64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct FundingNotFoundNews;
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct EstimateFeerateTooHighNews {
+    pub estimated_fee_rate: u64,
+    pub max_fee_rate: u64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct FeeEstimateUnavailableNews {
+    pub fee_rate: u64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct DispatchPausedHighFeesNews {
+    pub paused_count: u32,
+    pub fee_rate: u64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct FundingTopUpNews {
+    pub tx_id: Txid,
+    pub amount: u64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct SpeedupFeeCapExceededNews {
+    pub tx_id: Txid,
+    pub estimated_fee: u64,
+    pub cap: u64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ParentReplacedNews {
+    pub tx_id: Txid,
+    pub replacement_txid: Txid,
+    pub extra_fee: u64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct TickPartialFailureNews {
+    pub failed_count: u32,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct NodeUnreachableNews {
+    pub since: u64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct NodeRecoveredNews {
+    pub unreachable_ms: u64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct SettingsUpdatedNews {
+    pub changes: Vec<SettingChange>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct TransactionAlreadyInMempoolNews {
+    pub tx_id: Txid,
+    pub context: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct MempoolRejectionNews {
+    pub tx_id: Txid,
+    pub context: String,
+    pub error: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct NetworkErrorNews {
+    pub tx_id: Txid,
+    pub context: String,
+    pub error: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct DispatchCancelledNews {
+    pub tx_id: Txid,
+    pub context: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct TransactionExpiredNews {
+    pub tx_id: Txid,
+    pub context: String,
+    pub expires_at: BlockHeight,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct NonStandardAnchorNews {
+    pub tx_id: Txid,
+    pub context: String,
+    pub reason: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct RbfEscalationFailedNews {
+    pub tx_id: Txid,
+    pub attempts: u32,
+    pub error: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct MaxRbfAttemptsReachedNews {
+    pub tx_id: Txid,
+    pub attempts: u32,
+    pub fee: u64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct TransactionRebroadcastNews {
+    pub tx_id: Txid,
+    pub attempt: u32,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct MaxRebroadcastAttemptsReachedNews {
+    pub tx_id: Txid,
+    pub attempts: u32,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct SpeedupOrphanedNews {
+    pub tx_id: Txid,
+    pub paid_txids: Vec<Txid>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct SpeedupChainInvalidatedNews {
+    pub tx_ids: Vec<Txid>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct SpeedupCreatedNews {
+    pub tx_id: Txid,
+    pub paid_txids: Vec<Txid>,
+    pub fee: u64,
+    pub fee_rate: u64,
+    pub is_rbf: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct TransactionConflictedNews {
+    pub tx_id: Txid,
+    pub conflicting_txid: Txid,
+    pub context: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct TransactionReorgedNews {
+    pub tx_id: Txid,
+    pub orphan_block_hash: BlockHash,
+    pub context: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct DispatchScheduledNews {
+    pub tx_id: Txid,
+    pub block_height: BlockHeight,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct DependencyFailedNews {
+    pub tx_id: Txid,
+    pub dependency_txid: Txid,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OutpointSpentNews {
+    pub outpoint: OutPoint,
+    pub spending_txid: Txid,
+    pub input_index: u32,
+    pub block_info: BlockInfo,
+    pub context: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct AddressFundedNews {
+    pub script_pubkey: ScriptBuf,
+    pub tx: Transaction,
+    pub outputs: Vec<(u32, u64)>,
+    pub block_info: BlockInfo,
+    pub context: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct FundingSpentExternallyNews {
+    pub outpoint: OutPoint,
+    pub spending_txid: Option<Txid>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct FundingExhaustedNews {
+    pub tx_id: Txid,
+    pub change_sats: u64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct FundingTemporarilyUnavailableNews {
+    pub tx_id: Txid,
+    pub paid_txids: Vec<Txid>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct AnchorSpentExternallyNews {
+    pub tx_id: Txid,
+    pub anchor: OutPoint,
+    pub spending_txid: Txid,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct FeeOverpaymentNews {
+    pub tx_id: Txid,
+    pub paid_fee_rate: f64,
+    pub reference_fee_rate: u64,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ConfirmationMilestoneNews {
+    pub tx_id: Txid,
+    pub confirmations: u32,
+    pub context: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct CollateralSpentNews {
+    pub label: String,
+    pub outpoint: OutPoint,
+    pub spending_txid: Txid,
+    pub unspent_count: u32,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct CollateralFullySpentNews {
+    pub label: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct DispatchDeferredNews {
+    pub tx_ids: Vec<Txid>,
+    pub reason: DispatchDeferredReason,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct TickWorkSkippedNews {
+    pub reason: TickSkipReason,
+    pub error: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct SpeedupRejectedByPolicyNews {
+    pub tx_id: Txid,
+    pub reason: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct GroupCompletedNews {
+    pub group_id: Uuid,
+    pub context: String,
+}
+
+// The block hash of a new block news is the block hash of its record.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct NewBlockNews {
+    pub height: BlockHeight,
+}
+
+impl From<DispatchTransactionErrorNews> for CoordinatorNews {
+    fn from(news: DispatchTransactionErrorNews) -> Self {
+        CoordinatorNews::DispatchTransactionError(news.tx_id, news.context, news.error, news.kind)
+    }
+}
+
+impl From<DispatchSpeedUpErrorNews> for CoordinatorNews {
+    fn from(news: DispatchSpeedUpErrorNews) -> Self {
+        CoordinatorNews::DispatchSpeedUpError(
+            news.tx_ids,
+            news.contexts,
+            news.speedup_txid,
+            news.error,
+        )
+    }
+}
+
+impl From<InsufficientFundsNews> for CoordinatorNews {
+    fn from(news: InsufficientFundsNews) -> Self {
+        CoordinatorNews::InsufficientFunds(news.tx_id, news.available, news.required)
+    }
+}
+
+impl From<FundingNotFoundNews> for CoordinatorNews {
+    fn from(_: FundingNotFoundNews) -> Self {
+        CoordinatorNews::FundingNotFound
+    }
+}
+
+impl From<EstimateFeerateTooHighNews> for CoordinatorNews {
+    fn from(news: EstimateFeerateTooHighNews) -> Self {
+        CoordinatorNews::EstimateFeerateTooHigh(news.estimated_fee_rate, news.max_fee_rate)
+    }
+}
+
+impl From<FeeEstimateUnavailableNews> for CoordinatorNews {
+    fn from(news: FeeEstimateUnavailableNews) -> Self {
+        CoordinatorNews::FeeEstimateUnavailable(news.fee_rate)
+    }
+}
+
+impl From<DispatchPausedHighFeesNews> for CoordinatorNews {
+    fn from(news: DispatchPausedHighFeesNews) -> Self {
+        CoordinatorNews::DispatchPausedHighFees(news.paused_count, news.fee_rate)
+    }
+}
+
+impl From<FundingTopUpNews> for CoordinatorNews {
+    fn from(news: FundingTopUpNews) -> Self {
+        CoordinatorNews::FundingTopUp(news.tx_id, news.amount)
+    }
+}
+
+impl From<SpeedupFeeCapExceededNews> for CoordinatorNews {
+    fn from(news: SpeedupFeeCapExceededNews) -> Self {
+        CoordinatorNews::SpeedupFeeCapExceeded(news.tx_id, news.estimated_fee, news.cap)
+    }
+}
+
+impl From<ParentReplacedNews> for CoordinatorNews {
+    fn from(news: ParentReplacedNews) -> Self {
+        CoordinatorNews::ParentReplaced(news.tx_id, news.replacement_txid, news.extra_fee)
+    }
+}
+
+impl From<TickPartialFailureNews> for CoordinatorNews {
+    fn from(news: TickPartialFailureNews) -> Self {
+        CoordinatorNews::TickPartialFailure(news.failed_count)
+    }
+}
+
+impl From<NodeUnreachableNews> for CoordinatorNews {
+    fn from(news: NodeUnreachableNews) -> Self {
+        CoordinatorNews::NodeUnreachable(news.since)
+    }
+}
+
+impl From<NodeRecoveredNews> for CoordinatorNews {
+    fn from(news: NodeRecoveredNews) -> Self {
+        CoordinatorNews::NodeRecovered(news.unreachable_ms)
+    }
+}
+
+impl From<SettingsUpdatedNews> for CoordinatorNews {
+    fn from(news: SettingsUpdatedNews) -> Self {
+        CoordinatorNews::SettingsUpdated(news.changes)
+    }
+}
+
+impl From<TransactionAlreadyInMempoolNews> for CoordinatorNews {
+    fn from(news: TransactionAlreadyInMempoolNews) -> Self {
+        CoordinatorNews::TransactionAlreadyInMempool(news.tx_id, news.context)
+    }
+}
+
+impl From<MempoolRejectionNews> for CoordinatorNews {
+    fn from(news: MempoolRejectionNews) -> Self {
+        CoordinatorNews::MempoolRejection(news.tx_id, news.context, news.error)
+    }
+}
+
+impl From<NetworkErrorNews> for CoordinatorNews {
+    fn from(news: NetworkErrorNews) -> Self {
+        CoordinatorNews::NetworkError(news.tx_id, news.context, news.error)
+    }
+}
+
+impl From<DispatchCancelledNews> for CoordinatorNews {
+    fn from(news: DispatchCancelledNews) -> Self {
+        CoordinatorNews::DispatchCancelled(news.tx_id, news.context)
+    }
+}
+
+impl From<TransactionExpiredNews> for CoordinatorNews {
+    fn from(news: TransactionExpiredNews) -> Self {
+        CoordinatorNews::TransactionExpired(news.tx_id, news.context, news.expires_at)
+    }
+}
+
+impl From<NonStandardAnchorNews> for CoordinatorNews {
+    fn from(news: NonStandardAnchorNews) -> Self {
+        CoordinatorNews::NonStandardAnchor(news.tx_id, news.context, news.reason)
+    }
+}
+
+impl From<RbfEscalationFailedNews> for CoordinatorNews {
+    fn from(news: RbfEscalationFailedNews) -> Self {
+        CoordinatorNews::RbfEscalationFailed(news.tx_id, news.attempts, news.error)
+    }
+}
+
+impl From<MaxRbfAttemptsReachedNews> for CoordinatorNews {
+    fn from(news: MaxRbfAttemptsReachedNews) -> Self {
+        CoordinatorNews::MaxRbfAttemptsReached(news.tx_id, news.attempts, news.fee)
+    }
+}
+
+impl From<TransactionRebroadcastNews> for CoordinatorNews {
+    fn from(news: TransactionRebroadcastNews) -> Self {
+        CoordinatorNews::TransactionRebroadcast(news.tx_id, news.attempt)
+    }
+}
+
+impl From<MaxRebroadcastAttemptsReachedNews> for CoordinatorNews {
+    fn from(news: MaxRebroadcastAttemptsReachedNews) -> Self {
+        CoordinatorNews::MaxRebroadcastAttemptsReached(news.tx_id, news.attempts)
+    }
+}
+
+impl From<SpeedupOrphanedNews> for CoordinatorNews {
+    fn from(news: SpeedupOrphanedNews) -> Self {
+        CoordinatorNews::SpeedupOrphaned(news.tx_id, news.paid_txids)
+    }
+}
+
+impl From<SpeedupChainInvalidatedNews> for CoordinatorNews {
+    fn from(news: SpeedupChainInvalidatedNews) -> Self {
+        CoordinatorNews::SpeedupChainInvalidated(news.tx_ids)
+    }
+}
+
+impl From<SpeedupCreatedNews> for CoordinatorNews {
+    fn from(news: SpeedupCreatedNews) -> Self {
+        CoordinatorNews::SpeedupCreated(
+            news.tx_id,
+            news.paid_txids,
+            news.fee,
+            news.fee_rate,
+            news.is_rbf,
+        )
+    }
+}
+
+impl From<TransactionConflictedNews> for CoordinatorNews {
+    fn from(news: TransactionConflictedNews) -> Self {
+        CoordinatorNews::TransactionConflicted(news.tx_id, news.conflicting_txid, news.context)
+    }
+}
+
+impl From<TransactionReorgedNews> for CoordinatorNews {
+    fn from(news: TransactionReorgedNews) -> Self {
+        CoordinatorNews::TransactionReorged(news.tx_id, news.orphan_block_hash, news.context)
+    }
+}
+
+impl From<DispatchScheduledNews> for CoordinatorNews {
+    fn from(news: DispatchScheduledNews) -> Self {
+        CoordinatorNews::DispatchScheduled(news.tx_id, news.block_height)
+    }
+}
+
+impl From<DependencyFailedNews> for CoordinatorNews {
+    fn from(news: DependencyFailedNews) -> Self {
+        CoordinatorNews::DependencyFailed(news.tx_id, news.dependency_txid)
+    }
+}
+
+impl From<OutpointSpentNews> for CoordinatorNews {
+    fn from(news: OutpointSpentNews) -> Self {
+        CoordinatorNews::OutpointSpent(
+            news.outpoint,
+            news.spending_txid,
+            news.input_index,
+            news.block_info,
+            news.context,
+        )
+    }
+}
+
+impl From<FundingSpentExternallyNews> for CoordinatorNews {
+    fn from(news: FundingSpentExternallyNews) -> Self {
+        CoordinatorNews::FundingSpentExternally(news.outpoint, news.spending_txid)
+    }
+}
+
+impl From<FundingExhaustedNews> for CoordinatorNews {
+    fn from(news: FundingExhaustedNews) -> Self {
+        CoordinatorNews::FundingExhausted(news.tx_id, news.change_sats)
+    }
+}
+
+impl From<FundingTemporarilyUnavailableNews> for CoordinatorNews {
+    fn from(news: FundingTemporarilyUnavailableNews) -> Self {
+        CoordinatorNews::FundingTemporarilyUnavailable(news.tx_id, news.paid_txids)
+    }
+}
+
+impl From<AnchorSpentExternallyNews> for CoordinatorNews {
+    fn from(news: AnchorSpentExternallyNews) -> Self {
+        CoordinatorNews::AnchorSpentExternally(news.tx_id, news.anchor, news.spending_txid)
+    }
+}
+
+impl From<FeeOverpaymentNews> for CoordinatorNews {
+    fn from(news: FeeOverpaymentNews) -> Self {
+        CoordinatorNews::FeeOverpayment(news.tx_id, news.paid_fee_rate, news.reference_fee_rate)
+    }
+}
+
+impl From<ConfirmationMilestoneNews> for CoordinatorNews {
+    fn from(news: ConfirmationMilestoneNews) -> Self {
+        CoordinatorNews::ConfirmationMilestone(news.tx_id, news.confirmations, news.context)
+    }
+}
+
+impl From<CollateralSpentNews> for CoordinatorNews {
+    fn from(news: CollateralSpentNews) -> Self {
+        CoordinatorNews::CollateralSpent(
+            news.label,
+            news.outpoint,
+            news.spending_txid,
+            news.unspent_count,
+        )
+    }
+}
+
+impl From<CollateralFullySpentNews> for CoordinatorNews {
+    fn from(news: CollateralFullySpentNews) -> Self {
+        CoordinatorNews::CollateralFullySpent(news.label)
+    }
+}
+
+impl From<DispatchDeferredNews> for CoordinatorNews {
+    fn from(news: DispatchDeferredNews) -> Self {
+        CoordinatorNews::DispatchDeferred(news.tx_ids, news.reason)
+    }
+}
+
+impl From<TickWorkSkippedNews> for CoordinatorNews {
+    fn from(news: TickWorkSkippedNews) -> Self {
+        CoordinatorNews::TickWorkSkipped(news.reason, news.error)
+    }
+}
+
+impl From<SpeedupRejectedByPolicyNews> for CoordinatorNews {
+    fn from(news: SpeedupRejectedByPolicyNews) -> Self {
+        CoordinatorNews::SpeedupRejectedByPolicy(news.tx_id, news.reason)
+    }
+}
+
+impl From<GroupCompletedNews> for CoordinatorNews {
+    fn from(news: GroupCompletedNews) -> Self {
+        CoordinatorNews::GroupCompleted(news.group_id, news.context)
+    }
+}
+
+impl From<AddressFundedNews> for CoordinatorNews {
+    fn from(news: AddressFundedNews) -> Self {
+        CoordinatorNews::AddressFunded(
+            news.script_pubkey,
+            news.tx,
+            news.outputs,
+            news.block_info,
+            news.context,
+        )
+    }
+}
@@ -0,0 +1,21 @@
+use bitcoin::{Transaction, Txid};
+
+/// What to do with a signed speedup, answered by a `SpeedupReviewHook`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReviewDecision {
+    /// The speedup is broadcast.
+    Approve,
+    /// The speedup is not broadcast, the rejection is saved and reported with a `SpeedupRejectedByPolicy` news.
+    Reject(String),
+    /// The speedup is not broadcast and is built again on a later tick, it does not count as a failure.
+    Defer,
+}
+
+/// Reviews every speedup (CPFP or RBF) signed by the coordinator before it is broadcast, e.g. to log it or to
+/// have it approved. Set with `BitcoinCoordinator::with_speedup_review_hook`, speedups are broadcast unreviewed
+/// when it is not set.
+pub trait SpeedupReviewHook {
+    /// Called with the signed speedup, the transactions it pays for, its fee in sats and whether it replaces
+    /// a previous speedup.
+    fn review(&self, tx: &Transaction, parents: &[Txid], fee: u64, is_rbf: bool) -> ReviewDecision;
+}
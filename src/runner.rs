@@ -0,0 +1,213 @@
+use crate::{
+    coordinator::{BitcoinCoordinator, BitcoinCoordinatorApi},
+    errors::BitcoinCoordinatorError,
+    news::news_acks,
+    settings::{DEFAULT_RUNNER_TICK_INTERVAL_MILLIS, MAX_RETRY_BACKOFF_SECONDS},
+    types::{AckNews, News},
+};
+use std::{
+    sync::mpsc::{Receiver, RecvTimeoutError},
+    thread,
+    time::Duration,
+};
+use tracing::{error, info};
+
+// Decides which news are acknowledged once they were handled.
+// The news left out are reported again on the next step.
+pub trait NewsHandler {
+    fn handle_news(&mut self, news: &News) -> Vec<AckNews>;
+}
+
+impl<F: FnMut(&News) -> Vec<AckNews>> NewsHandler for F {
+    fn handle_news(&mut self, news: &News) -> Vec<AckNews> {
+        self(news)
+    }
+}
+
+// Logs every news and acknowledges all of them.
+pub struct AckAllNews;
+
+impl NewsHandler for AckAllNews {
+    fn handle_news(&mut self, news: &News) -> Vec<AckNews> {
+        for news in news.monitor_news.iter() {
+            info!("Monitor news: {:?}", news);
+        }
+        for news in news.coordinator_news.iter() {
+            info!("Coordinator news: {:?}", news);
+        }
+
+        news_acks(news)
+    }
+}
+
+pub struct RunnerConfig {
+    // Wait between two steps.
+    pub tick_interval: Duration,
+    // Steps run before run returns, None runs until the stop signal.
+    pub max_ticks: Option<u64>,
+    // run returns when a message is received, or when the sender is dropped.
+    pub stop_signal: Option<Receiver<()>>,
+    // After a failed step the wait doubles with each consecutive failure up to it.
+    pub max_backoff: Duration,
+}
+
+impl Default for RunnerConfig {
+    fn default() -> Self {
+        Self {
+            tick_interval: Duration::from_millis(DEFAULT_RUNNER_TICK_INTERVAL_MILLIS),
+            max_ticks: None,
+            stop_signal: None,
+            max_backoff: Duration::from_secs(MAX_RETRY_BACKOFF_SECONDS),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum StepOutcome {
+    // The tick succeeded. `news` were given to the handler and `acknowledged` of them were acknowledged.
+    Completed {
+        news: usize,
+        acknowledged: usize,
+    },
+    // The tick, or getting or acknowledging the news, failed. The next step waits `retry_in`.
+    Failed {
+        error: BitcoinCoordinatorError,
+        retry_in: Duration,
+    },
+}
+
+// Steps run by run, and how many of them failed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RunSummary {
+    pub ticks: u64,
+    pub failed_ticks: u64,
+    pub acknowledged_news: u64,
+}
+
+// Drives a coordinator like a service: ticks it, gives the news to the handler and acknowledges what it returns.
+// A failed step does not stop the runner, the next one is delayed with a backoff instead.
+pub struct CoordinatorRunner<H: NewsHandler, C: BitcoinCoordinatorApi = BitcoinCoordinator> {
+    coordinator: C,
+    handler: H,
+    config: RunnerConfig,
+    consecutive_failures: u32,
+}
+
+impl<H: NewsHandler, C: BitcoinCoordinatorApi> CoordinatorRunner<H, C> {
+    pub fn new(coordinator: C, handler: H, config: RunnerConfig) -> Self {
+        Self {
+            coordinator,
+            handler,
+            config,
+            consecutive_failures: 0,
+        }
+    }
+
+    pub fn coordinator(&self) -> &C {
+        &self.coordinator
+    }
+
+    pub fn into_coordinator(self) -> C {
+        self.coordinator
+    }
+
+    pub fn consecutive_failures(&self) -> u32 {
+        self.consecutive_failures
+    }
+
+    // Runs a single tick and hands the news to the handler, without waiting.
+    pub fn step(&mut self) -> StepOutcome {
+        match self.tick_and_handle_news() {
+            Ok((news, acknowledged)) => {
+                self.consecutive_failures = 0;
+                StepOutcome::Completed { news, acknowledged }
+            }
+            Err(error) => {
+                self.consecutive_failures += 1;
+                let retry_in = self.next_wait();
+                error!(
+                    "Coordinator step failed ({} in a row), retrying in {:?}: {}",
+                    self.consecutive_failures, retry_in, error
+                );
+                StepOutcome::Failed { error, retry_in }
+            }
+        }
+    }
+
+    // Wait before the next step: the tick interval, doubled with each consecutive failure up to max_backoff.
+    pub fn next_wait(&self) -> Duration {
+        if self.consecutive_failures == 0 {
+            return self.config.tick_interval;
+        }
+
+        let factor = 2u32.saturating_pow(self.consecutive_failures);
+        self.config
+            .tick_interval
+            .saturating_mul(factor)
+            .min(self.config.max_backoff)
+    }
+
+    // Runs steps until max_ticks steps were run or the stop signal is received.
+    pub fn run(&mut self) -> RunSummary {
+        let mut summary = RunSummary::default();
+
+        while self
+            .config
+            .max_ticks
+            .is_none_or(|max_ticks| summary.ticks < max_ticks)
+        {
+            summary.ticks += 1;
+            match self.step() {
+                StepOutcome::Completed { acknowledged, .. } => {
+                    summary.acknowledged_news += acknowledged as u64;
+                }
+                StepOutcome::Failed { .. } => summary.failed_ticks += 1,
+            }
+
+            // No wait after the last step
+            if self.config.max_ticks == Some(summary.ticks) {
+                break;
+            }
+
+            if self.wait(self.next_wait()) {
+                info!("Coordinator runner stopped");
+                break;
+            }
+        }
+
+        summary
+    }
+
+    fn tick_and_handle_news(&mut self) -> Result<(usize, usize), BitcoinCoordinatorError> {
+        self.coordinator.tick()?;
+
+        let news = self.coordinator.get_news()?;
+        if news.is_empty() {
+            return Ok((0, 0));
+        }
+
+        let count = news.monitor_news.len() + news.coordinator_news.len();
+        let acks = self.handler.handle_news(&news);
+        let acknowledged = if acks.is_empty() {
+            0
+        } else {
+            self.coordinator.ack_news_batch(acks)?
+        };
+
+        Ok((count, acknowledged))
+    }
+
+    // Waits until the next step. Returns true when the runner has to stop.
+    fn wait(&self, duration: Duration) -> bool {
+        match &self.config.stop_signal {
+            Some(stop_signal) => !matches!(
+                stop_signal.recv_timeout(duration),
+                Err(RecvTimeoutError::Timeout)
+            ),
+            None => {
+                thread::sleep(duration);
+                false
+            }
+        }
+    }
+}
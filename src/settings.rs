@@ -4,17 +4,41 @@
 pub const CPFP_TRANSACTION_CONTEXT: &str = "CPFP_TRANSACTION";
 pub const RBF_TRANSACTION_CONTEXT: &str = "RBF_TRANSACTION";
 pub const FUNDING_TRANSACTION_CONTEXT: &str = "FUNDING_TRANSACTION";
+// Context of the spends of the speedup outputs of the dispatched transactions.
+pub const ANCHOR_SPEND_CONTEXT: &str = "ANCHOR_SPEND";
+
+// Maximum length in bytes of the context given to dispatch and monitor.
+pub const MAX_CONTEXT_LENGTH: usize = 1024;
+
+// Confirmation targets accepted by estimatesmartfee.
+pub const MIN_FEE_CONF_TARGET: u16 = 1;
+pub const MAX_FEE_CONF_TARGET: u16 = 1008;
+
+// Confirmation target used by a SmartFee fee strategy with an estimate mode and no conf_target.
+pub const DEFAULT_FEE_CONF_TARGET: u16 = 6;
 
 // Bitcoin Core has a mempool policy called the "chain limit":
 // You can’t have more than 25 unconfirmed transactions chained together (i.e. one spending the other).
 pub const MAX_LIMIT_UNCONFIRMED_PARENTS: u32 = 25;
 
+// Bitcoin Core also limits the total virtual size of the unconfirmed ancestors of a transaction, itself included (101 kvB).
+pub const MAX_ANCESTOR_SIZE_VBYTES: u64 = 101_000;
+
 // Minimum number of unconfirmed transactions required to dispatch a CPFP (Child Pays For Parent) transaction.
 // This is due to Bitcoin's mempool chain limit policy, which restricts the number of unconfirmed transactions that can be chained together (default is 25).
 // To create a valid CPFP, there must be at least one unconfirmed parent transaction and at least one unconfirmed output available to spend for the CPFP.
 // This ensures that the CPFP transaction can be constructed and accepted by the mempool under Bitcoin's standardness rules.
 pub const MIN_UNCONFIRMED_TXS_FOR_CPFP: u32 = 2;
 
+// Minimum fee rate (sat/vB) a replacement pays over the fee of the transaction it replaces (BIP-125 rule 4).
+pub const INCREMENTAL_RELAY_FEE_RATE: u64 = 1;
+
+// Timeout of the requests to the Esplora server, in seconds.
+pub const DEFAULT_ESPLORA_TIMEOUT_SECONDS: u64 = 30;
+
+// Attempts of a store write that failed after its transaction was broadcast, the tick fails once they are exhausted.
+pub const MAX_STORE_WRITE_ATTEMPTS: u32 = 5;
+
 // SETTINGS CONFIGURABLE:
 
 // Maximum number of unconfirmed speedup transactions allowed before triggering a replacement speedup.
@@ -24,7 +48,8 @@ pub const DEFAULT_MAX_UNCONFIRMED_SPEEDUPS: u32 = 10;
 // Maximum transaction weight in bytes.
 pub const DEFAULT_MAX_TX_WEIGHT: u64 = 400_000;
 
-// Maximum number of RBF attempts for a single transaction
+// Maximum number of RBF attempts for a single transaction.
+// Once a CPFP was replaced this many times it is not replaced again until it confirms.
 pub const DEFAULT_MAX_RBF_ATTEMPTS: u32 = 10;
 
 // Minimum funding amount in sats to ensure sufficient funds for speedups
@@ -39,17 +64,127 @@ pub const DEFAULT_MIN_BLOCKS_BEFORE_RESEND_SPEEDUP: u32 = 1;
 // Maximum feerate sat/vbyte allowed for speedups
 pub const DEFAULT_MAX_FEERATE_SAT_VB: u64 = 1000;
 
+// Network presets: the defaults that depend on the network, the other settings have the same default everywhere.
+// Mainnet caps the feerate lower, a fee spike should not drain the funding.
+pub const MAINNET_MAX_FEERATE_SAT_VB: u64 = 500;
+
+// The test networks (regtest, signet, testnet, testnet4) never need a high feerate, a higher estimate is a broken one.
+pub const TEST_NETWORK_MAX_FEERATE_SAT_VB: u64 = 100;
+
+// Signet blocks are sparse, a speedup resent after a single block is replaced before it had a chance to confirm.
+pub const SIGNET_MIN_BLOCKS_BEFORE_RESEND_SPEEDUP: u32 = 3;
+
+// Testnet fee estimates are unreliable and often below what the mempool accepts, speedups pay at least this fee rate.
+pub const TESTNET_MIN_NETWORK_FEE_RATE: u64 = 2;
+
 // Fee multiplier for base fee multiplier
 pub const DEFAULT_BASE_FEE_MULTIPLIER: f64 = 1.0;
 
 // Bump fee percentage
 pub const DEFAULT_BUMP_FEE_PERCENTAGE: f64 = 1.5;
 
+// Bounds of the bump applied to a stuck speedup once adjusted to the recent speedup outcomes
+pub const DEFAULT_MIN_BUMP_FEE_PERCENTAGE: f64 = 1.1;
+pub const DEFAULT_MAX_BUMP_FEE_PERCENTAGE: f64 = 3.0;
+
+// Confirmed speedups kept to adjust the bump, the oldest are dropped first
+pub const BUMP_STATS_WINDOW: usize = 20;
+
 // Retry interval seconds
 pub const DEFAULT_RETRY_INTERVAL_SECONDS: u64 = 5;
 
+// Maximum wait in seconds between two retries, the retry interval doubles with each retry up to it
+pub const MAX_RETRY_BACKOFF_SECONDS: u64 = 1800;
+
+// Milliseconds the coordinator runner waits between two ticks
+pub const DEFAULT_RUNNER_TICK_INTERVAL_MILLIS: u64 = 1000;
+
 // Retry attempts sending tx after an error
 pub const DEFAULT_RETRY_ATTEMPTS_SENDING_TX: u32 = 3;
 
 // Minimum network fee rate
 pub const DEFAULT_MIN_NETWORK_FEE_RATE: u64 = 1;
+
+// Blocks a dispatched transaction can be missing from the chain and the mempool before checking if its inputs were double spent
+pub const DEFAULT_CONFLICT_DETECTION_BLOCKS: u32 = 6;
+
+// Blocks read from the node in a single tick to look for the double spends of the missing transactions.
+// Each block is read once per transaction, the blocks left are read on the next ticks.
+pub const DEFAULT_MAX_CONFLICT_SCAN_BLOCKS_PER_TICK: u32 = 20;
+
+// Blocks a dispatched transaction without speedup can be missing from the chain and the mempool before it is sent again
+pub const DEFAULT_REBROADCAST_AFTER_BLOCKS: u32 = 6;
+
+// Maximum number of times a transaction without speedup is sent again while it is missing
+pub const DEFAULT_MAX_REBROADCAST_ATTEMPTS: u32 = 5;
+
+// Depth in blocks used to prune the store automatically on tick. None disables the automatic pruning.
+pub const DEFAULT_AUTO_PRUNE_DEPTH_BLOCKS: Option<u32> = None;
+
+// Whether the node is asked (testmempoolaccept) if it would accept a transaction before saving it to be dispatched
+pub const DEFAULT_TEST_MEMPOOL_ACCEPT: bool = false;
+
+// Whether a speedup anchor below dust or with a script the CPFP can not spend is only reported instead of rejected
+pub const DEFAULT_ALLOW_NONSTANDARD_ANCHOR: bool = false;
+
+// Whether the node is asked (getmempoolentry) for the ancestors of the funding before building a CPFP
+pub const DEFAULT_CHECK_MEMPOOL_ANCESTRY: bool = false;
+
+// Maximum fee in sats of the CPFP paying a batch of transactions. None disables the cap.
+pub const DEFAULT_MAX_CPFP_FEE_SATS_PER_BATCH: Option<u64> = None;
+
+// Transactions broadcast in a single tick, the rest wait for the next ticks in the same order. None disables the limit.
+pub const DEFAULT_MAX_BROADCASTS_PER_TICK: Option<u32> = None;
+
+// New CPFPs sent in a single tick, the batches left wait for the next ticks. None disables the limit.
+pub const DEFAULT_MAX_SPEEDUPS_PER_TICK: Option<u32> = None;
+
+// Remaining funding in sats below which a FundingProvider is asked for more funding. None only asks when there is no funding.
+pub const DEFAULT_AUTO_TOPUP_BELOW_SATS: Option<u64> = None;
+
+// Amount in sats requested to the FundingProvider on each top-up
+pub const DEFAULT_AUTO_TOPUP_AMOUNT_SATS: u64 = 100_000;
+
+// Consecutive failures reaching the node before it is considered unreachable and dispatch and speedups are suspended
+pub const DEFAULT_NODE_FAILURE_THRESHOLD: u32 = 3;
+
+// Monitor ticks without indexing a new block before sync_to_tip gives up
+pub const DEFAULT_MAX_SYNC_STALLED_TICKS: u32 = 10;
+
+// Change in sats below which a CPFP has no change output, the change is added to the fee (P2WPKH dust limit)
+pub const DEFAULT_DUST_THRESHOLD_SATS: u64 = 294;
+
+// Network fee rates (sat/vB) above which the Normal and Low urgency dispatches are paused. None never pauses them.
+pub const DEFAULT_PAUSE_NORMAL_PRIORITY_ABOVE_SAT_VB: Option<u64> = None;
+pub const DEFAULT_PAUSE_LOW_PRIORITY_ABOVE_SAT_VB: Option<u64> = None;
+
+// Blocks a dispatch can be paused by high fees before it is dispatched anyway (about a day)
+pub const DEFAULT_MAX_PAUSE_BLOCKS: u32 = 144;
+
+// Whether the readiness of the monitor is logged only when it changes instead of on every tick
+pub const DEFAULT_QUIET_READY: bool = true;
+
+// Seconds without heartbeat after which the instance owning the store is considered gone and can be taken over
+pub const DEFAULT_OWNER_STALE_AFTER_SECONDS: u64 = 120;
+
+// Ratio between the package fee rate paid by a finalized transaction and the fee rate estimated at its
+// confirmation above which FeeOverpayment is reported
+pub const DEFAULT_FEE_OVERPAYMENT_RATIO: f64 = 2.0;
+
+// Summaries of finalized transactions kept for the confirmation stats, the oldest are dropped first
+pub const MAX_FINALIZED_TX_STATS: usize = 1000;
+
+// Number of journal entries read at once when the event journal is exported
+pub const JOURNAL_EXPORT_PAGE_SIZE: usize = 1000;
+
+// New entries of the finalized log handed to the finalized sink in a tick, the rest wait for the next ticks
+pub const FINALIZED_DELIVERY_PAGE_SIZE: usize = 100;
+
+// News batches a subscriber channel holds, when it is full the news wait for a later tick
+pub const NEWS_SUBSCRIPTION_CAPACITY: usize = 64;
+
+// Whether the coordinator store records are encrypted with a key derived from the key manager
+pub const DEFAULT_ENCRYPT_STORE: bool = false;
+
+// Index of the key manager key signing the message the store encryption key is derived from
+pub const STORE_ENCRYPTION_KEY_INDEX: u32 = 1_000_000;
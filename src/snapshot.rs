@@ -0,0 +1,75 @@
+use crate::errors::BitcoinCoordinatorStoreError;
+use bitcoin::hashes::{sha256, Hash};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::Path,
+};
+
+// Version of the snapshot file format, a snapshot of another version is not imported.
+pub const STORE_SNAPSHOT_VERSION: u32 = 1;
+
+// The records of a coordinator store written to a single file, to move the coordinator to another host.
+// Records are kept as they are in the store, the encrypted ones can only be read with the same encryption key.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct StoreSnapshot {
+    pub version: u32,
+    // Hash of the entries, to detect a truncated or edited file.
+    pub checksum: String,
+    // Records by key, sorted so the same records always give the same checksum.
+    pub entries: BTreeMap<String, Value>,
+}
+
+impl StoreSnapshot {
+    pub fn new(entries: BTreeMap<String, Value>) -> Self {
+        Self {
+            version: STORE_SNAPSHOT_VERSION,
+            checksum: entries_checksum(&entries),
+            entries,
+        }
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), BitcoinCoordinatorStoreError> {
+        let file = File::create(path).map_err(|e| file_error(path, e.to_string()))?;
+
+        serde_json::to_writer(BufWriter::new(file), self)
+            .map_err(|e| file_error(path, e.to_string()))
+    }
+
+    // Reads a snapshot, checking its version and its checksum.
+    pub fn read(path: &Path) -> Result<Self, BitcoinCoordinatorStoreError> {
+        let file = File::open(path).map_err(|e| file_error(path, e.to_string()))?;
+        let snapshot: StoreSnapshot = serde_json::from_reader(BufReader::new(file))
+            .map_err(|e| file_error(path, e.to_string()))?;
+
+        if snapshot.version != STORE_SNAPSHOT_VERSION {
+            return Err(BitcoinCoordinatorStoreError::UnsupportedSnapshotVersion(
+                snapshot.version,
+                STORE_SNAPSHOT_VERSION,
+            ));
+        }
+
+        let checksum = entries_checksum(&snapshot.entries);
+
+        if snapshot.checksum != checksum {
+            return Err(BitcoinCoordinatorStoreError::SnapshotChecksumMismatch(
+                snapshot.checksum,
+                checksum,
+            ));
+        }
+
+        Ok(snapshot)
+    }
+}
+
+fn entries_checksum(entries: &BTreeMap<String, Value>) -> String {
+    let json = serde_json::to_vec(entries).unwrap_or_default();
+    sha256::Hash::hash(&json).to_string()
+}
+
+fn file_error(path: &Path, error: String) -> BitcoinCoordinatorStoreError {
+    BitcoinCoordinatorStoreError::SnapshotFileError(path.display().to_string(), error)
+}
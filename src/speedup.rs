@@ -1,18 +1,44 @@
+use crate::clock::retry_backoff_millis;
 use crate::errors::BitcoinCoordinatorStoreError;
-use crate::settings::{MAX_LIMIT_UNCONFIRMED_PARENTS, MIN_UNCONFIRMED_TXS_FOR_CPFP};
-use crate::storage::BitcoinCoordinatorStore;
-use crate::types::{CoordinatedSpeedUpTransaction, RetryInfo, SpeedupState};
-use bitcoin::Txid;
-use chrono::Utc;
+use crate::settings::{
+    BUMP_STATS_WINDOW, MAX_LIMIT_UNCONFIRMED_PARENTS, MIN_UNCONFIRMED_TXS_FOR_CPFP,
+};
+use crate::storage::{BitcoinCoordinatorStore, BitcoinCoordinatorStoreApi};
+use crate::types::{
+    CoordinatedSpeedUpTransaction, CoordinatedTransaction, FundingSummary, InternalMonitor,
+    PendingSpeedupEntry, RetryInfo, SpeedupIntent, SpeedupOutcome, SpeedupRejection, SpeedupState,
+    SpeedupSummary, TransactionEvent, TransactionState,
+};
+use bitcoin::{OutPoint, PublicKey, Txid};
 use protocol_builder::types::Utxo;
-use storage_backend::storage::KeyValueStore;
+use std::collections::HashSet;
 use tracing::debug;
 
 pub trait SpeedupStore {
     fn add_funding(&self, funding: Utxo) -> Result<(), BitcoinCoordinatorStoreError>;
 
+    // Removes a funding UTXO waiting in the funding pool, or the funding checkpoint saved by add_funding while no
+    // speedup spends it. The speedups of the chain can not be removed (CannotRemoveActiveSpeedup).
+    fn remove_funding(&self, txid: Txid, vout: u32) -> Result<(), BitcoinCoordinatorStoreError>;
+
+    // Returns the funding UTXOs waiting to be used when the active speedup chain can not fund more speedups.
+    fn get_funding_pool(&self) -> Result<Vec<Utxo>, BitcoinCoordinatorStoreError>;
+
+    fn is_pool_funding(&self, funding: &Utxo) -> Result<bool, BitcoinCoordinatorStoreError>;
+
     fn get_funding(&self) -> Result<Option<Utxo>, BitcoinCoordinatorStoreError>;
 
+    // Returns the key the change of a speedup spending `funding` is paid to.
+    // Without a configured key it is the funding key, so the change of a speedup keeps its key along the chain.
+    fn get_change_key(&self, funding: &Utxo) -> Result<PublicKey, BitcoinCoordinatorStoreError>;
+
+    // Pays the change of the speedups spending `funding` to `change_key`.
+    fn set_change_key(
+        &self,
+        funding: &Utxo,
+        change_key: PublicKey,
+    ) -> Result<(), BitcoinCoordinatorStoreError>;
+
     fn get_pending_speedups(
         &self,
     ) -> Result<Vec<CoordinatedSpeedUpTransaction>, BitcoinCoordinatorStoreError>;
@@ -25,6 +51,17 @@ pub trait SpeedupStore {
         &self,
     ) -> Result<Vec<CoordinatedSpeedUpTransaction>, BitcoinCoordinatorStoreError>;
 
+    // Returns the unconfirmed speedups from the oldest to the newest, with the fee each one paid.
+    fn get_unconfirmed_speedup_entries(
+        &self,
+    ) -> Result<Vec<PendingSpeedupEntry>, BitcoinCoordinatorStoreError>;
+
+    // Returns the saved speedups (CPFP and RBF) paying for the given transaction, from the oldest to the newest.
+    fn get_speedups_for_tx(
+        &self,
+        txid: &Txid,
+    ) -> Result<Vec<SpeedupSummary>, BitcoinCoordinatorStoreError>;
+
     fn save_speedup(
         &self,
         speedup: CoordinatedSpeedUpTransaction,
@@ -37,10 +74,106 @@ pub trait SpeedupStore {
 
     fn can_speedup(&self) -> Result<bool, BitcoinCoordinatorStoreError>;
 
+    /// Returns the dispatched transactions with speedup data that are not paid by any saved or retrying speedup.
+    /// This happens when the process stops after broadcasting a batch and before its CPFP is created.
+    fn get_dispatched_txs_without_speedup(
+        &self,
+    ) -> Result<Vec<CoordinatedTransaction>, BitcoinCoordinatorStoreError>;
+
+    // Saves transactions broadcast without a CPFP because the funding could not pay for it.
+    // Their speedup data and context are kept in the transaction, so only the ids are saved.
+    fn defer_speedup(&self, txids: &[Txid]) -> Result<(), BitcoinCoordinatorStoreError>;
+
+    // Returns the transactions waiting for a CPFP because the funding could not pay for it.
+    fn get_deferred_speedup_txs(&self) -> Result<Vec<Txid>, BitcoinCoordinatorStoreError>;
+
+    fn remove_deferred_speedup_txs(
+        &self,
+        txids: &[Txid],
+    ) -> Result<(), BitcoinCoordinatorStoreError>;
+
+    // Saves a speedup about to be broadcast, before anything else is written about it.
+    fn save_speedup_intent(
+        &self,
+        intent: SpeedupIntent,
+    ) -> Result<(), BitcoinCoordinatorStoreError>;
+
+    fn remove_speedup_intent(&self, txid: &Txid) -> Result<(), BitcoinCoordinatorStoreError>;
+
+    // Returns the speedups that were about to be broadcast and were neither saved nor discarded.
+    fn get_speedup_intents(&self) -> Result<Vec<SpeedupIntent>, BitcoinCoordinatorStoreError>;
+
+    // Saves a speedup rejected by the SpeedupReviewHook, with the reason it was rejected.
+    fn save_speedup_rejection(
+        &self,
+        rejection: SpeedupRejection,
+    ) -> Result<(), BitcoinCoordinatorStoreError>;
+
+    // Returns the speedups rejected by the SpeedupReviewHook, from the oldest to the newest.
+    fn get_speedup_rejections(&self)
+        -> Result<Vec<SpeedupRejection>, BitcoinCoordinatorStoreError>;
+
     fn is_funding_available(&self) -> Result<bool, BitcoinCoordinatorStoreError>;
 
+    // Marks a funding spent outside the coordinator. It is removed from the funding pool and never selected again,
+    // so the next speedups are funded from the pool.
+    fn invalidate_funding(&self, funding: &Utxo) -> Result<(), BitcoinCoordinatorStoreError>;
+
+    fn is_funding_invalidated(&self, funding: &Utxo) -> Result<bool, BitcoinCoordinatorStoreError>;
+
+    // Returns the funding requested to the FundingProvider that is waiting for its transaction to be confirmed.
+    fn get_pending_funding_topup(&self) -> Result<Option<Utxo>, BitcoinCoordinatorStoreError>;
+
+    // Saves the requested funding until it is confirmed, None once it is registered with add_funding.
+    fn set_pending_funding_topup(
+        &self,
+        topup: Option<Utxo>,
+    ) -> Result<(), BitcoinCoordinatorStoreError>;
+
     fn has_enough_unconfirmed_txs_for_cpfp(&self) -> Result<bool, BitcoinCoordinatorStoreError>;
 
+    // Records a transaction the coordinator monitors for itself, its news are not returned to the consumer.
+    fn register_internal_monitor(
+        &self,
+        txid: Txid,
+        monitor: InternalMonitor,
+    ) -> Result<(), BitcoinCoordinatorStoreError>;
+
+    fn get_internal_monitor(
+        &self,
+        txid: &Txid,
+    ) -> Result<Option<InternalMonitor>, BitcoinCoordinatorStoreError>;
+
+    fn remove_internal_monitor(&self, txid: &Txid) -> Result<(), BitcoinCoordinatorStoreError>;
+
+    // Keeps the outcome of a confirmed speedup, dropping the oldest ones once there are BUMP_STATS_WINDOW.
+    fn record_speedup_outcome(
+        &self,
+        outcome: SpeedupOutcome,
+    ) -> Result<(), BitcoinCoordinatorStoreError>;
+
+    // Returns the outcomes of the last confirmed speedups, from the oldest to the newest.
+    fn get_speedup_outcomes(&self) -> Result<Vec<SpeedupOutcome>, BitcoinCoordinatorStoreError>;
+
+    // Returns the fee missing in the unconfirmed speedup chain to reach the given network fee rate, and the chain vsize.
+    fn get_unconfirmed_chain_fee_shortfall(
+        &self,
+        network_fee_rate: u64,
+    ) -> Result<(u64, usize), BitcoinCoordinatorStoreError>;
+
+    // Returns the remaining funding, the fees spent from the active funding and an estimate of how many more speedups
+    // can be afforded at the given network fee rate.
+    fn get_funding_summary(
+        &self,
+        network_fee_rate: u64,
+    ) -> Result<FundingSummary, BitcoinCoordinatorStoreError>;
+
+    // Returns the replacements (RBF) sent for the given CPFP that are still pending, the last one sent first.
+    fn get_speedup_replacements(
+        &self,
+        cpfp: &CoordinatedSpeedUpTransaction,
+    ) -> Result<Vec<CoordinatedSpeedUpTransaction>, BitcoinCoordinatorStoreError>;
+
     // This function will return the last speedup (CPFP) transaction to be bumped with RBF + the last replacement speedup.
     fn get_last_speedup(
         &self,
@@ -61,8 +194,31 @@ pub trait SpeedupStore {
 
     fn has_reached_max_unconfirmed_speedups(&self) -> Result<bool, BitcoinCoordinatorStoreError>;
 
+    // Marks an orphaned speedup and the speedups funded from its change as Orphaned.
+    // Returns the txids of the speedups marked as Orphaned.
+    fn orphan_speedup(&self, txid: Txid) -> Result<Vec<Txid>, BitcoinCoordinatorStoreError>;
+
+    // Marks an orphaned speedup mined again as Confirmed, and the speedups funded from its change as Dispatched.
+    fn restore_orphaned_speedup(&self, txid: Txid) -> Result<(), BitcoinCoordinatorStoreError>;
+
+    // Marks the speedups replaced by a confirmed RBF, and the speedups funded from their change, as Invalidated.
+    // Returns the txids of the speedups marked as Invalidated, from the oldest to the newest.
+    fn invalidate_replaced_speedups(
+        &self,
+        rbf_txid: Txid,
+    ) -> Result<Vec<Txid>, BitcoinCoordinatorStoreError>;
+
+    // Marks the unconfirmed speedups paying for a transaction dropped from the mempool, and the speedups funded from
+    // their change, as Invalidated. Returns the txids of the speedups marked as Invalidated, from the oldest to the newest.
+    fn invalidate_speedups_paying(
+        &self,
+        txid: Txid,
+    ) -> Result<Vec<Txid>, BitcoinCoordinatorStoreError>;
+
     fn get_available_unconfirmed_txs(&self) -> Result<u32, BitcoinCoordinatorStoreError>;
 
+    // Speedups of the retry queue with less than max_retries retries, whose backoff has passed.
+    // The backoff is interval_seconds doubled with each retry, up to MAX_RETRY_BACKOFF_SECONDS.
     fn get_speedups_for_retry(
         &self,
         max_retries: u32,
@@ -78,6 +234,21 @@ pub trait SpeedupStore {
 
     fn increment_speedup_retry_count(&self, txid: Txid)
         -> Result<(), BitcoinCoordinatorStoreError>;
+
+    // Removes the finalized speedups older than the last finalized one, which is the current funding checkpoint.
+    // Returns how many speedups were removed.
+    fn prune_finalized_speedups(&self) -> Result<u32, BitcoinCoordinatorStoreError>;
+
+    // Checks that the pending speedup list and the speedup records are consistent, for debugging.
+    // Returns the problems found, an empty list when the speedup chain is consistent.
+    fn verify_speedup_chain(&self) -> Result<Vec<String>, BitcoinCoordinatorStoreError>;
+
+    // Registers a funding group. Its speedups are chained from its own funding, apart from the default chain
+    // and the other groups. Registering a group again does nothing.
+    fn add_funding_group(&self, group: &str) -> Result<(), BitcoinCoordinatorStoreError>;
+
+    // Returns the registered funding groups, in the order they were added.
+    fn get_funding_groups(&self) -> Result<Vec<String>, BitcoinCoordinatorStoreError>;
 }
 
 enum SpeedupStoreKey {
@@ -86,6 +257,18 @@ enum SpeedupStoreKey {
     SpeedUpTransaction(Txid),
 
     RetrySpeedUpTransactionList,
+
+    FundingSpentFees,
+    FundingPool,
+    FundingChangeKey(Txid, u32),
+    DeferredSpeedupTxList,
+    SpeedupIntentList,
+    SpeedupRejectionList,
+    PendingFundingTopUp,
+    InvalidatedFundingList,
+    FundingGroupList,
+    InternalMonitor(Txid),
+    BumpStats,
 }
 
 impl SpeedupStoreKey {
@@ -99,139 +282,198 @@ impl SpeedupStoreKey {
             SpeedupStoreKey::RetrySpeedUpTransactionList => {
                 format!("{prefix}/speedup/retry/list")
             }
+            SpeedupStoreKey::FundingSpentFees => format!("{prefix}/speedup/funding/spent"),
+            SpeedupStoreKey::FundingPool => format!("{prefix}/speedup/funding/pool"),
+            SpeedupStoreKey::FundingChangeKey(txid, vout) => {
+                format!("{prefix}/speedup/funding/change_key/{txid}/{vout}")
+            }
+            SpeedupStoreKey::DeferredSpeedupTxList => format!("{prefix}/speedup/deferred/list"),
+            SpeedupStoreKey::SpeedupIntentList => format!("{prefix}/speedup/intent/list"),
+            SpeedupStoreKey::SpeedupRejectionList => format!("{prefix}/speedup/rejected/list"),
+            SpeedupStoreKey::PendingFundingTopUp => format!("{prefix}/speedup/funding/topup"),
+            SpeedupStoreKey::InvalidatedFundingList => {
+                format!("{prefix}/speedup/funding/invalidated")
+            }
+            SpeedupStoreKey::FundingGroupList => format!("{prefix}/speedup/group/list"),
+            SpeedupStoreKey::InternalMonitor(txid) => {
+                format!("{prefix}/speedup/internal_monitor/{txid}")
+            }
+            SpeedupStoreKey::BumpStats => format!("{prefix}/speedup/bump_stats"),
+        }
+    }
+
+    // Key of the speedup chain of a funding group. The default chain keeps the keys without group.
+    fn get_group_key(&self, group: Option<&str>) -> String {
+        match group {
+            Some(group) => {
+                self.get_key()
+                    .replacen("/speedup/", &format!("/speedup/group/{group}/"), 1)
+            }
+            None => self.get_key(),
         }
     }
 }
 
 impl SpeedupStore for BitcoinCoordinatorStore {
     fn add_funding(&self, next_funding: Utxo) -> Result<(), BitcoinCoordinatorStoreError> {
-        // When saving a new funding UTXO, we ignore any previous funding.
-        // From this point onward, next speedup transaction will use the new funding.
-        // Since this is a new funding, there is no previous funding UTXO; we use the same UTXO for both previous and next funding fields to avoid introducing an Option type.
-        // The broadcast block height is set to 0 and Finalized because funding should be confirmed on chain.
-        let funding_to_speedup = CoordinatedSpeedUpTransaction::new(
-            next_funding.txid,
-            next_funding.clone(),
-            next_funding,
-            false,
-            0,
-            SpeedupState::Finalized,
-            1.0,
-            vec![],
-            1,
-        );
+        // Funding UTXOs are kept in a pool. The new funding becomes the active one right away when the active funding
+        // is not paying for unconfirmed speedups, the previous one is kept in the pool.
+        // Otherwise, it waits in the pool until the active speedup chain can not fund more speedups.
+        let mut pool = self.get_funding_pool()?;
+        let active_funding = self.get_active_funding()?;
+
+        let already_exists = pool
+            .iter()
+            .chain(active_funding.iter())
+            .any(|utxo| utxo.txid == next_funding.txid && utxo.vout == next_funding.vout);
+
+        // A transaction of the speedup chain, or a funding it already spent, would be used twice.
+        let in_speedup_chain = self.get_all_pending_speedups()?.iter().any(|speedup| {
+            speedup.tx_id == next_funding.txid || speedup.prev_funding.txid == next_funding.txid
+        });
+
+        if already_exists || in_speedup_chain {
+            return Err(BitcoinCoordinatorStoreError::FundingTransactionAlreadyExists);
+        }
+
+        if !self.get_unconfirmed_speedups()?.is_empty() {
+            pool.push(next_funding);
+            self.save_funding_pool(pool)?;
+            return Ok(());
+        }
 
-        self.save_speedup(funding_to_speedup)?;
+        if let Some(active_funding) = active_funding {
+            if !self.is_funding_invalidated(&active_funding)? {
+                pool.push(active_funding);
+                self.save_funding_pool(pool)?;
+            }
+        }
+
+        self.save_funding_checkpoint(next_funding)?;
 
         Ok(())
     }
 
-    fn get_available_unconfirmed_txs(&self) -> Result<u32, BitcoinCoordinatorStoreError> {
-        let speedups = self.get_all_pending_speedups()?;
+    fn remove_funding(&self, txid: Txid, vout: u32) -> Result<(), BitcoinCoordinatorStoreError> {
+        let mut pool = self.get_funding_pool()?;
 
-        let mut available_utxos = MAX_LIMIT_UNCONFIRMED_PARENTS;
+        if let Some(index) = pool
+            .iter()
+            .position(|utxo| utxo.txid == txid && utxo.vout == vout)
+        {
+            pool.remove(index);
+            self.save_funding_pool(pool)?;
+            return Ok(());
+        }
 
-        let mut is_rbf_active = false;
+        // Otherwise only a funding checkpoint can be removed, e.g. a funding registered with a wrong vout or amount.
+        let speedups = self.get_all_pending_speedups()?;
 
-        for speedup in speedups.iter() {
-            // In case there is a RBF at the top, we necessary need to find a confirmed RBF
-            // to be able to fund otherwise there is no capacity for funding unconfirmed txs.
-            if is_rbf_active && !speedup.is_rbf {
-                return Ok(0);
+        let checkpoint = match speedups.iter().find(|speedup| speedup.tx_id == txid) {
+            Some(speedup) if !speedup.is_funding() => {
+                return Err(BitcoinCoordinatorStoreError::CannotRemoveActiveSpeedup(
+                    txid,
+                ))
             }
+            Some(speedup) if speedup.next_funding.vout == vout => speedup,
+            _ => return Err(BitcoinCoordinatorStoreError::FundingNotFound),
+        };
+
+        // A speedup funded from the checkpoint needs it to stay in the chain.
+        if let Some(speedup) = speedups.iter().find(|speedup| {
+            speedup.tx_id != checkpoint.tx_id
+                && speedup.prev_funding.txid == txid
+                && speedup.prev_funding.vout == vout
+        }) {
+            return Err(BitcoinCoordinatorStoreError::CannotRemoveActiveSpeedup(
+                speedup.tx_id,
+            ));
+        }
 
-            if speedup.state == SpeedupState::Confirmed || speedup.state == SpeedupState::Finalized
-            {
-                return Ok(available_utxos);
+        let key = self.group_key(SpeedupStoreKey::PendingSpeedUpList);
+        let mut speedup_ids = self.get_value::<&str, Vec<Txid>>(&key)?.unwrap_or_default();
+        speedup_ids.retain(|speedup_id| *speedup_id != txid);
+
+        // The funding active before the checkpoint was added to the pool by add_funding, it is active again.
+        // It is read before the batch, the reads inside it do not see the checkpoint removed.
+        let active_funding = match speedup_ids.last() {
+            Some(id) => self.get_speedup(id)?.change_funding(),
+            None => None,
+        };
+
+        self.atomically(|transaction_id| {
+            self.set_value(&key, &speedup_ids, Some(transaction_id))?;
+
+            self.store.remove(
+                SpeedupStoreKey::SpeedUpTransaction(txid).get_key(),
+                Some(transaction_id),
+            )?;
+
+            if let Some(active_funding) = active_funding {
+                pool.retain(|utxo| {
+                    utxo.txid != active_funding.txid || utxo.vout != active_funding.vout
+                });
+                self.set_value(
+                    self.group_key(SpeedupStoreKey::FundingPool),
+                    &pool,
+                    Some(transaction_id),
+                )?;
             }
 
-            if speedup.is_rbf && speedup.state == SpeedupState::Dispatched {
-                is_rbf_active = true;
-                continue;
-            }
+            Ok(())
+        })
+    }
 
-            if is_rbf_active && speedup.is_rbf {
-                return Ok(0);
-            }
+    fn get_funding_pool(&self) -> Result<Vec<Utxo>, BitcoinCoordinatorStoreError> {
+        let key = self.group_key(SpeedupStoreKey::FundingPool);
+        let pool = self.get_value::<&str, Vec<Utxo>>(&key)?.unwrap_or_default();
+        Ok(pool)
+    }
 
-            let cpfp_tx = 1;
-            let to_subtract = speedup.speedup_tx_data.len() as u32 + cpfp_tx;
-            available_utxos = available_utxos.saturating_sub(to_subtract);
-        }
+    fn is_pool_funding(&self, funding: &Utxo) -> Result<bool, BitcoinCoordinatorStoreError> {
+        let pool = self.get_funding_pool()?;
 
-        Ok(available_utxos)
+        Ok(pool
+            .iter()
+            .any(|utxo| utxo.txid == funding.txid && utxo.vout == funding.vout))
     }
 
-    fn get_funding(&self) -> Result<Option<Utxo>, BitcoinCoordinatorStoreError> {
-        // Attempt to determine the current funding UTXO by walking the speedup transaction history in reverse.
-        // The funding UTXO is derived from the most recent speedup transaction that is either:
-        //   - Finalized (serves as a checkpoint, i.e., a new funding insertion), or
-        //   - Confirmed (regardless of whether it's a replace speedup), or
-        //   - Not a replace speedup (i.e., a regular speedup, even if unconfirmed).
-        //
-        // If the latest speedup is an unconfirmed replace speedup, we must look further back for a confirmed replace speedup.
-        // This prevents chaining unconfirmed replace speedups, ensuring only a confirmed replace speedup can serve as funding.
-        //
-        // If no suitable funding is found, return None.
-
-        // If we have reached the max number of unconfirmed speedups, we are waiting for confirmations, then there is no funding available.
-        if self.has_reached_max_unconfirmed_speedups()? {
-            return Ok(None);
+    fn get_available_unconfirmed_txs(&self) -> Result<u32, BitcoinCoordinatorStoreError> {
+        // A funding from the pool starts a new chain, so all the unconfirmed slots are available.
+        if let Some((_, true)) = self.select_funding()? {
+            return Ok(MAX_LIMIT_UNCONFIRMED_PARENTS);
         }
 
-        let speedups = self.get_all_pending_speedups()?;
-
-        let mut should_be_a_replace = false;
-
-        for speedup in speedups.iter() {
-            if !should_be_a_replace {
-                if speedup.state == SpeedupState::Finalized
-                    || speedup.state == SpeedupState::Confirmed
-                {
-                    return Ok(Some(speedup.next_funding.clone()));
-                }
-
-                if !speedup.is_rbf {
-                    // Encountered an unconfirmed regular speedup. We can use this as funding.
-                    return Ok(Some(speedup.next_funding.clone()));
-                }
-
-                // Encountered an unconfirmed replace speedup; must look for a previous confirmed replace.
-                should_be_a_replace = true;
-
-                continue;
-            }
-
-            // We are searching for a previous confirmed replace speedup.
-            if speedup.is_rbf {
-                if speedup.state == SpeedupState::Confirmed {
-                    // Found a confirmed replace speedup; use as funding.
-                    return Ok(Some(speedup.next_funding.clone()));
-                }
+        self.get_chain_available_unconfirmed_txs()
+    }
 
-                continue;
-            }
+    fn get_funding(&self) -> Result<Option<Utxo>, BitcoinCoordinatorStoreError> {
+        let funding = self.select_funding()?.map(|(funding, _)| funding);
+        Ok(funding)
+    }
 
-            if speedup.state == SpeedupState::Confirmed {
-                // Found a confirmed regular speedup; use as funding.
-                return Ok(Some(speedup.next_funding.clone()));
-            } else {
-                // Found an unconfirmed regular speedup; cannot use as funding.
-                // This current speedup is responsible for getting into a chain of replacements.
-                return Ok(None);
-            }
-        }
+    fn get_change_key(&self, funding: &Utxo) -> Result<PublicKey, BitcoinCoordinatorStoreError> {
+        let key = SpeedupStoreKey::FundingChangeKey(funding.txid, funding.vout).get_key();
+        let change_key = self.get_value::<&str, PublicKey>(&key)?;
+        Ok(change_key.unwrap_or(funding.pub_key))
+    }
 
-        // No suitable funding found in the speedup history.
-        Ok(None)
+    fn set_change_key(
+        &self,
+        funding: &Utxo,
+        change_key: PublicKey,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        let key = SpeedupStoreKey::FundingChangeKey(funding.txid, funding.vout).get_key();
+        self.set_value(&key, change_key, None)?;
+        Ok(())
     }
 
     // Returns the list of pending speedups in reverse order until the last finalized speedup.
     fn get_pending_speedups(
         &self,
     ) -> Result<Vec<CoordinatedSpeedUpTransaction>, BitcoinCoordinatorStoreError> {
-        let key = SpeedupStoreKey::PendingSpeedUpList.get_key();
-        let speedups = self.store.get::<&str, Vec<Txid>>(&key)?.unwrap_or_default();
+        let key = self.group_key(SpeedupStoreKey::PendingSpeedUpList);
+        let speedups = self.get_value::<&str, Vec<Txid>>(&key)?.unwrap_or_default();
 
         let mut pending_speedups = Vec::new();
 
@@ -255,8 +497,8 @@ impl SpeedupStore for BitcoinCoordinatorStore {
     fn get_unconfirmed_speedups(
         &self,
     ) -> Result<Vec<CoordinatedSpeedUpTransaction>, BitcoinCoordinatorStoreError> {
-        let key = SpeedupStoreKey::PendingSpeedUpList.get_key();
-        let speedups = self.store.get::<&str, Vec<Txid>>(&key)?.unwrap_or_default();
+        let key = self.group_key(SpeedupStoreKey::PendingSpeedUpList);
+        let speedups = self.get_value::<&str, Vec<Txid>>(&key)?.unwrap_or_default();
 
         let mut pending_speedups = Vec::new();
 
@@ -269,6 +511,10 @@ impl SpeedupStore for BitcoinCoordinatorStore {
                 return Ok(pending_speedups);
             }
 
+            if speedup.state == SpeedupState::Invalidated {
+                continue;
+            }
+
             // If the speedup is not finalized or confirmed, it means that it is still unconfirmed.
             pending_speedups.push(speedup);
         }
@@ -279,8 +525,8 @@ impl SpeedupStore for BitcoinCoordinatorStore {
     fn get_all_pending_speedups(
         &self,
     ) -> Result<Vec<CoordinatedSpeedUpTransaction>, BitcoinCoordinatorStoreError> {
-        let key = SpeedupStoreKey::PendingSpeedUpList.get_key();
-        let speedup_ids = self.store.get::<&str, Vec<Txid>>(&key)?.unwrap_or_default();
+        let key = self.group_key(SpeedupStoreKey::PendingSpeedUpList);
+        let speedup_ids = self.get_value::<&str, Vec<Txid>>(&key)?.unwrap_or_default();
 
         let mut pending_speedups = Vec::new();
 
@@ -294,6 +540,67 @@ impl SpeedupStore for BitcoinCoordinatorStore {
         Ok(pending_speedups)
     }
 
+    fn get_speedups_for_tx(
+        &self,
+        txid: &Txid,
+    ) -> Result<Vec<SpeedupSummary>, BitcoinCoordinatorStoreError> {
+        // All speedups come from the newest to the oldest.
+        let summaries = self
+            .get_all_pending_speedups()?
+            .into_iter()
+            .rev()
+            .filter(|speedup| {
+                speedup
+                    .speedup_tx_data
+                    .iter()
+                    .any(|parent| parent.tx_id == *txid)
+            })
+            .map(|speedup| SpeedupSummary {
+                tx_id: speedup.tx_id,
+                fee: speedup
+                    .prev_funding
+                    .amount
+                    .saturating_sub(speedup.next_funding.amount),
+                paid_txids: speedup.paid_txids(),
+                state: speedup.state,
+                is_rbf: speedup.is_rbf,
+                broadcast_block_height: speedup.broadcast_block_height,
+                network_fee_rate_used: speedup.network_fee_rate_used,
+                vsize: speedup.vsize as u64,
+                bump_fee_percentage_used: speedup.bump_fee_percentage_used,
+                bumped_from: speedup.bumped_from,
+            })
+            .collect();
+
+        Ok(summaries)
+    }
+
+    fn get_unconfirmed_speedup_entries(
+        &self,
+    ) -> Result<Vec<PendingSpeedupEntry>, BitcoinCoordinatorStoreError> {
+        // Unconfirmed speedups come from the newest to the oldest.
+        let entries = self
+            .get_unconfirmed_speedups()?
+            .into_iter()
+            .rev()
+            .map(|speedup| PendingSpeedupEntry {
+                tx_id: speedup.tx_id,
+                fee: speedup
+                    .prev_funding
+                    .amount
+                    .saturating_sub(speedup.next_funding.amount),
+                paid_txids: speedup.paid_txids(),
+                context: speedup.context,
+                state: speedup.state,
+                is_rbf: speedup.is_rbf,
+                broadcast_block_height: speedup.broadcast_block_height,
+                network_fee_rate_used: speedup.network_fee_rate_used,
+            })
+            .collect();
+
+        Ok(entries)
+    }
+
     /// Determines if a speedup (CPFP) transaction can be created and dispatched.
     ///
     /// Returns `true` if:
@@ -307,119 +614,681 @@ impl SpeedupStore for BitcoinCoordinatorStore {
         Ok(is_funding_available && is_enough_unconfirmed_txs)
     }
 
-    fn is_funding_available(&self) -> Result<bool, BitcoinCoordinatorStoreError> {
-        let funding = self.get_funding()?;
-        let is_funding_available = funding.is_some();
-        Ok(is_funding_available)
-    }
+    fn get_dispatched_txs_without_speedup(
+        &self,
+    ) -> Result<Vec<CoordinatedTransaction>, BitcoinCoordinatorStoreError> {
+        let key = self.group_key(SpeedupStoreKey::RetrySpeedUpTransactionList);
+        let retry_speedups = self
+            .get_value::<&str, Vec<CoordinatedSpeedUpTransaction>>(&key)?
+            .unwrap_or_default();
 
-    fn has_enough_unconfirmed_txs_for_cpfp(&self) -> Result<bool, BitcoinCoordinatorStoreError> {
-        let available_unconfirmed_txs = self.get_available_unconfirmed_txs()?;
-        let is_enough_unconfirmed_txs = available_unconfirmed_txs >= MIN_UNCONFIRMED_TXS_FOR_CPFP;
-        Ok(is_enough_unconfirmed_txs)
+        // Only the transactions of the funding group are paid by its speedup chain.
+        let funding_group = self.funding_group();
+
+        // Transactions paid by a speedup in the chain or by one waiting to be resent.
+        let sped_up_txids: HashSet<Txid> = self
+            .get_all_pending_speedups()?
+            .iter()
+            .chain(retry_speedups.iter())
+            .filter(|speedup| speedup.state != SpeedupState::Invalidated)
+            .flat_map(|speedup| speedup.speedup_tx_data.iter())
+            .map(|parent| parent.tx_id)
+            .collect();
+
+        let txs = self
+            .get_txs_in_progress()?
+            .into_iter()
+            .filter(|tx| {
+                tx.state == TransactionState::Dispatched
+                    && tx.dispatch_options.funding_group == funding_group
+                    && tx.speedup_data.is_some()
+                    && !tx.dispatch_options.allow_rbf_of_parent
+                    && !sped_up_txids.contains(&tx.tx_id)
+            })
+            .collect();
+
+        Ok(txs)
     }
 
-    fn save_speedup(
-        &self,
-        speedup: CoordinatedSpeedUpTransaction,
-    ) -> Result<(), BitcoinCoordinatorStoreError> {
-        // Whenever a speedup is created, we add it to the list of pending speedups because is not finished.
-        // Also speedup should be saved at the end of the list. Because is gonna be the new way to fund next speedups.
-
-        let key = SpeedupStoreKey::PendingSpeedUpList.get_key();
-        let mut speedups = self.store.get::<&str, Vec<Txid>>(&key)?.unwrap_or_default();
-        speedups.push(speedup.tx_id);
+    fn defer_speedup(&self, txids: &[Txid]) -> Result<(), BitcoinCoordinatorStoreError> {
+        let mut deferred = self.get_deferred_speedup_txs()?;
 
-        self.store.set(&key, speedups, None)?;
+        for txid in txids {
+            if !deferred.contains(txid) {
+                deferred.push(*txid);
+            }
+        }
 
-        // Save speedup to get by id.
-        let key = SpeedupStoreKey::SpeedUpTransaction(speedup.tx_id).get_key();
-        self.store.set(&key, speedup, None)?;
+        let key = self.group_key(SpeedupStoreKey::DeferredSpeedupTxList);
+        self.set_value(&key, deferred, None)?;
 
         Ok(())
     }
 
-    fn get_speedup(
-        &self,
-        txid: &Txid,
-    ) -> Result<CoordinatedSpeedUpTransaction, BitcoinCoordinatorStoreError> {
-        let key = SpeedupStoreKey::SpeedUpTransaction(*txid).get_key();
-        let speedup = self
-            .store
-            .get::<&str, CoordinatedSpeedUpTransaction>(&key)?
-            .ok_or(BitcoinCoordinatorStoreError::SpeedupNotFound)?;
-
-        Ok(speedup)
+    fn get_deferred_speedup_txs(&self) -> Result<Vec<Txid>, BitcoinCoordinatorStoreError> {
+        let key = self.group_key(SpeedupStoreKey::DeferredSpeedupTxList);
+        let deferred = self.get_value::<&str, Vec<Txid>>(&key)?.unwrap_or_default();
+        Ok(deferred)
     }
 
-    fn has_reached_max_unconfirmed_speedups(&self) -> Result<bool, BitcoinCoordinatorStoreError> {
-        let speedups = self.get_pending_speedups()?;
+    fn remove_deferred_speedup_txs(
+        &self,
+        txids: &[Txid],
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        let mut deferred = self.get_deferred_speedup_txs()?;
+        let len = deferred.len();
 
-        // sum up all consecutive unconfirmed speedups, and if sum is greater than MAX_UNCONFIRMED_SPEEDUPS, return true.
-        let mut sum = 0;
+        deferred.retain(|txid| !txids.contains(txid));
 
-        for speedup in speedups.iter() {
-            if speedup.state == SpeedupState::Dispatched {
-                sum += 1;
-            } else {
-                break;
-            }
+        if deferred.len() != len {
+            let key = self.group_key(SpeedupStoreKey::DeferredSpeedupTxList);
+            self.set_value(&key, deferred, None)?;
         }
 
-        Ok(sum >= self.max_unconfirmed_speedups)
+        Ok(())
     }
 
-    fn update_speedup_state(
+    fn save_speedup_intent(
         &self,
-        txid: Txid,
-        state: SpeedupState,
+        intent: SpeedupIntent,
     ) -> Result<(), BitcoinCoordinatorStoreError> {
-        if state == SpeedupState::Finalized {
-            // Means that the speedup transaction was finalized.
-            // Then we need to remove it from the pending list.
-            let key = SpeedupStoreKey::PendingSpeedUpList.get_key();
-            let mut speedups = self
-                .store
-                .get::<&str, Vec<Txid>>(&key)?
-                .ok_or(BitcoinCoordinatorStoreError::SpeedupNotFound)?;
+        let mut intents = self.get_speedup_intents()?;
+        intents.retain(|saved| saved.speedup.tx_id != intent.speedup.tx_id);
+        intents.push(intent);
 
-            let index = speedups
-                .iter()
-                .position(|id| *id == txid)
-                .ok_or(BitcoinCoordinatorStoreError::SpeedupNotFound)?;
+        let key = self.group_key(SpeedupStoreKey::SpeedupIntentList);
+        self.set_value(&key, intents, None)?;
 
-            // Iterate over all previous speedup transactions (before the current index)
-            // to find any that have reached the Finalized state and remove them from the pending list.
-            // This cleanup prevents the pending speedup list from growing indefinitely with finalized entries.
-            for (i, txid) in speedups[0..index].iter().enumerate() {
-                if self.get_speedup(txid)?.state == SpeedupState::Finalized {
-                    // If a finalized transaction is found, remove it from the list and update the store.
-                    speedups.remove(i);
-                    self.store.set(&key, &speedups, None)?;
-                    break;
-                }
-            }
+        Ok(())
+    }
+
+    fn remove_speedup_intent(&self, txid: &Txid) -> Result<(), BitcoinCoordinatorStoreError> {
+        let mut intents = self.get_speedup_intents()?;
+        let len = intents.len();
+
+        intents.retain(|intent| intent.speedup.tx_id != *txid);
+
+        if intents.len() != len {
+            let key = self.group_key(SpeedupStoreKey::SpeedupIntentList);
+            self.set_value(&key, intents, None)?;
         }
 
-        // Update the new state of the transaction in transaction by id.
-        let key = SpeedupStoreKey::SpeedUpTransaction(txid).get_key();
+        Ok(())
+    }
 
-        let mut speedup = self
-            .store
-            .get::<&str, CoordinatedSpeedUpTransaction>(&key)?
-            .ok_or(BitcoinCoordinatorStoreError::SpeedupNotFound)?;
+    fn get_speedup_intents(&self) -> Result<Vec<SpeedupIntent>, BitcoinCoordinatorStoreError> {
+        let key = self.group_key(SpeedupStoreKey::SpeedupIntentList);
+        let intents = self
+            .get_value::<&str, Vec<SpeedupIntent>>(&key)?
+            .unwrap_or_default();
+        Ok(intents)
+    }
 
-        speedup.state = state;
+    fn save_speedup_rejection(
+        &self,
+        rejection: SpeedupRejection,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        let mut rejections = self.get_speedup_rejections()?;
+        rejections.push(rejection);
 
-        self.store.set(&key, &speedup, None)?;
+        let key = self.group_key(SpeedupStoreKey::SpeedupRejectionList);
+        self.set_value(&key, rejections, None)?;
 
         Ok(())
     }
 
-    fn get_last_speedup(
+    fn get_speedup_rejections(
         &self,
-    ) -> Result<
-        Option<(
+    ) -> Result<Vec<SpeedupRejection>, BitcoinCoordinatorStoreError> {
+        let key = self.group_key(SpeedupStoreKey::SpeedupRejectionList);
+        let rejections = self
+            .get_value::<&str, Vec<SpeedupRejection>>(&key)?
+            .unwrap_or_default();
+        Ok(rejections)
+    }
+
+    fn is_funding_available(&self) -> Result<bool, BitcoinCoordinatorStoreError> {
+        let funding = self.get_funding()?;
+        let is_funding_available = funding.is_some();
+        Ok(is_funding_available)
+    }
+
+    fn invalidate_funding(&self, funding: &Utxo) -> Result<(), BitcoinCoordinatorStoreError> {
+        let key = self.group_key(SpeedupStoreKey::InvalidatedFundingList);
+        let mut invalidated = self
+            .get_value::<&str, Vec<OutPoint>>(&key)?
+            .unwrap_or_default();
+        let outpoint = OutPoint::new(funding.txid, funding.vout);
+
+        if !invalidated.contains(&outpoint) {
+            invalidated.push(outpoint);
+            self.set_value(&key, invalidated, None)?;
+        }
+
+        let mut pool = self.get_funding_pool()?;
+        pool.retain(|utxo| !(utxo.txid == funding.txid && utxo.vout == funding.vout));
+        self.save_funding_pool(pool)?;
+
+        debug!(
+            "Invalidated funding | FundingTx({}) | Vout({})",
+            funding.txid, funding.vout
+        );
+
+        Ok(())
+    }
+
+    fn is_funding_invalidated(&self, funding: &Utxo) -> Result<bool, BitcoinCoordinatorStoreError> {
+        let key = self.group_key(SpeedupStoreKey::InvalidatedFundingList);
+        let invalidated = self
+            .get_value::<&str, Vec<OutPoint>>(&key)?
+            .unwrap_or_default();
+
+        Ok(invalidated.contains(&OutPoint::new(funding.txid, funding.vout)))
+    }
+
+    fn get_pending_funding_topup(&self) -> Result<Option<Utxo>, BitcoinCoordinatorStoreError> {
+        let key = self.group_key(SpeedupStoreKey::PendingFundingTopUp);
+        let topup = self.get_value::<&str, Option<Utxo>>(&key)?.flatten();
+        Ok(topup)
+    }
+
+    fn set_pending_funding_topup(
+        &self,
+        topup: Option<Utxo>,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        let key = self.group_key(SpeedupStoreKey::PendingFundingTopUp);
+        self.set_value(&key, topup, None)?;
+        Ok(())
+    }
+
+    fn register_internal_monitor(
+        &self,
+        txid: Txid,
+        monitor: InternalMonitor,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        let key = SpeedupStoreKey::InternalMonitor(txid).get_key();
+        self.set_value(&key, monitor, None)?;
+        Ok(())
+    }
+
+    fn get_internal_monitor(
+        &self,
+        txid: &Txid,
+    ) -> Result<Option<InternalMonitor>, BitcoinCoordinatorStoreError> {
+        let key = SpeedupStoreKey::InternalMonitor(*txid).get_key();
+        let monitor = self.get_value::<&str, InternalMonitor>(&key)?;
+        Ok(monitor)
+    }
+
+    fn remove_internal_monitor(&self, txid: &Txid) -> Result<(), BitcoinCoordinatorStoreError> {
+        self.store
+            .remove(SpeedupStoreKey::InternalMonitor(*txid).get_key(), None)?;
+        Ok(())
+    }
+
+    fn record_speedup_outcome(
+        &self,
+        outcome: SpeedupOutcome,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        // The outcomes are shared by every funding group, the network is the same for all of them.
+        let key = SpeedupStoreKey::BumpStats.get_key();
+        let mut outcomes = self.get_speedup_outcomes()?;
+
+        if outcomes
+            .iter()
+            .any(|recorded| recorded.tx_id == outcome.tx_id)
+        {
+            return Ok(());
+        }
+
+        outcomes.push(outcome);
+
+        let excess = outcomes.len().saturating_sub(BUMP_STATS_WINDOW);
+        outcomes.drain(..excess);
+
+        self.set_value(&key, outcomes, None)?;
+        Ok(())
+    }
+
+    fn get_speedup_outcomes(&self) -> Result<Vec<SpeedupOutcome>, BitcoinCoordinatorStoreError> {
+        let key = SpeedupStoreKey::BumpStats.get_key();
+        let outcomes = self
+            .get_value::<&str, Vec<SpeedupOutcome>>(&key)?
+            .unwrap_or_default();
+        Ok(outcomes)
+    }
+
+    fn has_enough_unconfirmed_txs_for_cpfp(&self) -> Result<bool, BitcoinCoordinatorStoreError> {
+        let available_unconfirmed_txs = self.get_available_unconfirmed_txs()?;
+        let is_enough_unconfirmed_txs = available_unconfirmed_txs >= MIN_UNCONFIRMED_TXS_FOR_CPFP;
+        Ok(is_enough_unconfirmed_txs)
+    }
+
+    fn get_unconfirmed_chain_fee_shortfall(
+        &self,
+        network_fee_rate: u64,
+    ) -> Result<(u64, usize), BitcoinCoordinatorStoreError> {
+        // Unconfirmed speedups come from the newest to the oldest.
+        let speedups = self.get_unconfirmed_speedups()?;
+
+        let mut replaced_fundings = Vec::new();
+        let mut fee_shortfall = 0;
+        let mut chain_vsize = 0;
+
+        for speedup in speedups.iter() {
+            let funding = (speedup.prev_funding.txid, speedup.prev_funding.vout);
+
+            // A speedup replaced by a newer RBF is not part of the chain anymore.
+            if replaced_fundings.contains(&funding) {
+                continue;
+            }
+
+            if speedup.is_rbf {
+                replaced_fundings.push(funding);
+            }
+
+            let speedup_rate = speedup.network_fee_rate_used;
+            fee_shortfall += network_fee_rate.saturating_sub(speedup_rate) * speedup.vsize as u64;
+            chain_vsize += speedup.vsize;
+
+            for parent in speedup.speedup_tx_data.iter() {
+                // The parents are paid at least at the rate of the speedup that pays for them.
+                let parent_rate = match self.get_tx(&parent.tx_id) {
+                    Ok(parent) => parent.fee_rate_at_dispatch.max(speedup_rate),
+                    Err(BitcoinCoordinatorStoreError::TransactionNotFound(_)) => speedup_rate,
+                    Err(e) => return Err(e),
+                };
+
+                let parent_vsize = parent.vsize;
+                fee_shortfall += network_fee_rate.saturating_sub(parent_rate) * parent_vsize as u64;
+                chain_vsize += parent_vsize;
+            }
+        }
+
+        debug!(
+            "Unconfirmed chain fee shortfall | NetworkFeeRate({}) | FeeShortfall({}) | ChainVsize({})",
+            network_fee_rate, fee_shortfall, chain_vsize
+        );
+
+        Ok((fee_shortfall, chain_vsize))
+    }
+
+    fn get_funding_summary(
+        &self,
+        network_fee_rate: u64,
+    ) -> Result<FundingSummary, BitcoinCoordinatorStoreError> {
+        // The newest speedup (or funding checkpoint) holds the latest change, which is the active funding.
+        let mut funding = self.get_active_funding()?;
+
+        if let Some(active_funding) = &funding {
+            if self.is_funding_invalidated(active_funding)? {
+                funding = None;
+            }
+        }
+        let funding_pool = self.get_funding_pool()?;
+
+        let key = self.group_key(SpeedupStoreKey::FundingSpentFees);
+        let spent_since_funding = self.get_value::<&str, u64>(&key)?.unwrap_or_default();
+
+        // Unconfirmed speedups come from the newest to the oldest.
+        let mut replaced_fundings = Vec::new();
+        let mut unconfirmed_speedups = 0;
+
+        for speedup in self.get_unconfirmed_speedups()?.iter() {
+            let funding = (speedup.prev_funding.txid, speedup.prev_funding.vout);
+
+            // A speedup replaced by a newer RBF is not in flight anymore.
+            if replaced_fundings.contains(&funding) {
+                continue;
+            }
+
+            if speedup.is_rbf {
+                replaced_fundings.push(funding);
+            }
+
+            unconfirmed_speedups += 1;
+        }
+
+        let vsizes: Vec<u64> = self
+            .get_pending_speedups()?
+            .iter()
+            .filter(|speedup| speedup.vsize > 0)
+            .map(|speedup| speedup.vsize as u64)
+            .collect();
+
+        let remaining = funding
+            .iter()
+            .chain(funding_pool.iter())
+            .map(|f| f.amount)
+            .sum::<u64>();
+
+        let affordable_speedups = if vsizes.is_empty() {
+            None
+        } else {
+            let average_vsize = vsizes.iter().sum::<u64>() / vsizes.len() as u64;
+            let speedup_fee = (average_vsize * network_fee_rate.max(1)).max(1);
+            Some(remaining / speedup_fee)
+        };
+
+        debug!(
+            "Funding summary | Remaining({}) | PoolSize({}) | Spent({}) | UnconfirmedSpeedups({}) | AffordableSpeedups({:?})",
+            remaining,
+            funding_pool.len(),
+            spent_since_funding,
+            unconfirmed_speedups,
+            affordable_speedups
+        );
+
+        Ok(FundingSummary {
+            funding,
+            funding_pool,
+            spent_since_funding,
+            unconfirmed_speedups,
+            affordable_speedups,
+        })
+    }
+
+    fn save_speedup(
+        &self,
+        speedup: CoordinatedSpeedUpTransaction,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        // Whenever a speedup is created, we add it to the list of pending speedups because is not finished.
+        // Also speedup should be saved at the end of the list. Because is gonna be the new way to fund next speedups.
+
+        // A speedup funded from the pool starts a new chain from that funding.
+        // The rotation is committed on its own before, the speedup is written over the rotated chain.
+        if speedup.state != SpeedupState::Finalized
+            && self.is_pool_funding(&speedup.prev_funding)?
+        {
+            self.rotate_funding(speedup.prev_funding.clone())?;
+        }
+
+        let key = self.group_key(SpeedupStoreKey::PendingSpeedUpList);
+        let mut speedups = self.get_value::<&str, Vec<Txid>>(&key)?.unwrap_or_default();
+        let is_new_speedup = !speedups.contains(&speedup.tx_id);
+
+        // Accumulate the fees paid from the funding. A RBF only adds what it pays over the speedup it replaces.
+        let mut spent_fees = None;
+        if speedup.state != SpeedupState::Finalized && is_new_speedup {
+            let mut spent = speedup
+                .prev_funding
+                .amount
+                .saturating_sub(speedup.next_funding.amount);
+
+            if speedup.is_rbf {
+                let replaced = self.get_all_pending_speedups()?.into_iter().find(|s| {
+                    s.prev_funding.txid == speedup.prev_funding.txid
+                        && s.prev_funding.vout == speedup.prev_funding.vout
+                });
+
+                if let Some(replaced) = replaced {
+                    spent = replaced
+                        .next_funding
+                        .amount
+                        .saturating_sub(speedup.next_funding.amount);
+                }
+            }
+
+            let spent_key = self.group_key(SpeedupStoreKey::FundingSpentFees);
+            let spent_before = self.get_value::<&str, u64>(&spent_key)?.unwrap_or_default();
+            spent_fees = Some((spent_key, spent_before + spent));
+        }
+
+        // Speedups can pay for transactions that are not coordinated, like the funding ones.
+        let mut parents = Vec::new();
+        if is_new_speedup {
+            for parent in speedup.speedup_tx_data.iter() {
+                match self.get_tx(&parent.tx_id) {
+                    Ok(_) => parents.push(parent.tx_id),
+                    Err(BitcoinCoordinatorStoreError::TransactionNotFound(_)) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+
+        speedups.push(speedup.tx_id);
+
+        // The list entry, the record and the history are written together, a crash never leaves one without the other.
+        self.atomically(|transaction_id| {
+            if let Some((spent_key, spent_fees)) = spent_fees {
+                self.set_value(&spent_key, spent_fees, Some(transaction_id))?;
+            }
+
+            self.set_value(&key, speedups, Some(transaction_id))?;
+
+            // Record the speedup in the history of the transactions it pays for.
+            let fee = speedup
+                .prev_funding
+                .amount
+                .saturating_sub(speedup.next_funding.amount);
+
+            for tx_id in parents {
+                self.record_tx_events(
+                    tx_id,
+                    vec![TransactionEvent::SpedUp {
+                        speedup_txid: speedup.tx_id,
+                        is_rbf: speedup.is_rbf,
+                        fee,
+                        block_height: speedup.broadcast_block_height,
+                    }],
+                    Some(transaction_id),
+                )?;
+            }
+
+            // Save speedup to get by id.
+            let key = SpeedupStoreKey::SpeedUpTransaction(speedup.tx_id).get_key();
+            self.set_value(&key, speedup, Some(transaction_id))
+        })
+    }
+
+    fn get_speedup(
+        &self,
+        txid: &Txid,
+    ) -> Result<CoordinatedSpeedUpTransaction, BitcoinCoordinatorStoreError> {
+        let key = SpeedupStoreKey::SpeedUpTransaction(*txid).get_key();
+        let speedup = self
+            .get_value::<&str, CoordinatedSpeedUpTransaction>(&key)?
+            .ok_or(BitcoinCoordinatorStoreError::SpeedupNotFound)?;
+
+        Ok(speedup)
+    }
+
+    fn has_reached_max_unconfirmed_speedups(&self) -> Result<bool, BitcoinCoordinatorStoreError> {
+        let speedups = self.get_pending_speedups()?;
+
+        // sum up all consecutive unconfirmed speedups, and if sum is greater than MAX_UNCONFIRMED_SPEEDUPS, return true.
+        let mut sum = 0;
+
+        for speedup in speedups.iter() {
+            if speedup.state == SpeedupState::Invalidated {
+                continue;
+            }
+
+            if speedup.state == SpeedupState::Dispatched {
+                sum += 1;
+            } else {
+                break;
+            }
+        }
+
+        Ok(sum >= self.max_unconfirmed_speedups())
+    }
+
+    fn orphan_speedup(&self, txid: Txid) -> Result<Vec<Txid>, BitcoinCoordinatorStoreError> {
+        let mut orphaned = vec![txid];
+        self.update_speedup_state(txid, SpeedupState::Orphaned)?;
+
+        for speedup in self.get_speedup_descendants(txid)? {
+            if speedup.state == SpeedupState::Confirmed || speedup.state == SpeedupState::Finalized
+            {
+                continue;
+            }
+
+            self.update_speedup_state(speedup.tx_id, SpeedupState::Orphaned)?;
+            orphaned.push(speedup.tx_id);
+        }
+
+        debug!("Orphaned speedups | Speedups({:?})", orphaned);
+
+        Ok(orphaned)
+    }
+
+    fn restore_orphaned_speedup(&self, txid: Txid) -> Result<(), BitcoinCoordinatorStoreError> {
+        self.update_speedup_state(txid, SpeedupState::Confirmed)?;
+
+        for speedup in self.get_speedup_descendants(txid)? {
+            if speedup.state == SpeedupState::Orphaned {
+                self.update_speedup_state(speedup.tx_id, SpeedupState::Dispatched)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn invalidate_replaced_speedups(
+        &self,
+        rbf_txid: Txid,
+    ) -> Result<Vec<Txid>, BitcoinCoordinatorStoreError> {
+        let rbf = self.get_speedup(&rbf_txid)?;
+
+        let mut speedups = self.get_all_pending_speedups()?;
+        speedups.reverse();
+
+        // The replaced speedups spend the same funding as the replacement.
+        let replaced: Vec<Txid> = speedups
+            .iter()
+            .filter(|speedup| {
+                speedup.tx_id != rbf_txid
+                    && speedup.prev_funding.txid == rbf.prev_funding.txid
+                    && speedup.prev_funding.vout == rbf.prev_funding.vout
+            })
+            .map(|speedup| speedup.tx_id)
+            .collect();
+
+        let invalidated = self.invalidate_speedups_and_descendants(replaced)?;
+
+        debug!(
+            "Invalidated speedups | Replacement({}) | Speedups({:?})",
+            rbf_txid, invalidated
+        );
+
+        Ok(invalidated)
+    }
+
+    fn invalidate_speedups_paying(
+        &self,
+        txid: Txid,
+    ) -> Result<Vec<Txid>, BitcoinCoordinatorStoreError> {
+        let mut speedups = self.get_all_pending_speedups()?;
+        speedups.reverse();
+
+        // A CPFP spends the outputs of all the transactions it pays for, it can not be mined without any of them.
+        let paying: Vec<Txid> = speedups
+            .iter()
+            .filter(|speedup| {
+                speedup
+                    .speedup_tx_data
+                    .iter()
+                    .any(|parent| parent.tx_id == txid)
+            })
+            .map(|speedup| speedup.tx_id)
+            .collect();
+
+        let invalidated = self.invalidate_speedups_and_descendants(paying)?;
+
+        debug!(
+            "Invalidated speedups | DroppedTransaction({}) | Speedups({:?})",
+            txid, invalidated
+        );
+
+        Ok(invalidated)
+    }
+
+    fn update_speedup_state(
+        &self,
+        txid: Txid,
+        state: SpeedupState,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        let mut pending_list = None;
+
+        if state == SpeedupState::Finalized {
+            // Means that the speedup transaction was finalized.
+            // Then we need to remove it from the pending list.
+            let key = self.group_key(SpeedupStoreKey::PendingSpeedUpList);
+            let mut speedups = self
+                .get_value::<&str, Vec<Txid>>(&key)?
+                .ok_or(BitcoinCoordinatorStoreError::SpeedupNotFound)?;
+
+            // A speedup turned into a funding checkpoint can be listed twice, the newest entry is the one being finalized.
+            let index = speedups
+                .iter()
+                .rposition(|id| *id == txid)
+                .ok_or(BitcoinCoordinatorStoreError::SpeedupNotFound)?;
+
+            // The newest finalized speedup before this one is the previous checkpoint of the chain. Once this
+            // speedup is finalized it is the new checkpoint, so the previous one is removed from the pending list.
+            // This cleanup prevents the pending speedup list from growing indefinitely with finalized entries.
+            let mut previous_checkpoint = None;
+
+            for id in speedups[0..index].iter().rev() {
+                if *id != txid && self.get_speedup(id)?.state == SpeedupState::Finalized {
+                    previous_checkpoint = Some(*id);
+                    break;
+                }
+            }
+
+            // Removed by txid, so every entry of the previous checkpoint is removed and no other speedup is touched.
+            if let Some(checkpoint) = previous_checkpoint {
+                speedups.retain(|id| *id != checkpoint);
+                pending_list = Some((key, speedups));
+            }
+        }
+
+        // Update the new state of the transaction in transaction by id.
+        let key = SpeedupStoreKey::SpeedUpTransaction(txid).get_key();
+
+        let mut speedup = self
+            .get_value::<&str, CoordinatedSpeedUpTransaction>(&key)?
+            .ok_or(BitcoinCoordinatorStoreError::SpeedupNotFound)?;
+
+        speedup.state = state;
+
+        self.atomically(|transaction_id| {
+            if let Some((pending_key, speedups)) = pending_list {
+                self.set_value(&pending_key, &speedups, Some(transaction_id))?;
+            }
+
+            self.set_value(&key, &speedup, Some(transaction_id))
+        })
+    }
+
+    fn get_speedup_replacements(
+        &self,
+        cpfp: &CoordinatedSpeedUpTransaction,
+    ) -> Result<Vec<CoordinatedSpeedUpTransaction>, BitcoinCoordinatorStoreError> {
+        // A replacement spends the same funding as the CPFP it replaces. Once a replacement confirms,
+        // the next CPFP spends its change, so a new chain starts without replacements.
+        let mut seen = HashSet::new();
+
+        let replacements = self
+            .get_pending_speedups()?
+            .into_iter()
+            .filter(|speedup| {
+                speedup.is_rbf
+                    && seen.insert(speedup.tx_id)
+                    && speedup.tx_id != cpfp.tx_id
+                    && speedup.prev_funding.txid == cpfp.prev_funding.txid
+                    && speedup.prev_funding.vout == cpfp.prev_funding.vout
+            })
+            .collect();
+
+        Ok(replacements)
+    }
+
+    fn get_last_speedup(
+        &self,
+    ) -> Result<
+        Option<(
             CoordinatedSpeedUpTransaction,
             Option<CoordinatedSpeedUpTransaction>,
         )>,
@@ -430,6 +1299,16 @@ impl SpeedupStore for BitcoinCoordinatorStore {
         let mut last_rbf_tx = None;
 
         for speedup in speedups.iter() {
+            if speedup.state == SpeedupState::Orphaned {
+                // An orphaned speedup waits to be mined again, there is nothing to replace.
+                continue;
+            }
+
+            if speedup.state == SpeedupState::Invalidated {
+                // Replaced by a confirmed RBF, it can never be mined.
+                continue;
+            }
+
             if speedup.is_rbf && speedup.state == SpeedupState::Dispatched {
                 if last_rbf_tx.is_none() {
                     last_rbf_tx = Some(speedup.clone());
@@ -454,19 +1333,20 @@ impl SpeedupStore for BitcoinCoordinatorStore {
         max_retries: u32,
         interval_seconds: u64,
     ) -> Result<Vec<CoordinatedSpeedUpTransaction>, BitcoinCoordinatorStoreError> {
-        let key = SpeedupStoreKey::RetrySpeedUpTransactionList.get_key();
+        let key = self.group_key(SpeedupStoreKey::RetrySpeedUpTransactionList);
         let speedups: Vec<CoordinatedSpeedUpTransaction> = self
-            .store
-            .get::<&str, Vec<CoordinatedSpeedUpTransaction>>(&key)?
+            .get_value::<&str, Vec<CoordinatedSpeedUpTransaction>>(&key)?
             .unwrap_or_default();
 
         let mut eligible_speedups = Vec::new();
-        let current_time = Utc::now().timestamp_millis() as u64;
+        let current_time = self.now_millis();
 
         for speedup in speedups.iter() {
             if let Some(retry_info) = &speedup.retry_info {
                 if retry_info.retries_count < max_retries {
-                    if current_time >= retry_info.last_retry_timestamp + interval_seconds * 1000 {
+                    let backoff = retry_backoff_millis(interval_seconds, retry_info.retries_count);
+
+                    if current_time >= retry_info.last_retry_timestamp + backoff {
                         eligible_speedups.push(speedup.clone());
                     } else {
                         debug!(
@@ -490,28 +1370,26 @@ impl SpeedupStore for BitcoinCoordinatorStore {
         &self,
         mut speedup: CoordinatedSpeedUpTransaction,
     ) -> Result<(), BitcoinCoordinatorStoreError> {
-        let key = SpeedupStoreKey::RetrySpeedUpTransactionList.get_key();
+        let key = self.group_key(SpeedupStoreKey::RetrySpeedUpTransactionList);
         let mut speedups = self
-            .store
-            .get::<&str, Vec<CoordinatedSpeedUpTransaction>>(&key)?
+            .get_value::<&str, Vec<CoordinatedSpeedUpTransaction>>(&key)?
             .unwrap_or_default();
 
-        speedup.retry_info = Some(RetryInfo::new(0, Utc::now().timestamp_millis() as u64));
+        speedup.retry_info = Some(RetryInfo::new(0, self.now_millis()));
 
         speedups.push(speedup);
-        self.store.set(&key, &speedups, None)?;
+        self.set_value(&key, &speedups, None)?;
 
         Ok(())
     }
 
     fn dequeue_speedup_for_retry(&self, txid: Txid) -> Result<(), BitcoinCoordinatorStoreError> {
-        let key = SpeedupStoreKey::RetrySpeedUpTransactionList.get_key();
+        let key = self.group_key(SpeedupStoreKey::RetrySpeedUpTransactionList);
         let mut speedups = self
-            .store
-            .get::<&str, Vec<CoordinatedSpeedUpTransaction>>(&key)?
+            .get_value::<&str, Vec<CoordinatedSpeedUpTransaction>>(&key)?
             .unwrap_or_default();
         speedups.retain(|s| s.tx_id != txid);
-        self.store.set(&key, &speedups, None)?;
+        self.set_value(&key, &speedups, None)?;
 
         Ok(())
     }
@@ -520,24 +1398,454 @@ impl SpeedupStore for BitcoinCoordinatorStore {
         &self,
         txid: Txid,
     ) -> Result<(), BitcoinCoordinatorStoreError> {
-        let key = SpeedupStoreKey::RetrySpeedUpTransactionList.get_key();
+        let key = self.group_key(SpeedupStoreKey::RetrySpeedUpTransactionList);
         let mut speedups = self
-            .store
-            .get::<&str, Vec<CoordinatedSpeedUpTransaction>>(&key)?
+            .get_value::<&str, Vec<CoordinatedSpeedUpTransaction>>(&key)?
             .unwrap_or_default();
 
         for speedup in speedups.iter_mut() {
             if speedup.tx_id == txid {
                 speedup.retry_info = Some(RetryInfo::new(
                     speedup.retry_info.clone().unwrap().retries_count + 1,
-                    Utc::now().timestamp_millis() as u64,
+                    self.now_millis(),
                 ));
 
-                self.store.set(&key, &speedups, None)?;
+                self.set_value(&key, &speedups, None)?;
                 break;
             }
         }
 
         Ok(())
     }
+
+    fn prune_finalized_speedups(&self) -> Result<u32, BitcoinCoordinatorStoreError> {
+        let key = self.group_key(SpeedupStoreKey::PendingSpeedUpList);
+        let speedup_ids = self.get_value::<&str, Vec<Txid>>(&key)?.unwrap_or_default();
+
+        let mut finalized: Vec<Txid> = Vec::new();
+
+        for txid in speedup_ids.iter() {
+            if !finalized.contains(txid) && self.get_speedup(txid)?.state == SpeedupState::Finalized
+            {
+                finalized.push(*txid);
+            }
+        }
+
+        // The last finalized speedup is the checkpoint of the speedup chain, it is always kept.
+        // A speedup turned into a checkpoint can be listed twice, so it is looked up by txid.
+        if let Some(checkpoint) = speedup_ids
+            .iter()
+            .rev()
+            .find(|txid| finalized.contains(txid))
+            .copied()
+        {
+            finalized.retain(|txid| *txid != checkpoint);
+        }
+
+        if finalized.is_empty() {
+            return Ok(0);
+        }
+
+        let remaining: Vec<Txid> = speedup_ids
+            .into_iter()
+            .filter(|txid| !finalized.contains(txid))
+            .collect();
+
+        self.atomically(|transaction_id| {
+            self.set_value(&key, &remaining, Some(transaction_id))?;
+
+            for txid in finalized.iter() {
+                self.store.remove(
+                    SpeedupStoreKey::SpeedUpTransaction(*txid).get_key(),
+                    Some(transaction_id),
+                )?;
+                self.store.remove(
+                    SpeedupStoreKey::InternalMonitor(*txid).get_key(),
+                    Some(transaction_id),
+                )?;
+            }
+
+            Ok(())
+        })?;
+
+        debug!("Pruned finalized speedups | Speedups({:?})", finalized);
+
+        Ok(finalized.len() as u32)
+    }
+
+    fn verify_speedup_chain(&self) -> Result<Vec<String>, BitcoinCoordinatorStoreError> {
+        let key = self.group_key(SpeedupStoreKey::PendingSpeedUpList);
+        let speedup_ids = self.get_value::<&str, Vec<Txid>>(&key)?.unwrap_or_default();
+
+        let mut problems = Vec::new();
+        let mut seen = HashSet::new();
+
+        for txid in speedup_ids.iter() {
+            let speedup = self.get_value::<&str, CoordinatedSpeedUpTransaction>(
+                &SpeedupStoreKey::SpeedUpTransaction(*txid).get_key(),
+            )?;
+
+            let Some(speedup) = speedup else {
+                problems.push(format!("Speedup {txid} is listed but has no record"));
+                continue;
+            };
+
+            if speedup.tx_id != *txid {
+                problems.push(format!(
+                    "Speedup {txid} is listed but its record has txid {}",
+                    speedup.tx_id
+                ));
+            }
+
+            // Only a funding checkpoint can be listed twice, and it has to be finalized.
+            if !seen.insert(*txid) && speedup.state != SpeedupState::Finalized {
+                problems.push(format!(
+                    "Speedup {txid} is listed more than once in state {:?}",
+                    speedup.state
+                ));
+            }
+        }
+
+        for problem in problems.iter() {
+            debug!("Inconsistent speedup chain | {}", problem);
+        }
+
+        Ok(problems)
+    }
+
+    fn add_funding_group(&self, group: &str) -> Result<(), BitcoinCoordinatorStoreError> {
+        let mut groups = self.get_funding_groups()?;
+
+        if groups.iter().any(|g| g == group) {
+            return Ok(());
+        }
+
+        groups.push(group.to_string());
+
+        let key = SpeedupStoreKey::FundingGroupList.get_key();
+        self.set_value(&key, groups, None)?;
+
+        Ok(())
+    }
+
+    fn get_funding_groups(&self) -> Result<Vec<String>, BitcoinCoordinatorStoreError> {
+        let key = SpeedupStoreKey::FundingGroupList.get_key();
+        let groups = self
+            .get_value::<&str, Vec<String>>(&key)?
+            .unwrap_or_default();
+        Ok(groups)
+    }
+}
+
+impl BitcoinCoordinatorStore {
+    // Drops the speedups of the pending list of the current funding group that have no record.
+    pub(crate) fn repair_dangling_speedups(&self) -> Result<u32, BitcoinCoordinatorStoreError> {
+        let key = self.group_key(SpeedupStoreKey::PendingSpeedUpList);
+
+        self.atomically(|transaction_id| {
+            self.drop_dangling_entries(
+                &key,
+                |txid| SpeedupStoreKey::SpeedUpTransaction(txid).get_key(),
+                transaction_id,
+            )
+        })
+    }
+
+    // Key of the speedup chain of the funding group the store is working on.
+    fn group_key(&self, key: SpeedupStoreKey) -> String {
+        key.get_group_key(self.funding_group().as_deref())
+    }
+
+    // Selects the funding for the next speedup and whether it comes from the funding pool.
+    // The active speedup chain is used while it can fund more speedups. When it is stuck (max unconfirmed speedups,
+    // waiting for a replacement or out of unconfirmed slots) the confirmed pool UTXO with the biggest amount is used.
+    fn select_funding(&self) -> Result<Option<(Utxo, bool)>, BitcoinCoordinatorStoreError> {
+        let mut chain_funding = self.get_chain_funding()?;
+
+        // A chain whose funding was spent outside the coordinator can not fund more speedups.
+        if let Some(funding) = &chain_funding {
+            if self.is_funding_invalidated(funding)? {
+                chain_funding = None;
+            }
+        }
+
+        if chain_funding.is_some()
+            && self.get_chain_available_unconfirmed_txs()? >= MIN_UNCONFIRMED_TXS_FOR_CPFP
+        {
+            return Ok(chain_funding.map(|funding| (funding, false)));
+        }
+
+        let mut pool_funding: Option<Utxo> = None;
+
+        for utxo in self.get_funding_pool()? {
+            if !self.is_funding_confirmed(&utxo)? {
+                continue;
+            }
+
+            let is_bigger = match &pool_funding {
+                Some(best) => utxo.amount > best.amount,
+                None => true,
+            };
+
+            if is_bigger {
+                pool_funding = Some(utxo);
+            }
+        }
+
+        if let Some(pool_funding) = pool_funding {
+            return Ok(Some((pool_funding, true)));
+        }
+
+        Ok(chain_funding.map(|funding| (funding, false)))
+    }
+
+    // Speedups funded, directly or through other speedups, from the change of the given speedup. From the oldest to the newest.
+    // Marks the speedups and the speedups funded from their change as Invalidated, except the ones already confirmed.
+    fn invalidate_speedups_and_descendants(
+        &self,
+        txids: Vec<Txid>,
+    ) -> Result<Vec<Txid>, BitcoinCoordinatorStoreError> {
+        let mut invalidated: Vec<Txid> = Vec::new();
+
+        for txid in txids {
+            let descendants = self.get_speedup_descendants(txid)?;
+
+            for speedup in std::iter::once(self.get_speedup(&txid)?).chain(descendants) {
+                if invalidated.contains(&speedup.tx_id)
+                    || speedup.state == SpeedupState::Confirmed
+                    || speedup.state == SpeedupState::Finalized
+                    || speedup.state == SpeedupState::Invalidated
+                {
+                    continue;
+                }
+
+                self.update_speedup_state(speedup.tx_id, SpeedupState::Invalidated)?;
+                invalidated.push(speedup.tx_id);
+            }
+        }
+
+        Ok(invalidated)
+    }
+
+    fn get_speedup_descendants(
+        &self,
+        txid: Txid,
+    ) -> Result<Vec<CoordinatedSpeedUpTransaction>, BitcoinCoordinatorStoreError> {
+        let mut speedups = self.get_all_pending_speedups()?;
+        speedups.reverse();
+
+        let mut fundings = vec![txid];
+        let mut descendants = Vec::new();
+
+        for speedup in speedups {
+            if speedup.tx_id == txid || !fundings.contains(&speedup.prev_funding.txid) {
+                continue;
+            }
+
+            fundings.push(speedup.tx_id);
+            descendants.push(speedup);
+        }
+
+        Ok(descendants)
+    }
+
+    // The funding of the newest speedup (or funding checkpoint), confirmed or not.
+    // None when the newest speedup spent all its funding.
+    fn get_active_funding(&self) -> Result<Option<Utxo>, BitcoinCoordinatorStoreError> {
+        let funding = self
+            .get_all_pending_speedups()?
+            .first()
+            .and_then(|speedup| speedup.change_funding());
+
+        Ok(funding)
+    }
+
+    // Funding added by the user is expected to be confirmed. The change of a speedup chain left behind
+    // can only be used once its speedup is confirmed.
+    fn is_funding_confirmed(&self, funding: &Utxo) -> Result<bool, BitcoinCoordinatorStoreError> {
+        match self.get_speedup(&funding.txid) {
+            Ok(speedup) => Ok(speedup.state == SpeedupState::Confirmed
+                || speedup.state == SpeedupState::Finalized),
+            Err(BitcoinCoordinatorStoreError::SpeedupNotFound) => Ok(true),
+            Err(e) => Err(e),
+        }
+    }
+
+    // Moves the active funding to the pool funding used by the speedup being saved.
+    // The change of the chain left behind goes back to the pool.
+    fn rotate_funding(&self, funding: Utxo) -> Result<(), BitcoinCoordinatorStoreError> {
+        let mut pool = self.get_funding_pool()?;
+        pool.retain(|utxo| !(utxo.txid == funding.txid && utxo.vout == funding.vout));
+
+        if let Some(active_funding) = self.get_active_funding()? {
+            if !self.is_funding_invalidated(&active_funding)? {
+                pool.push(active_funding);
+            }
+        }
+
+        self.save_funding_pool(pool)?;
+
+        debug!(
+            "Rotating funding | FundingTx({}) | Vout({}) | Amount({})",
+            funding.txid, funding.vout, funding.amount
+        );
+
+        self.save_funding_checkpoint(funding)
+    }
+
+    fn save_funding_checkpoint(&self, funding: Utxo) -> Result<(), BitcoinCoordinatorStoreError> {
+        // The funding is saved as a Finalized speedup, which is a checkpoint for the speedup chain.
+        // If the funding is the change of a speedup, that speedup becomes the checkpoint.
+        let speedup = match self.get_speedup(&funding.txid) {
+            Ok(mut speedup) if speedup.next_funding.vout == funding.vout => {
+                speedup.state = SpeedupState::Finalized;
+                speedup
+            }
+            Ok(_) | Err(BitcoinCoordinatorStoreError::SpeedupNotFound) => {
+                // Since this is a new funding, there is no previous funding UTXO; we use the same UTXO for both previous and next funding fields to avoid introducing an Option type.
+                // The broadcast block height is set to 0 and Finalized because funding should be confirmed on chain.
+                CoordinatedSpeedUpTransaction::new(
+                    funding.txid,
+                    funding.clone(),
+                    funding,
+                    false,
+                    0,
+                    SpeedupState::Finalized,
+                    1.0,
+                    vec![],
+                    1,
+                    0,
+                )
+            }
+            Err(e) => return Err(e),
+        };
+
+        self.save_speedup(speedup)?;
+
+        // The spent fees are counted from the last funding added.
+        let key = self.group_key(SpeedupStoreKey::FundingSpentFees);
+        self.set_value(&key, 0_u64, None)?;
+
+        Ok(())
+    }
+
+    fn save_funding_pool(&self, pool: Vec<Utxo>) -> Result<(), BitcoinCoordinatorStoreError> {
+        let key = self.group_key(SpeedupStoreKey::FundingPool);
+        self.set_value(&key, pool, None)?;
+        Ok(())
+    }
+
+    // Unconfirmed txs that can still be chained to the active funding, following the mempool chain limit.
+    fn get_chain_available_unconfirmed_txs(&self) -> Result<u32, BitcoinCoordinatorStoreError> {
+        let speedups = self.get_all_pending_speedups()?;
+
+        let mut available_utxos = MAX_LIMIT_UNCONFIRMED_PARENTS;
+
+        let mut is_rbf_active = false;
+
+        for speedup in speedups.iter() {
+            // Speedups replaced by a confirmed RBF do not take unconfirmed slots, they are not in the mempool.
+            if speedup.state == SpeedupState::Invalidated {
+                continue;
+            }
+
+            // In case there is a RBF at the top, we necessary need to find a confirmed RBF
+            // to be able to fund otherwise there is no capacity for funding unconfirmed txs.
+            if is_rbf_active && !speedup.is_rbf {
+                return Ok(0);
+            }
+
+            if speedup.state == SpeedupState::Confirmed || speedup.state == SpeedupState::Finalized
+            {
+                return Ok(available_utxos);
+            }
+
+            if speedup.is_rbf && speedup.state == SpeedupState::Dispatched {
+                is_rbf_active = true;
+                continue;
+            }
+
+            if is_rbf_active && speedup.is_rbf {
+                return Ok(0);
+            }
+
+            let cpfp_tx = 1;
+            let to_subtract = speedup.speedup_tx_data.len() as u32 + cpfp_tx;
+            available_utxos = available_utxos.saturating_sub(to_subtract);
+        }
+
+        Ok(available_utxos)
+    }
+
+    fn get_chain_funding(&self) -> Result<Option<Utxo>, BitcoinCoordinatorStoreError> {
+        // Attempt to determine the current funding UTXO by walking the speedup transaction history in reverse.
+        // The funding UTXO is derived from the most recent speedup transaction that is either:
+        //   - Finalized (serves as a checkpoint, i.e., a new funding insertion), or
+        //   - Confirmed (regardless of whether it's a replace speedup), or
+        //   - Not a replace speedup (i.e., a regular speedup, even if unconfirmed).
+        //
+        // If the latest speedup is an unconfirmed replace speedup, we must look further back for a confirmed replace speedup.
+        // This prevents chaining unconfirmed replace speedups, ensuring only a confirmed replace speedup can serve as funding.
+        //
+        // Orphaned speedups are skipped, their change no longer exists on the active chain.
+        // Invalidated speedups are skipped too, they were replaced by a confirmed RBF and their change will never exist.
+        //
+        // If no suitable funding is found, return None.
+
+        // If we have reached the max number of unconfirmed speedups, we are waiting for confirmations, then there is no funding available.
+        if self.has_reached_max_unconfirmed_speedups()? {
+            return Ok(None);
+        }
+
+        let speedups = self.get_all_pending_speedups()?;
+
+        let mut should_be_a_replace = false;
+
+        for speedup in speedups.iter() {
+            if speedup.state == SpeedupState::Orphaned || speedup.state == SpeedupState::Invalidated
+            {
+                continue;
+            }
+
+            if !should_be_a_replace {
+                if speedup.state == SpeedupState::Finalized
+                    || speedup.state == SpeedupState::Confirmed
+                {
+                    return Ok(speedup.change_funding());
+                }
+
+                if !speedup.is_rbf {
+                    // Encountered an unconfirmed regular speedup. We can use this as funding.
+                    return Ok(speedup.change_funding());
+                }
+
+                // Encountered an unconfirmed replace speedup; must look for a previous confirmed replace.
+                should_be_a_replace = true;
+
+                continue;
+            }
+
+            // We are searching for a previous confirmed replace speedup.
+            if speedup.is_rbf {
+                if speedup.state == SpeedupState::Confirmed {
+                    // Found a confirmed replace speedup; use as funding.
+                    return Ok(speedup.change_funding());
+                }
+
+                continue;
+            }
+
+            if speedup.state == SpeedupState::Confirmed {
+                // Found a confirmed regular speedup; use as funding.
+                return Ok(speedup.change_funding());
+            } else {
+                // Found an unconfirmed regular speedup; cannot use as funding.
+                // This current speedup is responsible for getting into a chain of replacements.
+                return Ok(None);
+            }
+        }
+
+        // No suitable funding found in the speedup history.
+        Ok(None)
+    }
 }
@@ -1,34 +1,159 @@
 use crate::{
+    clock::{retry_backoff_millis, Clock, SystemClock},
+    confirmation_stats::finalized_tx_stats,
+    encryption::StoreCipher,
     errors::BitcoinCoordinatorStoreError,
+    journal::EventJournal,
+    record::{
+        upgrade_record, AddressFundedNews, AnchorSpentExternallyNews, CollateralFullySpentNews,
+        CollateralSpentNews, ConfirmationMilestoneNews, DependencyFailedNews,
+        DispatchCancelledNews, DispatchDeferredNews, DispatchPausedHighFeesNews,
+        DispatchScheduledNews, DispatchSpeedUpErrorNews, DispatchTransactionErrorNews,
+        EstimateFeerateTooHighNews, FeeEstimateUnavailableNews, FeeOverpaymentNews,
+        FundingExhaustedNews, FundingNotFoundNews, FundingSpentExternallyNews,
+        FundingTemporarilyUnavailableNews, FundingTopUpNews, GroupCompletedNews,
+        InsufficientFundsNews, MaxRbfAttemptsReachedNews, MaxRebroadcastAttemptsReachedNews,
+        MempoolRejectionNews, NetworkErrorNews, NewBlockNews, NewsRecord, NodeRecoveredNews,
+        NodeUnreachableNews, NonStandardAnchorNews, OutpointSpentNews, ParentReplacedNews,
+        RbfEscalationFailedNews, SettingsUpdatedNews, SpeedupChainInvalidatedNews,
+        SpeedupCreatedNews, SpeedupFeeCapExceededNews, SpeedupOrphanedNews,
+        SpeedupRejectedByPolicyNews, StoredRecord, TickPartialFailureNews, TickWorkSkippedNews,
+        TransactionAlreadyInMempoolNews, TransactionConflictedNews, TransactionExpiredNews,
+        TransactionRebroadcastNews, TransactionReorgedNews,
+    },
+    settings::MAX_FINALIZED_TX_STATS,
+    snapshot::StoreSnapshot,
+    speedup::SpeedupStore,
+    store_backend::{InMemoryStore, StoreBackend},
     types::{
-        AckCoordinatorNews, CoordinatedTransaction, CoordinatorNews, RetryInfo, TransactionState,
+        AckCoordinatorNews, ConflictScanProgress, CoordinatedTransaction, CoordinatorNews,
+        CoordinatorRunState, DetectedPegin, DispatchDeferredReason, DispatchOptions,
+        FinalizedDelivery, FinalizedTxEntry, FinalizedTxStats, GroupMemberState, GroupStatus,
+        JournalEvent, PackageFeeReport, ParentReplacementIntent, PendingReason, PendingTxEntry,
+        PruneSummary, RetryInfo, Severity, StoreOwner, TickSkipReason, TransactionEvent,
+        TransactionHistory, TransactionHistoryEntry, TransactionState, WatchedAddress,
+        WatchedFinality, WatchedOutpoint, WatchedUtxoSet,
     },
 };
 
-use bitcoin::{BlockHash, Transaction, Txid};
+use bitcoin::{BlockHash, OutPoint, ScriptBuf, Transaction, Txid};
 use bitvmx_bitcoin_rpc::types::BlockHeight;
 use chrono::Utc;
 use protocol_builder::types::output::SpeedupData;
+use serde::{de::DeserializeOwned, Serialize};
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
 use std::rc::Rc;
-use storage_backend::storage::{KeyValueStore, Storage};
-use tracing::info;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+// Version of the per-state transaction indexes, saved once they are built.
+const STATE_INDEX_VERSION: u32 = 1;
+
 pub struct BitcoinCoordinatorStore {
-    pub store: Rc<Storage>,
-    pub max_unconfirmed_speedups: u32,
-    pub retry_attempts_sending_tx: u32,
-    pub retry_interval_seconds: u64,
+    pub store: Rc<dyn StoreBackend>,
+    // Limits taken from the settings, they can be changed while the coordinator is running.
+    max_unconfirmed_speedups: Cell<u32>,
+    retry_attempts_sending_tx: Cell<u32>,
+    retry_interval_seconds: Cell<u64>,
+    // Time of the retries, the system clock unless one is set with with_clock.
+    clock: Rc<dyn Clock>,
+    // Funding group whose speedup chain the speedup operations work on, the default chain when None.
+    funding_group: RefCell<Option<String>>,
+    journal: EventJournal,
+    // Set when the records are encrypted at rest, the journal entries are encrypted with it too.
+    cipher: Option<StoreCipher>,
+    // Records read through get_value since the store was opened.
+    reads: Cell<u64>,
+    // Whether the per-state transaction indexes were checked, and rebuilt for a legacy store.
+    state_indexes_ready: Cell<bool>,
+    // Whether the news log was checked, and built for the news stored before it.
+    news_log_ready: Cell<bool>,
+    // Asked before each write whether it fails, only set with with_write_fault to test failing writes.
+    write_fault: Option<Rc<dyn StoreWriteFault>>,
+}
+
+// Decides which writes of the store fail, to test how the coordinator recovers from a failing storage.
+pub trait StoreWriteFault {
+    fn fail_write(&self, key: &str) -> bool;
 }
+
 enum StoreKey {
     PendingTransactionList,
+    FinalizedTransactionList,
+    FinalizedTxStatsList,
+    FinalizedLogEntry(u64),
+    FinalizedLogNextSeq,
+    FinalizedLogBlocks(Txid),
+    FinalizedDelivery,
     Transaction(Txid),
+    TransactionHistory(Txid),
+    ContextTransactionList(String),
+    TransactionStateList(TransactionState),
+    TransactionStateIndexVersion,
+    ReplacedTransactionList,
+    ParentReplacementIntentList,
     DispatchTransactionErrorNewsList,
     DispatchSpeedUpErrorNewsList,
     InsufficientFundsNewsList,
+    SpeedupFeeCapExceededNewsList,
+    FundingTopUpNewsList,
+    ParentReplacedNewsList,
     FundingNotFoundNews,
     EstimateFeerateTooHighNewsList,
+    FeeEstimateUnavailableNews,
+    DispatchPausedHighFeesNews,
+    TickPartialFailureNews,
+    NodeUnreachableNews,
+    NodeRecoveredNews,
+    SettingsUpdatedNews,
     TransactionAlreadyInMempoolNewsList,
     MempoolRejectionNewsList,
     NetworkErrorNewsList,
+    DispatchCancelledNewsList,
+    TransactionExpiredNewsList,
+    NonStandardAnchorNewsList,
+    RbfEscalationFailedNewsList,
+    MaxRbfAttemptsReachedNewsList,
+    TransactionRebroadcastNewsList,
+    MaxRebroadcastAttemptsReachedNewsList,
+    SpeedupOrphanedNewsList,
+    SpeedupChainInvalidatedNewsList,
+    SpeedupCreatedNewsList,
+    TransactionConflictedNewsList,
+    TransactionReorgedNewsList,
+    DispatchScheduledNewsList,
+    DependencyFailedNewsList,
+    OutpointSpentNewsList,
+    AddressFundedNewsList,
+    FundingSpentExternallyNewsList,
+    FundingExhaustedNewsList,
+    FundingTemporarilyUnavailableNewsList,
+    AnchorSpentExternallyNewsList,
+    FeeOverpaymentNewsList,
+    ConfirmationMilestoneNewsList,
+    CollateralSpentNewsList,
+    CollateralFullySpentNewsList,
+    DispatchDeferredNewsList,
+    TickWorkSkippedNewsList,
+    SpeedupRejectedByPolicyNewsList,
+    GroupCompletedNewsList,
+    NewBlockNews,
+    NewsLogEntries,
+    NewsLogEntry(u64, Severity),
+    NewsLogNextSeq,
+    WatchedOutpointList,
+    WatchedAddressList,
+    WatchedUtxoSetList,
+    WatchedFinalityList,
+    TxGroupList,
+    NewBlockSubscription,
+    RunState,
+    Owner,
+    NewsSubscriberDelivered(String),
+    RskPeginContext,
+    DetectedPeginList,
 }
 pub trait BitcoinCoordinatorStoreApi {
     fn save_tx(
@@ -39,6 +164,23 @@ pub trait BitcoinCoordinatorStoreApi {
         context: String,
     ) -> Result<(), BitcoinCoordinatorStoreError>;
 
+    /// Saves a transaction to be dispatched with overrides of the global fee policy.
+    fn save_tx_with_options(
+        &self,
+        tx: Transaction,
+        speedup_data: Option<SpeedupData>,
+        target_block_height: Option<BlockHeight>,
+        context: String,
+        dispatch_options: DispatchOptions,
+    ) -> Result<(), BitcoinCoordinatorStoreError>;
+
+    /// Saves all the transactions to be dispatched, or none of them if any of them can not be saved.
+    fn save_txs(
+        &self,
+        txs: Vec<(Transaction, Option<SpeedupData>, String)>,
+        target_block_height: Option<BlockHeight>,
+    ) -> Result<(), BitcoinCoordinatorStoreError>;
+
     fn remove_tx(&self, tx_id: Txid) -> Result<(), BitcoinCoordinatorStoreError>;
 
     fn get_txs_in_progress(
@@ -49,696 +191,4901 @@ pub trait BitcoinCoordinatorStoreApi {
         &self,
     ) -> Result<Vec<CoordinatedTransaction>, BitcoinCoordinatorStoreError>;
 
+    /// Returns the transactions in state ToDispatch or Dispatched, in the order they were saved,
+    /// with the reason each one is held back at `current_block_height`.
+    fn get_pending_tx_entries(
+        &self,
+        current_block_height: BlockHeight,
+    ) -> Result<Vec<PendingTxEntry>, BitcoinCoordinatorStoreError>;
+
     fn get_tx(&self, tx_id: &Txid) -> Result<CoordinatedTransaction, BitcoinCoordinatorStoreError>;
 
+    /// Returns the current state of the transaction and the events recorded since it was saved.
+    fn get_tx_history(
+        &self,
+        tx_id: &Txid,
+    ) -> Result<TransactionHistory, BitcoinCoordinatorStoreError>;
+
     fn update_tx_state(
         &self,
         tx_id: Txid,
         status: TransactionState,
     ) -> Result<(), BitcoinCoordinatorStoreError>;
 
+    /// Moves the transaction to Finalized and appends it to the finalized log in the same store transaction.
+    /// Returns the new entry, None when the transaction was already logged as finalized in that block.
+    fn finalize_tx(
+        &self,
+        tx_id: Txid,
+        block_height: BlockHeight,
+        block_hash: BlockHash,
+    ) -> Result<Option<FinalizedTxEntry>, BitcoinCoordinatorStoreError>;
+
+    /// Returns at most `limit` entries of the finalized log with a sequence number equal or greater than
+    /// `since_seq`, in order.
+    fn read_finalized(
+        &self,
+        since_seq: u64,
+        limit: usize,
+    ) -> Result<Vec<FinalizedTxEntry>, BitcoinCoordinatorStoreError>;
+
+    fn get_finalized_delivery(&self) -> Result<FinalizedDelivery, BitcoinCoordinatorStoreError>;
+
+    fn set_finalized_delivery(
+        &self,
+        delivery: &FinalizedDelivery,
+    ) -> Result<(), BitcoinCoordinatorStoreError>;
+
     fn update_tx_to_dispatched(
         &self,
         tx_id: Txid,
         deliver_block_height: u32,
+        fee_rate_at_dispatch: u64,
     ) -> Result<(), BitcoinCoordinatorStoreError>;
 
-    fn update_news(
+    /// Saves the block height the transaction was confirmed at the first time, with the number of speedups
+    /// spent on it and the sats attributable to them. It does nothing if the first confirmation was already saved.
+    fn save_first_confirmation(
         &self,
-        news: CoordinatorNews,
-        current_block_hash: BlockHash,
+        tx_id: Txid,
+        block_height: BlockHeight,
+        speedup_count: u32,
+        speedup_fees: u64,
     ) -> Result<(), BitcoinCoordinatorStoreError>;
-    fn ack_news(&self, news: AckCoordinatorNews) -> Result<(), BitcoinCoordinatorStoreError>;
-    fn get_news(&self) -> Result<Vec<CoordinatorNews>, BitcoinCoordinatorStoreError>;
 
-    fn increment_tx_retry_count(&self, txid: Txid) -> Result<(), BitcoinCoordinatorStoreError>;
-}
-
-impl BitcoinCoordinatorStore {
-    pub fn new(
-        store: Rc<Storage>,
-        max_unconfirmed_speedups: u32,
-        retry_attempts_sending_tx: u32,
-        retry_interval_seconds: u64,
-    ) -> Result<Self, BitcoinCoordinatorStoreError> {
-        Ok(Self {
-            store,
-            max_unconfirmed_speedups,
-            retry_attempts_sending_tx,
-            retry_interval_seconds,
-        })
-    }
+    /// Saves the network fee rate estimated when the transaction was seen confirmed the first time.
+    /// It does nothing if a fee rate was already saved, so the rate is the one of the first confirmation.
+    fn save_fee_rate_at_confirmation(
+        &self,
+        tx_id: Txid,
+        fee_rate: u64,
+    ) -> Result<(), BitcoinCoordinatorStoreError>;
 
-    fn get_key(&self, key: StoreKey) -> String {
-        let prefix = "bitcoin_coordinator";
-        match key {
-            StoreKey::PendingTransactionList => format!("{prefix}/tx/list"),
-            StoreKey::Transaction(tx_id) => format!("{prefix}/tx/{tx_id}"),
+    /// Saves the highest confirmation milestone reported for the transaction, None when none was reached.
+    fn save_last_confirmation_milestone(
+        &self,
+        tx_id: Txid,
+        milestone: Option<u32>,
+    ) -> Result<(), BitcoinCoordinatorStoreError>;
 
-            //NEWS
-            StoreKey::InsufficientFundsNewsList => format!("{prefix}/news/insufficient_funds"),
-            StoreKey::DispatchTransactionErrorNewsList => {
-                format!("{prefix}/news/dispatch_transaction_error")
-            }
-            StoreKey::DispatchSpeedUpErrorNewsList => {
-                format!("{prefix}/news/dispatch_speed_up_error")
-            }
-            StoreKey::FundingNotFoundNews => format!("{prefix}/news/funding_not_found"),
-            StoreKey::EstimateFeerateTooHighNewsList => {
-                format!("{prefix}/news/estimate_feerate_too_high")
-            }
-            StoreKey::TransactionAlreadyInMempoolNewsList => {
-                format!("{prefix}/news/transaction_already_in_mempool")
-            }
-            StoreKey::MempoolRejectionNewsList => {
-                format!("{prefix}/news/mempool_rejection")
-            }
-            StoreKey::NetworkErrorNewsList => format!("{prefix}/news/network_error"),
-        }
-    }
+    /// Saves the fee rate paid by the transaction and its speedups, computed when it is finalized.
+    fn save_fee_report(
+        &self,
+        tx_id: Txid,
+        report: PackageFeeReport,
+    ) -> Result<(), BitcoinCoordinatorStoreError>;
 
-    fn get_txs(&self) -> Result<Vec<Txid>, BitcoinCoordinatorStoreError> {
-        let key = self.get_key(StoreKey::PendingTransactionList);
+    /// Saves the block height the dispatch of the transaction was first paused at because of high fees.
+    fn save_paused_since(
+        &self,
+        tx_id: Txid,
+        block_height: BlockHeight,
+    ) -> Result<(), BitcoinCoordinatorStoreError>;
 
-        let all_txs = self.store.get::<&str, Vec<Txid>>(&key)?;
+    /// Saves the block height the locktime of the transaction lets it be broadcast at, None when it is final.
+    fn save_locktime_height(
+        &self,
+        tx_id: Txid,
+        block_height: Option<BlockHeight>,
+    ) -> Result<(), BitcoinCoordinatorStoreError>;
 
-        match all_txs {
-            Some(txs) => Ok(txs),
-            None => Ok(vec![]),
-        }
-    }
-}
+    /// Saves the last block read looking for a double spend of the inputs of the transaction.
+    fn save_conflict_scan_progress(
+        &self,
+        tx_id: Txid,
+        progress: ConflictScanProgress,
+    ) -> Result<(), BitcoinCoordinatorStoreError>;
 
-impl BitcoinCoordinatorStoreApi for BitcoinCoordinatorStore {
-    fn get_tx(&self, tx_id: &Txid) -> Result<CoordinatedTransaction, BitcoinCoordinatorStoreError> {
-        let key = self.get_key(StoreKey::Transaction(*tx_id));
-        let tx = self.store.get::<&str, CoordinatedTransaction>(&key)?;
+    /// Returns the summaries of the last finalized transactions, from the oldest to the newest.
+    /// Only the last MAX_FINALIZED_TX_STATS summaries are kept.
+    fn get_finalized_tx_stats(&self)
+        -> Result<Vec<FinalizedTxStats>, BitcoinCoordinatorStoreError>;
 
-        if let Some(tx) = tx {
-            Ok(tx)
-        } else {
-            let message = format!("Transaction not found: {tx_id}");
-            Err(BitcoinCoordinatorStoreError::TransactionNotFound(message))
-        }
-    }
+    /// Changes the block height a pending transaction is dispatched at. None means it is dispatched on the next tick.
+    /// Fails with InvalidTransactionState if the transaction was already broadcast.
+    fn reschedule_tx(
+        &self,
+        tx_id: Txid,
+        target_block_height: Option<BlockHeight>,
+    ) -> Result<CoordinatedTransaction, BitcoinCoordinatorStoreError>;
 
-    fn get_txs_in_progress(
+    /// Returns the pending transactions waiting for a target block height.
+    fn get_scheduled_txs(
         &self,
-    ) -> Result<Vec<CoordinatedTransaction>, BitcoinCoordinatorStoreError> {
-        // Get all transactions in progress which are the ones are not Finalized
-        let txs = self.get_txs()?;
-        let mut txs_filter = Vec::new();
+    ) -> Result<Vec<CoordinatedTransaction>, BitcoinCoordinatorStoreError>;
 
-        for tx_id in txs {
-            let tx = self.get_tx(&tx_id)?;
+    /// Marks the transaction as cancelled. If it was not broadcast yet, it is also removed from the pending list.
+    fn cancel_tx(
+        &self,
+        tx_id: Txid,
+    ) -> Result<CoordinatedTransaction, BitcoinCoordinatorStoreError>;
 
-            if tx.state == TransactionState::ToDispatch
-                || tx.state == TransactionState::Dispatched
-                || tx.state == TransactionState::Confirmed
-            {
-                txs_filter.push(tx);
-            }
-        }
+    /// Marks the transaction as expired. If it was not broadcast yet, it is also removed from the pending list.
+    fn expire_tx(
+        &self,
+        tx_id: Txid,
+    ) -> Result<CoordinatedTransaction, BitcoinCoordinatorStoreError>;
 
-        Ok(txs_filter)
-    }
+    /// Removes the transaction from the pending list, so it is not processed anymore.
+    /// Its record and history are kept.
+    fn untrack_tx(&self, tx_id: Txid) -> Result<(), BitcoinCoordinatorStoreError>;
 
-    fn get_txs_to_dispatch(
+    /// Replaces a dispatched transaction with `replacement`, broadcast at `block_height` spending the same inputs
+    /// with a higher fee. The replacement takes the place of the original, which is not processed anymore,
+    /// and the news of the original are reported for the replacement.
+    fn replace_tx(
         &self,
-    ) -> Result<Vec<CoordinatedTransaction>, BitcoinCoordinatorStoreError> {
-        let txs = self.get_txs()?;
-        let mut txs_filter = Vec::new();
+        tx_id: Txid,
+        replacement: Transaction,
+        block_height: BlockHeight,
+        fee_rate: u64,
+        extra_fee: u64,
+    ) -> Result<CoordinatedTransaction, BitcoinCoordinatorStoreError>;
 
-        for tx_id in txs {
-            let tx = self.get_tx(&tx_id)?;
+    /// Returns the last transaction replacing `tx_id`, or None if it was not replaced.
+    fn get_replacement(&self, tx_id: &Txid) -> Result<Option<Txid>, BitcoinCoordinatorStoreError>;
 
-            if tx.state == TransactionState::ToDispatch {
-                if let Some(retry_info) = &tx.retry_info {
-                    if retry_info.retries_count < self.retry_attempts_sending_tx
-                        && Utc::now().timestamp_millis() as u64 - retry_info.last_retry_timestamp
-                            >= self.retry_interval_seconds * 1000
-                    {
-                        txs_filter.push(tx);
-                    }
-                } else {
-                    txs_filter.push(tx);
-                }
-            }
-        }
+    /// Saves a replacement about to be broadcast, before anything else is written about it.
+    /// `replace_tx` removes it in the same store transaction as the replacement is saved.
+    fn save_parent_replacement_intent(
+        &self,
+        intent: ParentReplacementIntent,
+    ) -> Result<(), BitcoinCoordinatorStoreError>;
 
-        Ok(txs_filter)
-    }
+    /// Removes the intent of the replacement of `replaced_txid`, once it is known it was not broadcast.
+    fn remove_parent_replacement_intent(
+        &self,
+        replaced_txid: &Txid,
+    ) -> Result<(), BitcoinCoordinatorStoreError>;
 
-    fn save_tx(
+    /// Returns the replacements that were about to be broadcast and were neither saved nor discarded.
+    fn get_parent_replacement_intents(
         &self,
-        tx: Transaction,
-        speedup_data: Option<SpeedupData>,
-        target_block_height: Option<BlockHeight>,
-        context: String,
-    ) -> Result<(), BitcoinCoordinatorStoreError> {
-        let key = self.get_key(StoreKey::Transaction(tx.compute_txid()));
+    ) -> Result<Vec<ParentReplacementIntent>, BitcoinCoordinatorStoreError>;
 
-        let tx_info = CoordinatedTransaction::new(
-            tx.clone(),
-            speedup_data,
-            TransactionState::ToDispatch,
-            target_block_height,
-            context,
-        );
+    /// Returns the ids of the pending transactions in the state, in the order they reached it.
+    /// Finalized transactions are not pending, so none are returned for that state.
+    fn get_tx_ids_by_state(
+        &self,
+        state: &TransactionState,
+    ) -> Result<Vec<Txid>, BitcoinCoordinatorStoreError>;
 
-        self.store.set(&key, &tx_info, None)?;
+    /// Returns the transactions saved with the context, in the order they were saved.
+    fn get_txs_by_context(
+        &self,
+        context: &str,
+    ) -> Result<Vec<CoordinatedTransaction>, BitcoinCoordinatorStoreError>;
 
-        let txs_key = self.get_key(StoreKey::PendingTransactionList);
-        let mut txs = self
-            .store
-            .get::<&str, Vec<Txid>>(&txs_key)?
-            .unwrap_or_default();
-        txs.push(tx.compute_txid());
-        self.store.set(&txs_key, &txs, None)?;
+    /// Saves an outpoint to watch until it is spent. Watching it again replaces its context.
+    fn watch_outpoint(
+        &self,
+        outpoint: OutPoint,
+        context: String,
+    ) -> Result<(), BitcoinCoordinatorStoreError>;
 
-        Ok(())
-    }
+    /// Stops watching an outpoint. Returns false if the outpoint was not watched.
+    fn unwatch_outpoint(&self, outpoint: OutPoint) -> Result<bool, BitcoinCoordinatorStoreError>;
 
-    fn remove_tx(&self, tx_id: Txid) -> Result<(), BitcoinCoordinatorStoreError> {
-        let tx_key = self.get_key(StoreKey::Transaction(tx_id));
-        self.store.remove(&tx_key, None)?;
+    fn get_watched_outpoints(&self) -> Result<Vec<WatchedOutpoint>, BitcoinCoordinatorStoreError>;
 
-        let txs_key = self.get_key(StoreKey::PendingTransactionList);
-        let mut txs = self
-            .store
-            .get::<&str, Vec<Txid>>(&txs_key)?
-            .unwrap_or_default();
+    /// Saves an output script to watch for transactions paying to it. Watching it again replaces its context.
+    fn watch_address(
+        &self,
+        script_pubkey: ScriptBuf,
+        context: String,
+    ) -> Result<(), BitcoinCoordinatorStoreError>;
 
-        txs.retain(|id| *id != tx_id);
-        self.store.set(&txs_key, &txs, None)?;
+    /// Stops watching an output script. Returns false if the script was not watched.
+    fn unwatch_address(
+        &self,
+        script_pubkey: &ScriptBuf,
+    ) -> Result<bool, BitcoinCoordinatorStoreError>;
 
-        Ok(())
-    }
+    fn get_watched_addresses(&self) -> Result<Vec<WatchedAddress>, BitcoinCoordinatorStoreError>;
 
-    fn update_tx_to_dispatched(
+    /// Saves a UTXO set watched until all its members are spent. Saving a set again replaces the one with its label.
+    fn watch_utxo_set(&self, set: WatchedUtxoSet) -> Result<(), BitcoinCoordinatorStoreError>;
+
+    /// Stops watching a UTXO set. Returns the removed set, or None if no set has the label.
+    fn unwatch_utxo_set(
         &self,
-        tx_id: Txid,
-        deliver_block_height: u32,
-    ) -> Result<(), BitcoinCoordinatorStoreError> {
-        let mut tx = self.get_tx(&tx_id)?;
+        label: &str,
+    ) -> Result<Option<WatchedUtxoSet>, BitcoinCoordinatorStoreError>;
 
-        // Validate state transition: only ToDispatch can transition to Dispatched
-        if tx.state != TransactionState::ToDispatch {
-            return Err(BitcoinCoordinatorStoreError::InvalidTransactionState);
-        }
+    fn get_watched_utxo_sets(&self) -> Result<Vec<WatchedUtxoSet>, BitcoinCoordinatorStoreError>;
 
-        tx.state = TransactionState::Dispatched;
+    /// Saves a group of transactions. Saving a group again replaces the one with its id.
+    fn save_tx_group(&self, group: GroupStatus) -> Result<(), BitcoinCoordinatorStoreError>;
 
-        tx.broadcast_block_height = Some(deliver_block_height);
+    fn get_tx_groups(&self) -> Result<Vec<GroupStatus>, BitcoinCoordinatorStoreError>;
 
-        let key = self.get_key(StoreKey::Transaction(tx_id));
-        self.store.set(key, tx, None)?;
+    /// Marks the pending members of the groups among `tx_ids` as cancelled.
+    fn cancel_group_members(&self, tx_ids: &[Txid]) -> Result<(), BitcoinCoordinatorStoreError>;
 
-        Ok(())
-    }
+    /// Saves the confirmations after which a monitored transaction is finalized. Saving it again replaces it.
+    fn watch_finality(&self, watch: WatchedFinality) -> Result<(), BitcoinCoordinatorStoreError>;
 
-    fn update_tx_state(
-        &self,
-        tx_id: Txid,
-        new_state: TransactionState,
-    ) -> Result<(), BitcoinCoordinatorStoreError> {
-        let mut tx = self.get_tx(&tx_id)?;
+    /// Removes the finality of a monitored transaction. Returns false if it had none.
+    fn unwatch_finality(&self, tx_id: &Txid) -> Result<bool, BitcoinCoordinatorStoreError>;
 
-        // Validate state transitions
-        let valid_transition = match (&tx.state, &new_state) {
-            // Valid transitions
-            (TransactionState::ToDispatch, TransactionState::Dispatched) => true,
-            (TransactionState::ToDispatch, TransactionState::Failed) => true,
-            (TransactionState::Dispatched, TransactionState::Confirmed) => true,
-            (TransactionState::Confirmed, TransactionState::Finalized) => true,
-            (current, new) if current == new => true,
-            // Invalid transitions
-            _ => false,
-        };
+    fn get_watched_finalities(&self) -> Result<Vec<WatchedFinality>, BitcoinCoordinatorStoreError>;
 
-        if !valid_transition {
-            return Err(BitcoinCoordinatorStoreError::InvalidStateTransition(
-                tx.state.clone(),
-                new_state.clone(),
-                tx_id,
-            ));
-        }
+    /// Persists the context of the RSK peg-in monitoring, the peg-ins are recorded with it.
+    fn watch_rsk_pegins(&self, context: String) -> Result<(), BitcoinCoordinatorStoreError>;
 
-        tx.state = new_state.clone();
+    /// Returns the context of the RSK peg-in monitoring, or None if it was not registered.
+    fn get_rsk_pegin_context(&self) -> Result<Option<String>, BitcoinCoordinatorStoreError>;
 
-        let key = self.get_key(StoreKey::Transaction(tx_id));
-        self.store.set(key, tx, None)?;
+    /// Persists whether the consumer is subscribed to `NewBlock` news.
+    fn set_new_block_subscription(
+        &self,
+        subscribed: bool,
+    ) -> Result<(), BitcoinCoordinatorStoreError>;
 
-        // Remove tx from the list if it is finalized
-        if new_state == TransactionState::Finalized {
-            let txs_key = self.get_key(StoreKey::PendingTransactionList);
-            let mut txs = self
-                .store
-                .get::<&str, Vec<Txid>>(&txs_key)?
-                .unwrap_or_default();
-            txs.retain(|id| *id != tx_id);
-            self.store.set(&txs_key, &txs, None)?;
-        }
+    /// Returns true if the consumer is subscribed to `NewBlock` news.
+    fn is_subscribed_to_new_blocks(&self) -> Result<bool, BitcoinCoordinatorStoreError>;
 
-        Ok(())
-    }
+    /// Persists whether the coordinator is running or was shut down, with the checkpoint of the shutdown.
+    fn set_run_state(&self, state: CoordinatorRunState)
+        -> Result<(), BitcoinCoordinatorStoreError>;
 
-    fn update_news(
+    /// Returns the run state persisted by the last coordinator, None if no coordinator ran on the store.
+    fn get_run_state(&self) -> Result<Option<CoordinatorRunState>, BitcoinCoordinatorStoreError>;
+
+    /// Records the instance as the owner of the store.
+    /// Fails with StoreAlreadyOwned when another instance sent a heartbeat in the last `stale_after_seconds`.
+    /// Returns the previous owner when its heartbeat was stale and the store was taken over.
+    fn acquire_ownership(
         &self,
-        news: CoordinatorNews,
-        current_block_hash: BlockHash,
-    ) -> Result<(), BitcoinCoordinatorStoreError> {
-        match news {
-            CoordinatorNews::InsufficientFunds(tx_id, amount, required) => {
-                let key = self.get_key(StoreKey::InsufficientFundsNewsList);
-                let mut news_list = self
-                    .store
-                    .get::<&str, Vec<(Txid, u64, u64, (BlockHash, bool))>>(&key)?
-                    .unwrap_or_default();
+        instance_id: Uuid,
+        stale_after_seconds: u64,
+    ) -> Result<Option<StoreOwner>, BitcoinCoordinatorStoreError>;
 
-                let is_new_news = news_list.iter().position(|(id, _, _, _)| id == &tx_id);
+    /// Updates the heartbeat of the owner. Fails with StoreOwnershipLost when the instance no longer owns the store.
+    fn refresh_ownership(&self, instance_id: Uuid) -> Result<(), BitcoinCoordinatorStoreError>;
 
-                if let Some(pos) = is_new_news {
-                    let (_, _, _, (existing_block_hash, _)) = &news_list[pos];
-                    if existing_block_hash == &current_block_hash {
-                        // We already have this news, do not update
-                        return Ok(());
-                    } else {
-                        // Replace the notification if the block hash is different
-                        news_list[pos] = (tx_id, amount, required, (current_block_hash, false));
-                    }
-                } else {
-                    // Insert news with current block hash and ack in false
-                    news_list.push((tx_id, amount, required, (current_block_hash, false)));
-                }
+    /// Fails with StoreOwnershipLost when the instance no longer owns the store.
+    fn check_ownership(&self, instance_id: Uuid) -> Result<(), BitcoinCoordinatorStoreError>;
 
-                self.store.set(&key, &news_list, None)?;
-            }
-            CoordinatorNews::DispatchTransactionError(tx_id, context, error) => {
-                let key = self.get_key(StoreKey::DispatchTransactionErrorNewsList);
-                let mut news_list = self
-                    .store
-                    .get::<&str, Vec<(Txid, String, String, (BlockHash, bool))>>(&key)?
-                    .unwrap_or_default();
+    /// Removes the ownership record if the instance still owns the store, so the next instance does not wait.
+    fn release_ownership(&self, instance_id: Uuid) -> Result<(), BitcoinCoordinatorStoreError>;
 
-                let is_new_news = news_list.iter().position(|(id, _, _, _)| id == &tx_id);
+    /// Returns the instance that owns the store, None if no instance holds it.
+    fn get_store_owner(&self) -> Result<Option<StoreOwner>, BitcoinCoordinatorStoreError>;
+
+    /// Returns the fingerprints of the unacknowledged news already delivered to a news subscriber.
+    fn get_delivered_news(
+        &self,
+        subscriber_id: &str,
+    ) -> Result<Vec<String>, BitcoinCoordinatorStoreError>;
+
+    /// Replaces the fingerprints of the news delivered to a news subscriber, its cursor.
+    fn set_delivered_news(
+        &self,
+        subscriber_id: &str,
+        delivered: Vec<String>,
+    ) -> Result<(), BitcoinCoordinatorStoreError>;
+
+    /// Records a detected peg-in. Returns false if it was already recorded in the same block.
+    fn save_detected_pegin(
+        &self,
+        pegin: DetectedPegin,
+    ) -> Result<bool, BitcoinCoordinatorStoreError>;
+
+    /// Returns the peg-ins mined at `since_height` or later, in the order they were detected.
+    fn get_detected_pegins(
+        &self,
+        since_height: BlockHeight,
+    ) -> Result<Vec<DetectedPegin>, BitcoinCoordinatorStoreError>;
+
+    fn update_news(
+        &self,
+        news: CoordinatorNews,
+        current_block_hash: BlockHash,
+    ) -> Result<(), BitcoinCoordinatorStoreError>;
+    fn ack_news(&self, news: AckCoordinatorNews) -> Result<(), BitcoinCoordinatorStoreError>;
+
+    /// Acknowledges a batch of news, loading and writing each news list once.
+    /// Unknown and already acknowledged news are skipped. Returns how many news were acknowledged.
+    fn ack_news_batch(
+        &self,
+        news: Vec<AckCoordinatorNews>,
+    ) -> Result<usize, BitcoinCoordinatorStoreError>;
+
+    /// Returns the unacknowledged news in the order they were reported, across every kind of news.
+    fn get_news(&self) -> Result<Vec<CoordinatorNews>, BitcoinCoordinatorStoreError>;
+
+    /// Returns at most `limit` unacknowledged news with a sequence number of `since_seq` or above, each with its
+    /// sequence number, in the order of get_news. Only the returned news are read from the store.
+    /// The returned flag is true when there are more news after the returned page.
+    fn get_news_page(
+        &self,
+        since_seq: u64,
+        limit: usize,
+    ) -> Result<(Vec<(u64, CoordinatorNews)>, bool), BitcoinCoordinatorStoreError>;
+
+    /// Returns the unacknowledged news of `min_severity` or above, in the order of get_news.
+    /// The severity is the one stored with each news when it was reported, the news below it are not read.
+    fn get_news_filtered(
+        &self,
+        min_severity: Severity,
+    ) -> Result<Vec<CoordinatorNews>, BitcoinCoordinatorStoreError>;
+
+    fn increment_tx_retry_count(&self, txid: Txid) -> Result<(), BitcoinCoordinatorStoreError>;
+
+    /// Moves a confirmed transaction whose block (`orphan_block_hash`) was orphaned back to Dispatched.
+    /// The state, the history and the `TransactionReorged` news are written in a single store transaction.
+    fn reorg_tx(
+        &self,
+        tx_id: Txid,
+        orphan_block_hash: BlockHash,
+        current_block_hash: BlockHash,
+    ) -> Result<CoordinatedTransaction, BitcoinCoordinatorStoreError>;
+
+    /// Records that a dispatched transaction was sent again at `block_height` and returns the rebroadcast attempt.
+    fn record_tx_rebroadcast(
+        &self,
+        tx_id: Txid,
+        block_height: BlockHeight,
+    ) -> Result<u32, BitcoinCoordinatorStoreError>;
+
+    /// Removes the speedup data of a transaction whose speedup output was spent by `spending_txid`,
+    /// so it is not paid by the next CPFPs, and records the spend in its history.
+    fn clear_tx_speedup(
+        &self,
+        tx_id: Txid,
+        spending_txid: Txid,
+    ) -> Result<(), BitcoinCoordinatorStoreError>;
+
+    /// Removes the acknowledged news not recorded in `recent_blocks`, the finalized transactions
+    /// and the finalized speedups older than the last funding checkpoint.
+    /// Unacknowledged news and non finalized speedups are never removed.
+    fn prune(
+        &self,
+        recent_blocks: &HashSet<BlockHash>,
+    ) -> Result<PruneSummary, BitcoinCoordinatorStoreError>;
+
+    /// Drops the entries of the transaction and speedup lists that point at a missing record, left by a
+    /// crash between two writes of an older version. Returns how many entries were dropped.
+    fn repair_dangling_entries(&self) -> Result<u32, BitcoinCoordinatorStoreError>;
+
+    /// Writes the records of the coordinator to a single file, versioned and with a checksum, to move the store
+    /// to another host: transactions, speedups, funding, news, subscriptions and the event journal.
+    /// The owner and the run state belong to the process using the store and are not written, and the
+    /// state indexes are rebuilt on import. Returns the number of records written.
+    fn export_snapshot(&self, path: &Path) -> Result<usize, BitcoinCoordinatorStoreError>;
+
+    /// Imports a snapshot written by export_snapshot, after checking its version and its checksum.
+    /// A store with records of a coordinator is only replaced when `force` is set, its records missing from
+    /// the snapshot are removed. The state indexes are rebuilt from the imported transactions.
+    /// The monitor state is not part of the snapshot, the coordinator monitors the imported transactions and
+    /// subscriptions again on its first tick. Returns the number of records imported.
+    fn import_snapshot(
+        &self,
+        path: &Path,
+        force: bool,
+    ) -> Result<usize, BitcoinCoordinatorStoreError>;
+}
+
+impl BitcoinCoordinatorStore {
+    pub fn new(
+        store: Rc<dyn StoreBackend>,
+        max_unconfirmed_speedups: u32,
+        retry_attempts_sending_tx: u32,
+        retry_interval_seconds: u64,
+    ) -> Result<Self, BitcoinCoordinatorStoreError> {
+        Ok(Self {
+            journal: EventJournal::new(store.clone()),
+            store,
+            max_unconfirmed_speedups: Cell::new(max_unconfirmed_speedups),
+            retry_attempts_sending_tx: Cell::new(retry_attempts_sending_tx),
+            retry_interval_seconds: Cell::new(retry_interval_seconds),
+            clock: Rc::new(SystemClock),
+            funding_group: RefCell::new(None),
+            cipher: None,
+            reads: Cell::new(0),
+            state_indexes_ready: Cell::new(false),
+            news_log_ready: Cell::new(false),
+            write_fault: None,
+        })
+    }
+
+    // A store kept in memory, nothing written to it survives the process and it needs no filesystem access.
+    pub fn in_memory(
+        max_unconfirmed_speedups: u32,
+        retry_attempts_sending_tx: u32,
+        retry_interval_seconds: u64,
+    ) -> Result<Self, BitcoinCoordinatorStoreError> {
+        Self::new(
+            Rc::new(InMemoryStore::new()),
+            max_unconfirmed_speedups,
+            retry_attempts_sending_tx,
+            retry_interval_seconds,
+        )
+    }
+
+    // Number of records read since the store was opened, to check how many reads an operation costs.
+    pub fn reads(&self) -> u64 {
+        self.reads.get()
+    }
+
+    pub fn funding_group(&self) -> Option<String> {
+        self.funding_group.borrow().clone()
+    }
+
+    // Runs `f` with the speedup operations working on the chain of the given funding group, None for the default chain.
+    pub fn with_funding_group<T>(&self, group: Option<&str>, f: impl FnOnce() -> T) -> T {
+        let previous = self
+            .funding_group
+            .replace(group.map(|group| group.to_string()));
+        let result = f();
+        self.funding_group.replace(previous);
+        result
+    }
+
+    pub fn max_unconfirmed_speedups(&self) -> u32 {
+        self.max_unconfirmed_speedups.get()
+    }
+
+    // Replaces the limits of the store, used when the coordinator settings are updated at runtime.
+    pub fn set_limits(
+        &self,
+        max_unconfirmed_speedups: u32,
+        retry_attempts_sending_tx: u32,
+        retry_interval_seconds: u64,
+    ) {
+        self.max_unconfirmed_speedups.set(max_unconfirmed_speedups);
+        self.retry_attempts_sending_tx
+            .set(retry_attempts_sending_tx);
+        self.retry_interval_seconds.set(retry_interval_seconds);
+    }
+
+    // Replaces the clock used to schedule the retries.
+    pub fn with_clock(mut self, clock: Rc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn now_millis(&self) -> u64 {
+        self.clock.now_millis()
+    }
+
+    // Makes the writes chosen by `fault` fail with WriteFailed.
+    pub fn with_write_fault(mut self, fault: Rc<dyn StoreWriteFault>) -> Self {
+        self.write_fault = Some(fault);
+        self
+    }
+
+    // Encrypts the records written from now on. Plaintext records already in the store are still readable.
+    pub fn with_encryption(mut self, cipher: StoreCipher) -> Self {
+        self.journal.set_cipher(cipher.clone());
+        self.cipher = Some(cipher);
+        self
+    }
+
+    pub fn with_encryption_key(self, key: [u8; 32]) -> Self {
+        self.with_encryption(StoreCipher::new(&key))
+    }
+
+    // Reads a record, decrypting it if it was written encrypted and upgrading it if it was written by an older version.
+    pub(crate) fn get_value<K: AsRef<str>, V: Serialize + DeserializeOwned>(
+        &self,
+        key: K,
+    ) -> Result<Option<V>, BitcoinCoordinatorStoreError> {
+        let key = key.as_ref();
+        self.reads.set(self.reads.get() + 1);
+
+        let record = match self.store.get::<&str, serde_json::Value>(key)? {
+            Some(serde_json::Value::String(record)) if StoreCipher::is_encrypted(&record) => {
+                let plaintext = self
+                    .cipher
+                    .as_ref()
+                    .and_then(|cipher| cipher.decrypt(&record))
+                    .ok_or_else(|| {
+                        BitcoinCoordinatorStoreError::DecryptionError(key.to_string())
+                    })?;
+
+                serde_json::from_slice(&plaintext)
+                    .map_err(|e| BitcoinCoordinatorStoreError::SerializationError(e.to_string()))?
+            }
+            Some(record) => record,
+            None => return Ok(None),
+        };
+
+        let (payload, upgraded) = upgrade_record(key, record)?;
+
+        let value = serde_json::from_value(payload)
+            .map_err(|e| BitcoinCoordinatorStoreError::SerializationError(e.to_string()))?;
+
+        // The record is written again with the current version, so it is only upgraded once.
+        if upgraded {
+            self.set_value(key, &value, None)?;
+        }
+
+        Ok(Some(value))
+    }
+
+    // Writes a record in the current version envelope, encrypted when the store has an encryption key.
+    pub(crate) fn set_value<K: AsRef<str>, V: Serialize>(
+        &self,
+        key: K,
+        value: V,
+        transaction_id: Option<Uuid>,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        if let Some(fault) = &self.write_fault {
+            if fault.fail_write(key.as_ref()) {
+                return Err(BitcoinCoordinatorStoreError::WriteFailed(
+                    key.as_ref().to_string(),
+                ));
+            }
+        }
+
+        let record = StoredRecord::new(value);
+
+        let Some(cipher) = &self.cipher else {
+            self.store.set(key, record, transaction_id)?;
+            return Ok(());
+        };
+
+        let key = key.as_ref();
+        let plaintext = serde_json::to_vec(&record)
+            .map_err(|e| BitcoinCoordinatorStoreError::SerializationError(e.to_string()))?;
+        let record = cipher
+            .encrypt(&plaintext)
+            .ok_or_else(|| BitcoinCoordinatorStoreError::EncryptionError(key.to_string()))?;
+
+        self.store.set(key, record, transaction_id)?;
+        Ok(())
+    }
+
+    // Runs the writes of `f` in a single store transaction, committed when it returns Ok and rolled back otherwise.
+    // Reads inside `f` do not see its own writes yet, so each key is read before and written once.
+    pub(crate) fn atomically<T>(
+        &self,
+        f: impl FnOnce(Uuid) -> Result<T, BitcoinCoordinatorStoreError>,
+    ) -> Result<T, BitcoinCoordinatorStoreError> {
+        let transaction_id = self.store.begin_transaction();
+
+        match f(transaction_id) {
+            Ok(value) => {
+                self.store.commit_transaction(transaction_id)?;
+                Ok(value)
+            }
+            Err(e) => {
+                self.store.rollback_transaction(transaction_id)?;
+                Err(e)
+            }
+        }
+    }
+
+    // Changes the state of the transaction. A transaction finalized in a block is appended to the finalized log in
+    // the same store transaction, unless it was already logged in that block.
+    fn write_tx_state(
+        &self,
+        tx_id: Txid,
+        new_state: TransactionState,
+        finalized_in: Option<(BlockHeight, BlockHash)>,
+    ) -> Result<Option<FinalizedTxEntry>, BitcoinCoordinatorStoreError> {
+        let mut tx = self.get_tx(&tx_id)?;
+
+        // A late monitor news can report a finalized transaction as confirmed, it is not moved back.
+        if tx.state == TransactionState::Finalized && new_state == TransactionState::Confirmed {
+            warn!(
+                "Transaction({}) is already Finalized, ignoring the update to Confirmed",
+                tx_id
+            );
+            return Ok(None);
+        }
+
+        // Validate state transitions
+        let valid_transition = match (&tx.state, &new_state) {
+            // Valid transitions
+            (TransactionState::ToDispatch, TransactionState::Dispatched) => true,
+            (TransactionState::ToDispatch, TransactionState::Failed) => true,
+            (TransactionState::Dispatched, TransactionState::Confirmed) => true,
+            (TransactionState::Confirmed, TransactionState::Finalized) => true,
+            (TransactionState::ToDispatch, TransactionState::Cancelled) => true,
+            (TransactionState::Dispatched, TransactionState::Cancelled) => true,
+            // An input of the transaction was spent by a confirmed conflicting transaction.
+            (TransactionState::Dispatched, TransactionState::Failed) => true,
+            // A cancelled transaction that was already in the mempool can still be confirmed.
+            (TransactionState::Cancelled, TransactionState::Confirmed) => true,
+            (TransactionState::ToDispatch, TransactionState::Expired) => true,
+            (TransactionState::Dispatched, TransactionState::Expired) => true,
+            // An expired transaction that was already in the mempool can still be confirmed.
+            (TransactionState::Expired, TransactionState::Confirmed) => true,
+            // Confirmed to Dispatched only happens on a reorg, see reorg_tx.
+            (current, new) if current == new => true,
+            // Invalid transitions
+            _ => false,
+        };
+
+        if !valid_transition {
+            return Err(BitcoinCoordinatorStoreError::InvalidStateTransition(
+                tx.state.clone(),
+                new_state.clone(),
+                tx_id,
+            ));
+        }
+
+        let previous_state = tx.state.clone();
+        tx.state = new_state.clone();
+
+        let state_changed = previous_state != new_state;
+
+        let finalized_entry = match finalized_in {
+            Some((block_height, block_hash)) if new_state == TransactionState::Finalized => {
+                self.new_finalized_entry(&tx, block_height, block_hash)?
+            }
+            _ => None,
+        };
+
+        self.ensure_state_indexes()?;
+
+        self.atomically(|transaction_id| {
+            let key = self.get_key(StoreKey::Transaction(tx_id));
+            self.set_value(key, &tx, Some(transaction_id))?;
+
+            if state_changed && new_state == TransactionState::Finalized {
+                self.push_finalized_tx_stats(&tx, Some(transaction_id))?;
+            }
+
+            if state_changed {
+                let indexed_state =
+                    (new_state != TransactionState::Finalized).then_some(&new_state);
+                self.move_state_index(tx_id, &previous_state, indexed_state, Some(transaction_id))?;
+
+                self.record_tx_events(
+                    tx_id,
+                    vec![TransactionEvent::StateChanged {
+                        from: previous_state.clone(),
+                        to: new_state.clone(),
+                    }],
+                    Some(transaction_id),
+                )?;
+            }
+
+            // Remove tx from the list if it is finalized
+            if new_state == TransactionState::Finalized {
+                let txs_key = self.get_key(StoreKey::PendingTransactionList);
+                let mut txs = self
+                    .get_value::<&str, Vec<Txid>>(&txs_key)?
+                    .unwrap_or_default();
+                txs.retain(|id| *id != tx_id);
+                self.set_value(&txs_key, &txs, Some(transaction_id))?;
+
+                // Keep track of the finalized transactions, so they can be pruned later.
+                if state_changed {
+                    let finalized_key = self.get_key(StoreKey::FinalizedTransactionList);
+                    let mut finalized = self
+                        .get_value::<&str, Vec<Txid>>(&finalized_key)?
+                        .unwrap_or_default();
+                    finalized.push(tx_id);
+                    self.set_value(&finalized_key, &finalized, Some(transaction_id))?;
+                }
+            }
+
+            if let Some((entry, logged_blocks)) = &finalized_entry {
+                self.set_value(
+                    self.get_key(StoreKey::FinalizedLogEntry(entry.seq)),
+                    entry,
+                    Some(transaction_id),
+                )?;
+                self.set_value(
+                    self.get_key(StoreKey::FinalizedLogNextSeq),
+                    entry.seq + 1,
+                    Some(transaction_id),
+                )?;
+                self.set_value(
+                    self.get_key(StoreKey::FinalizedLogBlocks(tx_id)),
+                    logged_blocks,
+                    Some(transaction_id),
+                )?;
+            }
+
+            Ok(finalized_entry.map(|(entry, _)| entry))
+        })
+    }
+
+    // The entry of the transaction in the finalized log, with the blocks it is logged in once the entry is written.
+    // None when it is already logged in the block.
+    fn new_finalized_entry(
+        &self,
+        tx: &CoordinatedTransaction,
+        block_height: BlockHeight,
+        block_hash: BlockHash,
+    ) -> Result<Option<(FinalizedTxEntry, Vec<BlockHash>)>, BitcoinCoordinatorStoreError> {
+        let mut logged_blocks = self
+            .get_value::<String, Vec<BlockHash>>(
+                self.get_key(StoreKey::FinalizedLogBlocks(tx.tx_id)),
+            )?
+            .unwrap_or_default();
+
+        if logged_blocks.contains(&block_hash) {
+            return Ok(None);
+        }
+        logged_blocks.push(block_hash);
+
+        let vsize = tx.tx.vsize() as u64;
+        let own_fee = tx
+            .fee_report
+            .as_ref()
+            .and_then(|report| report.parent_fee)
+            .unwrap_or(vsize);
+
+        let entry = FinalizedTxEntry {
+            seq: self.finalized_next_seq()?,
+            tx_id: tx.tx_id,
+            context: tx.context.clone(),
+            block_height,
+            block_hash,
+            total_fee: own_fee + tx.bump_fees,
+            timestamp: self.now_millis(),
+        };
+
+        Ok(Some((entry, logged_blocks)))
+    }
+
+    fn finalized_next_seq(&self) -> Result<u64, BitcoinCoordinatorStoreError> {
+        Ok(self
+            .get_value::<String, u64>(self.get_key(StoreKey::FinalizedLogNextSeq))?
+            .unwrap_or_default())
+    }
+
+    // Drops the txids of the list at `key` whose record at `record_key` is missing. Returns how many were dropped.
+    pub(crate) fn drop_dangling_entries(
+        &self,
+        key: &str,
+        record_key: impl Fn(Txid) -> String,
+        transaction_id: Uuid,
+    ) -> Result<u32, BitcoinCoordinatorStoreError> {
+        let Some(mut list) = self.get_value::<&str, Vec<Txid>>(key)? else {
+            return Ok(0);
+        };
+
+        let mut dangling = Vec::new();
+        for tx_id in list.iter() {
+            if !dangling.contains(tx_id) && !self.store.has_key(&record_key(*tx_id))? {
+                warn!(
+                    "Dropping Transaction({}) from {}, its record is missing",
+                    tx_id, key
+                );
+                dangling.push(*tx_id);
+            }
+        }
+
+        if dangling.is_empty() {
+            return Ok(0);
+        }
+
+        let len = list.len();
+        list.retain(|tx_id| !dangling.contains(tx_id));
+        self.set_value(key, &list, Some(transaction_id))?;
+
+        Ok((len - list.len()) as u32)
+    }
+
+    // The audit journal, written in the same store transactions as the transaction history.
+    pub fn journal(&self) -> &EventJournal {
+        &self.journal
+    }
+
+    fn get_key(&self, key: StoreKey) -> String {
+        let prefix = "bitcoin_coordinator";
+        match key {
+            StoreKey::PendingTransactionList => format!("{prefix}/tx/list"),
+            StoreKey::FinalizedTransactionList => format!("{prefix}/tx/finalized"),
+            StoreKey::FinalizedTxStatsList => format!("{prefix}/stats/finalized"),
+            StoreKey::FinalizedLogEntry(seq) => format!("{prefix}/finalized/log/{seq:020}"),
+            StoreKey::FinalizedLogNextSeq => format!("{prefix}/finalized/next_seq"),
+            StoreKey::FinalizedLogBlocks(tx_id) => format!("{prefix}/finalized/{tx_id}/blocks"),
+            StoreKey::FinalizedDelivery => format!("{prefix}/finalized/delivery"),
+            StoreKey::Transaction(tx_id) => format!("{prefix}/tx/{tx_id}"),
+            StoreKey::TransactionHistory(tx_id) => format!("{prefix}/tx/{tx_id}/history"),
+            StoreKey::ContextTransactionList(context) => format!("{prefix}/context/{context}/txs"),
+            StoreKey::TransactionStateList(state) => {
+                let state = match state {
+                    TransactionState::ToDispatch => "to_dispatch",
+                    TransactionState::Dispatched => "dispatched",
+                    TransactionState::Confirmed => "confirmed",
+                    TransactionState::Finalized => "finalized",
+                    TransactionState::Failed => "failed",
+                    TransactionState::Cancelled => "cancelled",
+                    TransactionState::Expired => "expired",
+                };
+                format!("{prefix}/tx/state/{state}")
+            }
+            StoreKey::TransactionStateIndexVersion => format!("{prefix}/tx/state/version"),
+            StoreKey::ReplacedTransactionList => format!("{prefix}/tx/replacements"),
+            StoreKey::ParentReplacementIntentList => {
+                format!("{prefix}/tx/replacements/intents")
+            }
+
+            //NEWS
+            StoreKey::InsufficientFundsNewsList => format!("{prefix}/news/insufficient_funds"),
+            StoreKey::SpeedupFeeCapExceededNewsList => {
+                format!("{prefix}/news/speedup_fee_cap_exceeded")
+            }
+            StoreKey::FundingTopUpNewsList => format!("{prefix}/news/funding_topup"),
+            StoreKey::ParentReplacedNewsList => format!("{prefix}/news/parent_replaced"),
+            StoreKey::DispatchTransactionErrorNewsList => {
+                format!("{prefix}/news/dispatch_transaction_error")
+            }
+            StoreKey::DispatchSpeedUpErrorNewsList => {
+                format!("{prefix}/news/dispatch_speed_up_error")
+            }
+            StoreKey::FundingNotFoundNews => format!("{prefix}/news/funding_not_found"),
+            StoreKey::EstimateFeerateTooHighNewsList => {
+                format!("{prefix}/news/estimate_feerate_too_high")
+            }
+            StoreKey::FeeEstimateUnavailableNews => {
+                format!("{prefix}/news/fee_estimate_unavailable")
+            }
+            StoreKey::DispatchPausedHighFeesNews => {
+                format!("{prefix}/news/dispatch_paused_high_fees")
+            }
+            StoreKey::TickPartialFailureNews => format!("{prefix}/news/tick_partial_failure"),
+            StoreKey::NodeUnreachableNews => format!("{prefix}/news/node_unreachable"),
+            StoreKey::NodeRecoveredNews => format!("{prefix}/news/node_recovered"),
+            StoreKey::SettingsUpdatedNews => format!("{prefix}/news/settings_updated"),
+            StoreKey::TransactionAlreadyInMempoolNewsList => {
+                format!("{prefix}/news/transaction_already_in_mempool")
+            }
+            StoreKey::MempoolRejectionNewsList => {
+                format!("{prefix}/news/mempool_rejection")
+            }
+            StoreKey::NetworkErrorNewsList => format!("{prefix}/news/network_error"),
+            StoreKey::DispatchCancelledNewsList => format!("{prefix}/news/dispatch_cancelled"),
+            StoreKey::TransactionExpiredNewsList => format!("{prefix}/news/transaction_expired"),
+            StoreKey::NonStandardAnchorNewsList => format!("{prefix}/news/non_standard_anchor"),
+            StoreKey::RbfEscalationFailedNewsList => {
+                format!("{prefix}/news/rbf_escalation_failed")
+            }
+            StoreKey::MaxRbfAttemptsReachedNewsList => {
+                format!("{prefix}/news/max_rbf_attempts_reached")
+            }
+            StoreKey::TransactionRebroadcastNewsList => {
+                format!("{prefix}/news/transaction_rebroadcast")
+            }
+            StoreKey::MaxRebroadcastAttemptsReachedNewsList => {
+                format!("{prefix}/news/max_rebroadcast_attempts_reached")
+            }
+            StoreKey::SpeedupOrphanedNewsList => format!("{prefix}/news/speedup_orphaned"),
+            StoreKey::SpeedupChainInvalidatedNewsList => {
+                format!("{prefix}/news/speedup_chain_invalidated")
+            }
+            StoreKey::SpeedupCreatedNewsList => format!("{prefix}/news/speedup_created"),
+            StoreKey::TransactionConflictedNewsList => {
+                format!("{prefix}/news/transaction_conflicted")
+            }
+            StoreKey::TransactionReorgedNewsList => format!("{prefix}/news/transaction_reorged"),
+            StoreKey::DispatchScheduledNewsList => format!("{prefix}/news/dispatch_scheduled"),
+            StoreKey::DependencyFailedNewsList => format!("{prefix}/news/dependency_failed"),
+            StoreKey::OutpointSpentNewsList => format!("{prefix}/news/outpoint_spent"),
+            StoreKey::AddressFundedNewsList => format!("{prefix}/news/address_funded"),
+            StoreKey::FundingSpentExternallyNewsList => {
+                format!("{prefix}/news/funding_spent_externally")
+            }
+            StoreKey::FundingExhaustedNewsList => format!("{prefix}/news/funding_exhausted"),
+            StoreKey::FundingTemporarilyUnavailableNewsList => {
+                format!("{prefix}/news/funding_temporarily_unavailable")
+            }
+            StoreKey::AnchorSpentExternallyNewsList => {
+                format!("{prefix}/news/anchor_spent_externally")
+            }
+            StoreKey::FeeOverpaymentNewsList => format!("{prefix}/news/fee_overpayment"),
+            StoreKey::ConfirmationMilestoneNewsList => {
+                format!("{prefix}/news/confirmation_milestone")
+            }
+            StoreKey::CollateralSpentNewsList => format!("{prefix}/news/collateral_spent"),
+            StoreKey::CollateralFullySpentNewsList => {
+                format!("{prefix}/news/collateral_fully_spent")
+            }
+            StoreKey::DispatchDeferredNewsList => format!("{prefix}/news/dispatch_deferred"),
+            StoreKey::TickWorkSkippedNewsList => format!("{prefix}/news/tick_work_skipped"),
+            StoreKey::SpeedupRejectedByPolicyNewsList => {
+                format!("{prefix}/news/speedup_rejected_by_policy")
+            }
+            StoreKey::GroupCompletedNewsList => format!("{prefix}/news/group_completed"),
+            StoreKey::NewBlockNews => format!("{prefix}/news/new_block"),
+            StoreKey::NewsLogEntries => format!("{prefix}/news_log/entries/"),
+            StoreKey::NewsLogEntry(seq, severity) => {
+                format!(
+                    "{prefix}/news_log/entries/{seq:020}/{}",
+                    severity_name(severity)
+                )
+            }
+            StoreKey::NewsLogNextSeq => format!("{prefix}/news_log/next_seq"),
+            StoreKey::WatchedOutpointList => format!("{prefix}/watch/outpoints"),
+            StoreKey::WatchedAddressList => format!("{prefix}/watch/addresses"),
+            StoreKey::WatchedUtxoSetList => format!("{prefix}/watch/utxo_sets"),
+            StoreKey::TxGroupList => format!("{prefix}/watch/groups"),
+            StoreKey::WatchedFinalityList => format!("{prefix}/watch/finality"),
+            StoreKey::RskPeginContext => format!("{prefix}/watch/rsk_pegin"),
+            StoreKey::NewBlockSubscription => format!("{prefix}/watch/new_block"),
+            StoreKey::RunState => format!("{prefix}/run_state"),
+            StoreKey::Owner => format!("{prefix}/owner"),
+            StoreKey::NewsSubscriberDelivered(subscriber_id) => {
+                format!("{prefix}/news/subscriber/{subscriber_id}/delivered")
+            }
+            StoreKey::DetectedPeginList => format!("{prefix}/pegin/detected"),
+        }
+    }
+
+    // Adds the transactions to the index of the transactions saved with the context.
+    fn index_context_txs(
+        &self,
+        context: &str,
+        tx_ids: &[Txid],
+        transaction_id: Option<Uuid>,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        let key = self.get_key(StoreKey::ContextTransactionList(context.to_string()));
+        let mut txs = self.get_value::<&str, Vec<Txid>>(&key)?.unwrap_or_default();
+        let len = txs.len();
+
+        for tx_id in tx_ids {
+            if !txs.contains(tx_id) {
+                txs.push(*tx_id);
+            }
+        }
+
+        if txs.len() != len {
+            self.set_value(&key, &txs, transaction_id)?;
+        }
+
+        Ok(())
+    }
+
+    fn unindex_context_txs(
+        &self,
+        context: &str,
+        tx_ids: &[Txid],
+        transaction_id: Option<Uuid>,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        let key = self.get_key(StoreKey::ContextTransactionList(context.to_string()));
+        let mut txs = self.get_value::<&str, Vec<Txid>>(&key)?.unwrap_or_default();
+
+        txs.retain(|id| !tx_ids.contains(id));
+
+        if txs.is_empty() {
+            self.store.remove(&key, transaction_id)?;
+        } else {
+            self.set_value(&key, &txs, transaction_id)?;
+        }
+
+        Ok(())
+    }
+
+    // Keeps the summary of a finalized transaction for the confirmation stats, dropping the oldest ones
+    // once there are MAX_FINALIZED_TX_STATS.
+    fn push_finalized_tx_stats(
+        &self,
+        tx: &CoordinatedTransaction,
+        transaction_id: Option<Uuid>,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        let stats = match finalized_tx_stats(tx) {
+            Some(stats) => stats,
+            None => return Ok(()),
+        };
+
+        let key = self.get_key(StoreKey::FinalizedTxStatsList);
+        let mut list = self
+            .get_value::<&str, Vec<FinalizedTxStats>>(&key)?
+            .unwrap_or_default();
+        list.push(stats);
+
+        let excess = list.len().saturating_sub(MAX_FINALIZED_TX_STATS);
+        list.drain(..excess);
+
+        self.set_value(&key, &list, transaction_id)
+    }
+
+    // Appends an event to the history of the transaction.
+    pub(crate) fn record_tx_event(
+        &self,
+        tx_id: Txid,
+        event: TransactionEvent,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        self.record_tx_events(tx_id, vec![event], None)
+    }
+
+    // Appends events to the history of the transaction, inside the store transaction if one is given.
+    pub(crate) fn record_tx_events(
+        &self,
+        tx_id: Txid,
+        new_events: Vec<TransactionEvent>,
+        transaction_id: Option<Uuid>,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        let key = self.get_key(StoreKey::TransactionHistory(tx_id));
+        let mut events = self
+            .get_value::<&str, Vec<TransactionHistoryEntry>>(&key)?
+            .unwrap_or_default();
+
+        let journal_events = new_events
+            .iter()
+            .map(|event| JournalEvent::Transaction {
+                tx_id,
+                event: event.clone(),
+            })
+            .collect();
+
+        let timestamp = Utc::now().timestamp_millis() as u64;
+        events.extend(
+            new_events
+                .into_iter()
+                .map(|event| TransactionHistoryEntry { timestamp, event }),
+        );
+
+        self.set_value(&key, &events, transaction_id)?;
+        self.journal.append_events(journal_events, transaction_id)?;
+
+        Ok(())
+    }
+
+    // Fails when the transaction is already waiting to be dispatched or confirmed.
+    // Saving it again would reset its state and could broadcast it or pay for it twice.
+    fn check_not_dispatched(&self, tx_id: Txid) -> Result<(), BitcoinCoordinatorStoreError> {
+        match self.get_tx(&tx_id) {
+            Ok(tx)
+                if tx.state == TransactionState::ToDispatch
+                    || tx.state == TransactionState::Dispatched =>
+            {
+                Err(BitcoinCoordinatorStoreError::TransactionAlreadyDispatched(
+                    tx_id,
+                ))
+            }
+            Ok(_) | Err(BitcoinCoordinatorStoreError::TransactionNotFound(_)) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    // Txids of the pending transactions in the state, in the order they reached it.
+    fn get_state_index(
+        &self,
+        state: &TransactionState,
+    ) -> Result<Vec<Txid>, BitcoinCoordinatorStoreError> {
+        self.ensure_state_indexes()?;
+
+        let key = self.get_key(StoreKey::TransactionStateList(state.clone()));
+        Ok(self.get_value::<&str, Vec<Txid>>(&key)?.unwrap_or_default())
+    }
+
+    fn add_to_state_index(
+        &self,
+        state: &TransactionState,
+        tx_ids: &[Txid],
+        transaction_id: Option<Uuid>,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        let mut index = self.get_state_index(state)?;
+
+        for tx_id in tx_ids {
+            if !index.contains(tx_id) {
+                index.push(*tx_id);
+            }
+        }
+
+        let key = self.get_key(StoreKey::TransactionStateList(state.clone()));
+        self.set_value(&key, &index, transaction_id)
+    }
+
+    // Returns whether any of the transactions was in the index of the state.
+    fn remove_from_state_index(
+        &self,
+        state: &TransactionState,
+        tx_ids: &[Txid],
+        transaction_id: Option<Uuid>,
+    ) -> Result<bool, BitcoinCoordinatorStoreError> {
+        let mut index = self.get_state_index(state)?;
+        let len = index.len();
+
+        index.retain(|id| !tx_ids.contains(id));
+
+        if index.len() == len {
+            return Ok(false);
+        }
+
+        let key = self.get_key(StoreKey::TransactionStateList(state.clone()));
+        self.set_value(&key, &index, transaction_id)?;
+
+        Ok(true)
+    }
+
+    // Moves a pending transaction to the index of its new state. None only removes it, once it is finalized.
+    // Transactions missing from the index of `from` are not pending anymore and are not indexed again.
+    fn move_state_index(
+        &self,
+        tx_id: Txid,
+        from: &TransactionState,
+        to: Option<&TransactionState>,
+        transaction_id: Option<Uuid>,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        if Some(from) == to {
+            return Ok(());
+        }
+
+        if !self.remove_from_state_index(from, &[tx_id], transaction_id)? {
+            return Ok(());
+        }
+
+        if let Some(to) = to {
+            self.add_to_state_index(to, &[tx_id], transaction_id)?;
+        }
+
+        Ok(())
+    }
+
+    // Stores written before the per-state indexes only have the pending list. The indexes are rebuilt from it
+    // the first time they are used, after the store is opened and its encryption key is set.
+    fn ensure_state_indexes(&self) -> Result<(), BitcoinCoordinatorStoreError> {
+        if self.state_indexes_ready.get() {
+            return Ok(());
+        }
+
+        let version_key = self.get_key(StoreKey::TransactionStateIndexVersion);
+
+        if self.get_value::<&str, u32>(&version_key)?.is_none() {
+            let mut indexes: HashMap<String, Vec<Txid>> = HashMap::new();
+
+            for tx_id in self.get_txs()? {
+                let tx = self.get_tx(&tx_id)?;
+                indexes
+                    .entry(self.get_key(StoreKey::TransactionStateList(tx.state)))
+                    .or_default()
+                    .push(tx_id);
+            }
+
+            self.atomically(|transaction_id| {
+                for (key, tx_ids) in indexes.iter() {
+                    self.set_value(key, tx_ids, Some(transaction_id))?;
+                }
+
+                self.set_value(&version_key, STATE_INDEX_VERSION, Some(transaction_id))
+            })?;
+
+            if !indexes.is_empty() {
+                info!(
+                    "Transaction state indexes rebuilt for {} transactions",
+                    indexes.values().map(|tx_ids| tx_ids.len()).sum::<usize>()
+                );
+            }
+        }
+
+        self.state_indexes_ready.set(true);
+
+        Ok(())
+    }
+
+    // Keys of the per-state indexes, derived from the transactions.
+    fn state_index_keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = [
+            TransactionState::ToDispatch,
+            TransactionState::Dispatched,
+            TransactionState::Confirmed,
+            TransactionState::Finalized,
+            TransactionState::Failed,
+            TransactionState::Cancelled,
+            TransactionState::Expired,
+        ]
+        .into_iter()
+        .map(|state| self.get_key(StoreKey::TransactionStateList(state)))
+        .collect();
+        keys.push(self.get_key(StoreKey::TransactionStateIndexVersion));
+
+        keys
+    }
+
+    // Keys of the records a snapshot moves to another host, every key of the coordinator but the owner and the
+    // run state of the process using the store and the state indexes.
+    fn snapshot_keys(&self) -> Result<Vec<String>, BitcoinCoordinatorStoreError> {
+        let mut excluded = self.state_index_keys();
+        excluded.push(self.get_key(StoreKey::Owner));
+        excluded.push(self.get_key(StoreKey::RunState));
+
+        let mut keys: Vec<String> = self
+            .store
+            .keys("bitcoin_coordinator/")?
+            .into_iter()
+            .filter(|key| !excluded.contains(key))
+            .collect();
+        keys.sort();
+
+        Ok(keys)
+    }
+
+    // Replaced txids with the txid of the transaction replacing each one, in the order they were replaced.
+    fn get_replacements(&self) -> Result<Vec<(Txid, Txid)>, BitcoinCoordinatorStoreError> {
+        let key = self.get_key(StoreKey::ReplacedTransactionList);
+        Ok(self
+            .get_value::<&str, Vec<(Txid, Txid)>>(&key)?
+            .unwrap_or_default())
+    }
+
+    // Reports the news of a replaced transaction for its replacement. Only the news lists identifying
+    // each news by the `tx_id` of a dispatched transaction are updated, the news keep their place in the news log.
+    fn remap_tx_news(
+        &self,
+        tx_id: Txid,
+        replacement_txid: Txid,
+        transaction_id: Uuid,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        self.remap_news_list::<DispatchTransactionErrorNews>(
+            StoreKey::DispatchTransactionErrorNewsList,
+            |news| &mut news.tx_id,
+            tx_id,
+            replacement_txid,
+            transaction_id,
+        )?;
+        self.remap_news_list::<TransactionAlreadyInMempoolNews>(
+            StoreKey::TransactionAlreadyInMempoolNewsList,
+            |news| &mut news.tx_id,
+            tx_id,
+            replacement_txid,
+            transaction_id,
+        )?;
+        self.remap_news_list::<MempoolRejectionNews>(
+            StoreKey::MempoolRejectionNewsList,
+            |news| &mut news.tx_id,
+            tx_id,
+            replacement_txid,
+            transaction_id,
+        )?;
+        self.remap_news_list::<NetworkErrorNews>(
+            StoreKey::NetworkErrorNewsList,
+            |news| &mut news.tx_id,
+            tx_id,
+            replacement_txid,
+            transaction_id,
+        )?;
+        self.remap_news_list::<TransactionRebroadcastNews>(
+            StoreKey::TransactionRebroadcastNewsList,
+            |news| &mut news.tx_id,
+            tx_id,
+            replacement_txid,
+            transaction_id,
+        )?;
+        self.remap_news_list::<MaxRebroadcastAttemptsReachedNews>(
+            StoreKey::MaxRebroadcastAttemptsReachedNewsList,
+            |news| &mut news.tx_id,
+            tx_id,
+            replacement_txid,
+            transaction_id,
+        )?;
+        self.remap_news_list::<TransactionConflictedNews>(
+            StoreKey::TransactionConflictedNewsList,
+            |news| &mut news.tx_id,
+            tx_id,
+            replacement_txid,
+            transaction_id,
+        )?;
+        self.remap_news_list::<TransactionReorgedNews>(
+            StoreKey::TransactionReorgedNewsList,
+            |news| &mut news.tx_id,
+            tx_id,
+            replacement_txid,
+            transaction_id,
+        )?;
+        self.remap_news_list::<DispatchScheduledNews>(
+            StoreKey::DispatchScheduledNewsList,
+            |news| &mut news.tx_id,
+            tx_id,
+            replacement_txid,
+            transaction_id,
+        )?;
+
+        Ok(())
+    }
+
+    // Replaces `tx_id` with `replacement_txid` in the news of the list stored at `key`.
+    fn remap_news_list<T>(
+        &self,
+        key: StoreKey,
+        news_tx_id: impl Fn(&mut T) -> &mut Txid,
+        tx_id: Txid,
+        replacement_txid: Txid,
+        transaction_id: Uuid,
+    ) -> Result<(), BitcoinCoordinatorStoreError>
+    where
+        T: Serialize + DeserializeOwned + Clone + Into<CoordinatorNews>,
+    {
+        let key = self.get_key(key);
+        let Some(mut news_list) = self.get_value::<&str, Vec<NewsRecord<T>>>(&key)? else {
+            return Ok(());
+        };
+
+        let mut remapped = false;
+        for record in news_list.iter_mut() {
+            let id = news_tx_id(&mut record.news);
+
+            if *id == tx_id {
+                *id = replacement_txid;
+                remapped = true;
+            }
+        }
+
+        if remapped {
+            self.save_news_list(&key, &mut news_list, Some(transaction_id))?;
+        }
+
+        Ok(())
+    }
+
+    // Returns why a transaction that failed to be sent is not sent again yet, or None when it can be sent.
+    fn retry_pending_reason(&self, tx: &CoordinatedTransaction) -> Option<PendingReason> {
+        let retry_info = tx.retry_info.as_ref()?;
+
+        if retry_info.retries_count >= self.retry_attempts_sending_tx.get() {
+            return Some(PendingReason::RetriesExhausted);
+        }
+
+        let elapsed = self
+            .now_millis()
+            .saturating_sub(retry_info.last_retry_timestamp);
+
+        // The first failure counts as a retry, it waits the retry interval.
+        let backoff = retry_backoff_millis(
+            self.retry_interval_seconds.get(),
+            retry_info.retries_count.saturating_sub(1),
+        );
+
+        (elapsed < backoff).then_some(PendingReason::RetryBackoff)
+    }
+
+    // Whether a dependency of the transaction is not confirmed yet. Dependencies replaced with a higher fee (RBF)
+    // are confirmed through their last replacement, and removed dependencies are not waited for.
+    fn has_unconfirmed_dependencies(
+        &self,
+        tx: &CoordinatedTransaction,
+    ) -> Result<bool, BitcoinCoordinatorStoreError> {
+        for dependency in tx.dispatch_options.depends_on.iter() {
+            let dependency = self.get_replacement(dependency)?.unwrap_or(*dependency);
+
+            match self.get_tx(&dependency) {
+                Ok(dependency_tx)
+                    if dependency_tx.state != TransactionState::Confirmed
+                        && dependency_tx.state != TransactionState::Finalized =>
+                {
+                    return Ok(true)
+                }
+                Ok(_) | Err(BitcoinCoordinatorStoreError::TransactionNotFound(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(false)
+    }
+
+    // Returns the transaction reorged news list with the news of `tx_id` added.
+    // A news already reported for the same orphaned block is left as it is.
+    fn transaction_reorged_news_list(
+        &self,
+        tx_id: Txid,
+        orphan_block_hash: BlockHash,
+        context: String,
+        current_block_hash: BlockHash,
+    ) -> Result<Vec<NewsRecord<TransactionReorgedNews>>, BitcoinCoordinatorStoreError> {
+        let key = self.get_key(StoreKey::TransactionReorgedNewsList);
+        let mut news_list = self
+            .get_value::<&str, Vec<NewsRecord<TransactionReorgedNews>>>(&key)?
+            .unwrap_or_default();
+
+        let news = NewsRecord::new(
+            TransactionReorgedNews {
+                tx_id,
+                orphan_block_hash,
+                context,
+            },
+            current_block_hash,
+        );
+
+        match news_list
+            .iter()
+            .position(|record| record.news.tx_id == tx_id)
+        {
+            Some(pos) => {
+                if news_list[pos].news.orphan_block_hash != orphan_block_hash {
+                    news_list[pos] = news;
+                }
+            }
+            None => news_list.push(news),
+        }
+
+        Ok(news_list)
+    }
+
+    // Adds the news to the list stored at `key`. The same news, found with `is_same`, is only reported again
+    // in another block, replacing the previous one.
+    fn report_news_in_block<T, F>(
+        &self,
+        key: StoreKey,
+        news: T,
+        current_block_hash: BlockHash,
+        is_same: F,
+    ) -> Result<(), BitcoinCoordinatorStoreError>
+    where
+        T: Serialize + DeserializeOwned + Clone + Into<CoordinatorNews>,
+        F: Fn(&T) -> bool,
+    {
+        let key = self.get_key(key);
+        let mut news_list = self
+            .get_value::<&str, Vec<NewsRecord<T>>>(&key)?
+            .unwrap_or_default();
+
+        match news_list.iter().position(|record| is_same(&record.news)) {
+            Some(pos) if news_list[pos].block_hash == current_block_hash => return Ok(()),
+            Some(pos) => news_list[pos] = NewsRecord::new(news, current_block_hash),
+            None => news_list.push(NewsRecord::new(news, current_block_hash)),
+        }
+
+        self.save_news_list(&key, &mut news_list, None)
+    }
+
+    // Adds the news to the list stored at `key`, unless the same news, found with `is_same`, was already reported.
+    fn report_news_once<T, F>(
+        &self,
+        key: StoreKey,
+        news: T,
+        current_block_hash: BlockHash,
+        is_same: F,
+    ) -> Result<(), BitcoinCoordinatorStoreError>
+    where
+        T: Serialize + DeserializeOwned + Clone + Into<CoordinatorNews>,
+        F: Fn(&T) -> bool,
+    {
+        let key = self.get_key(key);
+        let mut news_list = self
+            .get_value::<&str, Vec<NewsRecord<T>>>(&key)?
+            .unwrap_or_default();
+
+        if news_list.iter().any(|record| is_same(&record.news)) {
+            return Ok(());
+        }
+
+        news_list.push(NewsRecord::new(news, current_block_hash));
+        self.save_news_list(&key, &mut news_list, None)
+    }
+
+    // Flags as acknowledged the news in the list stored at `key` whose id is in `ids`.
+    // The list is written once, and only if something changed.
+    // Returns how many news were acknowledged.
+    fn ack_news_list<T, K, I>(
+        &self,
+        key: StoreKey,
+        ids: &[K],
+        news_id: I,
+    ) -> Result<usize, BitcoinCoordinatorStoreError>
+    where
+        T: Serialize + DeserializeOwned,
+        K: PartialEq,
+        I: Fn(&T) -> K,
+    {
+        let key = self.get_key(key);
+        let mut news_list = self
+            .get_value::<&str, Vec<NewsRecord<T>>>(&key)?
+            .unwrap_or_default();
+
+        let mut acknowledged = 0;
+
+        for record in news_list.iter_mut() {
+            // Already acknowledged news are skipped.
+            if !record.acknowledged && ids.contains(&news_id(&record.news)) {
+                record.acknowledged = true;
+                self.unlog_news(record, None)?;
+                acknowledged += 1;
+            }
+        }
+
+        if acknowledged > 0 {
+            self.set_value(&key, &news_list, None)?;
+        }
+
+        Ok(acknowledged)
+    }
+
+    // Flags as acknowledged the news stored alone at `key`. Returns 1 if it was acknowledged.
+    fn ack_news_record<T>(&self, key: StoreKey) -> Result<usize, BitcoinCoordinatorStoreError>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        let key = self.get_key(key);
+
+        match self.get_value::<&str, NewsRecord<T>>(&key)? {
+            Some(mut record) if !record.acknowledged => {
+                record.acknowledged = true;
+                self.unlog_news(&record, None)?;
+                self.set_value(&key, &record, None)?;
+                Ok(1)
+            }
+            _ => Ok(0),
+        }
+    }
+
+    // Removes from the list stored at `key` the acknowledged news whose block is not in `recent_blocks`.
+    // The list is written once, and only if something was removed.
+    // Returns how many news were removed.
+    fn prune_news_list<T>(
+        &self,
+        key: StoreKey,
+        recent_blocks: &HashSet<BlockHash>,
+    ) -> Result<u32, BitcoinCoordinatorStoreError>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        let key = self.get_key(key);
+        let mut news_list = self
+            .get_value::<&str, Vec<NewsRecord<T>>>(&key)?
+            .unwrap_or_default();
+
+        let len = news_list.len();
+
+        news_list
+            .retain(|record| !record.acknowledged || recent_blocks.contains(&record.block_hash));
+
+        let pruned = (len - news_list.len()) as u32;
+
+        if pruned > 0 {
+            self.set_value(&key, &news_list, None)?;
+        }
+
+        Ok(pruned)
+    }
+
+    // Removes the news stored alone at `key` if it was acknowledged and its block is not in `recent_blocks`.
+    // Returns 1 if it was removed.
+    fn prune_news_record<T>(
+        &self,
+        key: StoreKey,
+        recent_blocks: &HashSet<BlockHash>,
+    ) -> Result<u32, BitcoinCoordinatorStoreError>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        let key = self.get_key(key);
+
+        match self.get_value::<&str, NewsRecord<T>>(&key)? {
+            Some(record) if record.acknowledged && !recent_blocks.contains(&record.block_hash) => {
+                self.store.remove(&key, None)?;
+                Ok(1)
+            }
+            _ => Ok(0),
+        }
+    }
+
+    fn prune_news(
+        &self,
+        recent_blocks: &HashSet<BlockHash>,
+    ) -> Result<u32, BitcoinCoordinatorStoreError> {
+        let mut pruned = 0;
+
+        pruned += self.prune_news_list::<InsufficientFundsNews>(
+            StoreKey::InsufficientFundsNewsList,
+            recent_blocks,
+        )?;
+        pruned += self.prune_news_list::<SpeedupFeeCapExceededNews>(
+            StoreKey::SpeedupFeeCapExceededNewsList,
+            recent_blocks,
+        )?;
+        pruned += self
+            .prune_news_list::<FundingTopUpNews>(StoreKey::FundingTopUpNewsList, recent_blocks)?;
+        pruned += self.prune_news_list::<ParentReplacedNews>(
+            StoreKey::ParentReplacedNewsList,
+            recent_blocks,
+        )?;
+        pruned += self.prune_news_list::<DispatchTransactionErrorNews>(
+            StoreKey::DispatchTransactionErrorNewsList,
+            recent_blocks,
+        )?;
+        pruned += self.prune_news_list::<DispatchSpeedUpErrorNews>(
+            StoreKey::DispatchSpeedUpErrorNewsList,
+            recent_blocks,
+        )?;
+        pruned += self.prune_news_list::<EstimateFeerateTooHighNews>(
+            StoreKey::EstimateFeerateTooHighNewsList,
+            recent_blocks,
+        )?;
+        pruned += self.prune_news_list::<TransactionAlreadyInMempoolNews>(
+            StoreKey::TransactionAlreadyInMempoolNewsList,
+            recent_blocks,
+        )?;
+        pruned += self.prune_news_list::<MempoolRejectionNews>(
+            StoreKey::MempoolRejectionNewsList,
+            recent_blocks,
+        )?;
+        pruned += self
+            .prune_news_list::<NetworkErrorNews>(StoreKey::NetworkErrorNewsList, recent_blocks)?;
+        pruned += self.prune_news_list::<DispatchCancelledNews>(
+            StoreKey::DispatchCancelledNewsList,
+            recent_blocks,
+        )?;
+        pruned += self.prune_news_list::<TransactionExpiredNews>(
+            StoreKey::TransactionExpiredNewsList,
+            recent_blocks,
+        )?;
+        pruned += self.prune_news_list::<NonStandardAnchorNews>(
+            StoreKey::NonStandardAnchorNewsList,
+            recent_blocks,
+        )?;
+        pruned += self.prune_news_list::<RbfEscalationFailedNews>(
+            StoreKey::RbfEscalationFailedNewsList,
+            recent_blocks,
+        )?;
+        pruned += self.prune_news_list::<MaxRbfAttemptsReachedNews>(
+            StoreKey::MaxRbfAttemptsReachedNewsList,
+            recent_blocks,
+        )?;
+        pruned += self.prune_news_list::<TransactionRebroadcastNews>(
+            StoreKey::TransactionRebroadcastNewsList,
+            recent_blocks,
+        )?;
+        pruned += self.prune_news_list::<MaxRebroadcastAttemptsReachedNews>(
+            StoreKey::MaxRebroadcastAttemptsReachedNewsList,
+            recent_blocks,
+        )?;
+        pruned += self.prune_news_list::<SpeedupOrphanedNews>(
+            StoreKey::SpeedupOrphanedNewsList,
+            recent_blocks,
+        )?;
+        pruned += self.prune_news_list::<SpeedupChainInvalidatedNews>(
+            StoreKey::SpeedupChainInvalidatedNewsList,
+            recent_blocks,
+        )?;
+        pruned += self.prune_news_list::<SpeedupCreatedNews>(
+            StoreKey::SpeedupCreatedNewsList,
+            recent_blocks,
+        )?;
+        pruned += self.prune_news_list::<TransactionConflictedNews>(
+            StoreKey::TransactionConflictedNewsList,
+            recent_blocks,
+        )?;
+        pruned += self.prune_news_list::<TransactionReorgedNews>(
+            StoreKey::TransactionReorgedNewsList,
+            recent_blocks,
+        )?;
+        pruned += self.prune_news_list::<DispatchScheduledNews>(
+            StoreKey::DispatchScheduledNewsList,
+            recent_blocks,
+        )?;
+        pruned += self.prune_news_list::<DependencyFailedNews>(
+            StoreKey::DependencyFailedNewsList,
+            recent_blocks,
+        )?;
+        pruned += self
+            .prune_news_list::<OutpointSpentNews>(StoreKey::OutpointSpentNewsList, recent_blocks)?;
+        pruned += self
+            .prune_news_list::<AddressFundedNews>(StoreKey::AddressFundedNewsList, recent_blocks)?;
+        pruned += self.prune_news_list::<FundingSpentExternallyNews>(
+            StoreKey::FundingSpentExternallyNewsList,
+            recent_blocks,
+        )?;
+        pruned += self.prune_news_list::<FundingExhaustedNews>(
+            StoreKey::FundingExhaustedNewsList,
+            recent_blocks,
+        )?;
+        pruned += self.prune_news_list::<FundingTemporarilyUnavailableNews>(
+            StoreKey::FundingTemporarilyUnavailableNewsList,
+            recent_blocks,
+        )?;
+        pruned += self.prune_news_list::<AnchorSpentExternallyNews>(
+            StoreKey::AnchorSpentExternallyNewsList,
+            recent_blocks,
+        )?;
+        pruned += self.prune_news_list::<FeeOverpaymentNews>(
+            StoreKey::FeeOverpaymentNewsList,
+            recent_blocks,
+        )?;
+        pruned += self.prune_news_list::<ConfirmationMilestoneNews>(
+            StoreKey::ConfirmationMilestoneNewsList,
+            recent_blocks,
+        )?;
+        pruned += self.prune_news_list::<CollateralSpentNews>(
+            StoreKey::CollateralSpentNewsList,
+            recent_blocks,
+        )?;
+        pruned += self.prune_news_list::<CollateralFullySpentNews>(
+            StoreKey::CollateralFullySpentNewsList,
+            recent_blocks,
+        )?;
+        pruned += self.prune_news_list::<DispatchDeferredNews>(
+            StoreKey::DispatchDeferredNewsList,
+            recent_blocks,
+        )?;
+        pruned += self.prune_news_list::<TickWorkSkippedNews>(
+            StoreKey::TickWorkSkippedNewsList,
+            recent_blocks,
+        )?;
+        pruned += self.prune_news_list::<SpeedupRejectedByPolicyNews>(
+            StoreKey::SpeedupRejectedByPolicyNewsList,
+            recent_blocks,
+        )?;
+        pruned += self.prune_news_list::<GroupCompletedNews>(
+            StoreKey::GroupCompletedNewsList,
+            recent_blocks,
+        )?;
+
+        pruned += self.prune_news_record::<FundingNotFoundNews>(
+            StoreKey::FundingNotFoundNews,
+            recent_blocks,
+        )?;
+        pruned += self.prune_news_record::<FeeEstimateUnavailableNews>(
+            StoreKey::FeeEstimateUnavailableNews,
+            recent_blocks,
+        )?;
+        pruned += self.prune_news_record::<DispatchPausedHighFeesNews>(
+            StoreKey::DispatchPausedHighFeesNews,
+            recent_blocks,
+        )?;
+        pruned += self.prune_news_record::<TickPartialFailureNews>(
+            StoreKey::TickPartialFailureNews,
+            recent_blocks,
+        )?;
+        pruned += self.prune_news_record::<NodeUnreachableNews>(
+            StoreKey::NodeUnreachableNews,
+            recent_blocks,
+        )?;
+        pruned += self
+            .prune_news_record::<NodeRecoveredNews>(StoreKey::NodeRecoveredNews, recent_blocks)?;
+        pruned += self.prune_news_record::<SettingsUpdatedNews>(
+            StoreKey::SettingsUpdatedNews,
+            recent_blocks,
+        )?;
+        pruned += self.prune_news_record::<NewBlockNews>(StoreKey::NewBlockNews, recent_blocks)?;
+
+        Ok(pruned)
+    }
+
+    // Removes the records and the history of the transactions finalized since the last prune.
+    fn prune_finalized_txs(&self) -> Result<u32, BitcoinCoordinatorStoreError> {
+        let key = self.get_key(StoreKey::FinalizedTransactionList);
+        let txs = self.get_value::<&str, Vec<Txid>>(&key)?.unwrap_or_default();
+
+        let mut context_txs: HashMap<String, Vec<Txid>> = HashMap::new();
+        for tx_id in txs.iter() {
+            if let Some(tx) = self.get_value::<&str, CoordinatedTransaction>(
+                &self.get_key(StoreKey::Transaction(*tx_id)),
+            )? {
+                context_txs.entry(tx.context).or_default().push(*tx_id);
+            }
+        }
+
+        self.atomically(|transaction_id| {
+            for (context, tx_ids) in context_txs.iter() {
+                self.unindex_context_txs(context, tx_ids, Some(transaction_id))?;
+            }
+
+            for tx_id in txs.iter() {
+                self.store.remove(
+                    self.get_key(StoreKey::Transaction(*tx_id)),
+                    Some(transaction_id),
+                )?;
+                self.store.remove(
+                    self.get_key(StoreKey::TransactionHistory(*tx_id)),
+                    Some(transaction_id),
+                )?;
+            }
+
+            self.store.remove(&key, Some(transaction_id))?;
+
+            Ok(txs.len() as u32)
+        })
+    }
+
+    fn get_txs(&self) -> Result<Vec<Txid>, BitcoinCoordinatorStoreError> {
+        let key = self.get_key(StoreKey::PendingTransactionList);
+
+        let all_txs = self.get_value::<&str, Vec<Txid>>(&key)?;
+
+        match all_txs {
+            Some(txs) => Ok(txs),
+            None => Ok(vec![]),
+        }
+    }
+
+    // Writes a news list with the log entries of its news. The news not logged yet take the next sequence
+    // numbers, the entries of the news acknowledged or replaced are removed.
+    fn save_news_list<T>(
+        &self,
+        key: &str,
+        news_list: &mut [NewsRecord<T>],
+        transaction_id: Option<Uuid>,
+    ) -> Result<(), BitcoinCoordinatorStoreError>
+    where
+        T: Serialize + DeserializeOwned + Clone + Into<CoordinatorNews>,
+    {
+        let previous = self
+            .get_value::<&str, Vec<NewsRecord<T>>>(key)?
+            .unwrap_or_default();
+
+        self.with_news_seq(transaction_id, |next_seq| {
+            self.log_news(
+                &previous,
+                news_list,
+                next_seq,
+                coordinator_news::<T>,
+                transaction_id,
+            )
+        })?;
+
+        self.set_value(key, &*news_list, transaction_id)
+    }
+
+    // Writes the news stored alone at `key` with its log entry, replacing the entry of the previous one.
+    fn save_news_record<T>(
+        &self,
+        key: &str,
+        record: NewsRecord<T>,
+        to_news: impl Fn(&NewsRecord<T>) -> CoordinatorNews,
+        transaction_id: Option<Uuid>,
+    ) -> Result<(), BitcoinCoordinatorStoreError>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        let previous = self.get_value::<&str, NewsRecord<T>>(key)?;
+        let mut current = [record];
+
+        self.with_news_seq(transaction_id, |next_seq| {
+            self.log_news(
+                previous.as_slice(),
+                &mut current,
+                next_seq,
+                &to_news,
+                transaction_id,
+            )
+        })?;
+
+        let [record] = current;
+        self.set_value(key, &record, transaction_id)
+    }
+
+    // Runs `f` with the next sequence number of the news log, saved again if `f` took numbers.
+    fn with_news_seq(
+        &self,
+        transaction_id: Option<Uuid>,
+        f: impl FnOnce(&mut u64) -> Result<(), BitcoinCoordinatorStoreError>,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        let key = self.get_key(StoreKey::NewsLogNextSeq);
+        let next_seq = self.get_value::<&str, u64>(&key)?.unwrap_or_default();
+
+        let mut seq = next_seq;
+        f(&mut seq)?;
+
+        if seq != next_seq {
+            self.set_value(&key, seq, transaction_id)?;
+        }
+
+        Ok(())
+    }
+
+    // Updates the news log for the news of a list, or of a record, going from `previous` to `current`.
+    // Pending news not logged yet are numbered from `next_seq`, and the news changed in place are logged again
+    // with the same number. The entries of the news acknowledged or removed since `previous` are removed.
+    fn log_news<T>(
+        &self,
+        previous: &[NewsRecord<T>],
+        current: &mut [NewsRecord<T>],
+        next_seq: &mut u64,
+        to_news: impl Fn(&NewsRecord<T>) -> CoordinatorNews,
+        transaction_id: Option<Uuid>,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        for record in previous.iter().filter(|record| !record.acknowledged) {
+            let still_pending = current.iter().any(|current| {
+                current.seq.is_some() && current.seq == record.seq && !current.acknowledged
+            });
+
+            if !still_pending {
+                self.unlog_news(record, transaction_id)?;
+            }
+        }
+
+        for record in current.iter_mut().filter(|record| !record.acknowledged) {
+            let news = to_news(record);
+
+            let unchanged = record.seq.is_some()
+                && previous.iter().any(|previous| {
+                    previous.seq == record.seq
+                        && !previous.acknowledged
+                        && to_news(previous) == news
+                });
+
+            if unchanged {
+                continue;
+            }
+
+            let seq = *record.seq.get_or_insert_with(|| {
+                *next_seq += 1;
+                *next_seq - 1
+            });
+            let severity = *record.severity.get_or_insert_with(|| news.severity());
+
+            self.set_value(
+                self.get_key(StoreKey::NewsLogEntry(seq, severity)),
+                &news,
+                transaction_id,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    // Removes the log entry of a news, once it is acknowledged or replaced.
+    fn unlog_news<T>(
+        &self,
+        record: &NewsRecord<T>,
+        transaction_id: Option<Uuid>,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        // Logged news always have their severity, it is set when they are logged.
+        if let (Some(seq), Some(severity)) = (record.seq, record.severity) {
+            self.store.remove(
+                self.get_key(StoreKey::NewsLogEntry(seq, severity)),
+                transaction_id,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    // Logs the pending news of the list stored at `key` that are not in the news log yet.
+    fn log_news_list<T>(
+        &self,
+        key: StoreKey,
+        next_seq: &mut u64,
+        transaction_id: Uuid,
+    ) -> Result<(), BitcoinCoordinatorStoreError>
+    where
+        T: Serialize + DeserializeOwned + Clone + Into<CoordinatorNews>,
+    {
+        let key = self.get_key(key);
+        let Some(mut news_list) = self.get_value::<&str, Vec<NewsRecord<T>>>(&key)? else {
+            return Ok(());
+        };
+
+        self.log_news(
+            &[],
+            &mut news_list,
+            next_seq,
+            coordinator_news::<T>,
+            Some(transaction_id),
+        )?;
+
+        self.set_value(&key, &news_list, Some(transaction_id))
+    }
+
+    // Logs the news stored alone at `key` if it is pending and not in the news log yet.
+    fn log_news_record<T>(
+        &self,
+        key: StoreKey,
+        to_news: impl Fn(&NewsRecord<T>) -> CoordinatorNews,
+        next_seq: &mut u64,
+        transaction_id: Uuid,
+    ) -> Result<(), BitcoinCoordinatorStoreError>
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        let key = self.get_key(key);
+        let Some(record) = self.get_value::<&str, NewsRecord<T>>(&key)? else {
+            return Ok(());
+        };
+
+        let mut current = [record];
+        self.log_news(&[], &mut current, next_seq, to_news, Some(transaction_id))?;
+
+        let [record] = current;
+        self.set_value(&key, &record, Some(transaction_id))
+    }
+
+    // Stores written before the news log only have the news lists. The log is built from them the first time it
+    // is used, numbering the pending news in the order the lists were read before: grouped by kind.
+    fn ensure_news_log(&self) -> Result<(), BitcoinCoordinatorStoreError> {
+        if self.news_log_ready.get() {
+            return Ok(());
+        }
+
+        let next_seq_key = self.get_key(StoreKey::NewsLogNextSeq);
+
+        if self.get_value::<&str, u64>(&next_seq_key)?.is_none() {
+            let mut next_seq = 0;
+
+            self.atomically(|transaction_id| {
+                self.log_news_list::<InsufficientFundsNews>(
+                    StoreKey::InsufficientFundsNewsList,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_list::<FundingTopUpNews>(
+                    StoreKey::FundingTopUpNewsList,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_list::<ParentReplacedNews>(
+                    StoreKey::ParentReplacedNewsList,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_list::<SpeedupFeeCapExceededNews>(
+                    StoreKey::SpeedupFeeCapExceededNewsList,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_list::<DispatchTransactionErrorNews>(
+                    StoreKey::DispatchTransactionErrorNewsList,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_list::<DispatchSpeedUpErrorNews>(
+                    StoreKey::DispatchSpeedUpErrorNewsList,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_record::<FundingNotFoundNews>(
+                    StoreKey::FundingNotFoundNews,
+                    coordinator_news::<FundingNotFoundNews>,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_list::<EstimateFeerateTooHighNews>(
+                    StoreKey::EstimateFeerateTooHighNewsList,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_record::<FeeEstimateUnavailableNews>(
+                    StoreKey::FeeEstimateUnavailableNews,
+                    coordinator_news::<FeeEstimateUnavailableNews>,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_record::<DispatchPausedHighFeesNews>(
+                    StoreKey::DispatchPausedHighFeesNews,
+                    coordinator_news::<DispatchPausedHighFeesNews>,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_record::<TickPartialFailureNews>(
+                    StoreKey::TickPartialFailureNews,
+                    coordinator_news::<TickPartialFailureNews>,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_record::<NodeUnreachableNews>(
+                    StoreKey::NodeUnreachableNews,
+                    coordinator_news::<NodeUnreachableNews>,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_record::<NodeRecoveredNews>(
+                    StoreKey::NodeRecoveredNews,
+                    coordinator_news::<NodeRecoveredNews>,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_record::<SettingsUpdatedNews>(
+                    StoreKey::SettingsUpdatedNews,
+                    coordinator_news::<SettingsUpdatedNews>,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_list::<TransactionAlreadyInMempoolNews>(
+                    StoreKey::TransactionAlreadyInMempoolNewsList,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_list::<MempoolRejectionNews>(
+                    StoreKey::MempoolRejectionNewsList,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_list::<NetworkErrorNews>(
+                    StoreKey::NetworkErrorNewsList,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_list::<DispatchCancelledNews>(
+                    StoreKey::DispatchCancelledNewsList,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_list::<TransactionExpiredNews>(
+                    StoreKey::TransactionExpiredNewsList,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_list::<NonStandardAnchorNews>(
+                    StoreKey::NonStandardAnchorNewsList,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_list::<RbfEscalationFailedNews>(
+                    StoreKey::RbfEscalationFailedNewsList,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_list::<MaxRbfAttemptsReachedNews>(
+                    StoreKey::MaxRbfAttemptsReachedNewsList,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_list::<TransactionRebroadcastNews>(
+                    StoreKey::TransactionRebroadcastNewsList,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_list::<MaxRebroadcastAttemptsReachedNews>(
+                    StoreKey::MaxRebroadcastAttemptsReachedNewsList,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_list::<SpeedupOrphanedNews>(
+                    StoreKey::SpeedupOrphanedNewsList,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_list::<SpeedupChainInvalidatedNews>(
+                    StoreKey::SpeedupChainInvalidatedNewsList,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_list::<SpeedupCreatedNews>(
+                    StoreKey::SpeedupCreatedNewsList,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_list::<TransactionConflictedNews>(
+                    StoreKey::TransactionConflictedNewsList,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_list::<TransactionReorgedNews>(
+                    StoreKey::TransactionReorgedNewsList,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_list::<DispatchScheduledNews>(
+                    StoreKey::DispatchScheduledNewsList,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_list::<DependencyFailedNews>(
+                    StoreKey::DependencyFailedNewsList,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_list::<OutpointSpentNews>(
+                    StoreKey::OutpointSpentNewsList,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_list::<AddressFundedNews>(
+                    StoreKey::AddressFundedNewsList,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_list::<FundingSpentExternallyNews>(
+                    StoreKey::FundingSpentExternallyNewsList,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_list::<FundingExhaustedNews>(
+                    StoreKey::FundingExhaustedNewsList,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_list::<FundingTemporarilyUnavailableNews>(
+                    StoreKey::FundingTemporarilyUnavailableNewsList,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_list::<AnchorSpentExternallyNews>(
+                    StoreKey::AnchorSpentExternallyNewsList,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_list::<FeeOverpaymentNews>(
+                    StoreKey::FeeOverpaymentNewsList,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_list::<ConfirmationMilestoneNews>(
+                    StoreKey::ConfirmationMilestoneNewsList,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_list::<CollateralSpentNews>(
+                    StoreKey::CollateralSpentNewsList,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_list::<CollateralFullySpentNews>(
+                    StoreKey::CollateralFullySpentNewsList,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_list::<DispatchDeferredNews>(
+                    StoreKey::DispatchDeferredNewsList,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_list::<TickWorkSkippedNews>(
+                    StoreKey::TickWorkSkippedNewsList,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_list::<SpeedupRejectedByPolicyNews>(
+                    StoreKey::SpeedupRejectedByPolicyNewsList,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_list::<GroupCompletedNews>(
+                    StoreKey::GroupCompletedNewsList,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+                self.log_news_record::<NewBlockNews>(
+                    StoreKey::NewBlockNews,
+                    new_block_news,
+                    &mut next_seq,
+                    transaction_id,
+                )?;
+
+                self.set_value(&next_seq_key, next_seq, Some(transaction_id))
+            })?;
+
+            if next_seq > 0 {
+                info!("News log built for {} news", next_seq);
+            }
+        }
+
+        self.news_log_ready.set(true);
+
+        Ok(())
+    }
+
+    // Reads the pending news from the news log, in the order they were reported, from `since_seq` on.
+    // The entries are found by their keys, which hold their sequence number and severity, so only the entries
+    // of `min_severity` or above are read, and at most `limit` of them. The flag is true when more entries follow.
+    fn read_news_log(
+        &self,
+        since_seq: u64,
+        limit: Option<usize>,
+        min_severity: Severity,
+    ) -> Result<(Vec<(u64, CoordinatorNews)>, bool), BitcoinCoordinatorStoreError> {
+        self.ensure_news_log()?;
+
+        let prefix = self.get_key(StoreKey::NewsLogEntries);
+
+        let mut entries: Vec<(u64, Severity)> = self
+            .store
+            .keys(&prefix)?
+            .iter()
+            .filter_map(|key| parse_news_log_key(key.strip_prefix(prefix.as_str())?))
+            .filter(|(seq, severity)| *seq >= since_seq && *severity >= min_severity)
+            .collect();
+        entries.sort();
+
+        let has_more = limit.is_some_and(|limit| entries.len() > limit);
+        if let Some(limit) = limit {
+            entries.truncate(limit);
+        }
+
+        let mut news = Vec::with_capacity(entries.len());
+
+        for (seq, severity) in entries {
+            let key = self.get_key(StoreKey::NewsLogEntry(seq, severity));
+
+            if let Some(entry) = self.get_value::<&str, CoordinatorNews>(&key)? {
+                news.push((seq, entry));
+            }
+        }
+
+        Ok((news, has_more))
+    }
+}
+
+// The news returned to the consumer for a stored news.
+fn coordinator_news<T: Clone + Into<CoordinatorNews>>(record: &NewsRecord<T>) -> CoordinatorNews {
+    record.news.clone().into()
+}
+
+// The block hash of the new block news is the one of its record.
+fn new_block_news(record: &NewsRecord<NewBlockNews>) -> CoordinatorNews {
+    CoordinatorNews::NewBlock(record.news.height, record.block_hash)
+}
+
+// Name of a severity in the keys of the news log.
+fn severity_name(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Info => "info",
+        Severity::Warning => "warning",
+        Severity::Critical => "critical",
+    }
+}
+
+// Sequence number and severity of a news log entry, from its key without the prefix of the entries.
+fn parse_news_log_key(key: &str) -> Option<(u64, Severity)> {
+    let (seq, name) = key.split_once('/')?;
+    let severity = [Severity::Info, Severity::Warning, Severity::Critical]
+        .into_iter()
+        .find(|severity| severity_name(*severity) == name)?;
+
+    Some((seq.parse().ok()?, severity))
+}
+
+// Follows the replacements of the transaction up to the last one. None when it was not replaced.
+fn find_replacement(replacements: &[(Txid, Txid)], tx_id: Txid) -> Option<Txid> {
+    let mut replacement = None;
+    let mut current = tx_id;
+
+    while let Some((_, next)) = replacements.iter().find(|(id, _)| *id == current) {
+        replacement = Some(*next);
+        current = *next;
+    }
+
+    replacement
+}
+
+// The id of the transaction (or speedup) identifying the news acknowledged by `ack`.
+fn ack_txid(ack: &AckCoordinatorNews) -> Option<Txid> {
+    match ack {
+        AckCoordinatorNews::InsufficientFunds(txid)
+        | AckCoordinatorNews::SpeedupFeeCapExceeded(txid)
+        | AckCoordinatorNews::FundingTopUp(txid)
+        | AckCoordinatorNews::ParentReplaced(txid)
+        | AckCoordinatorNews::DispatchTransactionError(txid)
+        | AckCoordinatorNews::DispatchSpeedUpError(txid)
+        | AckCoordinatorNews::TransactionAlreadyInMempool(txid)
+        | AckCoordinatorNews::MempoolRejection(txid)
+        | AckCoordinatorNews::NetworkError(txid)
+        | AckCoordinatorNews::DispatchCancelled(txid)
+        | AckCoordinatorNews::TransactionExpired(txid)
+        | AckCoordinatorNews::NonStandardAnchor(txid)
+        | AckCoordinatorNews::RbfEscalationFailed(txid)
+        | AckCoordinatorNews::MaxRbfAttemptsReached(txid)
+        | AckCoordinatorNews::TransactionRebroadcast(txid)
+        | AckCoordinatorNews::MaxRebroadcastAttemptsReached(txid)
+        | AckCoordinatorNews::SpeedupOrphaned(txid)
+        | AckCoordinatorNews::SpeedupChainInvalidated(txid)
+        | AckCoordinatorNews::SpeedupCreated(txid)
+        | AckCoordinatorNews::TransactionConflicted(txid)
+        | AckCoordinatorNews::TransactionReorged(txid)
+        | AckCoordinatorNews::DispatchScheduled(txid)
+        | AckCoordinatorNews::DependencyFailed(txid)
+        | AckCoordinatorNews::FundingExhausted(txid)
+        | AckCoordinatorNews::FundingTemporarilyUnavailable(txid)
+        | AckCoordinatorNews::AnchorSpentExternally(txid)
+        | AckCoordinatorNews::FeeOverpayment(txid)
+        | AckCoordinatorNews::SpeedupRejectedByPolicy(txid) => Some(*txid),
+        AckCoordinatorNews::EstimateFeerateTooHigh(_, _)
+        | AckCoordinatorNews::FundingNotFound
+        | AckCoordinatorNews::FeeEstimateUnavailable
+        | AckCoordinatorNews::DispatchPausedHighFees
+        | AckCoordinatorNews::TickPartialFailure
+        | AckCoordinatorNews::NodeUnreachable
+        | AckCoordinatorNews::NodeRecovered
+        | AckCoordinatorNews::SettingsUpdated
+        | AckCoordinatorNews::OutpointSpent(_)
+        | AckCoordinatorNews::AddressFunded(_, _)
+        | AckCoordinatorNews::FundingSpentExternally(_)
+        | AckCoordinatorNews::ConfirmationMilestone(_, _)
+        | AckCoordinatorNews::CollateralSpent(_)
+        | AckCoordinatorNews::CollateralFullySpent(_)
+        | AckCoordinatorNews::DispatchDeferred(_)
+        | AckCoordinatorNews::TickWorkSkipped(_)
+        | AckCoordinatorNews::GroupCompleted(_)
+        | AckCoordinatorNews::NewBlock => None,
+    }
+}
+
+impl BitcoinCoordinatorStoreApi for BitcoinCoordinatorStore {
+    fn get_tx_history(
+        &self,
+        tx_id: &Txid,
+    ) -> Result<TransactionHistory, BitcoinCoordinatorStoreError> {
+        let tx = self.get_tx(tx_id)?;
+
+        let key = self.get_key(StoreKey::TransactionHistory(*tx_id));
+        let events = self
+            .get_value::<&str, Vec<TransactionHistoryEntry>>(&key)?
+            .unwrap_or_default();
+
+        Ok(TransactionHistory {
+            tx_id: tx.tx_id,
+            state: tx.state,
+            broadcast_block_height: tx.broadcast_block_height,
+            fee_report: tx.fee_report,
+            events,
+        })
+    }
+
+    fn get_tx(&self, tx_id: &Txid) -> Result<CoordinatedTransaction, BitcoinCoordinatorStoreError> {
+        let key = self.get_key(StoreKey::Transaction(*tx_id));
+        let tx = self.get_value::<&str, CoordinatedTransaction>(&key)?;
+
+        if let Some(tx) = tx {
+            Ok(tx)
+        } else {
+            let message = format!("Transaction not found: {tx_id}");
+            Err(BitcoinCoordinatorStoreError::TransactionNotFound(message))
+        }
+    }
+
+    fn get_txs_in_progress(
+        &self,
+    ) -> Result<Vec<CoordinatedTransaction>, BitcoinCoordinatorStoreError> {
+        // Get all transactions in progress which are the ones are not Finalized
+        // Cancelled and expired transactions are only kept in the list when they were already broadcast
+        let mut in_progress = HashSet::new();
+
+        for state in [
+            TransactionState::ToDispatch,
+            TransactionState::Dispatched,
+            TransactionState::Confirmed,
+            TransactionState::Cancelled,
+            TransactionState::Expired,
+        ] {
+            in_progress.extend(self.get_state_index(&state)?);
+        }
+
+        // Only the transactions in progress are loaded, in the order they were saved.
+        let mut txs_filter = Vec::new();
+
+        for tx_id in self.get_txs()? {
+            if in_progress.contains(&tx_id) {
+                txs_filter.push(self.get_tx(&tx_id)?);
+            }
+        }
+
+        Ok(txs_filter)
+    }
+
+    fn get_txs_to_dispatch(
+        &self,
+    ) -> Result<Vec<CoordinatedTransaction>, BitcoinCoordinatorStoreError> {
+        let txs = self.get_state_index(&TransactionState::ToDispatch)?;
+        let mut txs_filter = Vec::new();
+
+        for tx_id in txs {
+            let tx = self.get_tx(&tx_id)?;
+
+            if self.retry_pending_reason(&tx).is_none() {
+                txs_filter.push(tx);
+            }
+        }
+
+        Ok(txs_filter)
+    }
+
+    fn get_pending_tx_entries(
+        &self,
+        current_block_height: BlockHeight,
+    ) -> Result<Vec<PendingTxEntry>, BitcoinCoordinatorStoreError> {
+        let deferred = self.get_deferred_speedup_txs()?;
+        let can_speedup = self.can_speedup()?;
+        let mut entries = Vec::new();
+
+        let tx_ids = self
+            .get_state_index(&TransactionState::ToDispatch)?
+            .into_iter()
+            .chain(self.get_state_index(&TransactionState::Dispatched)?);
+
+        for tx_id in tx_ids {
+            let tx = self.get_tx(&tx_id)?;
+
+            let pending_reason = match tx.state {
+                TransactionState::ToDispatch => {
+                    if let Some(reason) = self.retry_pending_reason(&tx) {
+                        Some(reason)
+                    } else if tx
+                        .target_block_height
+                        .is_some_and(|target| current_block_height < target)
+                    {
+                        Some(PendingReason::TargetHeightNotReached)
+                    } else if let Some(height) = tx
+                        .locktime_block_height
+                        .filter(|height| current_block_height < *height)
+                    {
+                        Some(PendingReason::WaitingForLocktime(height))
+                    } else if self.has_unconfirmed_dependencies(&tx)? {
+                        Some(PendingReason::DependencyNotConfirmed)
+                    } else if tx.speedup_data.is_some() && !can_speedup {
+                        Some(PendingReason::FundingBlocked)
+                    } else {
+                        None
+                    }
+                }
+                TransactionState::Dispatched => deferred
+                    .contains(&tx_id)
+                    .then_some(PendingReason::FundingBlocked),
+                _ => continue,
+            };
+
+            entries.push(PendingTxEntry {
+                tx_id,
+                context: tx.context,
+                state: tx.state,
+                retry_count: tx.retry_info.map_or(0, |info| info.retries_count),
+                target_block_height: tx.target_block_height,
+                broadcast_block_height: tx.broadcast_block_height,
+                has_speedup: tx.speedup_data.is_some(),
+                pending_reason,
+            });
+        }
+
+        Ok(entries)
+    }
+
+    fn save_tx(
+        &self,
+        tx: Transaction,
+        speedup_data: Option<SpeedupData>,
+        target_block_height: Option<BlockHeight>,
+        context: String,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        self.save_tx_with_options(
+            tx,
+            speedup_data,
+            target_block_height,
+            context,
+            DispatchOptions::default(),
+        )
+    }
+
+    fn save_tx_with_options(
+        &self,
+        tx: Transaction,
+        speedup_data: Option<SpeedupData>,
+        target_block_height: Option<BlockHeight>,
+        context: String,
+        dispatch_options: DispatchOptions,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        self.check_not_dispatched(tx.compute_txid())?;
+
+        let key = self.get_key(StoreKey::Transaction(tx.compute_txid()));
+        let previous_state = self
+            .get_value::<&str, CoordinatedTransaction>(&key)?
+            .map(|tx| tx.state);
+
+        let mut tx_info = CoordinatedTransaction::new(
+            tx.clone(),
+            speedup_data,
+            TransactionState::ToDispatch,
+            target_block_height,
+            context,
+        );
+        tx_info.dispatch_options = dispatch_options;
+
+        // Legacy indexes are rebuilt before, in their own store transaction.
+        self.ensure_state_indexes()?;
+
+        self.atomically(|transaction_id| {
+            self.set_value(&key, &tx_info, Some(transaction_id))?;
+            self.index_context_txs(&tx_info.context, &[tx_info.tx_id], Some(transaction_id))?;
+
+            if let Some(previous_state) = previous_state {
+                self.remove_from_state_index(
+                    &previous_state,
+                    &[tx_info.tx_id],
+                    Some(transaction_id),
+                )?;
+            }
+            self.add_to_state_index(
+                &TransactionState::ToDispatch,
+                &[tx_info.tx_id],
+                Some(transaction_id),
+            )?;
+
+            let txs_key = self.get_key(StoreKey::PendingTransactionList);
+            let mut txs = self
+                .get_value::<&str, Vec<Txid>>(&txs_key)?
+                .unwrap_or_default();
+
+            if !txs.contains(&tx_info.tx_id) {
+                txs.push(tx_info.tx_id);
+                self.set_value(&txs_key, &txs, Some(transaction_id))?;
+            }
+
+            self.record_tx_events(
+                tx_info.tx_id,
+                vec![TransactionEvent::Saved {
+                    target_block_height,
+                }],
+                Some(transaction_id),
+            )
+        })
+    }
+
+    fn save_txs(
+        &self,
+        txs: Vec<(Transaction, Option<SpeedupData>, String)>,
+        target_block_height: Option<BlockHeight>,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        if txs.is_empty() {
+            return Err(BitcoinCoordinatorStoreError::EmptyTransactionBatch);
+        }
+
+        let mut tx_ids: Vec<Txid> = Vec::with_capacity(txs.len());
+        // Transactions saved again leave the index of their previous state.
+        let mut previous_states: Vec<(TransactionState, Vec<Txid>)> = Vec::new();
+        for (tx, _, _) in txs.iter() {
+            let tx_id = tx.compute_txid();
+            if tx_ids.contains(&tx_id) {
+                return Err(BitcoinCoordinatorStoreError::DuplicateTransactionInBatch(
+                    tx_id,
+                ));
+            }
+            self.check_not_dispatched(tx_id)?;
+            tx_ids.push(tx_id);
+
+            let key = self.get_key(StoreKey::Transaction(tx_id));
+            if let Some(previous) = self.get_value::<&str, CoordinatedTransaction>(&key)? {
+                match previous_states
+                    .iter_mut()
+                    .find(|(state, _)| *state == previous.state)
+                {
+                    Some((_, ids)) => ids.push(tx_id),
+                    None => previous_states.push((previous.state, vec![tx_id])),
+                }
+            }
+        }
+
+        let mut context_txs: HashMap<String, Vec<Txid>> = HashMap::new();
+        for (tx, _, context) in txs.iter() {
+            context_txs
+                .entry(context.clone())
+                .or_default()
+                .push(tx.compute_txid());
+        }
+
+        // Legacy indexes are rebuilt before, in their own store transaction.
+        self.ensure_state_indexes()?;
+
+        self.atomically(|transaction_id| {
+            for (context, context_tx_ids) in context_txs.iter() {
+                self.index_context_txs(context, context_tx_ids, Some(transaction_id))?;
+            }
+
+            for (tx, speedup_data, context) in txs {
+                let key = self.get_key(StoreKey::Transaction(tx.compute_txid()));
+                let tx_info = CoordinatedTransaction::new(
+                    tx,
+                    speedup_data,
+                    TransactionState::ToDispatch,
+                    target_block_height,
+                    context,
+                );
+                self.set_value(&key, &tx_info, Some(transaction_id))?;
+            }
+
+            let txs_key = self.get_key(StoreKey::PendingTransactionList);
+            let mut pending_txs = self
+                .get_value::<&str, Vec<Txid>>(&txs_key)?
+                .unwrap_or_default();
+            for tx_id in tx_ids.iter() {
+                if !pending_txs.contains(tx_id) {
+                    pending_txs.push(*tx_id);
+                }
+            }
+            self.set_value(&txs_key, &pending_txs, Some(transaction_id))?;
+
+            for (state, ids) in previous_states.iter() {
+                self.remove_from_state_index(state, ids, Some(transaction_id))?;
+            }
+            self.add_to_state_index(&TransactionState::ToDispatch, &tx_ids, Some(transaction_id))?;
+
+            for tx_id in tx_ids.iter() {
+                self.record_tx_events(
+                    *tx_id,
+                    vec![TransactionEvent::Saved {
+                        target_block_height,
+                    }],
+                    Some(transaction_id),
+                )?;
+            }
+
+            Ok(())
+        })
+    }
+
+    fn remove_tx(&self, tx_id: Txid) -> Result<(), BitcoinCoordinatorStoreError> {
+        let tx_key = self.get_key(StoreKey::Transaction(tx_id));
+        let tx = self.get_value::<&str, CoordinatedTransaction>(&tx_key)?;
+
+        self.ensure_state_indexes()?;
+
+        self.atomically(|transaction_id| {
+            if let Some(tx) = tx {
+                self.unindex_context_txs(&tx.context, &[tx_id], Some(transaction_id))?;
+                self.remove_from_state_index(&tx.state, &[tx_id], Some(transaction_id))?;
+            }
+
+            self.store.remove(&tx_key, Some(transaction_id))?;
+
+            let history_key = self.get_key(StoreKey::TransactionHistory(tx_id));
+            self.store.remove(&history_key, Some(transaction_id))?;
+
+            let txs_key = self.get_key(StoreKey::PendingTransactionList);
+            let mut txs = self
+                .get_value::<&str, Vec<Txid>>(&txs_key)?
+                .unwrap_or_default();
+
+            txs.retain(|id| *id != tx_id);
+            self.set_value(&txs_key, &txs, Some(transaction_id))
+        })
+    }
+
+    fn update_tx_to_dispatched(
+        &self,
+        tx_id: Txid,
+        deliver_block_height: u32,
+        fee_rate_at_dispatch: u64,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        let mut tx = self.get_tx(&tx_id)?;
+
+        // Validate state transition: only ToDispatch can transition to Dispatched
+        if tx.state != TransactionState::ToDispatch {
+            return Err(BitcoinCoordinatorStoreError::InvalidTransactionState);
+        }
+
+        tx.state = TransactionState::Dispatched;
+
+        tx.broadcast_block_height = Some(deliver_block_height);
+        tx.first_broadcast_block_height
+            .get_or_insert(deliver_block_height);
+        tx.fee_rate_at_dispatch = fee_rate_at_dispatch;
+
+        self.ensure_state_indexes()?;
+
+        self.atomically(|transaction_id| {
+            let key = self.get_key(StoreKey::Transaction(tx_id));
+            self.set_value(key, tx, Some(transaction_id))?;
+
+            self.move_state_index(
+                tx_id,
+                &TransactionState::ToDispatch,
+                Some(&TransactionState::Dispatched),
+                Some(transaction_id),
+            )?;
+
+            self.record_tx_events(
+                tx_id,
+                vec![TransactionEvent::Dispatched {
+                    block_height: deliver_block_height,
+                    fee_rate: fee_rate_at_dispatch,
+                }],
+                Some(transaction_id),
+            )
+        })
+    }
+
+    fn reschedule_tx(
+        &self,
+        tx_id: Txid,
+        target_block_height: Option<BlockHeight>,
+    ) -> Result<CoordinatedTransaction, BitcoinCoordinatorStoreError> {
+        let mut tx = self.get_tx(&tx_id)?;
+
+        if tx.state != TransactionState::ToDispatch || tx.broadcast_block_height.is_some() {
+            return Err(BitcoinCoordinatorStoreError::InvalidTransactionState);
+        }
+
+        tx.target_block_height = target_block_height;
+
+        let key = self.get_key(StoreKey::Transaction(tx_id));
+        self.set_value(&key, &tx, None)?;
+
+        Ok(tx)
+    }
+
+    fn get_scheduled_txs(
+        &self,
+    ) -> Result<Vec<CoordinatedTransaction>, BitcoinCoordinatorStoreError> {
+        let txs = self.get_state_index(&TransactionState::ToDispatch)?;
+        let mut scheduled_txs = Vec::new();
+
+        for tx_id in txs {
+            let tx = self.get_tx(&tx_id)?;
+
+            if tx.target_block_height.is_some() {
+                scheduled_txs.push(tx);
+            }
+        }
+
+        Ok(scheduled_txs)
+    }
+
+    fn cancel_tx(
+        &self,
+        tx_id: Txid,
+    ) -> Result<CoordinatedTransaction, BitcoinCoordinatorStoreError> {
+        self.update_tx_state(tx_id, TransactionState::Cancelled)?;
+
+        let tx = self.get_tx(&tx_id)?;
+
+        // A transaction that was never broadcast will not be dispatched anymore, so we stop tracking it.
+        if tx.broadcast_block_height.is_none() {
+            self.untrack_tx(tx_id)?;
+        }
+
+        Ok(tx)
+    }
+
+    fn expire_tx(
+        &self,
+        tx_id: Txid,
+    ) -> Result<CoordinatedTransaction, BitcoinCoordinatorStoreError> {
+        self.update_tx_state(tx_id, TransactionState::Expired)?;
+
+        let tx = self.get_tx(&tx_id)?;
+
+        // A transaction that was never broadcast can not be confirmed anymore, so we stop tracking it.
+        if tx.broadcast_block_height.is_none() {
+            self.untrack_tx(tx_id)?;
+        }
+
+        Ok(tx)
+    }
+
+    fn untrack_tx(&self, tx_id: Txid) -> Result<(), BitcoinCoordinatorStoreError> {
+        let tx = self.get_tx(&tx_id)?;
+        self.remove_from_state_index(&tx.state, &[tx_id], None)?;
+
+        let txs_key = self.get_key(StoreKey::PendingTransactionList);
+        let mut txs = self
+            .get_value::<&str, Vec<Txid>>(&txs_key)?
+            .unwrap_or_default();
+        txs.retain(|id| *id != tx_id);
+        self.set_value(&txs_key, &txs, None)?;
+
+        Ok(())
+    }
+
+    fn save_first_confirmation(
+        &self,
+        tx_id: Txid,
+        block_height: BlockHeight,
+        speedup_count: u32,
+        speedup_fees: u64,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        let mut tx = self.get_tx(&tx_id)?;
+
+        if tx.first_confirmation_block_height.is_some() {
+            return Ok(());
+        }
+
+        tx.first_confirmation_block_height = Some(block_height);
+        tx.bump_count += speedup_count;
+        tx.bump_fees += speedup_fees;
+
+        let key = self.get_key(StoreKey::Transaction(tx_id));
+        self.set_value(key, tx, None)
+    }
+
+    fn save_fee_rate_at_confirmation(
+        &self,
+        tx_id: Txid,
+        fee_rate: u64,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        let mut tx = self.get_tx(&tx_id)?;
+
+        if tx.fee_rate_at_confirmation.is_some() {
+            return Ok(());
+        }
+
+        tx.fee_rate_at_confirmation = Some(fee_rate);
+
+        let key = self.get_key(StoreKey::Transaction(tx_id));
+        self.set_value(key, tx, None)
+    }
+
+    fn save_last_confirmation_milestone(
+        &self,
+        tx_id: Txid,
+        milestone: Option<u32>,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        let mut tx = self.get_tx(&tx_id)?;
+        tx.last_confirmation_milestone = milestone;
+
+        let key = self.get_key(StoreKey::Transaction(tx_id));
+        self.set_value(key, tx, None)
+    }
+
+    fn save_paused_since(
+        &self,
+        tx_id: Txid,
+        block_height: BlockHeight,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        let mut tx = self.get_tx(&tx_id)?;
+        tx.paused_since_block_height = Some(block_height);
+
+        let key = self.get_key(StoreKey::Transaction(tx_id));
+        self.set_value(key, tx, None)
+    }
+
+    fn save_locktime_height(
+        &self,
+        tx_id: Txid,
+        block_height: Option<BlockHeight>,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        let mut tx = self.get_tx(&tx_id)?;
+        tx.locktime_block_height = block_height;
+
+        let key = self.get_key(StoreKey::Transaction(tx_id));
+        self.set_value(key, tx, None)
+    }
+
+    fn save_conflict_scan_progress(
+        &self,
+        tx_id: Txid,
+        progress: ConflictScanProgress,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        let mut tx = self.get_tx(&tx_id)?;
+        tx.conflict_scan_progress = Some(progress);
+
+        let key = self.get_key(StoreKey::Transaction(tx_id));
+        self.set_value(key, tx, None)
+    }
+
+    fn save_fee_report(
+        &self,
+        tx_id: Txid,
+        report: PackageFeeReport,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        let mut tx = self.get_tx(&tx_id)?;
+        tx.fee_report = Some(report);
+
+        let key = self.get_key(StoreKey::Transaction(tx_id));
+        self.set_value(key, tx, None)
+    }
+
+    fn get_finalized_tx_stats(
+        &self,
+    ) -> Result<Vec<FinalizedTxStats>, BitcoinCoordinatorStoreError> {
+        let key = self.get_key(StoreKey::FinalizedTxStatsList);
+
+        Ok(self.get_value(key)?.unwrap_or_default())
+    }
+
+    fn get_tx_ids_by_state(
+        &self,
+        state: &TransactionState,
+    ) -> Result<Vec<Txid>, BitcoinCoordinatorStoreError> {
+        self.get_state_index(state)
+    }
+
+    fn replace_tx(
+        &self,
+        tx_id: Txid,
+        replacement: Transaction,
+        block_height: BlockHeight,
+        fee_rate: u64,
+        extra_fee: u64,
+    ) -> Result<CoordinatedTransaction, BitcoinCoordinatorStoreError> {
+        let tx = self.get_tx(&tx_id)?;
+
+        if tx.state != TransactionState::Dispatched {
+            return Err(BitcoinCoordinatorStoreError::InvalidTransactionState);
+        }
+
+        let mut replaced_txids = tx.replaced_txids.clone();
+        replaced_txids.push(tx_id);
+
+        let replacement = CoordinatedTransaction {
+            tx_id: replacement.compute_txid(),
+            tx: replacement,
+            // The replacement is never paid by a CPFP, the speedup output is not tracked anymore.
+            speedup_data: None,
+            broadcast_block_height: Some(block_height),
+            retry_info: None,
+            fee_rate_at_dispatch: fee_rate,
+            rebroadcast_count: 0,
+            last_rebroadcast_block_height: None,
+            replaced_txids,
+            bump_count: tx.bump_count + 1,
+            bump_fees: tx.bump_fees + extra_fee,
+            ..tx
+        };
+
+        // Legacy indexes and the news log are built before, in their own store transactions.
+        self.ensure_state_indexes()?;
+        self.ensure_news_log()?;
+
+        self.atomically(|transaction_id| {
+            self.set_value(
+                self.get_key(StoreKey::Transaction(replacement.tx_id)),
+                &replacement,
+                Some(transaction_id),
+            )?;
+            self.index_context_txs(
+                &replacement.context,
+                &[replacement.tx_id],
+                Some(transaction_id),
+            )?;
+
+            // The replacement takes the place of the original in the pending list and the dispatched index.
+            for key in [
+                StoreKey::PendingTransactionList,
+                StoreKey::TransactionStateList(TransactionState::Dispatched),
+            ] {
+                let key = self.get_key(key);
+                let mut tx_ids = self.get_value::<&str, Vec<Txid>>(&key)?.unwrap_or_default();
+
+                for id in tx_ids.iter_mut().filter(|id| **id == tx_id) {
+                    *id = replacement.tx_id;
+                }
+
+                self.set_value(&key, &tx_ids, Some(transaction_id))?;
+            }
+
+            let mut replacements = self.get_replacements()?;
+            replacements.push((tx_id, replacement.tx_id));
+            self.set_value(
+                self.get_key(StoreKey::ReplacedTransactionList),
+                &replacements,
+                Some(transaction_id),
+            )?;
+
+            self.remap_tx_news(tx_id, replacement.tx_id, transaction_id)?;
+
+            let mut intents = self.get_parent_replacement_intents()?;
+            let len = intents.len();
+            intents.retain(|intent| intent.replaced_txid != tx_id);
+            if intents.len() != len {
+                self.set_value(
+                    self.get_key(StoreKey::ParentReplacementIntentList),
+                    &intents,
+                    Some(transaction_id),
+                )?;
+            }
+
+            self.record_tx_events(
+                tx_id,
+                vec![TransactionEvent::Replaced {
+                    replacement_txid: replacement.tx_id,
+                    extra_fee,
+                    block_height,
+                }],
+                Some(transaction_id),
+            )?;
+            self.record_tx_events(
+                replacement.tx_id,
+                vec![TransactionEvent::ReplacementDispatched {
+                    replaced_txid: tx_id,
+                    extra_fee,
+                    block_height,
+                }],
+                Some(transaction_id),
+            )?;
+
+            Ok(replacement)
+        })
+    }
+
+    fn get_replacement(&self, tx_id: &Txid) -> Result<Option<Txid>, BitcoinCoordinatorStoreError> {
+        Ok(find_replacement(&self.get_replacements()?, *tx_id))
+    }
+
+    fn save_parent_replacement_intent(
+        &self,
+        intent: ParentReplacementIntent,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        let mut intents = self.get_parent_replacement_intents()?;
+        intents.retain(|saved| saved.replaced_txid != intent.replaced_txid);
+        intents.push(intent);
+
+        let key = self.get_key(StoreKey::ParentReplacementIntentList);
+        self.set_value(&key, intents, None)
+    }
+
+    fn remove_parent_replacement_intent(
+        &self,
+        replaced_txid: &Txid,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        let mut intents = self.get_parent_replacement_intents()?;
+        let len = intents.len();
+
+        intents.retain(|intent| intent.replaced_txid != *replaced_txid);
+
+        if intents.len() != len {
+            let key = self.get_key(StoreKey::ParentReplacementIntentList);
+            self.set_value(&key, intents, None)?;
+        }
+
+        Ok(())
+    }
+
+    fn get_parent_replacement_intents(
+        &self,
+    ) -> Result<Vec<ParentReplacementIntent>, BitcoinCoordinatorStoreError> {
+        let key = self.get_key(StoreKey::ParentReplacementIntentList);
+        let intents = self
+            .get_value::<&str, Vec<ParentReplacementIntent>>(&key)?
+            .unwrap_or_default();
+
+        Ok(intents)
+    }
+
+    fn get_txs_by_context(
+        &self,
+        context: &str,
+    ) -> Result<Vec<CoordinatedTransaction>, BitcoinCoordinatorStoreError> {
+        let key = self.get_key(StoreKey::ContextTransactionList(context.to_string()));
+        let tx_ids = self.get_value::<&str, Vec<Txid>>(&key)?.unwrap_or_default();
+
+        let mut txs = Vec::new();
+
+        for tx_id in tx_ids.iter() {
+            // Context indexes are not repaired on open, they cannot be listed.
+            let tx = match self.get_tx(tx_id) {
+                Ok(tx) => tx,
+                Err(BitcoinCoordinatorStoreError::TransactionNotFound(_)) => {
+                    warn!(
+                        "Transaction({}) indexed for context {} has no record, skipping it",
+                        tx_id, context
+                    );
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+
+            // A transaction saved again with another context is left in the index of the previous one.
+            if tx.context == context {
+                txs.push(tx);
+            }
+        }
+
+        Ok(txs)
+    }
+
+    fn update_tx_state(
+        &self,
+        tx_id: Txid,
+        new_state: TransactionState,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        self.write_tx_state(tx_id, new_state, None)?;
+        Ok(())
+    }
+
+    fn finalize_tx(
+        &self,
+        tx_id: Txid,
+        block_height: BlockHeight,
+        block_hash: BlockHash,
+    ) -> Result<Option<FinalizedTxEntry>, BitcoinCoordinatorStoreError> {
+        self.write_tx_state(
+            tx_id,
+            TransactionState::Finalized,
+            Some((block_height, block_hash)),
+        )
+    }
+
+    fn read_finalized(
+        &self,
+        since_seq: u64,
+        limit: usize,
+    ) -> Result<Vec<FinalizedTxEntry>, BitcoinCoordinatorStoreError> {
+        let next_seq = self.finalized_next_seq()?;
+        let mut seq = since_seq;
+        let mut entries = Vec::new();
+
+        while seq < next_seq && entries.len() < limit {
+            // A rolled back finalization leaves a gap in the sequence numbers.
+            if let Some(entry) = self.get_value::<String, FinalizedTxEntry>(
+                self.get_key(StoreKey::FinalizedLogEntry(seq)),
+            )? {
+                entries.push(entry);
+            }
+
+            seq += 1;
+        }
+
+        Ok(entries)
+    }
+
+    fn get_finalized_delivery(&self) -> Result<FinalizedDelivery, BitcoinCoordinatorStoreError> {
+        Ok(self
+            .get_value::<String, FinalizedDelivery>(self.get_key(StoreKey::FinalizedDelivery))?
+            .unwrap_or_default())
+    }
+
+    fn set_finalized_delivery(
+        &self,
+        delivery: &FinalizedDelivery,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        self.set_value(self.get_key(StoreKey::FinalizedDelivery), delivery, None)
+    }
+
+    fn update_news(
+        &self,
+        news: CoordinatorNews,
+        current_block_hash: BlockHash,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        self.ensure_news_log()?;
+
+        // The new block record can not be converted back to the news without its block hash
+        let severity = news.severity();
+
+        match news {
+            CoordinatorNews::InsufficientFunds(tx_id, available, required) => self
+                .report_news_in_block(
+                    StoreKey::InsufficientFundsNewsList,
+                    InsufficientFundsNews {
+                        tx_id,
+                        available,
+                        required,
+                    },
+                    current_block_hash,
+                    |news| news.tx_id == tx_id,
+                )?,
+            CoordinatorNews::FundingTopUp(tx_id, amount) => {
+                // Each funding is registered once
+                self.report_news_once(
+                    StoreKey::FundingTopUpNewsList,
+                    FundingTopUpNews { tx_id, amount },
+                    current_block_hash,
+                    |news| news.tx_id == tx_id,
+                )?
+            }
+            CoordinatorNews::ParentReplaced(tx_id, replacement_txid, extra_fee) => {
+                // A transaction is replaced once, its replacement is replaced with another txid
+                self.report_news_once(
+                    StoreKey::ParentReplacedNewsList,
+                    ParentReplacedNews {
+                        tx_id,
+                        replacement_txid,
+                        extra_fee,
+                    },
+                    current_block_hash,
+                    |news| news.tx_id == tx_id,
+                )?
+            }
+            CoordinatorNews::SpeedupFeeCapExceeded(tx_id, estimated_fee, cap) => {
+                // Reported once per block while the transaction is deferred
+                self.report_news_in_block(
+                    StoreKey::SpeedupFeeCapExceededNewsList,
+                    SpeedupFeeCapExceededNews {
+                        tx_id,
+                        estimated_fee,
+                        cap,
+                    },
+                    current_block_hash,
+                    |news| news.tx_id == tx_id,
+                )?
+            }
+            CoordinatorNews::DispatchTransactionError(tx_id, context, error, kind) => self
+                .report_news_in_block(
+                    StoreKey::DispatchTransactionErrorNewsList,
+                    DispatchTransactionErrorNews {
+                        tx_id,
+                        context,
+                        error,
+                        kind,
+                    },
+                    current_block_hash,
+                    |news| news.tx_id == tx_id,
+                )?,
+            CoordinatorNews::DispatchSpeedUpError(tx_ids, contexts, speedup_txid, error) => {
+                let is_same = |news: &DispatchSpeedUpErrorNews| {
+                    news.tx_ids == tx_ids && news.speedup_txid == speedup_txid
+                };
+
+                self.report_news_in_block(
+                    StoreKey::DispatchSpeedUpErrorNewsList,
+                    DispatchSpeedUpErrorNews {
+                        tx_ids: tx_ids.clone(),
+                        contexts,
+                        speedup_txid,
+                        error,
+                    },
+                    current_block_hash,
+                    is_same,
+                )?
+            }
+            CoordinatorNews::FundingNotFound => {
+                let key = self.get_key(StoreKey::FundingNotFoundNews);
+                let news = self.get_value::<&str, NewsRecord<FundingNotFoundNews>>(&key)?;
+
+                // An existing news is only reported again in another block
+                if news.is_none_or(|record| record.block_hash != current_block_hash) {
+                    self.save_news_record(
+                        &key,
+                        NewsRecord::new(FundingNotFoundNews, current_block_hash),
+                        coordinator_news,
+                        None,
+                    )?;
+                }
+            }
+            CoordinatorNews::NewBlock(height, block_hash) => {
+                let key = self.get_key(StoreKey::NewBlockNews);
+                let news = self.get_value::<&str, NewsRecord<NewBlockNews>>(&key)?;
+
+                // Only the last block is kept, a block already reported keeps its ack.
+                if news.is_none_or(|record| record.block_hash != block_hash) {
+                    self.save_news_record(
+                        &key,
+                        NewsRecord::with_severity(NewBlockNews { height }, block_hash, severity),
+                        new_block_news,
+                        None,
+                    )?;
+                }
+            }
+            CoordinatorNews::FeeEstimateUnavailable(fee_rate) => {
+                let key = self.get_key(StoreKey::FeeEstimateUnavailableNews);
+                let news = self.get_value::<&str, NewsRecord<FeeEstimateUnavailableNews>>(&key)?;
+
+                // Only one news per block, the fee rate of a later fallback in the same block is not reported.
+                if news.is_none_or(|record| record.block_hash != current_block_hash) {
+                    self.save_news_record(
+                        &key,
+                        NewsRecord::new(
+                            FeeEstimateUnavailableNews { fee_rate },
+                            current_block_hash,
+                        ),
+                        coordinator_news,
+                        None,
+                    )?;
+                }
+            }
+            CoordinatorNews::DispatchPausedHighFees(paused_count, fee_rate) => {
+                let key = self.get_key(StoreKey::DispatchPausedHighFeesNews);
+                let news = self.get_value::<&str, NewsRecord<DispatchPausedHighFeesNews>>(&key)?;
+
+                // Only one news per block, the dispatches paused by later ticks of the same block are not reported.
+                if news.is_none_or(|record| record.block_hash != current_block_hash) {
+                    self.save_news_record(
+                        &key,
+                        NewsRecord::new(
+                            DispatchPausedHighFeesNews {
+                                paused_count,
+                                fee_rate,
+                            },
+                            current_block_hash,
+                        ),
+                        coordinator_news,
+                        None,
+                    )?;
+                }
+            }
+            CoordinatorNews::TickPartialFailure(failed_count) => {
+                // Only the last tick with failures is reported.
+                let key = self.get_key(StoreKey::TickPartialFailureNews);
+                self.save_news_record(
+                    &key,
+                    NewsRecord::new(TickPartialFailureNews { failed_count }, current_block_hash),
+                    coordinator_news,
+                    None,
+                )?;
+            }
+            CoordinatorNews::NodeUnreachable(since) => {
+                // Only the last outage is reported.
+                let key = self.get_key(StoreKey::NodeUnreachableNews);
+                self.save_news_record(
+                    &key,
+                    NewsRecord::new(NodeUnreachableNews { since }, current_block_hash),
+                    coordinator_news,
+                    None,
+                )?;
+            }
+            CoordinatorNews::NodeRecovered(unreachable_ms) => {
+                let key = self.get_key(StoreKey::NodeRecoveredNews);
+                self.save_news_record(
+                    &key,
+                    NewsRecord::new(NodeRecoveredNews { unreachable_ms }, current_block_hash),
+                    coordinator_news,
+                    None,
+                )?;
+            }
+            CoordinatorNews::SettingsUpdated(changes) => {
+                // Only the last update is reported, every update is kept in the journal.
+                let key = self.get_key(StoreKey::SettingsUpdatedNews);
+                self.save_news_record(
+                    &key,
+                    NewsRecord::new(SettingsUpdatedNews { changes }, current_block_hash),
+                    coordinator_news,
+                    None,
+                )?;
+            }
+            CoordinatorNews::EstimateFeerateTooHigh(estimated_fee_rate, max_fee_rate) => self
+                .report_news_in_block(
+                    StoreKey::EstimateFeerateTooHighNewsList,
+                    EstimateFeerateTooHighNews {
+                        estimated_fee_rate,
+                        max_fee_rate,
+                    },
+                    current_block_hash,
+                    |news| {
+                        news.estimated_fee_rate == estimated_fee_rate
+                            && news.max_fee_rate == max_fee_rate
+                    },
+                )?,
+            CoordinatorNews::TransactionAlreadyInMempool(tx_id, context) => self
+                .report_news_in_block(
+                    StoreKey::TransactionAlreadyInMempoolNewsList,
+                    TransactionAlreadyInMempoolNews { tx_id, context },
+                    current_block_hash,
+                    |news| news.tx_id == tx_id,
+                )?,
+            CoordinatorNews::MempoolRejection(tx_id, context, error) => self.report_news_in_block(
+                StoreKey::MempoolRejectionNewsList,
+                MempoolRejectionNews {
+                    tx_id,
+                    context,
+                    error,
+                },
+                current_block_hash,
+                |news| news.tx_id == tx_id,
+            )?,
+            CoordinatorNews::NetworkError(tx_id, context, error) => self.report_news_in_block(
+                StoreKey::NetworkErrorNewsList,
+                NetworkErrorNews {
+                    tx_id,
+                    context,
+                    error,
+                },
+                current_block_hash,
+                |news| news.tx_id == tx_id,
+            )?,
+            CoordinatorNews::DispatchCancelled(tx_id, context) => self.report_news_in_block(
+                StoreKey::DispatchCancelledNewsList,
+                DispatchCancelledNews { tx_id, context },
+                current_block_hash,
+                |news| news.tx_id == tx_id,
+            )?,
+            CoordinatorNews::TransactionExpired(tx_id, context, expires_at) => {
+                // A transaction expires once
+                self.report_news_once(
+                    StoreKey::TransactionExpiredNewsList,
+                    TransactionExpiredNews {
+                        tx_id,
+                        context,
+                        expires_at,
+                    },
+                    current_block_hash,
+                    |news| news.tx_id == tx_id,
+                )?
+            }
+            CoordinatorNews::NonStandardAnchor(tx_id, context, reason) => self
+                .report_news_in_block(
+                    StoreKey::NonStandardAnchorNewsList,
+                    NonStandardAnchorNews {
+                        tx_id,
+                        context,
+                        reason,
+                    },
+                    current_block_hash,
+                    |news| news.tx_id == tx_id,
+                )?,
+            CoordinatorNews::RbfEscalationFailed(tx_id, attempts, error) => self
+                .report_news_in_block(
+                    StoreKey::RbfEscalationFailedNewsList,
+                    RbfEscalationFailedNews {
+                        tx_id,
+                        attempts,
+                        error,
+                    },
+                    current_block_hash,
+                    |news| news.tx_id == tx_id,
+                )?,
+            CoordinatorNews::MaxRbfAttemptsReached(tx_id, attempts, fee) => {
+                let key = self.get_key(StoreKey::MaxRbfAttemptsReachedNewsList);
+                let mut news_list = self
+                    .get_value::<&str, Vec<NewsRecord<MaxRbfAttemptsReachedNews>>>(&key)?
+                    .unwrap_or_default();
+
+                let news = NewsRecord::new(
+                    MaxRbfAttemptsReachedNews {
+                        tx_id,
+                        attempts,
+                        fee,
+                    },
+                    current_block_hash,
+                );
+
+                // The news is reported on every tick while the cpfp is not confirmed,
+                // it is only reported again when the replacements change.
+                match news_list
+                    .iter()
+                    .position(|record| record.news.tx_id == tx_id)
+                {
+                    Some(pos) => {
+                        let last = &news_list[pos].news;
+
+                        if last.attempts != attempts || last.fee != fee {
+                            news_list[pos] = news;
+                        }
+                    }
+                    None => news_list.push(news),
+                }
+
+                self.save_news_list(&key, &mut news_list, None)?;
+            }
+            CoordinatorNews::TransactionRebroadcast(tx_id, attempt) => {
+                let key = self.get_key(StoreKey::TransactionRebroadcastNewsList);
+                let mut news_list = self
+                    .get_value::<&str, Vec<NewsRecord<TransactionRebroadcastNews>>>(&key)?
+                    .unwrap_or_default();
+
+                let news = NewsRecord::new(
+                    TransactionRebroadcastNews { tx_id, attempt },
+                    current_block_hash,
+                );
+
+                // Every attempt is reported again, even if the previous one was acknowledged.
+                match news_list
+                    .iter()
+                    .position(|record| record.news.tx_id == tx_id)
+                {
+                    Some(pos) => {
+                        if news_list[pos].news.attempt != attempt {
+                            news_list[pos] = news;
+                        }
+                    }
+                    None => news_list.push(news),
+                }
+
+                self.save_news_list(&key, &mut news_list, None)?;
+            }
+            CoordinatorNews::MaxRebroadcastAttemptsReached(tx_id, attempts) => {
+                // The news is reported on every tick while the transaction is missing, it is only stored once.
+                self.report_news_once(
+                    StoreKey::MaxRebroadcastAttemptsReachedNewsList,
+                    MaxRebroadcastAttemptsReachedNews { tx_id, attempts },
+                    current_block_hash,
+                    |news| news.tx_id == tx_id,
+                )?
+            }
+            CoordinatorNews::SpeedupOrphaned(tx_id, paid_txids) => self.report_news_in_block(
+                StoreKey::SpeedupOrphanedNewsList,
+                SpeedupOrphanedNews { tx_id, paid_txids },
+                current_block_hash,
+                |news| news.tx_id == tx_id,
+            )?,
+            CoordinatorNews::SpeedupChainInvalidated(tx_ids) => {
+                let is_same = |news: &SpeedupChainInvalidatedNews| news.tx_ids == tx_ids;
+
+                self.report_news_in_block(
+                    StoreKey::SpeedupChainInvalidatedNewsList,
+                    SpeedupChainInvalidatedNews {
+                        tx_ids: tx_ids.clone(),
+                    },
+                    current_block_hash,
+                    is_same,
+                )?
+            }
+            CoordinatorNews::SpeedupCreated(tx_id, paid_txids, fee, fee_rate, is_rbf) => {
+                // A speedup is created once, it is only stored the first time it is reported.
+                self.report_news_once(
+                    StoreKey::SpeedupCreatedNewsList,
+                    SpeedupCreatedNews {
+                        tx_id,
+                        paid_txids,
+                        fee,
+                        fee_rate,
+                        is_rbf,
+                    },
+                    current_block_hash,
+                    |news| news.tx_id == tx_id,
+                )?
+            }
+            CoordinatorNews::TransactionConflicted(tx_id, conflicting_txid, context) => self
+                .report_news_in_block(
+                    StoreKey::TransactionConflictedNewsList,
+                    TransactionConflictedNews {
+                        tx_id,
+                        conflicting_txid,
+                        context,
+                    },
+                    current_block_hash,
+                    |news| news.tx_id == tx_id,
+                )?,
+            CoordinatorNews::TransactionReorged(tx_id, orphan_block_hash, context) => {
+                let key = self.get_key(StoreKey::TransactionReorgedNewsList);
+                let mut news_list = self.transaction_reorged_news_list(
+                    tx_id,
+                    orphan_block_hash,
+                    context,
+                    current_block_hash,
+                )?;
+
+                self.save_news_list(&key, &mut news_list, None)?;
+            }
+            CoordinatorNews::DispatchScheduled(tx_id, block_height) => self.report_news_in_block(
+                StoreKey::DispatchScheduledNewsList,
+                DispatchScheduledNews {
+                    tx_id,
+                    block_height,
+                },
+                current_block_hash,
+                |news| news.tx_id == tx_id,
+            )?,
+            CoordinatorNews::DependencyFailed(tx_id, dependency_txid) => self
+                .report_news_in_block(
+                    StoreKey::DependencyFailedNewsList,
+                    DependencyFailedNews {
+                        tx_id,
+                        dependency_txid,
+                    },
+                    current_block_hash,
+                    |news| news.tx_id == tx_id,
+                )?,
+            CoordinatorNews::OutpointSpent(
+                outpoint,
+                spending_txid,
+                input_index,
+                block_info,
+                context,
+            ) => {
+                let key = self.get_key(StoreKey::OutpointSpentNewsList);
+                let mut news_list = self
+                    .get_value::<&str, Vec<NewsRecord<OutpointSpentNews>>>(&key)?
+                    .unwrap_or_default();
+
+                let is_new_news = news_list
+                    .iter()
+                    .position(|record| record.news.outpoint == outpoint);
+
+                let news = NewsRecord::new(
+                    OutpointSpentNews {
+                        outpoint,
+                        spending_txid,
+                        input_index,
+                        block_info,
+                        context,
+                    },
+                    current_block_hash,
+                );
 
                 if let Some(pos) = is_new_news {
-                    let (_, _, _, (last_block_hash, _)) = &news_list[pos];
+                    let last = &news_list[pos].news;
 
-                    if last_block_hash != &current_block_hash {
-                        // Update the news if the block hash is different
-                        news_list[pos] = (tx_id, context, error, (current_block_hash, false));
+                    // The spend is reported on every confirmation, the news is only updated when
+                    // the outpoint is spent by another transaction or in another block (reorg).
+                    if last.spending_txid != news.news.spending_txid
+                        || last.block_info.hash != news.news.block_info.hash
+                    {
+                        news_list[pos] = news;
                     }
                 } else {
-                    // Insert news if it doesn't already exist
-                    news_list.push((tx_id, context, error, (current_block_hash, false)));
+                    news_list.push(news);
                 }
 
-                self.store.set(&key, &news_list, None)?;
+                self.save_news_list(&key, &mut news_list, None)?;
+            }
+            CoordinatorNews::AddressFunded(script_pubkey, tx, outputs, block_info, context) => {
+                let tx_id = tx.compute_txid();
+                let is_same = |news: &AddressFundedNews| {
+                    news.script_pubkey == script_pubkey && news.tx.compute_txid() == tx_id
+                };
+
+                self.report_news_in_block(
+                    StoreKey::AddressFundedNewsList,
+                    AddressFundedNews {
+                        script_pubkey: script_pubkey.clone(),
+                        tx,
+                        outputs,
+                        block_info,
+                        context,
+                    },
+                    current_block_hash,
+                    is_same,
+                )?
+            }
+            CoordinatorNews::FundingSpentExternally(outpoint, spending_txid) => {
+                // A funding is invalidated once
+                self.report_news_once(
+                    StoreKey::FundingSpentExternallyNewsList,
+                    FundingSpentExternallyNews {
+                        outpoint,
+                        spending_txid,
+                    },
+                    current_block_hash,
+                    |news| news.outpoint == outpoint,
+                )?
+            }
+            CoordinatorNews::FundingExhausted(tx_id, change_sats) => {
+                // Reported when the speedup is sent, a speedup found already in the mempool reports it again
+                self.report_news_once(
+                    StoreKey::FundingExhaustedNewsList,
+                    FundingExhaustedNews { tx_id, change_sats },
+                    current_block_hash,
+                    |news| news.tx_id == tx_id,
+                )?
+            }
+            CoordinatorNews::FundingTemporarilyUnavailable(tx_id, paid_txids) => self
+                .report_news_in_block(
+                    StoreKey::FundingTemporarilyUnavailableNewsList,
+                    FundingTemporarilyUnavailableNews { tx_id, paid_txids },
+                    current_block_hash,
+                    |news| news.tx_id == tx_id,
+                )?,
+            CoordinatorNews::AnchorSpentExternally(tx_id, anchor, spending_txid) => {
+                // The speedup output of a transaction is spent once
+                self.report_news_once(
+                    StoreKey::AnchorSpentExternallyNewsList,
+                    AnchorSpentExternallyNews {
+                        tx_id,
+                        anchor,
+                        spending_txid,
+                    },
+                    current_block_hash,
+                    |news| news.tx_id == tx_id,
+                )?
             }
-            CoordinatorNews::DispatchSpeedUpError(tx_ids, contexts, txid, error) => {
-                let key = self.get_key(StoreKey::DispatchSpeedUpErrorNewsList);
+            CoordinatorNews::FeeOverpayment(tx_id, paid_fee_rate, reference_fee_rate) => self
+                .report_news_in_block(
+                    StoreKey::FeeOverpaymentNewsList,
+                    FeeOverpaymentNews {
+                        tx_id,
+                        paid_fee_rate,
+                        reference_fee_rate,
+                    },
+                    current_block_hash,
+                    |news| news.tx_id == tx_id,
+                )?,
+            // A milestone reached again after a reorg replaces the acknowledged news with a new one
+            CoordinatorNews::ConfirmationMilestone(tx_id, confirmations, context) => self
+                .report_news_in_block(
+                    StoreKey::ConfirmationMilestoneNewsList,
+                    ConfirmationMilestoneNews {
+                        tx_id,
+                        confirmations,
+                        context,
+                    },
+                    current_block_hash,
+                    |news| news.tx_id == tx_id && news.confirmations == confirmations,
+                )?,
+            CoordinatorNews::CollateralSpent(label, outpoint, spending_txid, unspent_count) => self
+                .report_news_in_block(
+                    StoreKey::CollateralSpentNewsList,
+                    CollateralSpentNews {
+                        label,
+                        outpoint,
+                        spending_txid,
+                        unspent_count,
+                    },
+                    current_block_hash,
+                    |news| news.outpoint == outpoint,
+                )?,
+            CoordinatorNews::CollateralFullySpent(label) => self.report_news_in_block(
+                StoreKey::CollateralFullySpentNewsList,
+                CollateralFullySpentNews {
+                    label: label.clone(),
+                },
+                current_block_hash,
+                |news| news.label == label,
+            )?,
+            // The same work is skipped again on every tick until the state is fixed, it is reported once per block.
+            CoordinatorNews::TickWorkSkipped(reason, error) => self.report_news_in_block(
+                StoreKey::TickWorkSkippedNewsList,
+                TickWorkSkippedNews { reason, error },
+                current_block_hash,
+                |news| news.reason == reason,
+            )?,
+            CoordinatorNews::GroupCompleted(group_id, context) => self.report_news_in_block(
+                StoreKey::GroupCompletedNewsList,
+                GroupCompletedNews { group_id, context },
+                current_block_hash,
+                |news| news.group_id == group_id,
+            )?,
+            CoordinatorNews::SpeedupRejectedByPolicy(tx_id, reason) => self.report_news_in_block(
+                StoreKey::SpeedupRejectedByPolicyNewsList,
+                SpeedupRejectedByPolicyNews { tx_id, reason },
+                current_block_hash,
+                |news| news.tx_id == tx_id,
+            )?,
+            CoordinatorNews::DispatchDeferred(tx_ids, reason) => {
+                let key = self.get_key(StoreKey::DispatchDeferredNewsList);
                 let mut news_list = self
-                    .store
-                    .get::<&str, Vec<(Vec<Txid>, Vec<String>, Txid, String, (BlockHash, bool))>>(
-                        &key,
-                    )?
+                    .get_value::<&str, Vec<NewsRecord<DispatchDeferredNews>>>(&key)?
                     .unwrap_or_default();
 
-                let is_new_news = news_list
-                    .iter()
-                    .position(|(ids, _, id, _, _)| ids == &tx_ids && id == &txid);
+                let news =
+                    NewsRecord::new(DispatchDeferredNews { tx_ids, reason }, current_block_hash);
+
+                // The transactions are deferred again on every tick, the news is only replaced when
+                // another set of transactions is deferred for the same reason.
+                match news_list
+                    .iter()
+                    .position(|record| record.news.reason == reason)
+                {
+                    Some(pos) if news_list[pos].news.tx_ids == news.news.tx_ids => return Ok(()),
+                    Some(pos) => news_list[pos] = news,
+                    None => news_list.push(news),
+                }
+
+                self.save_news_list(&key, &mut news_list, None)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn watch_outpoint(
+        &self,
+        outpoint: OutPoint,
+        context: String,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        let key = self.get_key(StoreKey::WatchedOutpointList);
+        let mut watched = self
+            .get_value::<&str, Vec<WatchedOutpoint>>(&key)?
+            .unwrap_or_default();
+
+        watched.retain(|watch| watch.outpoint != outpoint);
+        watched.push(WatchedOutpoint { outpoint, context });
+
+        self.set_value(&key, &watched, None)?;
+
+        Ok(())
+    }
+
+    fn unwatch_outpoint(&self, outpoint: OutPoint) -> Result<bool, BitcoinCoordinatorStoreError> {
+        let key = self.get_key(StoreKey::WatchedOutpointList);
+        let mut watched = self
+            .get_value::<&str, Vec<WatchedOutpoint>>(&key)?
+            .unwrap_or_default();
+
+        let len = watched.len();
+        watched.retain(|watch| watch.outpoint != outpoint);
+
+        if watched.len() == len {
+            return Ok(false);
+        }
+
+        self.set_value(&key, &watched, None)?;
+
+        Ok(true)
+    }
+
+    fn get_watched_outpoints(&self) -> Result<Vec<WatchedOutpoint>, BitcoinCoordinatorStoreError> {
+        let key = self.get_key(StoreKey::WatchedOutpointList);
+        let watched = self
+            .get_value::<&str, Vec<WatchedOutpoint>>(&key)?
+            .unwrap_or_default();
+
+        Ok(watched)
+    }
+
+    fn watch_address(
+        &self,
+        script_pubkey: ScriptBuf,
+        context: String,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        let key = self.get_key(StoreKey::WatchedAddressList);
+        let mut watched = self
+            .get_value::<&str, Vec<WatchedAddress>>(&key)?
+            .unwrap_or_default();
+
+        watched.retain(|watch| watch.script_pubkey != script_pubkey);
+        watched.push(WatchedAddress {
+            script_pubkey,
+            context,
+        });
+
+        self.set_value(&key, &watched, None)?;
+
+        Ok(())
+    }
+
+    fn unwatch_address(
+        &self,
+        script_pubkey: &ScriptBuf,
+    ) -> Result<bool, BitcoinCoordinatorStoreError> {
+        let key = self.get_key(StoreKey::WatchedAddressList);
+        let mut watched = self
+            .get_value::<&str, Vec<WatchedAddress>>(&key)?
+            .unwrap_or_default();
+
+        let len = watched.len();
+        watched.retain(|watch| &watch.script_pubkey != script_pubkey);
+
+        if watched.len() == len {
+            return Ok(false);
+        }
+
+        self.set_value(&key, &watched, None)?;
+
+        Ok(true)
+    }
+
+    fn get_watched_addresses(&self) -> Result<Vec<WatchedAddress>, BitcoinCoordinatorStoreError> {
+        let key = self.get_key(StoreKey::WatchedAddressList);
+        let watched = self
+            .get_value::<&str, Vec<WatchedAddress>>(&key)?
+            .unwrap_or_default();
+
+        Ok(watched)
+    }
+
+    fn watch_utxo_set(&self, set: WatchedUtxoSet) -> Result<(), BitcoinCoordinatorStoreError> {
+        let mut watched = self.get_watched_utxo_sets()?;
+
+        match watched.iter_mut().find(|item| item.label == set.label) {
+            Some(item) => *item = set,
+            None => watched.push(set),
+        }
+
+        let key = self.get_key(StoreKey::WatchedUtxoSetList);
+        self.set_value(&key, &watched, None)?;
+
+        Ok(())
+    }
+
+    fn unwatch_utxo_set(
+        &self,
+        label: &str,
+    ) -> Result<Option<WatchedUtxoSet>, BitcoinCoordinatorStoreError> {
+        let mut watched = self.get_watched_utxo_sets()?;
+
+        let pos = match watched.iter().position(|set| set.label == label) {
+            Some(pos) => pos,
+            None => return Ok(None),
+        };
+        let set = watched.remove(pos);
+
+        let key = self.get_key(StoreKey::WatchedUtxoSetList);
+        self.set_value(&key, &watched, None)?;
+
+        Ok(Some(set))
+    }
+
+    fn get_watched_utxo_sets(&self) -> Result<Vec<WatchedUtxoSet>, BitcoinCoordinatorStoreError> {
+        let key = self.get_key(StoreKey::WatchedUtxoSetList);
+        let watched = self
+            .get_value::<&str, Vec<WatchedUtxoSet>>(&key)?
+            .unwrap_or_default();
+
+        Ok(watched)
+    }
+
+    fn save_tx_group(&self, group: GroupStatus) -> Result<(), BitcoinCoordinatorStoreError> {
+        let mut groups = self.get_tx_groups()?;
+
+        match groups
+            .iter_mut()
+            .find(|item| item.group_id == group.group_id)
+        {
+            Some(item) => *item = group,
+            None => groups.push(group),
+        }
+
+        let key = self.get_key(StoreKey::TxGroupList);
+        self.set_value(&key, &groups, None)?;
+
+        Ok(())
+    }
+
+    fn get_tx_groups(&self) -> Result<Vec<GroupStatus>, BitcoinCoordinatorStoreError> {
+        let key = self.get_key(StoreKey::TxGroupList);
+        let groups = self
+            .get_value::<&str, Vec<GroupStatus>>(&key)?
+            .unwrap_or_default();
+
+        Ok(groups)
+    }
+
+    fn cancel_group_members(&self, tx_ids: &[Txid]) -> Result<(), BitcoinCoordinatorStoreError> {
+        let mut groups = self.get_tx_groups()?;
+        let mut changed = false;
+
+        for member in groups
+            .iter_mut()
+            .flat_map(|group| group.members.iter_mut())
+            .filter(|member| {
+                member.state == GroupMemberState::Pending && tx_ids.contains(&member.tx_id)
+            })
+        {
+            member.state = GroupMemberState::Cancelled;
+            changed = true;
+        }
+
+        if changed {
+            let key = self.get_key(StoreKey::TxGroupList);
+            self.set_value(&key, &groups, None)?;
+        }
+
+        Ok(())
+    }
+
+    fn watch_finality(&self, watch: WatchedFinality) -> Result<(), BitcoinCoordinatorStoreError> {
+        let mut watched = self.get_watched_finalities()?;
+
+        watched.retain(|item| item.tx_id != watch.tx_id);
+        watched.push(watch);
+
+        let key = self.get_key(StoreKey::WatchedFinalityList);
+        self.set_value(&key, &watched, None)?;
+
+        Ok(())
+    }
+
+    fn unwatch_finality(&self, tx_id: &Txid) -> Result<bool, BitcoinCoordinatorStoreError> {
+        let mut watched = self.get_watched_finalities()?;
+
+        let len = watched.len();
+        watched.retain(|item| item.tx_id != *tx_id);
+
+        if watched.len() == len {
+            return Ok(false);
+        }
+
+        let key = self.get_key(StoreKey::WatchedFinalityList);
+        self.set_value(&key, &watched, None)?;
+
+        Ok(true)
+    }
+
+    fn get_watched_finalities(&self) -> Result<Vec<WatchedFinality>, BitcoinCoordinatorStoreError> {
+        let key = self.get_key(StoreKey::WatchedFinalityList);
+        let watched = self
+            .get_value::<&str, Vec<WatchedFinality>>(&key)?
+            .unwrap_or_default();
+
+        Ok(watched)
+    }
+
+    fn watch_rsk_pegins(&self, context: String) -> Result<(), BitcoinCoordinatorStoreError> {
+        let key = self.get_key(StoreKey::RskPeginContext);
+        self.set_value(&key, &context, None)?;
+
+        Ok(())
+    }
+
+    fn get_rsk_pegin_context(&self) -> Result<Option<String>, BitcoinCoordinatorStoreError> {
+        let key = self.get_key(StoreKey::RskPeginContext);
+        let context = self.get_value::<&str, String>(&key)?;
+
+        Ok(context)
+    }
+
+    fn set_new_block_subscription(
+        &self,
+        subscribed: bool,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        let key = self.get_key(StoreKey::NewBlockSubscription);
+        self.set_value(&key, subscribed, None)?;
+
+        Ok(())
+    }
+
+    fn is_subscribed_to_new_blocks(&self) -> Result<bool, BitcoinCoordinatorStoreError> {
+        let key = self.get_key(StoreKey::NewBlockSubscription);
+        let subscribed = self.get_value::<&str, bool>(&key)?.unwrap_or(false);
+
+        Ok(subscribed)
+    }
+
+    fn set_run_state(
+        &self,
+        state: CoordinatorRunState,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        let key = self.get_key(StoreKey::RunState);
+        self.set_value(&key, state, None)
+    }
+
+    fn get_run_state(&self) -> Result<Option<CoordinatorRunState>, BitcoinCoordinatorStoreError> {
+        let key = self.get_key(StoreKey::RunState);
+        self.get_value::<&str, CoordinatorRunState>(&key)
+    }
+
+    fn acquire_ownership(
+        &self,
+        instance_id: Uuid,
+        stale_after_seconds: u64,
+    ) -> Result<Option<StoreOwner>, BitcoinCoordinatorStoreError> {
+        let now = self.now_millis();
+        let key = self.get_key(StoreKey::Owner);
+
+        // Compare and set in a single store transaction: the owner is read again right before the commit, and
+        // when another instance took the store in the meantime nothing is written, so two instances never
+        // both own it.
+        self.atomically(|transaction_id| {
+            let current = self.get_store_owner()?;
+            let previous = current
+                .clone()
+                .filter(|owner| owner.instance_id != instance_id);
 
-                if let Some(pos) = is_new_news {
-                    let (_, _, _, _, (last_block_hash, _)) = &news_list[pos];
-
-                    info!("last_block_hash: {:?} ", last_block_hash);
-                    info!("current_block_hash: {:?} ", current_block_hash);
-                    if last_block_hash != &current_block_hash {
-                        // Update the news if the block hash is different
-                        news_list[pos] =
-                            (tx_ids, contexts, txid, error, (current_block_hash, false));
-                    }
-                } else {
-                    // Insert news if it doesn't already exist
-                    news_list.push((tx_ids, contexts, txid, error, (current_block_hash, false)));
+            if let Some(owner) = &previous {
+                let heartbeat_age_seconds = now.saturating_sub(owner.heartbeat_at) / 1000;
+
+                if heartbeat_age_seconds < stale_after_seconds {
+                    return Err(BitcoinCoordinatorStoreError::StoreAlreadyOwned(
+                        owner.instance_id,
+                        owner.pid,
+                        heartbeat_age_seconds,
+                    ));
                 }
+            }
+
+            let owner = StoreOwner {
+                instance_id,
+                pid: std::process::id(),
+                heartbeat_at: now,
+            };
+            self.set_value(&key, owner, Some(transaction_id))?;
 
-                self.store.set(&key, &news_list, None)?;
+            let latest = self.get_store_owner()?;
+            if latest != current {
+                return Err(match latest {
+                    Some(owner) => BitcoinCoordinatorStoreError::StoreAlreadyOwned(
+                        owner.instance_id,
+                        owner.pid,
+                        now.saturating_sub(owner.heartbeat_at) / 1000,
+                    ),
+                    None => BitcoinCoordinatorStoreError::StoreOwnershipLost(None),
+                });
             }
-            CoordinatorNews::FundingNotFound => {
-                let key = self.get_key(StoreKey::FundingNotFoundNews);
-                let news = self.store.get::<&str, (BlockHash, bool)>(&key)?;
 
-                if let Some((last_block_hash, _)) = news {
-                    // If there is existing news, check if the block hash differs
-                    if last_block_hash != current_block_hash {
-                        self.store.set(&key, (current_block_hash, false), None)?;
-                    }
-                } else {
-                    // If no existing news, set the current block hash and mark it as not acknowledged
-                    self.store.set(&key, (current_block_hash, false), None)?;
-                }
+            Ok(previous)
+        })
+    }
+
+    fn refresh_ownership(&self, instance_id: Uuid) -> Result<(), BitcoinCoordinatorStoreError> {
+        let key = self.get_key(StoreKey::Owner);
+        let mut owner = match self.get_store_owner()? {
+            Some(owner) if owner.instance_id == instance_id => owner,
+            other => {
+                return Err(BitcoinCoordinatorStoreError::StoreOwnershipLost(
+                    other.map(|owner| owner.instance_id),
+                ))
             }
-            CoordinatorNews::EstimateFeerateTooHigh(estimate_fee, max_allowed) => {
-                let key = self.get_key(StoreKey::EstimateFeerateTooHighNewsList);
-                let mut news_list = self
-                    .store
-                    .get::<&str, Vec<(u64, u64, (BlockHash, bool))>>(&key)?
-                    .unwrap_or_default();
+        };
 
-                let is_new_news = news_list
-                    .iter()
-                    .position(|(fee, max, _)| *fee == estimate_fee && *max == max_allowed);
+        owner.heartbeat_at = self.now_millis();
+        self.set_value(&key, owner, None)
+    }
 
-                if let Some(pos) = is_new_news {
-                    let (_, _, (last_block_hash, _)) = &news_list[pos];
+    fn check_ownership(&self, instance_id: Uuid) -> Result<(), BitcoinCoordinatorStoreError> {
+        match self.get_store_owner()? {
+            Some(owner) if owner.instance_id == instance_id => Ok(()),
+            other => Err(BitcoinCoordinatorStoreError::StoreOwnershipLost(
+                other.map(|owner| owner.instance_id),
+            )),
+        }
+    }
 
-                    if last_block_hash != &current_block_hash {
-                        // Replace the notification if the block hash is different
-                        news_list[pos] = (estimate_fee, max_allowed, (current_block_hash, false));
-                    }
-                } else {
-                    // Insert news if it doesn't already exist
-                    news_list.push((estimate_fee, max_allowed, (current_block_hash, false)));
-                }
+    fn release_ownership(&self, instance_id: Uuid) -> Result<(), BitcoinCoordinatorStoreError> {
+        if self.check_ownership(instance_id).is_ok() {
+            let key = self.get_key(StoreKey::Owner);
+            self.store.remove(&key, None)?;
+        }
 
-                self.store.set(&key, &news_list, None)?;
-            }
-            CoordinatorNews::TransactionAlreadyInMempool(tx_id, context) => {
-                let key = self.get_key(StoreKey::TransactionAlreadyInMempoolNewsList);
-                let mut news_list = self
-                    .store
-                    .get::<&str, Vec<(Txid, String, (BlockHash, bool))>>(&key)?
-                    .unwrap_or_default();
+        Ok(())
+    }
 
-                let is_new_news = news_list.iter().position(|(id, _, _)| id == &tx_id);
+    fn get_store_owner(&self) -> Result<Option<StoreOwner>, BitcoinCoordinatorStoreError> {
+        let key = self.get_key(StoreKey::Owner);
+        self.get_value::<&str, StoreOwner>(&key)
+    }
 
-                if let Some(pos) = is_new_news {
-                    let (_, _, (last_block_hash, _)) = &news_list[pos];
+    fn get_delivered_news(
+        &self,
+        subscriber_id: &str,
+    ) -> Result<Vec<String>, BitcoinCoordinatorStoreError> {
+        let key = self.get_key(StoreKey::NewsSubscriberDelivered(subscriber_id.to_string()));
+        let delivered = self
+            .get_value::<&str, Vec<String>>(&key)?
+            .unwrap_or_default();
 
-                    if last_block_hash != &current_block_hash {
-                        news_list[pos] = (tx_id, context, (current_block_hash, false));
-                    }
-                } else {
-                    news_list.push((tx_id, context, (current_block_hash, false)));
-                }
+        Ok(delivered)
+    }
 
-                self.store.set(&key, &news_list, None)?;
-            }
-            CoordinatorNews::MempoolRejection(tx_id, context, error) => {
-                let key = self.get_key(StoreKey::MempoolRejectionNewsList);
-                let mut news_list = self
-                    .store
-                    .get::<&str, Vec<(Txid, String, String, (BlockHash, bool))>>(&key)?
-                    .unwrap_or_default();
+    fn set_delivered_news(
+        &self,
+        subscriber_id: &str,
+        delivered: Vec<String>,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        let key = self.get_key(StoreKey::NewsSubscriberDelivered(subscriber_id.to_string()));
+        self.set_value(&key, delivered, None)
+    }
 
-                let is_new_news = news_list.iter().position(|(id, _, _, _)| id == &tx_id);
+    fn save_detected_pegin(
+        &self,
+        pegin: DetectedPegin,
+    ) -> Result<bool, BitcoinCoordinatorStoreError> {
+        let key = self.get_key(StoreKey::DetectedPeginList);
+        let mut pegins = self
+            .get_value::<&str, Vec<DetectedPegin>>(&key)?
+            .unwrap_or_default();
 
-                if let Some(pos) = is_new_news {
-                    let (_, _, _, (last_block_hash, _)) = &news_list[pos];
+        // The monitor reports the peg-in on every tick until it is acknowledged.
+        // After a reorg it could be mined in another block, the record is updated.
+        match pegins
+            .iter()
+            .position(|recorded| recorded.tx_id == pegin.tx_id)
+        {
+            Some(pos) if pegins[pos].block_height == pegin.block_height => return Ok(false),
+            Some(pos) => pegins[pos] = pegin,
+            None => pegins.push(pegin),
+        }
 
-                    if last_block_hash != &current_block_hash {
-                        news_list[pos] = (tx_id, context, error, (current_block_hash, false));
-                    }
-                } else {
-                    news_list.push((tx_id, context, error, (current_block_hash, false)));
-                }
+        self.set_value(&key, &pegins, None)?;
 
-                self.store.set(&key, &news_list, None)?;
-            }
-            CoordinatorNews::NetworkError(tx_id, context, error) => {
-                let key = self.get_key(StoreKey::NetworkErrorNewsList);
-                let mut news_list = self
-                    .store
-                    .get::<&str, Vec<(Txid, String, String, (BlockHash, bool))>>(&key)?
-                    .unwrap_or_default();
+        Ok(true)
+    }
 
-                let is_new_news = news_list.iter().position(|(id, _, _, _)| id == &tx_id);
+    fn get_detected_pegins(
+        &self,
+        since_height: BlockHeight,
+    ) -> Result<Vec<DetectedPegin>, BitcoinCoordinatorStoreError> {
+        let key = self.get_key(StoreKey::DetectedPeginList);
+        let pegins = self
+            .get_value::<&str, Vec<DetectedPegin>>(&key)?
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|pegin| pegin.block_height >= since_height)
+            .collect();
 
-                if let Some(pos) = is_new_news {
-                    let (_, _, _, (last_block_hash, _)) = &news_list[pos];
-                    if last_block_hash != &current_block_hash {
-                        news_list[pos] = (tx_id, context, error, (current_block_hash, false));
-                    }
-                } else {
-                    news_list.push((tx_id, context, error, (current_block_hash, false)));
-                }
+        Ok(pegins)
+    }
 
-                self.store.set(&key, &news_list, None)?;
-            }
-        }
+    fn ack_news(&self, news: AckCoordinatorNews) -> Result<(), BitcoinCoordinatorStoreError> {
+        self.ack_news_batch(vec![news])?;
         Ok(())
     }
 
-    fn ack_news(&self, news: AckCoordinatorNews) -> Result<(), BitcoinCoordinatorStoreError> {
-        match news {
-            AckCoordinatorNews::InsufficientFunds(tx_id) => {
-                let key = self.get_key(StoreKey::InsufficientFundsNewsList);
-                let mut news_list = self
-                    .store
-                    .get::<&str, Vec<(Txid, u64, u64, (BlockHash, bool))>>(&key)?
-                    .unwrap_or_default();
+    fn ack_news_batch(
+        &self,
+        news: Vec<AckCoordinatorNews>,
+    ) -> Result<usize, BitcoinCoordinatorStoreError> {
+        // Group the acks by news type, so each news list is loaded and written once.
+        let mut groups: Vec<Vec<AckCoordinatorNews>> = Vec::new();
 
-                if let Some(pos) = news_list.iter().position(|(id, _, _, _)| *id == tx_id) {
-                    let (_, _, _, (_, ack)) = &mut news_list[pos];
-                    *ack = true;
-                    self.store.set(&key, &news_list, None)?;
-                }
+        for ack in news {
+            let kind = std::mem::discriminant(&ack);
+
+            match groups
+                .iter_mut()
+                .find(|group| std::mem::discriminant(&group[0]) == kind)
+            {
+                Some(group) => group.push(ack),
+                None => groups.push(vec![ack]),
             }
-            AckCoordinatorNews::DispatchTransactionError(tx_id) => {
-                let key = self.get_key(StoreKey::DispatchTransactionErrorNewsList);
-                let mut news_list = self
-                    .store
-                    .get::<&str, Vec<(Txid, String, String, (BlockHash, bool))>>(&key)?
-                    .unwrap_or_default();
+        }
+
+        let mut acknowledged = 0;
+
+        for acks in groups {
+            let mut txids: Vec<Txid> = acks.iter().filter_map(ack_txid).collect();
 
-                if let Some(pos) = news_list.iter().position(|(id, _, _, _)| *id == tx_id) {
-                    let (_, _, _, (_, ack)) = &mut news_list[pos];
-                    *ack = true;
-                    self.store.set(&key, &news_list, None)?;
+            // The news of a replaced transaction are reported for its replacement, they are acknowledged
+            // with either txid. ParentReplaced news are only acknowledged with the replaced txid.
+            if !txids.is_empty() && !matches!(acks[0], AckCoordinatorNews::ParentReplaced(_)) {
+                let replacements = self.get_replacements()?;
+                for txid in txids.iter_mut() {
+                    *txid = find_replacement(&replacements, *txid).unwrap_or(*txid);
                 }
             }
-            AckCoordinatorNews::DispatchSpeedUpError(speedup_txid) => {
-                let key = self.get_key(StoreKey::DispatchSpeedUpErrorNewsList);
-                let mut news_list = self
-                    .store
-                    .get::<&str, Vec<(Vec<Txid>, Vec<String>, Txid, String, (BlockHash, bool))>>(
-                        &key,
+
+            acknowledged += match &acks[0] {
+                AckCoordinatorNews::InsufficientFunds(_) => self.ack_news_list(
+                    StoreKey::InsufficientFundsNewsList,
+                    &txids,
+                    |news: &InsufficientFundsNews| news.tx_id,
+                )?,
+                AckCoordinatorNews::FundingTopUp(_) => self.ack_news_list(
+                    StoreKey::FundingTopUpNewsList,
+                    &txids,
+                    |news: &FundingTopUpNews| news.tx_id,
+                )?,
+                AckCoordinatorNews::ParentReplaced(_) => self.ack_news_list(
+                    StoreKey::ParentReplacedNewsList,
+                    &txids,
+                    |news: &ParentReplacedNews| news.tx_id,
+                )?,
+                AckCoordinatorNews::SpeedupFeeCapExceeded(_) => self.ack_news_list(
+                    StoreKey::SpeedupFeeCapExceededNewsList,
+                    &txids,
+                    |news: &SpeedupFeeCapExceededNews| news.tx_id,
+                )?,
+                AckCoordinatorNews::DispatchTransactionError(_) => self.ack_news_list(
+                    StoreKey::DispatchTransactionErrorNewsList,
+                    &txids,
+                    |news: &DispatchTransactionErrorNews| news.tx_id,
+                )?,
+                AckCoordinatorNews::DispatchSpeedUpError(_) => self.ack_news_list(
+                    StoreKey::DispatchSpeedUpErrorNewsList,
+                    &txids,
+                    |news: &DispatchSpeedUpErrorNews| news.speedup_txid,
+                )?,
+                AckCoordinatorNews::EstimateFeerateTooHigh(_, _) => {
+                    let fee_rates: Vec<(u64, u64)> = acks
+                        .iter()
+                        .filter_map(|ack| match ack {
+                            AckCoordinatorNews::EstimateFeerateTooHigh(fee, max) => {
+                                Some((*fee, *max))
+                            }
+                            _ => None,
+                        })
+                        .collect();
+
+                    self.ack_news_list(
+                        StoreKey::EstimateFeerateTooHighNewsList,
+                        &fee_rates,
+                        |news: &EstimateFeerateTooHighNews| {
+                            (news.estimated_fee_rate, news.max_fee_rate)
+                        },
                     )?
-                    .unwrap_or_default();
+                }
+                AckCoordinatorNews::FundingNotFound => {
+                    self.ack_news_record::<FundingNotFoundNews>(StoreKey::FundingNotFoundNews)?
+                }
+                AckCoordinatorNews::NewBlock => {
+                    self.ack_news_record::<NewBlockNews>(StoreKey::NewBlockNews)?
+                }
+                AckCoordinatorNews::FeeEstimateUnavailable => self
+                    .ack_news_record::<FeeEstimateUnavailableNews>(
+                        StoreKey::FeeEstimateUnavailableNews,
+                    )?,
+                AckCoordinatorNews::DispatchPausedHighFees => self
+                    .ack_news_record::<DispatchPausedHighFeesNews>(
+                        StoreKey::DispatchPausedHighFeesNews,
+                    )?,
+                AckCoordinatorNews::TickPartialFailure => self
+                    .ack_news_record::<TickPartialFailureNews>(StoreKey::TickPartialFailureNews)?,
+                AckCoordinatorNews::NodeUnreachable => {
+                    self.ack_news_record::<NodeUnreachableNews>(StoreKey::NodeUnreachableNews)?
+                }
+                AckCoordinatorNews::NodeRecovered => {
+                    self.ack_news_record::<NodeRecoveredNews>(StoreKey::NodeRecoveredNews)?
+                }
+                AckCoordinatorNews::SettingsUpdated => {
+                    self.ack_news_record::<SettingsUpdatedNews>(StoreKey::SettingsUpdatedNews)?
+                }
+                AckCoordinatorNews::TransactionAlreadyInMempool(_) => self.ack_news_list(
+                    StoreKey::TransactionAlreadyInMempoolNewsList,
+                    &txids,
+                    |news: &TransactionAlreadyInMempoolNews| news.tx_id,
+                )?,
+                AckCoordinatorNews::MempoolRejection(_) => self.ack_news_list(
+                    StoreKey::MempoolRejectionNewsList,
+                    &txids,
+                    |news: &MempoolRejectionNews| news.tx_id,
+                )?,
+                AckCoordinatorNews::NetworkError(_) => self.ack_news_list(
+                    StoreKey::NetworkErrorNewsList,
+                    &txids,
+                    |news: &NetworkErrorNews| news.tx_id,
+                )?,
+                AckCoordinatorNews::DispatchCancelled(_) => self.ack_news_list(
+                    StoreKey::DispatchCancelledNewsList,
+                    &txids,
+                    |news: &DispatchCancelledNews| news.tx_id,
+                )?,
+                AckCoordinatorNews::TransactionExpired(_) => self.ack_news_list(
+                    StoreKey::TransactionExpiredNewsList,
+                    &txids,
+                    |news: &TransactionExpiredNews| news.tx_id,
+                )?,
+                AckCoordinatorNews::NonStandardAnchor(_) => self.ack_news_list(
+                    StoreKey::NonStandardAnchorNewsList,
+                    &txids,
+                    |news: &NonStandardAnchorNews| news.tx_id,
+                )?,
+                AckCoordinatorNews::RbfEscalationFailed(_) => self.ack_news_list(
+                    StoreKey::RbfEscalationFailedNewsList,
+                    &txids,
+                    |news: &RbfEscalationFailedNews| news.tx_id,
+                )?,
+                AckCoordinatorNews::MaxRbfAttemptsReached(_) => self.ack_news_list(
+                    StoreKey::MaxRbfAttemptsReachedNewsList,
+                    &txids,
+                    |news: &MaxRbfAttemptsReachedNews| news.tx_id,
+                )?,
+                AckCoordinatorNews::TransactionRebroadcast(_) => self.ack_news_list(
+                    StoreKey::TransactionRebroadcastNewsList,
+                    &txids,
+                    |news: &TransactionRebroadcastNews| news.tx_id,
+                )?,
+                AckCoordinatorNews::MaxRebroadcastAttemptsReached(_) => self.ack_news_list(
+                    StoreKey::MaxRebroadcastAttemptsReachedNewsList,
+                    &txids,
+                    |news: &MaxRebroadcastAttemptsReachedNews| news.tx_id,
+                )?,
+                AckCoordinatorNews::SpeedupChainInvalidated(_) => self.ack_news_list(
+                    StoreKey::SpeedupChainInvalidatedNewsList,
+                    &txids,
+                    |news: &SpeedupChainInvalidatedNews| news.tx_ids[0],
+                )?,
+                AckCoordinatorNews::SpeedupOrphaned(_) => self.ack_news_list(
+                    StoreKey::SpeedupOrphanedNewsList,
+                    &txids,
+                    |news: &SpeedupOrphanedNews| news.tx_id,
+                )?,
+                AckCoordinatorNews::SpeedupCreated(_) => self.ack_news_list(
+                    StoreKey::SpeedupCreatedNewsList,
+                    &txids,
+                    |news: &SpeedupCreatedNews| news.tx_id,
+                )?,
+                AckCoordinatorNews::TransactionConflicted(_) => self.ack_news_list(
+                    StoreKey::TransactionConflictedNewsList,
+                    &txids,
+                    |news: &TransactionConflictedNews| news.tx_id,
+                )?,
+                AckCoordinatorNews::TransactionReorged(_) => self.ack_news_list(
+                    StoreKey::TransactionReorgedNewsList,
+                    &txids,
+                    |news: &TransactionReorgedNews| news.tx_id,
+                )?,
+                AckCoordinatorNews::DispatchScheduled(_) => self.ack_news_list(
+                    StoreKey::DispatchScheduledNewsList,
+                    &txids,
+                    |news: &DispatchScheduledNews| news.tx_id,
+                )?,
+                AckCoordinatorNews::DependencyFailed(_) => self.ack_news_list(
+                    StoreKey::DependencyFailedNewsList,
+                    &txids,
+                    |news: &DependencyFailedNews| news.tx_id,
+                )?,
+                AckCoordinatorNews::FundingExhausted(_) => self.ack_news_list(
+                    StoreKey::FundingExhaustedNewsList,
+                    &txids,
+                    |news: &FundingExhaustedNews| news.tx_id,
+                )?,
+                AckCoordinatorNews::FundingTemporarilyUnavailable(_) => self.ack_news_list(
+                    StoreKey::FundingTemporarilyUnavailableNewsList,
+                    &txids,
+                    |news: &FundingTemporarilyUnavailableNews| news.tx_id,
+                )?,
+                AckCoordinatorNews::AnchorSpentExternally(_) => self.ack_news_list(
+                    StoreKey::AnchorSpentExternallyNewsList,
+                    &txids,
+                    |news: &AnchorSpentExternallyNews| news.tx_id,
+                )?,
+                AckCoordinatorNews::FeeOverpayment(_) => self.ack_news_list(
+                    StoreKey::FeeOverpaymentNewsList,
+                    &txids,
+                    |news: &FeeOverpaymentNews| news.tx_id,
+                )?,
+                AckCoordinatorNews::OutpointSpent(_) => {
+                    let outpoints: Vec<OutPoint> = acks
+                        .iter()
+                        .filter_map(|ack| match ack {
+                            AckCoordinatorNews::OutpointSpent(outpoint) => Some(*outpoint),
+                            _ => None,
+                        })
+                        .collect();
 
-                if let Some(pos) = news_list
-                    .iter()
-                    .position(|(_, _, txid, _, _)| *txid == speedup_txid)
-                {
-                    let (_, _, _, _, (_, ack)) = &mut news_list[pos];
-                    *ack = true;
-                    self.store.set(&key, &news_list, None)?;
+                    self.ack_news_list(
+                        StoreKey::OutpointSpentNewsList,
+                        &outpoints,
+                        |news: &OutpointSpentNews| news.outpoint,
+                    )?
                 }
-            }
-            AckCoordinatorNews::EstimateFeerateTooHigh(estimate_fee, max_allowed) => {
-                let key = self.get_key(StoreKey::EstimateFeerateTooHighNewsList);
-                let mut news_list = self
-                    .store
-                    .get::<&str, Vec<(u64, u64, (BlockHash, bool))>>(&key)?
-                    .unwrap_or_default();
+                AckCoordinatorNews::ConfirmationMilestone(_, _) => {
+                    let milestones: Vec<(Txid, u32)> = acks
+                        .iter()
+                        .filter_map(|ack| match ack {
+                            AckCoordinatorNews::ConfirmationMilestone(tx_id, confirmations) => {
+                                Some((*tx_id, *confirmations))
+                            }
+                            _ => None,
+                        })
+                        .collect();
 
-                if let Some(pos) = news_list
-                    .iter()
-                    .position(|(fee, max, _)| *fee == estimate_fee && *max == max_allowed)
-                {
-                    let (_, _, (_, ack)) = &mut news_list[pos];
-                    *ack = true;
-                    self.store.set(&key, &news_list, None)?;
+                    self.ack_news_list(
+                        StoreKey::ConfirmationMilestoneNewsList,
+                        &milestones,
+                        |news: &ConfirmationMilestoneNews| (news.tx_id, news.confirmations),
+                    )?
                 }
-            }
-            AckCoordinatorNews::FundingNotFound => {
-                let key = self.get_key(StoreKey::FundingNotFoundNews);
-                let mut news = self.store.get::<&str, (BlockHash, bool)>(&key)?;
+                AckCoordinatorNews::CollateralSpent(_) => {
+                    let outpoints: Vec<OutPoint> = acks
+                        .iter()
+                        .filter_map(|ack| match ack {
+                            AckCoordinatorNews::CollateralSpent(outpoint) => Some(*outpoint),
+                            _ => None,
+                        })
+                        .collect();
 
-                if let Some((block_hash, _)) = news {
-                    news = Some((block_hash, true));
-                    self.store.set(&key, news, None)?;
+                    self.ack_news_list(
+                        StoreKey::CollateralSpentNewsList,
+                        &outpoints,
+                        |news: &CollateralSpentNews| news.outpoint,
+                    )?
                 }
-            }
-            AckCoordinatorNews::TransactionAlreadyInMempool(tx_id) => {
-                let key = self.get_key(StoreKey::TransactionAlreadyInMempoolNewsList);
-                let mut news_list = self
-                    .store
-                    .get::<&str, Vec<(Txid, String, (BlockHash, bool))>>(&key)?
-                    .unwrap_or_default();
+                AckCoordinatorNews::CollateralFullySpent(_) => {
+                    let labels: Vec<String> = acks
+                        .iter()
+                        .filter_map(|ack| match ack {
+                            AckCoordinatorNews::CollateralFullySpent(label) => Some(label.clone()),
+                            _ => None,
+                        })
+                        .collect();
 
-                if let Some(pos) = news_list.iter().position(|(id, _, _)| *id == tx_id) {
-                    let (_, _, (_, ack)) = &mut news_list[pos];
-                    *ack = true;
-                    self.store.set(&key, &news_list, None)?;
+                    self.ack_news_list(
+                        StoreKey::CollateralFullySpentNewsList,
+                        &labels,
+                        |news: &CollateralFullySpentNews| news.label.clone(),
+                    )?
                 }
-            }
-            AckCoordinatorNews::MempoolRejection(tx_id) => {
-                let key = self.get_key(StoreKey::MempoolRejectionNewsList);
-                let mut news_list = self
-                    .store
-                    .get::<&str, Vec<(Txid, String, String, (BlockHash, bool))>>(&key)?
-                    .unwrap_or_default();
+                AckCoordinatorNews::AddressFunded(_, _) => {
+                    let funded: Vec<(ScriptBuf, Txid)> = acks
+                        .iter()
+                        .filter_map(|ack| match ack {
+                            AckCoordinatorNews::AddressFunded(script_pubkey, tx_id) => {
+                                Some((script_pubkey.clone(), *tx_id))
+                            }
+                            _ => None,
+                        })
+                        .collect();
 
-                if let Some(pos) = news_list.iter().position(|(id, _, _, _)| *id == tx_id) {
-                    let (_, _, _, (_, ack)) = &mut news_list[pos];
-                    *ack = true;
-                    self.store.set(&key, &news_list, None)?;
+                    self.ack_news_list(
+                        StoreKey::AddressFundedNewsList,
+                        &funded,
+                        |news: &AddressFundedNews| {
+                            (news.script_pubkey.clone(), news.tx.compute_txid())
+                        },
+                    )?
                 }
-            }
-            AckCoordinatorNews::NetworkError(tx_id) => {
-                let key = self.get_key(StoreKey::NetworkErrorNewsList);
-                let mut news_list = self
-                    .store
-                    .get::<&str, Vec<(Txid, String, String, (BlockHash, bool))>>(&key)?
-                    .unwrap_or_default();
+                AckCoordinatorNews::FundingSpentExternally(_) => {
+                    let outpoints: Vec<OutPoint> = acks
+                        .iter()
+                        .filter_map(|ack| match ack {
+                            AckCoordinatorNews::FundingSpentExternally(outpoint) => Some(*outpoint),
+                            _ => None,
+                        })
+                        .collect();
 
-                if let Some(pos) = news_list.iter().position(|(id, _, _, _)| *id == tx_id) {
-                    let (_, _, _, (_, ack)) = &mut news_list[pos];
-                    *ack = true;
-                    self.store.set(&key, &news_list, None)?;
+                    self.ack_news_list(
+                        StoreKey::FundingSpentExternallyNewsList,
+                        &outpoints,
+                        |news: &FundingSpentExternallyNews| news.outpoint,
+                    )?
                 }
-            }
+                AckCoordinatorNews::DispatchDeferred(_) => {
+                    let reasons: Vec<DispatchDeferredReason> = acks
+                        .iter()
+                        .filter_map(|ack| match ack {
+                            AckCoordinatorNews::DispatchDeferred(reason) => Some(*reason),
+                            _ => None,
+                        })
+                        .collect();
+
+                    self.ack_news_list(
+                        StoreKey::DispatchDeferredNewsList,
+                        &reasons,
+                        |news: &DispatchDeferredNews| news.reason,
+                    )?
+                }
+                AckCoordinatorNews::TickWorkSkipped(_) => {
+                    let reasons: Vec<TickSkipReason> = acks
+                        .iter()
+                        .filter_map(|ack| match ack {
+                            AckCoordinatorNews::TickWorkSkipped(reason) => Some(*reason),
+                            _ => None,
+                        })
+                        .collect();
+
+                    self.ack_news_list(
+                        StoreKey::TickWorkSkippedNewsList,
+                        &reasons,
+                        |news: &TickWorkSkippedNews| news.reason,
+                    )?
+                }
+                AckCoordinatorNews::GroupCompleted(_) => {
+                    let group_ids: Vec<Uuid> = acks
+                        .iter()
+                        .filter_map(|ack| match ack {
+                            AckCoordinatorNews::GroupCompleted(group_id) => Some(*group_id),
+                            _ => None,
+                        })
+                        .collect();
+
+                    self.ack_news_list(
+                        StoreKey::GroupCompletedNewsList,
+                        &group_ids,
+                        |news: &GroupCompletedNews| news.group_id,
+                    )?
+                }
+                AckCoordinatorNews::SpeedupRejectedByPolicy(_) => self.ack_news_list(
+                    StoreKey::SpeedupRejectedByPolicyNewsList,
+                    &txids,
+                    |news: &SpeedupRejectedByPolicyNews| news.tx_id,
+                )?,
+            };
         }
-        Ok(())
+
+        Ok(acknowledged)
     }
 
     fn get_news(&self) -> Result<Vec<CoordinatorNews>, BitcoinCoordinatorStoreError> {
-        let mut all_news = Vec::new();
+        self.get_news_filtered(Severity::Info)
+    }
 
-        // Get insufficient funds news
-        let insufficient_funds_key = self.get_key(StoreKey::InsufficientFundsNewsList);
-        if let Some(news_list) = self
-            .store
-            .get::<&str, Vec<(Txid, u64, u64, (BlockHash, bool))>>(&insufficient_funds_key)?
-        {
-            for (txid, amount, required, (_, acked)) in news_list {
-                if !acked {
-                    all_news.push(CoordinatorNews::InsufficientFunds(txid, amount, required));
-                }
-            }
+    fn get_news_page(
+        &self,
+        since_seq: u64,
+        limit: usize,
+    ) -> Result<(Vec<(u64, CoordinatorNews)>, bool), BitcoinCoordinatorStoreError> {
+        self.read_news_log(since_seq, Some(limit), Severity::Info)
+    }
+
+    fn get_news_filtered(
+        &self,
+        min_severity: Severity,
+    ) -> Result<Vec<CoordinatorNews>, BitcoinCoordinatorStoreError> {
+        let (entries, _) = self.read_news_log(0, None, min_severity)?;
+        Ok(entries.into_iter().map(|(_, news)| news).collect())
+    }
+
+    fn increment_tx_retry_count(&self, txid: Txid) -> Result<(), BitcoinCoordinatorStoreError> {
+        let mut tx = self.get_tx(&txid)?;
+        let new_count = tx.retry_info.as_ref().map_or(0, |info| info.retries_count) + 1;
+        let previous_state = tx.state.clone();
+
+        if new_count >= self.retry_attempts_sending_tx.get() {
+            tx.state = TransactionState::Failed;
+        } else {
+            tx.retry_info = Some(RetryInfo::new(new_count, self.now_millis()));
         }
 
-        // Get dispatch error news
-        let dispatch_error_key = self.get_key(StoreKey::DispatchTransactionErrorNewsList);
-        if let Some(news_list) = self
-            .store
-            .get::<&str, Vec<(Txid, String, String, (BlockHash, bool))>>(&dispatch_error_key)?
-        {
-            for (tx_id, context, error, (_, acked)) in news_list {
-                if !acked {
-                    all_news.push(CoordinatorNews::DispatchTransactionError(
-                        tx_id, context, error,
-                    ));
-                }
-            }
+        self.set_value(self.get_key(StoreKey::Transaction(txid)), &tx, None)?;
+        self.move_state_index(txid, &previous_state, Some(&tx.state), None)?;
+
+        self.record_tx_event(
+            txid,
+            TransactionEvent::DispatchRetried {
+                retries_count: new_count,
+            },
+        )?;
+
+        if previous_state != tx.state {
+            self.record_tx_event(
+                txid,
+                TransactionEvent::StateChanged {
+                    from: previous_state,
+                    to: tx.state,
+                },
+            )?;
         }
 
-        // Get speed up error news
-        let speed_up_error_key = self.get_key(StoreKey::DispatchSpeedUpErrorNewsList);
-        if let Some(news_list) =
-            self.store
-                .get::<&str, Vec<(Vec<Txid>, Vec<String>, Txid, String, (BlockHash, bool))>>(
-                    &speed_up_error_key,
-                )?
-        {
-            for (tx_ids, contexts, txid, error, (_, acked)) in news_list {
-                if !acked {
-                    all_news.push(CoordinatorNews::DispatchSpeedUpError(
-                        tx_ids, contexts, txid, error,
-                    ));
-                }
-            }
+        Ok(())
+    }
+
+    fn reorg_tx(
+        &self,
+        tx_id: Txid,
+        orphan_block_hash: BlockHash,
+        current_block_hash: BlockHash,
+    ) -> Result<CoordinatedTransaction, BitcoinCoordinatorStoreError> {
+        let mut tx = self.get_tx(&tx_id)?;
+
+        if tx.state != TransactionState::Confirmed {
+            return Err(BitcoinCoordinatorStoreError::InvalidTransactionState);
         }
 
-        // Get funding not found news
-        let funding_not_found_key = self.get_key(StoreKey::FundingNotFoundNews);
-        if let Some((_, acked)) = self
-            .store
-            .get::<&str, (BlockHash, bool)>(&funding_not_found_key)?
-        {
-            if !acked {
-                all_news.push(CoordinatorNews::FundingNotFound);
-            }
+        tx.state = TransactionState::Dispatched;
+
+        let mut news_list = self.transaction_reorged_news_list(
+            tx_id,
+            orphan_block_hash,
+            tx.context.clone(),
+            current_block_hash,
+        )?;
+
+        self.ensure_state_indexes()?;
+        self.ensure_news_log()?;
+
+        self.atomically(|transaction_id| {
+            self.set_value(
+                self.get_key(StoreKey::Transaction(tx_id)),
+                &tx,
+                Some(transaction_id),
+            )?;
+
+            self.save_news_list(
+                &self.get_key(StoreKey::TransactionReorgedNewsList),
+                &mut news_list,
+                Some(transaction_id),
+            )?;
+
+            self.move_state_index(
+                tx_id,
+                &TransactionState::Confirmed,
+                Some(&TransactionState::Dispatched),
+                Some(transaction_id),
+            )?;
+
+            self.record_tx_events(
+                tx_id,
+                vec![
+                    TransactionEvent::Reorged {
+                        block_hash: orphan_block_hash,
+                    },
+                    TransactionEvent::StateChanged {
+                        from: TransactionState::Confirmed,
+                        to: TransactionState::Dispatched,
+                    },
+                ],
+                Some(transaction_id),
+            )?;
+
+            Ok(tx)
+        })
+    }
+
+    fn record_tx_rebroadcast(
+        &self,
+        tx_id: Txid,
+        block_height: BlockHeight,
+    ) -> Result<u32, BitcoinCoordinatorStoreError> {
+        let mut tx = self.get_tx(&tx_id)?;
+
+        if tx.state != TransactionState::Dispatched {
+            return Err(BitcoinCoordinatorStoreError::InvalidTransactionState);
         }
 
-        // Get estimate feerate too high news
-        let estimate_feerate_too_high_key = self.get_key(StoreKey::EstimateFeerateTooHighNewsList);
-        if let Some(news_list) = self
-            .store
-            .get::<&str, Vec<(u64, u64, (BlockHash, bool))>>(&estimate_feerate_too_high_key)?
-        {
-            for (estimate_fee, max_allowed, (_, acked)) in news_list {
-                if !acked {
-                    all_news.push(CoordinatorNews::EstimateFeerateTooHigh(
-                        estimate_fee,
-                        max_allowed,
-                    ));
-                }
-            }
+        tx.rebroadcast_count += 1;
+        tx.last_rebroadcast_block_height = Some(block_height);
+
+        let attempt = tx.rebroadcast_count;
+
+        self.set_value(self.get_key(StoreKey::Transaction(tx_id)), &tx, None)?;
+
+        self.record_tx_event(
+            tx_id,
+            TransactionEvent::Rebroadcast {
+                attempt,
+                block_height,
+            },
+        )?;
+
+        Ok(attempt)
+    }
+
+    fn clear_tx_speedup(
+        &self,
+        tx_id: Txid,
+        spending_txid: Txid,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        let mut tx = self.get_tx(&tx_id)?;
+
+        if tx.speedup_data.take().is_none() {
+            return Ok(());
         }
 
-        // Get transaction already in mempool news
-        let already_in_mempool_key = self.get_key(StoreKey::TransactionAlreadyInMempoolNewsList);
-        if let Some(news_list) = self
-            .store
-            .get::<&str, Vec<(Txid, String, (BlockHash, bool))>>(&already_in_mempool_key)?
-        {
-            for (tx_id, context, (_, acked)) in news_list {
-                if !acked {
-                    all_news.push(CoordinatorNews::TransactionAlreadyInMempool(tx_id, context));
-                }
-            }
+        self.set_value(self.get_key(StoreKey::Transaction(tx_id)), &tx, None)?;
+
+        self.record_tx_event(tx_id, TransactionEvent::AnchorSpent { spending_txid })?;
+
+        Ok(())
+    }
+
+    fn prune(
+        &self,
+        recent_blocks: &HashSet<BlockHash>,
+    ) -> Result<PruneSummary, BitcoinCoordinatorStoreError> {
+        let news = self.prune_news(recent_blocks)?;
+        let transactions = self.prune_finalized_txs()?;
+        let mut speedups = self.prune_finalized_speedups()?;
+
+        for group in self.get_funding_groups()? {
+            speedups +=
+                self.with_funding_group(Some(&group), || self.prune_finalized_speedups())?;
         }
 
-        // Get mempool rejection news
-        let mempool_rejection_key = self.get_key(StoreKey::MempoolRejectionNewsList);
-        if let Some(news_list) = self
-            .store
-            .get::<&str, Vec<(Txid, String, String, (BlockHash, bool))>>(&mempool_rejection_key)?
-        {
-            for (tx_id, context, error, (_, acked)) in news_list {
-                if !acked {
-                    all_news.push(CoordinatorNews::MempoolRejection(tx_id, context, error));
-                }
+        Ok(PruneSummary {
+            news,
+            transactions,
+            speedups,
+        })
+    }
+
+    fn repair_dangling_entries(&self) -> Result<u32, BitcoinCoordinatorStoreError> {
+        let mut keys = vec![
+            self.get_key(StoreKey::PendingTransactionList),
+            self.get_key(StoreKey::FinalizedTransactionList),
+        ];
+        for state in [
+            TransactionState::ToDispatch,
+            TransactionState::Dispatched,
+            TransactionState::Confirmed,
+            TransactionState::Failed,
+            TransactionState::Cancelled,
+            TransactionState::Expired,
+        ] {
+            keys.push(self.get_key(StoreKey::TransactionStateList(state)));
+        }
+
+        let mut dropped = self.atomically(|transaction_id| {
+            let mut dropped = 0;
+            for key in keys.iter() {
+                dropped += self.drop_dangling_entries(
+                    key,
+                    |tx_id| self.get_key(StoreKey::Transaction(tx_id)),
+                    transaction_id,
+                )?;
             }
+            Ok(dropped)
+        })?;
+
+        dropped += self.repair_dangling_speedups()?;
+        for group in self.get_funding_groups()? {
+            dropped += self.with_funding_group(Some(&group), || self.repair_dangling_speedups())?;
         }
 
-        // Get network error news
-        let network_error_key = self.get_key(StoreKey::NetworkErrorNewsList);
-        if let Some(news_list) = self
-            .store
-            .get::<&str, Vec<(Txid, String, String, (BlockHash, bool))>>(&network_error_key)?
-        {
-            for (tx_id, context, error, (_, acked)) in news_list {
-                if !acked {
-                    all_news.push(CoordinatorNews::NetworkError(tx_id, context, error));
-                }
+        if dropped > 0 {
+            warn!("Store repaired, {} dangling list entries dropped", dropped);
+        }
+
+        Ok(dropped)
+    }
+
+    fn export_snapshot(&self, path: &Path) -> Result<usize, BitcoinCoordinatorStoreError> {
+        let mut entries = BTreeMap::new();
+
+        for key in self.snapshot_keys()? {
+            if let Some(record) = self.store.get::<&str, serde_json::Value>(&key)? {
+                entries.insert(key, record);
             }
         }
 
-        Ok(all_news)
+        let snapshot = StoreSnapshot::new(entries);
+        snapshot.write(path)?;
+
+        info!(
+            "Store snapshot exported | Records({}) | Path({})",
+            snapshot.entries.len(),
+            path.display()
+        );
+
+        Ok(snapshot.entries.len())
     }
 
-    fn increment_tx_retry_count(&self, txid: Txid) -> Result<(), BitcoinCoordinatorStoreError> {
-        let mut tx = self.get_tx(&txid)?;
-        let new_count = tx.retry_info.as_ref().map_or(0, |info| info.retries_count) + 1;
+    fn import_snapshot(
+        &self,
+        path: &Path,
+        force: bool,
+    ) -> Result<usize, BitcoinCoordinatorStoreError> {
+        let snapshot = StoreSnapshot::read(path)?;
+        let existing = self.snapshot_keys()?;
 
-        if new_count >= self.retry_attempts_sending_tx {
-            tx.state = TransactionState::Failed;
-        } else {
-            tx.retry_info = Some(RetryInfo::new(
-                new_count,
-                Utc::now().timestamp_millis() as u64,
-            ));
+        if !existing.is_empty() && !force {
+            return Err(BitcoinCoordinatorStoreError::StoreNotEmpty(existing.len()));
         }
 
-        self.store
-            .set(self.get_key(StoreKey::Transaction(txid)), &tx, None)?;
+        let state_indexes = self.state_index_keys();
 
-        Ok(())
+        // The records are written as they were exported, they are already in the record envelope.
+        self.atomically(|transaction_id| {
+            for key in existing.iter().chain(state_indexes.iter()) {
+                if !snapshot.entries.contains_key(key) {
+                    self.store.remove(key, Some(transaction_id))?;
+                }
+            }
+
+            for (key, record) in snapshot.entries.iter() {
+                self.store.set(key, record, Some(transaction_id))?;
+            }
+
+            Ok(())
+        })?;
+
+        self.state_indexes_ready.set(false);
+        self.news_log_ready.set(false);
+        self.ensure_state_indexes()?;
+        self.repair_dangling_entries()?;
+
+        info!(
+            "Store snapshot imported | Records({}) | Replaced({}) | Path({})",
+            snapshot.entries.len(),
+            existing.len(),
+            path.display()
+        );
+
+        Ok(snapshot.entries.len())
     }
 }
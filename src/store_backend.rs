@@ -0,0 +1,269 @@
+use crate::errors::BitcoinCoordinatorStoreError;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use std::{cell::RefCell, collections::HashMap};
+use storage_backend::storage::{KeyValueStore, Storage};
+use uuid::Uuid;
+
+// Key-value storage the coordinator store is kept in: the on-disk Storage, or an InMemoryStore when nothing
+// needs to survive the process. Values are exchanged as JSON, the typed access is on `dyn StoreBackend`.
+// A write with a transaction id is applied when the transaction is committed, reads do not see it before.
+pub trait StoreBackend {
+    fn read(&self, key: &str) -> Result<Option<Value>, BitcoinCoordinatorStoreError>;
+
+    fn write(
+        &self,
+        key: &str,
+        value: Value,
+        transaction_id: Option<Uuid>,
+    ) -> Result<(), BitcoinCoordinatorStoreError>;
+
+    fn delete(
+        &self,
+        key: &str,
+        transaction_id: Option<Uuid>,
+    ) -> Result<(), BitcoinCoordinatorStoreError>;
+
+    fn contains(&self, key: &str) -> Result<bool, BitcoinCoordinatorStoreError>;
+
+    // Keys starting with `prefix`, in no particular order.
+    fn keys(&self, prefix: &str) -> Result<Vec<String>, BitcoinCoordinatorStoreError>;
+
+    fn begin(&self) -> Uuid;
+
+    fn commit(&self, transaction_id: Uuid) -> Result<(), BitcoinCoordinatorStoreError>;
+
+    fn rollback(&self, transaction_id: Uuid) -> Result<(), BitcoinCoordinatorStoreError>;
+}
+
+// Same access as the KeyValueStore of the on-disk storage, whatever the backend is.
+impl dyn StoreBackend {
+    pub fn get<K: AsRef<str>, V: DeserializeOwned>(
+        &self,
+        key: K,
+    ) -> Result<Option<V>, BitcoinCoordinatorStoreError> {
+        let Some(value) = self.read(key.as_ref())? else {
+            return Ok(None);
+        };
+
+        serde_json::from_value(value)
+            .map(Some)
+            .map_err(|e| BitcoinCoordinatorStoreError::SerializationError(e.to_string()))
+    }
+
+    pub fn set<K: AsRef<str>, V: Serialize>(
+        &self,
+        key: K,
+        value: V,
+        transaction_id: Option<Uuid>,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        let value = serde_json::to_value(value)
+            .map_err(|e| BitcoinCoordinatorStoreError::SerializationError(e.to_string()))?;
+
+        self.write(key.as_ref(), value, transaction_id)
+    }
+
+    pub fn remove<K: AsRef<str>>(
+        &self,
+        key: K,
+        transaction_id: Option<Uuid>,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        self.delete(key.as_ref(), transaction_id)
+    }
+
+    pub fn has_key<K: AsRef<str>>(&self, key: K) -> Result<bool, BitcoinCoordinatorStoreError> {
+        self.contains(key.as_ref())
+    }
+
+    pub fn begin_transaction(&self) -> Uuid {
+        self.begin()
+    }
+
+    pub fn commit_transaction(
+        &self,
+        transaction_id: Uuid,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        self.commit(transaction_id)
+    }
+
+    pub fn rollback_transaction(
+        &self,
+        transaction_id: Uuid,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        self.rollback(transaction_id)
+    }
+}
+
+impl StoreBackend for Storage {
+    fn read(&self, key: &str) -> Result<Option<Value>, BitcoinCoordinatorStoreError> {
+        Ok(KeyValueStore::get::<&str, Value>(self, key)?)
+    }
+
+    fn write(
+        &self,
+        key: &str,
+        value: Value,
+        transaction_id: Option<Uuid>,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        KeyValueStore::set(self, key, value, transaction_id)?;
+        Ok(())
+    }
+
+    fn delete(
+        &self,
+        key: &str,
+        transaction_id: Option<Uuid>,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        KeyValueStore::remove(self, key, transaction_id)?;
+        Ok(())
+    }
+
+    fn contains(&self, key: &str) -> Result<bool, BitcoinCoordinatorStoreError> {
+        Ok(KeyValueStore::has_key(self, key)?)
+    }
+
+    fn keys(&self, prefix: &str) -> Result<Vec<String>, BitcoinCoordinatorStoreError> {
+        Ok(self.partial_compare_keys(prefix)?)
+    }
+
+    fn begin(&self) -> Uuid {
+        KeyValueStore::begin_transaction(self)
+    }
+
+    fn commit(&self, transaction_id: Uuid) -> Result<(), BitcoinCoordinatorStoreError> {
+        KeyValueStore::commit_transaction(self, transaction_id)?;
+        Ok(())
+    }
+
+    fn rollback(&self, transaction_id: Uuid) -> Result<(), BitcoinCoordinatorStoreError> {
+        KeyValueStore::rollback_transaction(self, transaction_id)?;
+        Ok(())
+    }
+}
+
+// Storage kept in a map, for tests and short-lived coordinators (e.g. regtest simulations) that do not need
+// the store to survive the process. Values are kept serialized as in the on-disk storage, so what is read
+// back is always a copy of what was written.
+#[derive(Default)]
+pub struct InMemoryStore {
+    values: RefCell<HashMap<String, String>>,
+    // Writes of the open transactions in order, None for a removed key.
+    transactions: RefCell<HashMap<Uuid, Vec<(String, Option<String>)>>>,
+}
+
+impl InMemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn apply(
+        &self,
+        key: &str,
+        value: Option<String>,
+        transaction_id: Option<Uuid>,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        let Some(transaction_id) = transaction_id else {
+            let mut values = self.values.borrow_mut();
+
+            match value {
+                Some(value) => values.insert(key.to_string(), value),
+                None => values.remove(key),
+            };
+
+            return Ok(());
+        };
+
+        self.transactions
+            .borrow_mut()
+            .get_mut(&transaction_id)
+            .ok_or(BitcoinCoordinatorStoreError::StoreTransactionNotOpen(
+                transaction_id,
+            ))?
+            .push((key.to_string(), value));
+
+        Ok(())
+    }
+}
+
+impl StoreBackend for InMemoryStore {
+    fn read(&self, key: &str) -> Result<Option<Value>, BitcoinCoordinatorStoreError> {
+        let values = self.values.borrow();
+        let Some(value) = values.get(key) else {
+            return Ok(None);
+        };
+
+        serde_json::from_str(value)
+            .map(Some)
+            .map_err(|e| BitcoinCoordinatorStoreError::SerializationError(e.to_string()))
+    }
+
+    fn write(
+        &self,
+        key: &str,
+        value: Value,
+        transaction_id: Option<Uuid>,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        let value = serde_json::to_string(&value)
+            .map_err(|e| BitcoinCoordinatorStoreError::SerializationError(e.to_string()))?;
+
+        self.apply(key, Some(value), transaction_id)
+    }
+
+    fn delete(
+        &self,
+        key: &str,
+        transaction_id: Option<Uuid>,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        self.apply(key, None, transaction_id)
+    }
+
+    fn contains(&self, key: &str) -> Result<bool, BitcoinCoordinatorStoreError> {
+        Ok(self.values.borrow().contains_key(key))
+    }
+
+    fn keys(&self, prefix: &str) -> Result<Vec<String>, BitcoinCoordinatorStoreError> {
+        Ok(self
+            .values
+            .borrow()
+            .keys()
+            .filter(|key| key.starts_with(prefix))
+            .cloned()
+            .collect())
+    }
+
+    fn begin(&self) -> Uuid {
+        let transaction_id = Uuid::new_v4();
+        self.transactions
+            .borrow_mut()
+            .insert(transaction_id, Vec::new());
+
+        transaction_id
+    }
+
+    fn commit(&self, transaction_id: Uuid) -> Result<(), BitcoinCoordinatorStoreError> {
+        let writes = self
+            .transactions
+            .borrow_mut()
+            .remove(&transaction_id)
+            .ok_or(BitcoinCoordinatorStoreError::StoreTransactionNotOpen(
+                transaction_id,
+            ))?;
+
+        for (key, value) in writes {
+            self.apply(&key, value, None)?;
+        }
+
+        Ok(())
+    }
+
+    fn rollback(&self, transaction_id: Uuid) -> Result<(), BitcoinCoordinatorStoreError> {
+        self.transactions
+            .borrow_mut()
+            .remove(&transaction_id)
+            .ok_or(BitcoinCoordinatorStoreError::StoreTransactionNotOpen(
+                transaction_id,
+            ))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,1208 @@
+use crate::{
+    ancestry::MempoolAncestry,
+    clock::Clock,
+    config::{CoordinatorSettings, CoordinatorSettingsConfig, FeeEstimateMode},
+    coordinator::{BitcoinCoordinator, BitcoinCoordinatorApi},
+    errors::BitcoinCoordinatorError,
+    finalized::FinalizedSink,
+    funding::{FundingOutputChecker, FundingOutputState, FundingProvider},
+    locktime::{absolute_lock_height, relative_locks},
+    node::NodeApi,
+    observer::CoordinatorObserver,
+    parent_rbf::ParentTxSigner,
+    review::SpeedupReviewHook,
+    storage::{BitcoinCoordinatorStore, StoreWriteFault},
+    store_backend::{InMemoryStore, StoreBackend},
+};
+use bitcoin::{
+    absolute::LockTime, hashes::Hash, secp256k1::Secp256k1, secp256k1::SecretKey,
+    transaction::Version, Address, Amount, BlockHash, CompressedPublicKey, Network, OutPoint,
+    PublicKey, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Txid, Witness,
+};
+use bitvmx_bitcoin_rpc::{
+    bitcoin_client::{BitcoinClientApi, RawTxInfo},
+    errors::BitcoinClientError,
+    types::BlockHeight,
+};
+use bitvmx_transaction_monitor::{
+    errors::MonitorError,
+    monitor::MonitorApi,
+    types::{
+        AckMonitorNews, BlockInfo, FullBlock, MonitorNews, TransactionBlockchainStatus,
+        TransactionStatus, TypesToMonitor,
+    },
+};
+use key_manager::key_manager::KeyManager;
+use protocol_builder::types::{output::SpeedupData, Utxo};
+use std::{
+    cell::{Cell, RefCell},
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
+
+// In-process chain used by the fake client and the fake monitor, so coordinator tests run without a node.
+// Blocks include every transaction of the mempool, there are no scripts nor signatures checks.
+// Transactions whose locktime has not matured are rejected as non-final.
+// A transaction spending an output spent in the mempool replaces the spender (and its descendants)
+// when it pays a higher fee, and a transaction spending an output spent in the chain is rejected.
+#[derive(Clone)]
+pub struct FakeChain {
+    state: Rc<RefCell<ChainState>>,
+}
+
+struct FakeBlock {
+    hash: BlockHash,
+    prev_hash: BlockHash,
+    height: BlockHeight,
+    txs: Vec<Transaction>,
+}
+
+struct ChainState {
+    // The active chain, starting with the genesis block at height 0.
+    blocks: Vec<FakeBlock>,
+    // Blocks removed from the active chain by invalidate_block, in the order they were removed.
+    orphaned: Vec<FakeBlock>,
+    mempool: Vec<Transaction>,
+    fee_rate: u64,
+    // Makes the hashes of the blocks and the funding transactions unique.
+    nonce: u32,
+    // Whether the fake client fails with connection errors, like a node that is down or restarting.
+    unreachable: bool,
+    // Errors the fake client answers to the next broadcast of each transaction.
+    broadcast_errors: HashMap<Txid, String>,
+    // Transactions the fake client was asked to broadcast, the rejected ones included.
+    broadcasts: u32,
+}
+
+impl FakeChain {
+    pub fn new(fee_rate: u64) -> Self {
+        let genesis = FakeBlock {
+            hash: BlockHash::hash(b"genesis"),
+            prev_hash: BlockHash::all_zeros(),
+            height: 0,
+            txs: vec![],
+        };
+
+        Self {
+            state: Rc::new(RefCell::new(ChainState {
+                blocks: vec![genesis],
+                orphaned: vec![],
+                mempool: vec![],
+                fee_rate,
+                nonce: 0,
+                unreachable: false,
+                broadcast_errors: HashMap::new(),
+                broadcasts: 0,
+            })),
+        }
+    }
+
+    pub fn height(&self) -> BlockHeight {
+        self.state.borrow().tip().height
+    }
+
+    pub fn tip(&self) -> FullBlock {
+        let state = self.state.borrow();
+        let tip = state.tip();
+
+        FullBlock {
+            height: tip.height,
+            hash: tip.hash,
+            prev_hash: tip.prev_hash,
+            txs: tip.txs.clone(),
+            orphan: false,
+        }
+    }
+
+    pub fn block_hash(&self, height: BlockHeight) -> Option<BlockHash> {
+        self.state
+            .borrow()
+            .blocks
+            .get(height as usize)
+            .map(|block| block.hash)
+    }
+
+    pub fn fee_rate(&self) -> u64 {
+        self.state.borrow().fee_rate
+    }
+
+    // Changes the fee rate estimated by the node, e.g. to simulate a fee spike.
+    pub fn set_fee_rate(&self, fee_rate: u64) {
+        self.state.borrow_mut().fee_rate = fee_rate;
+    }
+
+    // Makes every call of the fake client fail with a connection error until it is reachable again.
+    // The monitor keeps answering from the blocks it already indexed.
+    pub fn set_unreachable(&self, unreachable: bool) {
+        self.state.borrow_mut().unreachable = unreachable;
+    }
+
+    fn check_reachable(&self) -> Result<(), BitcoinClientError> {
+        if self.state.borrow().unreachable {
+            return Err(BitcoinClientError::ClientError(
+                "Connection refused (os error 111)".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    // Broadcasts asked to the fake client, the rejected ones included.
+    pub fn broadcasts(&self) -> u32 {
+        self.state.borrow().broadcasts
+    }
+
+    // Makes the next broadcast of the transaction by the fake client fail with the given node error.
+    pub fn fail_next_broadcast(&self, txid: Txid, error: &str) {
+        self.state
+            .borrow_mut()
+            .broadcast_errors
+            .insert(txid, error.to_string());
+    }
+
+    pub fn mempool(&self) -> Vec<Transaction> {
+        self.state.borrow().mempool.clone()
+    }
+
+    pub fn in_mempool(&self, txid: &Txid) -> bool {
+        self.state
+            .borrow()
+            .mempool
+            .iter()
+            .any(|tx| tx.compute_txid() == *txid)
+    }
+
+    // Returns the transaction if it is in the active chain or in the mempool.
+    pub fn get_transaction(&self, txid: &Txid) -> Option<Transaction> {
+        let state = self.state.borrow();
+
+        state
+            .blocks
+            .iter()
+            .flat_map(|block| block.txs.iter())
+            .chain(state.mempool.iter())
+            .find(|tx| tx.compute_txid() == *txid)
+            .cloned()
+    }
+
+    // Number of confirmations of a transaction in the active chain.
+    pub fn confirmations(&self, txid: &Txid) -> Option<u32> {
+        let state = self.state.borrow();
+        let (block, _) = state.find_in_chain(txid)?;
+        Some(state.tip().height - block.height + 1)
+    }
+
+    // Status of the transaction as reported by a monitor: confirmed when it is in the active chain,
+    // orphan when it was only mined in an invalidated block, and None when it was never mined.
+    pub fn tx_status(&self, txid: &Txid) -> Option<TransactionStatus> {
+        let state = self.state.borrow();
+
+        if let Some((block, tx)) = state.find_in_chain(txid) {
+            return Some(TransactionStatus {
+                tx_id: *txid,
+                tx: tx.clone(),
+                block_info: Some(BlockInfo {
+                    height: block.height,
+                    hash: block.hash,
+                    is_orphan: false,
+                }),
+                confirmations: state.tip().height - block.height + 1,
+                status: TransactionBlockchainStatus::Confirmed,
+            });
+        }
+
+        state.orphaned.iter().rev().find_map(|block| {
+            let tx = block.txs.iter().find(|tx| tx.compute_txid() == *txid)?;
+
+            Some(TransactionStatus {
+                tx_id: *txid,
+                tx: tx.clone(),
+                block_info: Some(BlockInfo {
+                    height: block.height,
+                    hash: block.hash,
+                    is_orphan: true,
+                }),
+                confirmations: 0,
+                status: TransactionBlockchainStatus::Orphan,
+            })
+        })
+    }
+
+    // Returns the transaction of the active chain spending the outpoint.
+    pub fn get_spender(&self, outpoint: &OutPoint) -> Option<Transaction> {
+        let state = self.state.borrow();
+
+        state
+            .blocks
+            .iter()
+            .flat_map(|block| block.txs.iter())
+            .find(|tx| {
+                tx.input
+                    .iter()
+                    .any(|input| input.previous_output == *outpoint)
+            })
+            .cloned()
+    }
+
+    // Adds the transaction to the mempool, with the error messages of a node for the rejected ones.
+    pub fn send_transaction(&self, tx: &Transaction) -> Result<Txid, String> {
+        let txid = tx.compute_txid();
+        let mut state = self.state.borrow_mut();
+
+        if state.find_in_chain(&txid).is_some() {
+            return Err("Transaction outputs already in utxo set".to_string());
+        }
+
+        if state.mempool.iter().any(|tx| tx.compute_txid() == txid) {
+            return Ok(txid);
+        }
+
+        // Locktimes are checked against the next block, as a node does for its mempool.
+        let next_height = state.tip().height + 1;
+
+        if absolute_lock_height(tx).is_some_and(|height| height >= next_height) {
+            return Err("non-final".to_string());
+        }
+
+        let relative_lock_pending = relative_locks(tx).into_iter().any(|(parent, blocks)| {
+            match state.find_in_chain(&parent) {
+                Some((block, _)) => next_height < block.height + blocks,
+                None => state.find_in_mempool(&parent).is_some(),
+            }
+        });
+
+        if relative_lock_pending {
+            return Err("non-BIP68-final".to_string());
+        }
+
+        // Null outpoints are not tracked, so tests can create transactions without real inputs.
+        let outpoints: Vec<OutPoint> = tx
+            .input
+            .iter()
+            .map(|input| input.previous_output)
+            .filter(|outpoint| !outpoint.is_null())
+            .collect();
+
+        let spent_in_chain =
+            state
+                .blocks
+                .iter()
+                .flat_map(|block| block.txs.iter())
+                .any(|block_tx| {
+                    block_tx
+                        .input
+                        .iter()
+                        .any(|spent| outpoints.contains(&spent.previous_output))
+                });
+
+        if spent_in_chain {
+            return Err("bad-txns-inputs-missingorspent".to_string());
+        }
+
+        let conflicts: Vec<Txid> = state
+            .mempool
+            .iter()
+            .filter(|mempool_tx| {
+                mempool_tx
+                    .input
+                    .iter()
+                    .any(|spent| outpoints.contains(&spent.previous_output))
+            })
+            .map(|mempool_tx| mempool_tx.compute_txid())
+            .collect();
+
+        if !conflicts.is_empty() {
+            let fee = state.fee(tx);
+
+            for conflict in conflicts.iter() {
+                let conflict_tx = state.find_in_mempool(conflict).unwrap().clone();
+
+                // Without the values of every input the replacement is accepted.
+                if let (Some(fee), Some(conflict_fee)) = (fee, state.fee(&conflict_tx)) {
+                    if fee <= conflict_fee {
+                        return Err(format!(
+                            "insufficient fee, rejecting replacement {txid}, not enough additional fees to relay"
+                        ));
+                    }
+                }
+            }
+
+            state.remove_from_mempool(conflicts);
+        }
+
+        state.mempool.push(tx.clone());
+
+        Ok(txid)
+    }
+
+    // Mines the transactions of the mempool in the first block, the next blocks are empty.
+    pub fn mine_blocks(&self, blocks: u64) -> Vec<BlockHash> {
+        (0..blocks)
+            .map(|_| {
+                let mut state = self.state.borrow_mut();
+                let txs = std::mem::take(&mut state.mempool);
+                state.mine_block(txs)
+            })
+            .collect()
+    }
+
+    // Mines blocks without the transactions of the mempool, as when they are outbid by other transactions.
+    pub fn mine_empty_blocks(&self, blocks: u64) -> Vec<BlockHash> {
+        (0..blocks)
+            .map(|_| self.state.borrow_mut().mine_block(vec![]))
+            .collect()
+    }
+
+    // Removes the block and its descendants from the active chain, their transactions go back to the mempool.
+    pub fn invalidate_block(&self, hash: &BlockHash) -> Result<(), String> {
+        let mut state = self.state.borrow_mut();
+
+        let position = state
+            .blocks
+            .iter()
+            .position(|block| block.hash == *hash)
+            .ok_or(format!("Block not found: {hash}"))?;
+
+        if position == 0 {
+            return Err("The genesis block can not be invalidated".to_string());
+        }
+
+        let removed: Vec<FakeBlock> = state.blocks.drain(position..).collect();
+        let mut txs: Vec<Transaction> = removed
+            .iter()
+            .flat_map(|block| block.txs.iter().cloned())
+            .collect();
+
+        txs.append(&mut state.mempool);
+        state.mempool = txs;
+        state.orphaned.extend(removed);
+
+        Ok(())
+    }
+
+    // Invalidates the tip and returns its hash, so its transactions are orphaned and go back to the mempool.
+    pub fn invalidate_last_block(&self) -> Option<BlockHash> {
+        let tip = self.tip();
+
+        if tip.height == 0 {
+            return None;
+        }
+
+        self.invalidate_block(&tip.hash).ok()?;
+
+        Some(tip.hash)
+    }
+
+    // Mines a transaction paying `amount` to `script_pubkey` and returns it with the output index.
+    pub fn fund(&self, script_pubkey: ScriptBuf, amount: u64) -> (Transaction, u32) {
+        let funding = self.send_funding(script_pubkey, amount);
+        self.mine_blocks(1);
+
+        funding
+    }
+
+    // Same as `fund`, but the transaction is left unconfirmed in the mempool.
+    pub fn send_funding(&self, script_pubkey: ScriptBuf, amount: u64) -> (Transaction, u32) {
+        let tx = {
+            let mut state = self.state.borrow_mut();
+            state.nonce += 1;
+
+            Transaction {
+                version: Version::TWO,
+                lock_time: LockTime::from_consensus(state.nonce),
+                input: vec![TxIn {
+                    previous_output: OutPoint::null(),
+                    script_sig: ScriptBuf::new(),
+                    sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                    witness: Witness::new(),
+                }],
+                output: vec![TxOut {
+                    value: Amount::from_sat(amount),
+                    script_pubkey,
+                }],
+            }
+        };
+
+        self.state.borrow_mut().mempool.push(tx.clone());
+
+        (tx, 0)
+    }
+}
+
+impl ChainState {
+    fn tip(&self) -> &FakeBlock {
+        self.blocks.last().unwrap()
+    }
+
+    fn find_in_chain(&self, txid: &Txid) -> Option<(&FakeBlock, &Transaction)> {
+        self.blocks.iter().find_map(|block| {
+            block
+                .txs
+                .iter()
+                .find(|tx| tx.compute_txid() == *txid)
+                .map(|tx| (block, tx))
+        })
+    }
+
+    fn find_in_mempool(&self, txid: &Txid) -> Option<&Transaction> {
+        self.mempool.iter().find(|tx| tx.compute_txid() == *txid)
+    }
+
+    fn output_value(&self, outpoint: &OutPoint) -> Option<u64> {
+        self.blocks
+            .iter()
+            .flat_map(|block| block.txs.iter())
+            .chain(self.mempool.iter())
+            .find(|tx| tx.compute_txid() == outpoint.txid)
+            .and_then(|tx| tx.output.get(outpoint.vout as usize))
+            .map(|output| output.value.to_sat())
+    }
+
+    // Fee of the transaction, None when the value of some input is not known.
+    fn fee(&self, tx: &Transaction) -> Option<u64> {
+        let inputs = tx
+            .input
+            .iter()
+            .map(|input| self.output_value(&input.previous_output))
+            .sum::<Option<u64>>()?;
+        let outputs: u64 = tx.output.iter().map(|output| output.value.to_sat()).sum();
+
+        Some(inputs.saturating_sub(outputs))
+    }
+
+    // Removes the transactions and their descendants from the mempool.
+    fn remove_from_mempool(&mut self, mut txids: Vec<Txid>) {
+        while let Some(txid) = txids.pop() {
+            self.mempool.retain(|tx| tx.compute_txid() != txid);
+
+            txids.extend(
+                self.mempool
+                    .iter()
+                    .filter(|tx| {
+                        tx.input
+                            .iter()
+                            .any(|input| input.previous_output.txid == txid)
+                    })
+                    .map(|tx| tx.compute_txid()),
+            );
+        }
+    }
+
+    fn mine_block(&mut self, txs: Vec<Transaction>) -> BlockHash {
+        self.nonce += 1;
+
+        let prev_hash = self.tip().hash;
+        let height = self.tip().height + 1;
+
+        let mut data = prev_hash.to_byte_array().to_vec();
+        data.extend(height.to_le_bytes());
+        data.extend(self.nonce.to_le_bytes());
+
+        let hash = BlockHash::hash(&data);
+
+        self.blocks.push(FakeBlock {
+            hash,
+            prev_hash,
+            height,
+            txs,
+        });
+
+        hash
+    }
+}
+
+// Funding outputs spent in the active chain or in the mempool, like gettxout but with the spending transaction.
+impl FundingOutputChecker for FakeChain {
+    fn get_funding_output_state(
+        &self,
+        outpoint: &OutPoint,
+    ) -> Result<FundingOutputState, BitcoinCoordinatorError> {
+        self.check_reachable()?;
+
+        let spender = self.get_spender(outpoint).or_else(|| {
+            self.mempool().into_iter().find(|tx| {
+                tx.input
+                    .iter()
+                    .any(|input| input.previous_output == *outpoint)
+            })
+        });
+
+        Ok(match spender {
+            Some(tx) => FundingOutputState::Spent(Some(tx.compute_txid())),
+            None => FundingOutputState::Unspent,
+        })
+    }
+}
+
+// Node calls on top of the fake chain. The node estimates the fee rate of the chain, has no mempool min fee
+// and accepts every transaction in testmempoolaccept, the broadcast applies the rules of the chain.
+impl NodeApi for FakeChain {
+    fn is_unspent(
+        &self,
+        outpoint: &OutPoint,
+        include_mempool: bool,
+    ) -> Result<bool, BitcoinCoordinatorError> {
+        self.check_reachable()?;
+
+        let state = self.state.borrow();
+        let spends_outpoint = |tx: &&Transaction| {
+            tx.input
+                .iter()
+                .any(|input| input.previous_output == *outpoint)
+        };
+
+        let mut txs: Vec<&Transaction> = state
+            .blocks
+            .iter()
+            .flat_map(|block| block.txs.iter())
+            .collect();
+        if include_mempool {
+            txs.extend(state.mempool.iter());
+        }
+
+        let exists = txs.iter().any(|tx| {
+            tx.compute_txid() == outpoint.txid && (outpoint.vout as usize) < tx.output.len()
+        });
+
+        Ok(exists && !txs.iter().any(spends_outpoint))
+    }
+
+    fn get_block_count(&self) -> Result<BlockHeight, BitcoinCoordinatorError> {
+        self.check_reachable()?;
+        Ok(self.height())
+    }
+
+    fn get_block_hash(&self, height: BlockHeight) -> Result<BlockHash, BitcoinCoordinatorError> {
+        self.check_reachable()?;
+        self.block_hash(height).ok_or_else(|| {
+            BitcoinClientError::ClientError(format!("Block height out of range: {height}")).into()
+        })
+    }
+
+    fn get_block_transactions(
+        &self,
+        block_hash: &BlockHash,
+    ) -> Result<Vec<Transaction>, BitcoinCoordinatorError> {
+        self.check_reachable()?;
+
+        let state = self.state.borrow();
+
+        state
+            .blocks
+            .iter()
+            .chain(state.orphaned.iter())
+            .find(|block| block.hash == *block_hash)
+            .map(|block| block.txs.clone())
+            .ok_or_else(|| {
+                BitcoinClientError::ClientError(format!("Block not found: {block_hash}")).into()
+            })
+    }
+
+    fn get_mempool_min_fee(&self) -> Result<Option<u64>, BitcoinCoordinatorError> {
+        self.check_reachable()?;
+        Ok(None)
+    }
+
+    fn estimate_smart_fee(
+        &self,
+        _conf_target: u16,
+        _mode: Option<FeeEstimateMode>,
+    ) -> Result<Option<u64>, BitcoinCoordinatorError> {
+        self.check_reachable()?;
+        Ok(Some(self.fee_rate()))
+    }
+
+    fn test_mempool_accept(
+        &self,
+        _tx: &Transaction,
+    ) -> Result<Option<String>, BitcoinCoordinatorError> {
+        self.check_reachable()?;
+        Ok(None)
+    }
+
+    fn get_mempool_ancestry(
+        &self,
+        txid: &Txid,
+    ) -> Result<Option<MempoolAncestry>, BitcoinCoordinatorError> {
+        self.check_reachable()?;
+
+        let state = self.state.borrow();
+
+        if state.find_in_mempool(txid).is_none() {
+            return Ok(None);
+        }
+
+        // The transaction and its unconfirmed ancestors.
+        let mut ancestors = HashSet::new();
+        let mut pending = vec![*txid];
+
+        while let Some(txid) = pending.pop() {
+            if !ancestors.insert(txid) {
+                continue;
+            }
+
+            if let Some(tx) = state.find_in_mempool(&txid) {
+                pending.extend(
+                    tx.input
+                        .iter()
+                        .map(|input| input.previous_output.txid)
+                        .filter(|parent| state.find_in_mempool(parent).is_some()),
+                );
+            }
+        }
+
+        let ancestor_size = ancestors
+            .iter()
+            .filter_map(|txid| state.find_in_mempool(txid))
+            .map(|tx| tx.vsize() as u64)
+            .sum();
+
+        Ok(Some(MempoolAncestry {
+            ancestor_count: ancestors.len() as u64,
+            ancestor_size,
+        }))
+    }
+}
+
+// Client API on top of the fake chain.
+pub struct FakeBitcoinClient {
+    chain: FakeChain,
+}
+
+impl FakeBitcoinClient {
+    pub fn new(chain: FakeChain) -> Self {
+        Self { chain }
+    }
+
+    fn check_reachable(&self) -> Result<(), BitcoinClientError> {
+        self.chain.check_reachable()
+    }
+}
+
+impl BitcoinClientApi for FakeBitcoinClient {
+    fn send_transaction(&self, tx: &Transaction) -> Result<Txid, BitcoinClientError> {
+        self.check_reachable()?;
+
+        let error = {
+            let mut state = self.chain.state.borrow_mut();
+            state.broadcasts += 1;
+            state.broadcast_errors.remove(&tx.compute_txid())
+        };
+
+        if let Some(error) = error {
+            return Err(BitcoinClientError::FailedToSendTransaction { error });
+        }
+
+        self.chain
+            .send_transaction(tx)
+            .map_err(|error| BitcoinClientError::FailedToSendTransaction { error })
+    }
+
+    fn get_best_block(&self) -> Result<BlockHeight, BitcoinClientError> {
+        self.check_reachable()?;
+        Ok(self.chain.height())
+    }
+
+    fn estimate_smart_fee(&self) -> Result<u64, BitcoinClientError> {
+        self.check_reachable()?;
+        Ok(self.chain.fee_rate())
+    }
+
+    fn fund_address(
+        &self,
+        address: &Address,
+        amount: Amount,
+    ) -> Result<(Transaction, u32), BitcoinClientError> {
+        Ok(self.chain.fund(address.script_pubkey(), amount.to_sat()))
+    }
+
+    fn mine_blocks_to_address(
+        &self,
+        blocks: u64,
+        _address: &Address,
+    ) -> Result<Vec<BlockHash>, BitcoinClientError> {
+        Ok(self.chain.mine_blocks(blocks))
+    }
+
+    fn get_transaction(&self, txid: &Txid) -> Result<Option<Transaction>, BitcoinClientError> {
+        self.check_reachable()?;
+        Ok(self.chain.get_transaction(txid))
+    }
+
+    fn get_raw_transaction_info(&self, txid: &Txid) -> Result<RawTxInfo, BitcoinClientError> {
+        self.check_reachable()?;
+        Ok(RawTxInfo {
+            confirmations: self.chain.confirmations(txid),
+        })
+    }
+
+    fn get_block_id_by_height(
+        &self,
+        height: &BlockHeight,
+    ) -> Result<BlockHash, BitcoinClientError> {
+        self.check_reachable()?;
+        self.chain
+            .block_hash(*height)
+            .ok_or(BitcoinClientError::ClientError(format!(
+                "Block height out of range: {height}"
+            )))
+    }
+
+    fn invalidate_block(&self, hash: &BlockHash) -> Result<(), BitcoinClientError> {
+        self.chain
+            .invalidate_block(hash)
+            .map_err(BitcoinClientError::ClientError)
+    }
+
+    fn init_wallet(&self, _name: &str) -> Result<Address, BitcoinClientError> {
+        // Blocks have no coinbase, so the wallet address is only used as a destination.
+        let secret_key = SecretKey::from_slice(&[1; 32])
+            .map_err(|e| BitcoinClientError::ClientError(e.to_string()))?;
+        let public_key = CompressedPublicKey(secret_key.public_key(&Secp256k1::new()));
+
+        Ok(Address::p2wpkh(&public_key, Network::Regtest))
+    }
+}
+
+// Monitor fed from the fake chain. It is always ready, and reports the monitored transactions each time
+// their status changes until they reach max_monitoring_confirmations, like the transaction monitor.
+// Clones share their state, so a test can keep one to add news.
+#[derive(Clone)]
+pub struct FakeMonitor {
+    chain: FakeChain,
+    max_monitoring_confirmations: u32,
+    monitored: Rc<RefCell<Vec<TypesToMonitor>>>,
+    news: Rc<RefCell<Vec<MonitorNews>>>,
+    // Last status reported for each monitored transaction and context.
+    reported: Rc<RefCell<HashMap<(Txid, String), TransactionStatus>>>,
+    last_block: Rc<Cell<Option<BlockHash>>>,
+}
+
+impl FakeMonitor {
+    pub fn new(chain: FakeChain, max_monitoring_confirmations: u32) -> Self {
+        Self {
+            chain,
+            max_monitoring_confirmations,
+            monitored: Rc::new(RefCell::new(vec![])),
+            news: Rc::new(RefCell::new(vec![])),
+            reported: Rc::new(RefCell::new(HashMap::new())),
+            last_block: Rc::new(Cell::new(None)),
+        }
+    }
+
+    // Adds a news the fake chain does not produce, like a spend seen in the mempool of the node.
+    pub fn push_news(&self, news: MonitorNews) {
+        self.news.borrow_mut().push(news);
+    }
+
+    fn report_tx(&self, tx_id: Txid, status: TransactionStatus, context: &str) {
+        let key = (tx_id, context.to_string());
+
+        if status.confirmations > self.max_monitoring_confirmations
+            || self.reported.borrow().get(&key) == Some(&status)
+        {
+            return;
+        }
+
+        self.reported.borrow_mut().insert(key, status.clone());
+
+        // Only the last status of a transaction is reported.
+        let mut news = self.news.borrow_mut();
+        news.retain(|news| {
+            !matches!(news, MonitorNews::Transaction(id, _, ctx) if *id == tx_id && ctx == context)
+        });
+        news.push(MonitorNews::Transaction(tx_id, status, context.to_string()));
+    }
+
+    fn report_spend(&self, txid: Txid, vout: u32, context: &str) {
+        let spender = match self.chain.get_spender(&OutPoint::new(txid, vout)) {
+            Some(spender) => spender,
+            None => return,
+        };
+
+        let status = match self.chain.tx_status(&spender.compute_txid()) {
+            Some(status) => status,
+            None => return,
+        };
+
+        let key = (spender.compute_txid(), format!("{txid}:{vout}:{context}"));
+
+        if self.reported.borrow().get(&key) == Some(&status) {
+            return;
+        }
+
+        self.reported.borrow_mut().insert(key, status.clone());
+
+        let mut news = self.news.borrow_mut();
+        news.retain(|news| {
+            !matches!(news, MonitorNews::SpendingUTXOTransaction(id, index, _, ctx)
+                if *id == txid && *index == vout && ctx == context)
+        });
+        news.push(MonitorNews::SpendingUTXOTransaction(
+            txid,
+            vout,
+            status,
+            context.to_string(),
+        ));
+    }
+}
+
+impl MonitorApi for FakeMonitor {
+    fn tick(&self) -> Result<(), MonitorError> {
+        let monitored = self.monitored.borrow().clone();
+
+        for data in monitored {
+            match data {
+                TypesToMonitor::Transactions(tx_ids, context, _) => {
+                    for tx_id in tx_ids {
+                        if let Some(status) = self.chain.tx_status(&tx_id) {
+                            self.report_tx(tx_id, status, &context);
+                        }
+                    }
+                }
+                TypesToMonitor::SpendingUTXOTransaction(txid, vout, context, _) => {
+                    self.report_spend(txid, vout, &context);
+                }
+                TypesToMonitor::NewBlock => {
+                    let tip = self.chain.tip();
+
+                    if self.last_block.get() != Some(tip.hash) {
+                        self.last_block.set(Some(tip.hash));
+
+                        let mut news = self.news.borrow_mut();
+                        news.retain(|news| !matches!(news, MonitorNews::NewBlock(..)));
+                        news.push(MonitorNews::NewBlock(tip.height, tip.hash));
+                    }
+                }
+                // Peg-ins are not detected by the fake monitor.
+                TypesToMonitor::RskPegin(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn is_ready(&self) -> Result<bool, MonitorError> {
+        Ok(true)
+    }
+
+    fn get_current_block(&self) -> Result<Option<FullBlock>, MonitorError> {
+        Ok(Some(self.chain.tip()))
+    }
+
+    fn get_monitor_height(&self) -> Result<BlockHeight, MonitorError> {
+        Ok(self.chain.height())
+    }
+
+    fn get_tx_status(&self, tx_id: &Txid) -> Result<TransactionStatus, MonitorError> {
+        self.chain
+            .tx_status(tx_id)
+            .ok_or(MonitorError::TransactionNotFound(tx_id.to_string()))
+    }
+
+    fn monitor(&self, data: TypesToMonitor) -> Result<(), MonitorError> {
+        let mut monitored = self.monitored.borrow_mut();
+
+        if !monitored.contains(&data) {
+            monitored.push(data);
+        }
+
+        Ok(())
+    }
+
+    fn cancel(&self, data: TypesToMonitor) -> Result<(), MonitorError> {
+        match data {
+            TypesToMonitor::Transactions(tx_ids, context, _) => {
+                let mut monitored = self.monitored.borrow_mut();
+
+                for data in monitored.iter_mut() {
+                    if let TypesToMonitor::Transactions(ids, ctx, _) = data {
+                        if *ctx == context {
+                            ids.retain(|id| !tx_ids.contains(id));
+                        }
+                    }
+                }
+
+                monitored.retain(
+                    |data| !matches!(data, TypesToMonitor::Transactions(ids, _, _) if ids.is_empty()),
+                );
+            }
+            data => self.monitored.borrow_mut().retain(|item| *item != data),
+        }
+
+        Ok(())
+    }
+
+    fn get_news(&self) -> Result<Vec<MonitorNews>, MonitorError> {
+        Ok(self.news.borrow().clone())
+    }
+
+    fn ack_news(&self, data: AckMonitorNews) -> Result<(), MonitorError> {
+        self.news.borrow_mut().retain(|news| match (&data, news) {
+            (AckMonitorNews::Transaction(id, ctx), MonitorNews::Transaction(tx_id, _, context)) => {
+                id != tx_id || ctx != context
+            }
+            (
+                AckMonitorNews::SpendingUTXOTransaction(id, vout, ctx),
+                MonitorNews::SpendingUTXOTransaction(tx_id, index, _, context),
+            ) => id != tx_id || vout != index || ctx != context,
+            (
+                AckMonitorNews::RskPeginTransaction(id),
+                MonitorNews::RskPeginTransaction(tx_id, _),
+            ) => id != tx_id,
+            (AckMonitorNews::NewBlock, MonitorNews::NewBlock(..)) => false,
+            _ => true,
+        });
+
+        Ok(())
+    }
+
+    fn get_estimated_fee_rate(&self) -> Result<u64, MonitorError> {
+        Ok(self.chain.fee_rate())
+    }
+}
+
+// Clock that only moves when the test advances it, so the retries can be tested without sleeping.
+pub struct MockClock {
+    now_millis: Cell<u64>,
+}
+
+impl MockClock {
+    pub fn new(now_millis: u64) -> Self {
+        Self {
+            now_millis: Cell::new(now_millis),
+        }
+    }
+
+    pub fn advance_millis(&self, millis: u64) {
+        self.now_millis.set(self.now_millis.get() + millis);
+    }
+
+    pub fn advance_secs(&self, secs: u64) {
+        self.advance_millis(secs * 1000);
+    }
+}
+
+impl Clock for MockClock {
+    fn now_millis(&self) -> u64 {
+        self.now_millis.get()
+    }
+}
+
+// Fails once the next write of each key containing one of the given fragments.
+// After fail_writes_after every write past the allowed ones fails, like a storage that crashed.
+#[derive(Default)]
+pub struct FailingStoreWrites {
+    fragments: RefCell<Vec<String>>,
+    writes_left: Cell<Option<u32>>,
+}
+
+impl FailingStoreWrites {
+    pub fn fail_next_write(&self, key_fragment: &str) {
+        self.fragments.borrow_mut().push(key_fragment.to_string());
+    }
+
+    pub fn fail_writes_after(&self, writes: u32) {
+        self.writes_left.set(Some(writes));
+    }
+}
+
+impl StoreWriteFault for FailingStoreWrites {
+    fn fail_write(&self, key: &str) -> bool {
+        if let Some(writes_left) = self.writes_left.get() {
+            if writes_left == 0 {
+                return true;
+            }
+            self.writes_left.set(Some(writes_left - 1));
+        }
+
+        let mut fragments = self.fragments.borrow_mut();
+
+        match fragments
+            .iter()
+            .position(|fragment| key.contains(fragment.as_str()))
+        {
+            Some(index) => {
+                fragments.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+// A coordinator wired to a fake chain, for deterministic tests without a node.
+// Nothing happens until the test mines blocks or ticks the coordinator.
+pub struct CoordinatorTestHarness {
+    coordinator: BitcoinCoordinator,
+    chain: FakeChain,
+    monitor: FakeMonitor,
+    write_faults: Rc<FailingStoreWrites>,
+}
+
+impl CoordinatorTestHarness {
+    // Fee rate estimated by the fake chain until a test changes it.
+    pub const INITIAL_FEE_RATE: u64 = 2;
+
+    pub fn new(
+        storage: Rc<dyn StoreBackend>,
+        key_manager: Rc<KeyManager>,
+        settings: Option<CoordinatorSettingsConfig>,
+    ) -> Result<Self, BitcoinCoordinatorError> {
+        Self::with_chain(
+            FakeChain::new(Self::INITIAL_FEE_RATE),
+            storage,
+            key_manager,
+            settings,
+        )
+    }
+
+    // A coordinator with its store in memory, nothing is written to the filesystem.
+    pub fn ephemeral(
+        key_manager: Rc<KeyManager>,
+        settings: Option<CoordinatorSettingsConfig>,
+    ) -> Result<Self, BitcoinCoordinatorError> {
+        Self::new(Rc::new(InMemoryStore::new()), key_manager, settings)
+    }
+
+    // A coordinator on an existing chain, e.g. the chain of another harness to restart on the same storage.
+    pub fn with_chain(
+        chain: FakeChain,
+        storage: Rc<dyn StoreBackend>,
+        key_manager: Rc<KeyManager>,
+        settings: Option<CoordinatorSettingsConfig>,
+    ) -> Result<Self, BitcoinCoordinatorError> {
+        let settings =
+            settings.unwrap_or_else(|| CoordinatorSettingsConfig::defaults_for(Network::Regtest));
+        settings.validate()?;
+
+        let coordinator_settings = CoordinatorSettings::resolve(settings.clone(), Network::Regtest);
+
+        let store = BitcoinCoordinatorStore::new(
+            storage,
+            coordinator_settings.max_unconfirmed_speedups,
+            coordinator_settings.retry_attempts_sending_tx,
+            coordinator_settings.retry_interval_seconds,
+        )?;
+
+        let write_faults = Rc::new(FailingStoreWrites::default());
+        let store = store.with_write_fault(write_faults.clone());
+
+        let monitor = FakeMonitor::new(
+            chain.clone(),
+            coordinator_settings
+                .monitor_settings
+                .max_monitoring_confirmations,
+        );
+
+        let coordinator = BitcoinCoordinator::builder()
+            .with_monitor(Box::new(monitor.clone()))
+            .with_store(store)
+            .with_client(Box::new(FakeBitcoinClient::new(chain.clone())))
+            .with_node(Box::new(chain.clone()))
+            .with_key_manager(key_manager)
+            .with_settings(settings)
+            .build()?
+            .with_funding_output_checker(Rc::new(chain.clone()));
+
+        Ok(Self {
+            coordinator,
+            chain,
+            monitor,
+            write_faults,
+        })
+    }
+
+    pub fn with_funding_provider(mut self, provider: Rc<dyn FundingProvider>) -> Self {
+        self.coordinator = self.coordinator.with_funding_provider(provider);
+        self
+    }
+
+    pub fn with_observer(mut self, observer: Rc<dyn CoordinatorObserver>) -> Self {
+        self.coordinator = self.coordinator.with_observer(observer);
+        self
+    }
+
+    pub fn with_parent_tx_signer(mut self, signer: Rc<dyn ParentTxSigner>) -> Self {
+        self.coordinator = self.coordinator.with_parent_tx_signer(signer);
+        self
+    }
+
+    pub fn with_speedup_review_hook(mut self, hook: Rc<dyn SpeedupReviewHook>) -> Self {
+        self.coordinator = self.coordinator.with_speedup_review_hook(hook);
+        self
+    }
+
+    pub fn with_finalized_sink(mut self, sink: Rc<dyn FinalizedSink>) -> Self {
+        self.coordinator = self.coordinator.with_finalized_sink(sink);
+        self
+    }
+
+    pub fn coordinator(&self) -> &BitcoinCoordinator {
+        &self.coordinator
+    }
+
+    pub fn chain(&self) -> &FakeChain {
+        &self.chain
+    }
+
+    pub fn monitor(&self) -> &FakeMonitor {
+        &self.monitor
+    }
+
+    pub fn dispatch(
+        &self,
+        tx: Transaction,
+        speedup_data: Option<SpeedupData>,
+        context: &str,
+    ) -> Result<(), BitcoinCoordinatorError> {
+        self.coordinator
+            .dispatch(tx, speedup_data, context.to_string(), None, None)
+    }
+
+    pub fn tick(&self) -> Result<(), BitcoinCoordinatorError> {
+        self.coordinator.tick()
+    }
+
+    pub fn mine_blocks(&self, blocks: u64) -> Vec<BlockHash> {
+        self.chain.mine_blocks(blocks)
+    }
+
+    pub fn mine_empty_blocks(&self, blocks: u64) -> Vec<BlockHash> {
+        self.chain.mine_empty_blocks(blocks)
+    }
+
+    pub fn invalidate_last_block(&self) -> Option<BlockHash> {
+        self.chain.invalidate_last_block()
+    }
+
+    pub fn set_fee_rate(&self, fee_rate: u64) {
+        self.chain.set_fee_rate(fee_rate);
+    }
+
+    // Makes the next write of a store key containing `key_fragment` fail.
+    pub fn fail_next_store_write(&self, key_fragment: &str) {
+        self.write_faults.fail_next_write(key_fragment);
+    }
+
+    // Lets `writes` more store writes through and fails all the following ones, to simulate a crash.
+    pub fn fail_store_writes_after(&self, writes: u32) {
+        self.write_faults.fail_writes_after(writes);
+    }
+
+    // Mines a P2WPKH output paying `amount` to the key and returns it as a utxo.
+    pub fn fund(
+        &self,
+        public_key: &PublicKey,
+        amount: u64,
+    ) -> Result<Utxo, BitcoinCoordinatorError> {
+        let compressed = CompressedPublicKey::try_from(*public_key)
+            .map_err(|e| BitcoinCoordinatorError::InvalidConfiguration(e.to_string()))?;
+
+        let (tx, vout) = self
+            .chain
+            .fund(ScriptBuf::new_p2wpkh(&compressed.wpubkey_hash()), amount);
+
+        Ok(Utxo::new(tx.compute_txid(), vout, amount, public_key))
+    }
+}
@@ -1,14 +1,21 @@
-use bitcoin::{Transaction, Txid};
+use bitcoin::{
+    consensus::encode::serialize_hex, BlockHash, OutPoint, ScriptBuf, Transaction, Txid,
+};
 use bitvmx_bitcoin_rpc::types::BlockHeight;
 use bitvmx_transaction_monitor::types::{
     AckMonitorNews, BlockInfo, MonitorNews, TransactionBlockchainStatus,
 };
 use protocol_builder::types::{output::SpeedupData, Utxo};
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
+use crate::errors::{BitcoinCoordinatorStoreError, BroadcastFailureKind};
+use crate::locktime::absolute_lock_height;
+use crate::news::monitor_news_severity;
 use crate::settings::{
     CPFP_TRANSACTION_CONTEXT, FUNDING_TRANSACTION_CONTEXT, RBF_TRANSACTION_CONTEXT,
 };
+use crate::storage::BitcoinCoordinatorStoreApi;
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub enum TransactionState {
@@ -23,8 +30,15 @@ pub enum TransactionState {
     // The transaction has been successfully confirmed by the network.
     Finalized,
 
-    // The transaction has failed to be broadcasted.
+    // The transaction has failed to be broadcasted, or one of its inputs was spent by a conflicting transaction.
     Failed,
+
+    // The dispatch was cancelled by the user. If it was already broadcast it can still be confirmed.
+    Cancelled,
+
+    // The transaction was not confirmed by its expires_at_height. It is not dispatched nor sped up anymore,
+    // if it was already broadcast it can still be confirmed.
+    Expired,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -38,6 +52,59 @@ pub struct CoordinatedTransaction {
     pub state: TransactionState,
     pub context: String,
     pub retry_info: Option<RetryInfo>,
+    // The network fee rate (sat/vB) targeted when the transaction was dispatched.
+    #[serde(default)]
+    pub fee_rate_at_dispatch: u64,
+    // Overrides of the global fee policy for this transaction.
+    #[serde(default)]
+    pub dispatch_options: DispatchOptions,
+    // Times the transaction was sent again because it was missing from the mempool and the chain.
+    #[serde(default)]
+    pub rebroadcast_count: u32,
+    #[serde(default)]
+    pub last_rebroadcast_block_height: Option<BlockHeight>,
+    // Transactions replaced (RBF) by this one, starting with the transaction originally dispatched.
+    #[serde(default)]
+    pub replaced_txids: Vec<Txid>,
+    // Block height the transaction (or the one it replaced) was broadcast at the first time.
+    #[serde(default)]
+    pub first_broadcast_block_height: Option<BlockHeight>,
+    // Block height the transaction was confirmed at the first time, kept after a reorg.
+    #[serde(default)]
+    pub first_confirmation_block_height: Option<BlockHeight>,
+    // Speedups (CPFP and RBF) and replacements spent on the transaction, with the sats attributable to it.
+    #[serde(default)]
+    pub bump_count: u32,
+    #[serde(default)]
+    pub bump_fees: u64,
+    // Network fee rate (sat/vB) estimated when the transaction was seen confirmed the first time.
+    #[serde(default)]
+    pub fee_rate_at_confirmation: Option<u64>,
+    // Fee rate paid by the transaction and its speedups, computed when it is finalized.
+    #[serde(default)]
+    pub fee_report: Option<PackageFeeReport>,
+    // Highest confirmation milestone reported for the current confirmation of the transaction.
+    #[serde(default)]
+    pub last_confirmation_milestone: Option<u32>,
+    // Block height the dispatch was first paused at because of high fees, None if it was never paused.
+    #[serde(default)]
+    pub paused_since_block_height: Option<BlockHeight>,
+    // Block height the locktime of the transaction (absolute, or relative to a parent) lets it be broadcast at,
+    // None when it is final. Kept up to date while the transaction waits to be dispatched, a relative lock is only
+    // counted once its parent is confirmed.
+    #[serde(default)]
+    pub locktime_block_height: Option<BlockHeight>,
+    // Last block read looking for a double spend of the inputs while the transaction is missing.
+    #[serde(default)]
+    pub conflict_scan_progress: Option<ConflictScanProgress>,
+}
+
+// Block a conflict scan stopped at, the next tick reads from the block after it.
+// The hash tells whether the block was reorged out, then the blocks are read again from the broadcast height.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct ConflictScanProgress {
+    pub block_height: BlockHeight,
+    pub block_hash: BlockHash,
 }
 
 impl CoordinatedTransaction {
@@ -50,17 +117,213 @@ impl CoordinatedTransaction {
     ) -> Self {
         Self {
             tx_id: tx.compute_txid(),
-            tx,
             speedup_data,
             broadcast_block_height: None,
             state,
             target_block_height,
             context,
             retry_info: None,
+            fee_rate_at_dispatch: 0,
+            dispatch_options: DispatchOptions::default(),
+            rebroadcast_count: 0,
+            last_rebroadcast_block_height: None,
+            replaced_txids: Vec::new(),
+            first_broadcast_block_height: None,
+            first_confirmation_block_height: None,
+            bump_count: 0,
+            bump_fees: 0,
+            fee_rate_at_confirmation: None,
+            fee_report: None,
+            last_confirmation_milestone: None,
+            paused_since_block_height: None,
+            locktime_block_height: absolute_lock_height(&tx),
+            conflict_scan_progress: None,
+            tx,
         }
     }
 }
 
+// Per transaction overrides of the global fee policy. None means the global setting is used.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct DispatchOptions {
+    // Max fee rate (sat/vB) paid by the speedups of this transaction, instead of max_feerate_sat_vb.
+    pub max_feerate_sat_vb: Option<u64>,
+
+    // Bump fee percentage of the first speedup, instead of base_fee_multiplier.
+    pub initial_bump_fee_percentage: Option<f64>,
+
+    // If true, the transaction gets its own speedup (CPFP) instead of sharing it with other transactions.
+    pub exclusive_speedup: bool,
+
+    // If true, dispatching a transaction that is already waiting to be dispatched or confirmed does nothing
+    // instead of failing with AlreadyDispatched.
+    pub allow_duplicate: bool,
+
+    // If true, the transaction itself is replaced (RBF) with a higher fee when it is not confirmed after
+    // min_blocks_before_resend_speedup blocks, instead of being paid by a CPFP. The transaction must signal RBF
+    // and a ParentTxSigner must be set to sign the replacements.
+    pub allow_rbf_of_parent: bool,
+
+    // Output paying the extra fee of the replacements, the last output when None.
+    pub parent_change_vout: Option<u32>,
+
+    // Coordinated transactions that must be confirmed before this one is broadcast. If any of them fails,
+    // this transaction fails too.
+    pub depends_on: Vec<Txid>,
+
+    // Funding group paying the speedups of this transaction, added with add_funding_group.
+    // None means the default speedup chain.
+    pub funding_group: Option<String>,
+
+    // Confirmations after which the transaction is finalized, instead of max_monitoring_confirmations.
+    pub finality_confirmations: Option<u32>,
+
+    // Confirmations reported with a ConfirmationMilestone news, instead of confirmation_milestones.
+    pub confirmation_milestones: Option<Vec<u32>>,
+
+    // How long the dispatch can wait for high fees to come down.
+    pub urgency: DispatchUrgency,
+
+    // Blocks the dispatch can be paused by high fees before it is dispatched anyway, instead of max_pause_blocks.
+    pub max_pause_blocks: Option<u32>,
+
+    // If true, a coordinator in standby saves the transaction to be dispatched once it is promoted,
+    // instead of failing with CoordinatorInStandby.
+    pub queue_only: bool,
+
+    // Last block the transaction can be confirmed in. Once it is mined without the transaction, the transaction
+    // is expired: it is not dispatched nor sped up anymore.
+    pub expires_at_height: Option<BlockHeight>,
+}
+
+// Urgency of a dispatch. While the network fee rate is above the pause threshold of its urgency
+// (pause_normal_priority_above_sat_vb or pause_low_priority_above_sat_vb) the dispatch waits.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DispatchUrgency {
+    // Never paused.
+    Urgent,
+    #[default]
+    Normal,
+    Low,
+}
+
+// An output of an external transaction watched by the coordinator until it is spent.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct WatchedOutpoint {
+    pub outpoint: OutPoint,
+
+    // Context returned in the news when the outpoint is spent.
+    pub context: String,
+}
+
+// A set of outputs posted as collateral, watched with monitor_utxo_set until every one of them is spent.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct WatchedUtxoSet {
+    pub label: String,
+
+    // Context the outpoints are monitored with.
+    pub context: String,
+
+    // Members of the set in the order they were registered.
+    pub outpoints: Vec<UtxoSetMember>,
+}
+
+impl WatchedUtxoSet {
+    pub fn unspent_count(&self) -> u32 {
+        self.outpoints
+            .iter()
+            .filter(|member| member.spending_txid.is_none())
+            .count() as u32
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct UtxoSetMember {
+    pub outpoint: OutPoint,
+
+    // Transaction that spent the outpoint in a block, None while it is unspent.
+    pub spending_txid: Option<Txid>,
+}
+
+// Transactions monitored with monitor_group or dispatched with dispatch_group, complete once no member is pending.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct GroupStatus {
+    pub group_id: Uuid,
+
+    // Context the members are monitored with, returned in the GroupCompleted news.
+    pub context: String,
+
+    // Confirmations after which a member not dispatched by the coordinator is finalized.
+    // Dispatched members are finalized with their transaction.
+    pub finality_confirmations: u32,
+
+    // Members of the group in the order they were registered.
+    pub members: Vec<GroupMember>,
+
+    // Every member is finalized or cancelled, the GroupCompleted news was reported.
+    pub complete: bool,
+}
+
+impl GroupStatus {
+    pub fn pending_count(&self) -> u32 {
+        self.members
+            .iter()
+            .filter(|member| member.state == GroupMemberState::Pending)
+            .count() as u32
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct GroupMember {
+    pub tx_id: Txid,
+    pub state: GroupMemberState,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupMemberState {
+    Pending,
+    Finalized,
+    // Cancelled with cancel or cancel_dispatch, it does not keep the group incomplete.
+    Cancelled,
+}
+
+// An output script watched by the coordinator, the transactions paying to it are reported.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct WatchedAddress {
+    pub script_pubkey: ScriptBuf,
+
+    // Context returned in the news when a transaction pays to the script.
+    pub context: String,
+}
+
+// A transaction monitored with monitor_with_options that is finalized before max_monitoring_confirmations.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct WatchedFinality {
+    pub tx_id: Txid,
+
+    // Context the transaction is monitored with, used to stop monitoring it once finalized.
+    pub context: String,
+
+    pub finality_confirmations: u32,
+}
+
+// A RSK peg-in reported by the monitor, kept by the coordinator after the monitor news is acknowledged.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct DetectedPegin {
+    pub tx_id: Txid,
+
+    // Output locking the pegged-in amount.
+    pub vout: u32,
+    pub amount: u64,
+
+    // Height of the block the peg-in was mined in.
+    pub block_height: BlockHeight,
+
+    // Context given when the peg-in monitoring was registered.
+    pub context: String,
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct TransactionNew {
     pub tx_id: Txid,
@@ -70,12 +333,34 @@ pub struct TransactionNew {
     pub status: TransactionBlockchainStatus,
 }
 
+// Transactions the coordinator monitors for itself, their news are not returned by get_news.
+// They are registered by txid, so a consumer context that contains the same text is not hidden.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InternalMonitor {
+    Speedup,
+    FundingTopUp,
+}
+
+impl InternalMonitor {
+    pub fn context(&self) -> &'static str {
+        match self {
+            InternalMonitor::Speedup => CPFP_TRANSACTION_CONTEXT,
+            InternalMonitor::FundingTopUp => FUNDING_TRANSACTION_CONTEXT,
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub enum SpeedupState {
     Dispatched,
     Error,
     Confirmed,
     Finalized,
+    // The speedup (or the speedup it is funded from) was orphaned by a reorg, its change can not be used as funding.
+    Orphaned,
+    // The speedup (or the speedup it is funded from) was replaced by a confirmed RBF, or pays for a cancelled
+    // transaction dropped from the mempool, it can never be confirmed.
+    Invalidated,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -100,11 +385,669 @@ pub struct CoordinatedSpeedUpTransaction {
 
     pub bump_fee_percentage_used: f64,
 
-    pub speedup_tx_data: Vec<(SpeedupData, Transaction, String)>,
+    // The transactions paid by the speedup. Their raw transactions are only kept in their coordinated transaction
+    // records, see load_parents.
+    pub speedup_tx_data: Vec<SpeedupParent>,
 
     pub network_fee_rate_used: u64,
 
+    // The virtual size of the speedup transaction, used to top up its fee when the network fee rate increases.
+    #[serde(default)]
+    pub vsize: usize,
+
     pub retry_info: Option<RetryInfo>,
+
+    // The change in sats added to the fee because it was below the dust threshold. The speedup has no change
+    // output, so next_funding does not exist and the speedup ends its funding chain.
+    #[serde(default)]
+    pub exhausted_change: Option<u64>,
+
+    // The speedup was built and broadcast outside the coordinator and imported with import_external_speedup.
+    #[serde(default)]
+    pub is_external: bool,
+
+    // The bump fee percentage of the speedup this one replaces or boosts, bump_fee_percentage_used grew from it.
+    // None for the first CPFP of its transactions, priced with base_fee_multiplier or their initial bump fee.
+    #[serde(default)]
+    pub bumped_from: Option<f64>,
+}
+
+// A transaction paid by a speedup, with what the fee math needs to know about it.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct SpeedupParent {
+    pub tx_id: Txid,
+    pub speedup_data: SpeedupData,
+    pub vsize: usize,
+    // The sats of the speedup output spent by the speedup.
+    pub anchor_amount: u64,
+    pub context: String,
+}
+
+impl SpeedupParent {
+    pub fn new(speedup_data: SpeedupData, tx: &Transaction, context: String) -> Self {
+        let anchor_amount = match (&speedup_data.utxo, &speedup_data.partial_utxo) {
+            (Some(utxo), _) => utxo.amount,
+            (None, Some((_, _, amount, _))) => *amount,
+            (None, None) => 0,
+        };
+
+        Self {
+            tx_id: tx.compute_txid(),
+            speedup_data,
+            vsize: tx.vsize(),
+            anchor_amount,
+            context,
+        }
+    }
+}
+
+// Saved before a speedup is broadcast and removed once the speedup is saved, or once it is known it was not broadcast.
+// An intent left in the store means the process stopped in between, the node tells whether the speedup was broadcast.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct SpeedupIntent {
+    pub speedup: CoordinatedSpeedUpTransaction,
+    pub speedup_fee: u64,
+    // The speedup waiting for a retry that this speedup sends again.
+    pub retry_txid: Option<Txid>,
+}
+
+// Saved before the replacement of a transaction dispatched with allow_rbf_of_parent is broadcast, and removed when
+// the replacement is saved in the place of the original, or once it is known it was not broadcast.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ParentReplacementIntent {
+    pub replaced_txid: Txid,
+    pub replacement: Transaction,
+    pub block_height: BlockHeight,
+    pub fee_rate: u64,
+    pub extra_fee: u64,
+}
+
+// A signed speedup the SpeedupReviewHook rejected, it was never broadcast.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct SpeedupRejection {
+    pub tx_id: Txid,
+    pub paid_txids: Vec<Txid>,
+    pub fee: u64,
+    pub is_rbf: bool,
+    pub reason: String,
+}
+
+// Snapshot of the speedup budget returned by get_funding_summary.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct FundingSummary {
+    // The active funding utxo (the change of the last speedup), or None if no funding was ever added.
+    pub funding: Option<Utxo>,
+
+    // Funding UTXOs waiting to be used when the active speedup chain can not fund more speedups.
+    pub funding_pool: Vec<Utxo>,
+
+    // Total sats spent on speedups since the active funding was added (or taken from the pool).
+    pub spent_since_funding: u64,
+
+    // Number of speedups dispatched and not confirmed yet. Speedups replaced by a RBF are not counted.
+    pub unconfirmed_speedups: u32,
+
+    // Estimate of how many more speedups can be paid with the active and pool funding at the current network fee rate,
+    // using the average vsize of the speedups since the active funding. None if there are no speedups to average.
+    pub affordable_speedups: Option<u64>,
+}
+
+// Estimated cost of dispatching a set of transactions, returned by estimate_dispatch_cost.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DispatchCostEstimate {
+    // The batches the transactions would be dispatched in, each one paid by its own CPFP.
+    pub batches: Vec<BatchCostEstimate>,
+
+    // Transactions heavier than max_tx_weight, they can not be dispatched.
+    pub unbatchable_txs: Vec<Txid>,
+
+    // Transactions that do not fit in the unconfirmed chain, they would wait until speedups are confirmed.
+    pub deferred_txs: Vec<Txid>,
+
+    // The network fee rate (sat/vB) used for the estimate.
+    pub network_fee_rate: u64,
+
+    // Total sats paid by the CPFPs of all the batches.
+    pub total_fee: u64,
+
+    // Amount of the funding the first CPFP would spend, 0 if there is no funding.
+    pub funding_amount: u64,
+
+    // Whether the funding covers the total fee and the minimum funding amount.
+    pub is_funding_sufficient: bool,
+}
+
+// Estimated CPFP paying for one batch of transactions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchCostEstimate {
+    pub txids: Vec<Txid>,
+    pub cpfp_vsize: usize,
+    pub cpfp_fee: u64,
+}
+
+// Why transactions ready to be dispatched were left for a later tick, reported with DispatchDeferred.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub enum DispatchDeferredReason {
+    // Every unconfirmed slot of the speedup chain is taken, the transaction and its CPFP do not fit.
+    UnconfirmedChainLimit,
+    // The CPFP would exceed the mempool ancestor size limit.
+    AncestorSizeLimit,
+}
+
+// Why the rest of a tick step was skipped, reported with TickWorkSkipped.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub enum TickSkipReason {
+    // The funding checked before building a speedup was not found when it was used.
+    FundingDisappeared,
+    // A speedup was built without outputs, so it has no change to fund the next speedup.
+    SpeedupHasNoOutputs,
+    // The last speedup was gone when it was about to be replaced.
+    NoSpeedupToReplace,
+    // A transaction paid by a speedup has neither a speedup utxo nor a partial utxo amount.
+    SpeedupDataMissingAmount,
+    // A transaction to be paid by a speedup has no speedup data.
+    SpeedupDataMissing,
+}
+
+// Why a pending transaction was not dispatched (or sped up) yet.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub enum PendingReason {
+    // The transaction is dispatched once the target block height is reached.
+    TargetHeightNotReached,
+    // The locktime of the transaction matures at the block height, the node would refuse it before.
+    WaitingForLocktime(BlockHeight),
+    // Sending the transaction failed, it is sent again once the retry interval elapses.
+    RetryBackoff,
+    // Sending the transaction failed retry_attempts_sending_tx times.
+    RetriesExhausted,
+    // There is no funding (or no room in the speedup chain) to pay for the transaction CPFP.
+    FundingBlocked,
+    // A transaction it depends on is not confirmed yet.
+    DependencyNotConfirmed,
+}
+
+// A transaction the coordinator is working on, returned by get_pending_overview.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct PendingTxEntry {
+    pub tx_id: Txid,
+    pub context: String,
+    pub state: TransactionState,
+    pub retry_count: u32,
+    pub target_block_height: Option<BlockHeight>,
+    pub broadcast_block_height: Option<BlockHeight>,
+    pub has_speedup: bool,
+    // None when nothing holds the transaction back.
+    pub pending_reason: Option<PendingReason>,
+}
+
+// An unconfirmed speedup of the active speedup chain, returned by get_pending_overview.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct PendingSpeedupEntry {
+    pub tx_id: Txid,
+    pub context: String,
+    pub state: SpeedupState,
+    pub is_rbf: bool,
+    pub broadcast_block_height: BlockHeight,
+    // Sats paid by the speedup, the funding it spends minus its change.
+    pub fee: u64,
+    pub network_fee_rate_used: u64,
+    // Transactions paid by the speedup.
+    pub paid_txids: Vec<Txid>,
+}
+
+// A speedup (CPFP or RBF) that paid for a transaction, returned by get_speedups_for_tx.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct SpeedupSummary {
+    pub tx_id: Txid,
+    pub state: SpeedupState,
+    pub is_rbf: bool,
+    pub broadcast_block_height: BlockHeight,
+    // Sats paid by the speedup, the funding it spends minus its change.
+    pub fee: u64,
+    pub network_fee_rate_used: u64,
+    // Transactions paid by the speedup, the queried transaction included.
+    pub paid_txids: Vec<Txid>,
+    #[serde(default)]
+    pub vsize: u64,
+    // The bump fee percentage the fee was priced with, and the one of the speedup it replaces or boosts.
+    #[serde(default)]
+    pub bump_fee_percentage_used: f64,
+    #[serde(default)]
+    pub bumped_from: Option<f64>,
+}
+
+// Why a transaction is not confirmed yet, reported by diagnose.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub enum BlockingReason {
+    // The transaction is dispatched once the target block height is reached.
+    AwaitingTargetHeight,
+    // The locktime of the transaction matures at the block height.
+    AwaitingLocktime(BlockHeight),
+    // A transaction it depends on is not confirmed yet.
+    DependencyNotConfirmed,
+    // Sending the transaction failed, it is sent again once the retry interval elapses.
+    RetryBackoff,
+    // Sending the transaction failed retry_attempts_sending_tx times.
+    RetriesExhausted,
+    // There is no funding to pay for a speedup of the transaction.
+    FundingInsufficient,
+    // The unconfirmed chain reached the mempool limit of unconfirmed ancestors, no speedup can be chained to it.
+    AncestorLimitReached,
+    // The node is unreachable, nothing is sent until it answers again.
+    NodeUnreachable,
+    // The transaction (with its speedups) pays a lower fee rate than the network fee rate.
+    FeeBelowNetworkRate,
+}
+
+// Why a transaction has not confirmed, assembled from the store and the monitor by diagnose.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct TxDiagnosis {
+    pub tx_id: Txid,
+    pub context: String,
+    pub state: TransactionState,
+    // Monitor height the diagnosis was made at.
+    pub current_block_height: BlockHeight,
+    pub target_block_height: Option<BlockHeight>,
+    pub first_broadcast_block_height: Option<BlockHeight>,
+    pub broadcast_block_height: Option<BlockHeight>,
+    pub first_confirmation_block_height: Option<BlockHeight>,
+    // Confirmations reported by the monitor, None if it does not see the transaction.
+    pub confirmations: Option<u32>,
+
+    // Whether the transaction was ever sent to the node.
+    pub was_broadcast: bool,
+    // Last dispatch, retry, rebroadcast or replacement recorded in the history of the transaction.
+    pub last_dispatch_attempt: Option<TransactionEvent>,
+    pub retry_count: u32,
+
+    pub has_speedup: bool,
+    pub funding_group: Option<String>,
+    // Speedups (CPFP and RBF) that paid for the transaction, from the oldest to the newest.
+    pub speedups: Vec<SpeedupSummary>,
+    // Unconfirmed speedups in the speedup chain of the transaction, and the max before they are replaced instead.
+    pub unconfirmed_speedups: u32,
+    pub max_unconfirmed_speedups: u32,
+    pub last_rbf_block_height: Option<BlockHeight>,
+    // Sats of the speedups attributable to the transaction, as in the confirmation stats.
+    pub speedup_fees: u64,
+
+    pub network_fee_rate: u64,
+    // Fee rate the transaction is paid at: the rate of its last speedup, or its rate at dispatch without speedups.
+    pub paying_fee_rate: Option<u64>,
+    pub funding_available: bool,
+    // Whether the unconfirmed chain has no room left for another CPFP.
+    pub ancestor_limit_reached: bool,
+    // Timestamp in milliseconds since the node is unreachable, None when it answers.
+    pub node_unreachable_since: Option<u64>,
+
+    // Everything currently holding the transaction back, empty when nothing does.
+    pub blocking_reasons: Vec<BlockingReason>,
+}
+
+// Confirmation latency and fees of a finalized transaction, kept for get_confirmation_stats.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct FinalizedTxStats {
+    pub tx_id: Txid,
+    pub first_broadcast_block_height: BlockHeight,
+    pub first_confirmation_block_height: BlockHeight,
+    // Speedups (CPFP and RBF) and replacements spent on the transaction.
+    pub bump_count: u32,
+    // Sats attributable to the transaction: its own fee, assumed at 1 sat/vB, plus its share of the bumps.
+    pub fee: u64,
+    pub vsize: u64,
+    #[serde(default)]
+    pub fee_report: Option<PackageFeeReport>,
+}
+
+// Entry of the finalized log, written when a transaction is finalized, returned by read_finalized.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct FinalizedTxEntry {
+    // Position of the entry in the log, increasing with every entry and never reused.
+    pub seq: u64,
+    pub tx_id: Txid,
+    pub context: String,
+    // Block the transaction was finalized in.
+    pub block_height: BlockHeight,
+    pub block_hash: BlockHash,
+    // Fee of the transaction, assumed at 1 sat/vB when its inputs could not be fetched, plus the sats
+    // spent on its speedups and replacements.
+    pub total_fee: u64,
+    // Milliseconds since the Unix epoch when the transaction was finalized.
+    pub timestamp: u64,
+}
+
+// Progress of the FinalizedSink over the finalized log.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+pub struct FinalizedDelivery {
+    // Sequence number of the next entry never handed to the sink.
+    pub next_seq: u64,
+    // Entries the sink failed to take, handed to it again on the next ticks.
+    pub failed: Vec<u64>,
+}
+
+// Fees of a finalized transaction together with its confirmed speedups (CPFP and RBF).
+// The fee and the vsize of a speedup are split evenly between the transactions it pays.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct PackageFeeReport {
+    // Fee of the transaction itself from the amounts it spends, None if they could not be fetched.
+    // The transaction is then assumed to pay 1 sat/vbyte.
+    pub parent_fee: Option<u64>,
+    pub speedup_fees: u64,
+    // Vsize of the transaction plus its share of the speedups.
+    pub vsize: u64,
+    pub effective_fee_rate: f64,
+    // Network fee rate (sat/vB) estimated when the transaction was confirmed.
+    pub reference_fee_rate: Option<u64>,
+}
+
+impl PackageFeeReport {
+    // Effective fee rate over the reference fee rate, None without a reference.
+    pub fn overpayment_ratio(&self) -> Option<f64> {
+        self.reference_fee_rate
+            .filter(|rate| *rate > 0)
+            .map(|rate| self.effective_fee_rate / rate as f64)
+    }
+}
+
+// Aggregated over the transactions first confirmed in a window of blocks, returned by get_confirmation_stats.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+pub struct ConfirmationStats {
+    pub tx_count: u32,
+    // Blocks from the first broadcast to the first confirmation, as nearest-rank percentiles.
+    pub median_blocks_to_confirm: u32,
+    pub p90_blocks_to_confirm: u32,
+    // Total fee attributable to the transactions over their total vsize, speedups included.
+    pub avg_fee_rate_sat_vb: f64,
+    // Transactions that needed at least one speedup or replacement.
+    pub bumped_tx_count: u32,
+}
+
+// What the coordinator is sitting on, returned by get_pending_overview.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+pub struct PendingOverview {
+    // Transactions in state ToDispatch, in the order they were saved.
+    pub to_dispatch: Vec<PendingTxEntry>,
+
+    // Transactions in state Dispatched waiting for confirmation.
+    pub dispatched: Vec<PendingTxEntry>,
+
+    // Unconfirmed speedups from the oldest to the newest.
+    pub unconfirmed_speedups: Vec<PendingSpeedupEntry>,
+
+    // Timestamp in milliseconds since the node is unreachable, None when it answers.
+    pub node_unreachable_since: Option<u64>,
+
+    // Work done by the last tick and the transactions it left for the next ticks.
+    pub last_tick_budget: TickBudgetUsage,
+
+    // Recent speedup outcomes and the bump they lead to.
+    pub bump_strategy: BumpStrategyState,
+}
+
+// How a confirmed speedup or replacement did, recorded to adjust the bump of the next stuck speedups.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct SpeedupOutcome {
+    pub tx_id: Txid,
+    // Blocks from the broadcast to the confirmation.
+    pub blocks_waited: u32,
+    // Fee rate paid over the network fee rate estimated when the speedup was created.
+    pub fee_rate_ratio: f64,
+    // The speedup waited more than min_blocks_before_resend_speedup blocks.
+    pub missed: bool,
+    pub is_rbf: bool,
+}
+
+// Summary of the recorded speedup outcomes, returned in the pending overview.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+pub struct BumpStrategyState {
+    pub samples: u32,
+    pub miss_rate: Option<f64>,
+    pub average_blocks_waited: Option<f64>,
+    // Factor applied to the bump of the next stuck speedup, before following the network fee rate.
+    pub next_step: f64,
+    pub min_step: f64,
+    pub max_step: f64,
+}
+
+/// Whether the coordinator is running, persisted so the next start knows how the previous run stopped.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub enum CoordinatorRunState {
+    // Written when the coordinator is built, still there on the next start if the process stopped without shutdown.
+    Running,
+    Stopped(ShutdownCheckpoint),
+}
+
+/// What the coordinator left behind when it was shut down.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+pub struct ShutdownCheckpoint {
+    // Timestamp in milliseconds of the shutdown.
+    pub stopped_at: u64,
+
+    pub monitor_height: BlockHeight,
+
+    // Transactions in state ToDispatch, dispatched on the next start.
+    pub txs_to_dispatch: usize,
+
+    // Transactions dispatched and not finalized yet.
+    pub txs_in_progress: usize,
+
+    pub unconfirmed_speedups: usize,
+
+    // Dispatched transactions without a speedup paying for them, recovered on the next start.
+    pub txs_without_speedup: usize,
+
+    // Speedups broadcast or about to be, not saved yet.
+    pub speedup_intents: usize,
+
+    // Store writes of broadcast transactions that could not be flushed.
+    pub pending_store_writes: usize,
+}
+
+impl ShutdownCheckpoint {
+    /// A clean stop leaves nothing to reconcile on the next start.
+    pub fn is_clean(&self) -> bool {
+        self.txs_without_speedup == 0 && self.speedup_intents == 0 && self.pending_store_writes == 0
+    }
+}
+
+/// Returned by `shutdown`, the work flushed before stopping and the checkpoint persisted.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+pub struct ShutdownReport {
+    // Store writes of broadcast transactions that were waiting to be retried and were saved.
+    pub flushed_store_writes: usize,
+
+    pub checkpoint: ShutdownCheckpoint,
+}
+
+/// Advisory ownership record of the store, so two coordinators never work on the same store.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct StoreOwner {
+    // Random id of the coordinator instance, a new one each time a coordinator is built.
+    pub instance_id: Uuid,
+    pub pid: u32,
+    // Timestamp in milliseconds of the last tick of the owner.
+    pub heartbeat_at: u64,
+}
+
+// Work done by a tick with the max_broadcasts_per_tick, max_speedups_per_tick and max_conflict_scan_blocks_per_tick limits.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq)]
+pub struct TickBudgetUsage {
+    // Transactions sent to the node, accepted or not.
+    pub broadcasts: u32,
+
+    // CPFPs sent to pay for batches of transactions.
+    pub speedups: u32,
+
+    // Blocks read from the node to look for the double spends of the missing transactions.
+    #[serde(default)]
+    pub conflict_scan_blocks: u32,
+
+    // Transactions left for the next ticks because a limit was reached, in the order they are dispatched.
+    pub deferred_txs: Vec<Txid>,
+}
+
+// Progress of the blockchain indexing, returned by readiness.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ReadinessReport {
+    // Whether the coordinator processes transactions on tick.
+    pub ready: bool,
+
+    // Last block height indexed by the monitor.
+    pub indexed_height: BlockHeight,
+
+    // Best block height of the node. While the node is unreachable it is not asked, the indexed height is reported.
+    pub tip_height: BlockHeight,
+
+    // Blocks the monitor still has to index to reach the tip.
+    pub blocks_remaining: BlockHeight,
+
+    // Whether there are transactions waiting to be dispatched until the coordinator is ready.
+    pub has_pending_txs: bool,
+
+    // Timestamp in milliseconds since the node is unreachable, None when it answers.
+    // The coordinator is not ready while the node is unreachable.
+    pub node_unreachable_since: Option<u64>,
+}
+
+// Coordinator-side history of a transaction returned by get_transaction_history.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct TransactionHistory {
+    pub tx_id: Txid,
+
+    pub state: TransactionState,
+
+    pub broadcast_block_height: Option<BlockHeight>,
+
+    // Fee rate paid by the transaction and its speedups, once it is finalized.
+    pub fee_report: Option<PackageFeeReport>,
+
+    // Events in the order they were recorded.
+    pub events: Vec<TransactionHistoryEntry>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct TransactionHistoryEntry {
+    // Milliseconds since the Unix epoch when the event was recorded.
+    pub timestamp: u64,
+
+    pub event: TransactionEvent,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub enum TransactionEvent {
+    // The transaction was saved to be dispatched.
+    Saved {
+        target_block_height: Option<BlockHeight>,
+    },
+
+    // The transaction was broadcast at `block_height`, targeting `fee_rate` (sat/vB).
+    Dispatched {
+        block_height: BlockHeight,
+        fee_rate: u64,
+    },
+
+    // Sending the transaction failed and it was retried `retries_count` times.
+    DispatchRetried {
+        retries_count: u32,
+    },
+
+    // The block the transaction was confirmed in was orphaned by a reorg, the transaction is Dispatched again.
+    Reorged {
+        block_hash: BlockHash,
+    },
+
+    // The transaction was missing from the mempool and the chain and was sent again at `block_height`.
+    Rebroadcast {
+        attempt: u32,
+        block_height: BlockHeight,
+    },
+
+    // A speedup (CPFP or RBF) paying for the transaction was broadcast at `block_height`.
+    SpedUp {
+        speedup_txid: Txid,
+        is_rbf: bool,
+        fee: u64,
+        block_height: BlockHeight,
+    },
+
+    StateChanged {
+        from: TransactionState,
+        to: TransactionState,
+    },
+
+    // The speedup output was spent by `spending_txid`, not a speedup of the coordinator, so it is not sped up anymore.
+    AnchorSpent {
+        spending_txid: Txid,
+    },
+
+    // The transaction was replaced (RBF) at `block_height` by `replacement_txid`, paying `extra_fee` sats more.
+    Replaced {
+        replacement_txid: Txid,
+        extra_fee: u64,
+        block_height: BlockHeight,
+    },
+
+    // The transaction was broadcast at `block_height` to replace `replaced_txid`, paying `extra_fee` sats more.
+    ReplacementDispatched {
+        replaced_txid: Txid,
+        extra_fee: u64,
+        block_height: BlockHeight,
+    },
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct JournalEntry {
+    // Position of the entry in the journal, increasing with every entry and never reused.
+    pub seq: u64,
+
+    // Milliseconds since the Unix epoch when the entry was written.
+    pub timestamp: u64,
+
+    // Monitor height at the last tick, None before the first tick.
+    pub block_height: Option<BlockHeight>,
+
+    pub event: JournalEvent,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub enum JournalEvent {
+    // A transaction (or a speedup) was sent to the node. `error` is the error returned by the node, if any.
+    BroadcastAttempt {
+        tx_id: Txid,
+        raw_tx: String,
+        error: Option<String>,
+    },
+
+    // A CPFP (or an RBF of the last CPFP) was built with these fee inputs.
+    SpeedupCreated {
+        tx_id: Txid,
+        is_rbf: bool,
+        paid_txids: Vec<Txid>,
+        network_fee_rate: u64,
+        bump_fee_percentage: f64,
+        vsize: usize,
+        parents_vsize: usize,
+        fee: u64,
+    },
+
+    // An event of the history of a transaction, like a state transition.
+    Transaction {
+        tx_id: Txid,
+        event: TransactionEvent,
+    },
+
+    NewsEmitted(CoordinatorNews),
+}
+
+impl JournalEvent {
+    // An attempt to send `tx` to the node, with the error returned by the node if it was not accepted.
+    pub fn broadcast_attempt<E: ToString>(tx: &Transaction, result: &Result<Txid, E>) -> Self {
+        JournalEvent::BroadcastAttempt {
+            tx_id: tx.compute_txid(),
+            raw_tx: serialize_hex(tx),
+            error: result.as_ref().err().map(|e| e.to_string()),
+        }
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
@@ -132,8 +1075,9 @@ impl CoordinatedSpeedUpTransaction {
         broadcast_block_height: BlockHeight,
         state: SpeedupState,
         bump_fee_percentage_used: f64,
-        speedup_tx_data: Vec<(SpeedupData, Transaction, String)>,
+        speedup_tx_data: Vec<SpeedupParent>,
         network_fee_rate_used: u64,
+        vsize: usize,
     ) -> Self {
         let mut context = if is_rbf {
             RBF_TRANSACTION_CONTEXT.to_string()
@@ -159,7 +1103,11 @@ impl CoordinatedSpeedUpTransaction {
             bump_fee_percentage_used,
             speedup_tx_data,
             network_fee_rate_used,
+            vsize,
             retry_info: None,
+            exhausted_change: None,
+            is_external: false,
+            bumped_from: None,
         }
     }
 }
@@ -175,6 +1123,35 @@ impl CoordinatedSpeedUpTransaction {
         self.is_rbf
     }
 
+    pub fn paid_txids(&self) -> Vec<Txid> {
+        self.speedup_tx_data
+            .iter()
+            .map(|parent| parent.tx_id)
+            .collect()
+    }
+
+    // The transactions paid by the speedup, read from the store to build the speedup again.
+    pub fn load_parents(
+        &self,
+        store: &impl BitcoinCoordinatorStoreApi,
+    ) -> Result<Vec<(SpeedupData, Transaction, String)>, BitcoinCoordinatorStoreError> {
+        self.speedup_tx_data
+            .iter()
+            .map(|parent| {
+                let tx = store.get_tx(&parent.tx_id)?.tx;
+                Ok((parent.speedup_data.clone(), tx, parent.context.clone()))
+            })
+            .collect()
+    }
+
+    // The change that funds the next speedup of the chain, None when this speedup has no change output.
+    pub fn change_funding(&self) -> Option<Utxo> {
+        match self.exhausted_change {
+            Some(_) => None,
+            None => Some(self.next_funding.clone()),
+        }
+    }
+
     pub fn get_tx_name(&self) -> String {
         if self.is_funding() {
             "FUNDING".to_string()
@@ -191,19 +1168,69 @@ pub struct TransactionFullInfo {
     pub tx: Transaction,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct News {
     pub monitor_news: Vec<MonitorNews>,
     pub coordinator_news: Vec<CoordinatorNews>,
+    // Number of news of each severity, monitor and coordinator news together.
+    #[serde(default)]
+    pub severity_counts: SeverityCounts,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+/// How urgently a news needs attention, from the least to the most severe.
+/// Severities are ordered, so `Severity::Warning` and above are the warnings and the critical news.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Expected events the coordinator handled on its own
+    Info,
+    /// Something went wrong but the coordinator retries or works around it
+    Warning,
+    /// A transaction or the coordinator can not make progress without someone acting on it
+    Critical,
+}
+
+// Number of news of each severity in a News.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct SeverityCounts {
+    pub info: usize,
+    pub warning: usize,
+    pub critical: usize,
+}
+
+impl SeverityCounts {
+    pub fn add(&mut self, severity: Severity) {
+        match severity {
+            Severity::Info => self.info += 1,
+            Severity::Warning => self.warning += 1,
+            Severity::Critical => self.critical += 1,
+        }
+    }
+
+    pub fn get(&self, severity: Severity) -> usize {
+        match severity {
+            Severity::Info => self.info,
+            Severity::Warning => self.warning,
+            Severity::Critical => self.critical,
+        }
+    }
+}
+
+/// A setting changed by `update_settings`, with its old and new values as written in the settings.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct SettingChange {
+    pub name: String,
+    pub old_value: String,
+    pub new_value: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub enum CoordinatorNews {
     /// Error when dispatching a transaction
     /// - Txid: The transaction ID that failed to dispatch
     /// - String: Context information about the transaction
     /// - String: Error message describing what went wrong
-    DispatchTransactionError(Txid, String, String),
+    /// - BroadcastFailureKind: Why the node did not accept the transaction
+    DispatchTransactionError(Txid, String, String, BroadcastFailureKind),
 
     /// Error when attempting to speed up a transaction
     /// - Vec<Txid>: The transaction IDs that failed to speed up
@@ -226,7 +1253,56 @@ pub enum CoordinatorNews {
     /// - u64: The max allowed feerate from settings
     EstimateFeerateTooHigh(u64, u64),
 
+    /// Indicates that the node had no fee rate estimate (e.g. a fresh regtest node) and a fallback was used
+    /// - u64: The fallback fee rate in sat/vB (the mempool min fee of the node or the min network fee rate)
+    FeeEstimateUnavailable(u64),
+
+    /// Dispatches that are not urgent were paused because the network fee rate is above their pause threshold
+    /// Reported once per block, with the first tick of the block that paused dispatches.
+    /// - u32: The number of paused dispatches
+    /// - u64: The network fee rate in sat/vB
+    DispatchPausedHighFees(u32, u64),
+
+    /// Funding requested to the FundingProvider was confirmed and registered with add_funding
+    /// - Txid: The transaction ID of the funding
+    /// - u64: The amount of the funding in sats
+    FundingTopUp(Txid, u64),
+
+    /// The CPFP paying only for the transaction would exceed `max_cpfp_fee_sats_per_batch`
+    /// The transaction is deferred to a later tick instead of being batched.
+    /// - Txid: The transaction ID that needs the speedup
+    /// - u64: The estimated fee of its CPFP in sats
+    /// - u64: The max CPFP fee per batch from settings
+    SpeedupFeeCapExceeded(Txid, u64, u64),
+
+    /// A transaction dispatched with `allow_rbf_of_parent` was not confirmed in time and was replaced (RBF)
+    /// The replacement is tracked instead of the original, and the news of the original are reported for it.
+    /// - Txid: The transaction ID that was replaced
+    /// - Txid: The transaction ID of the replacement
+    /// - u64: The extra fee in sats paid by the replacement, taken from the change output
+    ParentReplaced(Txid, Txid, u64),
+
+    /// Some transactions could not be processed during a tick, the others were processed and the tick continued
+    /// The failed transactions are processed again on the next tick.
+    /// - u32: The number of transactions that failed in the tick
+    TickPartialFailure(u32),
+
+    /// The node failed `node_failure_threshold` times in a row, reported once until it recovers
+    /// Dispatch and speedups are suspended, each tick only probes the node until it answers again.
+    /// - u64: The timestamp in milliseconds since the node is unreachable
+    NodeUnreachable(u64),
+
+    /// The node answered again after being unreachable, dispatch and speedups are resumed
+    /// - u64: The time in milliseconds the node was unreachable
+    NodeRecovered(u64),
+
+    /// The coordinator settings were updated at runtime with `update_settings`
+    /// - Vec<SettingChange>: The settings that changed, with their old and new values
+    SettingsUpdated(Vec<SettingChange>),
+
     /// Transaction is already in mempool (treated as success)
+    /// No longer reported, a transaction the node already has is handled as dispatched.
+    /// Kept so the news saved by previous versions can be read and acknowledged.
     /// - Txid: The transaction ID that is already in mempool
     /// - String: Context information about the transaction
     TransactionAlreadyInMempool(Txid, String),
@@ -242,15 +1318,472 @@ pub enum CoordinatorNews {
     /// - String: Context information about the transaction
     /// - String: Error message describing the network error
     NetworkError(Txid, String, String),
+
+    /// The dispatch of a transaction was cancelled
+    /// - Txid: The transaction ID that was cancelled
+    /// - String: Context information about the transaction
+    DispatchCancelled(Txid, String),
+
+    /// A transaction was not confirmed by its expiry height, it is not dispatched nor sped up anymore
+    /// If it was already broadcast it can still be confirmed, and it is followed as usual.
+    /// - Txid: The transaction ID that expired
+    /// - String: Context information about the transaction
+    /// - BlockHeight: The expiry height given at dispatch
+    TransactionExpired(Txid, String, BlockHeight),
+
+    /// A transaction was dispatched with a speedup anchor that is below dust or can not be spent by the CPFP,
+    /// because `allow_nonstandard_anchor` is set. Its CPFP may be rejected by the node.
+    /// - Txid: The transaction ID of the anchor
+    /// - String: Context information about the transaction
+    /// - String: Why the anchor is not standard
+    NonStandardAnchor(Txid, String, String),
+
+    /// A replacement (RBF) was rejected for insufficient fee after escalating the fee bump
+    /// - Txid: The cpfp transaction ID that could not be replaced
+    /// - u32: The number of replacement attempts
+    /// - String: The last error message returned by the node
+    RbfEscalationFailed(Txid, u32, String),
+
+    /// A CPFP was replaced max_rbf_attempts times without confirming, it is not replaced again
+    /// - Txid: The cpfp transaction ID that is not replaced anymore
+    /// - u32: The number of replacements sent for the cpfp
+    /// - u64: The sum of the fees in sats of all the replacements sent for the cpfp
+    MaxRbfAttemptsReached(Txid, u32, u64),
+
+    /// A dispatched transaction without speedup was missing from the mempool and the chain and was sent again
+    /// - Txid: The transaction ID that was rebroadcast
+    /// - u32: The rebroadcast attempt, starting at 1
+    TransactionRebroadcast(Txid, u32),
+
+    /// A transaction without speedup was rebroadcast max_rebroadcast_attempts times without being found, it is not sent again
+    /// - Txid: The transaction ID that is not rebroadcast anymore
+    /// - u32: The number of rebroadcasts sent for the transaction
+    MaxRebroadcastAttemptsReached(Txid, u32),
+
+    /// A speedup transaction was orphaned by a reorg, its change can not be used as funding until it is confirmed again
+    /// - Txid: The speedup transaction ID that was orphaned
+    /// - Vec<Txid>: The transaction IDs paid by the orphaned speedup
+    SpeedupOrphaned(Txid, Vec<Txid>),
+
+    /// A replacement (RBF) was confirmed, or a cancelled transaction was dropped from the mempool, the speedups it replaced
+    /// (or that paid for the dropped transaction) and the speedups funded from their change will never be confirmed
+    /// - Vec<Txid>: The speedup transaction IDs invalidated, starting with the replaced speedup
+    SpeedupChainInvalidated(Vec<Txid>),
+
+    /// A speedup transaction (CPFP or RBF) was broadcast to pay for dispatched transactions
+    /// - Txid: The speedup transaction ID
+    /// - Vec<Txid>: The transaction IDs paid by the speedup
+    /// - u64: The fee in sats paid by the speedup
+    /// - u64: The network fee rate in sat/vB targeted by the speedup
+    /// - bool: Whether the speedup replaces (RBF) a previous speedup
+    SpeedupCreated(Txid, Vec<Txid>, u64, u64, bool),
+
+    /// A dispatched transaction will never be confirmed because one of its inputs was spent by a confirmed conflicting transaction
+    /// - Txid: The transaction ID that was double spent
+    /// - Txid: The conflicting transaction ID that spent the input
+    /// - String: Context information about the transaction
+    TransactionConflicted(Txid, Txid, String),
+
+    /// A confirmed transaction was orphaned by a reorg, it is Dispatched again until it is mined in a new block
+    /// - Txid: The transaction ID that was reorged
+    /// - BlockHash: The orphaned block the transaction was confirmed in
+    /// - String: Context information about the transaction
+    TransactionReorged(Txid, BlockHash, String),
+
+    /// A transaction scheduled for a target block height was broadcast
+    /// - Txid: The transaction ID that was broadcast
+    /// - BlockHeight: The block height the transaction was broadcast at
+    DispatchScheduled(Txid, BlockHeight),
+
+    /// A transaction waiting for its dependencies was not dispatched because one of them failed
+    /// - Txid: The transaction ID that was marked as failed
+    /// - Txid: The dependency that failed
+    DependencyFailed(Txid, Txid),
+
+    /// A watched outpoint was spent by a transaction mined in a block
+    /// - OutPoint: The watched outpoint
+    /// - Txid: The transaction ID that spent the outpoint
+    /// - u32: The index of the input of the spending transaction that consumed the outpoint
+    /// - BlockInfo: The block the spending transaction was mined in
+    /// - String: Context information given when the outpoint was watched
+    OutpointSpent(OutPoint, Txid, u32, BlockInfo, String),
+
+    /// A transaction paying to a watched output script was mined in a block
+    /// - ScriptBuf: The watched output script
+    /// - Transaction: The transaction paying to the script
+    /// - Vec<(u32, u64)>: The index and amount in sats of each output paying to the script
+    /// - BlockInfo: The block the transaction was mined in
+    /// - String: Context information given when the script was watched
+    AddressFunded(ScriptBuf, Transaction, Vec<(u32, u64)>, BlockInfo, String),
+
+    /// A funding output was spent outside the coordinator, it is not used for speedups anymore
+    /// - OutPoint: The funding output
+    /// - Option<Txid>: The transaction that spent it, when it is known
+    FundingSpentExternally(OutPoint, Option<Txid>),
+
+    /// The change of a speedup was below the dust threshold, it was added to the fee and the speedup has no change output
+    /// The funding chain ends with this speedup, new funding must be added to keep speeding up transactions.
+    /// - Txid: The speedup transaction ID that spent the last of the funding
+    /// - u64: The change in sats added to the fee
+    FundingExhausted(Txid, u64),
+
+    /// The funding of a speedup comes from the change of a speedup orphaned by a reorg, the output does not exist on
+    /// the active chain until the speedup is mined again. The speedup is not sent, it is retried on the next ticks.
+    /// - Txid: The orphaned speedup transaction ID the funding depends on
+    /// - Vec<Txid>: The transaction IDs waiting for the speedup
+    FundingTemporarilyUnavailable(Txid, Vec<Txid>),
+
+    /// The speedup output of a dispatched transaction was spent by a transaction that is not a speedup of the coordinator
+    /// The transaction is not paid by CPFPs anymore. If the spender pays for it, it is confirmed as usual.
+    /// - Txid: The transaction ID of the dispatched transaction
+    /// - OutPoint: The speedup output
+    /// - Txid: The transaction that spent it
+    AnchorSpentExternally(Txid, OutPoint, Txid),
+
+    /// A finalized transaction paid, across its package, more than the configured ratio over the fee rate
+    /// estimated when it was confirmed
+    /// - Txid: The finalized transaction ID
+    /// - f64: The effective fee rate of the package in sat/vB
+    /// - u64: The fee rate estimated at the confirmation block in sat/vB
+    FeeOverpayment(Txid, f64, u64),
+
+    /// A transaction reached one of its confirmation milestones, reported once per milestone unless a reorg
+    /// drops its confirmations below the milestone again
+    /// - Txid: The transaction ID
+    /// - u32: The milestone reached, in confirmations
+    /// - String: Context information about the transaction
+    ConfirmationMilestone(Txid, u32, String),
+
+    /// A member of a UTXO set watched with `monitor_utxo_set` was spent by a transaction mined in a block
+    /// - String: The label of the set
+    /// - OutPoint: The spent member
+    /// - Txid: The spending transaction ID
+    /// - u32: Members of the set still unspent
+    CollateralSpent(String, OutPoint, Txid, u32),
+
+    /// Every member of a UTXO set watched with `monitor_utxo_set` was spent, reported after the last `CollateralSpent`
+    /// - String: The label of the set
+    CollateralFullySpent(String),
+
+    /// Transactions ready to be dispatched were left for a later tick, the speedup chain has no room for their CPFPs
+    /// They stay waiting to be dispatched and are tried again on the next ticks.
+    /// - Vec<Txid>: The deferred transaction IDs
+    /// - DispatchDeferredReason: The limit that was reached
+    DispatchDeferred(Vec<Txid>, DispatchDeferredReason),
+
+    /// The rest of a tick step was skipped because the state it relied on changed or is invalid
+    /// The other steps of the tick run, the skipped work is tried again on the next tick.
+    /// - TickSkipReason: What was wrong
+    /// - String: The error message
+    TickWorkSkipped(TickSkipReason, String),
+
+    /// A signed speedup was rejected by the `SpeedupReviewHook`, it was not broadcast
+    /// - Txid: The speedup transaction ID
+    /// - String: The reason given by the hook
+    SpeedupRejectedByPolicy(Txid, String),
+
+    /// Every member of a group registered with `monitor_group` or `dispatch_group` is finalized or cancelled
+    /// - Uuid: The group ID
+    /// - String: The context of the group
+    GroupCompleted(Uuid, String),
+
+    /// A new block was indexed, only reported after subscribing with `TypesToMonitor::NewBlock`
+    /// - BlockHeight: The height of the block
+    /// - BlockHash: The hash of the block
+    NewBlock(BlockHeight, BlockHash),
+}
+
+impl CoordinatorNews {
+    // Name of the news variant, used as a label when reporting it.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            CoordinatorNews::DispatchTransactionError(..) => "DispatchTransactionError",
+            CoordinatorNews::DispatchSpeedUpError(..) => "DispatchSpeedUpError",
+            CoordinatorNews::InsufficientFunds(..) => "InsufficientFunds",
+            CoordinatorNews::FundingNotFound => "FundingNotFound",
+            CoordinatorNews::EstimateFeerateTooHigh(..) => "EstimateFeerateTooHigh",
+            CoordinatorNews::FeeEstimateUnavailable(..) => "FeeEstimateUnavailable",
+            CoordinatorNews::DispatchPausedHighFees(..) => "DispatchPausedHighFees",
+            CoordinatorNews::FundingTopUp(..) => "FundingTopUp",
+            CoordinatorNews::SpeedupFeeCapExceeded(..) => "SpeedupFeeCapExceeded",
+            CoordinatorNews::ParentReplaced(..) => "ParentReplaced",
+            CoordinatorNews::TickPartialFailure(..) => "TickPartialFailure",
+            CoordinatorNews::NodeUnreachable(..) => "NodeUnreachable",
+            CoordinatorNews::NodeRecovered(..) => "NodeRecovered",
+            CoordinatorNews::SettingsUpdated(..) => "SettingsUpdated",
+            CoordinatorNews::TransactionAlreadyInMempool(..) => "TransactionAlreadyInMempool",
+            CoordinatorNews::MempoolRejection(..) => "MempoolRejection",
+            CoordinatorNews::NetworkError(..) => "NetworkError",
+            CoordinatorNews::DispatchCancelled(..) => "DispatchCancelled",
+            CoordinatorNews::TransactionExpired(..) => "TransactionExpired",
+            CoordinatorNews::NonStandardAnchor(..) => "NonStandardAnchor",
+            CoordinatorNews::RbfEscalationFailed(..) => "RbfEscalationFailed",
+            CoordinatorNews::MaxRbfAttemptsReached(..) => "MaxRbfAttemptsReached",
+            CoordinatorNews::TransactionRebroadcast(..) => "TransactionRebroadcast",
+            CoordinatorNews::MaxRebroadcastAttemptsReached(..) => "MaxRebroadcastAttemptsReached",
+            CoordinatorNews::SpeedupOrphaned(..) => "SpeedupOrphaned",
+            CoordinatorNews::SpeedupChainInvalidated(..) => "SpeedupChainInvalidated",
+            CoordinatorNews::SpeedupCreated(..) => "SpeedupCreated",
+            CoordinatorNews::TransactionConflicted(..) => "TransactionConflicted",
+            CoordinatorNews::TransactionReorged(..) => "TransactionReorged",
+            CoordinatorNews::DispatchScheduled(..) => "DispatchScheduled",
+            CoordinatorNews::DependencyFailed(..) => "DependencyFailed",
+            CoordinatorNews::OutpointSpent(..) => "OutpointSpent",
+            CoordinatorNews::AddressFunded(..) => "AddressFunded",
+            CoordinatorNews::FundingSpentExternally(..) => "FundingSpentExternally",
+            CoordinatorNews::FundingExhausted(..) => "FundingExhausted",
+            CoordinatorNews::FundingTemporarilyUnavailable(..) => "FundingTemporarilyUnavailable",
+            CoordinatorNews::AnchorSpentExternally(..) => "AnchorSpentExternally",
+            CoordinatorNews::FeeOverpayment(..) => "FeeOverpayment",
+            CoordinatorNews::ConfirmationMilestone(..) => "ConfirmationMilestone",
+            CoordinatorNews::CollateralSpent(..) => "CollateralSpent",
+            CoordinatorNews::CollateralFullySpent(..) => "CollateralFullySpent",
+            CoordinatorNews::DispatchDeferred(..) => "DispatchDeferred",
+            CoordinatorNews::TickWorkSkipped(..) => "TickWorkSkipped",
+            CoordinatorNews::SpeedupRejectedByPolicy(..) => "SpeedupRejectedByPolicy",
+            CoordinatorNews::GroupCompleted(..) => "GroupCompleted",
+            CoordinatorNews::NewBlock(..) => "NewBlock",
+        }
+    }
+
+    /// How urgently the news needs attention.
+    /// Every variant declares its severity here, a new variant does not compile until it has one.
+    pub fn severity(&self) -> Severity {
+        match self {
+            CoordinatorNews::DispatchTransactionError(..) => Severity::Critical,
+            CoordinatorNews::DispatchSpeedUpError(..) => Severity::Critical,
+            CoordinatorNews::InsufficientFunds(..) => Severity::Critical,
+            CoordinatorNews::FundingNotFound => Severity::Critical,
+            // The fee rate is clamped to the max fee rate, the transactions are still sped up
+            CoordinatorNews::EstimateFeerateTooHigh(..) => Severity::Info,
+            CoordinatorNews::FeeEstimateUnavailable(..) => Severity::Warning,
+            CoordinatorNews::DispatchPausedHighFees(..) => Severity::Warning,
+            CoordinatorNews::FundingTopUp(..) => Severity::Info,
+            CoordinatorNews::SpeedupFeeCapExceeded(..) => Severity::Warning,
+            CoordinatorNews::ParentReplaced(..) => Severity::Info,
+            CoordinatorNews::TickPartialFailure(..) => Severity::Warning,
+            CoordinatorNews::NodeUnreachable(..) => Severity::Critical,
+            CoordinatorNews::NodeRecovered(..) => Severity::Info,
+            CoordinatorNews::SettingsUpdated(..) => Severity::Info,
+            CoordinatorNews::TransactionAlreadyInMempool(..) => Severity::Info,
+            CoordinatorNews::MempoolRejection(..) => Severity::Warning,
+            CoordinatorNews::NetworkError(..) => Severity::Warning,
+            CoordinatorNews::DispatchCancelled(..) => Severity::Info,
+            CoordinatorNews::TransactionExpired(..) => Severity::Warning,
+            CoordinatorNews::NonStandardAnchor(..) => Severity::Warning,
+            CoordinatorNews::RbfEscalationFailed(..) => Severity::Warning,
+            CoordinatorNews::MaxRbfAttemptsReached(..) => Severity::Critical,
+            CoordinatorNews::TransactionRebroadcast(..) => Severity::Info,
+            CoordinatorNews::MaxRebroadcastAttemptsReached(..) => Severity::Critical,
+            CoordinatorNews::SpeedupOrphaned(..) => Severity::Warning,
+            CoordinatorNews::SpeedupChainInvalidated(..) => Severity::Warning,
+            CoordinatorNews::SpeedupCreated(..) => Severity::Info,
+            CoordinatorNews::TransactionConflicted(..) => Severity::Critical,
+            CoordinatorNews::TransactionReorged(..) => Severity::Warning,
+            CoordinatorNews::DispatchScheduled(..) => Severity::Info,
+            CoordinatorNews::DependencyFailed(..) => Severity::Critical,
+            CoordinatorNews::OutpointSpent(..) => Severity::Info,
+            CoordinatorNews::AddressFunded(..) => Severity::Info,
+            CoordinatorNews::FundingSpentExternally(..) => Severity::Critical,
+            CoordinatorNews::FundingExhausted(..) => Severity::Critical,
+            CoordinatorNews::FundingTemporarilyUnavailable(..) => Severity::Warning,
+            CoordinatorNews::AnchorSpentExternally(..) => Severity::Warning,
+            CoordinatorNews::FeeOverpayment(..) => Severity::Warning,
+            CoordinatorNews::ConfirmationMilestone(..) => Severity::Info,
+            CoordinatorNews::CollateralSpent(..) => Severity::Info,
+            CoordinatorNews::CollateralFullySpent(..) => Severity::Info,
+            CoordinatorNews::DispatchDeferred(..) => Severity::Warning,
+            CoordinatorNews::TickWorkSkipped(..) => Severity::Warning,
+            CoordinatorNews::SpeedupRejectedByPolicy(..) => Severity::Warning,
+            CoordinatorNews::GroupCompleted(..) => Severity::Info,
+            CoordinatorNews::NewBlock(..) => Severity::Info,
+        }
+    }
+
+    // Acknowledgement of the news, as given to ack_news.
+    pub fn ack(&self) -> AckCoordinatorNews {
+        match self {
+            CoordinatorNews::DispatchTransactionError(tx_id, ..) => {
+                AckCoordinatorNews::DispatchTransactionError(*tx_id)
+            }
+            CoordinatorNews::DispatchSpeedUpError(_, _, speedup_txid, _) => {
+                AckCoordinatorNews::DispatchSpeedUpError(*speedup_txid)
+            }
+            CoordinatorNews::InsufficientFunds(tx_id, ..) => {
+                AckCoordinatorNews::InsufficientFunds(*tx_id)
+            }
+            CoordinatorNews::FundingNotFound => AckCoordinatorNews::FundingNotFound,
+            CoordinatorNews::EstimateFeerateTooHigh(fee_rate, max_fee_rate) => {
+                AckCoordinatorNews::EstimateFeerateTooHigh(*fee_rate, *max_fee_rate)
+            }
+            CoordinatorNews::FeeEstimateUnavailable(_) => {
+                AckCoordinatorNews::FeeEstimateUnavailable
+            }
+            CoordinatorNews::DispatchPausedHighFees(..) => {
+                AckCoordinatorNews::DispatchPausedHighFees
+            }
+            CoordinatorNews::FundingTopUp(tx_id, _) => AckCoordinatorNews::FundingTopUp(*tx_id),
+            CoordinatorNews::SpeedupFeeCapExceeded(tx_id, ..) => {
+                AckCoordinatorNews::SpeedupFeeCapExceeded(*tx_id)
+            }
+            CoordinatorNews::ParentReplaced(tx_id, ..) => {
+                AckCoordinatorNews::ParentReplaced(*tx_id)
+            }
+            CoordinatorNews::TickPartialFailure(_) => AckCoordinatorNews::TickPartialFailure,
+            CoordinatorNews::NodeUnreachable(_) => AckCoordinatorNews::NodeUnreachable,
+            CoordinatorNews::NodeRecovered(_) => AckCoordinatorNews::NodeRecovered,
+            CoordinatorNews::SettingsUpdated(_) => AckCoordinatorNews::SettingsUpdated,
+            CoordinatorNews::TransactionAlreadyInMempool(tx_id, _) => {
+                AckCoordinatorNews::TransactionAlreadyInMempool(*tx_id)
+            }
+            CoordinatorNews::MempoolRejection(tx_id, ..) => {
+                AckCoordinatorNews::MempoolRejection(*tx_id)
+            }
+            CoordinatorNews::NetworkError(tx_id, ..) => AckCoordinatorNews::NetworkError(*tx_id),
+            CoordinatorNews::DispatchCancelled(tx_id, _) => {
+                AckCoordinatorNews::DispatchCancelled(*tx_id)
+            }
+            CoordinatorNews::TransactionExpired(tx_id, ..) => {
+                AckCoordinatorNews::TransactionExpired(*tx_id)
+            }
+            CoordinatorNews::NonStandardAnchor(tx_id, ..) => {
+                AckCoordinatorNews::NonStandardAnchor(*tx_id)
+            }
+            CoordinatorNews::RbfEscalationFailed(tx_id, ..) => {
+                AckCoordinatorNews::RbfEscalationFailed(*tx_id)
+            }
+            CoordinatorNews::MaxRbfAttemptsReached(tx_id, ..) => {
+                AckCoordinatorNews::MaxRbfAttemptsReached(*tx_id)
+            }
+            CoordinatorNews::TransactionRebroadcast(tx_id, _) => {
+                AckCoordinatorNews::TransactionRebroadcast(*tx_id)
+            }
+            CoordinatorNews::MaxRebroadcastAttemptsReached(tx_id, _) => {
+                AckCoordinatorNews::MaxRebroadcastAttemptsReached(*tx_id)
+            }
+            CoordinatorNews::SpeedupOrphaned(tx_id, _) => {
+                AckCoordinatorNews::SpeedupOrphaned(*tx_id)
+            }
+            CoordinatorNews::SpeedupChainInvalidated(tx_ids) => {
+                AckCoordinatorNews::SpeedupChainInvalidated(tx_ids[0])
+            }
+            CoordinatorNews::SpeedupCreated(tx_id, ..) => {
+                AckCoordinatorNews::SpeedupCreated(*tx_id)
+            }
+            CoordinatorNews::TransactionConflicted(tx_id, ..) => {
+                AckCoordinatorNews::TransactionConflicted(*tx_id)
+            }
+            CoordinatorNews::TransactionReorged(tx_id, ..) => {
+                AckCoordinatorNews::TransactionReorged(*tx_id)
+            }
+            CoordinatorNews::DispatchScheduled(tx_id, _) => {
+                AckCoordinatorNews::DispatchScheduled(*tx_id)
+            }
+            CoordinatorNews::DependencyFailed(tx_id, _) => {
+                AckCoordinatorNews::DependencyFailed(*tx_id)
+            }
+            CoordinatorNews::OutpointSpent(outpoint, ..) => {
+                AckCoordinatorNews::OutpointSpent(*outpoint)
+            }
+            CoordinatorNews::AddressFunded(script_pubkey, tx, ..) => {
+                AckCoordinatorNews::AddressFunded(script_pubkey.clone(), tx.compute_txid())
+            }
+            CoordinatorNews::FundingSpentExternally(outpoint, _) => {
+                AckCoordinatorNews::FundingSpentExternally(*outpoint)
+            }
+            CoordinatorNews::FundingExhausted(tx_id, _) => {
+                AckCoordinatorNews::FundingExhausted(*tx_id)
+            }
+            CoordinatorNews::FundingTemporarilyUnavailable(tx_id, _) => {
+                AckCoordinatorNews::FundingTemporarilyUnavailable(*tx_id)
+            }
+            CoordinatorNews::AnchorSpentExternally(tx_id, ..) => {
+                AckCoordinatorNews::AnchorSpentExternally(*tx_id)
+            }
+            CoordinatorNews::FeeOverpayment(tx_id, ..) => {
+                AckCoordinatorNews::FeeOverpayment(*tx_id)
+            }
+            CoordinatorNews::ConfirmationMilestone(tx_id, confirmations, _) => {
+                AckCoordinatorNews::ConfirmationMilestone(*tx_id, *confirmations)
+            }
+            CoordinatorNews::CollateralSpent(_, outpoint, ..) => {
+                AckCoordinatorNews::CollateralSpent(*outpoint)
+            }
+            CoordinatorNews::CollateralFullySpent(label) => {
+                AckCoordinatorNews::CollateralFullySpent(label.clone())
+            }
+            CoordinatorNews::DispatchDeferred(_, reason) => {
+                AckCoordinatorNews::DispatchDeferred(*reason)
+            }
+            CoordinatorNews::TickWorkSkipped(reason, _) => {
+                AckCoordinatorNews::TickWorkSkipped(*reason)
+            }
+            CoordinatorNews::SpeedupRejectedByPolicy(tx_id, _) => {
+                AckCoordinatorNews::SpeedupRejectedByPolicy(*tx_id)
+            }
+            CoordinatorNews::GroupCompleted(group_id, _) => {
+                AckCoordinatorNews::GroupCompleted(*group_id)
+            }
+            CoordinatorNews::NewBlock(..) => AckCoordinatorNews::NewBlock,
+        }
+    }
 }
 
 impl News {
     pub fn new(monitor_news: Vec<MonitorNews>, coordinator_news: Vec<CoordinatorNews>) -> Self {
+        let mut severity_counts = SeverityCounts::default();
+
+        for news in monitor_news.iter() {
+            severity_counts.add(monitor_news_severity(news));
+        }
+
+        for news in coordinator_news.iter() {
+            severity_counts.add(news.severity());
+        }
+
         Self {
             monitor_news,
             coordinator_news,
+            severity_counts,
         }
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.monitor_news.is_empty() && self.coordinator_news.is_empty()
+    }
+}
+
+// A bounded slice of news returned by get_news_page, with at most `limit` monitor news and `limit` coordinator news.
+// `has_more` is true when there are still news after this page (monitor or coordinator).
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewsPage {
+    pub news: News,
+    pub has_more: bool,
+    // Sequence number to read the next page of coordinator news from.
+    pub next_seq: u64,
+}
+
+// Number of entries removed from the store by prune.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PruneSummary {
+    // Acknowledged news recorded before the pruning depth.
+    pub news: u32,
+
+    // Finalized transactions.
+    pub transactions: u32,
+
+    // Finalized speedups older than the last funding checkpoint.
+    pub speedups: u32,
+}
+
+// Transactions of a context handled by cancel_by_context.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContextCancelSummary {
+    // Transactions waiting to be dispatched or not confirmed yet, cancelled and no longer monitored.
+    pub cancelled: Vec<Txid>,
+
+    // Confirmed or finalized transactions, left untouched.
+    pub skipped_confirmed: Vec<Txid>,
 }
 
 pub enum AckCoordinatorNews {
@@ -259,9 +1792,52 @@ pub enum AckCoordinatorNews {
     DispatchSpeedUpError(Txid),
     EstimateFeerateTooHigh(u64, u64),
     FundingNotFound,
+    FeeEstimateUnavailable,
+    DispatchPausedHighFees,
+    FundingTopUp(Txid),
+    SpeedupFeeCapExceeded(Txid),
+    ParentReplaced(Txid),
+    TickPartialFailure,
+    NodeUnreachable,
+    NodeRecovered,
+    SettingsUpdated,
     TransactionAlreadyInMempool(Txid),
     MempoolRejection(Txid),
     NetworkError(Txid),
+    DispatchCancelled(Txid),
+    TransactionExpired(Txid),
+    NonStandardAnchor(Txid),
+    RbfEscalationFailed(Txid),
+    MaxRbfAttemptsReached(Txid),
+    TransactionRebroadcast(Txid),
+    MaxRebroadcastAttemptsReached(Txid),
+    SpeedupOrphaned(Txid),
+    // Acknowledged with the replaced speedup, the first txid of the news.
+    SpeedupChainInvalidated(Txid),
+    SpeedupCreated(Txid),
+    TransactionConflicted(Txid),
+    TransactionReorged(Txid),
+    DispatchScheduled(Txid),
+    DependencyFailed(Txid),
+    OutpointSpent(OutPoint),
+    // Acknowledged with the watched script and the transaction paying to it.
+    AddressFunded(ScriptBuf, Txid),
+    FundingSpentExternally(OutPoint),
+    FundingExhausted(Txid),
+    FundingTemporarilyUnavailable(Txid),
+    AnchorSpentExternally(Txid),
+    FeeOverpayment(Txid),
+    // Acknowledged with the transaction and the milestone reached.
+    ConfirmationMilestone(Txid, u32),
+    CollateralSpent(OutPoint),
+    CollateralFullySpent(String),
+    // Acknowledged with the reason, there is one news for each reason.
+    DispatchDeferred(DispatchDeferredReason),
+    // Acknowledged with the reason, there is one news for each reason.
+    TickWorkSkipped(TickSkipReason),
+    SpeedupRejectedByPolicy(Txid),
+    GroupCompleted(Uuid),
+    NewBlock,
 }
 
 pub enum AckNews {
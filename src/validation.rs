@@ -0,0 +1,240 @@
+use crate::{
+    cpfp::SpeedupOutputKind,
+    errors::BitcoinCoordinatorError,
+    settings::{
+        ANCHOR_SPEND_CONTEXT, CPFP_TRANSACTION_CONTEXT, FUNDING_TRANSACTION_CONTEXT,
+        MAX_CONTEXT_LENGTH, RBF_TRANSACTION_CONTEXT,
+    },
+};
+use bitcoin::{Transaction, TxOut, Txid};
+use protocol_builder::types::output::SpeedupData;
+
+// Rejections that do not mean the transaction is invalid: its inputs can be created by a transaction
+// not broadcast yet, it can be waiting for a timelock, or it can already be known by the node.
+const TOLERATED_MEMPOOL_REJECTIONS: [&str; 5] = [
+    "missing-inputs",
+    "non-final",
+    "non-BIP68-final",
+    "txn-already-known",
+    "txn-already-in-mempool",
+];
+
+// Fee rejections are expected for transactions with speedup data, the fee is paid by their CPFP.
+const FEE_MEMPOOL_REJECTIONS: [&str; 2] = ["min relay fee not met", "mempool min fee not met"];
+
+// Checks the context of a dispatch or a monitor. It is returned with every news of the transaction,
+// so it must be printable, and the contexts of the coordinator's own transactions are reserved.
+pub fn validate_context(context: &str) -> Result<(), BitcoinCoordinatorError> {
+    let invalid = |reason: &str| {
+        Err(BitcoinCoordinatorError::InvalidContext(
+            context.to_string(),
+            reason.to_string(),
+        ))
+    };
+
+    if context.trim().is_empty() {
+        return invalid("the context is empty");
+    }
+
+    if context.len() > MAX_CONTEXT_LENGTH {
+        return invalid(&format!(
+            "the context is longer than {MAX_CONTEXT_LENGTH} bytes"
+        ));
+    }
+
+    if context.chars().any(char::is_control) {
+        return invalid("the context contains control characters");
+    }
+
+    if [
+        CPFP_TRANSACTION_CONTEXT,
+        RBF_TRANSACTION_CONTEXT,
+        FUNDING_TRANSACTION_CONTEXT,
+        ANCHOR_SPEND_CONTEXT,
+    ]
+    .contains(&context)
+    {
+        return invalid("the context is reserved for the coordinator");
+    }
+
+    Ok(())
+}
+
+// Checks a transaction before it is saved to be dispatched, so invalid transactions fail on dispatch
+// instead of burning retries at broadcast time.
+// `test_mempool_accept` receives the transaction and returns the reason the node would reject it,
+// or None when it would be accepted (or the node is not asked).
+pub fn validate_tx_to_dispatch<F>(
+    tx: &Transaction,
+    speedup_data: Option<&SpeedupData>,
+    max_tx_weight: u64,
+    test_mempool_accept: F,
+) -> Result<(), BitcoinCoordinatorError>
+where
+    F: FnOnce(&Transaction) -> Result<Option<String>, BitcoinCoordinatorError>,
+{
+    let txid = tx.compute_txid();
+
+    if tx.input.is_empty() {
+        return Err(BitcoinCoordinatorError::InvalidTransaction(
+            txid,
+            "transaction has no inputs".to_string(),
+        ));
+    }
+
+    if tx.output.is_empty() {
+        return Err(BitcoinCoordinatorError::InvalidTransaction(
+            txid,
+            "transaction has no outputs".to_string(),
+        ));
+    }
+
+    let weight = tx.weight().to_wu();
+
+    if weight > max_tx_weight {
+        return Err(BitcoinCoordinatorError::TransactionTooHeavy(
+            txid.to_string(),
+            weight,
+            max_tx_weight,
+        ));
+    }
+
+    if let Some(speedup_data) = speedup_data {
+        validate_speedup_utxo(tx, txid, speedup_data)?;
+    }
+
+    if let Some(reason) = test_mempool_accept(tx)? {
+        let is_tolerated = TOLERATED_MEMPOOL_REJECTIONS
+            .iter()
+            .any(|tolerated| reason.contains(tolerated))
+            || (speedup_data.is_some()
+                && FEE_MEMPOOL_REJECTIONS
+                    .iter()
+                    .any(|tolerated| reason.contains(tolerated)));
+
+        if !is_tolerated {
+            return Err(BitcoinCoordinatorError::TransactionRejectedByMempool(
+                txid, reason,
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+// The speedup utxo is the output of the transaction spent by its CPFP.
+fn validate_speedup_utxo(
+    tx: &Transaction,
+    txid: Txid,
+    speedup_data: &SpeedupData,
+) -> Result<(), BitcoinCoordinatorError> {
+    let (vout, output) = anchor_output(tx, txid, speedup_data)?;
+
+    if speedup_data.utxo.is_some() {
+        return Ok(());
+    }
+
+    // A partial utxo is spent by the protocol builder with its output type.
+    let invalid = |reason: String| BitcoinCoordinatorError::InvalidSpeedupUtxo(txid, reason);
+    let Some((_, _, _, Some(output_type))) = &speedup_data.partial_utxo else {
+        return Err(invalid("partial utxo has no output type".to_string()));
+    };
+
+    if output_type.get_script_pubkey().as_script() != output.script_pubkey.as_script() {
+        return Err(invalid(format!(
+            "output {} script does not match the partial utxo output type",
+            vout
+        )));
+    }
+
+    Ok(())
+}
+
+// Checks that the speedup anchor of `tx` can be spent by a CPFP the node relays: its script is one the
+// CPFP builder spends and its amount is not below the dust threshold of that script.
+// A CPFP spending a dust anchor is rejected as non-standard, so the transaction would never be sped up.
+// A zero value anchor is an ephemeral anchor, where the transaction pays no fee and its CPFP pays for both.
+pub fn validate_anchor(
+    tx: &Transaction,
+    speedup_data: &SpeedupData,
+) -> Result<(), BitcoinCoordinatorError> {
+    let txid = tx.compute_txid();
+    let (vout, output) = anchor_output(tx, txid, speedup_data)?;
+    let unsupported =
+        |reason: String| BitcoinCoordinatorError::UnsupportedAnchorScript(txid, reason);
+
+    match &speedup_data.utxo {
+        // Spent with the key of the utxo (segwit v0 or taproot key path spend).
+        Some(utxo) => {
+            if SpeedupOutputKind::from_script_pubkey(&output.script_pubkey, &utxo.pub_key).is_none()
+            {
+                return Err(unsupported(format!(
+                    "output {} is not a P2WPKH or P2TR key path output of the speedup utxo public key",
+                    vout
+                )));
+            }
+        }
+        // The protocol builder only spends segwit v0 outputs.
+        None => {
+            if !output.script_pubkey.is_p2wpkh() && !output.script_pubkey.is_p2wsh() {
+                return Err(unsupported(format!(
+                    "output {} is not a segwit v0 output",
+                    vout
+                )));
+            }
+        }
+    }
+
+    let dust_threshold = output.script_pubkey.minimal_non_dust().to_sat();
+    if output.value.to_sat() > 0 && output.value.to_sat() < dust_threshold {
+        return Err(BitcoinCoordinatorError::AnchorBelowDust(
+            output.value.to_sat(),
+            dust_threshold,
+        ));
+    }
+
+    Ok(())
+}
+
+// Returns the output of `tx` that is the speedup utxo, with its index, checking the amount declared for it.
+fn anchor_output<'a>(
+    tx: &'a Transaction,
+    txid: Txid,
+    speedup_data: &SpeedupData,
+) -> Result<(u32, &'a TxOut), BitcoinCoordinatorError> {
+    let mismatch = |reason: String| BitcoinCoordinatorError::AnchorOutputMismatch(txid, reason);
+
+    let (utxo_txid, vout, amount) = match (&speedup_data.utxo, &speedup_data.partial_utxo) {
+        (Some(utxo), _) => (utxo.txid, utxo.vout, utxo.amount),
+        (None, Some((utxo_txid, vout, amount, _))) => (*utxo_txid, *vout, *amount),
+        (None, None) => {
+            return Err(BitcoinCoordinatorError::InvalidSpeedupUtxo(
+                txid,
+                "speedup data has no utxo".to_string(),
+            ))
+        }
+    };
+
+    if utxo_txid != txid {
+        return Err(mismatch(format!(
+            "utxo belongs to transaction {}",
+            utxo_txid
+        )));
+    }
+
+    let output = tx
+        .output
+        .get(vout as usize)
+        .ok_or_else(|| mismatch(format!("output {} does not exist", vout)))?;
+
+    if output.value.to_sat() != amount {
+        return Err(mismatch(format!(
+            "output {} has {} sats, speedup utxo has {} sats",
+            vout,
+            output.value.to_sat(),
+            amount
+        )));
+    }
+
+    Ok((vout, output))
+}
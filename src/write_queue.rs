@@ -0,0 +1,50 @@
+use crate::types::CoordinatedSpeedUpTransaction;
+use bitcoin::Txid;
+use bitvmx_bitcoin_rpc::types::BlockHeight;
+use std::{cell::RefCell, collections::VecDeque};
+
+// A store write that failed after its transaction was broadcast.
+// The node already has the transaction, so the write is retried instead of losing the record.
+#[derive(Debug, Clone)]
+pub enum PendingStoreWrite {
+    TxDispatched {
+        tx_id: Txid,
+        block_height: BlockHeight,
+        fee_rate: u64,
+    },
+    Speedup {
+        speedup: Box<CoordinatedSpeedUpTransaction>,
+        funding_group: Option<String>,
+        retry_txid: Option<Txid>,
+    },
+}
+
+// Writes waiting to be retried on the next ticks, in the order they failed, with the attempts already made.
+// It only lives in memory, a speedup whose record is lost with the process is recovered from its intent.
+#[derive(Default)]
+pub struct StoreWriteQueue {
+    writes: RefCell<VecDeque<(PendingStoreWrite, u32)>>,
+}
+
+impl StoreWriteQueue {
+    pub fn push(&self, write: PendingStoreWrite, attempts: u32) {
+        self.writes.borrow_mut().push_back((write, attempts));
+    }
+
+    // Puts back a write that failed again, ahead of the writes that came after it.
+    pub fn push_front(&self, write: PendingStoreWrite, attempts: u32) {
+        self.writes.borrow_mut().push_front((write, attempts));
+    }
+
+    pub fn pop(&self) -> Option<(PendingStoreWrite, u32)> {
+        self.writes.borrow_mut().pop_front()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.writes.borrow().is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.writes.borrow().len()
+    }
+}
@@ -0,0 +1,127 @@
+use bitcoin::Amount;
+use bitcoin_coordinator::{
+    coordinator::{BitcoinCoordinator, BitcoinCoordinatorApi},
+    types::{AckCoordinatorNews, AckNews, CoordinatorNews},
+    AckMonitorNews, MonitorNews,
+};
+use bitvmx_bitcoin_rpc::bitcoin_client::BitcoinClientApi;
+use protocol_builder::types::Utxo;
+use std::rc::Rc;
+
+use crate::utils::{config_trace_aux, coordinate_tx, create_test_setup, TestSetupConfig};
+mod utils;
+
+// This test acknowledges monitor and coordinator news in a single call.
+// A transaction is cancelled before being dispatched (DispatchCancelled news) and another one is confirmed
+// (monitor news). Both are acknowledged in one batch together with a news that does not exist.
+#[test]
+fn ack_news_batch_test() -> Result<(), anyhow::Error> {
+    config_trace_aux();
+
+    let mut blocks_mined = 102;
+    let setup = create_test_setup(TestSetupConfig {
+        blocks_mined,
+        bitcoind_flags: None,
+    })?;
+
+    let amount = Amount::from_sat(23450000);
+
+    let (funding_speedup, funding_speedup_vout) = setup
+        .bitcoin_client
+        .fund_address(&setup.funding_wallet, amount)?;
+
+    // Funding speed up tx mines 1 block
+    blocks_mined += 1;
+
+    let coordinator = Rc::new(BitcoinCoordinator::new_with_paths(
+        &setup.config_bitcoin_client,
+        setup.storage.clone(),
+        setup.key_manager.clone(),
+        None,
+    )?);
+
+    for _ in 0..blocks_mined {
+        coordinator.tick()?;
+    }
+
+    coordinator.add_funding(Utxo::new(
+        funding_speedup.compute_txid(),
+        funding_speedup_vout,
+        amount.to_sat(),
+        &setup.public_key,
+    ))?;
+
+    let cancelled_tx = coordinate_tx(
+        coordinator.clone(),
+        amount,
+        setup.network,
+        setup.key_manager.clone(),
+        setup.bitcoin_client.clone(),
+        None,
+    )?;
+    let cancelled_tx_id = cancelled_tx.compute_txid();
+    coordinator.cancel_dispatch(cancelled_tx_id)?;
+
+    let tx = coordinate_tx(
+        coordinator.clone(),
+        amount,
+        setup.network,
+        setup.key_manager.clone(),
+        setup.bitcoin_client.clone(),
+        None,
+    )?;
+    let tx_id = tx.compute_txid();
+
+    // Sync the block mined while funding the transactions and dispatch the transaction with its CPFP.
+    coordinator.tick()?;
+    coordinator.tick()?;
+
+    setup
+        .bitcoin_client
+        .mine_blocks_to_address(1, &setup.funding_wallet)?;
+    coordinator.tick()?;
+
+    let news = coordinator.get_news()?;
+    assert!(news.coordinator_news.iter().any(|news| matches!(
+        news,
+        CoordinatorNews::DispatchCancelled(txid, _) if *txid == cancelled_tx_id
+    )));
+    assert!(news.monitor_news.iter().any(|news| matches!(
+        news,
+        MonitorNews::Transaction(txid, _, _) if *txid == tx_id
+    )));
+
+    let mut acks = vec![
+        AckNews::Coordinator(AckCoordinatorNews::DispatchCancelled(cancelled_tx_id)),
+        // A news that was never emitted is skipped.
+        AckNews::Coordinator(AckCoordinatorNews::DispatchCancelled(tx_id)),
+    ];
+
+    for news in news.monitor_news.iter() {
+        if let MonitorNews::Transaction(txid, _, context) = news {
+            acks.push(AckNews::Monitor(AckMonitorNews::Transaction(
+                *txid,
+                context.clone(),
+            )));
+        }
+    }
+
+    let monitor_acks = acks.len() - 2;
+    let acknowledged = coordinator.ack_news_batch(acks)?;
+
+    assert_eq!(acknowledged, 1 + monitor_acks);
+
+    let news = coordinator.get_news()?;
+    assert!(!news.coordinator_news.iter().any(|news| matches!(
+        news,
+        CoordinatorNews::DispatchCancelled(txid, _) if *txid == cancelled_tx_id
+    )));
+    assert!(!news.monitor_news.iter().any(|news| matches!(
+        news,
+        MonitorNews::Transaction(txid, _, _) if *txid == tx_id
+    )));
+
+    setup.bitcoind.stop()?;
+
+    Ok(())
+}
@@ -0,0 +1,223 @@
+use bitcoin::consensus::encode::serialize_hex;
+use bitcoin_coordinator::{
+    admin::{self, AdminCommand, AdminOutput, DEFAULT_CONFIG_PATH},
+    coordinator::BitcoinCoordinatorApi,
+    errors::BitcoinCoordinatorError,
+    lock::StoreLock,
+    storage::BitcoinCoordinatorStoreApi,
+    testing::{CoordinatorTestHarness, FakeBitcoinClient},
+    types::{CoordinatorNews, TransactionState},
+};
+use key_manager::key_type::BitcoinKeyType;
+use protocol_builder::types::Utxo;
+use utils::{clear_output, generate_random_string, get_mocks, simple_tx};
+mod utils;
+
+fn args(line: &str) -> Vec<String> {
+    line.split_whitespace().map(|arg| arg.to_string()).collect()
+}
+
+#[test]
+fn test_parse_admin_args() -> Result<(), anyhow::Error> {
+    let (_, _, _, key_manager) = get_mocks();
+    let pubkey = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 0)?;
+    let txid = simple_tx(0).compute_txid();
+
+    let parsed = admin::parse_args(args("status"))?;
+    assert_eq!(parsed.config_path, DEFAULT_CONFIG_PATH);
+    assert!(!parsed.json);
+    assert_eq!(parsed.command, AdminCommand::Status);
+    assert!(!parsed.command.is_mutating());
+
+    // Options can be given before or after the command
+    let parsed = admin::parse_args(args("--json news --ack-all --config my.yaml"))?;
+    assert_eq!(parsed.config_path, "my.yaml");
+    assert!(parsed.json);
+    assert_eq!(parsed.command, AdminCommand::News { ack_all: true });
+    assert!(parsed.command.is_mutating());
+
+    // Reading the news opens the coordinator too, only status reads the store without it
+    assert!(admin::parse_args(args("news"))?.command.is_mutating());
+
+    let parsed = admin::parse_args(args(&format!(
+        "add-funding --txid {txid} --vout 1 --amount 50000 --pubkey {pubkey}"
+    )))?;
+    assert_eq!(
+        parsed.command,
+        AdminCommand::AddFunding(Utxo::new(txid, 1, 50_000, &pubkey))
+    );
+
+    let parsed = admin::parse_args(args(&format!(
+        "dispatch --tx-hex {} --context my_tx --speedup-utxo {txid}:0:540:{pubkey}",
+        serialize_hex(&simple_tx(0))
+    )))?;
+    assert_eq!(
+        parsed.command,
+        AdminCommand::Dispatch {
+            tx: simple_tx(0),
+            context: "my_tx".to_string(),
+            speedup_utxo: Some(Utxo::new(txid, 0, 540, &pubkey)),
+        }
+    );
+
+    assert_eq!(
+        admin::parse_args(args("tick"))?.command,
+        AdminCommand::Tick(1)
+    );
+    assert_eq!(
+        admin::parse_args(args("tick --count 5"))?.command,
+        AdminCommand::Tick(5)
+    );
+    assert_eq!(admin::parse_args(args(""))?.command, AdminCommand::Help);
+
+    for line in [
+        "cancel",
+        "cancel --txid nope",
+        "status --ack-all",
+        "status --unknown 1",
+        "tick --count",
+        "restart",
+        "dispatch --tx-hex 00 --context my_tx",
+    ] {
+        assert!(
+            matches!(
+                admin::parse_args(args(line)),
+                Err(BitcoinCoordinatorError::InvalidArgument(_))
+            ),
+            "{line}"
+        );
+    }
+
+    clear_output();
+    Ok(())
+}
+
+#[test]
+fn test_store_lock() -> Result<(), anyhow::Error> {
+    let storage_path = std::env::temp_dir()
+        .join(format!("admin_{}", generate_random_string()))
+        .display()
+        .to_string();
+
+    let lock = StoreLock::acquire(&storage_path)?;
+    assert!(StoreLock::is_locked(&storage_path));
+
+    // A second process can not hold the store while the lock is held
+    let result = StoreLock::acquire(&storage_path);
+    assert!(matches!(
+        result,
+        Err(BitcoinCoordinatorError::StoreLocked(path, holder))
+            if path == lock.path().display().to_string()
+                && holder == format!("pid {}", std::process::id())
+    ));
+
+    drop(lock);
+    assert!(!StoreLock::is_locked(&storage_path));
+    let _lock = StoreLock::acquire(&storage_path)?;
+
+    clear_output();
+    Ok(())
+}
+
+#[test]
+fn test_execute_admin_commands() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 0)?;
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+    let coordinator = harness.coordinator();
+    let funding = harness.fund(&funding_key, 100_000)?;
+    let tx_a = simple_tx(0);
+    let tx_b = simple_tx(1);
+
+    admin::execute(coordinator, &AdminCommand::AddFunding(funding.clone()))?;
+    for tx in [&tx_a, &tx_b] {
+        admin::execute(
+            coordinator,
+            &AdminCommand::Dispatch {
+                tx: tx.clone(),
+                context: "My tx".to_string(),
+                speedup_utxo: None,
+            },
+        )?;
+    }
+    admin::execute(coordinator, &AdminCommand::Cancel(tx_b.compute_txid()))?;
+
+    let AdminOutput::Status {
+        to_dispatch,
+        funding: summary,
+        ..
+    } = admin::execute(coordinator, &AdminCommand::Status)?
+    else {
+        panic!("Unexpected output");
+    };
+    assert_eq!(to_dispatch, 1);
+    assert_eq!(summary.funding, Some(funding));
+
+    admin::execute(coordinator, &AdminCommand::Tick(2))?;
+    assert!(harness.chain().in_mempool(&tx_a.compute_txid()));
+    assert_eq!(
+        store.get_tx(&tx_b.compute_txid())?.state,
+        TransactionState::Cancelled
+    );
+
+    // The news are printed as JSON and acknowledged
+    let output = admin::execute(coordinator, &AdminCommand::News { ack_all: true })?;
+    let AdminOutput::News { news, acknowledged } = &output else {
+        panic!("Unexpected output {output:?}");
+    };
+    assert!(news
+        .coordinator_news
+        .contains(&CoordinatorNews::DispatchCancelled(
+            tx_b.compute_txid(),
+            "My tx".to_string()
+        )));
+    assert_eq!(*acknowledged, news.coordinator_news.len());
+
+    let json = serde_json::to_value(&output)?;
+    assert_eq!(json["acknowledged"], serde_json::json!(acknowledged));
+    assert!(json["news"]["coordinator_news"].is_array());
+
+    assert!(coordinator.get_news()?.coordinator_news.is_empty());
+
+    clear_output();
+    Ok(())
+}
+
+#[test]
+fn test_status_reads_the_store() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 0)?;
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+    let funding = harness.fund(&funding_key, 100_000)?;
+    harness.coordinator().add_funding(funding.clone())?;
+    harness.dispatch(simple_tx(0), None, "My tx")?;
+
+    let owner = store.get_store_owner()?;
+    let run_state = store.get_run_state()?;
+    assert!(owner.is_some());
+
+    let client = FakeBitcoinClient::new(harness.chain().clone());
+    let AdminOutput::Status {
+        readiness,
+        to_dispatch,
+        dispatched,
+        funding: summary,
+        ..
+    } = admin::read_status(&store, harness.monitor(), &client, 1)?
+    else {
+        panic!("Unexpected output");
+    };
+    assert_eq!(to_dispatch, 1);
+    assert_eq!(dispatched, 0);
+    assert!(readiness.has_pending_txs);
+    assert_eq!(summary.funding, Some(funding));
+
+    // The store is not taken over from the running coordinator
+    assert_eq!(store.get_store_owner()?, owner);
+    assert_eq!(store.get_run_state()?, run_state);
+    harness.tick()?;
+    assert!(harness.chain().in_mempool(&simple_tx(0).compute_txid()));
+
+    clear_output();
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use bitcoin::{Transaction, Txid};
+use bitcoin_coordinator::{
+    coordinator::BitcoinCoordinatorApi,
+    storage::{BitcoinCoordinatorStore, BitcoinCoordinatorStoreApi},
+    testing::CoordinatorTestHarness,
+    types::{CoordinatorNews, TransactionState},
+};
+use key_manager::key_type::BitcoinKeyType;
+use protocol_builder::types::output::SpeedupData;
+use utils::{clear_output, get_mocks, tx_with_anchor};
+mod utils;
+
+const ANCHOR_AMOUNT: u64 = 540;
+
+// Formats an error the way the node RPC client reports a rejected sendrawtransaction.
+fn rpc_error(code: i32, message: &str) -> String {
+    format!(
+        "JSON-RPC error: RPC error response: RpcError {{ code: {code}, message: \"{message}\", data: None }}"
+    )
+}
+
+// A funded harness and a transaction with a speedup output, not dispatched yet.
+fn setup() -> Result<
+    (
+        CoordinatorTestHarness,
+        BitcoinCoordinatorStore,
+        Transaction,
+        SpeedupData,
+    ),
+    anyhow::Error,
+> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+
+    let funding = harness.fund(&funding_key, 1_000_000)?;
+    harness.coordinator().add_funding(funding)?;
+
+    let (tx, speedup_data) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 1);
+
+    Ok((harness, store, tx, speedup_data))
+}
+
+// News reporting the broadcast of the transaction as failed or as anything but a dispatch.
+fn broadcast_error_news(harness: &CoordinatorTestHarness, tx_id: Txid) -> Vec<CoordinatorNews> {
+    let news = harness.coordinator().get_news().unwrap();
+
+    news.coordinator_news
+        .into_iter()
+        .filter(|news| {
+            matches!(
+                news,
+                CoordinatorNews::DispatchTransactionError(id, ..)
+                    | CoordinatorNews::MempoolRejection(id, ..)
+                    | CoordinatorNews::NetworkError(id, ..)
+                    | CoordinatorNews::TransactionAlreadyInMempool(id, _)
+                    if *id == tx_id
+            )
+        })
+        .collect()
+}
+
+// A transaction the node already has in its mempool is dispatched at the current height
+// and paid by the CPFP of the tick, without news.
+#[test]
+fn test_already_in_mempool_is_dispatched() -> Result<(), anyhow::Error> {
+    for error in [
+        rpc_error(-26, "txn-already-in-mempool"),
+        rpc_error(-26, "txn-already-known"),
+        rpc_error(-26, "already known"),
+    ] {
+        let (harness, _, tx, speedup_data) = setup()?;
+        let tx_id = tx.compute_txid();
+
+        harness.chain().send_transaction(&tx).unwrap();
+        harness.chain().fail_next_broadcast(tx_id, &error);
+
+        harness.dispatch(tx, Some(speedup_data), "My tx")?;
+        harness.tick()?;
+
+        let history = harness.coordinator().get_transaction_history(tx_id)?;
+        assert_eq!(history.state, TransactionState::Dispatched, "{error}");
+        assert_eq!(
+            history.broadcast_block_height,
+            Some(harness.chain().height()),
+            "{error}"
+        );
+
+        let speedups = harness.coordinator().get_speedups_for_tx(tx_id)?;
+        assert_eq!(speedups.len(), 1, "{error}");
+        assert!(harness.chain().in_mempool(&speedups[0].tx_id), "{error}");
+
+        assert_eq!(broadcast_error_news(&harness, tx_id), vec![], "{error}");
+
+        clear_output();
+    }
+
+    Ok(())
+}
+
+// A transaction the node already has in a block goes straight to Confirmed, without a CPFP nor news.
+#[test]
+fn test_already_in_chain_is_confirmed() -> Result<(), anyhow::Error> {
+    for error in [
+        // The fake chain answers as a node for a mined transaction
+        None,
+        Some(rpc_error(-27, "Transaction already in block chain")),
+        Some(rpc_error(-26, "txn-already-known")),
+    ] {
+        let (harness, store, tx, speedup_data) = setup()?;
+        let tx_id = tx.compute_txid();
+
+        harness.chain().send_transaction(&tx).unwrap();
+        harness.mine_blocks(1);
+        let block_height = harness.chain().height();
+
+        if let Some(error) = &error {
+            harness.chain().fail_next_broadcast(tx_id, error);
+        }
+
+        harness.dispatch(tx, Some(speedup_data), "My tx")?;
+        harness.tick()?;
+
+        let history = harness.coordinator().get_transaction_history(tx_id)?;
+        assert_eq!(history.state, TransactionState::Confirmed, "{error:?}");
+        assert_eq!(
+            store.get_tx(&tx_id)?.first_confirmation_block_height,
+            Some(block_height),
+            "{error:?}"
+        );
+
+        assert!(harness.coordinator().get_speedups_for_tx(tx_id)?.is_empty());
+        assert!(harness.chain().mempool().is_empty(), "{error:?}");
+        assert_eq!(broadcast_error_news(&harness, tx_id), vec![], "{error:?}");
+
+        clear_output();
+    }
+
+    Ok(())
+}
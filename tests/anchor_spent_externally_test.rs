@@ -0,0 +1,181 @@
+use bitcoin::{
+    absolute::LockTime, transaction::Version, Amount, OutPoint, PublicKey, ScriptBuf, Sequence,
+    Transaction, TxIn, TxOut, Witness,
+};
+use bitcoin_coordinator::{
+    config::CoordinatorSettingsConfig,
+    coordinator::BitcoinCoordinatorApi,
+    settings::ANCHOR_SPEND_CONTEXT,
+    speedup::SpeedupStore,
+    storage::{BitcoinCoordinatorStore, BitcoinCoordinatorStoreApi},
+    testing::CoordinatorTestHarness,
+    types::{CoordinatorNews, TransactionEvent},
+    BlockInfo, MonitorNews, TransactionStatus,
+};
+use bitvmx_transaction_monitor::types::TransactionBlockchainStatus;
+use key_manager::key_type::BitcoinKeyType;
+use utils::{clear_output, get_mocks, tx_with_anchor};
+mod utils;
+
+const ANCHOR_AMOUNT: u64 = 540;
+
+// A transaction of someone else spending the anchor, e.g. a keyless anchor spent by a third party.
+fn foreign_spend(anchor: OutPoint) -> Transaction {
+    Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: anchor,
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: Amount::ZERO,
+            script_pubkey: ScriptBuf::new_op_return([]),
+        }],
+    }
+}
+
+// A funded harness where each speedup bump replaces the last CPFP.
+fn setup() -> Result<(CoordinatorTestHarness, BitcoinCoordinatorStore, PublicKey), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+
+    let harness = CoordinatorTestHarness::new(
+        store.store.clone(),
+        key_manager,
+        Some(CoordinatorSettingsConfig {
+            max_unconfirmed_speedups: Some(1),
+            ..Default::default()
+        }),
+    )?;
+
+    let funding = harness.fund(&funding_key, 1_000_000)?;
+    harness.coordinator().add_funding(funding)?;
+
+    Ok((harness, store, anchor_key))
+}
+
+fn anchor_spent_news(harness: &CoordinatorTestHarness) -> Vec<CoordinatorNews> {
+    harness
+        .coordinator()
+        .get_news()
+        .unwrap()
+        .coordinator_news
+        .into_iter()
+        .filter(|news| matches!(news, CoordinatorNews::AnchorSpentExternally(..)))
+        .collect()
+}
+
+// A transaction whose anchor is spent by a foreign transaction is reported and left out of the replacement
+// of the CPFP that paid for it, the other transactions of the batch are still paid.
+#[test]
+fn test_anchor_spent_externally_is_excluded_from_next_batch() -> Result<(), anyhow::Error> {
+    let (harness, store, anchor_key) = setup()?;
+
+    let (tx_a, speedup_a) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 1);
+    let (tx_b, speedup_b) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 2);
+    let (tx_a_id, tx_b_id) = (tx_a.compute_txid(), tx_b.compute_txid());
+    let anchor = OutPoint::new(tx_a_id, 0);
+
+    harness.dispatch(tx_a, Some(speedup_a), "tx_a")?;
+    harness.dispatch(tx_b, Some(speedup_b), "tx_b")?;
+    harness.tick()?;
+
+    // A single CPFP pays for both transactions
+    let cpfp = harness
+        .coordinator()
+        .get_speedups_for_tx(tx_a_id)?
+        .remove(0);
+    assert_eq!(
+        harness.coordinator().get_speedups_for_tx(tx_b_id)?[0].tx_id,
+        cpfp.tx_id
+    );
+
+    // The monitor reports a spend of the anchor by a transaction that is not a speedup of the coordinator
+    let spender = foreign_spend(anchor);
+    let tip = harness.chain().tip();
+    harness
+        .monitor()
+        .push_news(MonitorNews::SpendingUTXOTransaction(
+            anchor.txid,
+            anchor.vout,
+            TransactionStatus {
+                tx_id: spender.compute_txid(),
+                tx: spender.clone(),
+                block_info: Some(BlockInfo {
+                    height: tip.height,
+                    hash: tip.hash,
+                    is_orphan: false,
+                }),
+                confirmations: 1,
+                status: TransactionBlockchainStatus::Confirmed,
+            },
+            ANCHOR_SPEND_CONTEXT.to_string(),
+        ));
+    harness.tick()?;
+
+    assert_eq!(
+        anchor_spent_news(&harness),
+        vec![CoordinatorNews::AnchorSpentExternally(
+            tx_a_id,
+            anchor,
+            spender.compute_txid()
+        )]
+    );
+    assert!(store.get_tx(&tx_a_id)?.speedup_data.is_none());
+    assert!(harness
+        .coordinator()
+        .get_transaction_history(tx_a_id)?
+        .events
+        .iter()
+        .any(|entry| entry.event
+            == TransactionEvent::AnchorSpent {
+                spending_txid: spender.compute_txid()
+            }));
+
+    // The next bump replaces the CPFP paying only for the other transaction
+    harness.mine_empty_blocks(1);
+    harness.tick()?;
+
+    let (_, replacement) = store.get_last_speedup()?.unwrap();
+    let replacement = replacement.unwrap();
+    assert!(harness.chain().in_mempool(&replacement.tx_id));
+    assert_eq!(replacement.paid_txids(), vec![tx_b_id]);
+
+    let replacement_tx = harness.chain().get_transaction(&replacement.tx_id).unwrap();
+    assert!(!replacement_tx
+        .input
+        .iter()
+        .any(|input| input.previous_output == anchor));
+
+    assert_eq!(harness.coordinator().get_speedups_for_tx(tx_a_id)?.len(), 1);
+    assert_eq!(harness.coordinator().get_speedups_for_tx(tx_b_id)?.len(), 2);
+
+    clear_output();
+    Ok(())
+}
+
+// The spend of the anchor by the coordinator's own CPFP is not reported, the transaction keeps its speedup data.
+#[test]
+fn test_anchor_spent_by_own_speedup_is_ignored() -> Result<(), anyhow::Error> {
+    let (harness, store, anchor_key) = setup()?;
+
+    let (tx, speedup_data) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 1);
+    let tx_id = tx.compute_txid();
+
+    harness.dispatch(tx, Some(speedup_data), "My tx")?;
+    harness.tick()?;
+
+    // The CPFP is mined with the transaction, and the monitor reports it spending the anchor
+    harness.mine_blocks(1);
+    harness.tick()?;
+
+    assert!(anchor_spent_news(&harness).is_empty());
+    assert!(store.get_tx(&tx_id)?.speedup_data.is_some());
+
+    clear_output();
+    Ok(())
+}
@@ -0,0 +1,260 @@
+use bitcoin::Txid;
+use bitcoin_coordinator::{
+    batching::{plan_batches, BatchCandidate, BatchLimits, BatchPlan},
+    coordinator::BitcoinCoordinatorApi,
+    settings::MAX_LIMIT_UNCONFIRMED_PARENTS,
+    testing::CoordinatorTestHarness,
+    types::{AckCoordinatorNews, AckNews, CoordinatorNews, DispatchDeferredReason},
+};
+use key_manager::key_type::BitcoinKeyType;
+use std::convert::Infallible;
+use utils::{clear_output, get_mocks, tx_with_anchor};
+mod utils;
+
+// Small deterministic generator, every run checks the same cases.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn range(&mut self, min: u64, max: u64) -> u64 {
+        min + self.next() % (max - min + 1)
+    }
+}
+
+fn random_case(rng: &mut Rng) -> (Vec<BatchCandidate>, BatchLimits) {
+    let limits = BatchLimits {
+        max_weight: rng.range(1_000, 10_000),
+        unconfirmed_slots: rng.range(0, MAX_LIMIT_UNCONFIRMED_PARENTS as u64) as u32,
+        ancestor_vsize: rng.range(0, 20_000),
+    };
+
+    let candidates = (0..rng.range(0, 60))
+        .map(|_| BatchCandidate {
+            weight: rng.range(1, limits.max_weight),
+            ancestor_vsize: rng.range(100, 2_000),
+            exclusive: rng.range(0, 7) == 0,
+        })
+        .collect();
+
+    (candidates, limits)
+}
+
+fn check_invariants(candidates: &[BatchCandidate], limits: &BatchLimits, plan: &BatchPlan) {
+    // Every candidate is in a batch or deferred, once
+    let mut seen: Vec<usize> = plan
+        .batches
+        .iter()
+        .flatten()
+        .copied()
+        .chain(plan.deferred.iter().map(|(index, _)| *index))
+        .collect();
+    seen.sort();
+    assert_eq!(seen, (0..candidates.len()).collect::<Vec<_>>());
+
+    for batch in plan.batches.iter() {
+        assert!(!batch.is_empty());
+
+        let weight: u64 = batch.iter().map(|index| candidates[*index].weight).sum();
+        assert!(weight <= limits.max_weight);
+
+        if batch.iter().any(|index| candidates[*index].exclusive) {
+            assert_eq!(batch.len(), 1);
+        }
+    }
+
+    assert!(plan.consumed_slots() <= limits.unconfirmed_slots);
+
+    let ancestor_vsize: u64 = plan
+        .batches
+        .iter()
+        .flatten()
+        .map(|index| candidates[*index].ancestor_vsize)
+        .sum();
+    assert!(ancestor_vsize <= limits.ancestor_vsize);
+
+    // The deferred candidates are the last ones, the order of the dispatch is kept
+    if let Some((first_deferred, _)) = plan.deferred.first() {
+        assert!(plan
+            .batches
+            .iter()
+            .flatten()
+            .all(|index| index < first_deferred));
+    }
+}
+
+#[test]
+fn test_plan_batches_invariants() {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+
+    for _ in 0..2_000 {
+        let (candidates, limits) = random_case(&mut rng);
+        let plan = plan_batches(&candidates, &limits, |_, _| Ok::<_, Infallible>(false)).unwrap();
+        check_invariants(&candidates, &limits, &plan);
+    }
+}
+
+// Batches closed at random, as the CPFP fee cap does, keep the same invariants.
+#[test]
+fn test_plan_batches_invariants_with_closed_batches() {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+
+    for _ in 0..2_000 {
+        let (candidates, limits) = random_case(&mut rng);
+        let mut closes = Rng(rng.next() | 1);
+        let plan = plan_batches(&candidates, &limits, |_, _| {
+            Ok::<_, Infallible>(closes.range(0, 3) == 0)
+        })
+        .unwrap();
+        check_invariants(&candidates, &limits, &plan);
+    }
+}
+
+#[test]
+fn test_plan_batches_slot_accounting() {
+    let candidate = BatchCandidate {
+        weight: 1,
+        ancestor_vsize: 1,
+        exclusive: false,
+    };
+    let limits = BatchLimits {
+        max_weight: 1_000,
+        unconfirmed_slots: MAX_LIMIT_UNCONFIRMED_PARENTS,
+        ancestor_vsize: 1_000,
+    };
+
+    // A single batch takes every slot, one for each transaction and one for its CPFP
+    let candidates = vec![candidate; 30];
+    let plan = plan_batches(&candidates, &limits, |_, _| Ok::<_, Infallible>(false)).unwrap();
+    assert_eq!(plan.batches, vec![(0..24).collect::<Vec<_>>()]);
+    assert_eq!(plan.consumed_slots(), MAX_LIMIT_UNCONFIRMED_PARENTS);
+    assert_eq!(
+        plan.deferred,
+        (24..30)
+            .map(|index| (index, DispatchDeferredReason::UnconfirmedChainLimit))
+            .collect::<Vec<_>>()
+    );
+
+    // Each batch also takes the slot of its CPFP
+    let candidates = vec![
+        BatchCandidate {
+            exclusive: true,
+            ..candidate
+        };
+        13
+    ];
+    let plan = plan_batches(&candidates, &limits, |_, _| Ok::<_, Infallible>(false)).unwrap();
+    assert_eq!(plan.batches.len(), 12);
+    assert_eq!(
+        plan.deferred,
+        vec![(12, DispatchDeferredReason::UnconfirmedChainLimit)]
+    );
+
+    // The ancestor size is checked after the slots
+    let candidates = vec![
+        BatchCandidate {
+            ancestor_vsize: 400,
+            ..candidate
+        };
+        3
+    ];
+    let plan = plan_batches(&candidates, &limits, |_, _| Ok::<_, Infallible>(false)).unwrap();
+    assert_eq!(plan.batches, vec![vec![0, 1]]);
+    assert_eq!(
+        plan.deferred,
+        vec![(2, DispatchDeferredReason::AncestorSizeLimit)]
+    );
+
+    // Without a slot for the CPFP nothing is batched
+    let limits = BatchLimits {
+        unconfirmed_slots: 1,
+        ..limits
+    };
+    let plan = plan_batches(&[candidate], &limits, |_, _| Ok::<_, Infallible>(false)).unwrap();
+    assert!(plan.batches.is_empty());
+    assert_eq!(
+        plan.deferred,
+        vec![(0, DispatchDeferredReason::UnconfirmedChainLimit)]
+    );
+}
+
+fn dispatch_deferred_news(
+    harness: &CoordinatorTestHarness,
+) -> Result<Vec<(Vec<Txid>, DispatchDeferredReason)>, anyhow::Error> {
+    Ok(harness
+        .coordinator()
+        .get_news()?
+        .coordinator_news
+        .into_iter()
+        .filter_map(|news| match news {
+            CoordinatorNews::DispatchDeferred(tx_ids, reason) => Some((tx_ids, reason)),
+            _ => None,
+        })
+        .collect())
+}
+
+// The transactions that do not fit in the speedup chain are reported and dispatched once there is room again.
+#[test]
+fn test_dispatch_deferred_news() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+
+    let funding = harness.fund(&funding_key, 10_000_000)?;
+    harness.coordinator().add_funding(funding)?;
+
+    let mut txids = Vec::new();
+
+    for seed in 0..30 {
+        let (tx, speedup_data) = tx_with_anchor(&anchor_key, 0, seed);
+        txids.push(tx.compute_txid());
+        harness.dispatch(tx, Some(speedup_data), "batched")?;
+    }
+
+    harness.tick()?;
+
+    // 24 transactions and their CPFP fill the unconfirmed slots
+    let in_mempool: Vec<&Txid> = txids
+        .iter()
+        .filter(|txid| harness.chain().in_mempool(txid))
+        .collect();
+    assert_eq!(in_mempool.len(), 24);
+    assert_eq!(harness.chain().mempool().len(), 25);
+
+    let deferred = txids[24..].to_vec();
+    assert_eq!(
+        dispatch_deferred_news(&harness)?,
+        vec![(
+            deferred.clone(),
+            DispatchDeferredReason::UnconfirmedChainLimit
+        )]
+    );
+
+    // Deferred again on the next tick, the news is not repeated
+    harness.tick()?;
+    assert_eq!(dispatch_deferred_news(&harness)?.len(), 1);
+
+    harness
+        .coordinator()
+        .ack_news(AckNews::Coordinator(AckCoordinatorNews::DispatchDeferred(
+            DispatchDeferredReason::UnconfirmedChainLimit,
+        )))?;
+    assert!(dispatch_deferred_news(&harness)?.is_empty());
+
+    // The CPFP is mined, the deferred transactions are dispatched once its confirmation is processed
+    harness.mine_blocks(1);
+    harness.tick()?;
+    harness.tick()?;
+
+    assert!(deferred.iter().all(|txid| harness.chain().in_mempool(txid)));
+    assert!(dispatch_deferred_news(&harness)?.is_empty());
+
+    clear_output();
+    Ok(())
+}
@@ -0,0 +1,197 @@
+use bitcoin::{hashes::Hash, Txid};
+use bitcoin_coordinator::{
+    errors::{BitcoinBroadcastErrorKind, BroadcastFailureAction, BroadcastFailureKind},
+    types::CoordinatorNews,
+};
+
+// Formats an error the way the node RPC client reports a rejected sendrawtransaction.
+fn rpc_error(code: i32, message: &str) -> String {
+    format!(
+        "JSON-RPC error: RPC error response: RpcError {{ code: {code}, message: \"{message}\", data: None }}"
+    )
+}
+
+#[test]
+fn test_classify_error_messages() {
+    let cases = [
+        (
+            rpc_error(-26, "txn-already-in-mempool"),
+            BroadcastFailureKind::AlreadyInMempool,
+        ),
+        (
+            rpc_error(-26, "txn-already-known"),
+            BroadcastFailureKind::AlreadyInMempool,
+        ),
+        (
+            rpc_error(-26, "already known"),
+            BroadcastFailureKind::AlreadyInMempool,
+        ),
+        (
+            rpc_error(-27, "Transaction outputs already in utxo set"),
+            BroadcastFailureKind::AlreadyInChain,
+        ),
+        (
+            rpc_error(-27, "Transaction already in block chain"),
+            BroadcastFailureKind::AlreadyInChain,
+        ),
+        (
+            rpc_error(-26, "txn-mempool-conflict"),
+            BroadcastFailureKind::MempoolConflict,
+        ),
+        (
+            rpc_error(-26, "min relay fee not met, 100 < 141"),
+            BroadcastFailureKind::MinRelayFeeNotMet,
+        ),
+        (
+            rpc_error(-26, "mempool min fee not met, 110 < 2000"),
+            BroadcastFailureKind::MinRelayFeeNotMet,
+        ),
+        (
+            rpc_error(-26, "mempool full"),
+            BroadcastFailureKind::MempoolFull,
+        ),
+        (
+            rpc_error(
+                -26,
+                "too-long-mempool-chain, too many unconfirmed ancestors [limit: 25]",
+            ),
+            BroadcastFailureKind::TooLongMempoolChain,
+        ),
+        (
+            rpc_error(-26, "insufficient fee, rejecting replacement"),
+            BroadcastFailureKind::InsufficientReplacementFee,
+        ),
+        (
+            rpc_error(-26, "dust"),
+            BroadcastFailureKind::PolicyRejection,
+        ),
+        (
+            rpc_error(-28, "Loading block index…"),
+            BroadcastFailureKind::ConnectionError,
+        ),
+        (
+            "Client error: connection refused".to_string(),
+            BroadcastFailureKind::ConnectionError,
+        ),
+        (
+            rpc_error(-25, "bad-txns-inputs-missingorspent"),
+            BroadcastFailureKind::MissingInputs,
+        ),
+    ];
+
+    for (error_msg, expected) in cases {
+        assert_eq!(
+            BroadcastFailureKind::from_error_message(&error_msg),
+            expected,
+            "{error_msg}"
+        );
+    }
+}
+
+#[test]
+fn test_classify_by_code() {
+    assert_eq!(
+        BroadcastFailureKind::classify(Some(-27), "unknown"),
+        BroadcastFailureKind::AlreadyInChain
+    );
+    assert_eq!(
+        BroadcastFailureKind::classify(Some(-26), "unknown"),
+        BroadcastFailureKind::PolicyRejection
+    );
+    assert_eq!(
+        BroadcastFailureKind::classify(Some(-28), "unknown"),
+        BroadcastFailureKind::ConnectionError
+    );
+    assert_eq!(
+        BroadcastFailureKind::classify(None, "unknown"),
+        BroadcastFailureKind::Other
+    );
+}
+
+#[test]
+fn test_failure_actions_and_news() {
+    let txid = Txid::all_zeros();
+    let context = "My tx".to_string();
+    let error_msg = "error".to_string();
+
+    // A transaction the node already has is handled as a successful dispatch, without news
+    for kind in [
+        BroadcastFailureKind::AlreadyInMempool,
+        BroadcastFailureKind::AlreadyInChain,
+    ] {
+        assert_eq!(kind.action(), BroadcastFailureAction::Dispatched);
+        assert_eq!(kind.news(txid, context.clone(), error_msg.clone()), None);
+    }
+
+    // Connection errors are requeued without counting a retry attempt
+    let kind = BroadcastFailureKind::ConnectionError;
+    assert_eq!(kind.action(), BroadcastFailureAction::Requeue);
+    assert_eq!(
+        kind.news(txid, context.clone(), error_msg.clone()),
+        Some(CoordinatorNews::NetworkError(
+            txid,
+            context.clone(),
+            error_msg.clone()
+        ))
+    );
+
+    // Fee and mempool size rejections are retried
+    for kind in [
+        BroadcastFailureKind::MinRelayFeeNotMet,
+        BroadcastFailureKind::MempoolFull,
+        BroadcastFailureKind::InsufficientReplacementFee,
+    ] {
+        assert_eq!(kind.action(), BroadcastFailureAction::Retry);
+        assert_eq!(
+            kind.news(txid, context.clone(), error_msg.clone()),
+            Some(CoordinatorNews::MempoolRejection(
+                txid,
+                context.clone(),
+                error_msg.clone()
+            ))
+        );
+    }
+
+    // Policy rejections fail immediately and report the kind
+    for kind in [
+        BroadcastFailureKind::MempoolConflict,
+        BroadcastFailureKind::TooLongMempoolChain,
+        BroadcastFailureKind::PolicyRejection,
+        BroadcastFailureKind::Other,
+    ] {
+        assert_eq!(kind.action(), BroadcastFailureAction::Fail);
+        assert_eq!(
+            kind.news(txid, context.clone(), error_msg.clone()),
+            Some(CoordinatorNews::DispatchTransactionError(
+                txid,
+                context.clone(),
+                error_msg.clone(),
+                kind
+            ))
+        );
+    }
+}
+
+#[test]
+fn test_broadcast_error_kind_categories() {
+    assert_eq!(
+        BitcoinBroadcastErrorKind::from(BroadcastFailureKind::AlreadyInMempool),
+        BitcoinBroadcastErrorKind::AlreadyKnown
+    );
+    assert_eq!(
+        BitcoinBroadcastErrorKind::from(BroadcastFailureKind::AlreadyInChain),
+        BitcoinBroadcastErrorKind::AlreadyKnown
+    );
+    assert_eq!(
+        BitcoinBroadcastErrorKind::from(BroadcastFailureKind::MempoolFull),
+        BitcoinBroadcastErrorKind::MempoolRejection
+    );
+    assert_eq!(
+        BitcoinBroadcastErrorKind::from_error_message("txn-mempool-conflict"),
+        BitcoinBroadcastErrorKind::PolicyRejection
+    );
+    assert_eq!(
+        BitcoinBroadcastErrorKind::from_error_message("connection refused"),
+        BitcoinBroadcastErrorKind::NetworkError
+    );
+}
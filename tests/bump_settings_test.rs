@@ -0,0 +1,124 @@
+use bitcoin::Txid;
+use bitcoin_coordinator::{
+    config::CoordinatorSettingsConfig, coordinator::BitcoinCoordinatorApi, speedup::SpeedupStore,
+    storage::BitcoinCoordinatorStore, testing::CoordinatorTestHarness, types::SpeedupSummary,
+};
+use key_manager::key_type::BitcoinKeyType;
+use utils::{clear_output, get_mocks, tx_with_anchor};
+mod utils;
+
+const ANCHOR_AMOUNT: u64 = 540;
+const BASE_FEE_MULTIPLIER: f64 = 1.2;
+// The step is fixed by its bounds, so the recorded outcomes do not adjust it.
+const BUMP_FEE_PERCENTAGE: f64 = 1.4;
+const RBF_FEE_PERCENTAGE: f64 = 2.0;
+
+// A funded harness with non-default bump settings and a transaction dispatched.
+fn setup(
+    max_unconfirmed_speedups: u32,
+) -> Result<(CoordinatorTestHarness, BitcoinCoordinatorStore, Txid), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+
+    let harness = CoordinatorTestHarness::new(
+        store.store.clone(),
+        key_manager,
+        Some(CoordinatorSettingsConfig {
+            max_unconfirmed_speedups: Some(max_unconfirmed_speedups),
+            base_fee_multiplier: Some(BASE_FEE_MULTIPLIER),
+            bump_fee_percentage: Some(BUMP_FEE_PERCENTAGE),
+            min_bump_fee_percentage: Some(BUMP_FEE_PERCENTAGE),
+            max_bump_fee_percentage: Some(BUMP_FEE_PERCENTAGE),
+            rbf_fee_multiplier: Some(RBF_FEE_PERCENTAGE),
+            ..Default::default()
+        }),
+    )?;
+
+    let funding = harness.fund(&funding_key, 1_000_000)?;
+    harness.coordinator().add_funding(funding)?;
+
+    let (tx, speedup_data) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 1);
+    harness.dispatch(tx.clone(), Some(speedup_data), "My tx")?;
+
+    Ok((harness, store, tx.compute_txid()))
+}
+
+// The speedups paying the transaction, from the first one.
+fn speedups(harness: &CoordinatorTestHarness, tx_id: Txid) -> Vec<SpeedupSummary> {
+    harness.coordinator().get_speedups_for_tx(tx_id).unwrap()
+}
+
+// The first CPFP is priced with base_fee_multiplier and each boost grows the last bump by bump_fee_percentage.
+#[test]
+fn test_boosts_follow_bump_settings() -> Result<(), anyhow::Error> {
+    let (harness, store, tx_id) = setup(10)?;
+
+    harness.tick()?;
+
+    let cpfp = speedups(&harness, tx_id).remove(0);
+    assert_eq!(cpfp.bump_fee_percentage_used, BASE_FEE_MULTIPLIER);
+    assert_eq!(cpfp.bumped_from, None);
+
+    // Each boost is a CPFP of the last speedup, left in the mempool by the empty blocks
+    let mut bump_fee = BASE_FEE_MULTIPLIER;
+
+    for boost in 1..=2 {
+        harness.mine_empty_blocks(1);
+        harness.tick()?;
+
+        let mempool = harness.chain().mempool();
+        assert_eq!(mempool.len(), 2 + boost);
+
+        let (speedup, _) = store.get_last_speedup()?.unwrap();
+        assert!(!speedup.is_rbf);
+        assert_eq!(speedup.bumped_from, Some(bump_fee));
+        assert_eq!(
+            speedup.bump_fee_percentage_used,
+            bump_fee * BUMP_FEE_PERCENTAGE
+        );
+
+        bump_fee = speedup.bump_fee_percentage_used;
+    }
+
+    clear_output();
+    Ok(())
+}
+
+// With one unconfirmed speedup allowed, the CPFP is replaced. A replacement grows the last bump by
+// rbf_fee_percentage when it is bigger than the bump_fee_percentage step.
+#[test]
+fn test_replacements_follow_rbf_fee_percentage() -> Result<(), anyhow::Error> {
+    let (harness, _, tx_id) = setup(1)?;
+
+    harness.tick()?;
+
+    let cpfp = speedups(&harness, tx_id).remove(0);
+    assert!(!cpfp.is_rbf);
+    assert_eq!(cpfp.bump_fee_percentage_used, BASE_FEE_MULTIPLIER);
+    assert_eq!(cpfp.bumped_from, None);
+
+    let mut bump_fee = BASE_FEE_MULTIPLIER;
+    let mut fee = cpfp.fee;
+
+    for replacement in 1..=2 {
+        harness.mine_empty_blocks(1);
+        harness.tick()?;
+
+        let speedups = speedups(&harness, tx_id);
+        assert_eq!(speedups.len(), 1 + replacement);
+
+        let rbf = speedups.last().unwrap();
+        assert!(rbf.is_rbf);
+        assert!(harness.chain().in_mempool(&rbf.tx_id));
+        assert_eq!(rbf.bumped_from, Some(bump_fee));
+        assert_eq!(rbf.bump_fee_percentage_used, bump_fee * RBF_FEE_PERCENTAGE);
+        assert!(rbf.fee > fee);
+
+        bump_fee = rbf.bump_fee_percentage_used;
+        fee = rbf.fee;
+    }
+
+    clear_output();
+    Ok(())
+}
@@ -0,0 +1,129 @@
+use bitcoin::{hashes::Hash, Txid};
+use bitcoin_coordinator::{
+    bump::{miss_rate, next_bump_step},
+    config::CoordinatorSettingsConfig,
+    coordinator::BitcoinCoordinatorApi,
+    settings::{
+        BUMP_STATS_WINDOW, DEFAULT_BUMP_FEE_PERCENTAGE, DEFAULT_MAX_BUMP_FEE_PERCENTAGE,
+        DEFAULT_MIN_BUMP_FEE_PERCENTAGE,
+    },
+    speedup::SpeedupStore,
+    testing::CoordinatorTestHarness,
+    types::SpeedupOutcome,
+};
+use key_manager::key_type::BitcoinKeyType;
+use utils::{clear_output, get_mocks, tx_with_anchor};
+mod utils;
+
+const STEP: f64 = DEFAULT_BUMP_FEE_PERCENTAGE;
+const MIN: f64 = DEFAULT_MIN_BUMP_FEE_PERCENTAGE;
+const MAX: f64 = DEFAULT_MAX_BUMP_FEE_PERCENTAGE;
+
+fn outcome(seed: u8, missed: bool) -> SpeedupOutcome {
+    SpeedupOutcome {
+        tx_id: Txid::from_byte_array([seed; 32]),
+        blocks_waited: if missed { 4 } else { 1 },
+        fee_rate_ratio: 1.0,
+        missed,
+        is_rbf: false,
+    }
+}
+
+#[test]
+fn test_bump_step_follows_misses() -> Result<(), anyhow::Error> {
+    // Without outcomes the configured step is used
+    assert_eq!(miss_rate(&[]), None);
+    assert_eq!(next_bump_step(STEP, MIN, MAX, None, 1.0), STEP);
+
+    // Hits make the step smaller, misses make it bigger
+    let hits: Vec<_> = (0..10).map(|seed| outcome(seed, false)).collect();
+    let misses: Vec<_> = (0..10).map(|seed| outcome(seed, true)).collect();
+    let half: Vec<_> = (0..10).map(|seed| outcome(seed, seed % 2 == 0)).collect();
+
+    let hits_step = next_bump_step(STEP, MIN, MAX, miss_rate(&hits), 1.0);
+    let half_step = next_bump_step(STEP, MIN, MAX, miss_rate(&half), 1.0);
+    let misses_step = next_bump_step(STEP, MIN, MAX, miss_rate(&misses), 1.0);
+
+    assert_eq!(miss_rate(&hits), Some(0.0));
+    assert_eq!(miss_rate(&misses), Some(1.0));
+    assert_eq!(half_step, STEP);
+    assert!(hits_step < half_step);
+    assert!(misses_step > half_step);
+
+    // The step follows the network fee rate when it moved faster
+    assert_eq!(next_bump_step(STEP, MIN, MAX, miss_rate(&hits), 2.0), 2.0);
+
+    // The step stays within the bounds
+    assert_eq!(
+        next_bump_step(STEP, MIN, MAX, miss_rate(&misses), 10.0),
+        MAX
+    );
+    assert_eq!(next_bump_step(1.0, MIN, MAX, miss_rate(&hits), 0.5), MIN);
+    assert_eq!(next_bump_step(STEP, 1.6, MAX, miss_rate(&hits), 1.0), 1.6);
+
+    Ok(())
+}
+
+#[test]
+fn test_speedup_outcomes_adjust_bump_strategy() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+    let settings = CoordinatorSettingsConfig {
+        min_bump_fee_percentage: Some(1.2),
+        max_bump_fee_percentage: Some(2.0),
+        ..Default::default()
+    };
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, Some(settings))?;
+
+    let overview = harness.coordinator().get_pending_overview()?;
+    assert_eq!(overview.bump_strategy.samples, 0);
+    assert_eq!(overview.bump_strategy.next_step, STEP);
+    assert_eq!(overview.bump_strategy.min_step, 1.2);
+    assert_eq!(overview.bump_strategy.max_step, 2.0);
+
+    let funding = harness.fund(&funding_key, 1_000_000)?;
+    harness.coordinator().add_funding(funding)?;
+
+    // The CPFP is mined in the next block, it is a hit
+    let (tx, speedup_data) = tx_with_anchor(&anchor_key, 0, 1);
+    harness.dispatch(tx, Some(speedup_data), "My tx")?;
+    harness.tick()?;
+    let (speedup, _) = store.get_last_speedup()?.unwrap();
+
+    harness.mine_blocks(1);
+    harness.tick()?;
+    harness.mine_blocks(1);
+    harness.tick()?;
+
+    let outcomes = store.get_speedup_outcomes()?;
+    assert_eq!(outcomes.len(), 1);
+    assert_eq!(outcomes[0].tx_id, speedup.tx_id);
+    assert_eq!(outcomes[0].blocks_waited, 1);
+    assert!(!outcomes[0].missed);
+
+    let overview = harness.coordinator().get_pending_overview()?;
+    assert_eq!(overview.bump_strategy.samples, 1);
+    assert_eq!(overview.bump_strategy.miss_rate, Some(0.0));
+    assert_eq!(overview.bump_strategy.average_blocks_waited, Some(1.0));
+    assert!(overview.bump_strategy.next_step < STEP);
+    assert_eq!(overview.bump_strategy.next_step, 1.25);
+
+    // Only the last outcomes are kept, with only misses the step grows
+    for seed in 0..BUMP_STATS_WINDOW as u8 {
+        store.record_speedup_outcome(outcome(seed, true))?;
+    }
+
+    let outcomes = store.get_speedup_outcomes()?;
+    assert_eq!(outcomes.len(), BUMP_STATS_WINDOW);
+    assert!(outcomes
+        .iter()
+        .all(|outcome| outcome.tx_id != speedup.tx_id));
+
+    let overview = harness.coordinator().get_pending_overview()?;
+    assert_eq!(overview.bump_strategy.miss_rate, Some(1.0));
+    assert_eq!(overview.bump_strategy.next_step, 1.75);
+
+    clear_output();
+    Ok(())
+}
@@ -0,0 +1,126 @@
+use bitcoin::Transaction;
+use bitcoin_coordinator::{
+    coordinator::BitcoinCoordinatorApi,
+    speedup::SpeedupStore,
+    storage::{BitcoinCoordinatorStore, BitcoinCoordinatorStoreApi},
+    testing::CoordinatorTestHarness,
+    types::{CoordinatorNews, TransactionState},
+};
+use key_manager::key_type::BitcoinKeyType;
+use utils::{clear_output, get_mocks, tx_with_anchor};
+mod utils;
+
+const ANCHOR_AMOUNT: u64 = 540;
+const LOW_FUNDING_AMOUNT: u64 = 5_000;
+const FUNDING_AMOUNT: u64 = 100_000;
+const SESSION: &str = "session 1";
+const OTHER_SESSION: &str = "session 2";
+
+// Three transactions of a session: one confirmed, one broadcast waiting for its CPFP and one scheduled.
+// Cancelling the session cancels the last two, and no CPFP is created for them once there is funding.
+#[test]
+fn test_cancel_by_context() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let storage = store.store.clone();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+
+    let harness = CoordinatorTestHarness::new(storage.clone(), key_manager, None)?;
+    let coordinator = harness.coordinator();
+
+    let (confirmed_tx, _) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 1);
+    harness.dispatch(confirmed_tx.clone(), None, SESSION)?;
+    harness.tick()?;
+    harness.mine_blocks(1);
+    harness.tick()?;
+
+    // The funding can not pay for a CPFP, so the CPFP of the broadcast transactions is deferred
+    let low_funding = harness.fund(&funding_key, LOW_FUNDING_AMOUNT)?;
+    coordinator.add_funding(low_funding)?;
+
+    let (dispatched_tx, speedup_data) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 2);
+    harness.dispatch(dispatched_tx.clone(), Some(speedup_data), SESSION)?;
+    let (other_tx, other_speedup_data) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 3);
+    harness.dispatch(other_tx.clone(), Some(other_speedup_data), OTHER_SESSION)?;
+    harness.tick()?;
+
+    let (scheduled_tx, speedup_data) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 4);
+    coordinator.dispatch(
+        scheduled_tx.clone(),
+        Some(speedup_data),
+        SESSION.to_string(),
+        Some(harness.chain().height() + 100),
+        None,
+    )?;
+
+    let state = |tx: &Transaction| {
+        coordinator
+            .get_transaction_history(tx.compute_txid())
+            .unwrap()
+            .state
+    };
+    assert_eq!(state(&confirmed_tx), TransactionState::Confirmed);
+    assert_eq!(state(&dispatched_tx), TransactionState::Dispatched);
+    assert_eq!(state(&other_tx), TransactionState::Dispatched);
+    assert_eq!(state(&scheduled_tx), TransactionState::ToDispatch);
+
+    let reader = BitcoinCoordinatorStore::new(storage.clone(), 1, 3, 2)?;
+    assert_eq!(reader.get_txs_by_context(SESSION)?.len(), 3);
+    assert_eq!(reader.get_deferred_speedup_txs()?.len(), 2);
+
+    let summary = coordinator.cancel_by_context(SESSION)?;
+    assert_eq!(
+        summary.cancelled,
+        vec![dispatched_tx.compute_txid(), scheduled_tx.compute_txid()]
+    );
+    assert_eq!(summary.skipped_confirmed, vec![confirmed_tx.compute_txid()]);
+
+    assert_eq!(state(&confirmed_tx), TransactionState::Confirmed);
+    assert_eq!(state(&dispatched_tx), TransactionState::Cancelled);
+    assert_eq!(state(&scheduled_tx), TransactionState::Cancelled);
+    assert_eq!(state(&other_tx), TransactionState::Dispatched);
+    assert_eq!(
+        reader.get_deferred_speedup_txs()?,
+        vec![other_tx.compute_txid()]
+    );
+
+    let in_progress: Vec<_> = reader
+        .get_txs_in_progress()?
+        .into_iter()
+        .map(|tx| tx.tx_id)
+        .collect();
+    assert!(!in_progress.contains(&dispatched_tx.compute_txid()));
+    assert!(!in_progress.contains(&scheduled_tx.compute_txid()));
+
+    let news = coordinator.get_news()?;
+    assert!(news
+        .coordinator_news
+        .contains(&CoordinatorNews::DispatchCancelled(
+            dispatched_tx.compute_txid(),
+            SESSION.to_string()
+        )));
+
+    // With enough funding only the transaction of the other session is paid
+    let funding = harness.fund(&funding_key, FUNDING_AMOUNT)?;
+    coordinator.add_funding(funding)?;
+    harness.tick()?;
+
+    let speedups = coordinator.get_speedups_for_tx(other_tx.compute_txid())?;
+    assert_eq!(speedups.len(), 1);
+    assert!(coordinator
+        .get_speedups_for_tx(dispatched_tx.compute_txid())?
+        .is_empty());
+    assert_eq!(speedups[0].paid_txids, vec![other_tx.compute_txid()]);
+
+    // The scheduled transaction is not dispatched when its target is reached
+    harness.mine_blocks(100);
+    harness.tick()?;
+    assert!(!harness.chain().in_mempool(&scheduled_tx.compute_txid()));
+    assert!(harness
+        .chain()
+        .confirmations(&scheduled_tx.compute_txid())
+        .is_none());
+
+    clear_output();
+    Ok(())
+}
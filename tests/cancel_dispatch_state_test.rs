@@ -0,0 +1,179 @@
+use bitcoin::{OutPoint, ScriptBuf};
+use bitcoin_coordinator::{
+    config::CoordinatorSettingsConfig,
+    coordinator::{BitcoinCoordinator, BitcoinCoordinatorApi},
+    cpfp::SpeedupOutputKind,
+    errors::BitcoinCoordinatorError,
+    speedup::SpeedupStore,
+    storage::{BitcoinCoordinatorStore, BitcoinCoordinatorStoreApi},
+    testing::CoordinatorTestHarness,
+    types::{CoordinatorNews, SpeedupState, TransactionState},
+};
+use bitcoincore_rpc::{Auth, Client};
+use bitvmx_transaction_monitor::errors::MonitorError;
+use key_manager::key_type::BitcoinKeyType;
+use protocol_builder::types::{output::SpeedupData, Utxo};
+use utils::{clear_output, get_mocks, simple_tx, tx_with_output};
+mod utils;
+
+const CURRENT_HEIGHT: u32 = 100;
+const CONTEXT: &str = "My tx";
+
+// A failed or expired transaction is not dispatched anymore, cancelling it returns a dedicated error and
+// leaves its state untouched.
+#[test]
+fn test_cancel_inactive_transaction() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+
+    for (seed, state) in [
+        (1, TransactionState::Failed),
+        (2, TransactionState::Expired),
+    ] {
+        let tx = simple_tx(seed);
+        let txid = tx.compute_txid();
+        harness.dispatch(tx, None, CONTEXT)?;
+        store.update_tx_state(txid, state.clone())?;
+
+        assert!(matches!(
+            harness.coordinator().cancel_dispatch(txid),
+            Err(BitcoinCoordinatorError::CannotCancelInactive(id, ref s)) if id == txid && *s == state
+        ));
+        assert_eq!(store.get_tx(&txid)?.state, state);
+    }
+
+    assert!(store.get_news()?.is_empty());
+
+    clear_output();
+    Ok(())
+}
+
+// The monitor is cancelled before the transaction, when it fails the transaction is still to dispatch.
+#[test]
+fn test_cancel_dispatch_monitor_failure() -> Result<(), anyhow::Error> {
+    let (mut mock_monitor, store, mock_bitcoin_client, key_manager) = get_mocks();
+    let tx = simple_tx(1);
+    let txid = tx.compute_txid();
+
+    mock_monitor.expect_monitor().times(1).returning(|_| Ok(()));
+    mock_monitor
+        .expect_cancel()
+        .times(1)
+        .returning(|_| Err(MonitorError::UnexpectedError("cancel failed".to_string())));
+    mock_monitor
+        .expect_get_monitor_height()
+        .returning(|| Ok(CURRENT_HEIGHT));
+
+    let coordinator = BitcoinCoordinator::builder()
+        .with_monitor(Box::new(mock_monitor))
+        .with_store(BitcoinCoordinatorStore::new(store.store.clone(), 1, 3, 2)?)
+        .with_client(Box::new(mock_bitcoin_client))
+        .with_rpc_client(Client::new("http://127.0.0.1:18443", Auth::None)?)
+        .with_key_manager(key_manager)
+        .build()?;
+
+    coordinator.dispatch(tx, None, CONTEXT.to_string(), None, None)?;
+
+    assert!(matches!(
+        coordinator.cancel_dispatch(txid),
+        Err(BitcoinCoordinatorError::MonitorError(_))
+    ));
+    assert_eq!(store.get_tx(&txid)?.state, TransactionState::ToDispatch);
+    assert_eq!(store.get_txs_to_dispatch()?.len(), 1);
+    assert!(store.get_news()?.is_empty());
+
+    clear_output();
+    Ok(())
+}
+
+// A transaction cancelled after its broadcast is double spent, so it will never be confirmed. It is not followed
+// anymore and its CPFP, which can not be mined without it, is invalidated and frees its unconfirmed slots.
+// Cancelling it again does nothing.
+#[test]
+fn test_cancel_dropped_tx_frees_speedup_slot() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+    let harness = CoordinatorTestHarness::new(
+        store.store.clone(),
+        key_manager,
+        Some(CoordinatorSettingsConfig {
+            conflict_detection_blocks: Some(1),
+            ..Default::default()
+        }),
+    )?;
+
+    let funding = harness.fund(&funding_key, 10_000_000)?;
+    harness.coordinator().add_funding(funding)?;
+    let available_unconfirmed_txs = store.get_available_unconfirmed_txs()?;
+
+    let (input_tx, vout) = harness.chain().fund(ScriptBuf::new(), 10_000);
+    let outpoint = OutPoint::new(input_tx.compute_txid(), vout);
+
+    let mut tx = tx_with_output(
+        SpeedupOutputKind::P2trKeyPath.script_pubkey(&anchor_key)?,
+        540,
+        1,
+    );
+    tx.input[0].previous_output = outpoint;
+    let txid = tx.compute_txid();
+    let speedup_data = SpeedupData::new(Utxo::new(txid, 0, 540, &anchor_key));
+
+    harness.dispatch(tx, Some(speedup_data), CONTEXT)?;
+    harness.tick()?;
+    assert!(harness.chain().in_mempool(&txid));
+    assert!(store.get_available_unconfirmed_txs()? < available_unconfirmed_txs);
+
+    harness.coordinator().cancel_dispatch(txid)?;
+    harness.coordinator().cancel_dispatch(txid)?;
+
+    let cancelled_news = |news: &CoordinatorNews| matches!(news, CoordinatorNews::DispatchCancelled(id, _) if *id == txid);
+    let news = harness.coordinator().get_news()?;
+    assert_eq!(
+        news.coordinator_news
+            .iter()
+            .filter(|news| cancelled_news(news))
+            .count(),
+        1
+    );
+
+    // The double spend pays more, it replaces the transaction and its CPFP and it is mined.
+    let mut double_spend = tx_with_output(ScriptBuf::new_op_return([2]), 100, 2);
+    double_spend.input[0].previous_output = outpoint;
+    harness.chain().send_transaction(&double_spend).unwrap();
+    harness.mine_blocks(1);
+    harness.tick()?;
+
+    assert_eq!(store.get_tx(&txid)?.state, TransactionState::Cancelled);
+    assert!(!store
+        .get_txs_in_progress()?
+        .iter()
+        .any(|tx| tx.tx_id == txid));
+
+    let speedups = store.get_speedups_for_tx(&txid)?;
+    assert_eq!(speedups.len(), 1);
+    assert_eq!(speedups[0].state, SpeedupState::Invalidated);
+    assert_eq!(
+        store.get_available_unconfirmed_txs()?,
+        available_unconfirmed_txs
+    );
+
+    let news = harness.coordinator().get_news()?;
+    assert!(news.coordinator_news.iter().any(|news| matches!(
+        news,
+        CoordinatorNews::SpeedupChainInvalidated(txids) if *txids == vec![speedups[0].tx_id]
+    )));
+
+    harness.coordinator().cancel_dispatch(txid)?;
+    let news = harness.coordinator().get_news()?;
+    assert_eq!(
+        news.coordinator_news
+            .iter()
+            .filter(|news| cancelled_news(news))
+            .count(),
+        1
+    );
+
+    clear_output();
+    Ok(())
+}
@@ -0,0 +1,133 @@
+use bitcoin::Amount;
+use bitcoin_coordinator::{
+    coordinator::{BitcoinCoordinator, BitcoinCoordinatorApi},
+    errors::BitcoinCoordinatorError,
+    types::{AckCoordinatorNews, AckNews, CoordinatorNews},
+    MonitorNews,
+};
+use bitvmx_bitcoin_rpc::bitcoin_client::BitcoinClientApi;
+use protocol_builder::types::Utxo;
+use std::rc::Rc;
+
+use crate::utils::{config_trace_aux, coordinate_tx, create_test_setup, TestSetupConfig};
+mod utils;
+
+// This test verifies the cancellation of dispatched transactions.
+//
+// The test procedure includes:
+// - Cancelling a transaction before the first tick, so it is never broadcast.
+// - Cancelling a transaction after it was broadcast but before it is confirmed. As it is already in the mempool,
+//   it gets confirmed anyway and it is reported as a regular monitor news.
+// - Cancelling a confirmed transaction, which is rejected with CannotCancelConfirmed.
+#[test]
+fn cancel_dispatch_test() -> Result<(), anyhow::Error> {
+    config_trace_aux();
+
+    let mut blocks_mined = 102;
+    let setup = create_test_setup(TestSetupConfig {
+        blocks_mined,
+        bitcoind_flags: None,
+    })?;
+
+    let amount = Amount::from_sat(23450000);
+
+    let (funding_speedup, funding_speedup_vout) = setup
+        .bitcoin_client
+        .fund_address(&setup.funding_wallet, amount)?;
+
+    // Funding speed up tx mines 1 block
+    blocks_mined += 1;
+
+    let coordinator = Rc::new(BitcoinCoordinator::new_with_paths(
+        &setup.config_bitcoin_client,
+        setup.storage.clone(),
+        setup.key_manager.clone(),
+        None,
+    )?);
+
+    for _ in 0..blocks_mined {
+        coordinator.tick()?;
+    }
+
+    coordinator.add_funding(Utxo::new(
+        funding_speedup.compute_txid(),
+        funding_speedup_vout,
+        amount.to_sat(),
+        &setup.public_key,
+    ))?;
+
+    // Cancel a transaction before the first tick.
+    let tx1 = coordinate_tx(
+        coordinator.clone(),
+        amount,
+        setup.network,
+        setup.key_manager.clone(),
+        setup.bitcoin_client.clone(),
+        None,
+    )?;
+    let tx1_id = tx1.compute_txid();
+
+    coordinator.cancel_dispatch(tx1_id)?;
+
+    let news = coordinator.get_news()?;
+    assert_eq!(news.coordinator_news.len(), 1);
+    assert!(matches!(
+        &news.coordinator_news[0],
+        CoordinatorNews::DispatchCancelled(txid, _) if *txid == tx1_id
+    ));
+    coordinator.ack_news(AckNews::Coordinator(AckCoordinatorNews::DispatchCancelled(
+        tx1_id,
+    )))?;
+
+    // Cancel a transaction after it was broadcast but before it is confirmed.
+    let tx2 = coordinate_tx(
+        coordinator.clone(),
+        amount,
+        setup.network,
+        setup.key_manager.clone(),
+        setup.bitcoin_client.clone(),
+        None,
+    )?;
+    let tx2_id = tx2.compute_txid();
+
+    // Sync the block mined while funding tx2 and dispatch tx2 with its CPFP.
+    coordinator.tick()?;
+    coordinator.tick()?;
+
+    coordinator.cancel_dispatch(tx2_id)?;
+
+    let news = coordinator.get_news()?;
+    assert_eq!(news.coordinator_news.len(), 1);
+    assert!(matches!(
+        &news.coordinator_news[0],
+        CoordinatorNews::DispatchCancelled(txid, _) if *txid == tx2_id
+    ));
+
+    // The transaction was already in the mempool, so it gets confirmed anyway.
+    setup
+        .bitcoin_client
+        .mine_blocks_to_address(1, &setup.funding_wallet)?;
+    coordinator.tick()?;
+
+    let news = coordinator.get_news()?;
+    assert!(news.monitor_news.iter().any(|news| matches!(
+        news,
+        MonitorNews::Transaction(txid, _, _) if *txid == tx2_id
+    )));
+    // The cancelled transaction that was never broadcast is not reported.
+    assert!(!news.monitor_news.iter().any(|news| matches!(
+        news,
+        MonitorNews::Transaction(txid, _, _) if *txid == tx1_id
+    )));
+
+    // Cancel a confirmed transaction.
+    let result = coordinator.cancel_dispatch(tx2_id);
+    assert!(matches!(
+        result,
+        Err(BitcoinCoordinatorError::CannotCancelConfirmed(txid)) if txid == tx2_id
+    ));
+
+    setup.bitcoind.stop()?;
+
+    Ok(())
+}
@@ -0,0 +1,277 @@
+use bitcoin::{
+    key::Secp256k1,
+    secp256k1::Message,
+    sighash::{EcdsaSighashType, SighashCache},
+    OutPoint, PublicKey, Transaction, TxOut,
+};
+use bitcoin_coordinator::{
+    coordinator::{BitcoinCoordinator, BitcoinCoordinatorApi},
+    cpfp::SpeedupOutputKind,
+    errors::BitcoinCoordinatorError,
+    storage::BitcoinCoordinatorStore,
+};
+use bitcoincore_rpc::{Auth, Client};
+use bitvmx_transaction_monitor::errors::MonitorError;
+use key_manager::{key_manager::KeyManager, key_type::BitcoinKeyType};
+use protocol_builder::types::{output::SpeedupData, Utxo};
+use std::{
+    rc::Rc,
+    sync::{Arc, Mutex},
+};
+use utils::{clear_output, get_mocks, tx_with_output};
+mod utils;
+
+const CURRENT_HEIGHT: u32 = 100;
+const ANCHOR_AMOUNT: u64 = 540;
+const FUNDING_AMOUNT: u64 = 100_000;
+
+type SentTxs = Arc<Mutex<Vec<Transaction>>>;
+
+// A coordinator that keeps the speedups unconfirmed and chains them, with the transactions it sends.
+fn setup() -> Result<(BitcoinCoordinator, Rc<KeyManager>, SentTxs), anyhow::Error> {
+    let (mut mock_monitor, store, mut mock_bitcoin_client, key_manager) = get_mocks();
+    let store = BitcoinCoordinatorStore::new(store.store.clone(), 10, 3, 2)?;
+    let sent_txs = Arc::new(Mutex::new(Vec::new()));
+
+    mock_monitor.expect_monitor().returning(|_| Ok(()));
+    mock_monitor.expect_tick().returning(|| Ok(()));
+    mock_monitor.expect_is_ready().returning(|| Ok(true));
+    mock_monitor
+        .expect_get_monitor_height()
+        .returning(|| Ok(CURRENT_HEIGHT));
+    // Without a current block the speedup news are not stored
+    mock_monitor
+        .expect_get_current_block()
+        .returning(|| Ok(None));
+    mock_monitor.expect_get_news().returning(|| Ok(vec![]));
+    mock_monitor
+        .expect_get_tx_status()
+        .returning(|tx_id| Err(MonitorError::TransactionNotFound(tx_id.to_string())));
+    mock_monitor
+        .expect_get_estimated_fee_rate()
+        .returning(|| Ok(2));
+
+    let sent = sent_txs.clone();
+    mock_bitcoin_client
+        .expect_send_transaction()
+        .returning(move |tx| {
+            sent.lock().unwrap().push(tx.clone());
+            Ok(tx.compute_txid())
+        });
+    mock_bitcoin_client
+        .expect_get_best_block()
+        .returning(|| Ok(CURRENT_HEIGHT));
+
+    let coordinator = BitcoinCoordinator::builder()
+        .with_monitor(Box::new(mock_monitor))
+        .with_store(store)
+        .with_client(Box::new(mock_bitcoin_client))
+        .with_rpc_client(Client::new("http://127.0.0.1:18443", Auth::None)?)
+        .with_key_manager(key_manager.clone())
+        .build()?;
+
+    Ok((coordinator, key_manager, sent_txs))
+}
+
+// Dispatches a transaction with a taproot anchor and returns the CPFP sent for it in the next tick.
+fn dispatch_and_get_cpfp(
+    coordinator: &BitcoinCoordinator,
+    anchor_key: &PublicKey,
+    sent_txs: &SentTxs,
+    seed: u32,
+) -> Result<Transaction, anyhow::Error> {
+    let tx = tx_with_output(
+        SpeedupOutputKind::P2trKeyPath.script_pubkey(anchor_key)?,
+        ANCHOR_AMOUNT,
+        seed,
+    );
+    let speedup_data = SpeedupData::new(Utxo::new(tx.compute_txid(), 0, ANCHOR_AMOUNT, anchor_key));
+
+    coordinator.dispatch(
+        tx.clone(),
+        Some(speedup_data),
+        "My tx".to_string(),
+        None,
+        None,
+    )?;
+    coordinator.tick()?;
+
+    let sent_txs = sent_txs.lock().unwrap();
+    let cpfp = sent_txs.last().unwrap().clone();
+    assert_eq!(
+        cpfp.input[0].previous_output,
+        OutPoint::new(tx.compute_txid(), 0)
+    );
+
+    Ok(cpfp)
+}
+
+// Checks the funding input (the last one) is a P2WPKH spend of `prevout` signed by `public_key`.
+fn assert_funding_spent_by(
+    cpfp: &Transaction,
+    prevout: &TxOut,
+    public_key: &PublicKey,
+) -> Result<(), anyhow::Error> {
+    let index = cpfp.input.len() - 1;
+    let witness = &cpfp.input[index].witness;
+
+    assert_eq!(
+        prevout.script_pubkey,
+        SpeedupOutputKind::P2wpkh.script_pubkey(public_key)?
+    );
+    assert_eq!(PublicKey::from_slice(&witness[1])?, *public_key);
+
+    let sighash = SighashCache::new(cpfp).p2wpkh_signature_hash(
+        index,
+        &prevout.script_pubkey,
+        prevout.value,
+        EcdsaSighashType::All,
+    )?;
+    let signature = bitcoin::ecdsa::Signature::from_slice(&witness[0])?;
+
+    Secp256k1::verification_only().verify_ecdsa(
+        &Message::from(sighash),
+        &signature.signature,
+        &public_key.inner,
+    )?;
+
+    Ok(())
+}
+
+// The change key is rotated from A to B in the middle of a speedup chain. The change paid to A is still
+// spent with A, and the following speedups pay and spend their change with B.
+#[test]
+fn test_rotate_change_key_mid_chain() -> Result<(), anyhow::Error> {
+    let (coordinator, key_manager, sent_txs) = setup()?;
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let key_a = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+    let key_b = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 2)?;
+
+    let funding_tx = tx_with_output(
+        SpeedupOutputKind::P2wpkh.script_pubkey(&key_a)?,
+        FUNDING_AMOUNT,
+        1,
+    );
+    coordinator.add_funding(Utxo::new(
+        funding_tx.compute_txid(),
+        0,
+        FUNDING_AMOUNT,
+        &key_a,
+    ))?;
+
+    // Before the rotation the change is paid to the funding key
+    let cpfp_1 = dispatch_and_get_cpfp(&coordinator, &anchor_key, &sent_txs, 2)?;
+    assert_eq!(
+        cpfp_1.input.last().unwrap().previous_output,
+        OutPoint::new(funding_tx.compute_txid(), 0)
+    );
+    assert_funding_spent_by(&cpfp_1, &funding_tx.output[0], &key_a)?;
+    assert_eq!(
+        cpfp_1.output[0].script_pubkey,
+        SpeedupOutputKind::P2wpkh.script_pubkey(&key_a)?
+    );
+
+    coordinator.rotate_change_key(key_b)?;
+
+    // The change of the first CPFP is spent with A and the new change is paid to B
+    let cpfp_2 = dispatch_and_get_cpfp(&coordinator, &anchor_key, &sent_txs, 3)?;
+    assert_eq!(
+        cpfp_2.input.last().unwrap().previous_output,
+        OutPoint::new(cpfp_1.compute_txid(), 0)
+    );
+    assert_funding_spent_by(&cpfp_2, &cpfp_1.output[0], &key_a)?;
+    assert_eq!(
+        cpfp_2.output[0].script_pubkey,
+        SpeedupOutputKind::P2wpkh.script_pubkey(&key_b)?
+    );
+
+    // The chain keeps paying to B and spends the change with B
+    let cpfp_3 = dispatch_and_get_cpfp(&coordinator, &anchor_key, &sent_txs, 4)?;
+    assert_eq!(
+        cpfp_3.input.last().unwrap().previous_output,
+        OutPoint::new(cpfp_2.compute_txid(), 0)
+    );
+    assert_funding_spent_by(&cpfp_3, &cpfp_2.output[0], &key_b)?;
+    assert_eq!(
+        cpfp_3.output[0].script_pubkey,
+        SpeedupOutputKind::P2wpkh.script_pubkey(&key_b)?
+    );
+
+    let summary = coordinator.get_funding_summary()?;
+    assert_eq!(summary.funding.unwrap().pub_key, key_b);
+
+    clear_output();
+    Ok(())
+}
+
+#[test]
+fn test_add_funding_with_change_key() -> Result<(), anyhow::Error> {
+    let (coordinator, key_manager, sent_txs) = setup()?;
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+    let change_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 2)?;
+
+    // Rotating needs a funding
+    assert!(coordinator.rotate_change_key(change_key).is_err());
+
+    let funding_tx = tx_with_output(
+        SpeedupOutputKind::P2wpkh.script_pubkey(&funding_key)?,
+        FUNDING_AMOUNT,
+        1,
+    );
+    coordinator.add_funding_with_change_key(
+        Utxo::new(funding_tx.compute_txid(), 0, FUNDING_AMOUNT, &funding_key),
+        change_key,
+    )?;
+
+    // The funding is spent with its key, the change is paid to the change key
+    let cpfp = dispatch_and_get_cpfp(&coordinator, &anchor_key, &sent_txs, 2)?;
+    assert_funding_spent_by(&cpfp, &funding_tx.output[0], &funding_key)?;
+    assert_eq!(
+        cpfp.output[0].script_pubkey,
+        SpeedupOutputKind::P2wpkh.script_pubkey(&change_key)?
+    );
+
+    clear_output();
+    Ok(())
+}
+
+// An uncompressed change key can not be paid with P2WPKH, it is rejected before anything is saved.
+#[test]
+fn test_uncompressed_change_key_is_rejected() -> Result<(), anyhow::Error> {
+    let (coordinator, key_manager, sent_txs) = setup()?;
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+    let change_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 2)?;
+    let uncompressed = PublicKey::new_uncompressed(change_key.inner);
+
+    let funding_tx = tx_with_output(
+        SpeedupOutputKind::P2wpkh.script_pubkey(&funding_key)?,
+        FUNDING_AMOUNT,
+        1,
+    );
+    let funding = Utxo::new(funding_tx.compute_txid(), 0, FUNDING_AMOUNT, &funding_key);
+
+    assert!(matches!(
+        coordinator.add_funding_with_change_key(funding.clone(), uncompressed),
+        Err(BitcoinCoordinatorError::UncompressedPublicKey(key)) if key == uncompressed
+    ));
+    assert!(coordinator.get_funding_summary()?.funding.is_none());
+
+    coordinator.add_funding(funding)?;
+    assert!(matches!(
+        coordinator.rotate_change_key(uncompressed),
+        Err(BitcoinCoordinatorError::UncompressedPublicKey(_))
+    ));
+
+    // The change is still paid to the funding key
+    let cpfp = dispatch_and_get_cpfp(&coordinator, &anchor_key, &sent_txs, 2)?;
+    assert_funding_spent_by(&cpfp, &funding_tx.output[0], &funding_key)?;
+    assert_eq!(
+        cpfp.output[0].script_pubkey,
+        SpeedupOutputKind::P2wpkh.script_pubkey(&funding_key)?
+    );
+
+    clear_output();
+    Ok(())
+}
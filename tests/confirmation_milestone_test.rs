@@ -0,0 +1,156 @@
+use bitcoin::Txid;
+use bitcoin_coordinator::{
+    config::CoordinatorSettingsConfig,
+    coordinator::BitcoinCoordinatorApi,
+    errors::BitcoinCoordinatorError,
+    storage::BitcoinCoordinatorStoreApi,
+    testing::CoordinatorTestHarness,
+    types::{AckCoordinatorNews, AckNews, CoordinatorNews, DispatchOptions},
+};
+use bitvmx_transaction_monitor::config::MonitorSettingsConfig;
+use utils::{clear_output, get_mocks, simple_tx};
+mod utils;
+
+const CONTEXT: &str = "peg-in";
+
+fn harness_settings() -> CoordinatorSettingsConfig {
+    CoordinatorSettingsConfig {
+        confirmation_milestones: Some(vec![6, 1, 3]),
+        monitor_settings: Some(MonitorSettingsConfig {
+            max_monitoring_confirmations: Some(20),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+fn milestones(harness: &CoordinatorTestHarness) -> Result<Vec<(Txid, u32)>, anyhow::Error> {
+    Ok(harness
+        .coordinator()
+        .get_news()?
+        .coordinator_news
+        .into_iter()
+        .filter_map(|news| match news {
+            CoordinatorNews::ConfirmationMilestone(tx_id, confirmations, context) => {
+                assert_eq!(context, CONTEXT);
+                Some((tx_id, confirmations))
+            }
+            _ => None,
+        })
+        .collect())
+}
+
+#[test]
+fn test_confirmation_milestones_are_reported_once() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let harness =
+        CoordinatorTestHarness::new(store.store.clone(), key_manager, Some(harness_settings()))?;
+
+    let tx = simple_tx(1);
+    let tx_id = tx.compute_txid();
+    harness
+        .coordinator()
+        .dispatch(tx, None, CONTEXT.to_string(), None, None)?;
+    harness.tick()?;
+    assert!(milestones(&harness)?.is_empty());
+
+    // 1 confirmation
+    harness.mine_blocks(1);
+    harness.tick()?;
+    harness.tick()?;
+    assert_eq!(milestones(&harness)?, vec![(tx_id, 1)]);
+
+    // 3 confirmations
+    harness.mine_empty_blocks(2);
+    harness.tick()?;
+    assert_eq!(milestones(&harness)?, vec![(tx_id, 1), (tx_id, 3)]);
+
+    // 6 confirmations
+    harness.mine_empty_blocks(3);
+    harness.tick()?;
+    harness.tick()?;
+    assert_eq!(
+        milestones(&harness)?,
+        vec![(tx_id, 1), (tx_id, 3), (tx_id, 6)]
+    );
+    assert_eq!(store.get_tx(&tx_id)?.last_confirmation_milestone, Some(6));
+
+    harness.coordinator().ack_news_batch(
+        [1, 3, 6]
+            .into_iter()
+            .map(|confirmations| {
+                AckNews::Coordinator(AckCoordinatorNews::ConfirmationMilestone(
+                    tx_id,
+                    confirmations,
+                ))
+            })
+            .collect(),
+    )?;
+    assert!(milestones(&harness)?.is_empty());
+
+    // A reorg drops the transaction back to 2 confirmations, the 3 milestone is forgotten
+    for _ in 0..4 {
+        harness.invalidate_last_block();
+    }
+    harness.tick()?;
+    assert!(milestones(&harness)?.is_empty());
+    assert_eq!(store.get_tx(&tx_id)?.last_confirmation_milestone, Some(1));
+
+    // Back to 3 confirmations, only the 3 milestone is reported again
+    harness.mine_empty_blocks(1);
+    harness.tick()?;
+    harness.tick()?;
+    assert_eq!(milestones(&harness)?, vec![(tx_id, 3)]);
+
+    clear_output();
+    Ok(())
+}
+
+#[test]
+fn test_confirmation_milestones_dispatch_override() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let harness =
+        CoordinatorTestHarness::new(store.store.clone(), key_manager, Some(harness_settings()))?;
+
+    // A milestone the monitor never reaches is rejected
+    let result = harness.coordinator().dispatch_with_options(
+        simple_tx(1),
+        None,
+        CONTEXT.to_string(),
+        None,
+        None,
+        DispatchOptions {
+            confirmation_milestones: Some(vec![21]),
+            ..Default::default()
+        },
+    );
+    assert!(matches!(
+        result,
+        Err(BitcoinCoordinatorError::InvalidConfiguration(_))
+    ));
+
+    let tx = simple_tx(2);
+    let tx_id = tx.compute_txid();
+    harness.coordinator().dispatch_with_options(
+        tx,
+        None,
+        CONTEXT.to_string(),
+        None,
+        None,
+        DispatchOptions {
+            confirmation_milestones: Some(vec![4, 2]),
+            ..Default::default()
+        },
+    )?;
+    harness.tick()?;
+
+    // Both milestones are reached between two ticks, they are reported in order
+    harness.mine_blocks(1);
+    harness.mine_empty_blocks(5);
+    harness.tick()?;
+    harness.tick()?;
+    assert_eq!(milestones(&harness)?, vec![(tx_id, 2), (tx_id, 4)]);
+
+    clear_output();
+    Ok(())
+}
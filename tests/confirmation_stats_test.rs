@@ -0,0 +1,204 @@
+use bitcoin::Txid;
+use bitcoin_coordinator::{
+    confirmation_stats::{confirmation_stats, speedup_costs},
+    coordinator::BitcoinCoordinatorApi,
+    settings::MAX_FINALIZED_TX_STATS,
+    storage::{BitcoinCoordinatorStore, BitcoinCoordinatorStoreApi},
+    testing::CoordinatorTestHarness,
+    types::{ConfirmationStats, SpeedupState, SpeedupSummary, TransactionState},
+};
+use utils::{clear_output, create_store, get_mocks, simple_tx};
+mod utils;
+
+// Takes a transaction through its whole lifecycle: broadcast, first confirmation with the speedups spent on it,
+// and finalization.
+fn finalize_tx(
+    store: &BitcoinCoordinatorStore,
+    seed: u32,
+    broadcast_height: u32,
+    confirmation_height: u32,
+    bump_count: u32,
+    bump_fees: u64,
+) -> Result<Txid, anyhow::Error> {
+    let tx = simple_tx(seed);
+    let tx_id = tx.compute_txid();
+
+    store.save_tx(tx, None, None, "My tx".to_string())?;
+    store.update_tx_to_dispatched(tx_id, broadcast_height, 1)?;
+    store.save_first_confirmation(tx_id, confirmation_height, bump_count, bump_fees)?;
+    store.update_tx_state(tx_id, TransactionState::Confirmed)?;
+    store.update_tx_state(tx_id, TransactionState::Finalized)?;
+
+    Ok(tx_id)
+}
+
+#[test]
+fn test_confirmation_stats_percentiles_and_fees() -> Result<(), anyhow::Error> {
+    let store = create_store();
+    let vsize = simple_tx(0).vsize() as u64;
+
+    // Blocks to confirm: 1, 2, 3, 1 and 7. Three of them needed a speedup.
+    finalize_tx(&store, 0, 100, 101, 0, 0)?;
+    finalize_tx(&store, 1, 100, 102, 1, 500)?;
+    finalize_tx(&store, 2, 101, 104, 2, 1_000)?;
+    finalize_tx(&store, 3, 102, 103, 0, 0)?;
+    finalize_tx(&store, 4, 103, 110, 3, 2_000)?;
+
+    // A transaction finalized without its first confirmation, like one saved by an older version, is not summarized
+    let legacy = simple_tx(5);
+    store.save_tx(legacy.clone(), None, None, "My tx".to_string())?;
+    store.update_tx_to_dispatched(legacy.compute_txid(), 100, 1)?;
+    store.update_tx_state(legacy.compute_txid(), TransactionState::Confirmed)?;
+    store.update_tx_state(legacy.compute_txid(), TransactionState::Finalized)?;
+
+    let finalized = store.get_finalized_tx_stats()?;
+    assert_eq!(finalized.len(), 5);
+    assert_eq!(finalized[1].first_broadcast_block_height, 100);
+    assert_eq!(finalized[1].first_confirmation_block_height, 102);
+    assert_eq!(finalized[1].fee, vsize + 500);
+
+    // Every transaction was confirmed in the last 10 blocks
+    let stats = confirmation_stats(&finalized, 110, 10);
+    assert_eq!(stats.tx_count, 5);
+    assert_eq!(stats.median_blocks_to_confirm, 2);
+    assert_eq!(stats.p90_blocks_to_confirm, 7);
+    assert_eq!(stats.bumped_tx_count, 3);
+    assert_eq!(
+        stats.avg_fee_rate_sat_vb,
+        (5 * vsize + 3_500) as f64 / (5 * vsize) as f64
+    );
+
+    // Only the transactions confirmed after block 102 are in the last 8 blocks: 3, 1 and 7 blocks to confirm
+    let stats = confirmation_stats(&finalized, 110, 8);
+    assert_eq!(stats.tx_count, 3);
+    assert_eq!(stats.median_blocks_to_confirm, 3);
+    assert_eq!(stats.p90_blocks_to_confirm, 7);
+    assert_eq!(stats.bumped_tx_count, 2);
+    assert_eq!(
+        stats.avg_fee_rate_sat_vb,
+        (3 * vsize + 3_000) as f64 / (3 * vsize) as f64
+    );
+
+    assert_eq!(
+        confirmation_stats(&finalized, 110, 0),
+        ConfirmationStats::default()
+    );
+
+    clear_output();
+    Ok(())
+}
+
+#[test]
+fn test_first_confirmation_is_kept() -> Result<(), anyhow::Error> {
+    let store = create_store();
+    let tx = simple_tx(0);
+    let tx_id = tx.compute_txid();
+
+    store.save_tx(tx, None, None, "My tx".to_string())?;
+    store.update_tx_to_dispatched(tx_id, 100, 1)?;
+    store.save_first_confirmation(tx_id, 101, 1, 500)?;
+
+    // Confirmed again after a reorg, the first confirmation and its speedups are kept
+    store.save_first_confirmation(tx_id, 103, 2, 900)?;
+
+    let tx = store.get_tx(&tx_id)?;
+    assert_eq!(tx.first_broadcast_block_height, Some(100));
+    assert_eq!(tx.first_confirmation_block_height, Some(101));
+    assert_eq!(tx.bump_count, 1);
+    assert_eq!(tx.bump_fees, 500);
+
+    clear_output();
+    Ok(())
+}
+
+#[test]
+fn test_finalized_tx_stats_are_bounded() -> Result<(), anyhow::Error> {
+    let store = create_store();
+    let extra = 3;
+
+    let tx_ids = (0..MAX_FINALIZED_TX_STATS as u32 + extra)
+        .map(|seed| finalize_tx(&store, seed, 100, 101, 0, 0))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // The oldest summaries are dropped first
+    let finalized = store.get_finalized_tx_stats()?;
+    assert_eq!(finalized.len(), MAX_FINALIZED_TX_STATS);
+    assert_eq!(finalized[0].tx_id, tx_ids[extra as usize]);
+    assert_eq!(finalized.last().unwrap().tx_id, *tx_ids.last().unwrap());
+
+    clear_output();
+    Ok(())
+}
+
+#[test]
+fn test_speedup_costs_split_and_replaced() {
+    let paid = vec![
+        simple_tx(0).compute_txid(),
+        simple_tx(1).compute_txid(),
+        simple_tx(2).compute_txid(),
+    ];
+    let speedup =
+        |seed: u32, fee: u64, is_rbf: bool, state: SpeedupState, paid: &[Txid]| SpeedupSummary {
+            tx_id: simple_tx(100 + seed).compute_txid(),
+            state,
+            is_rbf,
+            broadcast_block_height: 100,
+            fee,
+            network_fee_rate_used: 1,
+            paid_txids: paid.to_vec(),
+            vsize: 0,
+            bump_fee_percentage_used: 1.0,
+            bumped_from: None,
+        };
+
+    // A CPFP paying three transactions is replaced, only the replacement is paid.
+    // The speedup that could not be sent is not counted, and the last CPFP pays two transactions.
+    let speedups = vec![
+        speedup(0, 900, false, SpeedupState::Finalized, &paid),
+        speedup(1, 1_500, true, SpeedupState::Finalized, &paid),
+        speedup(2, 600, false, SpeedupState::Error, &paid),
+        speedup(3, 400, false, SpeedupState::Confirmed, &paid[..2]),
+    ];
+
+    assert_eq!(speedup_costs(&speedups), (3, 500 + 200));
+    assert_eq!(speedup_costs(&[]), (0, 0));
+}
+
+// A transaction without speedup is sent, confirmed in the next block and finalized by the coordinator.
+#[test]
+fn test_confirmation_stats_from_coordinator() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+
+    let tx = simple_tx(0);
+    let tx_id = tx.compute_txid();
+    harness.dispatch(tx.clone(), None, "My tx")?;
+    harness.tick()?;
+
+    let broadcast_height = store.get_tx(&tx_id)?.first_broadcast_block_height.unwrap();
+
+    harness.chain().mine_blocks(1);
+    harness.tick()?;
+
+    let confirmed = store.get_tx(&tx_id)?;
+    assert_eq!(confirmed.state, TransactionState::Confirmed);
+    assert_eq!(
+        confirmed.first_confirmation_block_height,
+        Some(broadcast_height + 1)
+    );
+
+    while store.get_tx(&tx_id)?.state != TransactionState::Finalized {
+        harness.chain().mine_blocks(1);
+        harness.tick()?;
+    }
+
+    let stats = harness.coordinator().get_confirmation_stats(100)?;
+    assert_eq!(stats.tx_count, 1);
+    assert_eq!(stats.median_blocks_to_confirm, 1);
+    assert_eq!(stats.p90_blocks_to_confirm, 1);
+    assert_eq!(stats.bumped_tx_count, 0);
+    assert_eq!(stats.avg_fee_rate_sat_vb, 1.0);
+
+    clear_output();
+    Ok(())
+}
@@ -0,0 +1,97 @@
+use bitcoin::{
+    absolute::LockTime, transaction::Version, OutPoint, Sequence, Transaction, TxIn, Txid, Witness,
+};
+use bitcoin_coordinator::conflict::find_conflicting_tx;
+use std::{collections::HashMap, str::FromStr};
+
+fn input(txid: &str, vout: u32) -> TxIn {
+    TxIn {
+        previous_output: OutPoint::new(Txid::from_str(txid).unwrap(), vout),
+        script_sig: Default::default(),
+        sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+        witness: Witness::new(),
+    }
+}
+
+fn tx_with_inputs(inputs: Vec<TxIn>) -> Transaction {
+    Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: inputs,
+        output: vec![],
+    }
+}
+
+// Simulates the node: returns the confirmed spender of each outpoint in `spent`, the rest are unspent.
+fn get_confirmed_spender(
+    spent: &HashMap<OutPoint, Txid>,
+    outpoint: &OutPoint,
+) -> Result<Option<Txid>, bitcoin_coordinator::errors::BitcoinCoordinatorError> {
+    Ok(spent.get(outpoint).cloned())
+}
+
+#[test]
+fn test_conflict_detected_when_input_spent_by_other_tx() -> Result<(), anyhow::Error> {
+    let tx = tx_with_inputs(vec![
+        input(
+            "e9b7ad71b2f0bbce7165b5ab4a3c1e17e9189f2891650e3b7d644bb7e88f200a",
+            0,
+        ),
+        input(
+            "e9b7ad71b2f0bbce7165b5ab4a3c1e17e9189f2891650e3b7d644bb7e88f200a",
+            1,
+        ),
+    ]);
+
+    let conflicting_tx = tx_with_inputs(vec![input(
+        "e9b7ad71b2f0bbce7165b5ab4a3c1e17e9189f2891650e3b7d644bb7e88f200a",
+        1,
+    )]);
+
+    // The second input was spent by a confirmed conflicting transaction.
+    let mut spent = HashMap::new();
+    spent.insert(tx.input[1].previous_output, conflicting_tx.compute_txid());
+
+    let conflicting_txid =
+        find_conflicting_tx(&tx, |outpoint| get_confirmed_spender(&spent, outpoint))?;
+
+    assert_eq!(conflicting_txid, Some(conflicting_tx.compute_txid()));
+
+    Ok(())
+}
+
+#[test]
+fn test_no_conflict_when_inputs_unspent() -> Result<(), anyhow::Error> {
+    let tx = tx_with_inputs(vec![input(
+        "e9b7ad71b2f0bbce7165b5ab4a3c1e17e9189f2891650e3b7d644bb7e88f200a",
+        0,
+    )]);
+
+    let spent = HashMap::new();
+
+    let conflicting_txid =
+        find_conflicting_tx(&tx, |outpoint| get_confirmed_spender(&spent, outpoint))?;
+
+    assert_eq!(conflicting_txid, None);
+
+    Ok(())
+}
+
+#[test]
+fn test_no_conflict_when_input_spent_by_the_tx_itself() -> Result<(), anyhow::Error> {
+    let tx = tx_with_inputs(vec![input(
+        "e9b7ad71b2f0bbce7165b5ab4a3c1e17e9189f2891650e3b7d644bb7e88f200a",
+        0,
+    )]);
+
+    // The transaction was confirmed but the monitor did not index it yet.
+    let mut spent = HashMap::new();
+    spent.insert(tx.input[0].previous_output, tx.compute_txid());
+
+    let conflicting_txid =
+        find_conflicting_tx(&tx, |outpoint| get_confirmed_spender(&spent, outpoint))?;
+
+    assert_eq!(conflicting_txid, None);
+
+    Ok(())
+}
@@ -0,0 +1,168 @@
+use bitcoin::{
+    absolute::LockTime, transaction::Version, Amount, Network, OutPoint, ScriptBuf, Sequence,
+    Transaction, TxIn, TxOut, Witness,
+};
+use bitcoin_coordinator::{
+    config::CoordinatorSettingsConfig,
+    coordinator::{BitcoinCoordinator, BitcoinCoordinatorApi, BitcoinCoordinatorBuilder},
+    errors::BitcoinCoordinatorError,
+    types::TransactionState,
+    TypesToMonitor,
+};
+use bitcoincore_rpc::{Auth, Client};
+use bitvmx_transaction_monitor::errors::MonitorError;
+use utils::{clear_output, get_mocks};
+mod utils;
+
+const CURRENT_HEIGHT: u32 = 100;
+
+// The raw RPC client does not connect until it is used, the mock tests never use it.
+fn rpc_client() -> Client {
+    Client::new("http://127.0.0.1:18443", Auth::None).unwrap()
+}
+
+fn tx_to_dispatch() -> Transaction {
+    Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint::null(),
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: Amount::from_sat(10_000),
+            script_pubkey: ScriptBuf::new(),
+        }],
+    }
+}
+
+#[test]
+fn test_build_requires_every_part() -> Result<(), anyhow::Error> {
+    let (mock_monitor, store, mock_bitcoin_client, key_manager) = get_mocks();
+
+    // Without a monitor
+    let result = BitcoinCoordinator::builder()
+        .with_store(store)
+        .with_client(Box::new(mock_bitcoin_client))
+        .with_rpc_client(rpc_client())
+        .with_key_manager(key_manager.clone())
+        .build();
+    assert!(matches!(
+        result,
+        Err(BitcoinCoordinatorError::InvalidConfiguration(_))
+    ));
+
+    // With invalid settings
+    let (_, store, mock_bitcoin_client, _) = get_mocks();
+    let result = BitcoinCoordinatorBuilder::new()
+        .with_monitor(Box::new(mock_monitor))
+        .with_store(store)
+        .with_client(Box::new(mock_bitcoin_client))
+        .with_rpc_client(rpc_client())
+        .with_key_manager(key_manager)
+        .with_settings(CoordinatorSettingsConfig {
+            max_tx_weight: Some(0),
+            ..Default::default()
+        })
+        .build();
+    assert!(matches!(
+        result,
+        Err(BitcoinCoordinatorError::InvalidConfiguration(_))
+    ));
+
+    clear_output();
+    Ok(())
+}
+
+// A coordinator built with mocks dispatches a transaction without speedup on the first ready tick.
+#[test]
+fn test_dispatch_with_mocks() -> Result<(), anyhow::Error> {
+    let (mut mock_monitor, store, mut mock_bitcoin_client, key_manager) = get_mocks();
+    let tx = tx_to_dispatch();
+    let tx_id = tx.compute_txid();
+    let context = "My tx".to_string();
+
+    let to_monitor = TypesToMonitor::Transactions(vec![tx_id], context.clone(), None);
+    mock_monitor
+        .expect_monitor()
+        .withf(move |data| *data == to_monitor)
+        .times(1)
+        .returning(|_| Ok(()));
+
+    mock_monitor.expect_tick().returning(|| Ok(()));
+    mock_monitor.expect_is_ready().returning(|| Ok(true));
+    mock_monitor
+        .expect_get_monitor_height()
+        .returning(|| Ok(CURRENT_HEIGHT));
+    mock_monitor
+        .expect_get_tx_status()
+        .returning(|tx_id| Err(MonitorError::TransactionNotFound(tx_id.to_string())));
+    mock_monitor.expect_get_news().returning(|| Ok(vec![]));
+    mock_monitor
+        .expect_get_estimated_fee_rate()
+        .returning(|| Ok(2));
+
+    mock_bitcoin_client
+        .expect_send_transaction()
+        .times(1)
+        .returning(move |_| Ok(tx_id));
+    mock_bitcoin_client
+        .expect_get_best_block()
+        .returning(|| Ok(CURRENT_HEIGHT));
+
+    let coordinator = BitcoinCoordinator::builder()
+        .with_monitor(Box::new(mock_monitor))
+        .with_store(store)
+        .with_client(Box::new(mock_bitcoin_client))
+        .with_rpc_client(rpc_client())
+        .with_key_manager(key_manager)
+        .with_network(Network::Regtest)
+        .build()?;
+
+    coordinator.dispatch(tx, None, context, None, None)?;
+    assert!(coordinator.readiness()?.has_pending_txs);
+
+    coordinator.tick()?;
+
+    let history = coordinator.get_transaction_history(tx_id)?;
+    assert_eq!(history.state, TransactionState::Dispatched);
+    assert_eq!(history.broadcast_block_height, Some(CURRENT_HEIGHT));
+    assert!(!coordinator.readiness()?.has_pending_txs);
+
+    clear_output();
+    Ok(())
+}
+
+// While the monitor is indexing, a tick does nothing but ticking the monitor.
+#[test]
+fn test_tick_while_not_ready_with_mocks() -> Result<(), anyhow::Error> {
+    let (mut mock_monitor, store, mut mock_bitcoin_client, key_manager) = get_mocks();
+
+    mock_monitor.expect_tick().times(1).returning(|| Ok(()));
+    mock_monitor.expect_is_ready().returning(|| Ok(false));
+    mock_monitor
+        .expect_get_monitor_height()
+        .returning(|| Ok(90));
+    mock_bitcoin_client
+        .expect_get_best_block()
+        .returning(|| Ok(CURRENT_HEIGHT));
+
+    let coordinator = BitcoinCoordinator::builder()
+        .with_monitor(Box::new(mock_monitor))
+        .with_store(store)
+        .with_client(Box::new(mock_bitcoin_client))
+        .with_rpc_client(rpc_client())
+        .with_key_manager(key_manager)
+        .build()?;
+
+    coordinator.tick()?;
+
+    let readiness = coordinator.readiness()?;
+    assert!(!readiness.ready);
+    assert_eq!(readiness.blocks_remaining, 10);
+
+    clear_output();
+    Ok(())
+}
@@ -0,0 +1,155 @@
+use crate::utils::{config_trace_aux, create_test_setup, generate_random_string, generate_tx};
+use bitcoin::{Amount, OutPoint};
+use bitcoin_coordinator::{
+    coordinator::BitcoinCoordinator,
+    errors::BitcoinCoordinatorError,
+    handle::BitcoinCoordinatorHandle,
+    storage::{BitcoinCoordinatorStore, BitcoinCoordinatorStoreApi},
+    TypesToMonitor,
+};
+use bitvmx_bitcoin_rpc::bitcoin_client::BitcoinClientApi;
+use key_manager::{config::KeyManagerConfig, create_key_manager_from_config};
+use protocol_builder::types::output::SpeedupData;
+use std::{
+    future::Future,
+    pin::pin,
+    rc::Rc,
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+    thread::{self, Thread},
+};
+use storage_backend::{storage::Storage, storage_config::StorageConfig};
+mod utils;
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+// Minimal executor to drive a future from a plain thread, as an async caller would.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+// The coordinator is owned by the handle thread. Two threads dispatch a transaction each at the same time,
+// one awaiting the response and the other blocking on it. Both transactions must end up pending to be dispatched.
+#[test]
+fn coordinator_handle_test() -> Result<(), anyhow::Error> {
+    config_trace_aux();
+
+    let setup = create_test_setup(Default::default())?;
+    let amount = Amount::from_sat(23450000);
+
+    let mut txs = vec![];
+    for _ in 0..2 {
+        let (funding_tx, funding_vout) = setup
+            .bitcoin_client
+            .fund_address(&setup.funding_wallet, amount)?;
+
+        let (tx, speedup_utxo) = generate_tx(
+            OutPoint::new(funding_tx.compute_txid(), funding_vout),
+            amount.to_sat(),
+            setup.public_key,
+            setup.key_manager.clone(),
+            172,
+        )?;
+
+        txs.push((tx, SpeedupData::new(speedup_utxo)));
+    }
+
+    // The storage and the key manager are created in the coordinator thread.
+    let storage_path = format!("test_output/test/storage/{}", generate_random_string());
+    let key_manager_path = format!("test_output/test/key_manager/{}", generate_random_string());
+    let rpc_config = setup.config_bitcoin_client.clone();
+    let network = setup.network;
+    let coordinator_storage_path = storage_path.clone();
+
+    let handle = Arc::new(BitcoinCoordinatorHandle::spawn(move || {
+        let storage = Rc::new(
+            Storage::new(&StorageConfig::new(coordinator_storage_path, None))
+                .map_err(|e| BitcoinCoordinatorError::BitcoinCoordinatorError(format!("{e:?}")))?,
+        );
+
+        let key_manager = Rc::new(
+            create_key_manager_from_config(
+                &KeyManagerConfig::new(network.to_string(), None, None),
+                &StorageConfig::new(key_manager_path, None),
+            )
+            .map_err(|e| BitcoinCoordinatorError::BitcoinCoordinatorError(format!("{e:?}")))?,
+        );
+
+        BitcoinCoordinator::new_with_paths(&rpc_config, storage, key_manager, None)
+    })?);
+
+    let mut workers = vec![];
+    for (i, (tx, speedup_data)) in txs.clone().into_iter().enumerate() {
+        let handle = handle.clone();
+
+        workers.push(thread::spawn(move || {
+            let context = format!("My tx {i}");
+
+            handle
+                .monitor(TypesToMonitor::Transactions(
+                    vec![tx.compute_txid()],
+                    context.clone(),
+                    None,
+                ))
+                .wait()?;
+
+            let response = handle.dispatch(tx, Some(speedup_data), context, None, None);
+
+            if i == 0 {
+                block_on(response)
+            } else {
+                response.wait()
+            }
+        }));
+    }
+
+    for worker in workers {
+        worker.join().expect("Dispatch thread panicked")?;
+    }
+
+    // Invalid requests propagate the coordinator error to the caller.
+    let result = handle.cancel_dispatch(txs[0].0.input[0].previous_output.txid);
+    assert!(block_on(result).is_err());
+
+    // Stop the coordinator thread to release the storage.
+    Arc::try_unwrap(handle)
+        .map_err(|_| anyhow::anyhow!("The handle is still shared"))?
+        .shutdown()?;
+
+    let storage = Rc::new(Storage::new(&StorageConfig::new(storage_path, None))?);
+    let store = BitcoinCoordinatorStore::new(storage, 10, 3, 2)?;
+
+    let mut pending_txids = store
+        .get_txs_to_dispatch()?
+        .iter()
+        .map(|tx| tx.tx_id)
+        .collect::<Vec<_>>();
+    pending_txids.sort();
+
+    let mut expected_txids = txs
+        .iter()
+        .map(|(tx, _)| tx.compute_txid())
+        .collect::<Vec<_>>();
+    expected_txids.sort();
+
+    assert_eq!(pending_txids, expected_txids);
+
+    setup.bitcoind.stop()?;
+
+    Ok(())
+}
@@ -0,0 +1,218 @@
+use bitcoin::{Amount, OutPoint, PublicKey, Transaction};
+use bitcoin_coordinator::{
+    config::{CoordinatorSettingsConfig, FeeStrategy},
+    coordinator::BitcoinCoordinatorApi,
+    testing::CoordinatorTestHarness,
+    types::{CoordinatorNews, TransactionState},
+    MonitorNews,
+};
+use key_manager::key_type::BitcoinKeyType;
+use utils::{clear_output, get_mocks, tx_with_anchor};
+mod utils;
+
+const ANCHOR_AMOUNT: u64 = 540;
+const FUNDING_AMOUNT: u64 = 100_000;
+
+fn setup(
+    settings: Option<CoordinatorSettingsConfig>,
+) -> Result<(CoordinatorTestHarness, PublicKey), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, settings)?;
+    let funding = harness.fund(&funding_key, FUNDING_AMOUNT)?;
+    harness.coordinator().add_funding(funding)?;
+
+    Ok((harness, anchor_key))
+}
+
+fn is_confirmed_news(news: &MonitorNews, tx: &Transaction) -> bool {
+    matches!(news, MonitorNews::Transaction(tx_id, status, _)
+        if *tx_id == tx.compute_txid() && status.is_confirmed())
+}
+
+// The transaction and its CPFP reach the mempool in the same tick and are mined together.
+// The change of the first CPFP pays the CPFP of the next transaction.
+#[test]
+fn test_harness_speedup() -> Result<(), anyhow::Error> {
+    let (harness, anchor_key) = setup(None)?;
+    let (tx, speedup_data) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 1);
+
+    harness.dispatch(tx.clone(), Some(speedup_data), "My tx")?;
+    harness.tick()?;
+
+    let mempool = harness.chain().mempool();
+    assert_eq!(mempool.len(), 2);
+    assert_eq!(mempool[0], tx);
+    let cpfp = mempool[1].clone();
+    assert_eq!(
+        cpfp.input[0].previous_output,
+        OutPoint::new(tx.compute_txid(), 0)
+    );
+
+    harness.mine_blocks(1);
+    harness.tick()?;
+
+    let news = harness.coordinator().get_news()?;
+    assert!(news
+        .monitor_news
+        .iter()
+        .any(|news| is_confirmed_news(news, &tx)));
+    assert_eq!(
+        harness
+            .coordinator()
+            .get_transaction_history(tx.compute_txid())?
+            .state,
+        TransactionState::Confirmed
+    );
+
+    // The next CPFP spends the change of the confirmed one
+    let (tx_2, speedup_data_2) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 2);
+    harness.dispatch(tx_2.clone(), Some(speedup_data_2), "My tx")?;
+    harness.tick()?;
+
+    let cpfp_2 = harness.chain().mempool()[1].clone();
+    assert_eq!(
+        cpfp_2.input.last().unwrap().previous_output,
+        OutPoint::new(cpfp.compute_txid(), 0)
+    );
+
+    harness.mine_blocks(1);
+    harness.tick()?;
+    assert_eq!(harness.chain().confirmations(&tx_2.compute_txid()), Some(1));
+    assert!(harness.chain().mempool().is_empty());
+
+    clear_output();
+    Ok(())
+}
+
+// The block confirming the transaction is invalidated, the coordinator reports the reorg and the
+// transaction is confirmed again in the next block.
+#[test]
+fn test_harness_reorg() -> Result<(), anyhow::Error> {
+    let (harness, anchor_key) = setup(None)?;
+    let (tx, speedup_data) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 1);
+    let tx_id = tx.compute_txid();
+
+    harness.dispatch(tx, Some(speedup_data), "My tx")?;
+    harness.tick()?;
+    let confirmed_block = harness.mine_blocks(1)[0];
+    harness.tick()?;
+
+    assert_eq!(
+        harness.coordinator().get_transaction_history(tx_id)?.state,
+        TransactionState::Confirmed
+    );
+
+    assert_eq!(harness.invalidate_last_block(), Some(confirmed_block));
+    assert!(harness.chain().in_mempool(&tx_id));
+    harness.tick()?;
+
+    let news = harness.coordinator().get_news()?;
+    assert!(news.coordinator_news.iter().any(|news| matches!(
+        news,
+        CoordinatorNews::TransactionReorged(id, block_hash, _)
+            if *id == tx_id && *block_hash == confirmed_block
+    )));
+    assert_eq!(
+        harness.coordinator().get_transaction_history(tx_id)?.state,
+        TransactionState::Dispatched
+    );
+
+    harness.mine_blocks(1);
+    harness.tick()?;
+
+    assert_ne!(harness.chain().tip().hash, confirmed_block);
+    assert_eq!(harness.chain().confirmations(&tx_id), Some(1));
+    assert_eq!(
+        harness.coordinator().get_transaction_history(tx_id)?.state,
+        TransactionState::Confirmed
+    );
+
+    clear_output();
+    Ok(())
+}
+
+// The fees spike and the blocks are filled by other transactions. With a single unconfirmed speedup
+// allowed, the coordinator replaces the CPFP instead of chaining another one.
+#[test]
+fn test_harness_fee_spike() -> Result<(), anyhow::Error> {
+    let (harness, anchor_key) = setup(Some(CoordinatorSettingsConfig {
+        max_unconfirmed_speedups: Some(1),
+        ..Default::default()
+    }))?;
+    let (tx, speedup_data) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 1);
+
+    harness.dispatch(tx.clone(), Some(speedup_data), "My tx")?;
+    harness.tick()?;
+    let cpfp = harness.chain().mempool()[1].clone();
+
+    harness.set_fee_rate(CoordinatorTestHarness::INITIAL_FEE_RATE * 10);
+
+    for _ in 0..10 {
+        if !harness.chain().in_mempool(&cpfp.compute_txid()) {
+            break;
+        }
+
+        harness.mine_empty_blocks(1);
+        harness.tick()?;
+    }
+
+    // The replacement spends the same anchor and pays more
+    let mempool = harness.chain().mempool();
+    let replacement = mempool
+        .iter()
+        .find(|mempool_tx| mempool_tx.input[0].previous_output == cpfp.input[0].previous_output)
+        .unwrap();
+    assert_ne!(replacement.compute_txid(), cpfp.compute_txid());
+    assert!(
+        replacement.output.iter().map(|o| o.value).sum::<Amount>()
+            < cpfp.output.iter().map(|o| o.value).sum::<Amount>()
+    );
+
+    harness.mine_blocks(1);
+    harness.tick()?;
+    assert_eq!(
+        harness
+            .coordinator()
+            .get_transaction_history(tx.compute_txid())?
+            .state,
+        TransactionState::Confirmed
+    );
+
+    clear_output();
+    Ok(())
+}
+
+// The node checks are answered by the node of the harness, there is no RPC node behind it.
+#[test]
+fn test_harness_node_checks() -> Result<(), anyhow::Error> {
+    let (harness, anchor_key) = setup(Some(CoordinatorSettingsConfig {
+        fee_strategy: Some(FeeStrategy::SmartFee {
+            conf_target: Some(3),
+            mode: None,
+        }),
+        test_mempool_accept: Some(true),
+        check_mempool_ancestry: Some(true),
+        ..Default::default()
+    }))?;
+    let (tx, speedup_data) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 1);
+
+    harness.dispatch(tx.clone(), Some(speedup_data), "My tx")?;
+    harness.tick()?;
+
+    let mempool = harness.chain().mempool();
+    assert_eq!(mempool.len(), 2);
+    assert_eq!(mempool[0], tx);
+
+    // The estimate of the node was used, there was no fallback
+    let news = harness.coordinator().get_news()?;
+    assert!(!news
+        .coordinator_news
+        .iter()
+        .any(|news| matches!(news, CoordinatorNews::FeeEstimateUnavailable(_))));
+
+    clear_output();
+    Ok(())
+}
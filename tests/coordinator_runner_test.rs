@@ -0,0 +1,233 @@
+use bitcoin::{
+    absolute::LockTime, hashes::Hash, transaction::Version, Amount, BlockHash, OutPoint, ScriptBuf,
+    Sequence, Transaction, TxIn, TxOut, Witness,
+};
+use bitcoin_coordinator::{
+    coordinator::BitcoinCoordinator,
+    errors::BitcoinCoordinatorError,
+    runner::{AckAllNews, CoordinatorRunner, RunSummary, RunnerConfig, StepOutcome},
+    types::{AckNews, News},
+    AckMonitorNews, MonitorNews,
+};
+use bitcoincore_rpc::{Auth, Client};
+use bitvmx_transaction_monitor::{
+    errors::MonitorError,
+    types::{BlockInfo, TransactionBlockchainStatus, TransactionStatus},
+};
+use std::{
+    collections::VecDeque,
+    sync::{mpsc::channel, Arc, Mutex},
+    time::Duration,
+};
+use utils::{clear_output, get_mocks};
+mod utils;
+
+const TICK_INTERVAL: Duration = Duration::from_millis(10);
+const CONTEXT: &str = "My tx";
+
+fn news_tx() -> Transaction {
+    Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint::null(),
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: Amount::from_sat(10_000),
+            script_pubkey: ScriptBuf::new(),
+        }],
+    }
+}
+
+fn confirmed_news(tx: &Transaction) -> MonitorNews {
+    let status = TransactionStatus {
+        tx_id: tx.compute_txid(),
+        tx: tx.clone(),
+        block_info: Some(BlockInfo {
+            height: 100,
+            hash: BlockHash::all_zeros(),
+            is_orphan: false,
+        }),
+        confirmations: 1,
+        status: TransactionBlockchainStatus::Confirmed,
+    };
+
+    MonitorNews::Transaction(tx.compute_txid(), status, CONTEXT.to_string())
+}
+
+// A coordinator whose monitor ticks with the scripted results, Ok once the script is over.
+// The monitor reports a confirmed transaction until it is acknowledged.
+fn coordinator(ticks: Vec<bool>) -> Result<BitcoinCoordinator, anyhow::Error> {
+    let (mut mock_monitor, store, mock_bitcoin_client, key_manager) = get_mocks();
+    let ticks = Mutex::new(VecDeque::from(ticks));
+    let acked = Arc::new(Mutex::new(false));
+    let tx = news_tx();
+
+    mock_monitor
+        .expect_tick()
+        .returning(move || match ticks.lock().unwrap().pop_front() {
+            Some(false) => Err(MonitorError::UnexpectedError("tick failed".to_string())),
+            _ => Ok(()),
+        });
+    mock_monitor.expect_is_ready().returning(|| Ok(false));
+    mock_monitor
+        .expect_get_current_block()
+        .returning(|| Ok(None));
+
+    let reported = acked.clone();
+    mock_monitor.expect_get_news().returning(move || {
+        if *reported.lock().unwrap() {
+            Ok(vec![])
+        } else {
+            Ok(vec![confirmed_news(&tx)])
+        }
+    });
+    let txid = news_tx().compute_txid();
+    mock_monitor.expect_ack_news().returning(move |news| {
+        assert_eq!(news, AckMonitorNews::Transaction(txid, CONTEXT.to_string()));
+        *acked.lock().unwrap() = true;
+        Ok(())
+    });
+
+    Ok(BitcoinCoordinator::builder()
+        .with_monitor(Box::new(mock_monitor))
+        .with_store(store)
+        .with_client(Box::new(mock_bitcoin_client))
+        .with_rpc_client(Client::new("http://127.0.0.1:18443", Auth::None)?)
+        .with_key_manager(key_manager)
+        .build()?)
+}
+
+fn config() -> RunnerConfig {
+    RunnerConfig {
+        tick_interval: TICK_INTERVAL,
+        max_backoff: TICK_INTERVAL * 3,
+        ..Default::default()
+    }
+}
+
+// Failed ticks are retried with a backoff that doubles up to max_backoff and resets after a success.
+// The handler only sees the news of the ticks that succeeded, and only the news it returns are acknowledged.
+#[test]
+fn test_step_backoff_and_news_handling() -> Result<(), anyhow::Error> {
+    let handled = Arc::new(Mutex::new(0));
+    let calls = handled.clone();
+    let handler = move |news: &News| {
+        let mut calls = calls.lock().unwrap();
+        *calls += 1;
+        assert_eq!(news.monitor_news, vec![confirmed_news(&news_tx())]);
+
+        // The news is only acknowledged the second time it is handled
+        if *calls == 1 {
+            vec![]
+        } else {
+            vec![AckNews::Monitor(AckMonitorNews::Transaction(
+                news_tx().compute_txid(),
+                CONTEXT.to_string(),
+            ))]
+        }
+    };
+    let mut runner = CoordinatorRunner::new(
+        coordinator(vec![true, false, false, false, true])?,
+        handler,
+        config(),
+    );
+
+    assert!(matches!(
+        runner.step(),
+        StepOutcome::Completed {
+            news: 1,
+            acknowledged: 0
+        }
+    ));
+    assert_eq!(runner.next_wait(), TICK_INTERVAL);
+
+    let expected_backoff = [TICK_INTERVAL * 2, TICK_INTERVAL * 3, TICK_INTERVAL * 3];
+    for expected in expected_backoff {
+        match runner.step() {
+            StepOutcome::Failed { error, retry_in } => {
+                assert!(matches!(error, BitcoinCoordinatorError::MonitorError(_)));
+                assert_eq!(retry_in, expected);
+                assert_eq!(runner.next_wait(), expected);
+            }
+            outcome => panic!("expected a failed step, got {outcome:?}"),
+        }
+    }
+    assert_eq!(runner.consecutive_failures(), 3);
+    assert_eq!(*handled.lock().unwrap(), 1);
+
+    assert!(matches!(
+        runner.step(),
+        StepOutcome::Completed {
+            news: 1,
+            acknowledged: 1
+        }
+    ));
+    assert_eq!(runner.consecutive_failures(), 0);
+    assert_eq!(runner.next_wait(), TICK_INTERVAL);
+
+    // Nothing left to handle
+    assert!(matches!(
+        runner.step(),
+        StepOutcome::Completed {
+            news: 0,
+            acknowledged: 0
+        }
+    ));
+    assert_eq!(*handled.lock().unwrap(), 2);
+
+    clear_output();
+    Ok(())
+}
+
+#[test]
+fn test_run_until_max_ticks_or_stop_signal() -> Result<(), anyhow::Error> {
+    let mut runner = CoordinatorRunner::new(
+        coordinator(vec![false, true])?,
+        AckAllNews,
+        RunnerConfig {
+            max_ticks: Some(3),
+            ..config()
+        },
+    );
+    assert_eq!(
+        runner.run(),
+        RunSummary {
+            ticks: 3,
+            failed_ticks: 1,
+            acknowledged_news: 1,
+        }
+    );
+
+    // A stop signal sent before run stops it after the first step
+    let (stop, stop_signal) = channel();
+    stop.send(())?;
+    let mut runner = CoordinatorRunner::new(
+        coordinator(vec![])?,
+        AckAllNews,
+        RunnerConfig {
+            stop_signal: Some(stop_signal),
+            ..config()
+        },
+    );
+    assert_eq!(runner.run().ticks, 1);
+
+    // Dropping the sender stops it too
+    let (stop, stop_signal) = channel::<()>();
+    drop(stop);
+    let mut runner = CoordinatorRunner::new(
+        coordinator(vec![])?,
+        AckAllNews,
+        RunnerConfig {
+            stop_signal: Some(stop_signal),
+            ..config()
+        },
+    );
+    assert_eq!(runner.run().ticks, 1);
+
+    clear_output();
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use bitcoin::Amount;
+use bitcoin::Txid;
+use bitcoin_coordinator::{
+    coordinator::{BitcoinCoordinator, BitcoinCoordinatorApi},
+    speedup::SpeedupStore,
+    storage::BitcoinCoordinatorStore,
+    types::CoordinatorNews,
+};
+use bitvmx_bitcoin_rpc::bitcoin_client::BitcoinClientApi;
+use protocol_builder::types::Utxo;
+use std::rc::Rc;
+
+use crate::utils::{config_trace_aux, coordinate_tx, create_test_setup, TestSetupConfig};
+mod utils;
+
+// Two transactions are broadcast while the only funding is a dust UTXO, so their CPFP can not be paid.
+// Both are deferred, and once a proper funding is added the next tick sends a CPFP covering both.
+#[test]
+fn deferred_speedup_test() -> Result<(), anyhow::Error> {
+    config_trace_aux();
+
+    let mut blocks_mined = 102;
+    let setup = create_test_setup(TestSetupConfig {
+        blocks_mined,
+        bitcoind_flags: None,
+    })?;
+
+    let amount = Amount::from_sat(23450000);
+    let dust_amount = Amount::from_sat(5000);
+
+    let (dust_funding, dust_funding_vout) = setup
+        .bitcoin_client
+        .fund_address(&setup.funding_wallet, dust_amount)?;
+    let (funding_speedup, funding_speedup_vout) = setup
+        .bitcoin_client
+        .fund_address(&setup.funding_wallet, amount)?;
+
+    // Each funding mines 1 block
+    blocks_mined += 2;
+
+    let coordinator = Rc::new(BitcoinCoordinator::new_with_paths(
+        &setup.config_bitcoin_client,
+        setup.storage.clone(),
+        setup.key_manager.clone(),
+        None,
+    )?);
+
+    for _ in 0..blocks_mined {
+        coordinator.tick()?;
+    }
+
+    coordinator.add_funding(Utxo::new(
+        dust_funding.compute_txid(),
+        dust_funding_vout,
+        dust_amount.to_sat(),
+        &setup.public_key,
+    ))?;
+
+    let mut txids: Vec<Txid> = Vec::new();
+
+    for _ in 0..2 {
+        let tx = coordinate_tx(
+            coordinator.clone(),
+            amount,
+            setup.network,
+            setup.key_manager.clone(),
+            setup.bitcoin_client.clone(),
+            None,
+        )?;
+        txids.push(tx.compute_txid());
+    }
+
+    // Both transactions are broadcast, their CPFP can not be paid with the dust funding
+    coordinator.tick()?;
+
+    let news = coordinator.get_news()?;
+    assert!(news.coordinator_news.iter().any(|news| matches!(
+        news,
+        CoordinatorNews::InsufficientFunds(txid, ..) if *txid == dust_funding.compute_txid()
+    )));
+
+    let store = BitcoinCoordinatorStore::new(setup.storage.clone(), 10, 3, 2)?;
+    let mut deferred = store.get_deferred_speedup_txs()?;
+    deferred.sort();
+    let mut expected = txids.clone();
+    expected.sort();
+    assert_eq!(deferred, expected);
+    assert!(store.get_last_speedup()?.is_none());
+
+    // Nothing changes until a proper funding is added
+    coordinator.tick()?;
+    assert_eq!(store.get_deferred_speedup_txs()?.len(), 2);
+
+    coordinator.add_funding(Utxo::new(
+        funding_speedup.compute_txid(),
+        funding_speedup_vout,
+        amount.to_sat(),
+        &setup.public_key,
+    ))?;
+
+    coordinator.tick()?;
+
+    let (cpfp, _) = store.get_last_speedup()?.unwrap();
+    let mut paid_txids: Vec<Txid> = cpfp
+        .speedup_tx_data
+        .iter()
+        .map(|parent| parent.tx_id)
+        .collect();
+    paid_txids.sort();
+
+    assert_eq!(paid_txids, expected);
+    assert_eq!(cpfp.prev_funding.txid, funding_speedup.compute_txid());
+    assert!(store.get_deferred_speedup_txs()?.is_empty());
+
+    setup.bitcoind.stop()?;
+
+    Ok(())
+}
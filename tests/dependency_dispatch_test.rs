@@ -0,0 +1,175 @@
+use bitcoin::{Transaction, Txid};
+use bitcoin_coordinator::{
+    coordinator::BitcoinCoordinatorApi,
+    errors::BitcoinCoordinatorError,
+    storage::{BitcoinCoordinatorStore, BitcoinCoordinatorStoreApi},
+    testing::CoordinatorTestHarness,
+    types::{
+        AckCoordinatorNews, CoordinatorNews, DispatchOptions, PendingReason, TransactionState,
+    },
+};
+use utils::{clear_output, get_mocks, simple_tx};
+mod utils;
+
+fn setup() -> Result<(CoordinatorTestHarness, BitcoinCoordinatorStore), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+
+    Ok((harness, store))
+}
+
+fn dispatch_depending_on(
+    harness: &CoordinatorTestHarness,
+    tx: Transaction,
+    depends_on: Vec<Txid>,
+) -> Result<(), BitcoinCoordinatorError> {
+    harness.coordinator().dispatch_with_options(
+        tx,
+        None,
+        "My tx".to_string(),
+        None,
+        None,
+        DispatchOptions {
+            depends_on,
+            ..Default::default()
+        },
+    )
+}
+
+// A and B are dispatched together, B depends on A. A is confirmed on the second tick, after the transactions
+// to dispatch were processed, so B is only broadcast on the third tick.
+#[test]
+fn test_dependent_tx_waits_for_dependency() -> Result<(), anyhow::Error> {
+    let (harness, store) = setup()?;
+    let tx_a = simple_tx(0);
+    let tx_b = simple_tx(1);
+    let txid_a = tx_a.compute_txid();
+    let txid_b = tx_b.compute_txid();
+
+    harness.dispatch(tx_a, None, "My tx")?;
+    dispatch_depending_on(&harness, tx_b, vec![txid_a])?;
+
+    // Tick 1: A is broadcast, B waits for it
+    harness.tick()?;
+    assert!(harness.chain().in_mempool(&txid_a));
+    assert!(!harness.chain().in_mempool(&txid_b));
+
+    let overview = harness.coordinator().get_pending_overview()?;
+    let entry_b = overview
+        .to_dispatch
+        .iter()
+        .find(|entry| entry.tx_id == txid_b)
+        .unwrap();
+    assert_eq!(
+        entry_b.pending_reason,
+        Some(PendingReason::DependencyNotConfirmed)
+    );
+
+    // Tick 2: A is reported confirmed by the monitor, B is not broadcast yet
+    harness.mine_blocks(1);
+    harness.tick()?;
+    assert_eq!(store.get_tx(&txid_a)?.state, TransactionState::Confirmed);
+    assert_eq!(store.get_tx(&txid_b)?.state, TransactionState::ToDispatch);
+    assert!(!harness.chain().in_mempool(&txid_b));
+
+    // Tick 3: B is broadcast
+    harness.tick()?;
+    assert!(harness.chain().in_mempool(&txid_b));
+    assert_eq!(store.get_tx(&txid_b)?.state, TransactionState::Dispatched);
+
+    clear_output();
+    Ok(())
+}
+
+#[test]
+fn test_dispatch_rejects_unknown_and_cyclic_dependencies() -> Result<(), anyhow::Error> {
+    let (harness, store) = setup()?;
+    let tx_a = simple_tx(0);
+    let tx_b = simple_tx(1);
+    let txid_a = tx_a.compute_txid();
+    let txid_b = tx_b.compute_txid();
+    let unknown = simple_tx(2).compute_txid();
+
+    let result = dispatch_depending_on(&harness, tx_a.clone(), vec![unknown]);
+    assert!(matches!(
+        result,
+        Err(BitcoinCoordinatorError::UnknownDependency(tx_id, dependency))
+            if tx_id == txid_a && dependency == unknown
+    ));
+
+    // A transaction can not depend on itself
+    harness.dispatch(tx_a.clone(), None, "My tx")?;
+    harness.coordinator().cancel_dispatch(txid_a)?;
+    let result = dispatch_depending_on(&harness, tx_a.clone(), vec![txid_a]);
+    assert!(matches!(
+        result,
+        Err(BitcoinCoordinatorError::DependencyCycle(tx_id)) if tx_id == txid_a
+    ));
+
+    // B depends on A, so A dispatched again can not depend on B
+    dispatch_depending_on(&harness, tx_b, vec![txid_a])?;
+    let result = dispatch_depending_on(&harness, tx_a, vec![txid_b]);
+    assert!(matches!(
+        result,
+        Err(BitcoinCoordinatorError::DependencyCycle(tx_id)) if tx_id == txid_a
+    ));
+    assert_eq!(store.get_tx(&txid_a)?.state, TransactionState::Cancelled);
+
+    clear_output();
+    Ok(())
+}
+
+// A dependency that fails, or is cancelled before it is broadcast, will never be confirmed.
+// The dependent transactions are marked as failed and reported with a DependencyFailed news.
+#[test]
+fn test_dependency_failed() -> Result<(), anyhow::Error> {
+    let (harness, store) = setup()?;
+    let tx_a = simple_tx(0);
+    let tx_b = simple_tx(1);
+    let tx_c = simple_tx(2);
+    let tx_d = simple_tx(3);
+    let txid_a = tx_a.compute_txid();
+    let txid_b = tx_b.compute_txid();
+    let txid_c = tx_c.compute_txid();
+    let txid_d = tx_d.compute_txid();
+
+    harness.dispatch(tx_a, None, "My tx")?;
+    dispatch_depending_on(&harness, tx_b, vec![txid_a])?;
+    harness.tick()?;
+
+    store.update_tx_state(txid_a, TransactionState::Failed)?;
+    harness.tick()?;
+
+    assert_eq!(store.get_tx(&txid_b)?.state, TransactionState::Failed);
+    assert!(!harness.chain().in_mempool(&txid_b));
+
+    // C is scheduled for a later block and cancelled before it is broadcast
+    harness.coordinator().dispatch(
+        tx_c,
+        None,
+        "My tx".to_string(),
+        Some(harness.chain().height() + 10),
+        None,
+    )?;
+    dispatch_depending_on(&harness, tx_d, vec![txid_c])?;
+    harness.coordinator().cancel_dispatch(txid_c)?;
+    harness.tick()?;
+
+    assert_eq!(store.get_tx(&txid_d)?.state, TransactionState::Failed);
+
+    let news = harness.coordinator().get_news()?.coordinator_news;
+    assert!(news.contains(&CoordinatorNews::DependencyFailed(txid_b, txid_a)));
+    assert!(news.contains(&CoordinatorNews::DependencyFailed(txid_d, txid_c)));
+
+    store.ack_news(AckCoordinatorNews::DependencyFailed(txid_b))?;
+    store.ack_news(AckCoordinatorNews::DependencyFailed(txid_d))?;
+    assert!(!harness
+        .coordinator()
+        .get_news()?
+        .coordinator_news
+        .iter()
+        .any(|news| news.kind() == "DependencyFailed"));
+
+    clear_output();
+    Ok(())
+}
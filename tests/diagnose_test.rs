@@ -0,0 +1,203 @@
+use bitcoin::{PublicKey, Transaction};
+use bitcoin_coordinator::{
+    config::CoordinatorSettingsConfig,
+    coordinator::BitcoinCoordinatorApi,
+    testing::CoordinatorTestHarness,
+    types::{BlockingReason, TransactionEvent, TransactionState, TxDiagnosis},
+};
+use key_manager::key_type::BitcoinKeyType;
+use utils::{clear_output, get_mocks, tx_with_anchor};
+mod utils;
+
+// Ephemeral anchor, the funding pays the whole fee
+const ANCHOR_AMOUNT: u64 = 0;
+
+fn setup(
+    settings: Option<CoordinatorSettingsConfig>,
+) -> Result<(CoordinatorTestHarness, PublicKey, PublicKey), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, settings)?;
+
+    Ok((harness, anchor_key, funding_key))
+}
+
+fn diagnose(harness: &CoordinatorTestHarness, tx: &Transaction) -> TxDiagnosis {
+    harness.coordinator().diagnose(tx.compute_txid()).unwrap()
+}
+
+// A transaction scheduled for a future block and a transaction with speedup data without funding.
+#[test]
+fn test_diagnose_target_height_and_funding() -> Result<(), anyhow::Error> {
+    let (harness, anchor_key, _) = setup(None)?;
+
+    let (scheduled_tx, _) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 1);
+    let target = harness.chain().height() + 10;
+    harness.coordinator().dispatch(
+        scheduled_tx.clone(),
+        None,
+        "scheduled".to_string(),
+        Some(target),
+        None,
+    )?;
+
+    let (unfunded_tx, speedup_data) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 2);
+    harness.dispatch(unfunded_tx.clone(), Some(speedup_data), "unfunded")?;
+    harness.tick()?;
+
+    let diagnosis = diagnose(&harness, &scheduled_tx);
+    assert_eq!(diagnosis.state, TransactionState::ToDispatch);
+    assert_eq!(diagnosis.target_block_height, Some(target));
+    assert!(!diagnosis.was_broadcast);
+    assert_eq!(diagnosis.last_dispatch_attempt, None);
+    assert_eq!(
+        diagnosis.blocking_reasons,
+        vec![BlockingReason::AwaitingTargetHeight]
+    );
+
+    let diagnosis = diagnose(&harness, &unfunded_tx);
+    assert!(diagnosis.has_speedup);
+    assert!(!diagnosis.funding_available);
+    assert!(!diagnosis.ancestor_limit_reached);
+    assert!(diagnosis.speedups.is_empty());
+    assert_eq!(
+        diagnosis.blocking_reasons,
+        vec![BlockingReason::FundingInsufficient]
+    );
+
+    // The diagnosis is serializable for admin endpoints
+    let json = serde_json::to_value(&diagnosis)?;
+    assert_eq!(json["blocking_reasons"][0], "FundingInsufficient");
+
+    // Diagnosing does not change anything
+    harness.tick()?;
+    assert!(!harness.chain().in_mempool(&unfunded_tx.compute_txid()));
+
+    clear_output();
+    Ok(())
+}
+
+// The fee rate goes up after the CPFP of a transaction was sent.
+#[test]
+fn test_diagnose_fee_below_network_rate() -> Result<(), anyhow::Error> {
+    let (harness, anchor_key, funding_key) = setup(None)?;
+
+    let funding = harness.fund(&funding_key, 1_000_000)?;
+    harness.coordinator().add_funding(funding)?;
+
+    let (tx, speedup_data) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 1);
+    harness.dispatch(tx.clone(), Some(speedup_data), "My tx")?;
+    harness.tick()?;
+
+    let diagnosis = diagnose(&harness, &tx);
+    assert_eq!(diagnosis.state, TransactionState::Dispatched);
+    assert!(diagnosis.was_broadcast);
+    assert!(matches!(
+        diagnosis.last_dispatch_attempt,
+        Some(TransactionEvent::Dispatched { .. })
+    ));
+    assert_eq!(diagnosis.speedups.len(), 1);
+    assert_eq!(diagnosis.unconfirmed_speedups, 1);
+    assert!(diagnosis.speedup_fees > 0);
+    assert!(diagnosis.funding_available);
+    assert!(diagnosis.blocking_reasons.is_empty());
+
+    // The new fee rate is estimated on the next tick, the CPFP is not bumped until a block is mined
+    harness.set_fee_rate(CoordinatorTestHarness::INITIAL_FEE_RATE * 20);
+    harness.tick()?;
+
+    let diagnosis = diagnose(&harness, &tx);
+    assert_eq!(
+        diagnosis.network_fee_rate,
+        CoordinatorTestHarness::INITIAL_FEE_RATE * 20
+    );
+    assert!(diagnosis.paying_fee_rate.unwrap() < diagnosis.network_fee_rate);
+    assert_eq!(
+        diagnosis.blocking_reasons,
+        vec![BlockingReason::FeeBelowNetworkRate]
+    );
+
+    // Once confirmed nothing holds the transaction back
+    harness.mine_blocks(1);
+    harness.tick()?;
+    let diagnosis = diagnose(&harness, &tx);
+    assert_eq!(diagnosis.state, TransactionState::Confirmed);
+    assert_eq!(diagnosis.confirmations, Some(1));
+    assert!(diagnosis.first_confirmation_block_height.is_some());
+    assert!(diagnosis.blocking_reasons.is_empty());
+
+    clear_output();
+    Ok(())
+}
+
+// A single CPFP paying for many transactions fills the unconfirmed chain, the next transaction can not be sped up.
+#[test]
+fn test_diagnose_ancestor_limit() -> Result<(), anyhow::Error> {
+    let (harness, anchor_key, funding_key) = setup(None)?;
+
+    let funding = harness.fund(&funding_key, 10_000_000)?;
+    harness.coordinator().add_funding(funding)?;
+
+    for seed in 0..24 {
+        let (tx, speedup_data) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, seed);
+        harness.dispatch(tx, Some(speedup_data), "batch")?;
+    }
+    harness.tick()?;
+
+    let (tx, speedup_data) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 100);
+    harness.dispatch(tx.clone(), Some(speedup_data), "My tx")?;
+    harness.tick()?;
+
+    let diagnosis = diagnose(&harness, &tx);
+    assert_eq!(diagnosis.state, TransactionState::ToDispatch);
+    assert!(diagnosis.funding_available);
+    assert!(diagnosis.ancestor_limit_reached);
+    assert_eq!(
+        diagnosis.blocking_reasons,
+        vec![BlockingReason::AncestorLimitReached]
+    );
+
+    clear_output();
+    Ok(())
+}
+
+#[test]
+fn test_diagnose_node_unreachable() -> Result<(), anyhow::Error> {
+    let (harness, anchor_key, _) = setup(Some(CoordinatorSettingsConfig {
+        node_failure_threshold: Some(1),
+        ..Default::default()
+    }))?;
+
+    let (tx, _) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 1);
+    harness.dispatch(tx.clone(), None, "My tx")?;
+
+    harness.chain().set_unreachable(true);
+    harness.tick()?;
+
+    let diagnosis = diagnose(&harness, &tx);
+    assert_eq!(diagnosis.state, TransactionState::ToDispatch);
+    assert!(diagnosis.node_unreachable_since.is_some());
+    assert_eq!(
+        diagnosis.blocking_reasons,
+        vec![BlockingReason::NodeUnreachable]
+    );
+
+    harness.chain().set_unreachable(false);
+    harness.tick()?;
+    harness.tick()?;
+
+    let diagnosis = diagnose(&harness, &tx);
+    assert_eq!(diagnosis.state, TransactionState::Dispatched);
+    assert!(diagnosis.blocking_reasons.is_empty());
+
+    // Unknown transactions can not be diagnosed
+    let (unknown_tx, _) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 2);
+    assert!(harness
+        .coordinator()
+        .diagnose(unknown_tx.compute_txid())
+        .is_err());
+
+    clear_output();
+    Ok(())
+}
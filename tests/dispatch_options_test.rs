@@ -0,0 +1,184 @@
+use crate::utils::{
+    config_trace_aux, coordinate_tx, create_test_setup, generate_tx, TestSetupConfig,
+};
+use bitcoin::{Amount, OutPoint};
+use bitcoin_coordinator::{
+    config::CoordinatorSettingsConfig,
+    coordinator::{BitcoinCoordinator, BitcoinCoordinatorApi},
+    errors::BitcoinCoordinatorError,
+    speedup::SpeedupStore,
+    storage::BitcoinCoordinatorStore,
+    types::{DispatchOptions, DispatchUrgency},
+};
+use bitvmx_bitcoin_rpc::bitcoin_client::BitcoinClientApi;
+use protocol_builder::types::{output::SpeedupData, Utxo};
+use std::rc::Rc;
+mod utils;
+
+// This test dispatches two transactions with the global fee policy and one transaction with its own options.
+// The transaction with an exclusive speedup must be paid by its own CPFP, capped at its own max fee rate,
+// while the other transactions share a CPFP that uses the global settings.
+#[test]
+fn dispatch_options_test() -> Result<(), anyhow::Error> {
+    config_trace_aux();
+
+    const NETWORK_FEE_RATE: u64 = 20;
+    const EXCLUSIVE_MAX_FEERATE: u64 = 5;
+
+    let mut blocks_mined = 102;
+    let setup = create_test_setup(TestSetupConfig {
+        blocks_mined,
+        bitcoind_flags: None,
+    })?;
+
+    let amount = Amount::from_sat(23450000);
+
+    let (funding_speedup, funding_speedup_vout) = setup
+        .bitcoin_client
+        .fund_address(&setup.funding_wallet, amount)?;
+
+    // Funding speed up tx mines 1 block
+    blocks_mined += 1;
+
+    // Regtest can not estimate the fee rate, so the coordinator uses the min network fee rate.
+    let settings = CoordinatorSettingsConfig {
+        min_network_fee_rate: Some(NETWORK_FEE_RATE),
+        ..Default::default()
+    };
+
+    let coordinator = Rc::new(BitcoinCoordinator::new_with_paths(
+        &setup.config_bitcoin_client,
+        setup.storage.clone(),
+        setup.key_manager.clone(),
+        Some(settings),
+    )?);
+
+    for _ in 0..blocks_mined {
+        coordinator.tick()?;
+    }
+
+    coordinator.add_funding(Utxo::new(
+        funding_speedup.compute_txid(),
+        funding_speedup_vout,
+        amount.to_sat(),
+        &setup.public_key,
+    ))?;
+
+    // Two transactions dispatched with the global fee policy.
+    let mut shared_txids = vec![];
+    for _ in 0..2 {
+        let tx = coordinate_tx(
+            coordinator.clone(),
+            amount,
+            setup.network,
+            setup.key_manager.clone(),
+            setup.bitcoin_client.clone(),
+            None,
+        )?;
+        shared_txids.push(tx.compute_txid());
+    }
+
+    // One transaction with its own speedup and a lower max fee rate.
+    let (funding_tx, funding_vout) = setup
+        .bitcoin_client
+        .fund_address(&setup.funding_wallet, amount)?;
+
+    let (exclusive_tx, exclusive_speedup_utxo) = generate_tx(
+        OutPoint::new(funding_tx.compute_txid(), funding_vout),
+        amount.to_sat(),
+        setup.public_key,
+        setup.key_manager.clone(),
+        172,
+    )?;
+
+    // Invalid options are rejected before the transaction is saved.
+    let result = coordinator.dispatch_with_options(
+        exclusive_tx.clone(),
+        Some(SpeedupData::new(exclusive_speedup_utxo.clone())),
+        "My exclusive tx".to_string(),
+        None,
+        None,
+        DispatchOptions {
+            max_feerate_sat_vb: Some(0),
+            ..Default::default()
+        },
+    );
+    assert!(matches!(
+        result,
+        Err(BitcoinCoordinatorError::InvalidConfiguration(_))
+    ));
+
+    coordinator.dispatch_with_options(
+        exclusive_tx.clone(),
+        Some(SpeedupData::new(exclusive_speedup_utxo)),
+        "My exclusive tx".to_string(),
+        None,
+        None,
+        DispatchOptions {
+            max_feerate_sat_vb: Some(EXCLUSIVE_MAX_FEERATE),
+            initial_bump_fee_percentage: None,
+            exclusive_speedup: true,
+            allow_duplicate: false,
+            allow_rbf_of_parent: false,
+            parent_change_vout: None,
+            depends_on: Vec::new(),
+            funding_group: None,
+            finality_confirmations: None,
+            confirmation_milestones: None,
+            urgency: DispatchUrgency::Normal,
+            max_pause_blocks: None,
+            queue_only: false,
+            expires_at_height: None,
+        },
+    )?;
+
+    for _ in 0..5 {
+        coordinator.tick()?;
+    }
+
+    let store = BitcoinCoordinatorStore::new(setup.storage.clone(), 10, 3, 2)?;
+    let speedups = store
+        .get_all_pending_speedups()?
+        .into_iter()
+        .filter(|speedup| !speedup.is_funding())
+        .collect::<Vec<_>>();
+
+    // One CPFP for the shared batch and one for the exclusive transaction.
+    assert_eq!(speedups.len(), 2);
+
+    let exclusive_speedup = speedups
+        .iter()
+        .find(|speedup| {
+            speedup
+                .speedup_tx_data
+                .iter()
+                .any(|parent| parent.tx_id == exclusive_tx.compute_txid())
+        })
+        .expect("Expected a speedup for the exclusive transaction");
+
+    assert_eq!(exclusive_speedup.speedup_tx_data.len(), 1);
+    assert_eq!(
+        exclusive_speedup.network_fee_rate_used,
+        EXCLUSIVE_MAX_FEERATE
+    );
+
+    let shared_speedup = speedups
+        .iter()
+        .find(|speedup| speedup.tx_id != exclusive_speedup.tx_id)
+        .unwrap();
+
+    let mut paid_txids = shared_speedup
+        .speedup_tx_data
+        .iter()
+        .map(|parent| parent.tx_id)
+        .collect::<Vec<_>>();
+    paid_txids.sort();
+    shared_txids.sort();
+
+    assert_eq!(paid_txids, shared_txids);
+    assert_eq!(shared_speedup.network_fee_rate_used, NETWORK_FEE_RATE);
+
+    setup.bitcoind.stop()?;
+
+    Ok(())
+}
@@ -0,0 +1,208 @@
+use bitcoin::Txid;
+use bitcoin_coordinator::{
+    config::CoordinatorSettingsConfig,
+    coordinator::BitcoinCoordinatorApi,
+    errors::BitcoinCoordinatorError,
+    testing::CoordinatorTestHarness,
+    types::{AckCoordinatorNews, AckNews, CoordinatorNews, DispatchOptions, DispatchUrgency},
+};
+use utils::{clear_output, get_mocks, simple_tx};
+mod utils;
+
+const PAUSE_NORMAL_ABOVE: u64 = 20;
+const PAUSE_LOW_ABOVE: u64 = 10;
+const SPIKE_FEE_RATE: u64 = 50;
+
+fn harness(settings: CoordinatorSettingsConfig) -> Result<CoordinatorTestHarness, anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let settings = CoordinatorSettingsConfig {
+        pause_normal_priority_above_sat_vb: Some(PAUSE_NORMAL_ABOVE),
+        pause_low_priority_above_sat_vb: Some(PAUSE_LOW_ABOVE),
+        ..settings
+    };
+
+    Ok(CoordinatorTestHarness::new(
+        store.store.clone(),
+        key_manager,
+        Some(settings),
+    )?)
+}
+
+// Dispatches a transaction without speedup and returns its txid.
+fn dispatch(
+    harness: &CoordinatorTestHarness,
+    seed: u32,
+    options: DispatchOptions,
+) -> Result<Txid, anyhow::Error> {
+    let tx = simple_tx(seed);
+    let tx_id = tx.compute_txid();
+    harness.coordinator().dispatch_with_options(
+        tx,
+        None,
+        format!("tx {seed}"),
+        None,
+        None,
+        options,
+    )?;
+
+    Ok(tx_id)
+}
+
+fn with_urgency(urgency: DispatchUrgency) -> DispatchOptions {
+    DispatchOptions {
+        urgency,
+        ..Default::default()
+    }
+}
+
+fn paused_news(harness: &CoordinatorTestHarness) -> Result<Vec<CoordinatorNews>, anyhow::Error> {
+    Ok(harness
+        .coordinator()
+        .get_news()?
+        .coordinator_news
+        .into_iter()
+        .filter(|news| matches!(news, CoordinatorNews::DispatchPausedHighFees(..)))
+        .collect())
+}
+
+// During a fee spike only the urgent transaction is dispatched. The others are released once the fee rate
+// drops below the threshold of their urgency.
+#[test]
+fn test_dispatch_paused_during_fee_spike() -> Result<(), anyhow::Error> {
+    let harness = harness(CoordinatorSettingsConfig::default())?;
+    harness.set_fee_rate(SPIKE_FEE_RATE);
+
+    let normal = dispatch(&harness, 1, DispatchOptions::default())?;
+    let low = dispatch(&harness, 2, with_urgency(DispatchUrgency::Low))?;
+    let urgent = dispatch(&harness, 3, with_urgency(DispatchUrgency::Urgent))?;
+
+    harness.tick()?;
+    assert!(harness.chain().in_mempool(&urgent));
+    assert!(!harness.chain().in_mempool(&normal));
+    assert!(!harness.chain().in_mempool(&low));
+    assert_eq!(
+        paused_news(&harness)?,
+        vec![CoordinatorNews::DispatchPausedHighFees(2, SPIKE_FEE_RATE)]
+    );
+
+    // Reported once per block, the news of the first tick is kept
+    harness.set_fee_rate(SPIKE_FEE_RATE + 10);
+    harness.tick()?;
+    assert_eq!(
+        paused_news(&harness)?,
+        vec![CoordinatorNews::DispatchPausedHighFees(2, SPIKE_FEE_RATE)]
+    );
+    harness.coordinator().ack_news(AckNews::Coordinator(
+        AckCoordinatorNews::DispatchPausedHighFees,
+    ))?;
+    assert!(paused_news(&harness)?.is_empty());
+
+    // Between both thresholds only the low urgency transaction waits
+    harness.set_fee_rate(PAUSE_NORMAL_ABOVE);
+    harness.mine_empty_blocks(1);
+    harness.tick()?;
+    assert!(harness.chain().in_mempool(&normal));
+    assert!(!harness.chain().in_mempool(&low));
+    assert_eq!(
+        paused_news(&harness)?,
+        vec![CoordinatorNews::DispatchPausedHighFees(
+            1,
+            PAUSE_NORMAL_ABOVE
+        )]
+    );
+
+    harness.set_fee_rate(PAUSE_LOW_ABOVE);
+    harness.tick()?;
+    assert!(harness.chain().in_mempool(&low));
+
+    clear_output();
+    Ok(())
+}
+
+// A paused transaction is dispatched anyway after max_pause_blocks blocks, counted from its first pause.
+#[test]
+fn test_paused_dispatch_forced_after_max_pause_blocks() -> Result<(), anyhow::Error> {
+    let harness = harness(CoordinatorSettingsConfig {
+        max_pause_blocks: Some(3),
+        ..Default::default()
+    })?;
+    harness.set_fee_rate(SPIKE_FEE_RATE);
+
+    let default_pause = dispatch(&harness, 1, DispatchOptions::default())?;
+    let short_pause = dispatch(
+        &harness,
+        2,
+        DispatchOptions {
+            max_pause_blocks: Some(1),
+            ..Default::default()
+        },
+    )?;
+
+    harness.tick()?;
+    assert!(!harness.chain().in_mempool(&default_pause));
+    assert!(!harness.chain().in_mempool(&short_pause));
+
+    harness.mine_empty_blocks(1);
+    harness.tick()?;
+    assert!(!harness.chain().in_mempool(&default_pause));
+    assert!(harness.chain().in_mempool(&short_pause));
+
+    harness.mine_empty_blocks(1);
+    harness.tick()?;
+    assert!(!harness.chain().in_mempool(&default_pause));
+
+    // Still during the spike
+    harness.mine_empty_blocks(1);
+    harness.tick()?;
+    assert!(harness.chain().in_mempool(&default_pause));
+
+    clear_output();
+    Ok(())
+}
+
+#[test]
+fn test_pause_settings_validation() -> Result<(), anyhow::Error> {
+    let invalid = [
+        CoordinatorSettingsConfig {
+            pause_normal_priority_above_sat_vb: Some(0),
+            ..Default::default()
+        },
+        CoordinatorSettingsConfig {
+            pause_normal_priority_above_sat_vb: Some(PAUSE_LOW_ABOVE),
+            pause_low_priority_above_sat_vb: Some(PAUSE_NORMAL_ABOVE),
+            ..Default::default()
+        },
+        CoordinatorSettingsConfig {
+            max_pause_blocks: Some(0),
+            ..Default::default()
+        },
+    ];
+
+    for settings in invalid {
+        assert!(matches!(
+            settings.validate(),
+            Err(BitcoinCoordinatorError::InvalidConfiguration(_))
+        ));
+    }
+
+    // Without thresholds nothing is paused
+    let (_, store, _, key_manager) = get_mocks();
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+    harness.set_fee_rate(SPIKE_FEE_RATE);
+    let tx_id = dispatch(&harness, 1, with_urgency(DispatchUrgency::Low))?;
+    harness.tick()?;
+    assert!(harness.chain().in_mempool(&tx_id));
+
+    let result = dispatch(
+        &harness,
+        2,
+        DispatchOptions {
+            max_pause_blocks: Some(0),
+            ..Default::default()
+        },
+    );
+    assert!(result.is_err());
+
+    clear_output();
+    Ok(())
+}
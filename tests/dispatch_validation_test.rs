@@ -0,0 +1,269 @@
+use bitcoin::{
+    absolute::LockTime,
+    hashes::Hash,
+    key::{Secp256k1, UntweakedPublicKey},
+    transaction::Version,
+    Amount, OutPoint, PublicKey, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Txid, WPubkeyHash,
+    WScriptHash, Witness,
+};
+use bitcoin_coordinator::{
+    errors::BitcoinCoordinatorError,
+    validation::{validate_anchor, validate_tx_to_dispatch},
+};
+use protocol_builder::types::{output::SpeedupData, OutputType, Utxo};
+use std::str::FromStr;
+
+const MAX_TX_WEIGHT: u64 = 400_000;
+const SPEEDUP_AMOUNT: u64 = 540;
+
+fn public_key() -> PublicKey {
+    PublicKey::from_str("032e58afe51f9ed8ad3cc7897f634d881fdbe49a81564629ded8156bebd2ffd1af")
+        .unwrap()
+}
+
+// A transaction with one input and a p2wpkh speedup output for `public_key()`.
+fn tx_with_speedup_output() -> Transaction {
+    let wpubkey_hash = public_key().wpubkey_hash().unwrap();
+
+    Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint::new(Txid::all_zeros(), 0),
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: Amount::from_sat(SPEEDUP_AMOUNT),
+            script_pubkey: ScriptBuf::new_p2wpkh(&wpubkey_hash),
+        }],
+    }
+}
+
+fn speedup_data(tx: &Transaction, vout: u32, amount: u64) -> SpeedupData {
+    SpeedupData::new(Utxo::new(tx.compute_txid(), vout, amount, &public_key()))
+}
+
+fn accepted(_: &Transaction) -> Result<Option<String>, BitcoinCoordinatorError> {
+    Ok(None)
+}
+
+#[test]
+fn test_valid_tx_is_accepted() -> Result<(), anyhow::Error> {
+    let tx = tx_with_speedup_output();
+    let speedup_data = speedup_data(&tx, 0, SPEEDUP_AMOUNT);
+
+    validate_tx_to_dispatch(&tx, None, MAX_TX_WEIGHT, accepted)?;
+    validate_tx_to_dispatch(&tx, Some(&speedup_data), MAX_TX_WEIGHT, accepted)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_tx_without_inputs_or_outputs_is_rejected() {
+    let mut tx = tx_with_speedup_output();
+    tx.input.clear();
+
+    assert!(matches!(
+        validate_tx_to_dispatch(&tx, None, MAX_TX_WEIGHT, accepted),
+        Err(BitcoinCoordinatorError::InvalidTransaction(txid, _)) if txid == tx.compute_txid()
+    ));
+
+    let mut tx = tx_with_speedup_output();
+    tx.output.clear();
+
+    assert!(matches!(
+        validate_tx_to_dispatch(&tx, None, MAX_TX_WEIGHT, accepted),
+        Err(BitcoinCoordinatorError::InvalidTransaction(..))
+    ));
+}
+
+#[test]
+fn test_too_heavy_tx_is_rejected() {
+    let tx = tx_with_speedup_output();
+    let weight = tx.weight().to_wu();
+
+    assert!(matches!(
+        validate_tx_to_dispatch(&tx, None, weight - 1, accepted),
+        Err(BitcoinCoordinatorError::TransactionTooHeavy(_, tx_weight, max_weight))
+            if tx_weight == weight && max_weight == weight - 1
+    ));
+}
+
+#[test]
+fn test_speedup_utxo_mismatch_is_rejected() {
+    let tx = tx_with_speedup_output();
+
+    // The speedup output does not exist
+    let missing_vout = speedup_data(&tx, 1, SPEEDUP_AMOUNT);
+    assert!(matches!(
+        validate_tx_to_dispatch(&tx, Some(&missing_vout), MAX_TX_WEIGHT, accepted),
+        Err(BitcoinCoordinatorError::AnchorOutputMismatch(..))
+    ));
+
+    // The speedup output has a different amount
+    let wrong_amount = speedup_data(&tx, 0, SPEEDUP_AMOUNT + 1);
+    assert!(matches!(
+        validate_tx_to_dispatch(&tx, Some(&wrong_amount), MAX_TX_WEIGHT, accepted),
+        Err(BitcoinCoordinatorError::AnchorOutputMismatch(..))
+    ));
+
+    // The speedup utxo belongs to another transaction
+    let other_tx = SpeedupData::new(Utxo::new(
+        Txid::all_zeros(),
+        0,
+        SPEEDUP_AMOUNT,
+        &public_key(),
+    ));
+    assert!(matches!(
+        validate_tx_to_dispatch(&tx, Some(&other_tx), MAX_TX_WEIGHT, accepted),
+        Err(BitcoinCoordinatorError::AnchorOutputMismatch(..))
+    ));
+
+    // The anchor checks the output too
+    assert!(matches!(
+        validate_anchor(&tx, &missing_vout),
+        Err(BitcoinCoordinatorError::AnchorOutputMismatch(txid, _)) if txid == tx.compute_txid()
+    ));
+}
+
+#[test]
+fn test_mempool_rejection_is_reported() -> Result<(), anyhow::Error> {
+    let tx = tx_with_speedup_output();
+    let txid = tx.compute_txid();
+
+    let result = validate_tx_to_dispatch(&tx, None, MAX_TX_WEIGHT, |_| {
+        Ok(Some("bad-txns-in-belowout".to_string()))
+    });
+    assert!(matches!(
+        result,
+        Err(BitcoinCoordinatorError::TransactionRejectedByMempool(id, reason))
+            if id == txid && reason == "bad-txns-in-belowout"
+    ));
+
+    // Inputs created by a transaction not broadcast yet are not a reason to reject it
+    validate_tx_to_dispatch(&tx, None, MAX_TX_WEIGHT, |_| {
+        Ok(Some("missing-inputs".to_string()))
+    })?;
+
+    // A low fee is only tolerated when a CPFP pays for it
+    let fee_rejection = |_: &Transaction| Ok(Some("min relay fee not met".to_string()));
+    assert!(matches!(
+        validate_tx_to_dispatch(&tx, None, MAX_TX_WEIGHT, fee_rejection),
+        Err(BitcoinCoordinatorError::TransactionRejectedByMempool(..))
+    ));
+
+    let speedup_data = speedup_data(&tx, 0, SPEEDUP_AMOUNT);
+    validate_tx_to_dispatch(&tx, Some(&speedup_data), MAX_TX_WEIGHT, fee_rejection)?;
+
+    Ok(())
+}
+
+fn partial_speedup_data(tx: &Transaction, output_type: Option<OutputType>) -> SpeedupData {
+    SpeedupData {
+        utxo: None,
+        partial_utxo: Some((tx.compute_txid(), 0, SPEEDUP_AMOUNT, output_type)),
+    }
+}
+
+#[test]
+fn test_partial_speedup_utxo_is_validated() -> Result<(), anyhow::Error> {
+    let tx = tx_with_speedup_output();
+    let output_type = OutputType::segwit_key(SPEEDUP_AMOUNT, &public_key())?;
+
+    let speedup_data = partial_speedup_data(&tx, Some(output_type.clone()));
+    validate_tx_to_dispatch(&tx, Some(&speedup_data), MAX_TX_WEIGHT, accepted)?;
+
+    // The protocol builder needs the output type to spend a partial utxo
+    let speedup_data = partial_speedup_data(&tx, None);
+    assert!(matches!(
+        validate_tx_to_dispatch(&tx, Some(&speedup_data), MAX_TX_WEIGHT, accepted),
+        Err(BitcoinCoordinatorError::InvalidSpeedupUtxo(txid, _)) if txid == tx.compute_txid()
+    ));
+
+    // The output type must describe the speedup output
+    let mut other_tx = tx.clone();
+    other_tx.output[0].script_pubkey = ScriptBuf::new_p2wpkh(&WPubkeyHash::all_zeros());
+    let speedup_data = partial_speedup_data(&other_tx, Some(output_type));
+    assert!(matches!(
+        validate_tx_to_dispatch(&other_tx, Some(&speedup_data), MAX_TX_WEIGHT, accepted),
+        Err(BitcoinCoordinatorError::InvalidSpeedupUtxo(..))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_unsupported_anchor_script_is_rejected() -> Result<(), anyhow::Error> {
+    let tx = tx_with_speedup_output();
+    validate_anchor(&tx, &speedup_data(&tx, 0, SPEEDUP_AMOUNT))?;
+
+    // The speedup output is not spendable with the utxo key
+    let mut wrong_script = tx_with_speedup_output();
+    wrong_script.output[0].script_pubkey = ScriptBuf::new();
+    let anchor = speedup_data(&wrong_script, 0, SPEEDUP_AMOUNT);
+    assert!(matches!(
+        validate_anchor(&wrong_script, &anchor),
+        Err(BitcoinCoordinatorError::UnsupportedAnchorScript(txid, _))
+            if txid == wrong_script.compute_txid()
+    ));
+
+    // A P2WSH output is only spent as a partial utxo
+    let mut p2wsh = tx_with_speedup_output();
+    p2wsh.output[0].script_pubkey = ScriptBuf::new_p2wsh(&WScriptHash::all_zeros());
+    assert!(matches!(
+        validate_anchor(&p2wsh, &speedup_data(&p2wsh, 0, SPEEDUP_AMOUNT)),
+        Err(BitcoinCoordinatorError::UnsupportedAnchorScript(..))
+    ));
+
+    // The protocol builder does not spend a legacy output
+    let mut legacy = tx_with_speedup_output();
+    let output_type = OutputType::segwit_key(SPEEDUP_AMOUNT, &public_key())?;
+    legacy.output[0].script_pubkey = ScriptBuf::new_p2pkh(&public_key().pubkey_hash());
+    let anchor = SpeedupData {
+        utxo: None,
+        partial_utxo: Some((legacy.compute_txid(), 0, SPEEDUP_AMOUNT, Some(output_type))),
+    };
+    assert!(matches!(
+        validate_anchor(&legacy, &anchor),
+        Err(BitcoinCoordinatorError::UnsupportedAnchorScript(..))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_anchor_below_dust_is_rejected() -> Result<(), anyhow::Error> {
+    // 294 sats for a P2WPKH output, 330 sats for a P2TR output
+    let p2wpkh_dust = 294;
+    let mut tx = tx_with_speedup_output();
+    tx.output[0].value = Amount::from_sat(p2wpkh_dust);
+    validate_anchor(&tx, &speedup_data(&tx, 0, p2wpkh_dust))?;
+
+    tx.output[0].value = Amount::from_sat(50);
+    assert!(matches!(
+        validate_anchor(&tx, &speedup_data(&tx, 0, 50)),
+        Err(BitcoinCoordinatorError::AnchorBelowDust(50, required)) if required == p2wpkh_dust
+    ));
+
+    let p2tr_dust = 330;
+    let internal_key = UntweakedPublicKey::from(public_key().inner);
+    tx.output[0].script_pubkey =
+        ScriptBuf::new_p2tr(&Secp256k1::verification_only(), internal_key, None);
+    tx.output[0].value = Amount::from_sat(p2wpkh_dust);
+    assert!(matches!(
+        validate_anchor(&tx, &speedup_data(&tx, 0, p2wpkh_dust)),
+        Err(BitcoinCoordinatorError::AnchorBelowDust(amount, required))
+            if amount == p2wpkh_dust && required == p2tr_dust
+    ));
+
+    tx.output[0].value = Amount::from_sat(p2tr_dust);
+    validate_anchor(&tx, &speedup_data(&tx, 0, p2tr_dust))?;
+
+    // An ephemeral anchor has no value
+    tx.output[0].value = Amount::ZERO;
+    validate_anchor(&tx, &speedup_data(&tx, 0, 0))?;
+
+    Ok(())
+}
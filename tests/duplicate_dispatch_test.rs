@@ -0,0 +1,156 @@
+use bitcoin_coordinator::{
+    coordinator::{BitcoinCoordinator, BitcoinCoordinatorApi},
+    errors::{BitcoinCoordinatorError, BitcoinCoordinatorStoreError},
+    storage::BitcoinCoordinatorStoreApi,
+    types::{DispatchOptions, TransactionState},
+    TypesToMonitor,
+};
+use bitcoincore_rpc::{Auth, Client};
+use bitvmx_transaction_monitor::errors::MonitorError;
+use utils::{clear_output, get_mock_data, get_mocks, simple_tx};
+mod utils;
+
+const CURRENT_HEIGHT: u32 = 100;
+
+#[test]
+fn test_save_tx_is_idempotent() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let (_, tx, _, tx_id, context, _) = get_mock_data(key_manager);
+
+    store.save_tx(tx.clone(), None, None, context.clone())?;
+
+    // Saving a pending transaction again fails and keeps its state
+    store.update_tx_to_dispatched(tx_id, CURRENT_HEIGHT, 1)?;
+    assert!(matches!(
+        store.save_tx(tx.clone(), None, None, context.clone()),
+        Err(BitcoinCoordinatorStoreError::TransactionAlreadyDispatched(id)) if id == tx_id
+    ));
+    assert!(matches!(
+        store.save_txs(vec![(tx.clone(), None, context.clone())], None),
+        Err(BitcoinCoordinatorStoreError::TransactionAlreadyDispatched(id)) if id == tx_id
+    ));
+    assert_eq!(store.get_tx(&tx_id)?.state, TransactionState::Dispatched);
+
+    // A failed transaction can be saved again, without duplicating it in the pending list
+    store.update_tx_state(tx_id, TransactionState::Failed)?;
+    store.save_tx(tx, None, None, context)?;
+
+    let txs = store.get_txs_in_progress()?;
+    assert_eq!(txs.len(), 1);
+    assert_eq!(txs[0].state, TransactionState::ToDispatch);
+
+    clear_output();
+    Ok(())
+}
+
+// A consumer retrying a dispatch after a timeout gets an error and the transaction is saved once.
+#[test]
+fn test_dispatch_twice() -> Result<(), anyhow::Error> {
+    let (mut mock_monitor, store, mock_bitcoin_client, key_manager) = get_mocks();
+    let tx = simple_tx(1);
+    let tx_id = tx.compute_txid();
+    let context = "My tx".to_string();
+
+    mock_monitor.expect_monitor().times(1).returning(|_| Ok(()));
+    mock_monitor
+        .expect_get_monitor_height()
+        .returning(|| Ok(CURRENT_HEIGHT));
+
+    let coordinator = BitcoinCoordinator::builder()
+        .with_monitor(Box::new(mock_monitor))
+        .with_store(store)
+        .with_client(Box::new(mock_bitcoin_client))
+        .with_rpc_client(Client::new("http://127.0.0.1:18443", Auth::None)?)
+        .with_key_manager(key_manager)
+        .build()?;
+
+    coordinator.dispatch(tx.clone(), None, context.clone(), None, None)?;
+
+    let result = coordinator.dispatch(tx.clone(), None, context.clone(), None, None);
+    assert!(matches!(
+        result,
+        Err(BitcoinCoordinatorError::AlreadyDispatched(id)) if id == tx_id
+    ));
+
+    let result = coordinator.dispatch_batch(vec![(tx.clone(), None, context.clone())], None);
+    assert!(matches!(
+        result,
+        Err(BitcoinCoordinatorError::AlreadyDispatched(id)) if id == tx_id
+    ));
+
+    // With allow_duplicate the duplicated dispatch does nothing
+    coordinator.dispatch_with_options(
+        tx,
+        None,
+        context,
+        None,
+        None,
+        DispatchOptions {
+            allow_duplicate: true,
+            ..Default::default()
+        },
+    )?;
+
+    let overview = coordinator.get_pending_overview()?;
+    assert_eq!(overview.to_dispatch.len(), 1);
+    assert_eq!(overview.to_dispatch[0].tx_id, tx_id);
+
+    clear_output();
+    Ok(())
+}
+
+// When the monitor fails for a context of a batch, the contexts already monitored are cancelled and no
+// transaction of the batch is saved.
+#[test]
+fn test_dispatch_batch_monitor_failure() -> Result<(), anyhow::Error> {
+    let (mut mock_monitor, store, mock_bitcoin_client, key_manager) = get_mocks();
+    let first = simple_tx(1);
+    let second = simple_tx(2);
+    let first_id = first.compute_txid();
+
+    mock_monitor
+        .expect_monitor()
+        .times(2)
+        .returning(|data| match data {
+            TypesToMonitor::Transactions(_, context, _) if context == "second" => {
+                Err(MonitorError::UnexpectedError("monitor failed".to_string()))
+            }
+            _ => Ok(()),
+        });
+    mock_monitor
+        .expect_cancel()
+        .withf(move |data| {
+            *data == TypesToMonitor::Transactions(vec![first_id], "first".to_string(), None)
+        })
+        .times(1)
+        .returning(|_| Ok(()));
+    mock_monitor
+        .expect_get_monitor_height()
+        .returning(|| Ok(CURRENT_HEIGHT));
+
+    let coordinator = BitcoinCoordinator::builder()
+        .with_monitor(Box::new(mock_monitor))
+        .with_store(store)
+        .with_client(Box::new(mock_bitcoin_client))
+        .with_rpc_client(Client::new("http://127.0.0.1:18443", Auth::None)?)
+        .with_key_manager(key_manager)
+        .build()?;
+
+    let result = coordinator.dispatch_batch(
+        vec![
+            (first, None, "first".to_string()),
+            (second, None, "second".to_string()),
+        ],
+        None,
+    );
+    assert!(matches!(
+        result,
+        Err(BitcoinCoordinatorError::MonitorError(_))
+    ));
+
+    let overview = coordinator.get_pending_overview()?;
+    assert!(overview.to_dispatch.is_empty());
+
+    clear_output();
+    Ok(())
+}
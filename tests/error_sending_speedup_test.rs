@@ -1,4 +1,4 @@
-use bitcoin::Amount;
+use bitcoin::{Amount, OutPoint};
 use bitcoin_coordinator::{
     config::CoordinatorSettingsConfig,
     coordinator::{BitcoinCoordinator, BitcoinCoordinatorApi},
@@ -17,7 +17,7 @@ mod utils;
 // The test procedure includes:
 // - Dispatching a transaction that requires a speedup (e.g., CPFP).
 // - Intentionally causing an error by using an invalid funding UTXO for the speedup transaction.
-// - Asserting that the coordinator accurately reports the error.
+// - Asserting that the coordinator detects the invalid funding before broadcasting and reports it.
 #[test]
 fn error_sending_speedup_test() -> Result<(), anyhow::Error> {
     config_trace_aux();
@@ -93,7 +93,8 @@ fn error_sending_speedup_test() -> Result<(), anyhow::Error> {
 
     // Second tick: Retry sending the transaction, expecting another error
     info!("Should print error 2");
-    std::thread::sleep(std::time::Duration::from_secs(RETRY_INTERVAL_SECONDS));
+    // The wait before each retry doubles
+    std::thread::sleep(std::time::Duration::from_secs(2 * RETRY_INTERVAL_SECONDS));
     coordinator.tick()?;
 
     setup
@@ -104,7 +105,7 @@ fn error_sending_speedup_test() -> Result<(), anyhow::Error> {
 
     // Third tick: Retry sending the transaction again, expecting a third error
     info!("Should print error 3");
-    std::thread::sleep(std::time::Duration::from_secs(RETRY_INTERVAL_SECONDS));
+    std::thread::sleep(std::time::Duration::from_secs(4 * RETRY_INTERVAL_SECONDS));
     coordinator.tick()?;
 
     // Before the final retry, update the funding with a valid UTXO to allow successful dispatch
@@ -160,24 +161,22 @@ fn error_sending_speedup_test() -> Result<(), anyhow::Error> {
 
     let news = coordinator.get_news()?;
     // Verify error notifications and confirmed transactions.
-    // The error "bad-txns-inputs-missingorspent" is now classified as "Other" (non-retryable),
-    // so it's reported immediately as DispatchSpeedUpError.
-    // There should be just one error notification for the failed CPFP attempt.
+    // The funding output does not exist, so the node reports it as spent before the CPFP is built.
+    // The funding is invalidated and the CPFP is deferred until the valid funding is added.
     assert_eq!(
         news.coordinator_news.len(),
         1,
-        "Expected exactly one coordinator news (DispatchSpeedUpError), got {}: {:?}",
+        "Expected exactly one coordinator news (FundingSpentExternally), got {}: {:?}",
         news.coordinator_news.len(),
         news.coordinator_news
     );
 
-    assert!(
-        matches!(
-            &news.coordinator_news[0],
-            bitcoin_coordinator::types::CoordinatorNews::DispatchSpeedUpError(_, _, _, _)
+    assert_eq!(
+        news.coordinator_news[0],
+        bitcoin_coordinator::types::CoordinatorNews::FundingSpentExternally(
+            OutPoint::new(funding_speedup.compute_txid(), 10),
+            None
         ),
-        "Expected DispatchSpeedUpError, got: {:?}",
-        news.coordinator_news[0]
     );
 
     // Verify monitor news: should be exactly 1 (tx2 confirmed, tx1's CPFP failed so it may not be confirmed)
@@ -93,7 +93,8 @@ fn error_sending_speedup_test() -> Result<(), anyhow::Error> {
 
     // Second tick: Retry sending the transaction, expecting another error
     info!("Should print error 2");
-    std::thread::sleep(std::time::Duration::from_secs(RETRY_INTERVAL_SECONDS));
+    // The wait before each retry doubles
+    std::thread::sleep(std::time::Duration::from_secs(2 * RETRY_INTERVAL_SECONDS));
     coordinator.tick()?;
 
     setup
@@ -104,7 +105,7 @@ fn error_sending_speedup_test() -> Result<(), anyhow::Error> {
 
     // Third tick: Retry sending the transaction again, expecting a third error
     info!("Should print error 3");
-    std::thread::sleep(std::time::Duration::from_secs(RETRY_INTERVAL_SECONDS));
+    std::thread::sleep(std::time::Duration::from_secs(4 * RETRY_INTERVAL_SECONDS));
     coordinator.tick()?;
 
     // Before the final retry, update the funding with a valid UTXO to allow successful dispatch
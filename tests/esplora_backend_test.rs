@@ -0,0 +1,457 @@
+use bitcoin::{
+    block::{self, Header},
+    consensus::encode,
+    hashes::Hash,
+    Block, BlockHash, CompactTarget, CompressedPublicKey, OutPoint, PublicKey, ScriptBuf,
+    Transaction, TxIn, TxMerkleNode, Txid,
+};
+use bitcoin_coordinator::{
+    config::CoordinatorSettingsConfig,
+    coordinator::{BitcoinCoordinator, BitcoinCoordinatorApi},
+    errors::{BitcoinCoordinatorError, BroadcastFailureKind},
+    esplora::{select_fee_rate, EsploraClient},
+    funding::{FundingOutputChecker, FundingOutputState},
+    speedup::SpeedupStore,
+    storage::BitcoinCoordinatorStore,
+    testing::{FakeChain, FakeMonitor},
+    types::TransactionState,
+};
+use bitvmx_bitcoin_rpc::{bitcoin_client::BitcoinClientApi, errors::BitcoinClientError};
+use key_manager::key_type::BitcoinKeyType;
+use protocol_builder::types::Utxo;
+use std::{
+    collections::HashMap,
+    net::TcpListener,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    thread,
+};
+use tiny_http::{Method, Response, Server};
+use utils::{clear_output, get_mocks, tx_with_anchor, tx_with_output};
+mod utils;
+
+const ANCHOR_AMOUNT: u64 = 540;
+const FUNDING_AMOUNT: u64 = 100_000;
+
+#[derive(Default)]
+struct EsploraState {
+    tip_height: u32,
+    fee_estimates: String,
+    // Transactions received with POST /tx, in order.
+    broadcasts: Vec<Transaction>,
+    // Error returned to the next POST /tx.
+    reject_next: Option<String>,
+    // Blocks of the chain, starting at height 0.
+    blocks: Vec<Block>,
+}
+
+impl EsploraState {
+    // Height of the block with the transaction, None when it is only broadcast.
+    fn status(&self, txid: &Txid) -> Option<Option<usize>> {
+        if let Some(height) = self
+            .blocks
+            .iter()
+            .position(|block| block.txdata.iter().any(|tx| tx.compute_txid() == *txid))
+        {
+            return Some(Some(height));
+        }
+
+        self.broadcasts
+            .iter()
+            .any(|tx| tx.compute_txid() == *txid)
+            .then_some(None)
+    }
+}
+
+fn status_json(height: Option<usize>) -> String {
+    match height {
+        Some(height) => format!(r#"{{"confirmed":true,"block_height":{height}}}"#),
+        None => r#"{"confirmed":false}"#.to_string(),
+    }
+}
+
+// A block with the transactions, only its transactions are read by the coordinator.
+fn block_with(txdata: Vec<Transaction>) -> Block {
+    Block {
+        header: Header {
+            version: block::Version::TWO,
+            prev_blockhash: BlockHash::all_zeros(),
+            merkle_root: TxMerkleNode::all_zeros(),
+            time: 0,
+            bits: CompactTarget::from_consensus(0),
+            nonce: 0,
+        },
+        txdata,
+    }
+}
+
+// Stub of the Esplora endpoints used by the coordinator, answered from the shared state.
+fn start_esplora(state: Arc<Mutex<EsploraState>>) -> String {
+    let server = Server::http("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", server.server_addr());
+
+    thread::spawn(move || {
+        for mut request in server.incoming_requests() {
+            let mut body = String::new();
+            request.as_reader().read_to_string(&mut body).unwrap();
+
+            let path: Vec<String> = request
+                .url()
+                .split('/')
+                .filter(|part| !part.is_empty())
+                .map(str::to_string)
+                .collect();
+            let path: Vec<&str> = path.iter().map(String::as_str).collect();
+            let mut state = state.lock().unwrap();
+
+            let response = match (request.method(), path.as_slice()) {
+                (Method::Get, ["blocks", "tip", "height"]) => {
+                    Response::from_string(state.tip_height.to_string())
+                }
+                (Method::Get, ["fee-estimates"]) => {
+                    Response::from_string(state.fee_estimates.clone())
+                }
+                (Method::Post, ["tx"]) => match state.reject_next.take() {
+                    Some(error) => Response::from_string(error).with_status_code(400),
+                    None => {
+                        let tx: Transaction = encode::deserialize_hex(&body).unwrap();
+                        let txid = tx.compute_txid();
+                        state.broadcasts.push(tx);
+                        Response::from_string(txid.to_string())
+                    }
+                },
+                (Method::Get, ["tx", txid, "hex"]) => {
+                    let txid = Txid::from_str(txid).unwrap();
+                    match state.broadcasts.iter().find(|tx| tx.compute_txid() == txid) {
+                        Some(tx) => Response::from_string(encode::serialize_hex(tx)),
+                        None => {
+                            Response::from_string("Transaction not found").with_status_code(404)
+                        }
+                    }
+                }
+                (Method::Get, ["tx", txid, "status"]) => {
+                    match state.status(&Txid::from_str(txid).unwrap()) {
+                        Some(height) => Response::from_string(status_json(height)),
+                        None => {
+                            Response::from_string("Transaction not found").with_status_code(404)
+                        }
+                    }
+                }
+                (Method::Get, ["tx", txid, "outspend", vout]) => {
+                    let outpoint =
+                        OutPoint::new(Txid::from_str(txid).unwrap(), vout.parse().unwrap());
+                    let spender = state
+                        .blocks
+                        .iter()
+                        .flat_map(|block| block.txdata.iter())
+                        .chain(state.broadcasts.iter())
+                        .find(|tx| {
+                            tx.input
+                                .iter()
+                                .any(|input| input.previous_output == outpoint)
+                        });
+                    match spender {
+                        Some(tx) => Response::from_string(format!(
+                            r#"{{"spent":true,"txid":"{}","vin":0,"status":{}}}"#,
+                            tx.compute_txid(),
+                            status_json(state.status(&tx.compute_txid()).flatten())
+                        )),
+                        None => Response::from_string(r#"{"spent":false}"#),
+                    }
+                }
+                (Method::Get, ["block-height", height]) => {
+                    match state.blocks.get(height.parse::<usize>().unwrap()) {
+                        Some(block) => Response::from_string(block.block_hash().to_string()),
+                        None => Response::from_string("Block not found").with_status_code(404),
+                    }
+                }
+                (Method::Get, ["block", hash, "raw"]) => {
+                    let hash = BlockHash::from_str(hash).unwrap();
+                    match state.blocks.iter().find(|block| block.block_hash() == hash) {
+                        Some(block) => Response::from_data(encode::serialize(block)),
+                        None => Response::from_string("Block not found").with_status_code(404),
+                    }
+                }
+                _ => Response::from_string("Not found").with_status_code(404),
+            };
+
+            request.respond(response).unwrap();
+        }
+    });
+
+    url
+}
+
+// An address nothing listens on.
+fn unreachable_url() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    format!("http://{}", listener.local_addr().unwrap())
+}
+
+#[test]
+fn test_select_fee_rate() {
+    let estimates = HashMap::from([(1, 20.5), (3, 10.1), (6, 5.2), (144, 1.0)]);
+
+    assert_eq!(select_fee_rate(&estimates, 6), Some(6));
+    // The closest lower target pays enough
+    assert_eq!(select_fee_rate(&estimates, 2), Some(21));
+    assert_eq!(select_fee_rate(&estimates, 100), Some(6));
+    assert_eq!(select_fee_rate(&estimates, 1008), Some(1));
+
+    // Below every target the fastest estimate is used
+    let estimates = HashMap::from([(2, 8.0)]);
+    assert_eq!(select_fee_rate(&estimates, 1), Some(8));
+
+    assert_eq!(select_fee_rate(&HashMap::new(), 6), None);
+    assert_eq!(select_fee_rate(&HashMap::from([(6, 0.0)]), 6), None);
+}
+
+#[test]
+fn test_esplora_client_requests() -> Result<(), anyhow::Error> {
+    let state = Arc::new(Mutex::new(EsploraState {
+        tip_height: 812_345,
+        fee_estimates: r#"{"1":20.5,"3":10.1,"6":5.2,"144":1.0}"#.to_string(),
+        ..Default::default()
+    }));
+    let client = EsploraClient::new(&format!("{}/", start_esplora(state.clone())));
+
+    assert_eq!(client.get_best_block()?, 812_345);
+    assert_eq!(client.get_fee_rate(3)?, Some(11));
+    assert_eq!(client.estimate_smart_fee()?, 6);
+
+    let (tx, _) = tx_with_anchor(
+        &PublicKey::from_str("02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5")?,
+        ANCHOR_AMOUNT,
+        1,
+    );
+    let txid = tx.compute_txid();
+
+    assert_eq!(client.get_transaction(&txid)?, None);
+    assert_eq!(
+        client.get_funding_output_state(&OutPoint::new(txid, 0))?,
+        FundingOutputState::Unspent
+    );
+
+    assert_eq!(client.send_transaction(&tx)?, txid);
+    assert_eq!(state.lock().unwrap().broadcasts, vec![tx.clone()]);
+    assert_eq!(client.get_transaction(&txid)?, Some(tx.clone()));
+
+    let spender = Transaction {
+        input: vec![TxIn {
+            previous_output: OutPoint::new(txid, 0),
+            ..tx.input[0].clone()
+        }],
+        ..tx.clone()
+    };
+    client.send_transaction(&spender)?;
+    assert_eq!(
+        client.get_funding_output_state(&OutPoint::new(txid, 0))?,
+        FundingOutputState::Spent(Some(spender.compute_txid()))
+    );
+
+    // The rejections of the node are classified like a sendrawtransaction error
+    for (error, kind) in [
+        (
+            r#"sendrawtransaction RPC error: {"code":-26,"message":"min relay fee not met, 100 < 141"}"#,
+            BroadcastFailureKind::MinRelayFeeNotMet,
+        ),
+        (
+            r#"sendrawtransaction RPC error: {"code":-26,"message":"txn-mempool-conflict"}"#,
+            BroadcastFailureKind::MempoolConflict,
+        ),
+        (
+            r#"sendrawtransaction RPC error: {"code":-26,"message":"non-final"}"#,
+            BroadcastFailureKind::PolicyRejection,
+        ),
+        (
+            r#"sendrawtransaction RPC error: {"code":-27,"message":"Transaction already in block chain"}"#,
+            BroadcastFailureKind::AlreadyInChain,
+        ),
+    ] {
+        state.lock().unwrap().reject_next = Some(error.to_string());
+        let result = client.send_transaction(&tx);
+
+        assert!(matches!(
+            &result,
+            Err(BitcoinClientError::FailedToSendTransaction { error: message }) if message == error
+        ));
+        assert_eq!(
+            BroadcastFailureKind::from_error_message(&result.unwrap_err().to_string()),
+            kind
+        );
+    }
+
+    // A server that can not be reached is a connection error
+    let client = EsploraClient::new(&unreachable_url());
+    let error = client.send_transaction(&tx).unwrap_err();
+    assert_eq!(
+        BroadcastFailureKind::from_error_message(&error.to_string()),
+        BroadcastFailureKind::ConnectionError
+    );
+    assert!(client.get_best_block().is_err());
+
+    Ok(())
+}
+
+// The transaction and its CPFP are broadcast to Esplora, paying the fee rate it estimates, and are confirmed by the monitor.
+#[test]
+fn test_dispatch_and_cpfp_over_esplora() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+
+    let state = Arc::new(Mutex::new(EsploraState {
+        tip_height: 1,
+        fee_estimates: r#"{"2":15.0,"6":7.3,"144":1.0}"#.to_string(),
+        ..Default::default()
+    }));
+    let esplora_url = start_esplora(state.clone());
+
+    // The node only feeds the monitor, its fee rate is not used and there is no RPC client
+    let chain = FakeChain::new(2);
+    let coordinator = BitcoinCoordinator::builder()
+        .with_monitor(Box::new(FakeMonitor::new(chain.clone(), 6)))
+        .with_store(BitcoinCoordinatorStore::new(store.store.clone(), 10, 3, 2)?)
+        .with_esplora_client(EsploraClient::new(&esplora_url))
+        .with_key_manager(key_manager)
+        .build()?;
+
+    let compressed = CompressedPublicKey::try_from(funding_key)?;
+    let (funding_tx, vout) = chain.fund(
+        ScriptBuf::new_p2wpkh(&compressed.wpubkey_hash()),
+        FUNDING_AMOUNT,
+    );
+    let funding = Utxo::new(
+        funding_tx.compute_txid(),
+        vout,
+        FUNDING_AMOUNT,
+        &funding_key,
+    );
+    coordinator.add_funding(funding.clone())?;
+
+    let (tx, speedup_data) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 1);
+    coordinator.dispatch(
+        tx.clone(),
+        Some(speedup_data),
+        "My tx".to_string(),
+        None,
+        None,
+    )?;
+    coordinator.tick()?;
+
+    let broadcasts = state.lock().unwrap().broadcasts.clone();
+    assert_eq!(broadcasts.len(), 2);
+    assert_eq!(broadcasts[0], tx);
+
+    let cpfp = broadcasts[1].clone();
+    let spent: Vec<OutPoint> = cpfp
+        .input
+        .iter()
+        .map(|input| input.previous_output)
+        .collect();
+    assert!(spent.contains(&OutPoint::new(tx.compute_txid(), 0)));
+    assert!(spent.contains(&OutPoint::new(funding.txid, funding.vout)));
+
+    let (speedup, _) = store.get_last_speedup()?.unwrap();
+    assert_eq!(speedup.tx_id, cpfp.compute_txid());
+    assert_eq!(speedup.network_fee_rate_used, 8);
+
+    // The transactions reach the chain the monitor indexes
+    for tx in broadcasts.iter() {
+        chain.send_transaction(tx).map_err(anyhow::Error::msg)?;
+    }
+    chain.mine_blocks(1);
+    state.lock().unwrap().tip_height = chain.height();
+    coordinator.tick()?;
+
+    assert_eq!(
+        coordinator
+            .get_transaction_history(tx.compute_txid())?
+            .state,
+        TransactionState::Confirmed
+    );
+
+    clear_output();
+    Ok(())
+}
+
+// The node calls of the conflict detection and the fee estimate fallback are answered by Esplora.
+#[test]
+fn test_esplora_node_calls() -> Result<(), anyhow::Error> {
+    use bitcoin_coordinator::node::NodeApi;
+
+    let funding_tx = tx_with_output(ScriptBuf::new(), 10_000, 1);
+    let block = block_with(vec![funding_tx.clone()]);
+    let state = Arc::new(Mutex::new(EsploraState {
+        fee_estimates: r#"{"1":20.5,"3":10.1,"6":5.2,"144":1.3}"#.to_string(),
+        blocks: vec![block.clone()],
+        ..Default::default()
+    }));
+    let client = EsploraClient::new(&start_esplora(state.clone()));
+    let outpoint = OutPoint::new(funding_tx.compute_txid(), 0);
+
+    assert!(client.is_unspent(&outpoint, false)?);
+    assert!(client.is_unspent(&outpoint, true)?);
+    assert!(!client.is_unspent(&OutPoint::new(Txid::all_zeros(), 0), true)?);
+
+    // A mempool spend only counts when the mempool is included
+    let mut spender = tx_with_output(ScriptBuf::new(), 9_000, 2);
+    spender.input[0].previous_output = outpoint;
+    client.send_transaction(&spender)?;
+    assert!(client.is_unspent(&outpoint, false)?);
+    assert!(!client.is_unspent(&outpoint, true)?);
+
+    // Unconfirmed outputs do not exist in the chain
+    let spender_outpoint = OutPoint::new(spender.compute_txid(), 0);
+    assert!(!client.is_unspent(&spender_outpoint, false)?);
+    assert!(client.is_unspent(&spender_outpoint, true)?);
+
+    assert_eq!(NodeApi::get_block_count(&client)?, 0);
+    let block_hash = client.get_block_hash(0)?;
+    assert_eq!(block_hash, block.block_hash());
+    assert_eq!(
+        client.get_block_transactions(&block_hash)?,
+        vec![funding_tx]
+    );
+
+    assert_eq!(client.get_mempool_min_fee()?, Some(2));
+    assert_eq!(NodeApi::estimate_smart_fee(&client, 4, None)?, Some(11));
+
+    // Esplora has no mempool checks
+    assert!(!client.supports_mempool_checks());
+    assert!(client.test_mempool_accept(&spender).is_err());
+
+    Ok(())
+}
+
+// Without a node, the mempool checks are rejected since the Esplora server can not answer them.
+#[test]
+fn test_esplora_without_node_rejects_mempool_checks() -> Result<(), anyhow::Error> {
+    for settings in [
+        CoordinatorSettingsConfig {
+            test_mempool_accept: Some(true),
+            ..Default::default()
+        },
+        CoordinatorSettingsConfig {
+            check_mempool_ancestry: Some(true),
+            ..Default::default()
+        },
+    ] {
+        let (_, store, _, key_manager) = get_mocks();
+        let result = BitcoinCoordinator::builder()
+            .with_monitor(Box::new(FakeMonitor::new(FakeChain::new(2), 6)))
+            .with_store(BitcoinCoordinatorStore::new(store.store.clone(), 10, 3, 2)?)
+            .with_esplora_client(EsploraClient::new(&unreachable_url()))
+            .with_key_manager(key_manager)
+            .with_settings(settings)
+            .build();
+
+        assert!(matches!(
+            result,
+            Err(BitcoinCoordinatorError::InvalidConfiguration(_))
+        ));
+    }
+
+    clear_output();
+    Ok(())
+}
@@ -0,0 +1,145 @@
+use bitcoin::{
+    absolute::LockTime, hashes::Hash, transaction::Version, Amount, OutPoint, ScriptBuf, Sequence,
+    Transaction, TxIn, TxOut, Txid, WPubkeyHash, Witness,
+};
+use bitcoin_coordinator::{
+    coordinator::{BitcoinCoordinator, BitcoinCoordinatorApi},
+    speedup::SpeedupStore,
+    storage::{BitcoinCoordinatorStore, BitcoinCoordinatorStoreApi},
+};
+use bitvmx_bitcoin_rpc::bitcoin_client::BitcoinClientApi;
+use protocol_builder::types::{output::SpeedupData, Utxo};
+use std::rc::Rc;
+
+use crate::utils::{config_trace_aux, create_test_setup, generate_tx, TestSetupConfig};
+mod utils;
+
+// A transaction heavier than the default max_tx_weight.
+fn heavy_tx() -> Transaction {
+    let output = TxOut {
+        value: Amount::from_sat(1000),
+        script_pubkey: ScriptBuf::new_p2wpkh(&WPubkeyHash::all_zeros()),
+    };
+
+    Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint::new(Txid::all_zeros(), 0),
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+            witness: Witness::new(),
+        }],
+        output: vec![output; 4000],
+    }
+}
+
+// This test estimates the cost of dispatching two transactions and a transaction too heavy to be dispatched.
+// The estimate must not change the store, and the CPFP created when the two transactions are dispatched
+// must pay the estimated fee (the estimate uses the biggest signature size, so it can only be a bit higher).
+#[test]
+fn estimate_dispatch_cost_test() -> Result<(), anyhow::Error> {
+    config_trace_aux();
+
+    let mut blocks_mined = 102;
+    let setup = create_test_setup(TestSetupConfig {
+        blocks_mined,
+        bitcoind_flags: None,
+    })?;
+
+    let amount = Amount::from_sat(23450000);
+
+    let (funding_speedup, funding_speedup_vout) = setup
+        .bitcoin_client
+        .fund_address(&setup.funding_wallet, amount)?;
+    let (funding_tx1, funding_tx1_vout) = setup
+        .bitcoin_client
+        .fund_address(&setup.funding_wallet, amount)?;
+    let (funding_tx2, funding_tx2_vout) = setup
+        .bitcoin_client
+        .fund_address(&setup.funding_wallet, amount)?;
+
+    // Each funding mines 1 block
+    blocks_mined += 3;
+
+    let coordinator = Rc::new(BitcoinCoordinator::new_with_paths(
+        &setup.config_bitcoin_client,
+        setup.storage.clone(),
+        setup.key_manager.clone(),
+        None,
+    )?);
+
+    for _ in 0..blocks_mined {
+        coordinator.tick()?;
+    }
+
+    coordinator.add_funding(Utxo::new(
+        funding_speedup.compute_txid(),
+        funding_speedup_vout,
+        amount.to_sat(),
+        &setup.public_key,
+    ))?;
+
+    let mut txs = Vec::new();
+
+    for (funding_tx, funding_vout) in [
+        (funding_tx1, funding_tx1_vout),
+        (funding_tx2, funding_tx2_vout),
+    ] {
+        let (tx, speedup_utxo) = generate_tx(
+            OutPoint::new(funding_tx.compute_txid(), funding_vout),
+            amount.to_sat(),
+            setup.public_key,
+            setup.key_manager.clone(),
+            172,
+        )?;
+
+        txs.push((tx, SpeedupData::new(speedup_utxo)));
+    }
+
+    let heavy_tx = heavy_tx();
+    let mut txs_to_estimate = txs.clone();
+    txs_to_estimate.push((heavy_tx.clone(), txs[0].1.clone()));
+
+    let estimate = coordinator.estimate_dispatch_cost(txs_to_estimate)?;
+
+    assert_eq!(estimate.unbatchable_txs, vec![heavy_tx.compute_txid()]);
+    assert!(estimate.deferred_txs.is_empty());
+    assert_eq!(estimate.batches.len(), 1);
+    assert_eq!(
+        estimate.batches[0].txids,
+        vec![txs[0].0.compute_txid(), txs[1].0.compute_txid()]
+    );
+    assert_eq!(estimate.total_fee, estimate.batches[0].cpfp_fee);
+    assert_eq!(estimate.funding_amount, amount.to_sat());
+    assert!(estimate.is_funding_sufficient);
+
+    // Nothing was saved
+    let store = BitcoinCoordinatorStore::new(setup.storage.clone(), 10, 3, 2)?;
+    assert!(store.get_txs_to_dispatch()?.is_empty());
+    assert!(store.get_pending_speedups()?.is_empty());
+    assert!(coordinator.get_news()?.coordinator_news.is_empty());
+
+    for (tx, speedup_data) in txs {
+        coordinator.dispatch(tx, Some(speedup_data), "My tx".to_string(), None, None)?;
+    }
+
+    coordinator.tick()?;
+
+    let (cpfp, _) = store.get_last_speedup()?.unwrap();
+    assert_eq!(cpfp.speedup_tx_data.len(), 2);
+
+    let cpfp_fee = cpfp
+        .prev_funding
+        .amount
+        .saturating_sub(cpfp.next_funding.amount);
+
+    assert!(estimate.batches[0].cpfp_vsize >= cpfp.vsize);
+    assert!(estimate.batches[0].cpfp_vsize - cpfp.vsize <= 2);
+    assert!(estimate.total_fee >= cpfp_fee);
+    assert!(estimate.total_fee - cpfp_fee <= 2 * estimate.network_fee_rate);
+
+    setup.bitcoind.stop()?;
+
+    Ok(())
+}
@@ -0,0 +1,229 @@
+use bitcoin::{consensus::encode::serialize_hex, Transaction};
+use bitcoin_coordinator::{
+    coordinator::{BitcoinCoordinator, BitcoinCoordinatorApi},
+    cpfp::SpeedupOutputKind,
+    storage::{BitcoinCoordinatorStore, BitcoinCoordinatorStoreApi},
+    types::{JournalEntry, JournalEvent, TransactionEvent, TransactionState},
+};
+use bitcoincore_rpc::{Auth, Client};
+use bitvmx_transaction_monitor::errors::MonitorError;
+use key_manager::key_type::BitcoinKeyType;
+use protocol_builder::types::{output::SpeedupData, Utxo};
+use utils::{clear_output, get_mock_data, get_mocks, tx_with_output};
+mod utils;
+
+const CURRENT_HEIGHT: u32 = 100;
+const ANCHOR_AMOUNT: u64 = 540;
+const FUNDING_AMOUNT: u64 = 100_000;
+
+// A dispatch paid by a CPFP on the first tick. The journal records, in order, the transaction saved,
+// its broadcast with the raw transaction, the dispatch, the CPFP fee inputs and the CPFP broadcast.
+#[test]
+fn test_journal_records_dispatch_and_speedup() -> Result<(), anyhow::Error> {
+    let (mut mock_monitor, store, mut mock_bitcoin_client, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+    let context = "My tx".to_string();
+
+    // A taproot anchor, the CPFP is signed by the coordinator
+    let tx = tx_with_output(
+        SpeedupOutputKind::P2trKeyPath.script_pubkey(&anchor_key)?,
+        ANCHOR_AMOUNT,
+        1,
+    );
+    let tx_id = tx.compute_txid();
+    let speedup_data = SpeedupData::new(Utxo::new(tx_id, 0, ANCHOR_AMOUNT, &anchor_key));
+
+    let funding_tx = tx_with_output(
+        SpeedupOutputKind::P2wpkh.script_pubkey(&funding_key)?,
+        FUNDING_AMOUNT,
+        2,
+    );
+
+    mock_monitor.expect_monitor().returning(|_| Ok(()));
+    mock_monitor.expect_tick().returning(|| Ok(()));
+    mock_monitor.expect_is_ready().returning(|| Ok(true));
+    mock_monitor
+        .expect_get_monitor_height()
+        .returning(|| Ok(CURRENT_HEIGHT));
+    // Without a current block the speedup news are not stored
+    mock_monitor
+        .expect_get_current_block()
+        .returning(|| Ok(None));
+    mock_monitor.expect_get_news().returning(|| Ok(vec![]));
+    mock_monitor
+        .expect_get_tx_status()
+        .returning(|tx_id| Err(MonitorError::TransactionNotFound(tx_id.to_string())));
+    mock_monitor
+        .expect_get_estimated_fee_rate()
+        .returning(|| Ok(2));
+
+    mock_bitcoin_client
+        .expect_send_transaction()
+        .returning(|tx| Ok(tx.compute_txid()));
+    mock_bitcoin_client
+        .expect_get_best_block()
+        .returning(|| Ok(CURRENT_HEIGHT));
+
+    let coordinator = BitcoinCoordinator::builder()
+        .with_monitor(Box::new(mock_monitor))
+        .with_store(store)
+        .with_client(Box::new(mock_bitcoin_client))
+        .with_rpc_client(Client::new("http://127.0.0.1:18443", Auth::None)?)
+        .with_key_manager(key_manager)
+        .build()?;
+
+    coordinator.add_funding(Utxo::new(
+        funding_tx.compute_txid(),
+        0,
+        FUNDING_AMOUNT,
+        &funding_key,
+    ))?;
+    coordinator.dispatch(tx.clone(), Some(speedup_data), context, None, None)?;
+    coordinator.tick()?;
+
+    let entries = coordinator.read_events(0, 100)?;
+    assert!(entries.windows(2).all(|pair| pair[0].seq < pair[1].seq));
+
+    // Saved before the first tick, so there is no monitor height yet
+    assert_eq!(
+        entries[0].event,
+        JournalEvent::Transaction {
+            tx_id,
+            event: TransactionEvent::Saved {
+                target_block_height: None
+            },
+        }
+    );
+    assert_eq!(entries[0].block_height, None);
+
+    let tx_entries: Vec<&JournalEntry> = entries[1..]
+        .iter()
+        .filter(|entry| match &entry.event {
+            JournalEvent::BroadcastAttempt { tx_id: id, .. }
+            | JournalEvent::Transaction { tx_id: id, .. } => *id == tx_id,
+            _ => false,
+        })
+        .collect();
+
+    assert_eq!(
+        tx_entries[0].event,
+        JournalEvent::BroadcastAttempt {
+            tx_id,
+            raw_tx: serialize_hex(&tx),
+            error: None,
+        }
+    );
+    assert!(matches!(
+        tx_entries[1].event,
+        JournalEvent::Transaction {
+            event: TransactionEvent::Dispatched {
+                block_height: CURRENT_HEIGHT,
+                ..
+            },
+            ..
+        }
+    ));
+
+    // The CPFP fee inputs are recorded before the CPFP is sent
+    let speedup_position = entries
+        .iter()
+        .position(|entry| matches!(entry.event, JournalEvent::SpeedupCreated { .. }))
+        .unwrap();
+
+    let JournalEvent::SpeedupCreated {
+        tx_id: speedup_txid,
+        is_rbf,
+        paid_txids,
+        network_fee_rate,
+        vsize,
+        parents_vsize,
+        ..
+    } = entries[speedup_position].event.clone()
+    else {
+        unreachable!()
+    };
+
+    assert!(!is_rbf);
+    assert_eq!(paid_txids, vec![tx_id]);
+    assert_eq!(network_fee_rate, 2);
+    assert_eq!(parents_vsize, tx.vsize());
+    assert!(vsize > 0);
+
+    assert!(matches!(
+        &entries[speedup_position + 1].event,
+        JournalEvent::BroadcastAttempt { tx_id: id, raw_tx, error: None }
+            if *id == speedup_txid && !raw_tx.is_empty()
+    ));
+
+    // Everything written during the tick has the monitor height
+    assert!(entries[1..]
+        .iter()
+        .all(|entry| entry.block_height == Some(CURRENT_HEIGHT)));
+
+    clear_output();
+    Ok(())
+}
+
+#[test]
+fn test_journal_read_export_and_prune() -> Result<(), anyhow::Error> {
+    let (mut mock_monitor, store, _, key_manager) = get_mocks();
+    let (_, tx, _, tx_id, context, _) = get_mock_data(key_manager.clone());
+    let storage = store.store.clone();
+
+    store.save_tx(tx, None, None, context)?;
+    store.update_tx_to_dispatched(tx_id, CURRENT_HEIGHT, 1)?;
+    store.update_tx_state(tx_id, TransactionState::Confirmed)?;
+
+    // Another store on the same storage continues the sequence
+    let other_store = BitcoinCoordinatorStore::new(storage, 1, 3, 2)?;
+    other_store.update_tx_state(tx_id, TransactionState::Finalized)?;
+
+    mock_monitor
+        .expect_get_monitor_height()
+        .returning(|| Ok(CURRENT_HEIGHT));
+
+    let coordinator = BitcoinCoordinator::builder()
+        .with_monitor(Box::new(mock_monitor))
+        .with_store(store)
+        .with_client(Box::new(utils::get_mocks().2))
+        .with_rpc_client(Client::new("http://127.0.0.1:18443", Auth::None)?)
+        .with_key_manager(key_manager)
+        .build()?;
+
+    let entries = coordinator.read_events(0, 100)?;
+    let seqs: Vec<u64> = entries.iter().map(|entry| entry.seq).collect();
+    assert_eq!(seqs, (0..entries.len() as u64).collect::<Vec<_>>());
+    assert_eq!(
+        entries.last().unwrap().event,
+        JournalEvent::Transaction {
+            tx_id,
+            event: TransactionEvent::StateChanged {
+                from: TransactionState::Confirmed,
+                to: TransactionState::Finalized,
+            },
+        }
+    );
+
+    // Pages
+    assert_eq!(coordinator.read_events(1, 2)?, entries[1..3].to_vec());
+    assert!(coordinator
+        .read_events(entries.len() as u64, 10)?
+        .is_empty());
+
+    // Export
+    let path =
+        std::env::temp_dir().join(format!("journal_{}.json", utils::generate_random_string()));
+    assert_eq!(coordinator.export_events_json(&path)?, entries.len());
+    let exported: Vec<JournalEntry> = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+    assert_eq!(exported, entries);
+    std::fs::remove_file(&path)?;
+
+    // Pruning is explicit, the sequence numbers are not reused
+    assert_eq!(coordinator.prune_events(2)?, 2);
+    assert_eq!(coordinator.prune_events(2)?, 0);
+    assert_eq!(coordinator.read_events(0, 100)?, entries[2..].to_vec());
+
+    clear_output();
+    Ok(())
+}
@@ -0,0 +1,235 @@
+use bitcoin::{
+    absolute::LockTime, transaction::Version, Amount, OutPoint, PublicKey, ScriptBuf, Sequence,
+    Transaction, TxIn, TxOut, Witness,
+};
+use bitcoin_coordinator::{
+    config::CoordinatorSettingsConfig, coordinator::BitcoinCoordinatorApi, cpfp::SpeedupOutputKind,
+    errors::BitcoinCoordinatorError, speedup::SpeedupStore, testing::CoordinatorTestHarness,
+    types::SpeedupState,
+};
+use key_manager::key_type::BitcoinKeyType;
+use utils::{clear_output, get_mocks, tx_with_anchor};
+mod utils;
+
+const FUNDING_AMOUNT: u64 = 1_000_000;
+const CHANGE_AMOUNT: u64 = 990_000;
+
+// A CPFP built by hand, spending the funding and the anchor of the parent.
+fn manual_cpfp(funding: OutPoint, parent: &Transaction, change_key: &PublicKey) -> Transaction {
+    let input = |previous_output| TxIn {
+        previous_output,
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+        witness: Witness::new(),
+    };
+
+    Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![
+            input(funding),
+            input(OutPoint::new(parent.compute_txid(), 0)),
+        ],
+        output: vec![TxOut {
+            value: Amount::from_sat(CHANGE_AMOUNT),
+            script_pubkey: SpeedupOutputKind::P2wpkh.script_pubkey(change_key).unwrap(),
+        }],
+    }
+}
+
+// The funding is below the minimum, so the transaction is broadcast and its CPFP deferred.
+// The CPFP sent by hand is imported and becomes part of the speedup chain.
+#[test]
+fn test_import_external_speedup() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+    let change_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 2)?;
+    let harness = CoordinatorTestHarness::new(
+        store.store.clone(),
+        key_manager,
+        Some(CoordinatorSettingsConfig {
+            min_funding_amount_sats: Some(2 * FUNDING_AMOUNT),
+            ..Default::default()
+        }),
+    )?;
+
+    let funding = harness.fund(&funding_key, FUNDING_AMOUNT)?;
+    harness.coordinator().add_funding(funding.clone())?;
+
+    let (tx, speedup_data) = tx_with_anchor(&anchor_key, 0, 1);
+    harness.dispatch(tx.clone(), Some(speedup_data), "My tx")?;
+    harness.tick()?;
+
+    assert!(harness.chain().in_mempool(&tx.compute_txid()));
+    assert_eq!(store.get_deferred_speedup_txs()?, vec![tx.compute_txid()]);
+
+    let cpfp = manual_cpfp(OutPoint::new(funding.txid, funding.vout), &tx, &change_key);
+    harness
+        .chain()
+        .send_transaction(&cpfp)
+        .map_err(anyhow::Error::msg)?;
+
+    harness.coordinator().import_external_speedup(
+        cpfp.clone(),
+        vec![tx.compute_txid()],
+        0,
+        change_key,
+    )?;
+
+    let speedup = store.get_speedup(&cpfp.compute_txid())?;
+    assert!(speedup.is_external);
+    assert_eq!(speedup.state, SpeedupState::Dispatched);
+    assert_eq!(speedup.speedup_tx_data[0].tx_id, tx.compute_txid());
+
+    // The change is the funding of the next speedups
+    let next_funding = harness
+        .coordinator()
+        .get_funding_summary()?
+        .funding
+        .unwrap();
+    assert_eq!(next_funding.txid, cpfp.compute_txid());
+    assert_eq!(next_funding.vout, 0);
+    assert_eq!(next_funding.amount, CHANGE_AMOUNT);
+    assert_eq!(next_funding.pub_key, change_key);
+
+    assert!(store.get_deferred_speedup_txs()?.is_empty());
+    assert!(store.get_dispatched_txs_without_speedup()?.is_empty());
+    assert_eq!(
+        harness
+            .coordinator()
+            .get_funding_summary()?
+            .unconfirmed_speedups,
+        1
+    );
+
+    // The covered transaction is not sped up again
+    harness
+        .coordinator()
+        .update_settings(CoordinatorSettingsConfig::default())?;
+    harness.tick()?;
+    assert_eq!(harness.chain().mempool().len(), 2);
+
+    // The next CPFP spends the change of the imported speedup
+    let (next_tx, speedup_data) = tx_with_anchor(&anchor_key, 0, 2);
+    harness.dispatch(next_tx.clone(), Some(speedup_data), "Next tx")?;
+    harness.tick()?;
+
+    let (last_speedup, _) = store.get_last_speedup()?.unwrap();
+    assert!(!last_speedup.is_external);
+    assert_eq!(
+        last_speedup.speedup_tx_data[0].tx_id,
+        next_tx.compute_txid()
+    );
+    assert_eq!(last_speedup.prev_funding.txid, cpfp.compute_txid());
+    assert!(harness.chain().in_mempool(&last_speedup.tx_id));
+
+    clear_output();
+    Ok(())
+}
+
+// A speedup that does not spend the funding, or whose change does not match, is rejected without changing anything.
+#[test]
+fn test_import_external_speedup_validation() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+    let change_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 2)?;
+    let harness = CoordinatorTestHarness::new(
+        store.store.clone(),
+        key_manager,
+        Some(CoordinatorSettingsConfig {
+            min_funding_amount_sats: Some(2 * FUNDING_AMOUNT),
+            ..Default::default()
+        }),
+    )?;
+
+    let funding = harness.fund(&funding_key, FUNDING_AMOUNT)?;
+    harness.coordinator().add_funding(funding.clone())?;
+
+    let (tx, speedup_data) = tx_with_anchor(&anchor_key, 0, 1);
+    harness.dispatch(tx.clone(), Some(speedup_data), "My tx")?;
+    harness.tick()?;
+
+    let other_outpoint = OutPoint::new(tx.compute_txid(), 1);
+    let not_funded = manual_cpfp(other_outpoint, &tx, &change_key);
+    assert!(matches!(
+        harness.coordinator().import_external_speedup(
+            not_funded.clone(),
+            vec![tx.compute_txid()],
+            0,
+            change_key,
+        ),
+        Err(BitcoinCoordinatorError::ExternalSpeedupFundingNotSpent(txid, outpoint))
+            if txid == not_funded.compute_txid() && outpoint == OutPoint::new(funding.txid, funding.vout)
+    ));
+
+    let cpfp = manual_cpfp(OutPoint::new(funding.txid, funding.vout), &tx, &change_key);
+
+    // Paid to another key
+    assert!(matches!(
+        harness.coordinator().import_external_speedup(
+            cpfp.clone(),
+            vec![tx.compute_txid()],
+            0,
+            funding_key,
+        ),
+        Err(BitcoinCoordinatorError::ExternalSpeedupChangeMismatch(
+            _,
+            0,
+            _
+        ))
+    ));
+
+    // No such output
+    assert!(matches!(
+        harness.coordinator().import_external_speedup(
+            cpfp.clone(),
+            vec![tx.compute_txid()],
+            1,
+            change_key,
+        ),
+        Err(BitcoinCoordinatorError::ExternalSpeedupChangeMismatch(
+            _,
+            1,
+            _
+        ))
+    ));
+
+    // Declared to pay for a transaction it does not spend
+    let (other_tx, speedup_data) = tx_with_anchor(&anchor_key, 0, 2);
+    harness.dispatch(other_tx.clone(), Some(speedup_data), "Other tx")?;
+    assert!(matches!(
+        harness.coordinator().import_external_speedup(
+            cpfp.clone(),
+            vec![tx.compute_txid(), other_tx.compute_txid()],
+            0,
+            change_key,
+        ),
+        Err(BitcoinCoordinatorError::ExternalSpeedupParentNotSpent(_, txid))
+            if txid == other_tx.compute_txid()
+    ));
+
+    assert!(matches!(
+        harness
+            .coordinator()
+            .import_external_speedup(cpfp.clone(), vec![], 0, change_key),
+        Err(BitcoinCoordinatorError::InvalidArgument(_))
+    ));
+
+    // Nothing was saved, the funding is still the same
+    assert!(store.get_speedup(&cpfp.compute_txid()).is_err());
+    assert_eq!(
+        harness
+            .coordinator()
+            .get_funding_summary()?
+            .funding
+            .unwrap()
+            .txid,
+        funding.txid
+    );
+    assert_eq!(store.get_deferred_speedup_txs()?, vec![tx.compute_txid()]);
+
+    clear_output();
+    Ok(())
+}
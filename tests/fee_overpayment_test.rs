@@ -0,0 +1,193 @@
+use bitcoin::{
+    absolute::LockTime, transaction::Version, Amount, OutPoint, PublicKey, ScriptBuf, Sequence,
+    Transaction, TxIn, TxOut, Witness,
+};
+use bitcoin_coordinator::{
+    config::CoordinatorSettingsConfig,
+    coordinator::BitcoinCoordinatorApi,
+    cpfp::SpeedupOutputKind,
+    speedup::SpeedupStore,
+    storage::BitcoinCoordinatorStoreApi,
+    testing::CoordinatorTestHarness,
+    types::{AckCoordinatorNews, AckNews, CoordinatorNews, DispatchOptions, TransactionState},
+};
+use key_manager::key_type::BitcoinKeyType;
+use protocol_builder::types::{output::SpeedupData, Utxo};
+use utils::{clear_output, get_mocks};
+mod utils;
+
+const FINALITY_CONFIRMATIONS: u32 = 3;
+
+// Spends `funding` paying `fee` sats, with an anchor output when `anchor_key` is given.
+fn tx_spending(funding: &Utxo, fee: u64, anchor_key: Option<&PublicKey>) -> Transaction {
+    let mut output = vec![TxOut {
+        value: Amount::from_sat(funding.amount - fee),
+        script_pubkey: ScriptBuf::new(),
+    }];
+
+    if let Some(anchor_key) = anchor_key {
+        output.push(TxOut {
+            value: Amount::from_sat(0),
+            script_pubkey: SpeedupOutputKind::P2trKeyPath
+                .script_pubkey(anchor_key)
+                .unwrap(),
+        });
+    }
+
+    Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint::new(funding.txid, funding.vout),
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+            witness: Witness::new(),
+        }],
+        output,
+    }
+}
+
+fn options() -> DispatchOptions {
+    DispatchOptions {
+        finality_confirmations: Some(FINALITY_CONFIRMATIONS),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_overpaid_tx_is_reported() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 0)?;
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+
+    // The transaction pays 10000 sats by itself, far above the estimate of 2 sat/vB
+    let funding = harness.fund(&key, 100_000)?;
+    let tx = tx_spending(&funding, 10_000, None);
+    let tx_id = tx.compute_txid();
+    let vsize = tx.vsize() as u64;
+
+    harness.coordinator().dispatch_with_options(
+        tx,
+        None,
+        "My tx".to_string(),
+        None,
+        None,
+        options(),
+    )?;
+    harness.tick()?;
+    harness.mine_blocks(1);
+    harness.tick()?;
+
+    // The estimate when the confirmation is seen is the reference, a later change is not
+    assert_eq!(
+        store.get_tx(&tx_id)?.fee_rate_at_confirmation,
+        Some(CoordinatorTestHarness::INITIAL_FEE_RATE)
+    );
+    harness.set_fee_rate(50);
+    harness.mine_blocks(FINALITY_CONFIRMATIONS as u64);
+    harness.tick()?;
+
+    let tx = store.get_tx(&tx_id)?;
+    assert_eq!(tx.state, TransactionState::Finalized);
+
+    let report = tx.fee_report.expect("the report is saved on finalization");
+    let effective_fee_rate = 10_000.0 / vsize as f64;
+    assert_eq!(report.parent_fee, Some(10_000));
+    assert_eq!(report.speedup_fees, 0);
+    assert_eq!(report.vsize, vsize);
+    assert_eq!(report.effective_fee_rate, effective_fee_rate);
+    assert_eq!(
+        report.reference_fee_rate,
+        Some(CoordinatorTestHarness::INITIAL_FEE_RATE)
+    );
+
+    // The numbers are in the history and in the stats
+    let history = harness.coordinator().get_transaction_history(tx_id)?;
+    assert_eq!(history.fee_report, Some(report.clone()));
+    let stats = store.get_finalized_tx_stats()?;
+    assert_eq!(stats.last().unwrap().fee_report, Some(report));
+
+    let news = harness.coordinator().get_news()?;
+    let overpayment = CoordinatorNews::FeeOverpayment(
+        tx_id,
+        effective_fee_rate,
+        CoordinatorTestHarness::INITIAL_FEE_RATE,
+    );
+    assert!(news.coordinator_news.contains(&overpayment));
+
+    harness
+        .coordinator()
+        .ack_news(AckNews::Coordinator(AckCoordinatorNews::FeeOverpayment(
+            tx_id,
+        )))?;
+    let news = harness.coordinator().get_news()?;
+    assert!(!news.coordinator_news.contains(&overpayment));
+
+    clear_output();
+    Ok(())
+}
+
+#[test]
+fn test_package_fee_rate_includes_speedups() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 0)?;
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 1)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 2)?;
+
+    // The threshold is raised above the ratio paid, nothing is reported
+    let settings = CoordinatorSettingsConfig {
+        fee_overpayment_ratio: Some(50.0),
+        ..Default::default()
+    };
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, Some(settings))?;
+
+    let funding = harness.fund(&funding_key, 1_000_000)?;
+    harness.coordinator().add_funding(funding)?;
+
+    // The transaction pays 100 sats, its CPFP pays the rest of the package
+    let parent_funding = harness.fund(&key, 100_000)?;
+    let tx = tx_spending(&parent_funding, 100, Some(&anchor_key));
+    let tx_id = tx.compute_txid();
+    let vsize = tx.vsize() as u64;
+    let speedup_data = SpeedupData::new(Utxo::new(tx_id, 1, 0, &anchor_key));
+
+    harness.coordinator().dispatch_with_options(
+        tx,
+        Some(speedup_data),
+        "My tx".to_string(),
+        None,
+        None,
+        options(),
+    )?;
+    harness.tick()?;
+    harness.mine_blocks(1);
+    harness.tick()?;
+    harness.mine_blocks(FINALITY_CONFIRMATIONS as u64);
+    harness.tick()?;
+
+    let speedups = store.get_speedups_for_tx(&tx_id)?;
+    assert_eq!(speedups.len(), 1);
+    let speedup = &speedups[0];
+    assert!(speedup.vsize > 0);
+
+    let report = store
+        .get_tx(&tx_id)?
+        .fee_report
+        .expect("the report is saved on finalization");
+    assert_eq!(report.parent_fee, Some(100));
+    assert_eq!(report.speedup_fees, speedup.fee);
+    assert_eq!(report.vsize, vsize + speedup.vsize);
+    // The shares of the speedup are computed apart, the rate can differ in the last digit
+    let effective_fee_rate = (100 + speedup.fee) as f64 / (vsize + speedup.vsize) as f64;
+    assert!((report.effective_fee_rate - effective_fee_rate).abs() < 1e-9);
+    assert!(report.overpayment_ratio().unwrap() < 50.0);
+
+    let news = harness.coordinator().get_news()?;
+    assert!(!news
+        .coordinator_news
+        .iter()
+        .any(|news| matches!(news, CoordinatorNews::FeeOverpayment(..))));
+
+    clear_output();
+    Ok(())
+}
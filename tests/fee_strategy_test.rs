@@ -0,0 +1,276 @@
+use bitcoin::{hashes::Hash, BlockHash};
+use bitcoin_coordinator::{
+    config::{CoordinatorSettingsConfig, FeeEstimateMode, FeeStrategy},
+    coordinator::{BitcoinCoordinator, BitcoinCoordinatorApi},
+    errors::BitcoinCoordinatorError,
+    fee::{FeeRateEstimate, FeeRateEstimator, FeeRateProvider},
+    types::{AckCoordinatorNews, AckNews, CoordinatorNews},
+};
+use bitcoincore_rpc::{Auth, Client};
+use bitvmx_transaction_monitor::{errors::MonitorError, types::FullBlock};
+use std::{cell::Cell, rc::Rc};
+use utils::{clear_output, get_mocks};
+mod utils;
+
+const MIN_NETWORK_FEE_RATE: u64 = 2;
+
+// Returns the same fee rate every time and counts how many times it was asked.
+struct CountingProvider {
+    fee_rate: Option<u64>,
+    calls: Cell<usize>,
+}
+
+impl CountingProvider {
+    fn new(fee_rate: Option<u64>) -> Rc<Self> {
+        Rc::new(Self {
+            fee_rate,
+            calls: Cell::new(0),
+        })
+    }
+}
+
+impl FeeRateProvider for CountingProvider {
+    fn get_fee_rate(&self) -> Result<Option<u64>, BitcoinCoordinatorError> {
+        self.calls.set(self.calls.get() + 1);
+        Ok(self.fee_rate)
+    }
+}
+
+fn unused_smart_fee(
+    _: Option<u16>,
+    _: Option<FeeEstimateMode>,
+) -> Result<Option<u64>, BitcoinCoordinatorError> {
+    panic!("the node must not be asked for a fee estimate");
+}
+
+#[test]
+fn test_fixed_strategy() {
+    let estimator = FeeRateEstimator::new(FeeStrategy::Fixed(25), MIN_NETWORK_FEE_RATE);
+    assert_eq!(estimator.estimate(unused_smart_fee), 25);
+
+    // The floor is applied to a fixed fee rate as well
+    let estimator = FeeRateEstimator::new(FeeStrategy::Fixed(1), MIN_NETWORK_FEE_RATE);
+    assert_eq!(estimator.estimate(unused_smart_fee), MIN_NETWORK_FEE_RATE);
+}
+
+#[test]
+fn test_smart_fee_strategy_applies_floor() {
+    let strategy = FeeStrategy::SmartFee {
+        conf_target: Some(2),
+        mode: Some(FeeEstimateMode::Conservative),
+    };
+
+    let estimator = FeeRateEstimator::new(strategy.clone(), MIN_NETWORK_FEE_RATE);
+    let fee_rate = estimator.estimate(|conf_target, mode| {
+        assert_eq!(conf_target, Some(2));
+        assert_eq!(mode, Some(FeeEstimateMode::Conservative));
+        Ok(Some(12))
+    });
+    assert_eq!(fee_rate, 12);
+
+    // No estimate, a zero estimate and a failed estimate use the min network fee rate
+    for estimate in [
+        Ok(None),
+        Ok(Some(0)),
+        Err(BitcoinCoordinatorError::BitcoinCoordinatorError(
+            "estimatesmartfee failed".to_string(),
+        )),
+    ] {
+        let estimator = FeeRateEstimator::new(strategy.clone(), MIN_NETWORK_FEE_RATE);
+        assert_eq!(estimator.estimate(|_, _| estimate), MIN_NETWORK_FEE_RATE);
+    }
+
+    // The default strategy uses the node defaults
+    let estimator = FeeRateEstimator::new(FeeStrategy::default(), MIN_NETWORK_FEE_RATE);
+    let fee_rate = estimator.estimate(|conf_target, mode| {
+        assert_eq!((conf_target, mode), (None, None));
+        Ok(Some(7))
+    });
+    assert_eq!(fee_rate, 7);
+}
+
+#[test]
+fn test_external_provider_is_asked_once_per_tick() {
+    let provider = CountingProvider::new(Some(30));
+    let estimator = FeeRateEstimator::new(FeeStrategy::External, MIN_NETWORK_FEE_RATE)
+        .with_provider(provider.clone());
+
+    // First tick: every speedup of the tick uses the first estimate
+    estimator.reset();
+    for _ in 0..3 {
+        assert_eq!(estimator.estimate(unused_smart_fee), 30);
+    }
+    assert_eq!(provider.calls.get(), 1);
+
+    // Second tick
+    estimator.reset();
+    assert_eq!(estimator.estimate(unused_smart_fee), 30);
+    assert_eq!(estimator.estimate(unused_smart_fee), 30);
+    assert_eq!(provider.calls.get(), 2);
+
+    // A provider without estimate uses the min network fee rate
+    let provider = CountingProvider::new(None);
+    let estimator = FeeRateEstimator::new(FeeStrategy::External, MIN_NETWORK_FEE_RATE)
+        .with_provider(provider.clone());
+    assert_eq!(estimator.estimate(unused_smart_fee), MIN_NETWORK_FEE_RATE);
+
+    // Without a provider the min network fee rate is used
+    let estimator = FeeRateEstimator::new(FeeStrategy::External, MIN_NETWORK_FEE_RATE);
+    assert_eq!(estimator.estimate(unused_smart_fee), MIN_NETWORK_FEE_RATE);
+}
+
+#[test]
+fn test_fee_strategy_validation() {
+    let settings = |fee_strategy| CoordinatorSettingsConfig {
+        fee_strategy: Some(fee_strategy),
+        ..Default::default()
+    };
+
+    assert!(settings(FeeStrategy::Fixed(10)).validate().is_ok());
+    assert!(settings(FeeStrategy::External).validate().is_ok());
+    assert!(settings(FeeStrategy::SmartFee {
+        conf_target: Some(6),
+        mode: Some(FeeEstimateMode::Economical),
+    })
+    .validate()
+    .is_ok());
+
+    assert!(matches!(
+        settings(FeeStrategy::Fixed(0)).validate(),
+        Err(BitcoinCoordinatorError::InvalidConfiguration(_))
+    ));
+    assert!(matches!(
+        settings(FeeStrategy::SmartFee {
+            conf_target: Some(0),
+            mode: None,
+        })
+        .validate(),
+        Err(BitcoinCoordinatorError::InvalidConfiguration(_))
+    ));
+}
+
+#[test]
+fn test_fallback_to_mempool_min_fee() {
+    let estimator = FeeRateEstimator::new(FeeStrategy::default(), MIN_NETWORK_FEE_RATE);
+
+    // An estimate does not ask for the mempool min fee
+    let estimate = estimator.estimate_with_fallback(
+        |_, _| Ok(Some(12)),
+        || panic!("the mempool min fee must not be asked"),
+    );
+    assert_eq!(
+        estimate,
+        FeeRateEstimate {
+            fee_rate: 12,
+            fallback: false,
+        }
+    );
+
+    // No estimate, a zero estimate and a failed estimate use the mempool min fee, never below the floor
+    for estimate in [
+        Ok(None),
+        Ok(Some(0)),
+        Err(BitcoinCoordinatorError::BitcoinCoordinatorError(
+            "estimatesmartfee failed".to_string(),
+        )),
+    ] {
+        let estimator = FeeRateEstimator::new(FeeStrategy::default(), MIN_NETWORK_FEE_RATE);
+        assert_eq!(
+            estimator.estimate_with_fallback(|_, _| estimate, || Ok(Some(5))),
+            FeeRateEstimate {
+                fee_rate: 5,
+                fallback: true,
+            }
+        );
+
+        estimator.reset();
+        assert_eq!(
+            estimator.estimate_with_fallback(|_, _| Ok(None), || Ok(Some(1))),
+            FeeRateEstimate {
+                fee_rate: MIN_NETWORK_FEE_RATE,
+                fallback: true,
+            }
+        );
+    }
+
+    // Without a mempool min fee the min network fee rate is used
+    for mempool_min_fee in [
+        Ok(None),
+        Ok(Some(0)),
+        Err(BitcoinCoordinatorError::BitcoinCoordinatorError(
+            "getmempoolinfo failed".to_string(),
+        )),
+    ] {
+        let estimator = FeeRateEstimator::new(FeeStrategy::default(), MIN_NETWORK_FEE_RATE);
+        assert_eq!(
+            estimator.estimate_with_fallback(|_, _| Ok(None), || mempool_min_fee),
+            FeeRateEstimate {
+                fee_rate: MIN_NETWORK_FEE_RATE,
+                fallback: true,
+            }
+        );
+    }
+}
+
+// A node without fee estimates (a fresh regtest node) returns an error or zero. The min network fee rate
+// is used and FeeEstimateUnavailable is reported once per block.
+#[test]
+fn test_fee_estimate_unavailable_news() -> Result<(), anyhow::Error> {
+    const FLOOR: u64 = 3;
+
+    let estimates: [fn() -> Result<u64, MonitorError>; 2] = [
+        || {
+            Err(MonitorError::UnexpectedError(
+                "Insufficient data".to_string(),
+            ))
+        },
+        || Ok(0),
+    ];
+
+    for estimate in estimates {
+        let (mut mock_monitor, store, mock_bitcoin_client, key_manager) = get_mocks();
+
+        mock_monitor
+            .expect_get_estimated_fee_rate()
+            .returning(estimate);
+        mock_monitor.expect_get_current_block().returning(|| {
+            Ok(Some(FullBlock {
+                height: 100,
+                hash: BlockHash::all_zeros(),
+                prev_hash: BlockHash::all_zeros(),
+                txs: vec![],
+                orphan: false,
+            }))
+        });
+        mock_monitor.expect_get_news().returning(|| Ok(vec![]));
+
+        // No node at this address, the mempool min fee is not available either
+        let coordinator = BitcoinCoordinator::builder()
+            .with_monitor(Box::new(mock_monitor))
+            .with_store(store)
+            .with_client(Box::new(mock_bitcoin_client))
+            .with_rpc_client(Client::new("http://127.0.0.1:18443", Auth::None)?)
+            .with_key_manager(key_manager)
+            .with_settings(CoordinatorSettingsConfig {
+                min_network_fee_rate: Some(FLOOR),
+                ..Default::default()
+            })
+            .build()?;
+
+        // Both ask for the network fee rate in the same block
+        coordinator.get_funding_summary()?;
+        coordinator.get_funding_summary()?;
+
+        let news = coordinator.get_news()?.coordinator_news;
+        assert_eq!(news, vec![CoordinatorNews::FeeEstimateUnavailable(FLOOR)]);
+
+        coordinator.ack_news(AckNews::Coordinator(
+            AckCoordinatorNews::FeeEstimateUnavailable,
+        ))?;
+        assert!(coordinator.get_news()?.coordinator_news.is_empty());
+
+        clear_output();
+    }
+
+    Ok(())
+}
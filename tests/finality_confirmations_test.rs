@@ -0,0 +1,167 @@
+use bitcoin_coordinator::{
+    config::CoordinatorSettingsConfig,
+    coordinator::BitcoinCoordinatorApi,
+    errors::BitcoinCoordinatorError,
+    storage::BitcoinCoordinatorStoreApi,
+    testing::CoordinatorTestHarness,
+    types::{DispatchOptions, TransactionState},
+    TypesToMonitor,
+};
+use bitvmx_transaction_monitor::config::MonitorSettingsConfig;
+use utils::{clear_output, get_mocks, simple_tx};
+mod utils;
+
+const MAX_MONITORING_CONFIRMATIONS: u32 = 10;
+
+fn options(finality_confirmations: Option<u32>) -> DispatchOptions {
+    DispatchOptions {
+        finality_confirmations,
+        ..Default::default()
+    }
+}
+
+fn harness_settings() -> CoordinatorSettingsConfig {
+    CoordinatorSettingsConfig {
+        monitor_settings: Some(MonitorSettingsConfig {
+            max_monitoring_confirmations: Some(MAX_MONITORING_CONFIRMATIONS),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+// Two transactions with different finality thresholds: after 3 confirmations only the one
+// that needs a single confirmation is finalized and leaves the in-progress list.
+#[test]
+fn test_dispatch_with_finality_confirmations() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let harness =
+        CoordinatorTestHarness::new(store.store.clone(), key_manager, Some(harness_settings()))?;
+
+    let fast_tx = simple_tx(1);
+    let slow_tx = simple_tx(2);
+
+    for (tx, finality, context) in [(&fast_tx, 1, "fast"), (&slow_tx, 6, "slow")] {
+        harness.coordinator().dispatch_with_options(
+            tx.clone(),
+            None,
+            context.to_string(),
+            None,
+            None,
+            options(Some(finality)),
+        )?;
+    }
+
+    harness.tick()?;
+    assert!(harness.chain().in_mempool(&fast_tx.compute_txid()));
+    assert!(harness.chain().in_mempool(&slow_tx.compute_txid()));
+
+    harness.mine_blocks(3);
+    harness.tick()?;
+
+    let fast = store.get_tx(&fast_tx.compute_txid())?;
+    assert_eq!(fast.state, TransactionState::Finalized);
+    assert_eq!(fast.dispatch_options.finality_confirmations, Some(1));
+
+    let slow = store.get_tx(&slow_tx.compute_txid())?;
+    assert_eq!(slow.state, TransactionState::Confirmed);
+
+    let in_progress: Vec<_> = store
+        .get_txs_in_progress()?
+        .into_iter()
+        .map(|tx| tx.tx_id)
+        .collect();
+    assert!(!in_progress.contains(&fast_tx.compute_txid()));
+    assert!(in_progress.contains(&slow_tx.compute_txid()));
+
+    // The second transaction follows its own threshold
+    harness.mine_blocks(3);
+    harness.tick()?;
+    assert_eq!(
+        store.get_tx(&slow_tx.compute_txid())?.state,
+        TransactionState::Finalized
+    );
+    assert!(store.get_txs_in_progress()?.is_empty());
+
+    clear_output();
+    Ok(())
+}
+
+#[test]
+fn test_monitor_with_finality_confirmations() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let harness =
+        CoordinatorTestHarness::new(store.store.clone(), key_manager, Some(harness_settings()))?;
+
+    // A transaction sent by someone else, only watched by the coordinator
+    let tx = simple_tx(3);
+    let tx_id = tx.compute_txid();
+    harness.chain().send_transaction(&tx).unwrap();
+
+    harness.coordinator().monitor_with_options(
+        TypesToMonitor::Transactions(vec![tx_id], "watched".to_string(), None),
+        Some(2),
+    )?;
+
+    let watched = store.get_watched_finalities()?;
+    assert_eq!(watched.len(), 1);
+    assert_eq!(watched[0].tx_id, tx_id);
+    assert_eq!(watched[0].finality_confirmations, 2);
+
+    harness.mine_blocks(1);
+    harness.tick()?;
+    assert_eq!(store.get_watched_finalities()?.len(), 1);
+
+    harness.mine_blocks(1);
+    harness.tick()?;
+    assert!(store.get_watched_finalities()?.is_empty());
+
+    clear_output();
+    Ok(())
+}
+
+#[test]
+fn test_invalid_finality_confirmations() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let harness =
+        CoordinatorTestHarness::new(store.store.clone(), key_manager, Some(harness_settings()))?;
+
+    for finality in [0, MAX_MONITORING_CONFIRMATIONS + 1] {
+        let result = harness.coordinator().dispatch_with_options(
+            simple_tx(4),
+            None,
+            "invalid".to_string(),
+            None,
+            None,
+            options(Some(finality)),
+        );
+        assert!(matches!(
+            result,
+            Err(BitcoinCoordinatorError::InvalidConfiguration(_))
+        ));
+
+        let result = harness.coordinator().monitor_with_options(
+            TypesToMonitor::Transactions(vec![simple_tx(4).compute_txid()], String::new(), None),
+            Some(finality),
+        );
+        assert!(matches!(
+            result,
+            Err(BitcoinCoordinatorError::InvalidConfiguration(_))
+        ));
+    }
+
+    // Only transactions can be finalized earlier
+    let result = harness
+        .coordinator()
+        .monitor_with_options(TypesToMonitor::NewBlock, Some(1));
+    assert!(matches!(
+        result,
+        Err(BitcoinCoordinatorError::InvalidConfiguration(_))
+    ));
+
+    assert!(store.get_txs_in_progress()?.is_empty());
+    assert!(store.get_watched_finalities()?.is_empty());
+
+    clear_output();
+    Ok(())
+}
@@ -0,0 +1,148 @@
+use bitcoin::{hashes::Hash, BlockHash, Transaction, Txid};
+use bitcoin_coordinator::{
+    coordinator::BitcoinCoordinatorApi,
+    errors::BitcoinCoordinatorError,
+    finalized::FinalizedSink,
+    storage::BitcoinCoordinatorStoreApi,
+    testing::CoordinatorTestHarness,
+    types::{DispatchOptions, FinalizedTxEntry},
+};
+use std::{cell::RefCell, rc::Rc};
+use utils::{clear_output, get_mocks, simple_tx};
+mod utils;
+
+// Dispatches the transaction to be finalized with its first confirmation.
+fn dispatch(harness: &CoordinatorTestHarness, tx: &Transaction, context: &str) {
+    harness
+        .coordinator()
+        .dispatch_with_options(
+            tx.clone(),
+            None,
+            context.to_string(),
+            None,
+            None,
+            DispatchOptions {
+                finality_confirmations: Some(1),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+}
+
+// Takes the entries, failing once for each txid in `fail_once`.
+#[derive(Default)]
+struct RecordingSink {
+    delivered: RefCell<Vec<Txid>>,
+    fail_once: RefCell<Vec<Txid>>,
+}
+
+impl FinalizedSink for RecordingSink {
+    fn deliver(&self, entry: &FinalizedTxEntry) -> Result<(), BitcoinCoordinatorError> {
+        let mut fail_once = self.fail_once.borrow_mut();
+
+        if let Some(index) = fail_once.iter().position(|txid| *txid == entry.tx_id) {
+            fail_once.remove(index);
+            return Err(BitcoinCoordinatorError::BitcoinCoordinatorError(
+                "settlement backend unavailable".to_string(),
+            ));
+        }
+
+        self.delivered.borrow_mut().push(entry.tx_id);
+        Ok(())
+    }
+}
+
+// Transactions finalized across ticks are logged in order, and the log is read in pages.
+#[test]
+fn test_finalized_log_paging() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+    let txs: Vec<Transaction> = (1..=3).map(simple_tx).collect();
+
+    dispatch(&harness, &txs[0], "first");
+    harness.tick()?;
+    let first_block = harness.mine_blocks(1)[0];
+    harness.tick()?;
+
+    dispatch(&harness, &txs[1], "second");
+    dispatch(&harness, &txs[2], "third");
+    harness.tick()?;
+    let second_block = harness.mine_blocks(1)[0];
+    harness.tick()?;
+
+    let page = harness.coordinator().read_finalized(0, 2)?;
+    assert_eq!(page.len(), 2);
+    assert_eq!(page[0].seq, 0);
+    assert_eq!(page[0].tx_id, txs[0].compute_txid());
+    assert_eq!(page[0].context, "first");
+    assert_eq!(page[0].block_hash, first_block);
+    assert_eq!(page[1].seq, 1);
+    assert_eq!(page[1].block_hash, second_block);
+    assert_eq!(page[1].block_height, page[0].block_height + 1);
+    assert!(page.iter().all(|entry| entry.total_fee > 0));
+
+    let page = harness.coordinator().read_finalized(2, 2)?;
+    assert_eq!(page.len(), 1);
+    assert_eq!(page[0].seq, 2);
+
+    let tx_ids: Vec<Txid> = harness
+        .coordinator()
+        .read_finalized(0, 10)?
+        .iter()
+        .map(|entry| entry.tx_id)
+        .collect();
+    assert_eq!(tx_ids.len(), 3);
+    assert_eq!(tx_ids[0], txs[0].compute_txid());
+    assert!(tx_ids.contains(&txs[1].compute_txid()));
+    assert!(tx_ids.contains(&txs[2].compute_txid()));
+
+    // A finalization already logged in the block is not logged again, a finalization in another block is
+    let tx_id = txs[0].compute_txid();
+    let height = page[0].block_height;
+    assert!(store.finalize_tx(tx_id, height, first_block)?.is_none());
+    let other_block = BlockHash::all_zeros();
+    let entry = store.finalize_tx(tx_id, height + 1, other_block)?.unwrap();
+    assert_eq!(entry.seq, 3);
+    assert_eq!(harness.coordinator().read_finalized(3, 10)?, vec![entry]);
+
+    clear_output();
+    Ok(())
+}
+
+// A failing sink does not fail the tick, only the entries it failed to take are handed to it again.
+#[test]
+fn test_failing_sink_retries_failed_entries() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let sink = Rc::new(RecordingSink::default());
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?
+        .with_finalized_sink(sink.clone());
+    let txs: Vec<Transaction> = (1..=3).map(simple_tx).collect();
+    let tx_ids: Vec<Txid> = txs.iter().map(|tx| tx.compute_txid()).collect();
+
+    sink.fail_once.borrow_mut().push(tx_ids[1]);
+    for (index, tx) in txs.iter().enumerate() {
+        dispatch(&harness, tx, &format!("tx {index}"));
+    }
+    harness.tick()?;
+    harness.mine_blocks(1);
+    harness.tick()?;
+
+    let mut delivered = sink.delivered.borrow().clone();
+    delivered.sort();
+    let mut expected = vec![tx_ids[0], tx_ids[2]];
+    expected.sort();
+    assert_eq!(delivered, expected);
+    assert_eq!(store.get_finalized_delivery()?.failed.len(), 1);
+
+    harness.tick()?;
+    assert_eq!(sink.delivered.borrow().len(), 3);
+    assert_eq!(sink.delivered.borrow()[2], tx_ids[1]);
+    assert!(store.get_finalized_delivery()?.failed.is_empty());
+
+    // Nothing left to hand
+    harness.tick()?;
+    assert_eq!(sink.delivered.borrow().len(), 3);
+
+    clear_output();
+    Ok(())
+}
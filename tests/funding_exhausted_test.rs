@@ -0,0 +1,129 @@
+use bitcoin::{Amount, OutPoint, Transaction};
+use bitcoin_coordinator::{
+    coordinator::BitcoinCoordinatorApi,
+    speedup::SpeedupStore,
+    storage::BitcoinCoordinatorStoreApi,
+    testing::CoordinatorTestHarness,
+    types::{AckCoordinatorNews, AckNews, CoordinatorNews},
+};
+use key_manager::key_type::BitcoinKeyType;
+use utils::{clear_output, get_mocks, tx_with_anchor};
+mod utils;
+
+// Ephemeral anchor, the funding pays the whole fee
+const ANCHOR_AMOUNT: u64 = 0;
+const FEE_RATE: u64 = 100;
+const DUST_CHANGE: u64 = 100;
+
+fn cpfp_spending(harness: &CoordinatorTestHarness, outpoint: OutPoint) -> Option<Transaction> {
+    harness.chain().mempool().into_iter().find(|tx| {
+        tx.input
+            .iter()
+            .any(|input| input.previous_output == outpoint)
+    })
+}
+
+// Fee of the CPFP paying for a transaction with an anchor, from a funding big enough to keep its change.
+fn cpfp_fee() -> Result<u64, anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+    harness.set_fee_rate(FEE_RATE);
+
+    let funding = harness.fund(&funding_key, 1_000_000)?;
+    harness.coordinator().add_funding(funding.clone())?;
+
+    let (tx, speedup_data) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 1);
+    harness.dispatch(tx, Some(speedup_data), "My tx")?;
+    harness.tick()?;
+
+    let cpfp = cpfp_spending(&harness, OutPoint::new(funding.txid, funding.vout)).unwrap();
+    Ok(funding.amount + ANCHOR_AMOUNT - cpfp.output[0].value.to_sat())
+}
+
+#[test]
+fn test_dust_change_is_added_to_the_fee() -> Result<(), anyhow::Error> {
+    let fee = cpfp_fee()?;
+
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+    harness.set_fee_rate(FEE_RATE);
+
+    // The funding leaves a change below the dust threshold once the same CPFP fee is paid.
+    let funding = harness.fund(&funding_key, fee + DUST_CHANGE)?;
+    harness.coordinator().add_funding(funding.clone())?;
+    assert!(store.can_speedup()?);
+
+    let (tx, speedup_data) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 1);
+    harness.dispatch(tx.clone(), Some(speedup_data), "My tx")?;
+    harness.tick()?;
+
+    // The CPFP has no change output, the whole funding and the anchor are paid as fee
+    let cpfp = cpfp_spending(&harness, OutPoint::new(funding.txid, funding.vout)).unwrap();
+    assert_eq!(cpfp.output.len(), 1);
+    assert!(cpfp.output[0].script_pubkey.is_op_return());
+    assert_eq!(cpfp.output[0].value, Amount::ZERO);
+    assert!(cpfp
+        .input
+        .iter()
+        .any(|input| input.previous_output == OutPoint::new(tx.compute_txid(), 0)));
+
+    let news = harness.coordinator().get_news()?.coordinator_news;
+    assert!(news.contains(&CoordinatorNews::FundingExhausted(
+        cpfp.compute_txid(),
+        DUST_CHANGE
+    )));
+    assert!(news.iter().any(|news| matches!(
+        news,
+        CoordinatorNews::SpeedupCreated(txid, _, paid_fee, _, false)
+            if *txid == cpfp.compute_txid() && *paid_fee == fee + DUST_CHANGE
+    )));
+
+    // The chain ends with the CPFP, its OP_RETURN output is not funding
+    assert!(!store.can_speedup()?);
+    assert!(store.get_funding()?.is_none());
+    let speedup = store.get_speedup(&cpfp.compute_txid())?;
+    assert_eq!(speedup.exhausted_change, Some(DUST_CHANGE));
+    assert!(speedup.change_funding().is_none());
+
+    // The next transaction waits for new funding
+    let (next_tx, next_speedup_data) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 2);
+    harness.dispatch(next_tx.clone(), Some(next_speedup_data), "My next tx")?;
+    harness.tick()?;
+    assert!(!harness.chain().in_mempool(&next_tx.compute_txid()));
+    assert!(harness
+        .coordinator()
+        .get_news()?
+        .coordinator_news
+        .contains(&CoordinatorNews::FundingNotFound));
+
+    let new_funding = harness.fund(&funding_key, 1_000_000)?;
+    harness.coordinator().add_funding(new_funding.clone())?;
+    harness.tick()?;
+
+    let next_cpfp =
+        cpfp_spending(&harness, OutPoint::new(new_funding.txid, new_funding.vout)).unwrap();
+    assert!(harness.chain().in_mempool(&next_tx.compute_txid()));
+    assert!(next_cpfp
+        .input
+        .iter()
+        .any(|input| input.previous_output == OutPoint::new(next_tx.compute_txid(), 0)));
+
+    harness
+        .coordinator()
+        .ack_news(AckNews::Coordinator(AckCoordinatorNews::FundingExhausted(
+            cpfp.compute_txid(),
+        )))?;
+    assert!(!harness
+        .coordinator()
+        .get_news()?
+        .coordinator_news
+        .iter()
+        .any(|news| news.kind() == "FundingExhausted"));
+
+    clear_output();
+    Ok(())
+}
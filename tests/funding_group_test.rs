@@ -0,0 +1,163 @@
+use bitcoin::{OutPoint, PublicKey, Transaction};
+use bitcoin_coordinator::{
+    config::CoordinatorSettingsConfig,
+    coordinator::BitcoinCoordinatorApi,
+    errors::BitcoinCoordinatorError,
+    speedup::SpeedupStore,
+    storage::{BitcoinCoordinatorStore, BitcoinCoordinatorStoreApi},
+    testing::CoordinatorTestHarness,
+    types::{DispatchOptions, TransactionState},
+};
+use key_manager::key_type::BitcoinKeyType;
+use protocol_builder::types::Utxo;
+use utils::{clear_output, get_mocks, tx_with_anchor};
+mod utils;
+
+const ANCHOR_AMOUNT: u64 = 540;
+const FUNDING_AMOUNT: u64 = 100_000;
+
+// Two funding groups, each with its own funding. A group can only have one unconfirmed speedup.
+fn setup() -> Result<
+    (
+        CoordinatorTestHarness,
+        BitcoinCoordinatorStore,
+        PublicKey,
+        Utxo,
+        Utxo,
+    ),
+    anyhow::Error,
+> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+
+    let settings = CoordinatorSettingsConfig {
+        max_unconfirmed_speedups: Some(1),
+        ..Default::default()
+    };
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, Some(settings))?;
+
+    let funding_a = harness.fund(&funding_key, FUNDING_AMOUNT)?;
+    let funding_b = harness.fund(&funding_key, FUNDING_AMOUNT)?;
+    harness
+        .coordinator()
+        .add_funding_group("session_a", funding_a.clone())?;
+    harness
+        .coordinator()
+        .add_funding_group("session_b", funding_b.clone())?;
+
+    Ok((harness, store, anchor_key, funding_a, funding_b))
+}
+
+fn dispatch_in_group(
+    harness: &CoordinatorTestHarness,
+    anchor_key: &PublicKey,
+    seed: u32,
+    group: &str,
+) -> Result<Transaction, BitcoinCoordinatorError> {
+    let (tx, speedup_data) = tx_with_anchor(anchor_key, ANCHOR_AMOUNT, seed);
+
+    harness.coordinator().dispatch_with_options(
+        tx.clone(),
+        Some(speedup_data),
+        "My tx".to_string(),
+        None,
+        None,
+        DispatchOptions {
+            funding_group: Some(group.to_string()),
+            ..Default::default()
+        },
+    )?;
+
+    Ok(tx)
+}
+
+// The CPFP spending the given outpoint, if it is in the mempool.
+fn cpfp_spending(harness: &CoordinatorTestHarness, outpoint: OutPoint) -> Option<Transaction> {
+    harness.chain().mempool().into_iter().find(|tx| {
+        tx.input
+            .iter()
+            .any(|input| input.previous_output == outpoint)
+    })
+}
+
+#[test]
+fn test_funding_groups_are_isolated() -> Result<(), anyhow::Error> {
+    let (harness, store, anchor_key, funding_a, funding_b) = setup()?;
+
+    // The first transaction of session A takes the only unconfirmed speedup slot of the group
+    let tx_a1 = dispatch_in_group(&harness, &anchor_key, 1, "session_a")?;
+    harness.tick()?;
+
+    let cpfp_a1 = cpfp_spending(&harness, OutPoint::new(funding_a.txid, funding_a.vout)).unwrap();
+    assert!(cpfp_a1
+        .input
+        .iter()
+        .any(|input| input.previous_output == OutPoint::new(tx_a1.compute_txid(), 0)));
+    assert!(store.with_funding_group(Some("session_a"), || {
+        store.has_reached_max_unconfirmed_speedups()
+    })?);
+
+    // Session A waits for its speedup to be confirmed, session B is sped up on the same tick
+    let tx_a2 = dispatch_in_group(&harness, &anchor_key, 2, "session_a")?;
+    let tx_b1 = dispatch_in_group(&harness, &anchor_key, 3, "session_b")?;
+    harness.tick()?;
+
+    assert_eq!(
+        store.get_tx(&tx_a2.compute_txid())?.state,
+        TransactionState::ToDispatch
+    );
+    assert!(harness.chain().in_mempool(&tx_b1.compute_txid()));
+
+    // The CPFP of session B is paid by its own funding and only pays for its own transaction
+    let cpfp_b1 = cpfp_spending(&harness, OutPoint::new(funding_b.txid, funding_b.vout)).unwrap();
+    let paid: Vec<OutPoint> = cpfp_b1
+        .input
+        .iter()
+        .map(|input| input.previous_output)
+        .collect();
+    assert_eq!(
+        paid,
+        vec![
+            OutPoint::new(tx_b1.compute_txid(), 0),
+            OutPoint::new(funding_b.txid, funding_b.vout)
+        ]
+    );
+
+    // The default chain has no funding and no speedups
+    assert!(store.get_funding()?.is_none());
+    assert!(store.get_unconfirmed_speedups()?.is_empty());
+
+    // Once the speedup of session A is confirmed, its next transaction is dispatched from the change
+    harness.mine_blocks(1);
+    harness.tick()?;
+    harness.tick()?;
+
+    assert!(harness.chain().in_mempool(&tx_a2.compute_txid()));
+    assert!(cpfp_spending(&harness, OutPoint::new(cpfp_a1.compute_txid(), 0)).is_some());
+
+    clear_output();
+    Ok(())
+}
+
+#[test]
+fn test_dispatch_to_unknown_funding_group() -> Result<(), anyhow::Error> {
+    let (harness, _, anchor_key, funding_a, _) = setup()?;
+
+    let result = dispatch_in_group(&harness, &anchor_key, 1, "session_c");
+    assert!(matches!(
+        result,
+        Err(BitcoinCoordinatorError::UnknownFundingGroup(group)) if group == "session_c"
+    ));
+
+    let result = harness
+        .coordinator()
+        .add_funding_group("session/c", funding_a);
+    assert!(matches!(
+        result,
+        Err(BitcoinCoordinatorError::InvalidConfiguration(_))
+    ));
+
+    clear_output();
+    Ok(())
+}
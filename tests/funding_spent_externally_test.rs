@@ -0,0 +1,163 @@
+use bitcoin::{
+    absolute::LockTime, transaction::Version, Amount, OutPoint, PublicKey, ScriptBuf, Sequence,
+    Transaction, TxIn, TxOut, Witness,
+};
+use bitcoin_coordinator::{
+    coordinator::BitcoinCoordinatorApi,
+    speedup::SpeedupStore,
+    storage::{BitcoinCoordinatorStore, BitcoinCoordinatorStoreApi},
+    testing::CoordinatorTestHarness,
+    types::{AckCoordinatorNews, AckNews, CoordinatorNews},
+};
+use key_manager::key_type::BitcoinKeyType;
+use protocol_builder::types::Utxo;
+use utils::{clear_output, get_mocks, tx_with_anchor};
+mod utils;
+
+const ANCHOR_AMOUNT: u64 = 540;
+const FUNDING_AMOUNT: u64 = 100_000;
+
+fn setup() -> Result<
+    (
+        CoordinatorTestHarness,
+        BitcoinCoordinatorStore,
+        PublicKey,
+        PublicKey,
+    ),
+    anyhow::Error,
+> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+
+    Ok((harness, store, anchor_key, funding_key))
+}
+
+// The operator spends the funding from their wallet, the coordinator does not know about it.
+fn spend_externally(harness: &CoordinatorTestHarness, funding: &Utxo) -> Transaction {
+    let tx = Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint::new(funding.txid, funding.vout),
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: Amount::from_sat(funding.amount - 1_000),
+            script_pubkey: ScriptBuf::from_bytes(vec![0x51]),
+        }],
+    };
+    harness.chain().send_transaction(&tx).unwrap();
+    harness.mine_blocks(1);
+
+    tx
+}
+
+fn cpfp_spending(harness: &CoordinatorTestHarness, outpoint: OutPoint) -> Option<Transaction> {
+    harness.chain().mempool().into_iter().find(|tx| {
+        tx.input
+            .iter()
+            .any(|input| input.previous_output == outpoint)
+    })
+}
+
+fn funding_spent_news(
+    harness: &CoordinatorTestHarness,
+) -> Result<Vec<CoordinatorNews>, anyhow::Error> {
+    Ok(harness
+        .coordinator()
+        .get_news()?
+        .coordinator_news
+        .into_iter()
+        .filter(|news| news.kind() == "FundingSpentExternally")
+        .collect())
+}
+
+#[test]
+fn test_funding_spent_externally_defers_speedup() -> Result<(), anyhow::Error> {
+    let (harness, store, anchor_key, funding_key) = setup()?;
+
+    let funding = harness.fund(&funding_key, FUNDING_AMOUNT)?;
+    harness.coordinator().add_funding(funding.clone())?;
+    let spender = spend_externally(&harness, &funding);
+    let outpoint = OutPoint::new(funding.txid, funding.vout);
+
+    let (tx, speedup_data) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 1);
+    harness.dispatch(tx.clone(), Some(speedup_data), "My tx")?;
+    harness.tick()?;
+
+    // The transaction is sent, but no CPFP is built from the spent funding
+    assert!(harness.chain().in_mempool(&tx.compute_txid()));
+    assert!(cpfp_spending(&harness, outpoint).is_none());
+    assert!(store.is_funding_invalidated(&funding)?);
+    assert!(store.get_funding()?.is_none());
+    assert_eq!(store.get_deferred_speedup_txs()?, vec![tx.compute_txid()]);
+
+    assert_eq!(
+        funding_spent_news(&harness)?,
+        vec![CoordinatorNews::FundingSpentExternally(
+            outpoint,
+            Some(spender.compute_txid())
+        )]
+    );
+
+    // The deferred CPFP is sent from the new funding
+    let new_funding = harness.fund(&funding_key, FUNDING_AMOUNT)?;
+    harness.coordinator().add_funding(new_funding.clone())?;
+    harness.tick()?;
+
+    let cpfp = cpfp_spending(&harness, OutPoint::new(new_funding.txid, new_funding.vout)).unwrap();
+    assert!(cpfp
+        .input
+        .iter()
+        .any(|input| input.previous_output == OutPoint::new(tx.compute_txid(), 0)));
+    assert!(store.get_deferred_speedup_txs()?.is_empty());
+
+    harness.coordinator().ack_news(AckNews::Coordinator(
+        AckCoordinatorNews::FundingSpentExternally(outpoint),
+    ))?;
+    assert!(funding_spent_news(&harness)?.is_empty());
+
+    clear_output();
+    Ok(())
+}
+
+#[test]
+fn test_funding_spent_externally_falls_back_to_pool() -> Result<(), anyhow::Error> {
+    let (harness, store, anchor_key, funding_key) = setup()?;
+
+    // The first funding is kept in the pool when the second one is added
+    let pool_funding = harness.fund(&funding_key, FUNDING_AMOUNT)?;
+    let funding = harness.fund(&funding_key, FUNDING_AMOUNT + 1_000)?;
+    harness.coordinator().add_funding(pool_funding.clone())?;
+    harness.coordinator().add_funding(funding.clone())?;
+    spend_externally(&harness, &funding);
+
+    let (tx, speedup_data) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 1);
+    harness.dispatch(tx.clone(), Some(speedup_data), "My tx")?;
+    harness.tick()?;
+
+    // The CPFP is sent on the same tick, paid by the pool funding
+    assert!(cpfp_spending(&harness, OutPoint::new(funding.txid, funding.vout)).is_none());
+    let cpfp = cpfp_spending(
+        &harness,
+        OutPoint::new(pool_funding.txid, pool_funding.vout),
+    )
+    .unwrap();
+    assert!(cpfp
+        .input
+        .iter()
+        .any(|input| input.previous_output == OutPoint::new(tx.compute_txid(), 0)));
+    assert!(store.get_deferred_speedup_txs()?.is_empty());
+    assert_eq!(funding_spent_news(&harness)?.len(), 1);
+
+    // The spent funding is not listed anymore
+    let summary = harness.coordinator().get_funding_summary()?;
+    assert!(summary.funding_pool.is_empty());
+
+    clear_output();
+    Ok(())
+}
@@ -0,0 +1,169 @@
+use bitcoin::{CompressedPublicKey, OutPoint, PublicKey, ScriptBuf};
+use bitcoin_coordinator::{
+    config::CoordinatorSettingsConfig,
+    coordinator::BitcoinCoordinatorApi,
+    errors::BitcoinCoordinatorError,
+    funding::FundingProvider,
+    speedup::SpeedupStore,
+    storage::BitcoinCoordinatorStore,
+    testing::{CoordinatorTestHarness, FakeChain},
+    types::{AckCoordinatorNews, AckNews, CoordinatorNews, TransactionState},
+};
+use key_manager::key_type::BitcoinKeyType;
+use protocol_builder::types::Utxo;
+use std::{cell::RefCell, rc::Rc};
+use utils::{clear_output, get_mocks, tx_with_anchor};
+mod utils;
+
+const ANCHOR_AMOUNT: u64 = 540;
+const TOPUP_AMOUNT: u64 = 200_000;
+
+// Sends the requested funding to the mempool of the fake chain, like a wallet would, and records the requests.
+struct MockFundingProvider {
+    chain: FakeChain,
+    public_key: PublicKey,
+    requests: RefCell<Vec<u64>>,
+}
+
+impl FundingProvider for MockFundingProvider {
+    fn request_funding(&self, target_sats: u64) -> Result<Utxo, BitcoinCoordinatorError> {
+        self.requests.borrow_mut().push(target_sats);
+
+        let compressed = CompressedPublicKey::try_from(self.public_key).unwrap();
+        let (tx, vout) = self.chain.send_funding(
+            ScriptBuf::new_p2wpkh(&compressed.wpubkey_hash()),
+            target_sats,
+        );
+
+        Ok(Utxo::new(
+            tx.compute_txid(),
+            vout,
+            target_sats,
+            &self.public_key,
+        ))
+    }
+}
+
+// A harness with a funding provider paying to the key manager funding key.
+fn setup(
+    auto_topup_below_sats: Option<u64>,
+) -> Result<
+    (
+        CoordinatorTestHarness,
+        Rc<MockFundingProvider>,
+        BitcoinCoordinatorStore,
+        PublicKey,
+    ),
+    anyhow::Error,
+> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+
+    let harness = CoordinatorTestHarness::new(
+        store.store.clone(),
+        key_manager,
+        Some(CoordinatorSettingsConfig {
+            auto_topup_below_sats,
+            auto_topup_amount_sats: Some(TOPUP_AMOUNT),
+            ..Default::default()
+        }),
+    )?;
+
+    let provider = Rc::new(MockFundingProvider {
+        chain: harness.chain().clone(),
+        public_key: funding_key,
+        requests: RefCell::new(Vec::new()),
+    });
+    let harness = harness.with_funding_provider(provider.clone());
+
+    Ok((harness, provider, store, anchor_key))
+}
+
+// Without funding the provider is asked once. The funding is registered when it is confirmed
+// and pays for the CPFP of the transaction waiting to be dispatched.
+#[test]
+fn test_topup_registered_once_confirmed() -> Result<(), anyhow::Error> {
+    let (harness, provider, store, anchor_key) = setup(None)?;
+
+    let (tx, speedup_data) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 1);
+    let tx_id = tx.compute_txid();
+    harness.dispatch(tx, Some(speedup_data), "My tx")?;
+
+    harness.tick()?;
+    assert_eq!(*provider.requests.borrow(), vec![TOPUP_AMOUNT]);
+    let topup = store.get_pending_funding_topup()?.unwrap();
+
+    // Not registered while the funding is unconfirmed, nor requested again
+    harness.tick()?;
+    assert_eq!(provider.requests.borrow().len(), 1);
+    assert!(store.get_funding()?.is_none());
+    assert_eq!(
+        harness.coordinator().get_transaction_history(tx_id)?.state,
+        TransactionState::ToDispatch
+    );
+
+    harness.mine_blocks(1);
+    harness.tick()?;
+
+    assert_eq!(provider.requests.borrow().len(), 1);
+    assert!(store.get_pending_funding_topup()?.is_none());
+
+    let news = harness.coordinator().get_news()?;
+    assert!(news
+        .coordinator_news
+        .contains(&CoordinatorNews::FundingTopUp(topup.txid, TOPUP_AMOUNT)));
+    assert!(news.monitor_news.is_empty());
+
+    // The next CPFP spends the top-up
+    assert_eq!(
+        harness.coordinator().get_transaction_history(tx_id)?.state,
+        TransactionState::Dispatched
+    );
+    let speedups = harness.coordinator().get_speedups_for_tx(tx_id)?;
+    assert_eq!(speedups.len(), 1);
+    let cpfp = harness.chain().get_transaction(&speedups[0].tx_id).unwrap();
+    assert!(cpfp
+        .input
+        .iter()
+        .any(|input| input.previous_output == OutPoint::new(topup.txid, topup.vout)));
+
+    harness
+        .coordinator()
+        .ack_news(AckNews::Coordinator(AckCoordinatorNews::FundingTopUp(
+            topup.txid,
+        )))?;
+    assert!(!harness
+        .coordinator()
+        .get_news()?
+        .coordinator_news
+        .iter()
+        .any(|news| matches!(news, CoordinatorNews::FundingTopUp(..))));
+
+    clear_output();
+    Ok(())
+}
+
+// The provider is only asked when the remaining funding drops below auto_topup_below_sats.
+#[test]
+fn test_topup_below_threshold() -> Result<(), anyhow::Error> {
+    let (harness, provider, _, _) = setup(Some(50_000))?;
+    let funding_key = provider.public_key;
+
+    let funding = harness.fund(&funding_key, 80_000)?;
+    harness.coordinator().add_funding(funding)?;
+
+    harness.tick()?;
+    assert!(provider.requests.borrow().is_empty());
+
+    let (harness, provider, _, _) = setup(Some(50_000))?;
+
+    let funding = harness.fund(&funding_key, 30_000)?;
+    harness.coordinator().add_funding(funding)?;
+
+    harness.tick()?;
+    assert_eq!(*provider.requests.borrow(), vec![TOPUP_AMOUNT]);
+
+    clear_output();
+    Ok(())
+}
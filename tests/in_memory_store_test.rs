@@ -0,0 +1,87 @@
+use bitcoin_coordinator::{
+    coordinator::BitcoinCoordinatorApi,
+    errors::BitcoinCoordinatorStoreError,
+    storage::{BitcoinCoordinatorStore, BitcoinCoordinatorStoreApi},
+    store_backend::{InMemoryStore, StoreBackend},
+    testing::CoordinatorTestHarness,
+};
+use std::rc::Rc;
+use utils::{clear_output, get_mocks, simple_tx};
+mod utils;
+
+// Writes inside a transaction are only seen once it is committed, and dropped when it is rolled back.
+#[test]
+fn test_in_memory_transactions() -> Result<(), anyhow::Error> {
+    let storage: Rc<dyn StoreBackend> = Rc::new(InMemoryStore::new());
+    storage.set("a", 1u64, None)?;
+
+    let transaction_id = storage.begin_transaction();
+    storage.set("a", 2u64, Some(transaction_id))?;
+    storage.set("b", "value", Some(transaction_id))?;
+    assert_eq!(storage.get::<_, u64>("a")?, Some(1));
+    assert!(!storage.has_key("b")?);
+
+    storage.commit_transaction(transaction_id)?;
+    assert_eq!(storage.get::<_, u64>("a")?, Some(2));
+    assert_eq!(storage.get::<_, String>("b")?, Some("value".to_string()));
+
+    let transaction_id = storage.begin_transaction();
+    storage.remove("a", Some(transaction_id))?;
+    storage.rollback_transaction(transaction_id)?;
+    assert_eq!(storage.get::<_, u64>("a")?, Some(2));
+
+    // A closed transaction can not be written to
+    assert!(matches!(
+        storage.set("a", 3u64, Some(transaction_id)),
+        Err(BitcoinCoordinatorStoreError::StoreTransactionNotOpen(id)) if id == transaction_id
+    ));
+
+    Ok(())
+}
+
+// Two stores on the same in-memory storage see the records of each other, like on the same disk.
+#[test]
+fn test_in_memory_store_shared() -> Result<(), anyhow::Error> {
+    let storage: Rc<dyn StoreBackend> = Rc::new(InMemoryStore::new());
+    let store = BitcoinCoordinatorStore::new(storage.clone(), 1, 3, 2)?;
+    let tx = simple_tx(1);
+
+    store.save_tx(tx.clone(), None, None, "context".to_string())?;
+
+    let reopened = BitcoinCoordinatorStore::new(storage, 1, 3, 2)?;
+    assert_eq!(reopened.get_tx(&tx.compute_txid())?.tx, tx);
+
+    let empty = BitcoinCoordinatorStore::in_memory(1, 3, 2)?;
+    assert!(empty.get_txs_in_progress()?.is_empty());
+
+    Ok(())
+}
+
+// A coordinator with its store in memory dispatches and follows a transaction until it is confirmed.
+#[test]
+fn test_ephemeral_coordinator() -> Result<(), anyhow::Error> {
+    let (_, _, _, key_manager) = get_mocks();
+    let harness = CoordinatorTestHarness::ephemeral(key_manager, None)?;
+    let tx = simple_tx(2);
+
+    harness.dispatch(tx.clone(), None, "ephemeral")?;
+    harness.tick()?;
+    assert!(harness.chain().in_mempool(&tx.compute_txid()));
+
+    harness.mine_blocks(1);
+    harness.tick()?;
+    assert_eq!(
+        harness
+            .coordinator()
+            .get_transaction(tx.compute_txid())?
+            .confirmations,
+        1
+    );
+
+    let overview = harness.coordinator().get_pending_overview()?;
+    assert!(overview.to_dispatch.is_empty());
+    assert!(overview.dispatched.is_empty());
+
+    clear_output();
+    Ok(())
+}
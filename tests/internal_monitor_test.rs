@@ -0,0 +1,130 @@
+use bitcoin::{OutPoint, Transaction, Txid};
+use bitcoin_coordinator::{
+    coordinator::BitcoinCoordinatorApi,
+    errors::BitcoinCoordinatorError,
+    settings::{CPFP_TRANSACTION_CONTEXT, MAX_CONTEXT_LENGTH},
+    speedup::SpeedupStore,
+    storage::BitcoinCoordinatorStoreApi,
+    testing::CoordinatorTestHarness,
+};
+use bitvmx_transaction_monitor::types::{MonitorNews, TypesToMonitor};
+use key_manager::key_type::BitcoinKeyType;
+use utils::{clear_output, get_mocks, tx_with_anchor};
+mod utils;
+
+fn transaction_news(
+    harness: &CoordinatorTestHarness,
+) -> Result<Vec<(Txid, String)>, anyhow::Error> {
+    Ok(harness
+        .coordinator()
+        .get_news()?
+        .monitor_news
+        .into_iter()
+        .filter_map(|news| match news {
+            MonitorNews::Transaction(txid, _, context) => Some((txid, context)),
+            _ => None,
+        })
+        .collect())
+}
+
+// A consumer context containing the text of the CPFP context is not taken for the coordinator's own speedup.
+#[test]
+fn test_user_context_with_internal_context_text() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+
+    let funding = harness.fund(&funding_key, 1_000_000)?;
+    harness.coordinator().add_funding(funding)?;
+
+    let context = format!("my {CPFP_TRANSACTION_CONTEXT} test");
+    let (tx, speedup_data) = tx_with_anchor(&anchor_key, 0, 1);
+    harness.dispatch(tx.clone(), Some(speedup_data), &context)?;
+    harness.tick()?;
+
+    let (speedup, _) = store.get_last_speedup()?.unwrap();
+    assert!(harness.chain().in_mempool(&tx.compute_txid()));
+    assert!(harness.chain().in_mempool(&speedup.tx_id));
+
+    harness.mine_blocks(1);
+    harness.tick()?;
+
+    // The news of the consumer transaction flows, the one of the CPFP stays internal
+    let news = transaction_news(&harness)?;
+    assert!(news.contains(&(tx.compute_txid(), context.clone())));
+    assert!(news.iter().all(|(txid, _)| *txid != speedup.tx_id));
+
+    // The consumer can also monitor the CPFP itself, with its own context
+    harness.coordinator().monitor(TypesToMonitor::Transactions(
+        vec![speedup.tx_id],
+        context.clone(),
+        None,
+    ))?;
+    harness.mine_blocks(1);
+    harness.tick()?;
+
+    let news = transaction_news(&harness)?;
+    assert!(news.contains(&(speedup.tx_id, context)));
+    assert!(!news.contains(&(speedup.tx_id, CPFP_TRANSACTION_CONTEXT.to_string())));
+
+    clear_output();
+    Ok(())
+}
+
+#[test]
+fn test_invalid_context() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+
+    let too_long = "a".repeat(MAX_CONTEXT_LENGTH + 1);
+    let invalid = [
+        "",
+        "  ",
+        "line\nbreak",
+        "nul\0",
+        &too_long,
+        CPFP_TRANSACTION_CONTEXT,
+    ];
+
+    for context in invalid {
+        let (tx, speedup_data) = tx_with_anchor(&anchor_key, 0, 1);
+        let txid = tx.compute_txid();
+
+        assert!(matches!(
+            harness.dispatch(tx.clone(), Some(speedup_data.clone()), context),
+            Err(BitcoinCoordinatorError::InvalidContext(ctx, _)) if ctx == context
+        ));
+        assert!(matches!(
+            harness
+                .coordinator()
+                .dispatch_batch(vec![(tx, Some(speedup_data), context.to_string())], None),
+            Err(BitcoinCoordinatorError::InvalidContext(..))
+        ));
+        assert!(matches!(
+            harness.coordinator().monitor(TypesToMonitor::Transactions(
+                vec![txid],
+                context.to_string(),
+                None
+            )),
+            Err(BitcoinCoordinatorError::InvalidContext(..))
+        ));
+        assert!(matches!(
+            harness
+                .coordinator()
+                .watch_outpoint(OutPoint::new(txid, 0), context.to_string()),
+            Err(BitcoinCoordinatorError::InvalidContext(..))
+        ));
+    }
+
+    assert!(store.get_txs_in_progress()?.is_empty());
+    assert!(store.get_watched_outpoints()?.is_empty());
+
+    // The longest context is accepted
+    let (tx, speedup_data) = tx_with_anchor(&anchor_key, 0, 2);
+    harness.dispatch(tx, Some(speedup_data), &"a".repeat(MAX_CONTEXT_LENGTH))?;
+
+    clear_output();
+    Ok(())
+}
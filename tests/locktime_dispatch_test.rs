@@ -0,0 +1,212 @@
+use bitcoin::{
+    absolute::{LockTime, LOCK_TIME_THRESHOLD},
+    transaction::Version,
+    Amount, OutPoint, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Witness,
+};
+use bitcoin_coordinator::{
+    coordinator::BitcoinCoordinatorApi,
+    storage::BitcoinCoordinatorStoreApi,
+    testing::CoordinatorTestHarness,
+    types::{BlockingReason, CoordinatorNews, PendingReason, PendingTxEntry},
+};
+use bitvmx_bitcoin_rpc::types::BlockHeight;
+use utils::{clear_output, get_mocks};
+mod utils;
+
+fn tx(lock_time: LockTime, previous_output: OutPoint, sequence: Sequence) -> Transaction {
+    Transaction {
+        version: Version::TWO,
+        lock_time,
+        input: vec![TxIn {
+            previous_output,
+            script_sig: ScriptBuf::new(),
+            sequence,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: Amount::from_sat(10_000),
+            script_pubkey: ScriptBuf::new(),
+        }],
+    }
+}
+
+fn tx_locked_until(height: BlockHeight) -> Transaction {
+    tx(
+        LockTime::from_height(height).unwrap(),
+        OutPoint::null(),
+        Sequence::ENABLE_RBF_NO_LOCKTIME,
+    )
+}
+
+fn harness() -> Result<CoordinatorTestHarness, anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+
+    Ok(CoordinatorTestHarness::new(
+        store.store.clone(),
+        key_manager,
+        None,
+    )?)
+}
+
+fn to_dispatch(harness: &CoordinatorTestHarness, tx: &Transaction) -> Option<PendingTxEntry> {
+    harness
+        .coordinator()
+        .get_pending_overview()
+        .unwrap()
+        .to_dispatch
+        .into_iter()
+        .find(|entry| entry.tx_id == tx.compute_txid())
+}
+
+fn dispatch_errors(harness: &CoordinatorTestHarness) -> usize {
+    harness
+        .coordinator()
+        .get_news()
+        .unwrap()
+        .coordinator_news
+        .iter()
+        .filter(|news| matches!(news, CoordinatorNews::DispatchTransactionError(..)))
+        .count()
+}
+
+// A transaction locked until 3 blocks above the tip waits in ToDispatch and is broadcast by the tick
+// of the block its locktime matures at, without failed attempts.
+#[test]
+fn test_locktime_dispatched_at_maturity() -> Result<(), anyhow::Error> {
+    let harness = harness()?;
+    let lock_height = harness.chain().height() + 3;
+    let locked = tx_locked_until(lock_height);
+
+    harness.dispatch(locked.clone(), None, "locked")?;
+
+    for _ in 0..3 {
+        harness.tick()?;
+        assert!(!harness.chain().in_mempool(&locked.compute_txid()));
+
+        let entry = to_dispatch(&harness, &locked).unwrap();
+        assert_eq!(
+            entry.pending_reason,
+            Some(PendingReason::WaitingForLocktime(lock_height))
+        );
+        assert_eq!(entry.retry_count, 0);
+
+        harness.mine_empty_blocks(1);
+    }
+
+    harness.tick()?;
+    assert_eq!(harness.chain().height(), lock_height);
+    assert!(harness.chain().in_mempool(&locked.compute_txid()));
+    assert!(to_dispatch(&harness, &locked).is_none());
+    assert_eq!(dispatch_errors(&harness), 0);
+
+    let dispatched = harness.coordinator().get_pending_overview()?.dispatched;
+    assert_eq!(dispatched.len(), 1);
+    assert_eq!(dispatched[0].retry_count, 0);
+
+    clear_output();
+    Ok(())
+}
+
+// The diagnosis of a transaction waiting for its locktime reports the height it matures at.
+#[test]
+fn test_diagnose_awaiting_locktime() -> Result<(), anyhow::Error> {
+    let harness = harness()?;
+    let lock_height = harness.chain().height() + 10;
+    let locked = tx_locked_until(lock_height);
+
+    harness.dispatch(locked.clone(), None, "locked")?;
+    harness.tick()?;
+
+    let diagnosis = harness.coordinator().diagnose(locked.compute_txid())?;
+    assert!(!diagnosis.was_broadcast);
+    assert_eq!(
+        diagnosis.blocking_reasons,
+        vec![BlockingReason::AwaitingLocktime(lock_height)]
+    );
+
+    clear_output();
+    Ok(())
+}
+
+// A transaction relatively locked (CSV) to an unconfirmed parent waits for the parent to be confirmed,
+// then for the lock to mature counting from the block of the parent.
+#[test]
+fn test_relative_lock_waits_for_parent() -> Result<(), anyhow::Error> {
+    let harness = harness()?;
+    let parent = tx(
+        LockTime::from_consensus(LOCK_TIME_THRESHOLD + 1),
+        OutPoint::null(),
+        Sequence::ENABLE_RBF_NO_LOCKTIME,
+    );
+    let child = tx(
+        LockTime::ZERO,
+        OutPoint::new(parent.compute_txid(), 0),
+        Sequence::from_height(2),
+    );
+
+    harness.dispatch(parent.clone(), None, "parent")?;
+    harness.dispatch(child.clone(), None, "child")?;
+
+    harness.tick()?;
+    assert!(harness.chain().in_mempool(&parent.compute_txid()));
+    assert!(!harness.chain().in_mempool(&child.compute_txid()));
+
+    // Confirmed in the next block, the child is valid in the block after it
+    harness.mine_blocks(1);
+    let parent_height = harness.chain().height();
+    harness.tick()?;
+    assert!(!harness.chain().in_mempool(&child.compute_txid()));
+    assert_eq!(
+        to_dispatch(&harness, &child).unwrap().pending_reason,
+        Some(PendingReason::WaitingForLocktime(parent_height + 1))
+    );
+
+    harness.mine_empty_blocks(1);
+    harness.tick()?;
+    assert!(harness.chain().in_mempool(&child.compute_txid()));
+    assert_eq!(dispatch_errors(&harness), 0);
+
+    clear_output();
+    Ok(())
+}
+
+// While the parent is not confirmed the height the relative lock matures at moves with every block, the record of
+// the child is not rewritten each tick and keeps no lock height until the parent confirms.
+#[test]
+fn test_relative_lock_height_saved_once_parent_confirmed() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+    let parent = tx(
+        LockTime::from_consensus(LOCK_TIME_THRESHOLD + 1),
+        OutPoint::null(),
+        Sequence::ENABLE_RBF_NO_LOCKTIME,
+    );
+    let child = tx(
+        LockTime::ZERO,
+        OutPoint::new(parent.compute_txid(), 0),
+        Sequence::from_height(2),
+    );
+
+    harness.dispatch(parent.clone(), None, "parent")?;
+    harness.dispatch(child.clone(), None, "child")?;
+
+    for _ in 0..3 {
+        harness.tick()?;
+        harness.mine_empty_blocks(1);
+        assert_eq!(
+            store.get_tx(&child.compute_txid())?.locktime_block_height,
+            None
+        );
+    }
+
+    harness.mine_blocks(1);
+    let parent_height = harness.chain().height();
+    harness.tick()?;
+    assert_eq!(
+        store.get_tx(&child.compute_txid())?.locktime_block_height,
+        Some(parent_height + 1)
+    );
+
+    clear_output();
+    Ok(())
+}
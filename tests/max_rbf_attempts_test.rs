@@ -0,0 +1,124 @@
+use bitcoin::Amount;
+use bitcoin_coordinator::{
+    config::CoordinatorSettingsConfig,
+    coordinator::{BitcoinCoordinator, BitcoinCoordinatorApi},
+    speedup::SpeedupStore,
+    storage::BitcoinCoordinatorStore,
+    types::CoordinatorNews,
+};
+use bitcoind::bitcoind::BitcoindFlags;
+use bitvmx_bitcoin_rpc::bitcoin_client::BitcoinClientApi;
+use protocol_builder::types::Utxo;
+use std::rc::Rc;
+
+use crate::utils::{config_trace_aux, coordinate_tx, create_test_setup, TestSetupConfig};
+mod utils;
+
+// The node only mines transactions paying a high fee rate, so the CPFP paying for tx1 is not mined.
+// With max_unconfirmed_speedups = 1 the CPFP is replaced on every new block, but only max_rbf_attempts = 2 times.
+// On the third block interval no replacement is sent and a MaxRbfAttemptsReached news is reported instead.
+#[test]
+fn max_rbf_attempts_test() -> Result<(), anyhow::Error> {
+    config_trace_aux();
+
+    let mut blocks_mined = 102;
+    let setup = create_test_setup(TestSetupConfig {
+        blocks_mined,
+        bitcoind_flags: Some(BitcoindFlags {
+            block_min_tx_fee: 0.00004,
+            ..Default::default()
+        }),
+    })?;
+
+    let amount = Amount::from_sat(23450000);
+
+    let (funding_speedup, funding_speedup_vout) = setup
+        .bitcoin_client
+        .fund_address(&setup.funding_wallet, amount)?;
+
+    // Funding speed up tx mines 1 block
+    blocks_mined += 1;
+
+    let settings = CoordinatorSettingsConfig {
+        max_rbf_attempts: Some(2),
+        max_unconfirmed_speedups: Some(1),
+        ..Default::default()
+    };
+
+    let coordinator = Rc::new(BitcoinCoordinator::new_with_paths(
+        &setup.config_bitcoin_client,
+        setup.storage.clone(),
+        setup.key_manager.clone(),
+        Some(settings),
+    )?);
+
+    for _ in 0..blocks_mined {
+        coordinator.tick()?;
+    }
+
+    coordinator.add_funding(Utxo::new(
+        funding_speedup.compute_txid(),
+        funding_speedup_vout,
+        amount.to_sat(),
+        &setup.public_key,
+    ))?;
+
+    coordinate_tx(
+        coordinator.clone(),
+        amount,
+        setup.network,
+        setup.key_manager.clone(),
+        setup.bitcoin_client.clone(),
+        None,
+    )?;
+
+    // Dispatches tx1 and its CPFP
+    coordinator.tick()?;
+
+    let store = BitcoinCoordinatorStore::new(setup.storage.clone(), 1, 3, 2)?;
+    let (cpfp, _) = store.get_last_speedup()?.unwrap();
+
+    // Three block intervals without confirmation
+    for _ in 0..3 {
+        setup
+            .bitcoin_client
+            .mine_blocks_to_address(1, &setup.funding_wallet)?;
+
+        coordinator.tick()?;
+    }
+
+    let replacements = store.get_speedup_replacements(&cpfp)?;
+    assert_eq!(replacements.len(), 2);
+
+    // The fees of both replacements are added up
+    let fees: Vec<u64> = replacements
+        .iter()
+        .map(|replacement| {
+            replacement
+                .prev_funding
+                .amount
+                .saturating_sub(replacement.next_funding.amount)
+        })
+        .collect();
+    assert!(fees.iter().all(|fee| *fee > 0));
+    let fee_spent: u64 = fees.iter().sum();
+
+    let news = coordinator.get_news()?;
+    assert!(news
+        .coordinator_news
+        .contains(&CoordinatorNews::MaxRbfAttemptsReached(
+            cpfp.tx_id, 2, fee_spent
+        )));
+
+    // The CPFP is not replaced again
+    setup
+        .bitcoin_client
+        .mine_blocks_to_address(1, &setup.funding_wallet)?;
+    coordinator.tick()?;
+
+    assert_eq!(store.get_speedup_replacements(&cpfp)?.len(), 2);
+
+    setup.bitcoind.stop()?;
+
+    Ok(())
+}
@@ -0,0 +1,239 @@
+use bitcoin::{Transaction, Txid};
+use bitcoin_coordinator::{
+    ancestry::{MempoolAncestry, MempoolAncestryProvider},
+    config::CoordinatorSettingsConfig,
+    coordinator::{BitcoinCoordinator, BitcoinCoordinatorApi},
+    cpfp::SpeedupOutputKind,
+    errors::BitcoinCoordinatorError,
+    types::TransactionState,
+};
+use bitcoincore_rpc::{Auth, Client};
+use bitvmx_transaction_monitor::errors::MonitorError;
+use key_manager::key_type::BitcoinKeyType;
+use protocol_builder::types::{output::SpeedupData, Utxo};
+use std::{
+    cell::Cell,
+    rc::Rc,
+    sync::{Arc, Mutex},
+};
+use utils::{clear_output, get_mocks, tx_with_output};
+mod utils;
+
+const CURRENT_HEIGHT: u32 = 100;
+const ANCHOR_AMOUNT: u64 = 540;
+const FUNDING_AMOUNT: u64 = 100_000;
+
+// Answers with the same ancestry for any transaction and counts the requests.
+struct FixedAncestry {
+    ancestry: Option<MempoolAncestry>,
+    requests: Cell<u32>,
+}
+
+impl FixedAncestry {
+    fn new(ancestry: Option<MempoolAncestry>) -> Rc<Self> {
+        Rc::new(Self {
+            ancestry,
+            requests: Cell::new(0),
+        })
+    }
+}
+
+impl MempoolAncestryProvider for FixedAncestry {
+    fn get_mempool_ancestry(
+        &self,
+        _txid: &Txid,
+    ) -> Result<Option<MempoolAncestry>, BitcoinCoordinatorError> {
+        self.requests.set(self.requests.get() + 1);
+        Ok(self.ancestry)
+    }
+}
+
+type SentTxids = Arc<Mutex<Vec<Txid>>>;
+
+// A funded coordinator asking the provider for the ancestry, with `dispatched` transactions waiting
+// to be dispatched. Returns the transactions sent to the node.
+fn setup(
+    provider: Rc<FixedAncestry>,
+    check_mempool_ancestry: bool,
+    dispatched: u32,
+) -> Result<(BitcoinCoordinator, Vec<Transaction>, SentTxids), anyhow::Error> {
+    let (mut mock_monitor, store, mut mock_bitcoin_client, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+    let sent_txids = Arc::new(Mutex::new(Vec::new()));
+
+    mock_monitor.expect_monitor().returning(|_| Ok(()));
+    mock_monitor.expect_tick().returning(|| Ok(()));
+    mock_monitor.expect_is_ready().returning(|| Ok(true));
+    mock_monitor
+        .expect_get_monitor_height()
+        .returning(|| Ok(CURRENT_HEIGHT));
+    // Without a current block the speedup news are not stored
+    mock_monitor
+        .expect_get_current_block()
+        .returning(|| Ok(None));
+    mock_monitor.expect_get_news().returning(|| Ok(vec![]));
+    mock_monitor
+        .expect_get_tx_status()
+        .returning(|tx_id| Err(MonitorError::TransactionNotFound(tx_id.to_string())));
+    mock_monitor
+        .expect_get_estimated_fee_rate()
+        .returning(|| Ok(2));
+
+    let sent = sent_txids.clone();
+    mock_bitcoin_client
+        .expect_send_transaction()
+        .returning(move |tx| {
+            sent.lock().unwrap().push(tx.compute_txid());
+            Ok(tx.compute_txid())
+        });
+    mock_bitcoin_client
+        .expect_get_best_block()
+        .returning(|| Ok(CURRENT_HEIGHT));
+
+    let coordinator = BitcoinCoordinator::builder()
+        .with_monitor(Box::new(mock_monitor))
+        .with_store(store)
+        .with_client(Box::new(mock_bitcoin_client))
+        .with_rpc_client(Client::new("http://127.0.0.1:18443", Auth::None)?)
+        .with_key_manager(key_manager)
+        .with_settings(CoordinatorSettingsConfig {
+            check_mempool_ancestry: Some(check_mempool_ancestry),
+            ..Default::default()
+        })
+        .build()?
+        .with_mempool_ancestry_provider(provider);
+
+    let funding_tx = tx_with_output(
+        SpeedupOutputKind::P2wpkh.script_pubkey(&funding_key)?,
+        FUNDING_AMOUNT,
+        0,
+    );
+    coordinator.add_funding(Utxo::new(
+        funding_tx.compute_txid(),
+        0,
+        FUNDING_AMOUNT,
+        &funding_key,
+    ))?;
+
+    let mut txs = Vec::new();
+
+    for seed in 1..=dispatched {
+        let tx = tx_with_output(
+            SpeedupOutputKind::P2trKeyPath.script_pubkey(&anchor_key)?,
+            ANCHOR_AMOUNT,
+            seed,
+        );
+        let speedup_data =
+            SpeedupData::new(Utxo::new(tx.compute_txid(), 0, ANCHOR_AMOUNT, &anchor_key));
+
+        coordinator.dispatch(
+            tx.clone(),
+            Some(speedup_data),
+            "My tx".to_string(),
+            None,
+            None,
+        )?;
+        txs.push(tx);
+    }
+
+    Ok((coordinator, txs, sent_txids))
+}
+
+fn state(coordinator: &BitcoinCoordinator, tx: &Transaction) -> TransactionState {
+    coordinator
+        .get_transaction_history(tx.compute_txid())
+        .unwrap()
+        .state
+}
+
+// The funding is at the end of a chain of 24 unconfirmed transactions created by someone else.
+// A CPFP would be the 26th, so nothing is broadcast and the node is asked again on the next tick.
+#[test]
+fn test_deep_mempool_ancestry_defers_cpfp() -> Result<(), anyhow::Error> {
+    let provider = FixedAncestry::new(Some(MempoolAncestry {
+        ancestor_count: 24,
+        ancestor_size: 5_000,
+    }));
+    let (coordinator, txs, sent_txids) = setup(provider.clone(), true, 1)?;
+
+    coordinator.tick()?;
+    assert!(sent_txids.lock().unwrap().is_empty());
+    assert_eq!(state(&coordinator, &txs[0]), TransactionState::ToDispatch);
+    assert_eq!(provider.requests.get(), 1);
+
+    coordinator.tick()?;
+    assert!(sent_txids.lock().unwrap().is_empty());
+    assert_eq!(provider.requests.get(), 2);
+
+    clear_output();
+    Ok(())
+}
+
+// With 22 ancestors the batch is shrunk: two transactions and the CPFP paying them fit in the chain.
+#[test]
+fn test_mempool_ancestry_shrinks_batch() -> Result<(), anyhow::Error> {
+    let provider = FixedAncestry::new(Some(MempoolAncestry {
+        ancestor_count: 22,
+        ancestor_size: 5_000,
+    }));
+    let (coordinator, txs, sent_txids) = setup(provider.clone(), true, 3)?;
+
+    coordinator.tick()?;
+
+    let sent_txids = sent_txids.lock().unwrap();
+    assert_eq!(sent_txids.len(), 3);
+    assert_eq!(sent_txids[0], txs[0].compute_txid());
+    assert_eq!(sent_txids[1], txs[1].compute_txid());
+    assert_eq!(state(&coordinator, &txs[0]), TransactionState::Dispatched);
+    assert_eq!(state(&coordinator, &txs[1]), TransactionState::Dispatched);
+    assert_eq!(state(&coordinator, &txs[2]), TransactionState::ToDispatch);
+
+    // Asked once in the tick
+    assert_eq!(provider.requests.get(), 1);
+
+    clear_output();
+    Ok(())
+}
+
+#[test]
+fn test_mempool_ancestor_size_defers_cpfp() -> Result<(), anyhow::Error> {
+    let provider = FixedAncestry::new(Some(MempoolAncestry {
+        ancestor_count: 2,
+        ancestor_size: 100_900,
+    }));
+    let (coordinator, txs, sent_txids) = setup(provider, true, 1)?;
+
+    coordinator.tick()?;
+    assert!(sent_txids.lock().unwrap().is_empty());
+    assert_eq!(state(&coordinator, &txs[0]), TransactionState::ToDispatch);
+
+    clear_output();
+    Ok(())
+}
+
+// A confirmed funding has no mempool ancestors, and without the check the node is not asked.
+#[test]
+fn test_confirmed_funding_or_check_disabled() -> Result<(), anyhow::Error> {
+    let provider = FixedAncestry::new(None);
+    let (coordinator, txs, sent_txids) = setup(provider.clone(), true, 1)?;
+
+    coordinator.tick()?;
+    assert_eq!(sent_txids.lock().unwrap().len(), 2);
+    assert_eq!(state(&coordinator, &txs[0]), TransactionState::Dispatched);
+    assert_eq!(provider.requests.get(), 1);
+
+    let provider = FixedAncestry::new(Some(MempoolAncestry {
+        ancestor_count: 24,
+        ancestor_size: 5_000,
+    }));
+    let (coordinator, txs, sent_txids) = setup(provider.clone(), false, 1)?;
+
+    coordinator.tick()?;
+    assert_eq!(sent_txids.lock().unwrap().len(), 2);
+    assert_eq!(state(&coordinator, &txs[0]), TransactionState::Dispatched);
+    assert_eq!(provider.requests.get(), 0);
+
+    clear_output();
+    Ok(())
+}
@@ -0,0 +1,279 @@
+use bitcoin::Network;
+use bitcoin_coordinator::{
+    config::{CoordinatorConfig, CoordinatorSettings, CoordinatorSettingsConfig, FeeStrategy},
+    coordinator::{BitcoinCoordinator, BitcoinCoordinatorApi},
+    settings::{
+        DEFAULT_MAX_UNCONFIRMED_SPEEDUPS, DEFAULT_MIN_BLOCKS_BEFORE_RESEND_SPEEDUP,
+        DEFAULT_MIN_NETWORK_FEE_RATE, MAINNET_MAX_FEERATE_SAT_VB,
+        SIGNET_MIN_BLOCKS_BEFORE_RESEND_SPEEDUP, TESTNET_MIN_NETWORK_FEE_RATE,
+        TEST_NETWORK_MAX_FEERATE_SAT_VB,
+    },
+    testing::CoordinatorTestHarness,
+};
+use bitcoincore_rpc::{Auth, Client};
+use bitvmx_settings::settings::load_config_file;
+use utils::{clear_output, get_mocks};
+mod utils;
+
+const NETWORKS: [Network; 5] = [
+    Network::Regtest,
+    Network::Signet,
+    Network::Testnet,
+    Network::Testnet4,
+    Network::Bitcoin,
+];
+
+// The settings that depend on the network.
+fn preset(settings: &CoordinatorSettings) -> (u32, u64, u64) {
+    (
+        settings.min_blocks_before_resend_speedup,
+        settings.max_feerate_sat_vb,
+        settings.min_network_fee_rate,
+    )
+}
+
+fn build(
+    network: Network,
+    settings: Option<CoordinatorSettingsConfig>,
+) -> Result<BitcoinCoordinator, anyhow::Error> {
+    let (mut mock_monitor, store, mock_bitcoin_client, key_manager) = get_mocks();
+    // Asked for the block of the news reported by update_settings
+    mock_monitor
+        .expect_get_current_block()
+        .returning(|| Ok(None));
+
+    let mut builder = BitcoinCoordinator::builder()
+        .with_monitor(Box::new(mock_monitor))
+        .with_store(store)
+        .with_client(Box::new(mock_bitcoin_client))
+        .with_rpc_client(Client::new("http://127.0.0.1:18443", Auth::None)?)
+        .with_key_manager(key_manager)
+        .with_network(network);
+
+    if let Some(settings) = settings {
+        builder = builder.with_settings(settings);
+    }
+
+    Ok(builder.build()?)
+}
+
+#[test]
+fn test_network_defaults_differ() -> Result<(), anyhow::Error> {
+    let regtest = CoordinatorSettings::defaults_for(Network::Regtest);
+    let signet = CoordinatorSettings::defaults_for(Network::Signet);
+    let testnet = CoordinatorSettings::defaults_for(Network::Testnet);
+    let testnet4 = CoordinatorSettings::defaults_for(Network::Testnet4);
+    let mainnet = CoordinatorSettings::defaults_for(Network::Bitcoin);
+
+    assert_eq!(
+        preset(&regtest),
+        (
+            DEFAULT_MIN_BLOCKS_BEFORE_RESEND_SPEEDUP,
+            TEST_NETWORK_MAX_FEERATE_SAT_VB,
+            DEFAULT_MIN_NETWORK_FEE_RATE
+        )
+    );
+
+    // Signet waits more blocks before replacing a speedup
+    assert_eq!(
+        preset(&signet),
+        (
+            SIGNET_MIN_BLOCKS_BEFORE_RESEND_SPEEDUP,
+            TEST_NETWORK_MAX_FEERATE_SAT_VB,
+            DEFAULT_MIN_NETWORK_FEE_RATE
+        )
+    );
+    assert!(signet.min_blocks_before_resend_speedup > regtest.min_blocks_before_resend_speedup);
+
+    // Both testnets have a higher fee rate floor
+    for testnet in [&testnet, &testnet4] {
+        assert_eq!(
+            preset(testnet),
+            (
+                DEFAULT_MIN_BLOCKS_BEFORE_RESEND_SPEEDUP,
+                TEST_NETWORK_MAX_FEERATE_SAT_VB,
+                TESTNET_MIN_NETWORK_FEE_RATE
+            )
+        );
+        assert!(testnet.min_network_fee_rate > regtest.min_network_fee_rate);
+    }
+
+    // Mainnet has its own fee rate cap
+    assert_eq!(
+        preset(&mainnet),
+        (
+            DEFAULT_MIN_BLOCKS_BEFORE_RESEND_SPEEDUP,
+            MAINNET_MAX_FEERATE_SAT_VB,
+            DEFAULT_MIN_NETWORK_FEE_RATE
+        )
+    );
+    assert_ne!(mainnet.max_feerate_sat_vb, regtest.max_feerate_sat_vb);
+
+    // The other settings are the same on every network, and every preset is valid
+    for network in NETWORKS {
+        let defaults = CoordinatorSettings::defaults_for(network);
+        assert_eq!(
+            defaults.max_unconfirmed_speedups,
+            DEFAULT_MAX_UNCONFIRMED_SPEEDUPS
+        );
+        assert!(defaults.network_warnings(network).is_empty());
+        assert!(CoordinatorSettingsConfig::defaults_for(network)
+            .validate()
+            .is_ok());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_explicit_settings_override_preset() -> Result<(), anyhow::Error> {
+    let explicit = CoordinatorSettingsConfig {
+        min_blocks_before_resend_speedup: Some(2),
+        max_feerate_sat_vb: Some(40),
+        min_network_fee_rate: Some(5),
+        ..Default::default()
+    };
+
+    for network in NETWORKS {
+        let settings = CoordinatorSettings::resolve(explicit.clone(), network);
+        assert_eq!(preset(&settings), (2, 40, 5));
+
+        // A setting left out takes the preset, the ones set are kept
+        let partial = CoordinatorSettingsConfig {
+            max_feerate_sat_vb: None,
+            ..explicit.clone()
+        };
+        let settings = CoordinatorSettings::resolve(partial, network);
+        assert_eq!(
+            settings.max_feerate_sat_vb,
+            CoordinatorSettings::defaults_for(network).max_feerate_sat_vb
+        );
+        assert_eq!(settings.min_blocks_before_resend_speedup, 2);
+        assert_eq!(settings.min_network_fee_rate, 5);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_effective_settings_follow_the_network() -> Result<(), anyhow::Error> {
+    // Without settings the coordinator runs with the preset of its network
+    for network in NETWORKS {
+        let coordinator = build(network, None)?;
+        assert_eq!(
+            preset(&coordinator.get_effective_settings()),
+            preset(&CoordinatorSettings::defaults_for(network))
+        );
+    }
+
+    // The configured settings are kept on every network, the ones left out take the preset
+    let settings = CoordinatorSettingsConfig {
+        min_blocks_before_resend_speedup: Some(1),
+        max_feerate_sat_vb: None,
+        min_network_fee_rate: None,
+        ..Default::default()
+    };
+    let coordinator = build(Network::Signet, Some(settings))?;
+    assert_eq!(
+        preset(&coordinator.get_effective_settings()),
+        (
+            1,
+            TEST_NETWORK_MAX_FEERATE_SAT_VB,
+            DEFAULT_MIN_NETWORK_FEE_RATE
+        )
+    );
+
+    // Updates keep resolving against the network of the coordinator
+    let coordinator = build(Network::Testnet4, None)?;
+    coordinator.update_settings(CoordinatorSettingsConfig {
+        max_feerate_sat_vb: Some(60),
+        min_network_fee_rate: None,
+        ..Default::default()
+    })?;
+    let settings = coordinator.get_effective_settings();
+    assert_eq!(settings.max_feerate_sat_vb, 60);
+    assert_eq!(settings.min_network_fee_rate, TESTNET_MIN_NETWORK_FEE_RATE);
+
+    clear_output();
+    Ok(())
+}
+
+#[test]
+fn test_network_warnings() -> Result<(), anyhow::Error> {
+    let settings = |config: CoordinatorSettingsConfig, network: Network| {
+        CoordinatorSettings::resolve(config, network).network_warnings(network)
+    };
+
+    // A mainnet fee rate cap on regtest
+    let mainnet_cap = CoordinatorSettingsConfig {
+        max_feerate_sat_vb: Some(500),
+        ..CoordinatorSettingsConfig::defaults_for(Network::Regtest)
+    };
+    let warnings = settings(mainnet_cap.clone(), Network::Regtest);
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].contains("max_feerate_sat_vb"));
+    assert!(settings(mainnet_cap, Network::Bitcoin).is_empty());
+
+    // Speedups replaced after every block on signet
+    let eager_resend = CoordinatorSettingsConfig {
+        min_blocks_before_resend_speedup: Some(1),
+        ..CoordinatorSettingsConfig::defaults_for(Network::Signet)
+    };
+    assert_eq!(settings(eager_resend, Network::Signet).len(), 1);
+
+    // The testnet estimates with the regtest floor, unless the fee rate is fixed
+    let low_floor = CoordinatorSettingsConfig {
+        min_network_fee_rate: Some(1),
+        ..CoordinatorSettingsConfig::defaults_for(Network::Testnet4)
+    };
+    assert_eq!(settings(low_floor.clone(), Network::Testnet4).len(), 1);
+    let fixed = CoordinatorSettingsConfig {
+        fee_strategy: Some(FeeStrategy::Fixed(3)),
+        ..low_floor
+    };
+    assert!(settings(fixed, Network::Testnet4).is_empty());
+
+    // Only warnings, the coordinator is still built
+    let (_, store, _, key_manager) = get_mocks();
+    let harness = CoordinatorTestHarness::new(
+        store.store.clone(),
+        key_manager,
+        Some(CoordinatorSettingsConfig {
+            max_feerate_sat_vb: Some(500),
+            ..Default::default()
+        }),
+    )?;
+    assert_eq!(
+        harness
+            .coordinator()
+            .get_effective_settings()
+            .max_feerate_sat_vb,
+        500
+    );
+
+    clear_output();
+    Ok(())
+}
+
+#[test]
+fn test_load_testnet4_config() -> Result<(), anyhow::Error> {
+    let config = std::fs::read_to_string("config/coordinator_config.yaml")?.replacen(
+        "network: regtest",
+        "network: testnet4",
+        1,
+    );
+    let path = std::env::temp_dir().join(format!("coordinator_{}.yaml", std::process::id()));
+    std::fs::write(&path, config)?;
+
+    let config = load_config_file::<CoordinatorConfig>(Some(path.to_string_lossy().into_owned()));
+    std::fs::remove_file(&path)?;
+    let config = config?;
+    assert_eq!(config.rpc.network, Network::Testnet4);
+
+    // The settings of the example config left out take the testnet4 preset
+    let settings = CoordinatorSettings::resolve(config.settings.unwrap(), config.rpc.network);
+    assert_eq!(settings.min_network_fee_rate, TESTNET_MIN_NETWORK_FEE_RATE);
+    assert!(settings.network_warnings(Network::Testnet4).is_empty());
+
+    Ok(())
+}
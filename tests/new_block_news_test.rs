@@ -0,0 +1,242 @@
+use bitcoin::{hashes::Hash, BlockHash};
+use bitcoin_coordinator::{
+    coordinator::{BitcoinCoordinator, BitcoinCoordinatorApi},
+    cpfp::SpeedupOutputKind,
+    speedup::SpeedupStore,
+    storage::{BitcoinCoordinatorStore, BitcoinCoordinatorStoreApi},
+    store_backend::StoreBackend,
+    types::{AckCoordinatorNews, AckNews, CoordinatorNews},
+    TypesToMonitor,
+};
+use bitcoincore_rpc::{Auth, Client};
+use bitvmx_transaction_monitor::{errors::MonitorError, types::FullBlock};
+use key_manager::{key_manager::KeyManager, key_type::BitcoinKeyType};
+use protocol_builder::types::{output::SpeedupData, Utxo};
+use std::{
+    rc::Rc,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc, Mutex,
+    },
+};
+use utils::{clear_output, get_mocks, tx_with_output};
+mod utils;
+
+const INITIAL_HEIGHT: u32 = 100;
+const ANCHOR_AMOUNT: u64 = 540;
+const FUNDING_AMOUNT: u64 = 100_000;
+
+fn block_hash(height: u32) -> BlockHash {
+    let mut bytes = [0; 32];
+    bytes[..4].copy_from_slice(&height.to_le_bytes());
+    BlockHash::from_byte_array(bytes)
+}
+
+// The chain seen by the mocked monitor, with the number of times it was asked for its height.
+#[derive(Clone)]
+struct MockChain {
+    height: Arc<Mutex<u32>>,
+    height_requests: Arc<AtomicU32>,
+}
+
+impl MockChain {
+    fn mine_block(&self) {
+        *self.height.lock().unwrap() += 1;
+    }
+
+    fn take_height_requests(&self) -> u32 {
+        self.height_requests.swap(0, Ordering::SeqCst)
+    }
+}
+
+fn setup_on(
+    storage: Rc<dyn StoreBackend>,
+    key_manager: Rc<KeyManager>,
+    chain: &MockChain,
+) -> Result<BitcoinCoordinator, anyhow::Error> {
+    let (mut mock_monitor, _, mut mock_bitcoin_client, _) = get_mocks();
+    let store = BitcoinCoordinatorStore::new(storage, 1, 3, 2)?;
+
+    let (height, height_requests) = (chain.height.clone(), chain.height_requests.clone());
+    mock_monitor.expect_get_monitor_height().returning(move || {
+        height_requests.fetch_add(1, Ordering::SeqCst);
+        Ok(*height.lock().unwrap())
+    });
+
+    let height = chain.height.clone();
+    mock_monitor.expect_get_current_block().returning(move || {
+        let height = *height.lock().unwrap();
+        Ok(Some(FullBlock {
+            height,
+            hash: block_hash(height),
+            prev_hash: block_hash(height - 1),
+            txs: vec![],
+            orphan: false,
+        }))
+    });
+
+    mock_monitor.expect_monitor().returning(|_| Ok(()));
+    mock_monitor.expect_tick().returning(|| Ok(()));
+    mock_monitor.expect_is_ready().returning(|| Ok(true));
+    mock_monitor.expect_get_news().returning(|| Ok(vec![]));
+    mock_monitor
+        .expect_get_tx_status()
+        .returning(|tx_id| Err(MonitorError::TransactionNotFound(tx_id.to_string())));
+    mock_monitor
+        .expect_get_estimated_fee_rate()
+        .returning(|| Ok(2));
+
+    mock_bitcoin_client
+        .expect_send_transaction()
+        .returning(|tx| Ok(tx.compute_txid()));
+    mock_bitcoin_client
+        .expect_get_best_block()
+        .returning(|| Ok(INITIAL_HEIGHT));
+
+    let coordinator = BitcoinCoordinator::builder()
+        .with_monitor(Box::new(mock_monitor))
+        .with_store(store)
+        .with_client(Box::new(mock_bitcoin_client))
+        .with_rpc_client(Client::new("http://127.0.0.1:18443", Auth::None)?)
+        .with_key_manager(key_manager)
+        .build()?;
+
+    Ok(coordinator)
+}
+
+fn setup() -> Result<
+    (
+        BitcoinCoordinator,
+        MockChain,
+        Rc<dyn StoreBackend>,
+        Rc<KeyManager>,
+    ),
+    anyhow::Error,
+> {
+    let (_, store, _, key_manager) = get_mocks();
+    let chain = MockChain {
+        height: Arc::new(Mutex::new(INITIAL_HEIGHT)),
+        height_requests: Arc::new(AtomicU32::new(0)),
+    };
+    let coordinator = setup_on(store.store.clone(), key_manager.clone(), &chain)?;
+
+    Ok((coordinator, chain, store.store.clone(), key_manager))
+}
+
+fn new_block_news(coordinator: &BitcoinCoordinator) -> Vec<(u32, BlockHash)> {
+    coordinator
+        .get_news()
+        .unwrap()
+        .coordinator_news
+        .into_iter()
+        .filter_map(|news| match news {
+            CoordinatorNews::NewBlock(height, hash) => Some((height, hash)),
+            _ => None,
+        })
+        .collect()
+}
+
+// A tick dispatching a transaction with its CPFP asks the monitor for its height only once.
+#[test]
+fn test_monitor_height_asked_once_per_tick() -> Result<(), anyhow::Error> {
+    let (coordinator, chain, storage, key_manager) = setup()?;
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+
+    let funding_tx = tx_with_output(
+        SpeedupOutputKind::P2wpkh.script_pubkey(&funding_key)?,
+        FUNDING_AMOUNT,
+        0,
+    );
+    coordinator.add_funding(Utxo::new(
+        funding_tx.compute_txid(),
+        0,
+        FUNDING_AMOUNT,
+        &funding_key,
+    ))?;
+
+    let tx = tx_with_output(
+        SpeedupOutputKind::P2trKeyPath.script_pubkey(&anchor_key)?,
+        ANCHOR_AMOUNT,
+        1,
+    );
+    let speedup_data =
+        SpeedupData::new(Utxo::new(tx.compute_txid(), 0, ANCHOR_AMOUNT, &anchor_key));
+    coordinator.dispatch(tx, Some(speedup_data), "My tx".to_string(), None, None)?;
+    coordinator.monitor(TypesToMonitor::NewBlock)?;
+    chain.take_height_requests();
+
+    coordinator.tick()?;
+    assert_eq!(chain.take_height_requests(), 1);
+
+    let store = BitcoinCoordinatorStore::new(storage, 1, 3, 2)?;
+    assert_eq!(store.get_unconfirmed_speedups()?.len(), 1);
+
+    chain.mine_block();
+    coordinator.tick()?;
+    assert_eq!(chain.take_height_requests(), 1);
+
+    clear_output();
+    Ok(())
+}
+
+#[test]
+fn test_new_block_news_only_when_subscribed() -> Result<(), anyhow::Error> {
+    let (coordinator, chain, _, _) = setup()?;
+
+    coordinator.tick()?;
+    assert!(new_block_news(&coordinator).is_empty());
+
+    coordinator.monitor(TypesToMonitor::NewBlock)?;
+    coordinator.tick()?;
+    assert_eq!(
+        new_block_news(&coordinator),
+        vec![(INITIAL_HEIGHT, block_hash(INITIAL_HEIGHT))]
+    );
+
+    // The same block is not reported again, and the acked news is removed
+    coordinator.tick()?;
+    coordinator.ack_news(AckNews::Coordinator(AckCoordinatorNews::NewBlock))?;
+    coordinator.tick()?;
+    assert!(new_block_news(&coordinator).is_empty());
+
+    // The next block is reported
+    chain.mine_block();
+    coordinator.tick()?;
+    assert_eq!(
+        new_block_news(&coordinator),
+        vec![(INITIAL_HEIGHT + 1, block_hash(INITIAL_HEIGHT + 1))]
+    );
+
+    // After cancelling, new blocks are not reported
+    coordinator.ack_news(AckNews::Coordinator(AckCoordinatorNews::NewBlock))?;
+    coordinator.cancel(TypesToMonitor::NewBlock)?;
+    chain.mine_block();
+    coordinator.tick()?;
+    assert!(new_block_news(&coordinator).is_empty());
+
+    clear_output();
+    Ok(())
+}
+
+// The subscription is persisted, a coordinator restarted on the same storage keeps reporting new blocks.
+#[test]
+fn test_new_block_subscription_persisted() -> Result<(), anyhow::Error> {
+    let (coordinator, chain, storage, key_manager) = setup()?;
+
+    coordinator.monitor(TypesToMonitor::NewBlock)?;
+    drop(coordinator);
+
+    let store = BitcoinCoordinatorStore::new(storage.clone(), 1, 3, 2)?;
+    assert!(store.is_subscribed_to_new_blocks()?);
+
+    let coordinator = setup_on(storage, key_manager, &chain)?;
+    coordinator.tick()?;
+    assert_eq!(
+        new_block_news(&coordinator),
+        vec![(INITIAL_HEIGHT, block_hash(INITIAL_HEIGHT))]
+    );
+
+    clear_output();
+    Ok(())
+}
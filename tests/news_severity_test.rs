@@ -0,0 +1,369 @@
+use bitcoin::{
+    absolute::LockTime, hashes::Hash, transaction::Version, BlockHash, OutPoint, ScriptBuf,
+    Transaction, Txid,
+};
+use bitcoin_coordinator::{
+    coordinator::BitcoinCoordinatorApi,
+    errors::BroadcastFailureKind,
+    news::{monitor_news_severity, news_acks},
+    storage::BitcoinCoordinatorStoreApi,
+    testing::CoordinatorTestHarness,
+    types::{
+        CoordinatorNews, DispatchDeferredReason, News, SettingChange, Severity, SeverityCounts,
+        TickSkipReason,
+    },
+    BlockInfo, MonitorNews, TransactionStatus,
+};
+use bitvmx_transaction_monitor::types::TransactionBlockchainStatus;
+use key_manager::key_type::BitcoinKeyType;
+use utils::{clear_output, get_mocks, tx_with_anchor};
+use uuid::Uuid;
+mod utils;
+
+const ANCHOR_AMOUNT: u64 = 540;
+
+// Every coordinator and monitor news has the expected severity.
+#[test]
+fn test_news_severity_classification() -> Result<(), anyhow::Error> {
+    let txid = Txid::all_zeros();
+    let block_hash = BlockHash::all_zeros();
+    let outpoint = OutPoint::new(txid, 0);
+    let context = "context".to_string();
+    let error = "error".to_string();
+    let block_info = BlockInfo {
+        height: 100,
+        hash: block_hash,
+        is_orphan: false,
+    };
+    let tx = Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![],
+        output: vec![],
+    };
+
+    let coordinator_news = vec![
+        (
+            CoordinatorNews::DispatchTransactionError(
+                txid,
+                context.clone(),
+                error.clone(),
+                BroadcastFailureKind::Other,
+            ),
+            Severity::Critical,
+        ),
+        (
+            CoordinatorNews::DispatchSpeedUpError(
+                vec![txid],
+                vec![context.clone()],
+                txid,
+                error.clone(),
+            ),
+            Severity::Critical,
+        ),
+        (
+            CoordinatorNews::InsufficientFunds(txid, 1_000, 2_000),
+            Severity::Critical,
+        ),
+        (CoordinatorNews::FundingNotFound, Severity::Critical),
+        (
+            CoordinatorNews::EstimateFeerateTooHigh(200, 100),
+            Severity::Info,
+        ),
+        (
+            CoordinatorNews::FeeEstimateUnavailable(1),
+            Severity::Warning,
+        ),
+        (
+            CoordinatorNews::DispatchPausedHighFees(2, 100),
+            Severity::Warning,
+        ),
+        (CoordinatorNews::FundingTopUp(txid, 10_000), Severity::Info),
+        (
+            CoordinatorNews::SpeedupFeeCapExceeded(txid, 2_000, 1_000),
+            Severity::Warning,
+        ),
+        (
+            CoordinatorNews::ParentReplaced(txid, txid, 100),
+            Severity::Info,
+        ),
+        (CoordinatorNews::TickPartialFailure(1), Severity::Warning),
+        (CoordinatorNews::NodeUnreachable(0), Severity::Critical),
+        (CoordinatorNews::NodeRecovered(1_000), Severity::Info),
+        (
+            CoordinatorNews::SettingsUpdated(vec![SettingChange {
+                name: "max_feerate_sat_vb".to_string(),
+                old_value: "100".to_string(),
+                new_value: "200".to_string(),
+            }]),
+            Severity::Info,
+        ),
+        (
+            CoordinatorNews::TransactionAlreadyInMempool(txid, context.clone()),
+            Severity::Info,
+        ),
+        (
+            CoordinatorNews::MempoolRejection(txid, context.clone(), error.clone()),
+            Severity::Warning,
+        ),
+        (
+            CoordinatorNews::NetworkError(txid, context.clone(), error.clone()),
+            Severity::Warning,
+        ),
+        (
+            CoordinatorNews::DispatchCancelled(txid, context.clone()),
+            Severity::Info,
+        ),
+        (
+            CoordinatorNews::TransactionExpired(txid, context.clone(), 100),
+            Severity::Warning,
+        ),
+        (
+            CoordinatorNews::NonStandardAnchor(txid, context.clone(), error.clone()),
+            Severity::Warning,
+        ),
+        (
+            CoordinatorNews::RbfEscalationFailed(txid, 3, error.clone()),
+            Severity::Warning,
+        ),
+        (
+            CoordinatorNews::MaxRbfAttemptsReached(txid, 3, 1_000),
+            Severity::Critical,
+        ),
+        (
+            CoordinatorNews::TransactionRebroadcast(txid, 1),
+            Severity::Info,
+        ),
+        (
+            CoordinatorNews::MaxRebroadcastAttemptsReached(txid, 3),
+            Severity::Critical,
+        ),
+        (
+            CoordinatorNews::SpeedupOrphaned(txid, vec![txid]),
+            Severity::Warning,
+        ),
+        (
+            CoordinatorNews::SpeedupChainInvalidated(vec![txid]),
+            Severity::Warning,
+        ),
+        (
+            CoordinatorNews::SpeedupCreated(txid, vec![txid], 1_000, 10, false),
+            Severity::Info,
+        ),
+        (
+            CoordinatorNews::TransactionConflicted(txid, txid, context.clone()),
+            Severity::Critical,
+        ),
+        (
+            CoordinatorNews::TransactionReorged(txid, block_hash, context.clone()),
+            Severity::Warning,
+        ),
+        (
+            CoordinatorNews::DispatchScheduled(txid, 100),
+            Severity::Info,
+        ),
+        (
+            CoordinatorNews::DependencyFailed(txid, txid),
+            Severity::Critical,
+        ),
+        (
+            CoordinatorNews::OutpointSpent(outpoint, txid, 0, block_info.clone(), context.clone()),
+            Severity::Info,
+        ),
+        (
+            CoordinatorNews::AddressFunded(
+                ScriptBuf::new(),
+                tx.clone(),
+                vec![(0, 1_000)],
+                block_info.clone(),
+                context.clone(),
+            ),
+            Severity::Info,
+        ),
+        (
+            CoordinatorNews::FundingSpentExternally(outpoint, Some(txid)),
+            Severity::Critical,
+        ),
+        (
+            CoordinatorNews::FundingExhausted(txid, 100),
+            Severity::Critical,
+        ),
+        (
+            CoordinatorNews::FundingTemporarilyUnavailable(txid, vec![txid]),
+            Severity::Warning,
+        ),
+        (
+            CoordinatorNews::AnchorSpentExternally(txid, outpoint, txid),
+            Severity::Warning,
+        ),
+        (
+            CoordinatorNews::FeeOverpayment(txid, 50.0, 10),
+            Severity::Warning,
+        ),
+        (
+            CoordinatorNews::ConfirmationMilestone(txid, 6, context.clone()),
+            Severity::Info,
+        ),
+        (
+            CoordinatorNews::CollateralSpent(context.clone(), outpoint, txid, 1),
+            Severity::Info,
+        ),
+        (
+            CoordinatorNews::CollateralFullySpent(context.clone()),
+            Severity::Info,
+        ),
+        (
+            CoordinatorNews::DispatchDeferred(
+                vec![txid],
+                DispatchDeferredReason::UnconfirmedChainLimit,
+            ),
+            Severity::Warning,
+        ),
+        (
+            CoordinatorNews::TickWorkSkipped(TickSkipReason::FundingDisappeared, error.clone()),
+            Severity::Warning,
+        ),
+        (
+            CoordinatorNews::SpeedupRejectedByPolicy(txid, error.clone()),
+            Severity::Warning,
+        ),
+        (
+            CoordinatorNews::GroupCompleted(Uuid::nil(), context.clone()),
+            Severity::Info,
+        ),
+        (CoordinatorNews::NewBlock(100, block_hash), Severity::Info),
+    ];
+
+    for (news, severity) in coordinator_news.iter() {
+        assert_eq!(news.severity(), *severity, "{}", news.kind());
+    }
+
+    let status = TransactionStatus {
+        tx_id: txid,
+        tx: tx.clone(),
+        block_info: Some(block_info),
+        confirmations: 1,
+        status: TransactionBlockchainStatus::Confirmed,
+    };
+
+    let monitor_news = vec![
+        MonitorNews::Transaction(txid, status.clone(), context.clone()),
+        MonitorNews::RskPeginTransaction(txid, status.clone()),
+        MonitorNews::SpendingUTXOTransaction(txid, 0, status, context),
+        MonitorNews::NewBlock(100, block_hash),
+    ];
+
+    for news in monitor_news.iter() {
+        assert_eq!(monitor_news_severity(news), Severity::Info);
+    }
+
+    // Severities are ordered from the least to the most severe
+    assert!(Severity::Info < Severity::Warning && Severity::Warning < Severity::Critical);
+
+    Ok(())
+}
+
+// The store returns the news of the requested severity and above, and the news left out are still
+// acknowledged and not returned anymore.
+#[test]
+fn test_store_news_filtered_by_severity() -> Result<(), anyhow::Error> {
+    let (_, store, _, _) = get_mocks();
+    let block_hash = BlockHash::all_zeros();
+    let txid = Txid::all_zeros();
+
+    let critical = CoordinatorNews::FundingNotFound;
+    let warning = CoordinatorNews::MempoolRejection(txid, "tx".to_string(), "error".to_string());
+    let info = CoordinatorNews::SpeedupCreated(txid, vec![txid], 1_000, 10, false);
+
+    store.update_news(info.clone(), block_hash)?;
+    store.update_news(warning.clone(), block_hash)?;
+    store.update_news(critical.clone(), block_hash)?;
+
+    assert_eq!(store.get_news_filtered(Severity::Info)?, store.get_news()?);
+    assert_eq!(
+        store.get_news_filtered(Severity::Warning)?,
+        vec![warning.clone(), critical.clone()]
+    );
+    assert_eq!(
+        store.get_news_filtered(Severity::Critical)?,
+        vec![critical.clone()]
+    );
+
+    // Only the news of the requested severity are read from the store
+    let reads = store.reads();
+    store.get_news_filtered(Severity::Critical)?;
+    assert_eq!(store.reads() - reads, 1);
+
+    // The info news is not returned by the filter but it can be acknowledged
+    store.ack_news(info.ack())?;
+
+    assert_eq!(store.get_news()?, vec![warning, critical.clone()]);
+    assert_eq!(store.get_news_filtered(Severity::Critical)?, vec![critical]);
+
+    clear_output();
+    Ok(())
+}
+
+// The coordinator filters the monitor and coordinator news together and counts them by severity. The
+// news left out by the filter are acknowledged as usual.
+#[test]
+fn test_get_news_filtered() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+    let funding = harness.fund(&funding_key, 1_000_000)?;
+    harness.coordinator().add_funding(funding)?;
+
+    let (tx, speedup_data) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 1);
+    harness.dispatch(tx, Some(speedup_data), "tx")?;
+    harness.tick()?;
+    harness.mine_blocks(1);
+    harness.tick()?;
+
+    store.update_news(CoordinatorNews::FundingNotFound, harness.chain().tip().hash)?;
+
+    let news = harness.coordinator().get_news()?;
+    assert!(!news.monitor_news.is_empty());
+    assert_eq!(news.severity_counts.get(Severity::Critical), 1);
+    assert_eq!(
+        news.severity_counts.info + news.severity_counts.warning + news.severity_counts.critical,
+        news.monitor_news.len() + news.coordinator_news.len()
+    );
+
+    let critical = harness
+        .coordinator()
+        .get_news_filtered(Severity::Critical)?;
+    assert!(critical.monitor_news.is_empty());
+    assert_eq!(
+        critical.coordinator_news,
+        vec![CoordinatorNews::FundingNotFound]
+    );
+    assert_eq!(
+        critical.severity_counts,
+        SeverityCounts {
+            info: 0,
+            warning: 0,
+            critical: 1,
+        }
+    );
+
+    // Acknowledge every news the filter left out
+    let left_out = News::new(
+        news.monitor_news,
+        news.coordinator_news
+            .into_iter()
+            .filter(|news| news.severity() < Severity::Critical)
+            .collect(),
+    );
+    for ack in news_acks(&left_out) {
+        harness.coordinator().ack_news(ack)?;
+    }
+
+    let news = harness.coordinator().get_news()?;
+    assert_eq!(news, critical);
+
+    clear_output();
+    Ok(())
+}
@@ -0,0 +1,152 @@
+use bitcoin::Transaction;
+use bitcoin_coordinator::{
+    coordinator::BitcoinCoordinatorApi,
+    errors::BitcoinCoordinatorError,
+    testing::CoordinatorTestHarness,
+    types::{AckCoordinatorNews, AckNews, CoordinatorNews, News},
+    AckMonitorNews, MonitorNews,
+};
+use key_manager::key_type::BitcoinKeyType;
+use std::sync::mpsc::{Receiver, TryRecvError};
+use utils::{clear_output, get_mocks, simple_tx, tx_with_anchor};
+mod utils;
+
+// Every batch waiting in the channel, merged in a single News.
+fn receive(receiver: &Receiver<News>) -> News {
+    let mut news = News::new(vec![], vec![]);
+
+    while let Ok(batch) = receiver.try_recv() {
+        news.monitor_news.extend(batch.monitor_news);
+        news.coordinator_news.extend(batch.coordinator_news);
+    }
+
+    news
+}
+
+fn confirmations(news: &News, tx: &Transaction) -> Vec<u32> {
+    news.monitor_news
+        .iter()
+        .filter_map(|news| match news {
+            MonitorNews::Transaction(txid, status, _) if *txid == tx.compute_txid() => {
+                Some(status.confirmations)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+// Two ticks generate different news, each subscriber gets every news once.
+#[test]
+fn test_news_delivered_once_per_subscriber() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+
+    let first = harness.coordinator().subscribe_news("first")?;
+    let second = harness.coordinator().subscribe_news("second")?;
+
+    // A transaction with speedup data and no funding
+    let (unfunded_tx, speedup_data) = tx_with_anchor(&anchor_key, 0, 1);
+    harness.dispatch(unfunded_tx, Some(speedup_data), "unfunded")?;
+    harness.tick()?;
+
+    for receiver in [&first, &second] {
+        let news = receive(receiver);
+        assert!(news.monitor_news.is_empty());
+        assert_eq!(
+            news.coordinator_news,
+            vec![CoordinatorNews::FundingNotFound]
+        );
+    }
+
+    // A transaction is confirmed, only its news is delivered
+    let tx = simple_tx(2);
+    harness.dispatch(tx.clone(), None, "My tx")?;
+    harness.tick()?;
+    harness.mine_blocks(1);
+    harness.tick()?;
+
+    for receiver in [&first, &second] {
+        let news = receive(receiver);
+        assert_eq!(confirmations(&news, &tx), vec![1]);
+        assert!(news.coordinator_news.is_empty());
+    }
+
+    // Nothing new, nothing is sent
+    harness.tick()?;
+    assert!(matches!(first.try_recv(), Err(TryRecvError::Empty)));
+    assert!(matches!(second.try_recv(), Err(TryRecvError::Empty)));
+
+    clear_output();
+    Ok(())
+}
+
+// A subscriber that subscribes again resumes from its cursor, a new subscriber gets every unacknowledged news.
+#[test]
+fn test_news_cursor_survives_resubscription() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+
+    let subscriber = harness.coordinator().subscribe_news("consumer")?;
+
+    let (unfunded_tx, speedup_data) = tx_with_anchor(&anchor_key, 0, 1);
+    harness.dispatch(unfunded_tx, Some(speedup_data), "unfunded")?;
+    let tx = simple_tx(2);
+    harness.dispatch(tx.clone(), None, "My tx")?;
+    harness.tick()?;
+    harness.mine_blocks(1);
+    harness.tick()?;
+
+    let news = receive(&subscriber);
+    assert_eq!(
+        news.coordinator_news,
+        vec![CoordinatorNews::FundingNotFound]
+    );
+    assert_eq!(confirmations(&news, &tx), vec![1]);
+
+    // The consumer restarts, news generated meanwhile wait in the store
+    drop(subscriber);
+    harness.mine_blocks(1);
+    harness.tick()?;
+
+    let subscriber = harness.coordinator().subscribe_news("consumer")?;
+    let newcomer = harness.coordinator().subscribe_news("newcomer")?;
+    harness.tick()?;
+
+    let news = receive(&subscriber);
+    assert!(news.coordinator_news.is_empty());
+    assert_eq!(confirmations(&news, &tx), vec![2]);
+
+    let news = receive(&newcomer);
+    assert_eq!(
+        news.coordinator_news,
+        vec![CoordinatorNews::FundingNotFound]
+    );
+    assert_eq!(confirmations(&news, &tx), vec![2]);
+
+    // Delivery is not acknowledgement, the news stay until they are acknowledged
+    assert_eq!(
+        harness.coordinator().get_news()?.coordinator_news,
+        vec![CoordinatorNews::FundingNotFound]
+    );
+    harness
+        .coordinator()
+        .ack_news(AckNews::Coordinator(AckCoordinatorNews::FundingNotFound))?;
+    harness
+        .coordinator()
+        .ack_news(AckNews::Monitor(AckMonitorNews::Transaction(
+            tx.compute_txid(),
+            "My tx".to_string(),
+        )))?;
+    harness.tick()?;
+    assert!(matches!(subscriber.try_recv(), Err(TryRecvError::Empty)));
+
+    assert!(matches!(
+        harness.coordinator().subscribe_news(""),
+        Err(BitcoinCoordinatorError::InvalidArgument(_))
+    ));
+
+    clear_output();
+    Ok(())
+}
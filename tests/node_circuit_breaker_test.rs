@@ -0,0 +1,173 @@
+use bitcoin::Txid;
+use bitcoin_coordinator::{
+    config::CoordinatorSettingsConfig,
+    coordinator::BitcoinCoordinatorApi,
+    storage::{BitcoinCoordinatorStore, BitcoinCoordinatorStoreApi},
+    testing::CoordinatorTestHarness,
+    types::{CoordinatorNews, JournalEvent, TransactionState},
+};
+use utils::{clear_output, get_mocks, simple_tx};
+mod utils;
+
+const NODE_FAILURE_THRESHOLD: u32 = 3;
+
+fn setup() -> Result<(CoordinatorTestHarness, BitcoinCoordinatorStore), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+
+    let harness = CoordinatorTestHarness::new(
+        store.store.clone(),
+        key_manager,
+        Some(CoordinatorSettingsConfig {
+            node_failure_threshold: Some(NODE_FAILURE_THRESHOLD),
+            ..Default::default()
+        }),
+    )?;
+
+    Ok((harness, store))
+}
+
+// Dispatches transactions without speedup, so they are sent as soon as the coordinator ticks.
+fn dispatch(harness: &CoordinatorTestHarness, seeds: std::ops::Range<u32>) -> Vec<Txid> {
+    seeds
+        .map(|seed| {
+            let tx = simple_tx(seed);
+            harness.dispatch(tx.clone(), None, "My tx").unwrap();
+            tx.compute_txid()
+        })
+        .collect()
+}
+
+fn broadcast_attempts(harness: &CoordinatorTestHarness) -> Result<usize, anyhow::Error> {
+    Ok(harness
+        .coordinator()
+        .read_events(0, 1_000)?
+        .iter()
+        .filter(|entry| matches!(entry.event, JournalEvent::BroadcastAttempt { .. }))
+        .count())
+}
+
+fn count_news(harness: &CoordinatorTestHarness, kind: &str) -> Result<usize, anyhow::Error> {
+    Ok(harness
+        .coordinator()
+        .get_news()?
+        .coordinator_news
+        .iter()
+        .filter(|news| news.kind() == kind)
+        .count())
+}
+
+// The node goes down with 5 transactions to dispatch. The breaker opens after 3 failed broadcasts, the rest
+// of the tick is skipped and the next ticks only probe the node. Once the node answers the transactions are sent.
+#[test]
+fn test_breaker_opens_suppresses_work_and_closes() -> Result<(), anyhow::Error> {
+    let (harness, store) = setup()?;
+    let tx_ids = dispatch(&harness, 0..5);
+
+    harness.chain().set_unreachable(true);
+    harness.tick()?;
+
+    // Only the broadcasts before the breaker opened reached the node, without counting retries
+    assert_eq!(
+        broadcast_attempts(&harness)?,
+        NODE_FAILURE_THRESHOLD as usize
+    );
+    for tx_id in tx_ids.iter() {
+        let tx = store.get_tx(tx_id)?;
+        assert_eq!(tx.state, TransactionState::ToDispatch);
+        assert!(tx.retry_info.is_none());
+    }
+
+    // The failures before the breaker opened are reported, then a single NodeUnreachable news
+    assert_eq!(count_news(&harness, "NetworkError")?, 2);
+    assert_eq!(count_news(&harness, "NodeUnreachable")?, 1);
+
+    let readiness = harness.coordinator().readiness()?;
+    assert!(!readiness.ready);
+    let since = readiness.node_unreachable_since.unwrap();
+    assert_eq!(
+        harness
+            .coordinator()
+            .get_pending_overview()?
+            .node_unreachable_since,
+        Some(since)
+    );
+
+    // The next ticks return Ok without sending anything nor reporting more news
+    harness.tick()?;
+    harness.tick()?;
+
+    assert_eq!(
+        broadcast_attempts(&harness)?,
+        NODE_FAILURE_THRESHOLD as usize
+    );
+    assert_eq!(count_news(&harness, "NetworkError")?, 2);
+    assert!(harness
+        .coordinator()
+        .get_news()?
+        .coordinator_news
+        .contains(&CoordinatorNews::NodeUnreachable(since)));
+    assert_eq!(count_news(&harness, "NodeUnreachable")?, 1);
+
+    // The probe succeeds, the breaker closes and the transactions are sent in the same tick
+    harness.chain().set_unreachable(false);
+    harness.tick()?;
+
+    assert_eq!(count_news(&harness, "NodeRecovered")?, 1);
+    assert!(harness
+        .coordinator()
+        .readiness()?
+        .node_unreachable_since
+        .is_none());
+
+    for tx_id in tx_ids.iter() {
+        assert!(harness.chain().in_mempool(tx_id));
+        assert_eq!(store.get_tx(tx_id)?.state, TransactionState::Dispatched);
+    }
+
+    clear_output();
+    Ok(())
+}
+
+// Failures below the threshold do not open the breaker, and a broadcast reaching the node resets the count.
+#[test]
+fn test_breaker_counts_consecutive_failures() -> Result<(), anyhow::Error> {
+    let (harness, _) = setup()?;
+    let first = dispatch(&harness, 0..2);
+
+    harness.chain().set_unreachable(true);
+    harness.tick()?;
+
+    assert_eq!(count_news(&harness, "NetworkError")?, 2);
+    assert_eq!(count_news(&harness, "NodeUnreachable")?, 0);
+    assert!(harness
+        .coordinator()
+        .get_pending_overview()?
+        .node_unreachable_since
+        .is_none());
+
+    harness.chain().set_unreachable(false);
+    harness.tick()?;
+
+    for tx_id in first.iter() {
+        assert!(harness.chain().in_mempool(tx_id));
+    }
+
+    // Two more failures are not consecutive with the first ones
+    dispatch(&harness, 2..4);
+    harness.chain().set_unreachable(true);
+    harness.tick()?;
+
+    assert_eq!(count_news(&harness, "NetworkError")?, 4);
+    assert_eq!(count_news(&harness, "NodeUnreachable")?, 0);
+
+    // The next failure opens the breaker
+    dispatch(&harness, 4..5);
+    harness.tick()?;
+
+    assert_eq!(count_news(&harness, "NetworkError")?, 4);
+    assert_eq!(count_news(&harness, "NodeUnreachable")?, 1);
+    assert!(!harness.coordinator().is_ready()?);
+
+    clear_output();
+    Ok(())
+}
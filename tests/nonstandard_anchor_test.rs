@@ -0,0 +1,138 @@
+use bitcoin::{PublicKey, ScriptBuf, Transaction};
+use bitcoin_coordinator::{
+    config::CoordinatorSettingsConfig,
+    coordinator::BitcoinCoordinatorApi,
+    errors::BitcoinCoordinatorError,
+    testing::CoordinatorTestHarness,
+    types::{AckCoordinatorNews, AckNews, CoordinatorNews},
+};
+use protocol_builder::types::{output::SpeedupData, Utxo};
+use std::str::FromStr;
+use utils::{clear_output, get_mocks, tx_with_output};
+mod utils;
+
+const DUST_AMOUNT: u64 = 50;
+const P2WPKH_DUST_THRESHOLD: u64 = 294;
+
+fn public_key() -> PublicKey {
+    PublicKey::from_str("032e58afe51f9ed8ad3cc7897f634d881fdbe49a81564629ded8156bebd2ffd1af")
+        .unwrap()
+}
+
+// A transaction with a P2WPKH anchor of `amount` sats for `public_key()`, and its speedup data.
+fn tx_with_anchor(seed: u32, amount: u64) -> (Transaction, SpeedupData) {
+    let script_pubkey = ScriptBuf::new_p2wpkh(&public_key().wpubkey_hash().unwrap());
+    let tx = tx_with_output(script_pubkey, amount, seed);
+    let speedup_data = SpeedupData::new(Utxo::new(tx.compute_txid(), 0, amount, &public_key()));
+
+    (tx, speedup_data)
+}
+
+fn harness(allow_nonstandard_anchor: bool) -> Result<CoordinatorTestHarness, anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+
+    Ok(CoordinatorTestHarness::new(
+        store.store.clone(),
+        key_manager,
+        Some(CoordinatorSettingsConfig {
+            allow_nonstandard_anchor: Some(allow_nonstandard_anchor),
+            ..Default::default()
+        }),
+    )?)
+}
+
+fn nonstandard_anchor_news(
+    harness: &CoordinatorTestHarness,
+) -> Result<Vec<CoordinatorNews>, anyhow::Error> {
+    Ok(harness
+        .coordinator()
+        .get_news()?
+        .coordinator_news
+        .into_iter()
+        .filter(|news| matches!(news, CoordinatorNews::NonStandardAnchor(..)))
+        .collect())
+}
+
+// A dust anchor is rejected when it is dispatched, instead of failing every CPFP built over it.
+#[test]
+fn test_dust_anchor_rejected_at_dispatch() -> Result<(), anyhow::Error> {
+    let harness = harness(false)?;
+    let (tx, speedup_data) = tx_with_anchor(1, DUST_AMOUNT);
+
+    let result = harness.dispatch(tx.clone(), Some(speedup_data.clone()), "My tx");
+    assert!(matches!(
+        result,
+        Err(BitcoinCoordinatorError::AnchorBelowDust(
+            DUST_AMOUNT,
+            P2WPKH_DUST_THRESHOLD
+        ))
+    ));
+    assert!(harness
+        .coordinator()
+        .get_transaction_history(tx.compute_txid())
+        .is_err());
+
+    let result = harness
+        .coordinator()
+        .dispatch_batch(vec![(tx, Some(speedup_data), "My tx".to_string())], None);
+    assert!(matches!(
+        result,
+        Err(BitcoinCoordinatorError::AnchorBelowDust(..))
+    ));
+    assert!(nonstandard_anchor_news(&harness)?.is_empty());
+
+    // An anchor at the dust threshold is dispatched
+    let (tx, speedup_data) = tx_with_anchor(2, P2WPKH_DUST_THRESHOLD);
+    harness.dispatch(tx, Some(speedup_data), "My tx")?;
+
+    clear_output();
+    Ok(())
+}
+
+// With allow_nonstandard_anchor the dispatch goes through and the anchor is reported with a news.
+#[test]
+fn test_allow_nonstandard_anchor() -> Result<(), anyhow::Error> {
+    let harness = harness(true)?;
+    let (tx, speedup_data) = tx_with_anchor(1, DUST_AMOUNT);
+    let txid = tx.compute_txid();
+
+    harness.dispatch(tx, Some(speedup_data), "My tx")?;
+    assert!(harness.coordinator().get_transaction_history(txid).is_ok());
+
+    let news = nonstandard_anchor_news(&harness)?;
+    assert_eq!(news.len(), 1);
+    assert!(matches!(
+        &news[0],
+        CoordinatorNews::NonStandardAnchor(id, context, reason)
+            if *id == txid && context == "My tx" && reason.contains("dust")
+    ));
+
+    harness
+        .coordinator()
+        .ack_news(AckNews::Coordinator(AckCoordinatorNews::NonStandardAnchor(
+            txid,
+        )))?;
+    assert!(nonstandard_anchor_news(&harness)?.is_empty());
+
+    // The flag does not let through an anchor that is not an output of the transaction
+    let (tx, _) = tx_with_anchor(2, P2WPKH_DUST_THRESHOLD);
+    let (_, other_speedup_data) = tx_with_anchor(3, P2WPKH_DUST_THRESHOLD);
+    assert!(matches!(
+        harness.dispatch(tx, Some(other_speedup_data), "My tx"),
+        Err(BitcoinCoordinatorError::AnchorOutputMismatch(..))
+    ));
+
+    // Batches report their non standard anchors too
+    let (tx, speedup_data) = tx_with_anchor(4, DUST_AMOUNT);
+    let batch_txid = tx.compute_txid();
+    harness
+        .coordinator()
+        .dispatch_batch(vec![(tx, Some(speedup_data), "My batch".to_string())], None)?;
+    assert!(matches!(
+        nonstandard_anchor_news(&harness)?.as_slice(),
+        [CoordinatorNews::NonStandardAnchor(id, ..)] if *id == batch_txid
+    ));
+
+    clear_output();
+    Ok(())
+}
@@ -0,0 +1,201 @@
+use bitcoin::{Amount, Txid};
+use bitcoin_coordinator::{
+    coordinator::{BitcoinCoordinator, BitcoinCoordinatorApi},
+    observer::CoordinatorObserver,
+};
+use bitvmx_bitcoin_rpc::bitcoin_client::BitcoinClientApi;
+use protocol_builder::types::Utxo;
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+use crate::utils::{config_trace_aux, coordinate_tx, create_test_setup, TestSetupConfig};
+mod utils;
+
+#[derive(Debug, Clone, PartialEq)]
+enum ObserverCall {
+    TickCompleted(usize, usize, usize),
+    TransactionBroadcast(Txid, u32),
+    SpeedupCreated(Txid, u64, u64, usize, bool),
+    NewsEmitted(String),
+    DispatchError(Txid, String),
+}
+
+// Records every call, the tick duration is left out so calls can be compared.
+#[derive(Default)]
+struct RecordingObserver {
+    calls: RefCell<Vec<ObserverCall>>,
+}
+
+impl RecordingObserver {
+    fn take_calls(&self) -> Vec<ObserverCall> {
+        self.calls.borrow_mut().drain(..).collect()
+    }
+}
+
+impl CoordinatorObserver for RecordingObserver {
+    fn on_tick_completed(
+        &self,
+        _duration: Duration,
+        txs_pending: usize,
+        txs_in_progress: usize,
+        speedups_unconfirmed: usize,
+    ) {
+        self.calls.borrow_mut().push(ObserverCall::TickCompleted(
+            txs_pending,
+            txs_in_progress,
+            speedups_unconfirmed,
+        ));
+    }
+
+    fn on_transaction_broadcast(&self, txid: Txid, attempt: u32) {
+        self.calls
+            .borrow_mut()
+            .push(ObserverCall::TransactionBroadcast(txid, attempt));
+    }
+
+    fn on_speedup_created(
+        &self,
+        txid: Txid,
+        fee: u64,
+        fee_rate: u64,
+        num_parents: usize,
+        is_rbf: bool,
+    ) {
+        self.calls.borrow_mut().push(ObserverCall::SpeedupCreated(
+            txid,
+            fee,
+            fee_rate,
+            num_parents,
+            is_rbf,
+        ));
+    }
+
+    fn on_news_emitted(&self, kind: &str) {
+        self.calls
+            .borrow_mut()
+            .push(ObserverCall::NewsEmitted(kind.to_string()));
+    }
+
+    fn on_dispatch_error(&self, txid: Txid, error: &str) {
+        self.calls
+            .borrow_mut()
+            .push(ObserverCall::DispatchError(txid, error.to_string()));
+    }
+}
+
+// This test dispatches a transaction with speedup data using a coordinator with an observer.
+// The tick that dispatches it must report the broadcast, then the CPFP paying for it, then the tick metrics.
+// Once the CPFP is mined no speedup is reported as unconfirmed, and a cancelled dispatch reports its news.
+#[test]
+fn observer_dispatch_and_cpfp_test() -> Result<(), anyhow::Error> {
+    config_trace_aux();
+
+    let mut blocks_mined = 102;
+    let setup = create_test_setup(TestSetupConfig {
+        blocks_mined,
+        bitcoind_flags: None,
+    })?;
+
+    let amount = Amount::from_sat(23450000);
+
+    let (funding_speedup, funding_speedup_vout) = setup
+        .bitcoin_client
+        .fund_address(&setup.funding_wallet, amount)?;
+
+    // Funding speed up tx mines 1 block
+    blocks_mined += 1;
+
+    let observer = Rc::new(RecordingObserver::default());
+
+    let coordinator = Rc::new(
+        BitcoinCoordinator::new_with_paths(
+            &setup.config_bitcoin_client,
+            setup.storage.clone(),
+            setup.key_manager.clone(),
+            None,
+        )?
+        .with_observer(observer.clone()),
+    );
+
+    for _ in 0..blocks_mined {
+        coordinator.tick()?;
+    }
+
+    coordinator.add_funding(Utxo::new(
+        funding_speedup.compute_txid(),
+        funding_speedup_vout,
+        amount.to_sat(),
+        &setup.public_key,
+    ))?;
+
+    // Nothing was dispatched while the coordinator was catching up
+    assert!(observer
+        .take_calls()
+        .iter()
+        .all(|call| matches!(call, ObserverCall::TickCompleted(0, 0, 0))));
+
+    let tx = coordinate_tx(
+        coordinator.clone(),
+        amount,
+        setup.network,
+        setup.key_manager.clone(),
+        setup.bitcoin_client.clone(),
+        None,
+    )?;
+
+    coordinator.tick()?;
+
+    let calls = observer.take_calls();
+    assert_eq!(calls.len(), 3);
+    assert_eq!(
+        calls[0],
+        ObserverCall::TransactionBroadcast(tx.compute_txid(), 1)
+    );
+
+    match &calls[1] {
+        ObserverCall::SpeedupCreated(_, fee, fee_rate, num_parents, is_rbf) => {
+            assert!(*fee > 0);
+            assert!(*fee_rate > 0);
+            assert_eq!(*num_parents, 1);
+            assert!(!is_rbf);
+        }
+        call => panic!("Expected SpeedupCreated, got {:?}", call),
+    }
+
+    assert_eq!(calls[2], ObserverCall::TickCompleted(0, 1, 1));
+
+    setup
+        .bitcoin_client
+        .mine_blocks_to_address(1, &setup.funding_wallet)?;
+
+    coordinator.tick()?;
+
+    // The CPFP is mined, so it is not reported as unconfirmed anymore
+    let calls = observer.take_calls();
+    assert!(matches!(
+        calls.last(),
+        Some(ObserverCall::TickCompleted(0, 1, 0))
+    ));
+    assert!(!calls
+        .iter()
+        .any(|call| matches!(call, ObserverCall::DispatchError(..))));
+
+    // Cancelling a dispatch emits a news
+    let cancelled_tx = coordinate_tx(
+        coordinator.clone(),
+        amount,
+        setup.network,
+        setup.key_manager.clone(),
+        setup.bitcoin_client.clone(),
+        None,
+    )?;
+    coordinator.cancel_dispatch(cancelled_tx.compute_txid())?;
+
+    assert_eq!(
+        observer.take_calls(),
+        vec![ObserverCall::NewsEmitted("DispatchCancelled".to_string())]
+    );
+
+    setup.bitcoind.stop()?;
+
+    Ok(())
+}
@@ -0,0 +1,102 @@
+use bitcoin::{OutPoint, Transaction};
+use bitcoin_coordinator::{
+    coordinator::BitcoinCoordinatorApi,
+    speedup::SpeedupStore,
+    testing::CoordinatorTestHarness,
+    types::{CoordinatorNews, SpeedupState},
+};
+use key_manager::key_type::BitcoinKeyType;
+use utils::{clear_output, get_mocks, tx_with_anchor};
+mod utils;
+
+const ANCHOR_AMOUNT: u64 = 540;
+
+fn spends(tx: &Transaction, outpoint: &OutPoint) -> bool {
+    tx.input
+        .iter()
+        .any(|input| input.previous_output == *outpoint)
+}
+
+// A confirmed speedup is orphaned by a reorg in the same tick a new transaction is dispatched. The new CPFP
+// is not built from the change of the orphaned speedup, nor from the funding it spends, until it is mined again.
+#[test]
+fn test_orphaned_speedup_change_is_not_spent() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+    let funding = harness.fund(&funding_key, 1_000_000)?;
+    harness.coordinator().add_funding(funding)?;
+
+    let (tx_1, speedup_1) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 1);
+    let tx_1_id = tx_1.compute_txid();
+    harness.dispatch(tx_1, Some(speedup_1), "tx_1")?;
+    harness.tick()?;
+
+    harness.mine_blocks(1);
+    harness.tick()?;
+
+    let speedup = harness
+        .coordinator()
+        .get_speedups_for_tx(tx_1_id)?
+        .remove(0);
+    assert_eq!(
+        store.get_speedup(&speedup.tx_id)?.state,
+        SpeedupState::Confirmed
+    );
+
+    let change = speedup.change_funding().expect("speedup change");
+    let change = OutPoint::new(change.txid, change.vout);
+    let prev_funding = OutPoint::new(speedup.prev_funding.txid, speedup.prev_funding.vout);
+
+    // The block of the speedup is orphaned right before a new transaction is dispatched
+    harness.invalidate_last_block();
+
+    let (tx_2, speedup_2) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 2);
+    let tx_2_id = tx_2.compute_txid();
+    harness.dispatch(tx_2, Some(speedup_2), "tx_2")?;
+    harness.tick()?;
+
+    assert_eq!(
+        store.get_speedup(&speedup.tx_id)?.state,
+        SpeedupState::Orphaned
+    );
+    assert!(harness
+        .coordinator()
+        .get_speedups_for_tx(tx_2_id)?
+        .is_empty());
+
+    let mempool = harness.chain().mempool();
+    assert!(!mempool.iter().any(|tx| spends(tx, &change)));
+    assert!(!mempool
+        .iter()
+        .any(|tx| tx.compute_txid() != speedup.tx_id && spends(tx, &prev_funding)));
+
+    let news = harness.coordinator().get_news()?.coordinator_news;
+    assert!(news.iter().any(|news| matches!(
+        news,
+        CoordinatorNews::FundingTemporarilyUnavailable(txid, paid_txids)
+            if *txid == speedup.tx_id && paid_txids.contains(&tx_2_id)
+    )));
+
+    // Once the speedup is mined again its change funds the deferred CPFP
+    harness.mine_blocks(1);
+    harness.tick()?;
+
+    assert_eq!(
+        store.get_speedup(&speedup.tx_id)?.state,
+        SpeedupState::Confirmed
+    );
+    assert!(
+        harness
+            .coordinator()
+            .get_speedups_for_tx(tx_2_id)?
+            .iter()
+            .all(|cpfp| cpfp.prev_funding.txid == change.txid
+                && cpfp.prev_funding.vout == change.vout)
+    );
+
+    clear_output();
+    Ok(())
+}
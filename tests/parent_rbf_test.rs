@@ -0,0 +1,489 @@
+use bitcoin::{
+    absolute::LockTime, transaction::Version, Amount, OutPoint, PublicKey, ScriptBuf, Sequence,
+    Transaction, TxIn, TxOut, Witness,
+};
+use bitcoin_coordinator::{
+    config::CoordinatorSettingsConfig,
+    coordinator::BitcoinCoordinatorApi,
+    cpfp::SpeedupOutputKind,
+    errors::BitcoinCoordinatorError,
+    parent_rbf::{compute_parent_replacement, ParentReplacement, ParentTxSigner},
+    storage::{BitcoinCoordinatorStore, BitcoinCoordinatorStoreApi},
+    testing::{CoordinatorTestHarness, FakeChain},
+    types::{
+        AckCoordinatorNews, AckNews, CoordinatorNews, DispatchOptions, TransactionEvent,
+        TransactionState,
+    },
+    AckMonitorNews, MonitorNews,
+};
+use key_manager::key_type::BitcoinKeyType;
+use protocol_builder::types::{output::SpeedupData, Utxo};
+use std::{cell::Cell, rc::Rc};
+use utils::{clear_output, get_mocks};
+mod utils;
+
+const INPUT_AMOUNT: u64 = 100_000;
+const ANCHOR_AMOUNT: u64 = 540;
+const CHANGE_AMOUNT: u64 = 99_000;
+const FEE_RATE: u64 = 20;
+
+// Takes the prevouts from the fake chain and leaves the replacement unsigned, the fake chain does not check signatures.
+struct MockParentSigner {
+    chain: FakeChain,
+    signed: Cell<u32>,
+}
+
+impl ParentTxSigner for MockParentSigner {
+    fn get_prevouts(&self, tx: &Transaction) -> Result<Vec<TxOut>, BitcoinCoordinatorError> {
+        Ok(tx
+            .input
+            .iter()
+            .map(|input| {
+                let prev_tx = self
+                    .chain
+                    .get_transaction(&input.previous_output.txid)
+                    .unwrap();
+                prev_tx.output[input.previous_output.vout as usize].clone()
+            })
+            .collect())
+    }
+
+    fn sign_replacement(
+        &self,
+        replacement: Transaction,
+        _prevouts: &[TxOut],
+    ) -> Result<Transaction, BitcoinCoordinatorError> {
+        self.signed.set(self.signed.get() + 1);
+        Ok(replacement)
+    }
+}
+
+// A transaction signaling RBF, spending `input` to an anchor output and a change output.
+fn parent_tx(
+    input: &Utxo,
+    anchor_key: &PublicKey,
+    sequence: Sequence,
+) -> (Transaction, SpeedupData) {
+    let tx = Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint::new(input.txid, input.vout),
+            script_sig: ScriptBuf::new(),
+            sequence,
+            witness: Witness::new(),
+        }],
+        output: vec![
+            TxOut {
+                value: Amount::from_sat(ANCHOR_AMOUNT),
+                script_pubkey: SpeedupOutputKind::P2trKeyPath
+                    .script_pubkey(anchor_key)
+                    .unwrap(),
+            },
+            TxOut {
+                value: Amount::from_sat(CHANGE_AMOUNT),
+                script_pubkey: SpeedupOutputKind::P2trKeyPath
+                    .script_pubkey(anchor_key)
+                    .unwrap(),
+            },
+        ],
+    };
+    let speedup_data = SpeedupData::new(Utxo::new(tx.compute_txid(), 0, ANCHOR_AMOUNT, anchor_key));
+
+    (tx, speedup_data)
+}
+
+fn rbf_options() -> DispatchOptions {
+    DispatchOptions {
+        allow_rbf_of_parent: true,
+        ..Default::default()
+    }
+}
+
+// A harness with funding for CPFPs and a signer for the replacements. Returns the parent, not dispatched yet.
+fn setup() -> Result<
+    (
+        CoordinatorTestHarness,
+        Rc<MockParentSigner>,
+        BitcoinCoordinatorStore,
+        Transaction,
+        SpeedupData,
+    ),
+    anyhow::Error,
+> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+
+    let harness = CoordinatorTestHarness::new(
+        store.store.clone(),
+        key_manager,
+        Some(CoordinatorSettingsConfig::default()),
+    )?;
+
+    let signer = Rc::new(MockParentSigner {
+        chain: harness.chain().clone(),
+        signed: Cell::new(0),
+    });
+    let harness = harness.with_parent_tx_signer(signer.clone());
+
+    let funding = harness.fund(&funding_key, 1_000_000)?;
+    harness.coordinator().add_funding(funding)?;
+
+    let input = harness.fund(&anchor_key, INPUT_AMOUNT)?;
+    let (tx, speedup_data) = parent_tx(&input, &anchor_key, Sequence::ENABLE_RBF_NO_LOCKTIME);
+
+    Ok((harness, signer, store, tx, speedup_data))
+}
+
+// The replacement pays the network fee rate, the original fee times the multiplier or the incremental relay fee,
+// whichever is highest, and the extra fee is taken from the change.
+#[test]
+fn test_parent_replacement_math() -> Result<(), anyhow::Error> {
+    // The network fee rate is the highest: 125 vB * 20 sat/vB
+    assert_eq!(
+        compute_parent_replacement(460, 125, 99_000, 330, 20, 1.5, 1_000),
+        Some(ParentReplacement {
+            fee: 2_500,
+            extra_fee: 2_040,
+            change_amount: 96_960,
+        })
+    );
+
+    // The fee multiplier is the highest: 2,500 * 1.5
+    assert_eq!(
+        compute_parent_replacement(2_500, 125, 96_960, 330, 20, 1.5, 1_000),
+        Some(ParentReplacement {
+            fee: 3_750,
+            extra_fee: 1_250,
+            change_amount: 95_710,
+        })
+    );
+
+    // The incremental relay fee is the highest: 2,500 + 125 vB * 1 sat/vB
+    assert_eq!(
+        compute_parent_replacement(2_500, 125, 96_960, 330, 1, 1.0, 1_000),
+        Some(ParentReplacement {
+            fee: 2_625,
+            extra_fee: 125,
+            change_amount: 96_835,
+        })
+    );
+
+    // The change left would be dust, or the change can not pay the extra fee at all
+    assert_eq!(
+        compute_parent_replacement(460, 125, 2_300, 330, 20, 1.5, 1_000),
+        None
+    );
+    assert_eq!(
+        compute_parent_replacement(460, 125, 1_000, 330, 20, 1.5, 1_000),
+        None
+    );
+
+    // The replacement would pay more than the max fee rate
+    assert_eq!(
+        compute_parent_replacement(460, 125, 99_000, 330, 20, 1.5, 10),
+        None
+    );
+
+    clear_output();
+    Ok(())
+}
+
+// An unconfirmed parent is replaced instead of being paid by a CPFP. The replacement is monitored instead of
+// the original, is confirmed, and the news and acks of the original are remapped to it.
+#[test]
+fn test_parent_replaced_instead_of_cpfp() -> Result<(), anyhow::Error> {
+    let (harness, signer, store, tx, speedup_data) = setup()?;
+    let tx_id = tx.compute_txid();
+    let vsize = tx.vsize() as u64;
+    let original_fee = INPUT_AMOUNT - ANCHOR_AMOUNT - CHANGE_AMOUNT;
+
+    // Scheduled for the current block, so a DispatchScheduled news is reported for the original
+    let target_block_height = harness.chain().height();
+    harness.coordinator().dispatch_with_options(
+        tx,
+        Some(speedup_data),
+        "My tx".to_string(),
+        Some(target_block_height),
+        None,
+        rbf_options(),
+    )?;
+
+    harness.set_fee_rate(FEE_RATE);
+    harness.tick()?;
+
+    // Sent without a CPFP
+    assert_eq!(harness.chain().mempool().len(), 1);
+    assert!(harness.chain().in_mempool(&tx_id));
+    assert!(harness.coordinator().get_speedups_for_tx(tx_id)?.is_empty());
+    assert_eq!(signer.signed.get(), 0);
+
+    // Not confirmed after min_blocks_before_resend_speedup blocks, it is replaced
+    harness.mine_empty_blocks(1);
+    harness.tick()?;
+
+    assert_eq!(signer.signed.get(), 1);
+    let replacement_txid = store.get_replacement(&tx_id)?.unwrap();
+    let expected_fee = vsize * FEE_RATE;
+    let extra_fee = expected_fee - original_fee;
+
+    let mempool = harness.chain().mempool();
+    assert_eq!(mempool.len(), 1);
+    assert_eq!(mempool[0].compute_txid(), replacement_txid);
+    assert_eq!(
+        mempool[0].output[1].value.to_sat(),
+        CHANGE_AMOUNT - extra_fee
+    );
+
+    let news = harness.coordinator().get_news()?;
+    assert!(news
+        .coordinator_news
+        .contains(&CoordinatorNews::ParentReplaced(
+            tx_id,
+            replacement_txid,
+            extra_fee
+        )));
+    assert!(news.coordinator_news.iter().any(
+        |news| matches!(news, CoordinatorNews::DispatchScheduled(id, _) if *id == replacement_txid)
+    ));
+
+    // The replacement takes the place of the original
+    let replacement = store.get_tx(&replacement_txid)?;
+    assert_eq!(replacement.state, TransactionState::Dispatched);
+    assert_eq!(replacement.replaced_txids, vec![tx_id]);
+    assert_eq!(
+        store.get_tx_ids_by_state(&TransactionState::Dispatched)?,
+        vec![replacement_txid]
+    );
+    assert!(harness
+        .coordinator()
+        .get_transaction_history(tx_id)?
+        .events
+        .iter()
+        .any(|entry| entry.event
+            == TransactionEvent::Replaced {
+                replacement_txid,
+                extra_fee,
+                block_height: harness.chain().height(),
+            }));
+
+    // The replacement is monitored with the context of the original, the original is not
+    harness.mine_blocks(1);
+    harness.tick()?;
+
+    assert_eq!(
+        store.get_tx(&replacement_txid)?.state,
+        TransactionState::Confirmed
+    );
+    let monitor_news = harness.coordinator().get_news()?.monitor_news;
+    assert!(monitor_news.iter().any(|news| matches!(
+        news,
+        MonitorNews::Transaction(id, _, context) if *id == replacement_txid && context == "My tx"
+    )));
+    assert!(!monitor_news
+        .iter()
+        .any(|news| matches!(news, MonitorNews::Transaction(id, _, _) if *id == tx_id)));
+
+    // No CPFP was sent for the original nor its replacement
+    assert!(harness.coordinator().get_speedups_for_tx(tx_id)?.is_empty());
+    assert!(harness
+        .coordinator()
+        .get_speedups_for_tx(replacement_txid)?
+        .is_empty());
+
+    // The news are acknowledged with the txid of the original
+    let acknowledged = harness.coordinator().ack_news_batch(vec![
+        AckNews::Coordinator(AckCoordinatorNews::ParentReplaced(tx_id)),
+        AckNews::Coordinator(AckCoordinatorNews::DispatchScheduled(tx_id)),
+        AckNews::Monitor(AckMonitorNews::Transaction(tx_id, "My tx".to_string())),
+    ])?;
+    assert_eq!(acknowledged, 3);
+
+    let news = harness.coordinator().get_news()?;
+    assert!(!news.coordinator_news.iter().any(|news| matches!(
+        news,
+        CoordinatorNews::ParentReplaced(..) | CoordinatorNews::DispatchScheduled(..)
+    )));
+    assert!(!news
+        .monitor_news
+        .iter()
+        .any(|news| matches!(news, MonitorNews::Transaction(..))));
+
+    clear_output();
+    Ok(())
+}
+
+// A replacement still unconfirmed is replaced again, escalating the fee with rbf_fee_multiplier.
+// The original is resolved to the last replacement.
+#[test]
+fn test_replacement_replaced_again() -> Result<(), anyhow::Error> {
+    let (harness, signer, store, tx, speedup_data) = setup()?;
+    let tx_id = tx.compute_txid();
+    let vsize = tx.vsize() as u64;
+
+    harness.coordinator().dispatch_with_options(
+        tx,
+        Some(speedup_data),
+        "My tx".to_string(),
+        None,
+        None,
+        rbf_options(),
+    )?;
+
+    harness.set_fee_rate(FEE_RATE);
+    harness.tick()?;
+    harness.mine_empty_blocks(1);
+    harness.tick()?;
+    let first_txid = store.get_replacement(&tx_id)?.unwrap();
+
+    harness.mine_empty_blocks(1);
+    harness.tick()?;
+    assert_eq!(signer.signed.get(), 2);
+
+    let second_txid = store.get_replacement(&tx_id)?.unwrap();
+    assert_ne!(second_txid, first_txid);
+    assert_eq!(store.get_replacement(&first_txid)?, Some(second_txid));
+    assert_eq!(
+        store.get_tx(&second_txid)?.replaced_txids,
+        vec![tx_id, first_txid]
+    );
+
+    let first_fee = vsize * FEE_RATE;
+    let second_fee = (first_fee as f64 * 1.5).ceil() as u64;
+    assert!(harness.coordinator().get_news()?.coordinator_news.contains(
+        &CoordinatorNews::ParentReplaced(first_txid, second_txid, second_fee - first_fee)
+    ));
+
+    let mempool = harness.chain().mempool();
+    assert_eq!(mempool.len(), 1);
+    assert_eq!(mempool[0].compute_txid(), second_txid);
+
+    clear_output();
+    Ok(())
+}
+
+// The parent must signal RBF and a signer must be set.
+#[test]
+fn test_parent_rbf_dispatch_validation() -> Result<(), anyhow::Error> {
+    let (harness, _, _, tx, speedup_data) = setup()?;
+
+    let final_tx = Transaction {
+        input: vec![TxIn {
+            sequence: Sequence::MAX,
+            ..tx.input[0].clone()
+        }],
+        ..tx.clone()
+    };
+    let result = harness.coordinator().dispatch_with_options(
+        final_tx,
+        None,
+        "My tx".to_string(),
+        None,
+        None,
+        rbf_options(),
+    );
+    assert!(matches!(
+        result,
+        Err(BitcoinCoordinatorError::InvalidTransaction(_, _))
+    ));
+
+    let result = harness.coordinator().dispatch_with_options(
+        tx.clone(),
+        None,
+        "My tx".to_string(),
+        None,
+        None,
+        DispatchOptions {
+            parent_change_vout: Some(2),
+            ..rbf_options()
+        },
+    );
+    assert!(matches!(
+        result,
+        Err(BitcoinCoordinatorError::InvalidTransaction(_, _))
+    ));
+
+    let (_, store, _, key_manager) = get_mocks();
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+    let result = harness.coordinator().dispatch_with_options(
+        tx,
+        Some(speedup_data),
+        "My tx".to_string(),
+        None,
+        None,
+        rbf_options(),
+    );
+    assert!(matches!(
+        result,
+        Err(BitcoinCoordinatorError::InvalidConfiguration(_))
+    ));
+
+    clear_output();
+    Ok(())
+}
+
+// The replacement is saved before it is broadcast. A rejected replacement leaves the original in place, and one
+// broadcast by a tick that failed to save it is recovered from the node on the next tick without signing it again.
+#[test]
+fn test_parent_replacement_saved_before_broadcast() -> Result<(), anyhow::Error> {
+    let (harness, signer, store, tx, speedup_data) = setup()?;
+    let tx_id = tx.compute_txid();
+    let extra_fee = tx.vsize() as u64 * FEE_RATE - (INPUT_AMOUNT - ANCHOR_AMOUNT - CHANGE_AMOUNT);
+
+    let mut expected = tx.clone();
+    expected.output[1].value = Amount::from_sat(CHANGE_AMOUNT - extra_fee);
+    let replacement_txid = expected.compute_txid();
+
+    harness.coordinator().dispatch_with_options(
+        tx,
+        Some(speedup_data),
+        "My tx".to_string(),
+        None,
+        None,
+        rbf_options(),
+    )?;
+    harness.set_fee_rate(FEE_RATE);
+    harness.tick()?;
+
+    // The node rejects the replacement, the original is kept
+    harness
+        .chain()
+        .fail_next_broadcast(replacement_txid, "insufficient fee");
+    harness.mine_empty_blocks(1);
+    harness.tick()?;
+
+    assert_eq!(signer.signed.get(), 1);
+    assert!(store.get_parent_replacement_intents()?.is_empty());
+    assert_eq!(store.get_replacement(&tx_id)?, None);
+    assert_eq!(store.get_tx(&tx_id)?.state, TransactionState::Dispatched);
+    assert!(harness.chain().in_mempool(&tx_id));
+
+    // Broadcast, but the replacement is not saved in the place of the original
+    harness.fail_next_store_write(&replacement_txid.to_string());
+    harness.tick()?;
+
+    assert_eq!(signer.signed.get(), 2);
+    assert!(harness.chain().in_mempool(&replacement_txid));
+    assert_eq!(store.get_replacement(&tx_id)?, None);
+    let intents = store.get_parent_replacement_intents()?;
+    assert_eq!(intents.len(), 1);
+    assert_eq!(intents[0].replaced_txid, tx_id);
+
+    // The next tick finds it in the node and saves it
+    harness.tick()?;
+
+    assert_eq!(signer.signed.get(), 2);
+    assert!(store.get_parent_replacement_intents()?.is_empty());
+    assert_eq!(store.get_replacement(&tx_id)?, Some(replacement_txid));
+    assert_eq!(
+        store.get_tx(&replacement_txid)?.state,
+        TransactionState::Dispatched
+    );
+    assert!(harness.coordinator().get_news()?.coordinator_news.contains(
+        &CoordinatorNews::ParentReplaced(tx_id, replacement_txid, extra_fee)
+    ));
+
+    clear_output();
+    Ok(())
+}
@@ -0,0 +1,160 @@
+use bitcoin::{Amount, OutPoint};
+use bitcoin_coordinator::{
+    coordinator::{BitcoinCoordinator, BitcoinCoordinatorApi},
+    TypesToMonitor,
+};
+use bitcoincore_rpc::{Auth, Client, RpcApi};
+use bitvmx_bitcoin_rpc::bitcoin_client::BitcoinClientApi;
+use protocol_builder::{
+    builder::ProtocolBuilder,
+    types::{output::SpeedupData, OutputType, Utxo},
+};
+use utils::generate_tx;
+
+use crate::utils::{config_trace_aux, create_test_setup, TestSetupConfig};
+mod utils;
+
+const CPFP_FEE: u64 = 2_000;
+
+// Speedup data of a transaction created by another protocol, where only the outpoint, amount and
+// output type of its speedup output are known.
+fn partial_speedup_data(utxo: &Utxo) -> Result<SpeedupData, anyhow::Error> {
+    Ok(SpeedupData {
+        utxo: None,
+        partial_utxo: Some((
+            utxo.txid,
+            utxo.vout,
+            utxo.amount,
+            Some(OutputType::segwit_key(utxo.amount, &utxo.pub_key)?),
+        )),
+    })
+}
+
+// A CPFP spending a partial utxo is built and signed by the protocol builder and accepted by the node.
+// Then a transaction paid by a partial utxo is dispatched through the coordinator and mined with its CPFP.
+#[test]
+fn partial_speedup_utxo_test() -> Result<(), anyhow::Error> {
+    config_trace_aux();
+
+    let setup = create_test_setup(TestSetupConfig {
+        blocks_mined: 101,
+        bitcoind_flags: None,
+    })?;
+
+    let rpc_client = Client::new(
+        &setup.config_bitcoin_client.url,
+        Auth::UserPass(
+            setup.config_bitcoin_client.username.clone(),
+            setup.config_bitcoin_client.password.clone(),
+        ),
+    )?;
+
+    let amount = Amount::from_sat(23450000);
+
+    let (funding_tx, funding_vout) = setup
+        .bitcoin_client
+        .fund_address(&setup.funding_wallet, amount)?;
+    let (funding_speedup, funding_speedup_vout) = setup
+        .bitcoin_client
+        .fund_address(&setup.funding_wallet, amount)?;
+
+    let funding = Utxo::new(
+        funding_speedup.compute_txid(),
+        funding_speedup_vout,
+        amount.to_sat(),
+        &setup.public_key,
+    );
+
+    let (tx1, tx1_speedup_utxo) = generate_tx(
+        OutPoint::new(funding_tx.compute_txid(), funding_vout),
+        amount.to_sat(),
+        setup.public_key,
+        setup.key_manager.clone(),
+        172,
+    )?;
+
+    setup.bitcoin_client.send_transaction(&tx1)?;
+
+    let cpfp = (ProtocolBuilder {}).speedup_transactions(
+        &[partial_speedup_data(&tx1_speedup_utxo)?],
+        funding.clone(),
+        &setup.public_key,
+        CPFP_FEE,
+        &setup.key_manager,
+    )?;
+
+    assert_eq!(
+        cpfp.input[0].previous_output,
+        OutPoint::new(tx1_speedup_utxo.txid, tx1_speedup_utxo.vout)
+    );
+
+    let result = rpc_client.test_mempool_accept(&[&cpfp])?;
+    assert!(result[0].allowed, "{:?}", result[0].reject_reason);
+
+    // The coordinator pays for a transaction with a partial utxo using the same funding
+    let coordinator = BitcoinCoordinator::new_with_paths(
+        &setup.config_bitcoin_client,
+        setup.storage.clone(),
+        setup.key_manager.clone(),
+        None,
+    )?;
+
+    // Catch up with the blocks mined by the setup and the fundings.
+    coordinator.sync_to_tip(None)?;
+
+    let (funding_tx2, funding_vout2) = setup
+        .bitcoin_client
+        .fund_address(&setup.funding_wallet, amount)?;
+
+    let (tx2, tx2_speedup_utxo) = generate_tx(
+        OutPoint::new(funding_tx2.compute_txid(), funding_vout2),
+        amount.to_sat(),
+        setup.public_key,
+        setup.key_manager.clone(),
+        172,
+    )?;
+
+    let tx_context = "My tx".to_string();
+    coordinator.monitor(TypesToMonitor::Transactions(
+        vec![tx2.compute_txid()],
+        tx_context.clone(),
+        None,
+    ))?;
+    coordinator.dispatch(
+        tx2.clone(),
+        Some(partial_speedup_data(&tx2_speedup_utxo)?),
+        tx_context,
+        None,
+        None,
+    )?;
+    coordinator.add_funding(funding)?;
+
+    // Dispatch tx2 and its CPFP
+    coordinator.tick()?;
+
+    let overview = coordinator.get_pending_overview()?;
+    assert_eq!(overview.unconfirmed_speedups.len(), 1);
+    assert_eq!(
+        overview.unconfirmed_speedups[0].paid_txids,
+        vec![tx2.compute_txid()]
+    );
+
+    let speedup_txid = overview.unconfirmed_speedups[0].tx_id;
+    // The node accepted the CPFP, its change output is in the mempool
+    assert!(rpc_client
+        .get_tx_out(&speedup_txid, 0, Some(true))?
+        .is_some());
+
+    setup
+        .bitcoin_client
+        .mine_blocks_to_address(1, &setup.funding_wallet)?;
+
+    coordinator.tick()?;
+
+    let news = coordinator.get_news()?;
+    assert!(!news.monitor_news.is_empty());
+
+    setup.bitcoind.stop()?;
+
+    Ok(())
+}
@@ -0,0 +1,199 @@
+use bitcoin::{PublicKey, Transaction};
+use bitcoin_coordinator::{
+    coordinator::{BitcoinCoordinator, BitcoinCoordinatorApi},
+    cpfp::{build_cpfp_tx, SpeedupOutputKind},
+    errors::BitcoinCoordinatorError,
+    types::TransactionState,
+};
+use bitcoincore_rpc::{Auth, Client};
+use bitvmx_transaction_monitor::errors::MonitorError;
+use key_manager::key_type::BitcoinKeyType;
+use protocol_builder::types::{output::SpeedupData, OutputType, Utxo};
+use utils::{clear_output, get_mocks, tx_with_output};
+mod utils;
+
+const CURRENT_HEIGHT: u32 = 100;
+const ANCHOR_AMOUNT: u64 = 540;
+const FUNDING_AMOUNT: u64 = 100_000;
+const FEE: u64 = 1_000;
+
+fn partial_speedup_data(
+    tx: &Transaction,
+    public_key: &PublicKey,
+) -> Result<SpeedupData, anyhow::Error> {
+    Ok(SpeedupData {
+        utxo: None,
+        partial_utxo: Some((
+            tx.compute_txid(),
+            0,
+            ANCHOR_AMOUNT,
+            Some(OutputType::segwit_key(ANCHOR_AMOUNT, public_key)?),
+        )),
+    })
+}
+
+#[test]
+fn test_partial_speedup_output_kind() -> Result<(), anyhow::Error> {
+    let (_, _, _, key_manager) = get_mocks();
+    let public_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 0)?;
+
+    // A partial utxo on a P2WPKH output is a key spend
+    let tx = tx_with_output(
+        SpeedupOutputKind::P2wpkh.script_pubkey(&public_key)?,
+        ANCHOR_AMOUNT,
+        0,
+    );
+    let speedup_data = partial_speedup_data(&tx, &public_key)?;
+    assert_eq!(
+        SpeedupOutputKind::of_speedup_utxo(&tx, &speedup_data),
+        SpeedupOutputKind::P2wpkh
+    );
+
+    // A partial utxo on a P2WSH output is a script spend, with a bigger witness than a key spend
+    let tx = tx_with_output(
+        SpeedupOutputKind::P2wshScript.script_pubkey(&public_key)?,
+        ANCHOR_AMOUNT,
+        1,
+    );
+    let speedup_data = SpeedupData {
+        utxo: None,
+        partial_utxo: Some((tx.compute_txid(), 0, ANCHOR_AMOUNT, None)),
+    };
+    assert_eq!(
+        SpeedupOutputKind::of_speedup_utxo(&tx, &speedup_data),
+        SpeedupOutputKind::P2wshScript
+    );
+    assert!(
+        SpeedupOutputKind::P2wshScript.dummy_witness().size()
+            > SpeedupOutputKind::P2wpkh.dummy_witness().size()
+    );
+
+    // Script spends are only signed by the protocol builder
+    let funding_tx = tx_with_output(
+        SpeedupOutputKind::P2wpkh.script_pubkey(&public_key)?,
+        FUNDING_AMOUNT,
+        2,
+    );
+    let anchors = vec![(
+        Utxo::new(tx.compute_txid(), 0, ANCHOR_AMOUNT, &public_key),
+        SpeedupOutputKind::P2wshScript,
+    )];
+    let funding = Utxo::new(funding_tx.compute_txid(), 0, FUNDING_AMOUNT, &public_key);
+    assert!(matches!(
+        build_cpfp_tx(&anchors, &funding, &funding.pub_key, FEE, &key_manager),
+        Err(BitcoinCoordinatorError::SpeedupSigningError(_))
+    ));
+
+    clear_output();
+    Ok(())
+}
+
+// A CPFP can not spend a taproot anchor and a partial utxo, so the transaction paid by the partial utxo
+// is left to the next tick.
+#[test]
+fn test_partial_utxo_is_not_batched_with_taproot_anchor() -> Result<(), anyhow::Error> {
+    let (mut mock_monitor, store, mut mock_bitcoin_client, key_manager) = get_mocks();
+    let taproot_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let segwit_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+    let context = "My tx".to_string();
+
+    let taproot_tx = tx_with_output(
+        SpeedupOutputKind::P2trKeyPath.script_pubkey(&taproot_key)?,
+        ANCHOR_AMOUNT,
+        3,
+    );
+    let taproot_speedup_data = SpeedupData::new(Utxo::new(
+        taproot_tx.compute_txid(),
+        0,
+        ANCHOR_AMOUNT,
+        &taproot_key,
+    ));
+
+    let partial_tx = tx_with_output(
+        SpeedupOutputKind::P2wpkh.script_pubkey(&segwit_key)?,
+        ANCHOR_AMOUNT,
+        4,
+    );
+    let partial_data = partial_speedup_data(&partial_tx, &segwit_key)?;
+
+    let funding_tx = tx_with_output(
+        SpeedupOutputKind::P2wpkh.script_pubkey(&funding_key)?,
+        FUNDING_AMOUNT,
+        5,
+    );
+
+    mock_monitor.expect_monitor().returning(|_| Ok(()));
+    mock_monitor.expect_tick().returning(|| Ok(()));
+    mock_monitor.expect_is_ready().returning(|| Ok(true));
+    mock_monitor
+        .expect_get_monitor_height()
+        .returning(|| Ok(CURRENT_HEIGHT));
+    // Without a current block the speedup news are not stored
+    mock_monitor
+        .expect_get_current_block()
+        .returning(|| Ok(None));
+    mock_monitor.expect_get_news().returning(|| Ok(vec![]));
+    mock_monitor
+        .expect_get_tx_status()
+        .returning(|tx_id| Err(MonitorError::TransactionNotFound(tx_id.to_string())));
+    mock_monitor
+        .expect_get_estimated_fee_rate()
+        .returning(|| Ok(2));
+
+    mock_bitcoin_client
+        .expect_send_transaction()
+        .returning(|tx| Ok(tx.compute_txid()));
+    mock_bitcoin_client
+        .expect_get_best_block()
+        .returning(|| Ok(CURRENT_HEIGHT));
+
+    let coordinator = BitcoinCoordinator::builder()
+        .with_monitor(Box::new(mock_monitor))
+        .with_store(store)
+        .with_client(Box::new(mock_bitcoin_client))
+        .with_rpc_client(Client::new("http://127.0.0.1:18443", Auth::None)?)
+        .with_key_manager(key_manager)
+        .build()?;
+
+    coordinator.add_funding(Utxo::new(
+        funding_tx.compute_txid(),
+        0,
+        FUNDING_AMOUNT,
+        &funding_key,
+    ))?;
+
+    coordinator.dispatch(
+        taproot_tx.clone(),
+        Some(taproot_speedup_data),
+        context.clone(),
+        None,
+        None,
+    )?;
+    coordinator.dispatch(partial_tx.clone(), Some(partial_data), context, None, None)?;
+
+    coordinator.tick()?;
+
+    assert_eq!(
+        coordinator
+            .get_transaction_history(taproot_tx.compute_txid())?
+            .state,
+        TransactionState::Dispatched
+    );
+    assert_eq!(
+        coordinator
+            .get_transaction_history(partial_tx.compute_txid())?
+            .state,
+        TransactionState::ToDispatch
+    );
+
+    let overview = coordinator.get_pending_overview()?;
+    assert_eq!(overview.unconfirmed_speedups.len(), 1);
+    assert_eq!(
+        overview.unconfirmed_speedups[0].paid_txids,
+        vec![taproot_tx.compute_txid()]
+    );
+
+    clear_output();
+    Ok(())
+}
@@ -0,0 +1,199 @@
+use bitcoin::{PublicKey, Txid};
+use bitcoin_coordinator::{
+    coordinator::{BitcoinCoordinator, BitcoinCoordinatorApi},
+    speedup::SpeedupStore,
+    storage::BitcoinCoordinatorStoreApi,
+    types::{
+        CoordinatedSpeedUpTransaction, PendingReason, PendingTxEntry, SpeedupParent, SpeedupState,
+        TransactionState,
+    },
+};
+use bitcoincore_rpc::{Auth, Client};
+use protocol_builder::types::{output::SpeedupData, Utxo};
+use std::str::FromStr;
+use utils::{clear_output, get_mocks, simple_tx};
+mod utils;
+
+const CURRENT_HEIGHT: u32 = 100;
+const FUNDING_AMOUNT: u64 = 100_000;
+const SPEEDUP_FEE: u64 = 1_000;
+
+fn utxo(txid: Txid, amount: u64) -> Utxo {
+    Utxo::new(
+        txid,
+        0,
+        amount,
+        &PublicKey::from_str("032e58afe51f9ed8ad3cc7897f634d881fdbe49a81564629ded8156bebd2ffd1af")
+            .unwrap(),
+    )
+}
+
+fn reasons(entries: &[PendingTxEntry]) -> Vec<(Txid, Option<PendingReason>)> {
+    entries
+        .iter()
+        .map(|entry| (entry.tx_id, entry.pending_reason))
+        .collect()
+}
+
+#[test]
+fn test_pending_overview() -> Result<(), anyhow::Error> {
+    let (mut mock_monitor, store, mock_bitcoin_client, key_manager) = get_mocks();
+    let context = "My tx".to_string();
+
+    // Transactions waiting to be dispatched
+    let ready = simple_tx(1);
+    store.save_tx(ready.clone(), None, None, context.clone())?;
+
+    let scheduled = simple_tx(2);
+    store.save_tx(
+        scheduled.clone(),
+        None,
+        Some(CURRENT_HEIGHT + 10),
+        context.clone(),
+    )?;
+
+    let retrying = simple_tx(3);
+    store.save_tx(retrying.clone(), None, None, context.clone())?;
+    store.increment_tx_retry_count(retrying.compute_txid())?;
+
+    let with_speedup = simple_tx(4);
+    let speedup_data = SpeedupData::new(utxo(with_speedup.compute_txid(), 540));
+    store.save_tx(
+        with_speedup.clone(),
+        Some(speedup_data),
+        None,
+        context.clone(),
+    )?;
+
+    // Dispatched transactions, one of them waiting for funding to be sped up
+    let dispatched = simple_tx(5);
+    store.save_tx(dispatched.clone(), None, None, context.clone())?;
+    store.update_tx_to_dispatched(dispatched.compute_txid(), CURRENT_HEIGHT, 1)?;
+
+    let deferred = simple_tx(6);
+    store.save_tx(deferred.clone(), None, None, context.clone())?;
+    store.update_tx_to_dispatched(deferred.compute_txid(), CURRENT_HEIGHT, 1)?;
+    store.defer_speedup(&[deferred.compute_txid()])?;
+
+    // Confirmed transactions are not pending anymore
+    let confirmed = simple_tx(7);
+    store.save_tx(confirmed.clone(), None, None, context.clone())?;
+    store.update_tx_to_dispatched(confirmed.compute_txid(), CURRENT_HEIGHT, 1)?;
+    store.update_tx_state(confirmed.compute_txid(), TransactionState::Confirmed)?;
+
+    // Without funding the transaction with speedup can not be dispatched
+    let entries = store.get_pending_tx_entries(CURRENT_HEIGHT)?;
+    assert_eq!(
+        reasons(&entries),
+        vec![
+            (ready.compute_txid(), None),
+            (
+                scheduled.compute_txid(),
+                Some(PendingReason::TargetHeightNotReached)
+            ),
+            (retrying.compute_txid(), Some(PendingReason::RetryBackoff)),
+            (
+                with_speedup.compute_txid(),
+                Some(PendingReason::FundingBlocked)
+            ),
+            (dispatched.compute_txid(), None),
+            (deferred.compute_txid(), Some(PendingReason::FundingBlocked)),
+        ]
+    );
+
+    // The target height is reached
+    let entries = store.get_pending_tx_entries(CURRENT_HEIGHT + 10)?;
+    assert_eq!(entries[1].pending_reason, None);
+    assert_eq!(entries[1].target_block_height, Some(CURRENT_HEIGHT + 10));
+
+    // With funding the transaction with speedup is dispatched on the next tick
+    let funding_txid = simple_tx(8).compute_txid();
+    store.add_funding(utxo(funding_txid, FUNDING_AMOUNT))?;
+
+    let entries = store.get_pending_tx_entries(CURRENT_HEIGHT)?;
+    assert_eq!(entries[3].pending_reason, None);
+    assert!(entries[3].has_speedup);
+
+    // A speedup paying for the dispatched transaction
+    let speedup_txid = simple_tx(9).compute_txid();
+    store.save_speedup(CoordinatedSpeedUpTransaction::new(
+        speedup_txid,
+        utxo(funding_txid, FUNDING_AMOUNT),
+        utxo(speedup_txid, FUNDING_AMOUNT - SPEEDUP_FEE),
+        false,
+        CURRENT_HEIGHT,
+        SpeedupState::Dispatched,
+        1.0,
+        vec![SpeedupParent::new(
+            SpeedupData::new(utxo(dispatched.compute_txid(), 540)),
+            &dispatched,
+            context.clone(),
+        )],
+        3,
+        150,
+    ))?;
+
+    mock_monitor
+        .expect_get_monitor_height()
+        .returning(|| Ok(CURRENT_HEIGHT));
+
+    let coordinator = BitcoinCoordinator::builder()
+        .with_monitor(Box::new(mock_monitor))
+        .with_store(store)
+        .with_client(Box::new(mock_bitcoin_client))
+        .with_rpc_client(Client::new("http://127.0.0.1:18443", Auth::None)?)
+        .with_key_manager(key_manager)
+        .build()?;
+
+    let overview = coordinator.get_pending_overview()?;
+
+    // The store allows one unconfirmed speedup, so the speedup chain is full
+    assert_eq!(
+        reasons(&overview.to_dispatch),
+        vec![
+            (ready.compute_txid(), None),
+            (
+                scheduled.compute_txid(),
+                Some(PendingReason::TargetHeightNotReached)
+            ),
+            (retrying.compute_txid(), Some(PendingReason::RetryBackoff)),
+            (
+                with_speedup.compute_txid(),
+                Some(PendingReason::FundingBlocked)
+            ),
+        ]
+    );
+    assert_eq!(overview.to_dispatch[2].retry_count, 1);
+
+    assert_eq!(
+        reasons(&overview.dispatched),
+        vec![
+            (dispatched.compute_txid(), None),
+            (deferred.compute_txid(), Some(PendingReason::FundingBlocked)),
+        ]
+    );
+    assert!(overview
+        .dispatched
+        .iter()
+        .all(|entry| entry.state == TransactionState::Dispatched
+            && entry.broadcast_block_height == Some(CURRENT_HEIGHT)));
+
+    assert_eq!(overview.unconfirmed_speedups.len(), 1);
+    let speedup = &overview.unconfirmed_speedups[0];
+    assert_eq!(speedup.tx_id, speedup_txid);
+    assert_eq!(speedup.state, SpeedupState::Dispatched);
+    assert_eq!(speedup.fee, SPEEDUP_FEE);
+    assert_eq!(speedup.network_fee_rate_used, 3);
+    assert_eq!(speedup.paid_txids, vec![dispatched.compute_txid()]);
+
+    // The overview can be dumped as JSON
+    let json = serde_json::to_value(&overview)?;
+    assert_eq!(json["to_dispatch"].as_array().unwrap().len(), 4);
+    assert_eq!(
+        json["dispatched"][1]["pending_reason"],
+        serde_json::json!("FundingBlocked")
+    );
+
+    clear_output();
+    Ok(())
+}
@@ -0,0 +1,96 @@
+use bitcoin_coordinator::{
+    errors::{BitcoinBroadcastErrorKind, BitcoinCoordinatorError},
+    rbf::{escalate_replacement, RbfEscalation},
+};
+use std::cell::RefCell;
+
+const INSUFFICIENT_FEE_ERROR: &str =
+    "insufficient fee, rejecting replacement, not enough additional fees to relay";
+const BUMP_FEE_PERCENTAGE: f64 = 1.5;
+
+// Simulates a node that rejects the first `rejections` replacements for insufficient fee and accepts the next one.
+fn send_replacement(
+    rejections: usize,
+    bump_fees_used: &RefCell<Vec<f64>>,
+    bump_fee: f64,
+) -> Result<Option<String>, BitcoinCoordinatorError> {
+    bump_fees_used.borrow_mut().push(bump_fee);
+
+    if bump_fees_used.borrow().len() > rejections {
+        return Ok(None);
+    }
+
+    let error_kind = BitcoinBroadcastErrorKind::from_error_message(INSUFFICIENT_FEE_ERROR);
+    assert_eq!(
+        error_kind,
+        BitcoinBroadcastErrorKind::InsufficientReplacementFee
+    );
+
+    Ok(Some(INSUFFICIENT_FEE_ERROR.to_string()))
+}
+
+#[test]
+fn test_rbf_escalation_accepted_after_two_rejections() -> Result<(), anyhow::Error> {
+    let bump_fees_used = RefCell::new(vec![]);
+
+    let escalation = escalate_replacement(
+        5,
+        1.0,
+        |bump_fee| send_replacement(2, &bump_fees_used, bump_fee),
+        |bump_fee| Ok(bump_fee * BUMP_FEE_PERCENTAGE),
+    )?;
+
+    assert_eq!(
+        escalation,
+        RbfEscalation::Sent {
+            attempts: 3,
+            bump_fee: 2.25
+        }
+    );
+
+    // Each rejection increases the bump fee before the next attempt.
+    assert_eq!(*bump_fees_used.borrow(), vec![1.0, 1.5, 2.25]);
+
+    Ok(())
+}
+
+#[test]
+fn test_rbf_escalation_fails_after_max_attempts() -> Result<(), anyhow::Error> {
+    let bump_fees_used = RefCell::new(vec![]);
+
+    let escalation = escalate_replacement(
+        2,
+        1.0,
+        |bump_fee| send_replacement(3, &bump_fees_used, bump_fee),
+        |bump_fee| Ok(bump_fee * BUMP_FEE_PERCENTAGE),
+    )?;
+
+    match escalation {
+        RbfEscalation::Failed {
+            attempts,
+            last_error,
+        } => {
+            assert_eq!(attempts, 2);
+            assert!(last_error.contains(INSUFFICIENT_FEE_ERROR));
+        }
+        _ => panic!("Expected the RBF escalation to fail"),
+    }
+
+    assert_eq!(*bump_fees_used.borrow(), vec![1.0, 1.5]);
+
+    Ok(())
+}
+
+#[test]
+fn test_insufficient_fee_error_kind() {
+    assert_eq!(
+        BitcoinBroadcastErrorKind::from_error_message(
+            "insufficient fee, rejecting replacement 7e5d, new feerate 0.00002 <= old feerate 0.00002"
+        ),
+        BitcoinBroadcastErrorKind::InsufficientReplacementFee
+    );
+    assert_eq!(
+        BitcoinBroadcastErrorKind::from_error_message("mempool min fee not met"),
+        BitcoinBroadcastErrorKind::MempoolRejection
+    );
+}
@@ -0,0 +1,183 @@
+use bitcoin_coordinator::{
+    errors::BitcoinCoordinatorError,
+    readiness::{readiness_report, sync_to_tip},
+    types::ReadinessReport,
+};
+use std::{
+    cell::{Cell, RefCell},
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+};
+use utils::{clear_output, get_mocks};
+mod utils;
+
+// The monitor indexed block 90 while the node is at block 100, so the coordinator is not ready yet.
+#[test]
+fn test_readiness_report_while_indexing() -> Result<(), anyhow::Error> {
+    let (mut mock_monitor, _, mut mock_bitcoin_client, _) = get_mocks();
+
+    mock_monitor
+        .expect_get_monitor_height()
+        .times(1)
+        .returning(|| Ok(90));
+
+    mock_monitor
+        .expect_is_ready()
+        .times(1)
+        .returning(|| Ok(false));
+
+    mock_bitcoin_client
+        .expect_get_best_block()
+        .times(1)
+        .returning(|| Ok(100));
+
+    let report = readiness_report(&mock_monitor, &mock_bitcoin_client, true, None)?;
+
+    assert_eq!(
+        report,
+        ReadinessReport {
+            ready: false,
+            indexed_height: 90,
+            tip_height: 100,
+            blocks_remaining: 10,
+            has_pending_txs: true,
+            node_unreachable_since: None,
+        }
+    );
+
+    clear_output();
+    Ok(())
+}
+
+#[test]
+fn test_readiness_report_when_ready() -> Result<(), anyhow::Error> {
+    let (mut mock_monitor, _, mut mock_bitcoin_client, _) = get_mocks();
+
+    mock_monitor
+        .expect_get_monitor_height()
+        .returning(|| Ok(100));
+    mock_monitor.expect_is_ready().returning(|| Ok(true));
+    mock_bitcoin_client
+        .expect_get_best_block()
+        .returning(|| Ok(100));
+
+    let report = readiness_report(&mock_monitor, &mock_bitcoin_client, false, None)?;
+
+    assert!(report.ready);
+    assert_eq!(report.blocks_remaining, 0);
+    assert!(!report.has_pending_txs);
+
+    // The report can be logged as JSON
+    let json = serde_json::to_string(&report)?;
+    assert_eq!(serde_json::from_str::<ReadinessReport>(&json)?, report);
+
+    clear_output();
+    Ok(())
+}
+
+// While the node is unreachable it is not asked for its tip, and the coordinator is not ready.
+#[test]
+fn test_readiness_report_node_unreachable() -> Result<(), anyhow::Error> {
+    let (mut mock_monitor, _, mock_bitcoin_client, _) = get_mocks();
+
+    mock_monitor
+        .expect_get_monitor_height()
+        .times(1)
+        .returning(|| Ok(100));
+
+    let report = readiness_report(&mock_monitor, &mock_bitcoin_client, false, Some(1_000))?;
+
+    assert_eq!(
+        report,
+        ReadinessReport {
+            ready: false,
+            indexed_height: 100,
+            tip_height: 100,
+            blocks_remaining: 0,
+            has_pending_txs: false,
+            node_unreachable_since: Some(1_000),
+        }
+    );
+
+    clear_output();
+    Ok(())
+}
+
+// The monitor indexes one block per tick from block 95 up to the tip at block 100.
+#[test]
+fn test_sync_to_tip_reports_progress() -> Result<(), anyhow::Error> {
+    let (mut mock_monitor, _, mut mock_bitcoin_client, _) = get_mocks();
+    let height = Arc::new(AtomicU32::new(95));
+
+    let tick_height = height.clone();
+    mock_monitor.expect_tick().times(5).returning(move || {
+        tick_height.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    });
+
+    let monitor_height = height.clone();
+    mock_monitor
+        .expect_get_monitor_height()
+        .returning(move || Ok(monitor_height.load(Ordering::SeqCst)));
+
+    let ready_height = height.clone();
+    mock_monitor
+        .expect_is_ready()
+        .returning(move || Ok(ready_height.load(Ordering::SeqCst) == 100));
+
+    mock_bitcoin_client
+        .expect_get_best_block()
+        .returning(|| Ok(100));
+
+    let reported = RefCell::new(vec![]);
+    let progress =
+        |indexed_height, tip_height| reported.borrow_mut().push((indexed_height, tip_height));
+
+    sync_to_tip(&mock_monitor, &mock_bitcoin_client, 3, Some(&progress))?;
+
+    assert_eq!(
+        reported.into_inner(),
+        vec![(96, 100), (97, 100), (98, 100), (99, 100), (100, 100)]
+    );
+
+    clear_output();
+    Ok(())
+}
+
+// The monitor indexes block 91 and gets stuck, sync_to_tip gives up after 3 ticks without progress.
+#[test]
+fn test_sync_to_tip_stalled() -> Result<(), anyhow::Error> {
+    let (mut mock_monitor, _, mut mock_bitcoin_client, _) = get_mocks();
+    let height = Arc::new(AtomicU32::new(90));
+
+    let tick_height = height.clone();
+    mock_monitor.expect_tick().times(4).returning(move || {
+        tick_height.store(91, Ordering::SeqCst);
+        Ok(())
+    });
+
+    let monitor_height = height.clone();
+    mock_monitor
+        .expect_get_monitor_height()
+        .returning(move || Ok(monitor_height.load(Ordering::SeqCst)));
+    mock_monitor.expect_is_ready().returning(|| Ok(false));
+    mock_bitcoin_client
+        .expect_get_best_block()
+        .returning(|| Ok(100));
+
+    let ticks = Cell::new(0);
+    let progress = |_, _| ticks.set(ticks.get() + 1);
+
+    let result = sync_to_tip(&mock_monitor, &mock_bitcoin_client, 3, Some(&progress));
+
+    assert!(matches!(
+        result,
+        Err(BitcoinCoordinatorError::SyncStalled(91, 3))
+    ));
+    assert_eq!(ticks.get(), 4);
+
+    clear_output();
+    Ok(())
+}
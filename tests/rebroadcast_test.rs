@@ -0,0 +1,167 @@
+use bitcoin::{hashes::Hash, BlockHash};
+use bitcoin_coordinator::{
+    config::{CoordinatorSettings, CoordinatorSettingsConfig},
+    rebroadcast::{next_rebroadcast_action, rebroadcast_missing_tx, RebroadcastAction},
+    storage::BitcoinCoordinatorStoreApi,
+    types::{CoordinatorNews, TransactionEvent},
+};
+use protocol_builder::types::output::SpeedupData;
+use utils::{clear_output, get_mock_data, get_mocks};
+mod utils;
+
+const DISPATCH_HEIGHT: u32 = 100;
+const REBROADCAST_AFTER_BLOCKS: u32 = 3;
+const MAX_REBROADCAST_ATTEMPTS: u32 = 2;
+
+fn settings() -> CoordinatorSettings {
+    CoordinatorSettingsConfig {
+        rebroadcast_after_blocks: Some(REBROADCAST_AFTER_BLOCKS),
+        max_rebroadcast_attempts: Some(MAX_REBROADCAST_ATTEMPTS),
+        ..Default::default()
+    }
+    .into()
+}
+
+// The monitor does not find a dispatched transaction without speedup for 12 blocks.
+// It is sent again every 3 blocks, twice, and then a MaxRebroadcastAttemptsReached news is reported.
+#[test]
+fn test_rebroadcast_missing_tx() -> Result<(), anyhow::Error> {
+    let (_, store, mut mock_bitcoin_client, key_manager) = get_mocks();
+    let (_, tx, _, tx_id, context, _) = get_mock_data(key_manager);
+
+    store.save_tx(tx.clone(), None, None, context)?;
+    store.update_tx_to_dispatched(tx_id, DISPATCH_HEIGHT, 1)?;
+
+    mock_bitcoin_client
+        .expect_send_transaction()
+        .times(MAX_REBROADCAST_ATTEMPTS as usize)
+        .returning(move |_| Ok(tx_id));
+
+    let settings = settings();
+    let mut news = vec![];
+
+    // One tick per block while the monitor does not find the transaction
+    for height in DISPATCH_HEIGHT..=DISPATCH_HEIGHT + 12 {
+        let tx = store.get_tx(&tx_id)?;
+
+        if let Some(tick_news) =
+            rebroadcast_missing_tx(&mock_bitcoin_client, &store, &tx, height, &settings)?
+        {
+            news.push((height, tick_news));
+        }
+    }
+
+    let expected = vec![
+        (103, CoordinatorNews::TransactionRebroadcast(tx_id, 1)),
+        (106, CoordinatorNews::TransactionRebroadcast(tx_id, 2)),
+        (
+            109,
+            CoordinatorNews::MaxRebroadcastAttemptsReached(tx_id, 2),
+        ),
+        (
+            110,
+            CoordinatorNews::MaxRebroadcastAttemptsReached(tx_id, 2),
+        ),
+        (
+            111,
+            CoordinatorNews::MaxRebroadcastAttemptsReached(tx_id, 2),
+        ),
+        (
+            112,
+            CoordinatorNews::MaxRebroadcastAttemptsReached(tx_id, 2),
+        ),
+    ];
+    assert_eq!(news, expected);
+
+    let tx = store.get_tx(&tx_id)?;
+    assert_eq!(tx.rebroadcast_count, 2);
+    assert_eq!(tx.last_rebroadcast_block_height, Some(106));
+
+    // Every rebroadcast is recorded in the transaction history
+    let rebroadcasts: Vec<TransactionEvent> = store
+        .get_tx_history(&tx_id)?
+        .events
+        .into_iter()
+        .map(|entry| entry.event)
+        .filter(|event| matches!(event, TransactionEvent::Rebroadcast { .. }))
+        .collect();
+    assert_eq!(
+        rebroadcasts,
+        vec![
+            TransactionEvent::Rebroadcast {
+                attempt: 1,
+                block_height: 103,
+            },
+            TransactionEvent::Rebroadcast {
+                attempt: 2,
+                block_height: 106,
+            },
+        ]
+    );
+
+    // The terminal news is stored once
+    for news in [
+        CoordinatorNews::TransactionRebroadcast(tx_id, 2),
+        CoordinatorNews::MaxRebroadcastAttemptsReached(tx_id, 2),
+        CoordinatorNews::MaxRebroadcastAttemptsReached(tx_id, 2),
+    ] {
+        store.update_news(news, BlockHash::all_zeros())?;
+    }
+    assert_eq!(
+        store.get_news()?,
+        vec![
+            CoordinatorNews::TransactionRebroadcast(tx_id, 2),
+            CoordinatorNews::MaxRebroadcastAttemptsReached(tx_id, 2),
+        ]
+    );
+
+    clear_output();
+    Ok(())
+}
+
+// Only dispatched transactions without speedup are rebroadcast.
+#[test]
+fn test_rebroadcast_policy() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let (_, tx, _, tx_id, context, speedup_utxo) = get_mock_data(key_manager);
+
+    store.save_tx(tx.clone(), None, None, context.clone())?;
+
+    let action = |tx_id, height| -> Result<RebroadcastAction, anyhow::Error> {
+        Ok(next_rebroadcast_action(
+            &store.get_tx(&tx_id)?,
+            height,
+            REBROADCAST_AFTER_BLOCKS,
+            MAX_REBROADCAST_ATTEMPTS,
+        ))
+    };
+
+    // Not dispatched yet
+    assert_eq!(
+        action(tx_id, DISPATCH_HEIGHT + 10)?,
+        RebroadcastAction::Wait
+    );
+
+    store.update_tx_to_dispatched(tx_id, DISPATCH_HEIGHT, 1)?;
+    assert_eq!(action(tx_id, DISPATCH_HEIGHT + 2)?, RebroadcastAction::Wait);
+    assert_eq!(
+        action(tx_id, DISPATCH_HEIGHT + 3)?,
+        RebroadcastAction::Rebroadcast(1)
+    );
+
+    // Transactions with speedup are bumped with a CPFP instead
+    let mut speedup_tx = store.get_tx(&tx_id)?;
+    speedup_tx.speedup_data = Some(SpeedupData::new(speedup_utxo));
+    assert_eq!(
+        next_rebroadcast_action(
+            &speedup_tx,
+            DISPATCH_HEIGHT + 10,
+            REBROADCAST_AFTER_BLOCKS,
+            MAX_REBROADCAST_ATTEMPTS,
+        ),
+        RebroadcastAction::Wait
+    );
+
+    clear_output();
+    Ok(())
+}
@@ -0,0 +1,187 @@
+use bitcoin::{
+    absolute::LockTime, hashes::Hash, transaction::Version, Amount, BlockHash, OutPoint, ScriptBuf,
+    Sequence, Transaction, TxIn, TxOut, Witness,
+};
+use bitcoin_coordinator::{
+    coordinator::{BitcoinCoordinator, BitcoinCoordinatorApi},
+    errors::BitcoinCoordinatorStoreError,
+    storage::BitcoinCoordinatorStoreApi,
+    types::{CoordinatorNews, TransactionEvent, TransactionState},
+};
+use bitcoincore_rpc::{Auth, Client};
+use bitvmx_transaction_monitor::types::{
+    BlockInfo, FullBlock, TransactionBlockchainStatus, TransactionStatus,
+};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+use utils::{clear_output, get_mock_data, get_mocks};
+mod utils;
+
+const CURRENT_HEIGHT: u32 = 100;
+
+fn block_hash(seed: u8) -> BlockHash {
+    BlockHash::from_byte_array([seed; 32])
+}
+
+fn tx_to_dispatch() -> Transaction {
+    Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint::null(),
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: Amount::from_sat(10_000),
+            script_pubkey: ScriptBuf::new(),
+        }],
+    }
+}
+
+#[test]
+fn test_reorg_confirmed_tx_in_store() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let (_, tx, _, tx_id, context, _) = get_mock_data(key_manager);
+    let orphan_block_hash = block_hash(1);
+
+    store.save_tx(tx, None, None, context.clone())?;
+    store.update_tx_to_dispatched(tx_id, CURRENT_HEIGHT, 1)?;
+
+    // Only a confirmed transaction can be reorged
+    assert!(matches!(
+        store.reorg_tx(tx_id, orphan_block_hash, block_hash(2)),
+        Err(BitcoinCoordinatorStoreError::InvalidTransactionState)
+    ));
+
+    store.update_tx_state(tx_id, TransactionState::Confirmed)?;
+
+    // A plain Confirmed to Dispatched transition is still rejected
+    assert!(store
+        .update_tx_state(tx_id, TransactionState::Dispatched)
+        .is_err());
+
+    let tx = store.reorg_tx(tx_id, orphan_block_hash, block_hash(2))?;
+    assert_eq!(tx.state, TransactionState::Dispatched);
+    assert_eq!(store.get_tx(&tx_id)?.state, TransactionState::Dispatched);
+
+    let history = store.get_tx_history(&tx_id)?;
+    let events: Vec<TransactionEvent> = history.events.into_iter().map(|e| e.event).collect();
+    assert!(events.ends_with(&[
+        TransactionEvent::Reorged {
+            block_hash: orphan_block_hash,
+        },
+        TransactionEvent::StateChanged {
+            from: TransactionState::Confirmed,
+            to: TransactionState::Dispatched,
+        },
+    ]));
+
+    let expected_news = CoordinatorNews::TransactionReorged(tx_id, orphan_block_hash, context);
+    assert_eq!(store.get_news()?, vec![expected_news]);
+
+    clear_output();
+    Ok(())
+}
+
+// A transaction is confirmed on the first tick, and on the second tick the monitor reports its block as orphan.
+// The transaction goes back to Dispatched, is sent again and a TransactionReorged news is reported.
+#[test]
+fn test_confirmed_tx_flips_to_orphan() -> Result<(), anyhow::Error> {
+    let (mut mock_monitor, store, mut mock_bitcoin_client, key_manager) = get_mocks();
+    let tx = tx_to_dispatch();
+    let tx_id = tx.compute_txid();
+    let context = "My tx".to_string();
+    let orphan_block_hash = block_hash(1);
+
+    let status_calls = Arc::new(AtomicUsize::new(0));
+    let status_tx = tx.clone();
+    let calls = status_calls.clone();
+    mock_monitor.expect_get_tx_status().returning(move |_| {
+        let orphan = calls.fetch_add(1, Ordering::SeqCst) > 0;
+
+        Ok(TransactionStatus {
+            tx_id,
+            tx: status_tx.clone(),
+            block_info: Some(BlockInfo {
+                height: CURRENT_HEIGHT,
+                hash: orphan_block_hash,
+                is_orphan: orphan,
+            }),
+            confirmations: if orphan { 0 } else { 1 },
+            status: if orphan {
+                TransactionBlockchainStatus::Orphan
+            } else {
+                TransactionBlockchainStatus::Confirmed
+            },
+        })
+    });
+
+    mock_monitor.expect_monitor().returning(|_| Ok(()));
+    mock_monitor.expect_tick().returning(|| Ok(()));
+    mock_monitor.expect_is_ready().returning(|| Ok(true));
+    mock_monitor
+        .expect_get_monitor_height()
+        .returning(|| Ok(CURRENT_HEIGHT));
+    mock_monitor.expect_get_current_block().returning(|| {
+        Ok(Some(FullBlock {
+            height: CURRENT_HEIGHT,
+            hash: block_hash(2),
+            prev_hash: block_hash(3),
+            txs: vec![],
+            orphan: false,
+        }))
+    });
+    mock_monitor.expect_get_news().returning(|| Ok(vec![]));
+    mock_monitor
+        .expect_get_estimated_fee_rate()
+        .returning(|| Ok(2));
+
+    // Sent once on dispatch and once more after the reorg
+    mock_bitcoin_client
+        .expect_send_transaction()
+        .times(2)
+        .returning(move |_| Ok(tx_id));
+    mock_bitcoin_client
+        .expect_get_best_block()
+        .returning(|| Ok(CURRENT_HEIGHT));
+
+    let coordinator = BitcoinCoordinator::builder()
+        .with_monitor(Box::new(mock_monitor))
+        .with_store(store)
+        .with_client(Box::new(mock_bitcoin_client))
+        .with_rpc_client(Client::new("http://127.0.0.1:18443", Auth::None)?)
+        .with_key_manager(key_manager)
+        .build()?;
+
+    coordinator.dispatch(tx, None, context.clone(), None, None)?;
+
+    coordinator.tick()?;
+    assert_eq!(
+        coordinator.get_transaction_history(tx_id)?.state,
+        TransactionState::Confirmed
+    );
+
+    coordinator.tick()?;
+    let history = coordinator.get_transaction_history(tx_id)?;
+    assert_eq!(history.state, TransactionState::Dispatched);
+    assert!(history.events.iter().any(|entry| entry.event
+        == TransactionEvent::Reorged {
+            block_hash: orphan_block_hash,
+        }));
+
+    let news = coordinator.get_news()?;
+    assert!(news
+        .coordinator_news
+        .contains(&CoordinatorNews::TransactionReorged(
+            tx_id,
+            orphan_block_hash,
+            context
+        )));
+
+    clear_output();
+    Ok(())
+}
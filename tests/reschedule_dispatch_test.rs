@@ -0,0 +1,149 @@
+use bitcoin::{Amount, OutPoint};
+use bitcoin_coordinator::{
+    coordinator::{BitcoinCoordinator, BitcoinCoordinatorApi},
+    errors::BitcoinCoordinatorError,
+    types::{AckCoordinatorNews, AckNews, CoordinatorNews},
+    TypesToMonitor,
+};
+use bitvmx_bitcoin_rpc::bitcoin_client::BitcoinClientApi;
+use protocol_builder::types::{output::SpeedupData, Utxo};
+use std::rc::Rc;
+
+use crate::utils::{config_trace_aux, create_test_setup, generate_tx, TestSetupConfig};
+mod utils;
+
+// This test verifies the rescheduling of a transaction dispatched for a target block height.
+//
+// The test procedure includes:
+// - Dispatching a transaction for a target block height and moving the target further.
+// - Mining block by block, the transaction stays scheduled until the new target is reached.
+// - Once broadcast, the transaction is reported with DispatchScheduled and it can not be rescheduled.
+#[test]
+fn reschedule_dispatch_test() -> Result<(), anyhow::Error> {
+    config_trace_aux();
+
+    let mut blocks_mined = 102;
+    let setup = create_test_setup(TestSetupConfig {
+        blocks_mined,
+        bitcoind_flags: None,
+    })?;
+
+    let amount = Amount::from_sat(23450000);
+
+    let (funding_speedup, funding_speedup_vout) = setup
+        .bitcoin_client
+        .fund_address(&setup.funding_wallet, amount)?;
+
+    // Funding speed up tx mines 1 block
+    blocks_mined += 1;
+
+    let (funding_tx, funding_vout) = setup
+        .bitcoin_client
+        .fund_address(&setup.funding_wallet, amount)?;
+
+    // Funding tx mines 1 block
+    blocks_mined += 1;
+
+    let coordinator = Rc::new(BitcoinCoordinator::new_with_paths(
+        &setup.config_bitcoin_client,
+        setup.storage.clone(),
+        setup.key_manager.clone(),
+        None,
+    )?);
+
+    for _ in 0..blocks_mined {
+        coordinator.tick()?;
+    }
+
+    coordinator.add_funding(Utxo::new(
+        funding_speedup.compute_txid(),
+        funding_speedup_vout,
+        amount.to_sat(),
+        &setup.public_key,
+    ))?;
+
+    let (tx, speedup_utxo) = generate_tx(
+        OutPoint::new(funding_tx.compute_txid(), funding_vout),
+        amount.to_sat(),
+        setup.public_key,
+        setup.key_manager.clone(),
+        172,
+    )?;
+    let tx_id = tx.compute_txid();
+    let context = "My scheduled tx".to_string();
+
+    coordinator.monitor(TypesToMonitor::Transactions(
+        vec![tx_id],
+        context.clone(),
+        None,
+    ))?;
+
+    let current_height = setup.bitcoin_client.get_best_block()?;
+    let target_height = current_height + 2;
+    let new_target_height = current_height + 4;
+
+    coordinator.dispatch(
+        tx,
+        Some(SpeedupData::new(speedup_utxo)),
+        context.clone(),
+        Some(target_height),
+        None,
+    )?;
+
+    assert_eq!(
+        coordinator.get_scheduled_dispatches()?,
+        vec![(tx_id, target_height, context.clone())]
+    );
+
+    coordinator.reschedule_dispatch(tx_id, Some(new_target_height))?;
+
+    assert_eq!(
+        coordinator.get_scheduled_dispatches()?,
+        vec![(tx_id, new_target_height, context.clone())]
+    );
+
+    // The transaction is not broadcast at the original target height.
+    for height in current_height + 1..new_target_height {
+        setup
+            .bitcoin_client
+            .mine_blocks_to_address(1, &setup.funding_wallet)?;
+        coordinator.tick()?;
+
+        assert_eq!(
+            coordinator.get_scheduled_dispatches()?,
+            vec![(tx_id, new_target_height, context.clone())],
+            "Transaction dispatched before the new target at height {height}"
+        );
+    }
+
+    // Reaching the new target height broadcasts the transaction.
+    setup
+        .bitcoin_client
+        .mine_blocks_to_address(1, &setup.funding_wallet)?;
+    coordinator.tick()?;
+    coordinator.tick()?;
+
+    assert!(coordinator.get_scheduled_dispatches()?.is_empty());
+
+    let news = coordinator.get_news()?;
+    assert!(news.coordinator_news.iter().any(|news| matches!(
+        news,
+        CoordinatorNews::DispatchScheduled(txid, height)
+            if *txid == tx_id && *height == new_target_height
+    )));
+
+    coordinator.ack_news(AckNews::Coordinator(AckCoordinatorNews::DispatchScheduled(
+        tx_id,
+    )))?;
+
+    // A broadcast transaction can not be rescheduled.
+    let result = coordinator.reschedule_dispatch(tx_id, Some(new_target_height + 10));
+    assert!(matches!(
+        result,
+        Err(BitcoinCoordinatorError::CannotRescheduleDispatched(txid)) if txid == tx_id
+    ));
+
+    setup.bitcoind.stop()?;
+
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use crate::utils::{config_trace_aux, create_test_setup, generate_tx, TestSetupConfig};
+use bitcoin::{Amount, OutPoint};
+use bitcoin_coordinator::{
+    coordinator::{BitcoinCoordinator, BitcoinCoordinatorApi},
+    speedup::SpeedupStore,
+    storage::{BitcoinCoordinatorStore, BitcoinCoordinatorStoreApi},
+    TypesToMonitor,
+};
+use bitvmx_bitcoin_rpc::bitcoin_client::BitcoinClientApi;
+use protocol_builder::types::{output::SpeedupData, Utxo};
+mod utils;
+
+// This test simulates a process that stops after broadcasting a transaction and before creating its CPFP.
+// A new coordinator over the same storage must create a CPFP for the transaction on its first tick,
+// and following ticks must not create another CPFP for it.
+#[test]
+fn restart_recovery_test() -> Result<(), anyhow::Error> {
+    config_trace_aux();
+
+    let mut blocks_mined = 102;
+    let setup = create_test_setup(TestSetupConfig {
+        blocks_mined,
+        bitcoind_flags: None,
+    })?;
+
+    let amount = Amount::from_sat(23450000);
+
+    let (funding_speedup, funding_speedup_vout) = setup
+        .bitcoin_client
+        .fund_address(&setup.funding_wallet, amount)?;
+
+    // Funding speed up tx mines 1 block
+    blocks_mined += 1;
+
+    let (funding_tx, funding_vout) = setup
+        .bitcoin_client
+        .fund_address(&setup.funding_wallet, amount)?;
+
+    // Funding tx mines 1 block
+    blocks_mined += 1;
+
+    let coordinator = BitcoinCoordinator::new_with_paths(
+        &setup.config_bitcoin_client,
+        setup.storage.clone(),
+        setup.key_manager.clone(),
+        None,
+    )?;
+
+    for _ in 0..blocks_mined {
+        coordinator.tick()?;
+    }
+
+    coordinator.add_funding(Utxo::new(
+        funding_speedup.compute_txid(),
+        funding_speedup_vout,
+        amount.to_sat(),
+        &setup.public_key,
+    ))?;
+
+    let (tx, speedup_utxo) = generate_tx(
+        OutPoint::new(funding_tx.compute_txid(), funding_vout),
+        amount.to_sat(),
+        setup.public_key,
+        setup.key_manager.clone(),
+        172,
+    )?;
+    let tx_id = tx.compute_txid();
+
+    coordinator.monitor(TypesToMonitor::Transactions(
+        vec![tx_id],
+        "My tx".to_string(),
+        None,
+    ))?;
+
+    // The transaction is broadcast and marked as dispatched, then the process stops before its CPFP is created.
+    let store = BitcoinCoordinatorStore::new(setup.storage.clone(), 10, 3, 2)?;
+    store.save_tx(
+        tx.clone(),
+        Some(SpeedupData::new(speedup_utxo)),
+        None,
+        "My tx".to_string(),
+    )?;
+    setup.bitcoin_client.send_transaction(&tx)?;
+    store.update_tx_to_dispatched(tx_id, setup.bitcoin_client.get_best_block()?, 1)?;
+    drop(coordinator);
+
+    assert_eq!(store.get_dispatched_txs_without_speedup()?.len(), 1);
+
+    // Restart the coordinator over the same storage.
+    let coordinator = BitcoinCoordinator::new_with_paths(
+        &setup.config_bitcoin_client,
+        setup.storage.clone(),
+        setup.key_manager.clone(),
+        None,
+    )?;
+
+    coordinator.tick()?;
+
+    let speedups_paying_tx = |store: &BitcoinCoordinatorStore| -> Result<usize, anyhow::Error> {
+        Ok(store
+            .get_all_pending_speedups()?
+            .iter()
+            .filter(|speedup| {
+                speedup
+                    .speedup_tx_data
+                    .iter()
+                    .any(|parent| parent.tx_id == tx_id)
+            })
+            .count())
+    };
+
+    assert_eq!(speedups_paying_tx(&store)?, 1);
+    assert!(store.get_dispatched_txs_without_speedup()?.is_empty());
+
+    // Further ticks and restarts do not pay for the transaction again.
+    coordinator.tick()?;
+    drop(coordinator);
+
+    let coordinator = BitcoinCoordinator::new_with_paths(
+        &setup.config_bitcoin_client,
+        setup.storage.clone(),
+        setup.key_manager.clone(),
+        None,
+    )?;
+    coordinator.tick()?;
+
+    assert_eq!(speedups_paying_tx(&store)?, 1);
+
+    setup.bitcoind.stop()?;
+
+    Ok(())
+}
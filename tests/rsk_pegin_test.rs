@@ -0,0 +1,142 @@
+use bitcoin::{
+    absolute::LockTime, hashes::Hash, transaction::Version, Amount, BlockHash, ScriptBuf,
+    Transaction, TxOut,
+};
+use bitcoin_coordinator::{
+    news::filter_monitor_news,
+    pegin::{pegin_output, record_detected_pegins},
+    storage::BitcoinCoordinatorStoreApi,
+    types::DetectedPegin,
+    AckMonitorNews, BlockInfo, MonitorNews, TransactionStatus,
+};
+use bitvmx_transaction_monitor::{monitor::MonitorApi, types::TransactionBlockchainStatus};
+use std::collections::HashMap;
+use utils::{clear_output, get_mocks};
+mod utils;
+
+const PEGIN_AMOUNT: u64 = 500_000;
+const PEGIN_HEIGHT: u32 = 120;
+
+// A peg-in paying the federation after the OP_RETURN with the RSK destination.
+fn pegin_tx() -> Transaction {
+    Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![],
+        output: vec![
+            TxOut {
+                value: Amount::ZERO,
+                script_pubkey: ScriptBuf::new_op_return([0x52, 0x53, 0x4b, 0x54]),
+            },
+            TxOut {
+                value: Amount::from_sat(PEGIN_AMOUNT),
+                script_pubkey: ScriptBuf::new_p2wsh(&bitcoin::WScriptHash::all_zeros()),
+            },
+        ],
+    }
+}
+
+fn pegin_news(tx: &Transaction, block_info: Option<BlockInfo>) -> MonitorNews {
+    let tx_id = tx.compute_txid();
+
+    MonitorNews::RskPeginTransaction(
+        tx_id,
+        TransactionStatus {
+            tx_id,
+            tx: tx.clone(),
+            confirmations: if block_info.is_some() { 1 } else { 0 },
+            block_info,
+            status: TransactionBlockchainStatus::Confirmed,
+        },
+    )
+}
+
+#[test]
+fn test_detected_pegins_are_surfaced_and_recorded() -> Result<(), anyhow::Error> {
+    let (mut mock_monitor, store, _, _) = get_mocks();
+
+    let tx = pegin_tx();
+    let tx_id = tx.compute_txid();
+    let block_info = BlockInfo {
+        height: PEGIN_HEIGHT,
+        hash: BlockHash::all_zeros(),
+        is_orphan: false,
+    };
+    let news = pegin_news(&tx, Some(block_info));
+
+    // The monitor reports the peg-in until it is acknowledged
+    let monitor_news = news.clone();
+    mock_monitor
+        .expect_get_news()
+        .returning(move || Ok(vec![monitor_news.clone()]));
+
+    mock_monitor
+        .expect_ack_news()
+        .withf(move |ack| *ack == AckMonitorNews::RskPeginTransaction(tx_id))
+        .times(1)
+        .returning(|_| Ok(()));
+
+    store.watch_rsk_pegins("My pegins".to_string())?;
+    let context = store.get_rsk_pegin_context()?.unwrap();
+
+    let expected = DetectedPegin {
+        tx_id,
+        vout: 1,
+        amount: PEGIN_AMOUNT,
+        block_height: PEGIN_HEIGHT,
+        context: "My pegins".to_string(),
+    };
+
+    // Recorded once, even if the monitor reports it on every tick
+    assert_eq!(
+        record_detected_pegins(&mock_monitor, &store, &context)?,
+        vec![expected.clone()]
+    );
+    assert!(record_detected_pegins(&mock_monitor, &store, &context)?.is_empty());
+
+    // The peg-in news is not filtered out of the monitor news
+    let surfaced: Vec<MonitorNews> = filter_monitor_news(
+        mock_monitor.get_news()?,
+        store.get_watched_outpoints()?,
+        HashMap::new(),
+    )
+    .collect();
+    assert_eq!(surfaced, vec![news]);
+
+    // It is acknowledged through the monitor and still queryable afterwards
+    mock_monitor.ack_news(AckMonitorNews::RskPeginTransaction(tx_id))?;
+
+    assert_eq!(store.get_detected_pegins(PEGIN_HEIGHT)?, vec![expected]);
+    assert!(store.get_detected_pegins(PEGIN_HEIGHT + 1)?.is_empty());
+
+    clear_output();
+    Ok(())
+}
+
+#[test]
+fn test_unmined_pegins_are_not_recorded() -> Result<(), anyhow::Error> {
+    let (mut mock_monitor, store, _, _) = get_mocks();
+
+    let tx = pegin_tx();
+    let news = pegin_news(&tx, None);
+
+    mock_monitor
+        .expect_get_news()
+        .returning(move || Ok(vec![news.clone()]));
+
+    assert!(record_detected_pegins(&mock_monitor, &store, "My pegins")?.is_empty());
+    assert!(store.get_detected_pegins(0)?.is_empty());
+
+    // The pegged-in output skips the OP_RETURN
+    assert_eq!(pegin_output(&tx), Some((1, PEGIN_AMOUNT)));
+    assert_eq!(
+        pegin_output(&Transaction {
+            output: vec![tx.output[0].clone()],
+            ..tx
+        }),
+        None
+    );
+
+    clear_output();
+    Ok(())
+}
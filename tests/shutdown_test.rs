@@ -0,0 +1,176 @@
+use bitcoin_coordinator::{
+    coordinator::BitcoinCoordinatorApi,
+    errors::BitcoinCoordinatorError,
+    storage::BitcoinCoordinatorStoreApi,
+    testing::CoordinatorTestHarness,
+    types::{CoordinatorRunState, TransactionState},
+};
+use bitvmx_transaction_monitor::types::TypesToMonitor;
+use key_manager::key_type::BitcoinKeyType;
+use utils::{clear_output, get_mocks, tx_with_anchor};
+mod utils;
+
+// The queued transactions are left in the checkpoint, the coordinator refuses new work and the next one resumes it.
+#[test]
+fn test_shutdown_with_queued_work() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager.clone(), None)?;
+
+    assert_eq!(harness.coordinator().previous_run_state(), None);
+    assert_eq!(store.get_run_state()?, Some(CoordinatorRunState::Running));
+
+    let funding = harness.fund(&funding_key, 1_000_000)?;
+    harness.coordinator().add_funding(funding)?;
+
+    let txs: Vec<_> = (0..3)
+        .map(|seed| tx_with_anchor(&anchor_key, 0, seed))
+        .collect();
+    for (tx, speedup_data) in txs.iter() {
+        harness.dispatch(tx.clone(), Some(speedup_data.clone()), "My tx")?;
+    }
+
+    let report = harness.coordinator().shutdown()?;
+    let checkpoint = report.checkpoint.clone();
+
+    assert_eq!(report.flushed_store_writes, 0);
+    assert_eq!(checkpoint.monitor_height, harness.chain().height());
+    assert_eq!(checkpoint.txs_to_dispatch, 3);
+    assert_eq!(checkpoint.txs_in_progress, 0);
+    assert_eq!(checkpoint.pending_store_writes, 0);
+    assert!(checkpoint.is_clean());
+    assert_eq!(
+        store.get_run_state()?,
+        Some(CoordinatorRunState::Stopped(checkpoint.clone()))
+    );
+
+    // Nothing is accepted after the shutdown
+    let (tx, speedup_data) = tx_with_anchor(&anchor_key, 0, 10);
+    assert!(matches!(
+        harness.dispatch(tx.clone(), Some(speedup_data), "Late tx"),
+        Err(BitcoinCoordinatorError::CoordinatorStopped)
+    ));
+    assert!(matches!(
+        harness.coordinator().monitor(TypesToMonitor::Transactions(
+            vec![tx.compute_txid()],
+            "Late tx".to_string(),
+            None
+        )),
+        Err(BitcoinCoordinatorError::CoordinatorStopped)
+    ));
+    assert!(matches!(
+        harness.tick(),
+        Err(BitcoinCoordinatorError::CoordinatorStopped)
+    ));
+    assert!(matches!(
+        harness.coordinator().shutdown(),
+        Err(BitcoinCoordinatorError::CoordinatorStopped)
+    ));
+    assert!(harness.chain().mempool().is_empty());
+    assert_eq!(store.get_txs_to_dispatch()?.len(), 3);
+
+    // The next coordinator reads the checkpoint and dispatches the queued transactions
+    let chain = harness.chain().clone();
+    drop(harness);
+    let harness =
+        CoordinatorTestHarness::with_chain(chain, store.store.clone(), key_manager, None)?;
+
+    assert_eq!(
+        harness.coordinator().previous_run_state(),
+        Some(&CoordinatorRunState::Stopped(checkpoint))
+    );
+    assert_eq!(store.get_run_state()?, Some(CoordinatorRunState::Running));
+
+    harness.tick()?;
+
+    for (tx, _) in txs.iter() {
+        assert!(harness.chain().in_mempool(&tx.compute_txid()));
+    }
+    assert_eq!(harness.chain().mempool().len(), 4);
+
+    clear_output();
+    Ok(())
+}
+
+// A broadcast transaction whose save failed is saved by the shutdown instead of waiting for the next tick.
+#[test]
+fn test_shutdown_flushes_pending_store_writes() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+
+    let funding = harness.fund(&funding_key, 1_000_000)?;
+    harness.coordinator().add_funding(funding)?;
+
+    let (tx, speedup_data) = tx_with_anchor(&anchor_key, 0, 1);
+    harness.dispatch(tx.clone(), Some(speedup_data), "My tx")?;
+
+    harness.fail_next_store_write(&format!("tx/{}", tx.compute_txid()));
+    harness.tick()?;
+
+    assert!(harness.chain().in_mempool(&tx.compute_txid()));
+    assert_eq!(
+        store.get_tx(&tx.compute_txid())?.state,
+        TransactionState::ToDispatch
+    );
+
+    let report = harness.coordinator().shutdown()?;
+
+    assert_eq!(report.flushed_store_writes, 1);
+    assert_eq!(report.checkpoint.pending_store_writes, 0);
+    assert_eq!(report.checkpoint.txs_to_dispatch, 0);
+    assert_eq!(report.checkpoint.txs_in_progress, 1);
+    assert_eq!(
+        store.get_tx(&tx.compute_txid())?.state,
+        TransactionState::Dispatched
+    );
+
+    // The transaction is still waiting for its CPFP, so the stop is not clean
+    assert_eq!(report.checkpoint.txs_without_speedup, 1);
+    assert!(!report.checkpoint.is_clean());
+
+    clear_output();
+    Ok(())
+}
+
+// A coordinator dropped without shutdown leaves the Running state, the next one reconciles.
+#[test]
+fn test_unclean_stop_is_detected() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager.clone(), None)?;
+
+    let funding = harness.fund(&funding_key, 1_000_000)?;
+    harness.coordinator().add_funding(funding)?;
+
+    // The transaction is broadcast and saved, but its CPFP is never created
+    let (tx, speedup_data) = tx_with_anchor(&anchor_key, 0, 1);
+    harness.dispatch(tx.clone(), Some(speedup_data), "My tx")?;
+    harness.fail_next_store_write("speedup/intent");
+    assert!(harness.tick().is_err());
+    assert_eq!(
+        store.get_tx(&tx.compute_txid())?.state,
+        TransactionState::Dispatched
+    );
+    assert_eq!(harness.chain().mempool().len(), 1);
+
+    let chain = harness.chain().clone();
+    drop(harness);
+    let harness =
+        CoordinatorTestHarness::with_chain(chain, store.store.clone(), key_manager, None)?;
+
+    assert_eq!(
+        harness.coordinator().previous_run_state(),
+        Some(&CoordinatorRunState::Running)
+    );
+
+    // The reconciliation pays for the transaction left without a CPFP
+    harness.tick()?;
+    assert_eq!(harness.chain().mempool().len(), 2);
+
+    clear_output();
+    Ok(())
+}
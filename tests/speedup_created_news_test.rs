@@ -0,0 +1,88 @@
+use bitcoin::Transaction;
+use bitcoin_coordinator::{
+    coordinator::BitcoinCoordinatorApi,
+    testing::CoordinatorTestHarness,
+    types::{AckCoordinatorNews, AckNews, CoordinatorNews, SpeedupState},
+    MonitorNews,
+};
+use key_manager::key_type::BitcoinKeyType;
+use utils::{clear_output, get_mocks, tx_with_anchor};
+mod utils;
+
+const ANCHOR_AMOUNT: u64 = 540;
+const FUNDING_AMOUNT: u64 = 100_000;
+
+// The CPFP created for a dispatched transaction is reported as a SpeedupCreated news linked to its parent,
+// and returned by get_speedups_for_tx until it is confirmed.
+#[test]
+fn test_speedup_created_news_and_query() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+    let funding = harness.fund(&funding_key, FUNDING_AMOUNT)?;
+    harness.coordinator().add_funding(funding)?;
+
+    let (tx, speedup_data) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 1);
+    let tx_id = tx.compute_txid();
+
+    assert!(harness.coordinator().get_speedups_for_tx(tx_id)?.is_empty());
+
+    harness.dispatch(tx, Some(speedup_data), "My tx")?;
+    harness.tick()?;
+
+    let cpfp = harness.chain().mempool()[1].clone();
+    let cpfp_id = cpfp.compute_txid();
+    let cpfp_fee = FUNDING_AMOUNT + ANCHOR_AMOUNT - cpfp.output[0].value.to_sat();
+
+    let news = harness.coordinator().get_news()?;
+    let created: Vec<&CoordinatorNews> = news
+        .coordinator_news
+        .iter()
+        .filter(|news| matches!(news, CoordinatorNews::SpeedupCreated(..)))
+        .collect();
+    assert_eq!(
+        created,
+        vec![&CoordinatorNews::SpeedupCreated(
+            cpfp_id,
+            vec![tx_id],
+            cpfp_fee,
+            CoordinatorTestHarness::INITIAL_FEE_RATE,
+            false,
+        )]
+    );
+
+    let speedups = harness.coordinator().get_speedups_for_tx(tx_id)?;
+    assert_eq!(speedups.len(), 1);
+    assert_eq!(speedups[0].tx_id, cpfp_id);
+    assert_eq!(speedups[0].paid_txids, vec![tx_id]);
+    assert_eq!(speedups[0].state, SpeedupState::Dispatched);
+    assert!(!speedups[0].is_rbf);
+
+    harness
+        .coordinator()
+        .ack_news(AckNews::Coordinator(AckCoordinatorNews::SpeedupCreated(
+            cpfp_id,
+        )))?;
+
+    harness.mine_blocks(1);
+    harness.tick()?;
+
+    // The CPFP is not reported again, and its confirmation is not reported as monitor news
+    let news = harness.coordinator().get_news()?;
+    assert!(!news
+        .coordinator_news
+        .iter()
+        .any(|news| matches!(news, CoordinatorNews::SpeedupCreated(..))));
+    assert!(!news
+        .monitor_news
+        .iter()
+        .any(|news| matches!(news, MonitorNews::Transaction(id, _, _) if *id == cpfp_id)));
+
+    let speedups = harness.coordinator().get_speedups_for_tx(tx_id)?;
+    assert_eq!(speedups[0].state, SpeedupState::Confirmed);
+
+    clear_output();
+    Ok(())
+}
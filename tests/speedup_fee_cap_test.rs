@@ -0,0 +1,128 @@
+use bitcoin::Transaction;
+use bitcoin_coordinator::{
+    config::CoordinatorSettingsConfig,
+    coordinator::BitcoinCoordinatorApi,
+    testing::CoordinatorTestHarness,
+    types::{AckCoordinatorNews, AckNews, CoordinatorNews, TransactionState},
+};
+use key_manager::key_type::BitcoinKeyType;
+use utils::{clear_output, get_mocks, tx_with_anchor};
+mod utils;
+
+const ANCHOR_AMOUNT: u64 = 540;
+const FUNDING_AMOUNT: u64 = 1_000_000;
+// At this fee rate a CPFP paying one transaction costs about 4_600 sats, and about 7_000 paying two.
+const FEE_RATE: u64 = 20;
+
+// A funded harness capping the CPFP fee of each batch, with `count` transactions dispatched.
+fn setup(
+    max_cpfp_fee_sats_per_batch: u64,
+    count: u32,
+) -> Result<(CoordinatorTestHarness, Vec<Transaction>), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+
+    let harness = CoordinatorTestHarness::new(
+        store.store.clone(),
+        key_manager,
+        Some(CoordinatorSettingsConfig {
+            max_cpfp_fee_sats_per_batch: Some(max_cpfp_fee_sats_per_batch),
+            ..Default::default()
+        }),
+    )?;
+    harness.set_fee_rate(FEE_RATE);
+
+    let funding = harness.fund(&funding_key, FUNDING_AMOUNT)?;
+    harness.coordinator().add_funding(funding)?;
+
+    let mut txs = Vec::new();
+
+    for seed in 1..=count {
+        let (tx, speedup_data) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, seed);
+        harness.dispatch(tx.clone(), Some(speedup_data), "My tx")?;
+        txs.push(tx);
+    }
+
+    Ok((harness, txs))
+}
+
+fn fee_cap_news(harness: &CoordinatorTestHarness) -> Vec<CoordinatorNews> {
+    harness
+        .coordinator()
+        .get_news()
+        .unwrap()
+        .coordinator_news
+        .into_iter()
+        .filter(|news| matches!(news, CoordinatorNews::SpeedupFeeCapExceeded(..)))
+        .collect()
+}
+
+// Two transactions fit below the cap, the batch is closed before the third one, which is paid by a second CPFP.
+#[test]
+fn test_fee_cap_closes_batch_early() -> Result<(), anyhow::Error> {
+    let (harness, txs) = setup(7_500, 3)?;
+
+    harness.tick()?;
+
+    let txids: Vec<_> = txs.iter().map(|tx| tx.compute_txid()).collect();
+
+    let first_speedups = harness.coordinator().get_speedups_for_tx(txids[0])?;
+    assert_eq!(first_speedups.len(), 1);
+    assert_eq!(first_speedups[0].paid_txids, vec![txids[0], txids[1]]);
+
+    let last_speedups = harness.coordinator().get_speedups_for_tx(txids[2])?;
+    assert_eq!(last_speedups.len(), 1);
+    assert_eq!(last_speedups[0].paid_txids, vec![txids[2]]);
+
+    // Three transactions and two CPFPs
+    assert_eq!(harness.chain().mempool().len(), 5);
+    assert!(fee_cap_news(&harness).is_empty());
+
+    clear_output();
+    Ok(())
+}
+
+// A transaction whose own CPFP exceeds the cap is deferred and reported, until the fee rate goes down.
+#[test]
+fn test_fee_cap_defers_tx() -> Result<(), anyhow::Error> {
+    let (harness, txs) = setup(4_000, 1)?;
+    let tx_id = txs[0].compute_txid();
+
+    harness.tick()?;
+
+    assert!(harness.chain().mempool().is_empty());
+    assert_eq!(
+        harness.coordinator().get_transaction_history(tx_id)?.state,
+        TransactionState::ToDispatch
+    );
+
+    let news = fee_cap_news(&harness);
+    assert_eq!(news.len(), 1);
+    match news[0] {
+        CoordinatorNews::SpeedupFeeCapExceeded(id, estimated_fee, cap) => {
+            assert_eq!(id, tx_id);
+            assert!(estimated_fee > cap);
+            assert_eq!(cap, 4_000);
+        }
+        _ => unreachable!(),
+    }
+
+    harness.coordinator().ack_news(AckNews::Coordinator(
+        AckCoordinatorNews::SpeedupFeeCapExceeded(tx_id),
+    ))?;
+    assert!(fee_cap_news(&harness).is_empty());
+
+    harness.set_fee_rate(CoordinatorTestHarness::INITIAL_FEE_RATE);
+    harness.mine_blocks(1);
+    harness.tick()?;
+
+    assert_eq!(
+        harness.coordinator().get_transaction_history(tx_id)?.state,
+        TransactionState::Dispatched
+    );
+    assert_eq!(harness.coordinator().get_speedups_for_tx(tx_id)?.len(), 1);
+
+    clear_output();
+    Ok(())
+}
@@ -0,0 +1,183 @@
+use bitcoin::{consensus::serialize, PublicKey, Transaction, Txid, Witness};
+use bitcoin_coordinator::{
+    config::CoordinatorSettingsConfig,
+    coordinator::BitcoinCoordinatorApi,
+    record::STORE_RECORD_VERSION,
+    speedup::SpeedupStore,
+    storage::{BitcoinCoordinatorStore, BitcoinCoordinatorStoreApi},
+    testing::CoordinatorTestHarness,
+    types::CoordinatedSpeedUpTransaction,
+};
+use key_manager::{key_manager::KeyManager, key_type::BitcoinKeyType};
+use protocol_builder::types::output::SpeedupData;
+use serde_json::{json, Value};
+use std::rc::Rc;
+use utils::{clear_output, get_mocks, tx_with_anchor};
+mod utils;
+
+const ANCHOR_AMOUNT: u64 = 540;
+const FUNDING_AMOUNT: u64 = 10_000_000;
+const WITNESS_BYTES: usize = 20_000;
+
+// A pre-signed transaction with a large witness, paid by a CPFP from its anchor.
+fn large_tx(anchor_key: &PublicKey, seed: u32) -> (Transaction, SpeedupData) {
+    let (mut tx, speedup_data) = tx_with_anchor(anchor_key, ANCHOR_AMOUNT, seed);
+    // The witness is not part of the txid, the speedup data still points to the anchor
+    tx.input[0].witness = Witness::from_slice(&[vec![seed as u8; WITNESS_BYTES]]);
+
+    (tx, speedup_data)
+}
+
+// Dispatches a batch of large transactions paid by one CPFP, always with the same keys and funding.
+// With a single unconfirmed speedup allowed, a stuck CPFP is replaced (RBF).
+fn dispatch_batch(
+    key_manager: Rc<KeyManager>,
+    txs: u32,
+) -> Result<
+    (
+        CoordinatorTestHarness,
+        BitcoinCoordinatorStore,
+        CoordinatedSpeedUpTransaction,
+    ),
+    anyhow::Error,
+> {
+    let (_, store, _, _) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+
+    let settings = CoordinatorSettingsConfig {
+        max_unconfirmed_speedups: Some(1),
+        ..Default::default()
+    };
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, Some(settings))?;
+    let funding = harness.fund(&funding_key, FUNDING_AMOUNT)?;
+    harness.coordinator().add_funding(funding)?;
+
+    let batch = (1..=txs)
+        .map(|seed| {
+            let (tx, speedup_data) = large_tx(&anchor_key, seed);
+            (tx, Some(speedup_data), "My tx".to_string())
+        })
+        .collect();
+    harness.coordinator().dispatch_batch(batch, None)?;
+    harness.tick()?;
+
+    let (cpfp, _) = store.get_last_speedup()?.expect("the CPFP was sent");
+    assert_eq!(cpfp.speedup_tx_data.len(), txs as usize);
+
+    Ok((harness, store, cpfp))
+}
+
+fn speedup_key(tx_id: &Txid) -> String {
+    format!("bitcoin_coordinator/speedup/{tx_id}")
+}
+
+fn stored_bytes(store: &BitcoinCoordinatorStore, key: &str) -> Result<usize, anyhow::Error> {
+    let record: Value = store.store.get(key)?.unwrap();
+    Ok(serde_json::to_vec(&record)?.len())
+}
+
+// Writes the speedup as version 1 wrote it, with a copy of every transaction it pays for.
+fn write_legacy_speedup(
+    store: &BitcoinCoordinatorStore,
+    speedup: &CoordinatedSpeedUpTransaction,
+) -> Result<(), anyhow::Error> {
+    let key = speedup_key(&speedup.tx_id);
+    let mut record: Value = store.store.get(&key)?.unwrap();
+
+    let mut parents = Vec::new();
+    for parent in speedup.speedup_tx_data.iter() {
+        let tx = store.get_tx(&parent.tx_id)?.tx;
+        parents.push(json!([parent.speedup_data, tx, parent.context]));
+    }
+
+    record["version"] = json!(1);
+    record["payload"]["speedup_tx_data"] = Value::Array(parents);
+    store.store.set(&key, record, None)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_speedup_record_keeps_parents_by_txid() -> Result<(), anyhow::Error> {
+    let (_, _, _, key_manager) = get_mocks();
+    let (_, store, cpfp) = dispatch_batch(key_manager, 4)?;
+    let key = speedup_key(&cpfp.tx_id);
+
+    let slim_bytes = stored_bytes(&store, &key)?;
+    let parent_bytes: usize = cpfp
+        .speedup_tx_data
+        .iter()
+        .map(|parent| Ok(serialize(&store.get_tx(&parent.tx_id)?.tx).len()))
+        .sum::<Result<usize, anyhow::Error>>()?;
+
+    // The record is smaller than a single parent, and much smaller than a record with a copy of each parent
+    assert!(slim_bytes < parent_bytes / 4);
+
+    write_legacy_speedup(&store, &cpfp)?;
+    let fat_bytes = stored_bytes(&store, &key)?;
+    assert!(slim_bytes * 10 < fat_bytes);
+
+    // The legacy record is upgraded when it is read, and written again without the copies
+    let upgraded = store.get_speedup(&cpfp.tx_id)?;
+    assert_eq!(upgraded.speedup_tx_data, cpfp.speedup_tx_data);
+
+    let record: Value = store.store.get(&key)?.unwrap();
+    assert_eq!(record["version"], json!(STORE_RECORD_VERSION));
+    assert_eq!(stored_bytes(&store, &key)?, slim_bytes);
+
+    // The parents are read from their transaction records only when they are needed
+    let parents = upgraded.load_parents(&store)?;
+    for ((speedup_data, tx, context), parent) in parents.iter().zip(cpfp.speedup_tx_data.iter()) {
+        assert_eq!(tx.compute_txid(), parent.tx_id);
+        assert_eq!(tx.vsize(), parent.vsize);
+        assert_eq!(*speedup_data, parent.speedup_data);
+        assert_eq!(*context, parent.context);
+        assert_eq!(parent.anchor_amount, ANCHOR_AMOUNT);
+    }
+
+    clear_output();
+    Ok(())
+}
+
+// A replacement built from a speedup upgraded from a legacy record is the same as the one built from a
+// speedup written by this version.
+#[test]
+fn test_rbf_rebuilt_from_legacy_record_is_identical() -> Result<(), anyhow::Error> {
+    let (_, _, _, key_manager) = get_mocks();
+    let mut replacements = Vec::new();
+
+    for legacy in [false, true] {
+        let (harness, store, cpfp) = dispatch_batch(key_manager.clone(), 3)?;
+
+        if legacy {
+            write_legacy_speedup(&store, &cpfp)?;
+        }
+
+        // Not confirmed after min_blocks_before_resend_speedup blocks, it is replaced
+        harness.mine_empty_blocks(1);
+        harness.tick()?;
+
+        let (_, rbf) = store.get_last_speedup()?.unwrap();
+        let rbf = rbf.expect("the RBF was sent");
+        assert!(rbf.is_rbf);
+        assert_eq!(rbf.prev_funding, cpfp.prev_funding);
+
+        let mut rbf_tx = harness
+            .chain()
+            .get_transaction(&rbf.tx_id)
+            .expect("the RBF is in the mempool");
+
+        // The anchors are signed with Schnorr signatures, which use fresh randomness on every signature
+        for input in rbf_tx.input.iter_mut() {
+            input.witness = Witness::new();
+        }
+
+        replacements.push((serialize(&rbf_tx), serde_json::to_value(rbf)?));
+    }
+
+    assert_eq!(replacements[0], replacements[1]);
+
+    clear_output();
+    Ok(())
+}
@@ -91,11 +91,8 @@ fn speedup_tx() -> Result<(), anyhow::Error> {
         None,
     )?;
 
-    // Since we've already mined 102 blocks, we need to advance the coordinator by 102 ticks
-    // so the indexer can catch up with the current blockchain height.
-    for _ in 0..105 {
-        coordinator.tick()?;
-    }
+    // Since we've already mined 102 blocks, the indexer needs to catch up with the current blockchain height.
+    coordinator.sync_to_tip(None)?;
 
     let (tx1, tx1_speedup_utxo) = generate_tx(
         OutPoint::new(funding_tx.compute_txid(), funding_vout),
@@ -0,0 +1,199 @@
+use bitcoin::{
+    absolute::LockTime, transaction::Version, Amount, OutPoint, PublicKey, ScriptBuf, Sequence,
+    Transaction, TxIn, TxOut, Txid, Witness,
+};
+use bitcoin_coordinator::{
+    coordinator::BitcoinCoordinatorApi,
+    cpfp::SpeedupOutputKind,
+    review::{ReviewDecision, SpeedupReviewHook},
+    speedup::SpeedupStore,
+    storage::{BitcoinCoordinatorStore, BitcoinCoordinatorStoreApi},
+    testing::CoordinatorTestHarness,
+    types::{AckCoordinatorNews, AckNews, CoordinatorNews, TransactionState},
+};
+use key_manager::key_type::BitcoinKeyType;
+use protocol_builder::types::{output::SpeedupData, Utxo};
+use std::{cell::RefCell, collections::VecDeque, rc::Rc};
+use utils::{clear_output, get_mocks};
+mod utils;
+
+// Answers with the scripted decisions in order, then approves. Records every speedup reviewed.
+#[derive(Default)]
+struct ScriptedHook {
+    decisions: RefCell<VecDeque<ReviewDecision>>,
+    reviewed: RefCell<Vec<(Txid, Vec<Txid>, u64, bool)>>,
+}
+
+impl ScriptedHook {
+    fn new(decisions: Vec<ReviewDecision>) -> Rc<Self> {
+        Rc::new(ScriptedHook {
+            decisions: RefCell::new(decisions.into()),
+            ..Default::default()
+        })
+    }
+}
+
+impl SpeedupReviewHook for ScriptedHook {
+    fn review(&self, tx: &Transaction, parents: &[Txid], fee: u64, is_rbf: bool) -> ReviewDecision {
+        self.reviewed
+            .borrow_mut()
+            .push((tx.compute_txid(), parents.to_vec(), fee, is_rbf));
+
+        self.decisions
+            .borrow_mut()
+            .pop_front()
+            .unwrap_or(ReviewDecision::Approve)
+    }
+}
+
+fn tx_with_anchor(anchor_key: &PublicKey) -> Transaction {
+    Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint::null(),
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+            witness: Witness::new(),
+        }],
+        output: vec![
+            TxOut {
+                value: Amount::from_sat(1_000),
+                script_pubkey: ScriptBuf::new(),
+            },
+            TxOut {
+                value: Amount::from_sat(0),
+                script_pubkey: SpeedupOutputKind::P2trKeyPath
+                    .script_pubkey(anchor_key)
+                    .unwrap(),
+            },
+        ],
+    }
+}
+
+// Dispatches a transaction paid by a CPFP with the hook set, and ticks once.
+fn dispatch_reviewed(
+    hook: Rc<ScriptedHook>,
+) -> Result<(CoordinatorTestHarness, BitcoinCoordinatorStore, Txid), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 1)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 2)?;
+
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?
+        .with_speedup_review_hook(hook);
+
+    let funding = harness.fund(&funding_key, 1_000_000)?;
+    harness.coordinator().add_funding(funding)?;
+
+    let tx = tx_with_anchor(&anchor_key);
+    let tx_id = tx.compute_txid();
+    let speedup_data = SpeedupData::new(Utxo::new(tx_id, 1, 0, &anchor_key));
+    harness
+        .coordinator()
+        .dispatch(tx, Some(speedup_data), "My tx".to_string(), None, None)?;
+    harness.tick()?;
+
+    Ok((harness, store, tx_id))
+}
+
+fn rejected_news(harness: &CoordinatorTestHarness) -> Result<Vec<(Txid, String)>, anyhow::Error> {
+    Ok(harness
+        .coordinator()
+        .get_news()?
+        .coordinator_news
+        .into_iter()
+        .filter_map(|news| match news {
+            CoordinatorNews::SpeedupRejectedByPolicy(tx_id, reason) => Some((tx_id, reason)),
+            _ => None,
+        })
+        .collect())
+}
+
+#[test]
+fn test_approved_speedup_is_broadcast() -> Result<(), anyhow::Error> {
+    let hook = ScriptedHook::new(vec![ReviewDecision::Approve]);
+    let (harness, store, tx_id) = dispatch_reviewed(hook.clone())?;
+
+    // The signed CPFP is reviewed once, with the transaction it pays for and its fee
+    let (speedup, _) = store.get_last_speedup()?.expect("the CPFP was sent");
+    let summary = &store.get_speedups_for_tx(&tx_id)?[0];
+    assert_eq!(
+        *hook.reviewed.borrow(),
+        vec![(speedup.tx_id, vec![tx_id], summary.fee, false)]
+    );
+    assert!(harness.chain().in_mempool(&speedup.tx_id));
+    assert!(rejected_news(&harness)?.is_empty());
+
+    clear_output();
+    Ok(())
+}
+
+#[test]
+fn test_rejected_speedup_is_not_broadcast() -> Result<(), anyhow::Error> {
+    let reason = "Above the approved fee".to_string();
+    let hook = ScriptedHook::new(vec![ReviewDecision::Reject(reason.clone())]);
+    let (harness, store, tx_id) = dispatch_reviewed(hook.clone())?;
+
+    let (speedup_tx_id, _, fee, _) = hook.reviewed.borrow()[0].clone();
+    assert!(!harness.chain().in_mempool(&speedup_tx_id));
+    assert!(store.get_last_speedup()?.is_none());
+
+    // The rejection is saved and reported, the transaction stays dispatched without a CPFP
+    let rejections = store.get_speedup_rejections()?;
+    assert_eq!(rejections.len(), 1);
+    assert_eq!(rejections[0].tx_id, speedup_tx_id);
+    assert_eq!(rejections[0].paid_txids, vec![tx_id]);
+    assert_eq!(rejections[0].fee, fee);
+    assert_eq!(rejections[0].reason, reason);
+    assert_eq!(rejected_news(&harness)?, vec![(speedup_tx_id, reason)]);
+    assert_eq!(store.get_tx(&tx_id)?.state, TransactionState::Dispatched);
+
+    // It is not built again
+    harness.tick()?;
+    assert_eq!(hook.reviewed.borrow().len(), 1);
+
+    harness.coordinator().ack_news(AckNews::Coordinator(
+        AckCoordinatorNews::SpeedupRejectedByPolicy(speedup_tx_id),
+    ))?;
+    assert!(rejected_news(&harness)?.is_empty());
+
+    clear_output();
+    Ok(())
+}
+
+#[test]
+fn test_deferred_speedup_is_broadcast_once_approved() -> Result<(), anyhow::Error> {
+    let hook = ScriptedHook::new(vec![ReviewDecision::Defer, ReviewDecision::Defer]);
+    let (harness, store, tx_id) = dispatch_reviewed(hook.clone())?;
+
+    assert!(store.get_last_speedup()?.is_none());
+    assert!(harness.chain().in_mempool(&tx_id));
+
+    // Deferred again on the next tick, then approved
+    harness.tick()?;
+    assert!(store.get_last_speedup()?.is_none());
+
+    harness.tick()?;
+    let (speedup, _) = store.get_last_speedup()?.expect("the CPFP was sent");
+    assert!(harness.chain().in_mempool(&speedup.tx_id));
+    assert_eq!(store.get_speedups_for_tx(&tx_id)?.len(), 1);
+
+    let reviewed = hook.reviewed.borrow();
+    assert_eq!(reviewed.len(), 3);
+    assert!(reviewed
+        .iter()
+        .all(|(_, parents, _, is_rbf)| *parents == vec![tx_id] && !is_rbf));
+
+    // A deferral is neither a rejection nor a failure
+    assert!(store.get_speedup_rejections()?.is_empty());
+    assert!(rejected_news(&harness)?.is_empty());
+    assert!(!harness
+        .coordinator()
+        .get_news()?
+        .coordinator_news
+        .iter()
+        .any(|news| matches!(news, CoordinatorNews::DispatchSpeedUpError(..))));
+
+    clear_output();
+    Ok(())
+}
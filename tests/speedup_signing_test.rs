@@ -0,0 +1,119 @@
+use bitcoin::{Amount, PublicKey, Transaction, TxOut, Txid};
+use bitcoin_coordinator::{
+    coordinator::BitcoinCoordinatorApi, cpfp::SpeedupOutputKind, observer::CoordinatorObserver,
+    speedup::SpeedupStore, testing::CoordinatorTestHarness,
+};
+use key_manager::key_type::BitcoinKeyType;
+use protocol_builder::types::{output::SpeedupData, Utxo};
+use std::{cell::RefCell, rc::Rc};
+use utils::{clear_output, get_mocks, simple_tx};
+mod utils;
+
+// Counts the speedups signed and the speedups sent.
+#[derive(Default)]
+struct SigningObserver {
+    signed_inputs: RefCell<Vec<usize>>,
+    created: RefCell<Vec<Txid>>,
+}
+
+impl CoordinatorObserver for SigningObserver {
+    fn on_speedup_signed(&self, inputs: usize) {
+        self.signed_inputs.borrow_mut().push(inputs);
+    }
+
+    fn on_speedup_created(
+        &self,
+        txid: Txid,
+        _fee: u64,
+        _fee_rate: u64,
+        _num_parents: usize,
+        _is_rbf: bool,
+    ) {
+        self.created.borrow_mut().push(txid);
+    }
+}
+
+// A transaction with a zero value taproot anchor, its own fee does not change the speedup.
+fn tx_with_anchor(seed: u32, anchor_key: &PublicKey) -> Transaction {
+    let mut tx = simple_tx(seed);
+    tx.output.push(TxOut {
+        value: Amount::from_sat(0),
+        script_pubkey: SpeedupOutputKind::P2trKeyPath
+            .script_pubkey(anchor_key)
+            .unwrap(),
+    });
+
+    tx
+}
+
+// Fee of a first CPFP paying for parents of `parents_vsize` at `fee_rate`, computed from the vsize of the signed
+// CPFP as the coordinator did when it signed a CPFP with a dummy fee to learn its vsize.
+fn fee_from_signed_vsize(
+    parents_vsize: u64,
+    speedup_vsize: u64,
+    fee_rate: u64,
+    bump_fee_percentage: f64,
+) -> u64 {
+    let total_fee = ((parents_vsize + speedup_vsize) * fee_rate).saturating_sub(parents_vsize);
+
+    (total_fee as f64 * bump_fee_percentage).ceil() as u64
+}
+
+#[test]
+fn test_batched_cpfp_is_signed_once() -> Result<(), anyhow::Error> {
+    for batch_size in [1, 3, 6] {
+        let (_, store, _, key_manager) = get_mocks();
+        let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 1)?;
+        let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 2)?;
+
+        let observer = Rc::new(SigningObserver::default());
+        let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?
+            .with_observer(observer.clone());
+        harness.set_fee_rate(7);
+
+        let funding = harness.fund(&funding_key, 1_000_000)?;
+        harness.coordinator().add_funding(funding)?;
+
+        let mut parents_vsize = 0;
+        for seed in 0..batch_size {
+            let tx = tx_with_anchor(seed, &anchor_key);
+            let speedup_data = SpeedupData::new(Utxo::new(tx.compute_txid(), 1, 0, &anchor_key));
+            parents_vsize += tx.vsize() as u64;
+
+            harness.coordinator().dispatch(
+                tx,
+                Some(speedup_data),
+                format!("tx {seed}"),
+                None,
+                None,
+            )?;
+        }
+        harness.tick()?;
+
+        // A single CPFP pays for the batch, it is signed once with the anchors and the funding
+        assert_eq!(observer.created.borrow().len(), 1);
+        assert_eq!(
+            *observer.signed_inputs.borrow(),
+            vec![batch_size as usize + 1]
+        );
+
+        // The fee matches the one computed from the signed vsize within 1 sat/vB
+        let (speedup, _) = store.get_last_speedup()?.expect("a CPFP was sent");
+        assert_eq!(speedup.tx_id, observer.created.borrow()[0]);
+
+        let speedup_summary = &store.get_speedups_for_tx(&speedup.speedup_tx_data[0].tx_id)?[0];
+        let speedup_vsize = speedup.vsize as u64;
+        let expected_fee = fee_from_signed_vsize(
+            parents_vsize,
+            speedup_vsize,
+            speedup.network_fee_rate_used,
+            speedup.bump_fee_percentage_used,
+        );
+
+        assert!(speedup_summary.fee >= expected_fee);
+        assert!((speedup_summary.fee - expected_fee) as f64 / speedup_vsize as f64 <= 1.0);
+    }
+
+    clear_output();
+    Ok(())
+}
@@ -0,0 +1,176 @@
+use bitcoin_coordinator::{
+    config::{CoordinatorMode, CoordinatorSettingsConfig},
+    coordinator::BitcoinCoordinatorApi,
+    errors::BitcoinCoordinatorError,
+    speedup::SpeedupStore,
+    storage::BitcoinCoordinatorStoreApi,
+    testing::CoordinatorTestHarness,
+    types::{DispatchOptions, SpeedupState, TransactionState},
+};
+use bitvmx_transaction_monitor::config::MonitorSettingsConfig;
+use key_manager::key_type::BitcoinKeyType;
+use utils::{clear_output, get_mocks, tx_with_anchor};
+mod utils;
+
+const ANCHOR_AMOUNT: u64 = 540;
+const MAX_MONITORING_CONFIRMATIONS: u32 = 3;
+
+fn standby_settings() -> Option<CoordinatorSettingsConfig> {
+    Some(CoordinatorSettingsConfig {
+        mode: Some(CoordinatorMode::Standby),
+        ..Default::default()
+    })
+}
+
+fn queue_only() -> DispatchOptions {
+    DispatchOptions {
+        queue_only: true,
+        ..Default::default()
+    }
+}
+
+// A standby coordinator refuses dispatches, keeps the queued transactions without broadcasting anything,
+// and sends them with their CPFP once it is promoted.
+#[test]
+fn test_standby_queues_until_promoted() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+
+    let harness =
+        CoordinatorTestHarness::new(store.store.clone(), key_manager, standby_settings())?;
+    let funding = harness.fund(&funding_key, 1_000_000)?;
+    harness.coordinator().add_funding(funding)?;
+
+    let (tx, speedup_data) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 1);
+    let tx_id = tx.compute_txid();
+
+    let result = harness.dispatch(tx.clone(), Some(speedup_data.clone()), "My tx");
+    assert!(matches!(
+        result,
+        Err(BitcoinCoordinatorError::CoordinatorInStandby)
+    ));
+
+    let result = harness.coordinator().dispatch_batch(
+        vec![(tx.clone(), Some(speedup_data.clone()), "My tx".to_string())],
+        None,
+    );
+    assert!(matches!(
+        result,
+        Err(BitcoinCoordinatorError::CoordinatorInStandby)
+    ));
+
+    harness.coordinator().dispatch_with_options(
+        tx,
+        Some(speedup_data),
+        "My tx".to_string(),
+        None,
+        None,
+        queue_only(),
+    )?;
+
+    let broadcasts = harness.chain().broadcasts();
+
+    for _ in 0..3 {
+        harness.tick()?;
+        harness.mine_empty_blocks(1);
+    }
+
+    assert_eq!(harness.chain().broadcasts(), broadcasts);
+    assert!(harness.chain().mempool().is_empty());
+    assert_eq!(store.get_tx(&tx_id)?.state, TransactionState::ToDispatch);
+    assert!(store.get_tx(&tx_id)?.retry_info.is_none());
+    assert!(harness.coordinator().get_speedups_for_tx(tx_id)?.is_empty());
+
+    // The mode is only changed with promote
+    let result = harness
+        .coordinator()
+        .update_settings(CoordinatorSettingsConfig::default());
+    assert!(matches!(
+        result,
+        Err(BitcoinCoordinatorError::InvalidConfiguration(_))
+    ));
+
+    harness.coordinator().promote()?;
+    harness.tick()?;
+
+    assert_eq!(store.get_tx(&tx_id)?.state, TransactionState::Dispatched);
+    assert!(harness.chain().in_mempool(&tx_id));
+
+    let speedups = harness.coordinator().get_speedups_for_tx(tx_id)?;
+    assert_eq!(speedups.len(), 1);
+    assert!(harness.chain().in_mempool(&speedups[0].tx_id));
+
+    // Promoting an active coordinator does nothing
+    harness.coordinator().promote()?;
+    assert_eq!(
+        harness.coordinator().get_effective_settings().mode,
+        CoordinatorMode::Active
+    );
+
+    clear_output();
+    Ok(())
+}
+
+// A standby coordinator on the store of an active one follows its transactions and speedups
+// until they are finalized, without broadcasting, even when they are not confirmed in time.
+#[test]
+fn test_standby_follows_confirmations() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+
+    let active = CoordinatorTestHarness::new(store.store.clone(), key_manager.clone(), None)?;
+    let funding = active.fund(&funding_key, 1_000_000)?;
+    active.coordinator().add_funding(funding)?;
+
+    let (tx, speedup_data) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 1);
+    let tx_id = tx.compute_txid();
+
+    active.dispatch(tx, Some(speedup_data), "My tx")?;
+    active.tick()?;
+
+    let speedup_id = active.coordinator().get_speedups_for_tx(tx_id)?[0].tx_id;
+    let chain = active.chain().clone();
+    drop(active);
+
+    let standby = CoordinatorTestHarness::with_chain(
+        chain,
+        store.store.clone(),
+        key_manager,
+        Some(CoordinatorSettingsConfig {
+            mode: Some(CoordinatorMode::Standby),
+            monitor_settings: Some(MonitorSettingsConfig {
+                max_monitoring_confirmations: Some(MAX_MONITORING_CONFIRMATIONS),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }),
+    )?;
+    let broadcasts = standby.chain().broadcasts();
+
+    // The speedup is not bumped while it waits in the mempool
+    for _ in 0..3 {
+        standby.mine_empty_blocks(1);
+        standby.tick()?;
+    }
+    assert_eq!(standby.chain().broadcasts(), broadcasts);
+
+    standby.mine_blocks(1);
+    standby.tick()?;
+
+    assert_eq!(store.get_tx(&tx_id)?.state, TransactionState::Confirmed);
+    assert_eq!(
+        store.get_speedup(&speedup_id)?.state,
+        SpeedupState::Confirmed
+    );
+
+    standby.mine_empty_blocks(MAX_MONITORING_CONFIRMATIONS as u64);
+    standby.tick()?;
+
+    assert_eq!(store.get_tx(&tx_id)?.state, TransactionState::Finalized);
+    assert_eq!(standby.chain().broadcasts(), broadcasts);
+
+    clear_output();
+    Ok(())
+}
@@ -0,0 +1,181 @@
+use bitcoin::Txid;
+use bitcoin_coordinator::{
+    storage::{BitcoinCoordinatorStore, BitcoinCoordinatorStoreApi},
+    types::TransactionState,
+};
+use std::collections::HashSet;
+use utils::{clear_output, get_mocks, simple_tx};
+mod utils;
+
+const HEIGHT: u32 = 100;
+
+// Saves the transactions with seeds in the range, in a single batch, and returns their ids.
+fn save_batch(
+    store: &BitcoinCoordinatorStore,
+    seeds: std::ops::Range<u32>,
+) -> Result<Vec<Txid>, anyhow::Error> {
+    let txs: Vec<_> = seeds
+        .map(|seed| (simple_tx(seed), None, "My tx".to_string()))
+        .collect();
+    let tx_ids = txs.iter().map(|(tx, _, _)| tx.compute_txid()).collect();
+    store.save_txs(txs, None)?;
+    Ok(tx_ids)
+}
+
+fn as_set(tx_ids: &[Txid]) -> HashSet<Txid> {
+    tx_ids.iter().cloned().collect()
+}
+
+// Expected txids in each indexed state.
+struct Expected {
+    to_dispatch: Vec<Txid>,
+    dispatched: Vec<Txid>,
+    confirmed: Vec<Txid>,
+    cancelled: Vec<Txid>,
+    failed: Vec<Txid>,
+}
+
+impl Expected {
+    fn assert_indexes(&self, store: &BitcoinCoordinatorStore) -> Result<(), anyhow::Error> {
+        let expected = [
+            (TransactionState::ToDispatch, &self.to_dispatch),
+            (TransactionState::Dispatched, &self.dispatched),
+            (TransactionState::Confirmed, &self.confirmed),
+            (TransactionState::Cancelled, &self.cancelled),
+            (TransactionState::Failed, &self.failed),
+        ];
+
+        for (state, tx_ids) in expected {
+            let index = store.get_tx_ids_by_state(&state)?;
+            assert_eq!(index.len(), tx_ids.len(), "{state:?}");
+            assert_eq!(as_set(&index), as_set(tx_ids), "{state:?}");
+        }
+
+        assert!(store
+            .get_tx_ids_by_state(&TransactionState::Finalized)?
+            .is_empty());
+
+        Ok(())
+    }
+}
+
+// 5,000 transactions in every state. Each index holds exactly the transactions in its state,
+// and the per-tick reads only load the transactions they return.
+#[test]
+fn test_state_indexes_with_5000_txs() -> Result<(), anyhow::Error> {
+    let (_, store, _, _) = get_mocks();
+
+    // 400 dispatched: 100 finalized, 150 confirmed, 30 cancelled after the broadcast and 120 dispatched
+    let broadcast = save_batch(&store, 0..400)?;
+    for tx_id in broadcast.iter() {
+        store.update_tx_to_dispatched(*tx_id, HEIGHT, 1)?;
+    }
+    for tx_id in broadcast[..250].iter() {
+        store.update_tx_state(*tx_id, TransactionState::Confirmed)?;
+    }
+    for tx_id in broadcast[..100].iter() {
+        store.update_tx_state(*tx_id, TransactionState::Finalized)?;
+    }
+    for tx_id in broadcast[250..280].iter() {
+        store.cancel_tx(*tx_id)?;
+    }
+
+    // 200 never broadcast: 50 cancelled, 50 failed and 100 to dispatch
+    let not_broadcast = save_batch(&store, 400..600)?;
+    for tx_id in not_broadcast[..50].iter() {
+        store.cancel_tx(*tx_id)?;
+    }
+    for tx_id in not_broadcast[50..100].iter() {
+        store.update_tx_state(*tx_id, TransactionState::Failed)?;
+    }
+
+    // 4,400 to dispatch
+    let pending = save_batch(&store, 600..5_000)?;
+
+    let expected = Expected {
+        to_dispatch: [&not_broadcast[100..], &pending[..]].concat(),
+        dispatched: broadcast[280..].to_vec(),
+        confirmed: broadcast[100..250].to_vec(),
+        cancelled: broadcast[250..280].to_vec(),
+        failed: not_broadcast[50..100].to_vec(),
+    };
+    expected.assert_indexes(&store)?;
+
+    // The index is read once, then each transaction to dispatch
+    let reads = store.reads();
+    let to_dispatch = store.get_txs_to_dispatch()?;
+    assert_eq!(to_dispatch.len(), 4_500);
+    assert_eq!(store.reads() - reads, 1 + to_dispatch.len() as u64);
+    assert_eq!(
+        to_dispatch.iter().map(|tx| tx.tx_id).collect::<Vec<_>>(),
+        expected.to_dispatch
+    );
+
+    // Four indexes and the pending list are read, then each transaction in progress
+    let reads = store.reads();
+    let in_progress = store.get_txs_in_progress()?;
+    assert_eq!(in_progress.len(), 4_500 + 120 + 150 + 30);
+    assert_eq!(store.reads() - reads, 5 + in_progress.len() as u64);
+
+    // Removed transactions leave their index, and saving a cancelled one indexes it again
+    store.remove_tx(pending[0])?;
+    store.save_tx(simple_tx(400), None, None, "My tx".to_string())?;
+    let to_dispatch = store.get_tx_ids_by_state(&TransactionState::ToDispatch)?;
+    assert!(!to_dispatch.contains(&pending[0]));
+    assert!(to_dispatch.contains(&not_broadcast[0]));
+
+    clear_output();
+    Ok(())
+}
+
+// A store written before the per-state indexes only has the pending list. The indexes are rebuilt
+// from it the first time the store is used.
+#[test]
+fn test_state_indexes_rebuilt_for_legacy_store() -> Result<(), anyhow::Error> {
+    let (_, store, _, _) = get_mocks();
+
+    let tx_ids = save_batch(&store, 0..6)?;
+    store.update_tx_to_dispatched(tx_ids[0], HEIGHT, 1)?;
+    store.update_tx_to_dispatched(tx_ids[1], HEIGHT, 1)?;
+    store.update_tx_state(tx_ids[1], TransactionState::Confirmed)?;
+    store.update_tx_to_dispatched(tx_ids[2], HEIGHT, 1)?;
+    store.update_tx_state(tx_ids[2], TransactionState::Confirmed)?;
+    store.update_tx_state(tx_ids[2], TransactionState::Finalized)?;
+    store.update_tx_state(tx_ids[3], TransactionState::Failed)?;
+
+    // Drop the indexes, as in a store written by a previous version
+    for state in [
+        "to_dispatch",
+        "dispatched",
+        "confirmed",
+        "finalized",
+        "failed",
+        "cancelled",
+        "version",
+    ] {
+        store
+            .store
+            .remove(format!("bitcoin_coordinator/tx/state/{state}"), None)?;
+    }
+
+    let store = BitcoinCoordinatorStore::new(store.store.clone(), 1, 3, 2)?;
+
+    Expected {
+        to_dispatch: tx_ids[4..].to_vec(),
+        dispatched: vec![tx_ids[0]],
+        confirmed: vec![tx_ids[1]],
+        cancelled: vec![],
+        failed: vec![tx_ids[3]],
+    }
+    .assert_indexes(&store)?;
+
+    let to_dispatch: Vec<Txid> = store
+        .get_txs_to_dispatch()?
+        .iter()
+        .map(|tx| tx.tx_id)
+        .collect();
+    assert_eq!(to_dispatch, tx_ids[4..].to_vec());
+
+    clear_output();
+    Ok(())
+}
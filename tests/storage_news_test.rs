@@ -1,24 +1,38 @@
-use bitcoin::{absolute::LockTime, transaction::Version, BlockHash, Transaction, Txid};
+use bitcoin::{
+    absolute::LockTime, hashes::Hash, transaction::Version, BlockHash, OutPoint, Transaction, Txid,
+};
 use bitcoin_coordinator::{
+    errors::BroadcastFailureKind,
     storage::{BitcoinCoordinatorStore, BitcoinCoordinatorStoreApi},
     types::{AckCoordinatorNews, CoordinatorNews, TransactionState},
+    BlockInfo,
 };
-use std::{rc::Rc, str::FromStr};
-use storage_backend::{storage::Storage, storage_config::StorageConfig};
-use utils::{clear_output, generate_random_string};
+use std::str::FromStr;
+use utils::{clear_output, create_storage, TestBackend};
 mod utils;
 
-#[test]
-fn coordinator_news_test() -> Result<(), anyhow::Error> {
+backend_tests!(
+    coordinator_news_test,
+    test_transaction_already_in_mempool_news,
+    test_mempool_rejection_news,
+    test_network_error_news,
+    test_speedup_orphaned_news,
+    test_max_rbf_attempts_reached_news,
+    test_transaction_conflicted_news,
+    test_dispatch_scheduled_news,
+    test_outpoint_spent_news,
+    test_dispatch_transaction_error_news,
+    test_all_error_types_together,
+    test_transaction_state_failed_on_fatal_error,
+    test_get_news_page,
+    test_news_page_in_insertion_order,
+    test_ack_news_batch,
+);
+
+fn coordinator_news_test(backend: TestBackend) -> Result<(), anyhow::Error> {
     const MAX_RETRIES: u32 = 3;
     const RETRY_INTERVAL: u64 = 2;
-    let path = format!(
-        "test_output/coordinator_news_test/{}",
-        generate_random_string()
-    );
-
-    let storage_config = StorageConfig::new(path, None);
-    let storage = Rc::new(Storage::new(&storage_config)?);
+    let storage = create_storage(backend);
 
     let current_block_hash =
         BlockHash::from_str("0000000000000000000000000000000000000000000000000000000000000000")
@@ -47,8 +61,12 @@ fn coordinator_news_test() -> Result<(), anyhow::Error> {
         "error".to_string(),
     );
 
-    let transaction_error_news =
-        CoordinatorNews::DispatchTransactionError(tx_id_3, "tx_3".to_string(), "error".to_string());
+    let transaction_error_news = CoordinatorNews::DispatchTransactionError(
+        tx_id_3,
+        "tx_3".to_string(),
+        "error".to_string(),
+        BroadcastFailureKind::Other,
+    );
 
     let estimate_feerate_news = CoordinatorNews::EstimateFeerateTooHigh(12345, 10000);
 
@@ -140,11 +158,13 @@ fn coordinator_news_test() -> Result<(), anyhow::Error> {
         tx_id_6,
         "Test context 6".to_string(),
         "Test error 6".to_string(),
+        BroadcastFailureKind::MempoolConflict,
     );
     let transaction_error_news_2 = CoordinatorNews::DispatchTransactionError(
         tx_id_7,
         "Test context 7".to_string(),
         "Test error 7".to_string(),
+        BroadcastFailureKind::TooLongMempoolChain,
     );
 
     let speed_up_error_news_1 = CoordinatorNews::DispatchSpeedUpError(
@@ -212,14 +232,10 @@ fn coordinator_news_test() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
-#[test]
-fn test_transaction_already_in_mempool_news() -> Result<(), anyhow::Error> {
+fn test_transaction_already_in_mempool_news(backend: TestBackend) -> Result<(), anyhow::Error> {
     const MAX_RETRIES: u32 = 3;
     const RETRY_INTERVAL: u64 = 2;
-    let path = format!("test_output/storage_news_test/{}", generate_random_string());
-
-    let storage_config = StorageConfig::new(path, None);
-    let storage = Rc::new(Storage::new(&storage_config)?);
+    let storage = create_storage(backend);
 
     let current_block_hash =
         BlockHash::from_str("0000000000000000000000000000000000000000000000000000000000000000")
@@ -261,14 +277,10 @@ fn test_transaction_already_in_mempool_news() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
-#[test]
-fn test_mempool_rejection_news() -> Result<(), anyhow::Error> {
+fn test_mempool_rejection_news(backend: TestBackend) -> Result<(), anyhow::Error> {
     const MAX_RETRIES: u32 = 3;
     const RETRY_INTERVAL: u64 = 2;
-    let path = format!("test_output/storage_news_test/{}", generate_random_string());
-
-    let storage_config = StorageConfig::new(path, None);
-    let storage = Rc::new(Storage::new(&storage_config)?);
+    let storage = create_storage(backend);
 
     let current_block_hash =
         BlockHash::from_str("0000000000000000000000000000000000000000000000000000000000000000")
@@ -308,14 +320,10 @@ fn test_mempool_rejection_news() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
-#[test]
-fn test_network_error_news() -> Result<(), anyhow::Error> {
+fn test_network_error_news(backend: TestBackend) -> Result<(), anyhow::Error> {
     const MAX_RETRIES: u32 = 3;
     const RETRY_INTERVAL: u64 = 2;
-    let path = format!("test_output/storage_news_test/{}", generate_random_string());
-
-    let storage_config = StorageConfig::new(path, None);
-    let storage = Rc::new(Storage::new(&storage_config)?);
+    let storage = create_storage(backend);
 
     let current_block_hash =
         BlockHash::from_str("0000000000000000000000000000000000000000000000000000000000000000")
@@ -355,14 +363,272 @@ fn test_network_error_news() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
-#[test]
-fn test_dispatch_transaction_error_news() -> Result<(), anyhow::Error> {
+fn test_speedup_orphaned_news(backend: TestBackend) -> Result<(), anyhow::Error> {
+    const MAX_RETRIES: u32 = 3;
+    const RETRY_INTERVAL: u64 = 2;
+    let storage = create_storage(backend);
+
+    let current_block_hash =
+        BlockHash::from_str("0000000000000000000000000000000000000000000000000000000000000000")
+            .unwrap();
+
+    let store = BitcoinCoordinatorStore::new(storage, 1, MAX_RETRIES, RETRY_INTERVAL)?;
+
+    let speedup_id =
+        Txid::from_str("e9b7ad71b2f0bbce7165b5ab4a3c1e17e9189f2891650e3b7d644bb7e88f200a").unwrap();
+    let parent_id =
+        Txid::from_str("f9b7ad71b2f0bbce7165b5ab4a3c1e17e9189f2891650e3b7d644bb7e88f200b").unwrap();
+
+    // Add SpeedupOrphaned news
+    let news = CoordinatorNews::SpeedupOrphaned(speedup_id, vec![parent_id]);
+    store.update_news(news, current_block_hash)?;
+
+    // Verify the news is stored
+    let news_list = store.get_news()?;
+    assert_eq!(news_list.len(), 1);
+    match &news_list[0] {
+        CoordinatorNews::SpeedupOrphaned(id, parents) => {
+            assert_eq!(*id, speedup_id);
+            assert_eq!(parents, &vec![parent_id]);
+        }
+        _ => panic!("Expected SpeedupOrphaned news"),
+    }
+
+    // Acknowledge the news
+    store.ack_news(AckCoordinatorNews::SpeedupOrphaned(speedup_id))?;
+
+    // Verify the news is acknowledged
+    let news_list = store.get_news()?;
+    assert_eq!(news_list.len(), 0);
+
+    clear_output();
+    Ok(())
+}
+
+fn test_max_rbf_attempts_reached_news(backend: TestBackend) -> Result<(), anyhow::Error> {
+    let store = BitcoinCoordinatorStore::new(create_storage(backend), 1, 3, 2)?;
+
+    let block_hash_1 =
+        BlockHash::from_str("0000000000000000000000000000000000000000000000000000000000000001")?;
+    let block_hash_2 =
+        BlockHash::from_str("0000000000000000000000000000000000000000000000000000000000000002")?;
+
+    let cpfp_id =
+        Txid::from_str("e9b7ad71b2f0bbce7165b5ab4a3c1e17e9189f2891650e3b7d644bb7e88f200a")?;
+
+    store.update_news(
+        CoordinatorNews::MaxRbfAttemptsReached(cpfp_id, 2, 1500),
+        block_hash_1,
+    )?;
+
+    assert_eq!(
+        store.get_news()?,
+        vec![CoordinatorNews::MaxRbfAttemptsReached(cpfp_id, 2, 1500)]
+    );
+
+    store.ack_news(AckCoordinatorNews::MaxRbfAttemptsReached(cpfp_id))?;
+    assert!(store.get_news()?.is_empty());
+
+    // Reported again in a new block with the same replacements, it stays acknowledged
+    store.update_news(
+        CoordinatorNews::MaxRbfAttemptsReached(cpfp_id, 2, 1500),
+        block_hash_2,
+    )?;
+    assert!(store.get_news()?.is_empty());
+
+    // A different number of replacements is reported again
+    store.update_news(
+        CoordinatorNews::MaxRbfAttemptsReached(cpfp_id, 3, 2000),
+        block_hash_2,
+    )?;
+    assert_eq!(
+        store.get_news()?,
+        vec![CoordinatorNews::MaxRbfAttemptsReached(cpfp_id, 3, 2000)]
+    );
+
+    clear_output();
+    Ok(())
+}
+
+fn test_transaction_conflicted_news(backend: TestBackend) -> Result<(), anyhow::Error> {
+    const MAX_RETRIES: u32 = 3;
+    const RETRY_INTERVAL: u64 = 2;
+    let storage = create_storage(backend);
+
+    let current_block_hash =
+        BlockHash::from_str("0000000000000000000000000000000000000000000000000000000000000000")
+            .unwrap();
+
+    let store = BitcoinCoordinatorStore::new(storage, 1, MAX_RETRIES, RETRY_INTERVAL)?;
+
+    let tx = Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::from_time(1653195600).unwrap(),
+        input: vec![],
+        output: vec![],
+    };
+    let tx_id = tx.compute_txid();
+    let conflicting_txid =
+        Txid::from_str("f9b7ad71b2f0bbce7165b5ab4a3c1e17e9189f2891650e3b7d644bb7e88f200b").unwrap();
+
+    // A dispatched transaction that was double spent is marked as failed
+    store.save_tx(tx, None, None, "context_tx".to_string())?;
+    store.update_tx_to_dispatched(tx_id, 100, 1)?;
+    store.update_tx_state(tx_id, TransactionState::Failed)?;
+    assert_eq!(store.get_tx(&tx_id)?.state, TransactionState::Failed);
+
+    // Add TransactionConflicted news
+    let news =
+        CoordinatorNews::TransactionConflicted(tx_id, conflicting_txid, "context_tx".to_string());
+    store.update_news(news, current_block_hash)?;
+
+    // Verify the news is stored
+    let news_list = store.get_news()?;
+    assert_eq!(news_list.len(), 1);
+    match &news_list[0] {
+        CoordinatorNews::TransactionConflicted(id, conflicting_id, context) => {
+            assert_eq!(*id, tx_id);
+            assert_eq!(*conflicting_id, conflicting_txid);
+            assert_eq!(context, "context_tx");
+        }
+        _ => panic!("Expected TransactionConflicted news"),
+    }
+
+    // Acknowledge the news
+    store.ack_news(AckCoordinatorNews::TransactionConflicted(tx_id))?;
+
+    // Verify the news is acknowledged
+    let news_list = store.get_news()?;
+    assert_eq!(news_list.len(), 0);
+
+    clear_output();
+    Ok(())
+}
+
+fn test_dispatch_scheduled_news(backend: TestBackend) -> Result<(), anyhow::Error> {
+    const MAX_RETRIES: u32 = 3;
+    const RETRY_INTERVAL: u64 = 2;
+    let storage = create_storage(backend);
+
+    let current_block_hash =
+        BlockHash::from_str("0000000000000000000000000000000000000000000000000000000000000000")
+            .unwrap();
+
+    let store = BitcoinCoordinatorStore::new(storage, 1, MAX_RETRIES, RETRY_INTERVAL)?;
+
+    let tx_id =
+        Txid::from_str("e9b7ad71b2f0bbce7165b5ab4a3c1e17e9189f2891650e3b7d644bb7e88f200a").unwrap();
+
+    // Add DispatchScheduled news
+    let news = CoordinatorNews::DispatchScheduled(tx_id, 150);
+    store.update_news(news.clone(), current_block_hash)?;
+
+    // Adding the same news in the same block does not duplicate it
+    store.update_news(news, current_block_hash)?;
+
+    // Verify the news is stored
+    let news_list = store.get_news()?;
+    assert_eq!(news_list.len(), 1);
+    match &news_list[0] {
+        CoordinatorNews::DispatchScheduled(id, height) => {
+            assert_eq!(*id, tx_id);
+            assert_eq!(*height, 150);
+        }
+        _ => panic!("Expected DispatchScheduled news"),
+    }
+
+    // Acknowledge the news
+    store.ack_news(AckCoordinatorNews::DispatchScheduled(tx_id))?;
+
+    // Verify the news is acknowledged
+    let news_list = store.get_news()?;
+    assert_eq!(news_list.len(), 0);
+
+    clear_output();
+    Ok(())
+}
+
+fn test_outpoint_spent_news(backend: TestBackend) -> Result<(), anyhow::Error> {
     const MAX_RETRIES: u32 = 3;
     const RETRY_INTERVAL: u64 = 2;
-    let path = format!("test_output/storage_news_test/{}", generate_random_string());
+    let storage = create_storage(backend);
+
+    let block_hash_1 =
+        BlockHash::from_str("0000000000000000000000000000000000000000000000000000000000000001")
+            .unwrap();
+    let block_hash_2 =
+        BlockHash::from_str("0000000000000000000000000000000000000000000000000000000000000002")
+            .unwrap();
+
+    let store = BitcoinCoordinatorStore::new(storage, 1, MAX_RETRIES, RETRY_INTERVAL)?;
+
+    let watched_txid =
+        Txid::from_str("e9b7ad71b2f0bbce7165b5ab4a3c1e17e9189f2891650e3b7d644bb7e88f200a").unwrap();
+    let spending_txid =
+        Txid::from_str("f9b7ad71b2f0bbce7165b5ab4a3c1e17e9189f2891650e3b7d644bb7e88f200b").unwrap();
+    let outpoint = OutPoint::new(watched_txid, 1);
+
+    // Watch the outpoint, watching it again replaces its context
+    store.watch_outpoint(outpoint, "old context".to_string())?;
+    store.watch_outpoint(outpoint, "counterparty output".to_string())?;
+
+    let watched = store.get_watched_outpoints()?;
+    assert_eq!(watched.len(), 1);
+    assert_eq!(watched[0].outpoint, outpoint);
+    assert_eq!(watched[0].context, "counterparty output");
+
+    let block_info = BlockInfo {
+        height: 150,
+        hash: block_hash_1,
+        is_orphan: false,
+    };
+    let news = CoordinatorNews::OutpointSpent(
+        outpoint,
+        spending_txid,
+        2,
+        block_info.clone(),
+        "counterparty output".to_string(),
+    );
+    store.update_news(news.clone(), block_hash_1)?;
+
+    let news_list = store.get_news()?;
+    assert_eq!(news_list, vec![news.clone()]);
+
+    store.ack_news(AckCoordinatorNews::OutpointSpent(outpoint))?;
+    assert!(store.get_news()?.is_empty());
+
+    // The same spend reported on a later block does not bring the news back
+    store.update_news(news, block_hash_2)?;
+    assert!(store.get_news()?.is_empty());
+
+    // A spend mined in another block after a reorg is reported again
+    let reorg_news = CoordinatorNews::OutpointSpent(
+        outpoint,
+        spending_txid,
+        2,
+        BlockInfo {
+            height: 151,
+            hash: block_hash_2,
+            is_orphan: false,
+        },
+        "counterparty output".to_string(),
+    );
+    store.update_news(reorg_news.clone(), block_hash_2)?;
+    assert_eq!(store.get_news()?, vec![reorg_news]);
+
+    // Stop watching the outpoint
+    assert!(store.unwatch_outpoint(outpoint)?);
+    assert!(!store.unwatch_outpoint(outpoint)?);
+    assert!(store.get_watched_outpoints()?.is_empty());
+
+    clear_output();
+    Ok(())
+}
 
-    let storage_config = StorageConfig::new(path, None);
-    let storage = Rc::new(Storage::new(&storage_config)?);
+fn test_dispatch_transaction_error_news(backend: TestBackend) -> Result<(), anyhow::Error> {
+    const MAX_RETRIES: u32 = 3;
+    const RETRY_INTERVAL: u64 = 2;
+    let storage = create_storage(backend);
 
     let current_block_hash =
         BlockHash::from_str("0000000000000000000000000000000000000000000000000000000000000000")
@@ -376,17 +642,23 @@ fn test_dispatch_transaction_error_news() -> Result<(), anyhow::Error> {
     let error_msg = "invalid transaction format".to_string();
 
     // Add DispatchTransactionError news
-    let news = CoordinatorNews::DispatchTransactionError(tx_id, context.clone(), error_msg.clone());
+    let news = CoordinatorNews::DispatchTransactionError(
+        tx_id,
+        context.clone(),
+        error_msg.clone(),
+        BroadcastFailureKind::PolicyRejection,
+    );
     store.update_news(news, current_block_hash)?;
 
     // Verify the news is stored
     let news_list = store.get_news()?;
     assert_eq!(news_list.len(), 1);
     match &news_list[0] {
-        CoordinatorNews::DispatchTransactionError(id, ctx, err) => {
+        CoordinatorNews::DispatchTransactionError(id, ctx, err, kind) => {
             assert_eq!(*id, tx_id);
             assert_eq!(ctx, &context);
             assert_eq!(err, &error_msg);
+            assert_eq!(*kind, BroadcastFailureKind::PolicyRejection);
         }
         _ => panic!("Expected DispatchTransactionError news"),
     }
@@ -402,14 +674,10 @@ fn test_dispatch_transaction_error_news() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
-#[test]
-fn test_all_error_types_together() -> Result<(), anyhow::Error> {
+fn test_all_error_types_together(backend: TestBackend) -> Result<(), anyhow::Error> {
     const MAX_RETRIES: u32 = 3;
     const RETRY_INTERVAL: u64 = 2;
-    let path = format!("test_output/storage_news_test/{}", generate_random_string());
-
-    let storage_config = StorageConfig::new(path, None);
-    let storage = Rc::new(Storage::new(&storage_config)?);
+    let storage = create_storage(backend);
 
     let current_block_hash =
         BlockHash::from_str("0000000000000000000000000000000000000000000000000000000000000000")
@@ -452,6 +720,7 @@ fn test_all_error_types_together() -> Result<(), anyhow::Error> {
             tx_id_4,
             "context4".to_string(),
             "invalid tx".to_string(),
+            BroadcastFailureKind::Other,
         ),
         current_block_hash,
     )?;
@@ -480,7 +749,7 @@ fn test_all_error_types_together() -> Result<(), anyhow::Error> {
                 assert_eq!(*id, tx_id_3);
                 found_network_error = true;
             }
-            CoordinatorNews::DispatchTransactionError(id, _, _) => {
+            CoordinatorNews::DispatchTransactionError(id, ..) => {
                 assert_eq!(*id, tx_id_4);
                 found_dispatch_error = true;
             }
@@ -513,14 +782,10 @@ fn test_all_error_types_together() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
-#[test]
-fn test_transaction_state_failed_on_fatal_error() -> Result<(), anyhow::Error> {
+fn test_transaction_state_failed_on_fatal_error(backend: TestBackend) -> Result<(), anyhow::Error> {
     const MAX_RETRIES: u32 = 3;
     const RETRY_INTERVAL: u64 = 2;
-    let path = format!("test_output/storage_news_test/{}", generate_random_string());
-
-    let storage_config = StorageConfig::new(path, None);
-    let storage = Rc::new(Storage::new(&storage_config)?);
+    let storage = create_storage(backend);
 
     let store = BitcoinCoordinatorStore::new(storage, 1, MAX_RETRIES, RETRY_INTERVAL)?;
 
@@ -546,3 +811,221 @@ fn test_transaction_state_failed_on_fatal_error() -> Result<(), anyhow::Error> {
     clear_output();
     Ok(())
 }
+
+fn test_get_news_page(backend: TestBackend) -> Result<(), anyhow::Error> {
+    const MAX_RETRIES: u32 = 3;
+    const RETRY_INTERVAL: u64 = 2;
+    let storage = create_storage(backend);
+
+    let current_block_hash =
+        BlockHash::from_str("0000000000000000000000000000000000000000000000000000000000000000")
+            .unwrap();
+
+    let store = BitcoinCoordinatorStore::new(storage, 1, MAX_RETRIES, RETRY_INTERVAL)?;
+
+    // Initially, there should be no news
+    let (news_page, has_more) = store.get_news_page(0, 2)?;
+    assert_eq!(news_page.len(), 0);
+    assert!(!has_more);
+
+    // Add 5 network error news
+    let tx_ids: Vec<Txid> = (0..5)
+        .map(|i| {
+            Txid::from_str(&format!(
+                "e9b7ad71b2f0bbce7165b5ab4a3c1e17e9189f2891650e3b7d644bb7e88f200{}",
+                i
+            ))
+            .unwrap()
+        })
+        .collect();
+
+    for tx_id in tx_ids.iter() {
+        let news =
+            CoordinatorNews::NetworkError(*tx_id, "context".to_string(), "error".to_string());
+        store.update_news(news, current_block_hash)?;
+    }
+
+    // First page keeps the insertion order and informs there are more news
+    let (news_page, has_more) = store.get_news_page(0, 2)?;
+    assert_eq!(news_page.len(), 2);
+    assert!(has_more);
+    let page_news: Vec<CoordinatorNews> = news_page.iter().map(|(_, news)| news.clone()).collect();
+    assert_eq!(page_news, store.get_news()?[0..2].to_vec());
+
+    // The next page starts after the last sequence number of the first one
+    let next_seq = news_page[1].0 + 1;
+    let (news_page, has_more) = store.get_news_page(next_seq, 2)?;
+    assert_eq!(news_page.len(), 2);
+    assert!(has_more);
+    assert!(matches!(&news_page[1].1, CoordinatorNews::NetworkError(id, _, _) if *id == tx_ids[3]));
+
+    // Acknowledge a news that is not part of the pages read so far
+    store.ack_news(AckCoordinatorNews::NetworkError(tx_ids[4]))?;
+    let (last_page, has_more) = store.get_news_page(news_page[1].0 + 1, 2)?;
+    assert!(last_page.is_empty());
+    assert!(!has_more);
+
+    // Drain the news by acknowledging page by page
+    let mut since_seq = 0;
+    let mut pages = 0;
+    loop {
+        let (news_page, has_more) = store.get_news_page(since_seq, 2)?;
+        for (seq, news) in news_page {
+            if let CoordinatorNews::NetworkError(tx_id, _, _) = news {
+                store.ack_news(AckCoordinatorNews::NetworkError(tx_id))?;
+            }
+            since_seq = seq + 1;
+        }
+        pages += 1;
+
+        if !has_more {
+            break;
+        }
+    }
+
+    assert_eq!(pages, 2);
+    assert_eq!(store.get_news()?.len(), 0);
+
+    clear_output();
+    Ok(())
+}
+
+fn test_news_page_in_insertion_order(backend: TestBackend) -> Result<(), anyhow::Error> {
+    let store = BitcoinCoordinatorStore::new(create_storage(backend), 1, 3, 2)?;
+    let block_hash = BlockHash::all_zeros();
+    let next_block_hash =
+        BlockHash::from_str("0000000000000000000000000000000000000000000000000000000000000001")
+            .unwrap();
+    let tx_id = Txid::all_zeros();
+
+    // News of different kinds, reported in another order than the one of their lists
+    let reported = vec![
+        CoordinatorNews::NetworkError(tx_id, "tx".to_string(), "error".to_string()),
+        CoordinatorNews::FundingNotFound,
+        CoordinatorNews::InsufficientFunds(tx_id, 1_000, 2_000),
+        CoordinatorNews::EstimateFeerateTooHigh(500, 100),
+        CoordinatorNews::MempoolRejection(tx_id, "tx".to_string(), "error".to_string()),
+    ];
+
+    for news in reported.iter() {
+        store.update_news(news.clone(), block_hash)?;
+    }
+
+    assert_eq!(store.get_news()?, reported);
+
+    // Paging by sequence number returns the same order, reading only the news of each page
+    let mut paged = Vec::new();
+    let mut since_seq = 0;
+    loop {
+        let reads = store.reads();
+        let (news_page, has_more) = store.get_news_page(since_seq, 2)?;
+        assert_eq!(store.reads() - reads, news_page.len() as u64);
+
+        for (seq, news) in news_page {
+            assert!(seq >= since_seq);
+            since_seq = seq + 1;
+            paged.push(news);
+        }
+
+        if !has_more {
+            break;
+        }
+    }
+
+    assert_eq!(paged, reported);
+
+    // A news reported again in another block takes the last place
+    store.update_news(reported[2].clone(), next_block_hash)?;
+    let news = store.get_news()?;
+    assert_eq!(news.len(), reported.len());
+    assert_eq!(news.last(), Some(&reported[2]));
+
+    clear_output();
+    Ok(())
+}
+
+fn test_ack_news_batch(backend: TestBackend) -> Result<(), anyhow::Error> {
+    const MAX_RETRIES: u32 = 3;
+    const RETRY_INTERVAL: u64 = 2;
+    let storage = create_storage(backend);
+
+    let current_block_hash =
+        BlockHash::from_str("0000000000000000000000000000000000000000000000000000000000000000")
+            .unwrap();
+
+    let store = BitcoinCoordinatorStore::new(storage, 1, MAX_RETRIES, RETRY_INTERVAL)?;
+
+    let tx_id_1 =
+        Txid::from_str("e9b7ad71b2f0bbce7165b5ab4a3c1e17e9189f2891650e3b7d644bb7e88f200a").unwrap();
+    let tx_id_2 =
+        Txid::from_str("f9b7ad71b2f0bbce7165b5ab4a3c1e17e9189f2891650e3b7d644bb7e88f200b").unwrap();
+    let tx_id_3 =
+        Txid::from_str("a9b7ad71b2f0bbce7165b5ab4a3c1e17e9189f2891650e3b7d644bb7e88f200c").unwrap();
+    let unknown_tx_id =
+        Txid::from_str("b9b7ad71b2f0bbce7165b5ab4a3c1e17e9189f2891650e3b7d644bb7e88f200d").unwrap();
+
+    store.update_news(
+        CoordinatorNews::InsufficientFunds(tx_id_1, 100, 200),
+        current_block_hash,
+    )?;
+    store.update_news(
+        CoordinatorNews::InsufficientFunds(tx_id_2, 100, 200),
+        current_block_hash,
+    )?;
+    store.update_news(
+        CoordinatorNews::DispatchTransactionError(
+            tx_id_3,
+            "tx_3".to_string(),
+            "error".to_string(),
+            BroadcastFailureKind::Other,
+        ),
+        current_block_hash,
+    )?;
+    store.update_news(
+        CoordinatorNews::EstimateFeerateTooHigh(500, 100),
+        current_block_hash,
+    )?;
+
+    // tx_id_2 is acknowledged before the batch
+    store.ack_news(AckCoordinatorNews::InsufficientFunds(tx_id_2))?;
+    assert_eq!(store.get_news()?.len(), 3);
+
+    let acknowledged = store.ack_news_batch(vec![
+        AckCoordinatorNews::InsufficientFunds(tx_id_1),
+        AckCoordinatorNews::DispatchTransactionError(tx_id_3),
+        // Already acknowledged, unknown and repeated news are skipped
+        AckCoordinatorNews::InsufficientFunds(tx_id_2),
+        AckCoordinatorNews::InsufficientFunds(unknown_tx_id),
+        AckCoordinatorNews::DispatchTransactionError(tx_id_3),
+        AckCoordinatorNews::FundingNotFound,
+    ])?;
+
+    assert_eq!(acknowledged, 2);
+
+    // Only the news that was not in the batch remains
+    let news_list = store.get_news()?;
+    assert_eq!(news_list.len(), 1);
+    assert!(matches!(
+        news_list[0],
+        CoordinatorNews::EstimateFeerateTooHigh(500, 100)
+    ));
+
+    // Acknowledging the same batch again does nothing
+    let acknowledged = store.ack_news_batch(vec![
+        AckCoordinatorNews::InsufficientFunds(tx_id_1),
+        AckCoordinatorNews::DispatchTransactionError(tx_id_3),
+    ])?;
+    assert_eq!(acknowledged, 0);
+
+    assert_eq!(
+        store.ack_news_batch(vec![AckCoordinatorNews::EstimateFeerateTooHigh(500, 100)])?,
+        1
+    );
+    assert!(store.get_news()?.is_empty());
+
+    // An empty batch is valid
+    assert_eq!(store.ack_news_batch(vec![])?, 0);
+
+    clear_output();
+    Ok(())
+}
@@ -0,0 +1,214 @@
+use bitcoin::{absolute::LockTime, transaction::Version, BlockHash, PublicKey, Transaction, Txid};
+use bitcoin_coordinator::{
+    errors::BitcoinCoordinatorStoreError,
+    speedup::SpeedupStore,
+    storage::BitcoinCoordinatorStoreApi,
+    types::{
+        AckCoordinatorNews, CoordinatedSpeedUpTransaction, CoordinatorNews, PruneSummary,
+        SpeedupState, TransactionState,
+    },
+};
+use protocol_builder::types::Utxo;
+use std::{collections::HashSet, str::FromStr};
+use utils::{clear_output, create_store};
+mod utils;
+
+fn tx_with_locktime(time: u32) -> Transaction {
+    Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::from_time(time).unwrap(),
+        input: vec![],
+        output: vec![],
+    }
+}
+
+fn dummy_speedup(txid: Txid, state: SpeedupState) -> CoordinatedSpeedUpTransaction {
+    let utxo = Utxo::new(
+        txid,
+        0,
+        1000,
+        &PublicKey::from_str("032e58afe51f9ed8ad3cc7897f634d881fdbe49a81564629ded8156bebd2ffd1af")
+            .unwrap(),
+    );
+
+    CoordinatedSpeedUpTransaction::new(txid, utxo.clone(), utxo, false, 0, state, 1.0, vec![], 1, 0)
+}
+
+#[test]
+fn test_prune_news() -> Result<(), anyhow::Error> {
+    let store = create_store();
+
+    let old_block_hash =
+        BlockHash::from_str("0000000000000000000000000000000000000000000000000000000000000001")?;
+    let recent_block_hash =
+        BlockHash::from_str("0000000000000000000000000000000000000000000000000000000000000002")?;
+
+    let tx_id_1 = tx_with_locktime(1653195601).compute_txid();
+    let tx_id_2 = tx_with_locktime(1653195602).compute_txid();
+    let tx_id_3 = tx_with_locktime(1653195603).compute_txid();
+    let tx_id_4 = tx_with_locktime(1653195604).compute_txid();
+
+    // Acknowledged and old, it is pruned
+    store.update_news(
+        CoordinatorNews::InsufficientFunds(tx_id_1, 1000, 2000),
+        old_block_hash,
+    )?;
+    // Not acknowledged and old, it survives
+    store.update_news(
+        CoordinatorNews::InsufficientFunds(tx_id_2, 1000, 2000),
+        old_block_hash,
+    )?;
+    // Acknowledged and recent, it survives
+    store.update_news(
+        CoordinatorNews::DispatchCancelled(tx_id_3, "context".to_string()),
+        recent_block_hash,
+    )?;
+    // Not acknowledged and recent, it survives
+    store.update_news(
+        CoordinatorNews::NetworkError(tx_id_4, "context".to_string(), "error".to_string()),
+        recent_block_hash,
+    )?;
+    // Acknowledged and old, it is pruned
+    store.update_news(CoordinatorNews::FundingNotFound, old_block_hash)?;
+
+    store.ack_news_batch(vec![
+        AckCoordinatorNews::InsufficientFunds(tx_id_1),
+        AckCoordinatorNews::DispatchCancelled(tx_id_3),
+        AckCoordinatorNews::FundingNotFound,
+    ])?;
+
+    let recent_blocks = HashSet::from([recent_block_hash]);
+    let summary = store.prune(&recent_blocks)?;
+
+    assert_eq!(
+        summary,
+        PruneSummary {
+            news: 2,
+            transactions: 0,
+            speedups: 0,
+        }
+    );
+
+    // Unacknowledged news are still returned
+    let news = store.get_news()?;
+    assert_eq!(news.len(), 2);
+    assert!(news.contains(&CoordinatorNews::InsufficientFunds(tx_id_2, 1000, 2000)));
+    assert!(news.contains(&CoordinatorNews::NetworkError(
+        tx_id_4,
+        "context".to_string(),
+        "error".to_string()
+    )));
+
+    // Pruning again with the same depth removes nothing
+    assert_eq!(store.prune(&recent_blocks)?.news, 0);
+
+    // Once its block is old, the acknowledged recent news is pruned
+    assert_eq!(store.prune(&HashSet::new())?.news, 1);
+    assert_eq!(store.get_news()?.len(), 2);
+
+    clear_output();
+
+    Ok(())
+}
+
+#[test]
+fn test_prune_finalized_transactions() -> Result<(), anyhow::Error> {
+    let store = create_store();
+
+    let finalized_tx = tx_with_locktime(1653195601);
+    let finalized_tx_id = finalized_tx.compute_txid();
+    let dispatched_tx = tx_with_locktime(1653195602);
+    let dispatched_tx_id = dispatched_tx.compute_txid();
+
+    store.save_tx(finalized_tx, None, None, "finalized".to_string())?;
+    store.save_tx(dispatched_tx, None, None, "dispatched".to_string())?;
+
+    store.update_tx_state(finalized_tx_id, TransactionState::Dispatched)?;
+    store.update_tx_state(finalized_tx_id, TransactionState::Confirmed)?;
+    store.update_tx_state(finalized_tx_id, TransactionState::Finalized)?;
+    store.update_tx_state(dispatched_tx_id, TransactionState::Dispatched)?;
+
+    let summary = store.prune(&HashSet::new())?;
+    assert_eq!(summary.transactions, 1);
+
+    // The finalized transaction is gone
+    assert!(matches!(
+        store.get_tx(&finalized_tx_id),
+        Err(BitcoinCoordinatorStoreError::TransactionNotFound(_))
+    ));
+
+    // The dispatched transaction is still there
+    let tx = store.get_tx(&dispatched_tx_id)?;
+    assert_eq!(tx.state, TransactionState::Dispatched);
+    assert_eq!(store.get_txs_in_progress()?.len(), 1);
+
+    // Finalized transactions are pruned only once
+    assert_eq!(store.prune(&HashSet::new())?.transactions, 0);
+
+    clear_output();
+
+    Ok(())
+}
+
+#[test]
+fn test_prune_finalized_speedups() -> Result<(), anyhow::Error> {
+    let store = create_store();
+
+    let old_checkpoint = tx_with_locktime(1653195601).compute_txid();
+    let confirmed = tx_with_locktime(1653195602).compute_txid();
+    let checkpoint = tx_with_locktime(1653195603).compute_txid();
+    let dispatched = tx_with_locktime(1653195604).compute_txid();
+
+    store.save_speedup(dummy_speedup(old_checkpoint, SpeedupState::Finalized))?;
+    store.save_speedup(dummy_speedup(confirmed, SpeedupState::Confirmed))?;
+    store.save_speedup(dummy_speedup(checkpoint, SpeedupState::Finalized))?;
+    store.save_speedup(dummy_speedup(dispatched, SpeedupState::Dispatched))?;
+
+    let funding = store.get_funding()?;
+    let pending: Vec<Txid> = store
+        .get_pending_speedups()?
+        .iter()
+        .map(|speedup| speedup.tx_id)
+        .collect();
+
+    let summary = store.prune(&HashSet::new())?;
+    assert_eq!(summary.speedups, 1);
+
+    // Only the finalized speedup before the last checkpoint is removed
+    assert!(matches!(
+        store.get_speedup(&old_checkpoint),
+        Err(BitcoinCoordinatorStoreError::SpeedupNotFound)
+    ));
+    assert_eq!(
+        store.get_speedup(&confirmed)?.state,
+        SpeedupState::Confirmed
+    );
+    assert_eq!(
+        store.get_speedup(&checkpoint)?.state,
+        SpeedupState::Finalized
+    );
+    assert_eq!(
+        store.get_speedup(&dispatched)?.state,
+        SpeedupState::Dispatched
+    );
+
+    // The speedup chain is not affected
+    assert_eq!(store.get_funding()?, funding);
+    let pending_after: Vec<Txid> = store
+        .get_pending_speedups()?
+        .iter()
+        .map(|speedup| speedup.tx_id)
+        .collect();
+    assert_eq!(pending_after, pending);
+
+    // The last checkpoint is always kept
+    assert_eq!(store.prune(&HashSet::new())?.speedups, 0);
+    assert_eq!(
+        store.get_speedup(&checkpoint)?.state,
+        SpeedupState::Finalized
+    );
+
+    clear_output();
+
+    Ok(())
+}
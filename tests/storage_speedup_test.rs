@@ -1,18 +1,53 @@
-use bitcoin::{absolute::LockTime, transaction::Version, PublicKey, Transaction, Txid};
+use bitcoin::{
+    absolute::LockTime, hashes::Hash, transaction::Version, BlockHash, PublicKey, Transaction, Txid,
+};
 use bitcoin_coordinator::{
+    clock::retry_backoff_millis,
     errors::BitcoinCoordinatorStoreError,
-    settings::MAX_LIMIT_UNCONFIRMED_PARENTS,
+    settings::{MAX_LIMIT_UNCONFIRMED_PARENTS, MAX_RETRY_BACKOFF_SECONDS},
     speedup::SpeedupStore,
-    types::{CoordinatedSpeedUpTransaction, SpeedupState},
+    storage::{BitcoinCoordinatorStore, BitcoinCoordinatorStoreApi},
+    testing::MockClock,
+    types::{
+        AckCoordinatorNews, CoordinatedSpeedUpTransaction, CoordinatorNews, SpeedupParent,
+        SpeedupState,
+    },
 };
 use protocol_builder::types::{output::SpeedupData, Utxo};
 use rand::Rng;
-use std::str::FromStr;
-use utils::clear_output;
-
-use crate::utils::create_store;
+use std::{rc::Rc, str::FromStr};
+use utils::{clear_output, create_store_on, TestBackend};
 mod utils;
 
+backend_tests!(
+    test_add_and_get_funding,
+    test_save_and_get_speedup,
+    test_pending_speedups_break_on_finalized,
+    test_get_funding_with_replace_speedup_confirmed,
+    test_get_funding_with_replace_speedup_dispatched_and_no_confirmed,
+    test_can_speedup_none,
+    test_update_speedup_state_and_remove_from_pending,
+    test_finalize_speedup_removes_only_previous_checkpoint,
+    test_verify_speedup_chain_reports_duplicates,
+    test_update_speedup_state_not_found,
+    test_get_speedup_not_found,
+    test_save_speedup_overwrites,
+    test_get_unconfirmed_txs_count,
+    test_get_speedups_for_retry,
+    test_queue_and_enqueue_speedup_for_retry,
+    test_increment_speedup_retry_count,
+    test_speedup_retry_exponential_backoff,
+    test_unconfirmed_chain_fee_shortfall,
+    test_funding_summary,
+    test_funding_pool_rotation,
+    test_remove_wrong_funding_checkpoint,
+    test_orphan_speedup_falls_back_to_previous_funding,
+    test_confirmed_rbf_invalidates_replaced_speedup_chain,
+    test_dispatched_txs_without_speedup,
+    test_get_speedup_replacements,
+    test_deferred_speedup_txs,
+);
+
 fn dummy_utxo_with(txid: &Txid, vout: u32, sats: u64) -> Utxo {
     Utxo::new(
         *txid,
@@ -50,11 +85,12 @@ fn dummy_speedup_tx(
         state,
         0.0,
         vec![
-            (speedup_data_1, tx_1, "Context 1".to_string()),
-            (speedup_data_2, tx_2, "Context 2".to_string()),
-            (speedup_data_3, tx_3, "Context 3".to_string()),
+            SpeedupParent::new(speedup_data_1, &tx_1, "Context 1".to_string()),
+            SpeedupParent::new(speedup_data_2, &tx_2, "Context 2".to_string()),
+            SpeedupParent::new(speedup_data_3, &tx_3, "Context 3".to_string()),
         ],
         1,
+        0,
     )
 }
 
@@ -76,9 +112,30 @@ fn random_locktime() -> LockTime {
     LockTime::from_time(random_time).unwrap()
 }
 
-#[test]
-fn test_add_and_get_funding() -> Result<(), anyhow::Error> {
-    let store = create_store();
+// A store whose retries are scheduled with a clock moved forward by the test.
+fn create_store_with_clock(backend: TestBackend) -> (BitcoinCoordinatorStore, Rc<MockClock>) {
+    let clock = Rc::new(MockClock::new(1_000_000));
+    let store = create_store_on(backend).with_clock(clock.clone());
+
+    (store, clock)
+}
+
+fn enqueue_random_speedup(
+    store: &BitcoinCoordinatorStore,
+) -> Result<CoordinatedSpeedUpTransaction, anyhow::Error> {
+    let tx = generate_random_tx();
+    let speedup = dummy_speedup_tx(&tx.compute_txid(), SpeedupState::Dispatched, false, 0);
+    store.enqueue_speedup_for_retry(speedup.clone())?;
+
+    Ok(speedup)
+}
+
+fn retry_ids(speedups: &[CoordinatedSpeedUpTransaction]) -> Vec<Txid> {
+    speedups.iter().map(|speedup| speedup.tx_id).collect()
+}
+
+fn test_add_and_get_funding(backend: TestBackend) -> Result<(), anyhow::Error> {
+    let store = create_store_on(backend);
 
     // No funding at first
     let funding = store.get_funding()?;
@@ -94,23 +151,33 @@ fn test_add_and_get_funding() -> Result<(), anyhow::Error> {
     assert!(funding2.is_some());
     assert_eq!(funding2.unwrap().txid, tx.compute_txid());
 
-    // Add a new funding will replace the old one
+    // Add a new funding while there are no unconfirmed speedups, it becomes the active one
     let tx2 = generate_random_tx();
     let utxo2 = dummy_utxo(&tx2.compute_txid());
     store.add_funding(utxo2.clone())?;
 
-    // Funding should be the new one
+    // Funding should be the new one and the old one is kept in the pool
     let funding3 = store.get_funding()?;
     assert!(funding3.is_some());
     assert_eq!(funding3.unwrap().txid, tx2.compute_txid());
 
+    let pool = store.get_funding_pool()?;
+    assert_eq!(pool.len(), 1);
+    assert_eq!(pool[0].txid, tx.compute_txid());
+
+    // The same funding can not be added twice
+    let result = store.add_funding(utxo2);
+    assert!(matches!(
+        result,
+        Err(BitcoinCoordinatorStoreError::FundingTransactionAlreadyExists)
+    ));
+
     clear_output();
     Ok(())
 }
 
-#[test]
-fn test_save_and_get_speedup() -> Result<(), anyhow::Error> {
-    let store = create_store();
+fn test_save_and_get_speedup(backend: TestBackend) -> Result<(), anyhow::Error> {
+    let store = create_store_on(backend);
 
     // Save a speedup tx
     let tx = generate_random_tx();
@@ -134,9 +201,8 @@ fn test_save_and_get_speedup() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
-#[test]
-fn test_pending_speedups_break_on_finalized() -> Result<(), anyhow::Error> {
-    let store = create_store();
+fn test_pending_speedups_break_on_finalized(backend: TestBackend) -> Result<(), anyhow::Error> {
+    let store = create_store_on(backend);
 
     // Add a finalized speedup (should act as checkpoint)
     let tx1 = generate_random_tx();
@@ -184,9 +250,10 @@ fn test_pending_speedups_break_on_finalized() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
-#[test]
-fn test_get_funding_with_replace_speedup_confirmed() -> Result<(), anyhow::Error> {
-    let store = create_store();
+fn test_get_funding_with_replace_speedup_confirmed(
+    backend: TestBackend,
+) -> Result<(), anyhow::Error> {
+    let store = create_store_on(backend);
 
     // Add a replace speedup, confirmed
     let tx1 = generate_random_tx();
@@ -220,10 +287,10 @@ fn test_get_funding_with_replace_speedup_confirmed() -> Result<(), anyhow::Error
     Ok(())
 }
 
-#[test]
-fn test_get_funding_with_replace_speedup_dispatched_and_no_confirmed() -> Result<(), anyhow::Error>
-{
-    let store = create_store();
+fn test_get_funding_with_replace_speedup_dispatched_and_no_confirmed(
+    backend: TestBackend,
+) -> Result<(), anyhow::Error> {
+    let store = create_store_on(backend);
 
     // Add a replace speedup, dispatched
     let tx1 = generate_random_tx();
@@ -242,9 +309,8 @@ fn test_get_funding_with_replace_speedup_dispatched_and_no_confirmed() -> Result
     Ok(())
 }
 
-#[test]
-fn test_can_speedup_none() -> Result<(), anyhow::Error> {
-    let store = create_store();
+fn test_can_speedup_none(backend: TestBackend) -> Result<(), anyhow::Error> {
+    let store = create_store_on(backend);
     assert!(!store.can_speedup()?);
 
     // Add 10 dispatched speedups (none are finalized or confirmed)
@@ -259,9 +325,10 @@ fn test_can_speedup_none() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
-#[test]
-fn test_update_speedup_state_and_remove_from_pending() -> Result<(), anyhow::Error> {
-    let store = create_store();
+fn test_update_speedup_state_and_remove_from_pending(
+    backend: TestBackend,
+) -> Result<(), anyhow::Error> {
+    let store = create_store_on(backend);
 
     // Add a speedup tx
     let tx1 = generate_random_tx();
@@ -349,9 +416,87 @@ fn test_update_speedup_state_and_remove_from_pending() -> Result<(), anyhow::Err
     Ok(())
 }
 
-#[test]
-fn test_update_speedup_state_not_found() -> Result<(), anyhow::Error> {
-    let store = create_store();
+// Finalizing a speedup removes the previous checkpoint of the chain from the pending list, and only it.
+fn test_finalize_speedup_removes_only_previous_checkpoint(
+    backend: TestBackend,
+) -> Result<(), anyhow::Error> {
+    let store = create_store_on(backend);
+
+    let funding_txid = generate_random_tx().compute_txid();
+    store.add_funding(dummy_utxo(&funding_txid))?;
+
+    let mut txids = Vec::new();
+    for _ in 0..6 {
+        let txid = generate_random_tx().compute_txid();
+        store.save_speedup(dummy_speedup_tx(&txid, SpeedupState::Dispatched, false, 0))?;
+        txids.push(txid);
+    }
+
+    // Finalizing the third speedup removes the funding checkpoint.
+    store.update_speedup_state(txids[2], SpeedupState::Finalized)?;
+    assert!(store.verify_speedup_chain()?.is_empty());
+
+    // The first speedup is finalized after a newer one, so two finalized speedups are listed.
+    store.update_speedup_state(txids[0], SpeedupState::Finalized)?;
+    let all = store.get_all_pending_speedups()?;
+    assert_eq!(all.len(), 6);
+
+    // Finalizing the fifth speedup removes the stale checkpoint (the third one), the older finalized one is kept.
+    store.update_speedup_state(txids[4], SpeedupState::Finalized)?;
+
+    let listed: Vec<Txid> = store
+        .get_all_pending_speedups()?
+        .iter()
+        .map(|speedup| speedup.tx_id)
+        .collect();
+    assert_eq!(listed.len(), 5);
+    assert!(!listed.contains(&txids[2]));
+    for txid in [txids[0], txids[1], txids[3], txids[4], txids[5]] {
+        assert!(listed.contains(&txid));
+    }
+
+    // The stale checkpoint keeps its record.
+    assert_eq!(store.get_speedup(&txids[2])?.state, SpeedupState::Finalized);
+
+    // Only the speedup after the new checkpoint is pending, and it funds the next speedup.
+    let pending = store.get_pending_speedups()?;
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].tx_id, txids[5]);
+    assert_eq!(store.get_funding()?.unwrap().txid, txids[5]);
+
+    // The pending speedup and the three transactions it pays for use unconfirmed slots.
+    assert_eq!(
+        store.get_available_unconfirmed_txs()?,
+        MAX_LIMIT_UNCONFIRMED_PARENTS - 4
+    );
+
+    assert!(store.verify_speedup_chain()?.is_empty());
+
+    clear_output();
+    Ok(())
+}
+
+fn test_verify_speedup_chain_reports_duplicates(backend: TestBackend) -> Result<(), anyhow::Error> {
+    let store = create_store_on(backend);
+
+    let txid = generate_random_tx().compute_txid();
+    let speedup = dummy_speedup_tx(&txid, SpeedupState::Dispatched, false, 0);
+    store.save_speedup(speedup.clone())?;
+    assert!(store.verify_speedup_chain()?.is_empty());
+
+    // Only a finalized checkpoint can be listed twice.
+    store.save_speedup(speedup)?;
+    assert_eq!(store.verify_speedup_chain()?.len(), 1);
+
+    store.update_speedup_state(txid, SpeedupState::Finalized)?;
+    assert!(store.verify_speedup_chain()?.is_empty());
+
+    clear_output();
+    Ok(())
+}
+
+fn test_update_speedup_state_not_found(backend: TestBackend) -> Result<(), anyhow::Error> {
+    let store = create_store_on(backend);
     let tx = generate_random_tx();
     let res = store.update_speedup_state(tx.compute_txid(), SpeedupState::Finalized);
     assert!(matches!(
@@ -362,9 +507,8 @@ fn test_update_speedup_state_not_found() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
-#[test]
-fn test_get_speedup_not_found() -> Result<(), anyhow::Error> {
-    let store = create_store();
+fn test_get_speedup_not_found(backend: TestBackend) -> Result<(), anyhow::Error> {
+    let store = create_store_on(backend);
     let tx = generate_random_tx();
     let res = store.get_speedup(&tx.compute_txid());
     assert!(matches!(
@@ -375,9 +519,8 @@ fn test_get_speedup_not_found() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
-#[test]
-fn test_save_speedup_overwrites() -> Result<(), anyhow::Error> {
-    let store = create_store();
+fn test_save_speedup_overwrites(backend: TestBackend) -> Result<(), anyhow::Error> {
+    let store = create_store_on(backend);
     let tx = generate_random_tx();
     let s1 = dummy_speedup_tx(&tx.compute_txid(), SpeedupState::Dispatched, false, 0);
     let mut s2 = s1.clone();
@@ -398,9 +541,8 @@ fn test_save_speedup_overwrites() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
-#[test]
-fn test_get_unconfirmed_txs_count() -> Result<(), anyhow::Error> {
-    let store = create_store();
+fn test_get_unconfirmed_txs_count(backend: TestBackend) -> Result<(), anyhow::Error> {
+    let store = create_store_on(backend);
     let tx = generate_random_tx();
     // It has 3 child txs.
     let max_unconfirmed_parents = MAX_LIMIT_UNCONFIRMED_PARENTS;
@@ -491,9 +633,8 @@ fn test_get_unconfirmed_txs_count() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
-#[test]
-fn test_get_speedups_for_retry() -> Result<(), anyhow::Error> {
-    let store = create_store();
+fn test_get_speedups_for_retry(backend: TestBackend) -> Result<(), anyhow::Error> {
+    let (store, clock) = create_store_with_clock(backend);
     let max_retries = 3;
     let interval_seconds = 2;
 
@@ -501,143 +642,100 @@ fn test_get_speedups_for_retry() -> Result<(), anyhow::Error> {
     let speedups = store.get_speedups_for_retry(max_retries, interval_seconds)?;
     assert!(speedups.is_empty(), "Expected no speedups initially");
 
-    // Add a speedup with retries less than max_retries
-    let tx1 = generate_random_tx();
-    let s1 = dummy_speedup_tx(&tx1.compute_txid(), SpeedupState::Dispatched, false, 0);
-    store.enqueue_speedup_for_retry(s1.clone())?;
+    // Add three speedups that were not retried yet
+    let s1 = enqueue_random_speedup(&store)?;
+    let s2 = enqueue_random_speedup(&store)?;
+    let s3 = enqueue_random_speedup(&store)?;
 
-    // Add a speedup with retries equal to max_retries
-    let tx2 = generate_random_tx();
-    let s2 = dummy_speedup_tx(&tx2.compute_txid(), SpeedupState::Dispatched, false, 0);
-    store.enqueue_speedup_for_retry(s2.clone())?;
-
-    // Add a speedup with 0 retries
-    let tx3 = generate_random_tx();
-    let s3 = dummy_speedup_tx(&tx3.compute_txid(), SpeedupState::Dispatched, false, 0);
-    store.enqueue_speedup_for_retry(s3.clone())?;
-
-    std::thread::sleep(std::time::Duration::from_secs(1));
-    // After 1 seconds, no speedups should be eligible for retry
+    clock.advance_secs(1);
+    // After 1 second, no speedups should be eligible for retry
     let speedups = store.get_speedups_for_retry(max_retries, interval_seconds)?;
     assert_eq!(
         speedups.len(),
         0,
-        "Expected no speedups to be returned after 1 seconds"
+        "Expected no speedups to be returned after 1 second"
     );
 
-    std::thread::sleep(std::time::Duration::from_secs(1));
-
-    // Add a speedup with 1 retry
-    let tx4 = generate_random_tx();
-    let s4 = dummy_speedup_tx(&tx4.compute_txid(), SpeedupState::Dispatched, false, 0);
-    store.enqueue_speedup_for_retry(s4.clone())?;
+    clock.advance_secs(1);
 
-    // Add another speedup with retries equal to max_retries
-    let tx5 = generate_random_tx();
-    let s5 = dummy_speedup_tx(&tx5.compute_txid(), SpeedupState::Dispatched, false, 0);
-    store.enqueue_speedup_for_retry(s5.clone())?;
+    // Add two more speedups, their interval starts now
+    let s4 = enqueue_random_speedup(&store)?;
+    let s5 = enqueue_random_speedup(&store)?;
 
-    // After a total of 2 seconds, the speedups with retries less than max_retries should be returned
+    // After a total of 2 seconds, the first three speedups should be returned
     let speedups = store.get_speedups_for_retry(max_retries, interval_seconds)?;
-
     assert_eq!(
-        speedups.len(),
-        3,
-        "Expected three speedups to be returned after 2 seconds"
-    );
-    assert!(
-        speedups.iter().any(|s| s.tx_id == s1.tx_id),
-        "Expected the first speedup to be returned"
-    );
-    assert!(
-        speedups.iter().any(|s| s.tx_id == s2.tx_id),
-        "Expected the third speedup to be returned"
-    );
-    assert!(
-        speedups.iter().any(|s| s.tx_id == s3.tx_id),
-        "Expected the fourth speedup to be returned"
+        retry_ids(&speedups),
+        vec![s1.tx_id, s2.tx_id, s3.tx_id],
+        "Expected the first three speedups to be returned after 2 seconds"
     );
 
-    std::thread::sleep(std::time::Duration::from_secs(2 * interval_seconds));
+    clock.advance_secs(interval_seconds);
     let speedups = store.get_speedups_for_retry(max_retries, interval_seconds)?;
     assert_eq!(
-        speedups.len(),
-        5,
+        retry_ids(&speedups),
+        vec![s1.tx_id, s2.tx_id, s3.tx_id, s4.tx_id, s5.tx_id],
         "Expected five speedups to be returned after 4 seconds"
     );
 
+    // A speedup that reached the max retries is not returned anymore
+    for _ in 0..max_retries {
+        store.increment_speedup_retry_count(s1.tx_id)?;
+    }
+    clock.advance_secs(3600);
+    let speedups = store.get_speedups_for_retry(max_retries, interval_seconds)?;
+    assert_eq!(
+        retry_ids(&speedups),
+        vec![s2.tx_id, s3.tx_id, s4.tx_id, s5.tx_id]
+    );
+
     clear_output();
     Ok(())
 }
 
-#[test]
-fn test_queue_and_enqueue_speedup_for_retry() -> Result<(), anyhow::Error> {
-    let store = create_store();
+fn test_queue_and_enqueue_speedup_for_retry(backend: TestBackend) -> Result<(), anyhow::Error> {
+    let (store, clock) = create_store_with_clock(backend);
     let interval_seconds = 1;
 
     // Add three speedups to the retry queue
-    let tx1 = generate_random_tx();
-    let s1 = dummy_speedup_tx(&tx1.compute_txid(), SpeedupState::Dispatched, false, 0);
-    store.enqueue_speedup_for_retry(s1.clone())?;
+    let s1 = enqueue_random_speedup(&store)?;
+    let s2 = enqueue_random_speedup(&store)?;
+    let s3 = enqueue_random_speedup(&store)?;
 
-    let tx2 = generate_random_tx();
-    let s2 = dummy_speedup_tx(&tx2.compute_txid(), SpeedupState::Dispatched, false, 0);
-    store.enqueue_speedup_for_retry(s2.clone())?;
-
-    let tx3 = generate_random_tx();
-    let s3 = dummy_speedup_tx(&tx3.compute_txid(), SpeedupState::Dispatched, false, 0);
-    store.enqueue_speedup_for_retry(s3.clone())?;
+    // Move past the retry interval so the speedups are eligible
+    clock.advance_secs(interval_seconds);
 
-    // Wait for interval_seconds seconds to ensure the speedups are in the queue
-    std::thread::sleep(std::time::Duration::from_secs(interval_seconds));
     // Verify all three are in the queue
     let speedups = store.get_speedups_for_retry(10, interval_seconds)?;
-    assert_eq!(speedups.len(), 3, "Expected three speedups in the queue");
-    assert!(
-        speedups.iter().any(|s| s.tx_id == s1.tx_id),
-        "Expected the first speedup to be in the queue"
-    );
-    assert!(
-        speedups.iter().any(|s| s.tx_id == s2.tx_id),
-        "Expected the second speedup to be in the queue"
-    );
-    assert!(
-        speedups.iter().any(|s| s.tx_id == s3.tx_id),
-        "Expected the third speedup to be in the queue"
+    assert_eq!(
+        retry_ids(&speedups),
+        vec![s1.tx_id, s2.tx_id, s3.tx_id],
+        "Expected three speedups in the queue"
     );
 
-    // Enqueue (remove) the first speedup from the retry queue
+    // Dequeue (remove) the first speedup from the retry queue
     store.dequeue_speedup_for_retry(s1.tx_id)?;
 
-    std::thread::sleep(std::time::Duration::from_secs(interval_seconds));
     // Verify the first speedup is no longer in the queue
     let speedups = store.get_speedups_for_retry(10, interval_seconds)?;
     assert_eq!(
-        speedups.len(),
-        2,
+        retry_ids(&speedups),
+        vec![s2.tx_id, s3.tx_id],
         "Expected two speedups in the queue after removing the first"
     );
-    assert!(
-        !speedups.iter().any(|s| s.tx_id == s1.tx_id),
-        "Did not expect the first speedup to be in the queue"
-    );
 
-    // Enqueue (remove) the second speedup from the retry queue
+    // Dequeue (remove) the second speedup from the retry queue
     store.dequeue_speedup_for_retry(s2.tx_id)?;
 
     // Verify the second speedup is no longer in the queue
     let speedups = store.get_speedups_for_retry(10, interval_seconds)?;
     assert_eq!(
-        speedups.len(),
-        1,
+        retry_ids(&speedups),
+        vec![s3.tx_id],
         "Expected one speedup in the queue after removing the second"
     );
-    assert!(
-        !speedups.iter().any(|s| s.tx_id == s2.tx_id),
-        "Did not expect the second speedup to be in the queue"
-    );
 
-    // Enqueue (remove) the third speedup from the retry queue
+    // Dequeue (remove) the third speedup from the retry queue
     store.dequeue_speedup_for_retry(s3.tx_id)?;
 
     // Verify the queue is empty
@@ -651,21 +749,18 @@ fn test_queue_and_enqueue_speedup_for_retry() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
-#[test]
-fn test_increment_speedup_retry_count() -> Result<(), anyhow::Error> {
-    let store = create_store();
+fn test_increment_speedup_retry_count(backend: TestBackend) -> Result<(), anyhow::Error> {
+    let (store, clock) = create_store_with_clock(backend);
     let interval_seconds = 1;
 
     // Add a speedup to the retry queue
-    let tx1 = generate_random_tx();
-    let s1 = dummy_speedup_tx(&tx1.compute_txid(), SpeedupState::Dispatched, false, 0);
-    store.enqueue_speedup_for_retry(s1.clone())?;
+    let s1 = enqueue_random_speedup(&store)?;
 
     // Increment the retry count
     store.increment_speedup_retry_count(s1.tx_id)?;
 
-    // Wait for interval_seconds seconds to ensure the speedups are eligible for retry
-    std::thread::sleep(std::time::Duration::from_secs(interval_seconds));
+    // After one retry the backoff is twice the interval
+    clock.advance_secs(2 * interval_seconds);
 
     // Verify the retry count has been incremented
     let speedups = store.get_speedups_for_retry(10, interval_seconds)?;
@@ -682,8 +777,8 @@ fn test_increment_speedup_retry_count() -> Result<(), anyhow::Error> {
         store.increment_speedup_retry_count(s1.tx_id)?;
     }
 
-    // Wait for interval_seconds seconds to ensure the speedups are eligible for retry
-    std::thread::sleep(std::time::Duration::from_secs(interval_seconds));
+    // After four retries the backoff is 16 times the interval
+    clock.advance_secs(16 * interval_seconds);
 
     // Verify the retry count has been incremented to 4
     let speedups = store.get_speedups_for_retry(10, interval_seconds)?;
@@ -707,3 +802,710 @@ fn test_increment_speedup_retry_count() -> Result<(), anyhow::Error> {
     clear_output();
     Ok(())
 }
+
+// The wait before each retry doubles: 2, 4, 8 and 16 seconds with a 2 seconds interval, capped at
+// MAX_RETRY_BACKOFF_SECONDS.
+fn test_speedup_retry_exponential_backoff(backend: TestBackend) -> Result<(), anyhow::Error> {
+    let (store, clock) = create_store_with_clock(backend);
+    let interval_seconds = 2;
+    let max_retries = 20;
+
+    let speedup = enqueue_random_speedup(&store)?;
+
+    for retries in 0..4 {
+        let backoff = interval_seconds * 2u64.pow(retries);
+
+        clock.advance_millis(backoff * 1000 - 1);
+        assert!(
+            store
+                .get_speedups_for_retry(max_retries, interval_seconds)?
+                .is_empty(),
+            "Expected the speedup to wait {backoff} seconds after {retries} retries"
+        );
+
+        clock.advance_millis(1);
+        assert_eq!(
+            retry_ids(&store.get_speedups_for_retry(max_retries, interval_seconds)?),
+            vec![speedup.tx_id]
+        );
+
+        store.increment_speedup_retry_count(speedup.tx_id)?;
+    }
+
+    for _ in 4..12 {
+        store.increment_speedup_retry_count(speedup.tx_id)?;
+    }
+
+    clock.advance_secs(MAX_RETRY_BACKOFF_SECONDS - 1);
+    assert!(store
+        .get_speedups_for_retry(max_retries, interval_seconds)?
+        .is_empty());
+
+    clock.advance_secs(1);
+    assert_eq!(
+        retry_ids(&store.get_speedups_for_retry(max_retries, interval_seconds)?),
+        vec![speedup.tx_id]
+    );
+
+    assert_eq!(retry_backoff_millis(interval_seconds, 0), 2_000);
+    assert_eq!(retry_backoff_millis(interval_seconds, 3), 16_000);
+    assert_eq!(
+        retry_backoff_millis(interval_seconds, 40),
+        MAX_RETRY_BACKOFF_SECONDS * 1000
+    );
+
+    clear_output();
+    Ok(())
+}
+
+fn test_unconfirmed_chain_fee_shortfall(backend: TestBackend) -> Result<(), anyhow::Error> {
+    let store = create_store_on(backend);
+    const SPEEDUP_VSIZE: usize = 150;
+    const FEE_RATE_AT_DISPATCH: u64 = 2;
+    const NEW_NETWORK_FEE_RATE: u64 = 10;
+
+    let funding_tx = generate_random_tx();
+    store.add_funding(dummy_utxo(&funding_tx.compute_txid()))?;
+
+    // Without unconfirmed speedups there is nothing to top up.
+    assert_eq!(
+        store.get_unconfirmed_chain_fee_shortfall(NEW_NETWORK_FEE_RATE)?,
+        (0, 0)
+    );
+
+    // Create a chain of two unconfirmed speedups, each one paying for a dispatched transaction at 2 sat/vB.
+    let mut chain_vsize = 0;
+    let mut speedups = vec![];
+
+    for _ in 0..2 {
+        let tx = generate_random_tx();
+        store.save_tx(tx.clone(), None, None, "context".to_string())?;
+        store.update_tx_to_dispatched(tx.compute_txid(), 100, FEE_RATE_AT_DISPATCH)?;
+
+        let speedup_txid = generate_random_tx().compute_txid();
+        let speedup = CoordinatedSpeedUpTransaction::new(
+            speedup_txid,
+            dummy_utxo(&speedup_txid),
+            dummy_utxo_with(&speedup_txid, 0, 1000),
+            false,
+            100,
+            SpeedupState::Dispatched,
+            1.0,
+            vec![SpeedupParent::new(
+                SpeedupData::new(dummy_utxo(&tx.compute_txid())),
+                &tx,
+                "context".to_string(),
+            )],
+            FEE_RATE_AT_DISPATCH,
+            SPEEDUP_VSIZE,
+        );
+        store.save_speedup(speedup.clone())?;
+        speedups.push(speedup);
+
+        chain_vsize += SPEEDUP_VSIZE + tx.vsize();
+    }
+
+    // The network fee rate goes up to 10 sat/vB, so the chain misses exactly 8 sat/vB.
+    let (fee_shortfall, vsize) = store.get_unconfirmed_chain_fee_shortfall(NEW_NETWORK_FEE_RATE)?;
+    assert_eq!(vsize, chain_vsize);
+    assert_eq!(
+        fee_shortfall,
+        (NEW_NETWORK_FEE_RATE - FEE_RATE_AT_DISPATCH) * chain_vsize as u64
+    );
+
+    // If the fee rate goes down there is nothing to top up.
+    let (fee_shortfall, _) = store.get_unconfirmed_chain_fee_shortfall(1)?;
+    assert_eq!(fee_shortfall, 0);
+
+    // Replace the last speedup with a RBF at 6 sat/vB, the replaced speedup is not part of the chain anymore.
+    let last_speedup = speedups.last().unwrap();
+    let rbf_txid = generate_random_tx().compute_txid();
+    let rbf = CoordinatedSpeedUpTransaction::new(
+        rbf_txid,
+        last_speedup.prev_funding.clone(),
+        dummy_utxo(&rbf_txid),
+        true,
+        101,
+        SpeedupState::Dispatched,
+        1.5,
+        last_speedup.speedup_tx_data.clone(),
+        6,
+        SPEEDUP_VSIZE,
+    );
+    store.save_speedup(rbf)?;
+
+    let last_parent_vsize = last_speedup.speedup_tx_data[0].vsize;
+    let first_chain_vsize = chain_vsize - SPEEDUP_VSIZE - last_parent_vsize;
+    let (fee_shortfall, vsize) = store.get_unconfirmed_chain_fee_shortfall(NEW_NETWORK_FEE_RATE)?;
+    assert_eq!(vsize, chain_vsize);
+    assert_eq!(
+        fee_shortfall,
+        (NEW_NETWORK_FEE_RATE - FEE_RATE_AT_DISPATCH) * first_chain_vsize as u64
+            + (NEW_NETWORK_FEE_RATE - 6) * (SPEEDUP_VSIZE + last_parent_vsize) as u64
+    );
+
+    clear_output();
+    Ok(())
+}
+
+fn test_funding_summary(backend: TestBackend) -> Result<(), anyhow::Error> {
+    let store = create_store_on(backend);
+    const FUNDING_AMOUNT: u64 = 100_000;
+    const SPEEDUP_VSIZE: usize = 200;
+    const NETWORK_FEE_RATE: u64 = 10;
+
+    let funding_txid = generate_random_tx().compute_txid();
+    store.add_funding(dummy_utxo_with(&funding_txid, 0, FUNDING_AMOUNT))?;
+
+    let summary = store.get_funding_summary(NETWORK_FEE_RATE)?;
+    assert_eq!(summary.funding.unwrap().amount, FUNDING_AMOUNT);
+    assert_eq!(summary.spent_since_funding, 0);
+    assert_eq!(summary.unconfirmed_speedups, 0);
+    assert_eq!(summary.affordable_speedups, None);
+
+    // Chain three speedups, each one spending the change of the previous one.
+    let mut funding = dummy_utxo_with(&funding_txid, 0, FUNDING_AMOUNT);
+    let mut speedups = vec![];
+
+    for fee in [1_000, 2_000, 3_000] {
+        let speedup_txid = generate_random_tx().compute_txid();
+        let next_funding = dummy_utxo_with(&speedup_txid, 0, funding.amount - fee);
+        let speedup = CoordinatedSpeedUpTransaction::new(
+            speedup_txid,
+            funding.clone(),
+            next_funding.clone(),
+            false,
+            100,
+            SpeedupState::Dispatched,
+            1.0,
+            vec![],
+            NETWORK_FEE_RATE,
+            SPEEDUP_VSIZE,
+        );
+        store.save_speedup(speedup.clone())?;
+        speedups.push(speedup);
+        funding = next_funding;
+    }
+
+    let summary = store.get_funding_summary(NETWORK_FEE_RATE)?;
+    assert_eq!(summary.funding.unwrap().amount, 94_000);
+    assert_eq!(summary.spent_since_funding, 6_000);
+    assert_eq!(summary.unconfirmed_speedups, 3);
+    // 94_000 sats / (200 vB * 10 sat/vB)
+    assert_eq!(summary.affordable_speedups, Some(47));
+
+    // Replace the last speedup with a RBF paying 4_500 sats, only the extra 1_500 sats are spent.
+    let last_speedup = speedups.last().unwrap();
+    let rbf_txid = generate_random_tx().compute_txid();
+    let rbf = CoordinatedSpeedUpTransaction::new(
+        rbf_txid,
+        last_speedup.prev_funding.clone(),
+        dummy_utxo_with(&rbf_txid, 0, last_speedup.prev_funding.amount - 4_500),
+        true,
+        101,
+        SpeedupState::Dispatched,
+        1.5,
+        vec![],
+        NETWORK_FEE_RATE,
+        SPEEDUP_VSIZE,
+    );
+    store.save_speedup(rbf)?;
+
+    let summary = store.get_funding_summary(NETWORK_FEE_RATE)?;
+    assert_eq!(summary.funding.unwrap().amount, 92_500);
+    assert_eq!(summary.spent_since_funding, 7_500);
+    assert_eq!(summary.unconfirmed_speedups, 3);
+
+    // A new funding added while the chain is unconfirmed waits in the pool and counts as remaining balance.
+    let new_funding_txid = generate_random_tx().compute_txid();
+    store.add_funding(dummy_utxo_with(&new_funding_txid, 0, FUNDING_AMOUNT))?;
+
+    let summary = store.get_funding_summary(NETWORK_FEE_RATE)?;
+    assert_eq!(summary.funding.unwrap().amount, 92_500);
+    assert_eq!(summary.funding_pool.len(), 1);
+    assert_eq!(summary.funding_pool[0].txid, new_funding_txid);
+    assert_eq!(summary.spent_since_funding, 7_500);
+    // (92_500 + 100_000) sats / (200 vB * 10 sat/vB)
+    assert_eq!(summary.affordable_speedups, Some(96));
+
+    clear_output();
+    Ok(())
+}
+
+fn test_funding_pool_rotation(backend: TestBackend) -> Result<(), anyhow::Error> {
+    let store = create_store_on(backend);
+    const MAX_UNCONFIRMED_SPEEDUPS: usize = 10;
+    const SPEEDUP_FEE: u64 = 1_000;
+
+    let funding_a_txid = generate_random_tx().compute_txid();
+    let funding_a = dummy_utxo_with(&funding_a_txid, 0, 100_000);
+    store.add_funding(funding_a.clone())?;
+
+    let save_speedup_from = |funding: &Utxo| -> Result<Utxo, anyhow::Error> {
+        let speedup_txid = generate_random_tx().compute_txid();
+        let next_funding = dummy_utxo_with(&speedup_txid, 0, funding.amount - SPEEDUP_FEE);
+        store.save_speedup(CoordinatedSpeedUpTransaction::new(
+            speedup_txid,
+            funding.clone(),
+            next_funding.clone(),
+            false,
+            100,
+            SpeedupState::Dispatched,
+            1.0,
+            vec![],
+            1,
+            150,
+        ))?;
+        Ok(next_funding)
+    };
+
+    // Start a chain from funding A, then add funding B while the chain is unconfirmed.
+    let mut chain_a_tip = save_speedup_from(&funding_a)?;
+
+    let funding_b_txid = generate_random_tx().compute_txid();
+    let funding_b = dummy_utxo_with(&funding_b_txid, 0, 50_000);
+    store.add_funding(funding_b.clone())?;
+
+    // Funding B waits in the pool, the chain of funding A keeps funding the speedups.
+    assert_eq!(store.get_funding_pool()?.len(), 1);
+    assert_eq!(store.get_funding()?.unwrap().txid, chain_a_tip.txid);
+    assert!(!store.is_pool_funding(&chain_a_tip)?);
+
+    // Saturate the chain of funding A.
+    for _ in 1..MAX_UNCONFIRMED_SPEEDUPS {
+        chain_a_tip = save_speedup_from(&chain_a_tip)?;
+    }
+
+    assert!(store.has_reached_max_unconfirmed_speedups()?);
+
+    // Speedups continue from funding B with all the unconfirmed slots available.
+    let funding = store.get_funding()?.unwrap();
+    assert_eq!(funding.txid, funding_b_txid);
+    assert!(store.is_pool_funding(&funding)?);
+    assert!(store.can_speedup()?);
+    assert_eq!(
+        store.get_available_unconfirmed_txs()?,
+        MAX_LIMIT_UNCONFIRMED_PARENTS
+    );
+
+    // Saving a speedup funded by B rotates the active funding.
+    let chain_b_tip = save_speedup_from(&funding)?;
+
+    assert_eq!(store.get_funding()?.unwrap().txid, chain_b_tip.txid);
+    assert!(!store.has_reached_max_unconfirmed_speedups()?);
+    assert_eq!(store.get_pending_speedups()?.len(), 1);
+
+    let summary = store.get_funding_summary(1)?;
+    assert_eq!(summary.spent_since_funding, SPEEDUP_FEE);
+    assert_eq!(summary.unconfirmed_speedups, 1);
+
+    // The change of the chain left behind goes back to the pool, it can not be used until it is confirmed.
+    let pool = store.get_funding_pool()?;
+    assert_eq!(pool.len(), 1);
+    assert_eq!(pool[0].txid, chain_a_tip.txid);
+
+    for _ in 1..MAX_UNCONFIRMED_SPEEDUPS {
+        save_speedup_from(&store.get_funding()?.unwrap())?;
+    }
+    assert!(store.get_funding()?.is_none());
+
+    store.update_speedup_state(chain_a_tip.txid, SpeedupState::Confirmed)?;
+    assert_eq!(store.get_funding()?.unwrap().txid, chain_a_tip.txid);
+
+    // Funding in the pool can be removed.
+    store.remove_funding(chain_a_tip.txid, chain_a_tip.vout)?;
+    assert!(store.get_funding_pool()?.is_empty());
+    assert!(store.get_funding()?.is_none());
+
+    // Its speedup is still part of the chain.
+    let result = store.remove_funding(chain_a_tip.txid, chain_a_tip.vout);
+    assert!(matches!(
+        result,
+        Err(BitcoinCoordinatorStoreError::CannotRemoveActiveSpeedup(txid)) if txid == chain_a_tip.txid
+    ));
+
+    clear_output();
+    Ok(())
+}
+
+fn test_remove_wrong_funding_checkpoint(backend: TestBackend) -> Result<(), anyhow::Error> {
+    let store = create_store_on(backend);
+
+    let funding_a = dummy_utxo_with(&generate_random_tx().compute_txid(), 0, 100_000);
+    store.add_funding(funding_a.clone())?;
+    assert_eq!(store.get_funding()?, Some(funding_a.clone()));
+
+    // Funding B is registered with a wrong vout, it becomes the checkpoint and A waits in the pool.
+    let funding_b_txid = generate_random_tx().compute_txid();
+    let wrong_funding_b = dummy_utxo_with(&funding_b_txid, 1, 50_000);
+    store.add_funding(wrong_funding_b.clone())?;
+    assert_eq!(store.get_funding()?, Some(wrong_funding_b));
+    assert_eq!(store.get_funding_pool()?, vec![funding_a.clone()]);
+
+    let result = store.remove_funding(funding_b_txid, 0);
+    assert!(matches!(
+        result,
+        Err(BitcoinCoordinatorStoreError::FundingNotFound)
+    ));
+
+    // Removing the checkpoint makes A the active funding again.
+    store.remove_funding(funding_b_txid, 1)?;
+    assert_eq!(store.get_funding()?, Some(funding_a.clone()));
+    assert!(store.get_funding_pool()?.is_empty());
+    assert!(matches!(
+        store.get_speedup(&funding_b_txid),
+        Err(BitcoinCoordinatorStoreError::SpeedupNotFound)
+    ));
+    assert!(store.verify_speedup_chain()?.is_empty());
+
+    // B is added again with the right vout.
+    let funding_b = dummy_utxo_with(&funding_b_txid, 0, 50_000);
+    store.add_funding(funding_b.clone())?;
+    assert_eq!(store.get_funding()?, Some(funding_b.clone()));
+    assert_eq!(store.get_funding_pool()?, vec![funding_a]);
+
+    // Once a speedup spends B, neither the speedup nor B can be removed.
+    let speedup_txid = generate_random_tx().compute_txid();
+    let next_funding = dummy_utxo_with(&speedup_txid, 0, 49_000);
+    store.save_speedup(CoordinatedSpeedUpTransaction::new(
+        speedup_txid,
+        funding_b.clone(),
+        next_funding.clone(),
+        false,
+        100,
+        SpeedupState::Dispatched,
+        1.0,
+        vec![],
+        1,
+        150,
+    ))?;
+
+    for (txid, vout) in [(speedup_txid, 0), (funding_b_txid, 0)] {
+        let result = store.remove_funding(txid, vout);
+        assert!(matches!(
+            result,
+            Err(BitcoinCoordinatorStoreError::CannotRemoveActiveSpeedup(txid)) if txid == speedup_txid
+        ));
+    }
+
+    // The transactions of the chain can not be added as funding.
+    for utxo in [
+        next_funding.clone(),
+        dummy_utxo_with(&funding_b_txid, 1, 50_000),
+    ] {
+        let result = store.add_funding(utxo);
+        assert!(matches!(
+            result,
+            Err(BitcoinCoordinatorStoreError::FundingTransactionAlreadyExists)
+        ));
+    }
+
+    assert_eq!(store.get_funding()?, Some(next_funding));
+    assert!(store.verify_speedup_chain()?.is_empty());
+
+    clear_output();
+    Ok(())
+}
+
+fn test_orphan_speedup_falls_back_to_previous_funding(
+    backend: TestBackend,
+) -> Result<(), anyhow::Error> {
+    let store = create_store_on(backend);
+
+    let funding_txid = generate_random_tx().compute_txid();
+    let funding = dummy_utxo_with(&funding_txid, 0, 100_000);
+    store.add_funding(funding.clone())?;
+
+    let new_speedup = |prev_funding: &Utxo, state: SpeedupState| {
+        let speedup_txid = generate_random_tx().compute_txid();
+        CoordinatedSpeedUpTransaction::new(
+            speedup_txid,
+            prev_funding.clone(),
+            dummy_utxo_with(&speedup_txid, 0, prev_funding.amount - 1_000),
+            false,
+            100,
+            state,
+            1.0,
+            vec![],
+            1,
+            150,
+        )
+    };
+
+    // A confirmed speedup and an unconfirmed speedup funded from its change.
+    let confirmed_speedup = new_speedup(&funding, SpeedupState::Confirmed);
+    store.save_speedup(confirmed_speedup.clone())?;
+
+    let child_speedup = new_speedup(&confirmed_speedup.next_funding, SpeedupState::Dispatched);
+    store.save_speedup(child_speedup.clone())?;
+
+    assert_eq!(store.get_funding()?.unwrap().txid, child_speedup.tx_id);
+
+    // The confirmed speedup is orphaned by a reorg, so its change and the change of its child no longer exist.
+    let orphaned = store.orphan_speedup(confirmed_speedup.tx_id)?;
+    assert_eq!(orphaned, vec![confirmed_speedup.tx_id, child_speedup.tx_id]);
+    assert_eq!(
+        store.get_speedup(&child_speedup.tx_id)?.state,
+        SpeedupState::Orphaned
+    );
+
+    // Funding falls back to the previous valid checkpoint.
+    let fallback_funding = store.get_funding()?.unwrap();
+    assert_eq!(fallback_funding.txid, funding_txid);
+    assert_eq!(fallback_funding.amount, funding.amount);
+    assert!(store.get_last_speedup()?.is_none());
+
+    // Once the speedup is mined again, the chain is valid again.
+    store.restore_orphaned_speedup(confirmed_speedup.tx_id)?;
+    assert_eq!(
+        store.get_speedup(&confirmed_speedup.tx_id)?.state,
+        SpeedupState::Confirmed
+    );
+    assert_eq!(
+        store.get_speedup(&child_speedup.tx_id)?.state,
+        SpeedupState::Dispatched
+    );
+    assert_eq!(store.get_funding()?.unwrap().txid, child_speedup.tx_id);
+
+    clear_output();
+    Ok(())
+}
+
+fn test_confirmed_rbf_invalidates_replaced_speedup_chain(
+    backend: TestBackend,
+) -> Result<(), anyhow::Error> {
+    let store = create_store_on(backend);
+
+    let funding_txid = generate_random_tx().compute_txid();
+    let funding = dummy_utxo_with(&funding_txid, 0, 100_000);
+    store.add_funding(funding.clone())?;
+
+    let new_speedup = |prev_funding: &Utxo, is_rbf: bool| {
+        let speedup_txid = generate_random_tx().compute_txid();
+        CoordinatedSpeedUpTransaction::new(
+            speedup_txid,
+            prev_funding.clone(),
+            dummy_utxo_with(&speedup_txid, 0, prev_funding.amount - 1_000),
+            is_rbf,
+            100,
+            SpeedupState::Dispatched,
+            1.0,
+            vec![],
+            1,
+            150,
+        )
+    };
+
+    // A CPFP is replaced by an RBF spending the same funding, which is confirmed.
+    let cpfp = new_speedup(&funding, false);
+    store.save_speedup(cpfp.clone())?;
+
+    let rbf = new_speedup(&funding, true);
+    store.save_speedup(rbf.clone())?;
+    store.update_speedup_state(rbf.tx_id, SpeedupState::Confirmed)?;
+
+    // A CPFP funded from the change of the replaced one is sent after the RBF.
+    let child_cpfp = new_speedup(&cpfp.next_funding, false);
+    store.save_speedup(child_cpfp.clone())?;
+
+    let invalidated = store.invalidate_replaced_speedups(rbf.tx_id)?;
+    assert_eq!(invalidated, vec![cpfp.tx_id, child_cpfp.tx_id]);
+    assert_eq!(
+        store.get_speedup(&cpfp.tx_id)?.state,
+        SpeedupState::Invalidated
+    );
+    assert_eq!(
+        store.get_speedup(&child_cpfp.tx_id)?.state,
+        SpeedupState::Invalidated
+    );
+
+    // Funding comes from the confirmed RBF, and the invalidated speedups do not use unconfirmed slots.
+    let funding = store.get_funding()?.unwrap();
+    assert_eq!(funding.txid, rbf.next_funding.txid);
+    assert_eq!(funding.amount, rbf.next_funding.amount);
+    assert_eq!(
+        store.get_available_unconfirmed_txs()?,
+        MAX_LIMIT_UNCONFIRMED_PARENTS
+    );
+    // The last valid speedup is the confirmed RBF, there is nothing to replace.
+    assert!(store.get_last_speedup()?.is_none());
+
+    // Nothing else is invalidated when the RBF is processed again.
+    assert!(store.invalidate_replaced_speedups(rbf.tx_id)?.is_empty());
+
+    // The news is acknowledged with the replaced speedup.
+    let news = CoordinatorNews::SpeedupChainInvalidated(invalidated);
+    store.update_news(news.clone(), BlockHash::all_zeros())?;
+    assert_eq!(store.get_news()?, vec![news]);
+
+    store.ack_news(AckCoordinatorNews::SpeedupChainInvalidated(cpfp.tx_id))?;
+    assert!(store.get_news()?.is_empty());
+
+    clear_output();
+    Ok(())
+}
+
+fn test_dispatched_txs_without_speedup(backend: TestBackend) -> Result<(), anyhow::Error> {
+    let store = create_store_on(backend);
+
+    let funding_txid = generate_random_tx().compute_txid();
+    let funding = dummy_utxo_with(&funding_txid, 0, 100_000);
+    store.add_funding(funding.clone())?;
+
+    let save_dispatched_tx = |speedup: bool| -> Result<Transaction, anyhow::Error> {
+        let tx = generate_random_tx();
+        let speedup_data = speedup.then(|| SpeedupData::new(dummy_utxo(&tx.compute_txid())));
+        store.save_tx(tx.clone(), speedup_data, None, "context_tx".to_string())?;
+        store.update_tx_to_dispatched(tx.compute_txid(), 100, 1)?;
+        Ok(tx)
+    };
+
+    // Transactions broadcast before the process stopped, none of them has a speedup yet.
+    let sped_up_tx = save_dispatched_tx(true)?;
+    let retrying_tx = save_dispatched_tx(true)?;
+    let unsped_tx = save_dispatched_tx(true)?;
+    let tx_without_speedup_data = save_dispatched_tx(false)?;
+
+    // A transaction waiting to be dispatched is not recovered.
+    let pending_tx = generate_random_tx();
+    store.save_tx(
+        pending_tx.clone(),
+        Some(SpeedupData::new(dummy_utxo(&pending_tx.compute_txid()))),
+        None,
+        "context_tx".to_string(),
+    )?;
+
+    let mut unsped_txids = store
+        .get_dispatched_txs_without_speedup()?
+        .iter()
+        .map(|tx| tx.tx_id)
+        .collect::<Vec<_>>();
+    unsped_txids.sort();
+
+    let mut expected_txids = vec![
+        sped_up_tx.compute_txid(),
+        retrying_tx.compute_txid(),
+        unsped_tx.compute_txid(),
+    ];
+    expected_txids.sort();
+
+    assert_eq!(unsped_txids, expected_txids);
+    assert!(!unsped_txids.contains(&tx_without_speedup_data.compute_txid()));
+
+    let speedup_for = |tx: &Transaction| -> CoordinatedSpeedUpTransaction {
+        let speedup_txid = generate_random_tx().compute_txid();
+        CoordinatedSpeedUpTransaction::new(
+            speedup_txid,
+            funding.clone(),
+            dummy_utxo_with(&speedup_txid, 0, 99_000),
+            false,
+            100,
+            SpeedupState::Dispatched,
+            1.0,
+            vec![SpeedupParent::new(
+                SpeedupData::new(dummy_utxo(&tx.compute_txid())),
+                &tx,
+                "context_tx".to_string(),
+            )],
+            1,
+            150,
+        )
+    };
+
+    // Transactions paid by a saved speedup or by one waiting to be resent are covered.
+    store.save_speedup(speedup_for(&sped_up_tx))?;
+    store.enqueue_speedup_for_retry(speedup_for(&retrying_tx))?;
+
+    let unsped_txs = store.get_dispatched_txs_without_speedup()?;
+    assert_eq!(unsped_txs.len(), 1);
+    assert_eq!(unsped_txs[0].tx_id, unsped_tx.compute_txid());
+
+    // Once paid, nothing is left to recover.
+    store.save_speedup(speedup_for(&unsped_tx))?;
+    assert!(store.get_dispatched_txs_without_speedup()?.is_empty());
+
+    clear_output();
+    Ok(())
+}
+
+fn test_get_speedup_replacements(backend: TestBackend) -> Result<(), anyhow::Error> {
+    let store = create_store_on(backend);
+
+    let funding = dummy_utxo_with(&generate_random_tx().compute_txid(), 0, 100_000);
+    store.add_funding(funding.clone())?;
+
+    let speedup = |prev_funding: &Utxo, change: u64, is_rbf: bool| {
+        let speedup_txid = generate_random_tx().compute_txid();
+        let tx = generate_random_tx();
+
+        CoordinatedSpeedUpTransaction::new(
+            speedup_txid,
+            prev_funding.clone(),
+            dummy_utxo_with(&speedup_txid, 0, change),
+            is_rbf,
+            100,
+            SpeedupState::Dispatched,
+            1.0,
+            vec![SpeedupParent::new(
+                SpeedupData::new(dummy_utxo(&tx.compute_txid())),
+                &tx,
+                "context_tx".to_string(),
+            )],
+            1,
+            150,
+        )
+    };
+
+    let cpfp = speedup(&funding, 99_000, false);
+    store.save_speedup(cpfp.clone())?;
+    assert!(store.get_speedup_replacements(&cpfp)?.is_empty());
+
+    let rbf_1 = speedup(&funding, 98_000, true);
+    let rbf_2 = speedup(&funding, 97_000, true);
+    store.save_speedup(rbf_1.clone())?;
+    store.save_speedup(rbf_2.clone())?;
+
+    // Saving a replacement again does not count it twice
+    store.save_speedup(rbf_2.clone())?;
+
+    let replacements: Vec<Txid> = store
+        .get_speedup_replacements(&cpfp)?
+        .iter()
+        .map(|speedup| speedup.tx_id)
+        .collect();
+    assert_eq!(replacements, vec![rbf_2.tx_id, rbf_1.tx_id]);
+
+    // A CPFP funded from the change of the last replacement starts without replacements
+    let next_cpfp = speedup(&rbf_2.next_funding, 96_000, false);
+    store.save_speedup(next_cpfp.clone())?;
+    assert!(store.get_speedup_replacements(&next_cpfp)?.is_empty());
+
+    clear_output();
+    Ok(())
+}
+
+fn test_deferred_speedup_txs(backend: TestBackend) -> Result<(), anyhow::Error> {
+    let store = create_store_on(backend);
+
+    assert!(store.get_deferred_speedup_txs()?.is_empty());
+
+    let tx_1 = generate_random_tx().compute_txid();
+    let tx_2 = generate_random_tx().compute_txid();
+    let tx_3 = generate_random_tx().compute_txid();
+
+    store.defer_speedup(&[tx_1, tx_2])?;
+
+    // Deferring a transaction again does not duplicate it
+    store.defer_speedup(&[tx_2, tx_3])?;
+    assert_eq!(store.get_deferred_speedup_txs()?, vec![tx_1, tx_2, tx_3]);
+
+    store.remove_deferred_speedup_txs(&[tx_1, tx_3])?;
+    assert_eq!(store.get_deferred_speedup_txs()?, vec![tx_2]);
+
+    // Removing unknown transactions is a no-op
+    store.remove_deferred_speedup_txs(&[tx_1])?;
+    assert_eq!(store.get_deferred_speedup_txs()?, vec![tx_2]);
+
+    clear_output();
+    Ok(())
+}
@@ -1,23 +1,37 @@
-use bitcoin::{absolute::LockTime, Transaction, Txid};
+use bitcoin::{absolute::LockTime, PublicKey, Transaction, Txid};
 use bitcoin_coordinator::{
+    errors::BitcoinCoordinatorStoreError,
+    speedup::SpeedupStore,
     storage::{BitcoinCoordinatorStore, BitcoinCoordinatorStoreApi},
-    types::TransactionState,
+    testing::MockClock,
+    types::{
+        CoordinatedSpeedUpTransaction, DispatchOptions, DispatchUrgency, SpeedupParent,
+        SpeedupState, TransactionEvent, TransactionHistory, TransactionState,
+    },
 };
-use std::rc::Rc;
-use storage_backend::{storage::Storage, storage_config::StorageConfig};
-use utils::{clear_output, generate_random_string};
+use protocol_builder::types::{output::SpeedupData, Utxo};
+use std::{rc::Rc, str::FromStr};
+use utils::{clear_output, create_storage, TestBackend};
 mod utils;
 
-#[test]
-fn test_save_and_get_tx() -> Result<(), anyhow::Error> {
+backend_tests!(
+    test_save_and_get_tx,
+    test_multiple_transactions,
+    test_cancel_monitor,
+    test_increment_tx_retry_count_and_get_txs_to_dispatch,
+    test_tx_marked_as_failed_after_max_retries,
+    test_save_txs_batch,
+    test_cancel_tx,
+    test_save_tx_with_dispatch_options,
+    test_reschedule_tx,
+    test_transaction_history,
+);
+
+fn test_save_and_get_tx(backend: TestBackend) -> Result<(), anyhow::Error> {
     const MAX_UNCONFIRMED_SPEEDUPS: u32 = 1;
     const MAX_RETRIES: u32 = 3;
     const RETRY_INTERVAL: u64 = 2;
-    let storage_config = StorageConfig::new(
-        format!("test_output/test/{}", generate_random_string()),
-        None,
-    );
-    let storage = Rc::new(Storage::new(&storage_config)?);
+    let storage = create_storage(backend);
 
     let store = BitcoinCoordinatorStore::new(
         storage,
@@ -68,16 +82,11 @@ fn test_save_and_get_tx() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
-#[test]
-fn test_multiple_transactions() -> Result<(), anyhow::Error> {
+fn test_multiple_transactions(backend: TestBackend) -> Result<(), anyhow::Error> {
     const MAX_UNCONFIRMED_SPEEDUPS: u32 = 1;
     const MAX_RETRIES: u32 = 3;
     const RETRY_INTERVAL: u64 = 2;
-    let storage_config = StorageConfig::new(
-        format!("test_output/test/{}", generate_random_string()),
-        None,
-    );
-    let storage = Rc::new(Storage::new(&storage_config)?);
+    let storage = create_storage(backend);
     let store = BitcoinCoordinatorStore::new(
         storage,
         MAX_UNCONFIRMED_SPEEDUPS,
@@ -151,19 +160,11 @@ fn test_multiple_transactions() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
-#[test]
-fn test_cancel_monitor() -> Result<(), anyhow::Error> {
+fn test_cancel_monitor(backend: TestBackend) -> Result<(), anyhow::Error> {
     const MAX_UNCONFIRMED_SPEEDUPS: u32 = 1;
     const MAX_RETRIES: u32 = 3;
     const RETRY_INTERVAL: u64 = 2;
-    let storage_config = StorageConfig::new(
-        format!(
-            "test_output/test_cancel_monitor/{}",
-            generate_random_string()
-        ),
-        None,
-    );
-    let storage = Rc::new(Storage::new(&storage_config)?);
+    let storage = create_storage(backend);
     let coordinator = BitcoinCoordinatorStore::new(
         storage,
         MAX_UNCONFIRMED_SPEEDUPS,
@@ -207,23 +208,22 @@ fn test_cancel_monitor() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
-#[test]
-fn test_increment_tx_retry_count_and_get_txs_to_dispatch() -> Result<(), anyhow::Error> {
+fn test_increment_tx_retry_count_and_get_txs_to_dispatch(
+    backend: TestBackend,
+) -> Result<(), anyhow::Error> {
     const RETRY_INTERVAL: u64 = 2;
     const MAX_RETRIES: u32 = 3;
     const MAX_UNCONFIRMED_SPEEDUPS: u32 = 1;
 
-    let storage_config = StorageConfig::new(
-        format!("test_output/test/{}/retry", generate_random_string()),
-        None,
-    );
-    let storage = Rc::new(Storage::new(&storage_config)?);
+    let storage = create_storage(backend);
+    let clock = Rc::new(MockClock::new(1_000_000));
     let store = BitcoinCoordinatorStore::new(
         storage,
         MAX_UNCONFIRMED_SPEEDUPS,
         MAX_RETRIES,
         RETRY_INTERVAL,
-    )?;
+    )?
+    .with_clock(clock.clone());
 
     let tx = Transaction {
         version: bitcoin::transaction::Version::TWO,
@@ -256,25 +256,23 @@ fn test_increment_tx_retry_count_and_get_txs_to_dispatch() -> Result<(), anyhow:
     let tx_after_retry = store.get_tx(&tx_id)?;
     assert_eq!(tx_after_retry.retry_info.unwrap().retries_count, 2);
 
-    std::thread::sleep(std::time::Duration::from_secs(RETRY_INTERVAL));
+    // After the second failure the backoff is twice the retry interval
+    clock.advance_secs(RETRY_INTERVAL);
+    let to_dispatch = store.get_txs_to_dispatch()?;
+    assert_eq!(to_dispatch.len(), 0);
 
-    // Test get_txs_to_dispatch again after incrementing the retry count, should be empty because the retry interval is not reached
+    clock.advance_secs(RETRY_INTERVAL);
     let to_dispatch = store.get_txs_to_dispatch()?;
     assert_eq!(to_dispatch.len(), 1);
     clear_output();
     Ok(())
 }
 
-#[test]
-fn test_tx_marked_as_failed_after_max_retries() -> Result<(), anyhow::Error> {
+fn test_tx_marked_as_failed_after_max_retries(backend: TestBackend) -> Result<(), anyhow::Error> {
     const MAX_UNCONFIRMED_SPEEDUPS: u32 = 1;
     const MAX_RETRIES: u32 = 3;
     const RETRY_INTERVAL: u64 = 2;
-    let storage_config = StorageConfig::new(
-        format!("test_output/test/{}", generate_random_string()),
-        None,
-    );
-    let storage = Rc::new(Storage::new(&storage_config)?);
+    let storage = create_storage(backend);
     let store = BitcoinCoordinatorStore::new(
         storage,
         MAX_UNCONFIRMED_SPEEDUPS,
@@ -306,3 +304,352 @@ fn test_tx_marked_as_failed_after_max_retries() -> Result<(), anyhow::Error> {
     clear_output();
     Ok(())
 }
+
+fn test_save_txs_batch(backend: TestBackend) -> Result<(), anyhow::Error> {
+    const MAX_UNCONFIRMED_SPEEDUPS: u32 = 1;
+    const MAX_RETRIES: u32 = 3;
+    const RETRY_INTERVAL: u64 = 2;
+    let storage = create_storage(backend);
+    let store = BitcoinCoordinatorStore::new(
+        storage,
+        MAX_UNCONFIRMED_SPEEDUPS,
+        MAX_RETRIES,
+        RETRY_INTERVAL,
+    )?;
+
+    let txs: Vec<Transaction> = (0..3)
+        .map(|i| Transaction {
+            version: bitcoin::transaction::Version::TWO,
+            lock_time: LockTime::from_time(1653195600 + i).unwrap(),
+            input: vec![],
+            output: vec![],
+        })
+        .collect();
+
+    // An empty batch is rejected
+    let result = store.save_txs(vec![], None);
+    assert!(matches!(
+        result,
+        Err(BitcoinCoordinatorStoreError::EmptyTransactionBatch)
+    ));
+
+    // A batch with a duplicated transaction is rejected and nothing is saved
+    let batch = vec![
+        (txs[0].clone(), None, "context_tx".to_string()),
+        (txs[1].clone(), None, "context_tx".to_string()),
+        (txs[0].clone(), None, "context_tx".to_string()),
+    ];
+    let result = store.save_txs(batch, None);
+    assert!(matches!(
+        result,
+        Err(BitcoinCoordinatorStoreError::DuplicateTransactionInBatch(tx_id)) if tx_id == txs[0].compute_txid()
+    ));
+    assert_eq!(store.get_txs_in_progress()?.len(), 0);
+
+    // A valid batch saves all the transactions, in order, ready to be dispatched
+    let batch = txs
+        .iter()
+        .map(|tx| (tx.clone(), None, "context_tx".to_string()))
+        .collect();
+    store.save_txs(batch, Some(100))?;
+
+    let to_dispatch = store.get_txs_to_dispatch()?;
+    assert_eq!(to_dispatch.len(), 3);
+    for (tx, coordinated_tx) in txs.iter().zip(to_dispatch.iter()) {
+        assert_eq!(coordinated_tx.tx_id, tx.compute_txid());
+        assert_eq!(coordinated_tx.state, TransactionState::ToDispatch);
+        assert_eq!(coordinated_tx.target_block_height, Some(100));
+    }
+
+    clear_output();
+    Ok(())
+}
+
+fn test_cancel_tx(backend: TestBackend) -> Result<(), anyhow::Error> {
+    const MAX_UNCONFIRMED_SPEEDUPS: u32 = 1;
+    const MAX_RETRIES: u32 = 3;
+    const RETRY_INTERVAL: u64 = 2;
+    let storage = create_storage(backend);
+    let store = BitcoinCoordinatorStore::new(
+        storage,
+        MAX_UNCONFIRMED_SPEEDUPS,
+        MAX_RETRIES,
+        RETRY_INTERVAL,
+    )?;
+
+    let txs: Vec<Transaction> = (0..3)
+        .map(|i| Transaction {
+            version: bitcoin::transaction::Version::TWO,
+            lock_time: LockTime::from_time(1653195600 + i).unwrap(),
+            input: vec![],
+            output: vec![],
+        })
+        .collect();
+    let tx_ids: Vec<Txid> = txs.iter().map(|tx| tx.compute_txid()).collect();
+
+    for tx in txs.iter() {
+        store.save_tx(tx.clone(), None, None, "context_tx".to_string())?;
+    }
+
+    // Cancel a transaction that was not dispatched yet, it is not in progress anymore
+    let cancelled_tx = store.cancel_tx(tx_ids[0])?;
+    assert_eq!(cancelled_tx.state, TransactionState::Cancelled);
+    assert_eq!(store.get_tx(&tx_ids[0])?.state, TransactionState::Cancelled);
+    assert_eq!(store.get_txs_to_dispatch()?.len(), 2);
+    assert_eq!(store.get_txs_in_progress()?.len(), 2);
+
+    // Cancel a dispatched transaction, it is kept in progress because it can still be confirmed
+    store.update_tx_to_dispatched(tx_ids[1], 100, 1)?;
+    store.cancel_tx(tx_ids[1])?;
+    assert_eq!(store.get_txs_to_dispatch()?.len(), 1);
+
+    let in_progress = store.get_txs_in_progress()?;
+    assert_eq!(in_progress.len(), 2);
+    assert!(in_progress
+        .iter()
+        .any(|tx| tx.tx_id == tx_ids[1] && tx.state == TransactionState::Cancelled));
+
+    store.update_tx_state(tx_ids[1], TransactionState::Confirmed)?;
+    store.update_tx_state(tx_ids[1], TransactionState::Finalized)?;
+    assert_eq!(store.get_txs_in_progress()?.len(), 1);
+
+    // A confirmed transaction can not be cancelled
+    store.update_tx_to_dispatched(tx_ids[2], 100, 1)?;
+    store.update_tx_state(tx_ids[2], TransactionState::Confirmed)?;
+    let result = store.cancel_tx(tx_ids[2]);
+    assert!(matches!(
+        result,
+        Err(BitcoinCoordinatorStoreError::InvalidStateTransition(..))
+    ));
+    assert_eq!(store.get_tx(&tx_ids[2])?.state, TransactionState::Confirmed);
+
+    clear_output();
+    Ok(())
+}
+
+fn test_save_tx_with_dispatch_options(backend: TestBackend) -> Result<(), anyhow::Error> {
+    const MAX_RETRIES: u32 = 3;
+    const RETRY_INTERVAL: u64 = 2;
+    let storage = create_storage(backend);
+    let store = BitcoinCoordinatorStore::new(storage, 1, MAX_RETRIES, RETRY_INTERVAL)?;
+
+    let tx = Transaction {
+        version: bitcoin::transaction::Version::TWO,
+        lock_time: LockTime::from_time(1653195600).unwrap(),
+        input: vec![],
+        output: vec![],
+    };
+
+    let tx2 = Transaction {
+        version: bitcoin::transaction::Version::TWO,
+        lock_time: LockTime::from_time(1653195601).unwrap(),
+        input: vec![],
+        output: vec![],
+    };
+
+    let options = DispatchOptions {
+        max_feerate_sat_vb: Some(40),
+        initial_bump_fee_percentage: Some(2.0),
+        exclusive_speedup: true,
+        allow_duplicate: false,
+        allow_rbf_of_parent: false,
+        parent_change_vout: None,
+        depends_on: Vec::new(),
+        funding_group: None,
+        finality_confirmations: Some(3),
+        confirmation_milestones: Some(vec![1, 3]),
+        urgency: DispatchUrgency::Low,
+        max_pause_blocks: Some(6),
+        queue_only: false,
+        expires_at_height: None,
+    };
+
+    store.save_tx_with_options(
+        tx.clone(),
+        None,
+        None,
+        "context_tx".to_string(),
+        options.clone(),
+    )?;
+    store.save_tx(tx2.clone(), None, None, "context_tx2".to_string())?;
+
+    // The options are persisted with the transaction
+    let saved_tx = store.get_tx(&tx.compute_txid())?;
+    assert_eq!(saved_tx.dispatch_options, options);
+    assert_eq!(saved_tx.state, TransactionState::ToDispatch);
+
+    // A transaction saved without options uses the global settings
+    let saved_tx2 = store.get_tx(&tx2.compute_txid())?;
+    assert_eq!(saved_tx2.dispatch_options, DispatchOptions::default());
+
+    // The options survive state updates
+    store.update_tx_to_dispatched(tx.compute_txid(), 100, 10)?;
+    let dispatched_tx = store.get_tx(&tx.compute_txid())?;
+    assert_eq!(dispatched_tx.dispatch_options, options);
+
+    clear_output();
+
+    Ok(())
+}
+
+fn test_reschedule_tx(backend: TestBackend) -> Result<(), anyhow::Error> {
+    const MAX_RETRIES: u32 = 3;
+    const RETRY_INTERVAL: u64 = 2;
+    let storage = create_storage(backend);
+    let store = BitcoinCoordinatorStore::new(storage, 1, MAX_RETRIES, RETRY_INTERVAL)?;
+
+    let tx = Transaction {
+        version: bitcoin::transaction::Version::TWO,
+        lock_time: LockTime::from_time(1653195600).unwrap(),
+        input: vec![],
+        output: vec![],
+    };
+    let tx_id = tx.compute_txid();
+
+    let tx2 = Transaction {
+        version: bitcoin::transaction::Version::TWO,
+        lock_time: LockTime::from_time(1653195601).unwrap(),
+        input: vec![],
+        output: vec![],
+    };
+
+    store.save_tx(tx.clone(), None, Some(110), "context_tx".to_string())?;
+    store.save_tx(tx2.clone(), None, None, "context_tx2".to_string())?;
+
+    // Only the transaction with a target block height is scheduled
+    let scheduled_txs = store.get_scheduled_txs()?;
+    assert_eq!(scheduled_txs.len(), 1);
+    assert_eq!(scheduled_txs[0].tx_id, tx_id);
+    assert_eq!(scheduled_txs[0].target_block_height, Some(110));
+
+    // Move the target block height further
+    let rescheduled_tx = store.reschedule_tx(tx_id, Some(120))?;
+    assert_eq!(rescheduled_tx.target_block_height, Some(120));
+    assert_eq!(store.get_tx(&tx_id)?.target_block_height, Some(120));
+    assert_eq!(store.get_scheduled_txs()?[0].target_block_height, Some(120));
+
+    // Without a target the transaction is dispatched on the next tick and it is no longer scheduled
+    store.reschedule_tx(tx_id, None)?;
+    assert_eq!(store.get_tx(&tx_id)?.target_block_height, None);
+    assert_eq!(store.get_scheduled_txs()?.len(), 0);
+    assert_eq!(store.get_txs_to_dispatch()?.len(), 2);
+
+    // A broadcast transaction can not be rescheduled
+    store.update_tx_to_dispatched(tx_id, 100, 10)?;
+    let result = store.reschedule_tx(tx_id, Some(130));
+    assert!(matches!(
+        result,
+        Err(BitcoinCoordinatorStoreError::InvalidTransactionState)
+    ));
+
+    clear_output();
+
+    Ok(())
+}
+
+fn test_transaction_history(backend: TestBackend) -> Result<(), anyhow::Error> {
+    const MAX_RETRIES: u32 = 3;
+    const RETRY_INTERVAL: u64 = 2;
+    let storage = create_storage(backend);
+    let store = BitcoinCoordinatorStore::new(storage, 1, MAX_RETRIES, RETRY_INTERVAL)?;
+
+    let public_key =
+        PublicKey::from_str("032e58afe51f9ed8ad3cc7897f634d881fdbe49a81564629ded8156bebd2ffd1af")
+            .unwrap();
+
+    let tx = Transaction {
+        version: bitcoin::transaction::Version::TWO,
+        lock_time: LockTime::from_time(1653195600).unwrap(),
+        input: vec![],
+        output: vec![],
+    };
+    let tx_id = tx.compute_txid();
+    let speedup_data = SpeedupData::new(Utxo::new(tx_id, 1, 1000, &public_key));
+
+    // Save -> dispatch -> CPFP -> confirm -> finalize
+    store.save_tx(
+        tx.clone(),
+        Some(speedup_data.clone()),
+        None,
+        "context_tx".to_string(),
+    )?;
+    store.update_tx_to_dispatched(tx_id, 100, 10)?;
+
+    let funding_txid =
+        Txid::from_str("f9b7ad71b2f0bbce7165b5ab4a3c1e17e9189f2891650e3b7d644bb7e88f200b").unwrap();
+    let cpfp_txid =
+        Txid::from_str("a9b7ad71b2f0bbce7165b5ab4a3c1e17e9189f2891650e3b7d644bb7e88f200c").unwrap();
+
+    store.save_speedup(CoordinatedSpeedUpTransaction::new(
+        cpfp_txid,
+        Utxo::new(funding_txid, 0, 100_000, &public_key),
+        Utxo::new(cpfp_txid, 0, 98_500, &public_key),
+        false,
+        101,
+        SpeedupState::Dispatched,
+        1.0,
+        vec![SpeedupParent::new(
+            speedup_data,
+            &tx,
+            "context_tx".to_string(),
+        )],
+        10,
+        150,
+    ))?;
+
+    store.update_tx_state(tx_id, TransactionState::Confirmed)?;
+    store.update_tx_state(tx_id, TransactionState::Finalized)?;
+
+    let history = store.get_tx_history(&tx_id)?;
+    assert_eq!(history.tx_id, tx_id);
+    assert_eq!(history.state, TransactionState::Finalized);
+    assert_eq!(history.broadcast_block_height, Some(100));
+
+    let events = history
+        .events
+        .iter()
+        .map(|entry| entry.event.clone())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        events,
+        vec![
+            TransactionEvent::Saved {
+                target_block_height: None
+            },
+            TransactionEvent::Dispatched {
+                block_height: 100,
+                fee_rate: 10
+            },
+            TransactionEvent::SpedUp {
+                speedup_txid: cpfp_txid,
+                is_rbf: false,
+                fee: 1_500,
+                block_height: 101
+            },
+            TransactionEvent::StateChanged {
+                from: TransactionState::Dispatched,
+                to: TransactionState::Confirmed
+            },
+            TransactionEvent::StateChanged {
+                from: TransactionState::Confirmed,
+                to: TransactionState::Finalized
+            },
+        ]
+    );
+
+    // Events are recorded in order
+    assert!(history
+        .events
+        .windows(2)
+        .all(|entries| entries[0].timestamp <= entries[1].timestamp));
+
+    // The history can be logged as JSON
+    let json = serde_json::to_string(&history)?;
+    let parsed: TransactionHistory = serde_json::from_str(&json)?;
+    assert_eq!(parsed, history);
+
+    clear_output();
+
+    Ok(())
+}
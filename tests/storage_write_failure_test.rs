@@ -0,0 +1,145 @@
+use bitcoin::{Transaction, Txid};
+use bitcoin_coordinator::{
+    coordinator::BitcoinCoordinatorApi,
+    errors::{BitcoinCoordinatorError, BitcoinCoordinatorStoreError},
+    settings::MAX_STORE_WRITE_ATTEMPTS,
+    speedup::SpeedupStore,
+    storage::BitcoinCoordinatorStoreApi,
+    testing::CoordinatorTestHarness,
+    types::TransactionState,
+};
+use key_manager::key_type::BitcoinKeyType;
+use utils::{clear_output, get_mocks, tx_with_anchor};
+mod utils;
+
+// First record written when a speedup is saved.
+const SPEEDUP_SAVE_KEY: &str = "speedup/funding/spent";
+
+// The transactions of the mempool other than the given one, the CPFPs paying for it.
+fn cpfps(harness: &CoordinatorTestHarness, tx: &Transaction) -> Vec<Txid> {
+    harness
+        .chain()
+        .mempool()
+        .iter()
+        .map(|mempool_tx| mempool_tx.compute_txid())
+        .filter(|txid| *txid != tx.compute_txid())
+        .collect()
+}
+
+// The CPFP is broadcast but saving it fails once, it is saved on the next tick instead of being lost.
+#[test]
+fn test_speedup_saved_after_failed_write() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+
+    let funding = harness.fund(&funding_key, 1_000_000)?;
+    harness.coordinator().add_funding(funding)?;
+
+    let (tx, speedup_data) = tx_with_anchor(&anchor_key, 0, 1);
+    harness.dispatch(tx.clone(), Some(speedup_data), "My tx")?;
+
+    harness.fail_next_store_write(SPEEDUP_SAVE_KEY);
+    harness.tick()?;
+
+    let cpfp_txids = cpfps(&harness, &tx);
+    assert_eq!(cpfp_txids.len(), 1);
+    assert!(matches!(
+        store.get_speedup(&cpfp_txids[0]),
+        Err(BitcoinCoordinatorStoreError::SpeedupNotFound)
+    ));
+    assert_eq!(store.get_speedup_intents()?.len(), 1);
+
+    harness.tick()?;
+
+    let speedup = store.get_speedup(&cpfp_txids[0])?;
+    assert_eq!(speedup.speedup_tx_data[0].tx_id, tx.compute_txid());
+    assert!(store.get_speedup_intents()?.is_empty());
+
+    // No second CPFP was sent for the transaction
+    assert_eq!(cpfps(&harness, &tx), cpfp_txids);
+    assert!(store.verify_speedup_chain()?.is_empty());
+
+    clear_output();
+    Ok(())
+}
+
+// The transaction is broadcast but marking it dispatched fails once. Its CPFP waits until it is saved.
+#[test]
+fn test_dispatched_tx_saved_after_failed_write() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+
+    let funding = harness.fund(&funding_key, 1_000_000)?;
+    harness.coordinator().add_funding(funding)?;
+
+    let (tx, speedup_data) = tx_with_anchor(&anchor_key, 0, 1);
+    harness.dispatch(tx.clone(), Some(speedup_data), "My tx")?;
+
+    harness.fail_next_store_write(&format!("tx/{}", tx.compute_txid()));
+    harness.tick()?;
+
+    assert!(harness.chain().in_mempool(&tx.compute_txid()));
+    assert_eq!(
+        store.get_tx(&tx.compute_txid())?.state,
+        TransactionState::ToDispatch
+    );
+    assert!(cpfps(&harness, &tx).is_empty());
+
+    harness.tick()?;
+
+    assert_eq!(
+        store.get_tx(&tx.compute_txid())?.state,
+        TransactionState::Dispatched
+    );
+    assert_eq!(cpfps(&harness, &tx).len(), 1);
+
+    clear_output();
+    Ok(())
+}
+
+// A write failing on every attempt fails the tick. The speedup is recovered from its intent once the store works again.
+#[test]
+fn test_speedup_recovered_from_intent() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+
+    let funding = harness.fund(&funding_key, 1_000_000)?;
+    harness.coordinator().add_funding(funding)?;
+
+    let (tx, speedup_data) = tx_with_anchor(&anchor_key, 0, 1);
+    harness.dispatch(tx.clone(), Some(speedup_data), "My tx")?;
+
+    for _ in 0..MAX_STORE_WRITE_ATTEMPTS {
+        harness.fail_next_store_write(SPEEDUP_SAVE_KEY);
+    }
+
+    for _ in 1..MAX_STORE_WRITE_ATTEMPTS {
+        harness.tick()?;
+    }
+
+    assert!(matches!(
+        harness.tick(),
+        Err(BitcoinCoordinatorError::StoreWriteFailed(attempts, _)) if attempts == MAX_STORE_WRITE_ATTEMPTS
+    ));
+
+    let cpfp_txids = cpfps(&harness, &tx);
+    assert_eq!(cpfp_txids.len(), 1);
+    assert!(store.get_speedup(&cpfp_txids[0]).is_err());
+    assert_eq!(store.get_speedup_intents()?.len(), 1);
+
+    // The node has the CPFP, so it is saved from the intent
+    harness.tick()?;
+
+    assert!(store.get_speedup(&cpfp_txids[0]).is_ok());
+    assert!(store.get_speedup_intents()?.is_empty());
+    assert_eq!(cpfps(&harness, &tx), cpfp_txids);
+
+    clear_output();
+    Ok(())
+}
@@ -0,0 +1,157 @@
+use bitcoin::Txid;
+use bitcoin_coordinator::{
+    coordinator::BitcoinCoordinatorApi,
+    speedup::SpeedupStore,
+    storage::{BitcoinCoordinatorStore, BitcoinCoordinatorStoreApi},
+    testing::{CoordinatorTestHarness, FakeChain},
+    types::TransactionState,
+};
+use key_manager::{key_manager::KeyManager, key_type::BitcoinKeyType};
+use std::rc::Rc;
+use utils::{clear_output, get_mocks, simple_tx};
+mod utils;
+
+const CONTEXT: &str = "My tx";
+
+// A coordinator restarted on the storage of a crashed one, on the same chain. The ownership of the
+// crashed process is released instead of waiting for it to go stale.
+fn reopen(
+    chain: FakeChain,
+    store: &BitcoinCoordinatorStore,
+    key_manager: Rc<KeyManager>,
+) -> Result<CoordinatorTestHarness, anyhow::Error> {
+    if let Some(owner) = store.get_store_owner()? {
+        store.release_ownership(owner.instance_id)?;
+    }
+
+    Ok(CoordinatorTestHarness::with_chain(
+        chain,
+        store.store.clone(),
+        key_manager,
+        None,
+    )?)
+}
+
+fn in_progress(store: &BitcoinCoordinatorStore) -> Result<Vec<Txid>, anyhow::Error> {
+    Ok(store
+        .get_txs_in_progress()?
+        .into_iter()
+        .map(|tx| tx.tx_id)
+        .collect())
+}
+
+// A storage crashing after each write of a dispatch leaves nothing of it. The coordinator reopened on the
+// same storage ticks and dispatches the transaction again.
+#[test]
+fn test_interrupted_dispatch_leaves_no_partial_writes() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let tx = simple_tx(1);
+    let txid = tx.compute_txid();
+
+    let chain = FakeChain::new(CoordinatorTestHarness::INITIAL_FEE_RATE);
+
+    let mut writes = 0;
+    loop {
+        let harness = reopen(chain.clone(), &store, key_manager.clone())?;
+        harness.fail_store_writes_after(writes);
+
+        if harness.dispatch(tx.clone(), None, CONTEXT).is_ok() {
+            break;
+        }
+
+        let harness = reopen(chain.clone(), &store, key_manager.clone())?;
+        assert!(store.get_txs_in_progress()?.is_empty());
+        assert!(store.get_txs_by_context(CONTEXT)?.is_empty());
+        assert!(harness.coordinator().get_transaction_history(txid).is_err());
+        harness.tick()?;
+
+        writes += 1;
+    }
+
+    // The dispatch writes the record, the indexes and the history, none of them is left alone
+    assert!(writes > 2);
+
+    let harness = reopen(chain, &store, key_manager)?;
+    assert_eq!(in_progress(&store)?, vec![txid]);
+    harness.tick()?;
+    assert_eq!(store.get_tx(&txid)?.state, TransactionState::Dispatched);
+
+    clear_output();
+    Ok(())
+}
+
+// An interrupted state update leaves the transaction in its previous state and index.
+#[test]
+fn test_interrupted_state_update_is_rolled_back() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+    let tx = simple_tx(1);
+    let txid = tx.compute_txid();
+    harness.dispatch(tx, None, CONTEXT)?;
+
+    let history = harness.coordinator().get_transaction_history(txid)?;
+
+    // The record is written, the index and the history are not
+    harness.fail_store_writes_after(1);
+    assert!(harness.coordinator().cancel_dispatch(txid).is_err());
+    harness.fail_store_writes_after(u32::MAX);
+
+    assert_eq!(store.get_tx(&txid)?.state, TransactionState::ToDispatch);
+    assert_eq!(store.get_txs_to_dispatch()?.len(), 1);
+    assert_eq!(
+        harness
+            .coordinator()
+            .get_transaction_history(txid)?
+            .events
+            .len(),
+        history.events.len()
+    );
+
+    // Once the storage works again the cancel goes through
+    harness.coordinator().cancel_dispatch(txid)?;
+    assert!(store.get_txs_to_dispatch()?.is_empty());
+
+    clear_output();
+    Ok(())
+}
+
+// List entries left without their record by an older version are dropped when the store is opened.
+#[test]
+fn test_dangling_entries_repaired_on_open() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 0)?;
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager.clone(), None)?;
+
+    let (lost, kept) = (simple_tx(1), simple_tx(2));
+    harness.dispatch(lost.clone(), None, CONTEXT)?;
+    harness.dispatch(kept.clone(), None, CONTEXT)?;
+    harness.tick()?;
+
+    let funding = harness.fund(&funding_key, 1_000_000)?;
+    harness.coordinator().add_funding(funding.clone())?;
+
+    // The records are gone, the lists still point at them
+    store.store.remove(
+        format!("bitcoin_coordinator/tx/{}", lost.compute_txid()),
+        None,
+    )?;
+    store.store.remove(
+        format!("bitcoin_coordinator/speedup/{}", funding.txid),
+        None,
+    )?;
+    assert!(store.get_txs_in_progress().is_err());
+    assert!(store.get_all_pending_speedups().is_err());
+
+    let harness = reopen(harness.chain().clone(), &store, key_manager)?;
+
+    assert_eq!(in_progress(&store)?, vec![kept.compute_txid()]);
+    assert_eq!(store.get_txs_by_context(CONTEXT)?.len(), 1);
+    assert!(store.get_all_pending_speedups()?.is_empty());
+    harness.tick()?;
+
+    // Nothing left to repair
+    assert_eq!(store.repair_dangling_entries()?, 0);
+
+    clear_output();
+    Ok(())
+}
@@ -0,0 +1,193 @@
+use bitcoin::{
+    absolute::LockTime, consensus::encode::serialize_hex, hashes::Hash, transaction::Version,
+    Amount, BlockHash, OutPoint, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Txid, Witness,
+};
+use bitcoin_coordinator::{
+    errors::BitcoinCoordinatorStoreError,
+    storage::{BitcoinCoordinatorStore, BitcoinCoordinatorStoreApi},
+    store_backend::StoreBackend,
+    types::{CoordinatedTransaction, CoordinatorNews, JournalEvent, TransactionState},
+};
+use key_manager::key_type::BitcoinKeyType;
+use protocol_builder::types::{output::SpeedupData, Utxo};
+use std::rc::Rc;
+use utils::{clear_output, get_mocks};
+mod utils;
+
+const KEY: [u8; 32] = [7; 32];
+const WRONG_KEY: [u8; 32] = [8; 32];
+
+fn tx_to_store() -> Transaction {
+    Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::from_consensus(42),
+        input: vec![TxIn {
+            previous_output: OutPoint::new(Txid::all_zeros(), 3),
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+            witness: Witness::from_slice(&[vec![1, 2, 3]]),
+        }],
+        output: vec![TxOut {
+            value: Amount::from_sat(10_000),
+            script_pubkey: ScriptBuf::from_bytes(vec![0x51]),
+        }],
+    }
+}
+
+fn reopen(storage: &Rc<dyn StoreBackend>, key: Option<[u8; 32]>) -> BitcoinCoordinatorStore {
+    let store = BitcoinCoordinatorStore::new(storage.clone(), 1, 3, 2).unwrap();
+
+    match key {
+        Some(key) => store.with_encryption_key(key),
+        None => store,
+    }
+}
+
+fn assert_same_tx(stored: &CoordinatedTransaction, expected: &CoordinatedTransaction) {
+    assert_eq!(stored.tx_id, expected.tx_id);
+    assert_eq!(stored.tx, expected.tx);
+    assert_eq!(stored.state, expected.state);
+    assert_eq!(stored.context, expected.context);
+    assert_eq!(stored.target_block_height, expected.target_block_height);
+    assert_eq!(
+        serde_json::to_value(&stored.speedup_data).unwrap(),
+        serde_json::to_value(&expected.speedup_data).unwrap()
+    );
+}
+
+#[test]
+fn test_encrypted_tx_round_trip() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let storage = store.store.clone();
+    let store = reopen(&storage, Some(KEY));
+
+    let public_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let tx = tx_to_store();
+    let tx_id = tx.compute_txid();
+    let speedup_data = SpeedupData::new(Utxo::new(tx_id, 0, 10_000, &public_key));
+    let context = "Secret context".to_string();
+
+    store.save_tx(tx.clone(), Some(speedup_data), Some(120), context.clone())?;
+    let expected = store.get_tx(&tx_id)?;
+    assert_eq!(expected.tx, tx);
+    drop(store);
+
+    // The record is written with the format prefix and does not leak the payload
+    let record: String = storage
+        .get(format!("bitcoin_coordinator/tx/{tx_id}"))?
+        .unwrap();
+    assert!(record.starts_with("enc1:"));
+    assert!(!record.contains(&context));
+    assert!(!record.contains(&tx_id.to_string()));
+
+    // Opened with the wrong key or without a key the record can not be read
+    let store = reopen(&storage, Some(WRONG_KEY));
+    assert!(matches!(
+        store.get_tx(&tx_id),
+        Err(BitcoinCoordinatorStoreError::DecryptionError(_))
+    ));
+    let store = reopen(&storage, None);
+    assert!(matches!(
+        store.get_tx(&tx_id),
+        Err(BitcoinCoordinatorStoreError::DecryptionError(_))
+    ));
+
+    let store = reopen(&storage, Some(KEY));
+    assert_same_tx(&store.get_tx(&tx_id)?, &expected);
+    assert_eq!(store.get_tx(&tx_id)?.state, TransactionState::ToDispatch);
+
+    clear_output();
+    Ok(())
+}
+
+// Records written before the encryption was enabled are still readable, and updated records are encrypted.
+#[test]
+fn test_plaintext_records_readable_with_encryption() -> Result<(), anyhow::Error> {
+    let (_, store, _, _) = get_mocks();
+    let storage = store.store.clone();
+    let tx = tx_to_store();
+    let tx_id = tx.compute_txid();
+    let block_hash = BlockHash::all_zeros();
+
+    store.save_tx(tx.clone(), None, None, "Plain context".to_string())?;
+    store.update_news(CoordinatorNews::TickPartialFailure(1), block_hash)?;
+    let expected = store.get_tx(&tx_id)?;
+    drop(store);
+
+    let store = reopen(&storage, Some(KEY));
+    assert_same_tx(&store.get_tx(&tx_id)?, &expected);
+    assert_eq!(
+        store.get_news()?,
+        vec![CoordinatorNews::TickPartialFailure(1)]
+    );
+
+    store.update_tx_to_dispatched(tx_id, 100, 1)?;
+    store.update_news(CoordinatorNews::TickPartialFailure(2), block_hash)?;
+
+    let record: String = storage
+        .get(format!("bitcoin_coordinator/tx/{tx_id}"))?
+        .unwrap();
+    assert!(record.starts_with("enc1:"));
+
+    let store = reopen(&storage, Some(KEY));
+    assert_eq!(store.get_tx(&tx_id)?.state, TransactionState::Dispatched);
+    assert_eq!(
+        store.get_news()?,
+        vec![CoordinatorNews::TickPartialFailure(2)]
+    );
+
+    clear_output();
+    Ok(())
+}
+
+// The journal of an encrypted store holds the raw transactions of the broadcast attempts, accepted or rejected,
+// so its entries are encrypted with the records.
+#[test]
+fn test_encrypted_journal_does_not_leak_raw_txs() -> Result<(), anyhow::Error> {
+    let (_, store, _, _) = get_mocks();
+    let storage = store.store.clone();
+    let store = reopen(&storage, Some(KEY));
+    let tx = tx_to_store();
+    let tx_id = tx.compute_txid();
+    let raw_tx = serialize_hex(&tx);
+
+    let accepted: Result<Txid, String> = Ok(tx_id);
+    let rejected: Result<Txid, String> = Err("insufficient fee".to_string());
+    store
+        .journal()
+        .append(JournalEvent::broadcast_attempt(&tx, &accepted))?;
+    store
+        .journal()
+        .append(JournalEvent::broadcast_attempt(&tx, &rejected))?;
+    drop(store);
+
+    let keys = storage.keys("bitcoin_coordinator/journal/entry/")?;
+    assert_eq!(keys.len(), 2);
+    for key in keys {
+        let record: serde_json::Value = storage.get(&key)?.unwrap();
+        assert!(!record.to_string().contains(&raw_tx));
+    }
+
+    // The entries are read back with the key
+    let store = reopen(&storage, Some(KEY));
+    let entries = store.journal().read_events(0, 10)?;
+    assert_eq!(
+        entries
+            .into_iter()
+            .map(|entry| entry.event)
+            .collect::<Vec<_>>(),
+        vec![
+            JournalEvent::broadcast_attempt(&tx, &accepted),
+            JournalEvent::broadcast_attempt(&tx, &rejected),
+        ]
+    );
+
+    let store = reopen(&storage, None);
+    assert!(matches!(
+        store.journal().read_events(0, 10),
+        Err(BitcoinCoordinatorStoreError::DecryptionError(_))
+    ));
+
+    clear_output();
+    Ok(())
+}
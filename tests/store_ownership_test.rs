@@ -0,0 +1,231 @@
+use bitcoin_coordinator::{
+    coordinator::BitcoinCoordinatorApi,
+    errors::{BitcoinCoordinatorError, BitcoinCoordinatorStoreError},
+    settings::DEFAULT_OWNER_STALE_AFTER_SECONDS,
+    storage::{BitcoinCoordinatorStore, BitcoinCoordinatorStoreApi},
+    store_backend::{InMemoryStore, StoreBackend},
+    testing::{CoordinatorTestHarness, MockClock},
+};
+use key_manager::key_type::BitcoinKeyType;
+use serde_json::Value;
+use std::{cell::RefCell, rc::Rc};
+use utils::{clear_output, get_mocks, tx_with_anchor};
+use uuid::Uuid;
+mod utils;
+
+fn is_ownership_lost(result: Result<(), BitcoinCoordinatorError>, owner: Uuid) -> bool {
+    matches!(
+        result,
+        Err(BitcoinCoordinatorError::BitcoinCoordinatorStoreError(
+            BitcoinCoordinatorStoreError::StoreOwnershipLost(Some(id))
+        )) if id == owner
+    )
+}
+
+// Backend that runs `on_owner_write` when the owner record is written in a store transaction, before it is
+// committed, to let another instance contend for the store at the worst moment.
+struct ContendedStore {
+    inner: Rc<InMemoryStore>,
+    on_owner_write: RefCell<Option<Box<dyn FnOnce()>>>,
+}
+
+impl StoreBackend for ContendedStore {
+    fn read(&self, key: &str) -> Result<Option<Value>, BitcoinCoordinatorStoreError> {
+        self.inner.read(key)
+    }
+
+    fn write(
+        &self,
+        key: &str,
+        value: Value,
+        transaction_id: Option<Uuid>,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        self.inner.write(key, value, transaction_id)?;
+
+        if transaction_id.is_some() && key.ends_with("/owner") {
+            if let Some(on_owner_write) = self.on_owner_write.borrow_mut().take() {
+                on_owner_write();
+            }
+        }
+
+        Ok(())
+    }
+
+    fn delete(
+        &self,
+        key: &str,
+        transaction_id: Option<Uuid>,
+    ) -> Result<(), BitcoinCoordinatorStoreError> {
+        self.inner.delete(key, transaction_id)
+    }
+
+    fn contains(&self, key: &str) -> Result<bool, BitcoinCoordinatorStoreError> {
+        self.inner.contains(key)
+    }
+
+    fn keys(&self, prefix: &str) -> Result<Vec<String>, BitcoinCoordinatorStoreError> {
+        self.inner.keys(prefix)
+    }
+
+    fn begin(&self) -> Uuid {
+        self.inner.begin()
+    }
+
+    fn commit(&self, transaction_id: Uuid) -> Result<(), BitcoinCoordinatorStoreError> {
+        self.inner.commit(transaction_id)
+    }
+
+    fn rollback(&self, transaction_id: Uuid) -> Result<(), BitcoinCoordinatorStoreError> {
+        self.inner.rollback(transaction_id)
+    }
+}
+
+#[test]
+fn test_store_opened_twice() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager.clone(), None)?;
+    let instance_id = harness.coordinator().instance_id();
+
+    let owner = store.get_store_owner()?.unwrap();
+    assert_eq!(owner.instance_id, instance_id);
+    assert_eq!(owner.pid, std::process::id());
+
+    // A second coordinator on the same store is refused while the first one is alive
+    let second = CoordinatorTestHarness::with_chain(
+        harness.chain().clone(),
+        store.store.clone(),
+        key_manager.clone(),
+        None,
+    );
+    assert!(matches!(
+        second,
+        Err(BitcoinCoordinatorError::BitcoinCoordinatorStoreError(
+            BitcoinCoordinatorStoreError::StoreAlreadyOwned(id, pid, 0)
+        )) if id == instance_id && pid == std::process::id()
+    ));
+
+    // The first coordinator is not affected
+    harness.tick()?;
+    assert_eq!(store.get_store_owner()?.unwrap().instance_id, instance_id);
+
+    // Once dropped, the store is handed over right away
+    let chain = harness.chain().clone();
+    drop(harness);
+    assert_eq!(store.get_store_owner()?, None);
+
+    let harness =
+        CoordinatorTestHarness::with_chain(chain, store.store.clone(), key_manager, None)?;
+    assert_eq!(
+        store.get_store_owner()?.unwrap().instance_id,
+        harness.coordinator().instance_id()
+    );
+
+    clear_output();
+    Ok(())
+}
+
+#[test]
+fn test_store_taken_over_after_stale_heartbeat() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+    let instance_id = harness.coordinator().instance_id();
+
+    let funding = harness.fund(&funding_key, 1_000_000)?;
+    harness.coordinator().add_funding(funding.clone())?;
+    harness.tick()?;
+
+    // Another process opens the store, its clock tells how old the heartbeat of the owner is
+    let heartbeat_at = store.get_store_owner()?.unwrap().heartbeat_at;
+    let clock = Rc::new(MockClock::new(heartbeat_at));
+    let other_store =
+        BitcoinCoordinatorStore::new(store.store.clone(), 10, 3, 5)?.with_clock(clock.clone());
+    let other_id = Uuid::new_v4();
+
+    clock.advance_secs(DEFAULT_OWNER_STALE_AFTER_SECONDS - 1);
+    assert!(matches!(
+        other_store.acquire_ownership(other_id, DEFAULT_OWNER_STALE_AFTER_SECONDS),
+        Err(BitcoinCoordinatorStoreError::StoreAlreadyOwned(id, _, age))
+            if id == instance_id && age == DEFAULT_OWNER_STALE_AFTER_SECONDS - 1
+    ));
+
+    clock.advance_secs(1);
+    let previous_owner =
+        other_store.acquire_ownership(other_id, DEFAULT_OWNER_STALE_AFTER_SECONDS)?;
+    assert_eq!(previous_owner.unwrap().instance_id, instance_id);
+    assert_eq!(store.get_store_owner()?.unwrap().instance_id, other_id);
+
+    // The previous owner fails fast on every call changing the store
+    let (tx, speedup_data) = tx_with_anchor(&anchor_key, 0, 1);
+    assert!(is_ownership_lost(harness.tick(), other_id));
+    assert!(is_ownership_lost(
+        harness.dispatch(tx, Some(speedup_data), "My tx"),
+        other_id
+    ));
+    assert!(is_ownership_lost(
+        harness.coordinator().add_funding(funding),
+        other_id
+    ));
+    assert!(matches!(
+        harness.coordinator().shutdown(),
+        Err(BitcoinCoordinatorError::BitcoinCoordinatorStoreError(
+            BitcoinCoordinatorStoreError::StoreOwnershipLost(_)
+        ))
+    ));
+    assert!(store.get_txs_in_progress()?.is_empty());
+    assert!(harness.chain().mempool().is_empty());
+
+    // Dropping it does not release the store of the new owner
+    drop(harness);
+    assert_eq!(store.get_store_owner()?.unwrap().instance_id, other_id);
+    other_store.refresh_ownership(other_id)?;
+
+    clear_output();
+    Ok(())
+}
+
+#[test]
+fn test_store_ownership_contended() -> Result<(), anyhow::Error> {
+    let inner = Rc::new(InMemoryStore::new());
+    let backend = Rc::new(ContendedStore {
+        inner: inner.clone(),
+        on_owner_write: RefCell::new(None),
+    });
+    let first_store = BitcoinCoordinatorStore::new(backend.clone(), 10, 3, 5)?;
+    let second_store = Rc::new(BitcoinCoordinatorStore::new(inner, 10, 3, 5)?);
+    let first_id = Uuid::new_v4();
+    let second_id = Uuid::new_v4();
+
+    // Both instances read a store without owner, the second one takes it before the first one commits
+    let second_result = Rc::new(RefCell::new(None));
+    *backend.on_owner_write.borrow_mut() = Some(Box::new({
+        let second_store = second_store.clone();
+        let second_result = second_result.clone();
+        move || {
+            *second_result.borrow_mut() =
+                Some(second_store.acquire_ownership(second_id, DEFAULT_OWNER_STALE_AFTER_SECONDS));
+        }
+    }));
+
+    let first_result = first_store.acquire_ownership(first_id, DEFAULT_OWNER_STALE_AFTER_SECONDS);
+    assert!(matches!(second_result.borrow_mut().take(), Some(Ok(None))));
+    assert!(matches!(
+        first_result,
+        Err(BitcoinCoordinatorStoreError::StoreAlreadyOwned(id, _, 0)) if id == second_id
+    ));
+
+    // Only the second instance owns the store
+    assert_eq!(
+        first_store.get_store_owner()?.unwrap().instance_id,
+        second_id
+    );
+    second_store.refresh_ownership(second_id)?;
+    assert!(matches!(
+        first_store.refresh_ownership(first_id),
+        Err(BitcoinCoordinatorStoreError::StoreOwnershipLost(Some(id))) if id == second_id
+    ));
+
+    clear_output();
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use bitcoin::{hashes::Hash, BlockHash};
+use bitcoin_coordinator::{
+    errors::BitcoinCoordinatorStoreError,
+    record::STORE_RECORD_VERSION,
+    storage::BitcoinCoordinatorStoreApi,
+    types::{AckCoordinatorNews, CoordinatorNews, DispatchOptions, TransactionState},
+};
+use serde_json::{json, Value};
+use utils::{clear_output, create_store, simple_tx};
+mod utils;
+
+// A transaction as it was written before the records were versioned: without envelope nor the fields added since.
+#[test]
+fn test_unversioned_records_are_upgraded() -> Result<(), anyhow::Error> {
+    let store = create_store();
+    let tx = simple_tx(0);
+    let tx_id = tx.compute_txid();
+    let tx_key = format!("bitcoin_coordinator/tx/{tx_id}");
+    let news_key = "bitcoin_coordinator/news/insufficient_funds";
+
+    store.store.set(
+        &tx_key,
+        json!({
+            "tx_id": tx_id,
+            "tx": tx,
+            "speedup_data": null,
+            "broadcast_block_height": 100,
+            "target_block_height": null,
+            "state": TransactionState::Dispatched,
+            "context": "My tx",
+            "retry_info": null,
+        }),
+        None,
+    )?;
+    store.store.set(
+        news_key,
+        json!([[tx_id, 1_000, 2_000, [BlockHash::all_zeros(), false]]]),
+        None,
+    )?;
+
+    let stored = store.get_tx(&tx_id)?;
+    assert_eq!(stored.state, TransactionState::Dispatched);
+    assert_eq!(stored.broadcast_block_height, Some(100));
+    assert_eq!(stored.dispatch_options, DispatchOptions::default());
+    assert_eq!(stored.bump_count, 0);
+    assert!(stored.replaced_txids.is_empty());
+
+    assert_eq!(
+        store.get_news()?,
+        vec![CoordinatorNews::InsufficientFunds(tx_id, 1_000, 2_000)]
+    );
+
+    // Both records were written again with the current version
+    for key in [tx_key.as_str(), news_key] {
+        let record: Value = store.store.get(key)?.unwrap();
+        assert_eq!(record["version"], json!(STORE_RECORD_VERSION));
+    }
+
+    let news: Value = store.store.get(news_key)?.unwrap();
+    assert_eq!(news["payload"][0]["news"]["tx_id"], json!(tx_id));
+    assert_eq!(news["payload"][0]["acknowledged"], json!(false));
+
+    store.ack_news(AckCoordinatorNews::InsufficientFunds(tx_id))?;
+    assert!(store.get_news()?.is_empty());
+
+    clear_output();
+    Ok(())
+}
+
+#[test]
+fn test_missing_fields_are_read_with_default() -> Result<(), anyhow::Error> {
+    let store = create_store();
+    let tx = simple_tx(0);
+    let tx_id = tx.compute_txid();
+    let tx_key = format!("bitcoin_coordinator/tx/{tx_id}");
+
+    store.save_tx(tx, None, None, "My tx".to_string())?;
+
+    let mut record: Value = store.store.get(&tx_key)?.unwrap();
+    let payload = record["payload"].as_object_mut().unwrap();
+    payload.remove("bump_fees");
+    payload.remove("dispatch_options");
+    store.store.set(&tx_key, record, None)?;
+
+    let stored = store.get_tx(&tx_id)?;
+    assert_eq!(stored.bump_fees, 0);
+    assert_eq!(stored.dispatch_options, DispatchOptions::default());
+    assert_eq!(stored.context, "My tx");
+
+    clear_output();
+    Ok(())
+}
+
+#[test]
+fn test_newer_record_version_is_rejected() -> Result<(), anyhow::Error> {
+    let store = create_store();
+    let tx = simple_tx(0);
+    let tx_id = tx.compute_txid();
+    let tx_key = format!("bitcoin_coordinator/tx/{tx_id}");
+
+    store.save_tx(tx, None, None, "My tx".to_string())?;
+
+    let mut record: Value = store.store.get(&tx_key)?.unwrap();
+    record["version"] = json!(99);
+    store.store.set(&tx_key, record, None)?;
+
+    assert!(matches!(
+        store.get_tx(&tx_id),
+        Err(BitcoinCoordinatorStoreError::UnsupportedRecordVersion(key, 99)) if key == tx_key
+    ));
+
+    clear_output();
+    Ok(())
+}
@@ -0,0 +1,216 @@
+use bitcoin::{hashes::Hash, BlockHash, OutPoint};
+use bitcoin_coordinator::{
+    coordinator::BitcoinCoordinatorApi,
+    errors::{BitcoinCoordinatorError, BitcoinCoordinatorStoreError},
+    snapshot::StoreSnapshot,
+    speedup::SpeedupStore,
+    storage::{BitcoinCoordinatorStore, BitcoinCoordinatorStoreApi},
+    store_backend::InMemoryStore,
+    testing::CoordinatorTestHarness,
+    types::{CoordinatorNews, TransactionState},
+};
+use serde_json::{json, Value};
+use std::{path::PathBuf, rc::Rc};
+use utils::{
+    clear_output, create_storage, create_store_on, generate_random_string, get_mock_data,
+    get_mocks, simple_tx, TestBackend,
+};
+mod utils;
+
+backend_tests!(
+    test_snapshot_round_trip,
+    test_snapshot_into_non_empty_store,
+    test_snapshot_checks_version_and_checksum,
+);
+
+fn snapshot_path() -> PathBuf {
+    std::fs::create_dir_all("test_output/test").unwrap();
+    PathBuf::from(format!(
+        "test_output/test/snapshot_{}.json",
+        generate_random_string()
+    ))
+}
+
+// A store with transactions in several states and contexts, news, a funding and a watched outpoint.
+fn populated_store(backend: TestBackend) -> Result<BitcoinCoordinatorStore, anyhow::Error> {
+    let (_, _, _, key_manager) = get_mocks();
+    let (_, _, funding, _, _, _) = get_mock_data(key_manager);
+    let store = create_store_on(backend);
+
+    for seed in 0..4 {
+        let context = format!("context_{}", seed % 2);
+        store.save_tx(simple_tx(seed), None, None, context)?;
+    }
+
+    store.update_tx_to_dispatched(simple_tx(1).compute_txid(), 100, 2)?;
+    store.update_tx_to_dispatched(simple_tx(2).compute_txid(), 100, 2)?;
+    store.update_tx_state(simple_tx(2).compute_txid(), TransactionState::Confirmed)?;
+
+    store.update_news(
+        CoordinatorNews::InsufficientFunds(simple_tx(1).compute_txid(), 1_000, 2_000),
+        BlockHash::all_zeros(),
+    )?;
+    store.add_funding(funding)?;
+    store.watch_outpoint(
+        OutPoint::new(simple_tx(3).compute_txid(), 0),
+        "watched".to_string(),
+    )?;
+
+    Ok(store)
+}
+
+// Results of the same queries on a store, to compare two stores.
+fn queries(store: &BitcoinCoordinatorStore) -> Result<Value, anyhow::Error> {
+    let mut states = Vec::new();
+
+    for state in [
+        TransactionState::ToDispatch,
+        TransactionState::Dispatched,
+        TransactionState::Confirmed,
+    ] {
+        states.push(store.get_tx_ids_by_state(&state)?);
+    }
+
+    Ok(json!({
+        "in_progress": serde_json::to_value(store.get_txs_in_progress()?)?,
+        "to_dispatch": serde_json::to_value(store.get_txs_to_dispatch()?)?,
+        "states": serde_json::to_value(states)?,
+        "context_0": serde_json::to_value(store.get_txs_by_context("context_0")?)?,
+        "context_1": serde_json::to_value(store.get_txs_by_context("context_1")?)?,
+        "history": serde_json::to_value(store.get_tx_history(&simple_tx(2).compute_txid())?)?,
+        "news": serde_json::to_value(store.get_news()?)?,
+        "funding": serde_json::to_value(store.get_funding()?)?,
+        "watched": serde_json::to_value(store.get_watched_outpoints()?)?,
+    }))
+}
+
+// A snapshot imported into a fresh store answers every query as the store it was exported from.
+fn test_snapshot_round_trip(backend: TestBackend) -> Result<(), anyhow::Error> {
+    let store = populated_store(backend)?;
+    let path = snapshot_path();
+
+    let exported = store.export_snapshot(&path)?;
+    assert!(exported > 0);
+
+    let imported_store = create_store_on(backend);
+    assert_eq!(imported_store.import_snapshot(&path, false)?, exported);
+    assert_eq!(queries(&imported_store)?, queries(&store)?);
+
+    // The state indexes rebuilt on import are kept up to date
+    imported_store.update_tx_to_dispatched(simple_tx(0).compute_txid(), 101, 2)?;
+    assert!(!imported_store
+        .get_tx_ids_by_state(&TransactionState::ToDispatch)?
+        .contains(&simple_tx(0).compute_txid()));
+    assert!(imported_store
+        .get_tx_ids_by_state(&TransactionState::Dispatched)?
+        .contains(&simple_tx(0).compute_txid()));
+
+    std::fs::remove_file(&path)?;
+    clear_output();
+    Ok(())
+}
+
+// A store with records is only replaced when forced, and then only has the records of the snapshot.
+fn test_snapshot_into_non_empty_store(backend: TestBackend) -> Result<(), anyhow::Error> {
+    let store = populated_store(backend)?;
+    let path = snapshot_path();
+    store.export_snapshot(&path)?;
+
+    let other_store = BitcoinCoordinatorStore::new(create_storage(backend), 10, 3, 2)?;
+    other_store.save_tx(simple_tx(10), None, None, "context_0".to_string())?;
+
+    assert!(matches!(
+        other_store.import_snapshot(&path, false),
+        Err(BitcoinCoordinatorStoreError::StoreNotEmpty(_))
+    ));
+    assert_eq!(other_store.get_txs_in_progress()?.len(), 1);
+
+    other_store.import_snapshot(&path, true)?;
+    assert_eq!(queries(&other_store)?, queries(&store)?);
+    assert!(other_store.get_tx(&simple_tx(10).compute_txid()).is_err());
+
+    std::fs::remove_file(&path)?;
+    clear_output();
+    Ok(())
+}
+
+// A snapshot of another version or whose records do not match its checksum is not imported.
+fn test_snapshot_checks_version_and_checksum(backend: TestBackend) -> Result<(), anyhow::Error> {
+    let store = populated_store(backend)?;
+    let path = snapshot_path();
+    store.export_snapshot(&path)?;
+
+    let snapshot: StoreSnapshot = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+    let fresh_store = create_store_on(backend);
+
+    let mut edited = snapshot.clone();
+    edited
+        .entries
+        .insert("bitcoin_coordinator/extra".to_string(), json!(1));
+    std::fs::write(&path, serde_json::to_string(&edited)?)?;
+    assert!(matches!(
+        fresh_store.import_snapshot(&path, false),
+        Err(BitcoinCoordinatorStoreError::SnapshotChecksumMismatch(..))
+    ));
+
+    let mut newer = snapshot.clone();
+    newer.version += 1;
+    std::fs::write(&path, serde_json::to_string(&newer)?)?;
+    assert!(matches!(
+        fresh_store.import_snapshot(&path, false),
+        Err(BitcoinCoordinatorStoreError::UnsupportedSnapshotVersion(..))
+    ));
+
+    // Nothing was written by the rejected imports
+    assert!(fresh_store.get_txs_in_progress()?.is_empty());
+    assert!(fresh_store.get_news()?.is_empty());
+
+    std::fs::remove_file(&path)?;
+    clear_output();
+    Ok(())
+}
+
+// A coordinator moved to another host with a snapshot monitors the imported transactions on its first tick,
+// and follows them until they are confirmed.
+#[test]
+fn test_imported_snapshot_is_monitored() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager.clone(), None)?;
+    let tx = simple_tx(20);
+
+    harness.dispatch(tx.clone(), None, "moved")?;
+    harness.tick()?;
+    assert!(harness.chain().in_mempool(&tx.compute_txid()));
+
+    let path = snapshot_path();
+    harness.coordinator().export_snapshot(&path)?;
+
+    let moved = CoordinatorTestHarness::with_chain(
+        harness.chain().clone(),
+        Rc::new(InMemoryStore::new()),
+        key_manager,
+        None,
+    )?;
+    moved.coordinator().import_snapshot(&path, false)?;
+
+    assert!(matches!(
+        moved.coordinator().import_snapshot(&path, false),
+        Err(BitcoinCoordinatorError::BitcoinCoordinatorStoreError(
+            BitcoinCoordinatorStoreError::StoreNotEmpty(_)
+        ))
+    ));
+
+    moved.mine_blocks(1);
+    moved.tick()?;
+    assert_eq!(
+        moved
+            .coordinator()
+            .get_transaction_history(tx.compute_txid())?
+            .state,
+        TransactionState::Confirmed
+    );
+
+    std::fs::remove_file(&path)?;
+    clear_output();
+    Ok(())
+}
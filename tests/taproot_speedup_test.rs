@@ -0,0 +1,259 @@
+use bitcoin::{
+    key::Secp256k1,
+    secp256k1::{schnorr, Message, XOnlyPublicKey},
+    sighash::{EcdsaSighashType, Prevouts, SighashCache, TapSighashType},
+    OutPoint, PublicKey, ScriptBuf, Transaction, TxOut,
+};
+use bitcoin_coordinator::{
+    coordinator::BitcoinCoordinatorApi,
+    cpfp::{build_cpfp_tx, SpeedupOutputKind},
+    errors::BitcoinCoordinatorError,
+    speedup::SpeedupStore,
+    testing::CoordinatorTestHarness,
+    validation::validate_tx_to_dispatch,
+};
+use key_manager::key_type::BitcoinKeyType;
+use protocol_builder::types::{output::SpeedupData, Utxo};
+use utils::{clear_output, get_mocks, tx_with_output};
+mod utils;
+
+const ANCHOR_AMOUNT: u64 = 540;
+const FUNDING_AMOUNT: u64 = 100_000;
+const FEE: u64 = 1_000;
+
+fn anchor_utxo(tx: &Transaction, public_key: &PublicKey) -> Utxo {
+    Utxo::new(tx.compute_txid(), 0, ANCHOR_AMOUNT, public_key)
+}
+
+// Checks every input signature of `tx` against the output it spends.
+fn verify_witnesses(tx: &Transaction, prevouts: &[TxOut]) -> Result<(), anyhow::Error> {
+    let secp = Secp256k1::verification_only();
+    let mut sighash_cache = SighashCache::new(tx);
+
+    for (index, (input, prevout)) in tx.input.iter().zip(prevouts).enumerate() {
+        if prevout.script_pubkey.is_p2tr() {
+            assert_eq!(input.witness.len(), 1);
+            // SIGHASH_DEFAULT signatures have no sighash flag.
+            assert_eq!(input.witness[0].len(), 64);
+
+            let sighash = sighash_cache.taproot_key_spend_signature_hash(
+                index,
+                &Prevouts::All(prevouts),
+                TapSighashType::Default,
+            )?;
+            let signature = schnorr::Signature::from_slice(&input.witness[0])?;
+            let output_key = XOnlyPublicKey::from_slice(&prevout.script_pubkey.as_bytes()[2..])?;
+
+            secp.verify_schnorr(&signature, &Message::from(sighash), &output_key)?;
+        } else {
+            assert!(prevout.script_pubkey.is_p2wpkh());
+            assert_eq!(input.witness.len(), 2);
+
+            let public_key = PublicKey::from_slice(&input.witness[1])?;
+            assert_eq!(
+                ScriptBuf::new_p2wpkh(&public_key.wpubkey_hash()?),
+                prevout.script_pubkey
+            );
+
+            let sighash = sighash_cache.p2wpkh_signature_hash(
+                index,
+                &prevout.script_pubkey,
+                prevout.value,
+                EcdsaSighashType::All,
+            )?;
+            let signature = bitcoin::ecdsa::Signature::from_slice(&input.witness[0])?;
+            assert_eq!(signature.sighash_type, EcdsaSighashType::All);
+
+            secp.verify_ecdsa(
+                &Message::from(sighash),
+                &signature.signature,
+                &public_key.inner,
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_speedup_output_kind() -> Result<(), anyhow::Error> {
+    let (_, _, _, key_manager) = get_mocks();
+    let public_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 0)?;
+    let other_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+
+    for kind in [SpeedupOutputKind::P2wpkh, SpeedupOutputKind::P2trKeyPath] {
+        let script_pubkey = kind.script_pubkey(&public_key)?;
+
+        assert_eq!(
+            SpeedupOutputKind::from_script_pubkey(&script_pubkey, &public_key),
+            Some(kind)
+        );
+        assert_eq!(
+            SpeedupOutputKind::from_script_pubkey(&script_pubkey, &other_key),
+            None
+        );
+
+        let tx = tx_with_output(kind.script_pubkey(&public_key)?, ANCHOR_AMOUNT, 0);
+        let speedup_data = SpeedupData::new(anchor_utxo(&tx, &public_key));
+        assert_eq!(SpeedupOutputKind::of_speedup_utxo(&tx, &speedup_data), kind);
+
+        // Taproot key path anchors are accepted by dispatch validation as well
+        validate_tx_to_dispatch(&tx, Some(&speedup_data), 400_000, |_| Ok(None))?;
+    }
+
+    clear_output();
+    Ok(())
+}
+
+// Builds a CPFP over one P2TR anchor and one P2WPKH anchor, and checks every witness against its prevout.
+#[test]
+fn test_cpfp_with_taproot_and_segwit_anchors() -> Result<(), anyhow::Error> {
+    let (_, _, _, key_manager) = get_mocks();
+    let taproot_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let segwit_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+
+    let taproot_tx = tx_with_output(
+        SpeedupOutputKind::P2trKeyPath.script_pubkey(&taproot_key)?,
+        ANCHOR_AMOUNT,
+        1,
+    );
+    let segwit_tx = tx_with_output(
+        SpeedupOutputKind::P2wpkh.script_pubkey(&segwit_key)?,
+        ANCHOR_AMOUNT,
+        2,
+    );
+    let funding_tx = tx_with_output(
+        SpeedupOutputKind::P2wpkh.script_pubkey(&funding_key)?,
+        FUNDING_AMOUNT,
+        3,
+    );
+
+    let anchors = vec![
+        (
+            anchor_utxo(&taproot_tx, &taproot_key),
+            SpeedupOutputKind::P2trKeyPath,
+        ),
+        (
+            anchor_utxo(&segwit_tx, &segwit_key),
+            SpeedupOutputKind::P2wpkh,
+        ),
+    ];
+    let funding = Utxo::new(funding_tx.compute_txid(), 0, FUNDING_AMOUNT, &funding_key);
+
+    let cpfp = build_cpfp_tx(&anchors, &funding, &funding.pub_key, FEE, &key_manager)?;
+
+    // The anchors are spent first and the funding last
+    let outpoints: Vec<OutPoint> = cpfp.input.iter().map(|i| i.previous_output).collect();
+    assert_eq!(
+        outpoints,
+        vec![
+            OutPoint::new(taproot_tx.compute_txid(), 0),
+            OutPoint::new(segwit_tx.compute_txid(), 0),
+            OutPoint::new(funding_tx.compute_txid(), 0),
+        ]
+    );
+
+    // A single change output pays the funding key
+    assert_eq!(cpfp.output.len(), 1);
+    assert_eq!(
+        cpfp.output[0].value.to_sat(),
+        2 * ANCHOR_AMOUNT + FUNDING_AMOUNT - FEE
+    );
+    assert_eq!(
+        cpfp.output[0].script_pubkey,
+        SpeedupOutputKind::P2wpkh.script_pubkey(&funding_key)?
+    );
+
+    let prevouts = vec![
+        taproot_tx.output[0].clone(),
+        segwit_tx.output[0].clone(),
+        funding_tx.output[0].clone(),
+    ];
+    verify_witnesses(&cpfp, &prevouts)?;
+
+    // The taproot witness is smaller, so the CPFP is smaller than one spending only segwit v0 outputs
+    let other_segwit_tx = tx_with_output(
+        SpeedupOutputKind::P2wpkh.script_pubkey(&segwit_key)?,
+        ANCHOR_AMOUNT,
+        4,
+    );
+    let segwit_anchors = vec![
+        (
+            anchor_utxo(&other_segwit_tx, &segwit_key),
+            SpeedupOutputKind::P2wpkh,
+        ),
+        anchors[1].clone(),
+    ];
+    let segwit_cpfp = build_cpfp_tx(
+        &segwit_anchors,
+        &funding,
+        &funding.pub_key,
+        FEE,
+        &key_manager,
+    )?;
+    assert!(cpfp.vsize() < segwit_cpfp.vsize());
+
+    clear_output();
+    Ok(())
+}
+
+// An uncompressed key can not be paid with P2WPKH: the script, the CPFP paying its change to it and the funding
+// spent with it are errors instead of a panic.
+#[test]
+fn test_uncompressed_key_is_rejected() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+    let uncompressed = PublicKey::new_uncompressed(funding_key.inner);
+
+    assert!(matches!(
+        SpeedupOutputKind::P2wpkh.script_pubkey(&uncompressed),
+        Err(BitcoinCoordinatorError::UncompressedPublicKey(key)) if key == uncompressed
+    ));
+    assert!(SpeedupOutputKind::P2trKeyPath
+        .script_pubkey(&uncompressed)
+        .is_ok());
+
+    let anchor_tx = tx_with_output(
+        SpeedupOutputKind::P2trKeyPath.script_pubkey(&anchor_key)?,
+        ANCHOR_AMOUNT,
+        1,
+    );
+    let funding_tx = tx_with_output(
+        SpeedupOutputKind::P2wpkh.script_pubkey(&funding_key)?,
+        FUNDING_AMOUNT,
+        2,
+    );
+    let anchors = vec![(
+        anchor_utxo(&anchor_tx, &anchor_key),
+        SpeedupOutputKind::P2trKeyPath,
+    )];
+    let funding = Utxo::new(funding_tx.compute_txid(), 0, FUNDING_AMOUNT, &funding_key);
+
+    assert!(matches!(
+        build_cpfp_tx(&anchors, &funding, &uncompressed, FEE, &key_manager),
+        Err(BitcoinCoordinatorError::UncompressedPublicKey(_))
+    ));
+
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+    let uncompressed_funding =
+        Utxo::new(funding_tx.compute_txid(), 0, FUNDING_AMOUNT, &uncompressed);
+
+    assert!(matches!(
+        harness
+            .coordinator()
+            .add_funding(uncompressed_funding.clone()),
+        Err(BitcoinCoordinatorError::UncompressedPublicKey(_))
+    ));
+    assert!(matches!(
+        harness
+            .coordinator()
+            .add_funding_group("group", uncompressed_funding),
+        Err(BitcoinCoordinatorError::UncompressedPublicKey(_))
+    ));
+    assert!(store.get_funding()?.is_none());
+
+    clear_output();
+    Ok(())
+}
@@ -0,0 +1,254 @@
+use bitcoin::{OutPoint, ScriptBuf, Transaction, Txid};
+use bitcoin_coordinator::{
+    config::CoordinatorSettingsConfig,
+    coordinator::BitcoinCoordinatorApi,
+    errors::BitcoinCoordinatorError,
+    observer::CoordinatorObserver,
+    testing::CoordinatorTestHarness,
+    types::{CoordinatorNews, DispatchOptions, TransactionState},
+};
+use key_manager::key_type::BitcoinKeyType;
+use std::{cell::RefCell, rc::Rc};
+use utils::{clear_output, get_mocks, tx_with_anchor, tx_with_output};
+mod utils;
+
+const ANCHOR_AMOUNT: u64 = 540;
+
+// Records the calls made when the budget of a tick runs out.
+#[derive(Default)]
+struct BudgetObserver {
+    calls: RefCell<Vec<(u32, u32, usize)>>,
+}
+
+impl CoordinatorObserver for BudgetObserver {
+    fn on_tick_budget_exhausted(&self, broadcasts: u32, speedups: u32, txs_remaining: usize) {
+        self.calls
+            .borrow_mut()
+            .push((broadcasts, speedups, txs_remaining));
+    }
+}
+
+fn retry_counts(harness: &CoordinatorTestHarness) -> Result<Vec<u32>, BitcoinCoordinatorError> {
+    Ok(harness
+        .coordinator()
+        .get_pending_overview()?
+        .to_dispatch
+        .iter()
+        .map(|entry| entry.retry_count)
+        .collect())
+}
+
+// 30 transactions are dispatched 10 per tick, in the order they were dispatched.
+#[test]
+fn test_max_broadcasts_per_tick() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let observer = Rc::new(BudgetObserver::default());
+    let harness = CoordinatorTestHarness::new(
+        store.store.clone(),
+        key_manager,
+        Some(CoordinatorSettingsConfig {
+            max_broadcasts_per_tick: Some(10),
+            ..Default::default()
+        }),
+    )?
+    .with_observer(observer.clone());
+
+    let txs: Vec<Transaction> = (0..30)
+        .map(|seed| tx_with_output(ScriptBuf::new_op_return([seed as u8]), 1_000, seed))
+        .collect();
+    let txids: Vec<Txid> = txs.iter().map(|tx| tx.compute_txid()).collect();
+
+    for tx in txs.iter() {
+        harness.dispatch(tx.clone(), None, "budget")?;
+    }
+
+    for tick in 1..=3 {
+        harness.tick()?;
+
+        let mempool: Vec<Txid> = harness
+            .chain()
+            .mempool()
+            .iter()
+            .map(|tx| tx.compute_txid())
+            .collect();
+        assert_eq!(mempool, txids[..tick * 10]);
+
+        let overview = harness.coordinator().get_pending_overview()?;
+        assert_eq!(overview.last_tick_budget.broadcasts, 10);
+        assert_eq!(overview.last_tick_budget.deferred_txs, txids[tick * 10..]);
+
+        // Waiting for the budget is not a failed attempt
+        assert!(retry_counts(&harness)?.iter().all(|count| *count == 0));
+    }
+
+    assert_eq!(*observer.calls.borrow(), vec![(10, 0, 20), (10, 0, 10)]);
+
+    // Nothing left, the budget is not reported
+    harness.tick()?;
+    let overview = harness.coordinator().get_pending_overview()?;
+    assert_eq!(overview.last_tick_budget.broadcasts, 0);
+    assert!(overview.last_tick_budget.deferred_txs.is_empty());
+    assert_eq!(observer.calls.borrow().len(), 2);
+
+    clear_output();
+    Ok(())
+}
+
+// Each transaction needs its own CPFP, only two batches are sent on each tick.
+#[test]
+fn test_max_speedups_per_tick() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+    let harness = CoordinatorTestHarness::new(
+        store.store.clone(),
+        key_manager,
+        Some(CoordinatorSettingsConfig {
+            max_speedups_per_tick: Some(2),
+            ..Default::default()
+        }),
+    )?;
+
+    let funding = harness.fund(&funding_key, 10_000_000)?;
+    harness.coordinator().add_funding(funding)?;
+
+    let mut txids = Vec::new();
+
+    for seed in 0..5 {
+        let (tx, speedup_data) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, seed);
+        txids.push(tx.compute_txid());
+        harness.coordinator().dispatch_with_options(
+            tx,
+            Some(speedup_data),
+            "exclusive".to_string(),
+            None,
+            None,
+            DispatchOptions {
+                exclusive_speedup: true,
+                ..Default::default()
+            },
+        )?;
+    }
+
+    let in_mempool = |harness: &CoordinatorTestHarness| -> Vec<Txid> {
+        txids
+            .iter()
+            .filter(|txid| harness.chain().in_mempool(txid))
+            .cloned()
+            .collect()
+    };
+
+    harness.tick()?;
+    assert_eq!(in_mempool(&harness), txids[..2]);
+    assert_eq!(harness.chain().mempool().len(), 4);
+
+    let overview = harness.coordinator().get_pending_overview()?;
+    assert_eq!(overview.last_tick_budget.speedups, 2);
+    assert_eq!(overview.last_tick_budget.deferred_txs, txids[2..]);
+    assert!(retry_counts(&harness)?.iter().all(|count| *count == 0));
+
+    harness.tick()?;
+    assert_eq!(in_mempool(&harness), txids[..4]);
+    assert_eq!(harness.chain().mempool().len(), 8);
+
+    harness.tick()?;
+    assert_eq!(in_mempool(&harness), txids);
+    assert_eq!(harness.chain().mempool().len(), 10);
+    assert!(harness
+        .coordinator()
+        .get_pending_overview()?
+        .last_tick_budget
+        .deferred_txs
+        .is_empty());
+
+    clear_output();
+    Ok(())
+}
+
+#[test]
+fn test_tick_budget_validation() {
+    for settings in [
+        CoordinatorSettingsConfig {
+            max_broadcasts_per_tick: Some(0),
+            ..Default::default()
+        },
+        CoordinatorSettingsConfig {
+            max_speedups_per_tick: Some(0),
+            ..Default::default()
+        },
+    ] {
+        assert!(matches!(
+            settings.validate(),
+            Err(BitcoinCoordinatorError::InvalidConfiguration(_))
+        ));
+    }
+}
+
+// The input of a dispatched transaction is double spent 6 blocks after the broadcast. Two blocks are read per tick,
+// each block once, so the conflict is found on the fourth tick after reading the 7 blocks since the broadcast.
+#[test]
+fn test_max_conflict_scan_blocks_per_tick() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let harness = CoordinatorTestHarness::new(
+        store.store.clone(),
+        key_manager,
+        Some(CoordinatorSettingsConfig {
+            max_conflict_scan_blocks_per_tick: Some(2),
+            rebroadcast_after_blocks: Some(100),
+            ..Default::default()
+        }),
+    )?;
+
+    let (funding_tx, vout) = harness.chain().fund(ScriptBuf::new(), 10_000);
+    let outpoint = OutPoint::new(funding_tx.compute_txid(), vout);
+
+    let mut tx = tx_with_output(ScriptBuf::new_op_return([1]), 9_000, 1);
+    tx.input[0].previous_output = outpoint;
+    let mut double_spend = tx_with_output(ScriptBuf::new_op_return([2]), 5_000, 2);
+    double_spend.input[0].previous_output = outpoint;
+
+    harness.dispatch(tx.clone(), None, "conflict")?;
+    harness.tick()?;
+    assert!(harness.chain().in_mempool(&tx.compute_txid()));
+
+    // The double spend pays more, it replaces the transaction and is mined in the sixth block
+    harness.chain().send_transaction(&double_spend).unwrap();
+    harness.mine_empty_blocks(5);
+    harness.mine_blocks(1);
+
+    for _ in 0..3 {
+        harness.tick()?;
+
+        let overview = harness.coordinator().get_pending_overview()?;
+        assert_eq!(overview.last_tick_budget.conflict_scan_blocks, 2);
+        assert_eq!(
+            harness
+                .coordinator()
+                .get_transaction_history(tx.compute_txid())?
+                .state,
+            TransactionState::Dispatched
+        );
+    }
+
+    harness.tick()?;
+
+    let overview = harness.coordinator().get_pending_overview()?;
+    assert_eq!(overview.last_tick_budget.conflict_scan_blocks, 1);
+    assert_eq!(
+        harness
+            .coordinator()
+            .get_transaction_history(tx.compute_txid())?
+            .state,
+        TransactionState::Failed
+    );
+
+    let news = harness.coordinator().get_news()?;
+    assert!(news.coordinator_news.iter().any(|news| matches!(
+        news,
+        CoordinatorNews::TransactionConflicted(txid, conflicting_txid, _)
+            if *txid == tx.compute_txid() && *conflicting_txid == double_spend.compute_txid()
+    )));
+
+    clear_output();
+    Ok(())
+}
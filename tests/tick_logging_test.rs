@@ -0,0 +1,152 @@
+use bitcoin::Transaction;
+use bitcoin_coordinator::{
+    coordinator::BitcoinCoordinatorApi, testing::CoordinatorTestHarness, types::CoordinatorNews,
+};
+use key_manager::key_type::BitcoinKeyType;
+use std::{
+    io::{self, Write},
+    sync::{Arc, Mutex},
+};
+use tracing::Level;
+use utils::{clear_output, get_mocks, tx_with_anchor};
+mod utils;
+
+const ANCHOR_AMOUNT: u64 = 540;
+
+// Collects the log lines written by the tracing subscriber of a test.
+#[derive(Clone, Default)]
+struct LogBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for LogBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl LogBuffer {
+    fn lines(&self) -> Vec<String> {
+        String::from_utf8(self.0.lock().unwrap().clone())
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+// Runs `f` with the logs at the default level (info) written to the returned buffer.
+fn capture_logs(f: impl FnOnce() -> Result<(), anyhow::Error>) -> Result<LogBuffer, anyhow::Error> {
+    let buffer = LogBuffer::default();
+    let writer = buffer.clone();
+
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(Level::INFO)
+        .with_ansi(false)
+        .with_writer(move || writer.clone())
+        .finish();
+
+    tracing::subscriber::with_default(subscriber, f)?;
+
+    Ok(buffer)
+}
+
+// A tick dispatching two transactions paid by a CPFP logs a single summary line at info level,
+// the lines of each transaction and of the speedup are only logged at debug level.
+#[test]
+fn test_tick_logs_summary_line() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+    let funding = harness.fund(&funding_key, 1_000_000)?;
+    harness.coordinator().add_funding(funding)?;
+
+    let (tx_1, speedup_1) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 1);
+    let (tx_2, speedup_2) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 2);
+    harness.dispatch(tx_1, Some(speedup_1), "tx_1")?;
+    harness.dispatch(tx_2, Some(speedup_2), "tx_2")?;
+
+    let logs = capture_logs(|| Ok(harness.tick()?))?;
+    let lines = logs.lines();
+
+    let speedup_fee = harness
+        .coordinator()
+        .get_news()?
+        .coordinator_news
+        .iter()
+        .find_map(|news| match news {
+            CoordinatorNews::SpeedupCreated(_, _, fee, _, _) => Some(*fee),
+            _ => None,
+        })
+        .expect("SpeedupCreated news");
+
+    let summaries: Vec<&String> = lines
+        .iter()
+        .filter(|line| line.contains("Coordinator tick completed"))
+        .collect();
+    assert_eq!(summaries.len(), 1, "{lines:#?}");
+
+    let summary = summaries[0];
+    assert!(summary.contains(" INFO "), "{summary}");
+    for field in [
+        "dispatched=2".to_string(),
+        "confirmed=0".to_string(),
+        "finalized=0".to_string(),
+        "speedups=1".to_string(),
+        format!("speedup_fees_sats={speedup_fee}"),
+        "duration_ms=".to_string(),
+    ] {
+        assert!(summary.contains(&field), "{field} missing in {summary}");
+    }
+    assert!(summary.contains("news="), "{summary}");
+
+    // Nothing is logged for each transaction nor for the speedup at the default level
+    for per_item in [
+        "Sending Transaction",
+        "dispatched at block height",
+        "Send CPFP",
+    ] {
+        assert!(
+            !lines.iter().any(|line| line.contains(per_item)),
+            "{per_item} logged at info: {lines:#?}"
+        );
+    }
+
+    clear_output();
+    Ok(())
+}
+
+// The readiness of the monitor is logged on the first tick and then only when it changes.
+#[test]
+fn test_ready_logged_on_transitions() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+
+    let logs = capture_logs(|| {
+        for _ in 0..5 {
+            harness.tick()?;
+        }
+        Ok(())
+    })?;
+
+    let ready_lines = logs
+        .lines()
+        .into_iter()
+        .filter(|line| line.ends_with("Ready"))
+        .count();
+    assert_eq!(ready_lines, 1);
+
+    // Idle ticks do not log a summary at the default level
+    assert!(!logs
+        .lines()
+        .iter()
+        .any(|line| line.contains("Coordinator tick completed")));
+
+    clear_output();
+    Ok(())
+}
@@ -0,0 +1,150 @@
+use bitcoin::{hashes::Hash, BlockHash, Transaction, Txid};
+use bitcoin_coordinator::{
+    coordinator::{BitcoinCoordinator, BitcoinCoordinatorApi},
+    storage::BitcoinCoordinatorStoreApi,
+    types::{AckCoordinatorNews, AckNews, CoordinatorNews, TransactionState},
+};
+use bitcoincore_rpc::{Auth, Client};
+use bitvmx_transaction_monitor::{
+    errors::MonitorError,
+    types::{BlockInfo, FullBlock, TransactionBlockchainStatus, TransactionStatus},
+};
+use std::sync::{Arc, Mutex};
+use utils::{clear_output, get_mock_data, get_mocks, simple_tx};
+mod utils;
+
+const CURRENT_HEIGHT: u32 = 100;
+
+fn confirmed_status(tx: &Transaction) -> TransactionStatus {
+    TransactionStatus {
+        tx_id: tx.compute_txid(),
+        tx: tx.clone(),
+        block_info: Some(BlockInfo {
+            height: CURRENT_HEIGHT,
+            hash: BlockHash::all_zeros(),
+            is_orphan: false,
+        }),
+        confirmations: 1,
+        status: TransactionBlockchainStatus::Confirmed,
+    }
+}
+
+// The monitor reports a transaction scheduled for a later block as confirmed, so its state can not be updated.
+// The failure is reported and the other transactions are still dispatched and confirmed in the same tick.
+#[test]
+fn test_poisoned_tx_does_not_stop_the_tick() -> Result<(), anyhow::Error> {
+    let (mut mock_monitor, store, mut mock_bitcoin_client, key_manager) = get_mocks();
+    let poisoned_tx = simple_tx(1);
+    let dispatched_tx = simple_tx(2);
+    let pending_tx = simple_tx(3);
+    let context = "My tx".to_string();
+    let sent_txids: Arc<Mutex<Vec<Txid>>> = Arc::new(Mutex::new(Vec::new()));
+
+    // The poisoned transaction is processed first
+    store.save_tx(
+        poisoned_tx.clone(),
+        None,
+        Some(CURRENT_HEIGHT + 100),
+        context.clone(),
+    )?;
+    store.save_tx(dispatched_tx.clone(), None, None, context.clone())?;
+    store.update_tx_to_dispatched(dispatched_tx.compute_txid(), CURRENT_HEIGHT, 1)?;
+
+    let confirmed_txs = [poisoned_tx.clone(), dispatched_tx.clone()];
+    mock_monitor.expect_get_tx_status().returning(move |tx_id| {
+        match confirmed_txs.iter().find(|tx| tx.compute_txid() == *tx_id) {
+            Some(tx) => Ok(confirmed_status(tx)),
+            None => Err(MonitorError::TransactionNotFound(tx_id.to_string())),
+        }
+    });
+    mock_monitor.expect_get_current_block().returning(|| {
+        Ok(Some(FullBlock {
+            height: CURRENT_HEIGHT,
+            hash: BlockHash::all_zeros(),
+            prev_hash: BlockHash::all_zeros(),
+            txs: vec![],
+            orphan: false,
+        }))
+    });
+    mock_monitor.expect_monitor().returning(|_| Ok(()));
+    mock_monitor.expect_tick().returning(|| Ok(()));
+    mock_monitor.expect_is_ready().returning(|| Ok(true));
+    mock_monitor
+        .expect_get_monitor_height()
+        .returning(|| Ok(CURRENT_HEIGHT));
+    mock_monitor.expect_get_news().returning(|| Ok(vec![]));
+    mock_monitor
+        .expect_get_estimated_fee_rate()
+        .returning(|| Ok(2));
+
+    let sent = sent_txids.clone();
+    mock_bitcoin_client
+        .expect_send_transaction()
+        .returning(move |tx| {
+            sent.lock().unwrap().push(tx.compute_txid());
+            Ok(tx.compute_txid())
+        });
+    mock_bitcoin_client
+        .expect_get_best_block()
+        .returning(|| Ok(CURRENT_HEIGHT));
+
+    let coordinator = BitcoinCoordinator::builder()
+        .with_monitor(Box::new(mock_monitor))
+        .with_store(store)
+        .with_client(Box::new(mock_bitcoin_client))
+        .with_rpc_client(Client::new("http://127.0.0.1:18443", Auth::None)?)
+        .with_key_manager(key_manager)
+        .build()?;
+
+    coordinator.dispatch(pending_tx.clone(), None, context, None, None)?;
+    coordinator.tick()?;
+
+    let state = |tx: &Transaction| {
+        coordinator
+            .get_transaction_history(tx.compute_txid())
+            .unwrap()
+            .state
+    };
+    assert_eq!(state(&poisoned_tx), TransactionState::ToDispatch);
+    assert_eq!(state(&dispatched_tx), TransactionState::Confirmed);
+    assert_eq!(state(&pending_tx), TransactionState::Dispatched);
+    assert_eq!(*sent_txids.lock().unwrap(), vec![pending_tx.compute_txid()]);
+
+    let news = coordinator.get_news()?;
+    assert!(news
+        .coordinator_news
+        .contains(&CoordinatorNews::TickPartialFailure(1)));
+
+    coordinator.ack_news(AckNews::Coordinator(AckCoordinatorNews::TickPartialFailure))?;
+    assert!(!coordinator
+        .get_news()?
+        .coordinator_news
+        .iter()
+        .any(|news| matches!(news, CoordinatorNews::TickPartialFailure(..))));
+
+    clear_output();
+    Ok(())
+}
+
+// A late confirmation of a finalized transaction is ignored instead of failing.
+#[test]
+fn test_confirmed_update_of_finalized_tx_is_ignored() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let (_, tx, _, tx_id, context, _) = get_mock_data(key_manager);
+
+    store.save_tx(tx, None, None, context)?;
+    store.update_tx_to_dispatched(tx_id, CURRENT_HEIGHT, 1)?;
+    store.update_tx_state(tx_id, TransactionState::Confirmed)?;
+    store.update_tx_state(tx_id, TransactionState::Finalized)?;
+
+    store.update_tx_state(tx_id, TransactionState::Confirmed)?;
+    assert_eq!(store.get_tx(&tx_id)?.state, TransactionState::Finalized);
+
+    // Other downgrades are still rejected
+    assert!(store
+        .update_tx_state(tx_id, TransactionState::Dispatched)
+        .is_err());
+
+    clear_output();
+    Ok(())
+}
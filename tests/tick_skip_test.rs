@@ -0,0 +1,137 @@
+use bitcoin::{ScriptBuf, Txid};
+use bitcoin_coordinator::{
+    coordinator::BitcoinCoordinatorApi,
+    cpfp::SpeedupOutputKind,
+    observer::CoordinatorObserver,
+    speedup::SpeedupStore,
+    storage::{BitcoinCoordinatorStore, BitcoinCoordinatorStoreApi},
+    testing::CoordinatorTestHarness,
+    types::{AckCoordinatorNews, AckNews, CoordinatorNews, TickSkipReason, TransactionState},
+};
+use key_manager::key_type::BitcoinKeyType;
+use protocol_builder::types::{output::SpeedupData, Utxo};
+use std::rc::Rc;
+use utils::{clear_output, get_mocks, tx_with_output};
+mod utils;
+
+fn skipped_news(harness: &CoordinatorTestHarness) -> Result<Vec<TickSkipReason>, anyhow::Error> {
+    Ok(harness
+        .coordinator()
+        .get_news()?
+        .coordinator_news
+        .into_iter()
+        .filter_map(|news| match news {
+            CoordinatorNews::TickWorkSkipped(reason, _) => Some(reason),
+            _ => None,
+        })
+        .collect())
+}
+
+// Takes the funding away once the batch is broadcast, before its CPFP is built.
+struct FundingThief {
+    store: BitcoinCoordinatorStore,
+    funding: Utxo,
+}
+
+impl CoordinatorObserver for FundingThief {
+    fn on_transaction_broadcast(&self, _txid: Txid, _attempt: u32) {
+        self.store.invalidate_funding(&self.funding).unwrap();
+    }
+}
+
+#[test]
+fn test_funding_disappeared_skips_the_speedup() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 2)?;
+    let storage = store.store.clone();
+
+    let harness = CoordinatorTestHarness::new(storage, key_manager.clone(), None)?;
+    let funding = harness.fund(&funding_key, 1_000_000)?;
+    harness.coordinator().add_funding(funding.clone())?;
+
+    let thief = FundingThief {
+        store: BitcoinCoordinatorStore::new(store.store.clone(), 1, 3, 2)?,
+        funding,
+    };
+    let harness = harness.with_observer(Rc::new(thief));
+
+    let tx = tx_with_output(
+        SpeedupOutputKind::P2wpkh.script_pubkey(&anchor_key)?,
+        1_000,
+        1,
+    );
+    let tx_id = tx.compute_txid();
+    let speedup_data = SpeedupData::new(Utxo::new(tx_id, 0, 1_000, &anchor_key));
+    harness
+        .coordinator()
+        .dispatch(tx, Some(speedup_data), "My tx".to_string(), None, None)?;
+
+    // The tick completes, the transaction is broadcast without its CPFP
+    harness.tick()?;
+
+    assert_eq!(
+        skipped_news(&harness)?,
+        vec![TickSkipReason::FundingDisappeared]
+    );
+    assert_eq!(store.get_tx(&tx_id)?.state, TransactionState::Dispatched);
+    assert!(harness.chain().in_mempool(&tx_id));
+    assert!(store.get_last_speedup()?.is_none());
+
+    harness
+        .coordinator()
+        .ack_news(AckNews::Coordinator(AckCoordinatorNews::TickWorkSkipped(
+            TickSkipReason::FundingDisappeared,
+        )))?;
+    assert!(skipped_news(&harness)?.is_empty());
+
+    clear_output();
+    Ok(())
+}
+
+#[test]
+fn test_speedup_data_without_amount_skips_the_speedup() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 2)?;
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+
+    let funding = harness.fund(&funding_key, 1_000_000)?;
+    harness.coordinator().add_funding(funding)?;
+
+    // A transaction without speedup, confirmed while the other one is dispatched
+    let plain_tx = tx_with_output(ScriptBuf::new(), 1_000, 1);
+    let plain_tx_id = plain_tx.compute_txid();
+    harness
+        .coordinator()
+        .dispatch(plain_tx, None, "Plain tx".to_string(), None, None)?;
+    harness.tick()?;
+    harness.mine_blocks(1);
+
+    // Saved in the store directly, dispatch rejects it
+    let speedup_data = SpeedupData {
+        utxo: None,
+        partial_utxo: None,
+    };
+    store.save_tx(
+        tx_with_output(ScriptBuf::new(), 1_000, 2),
+        Some(speedup_data),
+        None,
+        "My tx".to_string(),
+    )?;
+
+    // The CPFP is skipped and the next steps of the tick still run
+    harness.tick()?;
+
+    assert_eq!(
+        skipped_news(&harness)?,
+        vec![TickSkipReason::SpeedupDataMissingAmount]
+    );
+    assert_eq!(
+        store.get_tx(&plain_tx_id)?.state,
+        TransactionState::Confirmed
+    );
+    assert!(store.get_last_speedup()?.is_none());
+
+    clear_output();
+    Ok(())
+}
@@ -1,8 +1,12 @@
-use bitcoin::{absolute::LockTime, transaction::Version, Amount, OutPoint, Transaction};
+use bitcoin::{
+    absolute::LockTime, hashes::Hash, transaction::Version, Amount, OutPoint, ScriptBuf, Sequence,
+    Transaction, TxIn, TxOut, Txid, Witness,
+};
 use bitcoin_coordinator::{
     config::CoordinatorSettingsConfig,
     coordinator::{BitcoinCoordinator, BitcoinCoordinatorApi},
-    types::CoordinatorNews,
+    errors::{BitcoinCoordinatorError, BroadcastFailureKind},
+    types::{CoordinatorNews, TransactionState},
     TypesToMonitor,
 };
 use bitcoind::bitcoind::BitcoindFlags;
@@ -70,12 +74,13 @@ fn create_simple_tx(
     Ok(result)
 }
 
-/// Test that verifies TransactionAlreadyInMempool error handling with transactions.
+/// Test that verifies a transaction the node already has is handled as a successful dispatch.
 /// This test:
 /// 1. Creates a transaction
-/// 2. Monitors it
-/// 3. Sends it directly to bitcoind to put it in mempool
-/// 4. Attempts to dispatch it through coordinator (should trigger "already in mempool" error)
+/// 2. Sends it directly to bitcoind and mines it
+/// 3. Monitors it
+/// 4. Dispatches it through coordinator (the node answers "Transaction outputs already in utxo set")
+/// 5. Checks the transaction is confirmed without error news
 #[test]
 fn test_transaction_already_in_mempool() -> Result<(), anyhow::Error> {
     config_trace_aux();
@@ -137,7 +142,7 @@ fn test_transaction_already_in_mempool() -> Result<(), anyhow::Error> {
     std::thread::sleep(std::time::Duration::from_secs(1));
 
     // Now monitor and try to dispatch the same transaction through coordinator
-    // This should trigger "Transaction outputs already in utxo set" which is treated as "already in mempool"
+    // This should trigger "Transaction outputs already in utxo set" which is treated as already in chain
     coordinator.monitor(TypesToMonitor::Transactions(
         vec![tx_id],
         context.clone(),
@@ -152,31 +157,21 @@ fn test_transaction_already_in_mempool() -> Result<(), anyhow::Error> {
     std::thread::sleep(std::time::Duration::from_secs(1));
     coordinator.tick()?;
 
-    // Check for TransactionAlreadyInMempool news
-    let news = coordinator.get_news()?;
-    let mut found_already_in_mempool = false;
-    for news_item in &news.coordinator_news {
-        if let CoordinatorNews::TransactionAlreadyInMempool(id, ctx) = news_item {
-            if *id == tx_id {
-                found_already_in_mempool = true;
-                info!(
-                    "Found TransactionAlreadyInMempool news for tx {} with context {}",
-                    tx_id, ctx
-                );
-                // Accept either the original context or the retry context
-                assert!(
-                    ctx == &context || ctx.contains("_retry"),
-                    "Unexpected context: {}",
-                    ctx
-                );
-                break;
-            }
-        }
-    }
+    // The transaction goes straight to Confirmed, the broadcast is not reported as an error
+    assert_eq!(
+        coordinator.get_transaction_history(tx_id)?.state,
+        TransactionState::Confirmed
+    );
 
+    let news = coordinator.get_news()?;
     assert!(
-        found_already_in_mempool,
-        "Expected TransactionAlreadyInMempool news but not found. News items: {:?}",
+        !news.coordinator_news.iter().any(|news| matches!(
+            news,
+            CoordinatorNews::DispatchTransactionError(id, ..)
+                | CoordinatorNews::TransactionAlreadyInMempool(id, _)
+                if *id == tx_id
+        )),
+        "Unexpected news for an already known transaction: {:?}",
         news.coordinator_news
     );
 
@@ -311,13 +306,34 @@ fn test_dispatch_transaction_error_fatal() -> Result<(), anyhow::Error> {
         coordinator.tick()?;
     }
 
+    // A transaction without inputs is rejected by dispatch before it is saved
+    let empty_tx = Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![],
+        output: vec![],
+    };
+
+    assert!(matches!(
+        coordinator.dispatch(empty_tx, None, "test_empty_tx".to_string(), None, None),
+        Err(BitcoinCoordinatorError::InvalidTransaction(..))
+    ));
+
     // Create an invalid transaction with non-existent input
     // This will cause a fatal error when trying to send
     let invalid_tx = Transaction {
         version: Version::TWO,
         lock_time: LockTime::ZERO,
-        input: vec![], // Empty inputs - invalid transaction
-        output: vec![],
+        input: vec![TxIn {
+            previous_output: OutPoint::new(Txid::all_zeros(), 0),
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: Amount::from_sat(10000),
+            script_pubkey: setup.funding_wallet.script_pubkey(),
+        }],
     };
 
     let tx_id = invalid_tx.compute_txid();
@@ -344,8 +360,9 @@ fn test_dispatch_transaction_error_fatal() -> Result<(), anyhow::Error> {
     let news = coordinator.get_news()?;
     let mut found_fatal_error = false;
     for news_item in &news.coordinator_news {
-        if let CoordinatorNews::DispatchTransactionError(id, ctx, error_msg) = news_item {
+        if let CoordinatorNews::DispatchTransactionError(id, ctx, error_msg, kind) = news_item {
             if *id == tx_id && ctx == &context {
+                assert_eq!(*kind, BroadcastFailureKind::Other);
                 found_fatal_error = true;
                 info!(
                     "Found DispatchTransactionError (fatal) news for tx {}: {}",
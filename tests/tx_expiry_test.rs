@@ -0,0 +1,177 @@
+use bitcoin::Transaction;
+use bitcoin_coordinator::{
+    coordinator::BitcoinCoordinatorApi,
+    storage::BitcoinCoordinatorStoreApi,
+    testing::CoordinatorTestHarness,
+    types::{CoordinatorNews, DispatchOptions, TransactionState},
+    MonitorNews,
+};
+use bitvmx_bitcoin_rpc::types::BlockHeight;
+use key_manager::key_type::BitcoinKeyType;
+use protocol_builder::types::output::SpeedupData;
+use utils::{clear_output, get_mocks, tx_with_anchor};
+mod utils;
+
+const ANCHOR_AMOUNT: u64 = 540;
+
+fn dispatch_expiring(
+    harness: &CoordinatorTestHarness,
+    tx: Transaction,
+    speedup_data: SpeedupData,
+    expires_at_height: BlockHeight,
+) -> Result<(), anyhow::Error> {
+    harness.coordinator().dispatch_with_options(
+        tx,
+        Some(speedup_data),
+        "My tx".to_string(),
+        None,
+        None,
+        DispatchOptions {
+            expires_at_height: Some(expires_at_height),
+            ..Default::default()
+        },
+    )?;
+
+    Ok(())
+}
+
+fn expired_news(harness: &CoordinatorTestHarness) -> Vec<CoordinatorNews> {
+    harness
+        .coordinator()
+        .get_news()
+        .unwrap()
+        .coordinator_news
+        .into_iter()
+        .filter(|news| matches!(news, CoordinatorNews::TransactionExpired(..)))
+        .collect()
+}
+
+// A transaction confirmed in the block of its expiry height is not expired.
+#[test]
+fn test_tx_confirmed_at_expiry_height_is_not_expired() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+    let funding = harness.fund(&funding_key, 1_000_000)?;
+    harness.coordinator().add_funding(funding)?;
+
+    let (tx, speedup_data) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 1);
+    let tx_id = tx.compute_txid();
+    let expires_at = harness.chain().height() + 1;
+    dispatch_expiring(&harness, tx, speedup_data, expires_at)?;
+    harness.tick()?;
+
+    harness.mine_blocks(1);
+    harness.tick()?;
+
+    assert_eq!(harness.chain().height(), expires_at);
+    assert_eq!(store.get_tx(&tx_id)?.state, TransactionState::Confirmed);
+
+    harness.mine_empty_blocks(2);
+    harness.tick()?;
+
+    assert_eq!(store.get_tx(&tx_id)?.state, TransactionState::Confirmed);
+    assert!(expired_news(&harness).is_empty());
+
+    clear_output();
+    Ok(())
+}
+
+// A transaction that could not be dispatched before its expiry height is expired once, and it is
+// not processed anymore.
+#[test]
+fn test_tx_expires_without_confirmation() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+
+    // Without funding the transaction waits to be dispatched
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+
+    let (tx, speedup_data) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 1);
+    let tx_id = tx.compute_txid();
+    let expires_at = harness.chain().height() + 2;
+    dispatch_expiring(&harness, tx, speedup_data, expires_at)?;
+
+    harness.tick()?;
+    harness.mine_empty_blocks(1);
+    harness.tick()?;
+
+    assert_eq!(store.get_tx(&tx_id)?.state, TransactionState::ToDispatch);
+    assert!(expired_news(&harness).is_empty());
+
+    harness.mine_empty_blocks(1);
+    harness.tick()?;
+
+    assert_eq!(store.get_tx(&tx_id)?.state, TransactionState::Expired);
+    assert!(!store
+        .get_txs_in_progress()?
+        .iter()
+        .any(|tx| tx.tx_id == tx_id));
+
+    harness.mine_empty_blocks(1);
+    harness.tick()?;
+
+    assert_eq!(
+        expired_news(&harness),
+        vec![CoordinatorNews::TransactionExpired(
+            tx_id,
+            "My tx".to_string(),
+            expires_at
+        )]
+    );
+    assert!(harness.chain().mempool().is_empty());
+
+    clear_output();
+    Ok(())
+}
+
+// A transaction already in the mempool when it expires is not sped up anymore. When it is confirmed later,
+// the confirmation is reported after the expiry and the transaction ends up confirmed.
+#[test]
+fn test_tx_confirmed_after_expiry() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+    let funding = harness.fund(&funding_key, 1_000_000)?;
+    harness.coordinator().add_funding(funding)?;
+
+    let (tx, speedup_data) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 1);
+    let tx_id = tx.compute_txid();
+    let expires_at = harness.chain().height() + 1;
+    dispatch_expiring(&harness, tx, speedup_data, expires_at)?;
+    harness.tick()?;
+
+    assert!(harness.chain().in_mempool(&tx_id));
+
+    // The block of the expiry height is mined without the transaction
+    harness.mine_empty_blocks(1);
+    harness.tick()?;
+
+    assert_eq!(store.get_tx(&tx_id)?.state, TransactionState::Expired);
+    assert_eq!(expired_news(&harness).len(), 1);
+
+    // Its CPFP is not bumped anymore
+    let broadcasts = harness.chain().broadcasts();
+    harness.mine_empty_blocks(3);
+    harness.tick()?;
+    assert_eq!(harness.chain().broadcasts(), broadcasts);
+
+    harness.mine_blocks(1);
+    harness.tick()?;
+
+    assert_eq!(store.get_tx(&tx_id)?.state, TransactionState::Confirmed);
+    assert_eq!(expired_news(&harness).len(), 1);
+
+    let news = harness.coordinator().get_news()?;
+    assert!(news.monitor_news.iter().any(|news| matches!(
+        news,
+        MonitorNews::Transaction(id, tx_status, _) if *id == tx_id && tx_status.is_confirmed()
+    )));
+
+    clear_output();
+    Ok(())
+}
@@ -0,0 +1,144 @@
+use bitcoin::Transaction;
+use bitcoin_coordinator::{
+    config::CoordinatorSettingsConfig,
+    coordinator::BitcoinCoordinatorApi,
+    errors::BitcoinCoordinatorError,
+    testing::CoordinatorTestHarness,
+    types::{AckCoordinatorNews, AckNews, CoordinatorNews, GroupMemberState},
+};
+use bitvmx_transaction_monitor::config::MonitorSettingsConfig;
+use utils::{clear_output, get_mocks, simple_tx};
+use uuid::Uuid;
+mod utils;
+
+const CONTEXT: &str = "round-1";
+
+fn completed_news(harness: &CoordinatorTestHarness) -> Result<Vec<(Uuid, String)>, anyhow::Error> {
+    Ok(harness
+        .coordinator()
+        .get_news()?
+        .coordinator_news
+        .into_iter()
+        .filter_map(|news| match news {
+            CoordinatorNews::GroupCompleted(group_id, context) => Some((group_id, context)),
+            _ => None,
+        })
+        .collect())
+}
+
+#[test]
+fn test_group_completes_once_every_member_is_finalized() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+
+    let txs: Vec<Transaction> = (1..=3).map(simple_tx).collect();
+    let tx_ids = txs.iter().map(|tx| tx.compute_txid()).collect();
+    let group_id = harness
+        .coordinator()
+        .monitor_group(tx_ids, CONTEXT.to_string(), Some(1))?;
+
+    // Two members are mined, the third one is still missing
+    harness.chain().send_transaction(&txs[0]).unwrap();
+    harness.chain().send_transaction(&txs[1]).unwrap();
+    harness.mine_blocks(1);
+    harness.tick()?;
+
+    let status = harness.coordinator().get_group_status(group_id)?;
+    assert_eq!(status.context, CONTEXT);
+    assert_eq!(
+        status
+            .members
+            .iter()
+            .map(|member| member.state)
+            .collect::<Vec<_>>(),
+        vec![
+            GroupMemberState::Finalized,
+            GroupMemberState::Finalized,
+            GroupMemberState::Pending
+        ]
+    );
+    assert!(!status.complete);
+    assert!(completed_news(&harness)?.is_empty());
+
+    // The last member is mined, the group is complete
+    harness.chain().send_transaction(&txs[2]).unwrap();
+    harness.mine_blocks(1);
+    harness.tick()?;
+
+    let status = harness.coordinator().get_group_status(group_id)?;
+    assert!(status.complete);
+    assert_eq!(status.pending_count(), 0);
+    assert_eq!(
+        completed_news(&harness)?,
+        vec![(group_id, CONTEXT.to_string())]
+    );
+
+    // It is reported once, even after it is acknowledged
+    harness.mine_empty_blocks(2);
+    harness.tick()?;
+    assert_eq!(completed_news(&harness)?.len(), 1);
+
+    harness
+        .coordinator()
+        .ack_news(AckNews::Coordinator(AckCoordinatorNews::GroupCompleted(
+            group_id,
+        )))?;
+    harness.mine_empty_blocks(1);
+    harness.tick()?;
+    assert!(completed_news(&harness)?.is_empty());
+
+    clear_output();
+    Ok(())
+}
+
+#[test]
+fn test_cancelled_member_completes_the_group() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let settings = CoordinatorSettingsConfig {
+        monitor_settings: Some(MonitorSettingsConfig {
+            max_monitoring_confirmations: Some(2),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, Some(settings))?;
+
+    let txs: Vec<(Transaction, _)> = (1..=3).map(|seed| (simple_tx(seed), None)).collect();
+    let cancelled_tx_id = txs[2].0.compute_txid();
+    let group_id = harness
+        .coordinator()
+        .dispatch_group(txs, CONTEXT.to_string(), None)?;
+
+    // The third member is cancelled before it is broadcast
+    harness.coordinator().cancel_dispatch(cancelled_tx_id)?;
+    harness.tick()?;
+    assert!(!harness.chain().in_mempool(&cancelled_tx_id));
+
+    harness.mine_blocks(1);
+    harness.tick()?;
+    assert!(!harness.coordinator().get_group_status(group_id)?.complete);
+    assert!(completed_news(&harness)?.is_empty());
+
+    // The two dispatched members are finalized
+    harness.mine_empty_blocks(1);
+    harness.tick()?;
+    harness.tick()?;
+
+    let status = harness.coordinator().get_group_status(group_id)?;
+    assert!(status.complete);
+    assert_eq!(status.members[2].tx_id, cancelled_tx_id);
+    assert_eq!(status.members[2].state, GroupMemberState::Cancelled);
+    assert_eq!(
+        completed_news(&harness)?,
+        vec![(group_id, CONTEXT.to_string())]
+    );
+
+    // A group id that was never registered
+    assert!(matches!(
+        harness.coordinator().get_group_status(Uuid::new_v4()),
+        Err(BitcoinCoordinatorError::UnknownGroup(_))
+    ));
+
+    clear_output();
+    Ok(())
+}
@@ -0,0 +1,167 @@
+use bitcoin::PublicKey;
+use bitcoin_coordinator::{
+    config::{CoordinatorSettingsConfig, FeeStrategy},
+    coordinator::BitcoinCoordinatorApi,
+    errors::BitcoinCoordinatorError,
+    testing::CoordinatorTestHarness,
+    types::{AckCoordinatorNews, AckNews, CoordinatorNews, SettingChange},
+};
+use key_manager::key_type::BitcoinKeyType;
+use utils::{clear_output, get_mocks, tx_with_anchor};
+mod utils;
+
+const ANCHOR_AMOUNT: u64 = 540;
+const FUNDING_AMOUNT: u64 = 100_000;
+
+fn settings(max_feerate_sat_vb: u64) -> CoordinatorSettingsConfig {
+    CoordinatorSettingsConfig {
+        max_feerate_sat_vb: Some(max_feerate_sat_vb),
+        ..Default::default()
+    }
+}
+
+// Fee rate of the SpeedupCreated news of the last speedup created.
+fn last_speedup_fee_rate(harness: &CoordinatorTestHarness) -> Option<u64> {
+    harness
+        .coordinator()
+        .get_news()
+        .unwrap()
+        .coordinator_news
+        .into_iter()
+        .rev()
+        .find_map(|news| match news {
+            CoordinatorNews::SpeedupCreated(_, _, _, fee_rate, _) => Some(fee_rate),
+            _ => None,
+        })
+}
+
+fn setup(max_feerate_sat_vb: u64) -> Result<(CoordinatorTestHarness, PublicKey), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let anchor_key = key_manager.derive_keypair(BitcoinKeyType::P2tr, 0)?;
+    let funding_key = key_manager.derive_keypair(BitcoinKeyType::P2wpkh, 1)?;
+
+    let harness = CoordinatorTestHarness::new(
+        store.store.clone(),
+        key_manager,
+        Some(settings(max_feerate_sat_vb)),
+    )?;
+    let funding = harness.fund(&funding_key, FUNDING_AMOUNT)?;
+    harness.coordinator().add_funding(funding)?;
+
+    Ok((harness, anchor_key))
+}
+
+// The fee rate cap is raised during a fee spike, the next CPFP pays the new cap.
+#[test]
+fn test_max_feerate_updated_between_ticks() -> Result<(), anyhow::Error> {
+    let (harness, anchor_key) = setup(20)?;
+    harness.set_fee_rate(50);
+
+    let (tx, speedup_data) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 1);
+    harness.dispatch(tx, Some(speedup_data), "My tx")?;
+    harness.tick()?;
+    assert_eq!(last_speedup_fee_rate(&harness), Some(20));
+
+    harness
+        .coordinator()
+        .ack_news(AckNews::Coordinator(AckCoordinatorNews::SpeedupCreated(
+            harness.chain().mempool()[1].compute_txid(),
+        )))?;
+    harness.mine_blocks(1);
+    harness.tick()?;
+
+    harness.coordinator().update_settings(settings(40))?;
+
+    let news = harness.coordinator().get_news()?;
+    assert!(news
+        .coordinator_news
+        .contains(&CoordinatorNews::SettingsUpdated(vec![SettingChange {
+            name: "max_feerate_sat_vb".to_string(),
+            old_value: "20".to_string(),
+            new_value: "40".to_string(),
+        }])));
+    harness
+        .coordinator()
+        .ack_news(AckNews::Coordinator(AckCoordinatorNews::SettingsUpdated))?;
+
+    let (tx, speedup_data) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 2);
+    harness.dispatch(tx, Some(speedup_data), "My tx")?;
+    harness.tick()?;
+    assert_eq!(last_speedup_fee_rate(&harness), Some(40));
+
+    // Updating with the same settings changes nothing and is not reported
+    harness.coordinator().update_settings(settings(40))?;
+    assert!(!harness
+        .coordinator()
+        .get_news()?
+        .coordinator_news
+        .iter()
+        .any(|news| matches!(news, CoordinatorNews::SettingsUpdated(..))));
+
+    clear_output();
+    Ok(())
+}
+
+#[test]
+fn test_unsafe_settings_updates_rejected() -> Result<(), anyhow::Error> {
+    let (harness, anchor_key) = setup(20)?;
+
+    for seed in 1..=2 {
+        let (tx, speedup_data) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, seed);
+        harness.dispatch(tx, Some(speedup_data), "My tx")?;
+        harness.tick()?;
+    }
+
+    // Two speedups are waiting to be confirmed
+    let result = harness
+        .coordinator()
+        .update_settings(CoordinatorSettingsConfig {
+            max_unconfirmed_speedups: Some(1),
+            ..settings(20)
+        });
+    assert!(
+        matches!(result, Err(BitcoinCoordinatorError::InvalidConfiguration(message)) if message.contains("max_unconfirmed_speedups"))
+    );
+
+    let result = harness
+        .coordinator()
+        .update_settings(CoordinatorSettingsConfig {
+            fee_strategy: Some(FeeStrategy::Fixed(5)),
+            ..settings(40)
+        });
+    assert!(matches!(
+        result,
+        Err(BitcoinCoordinatorError::InvalidConfiguration(_))
+    ));
+
+    let result = harness
+        .coordinator()
+        .update_settings(CoordinatorSettingsConfig {
+            max_tx_weight: Some(0),
+            ..settings(40)
+        });
+    assert!(matches!(
+        result,
+        Err(BitcoinCoordinatorError::InvalidConfiguration(_))
+    ));
+
+    // Nothing was applied
+    assert!(!harness
+        .coordinator()
+        .get_news()?
+        .coordinator_news
+        .iter()
+        .any(|news| matches!(news, CoordinatorNews::SettingsUpdated(..))));
+
+    harness.set_fee_rate(50);
+    harness.mine_blocks(1);
+    harness.tick()?;
+
+    let (tx, speedup_data) = tx_with_anchor(&anchor_key, ANCHOR_AMOUNT, 3);
+    harness.dispatch(tx, Some(speedup_data), "My tx")?;
+    harness.tick()?;
+    assert_eq!(last_speedup_fee_rate(&harness), Some(20));
+
+    clear_output();
+    Ok(())
+}
@@ -1,8 +1,11 @@
+use bitcoin::absolute::{LockTime, LOCK_TIME_THRESHOLD};
 use bitcoin::{absolute, transaction, Address, Amount, CompressedPublicKey, OutPoint, Transaction};
-use bitcoin::{Network, PublicKey, Txid};
+use bitcoin::{Network, PublicKey, ScriptBuf, Sequence, TxIn, TxOut, Txid, Witness};
 use bitcoin_coordinator::coordinator::{BitcoinCoordinator, BitcoinCoordinatorApi};
+use bitcoin_coordinator::cpfp::SpeedupOutputKind;
 use bitcoin_coordinator::errors::TxBuilderHelperError;
 use bitcoin_coordinator::storage::BitcoinCoordinatorStore;
+use bitcoin_coordinator::store_backend::{InMemoryStore, StoreBackend};
 use bitcoin_coordinator::TypesToMonitor;
 use bitcoind::bitcoind::{Bitcoind, BitcoindFlags};
 use bitcoind::config::BitcoindConfig;
@@ -98,6 +101,45 @@ pub fn get_mock_data(
     )
 }
 
+// A transaction with a single output, told apart from the others by its seed.
+// The seed is kept in the lock time, as a timestamp in the past so the transaction is final at any height.
+pub fn tx_with_output(script_pubkey: ScriptBuf, amount: u64, seed: u32) -> Transaction {
+    Transaction {
+        version: transaction::Version::TWO,
+        lock_time: LockTime::from_consensus(LOCK_TIME_THRESHOLD + seed),
+        input: vec![TxIn {
+            previous_output: OutPoint::null(),
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: Amount::from_sat(amount),
+            script_pubkey,
+        }],
+    }
+}
+
+// A transaction without speedup output.
+pub fn simple_tx(seed: u32) -> Transaction {
+    tx_with_output(ScriptBuf::new(), 1_000, seed)
+}
+
+// A transaction whose only output is a taproot anchor of `amount` sats, with the speedup data to dispatch it.
+pub fn tx_with_anchor(
+    anchor_key: &PublicKey,
+    amount: u64,
+    seed: u32,
+) -> (Transaction, SpeedupData) {
+    let script_pubkey = SpeedupOutputKind::P2trKeyPath
+        .script_pubkey(anchor_key)
+        .unwrap();
+    let tx = tx_with_output(script_pubkey, amount, seed);
+    let speedup_data = SpeedupData::new(Utxo::new(tx.compute_txid(), 0, amount, anchor_key));
+
+    (tx, speedup_data)
+}
+
 pub fn generate_tx(
     funding_outpoint: OutPoint,
     origin_amount: u64,
@@ -189,12 +231,57 @@ fn create_tx_to_speedup(
 }
 
 pub fn create_store() -> BitcoinCoordinatorStore {
+    create_store_on(TestBackend::OnDisk)
+}
+
+pub fn create_store_on(backend: TestBackend) -> BitcoinCoordinatorStore {
     const MAX_RETRIES: u32 = 3;
     const RETRY_INTERVAL: u64 = 2;
-    let path = format!("test_output/speedup/{}", generate_random_string());
-    let storage_config = StorageConfig::new(path, None);
-    let storage = Rc::new(Storage::new(&storage_config).unwrap());
-    BitcoinCoordinatorStore::new(storage, 10, MAX_RETRIES, RETRY_INTERVAL).unwrap()
+    BitcoinCoordinatorStore::new(create_storage(backend), 10, MAX_RETRIES, RETRY_INTERVAL).unwrap()
+}
+
+/// Storage a store test runs on, the tests of `backend_tests!` run once on each.
+#[derive(Clone, Copy, Debug)]
+pub enum TestBackend {
+    OnDisk,
+    InMemory,
+}
+
+/// Creates an empty storage, the in-memory one does not touch the filesystem.
+pub fn create_storage(backend: TestBackend) -> Rc<dyn StoreBackend> {
+    match backend {
+        TestBackend::OnDisk => {
+            let path = format!("test_output/test/storage/{}", generate_random_string());
+            let storage_config = StorageConfig::new(path, None);
+            Rc::new(Storage::new(&storage_config).unwrap())
+        }
+        TestBackend::InMemory => Rc::new(InMemoryStore::new()),
+    }
+}
+
+/// Runs each store test on the on-disk storage and in memory, in the modules `on_disk` and `in_memory`.
+/// The tests take the backend to create their storage on: `fn name(backend: TestBackend) -> Result<(), anyhow::Error>`.
+#[macro_export]
+macro_rules! backend_tests {
+    ($($test:ident),* $(,)?) => {
+        mod on_disk {
+            $(
+                #[test]
+                fn $test() -> Result<(), anyhow::Error> {
+                    super::$test($crate::utils::TestBackend::OnDisk)
+                }
+            )*
+        }
+
+        mod in_memory {
+            $(
+                #[test]
+                fn $test() -> Result<(), anyhow::Error> {
+                    super::$test($crate::utils::TestBackend::InMemory)
+                }
+            )*
+        }
+    };
 }
 
 pub fn config_trace_aux() {
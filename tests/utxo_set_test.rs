@@ -0,0 +1,211 @@
+use bitcoin::{
+    absolute::LockTime, transaction::Version, Amount, OutPoint, ScriptBuf, Sequence, Transaction,
+    TxIn, TxOut, Witness,
+};
+use bitcoin_coordinator::{
+    coordinator::BitcoinCoordinatorApi,
+    errors::BitcoinCoordinatorError,
+    testing::CoordinatorTestHarness,
+    types::{AckCoordinatorNews, AckNews, CoordinatorNews},
+};
+use utils::{clear_output, get_mocks};
+mod utils;
+
+const LABEL: &str = "session-1";
+
+fn spend(outpoints: &[OutPoint]) -> Transaction {
+    Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: outpoints
+            .iter()
+            .map(|outpoint| TxIn {
+                previous_output: *outpoint,
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                witness: Witness::new(),
+            })
+            .collect(),
+        output: vec![TxOut {
+            value: Amount::from_sat(1_000),
+            script_pubkey: ScriptBuf::new(),
+        }],
+    }
+}
+
+fn collateral(harness: &CoordinatorTestHarness) -> Vec<OutPoint> {
+    (0..3)
+        .map(|_| {
+            let (tx, vout) = harness.chain().fund(ScriptBuf::new(), 50_000);
+            OutPoint::new(tx.compute_txid(), vout)
+        })
+        .collect()
+}
+
+fn collateral_news(
+    harness: &CoordinatorTestHarness,
+) -> Result<Vec<CoordinatorNews>, anyhow::Error> {
+    Ok(harness
+        .coordinator()
+        .get_news()?
+        .coordinator_news
+        .into_iter()
+        .filter(|news| {
+            matches!(
+                news,
+                CoordinatorNews::CollateralSpent(..) | CoordinatorNews::CollateralFullySpent(..)
+            )
+        })
+        .collect())
+}
+
+#[test]
+fn test_utxo_set_partial_and_full_spends() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+
+    let outpoints = collateral(&harness);
+    harness.coordinator().monitor_utxo_set(
+        LABEL.to_string(),
+        outpoints.clone(),
+        "collateral".to_string(),
+    )?;
+
+    // A label is watched once
+    assert!(matches!(
+        harness.coordinator().monitor_utxo_set(
+            LABEL.to_string(),
+            outpoints.clone(),
+            "collateral".to_string(),
+        ),
+        Err(BitcoinCoordinatorError::UtxoSetAlreadyWatched(_))
+    ));
+
+    harness.tick()?;
+    assert!(collateral_news(&harness)?.is_empty());
+
+    // The first member is spent
+    let first_spend = spend(&outpoints[..1]);
+    harness.chain().send_transaction(&first_spend).unwrap();
+    harness.mine_blocks(1);
+    harness.tick()?;
+
+    assert_eq!(
+        collateral_news(&harness)?,
+        vec![CoordinatorNews::CollateralSpent(
+            LABEL.to_string(),
+            outpoints[0],
+            first_spend.compute_txid(),
+            2,
+        )]
+    );
+
+    let status = harness.coordinator().get_utxo_set_status(LABEL)?;
+    assert_eq!(status.unspent_count(), 2);
+    assert_eq!(
+        status.outpoints[0].spending_txid,
+        Some(first_spend.compute_txid())
+    );
+    assert_eq!(status.outpoints[1].spending_txid, None);
+    assert_eq!(status.outpoints[2].spending_txid, None);
+
+    // The monitor news of the spend are consumed by the coordinator
+    assert!(harness.coordinator().get_news()?.monitor_news.is_empty());
+
+    // The other two members are spent by the same transaction, the set is fully spent after both partial alerts
+    let second_spend = spend(&outpoints[1..]);
+    harness.chain().send_transaction(&second_spend).unwrap();
+    harness.mine_blocks(1);
+    harness.tick()?;
+
+    assert_eq!(
+        collateral_news(&harness)?,
+        vec![
+            CoordinatorNews::CollateralSpent(
+                LABEL.to_string(),
+                outpoints[0],
+                first_spend.compute_txid(),
+                2,
+            ),
+            CoordinatorNews::CollateralSpent(
+                LABEL.to_string(),
+                outpoints[1],
+                second_spend.compute_txid(),
+                1,
+            ),
+            CoordinatorNews::CollateralSpent(
+                LABEL.to_string(),
+                outpoints[2],
+                second_spend.compute_txid(),
+                0,
+            ),
+            CoordinatorNews::CollateralFullySpent(LABEL.to_string()),
+        ]
+    );
+
+    let status = harness.coordinator().get_utxo_set_status(LABEL)?;
+    assert_eq!(status.unspent_count(), 0);
+
+    // More confirmations of the spends are not reported again
+    harness.coordinator().ack_news_batch(
+        outpoints
+            .iter()
+            .map(|outpoint| AckNews::Coordinator(AckCoordinatorNews::CollateralSpent(*outpoint)))
+            .chain([AckNews::Coordinator(
+                AckCoordinatorNews::CollateralFullySpent(LABEL.to_string()),
+            )])
+            .collect(),
+    )?;
+    harness.mine_blocks(1);
+    harness.tick()?;
+    assert!(collateral_news(&harness)?.is_empty());
+
+    // Cancelling the set forgets it
+    assert!(harness.coordinator().cancel_utxo_set(LABEL)?);
+    assert!(!harness.coordinator().cancel_utxo_set(LABEL)?);
+    assert!(matches!(
+        harness.coordinator().get_utxo_set_status(LABEL),
+        Err(BitcoinCoordinatorError::UnknownUtxoSet(_))
+    ));
+
+    clear_output();
+    Ok(())
+}
+
+#[test]
+fn test_utxo_set_is_restored_after_restart() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager.clone(), None)?;
+
+    let outpoints = collateral(&harness);
+    harness.coordinator().monitor_utxo_set(
+        LABEL.to_string(),
+        outpoints.clone(),
+        "collateral".to_string(),
+    )?;
+    harness.tick()?;
+
+    // The new coordinator starts with an empty monitor
+    let chain = harness.chain().clone();
+    drop(harness);
+    let harness =
+        CoordinatorTestHarness::with_chain(chain, store.store.clone(), key_manager, None)?;
+
+    let spend = spend(&outpoints[1..2]);
+    harness.chain().send_transaction(&spend).unwrap();
+    harness.mine_blocks(1);
+    harness.tick()?;
+
+    assert_eq!(
+        collateral_news(&harness)?,
+        vec![CoordinatorNews::CollateralSpent(
+            LABEL.to_string(),
+            outpoints[1],
+            spend.compute_txid(),
+            2,
+        )]
+    );
+
+    clear_output();
+    Ok(())
+}
@@ -0,0 +1,138 @@
+use bitcoin::{
+    absolute::LockTime, transaction::Version, Amount, OutPoint, ScriptBuf, Sequence, Transaction,
+    TxIn, TxOut, Witness,
+};
+use bitcoin_coordinator::{
+    coordinator::BitcoinCoordinatorApi,
+    storage::{BitcoinCoordinatorStore, BitcoinCoordinatorStoreApi},
+    testing::CoordinatorTestHarness,
+    types::{AckCoordinatorNews, AckNews, CoordinatorNews},
+};
+use utils::{clear_output, get_mocks};
+mod utils;
+
+fn script(seed: u8) -> ScriptBuf {
+    ScriptBuf::from_bytes(vec![0x51, seed])
+}
+
+// A counterparty deposit paying twice to the watched script, with its change to another script.
+fn deposit(script_pubkey: ScriptBuf) -> Transaction {
+    let output = |value: u64, script_pubkey: ScriptBuf| TxOut {
+        value: Amount::from_sat(value),
+        script_pubkey,
+    };
+
+    Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::from_consensus(0),
+        input: vec![TxIn {
+            previous_output: OutPoint::null(),
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+            witness: Witness::new(),
+        }],
+        output: vec![
+            output(5_000, script_pubkey.clone()),
+            output(7_000, script(99)),
+            output(3_000, script_pubkey),
+        ],
+    }
+}
+
+fn address_news(harness: &CoordinatorTestHarness) -> Result<Vec<CoordinatorNews>, anyhow::Error> {
+    Ok(harness
+        .coordinator()
+        .get_news()?
+        .coordinator_news
+        .into_iter()
+        .filter(|news| news.kind() == "AddressFunded")
+        .collect())
+}
+
+#[test]
+fn test_address_funded_news() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+    let watched = script(1);
+
+    // Watching the script again only replaces its context
+    harness
+        .coordinator()
+        .monitor_address(watched.clone(), "Old context".to_string())?;
+    harness
+        .coordinator()
+        .monitor_address(watched.clone(), "Protocol address".to_string())?;
+
+    // The subscription is persisted
+    let restarted = BitcoinCoordinatorStore::new(store.store.clone(), 10, 3, 2)?;
+    let subscriptions = restarted.get_watched_addresses()?;
+    assert_eq!(subscriptions.len(), 1);
+    assert_eq!(subscriptions[0].context, "Protocol address");
+
+    let tx = deposit(watched.clone());
+    harness.chain().send_transaction(&tx).unwrap();
+
+    // Nothing is reported while the transaction is in the mempool
+    harness.tick()?;
+    assert!(address_news(&harness)?.is_empty());
+
+    harness.mine_blocks(1);
+    harness.tick()?;
+    harness.tick()?;
+
+    let block = harness.chain().tip();
+    let news = address_news(&harness)?;
+    assert_eq!(news.len(), 1);
+
+    let CoordinatorNews::AddressFunded(script_pubkey, funding_tx, outputs, block_info, context) =
+        &news[0]
+    else {
+        panic!("Unexpected news {:?}", news[0]);
+    };
+    assert_eq!(script_pubkey, &watched);
+    assert_eq!(funding_tx, &tx);
+    assert_eq!(outputs, &vec![(0, 5_000), (2, 3_000)]);
+    assert_eq!(block_info.height, block.height);
+    assert_eq!(block_info.hash, block.hash);
+    assert_eq!(context, "Protocol address");
+
+    harness
+        .coordinator()
+        .ack_news(AckNews::Coordinator(AckCoordinatorNews::AddressFunded(
+            watched,
+            tx.compute_txid(),
+        )))?;
+    harness.tick()?;
+    assert!(address_news(&harness)?.is_empty());
+
+    clear_output();
+    Ok(())
+}
+
+#[test]
+fn test_cancel_monitor_address() -> Result<(), anyhow::Error> {
+    let (_, store, _, key_manager) = get_mocks();
+    let harness = CoordinatorTestHarness::new(store.store.clone(), key_manager, None)?;
+    let watched = script(1);
+
+    harness
+        .coordinator()
+        .monitor_address(watched.clone(), "Protocol address".to_string())?;
+    harness
+        .coordinator()
+        .monitor_address(script(2), "Other address".to_string())?;
+
+    assert!(harness.coordinator().cancel_monitor_address(&watched)?);
+    assert!(!harness.coordinator().cancel_monitor_address(&watched)?);
+
+    let subscriptions = store.get_watched_addresses()?;
+    assert_eq!(subscriptions.len(), 1);
+    assert_eq!(subscriptions[0].script_pubkey, script(2));
+
+    harness.chain().fund(watched, 5_000);
+    harness.tick()?;
+    assert!(address_news(&harness)?.is_empty());
+
+    clear_output();
+    Ok(())
+}
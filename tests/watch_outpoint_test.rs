@@ -0,0 +1,138 @@
+use crate::utils::{config_trace_aux, create_test_setup, generate_tx, TestSetupConfig};
+use bitcoin::{Amount, OutPoint};
+use bitcoin_coordinator::{
+    coordinator::{BitcoinCoordinator, BitcoinCoordinatorApi},
+    storage::{BitcoinCoordinatorStore, BitcoinCoordinatorStoreApi},
+    types::{AckCoordinatorNews, AckNews, CoordinatorNews},
+    MonitorNews, TypesToMonitor,
+};
+use bitvmx_bitcoin_rpc::bitcoin_client::BitcoinClientApi;
+mod utils;
+
+// This test watches an output of a transaction the coordinator did not dispatch.
+// The coordinator is restarted after watching it, then a transaction spending the output is mined.
+// The spend must be reported as an OutpointSpent news with the context given when watching the output.
+#[test]
+fn watch_outpoint_test() -> Result<(), anyhow::Error> {
+    config_trace_aux();
+
+    let mut blocks_mined = 102;
+    let setup = create_test_setup(TestSetupConfig {
+        blocks_mined,
+        bitcoind_flags: None,
+    })?;
+
+    let amount = Amount::from_sat(23450000);
+
+    // The external transaction whose output is watched
+    let (external_tx, external_vout) = setup
+        .bitcoin_client
+        .fund_address(&setup.funding_wallet, amount)?;
+
+    // External tx mines 1 block
+    blocks_mined += 1;
+
+    let coordinator = BitcoinCoordinator::new_with_paths(
+        &setup.config_bitcoin_client,
+        setup.storage.clone(),
+        setup.key_manager.clone(),
+        None,
+    )?;
+
+    for _ in 0..blocks_mined {
+        coordinator.tick()?;
+    }
+
+    let outpoint = OutPoint::new(external_tx.compute_txid(), external_vout);
+    let context = "Counterparty output".to_string();
+
+    coordinator.watch_outpoint(outpoint, context.clone())?;
+    drop(coordinator);
+
+    // The subscription survives a restart
+    let coordinator = BitcoinCoordinator::new_with_paths(
+        &setup.config_bitcoin_client,
+        setup.storage.clone(),
+        setup.key_manager.clone(),
+        None,
+    )?;
+
+    let store = BitcoinCoordinatorStore::new(setup.storage.clone(), 10, 3, 2)?;
+    assert_eq!(store.get_watched_outpoints()?.len(), 1);
+
+    // The counterparty spends the watched output
+    let (spending_tx, _) = generate_tx(
+        outpoint,
+        amount.to_sat(),
+        setup.public_key,
+        setup.key_manager.clone(),
+        172,
+    )?;
+    setup.bitcoin_client.send_transaction(&spending_tx)?;
+    setup
+        .bitcoin_client
+        .mine_blocks_to_address(1, &setup.funding_wallet)?;
+
+    coordinator.tick()?;
+
+    let news = coordinator.get_news()?;
+
+    let spent_news: Vec<&CoordinatorNews> = news
+        .coordinator_news
+        .iter()
+        .filter(|news| matches!(news, CoordinatorNews::OutpointSpent(..)))
+        .collect();
+    assert_eq!(spent_news.len(), 1);
+
+    match spent_news[0] {
+        CoordinatorNews::OutpointSpent(
+            spent_outpoint,
+            spending_txid,
+            input_index,
+            block_info,
+            news_context,
+        ) => {
+            assert_eq!(*spent_outpoint, outpoint);
+            assert_eq!(*spending_txid, spending_tx.compute_txid());
+            assert_eq!(*input_index, 0);
+            assert_eq!(block_info.height, setup.bitcoin_client.get_best_block()?);
+            assert_eq!(*news_context, context);
+        }
+        _ => unreachable!(),
+    }
+
+    // The spend is not reported twice through the monitor news
+    assert!(!news
+        .monitor_news
+        .iter()
+        .any(|news| matches!(news, MonitorNews::SpendingUTXOTransaction(..))));
+
+    coordinator.ack_news(AckNews::Coordinator(AckCoordinatorNews::OutpointSpent(
+        outpoint,
+    )))?;
+
+    // Following confirmations do not report the spend again
+    setup
+        .bitcoin_client
+        .mine_blocks_to_address(1, &setup.funding_wallet)?;
+    coordinator.tick()?;
+
+    assert!(!coordinator
+        .get_news()?
+        .coordinator_news
+        .iter()
+        .any(|news| matches!(news, CoordinatorNews::OutpointSpent(..))));
+
+    // Cancelling the subscription removes it from the store
+    coordinator.cancel(TypesToMonitor::SpendingUTXOTransaction(
+        outpoint.txid,
+        outpoint.vout,
+        context,
+        None,
+    ))?;
+    assert!(store.get_watched_outpoints()?.is_empty());
+
+    setup.bitcoind.stop()?;
+
+    Ok(())
+}